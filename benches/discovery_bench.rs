@@ -0,0 +1,104 @@
+//! File Discovery Benchmarks
+//!
+//! Compares the sequential `ignore` walk with the work-stealing parallel
+//! walk used by indexing, on generated trees of different shapes:
+//! 1. Wide: many sibling directories with a few files each
+//! 2. Deep: one long directory chain with files at every level
+//! 3. Skewed: one large subtree next to many small ones
+//!
+//! Local disks hide most of the difference; run on a network mount to see
+//! the effect of per-directory latency.
+
+use codanna::indexing::walker::{walk_builder, walk_parallel};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
+
+const THREADS: &[usize] = &[1, 4, 8];
+
+/// Writes a tree under the given root and returns its file count
+type CreateTree = fn(&Path) -> usize;
+
+fn write_files(dir: &Path, count: usize) {
+    std::fs::create_dir_all(dir).expect("Failed to create directory");
+    for i in 0..count {
+        std::fs::write(dir.join(format!("file_{i}.rs")), "fn f() {}\n")
+            .expect("Failed to write file");
+    }
+}
+
+fn create_wide_tree(root: &Path) -> usize {
+    for d in 0..200 {
+        write_files(&root.join(format!("pkg_{d}")), 10);
+    }
+    200 * 10
+}
+
+fn create_deep_tree(root: &Path) -> usize {
+    let mut dir = root.to_path_buf();
+    for d in 0..100 {
+        dir = dir.join(format!("level_{d}"));
+        write_files(&dir, 20);
+    }
+    100 * 20
+}
+
+fn create_skewed_tree(root: &Path) -> usize {
+    for d in 0..50 {
+        write_files(&root.join("big").join(format!("mod_{d}")), 30);
+    }
+    for d in 0..50 {
+        write_files(&root.join(format!("small_{d}")), 2);
+    }
+    50 * 30 + 50 * 2
+}
+
+fn count_sequential(root: &Path) -> usize {
+    walk_builder(root)
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .count()
+}
+
+fn count_parallel(root: &Path, threads: usize) -> usize {
+    let count = AtomicUsize::new(0);
+    walk_parallel(root, threads, |_| {
+        count.fetch_add(1, Ordering::Relaxed);
+        true
+    });
+    count.into_inner()
+}
+
+fn bench_discovery(c: &mut Criterion) {
+    let shapes: [(&str, CreateTree); 3] = [
+        ("wide", create_wide_tree),
+        ("deep", create_deep_tree),
+        ("skewed", create_skewed_tree),
+    ];
+
+    for (shape, create) in shapes {
+        let dir = TempDir::new().expect("Failed to create temp dir");
+        let files = create(dir.path());
+
+        let mut group = c.benchmark_group(format!("discovery_{shape}"));
+        group.throughput(Throughput::Elements(files as u64));
+
+        group.bench_function("sequential", |b| {
+            b.iter(|| black_box(count_sequential(dir.path())))
+        });
+        for &threads in THREADS {
+            group.bench_with_input(
+                BenchmarkId::new("parallel", threads),
+                &threads,
+                |b, &threads| b.iter(|| black_box(count_parallel(dir.path(), threads))),
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_discovery);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+//! Local REST API over the index
+//!
+//! `codanna api` serves the core queries as plain JSON over HTTP, for
+//! dashboards and scripts that do not speak MCP:
+//!
+//! | Endpoint | Returns |
+//! |----------|---------|
+//! | `GET /api/v1/search?q=` | Full-text search results |
+//! | `GET /api/v1/symbols/{name}` | Symbols with that name |
+//! | `GET /api/v1/symbols/{name}/references` | Callers, users and implementors of each |
+//! | `GET /api/v1/outline?path=` | Symbols defined in a file, in source order |
+//! | `GET /api/v1/stats` | Index counts |
+//! | `GET /api/v1/openapi.json` | The OpenAPI description of these endpoints |
+//!
+//! The API is read-only and unauthenticated, so it binds to localhost by
+//! default. Routes are only compiled with the `http-server` feature.
+
+pub mod openapi;
+
+pub use openapi::{ENDPOINTS, Endpoint, Param, openapi_spec};
+
+/// URL prefix of every endpoint
+pub const API_PREFIX: &str = "/api/v1";
+
+/// Default bind address of `codanna api`
+pub const DEFAULT_BIND: &str = "127.0.0.1:8090";
+
+#[cfg(feature = "http-server")]
+mod routes;
+
+#[cfg(feature = "http-server")]
+pub use routes::{router, serve};
+
+#[cfg(not(feature = "http-server"))]
+pub async fn serve(
+    _facade: crate::indexing::facade::IndexFacade,
+    _bind: String,
+) -> anyhow::Result<()> {
+    eprintln!("REST API support is not compiled in.");
+    eprintln!("Please rebuild with: cargo build --features http-server");
+    std::process::exit(1);
+}
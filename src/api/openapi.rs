@@ -0,0 +1,204 @@
+//! OpenAPI description of the REST API
+//!
+//! The spec is generated from [`ENDPOINTS`], the same table the router is
+//! checked against, so the two cannot drift apart.
+
+use serde_json::{Map, Value, json};
+
+/// Where a parameter is passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamIn {
+    Path,
+    Query,
+}
+
+/// A request parameter
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    pub name: &'static str,
+    pub location: ParamIn,
+    pub required: bool,
+    /// OpenAPI type: `string` or `integer`
+    pub kind: &'static str,
+    pub description: &'static str,
+}
+
+/// A `GET` endpoint under [`super::API_PREFIX`]
+#[derive(Debug, Clone, Copy)]
+pub struct Endpoint {
+    /// Path relative to the prefix, with `{param}` placeholders
+    pub path: &'static str,
+    pub operation_id: &'static str,
+    pub summary: &'static str,
+    pub params: &'static [Param],
+}
+
+const fn query(name: &'static str, kind: &'static str, description: &'static str) -> Param {
+    Param {
+        name,
+        location: ParamIn::Query,
+        required: false,
+        kind,
+        description,
+    }
+}
+
+const NAME: Param = Param {
+    name: "name",
+    location: ParamIn::Path,
+    required: true,
+    kind: "string",
+    description: "Exact symbol name",
+};
+
+const LANG: Param = query("lang", "string", "Only symbols of this language id");
+
+/// Every endpoint the API serves
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint {
+        path: "/search",
+        operation_id: "search",
+        summary: "Full-text search over symbol names, docs and signatures",
+        params: &[
+            Param {
+                name: "q",
+                location: ParamIn::Query,
+                required: true,
+                kind: "string",
+                description: "Search query",
+            },
+            query(
+                "limit",
+                "integer",
+                "Maximum results (default 10, capped at query.max_candidates)",
+            ),
+            query("kind", "string", "Only symbols of this kind, e.g. function"),
+            query("module", "string", "Only symbols in this module path"),
+            LANG,
+        ],
+    },
+    Endpoint {
+        path: "/symbols/{name}",
+        operation_id: "getSymbol",
+        summary: "Symbols with an exact name",
+        params: &[NAME, LANG],
+    },
+    Endpoint {
+        path: "/symbols/{name}/references",
+        operation_id: "getReferences",
+        summary: "Callers, users and implementors of each symbol with a name",
+        params: &[NAME, LANG],
+    },
+    Endpoint {
+        path: "/outline",
+        operation_id: "getOutline",
+        summary: "Symbols defined in a file, in source order",
+        params: &[Param {
+            name: "path",
+            location: ParamIn::Query,
+            required: true,
+            kind: "string",
+            description: "File path as indexed, e.g. src/lib.rs",
+        }],
+    },
+    Endpoint {
+        path: "/stats",
+        operation_id: "getStats",
+        summary: "Symbol, file and relationship counts",
+        params: &[],
+    },
+    Endpoint {
+        path: "/openapi.json",
+        operation_id: "getOpenApi",
+        summary: "This OpenAPI document",
+        params: &[],
+    },
+];
+
+/// OpenAPI 3.0 document for the API served at `server_url`
+pub fn openapi_spec(server_url: &str) -> Value {
+    let mut paths = Map::new();
+    for endpoint in ENDPOINTS {
+        let parameters: Vec<Value> = endpoint
+            .params
+            .iter()
+            .map(|param| {
+                json!({
+                    "name": param.name,
+                    "in": match param.location {
+                        ParamIn::Path => "path",
+                        ParamIn::Query => "query",
+                    },
+                    "required": param.required,
+                    "description": param.description,
+                    "schema": { "type": param.kind },
+                })
+            })
+            .collect();
+
+        paths.insert(
+            format!("{}{}", super::API_PREFIX, endpoint.path),
+            json!({
+                "get": {
+                    "operationId": endpoint.operation_id,
+                    "summary": endpoint.summary,
+                    "parameters": parameters,
+                    "responses": {
+                        "200": {
+                            "description": "Success",
+                            "content": { "application/json": { "schema": { "type": "object" } } },
+                        },
+                        "400": { "description": "Invalid parameters" },
+                        "404": { "description": "Nothing matched" },
+                    },
+                }
+            }),
+        );
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "codanna REST API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Read-only queries over a codanna index",
+        },
+        "servers": [{ "url": server_url }],
+        "paths": paths,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_lists_every_endpoint() {
+        let spec = openapi_spec("http://127.0.0.1:8090");
+        let paths = spec["paths"].as_object().unwrap();
+        assert_eq!(paths.len(), ENDPOINTS.len());
+        assert!(paths.contains_key("/api/v1/symbols/{name}/references"));
+        assert_eq!(
+            spec["paths"]["/api/v1/search"]["get"]["parameters"][0]["name"],
+            "q"
+        );
+    }
+
+    #[test]
+    fn test_path_placeholders_are_declared() {
+        for endpoint in ENDPOINTS {
+            let placeholders: Vec<&str> = endpoint
+                .path
+                .split('/')
+                .filter_map(|part| part.strip_prefix('{')?.strip_suffix('}'))
+                .collect();
+            let declared: Vec<&str> = endpoint
+                .params
+                .iter()
+                .filter(|param| param.location == ParamIn::Path)
+                .map(|param| param.name)
+                .collect();
+            assert_eq!(placeholders, declared, "{}", endpoint.path);
+        }
+    }
+}
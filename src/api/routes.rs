@@ -0,0 +1,233 @@
+//! Axum routes of the REST API
+
+use super::{API_PREFIX, ENDPOINTS, openapi_spec};
+use crate::indexing::facade::IndexFacade;
+use crate::watcher::HotReloadWatcher;
+use crate::{RelationKind, Symbol};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{MethodRouter, get};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// How often the index directory is checked for a newer index
+const HOT_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Shared state of every handler
+#[derive(Clone)]
+struct ApiState {
+    indexer: Arc<RwLock<IndexFacade>>,
+    server_url: Arc<str>,
+}
+
+/// A request the API could not answer
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+type ApiResult = Result<Json<Value>, ApiError>;
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    q: String,
+    limit: Option<usize>,
+    kind: Option<String>,
+    module: Option<String>,
+    lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LangParams {
+    lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutlineParams {
+    path: String,
+}
+
+/// Router serving [`ENDPOINTS`] under [`API_PREFIX`]
+pub fn router(indexer: Arc<RwLock<IndexFacade>>, server_url: &str) -> Router {
+    let mut router = Router::new();
+    for endpoint in ENDPOINTS {
+        let handler = handler(endpoint.operation_id)
+            .unwrap_or_else(|| panic!("no handler for {}", endpoint.operation_id));
+        router = router.route(&format!("{API_PREFIX}{}", endpoint.path), handler);
+    }
+    router.with_state(ApiState {
+        indexer,
+        server_url: server_url.into(),
+    })
+}
+
+fn handler(operation_id: &str) -> Option<MethodRouter<ApiState>> {
+    Some(match operation_id {
+        "search" => get(search),
+        "getSymbol" => get(symbols),
+        "getReferences" => get(references),
+        "getOutline" => get(outline),
+        "getStats" => get(stats),
+        "getOpenApi" => get(openapi),
+        _ => return None,
+    })
+}
+
+async fn search(State(state): State<ApiState>, Query(params): Query<SearchParams>) -> ApiResult {
+    let kind = match params.kind.as_deref() {
+        Some(kind) => Some(
+            crate::SymbolKind::parse_ignore_case(kind)
+                .ok_or_else(|| ApiError::bad_request(format!("unknown kind '{kind}'")))?,
+        ),
+        None => None,
+    };
+
+    let indexer = state.indexer.read().await;
+    let limit = indexer
+        .query_budget()
+        .clamp_limit(params.limit.unwrap_or(10));
+    let results = indexer
+        .search(
+            &params.q,
+            limit,
+            kind,
+            params.module.as_deref(),
+            params.lang.as_deref(),
+        )
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    Ok(Json(json!({ "results": results })))
+}
+
+async fn symbols(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Query(params): Query<LangParams>,
+) -> ApiResult {
+    let indexer = state.indexer.read().await;
+    let symbols = find(&indexer, &name, params.lang.as_deref())?;
+    Ok(Json(json!({ "symbols": symbols })))
+}
+
+async fn references(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Query(params): Query<LangParams>,
+) -> ApiResult {
+    let indexer = state.indexer.read().await;
+    let references: Vec<Value> = find(&indexer, &name, params.lang.as_deref())?
+        .into_iter()
+        .map(|symbol| {
+            let mut dependents = indexer.get_dependents(symbol.id);
+            let mut take = |kind| dependents.remove(&kind).unwrap_or_default();
+            json!({
+                "callers": take(RelationKind::Calls),
+                "users": take(RelationKind::Uses),
+                "implementors": take(RelationKind::Implements),
+                "symbol": symbol,
+            })
+        })
+        .collect();
+    Ok(Json(json!({ "references": references })))
+}
+
+async fn outline(State(state): State<ApiState>, Query(params): Query<OutlineParams>) -> ApiResult {
+    let indexer = state.indexer.read().await;
+    let file_id = indexer
+        .get_file_id_for_path(&params.path)
+        .ok_or_else(|| ApiError::not_found(format!("'{}' is not indexed", params.path)))?;
+
+    let mut symbols = indexer.get_symbols_by_file(file_id);
+    symbols.sort_by_key(|symbol| (symbol.range.start_line, symbol.range.start_column));
+    Ok(Json(json!({ "path": params.path, "symbols": symbols })))
+}
+
+async fn stats(State(state): State<ApiState>) -> ApiResult {
+    let indexer = state.indexer.read().await;
+    Ok(Json(json!({
+        "symbols": indexer.symbol_count(),
+        "files": indexer.file_count(),
+        "relationships": indexer.relationship_count(),
+        "embeddings": indexer.semantic_search_embedding_count(),
+    })))
+}
+
+async fn openapi(State(state): State<ApiState>) -> ApiResult {
+    Ok(Json(openapi_spec(&state.server_url)))
+}
+
+fn find(indexer: &IndexFacade, name: &str, lang: Option<&str>) -> Result<Vec<Symbol>, ApiError> {
+    let symbols = indexer.find_symbols_by_name(name, lang);
+    if symbols.is_empty() {
+        return Err(ApiError::not_found(format!("no symbol named '{name}'")));
+    }
+    Ok(symbols)
+}
+
+/// Serve the API on `bind` until Ctrl+C or SIGTERM
+pub async fn serve(facade: IndexFacade, bind: String) -> anyhow::Result<()> {
+    let server_url = format!("http://{bind}");
+    let settings = facade.settings().clone();
+    let indexer = Arc::new(RwLock::new(facade));
+    let router = router(indexer.clone(), &server_url);
+
+    // Pick up re-indexing done by other processes, as the MCP servers do
+    let hot_reload = HotReloadWatcher::new(indexer, settings, HOT_RELOAD_INTERVAL);
+    tokio::spawn(hot_reload.watch());
+
+    let listener = tokio::net::TcpListener::bind(&bind).await?;
+    eprintln!("REST API listening on {server_url}{API_PREFIX}");
+    eprintln!("OpenAPI spec: {server_url}{API_PREFIX}/openapi.json");
+    eprintln!("Press Ctrl+C to stop the server");
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let signal = crate::mcp::shutdown::signal().await;
+            eprintln!("Received {signal}, shutting down REST API...");
+        })
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_endpoint_has_a_handler() {
+        for endpoint in ENDPOINTS {
+            assert!(
+                handler(endpoint.operation_id).is_some(),
+                "{}",
+                endpoint.operation_id
+            );
+        }
+        assert!(handler("deleteEverything").is_none());
+    }
+}
@@ -69,6 +69,10 @@ fn create_custom_help() -> String {
     help.push_str("  parse         Output AST nodes in JSONL format\n");
     help.push_str("  plugin        Manage Claude Code plugins\n");
     help.push_str("  documents     Index and search document collections\n");
+    help.push_str("  events        Show the index event log\n");
+    help.push_str("  summary       Generate file summaries and show the workspace map\n");
+    help.push_str("  vectors       List and drop vector store namespaces\n");
+    help.push_str("  self-update   Update codanna to the latest release\n");
     help.push_str("  help          Print this message or the help of the given subcommand(s)\n\n");
 
     help.push_str("See 'codanna help <command>' for more information on a specific command.\n\n");
@@ -131,8 +135,14 @@ pub enum Commands {
     },
 
     /// Index source files or directories
-    #[command(about = "Build searchable index from codebase")]
+    #[command(
+        about = "Build searchable index from codebase",
+        args_conflicts_with_subcommands = true
+    )]
     Index {
+        #[command(subcommand)]
+        action: Option<IndexAction>,
+
         /// Paths to files or directories to index (multiple paths allowed)
         #[arg(value_name = "PATH")]
         paths: Vec<PathBuf>,
@@ -156,6 +166,38 @@ pub enum Commands {
         /// Maximum number of files to index
         #[arg(long)]
         max_files: Option<usize>,
+
+        /// Index only files git reports as changed since the last indexed commit
+        #[arg(long, conflicts_with_all = ["force", "dry_run"])]
+        git_delta: bool,
+
+        /// Base revision for --git-delta instead of the recorded commit (implies --git-delta)
+        #[arg(long, value_name = "REV", conflicts_with_all = ["force", "dry_run"])]
+        since: Option<String>,
+
+        /// Index only one workspace package (Cargo crate, npm package or Go module)
+        #[arg(long, value_name = "NAME", conflicts_with = "paths")]
+        package: Option<String>,
+
+        /// Shallow-clone a git repository into the remote cache and index it
+        #[arg(
+            long,
+            value_name = "URL",
+            conflicts_with_all = ["paths", "package", "git_delta", "since", "dry_run", "max_files"]
+        )]
+        remote: Option<String>,
+
+        /// Branch, tag or full commit SHA of --remote to index (default: the default branch)
+        #[arg(long, value_name = "REV", requires = "remote")]
+        rev: Option<String>,
+
+        /// Reproducible build: same symbol IDs and index digest for the same files (overrides config)
+        #[arg(long)]
+        deterministic: bool,
+
+        /// Write per-stage throughput, wait and wall-time metrics as JSON (overrides config)
+        #[arg(long, value_name = "PATH")]
+        metrics_json: Option<PathBuf>,
     },
 
     /// Add a directory to the indexed paths list
@@ -188,14 +230,22 @@ pub enum Commands {
     },
 
     /// Show current configuration settings
-    #[command(about = "Display active settings from .codanna/settings.toml")]
-    Config,
+    #[command(
+        about = "Display active settings from .codanna/settings.toml",
+        long_about = "Display active settings from .codanna/settings.toml.\n\nDeprecated keys are migrated to their new names on load and reported as warnings. Use --migrate to rewrite the file with the new names.",
+        after_help = "Examples:\n  codanna config\n  codanna config --migrate"
+    )]
+    Config {
+        /// Rewrite settings.toml with deprecated keys migrated (keeps a .bak copy)
+        #[arg(long)]
+        migrate: bool,
+    },
 
     /// Start MCP server
     #[command(
         about = "Start MCP server",
         long_about = "Start MCP server with optional HTTP/HTTPS modes.",
-        after_help = "Examples:\n  codanna serve\n  codanna serve --http --watch\n  codanna serve --https --watch\n  codanna serve --http --bind 0.0.0.0:3000\n\nModes:\n  Default: stdio\n  --http: HTTP with OAuth\n  --https: HTTPS with TLS"
+        after_help = "Examples:\n  codanna serve\n  codanna serve --http --watch\n  codanna serve --https --watch\n  codanna serve --http --bind 0.0.0.0:3000\n  codanna serve --tcp 127.0.0.1:7777\n\nModes:\n  Default: stdio\n  --tcp: stdio plus local TCP clients sharing the index\n  --http: HTTP with OAuth\n  --https: HTTPS with TLS"
     )]
     Serve {
         /// Watch index file for changes and auto-reload
@@ -229,6 +279,47 @@ pub enum Commands {
             help = "Address to bind HTTP/HTTPS server to"
         )]
         bind: String,
+
+        /// Also accept MCP clients on a loopback TCP address, next to stdio
+        #[arg(
+            long,
+            value_name = "ADDR",
+            conflicts_with_all = ["http", "https"],
+            help = "Also serve MCP on a loopback TCP address (stdio mode only)"
+        )]
+        tcp: Option<String>,
+    },
+
+    /// Run watcher, incremental indexing and MCP server as one background process
+    #[command(
+        about = "Run the file watcher, incremental indexing and HTTP MCP server in one process",
+        long_about = "Catch up on changes made since the last run, then keep the index current with the file watcher while serving MCP over HTTP.\n\nA control socket in the index directory answers 'codanna daemon status', 'pause', 'resume' and 'stop'. While paused, file changes are collected and applied on resume.",
+        after_help = "Examples:\n  codanna daemon\n  codanna daemon --bind 127.0.0.1:3000\n  codanna daemon status --json\n  codanna daemon pause\n  codanna daemon stop",
+        args_conflicts_with_subcommands = true
+    )]
+    Daemon {
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
+
+        /// Bind address for the MCP server (defaults to server.bind)
+        #[arg(long)]
+        bind: Option<String>,
+    },
+
+    /// Serve the index as a local REST API
+    #[command(
+        about = "Serve the index as a local REST API",
+        long_about = "Serve search, symbol, references, outline and stats queries as JSON over HTTP, for dashboards and scripts that do not speak MCP.\n\nThe API is read-only and unauthenticated; keep it bound to localhost unless the network is trusted.",
+        after_help = "Examples:\n  codanna api\n  codanna api --bind 127.0.0.1:9000\n  codanna api --openapi > openapi.json\n  curl 'http://127.0.0.1:8090/api/v1/search?q=parse&limit=5'"
+    )]
+    Api {
+        /// Address to bind the API server to
+        #[arg(long, default_value = crate::api::DEFAULT_BIND)]
+        bind: String,
+
+        /// Print the OpenAPI spec and exit
+        #[arg(long)]
+        openapi: bool,
     },
 
     /// Test MCP connection
@@ -259,7 +350,7 @@ pub enum Commands {
     #[command(
         about = "Execute MCP tools directly",
         long_about = "Execute MCP tools directly without spawning a server.\n\nSupports positional arguments, key=value pairs, and JSON arguments.",
-        after_help = "Tools:\n  find_symbol       <name>              Exact name lookup\n  search_symbols    query:<text>        Fuzzy text search (kind:<type> limit:<n>)\n  get_calls         <name|symbol_id:N>  What this symbol calls\n  find_callers      <name|symbol_id:N>  What calls this symbol\n  analyze_impact    <name|symbol_id:N>  Full dependency graph\n  semantic_search_docs query:<text>     Code search by meaning\n  semantic_search_with_context query:<text>  Search with relationships\n  search_documents  query:<text>        Search markdown/text docs\n  get_index_info                        Index stats\n\nExamples:\n  codanna mcp find_symbol <name>\n  codanna mcp search_symbols query:<text> kind:function\n  codanna mcp get_calls <name>\n  codanna mcp get_calls symbol_id:<N>\n  codanna mcp semantic_search_docs query:\"<text>\" limit:5\n  codanna mcp search_symbols query:<text> --json | jq '.data[].symbol_id'"
+        after_help = "Tools:\n  find_symbol       <name>              Exact name lookup\n  search_symbols    query:<text>        Fuzzy text search (kind:<type> limit:<n>)\n  get_calls         <name|symbol_id:N>  What this symbol calls\n  find_callers      <name|symbol_id:N>  What calls this symbol\n  find_implementations <name|symbol_id:N>  Implementors and overrides\n  analyze_impact    <name|symbol_id:N>  Ranked impact of a change (or file_path:<path>)\n  test_context_for  <name|symbol_id:N>  Everything needed to write a test\n  semantic_search_docs query:<text>     Code search by meaning\n  semantic_search_with_context query:<text>  Search with relationships\n  search_documents  query:<text>        Search markdown/text docs\n  search_knowledge  query:<text>        Code and docs/ADRs in one ranking\n  find_discussions  <name|path>         Past AI conversations mentioning it\n  get_diagnostics   [path]              Parse errors and low-confidence symbols\n  get_file_outline  <path>              Nested symbols of a file\n  get_symbol_source <name|symbol_id:N>  Source of a symbol with its context (context:<n>)\n  get_dependency_graph [path]           File dependency graph as JSON (depth:<n> direction:<dir>)\n  find_similar_symbols <name|symbol_id:N>  Existing symbols that do the same job (snippet:<code> threshold:<0-1>)\n  find_unused_symbols [path]            Symbols nothing references (package:<name> kind:<type>)\n  reindex_paths     <path>              Re-index files now and report symbol changes (force:true)\n  translate_query   query:<text>        Natural-language request as a structured query\n  batch_query       --args '{\"queries\":[...]}'  Several queries in one call\n  get_index_info                        Index stats\n  list_projects                         Registered projects (MCP server: project:<name> on any tool)\n\nExamples:\n  codanna mcp find_symbol <name>\n  codanna mcp search_symbols query:<text> kind:function\n  codanna mcp get_calls <name>\n  codanna mcp get_calls symbol_id:<N>\n  codanna mcp semantic_search_docs query:\"<text>\" limit:5\n  codanna mcp batch_query --args '{\"queries\":[{\"tool\":\"find_callers\",\"arguments\":{\"function_name\":\"<name>\"}}]}'\n  codanna mcp search_symbols query:<text> --json | jq '.data[].symbol_id'"
     )]
     Mcp {
         /// Tool to call
@@ -366,6 +457,485 @@ pub enum Commands {
         #[command(subcommand)]
         action: crate::profiles::commands::ProfileAction,
     },
+
+    /// Update codanna from GitHub releases
+    #[command(
+        name = "self-update",
+        about = "Update codanna to the latest release",
+        long_about = "Download a codanna release from GitHub, verify its checksum, and replace the running binary.\n\nPass a version to install a specific release, including an older one whose index schema matches an existing index.",
+        after_help = "Examples:\n  codanna self-update\n  codanna self-update --check\n  codanna self-update 0.9.8"
+    )]
+    SelfUpdate {
+        /// Release to install (defaults to the latest)
+        #[arg(value_name = "VERSION")]
+        version: Option<String>,
+
+        /// Only report available updates and index compatibility
+        #[arg(long)]
+        check: bool,
+
+        /// Reinstall even if already on that version
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Show the index event log
+    #[command(
+        about = "Show file and symbol changes recorded in the index event log",
+        long_about = "Print events from <index_path>/events.jsonl.\n\nEnable the log with 'event_log = true' under [indexing] in settings.toml. Each event records a file that was added, updated, or removed, with the symbols it gained or lost.",
+        after_help = "Examples:\n  codanna events\n  codanna events --since 120 --json\n  codanna events --follow\n  codanna events --replay"
+    )]
+    Events {
+        /// Only show events with a sequence number greater than this
+        #[arg(long, default_value_t = 0)]
+        since: u64,
+
+        /// Keep running and print new events as they are written
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Summarize the index rebuilt from the log instead of listing events
+        #[arg(long, conflicts_with = "follow")]
+        replay: bool,
+
+        /// Output in JSON format (one event per line)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List past AI discussions about a symbol or file
+    #[command(
+        about = "List past AI conversations that mention a symbol or file",
+        long_about = "Search links recorded when the context watcher processes session exports.\n\nEvery message is scanned for file paths and symbol names; qualified names match on their trailing segments, so 'run' also finds 'ContextWatcher::run'.",
+        after_help = "Examples:\n  codanna conversations ContextWatcher::run\n  codanna conversations src/watcher/context_watcher.rs --limit 5\n  codanna conversations parse_file --session 3f2a --json"
+    )]
+    Conversations {
+        /// Symbol name or file path
+        query: String,
+
+        /// Only show messages from sessions whose id starts with this
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Maximum number of messages to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+
+        /// Output in JSON format (one link per line)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show parse errors recorded during indexing
+    #[command(
+        about = "Show parse errors in indexed files and the symbols they affect",
+        long_about = "List the regions tree-sitter could not parse (ERROR and MISSING nodes) in each indexed file.\n\nSymbols overlapping one of these regions may be cut short or have a wrong signature and are reported as low-confidence. Definitions inside an error region may be missing from the index entirely.",
+        after_help = "Examples:\n  codanna diagnostics\n  codanna diagnostics src/parser\n  codanna diagnostics --json"
+    )]
+    Diagnostics {
+        /// Only show files whose path contains this
+        path: Option<String>,
+
+        /// Output in JSON format (one file per line)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Generate natural-language file summaries
+    #[command(
+        about = "Generate cached file summaries and show the workspace map",
+        long_about = "Ask the LLM endpoint configured under [summary] in settings.toml for a short summary of each indexed file.\n\nSummaries are cached in <index_path>/summaries.json by content hash, so only changed files are resent. Cached summaries appear in search results.",
+        after_help = "Examples:\n  codanna summary\n  codanna summary --force\n  codanna summary --map"
+    )]
+    Summary {
+        /// Regenerate every summary, even for unchanged files
+        #[arg(short, long, conflicts_with = "map")]
+        force: bool,
+
+        /// Print cached summaries grouped by directory instead of refreshing
+        #[arg(long)]
+        map: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Manage vector store namespaces
+    #[command(
+        about = "List and drop vector store namespaces",
+        long_about = "Embeddings are stored in namespaces by content type (code, docs, conversations).\n\nEach namespace has its own directory, so dropping one leaves the others untouched.",
+        after_help = "Examples:\n  codanna vectors list\n  codanna vectors list --json\n  codanna vectors drop docs"
+    )]
+    Vectors {
+        #[command(subcommand)]
+        action: VectorAction,
+    },
+
+    /// Run index maintenance tasks
+    #[command(
+        about = "Compact the index, prune stale vectors and trim old index events",
+        long_about = "Maintenance tasks:\n  compact    Merge index segments and purge deleted documents\n  vectors    Drop embeddings of symbols no longer indexed\n  retention  Drop index events older than maintenance.event_retention_days\n\nWith [maintenance] enabled = true, 'codanna serve' runs due tasks while it is idle. 'run' starts them now, regardless of load or time window.",
+        after_help = "Examples:\n  codanna maintenance status\n  codanna maintenance run\n  codanna maintenance run compact"
+    )]
+    Maintenance {
+        #[command(subcommand)]
+        action: MaintenanceAction,
+    },
+
+    /// Analyze the indexed code
+    #[command(
+        about = "Report on the indexed code, such as likely duplicated logic or APIs across languages",
+        after_help = "Examples:\n  codanna analyze clusters\n  codanna analyze clusters --path src/parsing --threshold 0.95\n  codanna analyze links --dry-run"
+    )]
+    Analyze {
+        #[command(subcommand)]
+        action: AnalyzeAction,
+    },
+
+    /// Export the index for other tools
+    #[command(
+        about = "Write symbols, relationships and files as Parquet tables for SQL analytics",
+        after_help = "Examples:\n  codanna export parquet exports/2026-10-16\n  duckdb -c \"SELECT kind, count(*) FROM 'exports/2026-10-16/symbols.parquet' GROUP BY kind\""
+    )]
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+
+    /// Manage the semantic index
+    #[command(
+        about = "Re-embed the semantic index or calibrate its similarity thresholds",
+        after_help = "Examples:\n  codanna semantic migrate\n  codanna semantic calibrate"
+    )]
+    Semantic {
+        #[command(subcommand)]
+        action: SemanticAction,
+    },
+
+    /// Record a command into a scenario bundle
+    #[command(
+        about = "Run a command and record it into a bundle for bug reports",
+        long_about = "Run a codanna command and append its arguments, exit code, timing and error output to a JSON bundle, together with the settings and the indexed files (language, size, line count).\n\nPaths are anonymized with a salted hash that keeps file extensions; the salt stays in the index directory. Use --include-sources to record real paths and file contents instead.\n\nRecord several commands into the same bundle to capture a scenario, then attach the bundle to a bug report.",
+        after_help = "Examples:\n  codanna record -- index src --force\n  codanna record -- retrieve symbol Parser\n  codanna record --include-sources --bundle parser-bug.json -- index src/parser.rs"
+    )]
+    Record {
+        /// Bundle to create or append to
+        #[arg(long, default_value = "codanna-record.json")]
+        bundle: PathBuf,
+
+        /// Record real paths and file contents instead of anonymizing them
+        #[arg(long)]
+        include_sources: bool,
+
+        /// Command to run, after --
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Replay a scenario bundle
+    #[command(
+        about = "Rerun the commands of a recorded bundle in a scratch workspace",
+        long_about = "Rebuild the recorded workspace in a temporary directory with the recorded settings, run each step and compare exit codes with the recording.\n\nBundles recorded without --include-sources replace each file with blank text of the same size and line count. That reproduces file discovery, ignore rules and configuration, but not parser behavior.",
+        after_help = "Examples:\n  codanna replay codanna-record.json\n  codanna replay bug.json --keep --verbose"
+    )]
+    Replay {
+        /// Bundle written by 'codanna record'
+        bundle: PathBuf,
+
+        /// Keep the replay workspace instead of deleting it
+        #[arg(long)]
+        keep: bool,
+
+        /// Show the output of each replayed command
+        #[arg(short, long)]
+        verbose: bool,
+    },
+}
+
+/// Index snapshot actions
+#[derive(Subcommand)]
+pub enum IndexAction {
+    /// Package the index into a portable archive
+    #[command(
+        about = "Write the index, vectors and metadata to a .tar.zst snapshot",
+        after_help = "Example:\n  codanna index export codanna-index.tar.zst"
+    )]
+    Export {
+        /// Archive to write
+        #[arg(value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Replace the index with a snapshot
+    #[command(
+        about = "Unpack a snapshot written by 'codanna index export'",
+        after_help = "Examples:\n  codanna index import codanna-index.tar.zst\n  codanna index import codanna-index.tar.zst --force"
+    )]
+    Import {
+        /// Archive to read
+        #[arg(value_name = "FILE")]
+        archive: PathBuf,
+
+        /// Replace an existing index
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Upload the index to an object-store bucket
+    #[command(
+        about = "Upload the index to S3, GCS or a directory, sending only chunks the bucket lacks",
+        long_about = "Upload the index to an object-store bucket. Files are split into content-addressed chunks, so only chunks the bucket does not hold yet are sent. Credentials come from the environment (AWS_ACCESS_KEY_ID, AWS_REGION, GOOGLE_APPLICATION_CREDENTIALS, ...).",
+        after_help = "Examples:\n  codanna index push s3://my-bucket/codanna/my-repo\n  codanna index push gs://my-bucket/my-repo --tag main"
+    )]
+    Push {
+        /// s3://bucket/prefix, gs://bucket/prefix or a directory
+        #[arg(value_name = "REMOTE")]
+        remote: String,
+
+        /// Name the pushed index is pulled by
+        #[arg(long, default_value = "latest")]
+        tag: String,
+    },
+
+    /// Replace the index with one pushed to an object-store bucket
+    #[command(
+        about = "Download an index pushed with 'codanna index push', fetching only chunks the local index lacks",
+        after_help = "Examples:\n  codanna index pull s3://my-bucket/codanna/my-repo\n  codanna index pull gs://my-bucket/my-repo --tag main"
+    )]
+    Pull {
+        /// s3://bucket/prefix, gs://bucket/prefix or a directory
+        #[arg(value_name = "REMOTE")]
+        remote: String,
+
+        /// Name the index was pushed with
+        #[arg(long, default_value = "latest")]
+        tag: String,
+    },
+
+    /// Compare two index states
+    #[command(
+        about = "Report symbols and relationships added, removed or changed between two indexes",
+        long_about = "Compare two index states, each a snapshot archive from 'codanna index export' or an index directory. Symbols are matched by file, module path and name, so the two indexes may come from different builds or checkouts.",
+        after_help = "Examples:\n  codanna index diff main.tar.zst pr.tar.zst --json\n  codanna index diff main.tar.zst .codanna/index"
+    )]
+    Diff {
+        /// Index state before the change: snapshot archive or index directory
+        #[arg(value_name = "BEFORE")]
+        before: PathBuf,
+
+        /// Index state after the change: snapshot archive or index directory
+        #[arg(value_name = "AFTER")]
+        after: PathBuf,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show index statistics and health
+    #[command(
+        about = "Show file, symbol and relationship counts, vector coverage, stale files and storage size",
+        after_help = "Examples:\n  codanna index stats\n  codanna index stats --json"
+    )]
+    Stats {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Control requests for a running daemon
+#[derive(Subcommand, Clone, Copy)]
+pub enum DaemonAction {
+    /// Show the daemon's state
+    #[command(
+        about = "Show whether the daemon is paused, pending changes and index size",
+        after_help = "Example:\n  codanna daemon status --json"
+    )]
+    Status {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Stop applying file changes
+    #[command(about = "Stop applying file changes; they are collected until resume")]
+    Pause,
+
+    /// Apply file changes again
+    #[command(about = "Apply file changes again, including those collected while paused")]
+    Resume,
+
+    /// Shut the daemon down
+    #[command(about = "Flush pending changes, save the index and shut the daemon down")]
+    Stop,
+}
+
+/// Vector namespace actions
+#[derive(Subcommand)]
+pub enum VectorAction {
+    /// List namespaces with data
+    #[command(
+        about = "List vector namespaces with their size and embedding count",
+        after_help = "Example:\n  codanna vectors list --json"
+    )]
+    List {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Delete one namespace's vectors
+    #[command(
+        about = "Delete the vectors of one namespace",
+        after_help = "Examples:\n  codanna vectors drop docs\n  codanna vectors drop conversations"
+    )]
+    Drop {
+        /// Namespace to drop: code, docs, or conversations
+        namespace: String,
+    },
+}
+
+/// Maintenance actions
+#[derive(Subcommand)]
+pub enum MaintenanceAction {
+    /// Run tasks now
+    #[command(
+        about = "Run maintenance tasks now, ignoring load and time window",
+        after_help = "Examples:\n  codanna maintenance run\n  codanna maintenance run compact vectors"
+    )]
+    Run {
+        /// Tasks to run: compact, vectors, retention (default: all)
+        tasks: Vec<String>,
+    },
+
+    /// Show when each task last ran
+    #[command(
+        about = "Show when each maintenance task last ran and whether it is due",
+        after_help = "Example:\n  codanna maintenance status --json"
+    )]
+    Status {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Code analysis reports
+#[derive(Subcommand)]
+pub enum AnalyzeAction {
+    /// Group symbols by embedding similarity
+    #[command(
+        about = "Group symbols with near-identical embeddings to find duplicated logic",
+        long_about = "Compare the embeddings of indexed symbols pairwise and group those at least --threshold similar. Symbols in one group are documented or, with semantic_search.chunking = \"symbol\", written alike, which often means duplicated logic.\n\nEvery pair is compared, so scope large repositories with --path.\n\nRequires semantic search to be enabled.",
+        after_help = "Examples:\n  codanna analyze clusters\n  codanna analyze clusters --path src/parsing --min-size 3\n  codanna analyze clusters --threshold 0.95 --language rust --json"
+    )]
+    Clusters {
+        /// Similarity from 0 to 1 two symbols need to be grouped
+        #[arg(long, default_value_t = 0.9)]
+        threshold: f32,
+
+        /// Smallest group reported
+        #[arg(long, default_value_t = 2)]
+        min_size: usize,
+
+        /// Only compare symbols under this directory, or matching this glob
+        #[arg(long, value_name = "DIR")]
+        path: Option<String>,
+
+        /// Only compare symbols of this language
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Maximum number of groups shown
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Link functions of different languages that implement the same API
+    #[command(
+        about = "Link functions of different languages that implement the same API, such as a client method and its server handler",
+        long_about = "Find functions and methods of different languages that likely implement the same API: a TypeScript client method and the Rust handler of the endpoint it requests, say. Pairs must share a route literal (\"/users/{id}\" and `/users/${id}`) or a name of the same words (getUser and get_user_handler); their embedding similarity, raised by those signals, is the link's confidence.\n\nLinks are stored as low-confidence 'mirrors' relationships, replacing the previous run's, and shown by 'retrieve describe'. Re-indexing a file drops the links of its symbols; run this again afterwards.\n\nRequires semantic search to be enabled.",
+        after_help = "Examples:\n  codanna analyze links\n  codanna analyze links --dry-run --threshold 0.8\n  codanna analyze links --path services --json"
+    )]
+    Links {
+        /// Confidence from 0 to 1 a pair needs to be linked
+        #[arg(long, default_value_t = 0.75)]
+        threshold: f32,
+
+        /// Only link symbols under this directory, or matching this glob
+        #[arg(long, value_name = "DIR")]
+        path: Option<String>,
+
+        /// Report the links without storing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Maximum number of links shown
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Index export formats
+#[derive(Subcommand)]
+pub enum ExportAction {
+    /// Write the index as Parquet tables
+    #[command(
+        about = "Write symbols.parquet, relationships.parquet and files.parquet into a directory",
+        long_about = "Write the index as three Parquet tables, for DuckDB, Spark or a data warehouse:\n  symbols        id, name, kind, language, file, module, visibility, lines, signature, docs\n  relationships  from and to symbol ids and files, kind (Calls, Implements, ...), weight\n  files          id, path, language, content hash, modification time, symbol count\n\nPaths are relative to the workspace root, so exports of different checkouts or dates compare; symbol and file ids only hold within one export. Existing tables in the directory are replaced.",
+        after_help = "Examples:\n  codanna export parquet exports/$(date +%F)\n  duckdb -c \"SELECT visibility, count(*) FROM 'exports/*/symbols.parquet' GROUP BY ALL\""
+    )]
+    Parquet {
+        /// Directory to write the tables into, created if missing
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+    },
+}
+
+/// Semantic index actions
+#[derive(Subcommand)]
+pub enum SemanticAction {
+    /// Re-embed every symbol with the configured model
+    #[command(
+        about = "Re-embed the index after changing semantic_search.model or provider",
+        long_about = "Embed every indexed symbol again with the model semantic_search selects, without re-parsing.\n\nThe new vectors are built beside the index and swap in for the old ones once complete, so a running 'codanna serve' keeps searching the old vectors meanwhile and reloads the new ones afterwards. An interrupted migration leaves the old vectors in place.\n\nDoes nothing when the index already uses the configured model, unless --force.",
+        after_help = "Examples:\n  codanna semantic migrate\n  codanna semantic migrate --force"
+    )]
+    Migrate {
+        /// Re-embed even when the index already uses the configured model
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Measure similarity thresholds for the model of the index
+    #[command(
+        about = "Recommend similarity thresholds for the model of the index and save them",
+        long_about = "Score labeled pairs of symbols with the model of the semantic index: changed symbols against their earlier version in git, which should match, and random pairs of symbols, which should not.\n\nFrom the two score distributions it recommends a 'search' threshold, the default floor of semantic search scores, and a 'similar' threshold, the default of find_similar_symbols. They are saved to settings.toml under semantic_search.calibrated for that model.",
+        after_help = "Examples:\n  codanna semantic calibrate\n  codanna semantic calibrate --since v0.9.0 --samples 1000\n  codanna semantic calibrate --dry-run"
+    )]
+    Calibrate {
+        /// Revision earlier versions are read from (default: 50 commits before HEAD)
+        #[arg(long, value_name = "REV")]
+        since: Option<String>,
+
+        /// Most pairs of each label scored
+        #[arg(long, default_value_t = 500)]
+        samples: usize,
+
+        /// Print the recommendation without saving it
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 /// Plugin management actions
@@ -630,7 +1200,7 @@ pub enum RetrieveQuery {
 
     /// Search for symbols using full-text search
     #[command(
-        after_help = "Examples:\n  # Traditional flag format\n  codanna retrieve search \"parse\" --limit 5 --kind function\n  \n  # Key:value format (Unix-style)\n  codanna retrieve search query:parse limit:5 kind:function\n  \n  # Mixed format\n  codanna retrieve search \"parse\" limit:5 --json"
+        after_help = "Examples:\n  # Traditional flag format\n  codanna retrieve search \"parse\" --limit 5 --kind function\n  \n  # Key:value format (Unix-style)\n  codanna retrieve search query:parse limit:5 kind:function\n  \n  # Mixed format\n  codanna retrieve search \"parse\" limit:5 --json\n  \n  # Pick a result interactively and open it\n  vim $(codanna retrieve search parse --pick)"
     )]
     Search {
         /// Positional arguments (query and/or key:value pairs)
@@ -649,6 +1219,56 @@ pub enum RetrieveQuery {
         #[arg(short, long)]
         module: Option<String>,
 
+        /// Choose a result interactively and print its path:line:col
+        #[arg(long, conflicts_with = "json")]
+        pick: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Search with a natural-language request
+    #[command(
+        long_about = "Search with a natural-language request.\n\nFilters named in the request (kind, visibility, language, module or path, repository) are taken out of it and applied; the remaining words are searched for. The translator is chosen by query.translator: \"rules\" (default) or \"llm\", which uses the [summary] chat endpoint.",
+        after_help = "Examples:\n  codanna retrieve ask public async functions in the auth module that touch the database\n  codanna retrieve ask \"rust structs in src/parsing\" --limit 20\n  codanna retrieve ask \"ts interfaces in the backend repo\" --translate --json"
+    )]
+    Ask {
+        /// The request, as one or more words
+        #[arg(num_args = 1.., required = true)]
+        args: Vec<String>,
+
+        /// Maximum number of results
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+
+        /// Print the structured query instead of searching
+        #[arg(long)]
+        translate: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Search code and documentation together
+    #[command(
+        long_about = "Search doc comments and indexed documents (markdown, READMEs, ADRs) with one query.\n\nResults of both are ranked together and marked with where they come from. A document chunk that names a symbol among the results lists its symbol_id. Documents are searched once 'codanna documents index' has built the document store.",
+        after_help = "Examples:\n  codanna retrieve knowledge how are symbols chunked for embedding\n  codanna retrieve knowledge \"vector storage layout\" --collection adr --json"
+    )]
+    Knowledge {
+        /// The query, as one or more words
+        #[arg(num_args = 1.., required = true)]
+        args: Vec<String>,
+
+        /// Search only this document collection
+        #[arg(long)]
+        collection: Option<String>,
+
+        /// Maximum number of results
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+
         /// Output in JSON format
         #[arg(long)]
         json: bool,
@@ -666,4 +1286,17 @@ pub enum RetrieveQuery {
         #[arg(long)]
         json: bool,
     },
+
+    /// Show which commits introduced and last modified a symbol
+    #[command(
+        after_help = "Examples:\n  codanna retrieve blame parse_file\n  codanna retrieve blame symbol_id:1771 --json\n\nRequires indexing.blame = true when the index was built."
+    )]
+    Blame {
+        /// Positional arguments (symbol name and/or key:value pairs)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
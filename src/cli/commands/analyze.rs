@@ -0,0 +1,256 @@
+//! Analyze command - reports on the indexed code.
+
+use crate::cli::args::AnalyzeAction;
+use crate::indexing::facade::{IndexFacade, SemanticFilter, SymbolCluster};
+use crate::io::exit_code::ExitCode;
+use crate::semantic::CrossLanguageLink;
+
+/// Run the analyze command.
+pub fn run(action: AnalyzeAction, indexer: &IndexFacade) -> ExitCode {
+    match action {
+        AnalyzeAction::Clusters {
+            threshold,
+            min_size,
+            path,
+            language,
+            limit,
+            json,
+        } => clusters(
+            indexer,
+            threshold,
+            min_size,
+            path.as_deref(),
+            language,
+            limit,
+            json,
+        ),
+        AnalyzeAction::Links {
+            threshold,
+            path,
+            dry_run,
+            limit,
+            json,
+        } => links(indexer, threshold, path.as_deref(), dry_run, limit, json),
+    }
+}
+
+fn clusters(
+    indexer: &IndexFacade,
+    threshold: f32,
+    min_size: usize,
+    path: Option<&str>,
+    language: Option<String>,
+    limit: usize,
+    json: bool,
+) -> ExitCode {
+    if !indexer.has_semantic_search() {
+        eprintln!("Semantic search is not enabled");
+        eprintln!("Set semantic_search.enabled = true and run 'codanna index --force'");
+        return ExitCode::ConfigError;
+    }
+    let path = match path.map(scope_pattern).transpose() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    let filter = SemanticFilter {
+        language,
+        path,
+        ..Default::default()
+    };
+
+    let clusters = match indexer.semantic_clusters(threshold, min_size, &filter) {
+        Ok(clusters) => clusters,
+        Err(e) => {
+            eprintln!("Failed to cluster symbols: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    let found = clusters.len();
+
+    if json {
+        let entries: Vec<serde_json::Value> = clusters.iter().take(limit).map(to_json).collect();
+        let report = serde_json::json!({
+            "threshold": threshold,
+            "clusters": entries,
+            "total_clusters": found,
+        });
+        println!("{report}");
+    } else if found == 0 {
+        eprintln!("No symbols at least {threshold} similar");
+    } else {
+        for (number, cluster) in clusters.iter().take(limit).enumerate() {
+            println!(
+                "Cluster {} - {} symbols, similarity {:.3}",
+                number + 1,
+                cluster.symbols.len(),
+                cluster.similarity
+            );
+            for symbol in &cluster.symbols {
+                println!(
+                    "  {:?} {} at {}:{} [symbol_id:{}]",
+                    symbol.kind,
+                    symbol.name,
+                    symbol.file_path,
+                    symbol.range.start_line + 1,
+                    symbol.id.value()
+                );
+            }
+            println!();
+        }
+        if found > limit {
+            println!("{} more clusters not shown (use --limit)", found - limit);
+        }
+    }
+
+    if found == 0 {
+        ExitCode::NotFound
+    } else {
+        ExitCode::Success
+    }
+}
+
+fn links(
+    indexer: &IndexFacade,
+    threshold: f32,
+    path: Option<&str>,
+    dry_run: bool,
+    limit: usize,
+    json: bool,
+) -> ExitCode {
+    if !indexer.has_semantic_search() {
+        eprintln!("Semantic search is not enabled");
+        eprintln!("Set semantic_search.enabled = true and run 'codanna index --force'");
+        return ExitCode::ConfigError;
+    }
+    let path = match path.map(scope_pattern).transpose() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    let filter = SemanticFilter {
+        path,
+        ..Default::default()
+    };
+
+    let links = match indexer.cross_language_links(threshold, &filter) {
+        Ok(links) => links,
+        Err(e) => {
+            eprintln!("Failed to link symbols: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    let found = links.len();
+
+    if json {
+        let entries: Vec<serde_json::Value> = links
+            .iter()
+            .take(limit)
+            .filter_map(|link| link_to_json(indexer, link))
+            .collect();
+        let report = serde_json::json!({
+            "threshold": threshold,
+            "links": entries,
+            "total_links": found,
+            "stored": !dry_run,
+        });
+        println!("{report}");
+    } else if found == 0 {
+        eprintln!("No functions of different languages linked at confidence {threshold}");
+    } else {
+        for link in links.iter().take(limit) {
+            let (Some(from), Some(to)) =
+                (indexer.get_symbol(link.from), indexer.get_symbol(link.to))
+            else {
+                continue;
+            };
+            println!("{:.2}  {}", link.confidence, link.evidence);
+            for symbol in [&from, &to] {
+                println!(
+                    "  {} ({}) at {}:{} [symbol_id:{}]",
+                    symbol.name,
+                    symbol
+                        .language_id
+                        .map(|id| id.as_str())
+                        .unwrap_or("unknown"),
+                    symbol.file_path,
+                    symbol.range.start_line + 1,
+                    symbol.id.value()
+                );
+            }
+            println!();
+        }
+        if found > limit {
+            println!("{} more links not shown (use --limit)", found - limit);
+        }
+    }
+
+    if !dry_run {
+        match indexer.store_cross_language_links(&links) {
+            Ok(stored) => {
+                eprintln!("Stored {stored} links; see them with 'codanna retrieve describe'")
+            }
+            Err(e) => {
+                eprintln!("Failed to store links: {e}");
+                return ExitCode::IoError;
+            }
+        }
+    }
+
+    if found == 0 {
+        ExitCode::NotFound
+    } else {
+        ExitCode::Success
+    }
+}
+
+/// Glob for `--path`: a directory matches everything below it
+fn scope_pattern(path: &str) -> Result<glob::Pattern, String> {
+    let glob = if path.contains(['*', '?', '[']) {
+        path.to_string()
+    } else {
+        format!("{}/**", glob::Pattern::escape(path.trim_end_matches('/')))
+    };
+    glob::Pattern::new(&glob).map_err(|e| format!("Invalid path '{path}': {e}"))
+}
+
+fn to_json(cluster: &SymbolCluster) -> serde_json::Value {
+    serde_json::json!({
+        "similarity": cluster.similarity,
+        "symbols": cluster.symbols.iter().map(|symbol| {
+            serde_json::json!({
+                "name": &*symbol.name,
+                "kind": format!("{:?}", symbol.kind),
+                "file": &*symbol.file_path,
+                "line": symbol.range.start_line + 1,
+                "symbol_id": symbol.id.value(),
+            })
+        }).collect::<Vec<_>>(),
+    })
+}
+
+fn link_to_json(indexer: &IndexFacade, link: &CrossLanguageLink) -> Option<serde_json::Value> {
+    let symbol = |id| {
+        indexer.get_symbol(id).map(|symbol| {
+            serde_json::json!({
+                "name": &*symbol.name,
+                "kind": format!("{:?}", symbol.kind),
+                "language": symbol.language_id.map(|id| id.as_str()),
+                "file": &*symbol.file_path,
+                "line": symbol.range.start_line + 1,
+                "symbol_id": symbol.id.value(),
+            })
+        })
+    };
+    Some(serde_json::json!({
+        "confidence": link.confidence,
+        "similarity": link.similarity,
+        "evidence": link.evidence.to_string(),
+        "from": symbol(link.from)?,
+        "to": symbol(link.to)?,
+    }))
+}
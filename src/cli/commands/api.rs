@@ -0,0 +1,28 @@
+//! Api command - local REST API over the index.
+
+use crate::api::{self, API_PREFIX};
+use crate::indexing::facade::IndexFacade;
+
+/// Run the api command.
+///
+/// Prints the OpenAPI spec with `--openapi`, otherwise serves until interrupted.
+pub async fn run(facade: IndexFacade, bind: String, openapi: bool) {
+    if openapi {
+        let spec = api::openapi_spec(&format!("http://{bind}"));
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&spec).unwrap_or_else(|_| spec.to_string())
+        );
+        return;
+    }
+
+    eprintln!(
+        "Serving {} symbols from {} files",
+        facade.symbol_count(),
+        facade.file_count()
+    );
+    if let Err(e) = api::serve(facade, bind.clone()).await {
+        eprintln!("REST API error on {bind}{API_PREFIX}: {e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,63 @@
+//! Conversations command - list past AI discussions about a symbol or file.
+
+use crate::conversations::{LinkStore, MentionKind, MessageLink};
+use crate::io::exit_code::ExitCode;
+
+/// Run the conversations command.
+pub fn run(query: &str, session: Option<&str>, limit: usize, json: bool) -> ExitCode {
+    let store = LinkStore::default_location();
+    let links = match store.find(query, usize::MAX) {
+        Ok(links) => links,
+        Err(e) => {
+            eprintln!("Failed to read {}: {e}", store.path().display());
+            return ExitCode::IoError;
+        }
+    };
+    let links: Vec<MessageLink> = links
+        .into_iter()
+        .filter(|link| session.is_none_or(|id| link.session_id.starts_with(id)))
+        .take(limit)
+        .collect();
+
+    if json {
+        for link in &links {
+            if let Ok(line) = serde_json::to_string(link) {
+                println!("{line}");
+            }
+        }
+    } else if links.is_empty() {
+        eprintln!("No conversations mention '{query}'");
+        if !store.path().exists() {
+            eprintln!(
+                "Links are recorded when the context watcher processes session exports into {}",
+                store.path().display()
+            );
+        }
+    } else {
+        for link in &links {
+            print_link(link);
+        }
+    }
+
+    if links.is_empty() {
+        ExitCode::NotFound
+    } else {
+        ExitCode::Success
+    }
+}
+
+fn print_link(link: &MessageLink) {
+    let kind = match link.kind {
+        MentionKind::File => "file",
+        MentionKind::Symbol => "symbol",
+    };
+    println!(
+        "{} session {} [{}] {} {}",
+        link.timestamp.as_deref().unwrap_or("-"),
+        link.session_id,
+        link.role.as_deref().unwrap_or("message"),
+        kind,
+        link.mention
+    );
+    println!("  {}", link.excerpt);
+}
@@ -0,0 +1,108 @@
+//! Daemon command - long-running watcher + MCP server and its control client.
+
+use crate::cli::args::DaemonAction;
+use crate::config::Settings;
+use crate::indexing::facade::IndexFacade;
+use crate::io::exit_code::ExitCode;
+use crate::mcp::daemon::{self, DaemonRequest, DaemonStatus};
+use std::io;
+
+/// Start the daemon with an index already synced to the configuration.
+pub async fn run(config: Settings, facade: IndexFacade, bind: Option<String>) {
+    let bind = bind.unwrap_or_else(|| config.server.bind.clone());
+    let socket = daemon::socket_path(&config.index_path);
+
+    eprintln!("Starting codanna daemon");
+    eprintln!("Control socket: {}", socket.display());
+
+    use crate::mcp::http_server::serve_daemon;
+    if let Err(e) = serve_daemon(config, bind, facade, socket).await {
+        eprintln!("Daemon error: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Send a control request to the daemon serving this index.
+pub fn control(action: DaemonAction, config: &Settings) -> ExitCode {
+    let (request, json) = match action {
+        DaemonAction::Status { json } => (DaemonRequest::Status, json),
+        DaemonAction::Pause => (DaemonRequest::Pause, false),
+        DaemonAction::Resume => (DaemonRequest::Resume, false),
+        DaemonAction::Stop => (DaemonRequest::Stop, false),
+    };
+
+    let socket = daemon::socket_path(&config.index_path);
+    let status = match daemon::send(&socket, request) {
+        Ok(status) => status,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            eprintln!("No daemon is running for {}", config.index_path.display());
+            eprintln!("Start one with 'codanna daemon'");
+            return ExitCode::NotFound;
+        }
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => {
+            eprintln!("Error: {e}");
+            return ExitCode::UnsupportedOperation;
+        }
+        Err(e) => {
+            eprintln!("Error: Could not reach daemon at {}: {e}", socket.display());
+            return ExitCode::IoError;
+        }
+    };
+
+    match request {
+        DaemonRequest::Status if json => match serde_json::to_string_pretty(&status) {
+            Ok(output) => println!("{output}"),
+            Err(e) => eprintln!("Failed to serialize daemon status: {e}"),
+        },
+        DaemonRequest::Status => print_status(&status),
+        DaemonRequest::Pause => {
+            println!("Daemon paused; file changes are collected until 'codanna daemon resume'")
+        }
+        DaemonRequest::Resume => println!(
+            "Daemon resumed; applying {} pending changes",
+            status.pending_changes
+        ),
+        DaemonRequest::Stop => println!("Daemon (pid {}) is shutting down", status.pid),
+    }
+    ExitCode::Success
+}
+
+fn print_status(status: &DaemonStatus) {
+    let state = if status.stopping {
+        "stopping"
+    } else if status.paused {
+        "paused"
+    } else {
+        "running"
+    };
+    println!("Daemon {state} (pid {})", status.pid);
+    println!("  MCP endpoint:    http://{}/mcp", status.bind);
+    println!("  Started:         {}", format_time(status.started_at));
+    println!(
+        "  Index:           {} symbols in {} files",
+        status.symbols, status.files
+    );
+    println!("  Pending changes: {}", status.pending_changes);
+    println!(
+        "  Applied:         {} re-indexed, {} removed",
+        status.files_reindexed, status.files_removed
+    );
+    if let Some(last) = status.last_change {
+        println!("  Last change:     {}", format_time(last));
+    }
+}
+
+fn format_time(unix_secs: u64) -> String {
+    chrono::DateTime::from_timestamp(unix_secs as i64, 0)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| unix_secs.to_string())
+}
@@ -0,0 +1,77 @@
+//! Diagnostics command - list parse errors and the symbols they affect.
+
+use crate::indexing::facade::IndexFacade;
+use crate::io::exit_code::ExitCode;
+use crate::parsing::ParseDiagnostic;
+
+/// Run the diagnostics command.
+///
+/// Lists files whose last parse produced `ERROR` or `MISSING` nodes, optionally
+/// only those whose path contains `path_filter`.
+pub fn run(indexer: &IndexFacade, path_filter: Option<&str>, json: bool) -> ExitCode {
+    let all = match indexer.get_parse_diagnostics() {
+        Ok(all) => all,
+        Err(e) => {
+            eprintln!("Failed to read parse diagnostics: {e}");
+            return ExitCode::IoError;
+        }
+    };
+    let files: Vec<(&String, &Vec<ParseDiagnostic>)> = all
+        .iter()
+        .filter(|(path, _)| path_filter.is_none_or(|filter| path.contains(filter)))
+        .collect();
+
+    for (path, diagnostics) in &files {
+        let affected = indexer.get_symbols_affected_by(path, diagnostics);
+        if json {
+            let entry = serde_json::json!({
+                "path": path,
+                "diagnostics": diagnostics,
+                "low_confidence_symbols": affected.iter().map(|symbol| {
+                    serde_json::json!({
+                        "name": &*symbol.name,
+                        "kind": format!("{:?}", symbol.kind),
+                        "line": symbol.range.start_line + 1,
+                        "symbol_id": symbol.id.value(),
+                    })
+                }).collect::<Vec<_>>(),
+            });
+            println!("{entry}");
+            continue;
+        }
+
+        println!("{path}: {} parse error(s)", diagnostics.len());
+        for diagnostic in diagnostics.iter() {
+            println!(
+                "  {}:{}  {}",
+                diagnostic.range.start_line + 1,
+                diagnostic.range.start_column + 1,
+                diagnostic.message()
+            );
+        }
+        if !affected.is_empty() {
+            println!("  Low-confidence symbols:");
+            for symbol in &affected {
+                println!(
+                    "    {:?} {} at line {} [symbol_id:{}]",
+                    symbol.kind,
+                    symbol.name,
+                    symbol.range.start_line + 1,
+                    symbol.id.value()
+                );
+            }
+        }
+    }
+
+    if files.is_empty() {
+        if !json {
+            match path_filter {
+                Some(filter) => eprintln!("No parse errors in files matching '{filter}'"),
+                None => eprintln!("No parse errors in indexed files"),
+            }
+        }
+        ExitCode::NotFound
+    } else {
+        ExitCode::Success
+    }
+}
@@ -7,27 +7,35 @@ use crate::config::Settings;
 use crate::documents::{CollectionConfig, DocumentStore, IndexProgress, SearchQuery};
 use crate::io::status_line::StatusLine;
 use crate::io::{ProgressBar, ProgressBarOptions, ProgressBarStyle};
-use crate::vector::{FastEmbedGenerator, VectorDimension};
+use crate::vector::{EmbeddingGenerator, VectorDimension};
 
 /// Run documents management command.
 pub fn run(action: DocumentAction, config: &Settings, cli_config: Option<&PathBuf>) {
     let doc_path = config.index_path.join("documents");
-    let dimension = VectorDimension::dimension_384();
 
     // Helper to create store with optional embeddings
     let create_store_with_embeddings = || -> Result<DocumentStore, String> {
-        let store = DocumentStore::new(&doc_path, dimension)
-            .map_err(|e| format!("Failed to open document store: {e}"))?;
-
-        if config.semantic_search.enabled {
-            let generator = FastEmbedGenerator::new()
-                .map_err(|e| format!("Failed to create embedding generator: {e}"))?;
-            store
-                .with_embeddings(Box::new(generator))
-                .map_err(|e| format!("Failed to enable embeddings: {e}"))
-        } else {
-            Ok(store)
+        if !config.semantic_search.enabled {
+            return DocumentStore::new_compressed(
+                &doc_path,
+                VectorDimension::dimension_384(),
+                config.storage.compression.documents_level,
+            )
+            .map_err(|e| format!("Failed to open document store: {e}"));
         }
+
+        // The configured provider decides the vector size
+        let generator = crate::vector::create_provider(&config.semantic_search, false)
+            .map_err(|e| format!("Failed to create embedding generator: {e}"))?;
+        let store = DocumentStore::new_compressed(
+            &doc_path,
+            generator.dimension(),
+            config.storage.compression.documents_level,
+        )
+        .map_err(|e| format!("Failed to open document store: {e}"))?;
+        store
+            .with_embeddings(Box::new(generator))
+            .map_err(|e| format!("Failed to enable embeddings: {e}"))
     };
 
     match action {
@@ -0,0 +1,102 @@
+//! Events command - print or replay the index event log.
+
+use crate::io::exit_code::ExitCode;
+use crate::storage::{EventKind, EventLog, EventReplay, IndexEvent};
+use std::path::Path;
+use std::time::Duration;
+
+/// How often `--follow` checks the log for new lines.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Run the events command.
+pub fn run(index_path: &Path, since: u64, follow: bool, replay: bool, json: bool) -> ExitCode {
+    let event_log = EventLog::new(index_path);
+    if !event_log.path().exists() && !follow {
+        eprintln!("No event log at {}", event_log.path().display());
+        eprintln!("Enable it with 'event_log = true' under [indexing] in settings.toml");
+        return ExitCode::NotFound;
+    }
+
+    let (events, mut offset) = match event_log.read_from_offset(0) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Failed to read event log: {e}");
+            return ExitCode::IoError;
+        }
+    };
+
+    if replay {
+        print_replay(&EventReplay::from_events(&events), json);
+        return ExitCode::Success;
+    }
+
+    for event in events.iter().filter(|e| e.seq > since) {
+        print_event(event, json);
+    }
+
+    if follow {
+        // Resumed by sequence number, since retention may replace the file
+        let mut last_seq = events.last().map_or(0, |event| event.seq);
+        loop {
+            std::thread::sleep(FOLLOW_INTERVAL);
+            match event_log.read_after(last_seq, offset) {
+                Ok((events, next)) => {
+                    for event in &events {
+                        print_event(event, json);
+                    }
+                    if let Some(event) = events.last() {
+                        last_seq = event.seq;
+                    }
+                    offset = next;
+                }
+                Err(e) => {
+                    eprintln!("Failed to read event log: {e}");
+                    return ExitCode::IoError;
+                }
+            }
+        }
+    }
+
+    ExitCode::Success
+}
+
+fn print_event(event: &IndexEvent, json: bool) {
+    if json {
+        if let Ok(line) = serde_json::to_string(event) {
+            println!("{line}");
+        }
+        return;
+    }
+
+    let kind = match event.kind {
+        EventKind::FileAdded => "added",
+        EventKind::FileUpdated => "updated",
+        EventKind::FileRemoved => "removed",
+        EventKind::Reset => "reset",
+    };
+    let mut line = format!("{:>6}  {kind:<8} {}", event.seq, event.path);
+    if !event.added.is_empty() || !event.removed.is_empty() {
+        line.push_str(&format!(
+            "  (+{} -{})",
+            event.added.len(),
+            event.removed.len()
+        ));
+    }
+    println!("{}", line.trim_end());
+}
+
+fn print_replay(replay: &EventReplay, json: bool) {
+    if json {
+        let summary = serde_json::json!({
+            "last_seq": replay.last_seq,
+            "files": replay.files.len(),
+            "symbols": replay.symbol_count(),
+        });
+        println!("{summary}");
+        return;
+    }
+
+    println!("Replayed through seq {}", replay.last_seq);
+    println!("  Files:   {}", replay.files.len());
+    println!("  Symbols: {}", replay.symbol_count());
+}
@@ -0,0 +1,44 @@
+//! Export command - writes the index in formats other tools read.
+
+use crate::cli::args::ExportAction;
+#[cfg(feature = "parquet-export")]
+use crate::indexing::export::ExportTables;
+use crate::indexing::facade::IndexFacade;
+use crate::io::exit_code::ExitCode;
+use std::path::Path;
+
+/// Run the export command.
+pub fn run(action: ExportAction, indexer: &IndexFacade) -> ExitCode {
+    match action {
+        ExportAction::Parquet { dir } => parquet(indexer, &dir),
+    }
+}
+
+#[cfg(feature = "parquet-export")]
+fn parquet(indexer: &IndexFacade, dir: &Path) -> ExitCode {
+    let root = indexer.settings().workspace_root.clone();
+    let tables = match ExportTables::from_facade(indexer, root.as_deref()) {
+        Ok(tables) => tables,
+        Err(e) => {
+            eprintln!("Error: Could not read the index: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    if let Err(e) = tables.write(dir) {
+        eprintln!("Error: {e}");
+        return ExitCode::IoError;
+    }
+
+    println!("Exported index to {}", dir.display());
+    for table in &tables.tables {
+        println!("  {}.parquet: {} rows", table.name(), table.rows());
+    }
+    ExitCode::Success
+}
+
+#[cfg(not(feature = "parquet-export"))]
+fn parquet(_indexer: &IndexFacade, _dir: &Path) -> ExitCode {
+    eprintln!("Parquet export support is not compiled in.");
+    eprintln!("Please rebuild with: cargo build --features parquet-export");
+    ExitCode::GeneralError
+}
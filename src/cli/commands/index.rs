@@ -1,11 +1,12 @@
 //! Index command - index source code files and directories.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::cli::commands::directories::{SkipReason, add_paths_to_settings};
 use crate::config::Settings;
 use crate::indexing::facade::IndexFacade;
-use crate::storage::IndexPersistence;
+use crate::indexing::{git_delta, remote, shard};
+use crate::storage::{IndexMetadata, IndexPersistence, RemoteSource, RemoteStore};
 use crate::types::SymbolKind;
 
 /// Arguments for the index command.
@@ -16,6 +17,16 @@ pub struct IndexArgs {
     pub dry_run: bool,
     pub max_files: Option<usize>,
     pub cli_config: Option<PathBuf>,
+    /// Index only files changed since the last indexed commit
+    pub git_delta: bool,
+    /// Base revision for git delta indexing, instead of the recorded commit
+    pub since: Option<String>,
+    /// Index only this workspace package
+    pub package: Option<String>,
+    /// Clone and index this git repository instead of the workspace
+    pub remote: Option<String>,
+    /// Revision of the remote repository to index
+    pub rev: Option<String>,
 }
 
 /// Run the index command.
@@ -36,10 +47,38 @@ pub fn run(
         dry_run,
         max_files,
         cli_config,
+        git_delta,
+        since,
+        package,
+        remote,
+        rev,
     } = args;
 
+    cancel_on_interrupt(indexer);
+
+    if let Some(url) = remote {
+        index_remote(indexer, persistence, config, &url, rev, progress, force);
+        return;
+    }
+
+    // Only a run over every configured path may move the recorded commit
+    let complete_run = paths.is_empty() && max_files.is_none() && package.is_none();
+    // A forced rebuild of directories inside shards replaces just their files
+    let rebuild_in_place =
+        force && !dry_run && shard::partial_rebuild(&paths, config.workspace_root.as_deref());
+    // Over a shared index, main has already dropped the local changes; the
+    // reseeded index only needs the locally modified files again
+    let force = if force && config.shared_index_root().is_some() && !rebuild_in_place {
+        eprintln!("Discarded local changes; re-applying them on top of the shared index");
+        false
+    } else {
+        force
+    };
+
     // Determine paths to index
-    let paths_to_index = if !paths.is_empty() {
+    let paths_to_index = if let Some(name) = &package {
+        vec![package_root(indexer, config, name)]
+    } else if !paths.is_empty() {
         // CLI paths provided - add them to settings.toml first
         let config_path = if let Some(custom_path) = cli_config {
             custom_path
@@ -114,6 +153,20 @@ pub fn run(
         config_paths
     };
 
+    let git_base = if git_delta || since.is_some() {
+        let base = since.or_else(|| {
+            IndexMetadata::load(&config.index_path)
+                .ok()
+                .and_then(|metadata| metadata.indexed_commit)
+        });
+        if base.is_none() {
+            eprintln!("No indexed commit recorded yet, checking all files");
+        }
+        base
+    } else {
+        None
+    };
+
     // Process each path, tracking total changes
     let mut total_indexed = 0usize;
     for path in &paths_to_index {
@@ -122,7 +175,17 @@ pub fn run(
                 total_indexed += 1;
             }
         } else if path.is_dir() {
-            total_indexed += index_directory(indexer, path, progress, dry_run, force, max_files);
+            if rebuild_in_place {
+                clear_directory(indexer, path);
+            }
+            total_indexed += match &git_base {
+                Some(base) => index_git_delta(indexer, path, base, progress),
+                // Cleared files index as new, resolving against the other shards
+                None if rebuild_in_place => {
+                    index_directory(indexer, path, progress, dry_run, false, max_files)
+                }
+                None => index_directory(indexer, path, progress, dry_run, force, max_files),
+            };
         } else {
             eprintln!("Error: Path does not exist: {}", path.display());
             std::process::exit(1);
@@ -135,6 +198,136 @@ pub fn run(
     } else if !dry_run && total_indexed == 0 {
         tracing::debug!(target: "indexing", "no changes detected, skipping save");
     }
+
+    if !dry_run && complete_run && persistence.exists() {
+        record_indexed_commit(config);
+    }
+}
+
+/// Cancel indexing on the first Ctrl-C and exit on the second.
+///
+/// A cancelled run stops between batches; the files it already wrote are
+/// rolled back and indexed again by the next run.
+fn cancel_on_interrupt(indexer: &IndexFacade) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let cancel = indexer.indexing_cancel();
+    runtime.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        eprintln!("\nCancelling indexing, press Ctrl-C again to abort");
+        cancel.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
+    });
+}
+
+/// Exit if `e` reports a cancelled run.
+fn exit_if_cancelled(e: &crate::IndexError) {
+    if e.is_cancelled() {
+        eprintln!("Indexing cancelled; interrupted files are re-indexed on the next run");
+        std::process::exit(130);
+    }
+}
+
+/// Remove a directory's files from the index before rebuilding it.
+fn clear_directory(indexer: &mut IndexFacade, path: &Path) {
+    match indexer.clear_directory(path) {
+        Ok(removed) => eprintln!(
+            "Rebuilding {}: removed {removed} file(s), keeping the rest of the index",
+            path.display()
+        ),
+        Err(e) => {
+            eprintln!("Error clearing {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Clone `url` into the remote cache, index the checkout and record where
+/// it came from.
+fn index_remote(
+    indexer: &mut IndexFacade,
+    persistence: &IndexPersistence,
+    config: &Settings,
+    url: &str,
+    rev: Option<String>,
+    progress: bool,
+    force: bool,
+) {
+    match &rev {
+        Some(rev) => eprintln!("Cloning {url} at {rev}"),
+        None => eprintln!("Cloning {url}"),
+    }
+    let checkout = match remote::checkout(url, rev.as_deref()) {
+        Ok(checkout) => checkout,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    eprintln!(
+        "Indexing {} at {} from {}",
+        checkout.namespace,
+        short_commit(&checkout.commit),
+        checkout.path.display()
+    );
+
+    match indexer.index_remote_checkout(&checkout.path, progress, force) {
+        Ok(stats) if stats.files_indexed == 0 => {
+            eprintln!("Index up to date: {}", checkout.namespace);
+        }
+        Ok(_) => save_index(indexer, persistence, config),
+        Err(e) => {
+            exit_if_cancelled(&e);
+            eprintln!("Error indexing remote {url}: {e}");
+            std::process::exit(1);
+        }
+    }
+
+    let source = RemoteSource {
+        url: url.to_string(),
+        rev,
+        commit: checkout.commit,
+        path: checkout.path,
+        indexed_at: crate::indexing::get_utc_timestamp(),
+    };
+    if let Err(e) = RemoteStore::new(&config.index_path).record(&checkout.namespace, source) {
+        eprintln!("Warning: Could not record remote provenance: {e}");
+    }
+}
+
+/// Root of the workspace package `name`, which must lie inside an indexed
+/// directory so re-indexing it leaves the tracked paths unchanged.
+fn package_root(indexer: &IndexFacade, config: &Settings, name: &str) -> PathBuf {
+    let package = match indexer.workspace_package(name) {
+        Ok(package) => package,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    let covered = config
+        .get_indexed_paths()
+        .iter()
+        .any(|indexed| package.root.starts_with(indexed));
+    if !covered {
+        eprintln!(
+            "Error: package '{name}' at {} is outside the indexed directories",
+            package.root.display()
+        );
+        eprintln!("Add it first: codanna add-dir {}", package.root.display());
+        std::process::exit(1);
+    }
+    eprintln!(
+        "Indexing {} package '{name}' in {}",
+        package.kind.as_str(),
+        package.root.display()
+    );
+    package.root
 }
 
 /// Index a single file. Returns true if file was indexed (not cached).
@@ -201,6 +394,7 @@ fn index_single_file(indexer: &mut IndexFacade, path: &PathBuf, force: bool) ->
             was_indexed
         }
         Err(e) => {
+            exit_if_cancelled(&e);
             eprintln!("Error indexing file {}: {e}", path.display());
 
             let suggestions = e.recovery_suggestions();
@@ -249,6 +443,7 @@ fn index_directory(
             stats.files_indexed
         }
         Err(e) => {
+            exit_if_cancelled(&e);
             eprintln!("Error indexing directory {}: {e}", path.display());
 
             let suggestions = e.recovery_suggestions();
@@ -264,6 +459,75 @@ fn index_directory(
     }
 }
 
+/// Index the files git reports as changed under `path` since `base`.
+/// Returns the number of files indexed.
+///
+/// Falls back to a regular incremental run when git cannot answer, e.g.
+/// outside a repository or when `base` is unknown.
+fn index_git_delta(indexer: &mut IndexFacade, path: &PathBuf, base: &str, progress: bool) -> usize {
+    eprintln!();
+
+    match indexer.index_git_delta(path, base) {
+        Ok(stats) => {
+            if stats.files_indexed == 0 {
+                eprintln!("Index up to date: {}", path.display());
+            } else {
+                eprintln!(
+                    "Indexed {} changed files in {} since {}",
+                    stats.files_indexed,
+                    path.display(),
+                    short_commit(base)
+                );
+            }
+            stats.files_indexed
+        }
+        Err(e) => {
+            exit_if_cancelled(&e);
+            eprintln!(
+                "Git delta unavailable for {} ({e}), checking all files",
+                path.display()
+            );
+            index_directory(indexer, path, progress, false, false, None)
+        }
+    }
+}
+
+/// Record the workspace HEAD as the base for the next `--git-delta` run
+fn record_indexed_commit(config: &Settings) {
+    let root = config
+        .workspace_root
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let Some(commit) = git_delta::head_commit(&root) else {
+        return;
+    };
+
+    let mut metadata = match IndexMetadata::load(&config.index_path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            tracing::debug!(target: "indexing", "skipped recording indexed commit: {e}");
+            return;
+        }
+    };
+    if metadata.indexed_commit.as_deref() == Some(commit.as_str()) {
+        return;
+    }
+
+    metadata.indexed_commit = Some(commit);
+    if let Err(e) = metadata.save(&config.index_path) {
+        eprintln!("Warning: Could not record indexed commit: {e}");
+    }
+}
+
+/// First 12 characters of a commit id, or the revision as given
+fn short_commit(rev: &str) -> &str {
+    if rev.len() == 40 && rev.bytes().all(|b| b.is_ascii_hexdigit()) {
+        &rev[..12]
+    } else {
+        rev
+    }
+}
+
 fn save_index(indexer: &mut IndexFacade, persistence: &IndexPersistence, config: &Settings) {
     // Save the index
     eprintln!(
@@ -274,6 +538,12 @@ fn save_index(indexer: &mut IndexFacade, persistence: &IndexPersistence, config:
     match persistence.save_facade(indexer) {
         Ok(_) => {
             println!("Index saved to: {}", config.index_path.display());
+            if let Some(digest) = IndexMetadata::load(&config.index_path)
+                .ok()
+                .and_then(|metadata| metadata.content_digest)
+            {
+                println!("Index digest: {digest}");
+            }
         }
         Err(e) => {
             eprintln!("Error: Could not save index: {e}");
@@ -10,7 +10,7 @@ use crate::config::Settings;
 use crate::indexing::pipeline::{IncrementalStats, Phase2Stats, Pipeline, PipelineConfig};
 use crate::io::status_line::{ProgressBar, ProgressBarOptions, ProgressBarStyle};
 use crate::semantic::SimpleSemanticSearch;
-use crate::storage::DocumentIndex;
+use crate::storage::{DocumentIndex, EventLog};
 
 /// Arguments for the index-parallel command.
 pub struct IndexParallelArgs {
@@ -47,7 +47,7 @@ pub fn run(args: IndexParallelArgs, settings: &Settings) {
 
     // Resolve index path
     let index_path = settings.index_path.join("tantivy");
-    let semantic_path = settings.index_path.join("semantic");
+    let semantic_path = crate::vector::VectorNamespace::Code.dir(&settings.index_path);
 
     // Clear existing index if force flag is set
     if force && index_path.exists() {
@@ -60,6 +60,12 @@ pub fn run(args: IndexParallelArgs, settings: &Settings) {
                 tracing::warn!(target: "pipeline", "Failed to clear semantic index: {e}");
             }
         }
+        let event_log = EventLog::new(&settings.index_path);
+        if event_log.path().exists() {
+            if let Err(e) = event_log.record_reset() {
+                tracing::warn!(target: "pipeline", "Failed to write index events: {e}");
+            }
+        }
     }
 
     // Create document index
@@ -129,11 +135,12 @@ fn create_semantic_search(
         return None;
     }
 
-    let model = &settings.semantic_search.model;
+    let config = &settings.semantic_search;
+    let model = &config.model;
 
     // Try to load existing embeddings first
     if semantic_path.exists() {
-        match SimpleSemanticSearch::load(semantic_path) {
+        match SimpleSemanticSearch::load_with_config(semantic_path, config) {
             Ok(semantic) => {
                 tracing::debug!(target: "pipeline", "Loaded existing embeddings from {}", semantic_path.display());
                 return Some(Arc::new(Mutex::new(semantic)));
@@ -145,7 +152,7 @@ fn create_semantic_search(
     }
 
     // Create new semantic search instance
-    match SimpleSemanticSearch::from_model_name(model) {
+    match SimpleSemanticSearch::from_config(config) {
         Ok(semantic) => {
             tracing::debug!(target: "pipeline", "Created new semantic search with model: {model}");
             Some(Arc::new(Mutex::new(semantic)))
@@ -1,8 +1,9 @@
 //! Init and Config commands.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::Settings;
+use crate::io::exit_code::ExitCode;
 
 /// Run init command - create configuration file.
 pub fn run_init(force: bool) {
@@ -38,3 +39,30 @@ pub fn run_config(config: &Settings) {
         Err(e) => eprintln!("Error displaying config: {e}"),
     }
 }
+
+/// Run config --migrate - rewrite settings.toml with deprecated keys migrated.
+pub fn run_config_migrate(config_path: &Path) -> ExitCode {
+    if !config_path.exists() {
+        eprintln!("No configuration file found at: {}", config_path.display());
+        return ExitCode::ConfigError;
+    }
+
+    match Settings::migrate_file(config_path) {
+        Ok(warnings) if warnings.is_empty() => {
+            println!("Configuration is up to date: {}", config_path.display());
+            ExitCode::Success
+        }
+        Ok(warnings) => {
+            for warning in &warnings {
+                println!("  - {warning}");
+            }
+            println!("Migrated configuration at: {}", config_path.display());
+            println!("Previous version saved as: {}.bak", config_path.display());
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Error migrating configuration: {e}");
+            ExitCode::ConfigError
+        }
+    }
+}
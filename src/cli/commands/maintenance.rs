@@ -0,0 +1,114 @@
+//! Maintenance command - run index maintenance tasks or show their status.
+
+use crate::cli::args::MaintenanceAction;
+use crate::config::Settings;
+use crate::indexing::facade::IndexFacade;
+use crate::io::exit_code::ExitCode;
+use crate::mcp::maintenance::{self, MaintenanceState, MaintenanceTask};
+
+/// Run the maintenance command.
+pub fn run(action: MaintenanceAction, indexer: &IndexFacade, settings: &Settings) -> ExitCode {
+    match action {
+        MaintenanceAction::Run { tasks } => run_tasks(&tasks, indexer, settings),
+        MaintenanceAction::Status { json } => {
+            print_status(indexer, settings, json);
+            ExitCode::Success
+        }
+    }
+}
+
+fn run_tasks(names: &[String], indexer: &IndexFacade, settings: &Settings) -> ExitCode {
+    let tasks: Vec<MaintenanceTask> = if names.is_empty() {
+        MaintenanceTask::ALL.to_vec()
+    } else {
+        let parsed: Result<Vec<_>, String> = names.iter().map(|name| name.parse()).collect();
+        match parsed {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return ExitCode::GeneralError;
+            }
+        }
+    };
+
+    let index_path = indexer.index_base();
+    let mut state = MaintenanceState::load(index_path);
+    let mut failed = false;
+    for task in tasks {
+        match maintenance::run_task(task, indexer, &settings.maintenance) {
+            Ok(summary) => {
+                println!("{task}: {summary}");
+                state
+                    .last_run
+                    .insert(task, crate::utils::get_utc_timestamp());
+            }
+            Err(e) => {
+                eprintln!("{task}: failed: {e}");
+                failed = true;
+            }
+        }
+    }
+    if let Err(e) = state.save(index_path) {
+        eprintln!("Warning: failed to record maintenance runs: {e}");
+    }
+
+    if failed {
+        ExitCode::GeneralError
+    } else {
+        ExitCode::Success
+    }
+}
+
+fn print_status(indexer: &IndexFacade, settings: &Settings, json: bool) {
+    let config = &settings.maintenance;
+    let state = MaintenanceState::load(indexer.index_base());
+    let now = crate::utils::get_utc_timestamp();
+    let interval_secs = config.interval_hours * 60 * 60;
+
+    if json {
+        let tasks: Vec<_> = MaintenanceTask::ALL
+            .iter()
+            .map(|task| {
+                serde_json::json!({
+                    "task": task.as_str(),
+                    "last_run": state.last_run.get(task),
+                    "due": state.is_due(*task, now, interval_secs),
+                })
+            })
+            .collect();
+        let status = serde_json::json!({
+            "scheduled": config.enabled,
+            "window": config.window,
+            "interval_hours": config.interval_hours,
+            "tasks": tasks,
+        });
+        println!("{status}");
+        return;
+    }
+
+    if config.enabled {
+        let window = if config.window.is_empty() {
+            "any time"
+        } else {
+            config.window.as_str()
+        };
+        println!(
+            "Scheduled by 'codanna serve' every {}h ({window}, max load {} per {}s)",
+            config.interval_hours, config.max_load, config.load_window_secs
+        );
+    } else {
+        println!("Not scheduled (set [maintenance] enabled = true to run from 'codanna serve')");
+    }
+    for task in MaintenanceTask::ALL {
+        let last_run = match state.last_run.get(&task) {
+            Some(last) => format!("{}h ago", now.saturating_sub(*last) / 3600),
+            None => "never".to_string(),
+        };
+        let due = if state.is_due(task, now, interval_secs) {
+            "  (due)"
+        } else {
+            ""
+        };
+        println!("  {:<10} last run: {last_run}{due}", task.as_str());
+    }
+}
@@ -83,7 +83,11 @@ pub async fn run(
                             serde_json::Value::String(pos_arg.clone()),
                         );
                     }
-                    "analyze_impact" => {
+                    "analyze_impact"
+                    | "test_context_for"
+                    | "find_implementations"
+                    | "get_symbol_source"
+                    | "find_similar_symbols" => {
                         args_map.insert(
                             "symbol_name".to_string(),
                             serde_json::Value::String(pos_arg.clone()),
@@ -91,12 +95,27 @@ pub async fn run(
                     }
                     "semantic_search_docs"
                     | "semantic_search_with_context"
-                    | "search_documents" => {
+                    | "search_documents"
+                    | "search_knowledge"
+                    | "find_discussions"
+                    | "translate_query" => {
                         args_map.insert(
                             "query".to_string(),
                             serde_json::Value::String(pos_arg.clone()),
                         );
                     }
+                    "get_diagnostics"
+                    | "get_file_outline"
+                    | "get_dependency_graph"
+                    | "find_unused_symbols" => {
+                        args_map.insert(
+                            "path".to_string(),
+                            serde_json::Value::String(pos_arg.clone()),
+                        );
+                    }
+                    "reindex_paths" => {
+                        args_map.insert("paths".to_string(), serde_json::json!([pos_arg]));
+                    }
                     "search_symbols" => {
                         args_map.insert(
                             "query".to_string(),
@@ -349,8 +368,30 @@ pub async fn run(
             .as_ref()
             .and_then(|m| m.get("lang"))
             .and_then(|v| v.as_str());
+        let file_path = arguments
+            .as_ref()
+            .and_then(|m| m.get("file_path"))
+            .and_then(|v| v.as_str());
 
-        if let Some(id) = symbol_id {
+        if let Some(path) = file_path {
+            // Every symbol of the file counts as changed; list what lies outside it
+            facade.resolve_indexed_file(path).map(|(file_id, _)| {
+                let max_depth = arguments
+                    .as_ref()
+                    .and_then(|m| m.get("max_depth"))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(3) as usize;
+                let changed = facade.get_symbols_by_file(file_id);
+                let mut seen: std::collections::HashSet<_> =
+                    changed.iter().map(|symbol| symbol.id).collect();
+                changed
+                    .iter()
+                    .flat_map(|symbol| facade.get_impact_radius(symbol.id, Some(max_depth)))
+                    .filter(|id| seen.insert(*id))
+                    .filter_map(|id| facade.get_symbol(id))
+                    .collect::<Vec<_>>()
+            })
+        } else if let Some(id) = symbol_id {
             // Direct lookup by symbol ID
             if let Some(symbol) = facade.get_symbol(crate::SymbolId(id)) {
                 let max_depth = arguments
@@ -440,16 +481,23 @@ pub async fn run(
                 .and_then(|v| v.as_str());
 
             // Parse the kind filter if provided
-            let kind_filter = kind.as_ref().and_then(|k| match k.to_lowercase().as_str() {
-                "function" => Some(crate::SymbolKind::Function),
-                "struct" => Some(crate::SymbolKind::Struct),
-                "trait" => Some(crate::SymbolKind::Trait),
-                "method" => Some(crate::SymbolKind::Method),
-                "field" => Some(crate::SymbolKind::Field),
-                "module" => Some(crate::SymbolKind::Module),
-                "constant" => Some(crate::SymbolKind::Constant),
-                _ => None,
-            });
+            let kind_filter = match kind.as_deref() {
+                None => None,
+                Some(value) => match crate::SymbolKind::parse_ignore_case(value) {
+                    Some(kind) => Some(kind),
+                    None => {
+                        use crate::io::exit_code::ExitCode;
+                        use crate::io::format::JsonResponse;
+                        let response = JsonResponse::error(
+                            ExitCode::GeneralError,
+                            &format!("Unknown symbol kind '{value}'"),
+                            vec!["Use a kind such as function, method, struct or trait"],
+                        );
+                        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                        std::process::exit(1);
+                    }
+                },
+            };
 
             match facade.search(q, limit as usize, kind_filter, module, language) {
                 Ok(results) => Some(results),
@@ -656,7 +704,7 @@ pub async fn run(
     };
 
     // Embedded mode - use already loaded facade directly
-    // Try to load DocumentStore for search_documents tool
+    // Try to load DocumentStore for search_documents and search_knowledge tools
     let server = {
         let mut server = crate::mcp::CodeIntelligenceServer::new(facade);
 
@@ -665,14 +713,18 @@ pub async fn run(
             let doc_path = config.index_path.join("documents");
             if doc_path.exists() {
                 use crate::documents::DocumentStore;
-                use crate::vector::{EmbeddingGenerator, FastEmbedGenerator};
+                use crate::vector::EmbeddingGenerator;
 
                 // Create generator first to get dimension from model
                 if let Ok(generator) =
-                    FastEmbedGenerator::from_settings(&config.semantic_search.model, false)
+                    crate::vector::create_provider(&config.semantic_search, false)
                 {
                     let dimension = generator.dimension();
-                    if let Ok(store) = DocumentStore::new(&doc_path, dimension) {
+                    if let Ok(store) = DocumentStore::new_compressed(
+                        &doc_path,
+                        dimension,
+                        config.storage.compression.documents_level,
+                    ) {
                         if let Ok(store_with_emb) = store.with_embeddings(Box::new(generator)) {
                             server = server.with_document_store(store_with_emb);
                         }
@@ -758,10 +810,29 @@ pub async fn run(
                 std::process::exit(1);
             }
 
+            let offset = arguments
+                .as_ref()
+                .and_then(|m| m.get("offset"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as u32;
+            let limit = arguments
+                .as_ref()
+                .and_then(|m| m.get("limit"))
+                .and_then(|v| v.as_u64())
+                .map(|l| l as u32);
+            let depth = arguments
+                .as_ref()
+                .and_then(|m| m.get("depth"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(1) as u32;
+
             server
                 .find_callers(Parameters(FindCallersRequest {
                     function_name,
                     symbol_id,
+                    offset,
+                    limit,
+                    depth,
                 }))
                 .await
         }
@@ -778,10 +849,16 @@ pub async fn run(
                 .and_then(|v| v.as_u64())
                 .map(|id| id as u32);
 
-            // Require either symbol_name or symbol_id
-            if symbol_name.is_none() && symbol_id.is_none() {
+            let file_path = arguments
+                .as_ref()
+                .and_then(|m| m.get("file_path"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            // Require symbol_name, symbol_id or file_path
+            if symbol_name.is_none() && symbol_id.is_none() && file_path.is_none() {
                 eprintln!(
-                    "Error: analyze_impact requires either 'symbol_name' or 'symbol_id' parameter"
+                    "Error: analyze_impact requires 'symbol_name', 'symbol_id' or 'file_path' parameter"
                 );
                 std::process::exit(1);
             }
@@ -795,10 +872,77 @@ pub async fn run(
                 .analyze_impact(Parameters(AnalyzeImpactRequest {
                     symbol_name,
                     symbol_id,
+                    file_path,
                     max_depth,
                 }))
                 .await
         }
+        "find_implementations" => {
+            let symbol_name = arguments
+                .as_ref()
+                .and_then(|m| m.get("symbol_name"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            let symbol_id = arguments
+                .as_ref()
+                .and_then(|m| m.get("symbol_id"))
+                .and_then(|v| v.as_u64())
+                .map(|id| id as u32);
+
+            if symbol_name.is_none() && symbol_id.is_none() {
+                eprintln!(
+                    "Error: find_implementations requires either 'symbol_name' or 'symbol_id' parameter"
+                );
+                std::process::exit(1);
+            }
+
+            let lang = arguments
+                .as_ref()
+                .and_then(|m| m.get("lang"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            server
+                .find_implementations(Parameters(FindImplementationsRequest {
+                    symbol_name,
+                    symbol_id,
+                    lang,
+                }))
+                .await
+        }
+        "test_context_for" => {
+            let symbol_name = arguments
+                .as_ref()
+                .and_then(|m| m.get("symbol_name"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            let symbol_id = arguments
+                .as_ref()
+                .and_then(|m| m.get("symbol_id"))
+                .and_then(|v| v.as_u64())
+                .map(|id| id as u32);
+
+            if symbol_name.is_none() && symbol_id.is_none() {
+                eprintln!(
+                    "Error: test_context_for requires either 'symbol_name' or 'symbol_id' parameter"
+                );
+                std::process::exit(1);
+            }
+
+            let limit = arguments
+                .as_ref()
+                .and_then(|m| m.get("limit"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(5) as u32;
+            server
+                .test_context_for(Parameters(TestContextForRequest {
+                    symbol_name,
+                    symbol_id,
+                    limit,
+                }))
+                .await
+        }
         "get_index_info" => {
             use crate::mcp::GetIndexInfoRequest;
             use rmcp::handler::server::wrapper::Parameters;
@@ -806,6 +950,13 @@ pub async fn run(
                 .get_index_info(Parameters(GetIndexInfoRequest {}))
                 .await
         }
+        "list_projects" => {
+            use crate::mcp::ListProjectsRequest;
+            use rmcp::handler::server::wrapper::Parameters;
+            server
+                .list_projects(Parameters(ListProjectsRequest {}))
+                .await
+        }
         "search_symbols" => {
             let query = arguments
                 .as_ref()
@@ -835,6 +986,21 @@ pub async fn run(
                 .and_then(|m| m.get("lang"))
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
+            let repo = arguments
+                .as_ref()
+                .and_then(|m| m.get("repo"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let package = arguments
+                .as_ref()
+                .and_then(|m| m.get("package"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let shard = arguments
+                .as_ref()
+                .and_then(|m| m.get("shard"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
             server
                 .search_symbols(Parameters(SearchSymbolsRequest {
                     query: query.to_string(),
@@ -842,6 +1008,9 @@ pub async fn run(
                     kind,
                     module,
                     lang,
+                    repo,
+                    package,
+                    shard,
                 }))
                 .await
         }
@@ -875,6 +1044,12 @@ pub async fn run(
                     limit,
                     threshold,
                     lang,
+                    mode: arguments
+                        .as_ref()
+                        .and_then(|m| m.get("mode"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    filters: semantic_filters(arguments.as_ref()),
                 }))
                 .await
         }
@@ -908,6 +1083,7 @@ pub async fn run(
                     limit,
                     threshold,
                     lang,
+                    filters: semantic_filters(arguments.as_ref()),
                 }))
                 .await
         }
@@ -940,6 +1116,163 @@ pub async fn run(
                 }))
                 .await
         }
+        "search_knowledge" => {
+            use crate::mcp::SearchKnowledgeRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .and_then(|value| serde_json::from_value::<SearchKnowledgeRequest>(value).ok())
+                .unwrap_or_else(|| {
+                    eprintln!("Error: search_knowledge requires 'query' parameter");
+                    std::process::exit(1);
+                });
+            server.search_knowledge(Parameters(request)).await
+        }
+        "find_discussions" => {
+            use crate::mcp::FindDiscussionsRequest;
+            let query = arguments
+                .as_ref()
+                .and_then(|m| m.get("query"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_else(|| {
+                    eprintln!("Error: find_discussions requires 'query' parameter");
+                    std::process::exit(1);
+                })
+                .to_string();
+            let limit = arguments
+                .as_ref()
+                .and_then(|m| m.get("limit"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10) as u32;
+            server
+                .find_discussions(Parameters(FindDiscussionsRequest { query, limit }))
+                .await
+        }
+        "get_diagnostics" => {
+            use crate::mcp::GetDiagnosticsRequest;
+            let path = arguments
+                .as_ref()
+                .and_then(|m| m.get("path"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let limit = arguments
+                .as_ref()
+                .and_then(|m| m.get("limit"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10) as u32;
+            server
+                .get_diagnostics(Parameters(GetDiagnosticsRequest { path, limit }))
+                .await
+        }
+        "get_file_outline" => {
+            use crate::mcp::GetFileOutlineRequest;
+            let path = arguments
+                .as_ref()
+                .and_then(|m| m.get("path"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_else(|| {
+                    eprintln!("Error: get_file_outline requires 'path' parameter");
+                    std::process::exit(1);
+                })
+                .to_string();
+            server
+                .get_file_outline(Parameters(GetFileOutlineRequest { path }))
+                .await
+        }
+        "get_symbol_source" => {
+            use crate::mcp::GetSymbolSourceRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .and_then(|value| serde_json::from_value::<GetSymbolSourceRequest>(value).ok())
+                .filter(|request| request.symbol_name.is_some() || request.symbol_id.is_some())
+                .unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: get_symbol_source requires either 'symbol_name' or 'symbol_id' parameter"
+                    );
+                    std::process::exit(1);
+                });
+            server.get_symbol_source(Parameters(request)).await
+        }
+        "get_dependency_graph" => {
+            use crate::mcp::GetDependencyGraphRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .map(serde_json::from_value::<GetDependencyGraphRequest>)
+                .unwrap_or_else(|| serde_json::from_value(serde_json::json!({})))
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: invalid get_dependency_graph arguments: {e}");
+                    std::process::exit(1);
+                });
+            server.get_dependency_graph(Parameters(request)).await
+        }
+        "find_similar_symbols" => {
+            use crate::mcp::FindSimilarSymbolsRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .map(serde_json::from_value::<FindSimilarSymbolsRequest>)
+                .unwrap_or_else(|| serde_json::from_value(serde_json::json!({})))
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: invalid find_similar_symbols arguments: {e}");
+                    std::process::exit(1);
+                });
+            server.find_similar_symbols(Parameters(request)).await
+        }
+        "find_unused_symbols" => {
+            use crate::mcp::FindUnusedSymbolsRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .map(serde_json::from_value::<FindUnusedSymbolsRequest>)
+                .unwrap_or_else(|| serde_json::from_value(serde_json::json!({})))
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: invalid find_unused_symbols arguments: {e}");
+                    std::process::exit(1);
+                });
+            server.find_unused_symbols(Parameters(request)).await
+        }
+        "reindex_paths" => {
+            use crate::mcp::ReindexPathsRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .and_then(|value| serde_json::from_value::<ReindexPathsRequest>(value).ok())
+                .unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: reindex_paths requires paths (e.g. reindex_paths src/lib.rs)"
+                    );
+                    std::process::exit(1);
+                });
+            server.reindex_paths(Parameters(request)).await
+        }
+        "translate_query" => {
+            use crate::mcp::TranslateQueryRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .and_then(|value| serde_json::from_value::<TranslateQueryRequest>(value).ok())
+                .unwrap_or_else(|| {
+                    eprintln!("Error: translate_query requires 'query' parameter");
+                    std::process::exit(1);
+                });
+            server.translate_query(Parameters(request)).await
+        }
+        "batch_query" => {
+            use crate::mcp::BatchQueryRequest;
+            let request = arguments
+                .clone()
+                .map(serde_json::Value::Object)
+                .and_then(|value| serde_json::from_value::<BatchQueryRequest>(value).ok())
+                .unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: batch_query requires --args '{{\"queries\": [{{\"tool\": ..., \"arguments\": {{...}}}}]}}'"
+                    );
+                    std::process::exit(1);
+                });
+            server.batch_query(Parameters(request)).await
+        }
         _ => {
             if json {
                 use crate::io::exit_code::ExitCode;
@@ -948,14 +1281,14 @@ pub async fn run(
                     ExitCode::GeneralError,
                     &format!("Unknown tool: {tool}"),
                     vec![
-                        "Available tools: find_symbol, get_calls, find_callers, analyze_impact, get_index_info, search_symbols, semantic_search_docs, semantic_search_with_context, search_documents",
+                        "Available tools: find_symbol, get_calls, find_callers, find_implementations, analyze_impact, test_context_for, get_index_info, search_symbols, semantic_search_docs, semantic_search_with_context, search_documents, search_knowledge, find_discussions, get_diagnostics, get_file_outline, get_symbol_source, get_dependency_graph, find_similar_symbols, find_unused_symbols, reindex_paths, translate_query, batch_query, list_projects",
                     ],
                 );
                 println!("{}", serde_json::to_string_pretty(&response).unwrap());
             } else {
                 eprintln!("Unknown tool: {tool}");
                 eprintln!(
-                    "Available tools: find_symbol, get_calls, find_callers, analyze_impact, get_index_info, search_symbols, semantic_search_docs, semantic_search_with_context, search_documents"
+                    "Available tools: find_symbol, get_calls, find_callers, find_implementations, analyze_impact, test_context_for, get_index_info, search_symbols, semantic_search_docs, semantic_search_with_context, search_documents, search_knowledge, find_discussions, get_diagnostics, get_file_outline, get_symbol_source, get_dependency_graph, find_similar_symbols, find_unused_symbols, reindex_paths, translate_query, batch_query, list_projects"
                 );
             }
             std::process::exit(1);
@@ -1115,7 +1448,7 @@ pub async fn run(
                         } else {
                             arguments
                                 .as_ref()
-                                .and_then(|m| m.get("symbol_name"))
+                                .and_then(|m| m.get("symbol_name").or_else(|| m.get("file_path")))
                                 .and_then(|v| v.as_str())
                                 .unwrap_or("unknown")
                                 .to_string()
@@ -1459,3 +1792,21 @@ pub async fn run(
         }
     }
 }
+
+/// Structured semantic search filters from the tool arguments
+fn semantic_filters(
+    arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> crate::mcp::SemanticSearchFilters {
+    let get = |key: &str| {
+        arguments
+            .and_then(|m| m.get(key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+    crate::mcp::SemanticSearchFilters {
+        kind: get("kind"),
+        path: get("path"),
+        visibility: get("visibility"),
+        repo: get("repo"),
+    }
+}
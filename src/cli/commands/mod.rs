@@ -3,15 +3,31 @@
 //! Each command is implemented in its own module.
 //! Commands are progressively migrated from main.rs.
 
+pub mod analyze;
+pub mod api;
 pub mod benchmark;
+pub mod conversations;
+pub mod daemon;
+pub mod diagnostics;
 pub mod directories;
 pub mod documents;
+pub mod events;
+pub mod export;
 pub mod index;
 pub mod index_parallel;
 pub mod init;
+pub mod maintenance;
 pub mod mcp;
 pub mod parse;
 pub mod plugin;
 pub mod profile;
+pub mod record;
+pub mod replay;
 pub mod retrieve;
+pub mod self_update;
+pub mod semantic;
 pub mod serve;
+pub mod snapshot;
+pub mod stats;
+pub mod summary;
+pub mod vectors;
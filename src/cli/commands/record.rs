@@ -0,0 +1,157 @@
+//! Record command - run a codanna command and append it to a scenario bundle.
+
+use crate::config::Settings;
+use crate::io::exit_code::ExitCode;
+use crate::recording::{self, Anonymizer, Bundle, Step};
+use crate::storage::DocumentIndex;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+/// Run the record command.
+///
+/// The recorded command runs as a child process so its exit code, output and
+/// timing are exactly what the user would see. Returns the child's exit code,
+/// or an [`ExitCode`] if recording itself fails.
+pub fn run(
+    command: Vec<String>,
+    bundle_path: &Path,
+    include_sources: bool,
+    config_path: Option<&Path>,
+    settings: &Settings,
+) -> i32 {
+    if matches!(
+        command.first().map(String::as_str),
+        Some("record" | "replay")
+    ) {
+        eprintln!("Error: record and replay cannot be recorded");
+        return ExitCode::GeneralError as i32;
+    }
+
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("Error: cannot locate the codanna executable: {e}");
+            return ExitCode::GeneralError as i32;
+        }
+    };
+    let mut child = Command::new(exe);
+    if let Some(config_path) = config_path {
+        child.arg("--config").arg(config_path);
+    }
+    child.args(&command);
+
+    let started = Instant::now();
+    let output = match child.output() {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Error: failed to run codanna: {e}");
+            return ExitCode::GeneralError as i32;
+        }
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let _ = std::io::stdout().write_all(&output.stdout);
+    let _ = std::io::stderr().write_all(&output.stderr);
+    let exit_code = output.status.code().unwrap_or(1);
+
+    let workspace_root = settings
+        .workspace_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."));
+    let mut anonymizer = if include_sources {
+        None
+    } else {
+        match Anonymizer::for_index(&settings.index_path, &workspace_root) {
+            Ok(anonymizer) => Some(anonymizer),
+            Err(e) => {
+                eprintln!("Error: failed to prepare path anonymization: {e}");
+                return ExitCode::IoError as i32;
+            }
+        }
+    };
+
+    let mut bundle = if bundle_path.exists() {
+        match Bundle::load(bundle_path) {
+            Ok(bundle) if bundle.anonymized == anonymizer.is_some() => bundle,
+            Ok(_) => {
+                eprintln!(
+                    "Error: {} was recorded {} --include-sources; use the same mode or a new bundle",
+                    bundle_path.display(),
+                    if include_sources { "without" } else { "with" }
+                );
+                return ExitCode::GeneralError as i32;
+            }
+            Err(e) => {
+                eprintln!("Error: failed to read {}: {e}", bundle_path.display());
+                return ExitCode::IoError as i32;
+            }
+        }
+    } else {
+        Bundle::new(String::new(), anonymizer.is_some())
+    };
+
+    // Files first, so their paths are known when sanitizing output
+    let indexed = indexed_paths(settings);
+    bundle.files = recording::snapshot_files(&indexed, &workspace_root, anonymizer.as_mut());
+    bundle.config = recording::recorded_config(settings, anonymizer.as_mut());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let (args, stderr) = match anonymizer.as_mut() {
+        Some(anonymizer) => {
+            // The subcommand name is kept even if a file happens to share it
+            let args = command
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| {
+                    if i == 0 {
+                        arg.clone()
+                    } else {
+                        anonymizer.arg(arg)
+                    }
+                })
+                .collect();
+            (args, anonymizer.text(recording::tail(&stderr)))
+        }
+        None => (command, recording::tail(&stderr).to_string()),
+    };
+    bundle.steps.push(Step {
+        args,
+        exit_code,
+        duration_ms,
+        stderr,
+        stdout_lines: String::from_utf8_lossy(&output.stdout).lines().count(),
+    });
+
+    if let Err(e) = bundle.save(bundle_path) {
+        eprintln!("Error: failed to write {}: {e}", bundle_path.display());
+        return ExitCode::IoError as i32;
+    }
+    eprintln!(
+        "Recorded step {} ({} files) in {}",
+        bundle.steps.len(),
+        bundle.files.len(),
+        bundle_path.display()
+    );
+    if !include_sources {
+        eprintln!("Paths are anonymized; review the bundle before attaching it to a report");
+    }
+
+    exit_code
+}
+
+/// Files in the index, read directly so the heavier facade is not loaded
+fn indexed_paths(settings: &Settings) -> Vec<PathBuf> {
+    let tantivy_path = settings.index_path.join("tantivy");
+    if !tantivy_path.join("meta.json").exists() {
+        return Vec::new();
+    }
+    match DocumentIndex::new(&tantivy_path, settings) {
+        Ok(index) => index.get_all_indexed_paths().unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("[record] failed to open index: {e}");
+            Vec::new()
+        }
+    }
+}
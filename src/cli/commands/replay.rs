@@ -0,0 +1,129 @@
+//! Replay command - rerun a recorded scenario bundle in a scratch workspace.
+
+use crate::io::exit_code::ExitCode;
+use crate::recording::{self, Bundle};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Instant;
+use tempfile::TempDir;
+
+/// Run the replay command.
+///
+/// Rebuilds the recorded workspace in a temporary directory and runs each step
+/// with the recorded settings. Fails if any step exits differently than it
+/// did when recorded.
+pub fn run(bundle_path: &Path, keep: bool, verbose: bool) -> ExitCode {
+    let bundle = match Bundle::load(bundle_path) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("Error: failed to read {}: {e}", bundle_path.display());
+            return ExitCode::IoError;
+        }
+    };
+
+    println!(
+        "Bundle recorded by codanna {} on {}/{}: {} files, {} steps",
+        bundle.codanna_version,
+        bundle.os,
+        bundle.arch,
+        bundle.files.len(),
+        bundle.steps.len()
+    );
+    if bundle.codanna_version != env!("CARGO_PKG_VERSION") {
+        println!(
+            "Note: replaying with codanna {}; results may differ",
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+    if bundle.anonymized {
+        println!(
+            "Note: sources were not recorded; files are replaced by blank text of the same size"
+        );
+    }
+
+    let workspace = match TempDir::new() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Error: failed to create replay directory: {e}");
+            return ExitCode::IoError;
+        }
+    };
+    if let Err(e) = prepare(&bundle, workspace.path()) {
+        eprintln!("Error: failed to rebuild the recorded workspace: {e}");
+        return ExitCode::IoError;
+    }
+
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("Error: cannot locate the codanna executable: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+
+    let mut mismatches = 0;
+    for (i, step) in bundle.steps.iter().enumerate() {
+        let mut child = Command::new(&exe);
+        child
+            .args(&step.args)
+            .current_dir(workspace.path())
+            .stdin(Stdio::null());
+        if !verbose {
+            child.stdout(Stdio::null()).stderr(Stdio::null());
+        }
+
+        let started = Instant::now();
+        let exit_code = match child.status() {
+            Ok(status) => status.code().unwrap_or(1),
+            Err(e) => {
+                eprintln!("Error: failed to run step {}: {e}", i + 1);
+                return ExitCode::GeneralError;
+            }
+        };
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        let verdict = if exit_code == step.exit_code {
+            "match"
+        } else {
+            mismatches += 1;
+            "DIFFERENT"
+        };
+        println!(
+            "[{}] codanna {}\n    recorded: exit {} in {}ms\n    replayed: exit {exit_code} in {duration_ms}ms  ({verdict})",
+            i + 1,
+            step.args.join(" "),
+            step.exit_code,
+            step.duration_ms
+        );
+        if exit_code != step.exit_code && !step.stderr.is_empty() {
+            println!("    recorded error output:");
+            for line in step.stderr.lines() {
+                println!("      {line}");
+            }
+        }
+    }
+
+    if keep {
+        let path = workspace.keep();
+        println!("Replay workspace kept at {}", path.display());
+    }
+
+    if mismatches == 0 {
+        println!("All {} steps reproduced", bundle.steps.len());
+        ExitCode::Success
+    } else {
+        println!(
+            "{mismatches} of {} steps exited differently",
+            bundle.steps.len()
+        );
+        ExitCode::GeneralError
+    }
+}
+
+/// Write the recorded files and settings under `root`
+fn prepare(bundle: &Bundle, root: &Path) -> std::io::Result<()> {
+    recording::materialize(bundle, root)?;
+    let config_dir = root.join(".codanna");
+    std::fs::create_dir_all(&config_dir)?;
+    std::fs::write(config_dir.join("settings.toml"), &bundle.config)
+}
@@ -1,7 +1,7 @@
 //! Retrieve command - query symbol information from the index.
 
 use crate::cli::RetrieveQuery;
-use crate::indexing::facade::IndexFacade;
+use crate::indexing::facade::{IndexFacade, SearchScope};
 use crate::io::ExitCode;
 use crate::io::OutputFormat;
 use crate::retrieve;
@@ -109,6 +109,7 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
             json,
             kind,
             module,
+            pick,
         } => {
             use crate::io::args::parse_positional_args;
 
@@ -136,8 +137,25 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
             let final_kind = kind.or_else(|| params.get("kind").cloned());
             let final_module = module.or_else(|| params.get("module").cloned());
 
-            // Extract language filter
+            // Extract language, repository, package and shard filters
             let language = params.get("lang").map(|s| s.as_str());
+            let scope = SearchScope {
+                repository: params.get("repo").cloned(),
+                package: params.get("package").cloned(),
+                shard: params.get("shard").cloned(),
+            };
+
+            if pick {
+                return retrieve::retrieve_search_pick(
+                    indexer,
+                    &final_query,
+                    final_limit,
+                    final_kind.as_deref(),
+                    final_module.as_deref(),
+                    language,
+                    &scope,
+                );
+            }
 
             // Call retrieve function with merged parameters
             let format = OutputFormat::from_json_flag(json);
@@ -148,9 +166,28 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
                 final_kind.as_deref(),
                 final_module.as_deref(),
                 language,
+                &scope,
                 format,
             )
         }
+        RetrieveQuery::Ask {
+            args,
+            limit,
+            translate,
+            json,
+        } => {
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_ask(indexer, &args.join(" "), limit, translate, format)
+        }
+        RetrieveQuery::Knowledge {
+            args,
+            collection,
+            limit,
+            json,
+        } => {
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_knowledge(indexer, &args.join(" "), collection, limit, format)
+        }
         RetrieveQuery::Describe { args, json } => {
             use crate::io::args::parse_positional_args;
 
@@ -175,5 +212,25 @@ pub fn run(query: RetrieveQuery, indexer: &IndexFacade) -> ExitCode {
             let format = OutputFormat::from_json_flag(json);
             retrieve::retrieve_describe(indexer, &final_symbol, language, format)
         }
+        RetrieveQuery::Blame { args, json } => {
+            use crate::io::args::parse_positional_args;
+
+            let (positional_symbol, params) = parse_positional_args(&args);
+
+            let final_symbol = positional_symbol
+                .or_else(|| params.get("symbol").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+                .unwrap_or_else(|| {
+                    eprintln!("Error: blame requires a symbol name or symbol_id");
+                    eprintln!("Usage: codanna retrieve blame parse_file");
+                    eprintln!("   or: codanna retrieve blame symbol_id:1771");
+                    std::process::exit(1);
+                });
+
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_blame(indexer, &final_symbol, language, format)
+        }
     }
 }
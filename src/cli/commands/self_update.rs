@@ -0,0 +1,50 @@
+//! Self-update command.
+
+use crate::self_update::{self, UpdateStatus};
+use crate::storage::{Compatibility, IndexPersistence};
+use std::path::Path;
+
+/// Run self-update, or report update and index status with `--check`.
+pub fn run(version: Option<&str>, check: bool, force: bool, index_path: &Path) {
+    match self_update::self_update(version, check, force) {
+        Ok(UpdateStatus::UpToDate { version }) => {
+            println!("codanna {version} is up to date");
+        }
+        Ok(UpdateStatus::Available { current, latest }) => {
+            println!("codanna {latest} is available (installed: {current})");
+            println!("Run 'codanna self-update' to install it");
+        }
+        Ok(UpdateStatus::Updated { from, to }) => {
+            println!("Updated codanna {from} -> {to}");
+            println!("If the index schema changed, the next command will explain how to proceed");
+        }
+        Err(e) => {
+            eprintln!("Self-update failed: {e}");
+            std::process::exit(i32::from(e.exit_code()));
+        }
+    }
+
+    if check {
+        report_index_compatibility(index_path);
+    }
+}
+
+fn report_index_compatibility(index_path: &Path) {
+    if !IndexPersistence::new(index_path.to_path_buf()).exists() {
+        return;
+    }
+    match Compatibility::check(index_path) {
+        Ok(Compatibility::Current) => println!("Index at {} is compatible", index_path.display()),
+        Ok(Compatibility::Migratable { found }) => println!(
+            "Index at {} uses schema v{found}; it will be migrated on next use",
+            index_path.display()
+        ),
+        Ok(other) => {
+            println!("Index at {} is not compatible:", index_path.display());
+            if let Some(guidance) = other.guidance() {
+                println!("{guidance}");
+            }
+        }
+        Err(e) => eprintln!("Warning: Could not read index metadata: {e}"),
+    }
+}
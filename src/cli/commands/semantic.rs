@@ -0,0 +1,183 @@
+//! Semantic command - maintains the semantic index.
+
+use crate::cli::args::SemanticAction;
+use crate::config::Settings;
+use crate::indexing::facade::IndexFacade;
+use crate::indexing::git_delta;
+use crate::io::exit_code::ExitCode;
+use crate::semantic::{MIN_PAIRS, SemanticMigration};
+use std::io::Write;
+use std::path::Path;
+
+/// Commits before HEAD that calibration compares with by default
+const DEFAULT_HISTORY_DEPTH: usize = 50;
+
+/// Run the semantic command.
+pub fn run(
+    action: SemanticAction,
+    indexer: &mut IndexFacade,
+    cli_config: Option<&Path>,
+) -> ExitCode {
+    match action {
+        SemanticAction::Migrate { force } => migrate(indexer, force),
+        SemanticAction::Calibrate {
+            since,
+            samples,
+            dry_run,
+        } => calibrate(indexer, since, samples, dry_run, cli_config),
+    }
+}
+
+fn migrate(indexer: &mut IndexFacade, force: bool) -> ExitCode {
+    let config = indexer.settings().semantic_search.clone();
+    if !config.enabled {
+        eprintln!("Semantic search is not enabled");
+        eprintln!("Set semantic_search.enabled = true and run 'codanna index'");
+        return ExitCode::ConfigError;
+    }
+    let Some(indexed) = indexer.get_semantic_metadata() else {
+        eprintln!("No semantic index to migrate; run 'codanna index' to build one");
+        return ExitCode::NotFound;
+    };
+
+    let mut migration = match SemanticMigration::new(&config) {
+        Ok(migration) => migration,
+        Err(e) => {
+            eprintln!("Failed to load the configured model: {e}");
+            return ExitCode::ConfigError;
+        }
+    };
+    let same_model =
+        indexed.model_name == migration.model_id() && indexed.dimension == migration.dimensions();
+    if same_model && !force {
+        println!(
+            "Semantic index already uses '{}' ({} dimensions); nothing to migrate",
+            indexed.model_name, indexed.dimension
+        );
+        return ExitCode::Success;
+    }
+    println!(
+        "Re-embedding with '{}' ({} dimensions), was '{}' ({} dimensions)",
+        migration.model_id(),
+        migration.dimensions(),
+        indexed.model_name,
+        indexed.dimension
+    );
+
+    let symbols = indexer.semantic_embedding_texts(None);
+    let total = symbols.len();
+    let embedded = migration.embed(&symbols, |done| {
+        eprint!("\rEmbedded {done}/{total} symbols");
+        let _ = std::io::stderr().flush();
+    });
+    eprintln!();
+    if let Err(e) = embedded {
+        eprintln!("Failed to re-embed the index: {e}");
+        eprintln!("The previous embeddings are unchanged");
+        return ExitCode::GeneralError;
+    }
+
+    match indexer.install_semantic_migration(migration) {
+        Ok(count) => {
+            println!("Installed {count} embeddings");
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Failed to install the re-embedded index: {e}");
+            eprintln!("The previous embeddings are unchanged");
+            ExitCode::IoError
+        }
+    }
+}
+
+fn calibrate(
+    indexer: &IndexFacade,
+    since: Option<String>,
+    samples: usize,
+    dry_run: bool,
+    cli_config: Option<&Path>,
+) -> ExitCode {
+    if !indexer.has_semantic_search() {
+        eprintln!("No semantic index to calibrate; enable semantic_search and run 'codanna index'");
+        return ExitCode::NotFound;
+    }
+    let root = indexer
+        .settings()
+        .workspace_root
+        .clone()
+        .unwrap_or_else(|| ".".into());
+    let Some(base) = since.or_else(|| git_delta::ancestor(&root, DEFAULT_HISTORY_DEPTH)) else {
+        eprintln!("Calibration reads earlier versions of symbols from git");
+        eprintln!("No git history found in {}", root.display());
+        return ExitCode::NotFound;
+    };
+
+    let scores = match indexer.semantic_calibration_scores(&base, samples) {
+        Ok(scores) => scores,
+        Err(e) => {
+            eprintln!("Calibration failed: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    println!("Model: {}", scores.model_id);
+    println!(
+        "Symbols changed since {base}: {} pairs, mean similarity {}",
+        scores.same.len(),
+        mean(&scores.same)
+    );
+    println!(
+        "Random symbol pairs: {} pairs, mean similarity {}",
+        scores.random.len(),
+        mean(&scores.random)
+    );
+
+    let Some(thresholds) = scores.recommend() else {
+        eprintln!("Need at least {MIN_PAIRS} pairs of each kind to recommend thresholds");
+        eprintln!("Compare with an older revision: --since <REV>");
+        return ExitCode::NotFound;
+    };
+    println!("\nRecommended thresholds:");
+    println!(
+        "  search  = {:.2}  (default floor of semantic search)",
+        thresholds.search
+    );
+    println!(
+        "  similar = {:.2}  (default of find_similar_symbols)",
+        thresholds.similar
+    );
+    if dry_run {
+        return ExitCode::Success;
+    }
+
+    let Some(config_path) = cli_config
+        .map(Path::to_path_buf)
+        .or_else(Settings::find_workspace_config)
+    else {
+        eprintln!("No configuration file found. Run 'codanna init' first.");
+        return ExitCode::ConfigError;
+    };
+    let mut settings = match Settings::load_from(&config_path) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Error loading configuration: {e}");
+            return ExitCode::ConfigError;
+        }
+    };
+    settings
+        .semantic_search
+        .calibrated
+        .insert(scores.model_id, thresholds);
+    if let Err(e) = settings.save(&config_path) {
+        eprintln!("Error saving configuration: {e}");
+        return ExitCode::IoError;
+    }
+    println!("\nSaved to {}", config_path.display());
+    ExitCode::Success
+}
+
+fn mean(scores: &[f32]) -> String {
+    if scores.is_empty() {
+        return "-".to_string();
+    }
+    format!("{:.3}", scores.iter().sum::<f32>() / scores.len() as f32)
+}
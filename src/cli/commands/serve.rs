@@ -2,9 +2,12 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::config::Settings;
 use crate::indexing::facade::IndexFacade;
+use crate::mcp::executor::ToolExecutor;
+use crate::mcp::shutdown::Shutdown;
 
 /// Arguments for the serve command.
 pub struct ServeArgs {
@@ -13,6 +16,8 @@ pub struct ServeArgs {
     pub http: bool,
     pub https: bool,
     pub bind: String,
+    /// Loopback address to accept MCP clients on next to stdio
+    pub tcp: Option<String>,
 }
 
 /// Run the serve command.
@@ -29,6 +34,7 @@ pub async fn run(
         http,
         https,
         bind,
+        tcp,
     } = args;
 
     // Determine server mode:
@@ -64,6 +70,13 @@ pub async fn run(
         config.server.watch_interval
     };
 
+    if tcp.is_some() && server_mode != "stdio" {
+        eprintln!(
+            "Error: --tcp serves next to stdio and cannot be combined with {server_mode} mode"
+        );
+        std::process::exit(1);
+    }
+
     match server_mode {
         "https" => {
             run_https_server(&config, watch, bind_address).await;
@@ -79,6 +92,7 @@ pub async fn run(
                 index_path,
                 watch,
                 actual_watch_interval,
+                tcp,
             )
             .await;
         }
@@ -140,9 +154,24 @@ async fn run_stdio_server(
     index_path: PathBuf,
     watch: bool,
     actual_watch_interval: u64,
+    tcp: Option<String>,
 ) {
+    use crate::mcp::notifications::NotificationBroadcaster;
+
     // stdio mode - current implementation
     eprintln!("Starting MCP server on stdio transport");
+
+    // Bind before loading anything else so a taken port fails fast
+    let tcp_listener = match &tcp {
+        Some(address) => match crate::mcp::tcp_server::bind(address).await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                eprintln!("Failed to listen on {address}: {e}");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
     if watch {
         eprintln!("Index watching enabled (interval: {actual_watch_interval}s)");
     }
@@ -157,10 +186,13 @@ async fn run_stdio_server(
     );
     let server = crate::mcp::CodeIntelligenceServer::new(facade);
 
+    // Coordinated shutdown; the token stops watchers and the transport once tool calls drain
+    let mut shutdown = Shutdown::new(Duration::from_secs(config.server.shutdown_timeout));
+    let ct = shutdown.token();
+
     // If watch mode is enabled, start the hot-reload watcher
     if watch {
         use crate::watcher::HotReloadWatcher;
-        use std::time::Duration;
 
         let facade_arc = server.get_facade_arc();
         let watcher = HotReloadWatcher::new(
@@ -169,25 +201,29 @@ async fn run_stdio_server(
             Duration::from_secs(actual_watch_interval),
         );
 
-        // Spawn watcher in background
+        // Spawn watcher in background; it only reads the index, so stop it outright
+        let watcher_ct = ct.clone();
         tokio::spawn(async move {
-            watcher.watch().await;
+            tokio::select! {
+                _ = watcher.watch() => {}
+                _ = watcher_ct.cancelled() => {}
+            }
         });
 
         eprintln!("Hot-reload watcher started");
     }
 
+    // File change events, forwarded to the stdio and TCP sessions
+    let broadcaster = Arc::new(NotificationBroadcaster::new(100));
+
     // Start unified file watcher if enabled
     if watch || config.file_watch.enabled {
         use crate::documents::DocumentStore;
-        use crate::mcp::notifications::NotificationBroadcaster;
-        use crate::vector::{EmbeddingGenerator, FastEmbedGenerator};
+        use crate::vector::EmbeddingGenerator;
         use crate::watcher::UnifiedWatcher;
         use crate::watcher::handlers::{CodeFileHandler, ConfigFileHandler, DocumentFileHandler};
         use tokio::sync::RwLock;
 
-        let broadcaster = Arc::new(NotificationBroadcaster::new(100));
-
         let workspace_root = config
             .workspace_root
             .clone()
@@ -203,7 +239,8 @@ async fn run_stdio_server(
             .indexer(facade_arc.clone())
             .index_path(index_path.clone())
             .workspace_root(workspace_root.clone())
-            .debounce_ms(debounce_ms);
+            .debounce_ms(debounce_ms)
+            .shutdown_token(ct.clone());
 
         // Add code file handler
         builder = builder.handler(CodeFileHandler::new(
@@ -226,10 +263,14 @@ async fn run_stdio_server(
             let doc_path = config.index_path.join("documents");
             if doc_path.exists() {
                 if let Ok(generator) =
-                    FastEmbedGenerator::from_settings(&config.semantic_search.model, false)
+                    crate::vector::create_provider(&config.semantic_search, false)
                 {
                     let dimension = generator.dimension();
-                    if let Ok(store) = DocumentStore::new(&doc_path, dimension) {
+                    if let Ok(store) = DocumentStore::new_compressed(
+                        &doc_path,
+                        dimension,
+                        config.storage.compression.documents_level,
+                    ) {
                         if let Ok(store_with_emb) = store.with_embeddings(Box::new(generator)) {
                             let store_arc = Arc::new(RwLock::new(store_with_emb));
                             builder = builder
@@ -252,21 +293,24 @@ async fn run_stdio_server(
         // Build and start the unified watcher
         match builder.build() {
             Ok(unified_watcher) => {
-                tokio::spawn(async move {
+                let handle = tokio::spawn(async move {
                     if let Err(e) = unified_watcher.watch().await {
                         eprintln!("Unified watcher error: {e}");
                     }
                 });
+                shutdown.track("watcher", handle);
                 eprintln!(
                     "Unified watcher started (debounce: {debounce_ms}ms, config: {})",
                     settings_path.display()
                 );
 
                 // Start notification listener to forward events to MCP client
+                let listener_ct = ct.clone();
                 tokio::spawn(async move {
-                    notification_server
-                        .start_notification_listener(notification_receiver)
-                        .await;
+                    tokio::select! {
+                        _ = notification_server.start_notification_listener(notification_receiver) => {}
+                        _ = listener_ct.cancelled() => {}
+                    }
                 });
             }
             Err(e) => {
@@ -275,8 +319,26 @@ async fn run_stdio_server(
         }
     }
 
+    // Compaction and retention while the server is idle
+    crate::mcp::maintenance::spawn(&config, server.get_facade_arc(), index_path, ct.clone());
+
+    // More clients over TCP, sharing the loaded index until the stdio client leaves
+    if let Some(listener) = tcp_listener {
+        if let Ok(address) = listener.local_addr() {
+            eprintln!("Also serving MCP over TCP on {address}");
+        }
+        tokio::spawn(crate::mcp::tcp_server::serve(
+            listener,
+            server.get_facade_arc(),
+            settings.clone(),
+            broadcaster.clone(),
+            ct.clone(),
+        ));
+    }
+
     // Start server with stdio transport
     use rmcp::{ServiceExt, transport::stdio};
+    let reindexing = server.clone();
     let service = server
         .serve(stdio())
         .await
@@ -286,15 +348,39 @@ async fn run_stdio_server(
         })
         .unwrap();
 
-    // Wait for server to complete
-    service
-        .waiting()
-        .await
-        .map_err(|e| {
-            eprintln!("MCP server error: {e}");
-            std::process::exit(1);
-        })
-        .unwrap();
+    // Wait for the client to disconnect or a shutdown signal. The service runs in
+    // its own task so in-flight tool calls can still answer after a signal.
+    let executor = ToolExecutor::shared(&config.mcp);
+    let service_ct = service.cancellation_token();
+    let mut service_task = tokio::spawn(service.waiting());
+
+    let mut failed = false;
+    let signalled = tokio::select! {
+        result = &mut service_task => {
+            if let Ok(Err(e)) | Err(e) = result {
+                eprintln!("MCP server error: {e}");
+                failed = true;
+            }
+            false
+        }
+        signal = crate::mcp::shutdown::signal() => {
+            eprintln!("Received {signal}, shutting down MCP server...");
+            // A running force-reindex would hold the index past the deadline
+            reindexing.cancel_reindex();
+            true
+        }
+    };
+
+    shutdown.run(&executor).await;
+
+    if signalled {
+        service_ct.cancel();
+        let _ = service_task.await;
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
 }
 
 /// Run the MCP test command.
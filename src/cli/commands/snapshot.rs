@@ -0,0 +1,292 @@
+//! Index snapshot commands - export, import and diff portable index archives,
+//! and push or pull the index to an object-store bucket.
+
+use crate::cli::args::IndexAction;
+use crate::config::Settings;
+use crate::indexing::diff::{self, IndexDiff, IndexState, SymbolEntry};
+use crate::io::exit_code::ExitCode;
+#[cfg(feature = "remote-sync")]
+use crate::storage::bucket::{self, Remote, SyncStats};
+use crate::storage::{IndexPersistence, SnapshotManifest, snapshot};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Run an `index` subcommand.
+pub fn run(action: IndexAction, settings: &Settings) -> ExitCode {
+    let workspace_root = settings
+        .workspace_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    match action {
+        IndexAction::Export { output } => export(settings, &workspace_root, &output),
+        IndexAction::Import { archive, force } => {
+            import(settings, &workspace_root, &archive, force)
+        }
+        IndexAction::Push { remote, tag } => push(settings, &workspace_root, &remote, &tag),
+        IndexAction::Pull { remote, tag } => pull(settings, &workspace_root, &remote, &tag),
+        IndexAction::Diff {
+            before,
+            after,
+            json,
+        } => compare(settings, &workspace_root, &before, &after, json),
+        IndexAction::Stats { json } => {
+            crate::cli::commands::stats::run(settings, &workspace_root, json)
+        }
+    }
+}
+
+fn export(settings: &Settings, workspace_root: &Path, output: &Path) -> ExitCode {
+    match snapshot::export(&settings.index_path, workspace_root, output) {
+        Ok(manifest) => {
+            println!("Exported index to {}", output.display());
+            print_manifest(&manifest);
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Error: Could not export index: {e}");
+            ExitCode::IoError
+        }
+    }
+}
+
+fn import(settings: &Settings, workspace_root: &Path, archive: &Path, force: bool) -> ExitCode {
+    if !archive.is_file() {
+        eprintln!("Error: Snapshot not found: {}", archive.display());
+        return ExitCode::NotFound;
+    }
+
+    match snapshot::import(archive, &settings.index_path, workspace_root, force) {
+        Ok(manifest) => {
+            println!("Imported index into {}", settings.index_path.display());
+            print_manifest(&manifest);
+            println!("Run 'codanna index' to pick up local changes since the snapshot");
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Error: Could not import snapshot: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+#[cfg(feature = "remote-sync")]
+fn push(settings: &Settings, workspace_root: &Path, url: &str, tag: &str) -> ExitCode {
+    let remote = match Remote::open(url) {
+        Ok(remote) => remote,
+        Err(e) => {
+            eprintln!("Error: Could not open {url}: {e}");
+            return ExitCode::ConfigError;
+        }
+    };
+    let result = block_on(bucket::push(
+        &remote,
+        tag,
+        &settings.index_path,
+        workspace_root,
+    ));
+    match result {
+        Ok((manifest, stats)) => {
+            println!("Pushed index to {url} as '{tag}'");
+            print_manifest(&manifest.snapshot);
+            print_transfer("uploaded", &stats);
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Error: Could not push index: {e}");
+            ExitCode::IoError
+        }
+    }
+}
+
+#[cfg(feature = "remote-sync")]
+fn pull(settings: &Settings, workspace_root: &Path, url: &str, tag: &str) -> ExitCode {
+    let remote = match Remote::open(url) {
+        Ok(remote) => remote,
+        Err(e) => {
+            eprintln!("Error: Could not open {url}: {e}");
+            return ExitCode::ConfigError;
+        }
+    };
+    let result = block_on(bucket::pull(
+        &remote,
+        tag,
+        &settings.index_path,
+        workspace_root,
+    ));
+    match result {
+        Ok((manifest, stats)) => {
+            println!(
+                "Pulled '{tag}' from {url} into {}",
+                settings.index_path.display()
+            );
+            print_manifest(&manifest.snapshot);
+            print_transfer("downloaded", &stats);
+            println!("Run 'codanna index' to pick up local changes since the push");
+            ExitCode::Success
+        }
+        Err(e) => {
+            eprintln!("Error: Could not pull index: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Wait for `future` on the runtime `main` started
+#[cfg(feature = "remote-sync")]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+}
+
+#[cfg(feature = "remote-sync")]
+fn print_transfer(direction: &str, stats: &SyncStats) {
+    println!(
+        "  {} files in {} chunks; {} chunks {direction} ({:.1} MiB)",
+        stats.files,
+        stats.chunks,
+        stats.transferred,
+        stats.bytes as f64 / (1024.0 * 1024.0)
+    );
+}
+
+#[cfg(not(feature = "remote-sync"))]
+fn push(_settings: &Settings, _workspace_root: &Path, _url: &str, _tag: &str) -> ExitCode {
+    sync_not_compiled()
+}
+
+#[cfg(not(feature = "remote-sync"))]
+fn pull(_settings: &Settings, _workspace_root: &Path, _url: &str, _tag: &str) -> ExitCode {
+    sync_not_compiled()
+}
+
+#[cfg(not(feature = "remote-sync"))]
+fn sync_not_compiled() -> ExitCode {
+    eprintln!("Index sync support is not compiled in.");
+    eprintln!("Please rebuild with: cargo build --features remote-sync");
+    ExitCode::GeneralError
+}
+
+fn print_manifest(manifest: &SnapshotManifest) {
+    println!(
+        "  {} symbols in {} files (schema v{}, codanna {})",
+        manifest.symbol_count, manifest.file_count, manifest.schema_version, manifest.written_by
+    );
+    if let Some(commit) = &manifest.indexed_commit {
+        println!("  Indexed commit: {commit}");
+    }
+    if let Some(digest) = &manifest.content_digest {
+        println!("  Index digest: {digest}");
+    }
+}
+
+fn compare(
+    settings: &Settings,
+    workspace_root: &Path,
+    before: &Path,
+    after: &Path,
+    json: bool,
+) -> ExitCode {
+    let mut states = Vec::with_capacity(2);
+    for path in [before, after] {
+        if !path.exists() {
+            eprintln!("Error: Index state not found: {}", path.display());
+            return ExitCode::NotFound;
+        }
+        match load_state(path, settings, workspace_root) {
+            Ok(state) => states.push(state),
+            Err(e) => {
+                eprintln!("Error: Could not load {}: {e}", path.display());
+                return ExitCode::GeneralError;
+            }
+        }
+    }
+
+    let result = diff::diff(&states[0], &states[1]);
+    if json {
+        match serde_json::to_string_pretty(&result) {
+            Ok(output) => println!("{output}"),
+            Err(e) => {
+                eprintln!("Failed to serialize index diff: {e}");
+                return ExitCode::GeneralError;
+            }
+        }
+    } else {
+        print_diff(&result);
+    }
+    ExitCode::Success
+}
+
+/// Load a snapshot archive or index directory for comparison
+fn load_state(
+    path: &Path,
+    settings: &Settings,
+    workspace_root: &Path,
+) -> Result<IndexState, String> {
+    // Unpacked archives live only as long as the comparison needs them
+    let scratch;
+    let (index_path, root) = if path.is_dir() {
+        (path.to_path_buf(), Some(workspace_root.to_path_buf()))
+    } else {
+        scratch = tempfile::tempdir().map_err(|e| e.to_string())?;
+        let manifest =
+            snapshot::unpack(path, scratch.path(), workspace_root).map_err(|e| e.to_string())?;
+        (scratch.path().to_path_buf(), manifest.source_root)
+    };
+
+    let mut index_settings = settings.clone();
+    index_settings.index_path = index_path.clone();
+    let facade = IndexPersistence::new(index_path)
+        .load_facade(Arc::new(index_settings))
+        .map_err(|e| e.to_string())?;
+    IndexState::from_facade(&facade, root.as_deref()).map_err(|e| e.to_string())
+}
+
+fn print_diff(result: &IndexDiff) {
+    if result.is_empty() {
+        println!("No symbol or relationship changes");
+        return;
+    }
+
+    let summary = &result.summary;
+    println!(
+        "Symbols: {} added, {} removed, {} changed",
+        summary.symbols_added, summary.symbols_removed, summary.symbols_changed
+    );
+    println!(
+        "Relationships: {} added, {} removed",
+        summary.relationships_added, summary.relationships_removed
+    );
+
+    for entry in &result.symbols.added {
+        println!("  + {}", describe(entry));
+    }
+    for entry in &result.symbols.removed {
+        println!("  - {}", describe(entry));
+    }
+    for changed in &result.symbols.changed {
+        println!(
+            "  ~ {} ({})",
+            describe(&changed.after),
+            changed.changes.join(", ")
+        );
+        if let (Some(before), Some(after)) = (&changed.before.signature, &changed.after.signature) {
+            if before != after {
+                println!("      was: {before}");
+            }
+        }
+    }
+    for edge in &result.relationships.added {
+        println!("  + {} {} {}", edge.from.name, edge.kind, edge.to.name);
+    }
+    for edge in &result.relationships.removed {
+        println!("  - {} {} {}", edge.from.name, edge.kind, edge.to.name);
+    }
+}
+
+fn describe(entry: &SymbolEntry) -> String {
+    format!(
+        "{} {} ({}:{})",
+        entry.kind, entry.symbol.name, entry.symbol.file, entry.line
+    )
+}
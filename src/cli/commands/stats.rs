@@ -0,0 +1,104 @@
+//! Index stats command - counts, coverage and health of the index.
+
+use crate::cli::commands::vectors::format_bytes;
+use crate::config::Settings;
+use crate::indexing::stats::IndexReport;
+use crate::io::exit_code::ExitCode;
+use crate::storage::IndexPersistence;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Run the `index stats` command.
+pub fn run(settings: &Settings, workspace_root: &Path, json: bool) -> ExitCode {
+    let persistence = IndexPersistence::new(settings.index_path.clone());
+    if !persistence.exists() {
+        eprintln!("No index found at {}", settings.index_path.display());
+        eprintln!("Run 'codanna index' to build one");
+        return ExitCode::NotFound;
+    }
+
+    let report = persistence
+        .load_facade(Arc::new(settings.clone()))
+        .and_then(|facade| IndexReport::collect(&facade, &settings.index_path, workspace_root));
+    let report = match report {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: Could not read index: {e}");
+            return ExitCode::from_error(&e);
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(output) => println!("{output}"),
+            Err(e) => {
+                eprintln!("Failed to serialize index stats: {e}");
+                return ExitCode::GeneralError;
+            }
+        }
+    } else {
+        print_report(&report);
+    }
+    ExitCode::Success
+}
+
+fn print_report(report: &IndexReport) {
+    println!(
+        "Index: {} files, {} symbols, {} relationships",
+        report.files, report.symbols, report.relationships
+    );
+    if report.aliases > 0 {
+        println!(
+            "Aliases: {} files share the content of another file and were parsed once",
+            report.aliases
+        );
+    }
+
+    println!("\nLanguages:");
+    println!("  {:<16} {:>8} {:>10}", "LANGUAGE", "FILES", "SYMBOLS");
+    for (language, stats) in &report.languages {
+        println!(
+            "  {:<16} {:>8} {:>10}",
+            language, stats.files, stats.symbols
+        );
+    }
+
+    print_counts("Symbol kinds", &report.symbol_kinds);
+    print_counts("Relationship kinds", &report.relationship_kinds);
+
+    let vectors = &report.vectors;
+    println!(
+        "\nVectors: {} embeddings for {} documented symbols ({:.0}% coverage)",
+        vectors.embeddings,
+        vectors.documented_symbols,
+        vectors.coverage * 100.0
+    );
+
+    let stale = &report.stale;
+    if stale.modified + stale.missing == 0 {
+        println!("Stale files: none");
+    } else {
+        println!(
+            "Stale files: {} modified, {} missing (run 'codanna index' to update)",
+            stale.modified, stale.missing
+        );
+    }
+
+    println!("\nStorage: {}", format_bytes(report.storage.total_bytes));
+    for (entry, bytes) in &report.storage.entries {
+        println!("  {:<24} {:>10}", entry, format_bytes(*bytes));
+    }
+}
+
+fn print_counts(title: &str, counts: &BTreeMap<String, usize>) {
+    if counts.is_empty() {
+        return;
+    }
+    println!("\n{title}:");
+    let mut sorted: Vec<_> = counts.iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    for (name, count) in sorted {
+        println!("  {name:<24} {count:>10}");
+    }
+}
@@ -0,0 +1,88 @@
+//! Summary command - generate cached module summaries and show the workspace map.
+
+use crate::indexing::facade::IndexFacade;
+use crate::io::exit_code::ExitCode;
+use crate::summary::{self, LlmSummarizer, SummaryCache, SummaryError};
+
+/// Run the summary command.
+///
+/// Refreshes summaries for changed files, or prints the workspace map with `--map`.
+pub fn run(indexer: &IndexFacade, force: bool, map: bool, json: bool) -> ExitCode {
+    let settings = indexer.settings();
+    let mut cache = match SummaryCache::load(indexer.index_base()) {
+        Ok(cache) => cache,
+        Err(e) => return fail(e),
+    };
+
+    if map {
+        print_map(&cache, json);
+        return ExitCode::Success;
+    }
+
+    if !settings.summary.enabled {
+        return fail(SummaryError::Disabled);
+    }
+
+    let summarizer = LlmSummarizer::from_config(&settings.summary);
+    let stats = summary::refresh(indexer, &mut cache, &summarizer, force, |path| {
+        if !json {
+            eprintln!("Summarizing {path}");
+        }
+    });
+    if let Err(e) = cache.save() {
+        return fail(e);
+    }
+
+    if json {
+        let report = serde_json::json!({
+            "generated": stats.generated,
+            "unchanged": stats.unchanged,
+            "removed": stats.removed,
+            "failed": stats.failed.iter().map(|(path, reason)| {
+                serde_json::json!({ "path": path, "reason": reason })
+            }).collect::<Vec<_>>(),
+        });
+        println!("{report}");
+    } else {
+        println!("Summaries refreshed");
+        println!("  Generated: {}", stats.generated);
+        println!("  Unchanged: {}", stats.unchanged);
+        println!("  Removed:   {}", stats.removed);
+        if !stats.failed.is_empty() {
+            println!("  Failed:    {}", stats.failed.len());
+            for (path, reason) in stats.failed.iter().take(5) {
+                eprintln!("    {path}: {}", reason.lines().next().unwrap_or(""));
+            }
+        }
+    }
+
+    if stats.generated == 0 && !stats.failed.is_empty() {
+        ExitCode::GeneralError
+    } else {
+        ExitCode::Success
+    }
+}
+
+fn print_map(cache: &SummaryCache, json: bool) {
+    let map = cache.workspace_map();
+    if json {
+        println!("{}", serde_json::json!(map));
+        return;
+    }
+
+    if map.is_empty() {
+        println!("No summaries yet. Run 'codanna summary' to generate them.");
+        return;
+    }
+    for (dir, files) in &map {
+        println!("{dir}/");
+        for (file, summary) in files {
+            println!("  {file}  {summary}");
+        }
+    }
+}
+
+fn fail(e: SummaryError) -> ExitCode {
+    eprintln!("Error: {e}");
+    e.exit_code()
+}
@@ -0,0 +1,100 @@
+//! Vectors command - list and drop vector store namespaces.
+
+use crate::cli::args::VectorAction;
+use crate::io::exit_code::ExitCode;
+use crate::vector::namespace::{self, NamespaceInfo, VectorNamespace};
+use std::path::Path;
+
+/// Run the vectors command.
+pub fn run(action: VectorAction, index_path: &Path) -> ExitCode {
+    match action {
+        VectorAction::List { json } => {
+            print_namespaces(&namespace::discover(index_path), json);
+            ExitCode::Success
+        }
+        VectorAction::Drop { namespace } => drop_namespace(index_path, &namespace),
+    }
+}
+
+fn print_namespaces(namespaces: &[NamespaceInfo], json: bool) {
+    if json {
+        match serde_json::to_string_pretty(namespaces) {
+            Ok(output) => println!("{output}"),
+            Err(e) => eprintln!("Failed to serialize namespaces: {e}"),
+        }
+        return;
+    }
+
+    if namespaces.is_empty() {
+        println!("No vector namespaces found");
+        return;
+    }
+
+    println!(
+        "{:<28} {:>12} {:>10}  PATH",
+        "NAMESPACE", "EMBEDDINGS", "SIZE"
+    );
+    for info in namespaces {
+        let embeddings = info
+            .embeddings
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<28} {:>12} {:>10}  {}",
+            info.namespace,
+            embeddings,
+            format_bytes(info.bytes),
+            info.path.display()
+        );
+    }
+}
+
+fn drop_namespace(index_path: &Path, name: &str) -> ExitCode {
+    let namespace: VectorNamespace = match name.parse() {
+        Ok(namespace) => namespace,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::GeneralError;
+        }
+    };
+
+    match namespace::remove(index_path, namespace) {
+        Ok(true) => {
+            println!("Dropped vectors for namespace '{namespace}'");
+            match namespace {
+                VectorNamespace::Code => {
+                    println!("Run 'codanna index --force' to embed code again")
+                }
+                VectorNamespace::Docs => {
+                    println!("Run 'codanna documents index --all --force' to embed documents again")
+                }
+                VectorNamespace::Conversations => {}
+            }
+            ExitCode::Success
+        }
+        Ok(false) => {
+            eprintln!("Namespace '{namespace}' has no vectors");
+            ExitCode::NotFound
+        }
+        Err(e) => {
+            eprintln!("Failed to drop namespace '{namespace}': {e}");
+            ExitCode::IoError
+        }
+    }
+}
+
+pub(super) fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.1}GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1}MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1}KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{bytes}B")
+    }
+}
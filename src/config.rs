@@ -12,7 +12,7 @@
 //! to separate nested levels:
 //! - `CI_INDEXING__PARALLELISM=8` sets `indexing.parallelism`
 //! - `CI_LOGGING__DEFAULT=debug` sets `logging.default`
-//! - `CI_INDEXING__INCLUDE_TESTS=false` sets `indexing.include_tests`
+//! - `CI_SEMANTIC_SEARCH__ENABLED=true` sets `semantic_search.enabled`
 //!
 //! For logging, use `RUST_LOG` environment variable directly (standard Rust pattern).
 
@@ -21,7 +21,7 @@ use figment::{
     providers::{Env, Format, Serialized, Toml},
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -42,18 +42,46 @@ pub struct Settings {
     #[serde(default)]
     pub indexing: IndexingConfig,
 
+    /// How the index stores its data
+    #[serde(default)]
+    pub storage: StorageConfig,
+
     /// Cached canonicalized paths for fast lookups (not serialized)
     #[serde(skip)]
     pub indexed_paths_cache: Vec<PathBuf>,
 
+    /// Deprecated or unknown keys found in the config file (not serialized)
+    #[serde(skip)]
+    pub config_warnings: Vec<ConfigWarning>,
+
     /// Language-specific settings
     #[serde(default)]
     pub languages: HashMap<String, LanguageConfig>,
 
+    /// Tree-sitter grammars loaded at runtime, keyed by language id
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub grammars: HashMap<String, GrammarConfig>,
+
+    /// Further repositories indexed into the same index, keyed by name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub repositories: HashMap<String, RepositoryConfig>,
+
     /// MCP server settings
     #[serde(default)]
     pub mcp: McpConfig,
 
+    /// Per-query limits for retrieve commands and MCP tools
+    #[serde(default)]
+    pub query: QueryConfig,
+
+    /// Boosts for results in what the user is working on
+    #[serde(default)]
+    pub ranking: RankingConfig,
+
+    /// LLM-generated module summaries
+    #[serde(default)]
+    pub summary: SummaryConfig,
+
     /// Semantic search settings
     #[serde(default)]
     pub semantic_search: SemanticSearchConfig,
@@ -66,6 +94,10 @@ pub struct Settings {
     #[serde(default)]
     pub server: ServerConfig,
 
+    /// Background maintenance run by `codanna serve`
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
     /// Logging configuration
     #[serde(default)]
     pub logging: LoggingConfig,
@@ -119,14 +151,166 @@ pub struct IndexingConfig {
     #[serde(default = "default_batches_per_commit")]
     pub batches_per_commit: usize,
 
+    /// Memory budget for in-flight pipeline data in megabytes (0 = unlimited)
+    /// Reading pauses while file contents awaiting parsing exceed it, and
+    /// pending relationships spill to disk past half of it
+    #[serde(default = "default_memory_budget_mb")]
+    pub memory_budget_mb: usize,
+
     /// Enable detailed pipeline stage tracing (timing, memory, throughput)
     /// Set logging.modules.pipeline = "info" to see output
     #[serde(default)]
     pub pipeline_tracing: bool,
 
+    /// Write per-stage throughput, wait and wall times as JSON to this path
+    /// after each indexing run, to help tune thread counts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_json: Option<PathBuf>,
+
+    /// Produce the same symbol IDs and index contents for the same inputs
+    /// (default: false). Files are numbered in path order and written from
+    /// one thread, so indexing is slower
+    #[serde(default)]
+    pub deterministic: bool,
+
     /// Show progress bars during indexing (default: true)
     #[serde(default = "default_true")]
     pub show_progress: bool,
+
+    /// Append index mutations to `<index_path>/events.jsonl` (default: false)
+    /// Read with `codanna events`; used for replay and delta sync
+    #[serde(default)]
+    pub event_log: bool,
+
+    /// Parse code embedded in string literals (e.g., SQL queries) and index it
+    /// under the symbol that contains it (default: false)
+    #[serde(default)]
+    pub language_injections: bool,
+
+    /// Guess receiver types in Python, JavaScript and TypeScript from
+    /// annotations and constructor calls to resolve method calls (default: true)
+    #[serde(default = "default_true")]
+    pub type_inference: bool,
+
+    /// What to do with generated and minified files (default: no_embeddings)
+    /// Binary files are always skipped
+    #[serde(default)]
+    pub generated_files: GeneratedFiles,
+
+    /// Index open, branch-touched and recently modified files first
+    /// (default: true)
+    #[serde(default = "default_true")]
+    pub priority_indexing: bool,
+
+    /// Files larger than this many MB are parsed in chunks that split at
+    /// top-level definitions (default: 2, 0 = never)
+    /// Override per language with `languages.<id>.large_file_mb`
+    #[serde(default = "default_large_file_mb")]
+    pub large_file_mb: usize,
+
+    /// Parse and embed files with identical content once; the copies are
+    /// recorded as aliases of the first (default: true)
+    /// Ignored in deterministic mode
+    #[serde(default = "default_true")]
+    pub dedup_identical_files: bool,
+
+    /// Index source files inside archives (.jar, .whl, .zip, .tar, .tar.gz)
+    /// under read-only `<archive>!/<entry>` paths (default: false)
+    #[serde(default)]
+    pub index_archives: bool,
+
+    /// Record the commit that introduced and the commit that last modified
+    /// each symbol, from git blame (default: false)
+    #[serde(default)]
+    pub blame: bool,
+
+    /// Prebuilt index directory to layer this index over, e.g. on a network
+    /// share. It is only read; local changes are kept in `index_path`.
+    /// Relative paths are resolved against the workspace root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_index: Option<PathBuf>,
+}
+
+/// Handling of files written by code generators or minifiers
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratedFiles {
+    /// Leave them out of the index
+    Skip,
+    /// Index their symbols but do not embed their doc comments
+    #[default]
+    NoEmbeddings,
+    /// Index them like hand-written code
+    Index,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct StorageConfig {
+    /// Compression of stored text
+    #[serde(default)]
+    pub compression: CompressionConfig,
+}
+
+/// zstd compression of the Tantivy document stores, one level per store
+///
+/// Tantivy's docstore compresses all stored fields of an index together in
+/// blocks and takes a single compressor per index, so levels are set per
+/// store, not per field. A level applies when the index is created; existing
+/// indexes keep theirs until rebuilt. An index layered over
+/// `indexing.shared_index` keeps the compression of the shared build.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CompressionConfig {
+    /// Symbols: doc comments, signatures, source context and relationship
+    /// context
+    #[serde(default)]
+    pub code_level: CompressionLevel,
+
+    /// Document chunk bodies
+    #[serde(default)]
+    pub documents_level: CompressionLevel,
+}
+
+/// zstd level from 1 (fastest) to 22 (smallest), or 0 for lz4; 3 by default
+///
+/// Out-of-range levels are rejected when the configuration is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "i32", into = "i32")]
+pub struct CompressionLevel(i32);
+
+impl CompressionLevel {
+    /// Tantivy's default lz4 compression
+    pub const LZ4: Self = Self(0);
+
+    /// The zstd level, or `None` for lz4
+    pub fn zstd(self) -> Option<i32> {
+        (self.0 > 0).then_some(self.0)
+    }
+}
+
+impl Default for CompressionLevel {
+    fn default() -> Self {
+        Self(3)
+    }
+}
+
+impl TryFrom<i32> for CompressionLevel {
+    type Error = String;
+
+    fn try_from(level: i32) -> Result<Self, Self::Error> {
+        if (0..=22).contains(&level) {
+            Ok(Self(level))
+        } else {
+            Err(format!(
+                "compression level {level} is out of range: use 1-22 for zstd or 0 for lz4"
+            ))
+        }
+    }
+}
+
+impl From<CompressionLevel> for i32 {
+    fn from(level: CompressionLevel) -> Self {
+        level.0
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -147,6 +331,44 @@ pub struct LanguageConfig {
     /// Empty by default - project resolution is opt-in
     #[serde(default)]
     pub config_files: Vec<PathBuf>,
+
+    /// Size in MB above which files of this language are parsed in chunks
+    /// (default: indexing.large_file_mb)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_file_mb: Option<usize>,
+
+    /// Text embedded per symbol (default: semantic_search.chunking)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking: Option<String>,
+}
+
+/// A tree-sitter grammar compiled outside codanna
+///
+/// Relative paths are resolved against the workspace root.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GrammarConfig {
+    /// Compiled grammar: a shared library (`.so`, `.dylib`, `.dll`) or a `.wasm` file
+    pub library: PathBuf,
+
+    /// Exported language function; defaults to `tree_sitter_<name>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+
+    /// File extensions handled by the grammar, without the dot
+    pub extensions: Vec<String>,
+
+    /// Query file whose `@definition.<kind>` captures become symbols
+    pub query: PathBuf,
+}
+
+/// A repository indexed alongside the workspace
+///
+/// Relative paths are resolved against the workspace root. Symbols from the
+/// repository can be selected with the `repo:<name>` search filter.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RepositoryConfig {
+    /// Root directory of the repository
+    pub path: PathBuf,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -154,6 +376,159 @@ pub struct McpConfig {
     /// Maximum context size in bytes
     #[serde(default = "default_max_context_size")]
     pub max_context_size: usize,
+
+    /// Maximum edges loaded per symbol by relationship queries
+    /// Larger edge sets are paged (find_callers) or summarized (analyze_impact)
+    #[serde(default = "default_max_edges_per_symbol")]
+    pub max_edges_per_symbol: usize,
+
+    /// Maximum symbols collected by an impact traversal before stopping early
+    #[serde(default = "default_max_impact_results")]
+    pub max_impact_results: usize,
+
+    /// Worker threads for the dedicated tool runtime (0 = run tools on the server runtime)
+    #[serde(default = "default_tool_threads")]
+    pub tool_threads: usize,
+
+    /// Concurrent calls allowed per tool unless overridden in `tool_concurrency`
+    #[serde(default = "default_max_concurrent_calls")]
+    pub max_concurrent_calls: usize,
+
+    /// Concurrent calls allowed across all tools and clients (0 = no limit)
+    #[serde(default = "default_max_concurrent_queries")]
+    pub max_concurrent_queries: usize,
+
+    /// Calls waiting for a free slot before new ones are refused (0 = no limit)
+    #[serde(default = "default_max_queued_calls")]
+    pub max_queued_calls: usize,
+
+    /// Tool calls each client may make per minute (0 = no limit)
+    #[serde(default)]
+    pub rate_limit_per_minute: u32,
+
+    /// Calls a client may make in a burst before `rate_limit_per_minute` applies
+    #[serde(default = "default_rate_limit_burst")]
+    pub rate_limit_burst: u32,
+
+    /// Tool results longer than this are split into pages (0 = never page)
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: usize,
+
+    /// Send every page of a long result as a progress notification when the
+    /// client passes a progress token, instead of returning a cursor
+    #[serde(default = "default_false")]
+    pub stream_pages: bool,
+
+    /// Indexes of other registered projects kept open for `project` routing
+    #[serde(default = "default_max_open_projects")]
+    pub max_open_projects: usize,
+
+    /// Preload the warm set and send a primer on every handshake, not only
+    /// when the client asks with the `codanna/warmStart` capability
+    #[serde(default = "default_false")]
+    pub warm_start: bool,
+
+    /// Symbols, files and queries preloaded by a warm start
+    #[serde(default = "default_warm_set_size")]
+    pub warm_set_size: usize,
+
+    /// Per-tool concurrency overrides (e.g., `analyze_impact = 1`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_concurrency: HashMap<String, usize>,
+
+    /// Which tools clients may see and call
+    #[serde(default)]
+    pub permissions: ToolPermissions,
+}
+
+/// Tool access for MCP clients, from `[mcp.permissions]`
+///
+/// Applies to tool calls and custom requests from MCP clients, including
+/// calls made inside `batch_query` and prompts, but not to `codanna mcp`.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ToolPermissions {
+    /// Only these tools are available (empty = all tools)
+    #[serde(default)]
+    pub allowed: Vec<String>,
+
+    /// Tools hidden from clients and refused when called
+    #[serde(default)]
+    pub disabled: Vec<String>,
+
+    /// Tools treated as mutating besides the built-in ones (`reindex_paths`
+    /// and the force-reindex and cancel-reindex requests)
+    #[serde(default)]
+    pub mutating: Vec<String>,
+
+    /// Refuse every mutating tool and request, whatever the token scope
+    #[serde(default = "default_false")]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct QueryConfig {
+    /// Milliseconds a single query may run before returning partial results (0 = no limit)
+    #[serde(default = "default_query_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// Maximum candidates a single query may expand (0 = no limit)
+    #[serde(default = "default_max_candidates")]
+    pub max_candidates: usize,
+
+    /// Turns natural-language requests into filters: "rules" or "llm"
+    #[serde(default = "default_query_translator")]
+    pub translator: String,
+}
+
+/// Boosts of MCP search results, as shares added to their score (0 = off)
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RankingConfig {
+    /// Boost of a symbol the MCP session looked up last, fading with age
+    #[serde(default = "default_session_symbol_boost")]
+    pub session_symbol_boost: f32,
+
+    /// Boost of a result in the file the MCP session looked at last
+    #[serde(default = "default_session_file_boost")]
+    pub session_file_boost: f32,
+
+    /// Boost of a result in a file committed to today, fading over `recent_days`
+    #[serde(default = "default_recent_boost")]
+    pub recent_boost: f32,
+
+    /// Days of git history that count as recent
+    #[serde(default = "default_recent_days")]
+    pub recent_days: u32,
+
+    /// Boost of a result in a file the current branch changed
+    #[serde(default = "default_branch_boost")]
+    pub branch_boost: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SummaryConfig {
+    /// Enable `codanna summary` and show cached summaries in search results
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// OpenAI-compatible chat completions endpoint
+    #[serde(default = "default_summary_endpoint")]
+    pub endpoint: String,
+
+    /// Model name sent with each request
+    #[serde(default = "default_summary_model")]
+    pub model: String,
+
+    /// Environment variable holding the API key sent as a bearer token
+    #[serde(default = "default_summary_api_key_env")]
+    pub api_key_env: String,
+
+    /// Maximum characters of each file sent to the model
+    #[serde(default = "default_summary_max_input_chars")]
+    pub max_input_chars: usize,
+
+    /// Seconds to wait for each summary before giving up on that file
+    #[serde(default = "default_summary_timeout_secs")]
+    pub timeout_secs: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -173,6 +548,93 @@ pub struct SemanticSearchConfig {
     /// Number of parallel embedding model instances
     #[serde(default = "default_embedding_threads")]
     pub embedding_threads: usize,
+
+    /// Embedding backend: "local" (ONNX in process), "ollama" or "openai"
+    #[serde(default = "default_embedding_provider")]
+    pub provider: String,
+
+    /// Server URL for remote providers; empty uses the provider's default
+    #[serde(default)]
+    pub endpoint: String,
+
+    /// Environment variable holding the API key of a remote provider
+    #[serde(default = "default_embedding_api_key_env")]
+    pub api_key_env: String,
+
+    /// Vector size requested from OpenAI-compatible APIs (0 = model default)
+    #[serde(default)]
+    pub dimensions: usize,
+
+    /// Texts embedded per model call or request
+    #[serde(default = "default_embedding_batch_size")]
+    pub batch_size: usize,
+
+    /// Seconds to wait for each request to a remote provider
+    #[serde(default = "default_embedding_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Where local models run: "auto", "cpu" or "gpu"
+    #[serde(default = "default_embedding_device")]
+    pub device: String,
+
+    /// Embeddings kept in memory: "none", "int8" or "binary"
+    #[serde(default = "default_quantization")]
+    pub quantization: String,
+
+    /// Candidates rescored with full vectors, per requested result
+    #[serde(default = "default_rescore_factor")]
+    pub rescore_factor: usize,
+
+    /// Cross-encoder re-ranking hybrid results (empty = no re-ranking)
+    #[serde(default)]
+    pub rerank_model: String,
+
+    /// Best hybrid results the cross-encoder scores again
+    #[serde(default = "default_rerank_candidates")]
+    pub rerank_candidates: usize,
+
+    /// Reuse embeddings across projects from a per-user cache
+    #[serde(default = "default_true")]
+    pub embedding_cache: bool,
+
+    /// Size of a model's cache file past which it is started over
+    #[serde(default = "default_embedding_cache_max_mb")]
+    pub embedding_cache_max_mb: u64,
+
+    /// Text embedded per symbol: "docstring", "signature", "symbol" or "window"
+    #[serde(default = "default_chunking")]
+    pub chunking: String,
+
+    /// Source lines per chunk with "window" chunking
+    #[serde(default = "default_chunk_lines")]
+    pub chunk_lines: usize,
+
+    /// Lines consecutive windows share
+    #[serde(default = "default_chunk_overlap")]
+    pub chunk_overlap: usize,
+
+    /// Split identifiers and spell out abbreviations in hybrid search queries
+    #[serde(default = "default_true")]
+    pub query_expansion: bool,
+
+    /// Search saved vectors in place through a memory map instead of loading
+    /// them, when not quantized
+    #[serde(default = "default_false")]
+    pub mmap_vectors: bool,
+
+    /// Similarity thresholds measured by `codanna semantic calibrate`, by
+    /// model id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub calibrated: BTreeMap<String, ModelThresholds>,
+}
+
+/// Similarity thresholds calibrated for one embedding model
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct ModelThresholds {
+    /// Lowest score of a search result; unrelated symbols mostly score less
+    pub search: f32,
+    /// Lowest score of two symbols reported as similar
+    pub similar: f32,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -199,6 +661,85 @@ pub struct ServerConfig {
     /// Watch interval for stdio mode (seconds)
     #[serde(default = "default_watch_interval")]
     pub watch_interval: u64,
+
+    /// Seconds to wait for in-flight tool calls and index writes on shutdown
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+
+    /// Bearer tokens accepted by the HTTP and HTTPS servers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<TokenConfig>,
+}
+
+/// A bearer token for the network MCP transports
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TokenConfig {
+    /// Name logged for requests made with this token
+    pub name: String,
+
+    /// The token itself; prefer `token_env` to keep it out of settings.toml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Environment variable holding the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+
+    /// What requests made with this token may do
+    #[serde(default)]
+    pub scope: TokenScope,
+}
+
+impl TokenConfig {
+    /// The token value, from `token` or else from `token_env`
+    pub fn value(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| std::env::var(self.token_env.as_deref()?).ok())
+            .filter(|token| !token.is_empty())
+    }
+}
+
+/// Permissions granted by a bearer token
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Queries only
+    #[default]
+    Read,
+    /// Queries, plus forcing and cancelling re-indexing
+    Reindex,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MaintenanceConfig {
+    /// Run compaction, vector pruning and event retention from `codanna serve`
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Local time window for scheduled runs, e.g. "22:00-06:00" (empty = any time)
+    #[serde(default)]
+    pub window: String,
+
+    /// Tool calls and re-indexed files per `load_window_secs` above which tasks wait
+    #[serde(default = "default_maintenance_max_load")]
+    pub max_load: usize,
+
+    /// Seconds of recent activity counted against `max_load`
+    #[serde(default = "default_maintenance_load_window_secs")]
+    pub load_window_secs: u64,
+
+    /// Seconds between checks for due tasks
+    #[serde(default = "default_maintenance_check_interval_secs")]
+    pub check_interval_secs: u64,
+
+    /// Hours between runs of each task
+    #[serde(default = "default_maintenance_interval_hours")]
+    pub interval_hours: u64,
+
+    /// Days of index events kept in events.jsonl (0 = keep all)
+    #[serde(default = "default_event_retention_days")]
+    pub event_retention_days: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -304,6 +845,12 @@ fn default_batch_size() -> usize {
 fn default_batches_per_commit() -> usize {
     10 // Commit every 10 batches (~50K symbols)
 }
+fn default_memory_budget_mb() -> usize {
+    1024 // Leaves room for the Tantivy heap and symbol cache on 8GB machines
+}
+fn default_large_file_mb() -> usize {
+    2 // Generated tables and bundled sources; hand-written files stay far below
+}
 fn default_true() -> bool {
     true
 }
@@ -313,6 +860,75 @@ fn default_false() -> bool {
 fn default_max_context_size() -> usize {
     100_000
 }
+fn default_max_edges_per_symbol() -> usize {
+    500 // Keeps hub symbols (loggers, utilities) from producing megabyte responses
+}
+fn default_max_impact_results() -> usize {
+    2000
+}
+fn default_tool_threads() -> usize {
+    num_cpus::get().clamp(1, 4) // Heavy graph queries shouldn't starve the transport
+}
+fn default_max_concurrent_calls() -> usize {
+    4
+}
+fn default_max_concurrent_queries() -> usize {
+    16
+}
+fn default_max_queued_calls() -> usize {
+    256
+}
+fn default_rate_limit_burst() -> u32 {
+    20
+}
+fn default_max_response_bytes() -> usize {
+    40_000
+}
+fn default_max_open_projects() -> usize {
+    4
+}
+fn default_warm_set_size() -> usize {
+    20
+}
+fn default_query_timeout_ms() -> u64 {
+    5000
+}
+fn default_max_candidates() -> usize {
+    1000
+}
+fn default_query_translator() -> String {
+    "rules".to_string()
+}
+fn default_session_symbol_boost() -> f32 {
+    0.3
+}
+fn default_session_file_boost() -> f32 {
+    0.15
+}
+fn default_recent_boost() -> f32 {
+    0.1
+}
+fn default_recent_days() -> u32 {
+    14
+}
+fn default_branch_boost() -> f32 {
+    0.15
+}
+fn default_summary_endpoint() -> String {
+    "http://localhost:11434/v1/chat/completions".to_string() // Ollama's OpenAI-compatible API
+}
+fn default_summary_model() -> String {
+    "llama3.2".to_string()
+}
+fn default_summary_api_key_env() -> String {
+    "CODANNA_SUMMARY_API_KEY".to_string()
+}
+fn default_summary_max_input_chars() -> usize {
+    12_000
+}
+fn default_summary_timeout_secs() -> u64 {
+    60
+}
 fn default_embedding_model() -> String {
     "AllMiniLML6V2".to_string()
 }
@@ -322,6 +938,42 @@ fn default_similarity_threshold() -> f32 {
 fn default_embedding_threads() -> usize {
     3
 }
+fn default_embedding_provider() -> String {
+    "local".to_string()
+}
+fn default_embedding_api_key_env() -> String {
+    "CODANNA_EMBEDDING_API_KEY".to_string()
+}
+fn default_embedding_batch_size() -> usize {
+    crate::vector::DEFAULT_LOCAL_BATCH_SIZE
+}
+fn default_embedding_timeout_secs() -> u64 {
+    30
+}
+fn default_embedding_device() -> String {
+    "auto".to_string()
+}
+fn default_quantization() -> String {
+    "none".to_string()
+}
+fn default_rescore_factor() -> usize {
+    4
+}
+fn default_rerank_candidates() -> usize {
+    20
+}
+fn default_embedding_cache_max_mb() -> u64 {
+    2048
+}
+fn default_chunking() -> String {
+    "docstring".to_string()
+}
+fn default_chunk_lines() -> usize {
+    40
+}
+fn default_chunk_overlap() -> usize {
+    10
+}
 fn default_debounce_ms() -> u64 {
     500
 }
@@ -334,6 +986,24 @@ fn default_bind_address() -> String {
 fn default_watch_interval() -> u64 {
     5
 }
+fn default_shutdown_timeout() -> u64 {
+    10
+}
+fn default_maintenance_max_load() -> usize {
+    5
+}
+fn default_maintenance_load_window_secs() -> u64 {
+    300
+}
+fn default_maintenance_check_interval_secs() -> u64 {
+    300
+}
+fn default_maintenance_interval_hours() -> u64 {
+    24
+}
+fn default_event_retention_days() -> u64 {
+    30
+}
 
 impl Default for Settings {
     fn default() -> Self {
@@ -342,12 +1012,20 @@ impl Default for Settings {
             index_path: default_index_path(),
             workspace_root: None,
             indexing: IndexingConfig::default(),
+            storage: StorageConfig::default(),
             indexed_paths_cache: Vec::new(),
+            config_warnings: Vec::new(),
             languages: generate_language_defaults(), // Now uses registry
+            grammars: HashMap::new(),
+            repositories: HashMap::new(),
             mcp: McpConfig::default(),
+            query: QueryConfig::default(),
+            ranking: RankingConfig::default(),
+            summary: SummaryConfig::default(),
             semantic_search: SemanticSearchConfig::default(),
             file_watch: FileWatchConfig::default(),
             server: ServerConfig::default(),
+            maintenance: MaintenanceConfig::default(),
             logging: LoggingConfig::default(),
             guidance: GuidanceConfig::default(),
             documents: crate::documents::DocumentsConfig::default(),
@@ -371,8 +1049,21 @@ impl Default for IndexingConfig {
             indexed_paths: Vec::new(),
             batch_size: default_batch_size(),
             batches_per_commit: default_batches_per_commit(),
+            memory_budget_mb: default_memory_budget_mb(),
             pipeline_tracing: false,
+            metrics_json: None,
+            deterministic: false,
             show_progress: true,
+            event_log: false,
+            language_injections: false,
+            type_inference: true,
+            generated_files: GeneratedFiles::default(),
+            priority_indexing: true,
+            large_file_mb: default_large_file_mb(),
+            dedup_identical_files: true,
+            index_archives: false,
+            blame: false,
+            shared_index: None,
         }
     }
 }
@@ -381,6 +1072,56 @@ impl Default for McpConfig {
     fn default() -> Self {
         Self {
             max_context_size: default_max_context_size(),
+            max_edges_per_symbol: default_max_edges_per_symbol(),
+            max_impact_results: default_max_impact_results(),
+            tool_threads: default_tool_threads(),
+            max_concurrent_calls: default_max_concurrent_calls(),
+            max_concurrent_queries: default_max_concurrent_queries(),
+            max_queued_calls: default_max_queued_calls(),
+            rate_limit_per_minute: 0,
+            rate_limit_burst: default_rate_limit_burst(),
+            max_response_bytes: default_max_response_bytes(),
+            stream_pages: false,
+            max_open_projects: default_max_open_projects(),
+            warm_start: false,
+            warm_set_size: default_warm_set_size(),
+            tool_concurrency: HashMap::new(),
+            permissions: ToolPermissions::default(),
+        }
+    }
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: default_query_timeout_ms(),
+            max_candidates: default_max_candidates(),
+            translator: default_query_translator(),
+        }
+    }
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            session_symbol_boost: default_session_symbol_boost(),
+            session_file_boost: default_session_file_boost(),
+            recent_boost: default_recent_boost(),
+            recent_days: default_recent_days(),
+            branch_boost: default_branch_boost(),
+        }
+    }
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_summary_endpoint(),
+            model: default_summary_model(),
+            api_key_env: default_summary_api_key_env(),
+            max_input_chars: default_summary_max_input_chars(),
+            timeout_secs: default_summary_timeout_secs(),
         }
     }
 }
@@ -392,6 +1133,25 @@ impl Default for SemanticSearchConfig {
             model: default_embedding_model(),
             threshold: default_similarity_threshold(),
             embedding_threads: default_embedding_threads(),
+            provider: default_embedding_provider(),
+            endpoint: String::new(),
+            api_key_env: default_embedding_api_key_env(),
+            dimensions: 0,
+            batch_size: default_embedding_batch_size(),
+            timeout_secs: default_embedding_timeout_secs(),
+            device: default_embedding_device(),
+            quantization: default_quantization(),
+            rescore_factor: default_rescore_factor(),
+            rerank_model: String::new(),
+            rerank_candidates: default_rerank_candidates(),
+            embedding_cache: true,
+            embedding_cache_max_mb: default_embedding_cache_max_mb(),
+            chunking: default_chunking(),
+            chunk_lines: default_chunk_lines(),
+            chunk_overlap: default_chunk_overlap(),
+            query_expansion: true,
+            mmap_vectors: false,
+            calibrated: BTreeMap::new(),
         }
     }
 }
@@ -411,6 +1171,22 @@ impl Default for ServerConfig {
             mode: default_server_mode(),
             bind: default_bind_address(),
             watch_interval: default_watch_interval(),
+            shutdown_timeout: default_shutdown_timeout(),
+            tokens: Vec::new(),
+        }
+    }
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window: String::new(),
+            max_load: default_maintenance_max_load(),
+            load_window_secs: default_maintenance_load_window_secs(),
+            check_interval_secs: default_maintenance_check_interval_secs(),
+            interval_hours: default_maintenance_interval_hours(),
+            event_retention_days: default_event_retention_days(),
         }
     }
 }
@@ -466,6 +1242,14 @@ fn default_guidance_templates() -> HashMap<String, GuidanceTemplate> {
         custom: vec![],
     });
 
+    // Find implementations
+    templates.insert("find_implementations".to_string(), GuidanceTemplate {
+        no_results: Some("Nothing implements or overrides this symbol. Implementations in files that failed to resolve are missed; try 'search_symbols' with the trait name.".to_string()),
+        single_result: Some("Found 1 implementation. Use 'find_symbol' on it to see its full context.".to_string()),
+        multiple_results: Some("Found {result_count} implementations. Check each before changing the signature, or use 'analyze_impact' for their callers as well.".to_string()),
+        custom: vec![],
+    });
+
     // Analyze impact
     templates.insert("analyze_impact".to_string(), GuidanceTemplate {
         no_results: Some("No impact detected. This symbol appears isolated. Consider using the codanna-navigator agent for comprehensive multi-hop analysis of complex relationships.".to_string()),
@@ -485,6 +1269,14 @@ fn default_guidance_templates() -> HashMap<String, GuidanceTemplate> {
         ],
     });
 
+    // Test context
+    templates.insert("test_context_for".to_string(), GuidanceTemplate {
+        no_results: Some("No existing tests reference this symbol. Follow the detected framework's conventions and copy construction sites from the dependencies above.".to_string()),
+        single_result: Some("Found 1 related test. Mirror its setup and assertions for the new test.".to_string()),
+        multiple_results: Some("Found {result_count} related tests. Pick the closest one as a template, or use 'find_symbol' on it to see its full body location.".to_string()),
+        custom: vec![],
+    });
+
     // Search symbols
     templates.insert("search_symbols".to_string(), GuidanceTemplate {
         no_results: Some("No symbols match your query. Try 'semantic_search_docs' for natural language search or adjust your pattern.".to_string()),
@@ -540,6 +1332,8 @@ fn generate_language_defaults() -> HashMap<String, LanguageConfig> {
                     extensions: def.extensions().iter().map(|s| s.to_string()).collect(),
                     parser_options: HashMap::new(),
                     config_files: Vec::new(), // Empty by default - opt-in feature
+                    large_file_mb: None,
+                    chunking: None,
                 },
             );
         }
@@ -568,6 +1362,8 @@ fn fallback_minimal_languages() -> HashMap<String, LanguageConfig> {
             extensions: vec!["rs".to_string()],
             parser_options: HashMap::new(),
             config_files: Vec::new(),
+            large_file_mb: None,
+            chunking: None,
         },
     );
 
@@ -598,12 +1394,13 @@ impl Settings {
         let local_dir = crate::init::local_dir_name();
         let config_path = Self::find_workspace_config()
             .unwrap_or_else(|| PathBuf::from(local_dir).join("settings.toml"));
+        let (file, config_warnings) = Self::migrated_file(&config_path);
 
         Figment::new()
             // Start with defaults
             .merge(Serialized::defaults(Settings::default()))
-            // Layer in config file if it exists
-            .merge(Toml::file(config_path))
+            // Layer in config file if it exists, with deprecated keys migrated
+            .merge(file)
             // Layer in environment variables with CI_ prefix
             // Use double underscore (__) to separate nested levels
             // Single underscore (_) remains as is within field names
@@ -621,6 +1418,7 @@ impl Settings {
                 if settings.workspace_root.is_none() {
                     settings.workspace_root = Self::workspace_root();
                 }
+                settings.config_warnings = config_warnings;
                 settings.sync_indexed_path_cache();
                 settings
             })
@@ -691,18 +1489,72 @@ impl Settings {
 
     /// Load configuration from a specific file
     pub fn load_from(path: impl AsRef<std::path::Path>) -> Result<Self, Box<figment::Error>> {
+        let (file, config_warnings) = Self::migrated_file(path.as_ref());
+
         Figment::new()
             .merge(Serialized::defaults(Settings::default()))
-            .merge(Toml::file(path))
+            .merge(file)
             .merge(Env::prefixed("CI_").split("_"))
             .extract()
             .map(|mut settings: Settings| {
+                settings.config_warnings = config_warnings;
                 settings.sync_indexed_path_cache();
                 settings
             })
             .map_err(Box::new)
     }
 
+    /// Read a config file with deprecated keys migrated
+    ///
+    /// Files that need no migration, are missing, or fail to parse are handed
+    /// to figment unchanged so its errors still name the file.
+    fn migrated_file(path: &Path) -> (figment::providers::Data<Toml>, Vec<ConfigWarning>) {
+        let Some(mut table) = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str::<toml::Table>(&content).ok())
+        else {
+            return (Toml::file(path), Vec::new());
+        };
+
+        let warnings = migrate_config(&mut table);
+        let migrated = warnings
+            .iter()
+            .any(|w| !matches!(w, ConfigWarning::Unknown { .. }));
+        match toml::to_string(&table) {
+            Ok(content) if migrated => (Toml::string(&content), warnings),
+            _ => (Toml::file(path), warnings),
+        }
+    }
+
+    /// Rewrite a config file with deprecated keys migrated and unknown keys
+    /// dropped. The original is kept next to it as `settings.toml.bak`.
+    ///
+    /// Returns the warnings that were resolved; the file is left untouched
+    /// when there are none.
+    pub fn migrate_file(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Vec<ConfigWarning>, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let mut table: toml::Table = toml::from_str(&content)?;
+        let warnings = migrate_config(&mut table);
+        if warnings.is_empty() {
+            return Ok(warnings);
+        }
+
+        let settings: Settings = Figment::new()
+            .merge(Serialized::defaults(Settings::default()))
+            .merge(Toml::string(&toml::to_string(&table)?))
+            .extract()?;
+
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        std::fs::copy(path, PathBuf::from(backup))?;
+        settings.save(path)?;
+
+        Ok(warnings)
+    }
+
     /// Save current configuration to file
     pub fn save(
         &self,
@@ -808,6 +1660,8 @@ impl Settings {
         );
 
         let mut in_languages_section = false;
+        let mut in_summary_section = false;
+        let mut in_semantic_section = false;
         let mut prev_line_was_section = false;
 
         for line in toml.lines() {
@@ -817,6 +1671,10 @@ impl Settings {
                 continue;
             }
             prev_line_was_section = false;
+            if line.starts_with('[') {
+                in_summary_section = line == "[summary]";
+                in_semantic_section = line == "[semantic_search]";
+            }
 
             // Add section and field comments
             if line == "version = 1" {
@@ -825,6 +1683,85 @@ impl Settings {
                 result.push_str("\n# Path to the index directory (relative to workspace root)\n");
             } else if line.starts_with("workspace_root = ") {
                 result.push_str("\n# Workspace root directory (automatically detected)\n");
+            } else if in_semantic_section && line.starts_with("provider = ") {
+                result.push_str("\n# Embedding backend: \"local\" runs the model in process,\n");
+                result.push_str(
+                    "# \"ollama\" and \"openai\" (any OpenAI-compatible API) call a server\n",
+                );
+                result.push_str("# With a remote provider, model is the name the server knows\n");
+            } else if in_semantic_section && line.starts_with("endpoint = ") {
+                result.push_str("\n# Server URL for remote providers (empty = provider default)\n");
+            } else if in_semantic_section && line.starts_with("api_key_env = ") {
+                result.push_str("\n# Environment variable holding the provider's API key\n");
+            } else if in_semantic_section && line.starts_with("dimensions = ") {
+                result.push_str(
+                    "\n# Vector size requested from OpenAI-compatible APIs (0 = model default)\n",
+                );
+            } else if in_semantic_section && line.starts_with("batch_size = ") {
+                result.push_str("\n# Texts embedded per model call or request\n");
+            } else if in_semantic_section && line.starts_with("timeout_secs = ") {
+                result.push_str("\n# Seconds to wait for each request to a remote provider\n");
+            } else if in_semantic_section && line.starts_with("device = ") {
+                result
+                    .push_str("\n# Where local models run: \"auto\" uses a GPU when available,\n");
+                result.push_str(
+                    "# \"gpu\" warns when it falls back to the CPU, \"cpu\" never uses one\n",
+                );
+                result.push_str(
+                    "# GPU support needs a build with --features gpu-cuda or gpu-coreml\n",
+                );
+            } else if in_semantic_section && line.starts_with("quantization = ") {
+                result.push_str(
+                    "\n# Embeddings kept in memory for search: \"none\" (full precision),\n",
+                );
+                result.push_str(
+                    "# \"int8\" (4x smaller) or \"binary\" (32x smaller). Full vectors stay on\n",
+                );
+                result.push_str("# disk to rescore the best candidates\n");
+            } else if in_semantic_section && line.starts_with("rescore_factor = ") {
+                result.push_str(
+                    "\n# Candidates rescored with full vectors for each result requested\n",
+                );
+            } else if in_semantic_section && line.starts_with("rerank_model = ") {
+                result.push_str(
+                    "\n# Cross-encoder re-ranking the best hybrid search results, e.g.\n",
+                );
+                result.push_str(
+                    "# \"JINARerankerV1TurboEn\" (small, English) or \"BGERerankerV2M3\"\n",
+                );
+                result.push_str("# (multilingual). Empty disables re-ranking\n");
+            } else if in_semantic_section && line.starts_with("rerank_candidates = ") {
+                result.push_str("\n# Best hybrid results the cross-encoder scores again\n");
+            } else if in_semantic_section && line.starts_with("embedding_cache = ") {
+                result
+                    .push_str("\n# Reuse embeddings of identical documentation across projects,\n");
+                result.push_str("# branches and worktrees (cached in ~/.codanna/cache)\n");
+            } else if in_semantic_section && line.starts_with("embedding_cache_max_mb = ") {
+                result.push_str("\n# Cache size per model in MB before it is started over\n");
+            } else if in_semantic_section && line.starts_with("chunking = ") {
+                result
+                    .push_str("\n# Text embedded per symbol: \"docstring\" (doc comments only),\n");
+                result.push_str(
+                    "# \"signature\" (signature and doc summary, undocumented symbols too),\n",
+                );
+                result.push_str(
+                    "# \"symbol\" (function source) or \"window\" (source in overlapping windows)\n",
+                );
+                result.push_str("# Override per language with languages.<id>.chunking\n");
+            } else if in_semantic_section && line.starts_with("chunk_lines = ") {
+                result.push_str("\n# Source lines per window with \"window\" chunking\n");
+            } else if in_semantic_section && line.starts_with("chunk_overlap = ") {
+                result.push_str("\n# Lines consecutive windows share\n");
+            } else if in_semantic_section && line.starts_with("query_expansion = ") {
+                result.push_str(
+                    "\n# Split camelCase/snake_case identifiers and spell out abbreviations\n",
+                );
+                result.push_str("# (cfg -> config) in hybrid search queries\n");
+            } else if in_semantic_section && line.starts_with("mmap_vectors = ") {
+                result.push_str(
+                    "\n# Search saved vectors in place on disk instead of loading them at startup\n",
+                );
+                result.push_str("# Uses less memory on large indexes; ignored when quantized\n");
             } else if line == "[indexing]" {
                 result.push_str("\n[indexing]\n");
                 prev_line_was_section = true;
@@ -852,19 +1789,216 @@ impl Settings {
                 result.push_str("\n# Items per batch before flushing to index (default: 5000)\n");
             } else if line.starts_with("batches_per_commit = ") {
                 result.push_str("\n# Number of batches before committing to disk (default: 10)\n");
+            } else if line.starts_with("memory_budget_mb = ") {
+                result.push_str(
+                    "\n# Memory for file contents and batches in flight, in MB (default: 1024)\n",
+                );
+                result.push_str("# Reading slows down and relationships spill to disk above it; 0 = unlimited\n");
             } else if line.starts_with("pipeline_tracing = ") {
                 result.push_str("\n# Enable detailed pipeline stage tracing\n");
                 result.push_str("# Shows timing, throughput, and memory for each stage\n");
                 result.push_str("# Requires: logging.modules.pipeline = \"info\"\n");
+            } else if line.starts_with("deterministic = ") {
+                result.push_str("\n# Reproducible builds: same symbol IDs and index digest for the same files\n");
+                result.push_str(
+                    "# Slower; timestamps come from SOURCE_DATE_EPOCH (default: false)\n",
+                );
             } else if line.starts_with("show_progress = ") {
                 result.push_str("\n# Show progress bars during indexing (default: true)\n");
                 result.push_str("# Use --no-progress CLI flag to override\n");
+            } else if line.starts_with("event_log = ") {
+                result
+                    .push_str("\n# Record file and symbol changes in <index_path>/events.jsonl\n");
+                result.push_str("# Follow them with: codanna events --follow\n");
+            } else if line.starts_with("language_injections = ") {
+                result.push_str(
+                    "\n# Index SQL found in string literals under the enclosing symbol\n",
+                );
+                result.push_str("# Queried tables become Uses relationships of that symbol\n");
+            } else if line.starts_with("type_inference = ") {
+                result.push_str(
+                    "\n# Guess receiver types in Python/JavaScript/TypeScript to resolve method calls\n",
+                );
+                result.push_str("# Calls resolved this way carry a confidence below 1.0\n");
+            } else if line.starts_with("generated_files = ") {
+                result.push_str(
+                    "\n# Generated and minified files: \"skip\", \"no_embeddings\" or \"index\"\n",
+                );
+                result.push_str("# Binary files are never indexed (default: \"no_embeddings\")\n");
+            } else if line.starts_with("priority_indexing = ") {
+                result.push_str(
+                    "\n# Index files open in editors (<index_path>/open_files), touched on the\n",
+                );
+                result.push_str(
+                    "# current git branch or modified today before the rest (default: true)\n",
+                );
+            } else if line.starts_with("large_file_mb = ") {
+                result.push_str(
+                    "\n# Files above this size in MB are parsed in chunks (default: 2, 0 = never)\n",
+                );
+                result.push_str("# Set languages.<id>.large_file_mb to override it per language\n");
+            } else if line.starts_with("dedup_identical_files = ") {
+                result.push_str(
+                    "\n# Parse and embed identical files (vendored copies) once, as aliases\n",
+                );
+                result.push_str("# of the first copy read (default: true)\n");
+            } else if line.starts_with("index_archives = ") {
+                result.push_str(
+                    "\n# Index sources inside .jar, .whl, .zip and .tar(.gz) archives as\n",
+                );
+                result.push_str("# read-only <archive>!/<entry> paths (default: false)\n");
+            } else if line.starts_with("blame = ") {
+                result.push_str(
+                    "\n# Record which commits introduced and last modified each symbol, from\n",
+                );
+                result.push_str("# git blame; slows indexing (default: false)\n");
+            } else if line == "[storage]" {
+                result.push_str("\n[storage]\n");
+                prev_line_was_section = true;
+                continue;
+            } else if line == "[storage.compression]" {
+                result.push_str("\n[storage.compression]\n");
+                result.push_str("# zstd levels per store, 1-22 (default: 3), or 0 for lz4;\n");
+                result.push_str("# applied to new indexes (rebuild: 'codanna index --force')\n");
+                prev_line_was_section = true;
+                continue;
+            } else if line.starts_with("code_level = ") {
+                result.push_str("# Symbol doc comments, signatures and source context\n");
+            } else if line.starts_with("documents_level = ") {
+                result.push_str("# Document chunk bodies\n");
             } else if line == "[mcp]" {
                 result.push_str("\n[mcp]\n");
                 prev_line_was_section = true;
                 continue;
             } else if line.starts_with("max_context_size = ") {
                 result.push_str("# Maximum context size in bytes for MCP server\n");
+            } else if line.starts_with("max_edges_per_symbol = ") {
+                result.push_str("\n# Maximum relationships loaded per symbol (default: 500)\n");
+                result.push_str("# Callers beyond this are paged; hub symbols are summarized in impact analysis\n");
+            } else if line.starts_with("max_impact_results = ") {
+                result
+                    .push_str("\n# Maximum symbols collected by analyze_impact (default: 2000)\n");
+            } else if line.starts_with("tool_threads = ") {
+                result.push_str(
+                    "\n# Worker threads for running MCP tools (0 = share the server runtime)\n",
+                );
+                result.push_str("# Keeps one enormous graph query from blocking simple lookups\n");
+            } else if line.starts_with("max_concurrent_calls = ") {
+                result.push_str("\n# Concurrent calls allowed per tool (default: 4)\n");
+                result.push_str(
+                    "# Override per tool with [mcp.tool_concurrency], e.g. analyze_impact = 1\n",
+                );
+            } else if line.starts_with("max_concurrent_queries = ") {
+                result
+                    .push_str("\n# Concurrent calls across all tools and clients (0 = no limit)\n");
+            } else if line.starts_with("max_queued_calls = ") {
+                result.push_str(
+                    "\n# Calls allowed to wait for a slot; more are refused as busy (0 = no limit)\n",
+                );
+            } else if line.starts_with("rate_limit_per_minute = ") {
+                result.push_str("\n# Tool calls per minute for each client (0 = no limit)\n");
+                result.push_str("# Clients are told by bearer token, else by MCP session\n");
+            } else if line.starts_with("rate_limit_burst = ") {
+                result.push_str("\n# Calls a client may make at once before the rate applies\n");
+            } else if line.starts_with("max_response_bytes = ") {
+                result.push_str(
+                    "\n# Tool results longer than this many bytes are paged (0 = never)\n",
+                );
+                result
+                    .push_str("# Clients fetch the rest with the next_page tool and the cursor\n");
+            } else if line.starts_with("stream_pages = ") {
+                result
+                    .push_str("\n# Stream pages as progress notifications to clients that send\n");
+                result.push_str("# a progress token, instead of returning a cursor\n");
+            } else if line.starts_with("max_open_projects = ") {
+                result.push_str(
+                    "\n# Other registered projects kept open for the 'project' tool argument\n",
+                );
+            } else if line.starts_with("warm_start = ") {
+                result.push_str(
+                    "\n# Preload the most used symbols and files on every MCP handshake\n",
+                );
+                result.push_str(
+                    "# and send a primer of them (clients can also ask with codanna/warmStart)\n",
+                );
+            } else if line.starts_with("warm_set_size = ") {
+                result.push_str("\n# Symbols, files and queries preloaded by a warm start\n");
+            } else if line == "[mcp.permissions]" {
+                result.push_str("\n[mcp.permissions]\n");
+                result.push_str(
+                    "# Tools MCP clients may see and call (codanna mcp is not affected)\n",
+                );
+                prev_line_was_section = true;
+                continue;
+            } else if line.starts_with("allowed = ") {
+                result.push_str("# Only these tools are available (empty = all)\n");
+            } else if line.starts_with("disabled = ") {
+                result.push_str(
+                    "\n# Tools hidden from clients and refused, e.g. [\"get_symbol_source\"]\n",
+                );
+            } else if line.starts_with("mutating = ") {
+                result.push_str(
+                    "\n# Tools that need a reindex-scoped token, besides reindex_paths\n",
+                );
+            } else if line.starts_with("read_only = ") {
+                result.push_str("\n# Refuse every mutating tool and request, whatever the token\n");
+            } else if line == "[query]" {
+                result.push_str("\n[query]\n");
+                result.push_str("# Limits for retrieve commands and MCP tools\n");
+                result.push_str(
+                    "# Queries that hit a limit return partial results flagged as truncated\n",
+                );
+                prev_line_was_section = true;
+                continue;
+            } else if line.starts_with("timeout_ms = ") {
+                result.push_str(
+                    "# Milliseconds before a query stops expanding results (0 = no limit)\n",
+                );
+            } else if line.starts_with("max_candidates = ") {
+                result.push_str("\n# Maximum candidates a query may expand (0 = no limit)\n");
+            } else if line.starts_with("translator = ") {
+                result.push_str(
+                    "\n# How 'retrieve ask' turns a request into filters: \"rules\" (built in)\n",
+                );
+                result.push_str("# or \"llm\" (the [summary] endpoint, falling back to rules)\n");
+            } else if line == "[ranking]" {
+                result.push_str("\n[ranking]\n");
+                result.push_str("# Rank MCP search results in what you are working on higher\n");
+                result.push_str("# Each boost is a share added to the score (0 = off)\n");
+                prev_line_was_section = true;
+                continue;
+            } else if line.starts_with("session_symbol_boost = ") {
+                result.push_str("# Symbols the MCP session looked up, the latest most\n");
+            } else if line.starts_with("session_file_boost = ") {
+                result.push_str("\n# Files the MCP session looked at, the latest most\n");
+            } else if line.starts_with("recent_boost = ") {
+                result.push_str("\n# Files committed to in the last recent_days, newest most\n");
+            } else if line.starts_with("recent_days = ") {
+                result.push_str("\n# Days of git history that count as recent\n");
+            } else if line.starts_with("branch_boost = ") {
+                result.push_str("\n# Files the current branch changed since it forked from main\n");
+            } else if line == "[summary]" {
+                result.push_str("\n[summary]\n");
+                result.push_str("# Natural-language file summaries generated by an LLM endpoint\n");
+                result.push_str(
+                    "# Run 'codanna summary' to refresh; only changed files are resent\n",
+                );
+                prev_line_was_section = true;
+                continue;
+            } else if in_summary_section && line.starts_with("enabled = ") {
+                // enabled field in summary - comment already added above
+            } else if line.starts_with("endpoint = ") {
+                result.push_str("\n# OpenAI-compatible chat completions endpoint\n");
+            } else if in_summary_section && line.starts_with("model = ") {
+                result.push_str("\n# Model name sent to the endpoint\n");
+            } else if line.starts_with("api_key_env = ") {
+                result.push_str(
+                    "\n# Environment variable holding the API key (sent as a bearer token)\n",
+                );
+            } else if line.starts_with("max_input_chars = ") {
+                result.push_str("\n# Maximum characters of each file sent to the model\n");
+            } else if line.starts_with("timeout_secs = ") {
+                result.push_str("\n# Seconds to wait for each summary\n");
             } else if line == "[semantic_search]" {
                 result.push_str("\n[semantic_search]\n");
                 result.push_str("# Semantic search for natural language code queries\n");
@@ -875,7 +2009,7 @@ impl Settings {
             } else if line.starts_with("model = ") {
                 result.push_str("\n# Model to use for embeddings\n");
                 result.push_str(
-                    "# Note: Changing models requires re-indexing (codanna index --force)\n",
+                    "# Note: Changing models requires re-embedding (codanna semantic migrate)\n",
                 );
                 result.push_str("# - AllMiniLML6V2: English-only, 384 dimensions (default)\n");
                 result.push_str("# - MultilingualE5Small: 94 languages including, 384 dimensions (recommended for multilingual)\n");
@@ -921,6 +2055,35 @@ impl Settings {
                 result.push_str("\n# HTTP server bind address (only used when mode = \"http\" or --http flag)\n");
             } else if line.starts_with("watch_interval = ") {
                 result.push_str("\n# Watch interval for stdio mode in seconds (how often to check for file changes)\n");
+            } else if line.starts_with("shutdown_timeout = ") {
+                result.push_str("\n# Seconds to let in-flight tool calls and index writes finish on Ctrl+C/SIGTERM\n");
+            } else if line == "[maintenance]" {
+                result.push_str("\n[maintenance]\n");
+                result.push_str(
+                    "# Compact the index, prune stale vectors and trim old index events\n",
+                );
+                result.push_str(
+                    "# while 'codanna serve' is idle; 'codanna maintenance run' forces a run\n",
+                );
+                prev_line_was_section = true;
+                continue;
+            } else if line.starts_with("window = ") {
+                result.push_str(
+                    "\n# Local time window for runs, e.g. \"22:00-06:00\" (empty = any time)\n",
+                );
+            } else if line.starts_with("max_load = ") {
+                result.push_str(
+                    "\n# Tool calls and re-indexed files within load_window_secs above which\n",
+                );
+                result.push_str("# maintenance waits for a quieter moment\n");
+            } else if line.starts_with("load_window_secs = ") {
+                result.push_str("\n# Seconds of recent activity counted against max_load\n");
+            } else if line.starts_with("check_interval_secs = ") {
+                result.push_str("\n# Seconds between checks for due tasks\n");
+            } else if line.starts_with("interval_hours = ") {
+                result.push_str("\n# Hours between runs of each task\n");
+            } else if line.starts_with("event_retention_days = ") {
+                result.push_str("\n# Days of index events kept in events.jsonl (0 = keep all)\n");
             } else if line == "[logging]" {
                 result.push_str("\n[logging]\n");
                 result.push_str("# Logging configuration\n");
@@ -991,6 +2154,10 @@ impl Settings {
                 result.push_str("\n# Collection configuration\n");
                 result.push_str("# paths: directories or files to include\n");
                 result.push_str("# patterns: glob patterns to match (default: [\"**/*.md\"])\n");
+            } else if line.starts_with("[semantic_search.calibrated.") {
+                result.push_str("\n# Similarity thresholds of this model, set by 'codanna semantic calibrate'\n");
+                result.push_str("# search: default floor of semantic search scores\n");
+                result.push_str("# similar: default threshold of find_similar\n");
             } else if line.starts_with("[languages.") {
                 if !in_languages_section {
                     result.push_str("\n# Language-specific settings\n");
@@ -1003,6 +2170,20 @@ impl Settings {
             result.push('\n');
         }
 
+        if !toml.contains("[grammars.") {
+            result.push_str("\n# Tree-sitter grammars loaded at runtime. Example:\n");
+            result.push_str("# [grammars.zig]\n");
+            result.push_str("# library = \"grammars/libtree-sitter-zig.so\"  # or a .wasm file\n");
+            result.push_str("# extensions = [\"zig\"]\n");
+            result.push_str("# query = \"grammars/zig-tags.scm\"\n");
+        }
+
+        if !toml.contains("[repositories.") {
+            result.push_str("\n# Other repositories indexed into this index. Example:\n");
+            result.push_str("# [repositories.billing]\n");
+            result.push_str("# path = \"../billing\"  # search with repo:billing\n");
+        }
+
         result
     }
 
@@ -1166,8 +2347,417 @@ __pycache__/
 
     /// Get all indexed paths
     /// Returns empty vector if none are configured (maintains backward compatibility)
+    ///
+    /// Roots from `[repositories]` follow the configured paths unless one of
+    /// those already covers them.
     pub fn get_indexed_paths(&self) -> Vec<PathBuf> {
-        self.indexing.indexed_paths.clone()
+        let mut paths = self.indexing.indexed_paths.clone();
+        for (_, root) in self.repository_roots() {
+            if !paths.iter().any(|path| root.starts_with(path)) {
+                paths.push(root);
+            }
+        }
+        paths
+    }
+
+    /// Name and root of every configured repository, sorted by name
+    ///
+    /// Relative paths are resolved against the workspace root and
+    /// canonicalized when they exist.
+    pub fn repository_roots(&self) -> Vec<(String, PathBuf)> {
+        let base = self
+            .workspace_root
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."));
+        let mut roots: Vec<(String, PathBuf)> = self
+            .repositories
+            .iter()
+            .map(|(name, repository)| {
+                let root = base.join(&repository.path);
+                let root = root.canonicalize().unwrap_or(root);
+                (name.clone(), root)
+            })
+            .collect();
+        roots.sort();
+        roots
+    }
+
+    /// Name of the repository containing `file_path`
+    ///
+    /// When repositories are nested the innermost wins.
+    pub fn repository_for(&self, file_path: &Path) -> Option<String> {
+        let absolute = self.resolve_stored_path(file_path);
+        self.repository_roots()
+            .into_iter()
+            .filter(|(_, root)| absolute.starts_with(root))
+            .max_by_key(|(_, root)| root.components().count())
+            .map(|(name, _)| name)
+    }
+
+    /// Shared index directory configured in `indexing.shared_index`
+    pub fn shared_index_root(&self) -> Option<PathBuf> {
+        let shared = self.indexing.shared_index.as_ref()?;
+        let base = self
+            .workspace_root
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."));
+        Some(base.join(shared))
+    }
+
+    /// Absolute form of a path as the index stores it
+    ///
+    /// Relative paths are taken relative to the canonical workspace root.
+    pub fn resolve_stored_path(&self, file_path: &Path) -> PathBuf {
+        match &self.workspace_root {
+            Some(root) if file_path.is_relative() => root
+                .canonicalize()
+                .unwrap_or_else(|_| root.clone())
+                .join(file_path),
+            _ => file_path.to_path_buf(),
+        }
+    }
+}
+
+// Config key registry
+//
+// Every key `Settings` understands is derived from its serialized defaults,
+// so new fields register themselves. Renamed and removed keys are listed in
+// `DEPRECATED_KEYS` and migrated when a config file is loaded.
+
+/// Type of value a config key holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigValueKind {
+    Boolean,
+    Integer,
+    Float,
+    String,
+    Datetime,
+    Array,
+    Table,
+}
+
+impl ConfigValueKind {
+    fn of(value: &toml::Value) -> Self {
+        match value {
+            toml::Value::Boolean(_) => Self::Boolean,
+            toml::Value::Integer(_) => Self::Integer,
+            toml::Value::Float(_) => Self::Float,
+            toml::Value::String(_) => Self::String,
+            toml::Value::Datetime(_) => Self::Datetime,
+            toml::Value::Array(_) => Self::Array,
+            toml::Value::Table(_) => Self::Table,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::String => "string",
+            Self::Datetime => "datetime",
+            Self::Array => "array",
+            Self::Table => "table",
+        }
+    }
+}
+
+/// A key understood by `Settings`
+#[derive(Debug, Clone)]
+pub struct ConfigKey {
+    /// Dotted path, e.g. `indexing.parallelism`
+    pub path: String,
+    pub kind: ConfigValueKind,
+    /// Default value; `None` for tables and keys that are unset by default
+    pub default: Option<toml::Value>,
+}
+
+/// A key that was renamed or removed
+#[derive(Debug, Clone, Copy)]
+pub struct DeprecatedKey {
+    /// Dotted path of the old key or table
+    pub path: &'static str,
+    /// Where the value moved to; `None` if the key was removed
+    pub replacement: Option<&'static str>,
+    /// Shown to the user alongside the warning
+    pub note: &'static str,
+}
+
+/// Deprecated keys, applied in order (so a renamed table is moved before
+/// its own renamed keys)
+pub const DEPRECATED_KEYS: &[DeprecatedKey] = &[
+    DeprecatedKey {
+        path: "semantic",
+        replacement: Some("semantic_search"),
+        note: "the [semantic] table is now [semantic_search]",
+    },
+    DeprecatedKey {
+        path: "semantic_search.similarity_threshold",
+        replacement: Some("semantic_search.threshold"),
+        note: "",
+    },
+    DeprecatedKey {
+        path: "indexing.threads",
+        replacement: Some("indexing.parallelism"),
+        note: "",
+    },
+    DeprecatedKey {
+        path: "indexing.parallel_threads",
+        replacement: Some("indexing.parallelism"),
+        note: "",
+    },
+    DeprecatedKey {
+        path: "indexing.include_tests",
+        replacement: None,
+        note: "test files are always indexed; exclude them with ignore_patterns",
+    },
+    DeprecatedKey {
+        path: "indexing.max_file_size_mb",
+        replacement: None,
+        note: "large files are parsed in chunks above indexing.large_file_mb",
+    },
+    DeprecatedKey {
+        path: "performance",
+        replacement: None,
+        note: "cache sizes are no longer configurable",
+    },
+];
+
+/// Tables whose entries are user-chosen names rather than fixed keys
+const OPEN_TABLES: &[&str] = &[
+    "languages",
+    "grammars",
+    "repositories",
+    "logging.modules",
+    "mcp.tool_concurrency",
+    "guidance.templates",
+    "guidance.variables",
+    "documents.collections",
+    "semantic_search.calibrated",
+];
+
+/// Keys that are unset by default and so missing from the serialized defaults
+const OPTIONAL_KEYS: &[(&str, ConfigValueKind)] = &[
+    ("workspace_root", ConfigValueKind::String),
+    ("indexing.project_root", ConfigValueKind::String),
+    ("indexing.shared_index", ConfigValueKind::String),
+    ("indexing.metrics_json", ConfigValueKind::String),
+    ("server.tokens", ConfigValueKind::Array),
+    ("grammars", ConfigValueKind::Table),
+    ("repositories", ConfigValueKind::Table),
+    ("semantic_search.calibrated", ConfigValueKind::Table),
+];
+
+/// Problem found in a config file while loading it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigWarning {
+    /// Old key was moved to its new name
+    Renamed {
+        from: String,
+        to: String,
+        note: &'static str,
+    },
+    /// Old and new key were both set; the old value was dropped
+    Shadowed { from: String, to: String },
+    /// Key no longer has any effect and was dropped
+    Removed { key: String, note: &'static str },
+    /// Key is not part of the registry
+    Unknown { key: String },
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Renamed { from, to, note } => {
+                write!(f, "config key '{from}' is deprecated, use '{to}'")?;
+                if !note.is_empty() {
+                    write!(f, " ({note})")?;
+                }
+                Ok(())
+            }
+            Self::Shadowed { from, to } => {
+                write!(
+                    f,
+                    "config key '{from}' is deprecated and ignored because '{to}' is set"
+                )
+            }
+            Self::Removed { key, note } => {
+                write!(f, "config key '{key}' was removed and is ignored ({note})")
+            }
+            Self::Unknown { key } => write!(f, "unknown config key '{key}' is ignored"),
+        }
+    }
+}
+
+/// All keys understood by `Settings`, in file order
+pub fn config_keys() -> Vec<ConfigKey> {
+    let mut keys = Vec::new();
+    if let Ok(toml::Value::Table(defaults)) = toml::Value::try_from(Settings::default()) {
+        collect_keys("", &defaults, &mut keys);
+    }
+    for (path, kind) in OPTIONAL_KEYS {
+        keys.push(ConfigKey {
+            path: path.to_string(),
+            kind: *kind,
+            default: None,
+        });
+    }
+    keys
+}
+
+fn collect_keys(prefix: &str, table: &toml::Table, keys: &mut Vec<ConfigKey>) {
+    for (name, value) in table {
+        let path = join_key(prefix, name);
+        let kind = ConfigValueKind::of(value);
+        match value {
+            toml::Value::Table(inner) => {
+                let open = OPEN_TABLES.contains(&path.as_str());
+                keys.push(ConfigKey {
+                    path: path.clone(),
+                    kind,
+                    default: None,
+                });
+                if !open {
+                    collect_keys(&path, inner, keys);
+                }
+            }
+            _ => keys.push(ConfigKey {
+                path,
+                kind,
+                default: Some(value.clone()),
+            }),
+        }
+    }
+}
+
+fn join_key(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
+
+/// Move deprecated keys to their replacements, drop removed keys, and
+/// report keys the registry does not know.
+///
+/// A renamed key only fills its replacement when that is unset; if both are
+/// set the new key wins.
+pub fn migrate_config(table: &mut toml::Table) -> Vec<ConfigWarning> {
+    let mut warnings = Vec::new();
+
+    for deprecated in DEPRECATED_KEYS {
+        let Some(old) = take_key(table, deprecated.path) else {
+            continue;
+        };
+        let from = deprecated.path.to_string();
+        let Some(to) = deprecated.replacement else {
+            warnings.push(ConfigWarning::Removed {
+                key: from,
+                note: deprecated.note,
+            });
+            continue;
+        };
+
+        let shadowed = match get_key_mut(table, to) {
+            None => {
+                insert_key(table, to, old);
+                false
+            }
+            // Merge a renamed table entry by entry
+            Some(toml::Value::Table(new)) => match old {
+                toml::Value::Table(old) => {
+                    let mut shadowed = false;
+                    for (name, value) in old {
+                        if new.contains_key(&name) {
+                            shadowed = true;
+                        } else {
+                            new.insert(name, value);
+                        }
+                    }
+                    shadowed
+                }
+                _ => true,
+            },
+            Some(_) => true,
+        };
+
+        warnings.push(if shadowed {
+            ConfigWarning::Shadowed {
+                from,
+                to: to.to_string(),
+            }
+        } else {
+            ConfigWarning::Renamed {
+                from,
+                to: to.to_string(),
+                note: deprecated.note,
+            }
+        });
+    }
+
+    let known: std::collections::HashSet<String> =
+        config_keys().into_iter().map(|key| key.path).collect();
+    find_unknown_keys("", table, &known, &mut warnings);
+
+    warnings
+}
+
+fn find_unknown_keys(
+    prefix: &str,
+    table: &toml::Table,
+    known: &std::collections::HashSet<String>,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    for (name, value) in table {
+        let path = join_key(prefix, name);
+        if !known.contains(&path) {
+            warnings.push(ConfigWarning::Unknown { key: path });
+            continue;
+        }
+        match value {
+            toml::Value::Table(inner) if !OPEN_TABLES.contains(&path.as_str()) => {
+                find_unknown_keys(&path, inner, known, warnings);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn get_key_mut<'a>(table: &'a mut toml::Table, path: &str) -> Option<&'a mut toml::Value> {
+    let (parent, name) = match path.rsplit_once('.') {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, path),
+    };
+    let parent = match parent {
+        Some(parent) => get_key_mut(table, parent)?.as_table_mut()?,
+        None => table,
+    };
+    parent.get_mut(name)
+}
+
+fn take_key(table: &mut toml::Table, path: &str) -> Option<toml::Value> {
+    match path.rsplit_once('.') {
+        Some((parent, name)) => get_key_mut(table, parent)?.as_table_mut()?.remove(name),
+        None => table.remove(path),
+    }
+}
+
+fn insert_key(table: &mut toml::Table, path: &str, value: toml::Value) {
+    let mut current = table;
+    let mut parts = path.split('.').peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            current.insert(part.to_string(), value);
+            return;
+        }
+        let entry = current
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        let Some(next) = entry.as_table_mut() else {
+            return;
+        };
+        current = next;
     }
 }
 
@@ -1261,6 +2851,11 @@ enabled = true
         // Default values should still be present
         assert_eq!(settings.version, 1);
         assert_eq!(settings.mcp.max_context_size, 100_000);
+        assert_eq!(settings.mcp.max_edges_per_symbol, 500);
+        assert_eq!(settings.mcp.max_impact_results, 2000);
+        assert_eq!(settings.mcp.max_concurrent_calls, 4);
+        assert!(settings.mcp.tool_concurrency.is_empty());
+        assert_eq!(settings.server.shutdown_timeout, 10);
         // Default ignore patterns should be present
         assert!(!settings.indexing.ignore_patterns.is_empty());
     }
@@ -1544,6 +3139,48 @@ enabled = true
         assert_eq!(canonical_returned, canonical_test);
     }
 
+    #[test]
+    fn test_repositories_resolve_against_workspace_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().join("web");
+        let billing = temp_dir.path().join("billing");
+        fs::create_dir_all(workspace.join("vendor/billing")).unwrap();
+        fs::create_dir(&billing).unwrap();
+
+        let toml_content = r#"
+[repositories.billing]
+path = "../billing"
+
+[repositories.vendored]
+path = "vendor/billing"
+"#;
+        let mut settings: Settings = toml::from_str(toml_content).unwrap();
+        settings.workspace_root = Some(workspace.clone());
+        settings.add_indexed_path(workspace.clone()).unwrap();
+
+        // The vendored repository is already covered by the workspace
+        let canonical_billing = billing.canonicalize().unwrap();
+        let canonical_workspace = workspace.canonicalize().unwrap();
+        assert_eq!(
+            settings.get_indexed_paths(),
+            vec![canonical_workspace.clone(), canonical_billing.clone()]
+        );
+
+        assert_eq!(
+            settings
+                .repository_for(&canonical_billing.join("src/lib.rs"))
+                .as_deref(),
+            Some("billing")
+        );
+        assert_eq!(
+            settings
+                .repository_for(Path::new("vendor/billing/api.rs"))
+                .as_deref(),
+            Some("vendored")
+        );
+        assert_eq!(settings.repository_for(Path::new("src/main.rs")), None);
+    }
+
     #[test]
     fn test_indexed_paths_from_toml() {
         let temp_dir = TempDir::new().unwrap();
@@ -1650,6 +3287,25 @@ max_chunk_chars = 2500
         assert_eq!(external.max_chunk_chars, Some(2500));
     }
 
+    #[test]
+    fn test_compression_levels_default_to_zstd_and_are_range_checked() {
+        let settings = Settings::default();
+        assert_eq!(settings.storage.compression.code_level.zstd(), Some(3));
+        assert_eq!(settings.storage.compression.documents_level.zstd(), Some(3));
+
+        let compression: CompressionConfig = toml::from_str("code_level = 19").unwrap();
+        assert_eq!(compression.code_level.zstd(), Some(19));
+        assert_eq!(compression.documents_level, CompressionLevel::default());
+        let compression: CompressionConfig = toml::from_str("code_level = 0").unwrap();
+        assert_eq!(compression.code_level, CompressionLevel::LZ4);
+
+        for level in ["-1", "23"] {
+            let toml = format!("documents_level = {level}");
+            let error = toml::from_str::<CompressionConfig>(&toml).unwrap_err();
+            assert!(error.to_string().contains("out of range"), "{error}");
+        }
+    }
+
     #[test]
     fn test_documents_config_defaults() {
         // When no [documents] section exists, defaults should apply
@@ -1661,4 +3317,166 @@ max_chunk_chars = 2500
         assert_eq!(settings.documents.defaults.overlap_chars, 100);
         assert!(settings.documents.collections.is_empty());
     }
+
+    #[test]
+    fn test_config_keys_registry() {
+        let keys = config_keys();
+        let find = |path: &str| keys.iter().find(|key| key.path == path);
+
+        let parallelism = find("indexing.parallelism").expect("registered key");
+        assert_eq!(parallelism.kind, ConfigValueKind::Integer);
+        assert!(parallelism.default.is_some());
+
+        // Open tables are registered, their user-named entries are not
+        assert_eq!(find("languages").unwrap().kind, ConfigValueKind::Table);
+        assert!(find("languages.rust.enabled").is_none());
+
+        // Keys that are unset by default are still known
+        assert!(find("indexing.project_root").is_some());
+    }
+
+    #[test]
+    fn test_migrate_config() {
+        let mut table: toml::Table = toml::from_str(
+            r#"
+[semantic]
+model = "MultilingualE5Small"
+similarity_threshold = 0.7
+
+[semantic_search]
+enabled = true
+
+[indexing]
+threads = 4
+include_tests = false
+
+[performance]
+cache_size_mb = 100
+
+[languages.rust]
+enabled = true
+
+[mcp]
+max_contex_size = 1000
+"#,
+        )
+        .unwrap();
+
+        let warnings = migrate_config(&mut table);
+
+        let semantic = table["semantic_search"].as_table().unwrap();
+        assert_eq!(semantic["model"].as_str(), Some("MultilingualE5Small"));
+        assert_eq!(semantic["enabled"].as_bool(), Some(true));
+        assert_eq!(semantic["threshold"].as_float(), Some(0.7));
+        assert!(!table.contains_key("semantic"));
+        assert!(!table.contains_key("performance"));
+        assert_eq!(table["indexing"]["parallelism"].as_integer(), Some(4));
+
+        assert!(warnings.contains(&ConfigWarning::Renamed {
+            from: "indexing.threads".to_string(),
+            to: "indexing.parallelism".to_string(),
+            note: "",
+        }));
+        assert!(warnings.iter().any(
+            |w| matches!(w, ConfigWarning::Removed { key, .. } if key == "indexing.include_tests")
+        ));
+        assert!(warnings.contains(&ConfigWarning::Unknown {
+            key: "mcp.max_contex_size".to_string()
+        }));
+        // User-named entries in open tables are not reported
+        assert!(
+            !warnings.iter().any(
+                |w| matches!(w, ConfigWarning::Unknown { key } if key.starts_with("languages"))
+            )
+        );
+    }
+
+    #[test]
+    fn test_migrate_config_keeps_optional_keys() {
+        let mut table: toml::Table =
+            toml::from_str("[indexing]\nmetrics_json = \"metrics.json\"\n").unwrap();
+
+        assert!(migrate_config(&mut table).is_empty());
+        assert_eq!(
+            table["indexing"]["metrics_json"].as_str(),
+            Some("metrics.json")
+        );
+    }
+
+    #[test]
+    fn test_migrate_config_new_key_wins() {
+        let mut table: toml::Table =
+            toml::from_str("[indexing]\nthreads = 4\nparallelism = 8\n").unwrap();
+
+        let warnings = migrate_config(&mut table);
+
+        assert_eq!(table["indexing"]["parallelism"].as_integer(), Some(8));
+        assert_eq!(
+            warnings,
+            vec![ConfigWarning::Shadowed {
+                from: "indexing.threads".to_string(),
+                to: "indexing.parallelism".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_calibrated_thresholds_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("settings.toml");
+        let mut settings = Settings::default();
+        settings.semantic_search.calibrated.insert(
+            "ollama:nomic-embed-text".to_string(),
+            ModelThresholds {
+                search: 0.5,
+                similar: 0.75,
+            },
+        );
+        settings.save(&config_path).unwrap();
+
+        let loaded = Settings::load_from(&config_path).unwrap();
+        assert!(
+            loaded.config_warnings.is_empty(),
+            "{:?}",
+            loaded.config_warnings
+        );
+        assert_eq!(
+            loaded.semantic_search.calibrated,
+            settings.semantic_search.calibrated
+        );
+
+        // Nothing to migrate, so the calibration stays in the file
+        assert!(Settings::migrate_file(&config_path).unwrap().is_empty());
+        let reloaded = Settings::load_from(&config_path).unwrap();
+        assert_eq!(
+            reloaded.semantic_search.calibrated,
+            settings.semantic_search.calibrated
+        );
+    }
+
+    #[test]
+    fn test_load_and_migrate_deprecated_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("settings.toml");
+        fs::write(
+            &config_path,
+            "[semantic]\nmodel = \"MultilingualE5Small\"\n\n[indexing]\nthreads = 3\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load_from(&config_path).unwrap();
+        assert_eq!(settings.semantic_search.model, "MultilingualE5Small");
+        assert_eq!(settings.indexing.parallelism, 3);
+        assert_eq!(settings.config_warnings.len(), 2);
+
+        let warnings = Settings::migrate_file(&config_path).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(temp_dir.path().join("settings.toml.bak").exists());
+
+        let migrated = Settings::load_from(&config_path).unwrap();
+        assert!(migrated.config_warnings.is_empty());
+        assert_eq!(migrated.semantic_search.model, "MultilingualE5Small");
+        assert_eq!(migrated.indexing.parallelism, 3);
+        assert!(migrated.languages.contains_key("rust"));
+    }
 }
@@ -0,0 +1,500 @@
+//! Links between AI conversations and code
+//!
+//! When the context watcher processes a session export, every message is
+//! scanned for file paths and symbol names. Each mention becomes a
+//! [`MessageLink`] appended to a JSONL store, so later queries can list the
+//! past discussions about a symbol or file:
+//!
+//! ```text
+//! codanna conversations ContextWatcher::run
+//! codanna mcp find_discussions ContextWatcher::run
+//! ```
+//!
+//! Mentions are recognized syntactically: code spans (`` `parse_file` ``),
+//! qualified names (`Foo::bar`), paths with a known source extension
+//! (`src/main.rs:42`) and files opened by tool calls. Links store names, not
+//! symbol ids, so they survive re-indexing.
+
+use crate::parsing::get_registry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// File name of the link store in the context storage directory
+pub const LINKS_FILE: &str = "conversation-links.jsonl";
+
+/// Longest code span still treated as a name
+const MAX_MENTION_LEN: usize = 120;
+
+/// Characters of message text kept around a mention
+const EXCERPT_LEN: usize = 200;
+
+/// What a message mentions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MentionKind {
+    File,
+    Symbol,
+}
+
+/// A file path or symbol name found in a message
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mention {
+    pub kind: MentionKind,
+    pub text: String,
+}
+
+/// A message that mentions a file or symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageLink {
+    pub session_id: String,
+    /// Message uuid, or `L<line>` for plain-text exports
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    pub kind: MentionKind,
+    /// The mention as written, e.g. `ContextWatcher::run` or `src/lib.rs`
+    pub mention: String,
+    /// Message text around the mention
+    pub excerpt: String,
+    /// Export file the message came from
+    pub export: String,
+}
+
+impl MessageLink {
+    /// Whether this link is about `query`, a symbol name or file path
+    ///
+    /// Qualified names match on their trailing segments, so `run` and
+    /// `ContextWatcher::run` both match a mention of `ContextWatcher::run`,
+    /// and paths match on their trailing components.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() {
+            return false;
+        }
+        match self.kind {
+            MentionKind::File => {
+                let mention = Path::new(&self.mention);
+                let query = Path::new(query);
+                mention.ends_with(query) || query.ends_with(mention)
+            }
+            MentionKind::Symbol => {
+                let mention = segments(&self.mention);
+                let query = segments(query);
+                mention.ends_with(&query) || query.ends_with(&mention)
+            }
+        }
+    }
+
+    fn key(&self) -> (String, String, String) {
+        (
+            self.session_id.clone(),
+            self.message_id.clone(),
+            self.mention.clone(),
+        )
+    }
+}
+
+/// Split a qualified name on `::`, `.` and `#`
+fn segments(name: &str) -> Vec<&str> {
+    name.split("::")
+        .flat_map(|part| part.split(['.', '#']))
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Find the file paths and symbol names mentioned in `text`
+pub fn extract_mentions(text: &str) -> Vec<Mention> {
+    let mut mentions = Vec::new();
+    let mut seen = HashSet::new();
+    let mut push = |mention: Mention| {
+        if seen.insert(mention.clone()) {
+            mentions.push(mention);
+        }
+    };
+
+    for (i, part) in strip_fences(text).split('`').enumerate() {
+        if i % 2 == 1 {
+            // Inside a code span: the whole span is one name
+            if let Some(mention) = classify(part, true) {
+                push(mention);
+            }
+            continue;
+        }
+        for word in part.split(|c: char| c.is_whitespace() || "()[]{}<>,;\"'".contains(c)) {
+            if let Some(mention) = classify(word, false) {
+                push(mention);
+            }
+        }
+    }
+    mentions
+}
+
+/// Remove fenced code blocks, whose contents are code rather than mentions
+fn strip_fences(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Classify a word or code span. Outside code spans only paths and
+/// qualified names count, since bare words are mostly prose.
+fn classify(raw: &str, code_span: bool) -> Option<Mention> {
+    let word = raw.trim().trim_end_matches([':', '.', '!', '?']);
+    let word = word.strip_suffix("()").unwrap_or(word);
+    if word.is_empty() || word.len() > MAX_MENTION_LEN || word.contains(char::is_whitespace) {
+        return None;
+    }
+
+    if let Some(path) = source_path(word) {
+        return Some(Mention {
+            kind: MentionKind::File,
+            text: path.to_string(),
+        });
+    }
+
+    let is_name = word
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '.' | '#' | '$'))
+        && word
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && !word.ends_with(':');
+    if is_name && (code_span || word.contains("::")) {
+        return Some(Mention {
+            kind: MentionKind::Symbol,
+            text: word.to_string(),
+        });
+    }
+    None
+}
+
+/// `word` without a trailing `:line[:col]`, if it is a path with an
+/// extension some language claims
+fn source_path(word: &str) -> Option<&str> {
+    let path = word.split(':').next().filter(|path| !path.contains("//"))?;
+    let extension = Path::new(path).extension()?.to_str()?;
+    if path.starts_with('.') && !path.contains('/') {
+        return None;
+    }
+    let registry = get_registry().lock().ok()?;
+    registry.get_by_extension(extension).map(|_| path)
+}
+
+/// Message text around the first occurrence of `mention`
+fn excerpt(text: &str, mention: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let Some(at) = collapsed.find(mention) else {
+        return crate::parsing::truncate_for_display(&collapsed, EXCERPT_LEN);
+    };
+
+    let mut start = at.saturating_sub(EXCERPT_LEN / 2);
+    while !collapsed.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (at + mention.len() + EXCERPT_LEN / 2).min(collapsed.len());
+    while !collapsed.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut excerpt = String::new();
+    if start > 0 {
+        excerpt.push_str("...");
+    }
+    excerpt.push_str(&collapsed[start..end]);
+    if end < collapsed.len() {
+        excerpt.push_str("...");
+    }
+    excerpt
+}
+
+/// Text of a session JSONL entry and the paths its tool calls touched
+fn entry_text(entry: &serde_json::Value) -> String {
+    let content = &entry["message"]["content"];
+    if let Some(text) = content.as_str() {
+        return text.to_string();
+    }
+
+    let mut parts = Vec::new();
+    for block in content.as_array().into_iter().flatten() {
+        match block["type"].as_str() {
+            Some("text") => parts.extend(block["text"].as_str().map(str::to_string)),
+            Some("tool_use") => {
+                for key in ["file_path", "path", "notebook_path"] {
+                    parts.extend(block["input"][key].as_str().map(|path| format!("`{path}`")));
+                }
+            }
+            _ => {}
+        }
+    }
+    parts.join("\n")
+}
+
+/// Links for every mention in the session export at `path`
+///
+/// `.jsonl` exports hold one session entry per line; other files are read
+/// as plain text, one message per paragraph.
+pub fn links_from_export(path: &Path) -> io::Result<Vec<MessageLink>> {
+    let export = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_session = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let content = fs::read_to_string(path)?;
+
+    let mut links = Vec::new();
+    let mut add = |text: &str,
+                   session_id: &str,
+                   message_id: String,
+                   timestamp: Option<String>,
+                   role: Option<String>| {
+        for mention in extract_mentions(text) {
+            links.push(MessageLink {
+                session_id: session_id.to_string(),
+                message_id: message_id.clone(),
+                timestamp: timestamp.clone(),
+                role: role.clone(),
+                kind: mention.kind,
+                excerpt: excerpt(text, &mention.text),
+                mention: mention.text,
+                export: export.clone(),
+            });
+        }
+    };
+
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        for (line_number, line) in content.lines().enumerate() {
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+                continue;
+            };
+            let text = entry_text(&entry);
+            if text.is_empty() {
+                continue;
+            }
+            let session_id = entry["sessionId"].as_str().unwrap_or(&file_session);
+            let message_id = entry["uuid"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("L{}", line_number + 1));
+            let timestamp = entry["timestamp"].as_str().map(str::to_string);
+            let role = entry["message"]["role"]
+                .as_str()
+                .or_else(|| entry["type"].as_str())
+                .map(str::to_string);
+            add(&text, session_id, message_id, timestamp, role);
+        }
+    } else {
+        let mut paragraph = String::new();
+        let mut first_line = 1;
+        for (i, line) in content.lines().chain(std::iter::once("")).enumerate() {
+            if line.trim().is_empty() {
+                if !paragraph.is_empty() {
+                    add(
+                        &paragraph,
+                        &file_session,
+                        format!("L{first_line}"),
+                        None,
+                        None,
+                    );
+                    paragraph.clear();
+                }
+                first_line = i + 2;
+            } else {
+                paragraph.push_str(line);
+                paragraph.push('\n');
+            }
+        }
+    }
+
+    Ok(links)
+}
+
+/// Append-only JSONL store of message links
+#[derive(Debug, Clone)]
+pub struct LinkStore {
+    path: PathBuf,
+}
+
+impl LinkStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store the context watcher writes to by default
+    pub fn default_location() -> Self {
+        Self::new(crate::watcher::ContextConfig::default().conversation_links_file)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All stored links. A missing store has none; unreadable lines are skipped.
+    pub fn load(&self) -> io::Result<Vec<MessageLink>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut links = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Ok(link) = serde_json::from_str(&line?) {
+                links.push(link);
+            }
+        }
+        Ok(links)
+    }
+
+    /// Append links not stored yet and return how many were new
+    ///
+    /// Delta exports repeat the tail of the previous export, so the same
+    /// message can be processed twice.
+    pub fn append(&self, links: &[MessageLink]) -> io::Result<usize> {
+        let mut known: HashSet<_> = self.load()?.iter().map(MessageLink::key).collect();
+        let fresh: Vec<&MessageLink> = links
+            .iter()
+            .filter(|link| known.insert(link.key()))
+            .collect();
+        if fresh.is_empty() {
+            return Ok(0);
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = String::new();
+        for link in &fresh {
+            out.push_str(&serde_json::to_string(link).map_err(io::Error::other)?);
+            out.push('\n');
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(out.as_bytes())?;
+        Ok(fresh.len())
+    }
+
+    /// Links about `query`, newest first
+    pub fn find(&self, query: &str, limit: usize) -> io::Result<Vec<MessageLink>> {
+        let mut links: Vec<MessageLink> = self
+            .load()?
+            .into_iter()
+            .filter(|link| link.matches(query))
+            .collect();
+        links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        links.truncate(limit);
+        Ok(links)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(mentions: &[Mention]) -> Vec<(&str, MentionKind)> {
+        mentions.iter().map(|m| (m.text.as_str(), m.kind)).collect()
+    }
+
+    #[test]
+    fn test_extract_mentions() {
+        let text = "The bug is in `ContextWatcher::run()`, see src/watcher/context_watcher.rs:1291.\n\
+            Also Settings::load is involved, and `parse_file`.\n\
+            ```rust\nfn ignored() {}\n```\n\
+            Ordinary words and https://example.com/page.rs are not mentions.";
+        assert_eq!(
+            texts(&extract_mentions(text)),
+            vec![
+                ("ContextWatcher::run", MentionKind::Symbol),
+                ("src/watcher/context_watcher.rs", MentionKind::File),
+                ("Settings::load", MentionKind::Symbol),
+                ("parse_file", MentionKind::Symbol),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_matching() {
+        let link = |kind, mention: &str| MessageLink {
+            session_id: "s".into(),
+            message_id: "m".into(),
+            timestamp: None,
+            role: None,
+            kind,
+            mention: mention.into(),
+            excerpt: String::new(),
+            export: String::new(),
+        };
+
+        let symbol = link(MentionKind::Symbol, "ContextWatcher::run");
+        assert!(symbol.matches("run"));
+        assert!(symbol.matches("ContextWatcher::run"));
+        assert!(symbol.matches("watcher::ContextWatcher::run"));
+        assert!(!symbol.matches("Watcher::run"));
+
+        let file = link(MentionKind::File, "src/watcher/context_watcher.rs");
+        assert!(file.matches("context_watcher.rs"));
+        assert!(!file.matches("watcher.rs"));
+    }
+
+    #[test]
+    fn test_links_from_jsonl_export_are_deduplicated() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let export = dir.path().join("session.jsonl");
+        let entries = [
+            serde_json::json!({
+                "type": "user",
+                "uuid": "u1",
+                "sessionId": "abc",
+                "timestamp": "2025-01-01T10:00:00Z",
+                "message": {"role": "user", "content": "Why does `IndexFacade::search` return nothing?"}
+            }),
+            serde_json::json!({
+                "type": "assistant",
+                "uuid": "u2",
+                "sessionId": "abc",
+                "timestamp": "2025-01-01T10:00:05Z",
+                "message": {"role": "assistant", "content": [
+                    {"type": "text", "text": "Let me read the facade."},
+                    {"type": "tool_use", "name": "Read", "input": {"file_path": "/repo/src/indexing/facade.rs"}}
+                ]}
+            }),
+        ];
+        let content: Vec<String> = entries.iter().map(|e| e.to_string()).collect();
+        fs::write(&export, content.join("\n")).unwrap();
+
+        let links = links_from_export(&export).unwrap();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].mention, "IndexFacade::search");
+        assert_eq!(links[0].role.as_deref(), Some("user"));
+        assert_eq!(links[1].kind, MentionKind::File);
+        assert_eq!(links[1].message_id, "u2");
+
+        let store = LinkStore::new(dir.path().join(LINKS_FILE));
+        assert_eq!(store.append(&links).unwrap(), 2);
+        assert_eq!(store.append(&links).unwrap(), 0);
+
+        let found = store.find("search", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].session_id, "abc");
+        assert!(found[0].excerpt.contains("IndexFacade::search"));
+    }
+}
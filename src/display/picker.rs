@@ -0,0 +1,209 @@
+//! Interactive fuzzy picker for choosing one result in the terminal.
+//!
+//! The list is drawn on stderr and keys are read from the terminal, so
+//! stdout stays free for the selection: `vim $(codanna retrieve search --pick foo)`.
+
+use crate::display::theme::{THEME, Theme};
+use console::{Key, Term};
+use std::io;
+
+/// Most rows shown at once
+const MAX_VISIBLE: usize = 12;
+
+/// One selectable entry
+#[derive(Debug, Clone)]
+pub struct PickItem {
+    /// Text matched against the filter and shown first
+    pub label: String,
+    /// Secondary text shown dimmed after the label, also matched
+    pub detail: String,
+}
+
+impl PickItem {
+    pub fn new(label: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Score `text` against a fuzzy `query`; `None` if it does not match.
+///
+/// Every query character must appear in order (case-insensitive).
+/// Consecutive matches and matches at word starts score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0i64;
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous: Option<char> = None;
+    let mut streak = 0i64;
+
+    for c in text.chars() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(std::iter::once(wanted));
+        if matched {
+            query_chars.next();
+            streak += 1;
+            score += 1 + streak;
+            let word_start = previous
+                .is_none_or(|p| !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()));
+            if word_start {
+                score += 3;
+            }
+        } else {
+            streak = 0;
+        }
+        previous = Some(c);
+    }
+
+    query_chars.peek().is_none().then_some(score)
+}
+
+/// Indices of `items` matching `query`, best match first.
+pub fn filter(items: &[PickItem], query: &str) -> Vec<usize> {
+    let mut matches: Vec<(i64, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let label = fuzzy_score(query, &item.label);
+            let detail = fuzzy_score(query, &item.detail).map(|score| score / 2);
+            label.max(detail).map(|score| (score, index))
+        })
+        .collect();
+    // Stable: equal scores keep the original (search rank) order
+    matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    matches.into_iter().map(|(_, index)| index).collect()
+}
+
+/// Let the user choose one of `items`.
+///
+/// Typing narrows the list, arrow keys (or Ctrl-N/Ctrl-P) move, Enter
+/// selects and Esc cancels. Returns the index of the chosen item, or
+/// `None` when cancelled.
+pub fn pick(items: &[PickItem], prompt: &str) -> io::Result<Option<usize>> {
+    let term = Term::stderr();
+    if !term.is_term() {
+        return Err(io::Error::other("--pick needs an interactive terminal"));
+    }
+
+    let visible = MAX_VISIBLE.min((term.size().0 as usize).saturating_sub(2).max(1));
+    let mut query = String::new();
+    let mut selected = 0usize;
+    let mut drawn = 0usize;
+
+    term.hide_cursor()?;
+    let result = loop {
+        let matches = filter(items, &query);
+        selected = selected.min(matches.len().saturating_sub(1));
+
+        term.clear_last_lines(drawn)?;
+        drawn = render(&term, items, &matches, &query, prompt, selected, visible)?;
+
+        match term.read_key()? {
+            Key::Enter => break matches.get(selected).copied(),
+            Key::Escape | Key::CtrlC => break None,
+            Key::ArrowUp | Key::Char('\u{10}') => selected = selected.saturating_sub(1),
+            Key::ArrowDown | Key::Char('\u{e}') if selected + 1 < matches.len() => selected += 1,
+            Key::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            Key::Char(c) if !c.is_control() => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => {}
+        }
+    };
+    term.clear_last_lines(drawn)?;
+    term.show_cursor()?;
+
+    Ok(result)
+}
+
+fn render(
+    term: &Term,
+    items: &[PickItem],
+    matches: &[usize],
+    query: &str,
+    prompt: &str,
+    selected: usize,
+    visible: usize,
+) -> io::Result<usize> {
+    let plain = Theme::should_disable_colors();
+    let width = term.size().1 as usize;
+
+    // Keep the selection on screen
+    let first = selected.saturating_sub(visible - 1);
+    let mut lines = 0;
+    for (row, &index) in matches.iter().enumerate().skip(first).take(visible) {
+        let item = &items[index];
+        let marker = if row == selected { ">" } else { " " };
+        let line = console::truncate_str(
+            &format!("{marker} {}  {}", item.label, item.detail),
+            width.saturating_sub(1),
+            "…",
+        )
+        .into_owned();
+        let line = if plain {
+            line
+        } else if row == selected {
+            THEME.emphasis.apply_to(line).to_string()
+        } else {
+            THEME.dim.apply_to(line).to_string()
+        };
+        term.write_line(&line)?;
+        lines += 1;
+    }
+
+    let counter = format!("{}/{}", matches.len(), items.len());
+    let status = if plain {
+        format!("{prompt} {query}  [{counter}]")
+    } else {
+        format!(
+            "{} {query}  {}",
+            THEME.header.apply_to(prompt),
+            THEME.dim.apply_to(format!("[{counter}]"))
+        )
+    };
+    term.write_line(&status)?;
+
+    Ok(lines + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("prs", "parse_file").is_some());
+        assert!(fuzzy_score("PF", "parse_file").is_some());
+        assert!(fuzzy_score("fp", "parse_file").is_none());
+
+        // Word starts and consecutive runs beat scattered matches
+        let word_start = fuzzy_score("pf", "parse_file").unwrap();
+        let scattered = fuzzy_score("pf", "upfront").unwrap();
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_filter_orders_by_score() {
+        let items = vec![
+            PickItem::new("unparse_field", "src/a.rs:1:1"),
+            PickItem::new("parse_file", "src/b.rs:2:1"),
+            PickItem::new("render", "src/c.rs:3:1"),
+        ];
+
+        assert_eq!(filter(&items, ""), vec![0, 1, 2]);
+        assert_eq!(filter(&items, "parse_file"), vec![1]);
+        // Details are matched too
+        assert_eq!(filter(&items, "b.rs:2"), vec![1]);
+    }
+}
@@ -18,4 +18,4 @@ pub use config::{
 };
 pub use schema::DocumentSchema;
 pub use store::{CollectionStats, DocumentStore, IndexProgress, SearchQuery, SearchResult};
-pub use types::{ChunkId, CollectionId, DocumentChunk, FileState};
+pub use types::{ChunkId, CollectionId, DocumentChunk, DocumentKind, FileState};
@@ -29,16 +29,16 @@ use tantivy::directory::MmapDirectory;
 use tantivy::directory::error::OpenDirectoryError;
 use tantivy::query::{BooleanQuery, Occur, Query, TermQuery};
 use tantivy::schema::Value;
-use tantivy::{
-    Index, IndexReader, IndexSettings, IndexWriter, ReloadPolicy, TantivyDocument as Document, Term,
-};
+use tantivy::{Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument as Document, Term};
 use thiserror::Error;
 
 use super::chunker::{Chunker, HybridChunker, RawChunk};
 use super::config::{ChunkingConfig, CollectionConfig};
 use super::schema::DocumentSchema;
-use super::types::{ChunkId, CollectionId, FileState};
+use super::types::{ChunkId, CollectionId, DocumentKind, FileState};
+use crate::config::CompressionLevel;
 use crate::indexing::file_info::{calculate_hash, get_utc_timestamp};
+use crate::storage::tantivy::docstore_settings;
 use crate::vector::{
     ClusterId, EmbeddingGenerator, MmapVectorStorage, SegmentOrdinal, VectorDimension, VectorId,
     VectorStorageError, cosine_similarity, kmeans_clustering,
@@ -279,6 +279,8 @@ pub struct SearchResult {
     pub collection: String,
     /// Source file path.
     pub source_path: PathBuf,
+    /// Whether the source is a README, an ADR or other documentation.
+    pub kind: DocumentKind,
     /// Heading hierarchy for context.
     pub heading_context: Vec<String>,
     /// Content preview (first ~200 chars).
@@ -360,6 +362,18 @@ impl DocumentStore {
     /// * `base_path` - Directory for all storage files (tantivy index, vectors, state)
     /// * `dimension` - Vector dimension for embeddings
     pub fn new(base_path: impl AsRef<Path>, dimension: VectorDimension) -> StoreResult<Self> {
+        Self::new_compressed(base_path, dimension, CompressionLevel::default())
+    }
+
+    /// Create or open a document store, compressing chunk bodies of a new
+    /// store at `level`.
+    ///
+    /// An existing store keeps the compression it was created with.
+    pub fn new_compressed(
+        base_path: impl AsRef<Path>,
+        dimension: VectorDimension,
+        level: CompressionLevel,
+    ) -> StoreResult<Self> {
         let base_path = base_path.as_ref().to_path_buf();
         std::fs::create_dir_all(&base_path)?;
 
@@ -373,7 +387,7 @@ impl DocumentStore {
             Index::open_in_dir(&index_path)?
         } else {
             let dir = MmapDirectory::open(&index_path)?;
-            Index::create(dir, tantivy_schema, IndexSettings::default())?
+            Index::create(dir, tantivy_schema, docstore_settings(level))?
         };
 
         let reader = index
@@ -1281,10 +1295,12 @@ impl DocumentStore {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(0) as usize;
 
+                let kind = DocumentKind::classify(&source_path, &heading_context);
                 results.push(SearchResult {
                     chunk_id,
                     collection,
                     source_path,
+                    kind,
                     heading_context,
                     content_preview,
                     byte_range: (byte_start, byte_end),
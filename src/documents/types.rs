@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Unique identifier for a document chunk.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -129,6 +129,63 @@ impl DocumentChunk {
     }
 }
 
+/// What a source document is, told from its path and headings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    /// A README of the project or of a directory.
+    Readme,
+    /// An architecture decision record.
+    Adr,
+    /// Any other documentation.
+    Guide,
+}
+
+/// Directory names architecture decision records are kept in.
+const ADR_DIRS: &[&str] = &["adr", "adrs", "decisions", "architecture-decisions"];
+
+impl DocumentKind {
+    /// Classify the document at `path`, whose chunk sits under `heading_context`.
+    ///
+    /// An ADR lives in a directory such as `docs/adr/`, has a file name
+    /// starting with `adr-`, or is titled like "ADR 12: Use tantivy".
+    pub fn classify(path: &Path, heading_context: &[String]) -> Self {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if stem == "readme" {
+            return Self::Readme;
+        }
+        let in_adr_dir = path.parent().is_some_and(|parent| {
+            parent.components().any(|component| {
+                let name = component.as_os_str().to_string_lossy().to_lowercase();
+                ADR_DIRS.contains(&name.as_str())
+            })
+        });
+        let adr_title = heading_context.first().is_some_and(|title| {
+            let title = title.to_lowercase();
+            title.strip_prefix("adr").is_some_and(|rest| {
+                rest.starts_with(|c: char| c.is_ascii_digit() || matches!(c, ' ' | '-' | ':'))
+            })
+        });
+        if in_adr_dir || stem.starts_with("adr-") || stem.starts_with("adr_") || adr_title {
+            return Self::Adr;
+        }
+        Self::Guide
+    }
+}
+
+impl std::fmt::Display for DocumentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Readme => "readme",
+            Self::Adr => "adr",
+            Self::Guide => "guide",
+        })
+    }
+}
+
 /// State of a source file for change detection.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileState {
@@ -226,4 +283,29 @@ mod tests {
         assert!(!state.has_changed("abc123"));
         assert!(state.has_changed("def456"));
     }
+
+    #[test]
+    fn test_document_kind_classification() {
+        let classify =
+            |path: &str, title: &str| DocumentKind::classify(Path::new(path), &[title.to_string()]);
+        assert_eq!(classify("README.md", "Codanna"), DocumentKind::Readme);
+        assert_eq!(classify("src/parsing/readme.txt", ""), DocumentKind::Readme);
+        assert_eq!(
+            classify("docs/adr/0007-mmap-vectors.md", "Memory-mapped vectors"),
+            DocumentKind::Adr
+        );
+        assert_eq!(classify("docs/ADR-012.md", ""), DocumentKind::Adr);
+        assert_eq!(
+            classify("notes/storage.md", "ADR 3: Use tantivy"),
+            DocumentKind::Adr
+        );
+        assert_eq!(
+            classify("docs/adrenaline.md", "Adrenaline"),
+            DocumentKind::Guide
+        );
+        assert_eq!(
+            classify("docs/user-guide/search.md", "Search"),
+            DocumentKind::Guide
+        );
+    }
 }
@@ -116,6 +116,12 @@ impl IndexError {
     pub fn lock_error() -> Self {
         Self::LockError("mutex poisoned".to_string())
     }
+
+    /// Whether indexing stopped because it was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        use crate::indexing::pipeline::PipelineError;
+        matches!(self, Self::Pipeline(e) if matches!(**e, PipelineError::Cancelled))
+    }
 }
 
 impl From<std::io::Error> for IndexError {
@@ -0,0 +1,415 @@
+//! Source files inside archives
+//!
+//! Dependencies often ship their sources packed: JARs with sources, Python
+//! wheels, tarballs of vendored code. With `indexing.index_archives` on, the
+//! discover stage lists the source files inside each archive it walks past
+//! and indexes them under virtual paths of the form `<archive>!/<entry>`, the
+//! convention of JAR URLs:
+//!
+//! ```text
+//! libs/guava-sources.jar!/com/google/common/base/Strings.java
+//! ```
+//!
+//! Virtual paths are read-only. Their content is read through
+//! [`read_source`], or an [`ArchiveCache`] when many entries are read in a
+//! row, and they count as modified whenever the archive is.
+
+use crate::security::safe_open;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Entries larger than this are not indexed
+const MAX_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Archive formats that can be indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// `.jar`, `.war`, `.aar`, `.whl` and `.zip`
+    Zip,
+    /// `.tar`
+    Tar,
+    /// `.tar.gz`, `.tgz` and `.crate`
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Format of the archive at `path`, judged by its name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.file_name()?.to_str()?)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        let ends_with = |suffixes: &[&str]| suffixes.iter().any(|suffix| name.ends_with(suffix));
+        if ends_with(&[".jar", ".war", ".aar", ".whl", ".zip"]) {
+            Some(ArchiveFormat::Zip)
+        } else if ends_with(&[".tar.gz", ".tgz", ".crate"]) {
+            Some(ArchiveFormat::TarGz)
+        } else if ends_with(&[".tar"]) {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Virtual path of `entry` inside `archive`
+pub fn virtual_path(archive: &Path, entry: &str) -> PathBuf {
+    let mut path = archive.as_os_str().to_owned();
+    path.push("!");
+    let mut path = PathBuf::from(path);
+    path.extend(entry.split('/'));
+    path
+}
+
+/// Split a virtual path into the archive and the entry inside it
+pub fn split(path: &Path) -> Option<(PathBuf, String)> {
+    let mut archive = PathBuf::new();
+    let mut components = path.components();
+
+    while let Some(component) = components.next() {
+        let name = component
+            .as_os_str()
+            .to_str()
+            .and_then(|name| name.strip_suffix('!'))
+            .filter(|name| ArchiveFormat::from_name(name).is_some());
+        let Some(name) = name else {
+            archive.push(component);
+            continue;
+        };
+
+        archive.push(name);
+        let entry: Vec<_> = components
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+        return (!entry.is_empty()).then(|| (archive, entry.join("/")));
+    }
+
+    None
+}
+
+/// Archive a virtual path points into
+pub fn archive_of(path: &Path) -> Option<PathBuf> {
+    split(path).map(|(archive, _)| archive)
+}
+
+/// Whether `path` points into an archive
+pub fn is_virtual(path: &Path) -> bool {
+    split(path).is_some()
+}
+
+/// Names of the files in `archive` that `keep` accepts
+///
+/// Entries with absolute paths or `..` components are left out, as are
+/// entries above the size limit.
+pub fn list_entries(archive: &Path, keep: impl Fn(&Path) -> bool) -> io::Result<Vec<String>> {
+    let format = ArchiveFormat::from_path(archive).ok_or_else(|| unsupported(archive))?;
+    let file = safe_open(archive).map_err(io::Error::from)?;
+    let mut entries = Vec::new();
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+            for i in 0..zip.len() {
+                let entry = zip.by_index_raw(i).map_err(io::Error::other)?;
+                if !entry.is_file() || entry.size() > MAX_ENTRY_BYTES {
+                    continue;
+                }
+                if let Some(name) = entry.enclosed_name().and_then(|name| entry_name(&name)) {
+                    if keep(Path::new(&name)) {
+                        entries.push(name);
+                    }
+                }
+            }
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let mut tar = tar_reader(format, file);
+            for entry in tar.entries()? {
+                let entry = entry?;
+                if !entry.header().entry_type().is_file() || entry.size() > MAX_ENTRY_BYTES {
+                    continue;
+                }
+                if let Some(name) = entry_name(&entry.path()?) {
+                    if keep(Path::new(&name)) {
+                        entries.push(name);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Text of `entry` inside `archive`
+pub fn read_entry(archive: &Path, entry: &str) -> io::Result<String> {
+    let format = ArchiveFormat::from_path(archive).ok_or_else(|| unsupported(archive))?;
+    let file = safe_open(archive).map_err(io::Error::from)?;
+    let mut content = String::new();
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+            content = read_zip_entry(&mut zip, archive, entry)?;
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let mut tar = tar_reader(format, file);
+            let mut found = false;
+            for file in tar.entries()? {
+                let file = file?;
+                if entry_name(&file.path()?).as_deref() == Some(entry) {
+                    file.take(MAX_ENTRY_BYTES).read_to_string(&mut content)?;
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                return Err(not_found(archive, entry));
+            }
+        }
+    }
+
+    Ok(content)
+}
+
+/// Archives kept open while their entries are read
+///
+/// [`read_entry`] opens the archive on every call, and finding an entry in a
+/// tar means reading the archive up to it, so reading every entry that way
+/// is quadratic. The cache opens each archive once: a zip stays open, and a
+/// tar is unpacked in a single pass, each entry kept until it is read.
+#[derive(Default)]
+pub struct ArchiveCache {
+    archives: Mutex<HashMap<PathBuf, Arc<Mutex<Option<OpenArchive>>>>>,
+}
+
+enum OpenArchive {
+    Zip(zip::ZipArchive<std::fs::File>),
+    /// Entries not read yet
+    Tar(HashMap<String, Vec<u8>>),
+}
+
+impl ArchiveCache {
+    /// Text of `entry` inside `archive`, opening the archive on first use
+    pub fn read_entry(&self, archive: &Path, entry: &str) -> io::Result<String> {
+        // Opened outside the map lock so other archives are not held up
+        let slot = self
+            .archives
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(archive.to_path_buf())
+            .or_default()
+            .clone();
+        let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+        let open = match slot.take() {
+            Some(open) => open,
+            None => OpenArchive::open(archive)?,
+        };
+
+        match slot.insert(open) {
+            OpenArchive::Zip(zip) => read_zip_entry(zip, archive, entry),
+            OpenArchive::Tar(entries) => {
+                let bytes = entries
+                    .remove(entry)
+                    .ok_or_else(|| not_found(archive, entry))?;
+                String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+        }
+    }
+}
+
+impl OpenArchive {
+    fn open(archive: &Path) -> io::Result<Self> {
+        let format = ArchiveFormat::from_path(archive).ok_or_else(|| unsupported(archive))?;
+        let file = safe_open(archive).map_err(io::Error::from)?;
+
+        match format {
+            ArchiveFormat::Zip => Ok(OpenArchive::Zip(
+                zip::ZipArchive::new(file).map_err(io::Error::other)?,
+            )),
+            ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+                let mut tar = tar_reader(format, file);
+                let mut entries = HashMap::new();
+                for file in tar.entries()? {
+                    let file = file?;
+                    if !file.header().entry_type().is_file() || file.size() > MAX_ENTRY_BYTES {
+                        continue;
+                    }
+                    if let Some(name) = entry_name(&file.path()?) {
+                        let mut bytes = Vec::new();
+                        file.take(MAX_ENTRY_BYTES).read_to_end(&mut bytes)?;
+                        entries.insert(name, bytes);
+                    }
+                }
+                Ok(OpenArchive::Tar(entries))
+            }
+        }
+    }
+}
+
+/// Text of a source file, on disk or inside an archive
+pub fn read_source(path: &Path) -> io::Result<String> {
+    match split(path) {
+        Some((archive, entry)) => read_entry(&archive, &entry),
+        None => std::fs::read_to_string(path),
+    }
+}
+
+fn read_zip_entry(
+    zip: &mut zip::ZipArchive<std::fs::File>,
+    archive: &Path,
+    entry: &str,
+) -> io::Result<String> {
+    let file = zip.by_name(entry).map_err(|e| match e {
+        zip::result::ZipError::FileNotFound => not_found(archive, entry),
+        e => io::Error::other(e),
+    })?;
+    let mut content = String::new();
+    file.take(MAX_ENTRY_BYTES).read_to_string(&mut content)?;
+    Ok(content)
+}
+
+fn tar_reader(format: ArchiveFormat, file: std::fs::File) -> tar::Archive<Box<dyn Read>> {
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::TarGz => Box::new(flate2::read::GzDecoder::new(file)),
+        _ => Box::new(file),
+    };
+    tar::Archive::new(reader)
+}
+
+/// `/`-separated name of an entry, or `None` if it could escape the archive
+fn entry_name(path: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+fn unsupported(archive: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{} is not a supported archive", archive.display()),
+    )
+}
+
+fn not_found(archive: &Path, entry: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{entry} not found in {}", archive.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_virtual_paths_round_trip() {
+        let path = virtual_path(
+            Path::new("libs/guava-sources.jar"),
+            "com/google/Strings.java",
+        );
+        assert_eq!(
+            path,
+            PathBuf::from("libs/guava-sources.jar!/com/google/Strings.java")
+        );
+        assert_eq!(
+            split(&path),
+            Some((
+                PathBuf::from("libs/guava-sources.jar"),
+                "com/google/Strings.java".to_string()
+            ))
+        );
+        assert!(!is_virtual(Path::new("src/wow!/main.rs")));
+        assert!(!is_virtual(Path::new("libs/guava-sources.jar")));
+    }
+
+    #[test]
+    fn test_list_and_read_zip_and_tar_entries() {
+        let temp = TempDir::new().unwrap();
+        let is_java = |path: &Path| path.extension().is_some_and(|ext| ext == "java");
+
+        let jar = temp.path().join("dep-sources.jar");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&jar).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("com/dep/Api.java", options).unwrap();
+        zip.write_all(b"class Api {}").unwrap();
+        zip.start_file("META-INF/MANIFEST.MF", options).unwrap();
+        zip.write_all(b"Manifest-Version: 1.0").unwrap();
+        zip.finish().unwrap();
+
+        assert_eq!(
+            list_entries(&jar, is_java).unwrap(),
+            vec!["com/dep/Api.java"]
+        );
+        let path = virtual_path(&jar, "com/dep/Api.java");
+        assert_eq!(read_source(&path).unwrap(), "class Api {}");
+
+        let tgz = temp.path().join("vendor.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&tgz).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let source = b"class Util {}";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(source.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "pkg/Util.java", &source[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(list_entries(&tgz, is_java).unwrap(), vec!["pkg/Util.java"]);
+        assert_eq!(read_entry(&tgz, "pkg/Util.java").unwrap(), "class Util {}");
+        assert_eq!(
+            read_entry(&tgz, "pkg/Missing.java").unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn test_cache_reads_each_entry_of_an_open_archive() {
+        let temp = TempDir::new().unwrap();
+        let tar_path = temp.path().join("vendor.tar");
+        let mut builder = tar::Builder::new(std::fs::File::create(&tar_path).unwrap());
+        for name in ["a/One.java", "a/Two.java"] {
+            let source = format!("// {name}");
+            let mut header = tar::Header::new_gnu();
+            header.set_size(source.len() as u64);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, source.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap();
+
+        let cache = ArchiveCache::default();
+        assert_eq!(
+            cache.read_entry(&tar_path, "a/Two.java").unwrap(),
+            "// a/Two.java"
+        );
+        // Still served once the archive is gone: it was unpacked on first use
+        std::fs::remove_file(&tar_path).unwrap();
+        assert_eq!(
+            cache.read_entry(&tar_path, "a/One.java").unwrap(),
+            "// a/One.java"
+        );
+        assert_eq!(
+            cache
+                .read_entry(&tar_path, "a/Three.java")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+}
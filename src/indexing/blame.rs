@@ -0,0 +1,205 @@
+//! Symbol provenance from git blame
+//!
+//! With `indexing.blame` on, the index stage blames every file it writes and
+//! records for each symbol the commit that introduced it and the commit that
+//! last modified it: the oldest and newest commits among the lines the
+//! symbol spans. The records live in `blame.json` next to the Tantivy index
+//! and answer "who owns this function" in `codanna retrieve blame` and the
+//! MCP `find_symbol` tool.
+//!
+//! Blame is taken at `HEAD`, so uncommitted files get no record. Files
+//! outside a git repository and files inside archives are skipped.
+
+use crate::Symbol;
+use crate::indexing::archive;
+use git2::{BlameHunk, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A commit that touched a symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlameCommit {
+    pub commit: String,
+    pub author: String,
+    pub email: String,
+    /// Commit time in seconds since the epoch
+    pub time: i64,
+}
+
+impl BlameCommit {
+    fn from_hunk(hunk: &BlameHunk) -> Self {
+        let signature = hunk.final_signature();
+        Self {
+            commit: hunk.final_commit_id().to_string(),
+            author: signature.name().unwrap_or_default().to_string(),
+            email: signature.email().unwrap_or_default().to_string(),
+            time: signature.when().seconds(),
+        }
+    }
+
+    /// `<short id> by <author> on <date>`
+    pub fn describe(&self) -> String {
+        let date = chrono::DateTime::from_timestamp(self.time, 0)
+            .map(|time| time.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let short = self.commit.get(..12).unwrap_or(&self.commit);
+        format!("{short} by {} on {date}", self.author)
+    }
+}
+
+/// Provenance of one symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolBlame {
+    pub name: String,
+    /// First line of the symbol, 0-based like [`crate::types::Range`]
+    pub line: u32,
+    pub introduced: BlameCommit,
+    pub last_modified: BlameCommit,
+}
+
+/// Blames files, reusing the repository opened for the previous one
+#[derive(Default)]
+pub struct Blamer {
+    repo: Option<Repository>,
+}
+
+impl Blamer {
+    /// Provenance of `symbols`, all defined in `path`
+    ///
+    /// Empty when the file cannot be blamed.
+    pub fn blame_symbols<'a>(
+        &mut self,
+        path: &Path,
+        symbols: impl IntoIterator<Item = &'a Symbol>,
+    ) -> Vec<SymbolBlame> {
+        if archive::is_virtual(path) {
+            return Vec::new();
+        }
+        let Ok(path) = path.canonicalize() else {
+            return Vec::new();
+        };
+        let Some(repo) = self.repository(&path) else {
+            return Vec::new();
+        };
+        let relative = match repo.workdir().and_then(|root| path.strip_prefix(root).ok()) {
+            Some(relative) => relative.to_path_buf(),
+            None => return Vec::new(),
+        };
+        let blame = match repo.blame_file(&relative, None) {
+            Ok(blame) => blame,
+            Err(e) => {
+                tracing::debug!(target: "pipeline", "no blame for {}: {e}", path.display());
+                return Vec::new();
+            }
+        };
+        let hunks: Vec<BlameHunk> = blame.iter().collect();
+
+        symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                // Hunk lines are 1-based
+                let first = symbol.range.start_line as usize + 1;
+                let last = symbol.range.end_line as usize + 1;
+                let touching = hunks.iter().filter(|hunk| {
+                    let start = hunk.final_start_line();
+                    start <= last && start + hunk.lines_in_hunk() > first
+                });
+                let introduced = touching
+                    .clone()
+                    .min_by_key(|hunk| hunk.final_signature().when().seconds())?;
+                let last_modified =
+                    touching.max_by_key(|hunk| hunk.final_signature().when().seconds())?;
+
+                Some(SymbolBlame {
+                    name: symbol.name.to_string(),
+                    line: symbol.range.start_line,
+                    introduced: BlameCommit::from_hunk(introduced),
+                    last_modified: BlameCommit::from_hunk(last_modified),
+                })
+            })
+            .collect()
+    }
+
+    fn repository(&mut self, path: &Path) -> Option<&Repository> {
+        let reusable = self.repo.as_ref().is_some_and(|repo| {
+            repo.workdir()
+                .is_some_and(|root| path.starts_with(root) && !nested_repository(root, path))
+        });
+        if !reusable {
+            self.repo = Repository::discover(path.parent()?).ok();
+        }
+        self.repo.as_ref()
+    }
+}
+
+/// Whether a directory between `root` and `path` holds a repository of its own
+fn nested_repository(root: &Path, path: &Path) -> bool {
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| *dir != root && dir.starts_with(root))
+        .any(|dir| dir.join(".git").exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, Range, SymbolId, SymbolKind};
+    use git2::{Signature, Time};
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, author: &str, time: i64, message: &str) {
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::new(author, "dev@example.com", &Time::new(time, 0)).unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_blame_finds_introducing_and_last_commit() {
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        let file = temp.path().join("lib.rs");
+
+        std::fs::write(&file, "fn parse() {\n    one();\n}\n\nfn other() {}\n").unwrap();
+        commit(&repo, "Ada", 1_600_000_000, "add parse");
+        std::fs::write(&file, "fn parse() {\n    two();\n}\n\nfn other() {}\n").unwrap();
+        commit(&repo, "Grace", 1_700_000_000, "change parse");
+
+        let symbol = |id: u32, name: &str, start: u32, end: u32| {
+            Symbol::new(
+                SymbolId::new(id).unwrap(),
+                name,
+                SymbolKind::Function,
+                FileId::new(1).unwrap(),
+                Range::new(start, 0, end, 1),
+            )
+        };
+        let symbols = [symbol(1, "parse", 0, 2), symbol(2, "other", 4, 4)];
+
+        let blame = Blamer::default().blame_symbols(&file, &symbols);
+        assert_eq!(blame.len(), 2);
+        assert_eq!(blame[0].name, "parse");
+        assert_eq!(blame[0].introduced.author, "Ada");
+        assert_eq!(blame[0].last_modified.author, "Grace");
+        assert_eq!(blame[1].introduced, blame[1].last_modified);
+        assert_eq!(blame[1].introduced.author, "Ada");
+        assert!(
+            blame[1]
+                .introduced
+                .describe()
+                .ends_with("by Ada on 2020-09-13")
+        );
+    }
+}
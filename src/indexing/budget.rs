@@ -0,0 +1,187 @@
+//! Per-query time and size limits.
+//!
+//! A [`QueryBudget`] is created at the start of a retrieve operation or MCP
+//! tool call. Loops that expand candidates check it between steps and stop
+//! early once the deadline passes or the candidate ceiling is hit, so a
+//! pathological query returns partial results instead of hanging.
+
+use crate::config::QueryConfig;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// Why a query returned fewer results than it would have without limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationReason {
+    /// The query ran past its timeout
+    Timeout,
+    /// More candidates were found than `max_candidates` allows
+    CandidateLimit,
+}
+
+/// Deadline and candidate ceiling for a single query
+#[derive(Debug)]
+pub struct QueryBudget {
+    started: Instant,
+    timeout: Option<Duration>,
+    max_candidates: usize,
+    truncated: Cell<Option<TruncationReason>>,
+}
+
+impl QueryBudget {
+    /// Create a budget. A zero timeout or ceiling disables that limit.
+    pub fn new(timeout: Duration, max_candidates: usize) -> Self {
+        Self {
+            started: Instant::now(),
+            timeout: (!timeout.is_zero()).then_some(timeout),
+            max_candidates: if max_candidates == 0 {
+                usize::MAX
+            } else {
+                max_candidates
+            },
+            truncated: Cell::new(None),
+        }
+    }
+
+    /// Create a budget from the `[query]` settings.
+    pub fn from_config(config: &QueryConfig) -> Self {
+        Self::new(
+            Duration::from_millis(config.timeout_ms),
+            config.max_candidates,
+        )
+    }
+
+    /// A budget with no limits.
+    pub fn unlimited() -> Self {
+        Self::new(Duration::ZERO, 0)
+    }
+
+    /// Maximum candidates a single query may expand
+    pub fn max_candidates(&self) -> usize {
+        self.max_candidates
+    }
+
+    /// Time spent since the budget was created
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Check the deadline, recording a timeout if it has passed.
+    pub fn expired(&self) -> bool {
+        match self.timeout {
+            Some(timeout) if self.started.elapsed() >= timeout => {
+                self.mark(TruncationReason::Timeout);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Clamp a requested result count to the candidate ceiling.
+    pub fn clamp_limit(&self, limit: usize) -> usize {
+        limit.min(self.max_candidates)
+    }
+
+    /// Drop candidates beyond the ceiling, recording the truncation.
+    pub fn cap<T>(&self, mut items: Vec<T>) -> Vec<T> {
+        if items.len() > self.max_candidates {
+            items.truncate(self.max_candidates);
+            self.mark(TruncationReason::CandidateLimit);
+        }
+        items
+    }
+
+    /// Map candidates until the deadline passes or the ceiling is reached.
+    ///
+    /// Items mapped to `None` are skipped and do not count against the ceiling.
+    pub fn collect<I, T, U, F>(&self, items: I, mut f: F) -> Vec<U>
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(T) -> Option<U>,
+    {
+        let mut results = Vec::new();
+        let mut items = items.into_iter().peekable();
+        while items.peek().is_some() {
+            if results.len() >= self.max_candidates {
+                self.mark(TruncationReason::CandidateLimit);
+                break;
+            }
+            if self.expired() {
+                break;
+            }
+            if let Some(result) = items.next().and_then(&mut f) {
+                results.push(result);
+            }
+        }
+        results
+    }
+
+    /// Record that results were cut short. The first reason wins.
+    pub fn mark(&self, reason: TruncationReason) {
+        if self.truncated.get().is_none() {
+            self.truncated.set(Some(reason));
+        }
+    }
+
+    /// Why the query was truncated, if it was
+    pub fn truncation(&self) -> Option<TruncationReason> {
+        self.truncated.get()
+    }
+
+    /// Whether any limit cut the results short
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.get().is_some()
+    }
+
+    /// Human-readable note explaining the truncation, if any
+    pub fn truncation_note(&self) -> Option<String> {
+        self.truncated.get().map(|reason| match reason {
+            TruncationReason::Timeout => format!(
+                "Results truncated: query exceeded its {}ms timeout (query.timeout_ms)",
+                self.timeout.map(|t| t.as_millis()).unwrap_or_default()
+            ),
+            TruncationReason::CandidateLimit => format!(
+                "Results truncated: more than {} candidates (query.max_candidates)",
+                self.max_candidates
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_marks_candidate_limit() {
+        let budget = QueryBudget::new(Duration::ZERO, 3);
+        let items = budget.cap((0..10).collect::<Vec<_>>());
+        assert_eq!(items, vec![0, 1, 2]);
+        assert_eq!(budget.truncation(), Some(TruncationReason::CandidateLimit));
+
+        let budget = QueryBudget::new(Duration::ZERO, 3);
+        assert_eq!(budget.cap(vec![1, 2]).len(), 2);
+        assert!(!budget.is_truncated());
+    }
+
+    #[test]
+    fn test_collect_stops_at_deadline() {
+        let budget = QueryBudget::new(Duration::from_millis(1), 0);
+        std::thread::sleep(Duration::from_millis(5));
+        let results = budget.collect(0..100, Some);
+        assert!(results.is_empty());
+        assert_eq!(budget.truncation(), Some(TruncationReason::Timeout));
+        assert!(budget.truncation_note().unwrap().contains("1ms"));
+    }
+
+    #[test]
+    fn test_collect_skips_none_without_counting() {
+        let budget = QueryBudget::new(Duration::ZERO, 2);
+        let results = budget.collect(0..10, |n| (n % 2 == 1).then_some(n));
+        assert_eq!(results, vec![1, 3]);
+        assert!(budget.is_truncated());
+
+        let unlimited = QueryBudget::unlimited();
+        assert_eq!(unlimited.collect(0..10, Some).len(), 10);
+        assert!(!unlimited.is_truncated());
+    }
+}
@@ -0,0 +1,294 @@
+//! File-level dependency graphs
+//!
+//! A dependency graph lifts the symbol relationships of the index to files:
+//! file A depends on file B when a symbol in A calls, uses, implements or
+//! extends a symbol in B. Starting from the files under a path, the graph is
+//! walked breadth-first up to a depth limit, following dependencies,
+//! dependents or both. Each node also lists the file's raw import paths, so
+//! imports of external packages show up even though they have no edges.
+
+use crate::{RelationKind, SymbolId};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Relationship kinds that make one file depend on another
+pub const DEPENDENCY_KINDS: [RelationKind; 4] = [
+    RelationKind::Calls,
+    RelationKind::Uses,
+    RelationKind::Implements,
+    RelationKind::Extends,
+];
+
+/// Which edges the walk follows away from the starting files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphDirection {
+    /// Files the starting files depend on
+    #[default]
+    Dependencies,
+    /// Files depending on the starting files
+    Dependents,
+    Both,
+}
+
+impl GraphDirection {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dependencies" | "outgoing" | "out" => Some(Self::Dependencies),
+            "dependents" | "incoming" | "in" => Some(Self::Dependents),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    fn outgoing(self) -> bool {
+        self != Self::Dependents
+    }
+
+    fn incoming(self) -> bool {
+        self != Self::Dependencies
+    }
+}
+
+/// A symbol relationship crossing from one file into another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLink {
+    /// The file on the other end of the relationship
+    pub file: String,
+    pub kind: RelationKind,
+    pub from_symbol: SymbolId,
+    pub to_symbol: SymbolId,
+}
+
+/// What the index knows about one file's dependencies
+#[derive(Debug, Clone, Default)]
+pub struct FileDependencies {
+    pub symbols: usize,
+    pub imports: Vec<String>,
+    /// Relationships from this file's symbols into other files
+    pub outgoing: Vec<FileLink>,
+    /// Relationships from other files into this file's symbols
+    pub incoming: Vec<FileLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    /// Stored path of the file, also the node's ID in edges
+    pub id: String,
+    /// Hops from the nearest starting file
+    pub depth: u32,
+    pub symbols: usize,
+    pub imports: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Relationship kinds behind the edge, in first-seen order
+    pub kinds: Vec<RelationKind>,
+    /// Number of symbol relationships behind the edge
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// True when `max_nodes` or `query.timeout_ms` stopped the walk early
+    pub truncated: bool,
+}
+
+/// Walk the graph from `roots` up to `max_depth` hops, keeping at most
+/// `max_nodes` files
+///
+/// `dependencies` reports the relationships of one file; `expired` is
+/// checked before each file is expanded. Edges are kept only between files
+/// in the graph, so edges leaving it at the depth limit are dropped.
+pub fn build(
+    roots: Vec<String>,
+    direction: GraphDirection,
+    max_depth: u32,
+    max_nodes: usize,
+    mut dependencies: impl FnMut(&str) -> FileDependencies,
+    expired: impl Fn() -> bool,
+) -> DependencyGraph {
+    let mut walk = Walk {
+        graph: DependencyGraph::default(),
+        index: HashMap::new(),
+        queue: VecDeque::new(),
+        max_nodes,
+    };
+    let mut pending: Vec<(String, String, RelationKind)> = Vec::new();
+    let mut seen_links = HashSet::new();
+    for root in roots {
+        walk.add(root, 0);
+    }
+
+    while let Some((path, depth)) = walk.queue.pop_front() {
+        if expired() {
+            walk.graph.truncated = true;
+            break;
+        }
+        let file = dependencies(&path);
+        if let Some(&position) = walk.index.get(&path) {
+            let node = &mut walk.graph.nodes[position];
+            node.symbols = file.symbols;
+            node.imports = file.imports;
+        }
+
+        let outgoing = file.outgoing.into_iter().filter(|_| direction.outgoing());
+        let incoming = file.incoming.into_iter().filter(|_| direction.incoming());
+        for (link, out) in outgoing
+            .map(|l| (l, true))
+            .chain(incoming.map(|l| (l, false)))
+        {
+            // Seen from both ends when both files are expanded
+            if !seen_links.insert((link.from_symbol, link.to_symbol, link.kind)) {
+                continue;
+            }
+            if depth < max_depth {
+                walk.add(link.file.clone(), depth + 1);
+            }
+            if out {
+                pending.push((path.clone(), link.file, link.kind));
+            } else {
+                pending.push((link.file, path.clone(), link.kind));
+            }
+        }
+    }
+
+    let Walk {
+        mut graph, index, ..
+    } = walk;
+    let mut edges: HashMap<(String, String), usize> = HashMap::new();
+    for (from, to, kind) in pending {
+        if !index.contains_key(&from) || !index.contains_key(&to) {
+            continue;
+        }
+        let position = *edges.entry((from.clone(), to.clone())).or_insert_with(|| {
+            graph.edges.push(GraphEdge {
+                from,
+                to,
+                kinds: Vec::new(),
+                count: 0,
+            });
+            graph.edges.len() - 1
+        });
+        let edge = &mut graph.edges[position];
+        edge.count += 1;
+        if !edge.kinds.contains(&kind) {
+            edge.kinds.push(kind);
+        }
+    }
+    graph
+}
+
+struct Walk {
+    graph: DependencyGraph,
+    /// Position of each file in `graph.nodes`
+    index: HashMap<String, usize>,
+    queue: VecDeque<(String, u32)>,
+    max_nodes: usize,
+}
+
+impl Walk {
+    fn add(&mut self, path: String, depth: u32) {
+        if self.index.contains_key(&path) {
+            return;
+        }
+        if self.graph.nodes.len() >= self.max_nodes {
+            self.graph.truncated = true;
+            return;
+        }
+        self.index.insert(path.clone(), self.graph.nodes.len());
+        self.graph.nodes.push(GraphNode {
+            id: path.clone(),
+            depth,
+            symbols: 0,
+            imports: Vec::new(),
+        });
+        self.queue.push_back((path, depth));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(file: &str, kind: RelationKind, from: u32, to: u32) -> FileLink {
+        FileLink {
+            file: file.to_string(),
+            kind,
+            from_symbol: SymbolId(from),
+            to_symbol: SymbolId(to),
+        }
+    }
+
+    /// main.rs -> lib.rs -> store.rs, with two relationships main -> lib
+    fn files(path: &str) -> FileDependencies {
+        match path {
+            "src/main.rs" => FileDependencies {
+                symbols: 1,
+                imports: vec!["clap::Parser".to_string()],
+                outgoing: vec![
+                    link("src/lib.rs", RelationKind::Calls, 1, 2),
+                    link("src/lib.rs", RelationKind::Uses, 1, 3),
+                ],
+                incoming: Vec::new(),
+            },
+            "src/lib.rs" => FileDependencies {
+                symbols: 2,
+                imports: Vec::new(),
+                outgoing: vec![link("src/store.rs", RelationKind::Calls, 2, 4)],
+                incoming: vec![
+                    link("src/main.rs", RelationKind::Calls, 1, 2),
+                    link("src/main.rs", RelationKind::Uses, 1, 3),
+                ],
+            },
+            "src/store.rs" => FileDependencies {
+                symbols: 1,
+                imports: Vec::new(),
+                outgoing: Vec::new(),
+                incoming: vec![link("src/lib.rs", RelationKind::Calls, 2, 4)],
+            },
+            _ => FileDependencies::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_walks_files_to_depth() {
+        let roots = vec!["src/main.rs".to_string()];
+        let graph = build(
+            roots.clone(),
+            GraphDirection::Dependencies,
+            1,
+            10,
+            files,
+            || false,
+        );
+        let ids: Vec<_> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, ["src/main.rs", "src/lib.rs"]);
+        assert_eq!(graph.nodes[0].imports, ["clap::Parser"]);
+        // The edge to store.rs leaves the graph at the depth limit
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].count, 2);
+        assert_eq!(
+            graph.edges[0].kinds,
+            [RelationKind::Calls, RelationKind::Uses]
+        );
+        assert!(!graph.truncated);
+
+        // Walking both ways counts each relationship once
+        let roots = vec!["src/lib.rs".to_string()];
+        let graph = build(roots, GraphDirection::Both, 2, 10, files, || false);
+        assert_eq!(graph.nodes.len(), 3);
+        let counts: Vec<_> = graph.edges.iter().map(|edge| edge.count).collect();
+        assert_eq!(counts, [1, 2]);
+
+        let roots = vec!["src/store.rs".to_string()];
+        let graph = build(roots, GraphDirection::Dependents, 5, 2, files, || false);
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.truncated);
+    }
+}
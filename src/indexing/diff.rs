@@ -0,0 +1,339 @@
+//! Symbol and relationship differences between two index states
+//!
+//! Symbol IDs are assigned per build, so two indexes are compared by what a
+//! symbol is rather than its ID: file (relative to the workspace root the
+//! index was built in), module path and name. A symbol present on both sides
+//! whose kind, signature or visibility differs is reported as changed; moving
+//! within its file is not a change. Relationships are compared as edges
+//! between those identities.
+
+use crate::indexing::facade::{FacadeResult, IndexFacade};
+use crate::{RelationKind, Symbol, SymbolId};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Symbols and relationship edges of one index
+pub struct IndexState {
+    symbols: Vec<Symbol>,
+    edges: Vec<(SymbolId, SymbolId, RelationKind)>,
+    /// Workspace root stripped from symbol paths
+    root: Option<String>,
+}
+
+impl IndexState {
+    pub fn new(
+        symbols: Vec<Symbol>,
+        edges: Vec<(SymbolId, SymbolId, RelationKind)>,
+        root: Option<&Path>,
+    ) -> Self {
+        Self {
+            symbols,
+            edges,
+            root: root.map(|root| root.to_string_lossy().into_owned()),
+        }
+    }
+
+    /// Read every symbol and relationship of `facade`
+    pub fn from_facade(facade: &IndexFacade, root: Option<&Path>) -> FacadeResult<Self> {
+        let index = facade.document_index();
+        let symbols = index.get_all_symbols(facade.symbol_count().max(1))?;
+        let edges = index
+            .query_relationships()?
+            .into_iter()
+            .map(|(from, to, relationship)| (from, to, relationship.kind))
+            .collect();
+        Ok(Self::new(symbols, edges, root))
+    }
+
+    fn relative_path<'a>(&self, path: &'a str) -> &'a str {
+        self.root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root))
+            .map(|rest| rest.trim_start_matches(['/', '\\']))
+            .unwrap_or(path)
+    }
+
+    fn reference(&self, symbol: &Symbol) -> SymbolRef {
+        SymbolRef {
+            name: symbol.name.to_string(),
+            module: symbol.module_path.as_deref().map(str::to_string),
+            file: self.relative_path(&symbol.file_path).to_string(),
+        }
+    }
+
+    fn entry(&self, symbol: &Symbol) -> SymbolEntry {
+        SymbolEntry {
+            symbol: self.reference(symbol),
+            kind: format!("{:?}", symbol.kind),
+            line: symbol.range.start_line + 1,
+            signature: symbol.signature.as_deref().map(str::to_string),
+            visibility: format!("{:?}", symbol.visibility),
+        }
+    }
+
+    fn entries(&self) -> BTreeMap<SymbolRef, Vec<SymbolEntry>> {
+        let mut entries: BTreeMap<SymbolRef, Vec<SymbolEntry>> = BTreeMap::new();
+        for symbol in &self.symbols {
+            let entry = self.entry(symbol);
+            entries.entry(entry.symbol.clone()).or_default().push(entry);
+        }
+        for group in entries.values_mut() {
+            group.sort_by_key(|entry| entry.line);
+        }
+        entries
+    }
+
+    fn edge_set(&self) -> BTreeSet<Edge> {
+        let refs: HashMap<SymbolId, SymbolRef> = self
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.id, self.reference(symbol)))
+            .collect();
+        self.edges
+            .iter()
+            .filter_map(|(from, to, kind)| {
+                Some(Edge {
+                    from: refs.get(from)?.clone(),
+                    kind: format!("{kind:?}"),
+                    to: refs.get(to)?.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Identity of a symbol across index builds
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct SymbolRef {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    pub file: String,
+}
+
+/// A symbol as reported in a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolEntry {
+    #[serde(flatten)]
+    pub symbol: SymbolRef,
+    pub kind: String,
+    /// 1-based line of the definition
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub visibility: String,
+}
+
+impl SymbolEntry {
+    /// Which API-relevant fields differ from `other`
+    fn changes(&self, other: &SymbolEntry) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        if self.kind != other.kind {
+            changes.push("kind");
+        }
+        if self.signature != other.signature {
+            changes.push("signature");
+        }
+        if self.visibility != other.visibility {
+            changes.push("visibility");
+        }
+        changes
+    }
+}
+
+/// A symbol present in both indexes with a different definition
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangedSymbol {
+    pub before: SymbolEntry,
+    pub after: SymbolEntry,
+    /// Fields that differ: `kind`, `signature`, `visibility`
+    pub changes: Vec<&'static str>,
+}
+
+/// Relationship between two symbols, e.g. `Calls`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Edge {
+    pub from: SymbolRef,
+    pub kind: String,
+    pub to: SymbolRef,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SymbolChanges {
+    pub added: Vec<SymbolEntry>,
+    pub removed: Vec<SymbolEntry>,
+    pub changed: Vec<ChangedSymbol>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EdgeChanges {
+    pub added: Vec<Edge>,
+    pub removed: Vec<Edge>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DiffSummary {
+    pub symbols_added: usize,
+    pub symbols_removed: usize,
+    pub symbols_changed: usize,
+    pub relationships_added: usize,
+    pub relationships_removed: usize,
+}
+
+/// Differences from one index state to another
+#[derive(Debug, Default, Serialize)]
+pub struct IndexDiff {
+    pub summary: DiffSummary,
+    pub symbols: SymbolChanges,
+    pub relationships: EdgeChanges,
+}
+
+impl IndexDiff {
+    pub fn is_empty(&self) -> bool {
+        self.symbols.added.is_empty()
+            && self.symbols.removed.is_empty()
+            && self.symbols.changed.is_empty()
+            && self.relationships.added.is_empty()
+            && self.relationships.removed.is_empty()
+    }
+}
+
+/// Compare `before` with `after`
+pub fn diff(before: &IndexState, after: &IndexState) -> IndexDiff {
+    let mut symbols = SymbolChanges::default();
+    let mut old = before.entries();
+    let mut new = after.entries();
+
+    let keys: BTreeSet<SymbolRef> = old.keys().chain(new.keys()).cloned().collect();
+    for key in keys {
+        let mut removed = old.remove(&key).unwrap_or_default();
+        let mut added = new.remove(&key).unwrap_or_default();
+
+        // Same name twice in one module (overloads, methods of different
+        // types): pair identical definitions first, then the rest by line
+        added.retain(
+            |entry| match removed.iter().position(|old| old.changes(entry).is_empty()) {
+                Some(index) => {
+                    removed.remove(index);
+                    false
+                }
+                None => true,
+            },
+        );
+        let paired = removed.len().min(added.len());
+        for (before, after) in removed.drain(..paired).zip(added.drain(..paired)) {
+            let changes = before.changes(&after);
+            symbols.changed.push(ChangedSymbol {
+                before,
+                after,
+                changes,
+            });
+        }
+        symbols.removed.extend(removed);
+        symbols.added.extend(added);
+    }
+
+    let old_edges = before.edge_set();
+    let new_edges = after.edge_set();
+    let relationships = EdgeChanges {
+        added: new_edges.difference(&old_edges).cloned().collect(),
+        removed: old_edges.difference(&new_edges).cloned().collect(),
+    };
+
+    IndexDiff {
+        summary: DiffSummary {
+            symbols_added: symbols.added.len(),
+            symbols_removed: symbols.removed.len(),
+            symbols_changed: symbols.changed.len(),
+            relationships_added: relationships.added.len(),
+            relationships_removed: relationships.removed.len(),
+        },
+        symbols,
+        relationships,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range, SymbolKind, Visibility};
+
+    fn symbol(id: u32, name: &str, line: u32, signature: &str, root: &str) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(line, 0, line + 2, 1),
+        )
+        .with_file_path(format!("{root}/src/lib.rs"))
+        .with_module_path("crate")
+        .with_signature(signature)
+        .with_visibility(Visibility::Public)
+    }
+
+    #[test]
+    fn test_diff_matches_symbols_by_identity_not_id() {
+        let before = IndexState::new(
+            vec![
+                symbol(1, "parse", 10, "fn parse(input: &str)", "/ci/work"),
+                symbol(2, "run", 20, "fn run()", "/ci/work"),
+                symbol(3, "legacy", 30, "fn legacy()", "/ci/work"),
+            ],
+            vec![(
+                SymbolId::new(2).unwrap(),
+                SymbolId::new(1).unwrap(),
+                RelationKind::Calls,
+            )],
+            Some(Path::new("/ci/work")),
+        );
+        // Rebuilt elsewhere with new IDs; `run` only moved
+        let after = IndexState::new(
+            vec![
+                symbol(7, "run", 25, "fn run()", "/home/dev/project"),
+                symbol(
+                    8,
+                    "parse",
+                    12,
+                    "fn parse(input: &[u8])",
+                    "/home/dev/project",
+                ),
+                symbol(9, "render", 40, "fn render()", "/home/dev/project"),
+            ],
+            vec![
+                (
+                    SymbolId::new(7).unwrap(),
+                    SymbolId::new(8).unwrap(),
+                    RelationKind::Calls,
+                ),
+                (
+                    SymbolId::new(7).unwrap(),
+                    SymbolId::new(9).unwrap(),
+                    RelationKind::Calls,
+                ),
+            ],
+            Some(Path::new("/home/dev/project")),
+        );
+
+        let result = diff(&before, &after);
+
+        let names = |entries: &[SymbolEntry]| -> Vec<String> {
+            entries.iter().map(|e| e.symbol.name.clone()).collect()
+        };
+        assert_eq!(names(&result.symbols.added), vec!["render"]);
+        assert_eq!(names(&result.symbols.removed), vec!["legacy"]);
+        assert_eq!(result.symbols.changed.len(), 1);
+        assert_eq!(result.symbols.changed[0].after.symbol.name, "parse");
+        assert_eq!(result.symbols.changed[0].changes, vec!["signature"]);
+        assert_eq!(result.symbols.added[0].symbol.file, "src/lib.rs");
+
+        assert_eq!(result.relationships.removed.len(), 0);
+        assert_eq!(result.relationships.added.len(), 1);
+        assert_eq!(result.relationships.added[0].to.name, "render");
+        assert_eq!(result.summary.symbols_changed, 1);
+
+        let unchanged = diff(&after, &after);
+        assert!(unchanged.is_empty());
+    }
+}
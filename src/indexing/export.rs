@@ -0,0 +1,401 @@
+//! Parquet tables of the index for SQL analytics
+//!
+//! `codanna export parquet <dir>` writes `symbols.parquet`,
+//! `relationships.parquet` and `files.parquet`, which DuckDB or a warehouse
+//! reads as they are. Exports taken over time answer questions the index
+//! itself does not: how the public API of a module grows, or which files
+//! gain and lose dependencies. Paths are relative to the workspace root, as
+//! in `index diff`, so exports of different checkouts line up.
+
+use crate::indexing::facade::{FacadeResult, IndexFacade};
+use crate::{FileId, IndexError, RelationKind, Symbol, SymbolId};
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::column::writer::ColumnWriterImpl;
+use parquet::data_type::{ByteArray, ByteArrayType, DataType, DoubleType, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A file of the index, as the `files` table lists it
+#[derive(Debug, Clone)]
+pub struct IndexedFile {
+    pub id: FileId,
+    pub path: String,
+    pub content_hash: String,
+    /// Modification time in seconds since the epoch, 0 if unknown
+    pub mtime: u64,
+}
+
+/// Values of one column, `None` for null
+enum Values {
+    Int(Vec<Option<i64>>),
+    Double(Vec<Option<f64>>),
+    Text(Vec<Option<String>>),
+}
+
+impl Values {
+    fn physical_type(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "int64",
+            Self::Double(_) => "double",
+            Self::Text(_) => "binary",
+        }
+    }
+}
+
+/// A table written to `<name>.parquet`
+pub struct Table {
+    name: &'static str,
+    columns: Vec<(&'static str, Values)>,
+}
+
+impl Table {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn rows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, values)| match values {
+            Values::Int(values) => values.len(),
+            Values::Double(values) => values.len(),
+            Values::Text(values) => values.len(),
+        })
+    }
+
+    fn schema(&self) -> String {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|(name, values)| match values {
+                Values::Text(_) => format!("optional binary {name} (UTF8);"),
+                _ => format!("optional {} {name};", values.physical_type()),
+            })
+            .collect();
+        format!("message {} {{ {} }}", self.name, fields.join(" "))
+    }
+
+    fn write(&self, path: &Path) -> Result<(), ParquetError> {
+        let schema = Arc::new(parse_message_type(&self.schema())?);
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                "codanna.version".to_string(),
+                env!("CARGO_PKG_VERSION").to_string(),
+            )]))
+            .build();
+        let file = File::create(path)?;
+        let mut writer = SerializedFileWriter::new(file, schema, Arc::new(properties))?;
+        let mut row_group = writer.next_row_group()?;
+        for (_, values) in &self.columns {
+            let Some(mut column) = row_group.next_column()? else {
+                break;
+            };
+            match values {
+                Values::Int(values) => {
+                    write_column(column.typed::<Int64Type>(), values.iter().copied())?
+                }
+                Values::Double(values) => {
+                    write_column(column.typed::<DoubleType>(), values.iter().copied())?
+                }
+                Values::Text(values) => write_column(
+                    column.typed::<ByteArrayType>(),
+                    values
+                        .iter()
+                        .map(|value| value.as_deref().map(ByteArray::from)),
+                )?,
+            }
+            column.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// Write optional values with a definition level each: 1 set, 0 null
+fn write_column<T: DataType>(
+    writer: &mut ColumnWriterImpl<'_, T>,
+    values: impl Iterator<Item = Option<T::T>>,
+) -> Result<(), ParquetError> {
+    let mut present = Vec::new();
+    let mut levels = Vec::new();
+    for value in values {
+        levels.push(i16::from(value.is_some()));
+        present.extend(value);
+    }
+    writer.write_batch(&present, Some(&levels), None)?;
+    Ok(())
+}
+
+/// Symbols, relationships and files of an index as tables
+pub struct ExportTables {
+    pub tables: Vec<Table>,
+}
+
+impl ExportTables {
+    pub fn new(
+        symbols: &[Symbol],
+        relationships: &[(SymbolId, SymbolId, RelationKind, f32)],
+        files: &[IndexedFile],
+        root: Option<&Path>,
+    ) -> Self {
+        let relative = |path: &str| relative_path(path, root);
+        let text = |value: Option<&str>| value.map(str::to_string);
+
+        let symbol_table = Table {
+            name: "symbols",
+            columns: vec![
+                ("symbol_id", int(symbols, |s| s.id.value().into())),
+                ("name", texts(symbols, |s| Some(s.name.to_string()))),
+                ("kind", texts(symbols, |s| Some(format!("{:?}", s.kind)))),
+                (
+                    "language",
+                    texts(symbols, |s| {
+                        text(s.language_id.as_ref().map(|l| l.as_str()))
+                    }),
+                ),
+                (
+                    "file_path",
+                    texts(symbols, |s| Some(relative(&s.file_path))),
+                ),
+                (
+                    "module_path",
+                    texts(symbols, |s| text(s.module_path.as_deref())),
+                ),
+                (
+                    "visibility",
+                    texts(symbols, |s| Some(format!("{:?}", s.visibility))),
+                ),
+                (
+                    "start_line",
+                    int(symbols, |s| i64::from(s.range.start_line) + 1),
+                ),
+                (
+                    "end_line",
+                    int(symbols, |s| i64::from(s.range.end_line) + 1),
+                ),
+                (
+                    "signature",
+                    texts(symbols, |s| text(s.signature.as_deref())),
+                ),
+                (
+                    "doc_comment",
+                    texts(symbols, |s| text(s.doc_comment.as_deref())),
+                ),
+            ],
+        };
+
+        let file_of: HashMap<SymbolId, &str> = symbols
+            .iter()
+            .map(|symbol| (symbol.id, &*symbol.file_path))
+            .collect();
+        let file_path = |id: &SymbolId| file_of.get(id).map(|path| relative(path));
+        let relationship_table = Table {
+            name: "relationships",
+            columns: vec![
+                (
+                    "from_symbol_id",
+                    int(relationships, |(from, ..)| from.value().into()),
+                ),
+                (
+                    "to_symbol_id",
+                    int(relationships, |(_, to, ..)| to.value().into()),
+                ),
+                (
+                    "kind",
+                    texts(relationships, |(_, _, kind, _)| Some(format!("{kind:?}"))),
+                ),
+                (
+                    "weight",
+                    Values::Double(
+                        relationships
+                            .iter()
+                            .map(|(.., weight)| Some(f64::from(*weight)))
+                            .collect(),
+                    ),
+                ),
+                (
+                    "from_file",
+                    texts(relationships, |(from, ..)| file_path(from)),
+                ),
+                ("to_file", texts(relationships, |(_, to, ..)| file_path(to))),
+            ],
+        };
+
+        let mut symbol_counts: HashMap<FileId, i64> = HashMap::new();
+        let mut languages: HashMap<FileId, &str> = HashMap::new();
+        for symbol in symbols {
+            *symbol_counts.entry(symbol.file_id).or_default() += 1;
+            if let Some(language) = &symbol.language_id {
+                languages.entry(symbol.file_id).or_insert(language.as_str());
+            }
+        }
+        let file_table = Table {
+            name: "files",
+            columns: vec![
+                ("file_id", int(files, |f| f.id.value().into())),
+                ("file_path", texts(files, |f| Some(relative(&f.path)))),
+                (
+                    "language",
+                    texts(files, |f| text(languages.get(&f.id).copied())),
+                ),
+                (
+                    "content_hash",
+                    texts(files, |f| Some(f.content_hash.clone())),
+                ),
+                (
+                    "modified",
+                    Values::Int(
+                        files
+                            .iter()
+                            .map(|f| (f.mtime > 0).then_some(f.mtime as i64))
+                            .collect(),
+                    ),
+                ),
+                (
+                    "symbol_count",
+                    int(files, |f| symbol_counts.get(&f.id).copied().unwrap_or(0)),
+                ),
+            ],
+        };
+
+        Self {
+            tables: vec![symbol_table, relationship_table, file_table],
+        }
+    }
+
+    /// Read every symbol, relationship and file of `facade`
+    pub fn from_facade(facade: &IndexFacade, root: Option<&Path>) -> FacadeResult<Self> {
+        let index = facade.document_index();
+        let symbols = index.get_all_symbols(facade.symbol_count().max(1))?;
+        let relationships: Vec<_> = index
+            .query_relationships()?
+            .into_iter()
+            .map(|(from, to, relationship)| (from, to, relationship.kind, relationship.weight))
+            .collect();
+        let mut files = Vec::new();
+        for path in index.get_all_indexed_paths()? {
+            let path = path.to_string_lossy().into_owned();
+            if let Some((id, content_hash, mtime)) = index.get_file_info(&path)? {
+                files.push(IndexedFile {
+                    id,
+                    path,
+                    content_hash,
+                    mtime,
+                });
+            }
+        }
+        files.sort_by_key(|file| file.id.value());
+        Ok(Self::new(&symbols, &relationships, &files, root))
+    }
+
+    /// Write `<table>.parquet` for each table into `dir`
+    pub fn write(&self, dir: &Path) -> FacadeResult<()> {
+        std::fs::create_dir_all(dir).map_err(|source| IndexError::FileWrite {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        for table in &self.tables {
+            let path = dir.join(format!("{}.parquet", table.name));
+            table.write(&path).map_err(|e| IndexError::FileWrite {
+                path: path.clone(),
+                source: std::io::Error::other(e),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// `path` relative to `root` when it lies inside it, as stored otherwise
+fn relative_path(path: &str, root: Option<&Path>) -> String {
+    root.and_then(|root| Path::new(path).strip_prefix(root).ok())
+        .map(|rest| rest.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn int<T>(rows: &[T], value: impl Fn(&T) -> i64) -> Values {
+    Values::Int(rows.iter().map(|row| Some(value(row))).collect())
+}
+
+fn texts<T>(rows: &[T], value: impl Fn(&T) -> Option<String>) -> Values {
+    Values::Text(rows.iter().map(value).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Range, SymbolKind, Visibility};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    fn symbol(id: u32, name: &str, file: u32) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(file).unwrap(),
+            Range::new(4, 0, 9, 1),
+        )
+        .with_file_path(format!("/work/src/{file}.rs"))
+        .with_visibility(Visibility::Public)
+    }
+
+    #[test]
+    fn test_paths_are_relative_only_inside_the_root() {
+        let root = Some(Path::new("/work"));
+        assert_eq!(relative_path("/work/src/a.rs", root), "src/a.rs");
+        // A sibling sharing the root's name as a prefix is left alone
+        assert_eq!(relative_path("/workspace/x.rs", root), "/workspace/x.rs");
+        assert_eq!(relative_path("src/a.rs", None), "src/a.rs");
+    }
+
+    #[test]
+    fn test_index_is_written_as_parquet_tables() {
+        let symbols = [symbol(1, "parse", 1), symbol(2, "run", 2)];
+        let relationships = [(
+            SymbolId::new(2).unwrap(),
+            SymbolId::new(1).unwrap(),
+            RelationKind::Calls,
+            1.0,
+        )];
+        let files = [IndexedFile {
+            id: FileId::new(1).unwrap(),
+            path: "/work/src/1.rs".to_string(),
+            content_hash: "abc".to_string(),
+            mtime: 0,
+        }];
+        let tables = ExportTables::new(&symbols, &relationships, &files, Some(Path::new("/work")));
+        let rows: Vec<(&str, usize)> = tables
+            .tables
+            .iter()
+            .map(|table| (table.name(), table.rows()))
+            .collect();
+        assert_eq!(rows, [("symbols", 2), ("relationships", 1), ("files", 1)]);
+
+        let dir = tempfile::tempdir().unwrap();
+        tables.write(dir.path()).unwrap();
+        let file = File::open(dir.path().join("relationships.parquet")).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 1);
+        let row = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        assert_eq!(
+            row.to_string(),
+            "{from_symbol_id: 2, to_symbol_id: 1, kind: \"Calls\", weight: 1.0, \
+             from_file: \"src/2.rs\", to_file: \"src/1.rs\"}"
+        );
+
+        let file = File::open(dir.path().join("files.parquet")).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        let row = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        assert_eq!(
+            row.to_string(),
+            "{file_id: 1, file_path: \"src/1.rs\", language: null, content_hash: \"abc\", \
+             modified: null, symbol_count: 1}"
+        );
+    }
+}
@@ -10,7 +10,7 @@
 //! IndexFacade
 //!   ├── DocumentIndex (Arc) - All query operations
 //!   ├── Pipeline - All mutation/indexing operations
-//!   ├── SimpleSemanticSearch (Option<Arc<Mutex>>) - Semantic search
+//!   ├── SimpleSemanticSearch (Option<Arc<Mutex>>) - Semantic search, optionally lazy
 //!   ├── SymbolCache (Option<Arc>) - O(1) symbol lookups
 //!   └── indexed_paths (HashSet) - Directory tracking
 //! ```
@@ -23,19 +23,211 @@
 //! let symbols = facade.find_symbols_by_name("main")?;  // Uses DocumentIndex
 //! ```
 
-use crate::config::Settings;
-use crate::indexing::pipeline::Pipeline;
-use crate::semantic::{EmbeddingPool, SimpleSemanticSearch};
-use crate::storage::{DocumentIndex, SearchResult};
+use crate::config::{ModelThresholds, Settings};
+use crate::indexing::budget::QueryBudget;
+use crate::indexing::dependency_graph::{
+    self, DEPENDENCY_KINDS, DependencyGraph, FileDependencies, FileLink, GraphDirection,
+};
+use crate::indexing::git_delta;
+use crate::indexing::pipeline::stages::{init_parser_cache, parse_file};
+use crate::indexing::pipeline::types::{FileContent, RawSymbol};
+use crate::indexing::pipeline::{Pipeline, PipelineCancel};
+use crate::parsing::ParseDiagnostic;
+use crate::project_resolver::workspace::{WorkspacePackage, discover_packages, package_for};
+use crate::semantic::{
+    CalibrationScores, ChunkingStrategy, CrossLanguageLink, EmbeddingPool, KnowledgeHit,
+    LinkCandidate, RRF_K, Reranker, SemanticMigration, SimpleSemanticSearch, SymbolTexts,
+    chunk_symbols, cluster_embeddings, expand_query, extract_route, fuse_knowledge,
+    link_across_languages, merge_chunk_embeddings, reciprocal_rank_fusion, recover_migration,
+};
+use crate::storage::{
+    BlameStore, CompactionStats, DiagnosticsStore, DocumentIndex, FileBlames, FileDiagnostics,
+    SearchResult,
+};
 use crate::symbol::context::{ContextIncludes, SymbolContext, SymbolRelationships};
-use crate::{FileId, IndexError, RelationKind, Relationship, Symbol, SymbolId, SymbolKind};
+use crate::symbol::outline::{self, OutlineNode};
+use crate::{
+    FileId, IndexError, RelationKind, Relationship, Symbol, SymbolId, SymbolKind, Visibility,
+};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Result type for facade operations
 pub type FacadeResult<T> = Result<T, IndexError>;
 
+/// Most search hits inspected when filtering by repository or package
+const MAX_SCOPED_CANDIDATES: usize = 1_000;
+
+/// Hits fetched from each search per result of a hybrid search
+const HYBRID_CANDIDATE_FACTOR: usize = 3;
+
+/// Lines above a symbol searched for a route, for attributes and decorators
+const ROUTE_LINES_ABOVE: usize = 3;
+
+/// Lines of a symbol's body searched for a route
+const ROUTE_LINES_BELOW: usize = 15;
+
+/// Where a scoped search looks, see [`IndexFacade::search_scoped`]
+#[derive(Debug, Clone, Default)]
+pub struct SearchScope {
+    /// Repository name from `[repositories]`
+    pub repository: Option<String>,
+    /// Workspace package name, e.g. a Cargo crate or npm package
+    pub package: Option<String>,
+    /// Top-level directory shard, see [`crate::indexing::shard`]
+    pub shard: Option<String>,
+}
+
+impl SearchScope {
+    pub fn is_empty(&self) -> bool {
+        self.repository.is_none() && self.package.is_none() && self.shard.is_none()
+    }
+}
+
+/// Restrictions on semantic search hits, see [`IndexFacade::semantic_search_filtered`]
+#[derive(Debug, Clone, Default)]
+pub struct SemanticFilter {
+    /// Language id, e.g. "rust"
+    pub language: Option<String>,
+    pub kind: Option<SymbolKind>,
+    /// Glob over the file path as stored, e.g. `src/**/*.rs`
+    pub path: Option<glob::Pattern>,
+    pub visibility: Option<Visibility>,
+    /// Repository name from `[repositories]`
+    pub repository: Option<String>,
+}
+
+impl SemanticFilter {
+    /// Filter from the values the search tools take, or what is invalid
+    pub fn parse(
+        language: Option<String>,
+        kind: Option<&str>,
+        path: Option<&str>,
+        visibility: Option<&str>,
+        repository: Option<String>,
+    ) -> Result<Self, String> {
+        let kind = match kind {
+            Some(kind) => Some(SymbolKind::parse_ignore_case(kind).ok_or_else(|| {
+                format!("Unknown kind '{kind}'. Use e.g. Function, Method, Struct, Trait, Class")
+            })?),
+            None => None,
+        };
+        let path = match path {
+            Some(glob) => Some(
+                glob::Pattern::new(glob).map_err(|e| format!("Invalid path glob '{glob}': {e}"))?,
+            ),
+            None => None,
+        };
+        let visibility = match visibility.map(str::to_lowercase).as_deref() {
+            Some("public" | "pub") => Some(Visibility::Public),
+            Some("crate") => Some(Visibility::Crate),
+            Some("module") => Some(Visibility::Module),
+            Some("private") => Some(Visibility::Private),
+            Some(other) => {
+                return Err(format!(
+                    "Unknown visibility '{other}'. Use public, crate, module or private"
+                ));
+            }
+            None => None,
+        };
+        Ok(Self {
+            language,
+            kind,
+            path,
+            visibility,
+            repository,
+        })
+    }
+
+    fn matches(&self, settings: &Settings, symbol: &Symbol) -> bool {
+        self.kind.is_none_or(|kind| symbol.kind == kind)
+            && self
+                .visibility
+                .is_none_or(|visibility| symbol.visibility == visibility)
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|pattern| pattern.matches(&symbol.file_path))
+            && self.repository.as_deref().is_none_or(|repository| {
+                settings
+                    .repository_for(Path::new(&*symbol.file_path))
+                    .as_deref()
+                    == Some(repository)
+            })
+    }
+
+    /// Whether the kind, path or repository is filtered, which semantic
+    /// search checks before scoring
+    fn scopes_symbols(&self) -> bool {
+        self.kind.is_some() || self.path.is_some() || self.repository.is_some()
+    }
+}
+
+/// The parts of `symbol` that chunking reads
+fn raw_symbol(symbol: &Symbol) -> RawSymbol {
+    let mut raw = RawSymbol::new(symbol.name.clone(), symbol.kind, symbol.range);
+    raw.signature = symbol.signature.clone();
+    raw.doc_comment = symbol.doc_comment.clone();
+    raw.visibility = symbol.visibility;
+    raw
+}
+
+/// Route a symbol serves or requests, from its docs, signature or source
+fn route_near(symbol: &Symbol, source: Option<&str>) -> Option<String> {
+    let documented = [symbol.doc_comment.as_deref(), symbol.signature.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(extract_route);
+    documented.or_else(|| {
+        let start = (symbol.range.start_line as usize).saturating_sub(ROUTE_LINES_ABOVE);
+        let lines: Vec<&str> = source?
+            .lines()
+            .skip(start)
+            .take(ROUTE_LINES_ABOVE + ROUTE_LINES_BELOW)
+            .collect();
+        extract_route(&lines.join("\n"))
+    })
+}
+
+/// Number of symbols with each name and kind
+fn name_counts(
+    names: impl IntoIterator<Item = (String, SymbolKind)>,
+) -> HashMap<(String, SymbolKind), usize> {
+    let mut counts = HashMap::new();
+    for name in names {
+        *counts.entry(name).or_default() += 1;
+    }
+    counts
+}
+
+/// Texts semantic search embeds for a chunked symbol
+fn embedded_texts(raw: RawSymbol) -> Vec<String> {
+    match raw.chunks {
+        Some(chunks) => chunks.into_iter().map(String::from).collect(),
+        None => raw.doc_comment.into_iter().map(String::from).collect(),
+    }
+}
+
+/// Package called `name`, or an error listing the known ones
+fn find_package<'a>(
+    packages: &'a [WorkspacePackage],
+    name: &str,
+) -> FacadeResult<&'a WorkspacePackage> {
+    packages
+        .iter()
+        .find(|package| package.name == name)
+        .ok_or_else(|| {
+            let known: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+            IndexError::ConfigError {
+                reason: format!(
+                    "unknown workspace package '{name}' (found: {})",
+                    known.join(", ")
+                ),
+            }
+        })
+}
+
 /// Statistics for indexing operations
 #[derive(Debug, Clone, Default)]
 pub struct IndexingStats {
@@ -64,6 +256,154 @@ impl SyncStats {
     }
 }
 
+/// A window over a relationship list that may be too large to return whole
+#[derive(Debug, Clone)]
+pub struct RelationshipPage<T> {
+    /// Items in this page
+    pub items: Vec<T>,
+    /// Position of the first item within the full list
+    pub offset: usize,
+    /// Total number of relationships, including those outside this page
+    pub total: usize,
+    /// Relationships in this page left out of `items` because their source
+    /// symbol no longer loads, e.g. from an index written mid-update
+    pub skipped: usize,
+}
+
+impl<T> RelationshipPage<T> {
+    /// Position just past the last relationship of this page
+    pub fn end(&self) -> usize {
+        self.offset + self.items.len() + self.skipped
+    }
+
+    /// Whether more relationships exist beyond this page
+    pub fn is_truncated(&self) -> bool {
+        self.end() < self.total
+    }
+
+    /// Offset to request for the following page, if any
+    pub fn next_offset(&self) -> Option<usize> {
+        self.is_truncated().then_some(self.end())
+    }
+}
+
+/// Result of a bounded impact traversal
+#[derive(Debug, Clone, Default)]
+pub struct ImpactRadius {
+    /// Impacted symbols in BFS order (closest first)
+    pub symbols: Vec<SymbolId>,
+    /// Hops from the analyzed symbol to each of `symbols`, starting at 1
+    pub depths: Vec<usize>,
+    /// Kind of the relationship each of `symbols` was reached through
+    pub kinds: Vec<RelationKind>,
+    /// Hub symbols whose dependents were counted but not expanded, with their dependent count
+    pub hubs: Vec<(SymbolId, usize)>,
+    /// True when the traversal stopped at the result limit or the query timeout
+    pub truncated: bool,
+    /// True when the traversal stopped because `query.timeout_ms` elapsed
+    pub timed_out: bool,
+}
+
+/// A type implementing or extending another, directly or through a supertype
+#[derive(Debug, Clone)]
+pub struct Implementor {
+    pub symbol: Symbol,
+    /// `Implements` or `Extends`, the edge to `supertype`
+    pub kind: RelationKind,
+    /// Type it implements or extends directly: the queried type, or a type
+    /// that is itself an implementor
+    pub supertype: SymbolId,
+}
+
+/// Symbols embedded alike, see [`IndexFacade::semantic_clusters`]
+#[derive(Debug, Clone)]
+pub struct SymbolCluster {
+    /// Members, most connected first
+    pub symbols: Vec<Symbol>,
+    /// Mean similarity of the linked pairs
+    pub similarity: f32,
+}
+
+/// A caller in a transitive call tree
+#[derive(Debug, Clone)]
+pub struct CallerNode {
+    pub symbol: Symbol,
+    /// 0-based line of the call site, when the parser recorded it
+    pub call_line: Option<u32>,
+    /// Callers of this caller, one level further out
+    pub callers: Vec<CallerNode>,
+    /// Callers of this caller that were not listed (beyond the fan-out limit)
+    pub omitted: usize,
+    /// Already expanded elsewhere in the tree, so its callers are not repeated
+    pub repeated: bool,
+}
+
+/// Result of a bounded transitive caller traversal
+#[derive(Debug, Clone, Default)]
+pub struct CallerTree {
+    /// Direct callers of the root symbol
+    pub callers: Vec<CallerNode>,
+    /// Direct callers that were not listed (beyond the fan-out limit)
+    pub omitted: usize,
+    /// Number of nodes in the tree
+    pub listed: usize,
+    /// True when the traversal stopped at the result limit or the query timeout
+    pub truncated: bool,
+    /// True when the traversal stopped because `query.timeout_ms` elapsed
+    pub timed_out: bool,
+}
+
+/// Depth-first walk over incoming `Calls` relationships
+struct CallerWalk<'a> {
+    facade: &'a IndexFacade,
+    budget: QueryBudget,
+    max_fanout: usize,
+    max_results: usize,
+    expanded: HashSet<SymbolId>,
+    tree: CallerTree,
+}
+
+impl CallerWalk<'_> {
+    /// Callers of `symbol_id` up to `depth` levels out, and how many were left out
+    fn callers(&mut self, symbol_id: SymbolId, depth: usize) -> (Vec<CallerNode>, usize) {
+        let page = self
+            .facade
+            .get_calling_functions_page(symbol_id, 0, self.max_fanout);
+        let mut nodes = Vec::with_capacity(page.items.len());
+
+        for (caller, metadata) in page.items {
+            if self.budget.expired() {
+                self.tree.truncated = true;
+                self.tree.timed_out = true;
+                break;
+            }
+            if self.tree.listed >= self.max_results {
+                self.tree.truncated = true;
+                break;
+            }
+            self.tree.listed += 1;
+
+            // Recursion and shared callers are listed once with their subtree
+            let repeated = !self.expanded.insert(caller.id);
+            let (callers, omitted) = if repeated || depth <= 1 {
+                (Vec::new(), 0)
+            } else {
+                self.callers(caller.id, depth - 1)
+            };
+            nodes.push(CallerNode {
+                symbol: caller,
+                call_line: metadata.and_then(|meta| meta.line),
+                callers,
+                omitted,
+                repeated,
+            });
+        }
+
+        let omitted = page.total.saturating_sub(page.skipped + nodes.len());
+        (nodes, omitted)
+    }
+}
+
 /// IndexFacade - Unified interface for code intelligence operations
 ///
 /// This facade wraps DocumentIndex (for queries) and Pipeline (for indexing),
@@ -75,8 +415,14 @@ pub struct IndexFacade {
     /// Parallel indexing pipeline - used for mutations
     pipeline: Pipeline,
 
-    /// Optional semantic search for doc comment embeddings
-    semantic_search: Option<Arc<Mutex<SimpleSemanticSearch>>>,
+    /// Optional semantic search for doc comment embeddings, set once loaded
+    semantic_search: OnceLock<Option<Arc<Mutex<SimpleSemanticSearch>>>>,
+
+    /// Semantic data to load on first use, see [`Self::defer_semantic_search`]
+    deferred_semantic: Option<PathBuf>,
+
+    /// Cross-encoder for hybrid results, loaded on first use if configured
+    reranker: OnceLock<Option<Arc<Reranker>>>,
 
     /// Optional embedding pool for parallel embedding generation
     embedding_pool: Option<Arc<EmbeddingPool>>,
@@ -89,6 +435,9 @@ pub struct IndexFacade {
 
     /// Base path for index storage
     index_base: PathBuf,
+
+    /// Workspace packages, discovered on first use and again after a re-index
+    packages: OnceLock<Vec<WorkspacePackage>>,
 }
 
 impl IndexFacade {
@@ -113,11 +462,14 @@ impl IndexFacade {
         Ok(Self {
             document_index,
             pipeline,
-            semantic_search: None,
+            semantic_search: OnceLock::from(None),
+            deferred_semantic: None,
+            reranker: OnceLock::new(),
             embedding_pool: None,
             settings,
             indexed_paths: HashSet::new(),
             index_base,
+            packages: OnceLock::new(),
         })
     }
 
@@ -137,11 +489,14 @@ impl IndexFacade {
         Self {
             document_index,
             pipeline,
-            semantic_search,
+            semantic_search: OnceLock::from(semantic_search),
+            deferred_semantic: None,
+            reranker: OnceLock::new(),
             embedding_pool: None,
             settings,
             indexed_paths: HashSet::new(),
             index_base,
+            packages: OnceLock::new(),
         }
     }
 
@@ -155,77 +510,171 @@ impl IndexFacade {
         &self.pipeline
     }
 
+    /// Handle that cancels the indexing run in progress.
+    ///
+    /// Take it before the run starts: runs hold the facade mutably.
+    pub fn indexing_cancel(&self) -> PipelineCancel {
+        self.pipeline.cancellation()
+    }
+
     /// Get a reference to the settings.
     pub fn settings(&self) -> &Arc<Settings> {
         &self.settings
     }
 
+    /// Start a query budget from the `[query]` settings.
+    pub fn query_budget(&self) -> QueryBudget {
+        QueryBudget::from_config(&self.settings.query)
+    }
+
     /// Get the index base path.
     pub fn index_base(&self) -> &Path {
         &self.index_base
     }
 
+    /// Event log the pipeline appends to, when `indexing.event_log` is on
+    pub fn event_log(&self) -> Option<Arc<crate::storage::EventLog>> {
+        self.pipeline.event_log()
+    }
+
     // =========================================================================
     // Semantic Search Management
     // =========================================================================
 
-    /// Enable semantic search with the configured model.
+    /// Enable semantic search with the configured provider and model.
     pub fn enable_semantic_search(&mut self) -> FacadeResult<()> {
-        let semantic_path = self.index_base.join("semantic");
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.index_base);
         std::fs::create_dir_all(&semantic_path)?;
 
-        let model = &self.settings.semantic_search.model;
+        let config = &self.settings.semantic_search;
 
-        let semantic = SimpleSemanticSearch::from_model_name(model)?;
-        self.semantic_search = Some(Arc::new(Mutex::new(semantic)));
+        let semantic = SimpleSemanticSearch::from_config(config)?;
+        self.semantic_search = OnceLock::from(Some(Arc::new(Mutex::new(semantic))));
+        self.deferred_semantic = None;
 
         // Create embedding pool for parallel generation
-        let pool_size = self.settings.semantic_search.embedding_threads;
-        let embedding_model = crate::vector::parse_embedding_model(model)
-            .map_err(|e| IndexError::General(format!("Failed to parse embedding model: {e}")))?;
-        let pool = EmbeddingPool::new(pool_size, embedding_model)?;
+        let pool = EmbeddingPool::from_config(config)?;
         self.embedding_pool = Some(Arc::new(pool));
 
         Ok(())
     }
 
     /// Check if semantic search is enabled.
+    ///
+    /// Deferred semantic data counts as enabled without being loaded.
     pub fn has_semantic_search(&self) -> bool {
-        self.semantic_search.is_some()
+        match self.semantic_search.get() {
+            Some(semantic) => semantic.is_some(),
+            None => self.deferred_semantic.is_some(),
+        }
+    }
+
+    /// Semantic search, loading deferred data on first call
+    fn semantic(&self) -> Option<&Arc<Mutex<SimpleSemanticSearch>>> {
+        self.semantic_search
+            .get_or_init(|| {
+                let path = self.deferred_semantic.as_deref()?;
+                let start = std::time::Instant::now();
+                match SimpleSemanticSearch::load_with_config(path, &self.settings.semantic_search) {
+                    Ok(semantic) => {
+                        tracing::debug!("Loaded deferred semantic search in {:?}", start.elapsed());
+                        Some(Arc::new(Mutex::new(semantic)))
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to load semantic search: {e}");
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// The configured cross-encoder, loaded on first call
+    ///
+    /// A model that fails to load is reported once and leaves hybrid
+    /// results in fused order.
+    fn reranker(&self) -> Option<&Arc<Reranker>> {
+        self.reranker
+            .get_or_init(|| {
+                let name = self.settings.semantic_search.rerank_model.as_str();
+                if name.is_empty() {
+                    return None;
+                }
+                match Reranker::new(name, false) {
+                    Ok(reranker) => Some(Arc::new(reranker)),
+                    Err(e) => {
+                        tracing::warn!("Failed to load rerank model: {e}");
+                        None
+                    }
+                }
+            })
+            .as_ref()
     }
 
     /// Save semantic search data to disk.
+    ///
+    /// Deferred data that was never loaded is unchanged and left as it is.
     pub fn save_semantic_search(&self, path: &Path) -> FacadeResult<()> {
-        if let Some(ref semantic) = self.semantic_search {
+        if let Some(semantic) = self.semantic_search.get().and_then(Option::as_ref) {
             let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
             sem.save(path)?;
         }
         Ok(())
     }
 
+    /// Drop embeddings of symbols that are no longer indexed and rewrite the
+    /// semantic store at `path`.
+    ///
+    /// Returns the number of embeddings dropped.
+    pub fn prune_semantic_search(&self, path: &Path) -> FacadeResult<usize> {
+        let Some(semantic) = self.semantic() else {
+            return Ok(0);
+        };
+        let mut sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
+        // Lookup errors keep the embedding; only confirmed misses are dropped
+        let orphans: Vec<SymbolId> = sem
+            .symbol_ids()
+            .into_iter()
+            .filter(|id| matches!(self.document_index.find_symbol_by_id(*id), Ok(None)))
+            .collect();
+        sem.remove_embeddings(&orphans);
+        sem.save(path)?;
+        Ok(orphans.len())
+    }
+
     /// Load semantic search data from disk.
     ///
     /// Also initializes the embedding pool for incremental updates.
     pub fn load_semantic_search(&mut self, path: &Path) -> FacadeResult<bool> {
+        recover_migration(path);
         if path.join("metadata.json").exists() {
-            match SimpleSemanticSearch::load(path) {
+            match SimpleSemanticSearch::load_with_config(path, &self.settings.semantic_search) {
                 Ok(semantic) => {
-                    self.semantic_search = Some(Arc::new(Mutex::new(semantic)));
-
-                    // Initialize embedding pool for incremental updates (watcher reindexing)
-                    if self.embedding_pool.is_none() {
-                        let model = &self.settings.semantic_search.model;
-                        let pool_size = self.settings.semantic_search.embedding_threads;
-                        if let Ok(embedding_model) = crate::vector::parse_embedding_model(model) {
-                            if let Ok(pool) = EmbeddingPool::new(pool_size, embedding_model) {
-                                self.embedding_pool = Some(Arc::new(pool));
-                                tracing::debug!(
-                                    "Initialized embedding pool for incremental updates"
-                                );
-                            }
+                    let model_id = semantic.provider().model_id().to_string();
+                    self.semantic_search = OnceLock::from(Some(Arc::new(Mutex::new(semantic))));
+                    self.deferred_semantic = None;
+
+                    // Initialize embedding pool for incremental updates (watcher reindexing),
+                    // with the model that built the loaded embeddings
+                    let stale_pool = self
+                        .embedding_pool
+                        .as_ref()
+                        .is_none_or(|pool| pool.model_name() != model_id);
+                    if stale_pool {
+                        let config = &self.settings.semantic_search;
+                        if let Ok(pool) = EmbeddingPool::for_model_id(&model_id, config) {
+                            self.embedding_pool = Some(Arc::new(pool));
+                            tracing::debug!("Initialized embedding pool for incremental updates");
                         }
                     }
 
+                    if let Some((indexed, configured)) = self.semantic_model_mismatch() {
+                        tracing::warn!(
+                            "Semantic index was built with '{indexed}', not the configured \
+                             '{configured}', and keeps using it. Run 'codanna semantic migrate' \
+                             to re-embed with '{configured}'"
+                        );
+                    }
                     return Ok(true);
                 }
                 Err(e) => {
@@ -236,19 +685,280 @@ impl IndexFacade {
         Ok(false)
     }
 
+    /// Model the semantic index was built with and the configured one, when
+    /// they differ
+    pub fn semantic_model_mismatch(&self) -> Option<(String, String)> {
+        let indexed = self.get_semantic_metadata()?.model_name;
+        let configured = crate::vector::configured_model_id(&self.settings.semantic_search).ok()?;
+        (indexed != configured).then_some((indexed, configured))
+    }
+
+    /// Thresholds `codanna semantic calibrate` measured for the model of the
+    /// semantic index
+    pub fn calibrated_thresholds(&self) -> Option<ModelThresholds> {
+        let model_id = self.get_semantic_metadata()?.model_name;
+        self.settings
+            .semantic_search
+            .calibrated
+            .get(&model_id)
+            .copied()
+    }
+
+    /// Texts semantic search embeds for each indexed symbol, as indexing
+    /// would produce them with the current chunking settings
+    ///
+    /// Sources are read again for strategies that embed them; a file that
+    /// cannot be read falls back to signatures and doc comments. With
+    /// `only`, other symbols are skipped.
+    pub fn semantic_embedding_texts(&self, only: Option<&HashSet<SymbolId>>) -> Vec<SymbolTexts> {
+        let mut symbols_texts = Vec::new();
+        for path in self.get_all_indexed_paths() {
+            let path = path.to_string_lossy();
+            let Some(file_id) = self.get_file_id_for_path(&path) else {
+                continue;
+            };
+            let symbols: Vec<Symbol> = self
+                .get_symbols_by_file(file_id)
+                .into_iter()
+                .filter(|symbol| only.is_none_or(|only| only.contains(&symbol.id)))
+                .collect();
+            let Some(language) = symbols.iter().find_map(|symbol| symbol.language_id) else {
+                continue;
+            };
+            let strategy = ChunkingStrategy::for_language(&self.settings, language.as_str());
+            let source = strategy
+                .needs_source()
+                .then(|| self.read_indexed_source(&path))
+                .flatten()
+                .map(|(source, _)| source);
+
+            let mut raw: Vec<RawSymbol> = symbols.iter().map(raw_symbol).collect();
+            chunk_symbols(&mut raw, source.as_deref(), strategy, &self.settings);
+            for (symbol, raw) in symbols.iter().zip(raw) {
+                let texts = embedded_texts(raw);
+                if !texts.is_empty() {
+                    symbols_texts.push(SymbolTexts {
+                        id: symbol.id,
+                        texts,
+                        language: language.as_str().to_string(),
+                    });
+                }
+            }
+        }
+        symbols_texts
+    }
+
+    /// Similarity of changed symbols to their version at `base` and of
+    /// random pairs of symbols, scored with the model of the semantic index
+    ///
+    /// Old versions are read from the git repository of the workspace and
+    /// parsed again. Symbols are matched across versions by file, name and
+    /// kind, and only those whose embedded text changed are scored. At most
+    /// `samples` pairs of each label are scored.
+    pub fn semantic_calibration_scores(
+        &self,
+        base: &str,
+        samples: usize,
+    ) -> FacadeResult<CalibrationScores> {
+        use rand::Rng;
+
+        let model_id = self
+            .get_semantic_metadata()
+            .map(|metadata| metadata.model_name)
+            .ok_or(IndexError::SemanticSearchNotEnabled)?;
+        let semantic = self
+            .semantic()
+            .ok_or(IndexError::SemanticSearchNotEnabled)?;
+        let mut scores = CalibrationScores {
+            model_id,
+            ..Default::default()
+        };
+
+        {
+            let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
+            let ids = sem.symbol_ids();
+            let mut rng = rand::rng();
+            // Bounded so an index of one symbol repeated cannot spin
+            for _ in 0..samples.saturating_mul(4) {
+                if ids.len() < 2 || scores.random.len() >= samples {
+                    break;
+                }
+                let a = ids[rng.random_range(0..ids.len())];
+                let b = ids[rng.random_range(0..ids.len())];
+                if a == b {
+                    continue;
+                }
+                if let (Some(a), Some(b)) = (sem.embedding_of(a), sem.embedding_of(b)) {
+                    scores.random.push(crate::vector::cosine_similarity(&a, &b));
+                }
+            }
+        }
+
+        let root = self
+            .settings
+            .workspace_root
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."));
+        let changed: HashSet<PathBuf> = git_delta::changed_files(&root, base)
+            .map_err(|e| IndexError::General(format!("Failed to compare with '{base}': {e}")))?
+            .into_iter()
+            .filter_map(|path| path.canonicalize().ok())
+            .collect();
+        let pool = match &self.embedding_pool {
+            Some(pool) if pool.model_name() == scores.model_id => pool.clone(),
+            _ => Arc::new(EmbeddingPool::for_model_id(
+                &scores.model_id,
+                &self.settings.semantic_search,
+            )?),
+        };
+        init_parser_cache(self.settings.clone());
+
+        for stored in self.get_all_indexed_paths() {
+            if scores.same.len() >= samples {
+                break;
+            }
+            let Ok(path) = self.settings.resolve_stored_path(&stored).canonicalize() else {
+                continue;
+            };
+            if !changed.contains(&path) {
+                continue;
+            }
+            let Some(old) = git_delta::file_at(&path, base).ok().flatten() else {
+                continue;
+            };
+            let hash = crate::indexing::calculate_hash(&old);
+            let Ok(parsed) = parse_file(FileContent::new(path, old, hash), &self.settings) else {
+                continue;
+            };
+
+            let stored = stored.to_string_lossy();
+            let Some(file_id) = self.get_file_id_for_path(&stored) else {
+                continue;
+            };
+            let symbols = self.get_symbols_by_file(file_id);
+            let Some(language) = symbols.iter().find_map(|symbol| symbol.language_id) else {
+                continue;
+            };
+            let strategy = ChunkingStrategy::for_language(&self.settings, language.as_str());
+            let source = strategy
+                .needs_source()
+                .then(|| self.read_indexed_source(&stored))
+                .flatten()
+                .map(|(source, _)| source);
+            let mut raw: Vec<RawSymbol> = symbols.iter().map(raw_symbol).collect();
+            chunk_symbols(&mut raw, source.as_deref(), strategy, &self.settings);
+
+            // Names defined once per kind identify a symbol across versions
+            let current_names = name_counts(
+                symbols
+                    .iter()
+                    .map(|symbol| (symbol.name.to_string(), symbol.kind)),
+            );
+            let old_names = name_counts(
+                parsed
+                    .raw_symbols
+                    .iter()
+                    .map(|raw| (raw.name.to_string(), raw.kind)),
+            );
+            let old_texts: HashMap<(String, SymbolKind), Vec<String>> = parsed
+                .raw_symbols
+                .into_iter()
+                .map(|raw| ((raw.name.to_string(), raw.kind), embedded_texts(raw)))
+                .collect();
+
+            let mut pairs: Vec<(SymbolId, Vec<String>)> = Vec::new();
+            for (symbol, raw) in symbols.iter().zip(raw) {
+                let key = (symbol.name.to_string(), symbol.kind);
+                if current_names.get(&key) != Some(&1) || old_names.get(&key) != Some(&1) {
+                    continue;
+                }
+                let Some(old) = old_texts.get(&key) else {
+                    continue;
+                };
+                if !old.is_empty() && *old != embedded_texts(raw) {
+                    pairs.push((symbol.id, old.clone()));
+                }
+            }
+            pairs.truncate(samples - scores.same.len());
+
+            let items: Vec<(SymbolId, &str, &str)> = pairs
+                .iter()
+                .flat_map(|(id, texts)| {
+                    texts
+                        .iter()
+                        .map(|text| (*id, text.as_str(), language.as_str()))
+                })
+                .collect();
+            let embedded = merge_chunk_embeddings(pool.embed_parallel(&items));
+            let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
+            for (id, old, _) in embedded {
+                if let Some(current) = sem.embedding_of(id) {
+                    scores
+                        .same
+                        .push(crate::vector::cosine_similarity(&old, &current));
+                }
+            }
+        }
+        Ok(scores)
+    }
+
+    /// Swap the embeddings of a finished migration in for the saved ones
+    ///
+    /// Embeddings of symbols removed while the migration ran are dropped
+    /// first. Incremental updates embed with the new model from then on.
+    /// Returns the number of embeddings installed.
+    pub fn install_semantic_migration(
+        &mut self,
+        mut migration: SemanticMigration,
+    ) -> FacadeResult<usize> {
+        let path = crate::vector::VectorNamespace::Code.dir(&self.index_base);
+        let live = self.document_index.find_symbol_ids(None, None)?;
+        let removed: Vec<SymbolId> = migration
+            .symbol_ids()
+            .into_iter()
+            .filter(|id| !live.contains(id))
+            .collect();
+        migration.remove(&removed);
+
+        let (semantic, pool) = migration.install(&path)?;
+        let count = semantic.embedding_count();
+        self.semantic_search = OnceLock::from(Some(Arc::new(Mutex::new(semantic))));
+        self.deferred_semantic = None;
+        self.embedding_pool = Some(Arc::new(pool));
+        Ok(count)
+    }
+
+    /// Register semantic search data to load on first use.
+    ///
+    /// Loading the embeddings and the model dominates the startup of one-shot
+    /// commands that never search by meaning. No embedding pool is created, so
+    /// a deferred facade is meant for queries rather than re-indexing.
+    pub fn defer_semantic_search(&mut self, path: &Path) -> bool {
+        if !path.join("metadata.json").exists() {
+            return false;
+        }
+        self.semantic_search = OnceLock::new();
+        self.deferred_semantic = Some(path.to_path_buf());
+        true
+    }
+
     /// Get semantic search embedding count.
     pub fn semantic_search_embedding_count(&self) -> usize {
-        self.semantic_search
-            .as_ref()
+        self.semantic()
             .map(|s| s.lock().map(|sem| sem.embedding_count()).unwrap_or(0))
             .unwrap_or(0)
     }
 
     /// Get semantic search metadata.
+    ///
+    /// Read from disk when semantic data is deferred and not loaded yet.
     pub fn get_semantic_metadata(&self) -> Option<crate::semantic::SemanticMetadata> {
-        self.semantic_search
-            .as_ref()
-            .and_then(|s| s.lock().ok().and_then(|sem| sem.metadata().cloned()))
+        match (self.semantic_search.get(), &self.deferred_semantic) {
+            (None, Some(path)) => crate::semantic::SemanticMetadata::load(path).ok(),
+            _ => self
+                .semantic()
+                .and_then(|s| s.lock().ok().and_then(|sem| sem.metadata().cloned())),
+        }
     }
 
     // =========================================================================
@@ -370,6 +1080,41 @@ impl IndexFacade {
         results
     }
 
+    /// Get one page of functions that call a symbol, with metadata.
+    ///
+    /// Use this for hub symbols where the full caller list is too large to return.
+    pub fn get_calling_functions_page(
+        &self,
+        symbol_id: SymbolId,
+        offset: usize,
+        limit: usize,
+    ) -> RelationshipPage<(Symbol, Option<crate::relationship::RelationshipMetadata>)> {
+        let total = self
+            .document_index
+            .count_relationships_to(symbol_id, RelationKind::Calls)
+            .unwrap_or(0);
+        let relationships = self
+            .document_index
+            .get_relationships_to_page(symbol_id, RelationKind::Calls, offset, limit)
+            .unwrap_or_default();
+
+        let edges = relationships.len();
+        let items: Vec<_> = relationships
+            .into_iter()
+            .filter_map(|(from_id, _, rel)| {
+                self.get_symbol(from_id)
+                    .map(|symbol| (symbol, rel.metadata))
+            })
+            .collect();
+
+        RelationshipPage {
+            skipped: edges - items.len(),
+            items,
+            offset,
+            total,
+        }
+    }
+
     /// Get implementations of a trait/interface.
     pub fn get_implementations(&self, trait_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
@@ -386,6 +1131,151 @@ impl IndexFacade {
         symbols
     }
 
+    /// Types implementing or extending `type_id`, transitively, closest first
+    ///
+    /// A class extending an implementor is an implementor too, so the walk
+    /// follows incoming `Implements` and `Extends` edges until it has
+    /// collected `max_results` types or `query.timeout_ms` elapses.
+    pub fn get_implementors(&self, type_id: SymbolId, max_results: usize) -> Vec<Implementor> {
+        let budget = self.query_budget();
+        let mut implementors = Vec::new();
+        let mut visited = HashSet::from([type_id]);
+        let mut queue = std::collections::VecDeque::from([type_id]);
+
+        'walk: while let Some(supertype) = queue.pop_front() {
+            if budget.expired() {
+                break;
+            }
+            for kind in [RelationKind::Implements, RelationKind::Extends] {
+                let relationships = self
+                    .document_index
+                    .get_relationships_to(supertype, kind)
+                    .unwrap_or_default();
+                for (from_id, _, _) in relationships {
+                    if !visited.insert(from_id) {
+                        continue;
+                    }
+                    let Some(symbol) = self.get_symbol(from_id) else {
+                        continue;
+                    };
+                    implementors.push(Implementor {
+                        symbol,
+                        kind,
+                        supertype,
+                    });
+                    if implementors.len() >= max_results {
+                        break 'walk;
+                    }
+                    queue.push_back(from_id);
+                }
+            }
+        }
+        implementors
+    }
+
+    /// Methods implementing or overriding `method_id`
+    ///
+    /// These are the methods of the same name defined by implementors of the
+    /// types that define `method_id`, plus methods linked to it directly by
+    /// an `Implements` edge (such as handlers of a gRPC method).
+    pub fn get_overrides(&self, method_id: SymbolId, max_results: usize) -> Vec<Symbol> {
+        let Some(method) = self.get_symbol(method_id) else {
+            return Vec::new();
+        };
+        let mut seen = HashSet::from([method_id]);
+        let mut overrides: Vec<Symbol> = self
+            .document_index
+            .get_relationships_to(method_id, RelationKind::Implements)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(from_id, _, _)| seen.insert(*from_id))
+            .filter_map(|(from_id, _, _)| self.get_symbol(from_id))
+            .collect();
+
+        let owners = self
+            .document_index
+            .get_relationships_to(method_id, RelationKind::Defines)
+            .unwrap_or_default();
+        for (owner_id, _, _) in owners {
+            for implementor in self.get_implementors(owner_id, max_results) {
+                let members = self
+                    .document_index
+                    .get_relationships_from(implementor.symbol.id, RelationKind::Defines)
+                    .unwrap_or_default();
+                for (_, member_id, _) in members {
+                    if !seen.insert(member_id) {
+                        continue;
+                    }
+                    match self.get_symbol(member_id) {
+                        Some(member) if member.name == method.name => overrides.push(member),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        overrides.truncate(max_results);
+        overrides
+    }
+
+    /// Whether any indexed symbol calls, uses, implements, extends or
+    /// references `symbol_id`
+    pub fn has_incoming_references(&self, symbol_id: SymbolId) -> bool {
+        const REFERENCE_KINDS: [RelationKind; 5] = [
+            RelationKind::Calls,
+            RelationKind::Uses,
+            RelationKind::Implements,
+            RelationKind::Extends,
+            RelationKind::References,
+        ];
+        REFERENCE_KINDS.iter().any(|kind| {
+            self.document_index
+                .count_relationships_to(symbol_id, *kind)
+                .unwrap_or(0)
+                > 0
+        })
+    }
+
+    /// Whether `method` implements or overrides a method of a type its owner
+    /// implements or extends, and so is called through that type
+    ///
+    /// Looks one supertype up, plus methods linked by an `Implements` edge.
+    pub fn overrides_inherited_method(&self, method: &Symbol) -> bool {
+        let linked = self
+            .document_index
+            .get_relationships_from(method.id, RelationKind::Implements)
+            .unwrap_or_default();
+        if !linked.is_empty() {
+            return true;
+        }
+
+        let owners = self
+            .document_index
+            .get_relationships_to(method.id, RelationKind::Defines)
+            .unwrap_or_default();
+        for (owner_id, _, _) in owners {
+            for kind in [RelationKind::Implements, RelationKind::Extends] {
+                let parents = self
+                    .document_index
+                    .get_relationships_from(owner_id, kind)
+                    .unwrap_or_default();
+                for (_, parent_id, _) in parents {
+                    let members = self
+                        .document_index
+                        .get_relationships_from(parent_id, RelationKind::Defines)
+                        .unwrap_or_default();
+                    let inherited = members.iter().any(|(_, member_id, _)| {
+                        self.get_symbol(*member_id)
+                            .is_some_and(|member| member.name == method.name)
+                    });
+                    if inherited {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
     /// Get traits implemented by a type.
     pub fn get_implemented_traits(&self, type_id: SymbolId) -> Vec<Symbol> {
         let relationships = self
@@ -643,43 +1533,138 @@ impl IndexFacade {
     }
 
     /// Get impact radius (BFS traversal of dependents).
+    ///
+    /// Bounded by `mcp.max_edges_per_symbol` and `mcp.max_impact_results`;
+    /// see [`Self::get_impact_radius_bounded`] for hub handling.
     pub fn get_impact_radius(
         &self,
         symbol_id: SymbolId,
         max_depth: Option<usize>,
     ) -> Vec<SymbolId> {
-        let max_depth = max_depth.unwrap_or(2);
-        let mut visited = HashSet::new();
-        let mut queue = std::collections::VecDeque::new();
+        self.get_impact_radius_bounded(
+            symbol_id,
+            max_depth,
+            self.settings.mcp.max_edges_per_symbol,
+            self.settings.mcp.max_impact_results,
+        )
+        .symbols
+    }
 
-        queue.push_back((symbol_id, 0usize));
+    /// Get impact radius with limits for giant graphs.
+    ///
+    /// A symbol with more than `max_fanout` dependents dominates everything
+    /// reachable through it, so expanding it adds noise rather than insight.
+    /// Such hubs are reported with their dependent count and not traversed
+    /// further. The starting symbol is always expanded, up to `max_fanout`
+    /// edges per relationship kind. Traversal stops once `max_results`
+    /// symbols have been collected or `query.timeout_ms` elapses.
+    pub fn get_impact_radius_bounded(
+        &self,
+        symbol_id: SymbolId,
+        max_depth: Option<usize>,
+        max_fanout: usize,
+        max_results: usize,
+    ) -> ImpactRadius {
+        const DEPENDENT_KINDS: [RelationKind; 4] = [
+            RelationKind::Calls,
+            RelationKind::Uses,
+            RelationKind::Implements,
+            RelationKind::Extends,
+        ];
+
+        let max_depth = max_depth.unwrap_or(2);
+        let mut result = ImpactRadius::default();
+        let mut visited = HashSet::new();
+        let mut queue = std::collections::VecDeque::new();
+
+        queue.push_back((symbol_id, 0usize));
         visited.insert(symbol_id);
 
-        while let Some((current_id, depth)) = queue.pop_front() {
+        let budget = self.query_budget();
+
+        'traversal: while let Some((current_id, depth)) = queue.pop_front() {
             if depth >= max_depth {
                 continue;
             }
+            if budget.expired() {
+                result.truncated = true;
+                result.timed_out = true;
+                break;
+            }
 
-            // Get dependents via Calls, Uses, Implements, Extends
-            for kind in &[
-                RelationKind::Calls,
-                RelationKind::Uses,
-                RelationKind::Implements,
-                RelationKind::Extends,
-            ] {
-                if let Ok(rels) = self.document_index.get_relationships_to(current_id, *kind) {
-                    for (from_id, _, _) in rels {
-                        if visited.insert(from_id) {
-                            queue.push_back((from_id, depth + 1));
+            // Count before loading so hubs never materialize their edge lists
+            let counts: Vec<(RelationKind, usize)> = DEPENDENT_KINDS
+                .iter()
+                .map(|kind| {
+                    let count = self
+                        .document_index
+                        .count_relationships_to(current_id, *kind)
+                        .unwrap_or(0);
+                    (*kind, count)
+                })
+                .collect();
+            let dependents: usize = counts.iter().map(|(_, count)| count).sum();
+
+            if dependents > max_fanout {
+                result.hubs.push((current_id, dependents));
+                if current_id != symbol_id {
+                    continue;
+                }
+            }
+
+            for (kind, count) in counts {
+                if count == 0 {
+                    continue;
+                }
+                let rels = self
+                    .document_index
+                    .get_relationships_to_page(current_id, kind, 0, max_fanout)
+                    .unwrap_or_default();
+                for (from_id, _, _) in rels {
+                    if visited.insert(from_id) {
+                        result.symbols.push(from_id);
+                        result.depths.push(depth + 1);
+                        result.kinds.push(kind);
+                        if result.symbols.len() >= max_results {
+                            result.truncated = true;
+                            break 'traversal;
                         }
+                        queue.push_back((from_id, depth + 1));
                     }
                 }
             }
         }
 
-        // Remove the initial symbol from results
-        visited.remove(&symbol_id);
-        visited.into_iter().collect()
+        result
+    }
+
+    /// Tree of the functions calling a symbol, transitively up to `max_depth` levels
+    ///
+    /// Each symbol lists at most `max_fanout` callers. A caller reached a
+    /// second time, through recursion or from another branch, is listed
+    /// but not expanded again. Traversal stops once `max_results` callers
+    /// have been listed or `query.timeout_ms` elapses.
+    pub fn get_caller_tree(
+        &self,
+        symbol_id: SymbolId,
+        max_depth: usize,
+        max_fanout: usize,
+        max_results: usize,
+    ) -> CallerTree {
+        let mut walk = CallerWalk {
+            facade: self,
+            budget: self.query_budget(),
+            max_fanout,
+            max_results,
+            expanded: HashSet::from([symbol_id]),
+            tree: CallerTree::default(),
+        };
+        let (callers, omitted) = walk.callers(symbol_id, max_depth.max(1));
+        CallerTree {
+            callers,
+            omitted,
+            ..walk.tree
+        }
     }
 
     // =========================================================================
@@ -700,6 +1685,127 @@ impl IndexFacade {
             .map_err(Into::into)
     }
 
+    /// Full-text search limited to a repository, workspace package and/or shard.
+    ///
+    /// With an empty scope this is [`search`](Self::search). The shard is
+    /// filtered inside Tantivy; for a repository or package more candidates
+    /// are fetched until `limit` of them fall inside the scope or the index
+    /// runs out.
+    pub fn search_scoped(
+        &self,
+        query: &str,
+        limit: usize,
+        kind_filter: Option<SymbolKind>,
+        module_filter: Option<&str>,
+        language_filter: Option<&str>,
+        scope: &SearchScope,
+    ) -> FacadeResult<Vec<SearchResult>> {
+        if scope.repository.is_none() && scope.package.is_none() {
+            return self
+                .document_index
+                .search_in_shard(
+                    query,
+                    limit,
+                    kind_filter,
+                    module_filter,
+                    language_filter,
+                    scope.shard.as_deref(),
+                )
+                .map_err(Into::into);
+        }
+        if let Some(repository) = scope.repository.as_deref() {
+            self.check_repository(repository)?;
+        }
+        let packages = self.workspace_packages();
+        let package_root = match scope.package.as_deref() {
+            Some(name) => Some(find_package(packages, name)?.root.clone()),
+            None => None,
+        };
+
+        let in_scope = |result: &SearchResult| {
+            let path = Path::new(&result.file_path);
+            let in_repository = scope.repository.as_deref().is_none_or(|repository| {
+                self.settings.repository_for(path).as_deref() == Some(repository)
+            });
+            let in_package = package_root.as_ref().is_none_or(|root| {
+                let absolute = self.settings.resolve_stored_path(path);
+                package_for(packages, &absolute).is_some_and(|package| &package.root == root)
+            });
+            in_repository && in_package
+        };
+
+        let mut candidates = limit.max(1);
+        loop {
+            let results = self.document_index.search_in_shard(
+                query,
+                candidates,
+                kind_filter,
+                module_filter,
+                language_filter,
+                scope.shard.as_deref(),
+            )?;
+            let exhausted = results.len() < candidates;
+            let matching: Vec<SearchResult> =
+                results.into_iter().filter(in_scope).take(limit).collect();
+            if matching.len() == limit || exhausted || candidates >= MAX_SCOPED_CANDIDATES {
+                return Ok(matching);
+            }
+            candidates = (candidates * 4).min(MAX_SCOPED_CANDIDATES);
+        }
+    }
+
+    /// Error listing the configured repositories unless `repository` is one
+    fn check_repository(&self, repository: &str) -> FacadeResult<()> {
+        if self.settings.repositories.contains_key(repository) {
+            return Ok(());
+        }
+        let mut known: Vec<&str> = self
+            .settings
+            .repositories
+            .keys()
+            .map(String::as_str)
+            .collect();
+        known.sort();
+        Err(IndexError::ConfigError {
+            reason: format!(
+                "unknown repository '{repository}' (configured: {})",
+                known.join(", ")
+            ),
+        })
+    }
+
+    /// Packages of the Cargo, Node and Go workspaces at the workspace root
+    /// and the indexed directories, sorted by root
+    ///
+    /// Manifests are read on the first call and after every re-index, not on
+    /// each scoped search.
+    pub fn workspace_packages(&self) -> &[WorkspacePackage] {
+        self.packages
+            .get_or_init(|| self.discover_workspace_packages())
+    }
+
+    fn discover_workspace_packages(&self) -> Vec<WorkspacePackage> {
+        let mut roots = self.settings.get_indexed_paths();
+        if let Some(root) = &self.settings.workspace_root {
+            roots.push(root.canonicalize().unwrap_or_else(|_| root.clone()));
+        }
+        roots.sort();
+        roots.dedup();
+
+        let mut packages: Vec<WorkspacePackage> = roots
+            .iter()
+            .flat_map(|root| discover_packages(root))
+            .collect();
+        packages.sort_by(|a, b| a.root.cmp(&b.root));
+        packages.dedup_by(|a, b| a.root == b.root);
+        packages
+    }
+
+    /// Workspace package called `name`, see [`workspace_packages`](Self::workspace_packages)
+    pub fn workspace_package(&self, name: &str) -> FacadeResult<WorkspacePackage> {
+        find_package(self.workspace_packages(), name).cloned()
+    }
+
     /// Semantic search using doc comment embeddings.
     pub fn semantic_search_docs(
         &self,
@@ -717,8 +1823,7 @@ impl IndexFacade {
         language_filter: Option<&str>,
     ) -> FacadeResult<Vec<(Symbol, f32)>> {
         let semantic = self
-            .semantic_search
-            .as_ref()
+            .semantic()
             .ok_or(IndexError::SemanticSearchNotEnabled)?;
 
         let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
@@ -734,6 +1839,204 @@ impl IndexFacade {
         Ok(symbols)
     }
 
+    /// Symbols documented most like `symbol`, best first, without it
+    ///
+    /// Compares the symbol's doc embedding with all others; a symbol without
+    /// one is compared by its signature instead.
+    pub fn semantic_search_similar_to(
+        &self,
+        symbol: &Symbol,
+        limit: usize,
+        language_filter: Option<&str>,
+    ) -> FacadeResult<Vec<(Symbol, f32)>> {
+        let semantic = self
+            .semantic()
+            .ok_or(IndexError::SemanticSearchNotEnabled)?;
+
+        let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
+        let results = match sem.embedding_of(symbol.id) {
+            Some(embedding) => sem.search_by_embedding(&embedding, limit + 1, language_filter),
+            None => {
+                let text = symbol.signature.as_deref().unwrap_or(&symbol.name);
+                sem.search_with_language(text, limit + 1, language_filter)?
+            }
+        };
+
+        Ok(results
+            .into_iter()
+            .filter(|(id, _)| *id != symbol.id)
+            .filter_map(|(id, score)| Some((self.get_symbol(id)?, score)))
+            .take(limit)
+            .collect())
+    }
+
+    /// Groups of symbols whose embeddings are at least `threshold` similar,
+    /// largest first, as candidates for duplicated logic
+    ///
+    /// Only symbols passing `filter` are compared, which keeps the pairwise
+    /// comparison small when scoped to a directory.
+    pub fn semantic_clusters(
+        &self,
+        threshold: f32,
+        min_size: usize,
+        filter: &SemanticFilter,
+    ) -> FacadeResult<Vec<SymbolCluster>> {
+        if let Some(repository) = filter.repository.as_deref() {
+            self.check_repository(repository)?;
+        }
+        let semantic = self
+            .semantic()
+            .ok_or(IndexError::SemanticSearchNotEnabled)?;
+
+        let mut symbols: HashMap<SymbolId, Symbol> = HashMap::new();
+        let mut embeddings = Vec::new();
+        {
+            let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
+            for id in sem.symbol_ids() {
+                let Some(symbol) = self.get_symbol(id) else {
+                    continue;
+                };
+                let language = symbol.language_id.map(|language| language.as_str());
+                if filter
+                    .language
+                    .as_deref()
+                    .is_some_and(|wanted| language != Some(wanted))
+                    || !filter.matches(&self.settings, &symbol)
+                {
+                    continue;
+                }
+                if let Some(embedding) = sem.embedding_of(id) {
+                    embeddings.push((id, embedding));
+                    symbols.insert(id, symbol);
+                }
+            }
+        }
+
+        Ok(cluster_embeddings(embeddings, threshold, min_size)
+            .into_iter()
+            .map(|cluster| SymbolCluster {
+                symbols: cluster
+                    .members
+                    .iter()
+                    .filter_map(|id| symbols.remove(id))
+                    .collect(),
+                similarity: cluster.similarity,
+            })
+            .collect())
+    }
+
+    /// Functions and methods of different languages that likely implement
+    /// the same API, see [`link_across_languages`]
+    ///
+    /// Only symbols passing `filter` are considered. Routes are looked for in
+    /// the doc comment, the signature and the first lines of the source,
+    /// including the attributes or decorators just above it.
+    pub fn cross_language_links(
+        &self,
+        threshold: f32,
+        filter: &SemanticFilter,
+    ) -> FacadeResult<Vec<CrossLanguageLink>> {
+        if let Some(repository) = filter.repository.as_deref() {
+            self.check_repository(repository)?;
+        }
+        let semantic = self
+            .semantic()
+            .ok_or(IndexError::SemanticSearchNotEnabled)?;
+
+        let mut symbols = Vec::new();
+        {
+            let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
+            for id in sem.symbol_ids() {
+                let Some(symbol) = self.get_symbol(id) else {
+                    continue;
+                };
+                let Some(language) = symbol.language_id else {
+                    continue;
+                };
+                if !matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+                    || filter
+                        .language
+                        .as_deref()
+                        .is_some_and(|wanted| language.as_str() != wanted)
+                    || !filter.matches(&self.settings, &symbol)
+                {
+                    continue;
+                }
+                if let Some(embedding) = sem.embedding_of(id) {
+                    symbols.push((symbol, language, embedding));
+                }
+            }
+        }
+        if symbols
+            .iter()
+            .all(|(_, language, _)| *language == symbols[0].1)
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut sources: HashMap<Arc<str>, Option<String>> = HashMap::new();
+        let candidates: Vec<LinkCandidate> = symbols
+            .into_iter()
+            .map(|(symbol, language, embedding)| {
+                let source = sources
+                    .entry(symbol.file_path.clone())
+                    .or_insert_with(|| {
+                        self.read_indexed_source(&symbol.file_path)
+                            .map(|(source, _)| source)
+                    })
+                    .as_deref();
+                LinkCandidate {
+                    id: symbol.id,
+                    language: language.as_str().to_string(),
+                    name: symbol.name.to_string(),
+                    route: route_near(&symbol, source),
+                    embedding,
+                }
+            })
+            .collect();
+        Ok(link_across_languages(&candidates, threshold))
+    }
+
+    /// Replace the stored cross-language links with `links`, as
+    /// [`RelationKind::Mirrors`] in both directions weighted by confidence
+    ///
+    /// Returns the number of symbol pairs stored.
+    pub fn store_cross_language_links(&self, links: &[CrossLanguageLink]) -> FacadeResult<usize> {
+        let edges: Vec<(SymbolId, SymbolId, Relationship)> = links
+            .iter()
+            .flat_map(|link| {
+                let relationship = Relationship::new(RelationKind::Mirrors)
+                    .with_weight(link.confidence)
+                    .with_metadata(
+                        crate::relationship::RelationshipMetadata::new()
+                            .with_context(link.evidence.to_string()),
+                    );
+                [
+                    (link.from, link.to, relationship.clone()),
+                    (link.to, link.from, relationship),
+                ]
+            })
+            .collect();
+        self.document_index
+            .replace_relationships_of_kind(RelationKind::Mirrors, &edges)?;
+        Ok(links.len())
+    }
+
+    /// Symbols of other languages linked to `symbol_id` by
+    /// [`store_cross_language_links`](Self::store_cross_language_links),
+    /// most confident first
+    pub fn get_mirrors(&self, symbol_id: SymbolId) -> Vec<(Symbol, Relationship)> {
+        let mut mirrors: Vec<(Symbol, Relationship)> = self
+            .document_index
+            .get_relationships_from(symbol_id, RelationKind::Mirrors)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(_, to_id, relationship)| Some((self.get_symbol(to_id)?, relationship)))
+            .collect();
+        mirrors.sort_by(|a, b| b.1.weight.total_cmp(&a.1.weight));
+        mirrors
+    }
+
     /// Semantic search with score threshold.
     pub fn semantic_search_docs_with_threshold(
         &self,
@@ -760,6 +2063,273 @@ impl IndexFacade {
             .collect())
     }
 
+    /// Full-text search returning only hits that pass `filter`, best first
+    ///
+    /// Kind and language narrow the search itself; more candidates are
+    /// fetched when path, visibility or repository are checked on the hits.
+    pub fn text_search_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &SemanticFilter,
+    ) -> FacadeResult<Vec<(Symbol, f32)>> {
+        if let Some(repository) = filter.repository.as_deref() {
+            self.check_repository(repository)?;
+        }
+        let checks_hits =
+            filter.path.is_some() || filter.visibility.is_some() || filter.repository.is_some();
+        let candidates = if checks_hits {
+            MAX_SCOPED_CANDIDATES.max(limit)
+        } else {
+            limit
+        };
+        let hits = self.search(
+            query,
+            candidates,
+            filter.kind,
+            None,
+            filter.language.as_deref(),
+        )?;
+        Ok(hits
+            .into_iter()
+            .filter_map(|hit| Some((self.get_symbol(hit.symbol_id)?, hit.score)))
+            .filter(|(symbol, _)| filter.matches(&self.settings, symbol))
+            .take(limit)
+            .collect())
+    }
+
+    /// Semantic search returning only hits that pass `filter`, best first
+    ///
+    /// The language, kind, path and repository narrow the embeddings before
+    /// they are scored, see [`semantic_scope`](Self::semantic_scope). Only
+    /// visibility is checked on the hits: more candidates are fetched until
+    /// `limit` of them pass or the index runs out, as in
+    /// [`search_scoped`](Self::search_scoped). Without `threshold`, the
+    /// search threshold calibrated for the model applies, if there is one.
+    pub fn semantic_search_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        threshold: Option<f32>,
+        filter: &SemanticFilter,
+    ) -> FacadeResult<Vec<(Symbol, f32)>> {
+        if let Some(repository) = filter.repository.as_deref() {
+            self.check_repository(repository)?;
+        }
+        let semantic = self
+            .semantic()
+            .ok_or(IndexError::SemanticSearchNotEnabled)?;
+        let language = filter.language.as_deref();
+        let threshold = threshold
+            .or_else(|| self.calibrated_thresholds().map(|t| t.search))
+            .unwrap_or(f32::MIN);
+        let scope = self.semantic_scope(filter)?;
+        if scope.as_ref().is_some_and(HashSet::is_empty) {
+            return Ok(Vec::new());
+        }
+
+        let mut candidates = limit.max(1);
+        loop {
+            let results = {
+                let sem = semantic.lock().map_err(|_| IndexError::lock_error())?;
+                sem.search_in_scope(query, candidates, language, scope.as_ref())?
+            };
+            let results: Vec<(Symbol, f32)> = results
+                .into_iter()
+                .filter_map(|(id, score)| Some((self.get_symbol(id)?, score)))
+                .collect();
+            let exhausted = results.len() < candidates || filter.visibility.is_none();
+            let matching: Vec<(Symbol, f32)> = results
+                .into_iter()
+                .filter(|(symbol, score)| {
+                    *score >= threshold && filter.matches(&self.settings, symbol)
+                })
+                .take(limit)
+                .collect();
+            if matching.len() == limit || exhausted || candidates >= MAX_SCOPED_CANDIDATES {
+                return Ok(matching);
+            }
+            candidates = (candidates * 4).min(MAX_SCOPED_CANDIDATES);
+        }
+    }
+
+    /// Symbols of the kind, files and repository `filter` asks for, looked
+    /// up in the document index so semantic search scores only them
+    ///
+    /// `None` when none of those are filtered.
+    fn semantic_scope(&self, filter: &SemanticFilter) -> FacadeResult<Option<HashSet<SymbolId>>> {
+        if !filter.scopes_symbols() {
+            return Ok(None);
+        }
+        let files: Option<Vec<String>> = (filter.path.is_some() || filter.repository.is_some())
+            .then(|| {
+                self.get_all_indexed_paths()
+                    .into_iter()
+                    .filter(|path| {
+                        filter.repository.as_deref().is_none_or(|repository| {
+                            self.settings.repository_for(path).as_deref() == Some(repository)
+                        })
+                    })
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .filter(|path| filter.path.as_ref().is_none_or(|glob| glob.matches(path)))
+                    .collect()
+            });
+        if files.as_ref().is_some_and(Vec::is_empty) {
+            return Ok(Some(HashSet::new()));
+        }
+        let scope = self
+            .document_index
+            .find_symbol_ids(filter.kind, files.as_deref())?;
+        tracing::debug!(
+            target: "semantic",
+            "filter scoped search to {} symbols",
+            scope.len()
+        );
+        Ok(Some(scope))
+    }
+
+    /// Full-text (BM25) and semantic search fused by reciprocal rank
+    ///
+    /// Both searches run in parallel and are filtered alike; `threshold`
+    /// only applies to semantic hits. A symbol named in the query is found
+    /// even when its documentation does not embed close to it. Scores are
+    /// fused ranks from 0 to 1, not similarities. With a rerank model
+    /// configured, the best `rerank_candidates` are ordered by the
+    /// cross-encoder instead and scored by its relevance.
+    ///
+    /// With `query_expansion`, identifiers in the query are split and
+    /// abbreviations spelled out first, see [`expand_query`]: the semantic
+    /// search embeds the expanded text, and full-text hits of the query and
+    /// its identifier variants are fused into one ranking.
+    pub fn hybrid_search_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        threshold: Option<f32>,
+        filter: &SemanticFilter,
+    ) -> FacadeResult<Vec<(Symbol, f32)>> {
+        let reranker = self.reranker();
+        let rerank_candidates = self.settings.semantic_search.rerank_candidates;
+        let mut candidates = limit.max(1) * HYBRID_CANDIDATE_FACTOR;
+        if reranker.is_some() {
+            candidates = candidates.max(rerank_candidates);
+        }
+        let (semantic_query, lexical_queries) = if self.settings.semantic_search.query_expansion {
+            let expanded = expand_query(query);
+            (expanded.semantic_text(), expanded.lexical_queries())
+        } else {
+            (query.to_string(), vec![query.to_string()])
+        };
+        tracing::debug!(
+            target: "semantic",
+            "hybrid query '{semantic_query}', full-text queries {lexical_queries:?}"
+        );
+
+        let (semantic, lexical) = rayon::join(
+            || self.semantic_search_filtered(&semantic_query, candidates, threshold, filter),
+            || -> FacadeResult<Vec<Vec<SymbolId>>> {
+                lexical_queries
+                    .iter()
+                    .map(|lexical_query| {
+                        let hits = self.search(
+                            lexical_query,
+                            candidates,
+                            filter.kind,
+                            None,
+                            filter.language.as_deref(),
+                        )?;
+                        Ok(hits.into_iter().map(|hit| hit.symbol_id).collect())
+                    })
+                    .collect()
+            },
+        );
+        let semantic = semantic?;
+        let rankings = lexical?;
+        let rankings: Vec<&[SymbolId]> = rankings.iter().map(Vec::as_slice).collect();
+        let lexical: Vec<Symbol> = reciprocal_rank_fusion(&rankings, RRF_K)
+            .into_iter()
+            .filter_map(|(id, _)| self.get_symbol(id))
+            .filter(|symbol| filter.matches(&self.settings, symbol))
+            .take(candidates)
+            .collect();
+
+        let semantic_ids: Vec<SymbolId> = semantic.iter().map(|(symbol, _)| symbol.id).collect();
+        let lexical_ids: Vec<SymbolId> = lexical.iter().map(|symbol| symbol.id).collect();
+        let mut symbols: HashMap<SymbolId, Symbol> = lexical
+            .into_iter()
+            .chain(semantic.into_iter().map(|(symbol, _)| symbol))
+            .map(|symbol| (symbol.id, symbol))
+            .collect();
+
+        let fused = reciprocal_rank_fusion(&[&lexical_ids[..], &semantic_ids[..]], RRF_K);
+        let mut results: Vec<(Symbol, f32)> = fused
+            .into_iter()
+            .filter_map(|(id, score)| Some((symbols.remove(&id)?, score)))
+            .collect();
+        if let Some(reranker) = reranker {
+            reranker.rerank(query, &mut results, rerank_candidates)?;
+        }
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Symbols and document chunks answering `query`, in one ranking
+    ///
+    /// `documents` are the document store's hits for the same query; they
+    /// are merged with the semantic hits of doc comments matching `filter`.
+    /// Without semantic search only the documents are ranked.
+    pub fn knowledge_search(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &SemanticFilter,
+        documents: Vec<crate::documents::SearchResult>,
+    ) -> FacadeResult<Vec<KnowledgeHit>> {
+        let code = if self.has_semantic_search() {
+            self.semantic_search_filtered(query, limit, None, filter)?
+        } else {
+            Vec::new()
+        };
+        // The document store always embeds with the configured model
+        let comparable = self.semantic_model_mismatch().is_none();
+        Ok(fuse_knowledge(code, documents, comparable, limit))
+    }
+
+    /// First `max_lines` lines of a symbol's source, read from disk
+    ///
+    /// `None` for files inside archives and files that changed or vanished
+    /// since they were indexed.
+    pub fn symbol_snippet(&self, symbol: &Symbol, max_lines: usize) -> Option<String> {
+        let path = Path::new(&*symbol.file_path);
+        if crate::indexing::archive::is_virtual(path) {
+            return None;
+        }
+        let source = std::fs::read_to_string(self.settings.resolve_stored_path(path)).ok()?;
+        let span = (symbol.range.end_line - symbol.range.start_line) as usize + 1;
+        let lines: Vec<&str> = source
+            .lines()
+            .skip(symbol.range.start_line as usize)
+            .take(span.min(max_lines))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Text of an indexed file as it is now, on disk or inside an archive,
+    /// and whether it changed since it was indexed
+    pub fn read_indexed_source(&self, path: &str) -> Option<(String, bool)> {
+        let text = crate::indexing::archive::read_source(
+            &self.settings.resolve_stored_path(Path::new(path)),
+        )
+        .ok()?;
+        let changed = self
+            .document_index
+            .get_file_info(path)
+            .ok()
+            .flatten()
+            .is_some_and(|(_, hash, _)| hash != crate::indexing::calculate_hash(&text));
+        Some((text, changed))
+    }
+
     // =========================================================================
     // File Operations
     // =========================================================================
@@ -780,6 +2350,158 @@ impl IndexFacade {
         self.document_index.get_file_path(file_id).ok().flatten()
     }
 
+    /// Find an indexed file from a path as a user or agent wrote it
+    ///
+    /// Accepts the path as stored, an absolute path inside the workspace, or
+    /// a path suffix such as `parsing/parser.rs` that matches a single file.
+    /// Returns the file and its stored path.
+    pub fn resolve_indexed_file(&self, path: &str) -> Option<(FileId, String)> {
+        if let Some(file_id) = self.get_file_id_for_path(path) {
+            return Some((file_id, path.to_string()));
+        }
+        let absolute = self.settings.resolve_stored_path(Path::new(path));
+        if let Some(root) = &self.settings.workspace_root {
+            let root = root.canonicalize().unwrap_or_else(|_| root.clone());
+            let relative = absolute.strip_prefix(&root).ok().and_then(Path::to_str);
+            if let Some(file_id) = relative.and_then(|rel| self.get_file_id_for_path(rel)) {
+                return Some((file_id, relative?.to_string()));
+            }
+        }
+
+        let suffix = Path::new(path.trim_start_matches("./"));
+        let mut matches = self
+            .get_all_indexed_paths()
+            .into_iter()
+            .filter(|indexed| indexed.ends_with(suffix));
+        let stored = matches.next()?.to_string_lossy().into_owned();
+        if matches.next().is_some() {
+            return None;
+        }
+        Some((self.get_file_id_for_path(&stored)?, stored))
+    }
+
+    /// Symbols of an indexed file nested by range, with the file's stored path
+    pub fn get_file_outline(&self, path: &str) -> Option<(String, Vec<OutlineNode>)> {
+        let (file_id, stored) = self.resolve_indexed_file(path)?;
+        Some((stored, outline::build(self.get_symbols_by_file(file_id))))
+    }
+
+    /// Indexed files at `path`: the file itself, or every file under it
+    /// when it is a directory, as stored paths in sorted order
+    pub fn indexed_files_under(&self, path: &str) -> Vec<String> {
+        if let Some((_, stored)) = self.resolve_indexed_file(path) {
+            return vec![stored];
+        }
+        let prefix = Path::new(path.trim_start_matches("./"));
+        let absolute = self.settings.resolve_stored_path(prefix);
+        let mut files: Vec<String> = self
+            .get_all_indexed_paths()
+            .into_iter()
+            .filter(|indexed| {
+                indexed.starts_with(prefix)
+                    || self
+                        .settings
+                        .resolve_stored_path(indexed)
+                        .starts_with(&absolute)
+            })
+            .map(|indexed| indexed.to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// File-level dependency graph of the files at `path`
+    ///
+    /// Walks up to `max_depth` hops from those files in `direction`, keeping
+    /// at most `max_nodes` files. Stops early once `query.timeout_ms`
+    /// elapses; see [`dependency_graph::build`].
+    pub fn get_dependency_graph(
+        &self,
+        path: &str,
+        direction: GraphDirection,
+        max_depth: u32,
+        max_nodes: usize,
+    ) -> DependencyGraph {
+        let budget = self.query_budget();
+        let mut symbol_files: HashMap<SymbolId, Option<String>> = HashMap::new();
+        let mut file_of = |id: SymbolId| -> Option<String> {
+            symbol_files
+                .entry(id)
+                .or_insert_with(|| self.get_symbol(id).map(|s| s.file_path.to_string()))
+                .clone()
+        };
+
+        dependency_graph::build(
+            self.indexed_files_under(path),
+            direction,
+            max_depth,
+            max_nodes,
+            |file| {
+                let mut deps = FileDependencies::default();
+                let Some(file_id) = self.get_file_id_for_path(file) else {
+                    return deps;
+                };
+                deps.imports = self
+                    .document_index
+                    .get_imports_for_file(file_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|import| import.path)
+                    .collect();
+                deps.imports.sort();
+                deps.imports.dedup();
+
+                let symbols = self.get_symbols_by_file(file_id);
+                deps.symbols = symbols.len();
+                let outgoing = direction != GraphDirection::Dependents;
+                let incoming = direction != GraphDirection::Dependencies;
+                for symbol in &symbols {
+                    for kind in DEPENDENCY_KINDS {
+                        let outgoing = outgoing
+                            .then(|| self.document_index.get_relationships_from(symbol.id, kind))
+                            .and_then(Result::ok)
+                            .unwrap_or_default();
+                        for (from_symbol, to_symbol, _) in outgoing {
+                            match file_of(to_symbol) {
+                                Some(other) if other != file => deps.outgoing.push(FileLink {
+                                    file: other,
+                                    kind,
+                                    from_symbol,
+                                    to_symbol,
+                                }),
+                                _ => {}
+                            }
+                        }
+                        let incoming = incoming
+                            .then(|| self.document_index.get_relationships_to(symbol.id, kind))
+                            .and_then(Result::ok)
+                            .unwrap_or_default();
+                        for (from_symbol, to_symbol, _) in incoming {
+                            match file_of(from_symbol) {
+                                Some(other) if other != file => deps.incoming.push(FileLink {
+                                    file: other,
+                                    kind,
+                                    from_symbol,
+                                    to_symbol,
+                                }),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                deps
+            },
+            || budget.expired(),
+        )
+    }
+
+    /// Imports of any file that name `name` as their last path segment or alias
+    pub fn find_imports_of_name(&self, name: &str) -> Vec<crate::parsing::Import> {
+        self.document_index
+            .find_imports_of_name(name)
+            .unwrap_or_default()
+    }
+
     /// Get all indexed file paths.
     pub fn get_all_indexed_paths(&self) -> Vec<PathBuf> {
         self.document_index
@@ -787,6 +2509,38 @@ impl IndexFacade {
             .unwrap_or_default()
     }
 
+    /// Get the parse diagnostics of every indexed file with syntax errors.
+    pub fn get_parse_diagnostics(&self) -> FacadeResult<FileDiagnostics> {
+        DiagnosticsStore::new(self.document_index.path())
+            .load()
+            .map_err(Into::into)
+    }
+
+    /// Get the symbols of a file that overlap one of its parse diagnostics.
+    ///
+    /// Their extent or signature may be wrong, so results that include them
+    /// should be treated as low-confidence.
+    pub fn get_symbols_affected_by(
+        &self,
+        path: &str,
+        diagnostics: &[ParseDiagnostic],
+    ) -> Vec<Symbol> {
+        let Some(file_id) = self.get_file_id_for_path(path) else {
+            return Vec::new();
+        };
+        self.get_symbols_by_file(file_id)
+            .into_iter()
+            .filter(|symbol| crate::parsing::diagnostics::affects(diagnostics, &symbol.range))
+            .collect()
+    }
+
+    /// Get the git provenance of the symbols of every blamed file.
+    pub fn get_symbol_blames(&self) -> FacadeResult<FileBlames> {
+        BlameStore::new(self.document_index.path())
+            .load()
+            .map_err(Into::into)
+    }
+
     // =========================================================================
     // Statistics Methods
     // =========================================================================
@@ -806,11 +2560,21 @@ impl IndexFacade {
         self.document_index.count_relationships().unwrap_or(0)
     }
 
+    /// Merge index segments and purge deleted documents.
+    pub fn compact_index(&self) -> FacadeResult<CompactionStats> {
+        self.document_index.compact().map_err(Into::into)
+    }
+
     /// Get total Tantivy document count.
     pub fn document_count(&self) -> FacadeResult<u64> {
         self.document_index.document_count().map_err(Into::into)
     }
 
+    /// Digest of the stored documents, stable across deterministic builds.
+    pub fn content_digest(&self) -> FacadeResult<String> {
+        self.document_index.content_digest().map_err(Into::into)
+    }
+
     // =========================================================================
     // Directory Tracking
     // =========================================================================
@@ -858,10 +2622,11 @@ impl IndexFacade {
         path: impl AsRef<std::path::Path>,
     ) -> crate::IndexResult<crate::IndexingResult> {
         let path = path.as_ref();
+        self.packages.take();
         let stats = self.pipeline.index_file_single(
             path,
             Arc::clone(&self.document_index),
-            self.semantic_search.clone(),
+            self.semantic().cloned(),
             self.embedding_pool.clone(),
         )?;
 
@@ -879,8 +2644,8 @@ impl IndexFacade {
         let path = path.as_ref();
 
         if force {
-            // Remove first to force re-index
-            let _ = self.remove_file(path);
+            // Remove first to force re-index; the pipeline logs the pair as an update
+            let _ = self.cleanup_file(path);
         }
 
         self.index_file(path)
@@ -890,18 +2655,73 @@ impl IndexFacade {
     ///
     /// Uses the Pipeline's cleanup stage to remove symbols and embeddings.
     pub fn remove_file(&mut self, path: impl AsRef<std::path::Path>) -> crate::IndexResult<()> {
-        let path = path.as_ref();
-        let semantic_path = self.settings.index_path.join("semantic");
+        self.cleanup_file(path.as_ref())?;
+
+        if let Some(event_log) = self.pipeline.event_log() {
+            if let Err(e) = event_log.flush_pending() {
+                tracing::warn!(target: "pipeline", "Failed to write index events: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove every indexed file under `dir`, a directory inside a shard.
+    ///
+    /// Only the documents of that shard are looked at, so a forced rebuild
+    /// of the directory leaves the rest of the index in place. Returns the
+    /// number of files removed.
+    pub fn clear_directory(&mut self, dir: &Path) -> crate::IndexResult<usize> {
+        let Some(relative) = self
+            .settings
+            .workspace_root
+            .as_deref()
+            .and_then(|root| crate::indexing::shard::shard_dir(dir, root))
+        else {
+            return Err(IndexError::General(format!(
+                "{} is not a directory inside the workspace",
+                dir.display()
+            )));
+        };
+        let shard = crate::indexing::shard::shard_of(&relative.to_string_lossy()).to_string();
+        let files: Vec<PathBuf> = self
+            .document_index
+            .get_shard_paths(&shard)?
+            .into_iter()
+            .filter(|path| path.starts_with(&relative))
+            .collect();
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        self.cleanup_files(&files)?;
+        if let Some(event_log) = self.pipeline.event_log() {
+            if let Err(e) = event_log.flush_pending() {
+                tracing::warn!(target: "pipeline", "Failed to write index events: {e}");
+            }
+        }
+        Ok(files.len())
+    }
+
+    /// Remove a file's symbols and embeddings without logging the removal yet.
+    fn cleanup_file(&mut self, path: &std::path::Path) -> crate::IndexResult<()> {
+        self.cleanup_files(&[path.to_path_buf()])
+    }
+
+    /// Remove files' symbols and embeddings without logging the removals yet.
+    fn cleanup_files(&mut self, files: &[PathBuf]) -> crate::IndexResult<()> {
+        self.packages.take();
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.settings.index_path);
 
         use crate::indexing::pipeline::stages::CleanupStage;
-        let cleanup_stage = if let Some(ref sem) = self.semantic_search {
+        let cleanup_stage = if let Some(sem) = self.semantic() {
             CleanupStage::new(Arc::clone(&self.document_index), &semantic_path)
                 .with_semantic(Arc::clone(sem))
         } else {
             CleanupStage::new(Arc::clone(&self.document_index), &semantic_path)
-        };
+        }
+        .with_event_log(self.pipeline.event_log());
 
-        cleanup_stage.cleanup_files(&[path.to_path_buf()])?;
+        cleanup_stage.cleanup_files(files)?;
         Ok(())
     }
 
@@ -909,10 +2729,11 @@ impl IndexFacade {
     ///
     /// This is the primary indexing entry point using Pipeline.
     pub fn index_directory(&mut self, path: &Path, force: bool) -> FacadeResult<IndexingStats> {
+        self.packages.take();
         let stats = self.pipeline.index_incremental(
             path,
             Arc::clone(&self.document_index),
-            self.semantic_search.clone(),
+            self.semantic().cloned(),
             self.embedding_pool.clone(),
             force,
         )?;
@@ -973,13 +2794,14 @@ impl IndexFacade {
             stats.files_indexed = total_files;
             return Ok(stats);
         }
+        self.packages.take();
 
         // Use Pipeline for indexing with progress flag
         // The pipeline manages progress bars internally for clean sequential display
         let pipeline_stats = self.pipeline.index_incremental_with_progress_flag(
             dir,
             Arc::clone(&self.document_index),
-            self.semantic_search.clone(),
+            self.semantic().cloned(),
             self.embedding_pool.clone(),
             force,
             progress && total_files > 0,
@@ -998,6 +2820,77 @@ impl IndexFacade {
         Ok(stats)
     }
 
+    /// Index a checkout of a remote repository.
+    ///
+    /// Unlike [`Self::index_directory_with_options`], the checkout is not
+    /// tracked as an indexed directory, so syncing with `settings.toml`
+    /// leaves its files in place.
+    pub fn index_remote_checkout(
+        &mut self,
+        dir: impl AsRef<Path>,
+        progress: bool,
+        force: bool,
+    ) -> crate::IndexResult<crate::indexing::progress::IndexStats> {
+        use crate::indexing::FileWalker;
+        use crate::indexing::progress::IndexStats;
+
+        let dir = dir.as_ref();
+        self.packages.take();
+        let total_files = FileWalker::new(Arc::clone(&self.settings))
+            .walk(dir)
+            .count();
+
+        let pipeline_stats = self.pipeline.index_incremental_with_progress_flag(
+            dir,
+            Arc::clone(&self.document_index),
+            self.semantic().cloned(),
+            self.embedding_pool.clone(),
+            force,
+            progress && total_files > 0,
+            total_files,
+        )?;
+
+        let mut stats = IndexStats::default();
+        stats.files_indexed = pipeline_stats.new_files + pipeline_stats.modified_files;
+        stats.symbols_found = pipeline_stats.index_stats.symbols_found;
+        stats.elapsed = pipeline_stats.elapsed;
+
+        Ok(stats)
+    }
+
+    /// Index only the files git reports as changed since `base`.
+    ///
+    /// `base` is any revision git understands, usually the commit recorded
+    /// when the index was last written.
+    pub fn index_git_delta(
+        &mut self,
+        dir: impl AsRef<Path>,
+        base: &str,
+    ) -> crate::IndexResult<crate::indexing::progress::IndexStats> {
+        use crate::indexing::progress::IndexStats;
+
+        let dir = dir.as_ref();
+        self.packages.take();
+        let pipeline_stats = self.pipeline.index_git_delta(
+            dir,
+            base,
+            Arc::clone(&self.document_index),
+            self.semantic().cloned(),
+            self.embedding_pool.clone(),
+            None,
+        )?;
+
+        // Update tracked paths
+        self.add_indexed_path(dir);
+
+        let mut stats = IndexStats::default();
+        stats.files_indexed = pipeline_stats.new_files + pipeline_stats.modified_files;
+        stats.symbols_found = pipeline_stats.index_stats.symbols_found;
+        stats.elapsed = pipeline_stats.elapsed;
+
+        Ok(stats)
+    }
+
     /// Sync with configuration (compare stored vs config paths).
     ///
     /// Returns (added_dirs, removed_dirs, files_indexed, symbols_found).
@@ -1036,7 +2929,7 @@ impl IndexFacade {
             let result = self.pipeline.index_incremental_with_progress_flag(
                 path,
                 Arc::clone(&self.document_index),
-                self.semantic_search.clone(),
+                self.semantic().cloned(),
                 self.embedding_pool.clone(),
                 true, // force: new directories should be fully indexed
                 progress,
@@ -1055,6 +2948,7 @@ impl IndexFacade {
 
         // Update tracked paths
         self.indexed_paths = config_set;
+        self.packages.take();
 
         Ok(stats)
     }
@@ -1066,3 +2960,244 @@ impl IndexFacade {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RepositoryConfig;
+    use crate::vector::MockEmbeddingGenerator;
+    use tempfile::TempDir;
+
+    const RUST_SOURCE: &str = "\
+/// Parse JSON input
+pub fn parse_json() {}
+
+/// Parse json records
+pub fn parse_records() {}
+
+/// Parse json fields
+pub fn parse_fields() {
+    let a = 1;
+    let b = 2;
+}
+
+/// Report an error
+fn report_error() {}
+
+/// Parse json config
+pub struct JsonParser;
+";
+
+    const PYTHON_SOURCE: &str = "\
+def parse_json():
+    \"\"\"Parse JSON text\"\"\"
+    pass
+";
+
+    /// Facade over a `core` Rust repository and a `tools` Python one, every
+    /// documented symbol embedded with the mock generator
+    fn facade_with_embeddings() -> (TempDir, IndexFacade) {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("core/src")).unwrap();
+        std::fs::write(root.join("core/src/lib.rs"), RUST_SOURCE).unwrap();
+        std::fs::create_dir_all(root.join("tools")).unwrap();
+        std::fs::write(root.join("tools/util.py"), PYTHON_SOURCE).unwrap();
+
+        let repository = |path: &str| RepositoryConfig { path: path.into() };
+        let settings = Settings {
+            workspace_root: Some(root.clone()),
+            index_path: root.join("index"),
+            repositories: HashMap::from([
+                ("core".to_string(), repository("core")),
+                ("tools".to_string(), repository("tools")),
+            ]),
+            ..Settings::default()
+        };
+        let mut facade = IndexFacade::new(Arc::new(settings)).unwrap();
+        facade.index_directory(&root, true).unwrap();
+
+        let mut semantic =
+            SimpleSemanticSearch::with_provider(Arc::new(MockEmbeddingGenerator::new()));
+        for symbol in facade.get_all_symbols() {
+            let Some(doc) = symbol.doc_comment.as_deref() else {
+                continue;
+            };
+            let text = format!("{} {doc}", symbol.name);
+            let language = symbol.language_id.map(|l| l.as_str()).unwrap_or_default();
+            semantic
+                .index_doc_comment_with_language(symbol.id, &text, language)
+                .unwrap();
+        }
+        facade.semantic_search = OnceLock::from(Some(Arc::new(Mutex::new(semantic))));
+        (temp, facade)
+    }
+
+    fn search(facade: &IndexFacade, limit: usize, filter: &SemanticFilter) -> Vec<String> {
+        let mut names: Vec<String> = facade
+            .semantic_search_filtered("parse json", limit, None, filter)
+            .unwrap()
+            .into_iter()
+            .map(|(symbol, _)| {
+                let file = Path::new(&*symbol.file_path).file_name().unwrap();
+                format!("{}@{}", symbol.name, file.to_string_lossy())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn filter(
+        language: Option<&str>,
+        kind: Option<&str>,
+        path: Option<&str>,
+        visibility: Option<&str>,
+        repository: Option<&str>,
+    ) -> SemanticFilter {
+        SemanticFilter::parse(
+            language.map(str::to_string),
+            kind,
+            path,
+            visibility,
+            repository.map(str::to_string),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_semantic_filter_narrows_each_way() {
+        let (_temp, facade) = facade_with_embeddings();
+
+        assert_eq!(search(&facade, 10, &SemanticFilter::default()).len(), 6);
+        assert_eq!(
+            search(&facade, 10, &filter(Some("python"), None, None, None, None)),
+            ["parse_json@util.py"]
+        );
+        assert_eq!(
+            search(&facade, 10, &filter(None, Some("struct"), None, None, None)),
+            ["JsonParser@lib.rs"]
+        );
+        assert_eq!(
+            search(
+                &facade,
+                10,
+                &filter(None, None, Some("**/*.py"), None, None)
+            ),
+            ["parse_json@util.py"]
+        );
+        assert_eq!(
+            search(
+                &facade,
+                10,
+                &filter(Some("rust"), None, None, Some("private"), None)
+            ),
+            ["report_error@lib.rs"]
+        );
+        assert_eq!(
+            search(&facade, 10, &filter(None, None, None, None, Some("tools"))),
+            ["parse_json@util.py"]
+        );
+        assert_eq!(
+            search(
+                &facade,
+                10,
+                &filter(
+                    Some("rust"),
+                    Some("function"),
+                    None,
+                    Some("pub"),
+                    Some("core")
+                )
+            ),
+            [
+                "parse_fields@lib.rs",
+                "parse_json@lib.rs",
+                "parse_records@lib.rs"
+            ]
+        );
+
+        let unknown = filter(None, None, None, None, Some("nowhere"));
+        assert!(
+            facade
+                .semantic_search_filtered("parse json", 10, None, &unknown)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_semantic_filter_rejects_invalid_values() {
+        let parse =
+            |kind, visibility, path| SemanticFilter::parse(None, kind, path, visibility, None);
+
+        assert!(parse(Some("Function"), Some("Public"), Some("src/**")).is_ok());
+        assert!(
+            parse(Some("Gadget"), None, None)
+                .unwrap_err()
+                .contains("Gadget")
+        );
+        assert!(
+            parse(None, Some("friend"), None)
+                .unwrap_err()
+                .contains("friend")
+        );
+        assert!(parse(None, None, Some("src/[")).is_err());
+    }
+
+    #[test]
+    fn test_semantic_filter_widens_candidates_past_dropped_hits() {
+        let (_temp, facade) = facade_with_embeddings();
+
+        // The only private Rust symbol ranks last for the query, so the
+        // first candidates are all dropped by the visibility check
+        let private = filter(Some("rust"), None, None, Some("private"), None);
+        let ranked = facade
+            .semantic_search_filtered("parse json", 1, None, &SemanticFilter::default())
+            .unwrap();
+        assert_ne!(&*ranked[0].0.name, "report_error");
+        assert_eq!(search(&facade, 1, &private), ["report_error@lib.rs"]);
+    }
+
+    #[test]
+    fn test_workspace_packages_are_cached_until_reindex() {
+        let (temp, mut facade) = facade_with_embeddings();
+        let root = temp.path().canonicalize().unwrap();
+        assert!(facade.workspace_packages().is_empty());
+
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"core\"]\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("core/Cargo.toml"), "[package]\nname = \"core\"\n").unwrap();
+        assert!(facade.workspace_packages().is_empty());
+
+        facade.remove_file(root.join("tools/util.py")).unwrap();
+        let names: Vec<&str> = facade
+            .workspace_packages()
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect();
+        assert_eq!(names, ["core"]);
+    }
+
+    #[test]
+    fn test_symbol_snippet_bounds() {
+        let (temp, facade) = facade_with_embeddings();
+        let symbol = facade
+            .find_symbols_by_name("parse_fields", Some("rust"))
+            .pop()
+            .unwrap();
+
+        assert_eq!(
+            facade.symbol_snippet(&symbol, 2).unwrap(),
+            "pub fn parse_fields() {\n    let a = 1;"
+        );
+        // Never past the end of the symbol
+        let whole = facade.symbol_snippet(&symbol, 100).unwrap();
+        assert_eq!(whole.lines().count(), 4);
+        assert!(whole.ends_with('}'));
+
+        std::fs::remove_file(temp.path().join("core/src/lib.rs")).unwrap();
+        assert!(facade.symbol_snippet(&symbol, 2).is_none());
+    }
+}
@@ -43,8 +43,11 @@ impl FileInfo {
 }
 
 /// Get file modification time in seconds since UNIX_EPOCH
+///
+/// A file inside an archive has the modification time of the archive.
 pub fn get_file_mtime(path: &Path) -> Option<u64> {
-    std::fs::metadata(path)
+    let archive = crate::indexing::archive::archive_of(path);
+    std::fs::metadata(archive.as_deref().unwrap_or(path))
         .ok()
         .and_then(|m| m.modified().ok())
         .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
@@ -0,0 +1,303 @@
+//! Git-aware change detection for delta indexing
+//!
+//! Asks libgit2 which files differ between a base commit and the working
+//! tree, so re-indexing after a small change reads a handful of files
+//! instead of walking and hashing the whole tree.
+
+use git2::{DiffOptions, Repository};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Commit id of HEAD in the repository containing `path`
+///
+/// Returns `None` outside a git repository or before the first commit.
+pub fn head_commit(path: &Path) -> Option<String> {
+    let repo = Repository::discover(path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+/// Commits `recently_changed` reads at most
+const MAX_RECENT_COMMITS: usize = 500;
+
+/// Branches the active branch is compared with, most specific first
+const DEFAULT_BRANCHES: &[&str] = &[
+    "refs/remotes/origin/HEAD",
+    "refs/heads/main",
+    "refs/heads/master",
+    "refs/remotes/origin/main",
+    "refs/remotes/origin/master",
+];
+
+/// Commit where the active branch forked from the default branch
+///
+/// Files changed since this commit are the ones touched on the branch. On
+/// the default branch itself this is HEAD. Returns `None` outside a git
+/// repository or when no default branch exists.
+pub fn branch_base(path: &Path) -> Option<String> {
+    let repo = Repository::discover(path).ok()?;
+    let head = repo.head().ok()?.peel_to_commit().ok()?.id();
+    let default = DEFAULT_BRANCHES
+        .iter()
+        .find_map(|name| repo.revparse_single(name).ok()?.peel_to_commit().ok())?;
+    let base = repo.merge_base(head, default.id()).ok()?;
+    Some(base.to_string())
+}
+
+/// Commit `depth` first parents before HEAD, or the root commit of a
+/// shorter history
+///
+/// Returns `None` outside a git repository or before the first commit.
+pub fn ancestor(path: &Path, depth: usize) -> Option<String> {
+    let repo = Repository::discover(path).ok()?;
+    let mut commit = repo.head().ok()?.peel_to_commit().ok()?;
+    for _ in 0..depth {
+        match commit.parent(0) {
+            Ok(parent) => commit = parent,
+            Err(_) => break,
+        }
+    }
+    Some(commit.id().to_string())
+}
+
+/// Content of `file` at revision `rev` of the repository containing it
+///
+/// Returns `None` when the file did not exist at `rev` or is not UTF-8.
+pub fn file_at(file: &Path, rev: &str) -> Result<Option<String>, git2::Error> {
+    let repo = Repository::discover(file.parent().unwrap_or(file))?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("repository has no working tree"))?
+        .canonicalize()
+        .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+    let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let Ok(relative) = file.strip_prefix(&workdir) else {
+        return Ok(None);
+    };
+    let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+    let Ok(entry) = tree.get_path(relative) else {
+        return Ok(None);
+    };
+    let blob = entry.to_object(&repo)?.peel_to_blob()?;
+    Ok(std::str::from_utf8(blob.content()).ok().map(str::to_string))
+}
+
+/// Files that differ between `base` and the working tree of the repository
+/// containing `path`
+///
+/// Covers committed, staged, unstaged and untracked changes; ignored files
+/// are left out. Paths are absolute and include deleted files, so callers
+/// check the disk to tell a change from a deletion.
+pub fn changed_files(path: &Path, base: &str) -> Result<Vec<PathBuf>, git2::Error> {
+    let repo = Repository::discover(path)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("repository has no working tree"))?
+        .to_path_buf();
+    let tree = repo.revparse_single(base)?.peel_to_tree()?;
+
+    let mut options = DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))?;
+
+    // A rename shows up as a deletion plus an addition; keep both sides
+    let files: BTreeSet<PathBuf> = diff
+        .deltas()
+        .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+        .flatten()
+        .map(|relative| workdir.join(relative))
+        .collect();
+
+    Ok(files.into_iter().collect())
+}
+
+/// Files changed by the first-parent commits of HEAD made at or after
+/// `since` (Unix seconds), each with the time of its newest such commit
+///
+/// Paths are absolute, below the canonical working tree. Deleted files are
+/// left out.
+pub fn recently_changed(path: &Path, since: i64) -> Result<Vec<(PathBuf, i64)>, git2::Error> {
+    let repo = Repository::discover(path)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("repository has no working tree"))?;
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+
+    let mut files: HashMap<PathBuf, i64> = HashMap::new();
+    let mut commit = repo.head()?.peel_to_commit()?;
+    for _ in 0..MAX_RECENT_COMMITS {
+        let time = commit.time().seconds();
+        if time < since {
+            break;
+        }
+        let parent = commit.parent(0).ok();
+        let parent_tree = parent.as_ref().map(|parent| parent.tree()).transpose()?;
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        for delta in diff.deltas() {
+            if delta.status() == git2::Delta::Deleted {
+                continue;
+            }
+            if let Some(relative) = delta.new_file().path() {
+                // Newest first, so the first time seen is the newest
+                files.entry(workdir.join(relative)).or_insert(time);
+            }
+        }
+        match parent {
+            Some(parent) => commit = parent,
+            None => break,
+        }
+    }
+    Ok(files.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_all(repo: &Repository, message: &str) -> String {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+        .to_string()
+    }
+
+    #[test]
+    fn test_changed_files_since_base() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let repo = Repository::init(root).unwrap();
+
+        fs::write(root.join("kept.rs"), "fn kept() {}").unwrap();
+        fs::write(root.join("edited.rs"), "fn edited() {}").unwrap();
+        fs::write(root.join("removed.rs"), "fn removed() {}").unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        let base = commit_all(&repo, "base");
+        assert_eq!(head_commit(root).as_deref(), Some(base.as_str()));
+
+        // One committed change, one working tree change, one untracked file
+        fs::remove_file(root.join("removed.rs")).unwrap();
+        commit_all(&repo, "remove");
+        fs::write(root.join("edited.rs"), "fn edited() { todo!() }").unwrap();
+        fs::write(root.join("added.rs"), "fn added() {}").unwrap();
+        fs::create_dir(root.join("target")).unwrap();
+        fs::write(root.join("target/build.rs"), "fn ignored() {}").unwrap();
+
+        let workdir = root.canonicalize().unwrap();
+        let names: Vec<_> = changed_files(root, &base)
+            .unwrap()
+            .into_iter()
+            .map(|path| {
+                path.canonicalize()
+                    .unwrap_or(path)
+                    .strip_prefix(&workdir)
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        assert!(names.contains(&PathBuf::from("edited.rs")), "{names:?}");
+        assert!(names.contains(&PathBuf::from("added.rs")), "{names:?}");
+        assert!(!names.contains(&PathBuf::from("kept.rs")), "{names:?}");
+        assert!(
+            !names.contains(&PathBuf::from("target/build.rs")),
+            "{names:?}"
+        );
+        assert!(changed_files(root, "no-such-rev").is_err());
+    }
+
+    #[test]
+    fn test_file_at_reads_older_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let repo = Repository::init(root).unwrap();
+
+        fs::write(root.join("lib.rs"), "fn old() {}").unwrap();
+        let first = commit_all(&repo, "first");
+        fs::write(root.join("lib.rs"), "fn new() {}").unwrap();
+        fs::write(root.join("added.rs"), "fn added() {}").unwrap();
+        commit_all(&repo, "second");
+
+        assert_eq!(ancestor(root, 5).as_deref(), Some(first.as_str()));
+        assert_eq!(
+            file_at(&root.join("lib.rs"), &first).unwrap().as_deref(),
+            Some("fn old() {}")
+        );
+        assert_eq!(file_at(&root.join("added.rs"), &first).unwrap(), None);
+        assert_eq!(
+            file_at(&root.join("lib.rs"), "HEAD").unwrap().as_deref(),
+            Some("fn new() {}")
+        );
+    }
+
+    #[test]
+    fn test_branch_base_is_fork_point() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let repo = Repository::init(root).unwrap();
+
+        fs::write(root.join("lib.rs"), "fn lib() {}").unwrap();
+        let fork = commit_all(&repo, "base");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        // Already exists when the initial branch is called main
+        let _ = repo.branch("main", &head, false);
+        assert_eq!(branch_base(root).as_deref(), Some(fork.as_str()));
+
+        // Commits on a feature branch leave the fork point where it was
+        repo.branch("feature", &head, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        fs::write(root.join("feature.rs"), "fn feature() {}").unwrap();
+        commit_all(&repo, "feature");
+        assert_eq!(branch_base(root).as_deref(), Some(fork.as_str()));
+    }
+
+    #[test]
+    fn test_recently_changed_stops_at_since() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let repo = Repository::init(root).unwrap();
+
+        fs::write(root.join("old.rs"), "fn old() {}").unwrap();
+        commit_all(&repo, "old");
+        fs::write(root.join("new.rs"), "fn new() {}").unwrap();
+        commit_all(&repo, "new");
+
+        let workdir = root.canonicalize().unwrap();
+        let mut files: Vec<PathBuf> = recently_changed(root, 0)
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        files.sort();
+        assert_eq!(files, [workdir.join("new.rs"), workdir.join("old.rs")]);
+
+        let future = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .time()
+            .seconds()
+            + 1;
+        assert!(recently_changed(root, future).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+//! Layered ignore rules for single paths
+//!
+//! The walker gets gitignore handling from the `ignore` crate while it walks
+//! the tree. Git delta discovery and the file watcher see one path at a time
+//! instead, so they ask [`IgnoreRules`], which applies the same files with
+//! the same precedence:
+//!
+//! 1. `.codannaignore`
+//! 2. `.ignore`
+//! 3. `.gitignore`
+//! 4. `.git/info/exclude` and the global git excludes file
+//!
+//! Within each kind the file in the deepest directory wins, and a `!`
+//! pattern whitelists a path a lower-priority file ignores.
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Custom ignore file read next to `.gitignore`
+pub const CODANNA_IGNORE_FILE: &str = ".codannaignore";
+
+/// Per-directory ignore files, highest priority first
+pub const IGNORE_FILE_NAMES: &[&str] = &[CODANNA_IGNORE_FILE, ".ignore", ".gitignore"];
+
+/// Whether `path` is one of the per-directory ignore files
+pub fn is_ignore_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| IGNORE_FILE_NAMES.contains(&name))
+}
+
+/// Matchers for the ignore files of one directory, in `IGNORE_FILE_NAMES` order
+type DirMatchers = Arc<Vec<Option<Gitignore>>>;
+
+/// Answers whether a path under `root` is ignored
+///
+/// Matchers are read lazily and cached per directory; call
+/// [`reload`](Self::reload) after an ignore file changes.
+pub struct IgnoreRules {
+    root: PathBuf,
+    /// `root` as given, when it differs from the canonical path
+    alias: PathBuf,
+    /// `.git/info/exclude` and the global excludes file
+    repository: Vec<Gitignore>,
+    dirs: Mutex<HashMap<PathBuf, DirMatchers>>,
+}
+
+impl IgnoreRules {
+    /// Create rules for the tree under `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let alias = root.into();
+        let root = alias.canonicalize().unwrap_or_else(|_| alias.clone());
+        let repository = repository_matchers(&root);
+        Self {
+            root,
+            alias,
+            repository,
+            dirs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Root directory the rules apply to
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Forget cached matchers so changed ignore files are read again
+    pub fn reload(&mut self) {
+        self.repository = repository_matchers(&self.root);
+        if let Ok(mut dirs) = self.dirs.lock() {
+            dirs.clear();
+        }
+    }
+
+    /// Whether `path` (absolute or relative to the root) is ignored
+    ///
+    /// A path is ignored when it or one of its parent directories matches.
+    /// Paths outside the root are never ignored.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        let Ok(relative) = absolute
+            .strip_prefix(&self.root)
+            .or_else(|_| absolute.strip_prefix(&self.alias))
+        else {
+            return false;
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        // Directories from the file's parent up to the root, deepest first
+        let chain: Vec<(&Path, DirMatchers)> = relative
+            .ancestors()
+            .skip(1)
+            .map(|dir| (dir, self.matchers_for(dir)))
+            .collect();
+
+        for kind in 0..IGNORE_FILE_NAMES.len() {
+            for (dir, matchers) in &chain {
+                let Some(matcher) = &matchers[kind] else {
+                    continue;
+                };
+                let within = relative.strip_prefix(dir).unwrap_or(relative);
+                match matcher.matched_path_or_any_parents(within, is_dir) {
+                    Match::None => {}
+                    matched => return matched.is_ignore(),
+                }
+            }
+        }
+
+        self.repository
+            .iter()
+            .map(|matcher| matcher.matched_path_or_any_parents(relative, is_dir))
+            .find(|matched| !matched.is_none())
+            .is_some_and(|matched| matched.is_ignore())
+    }
+
+    /// Cached matchers for `dir`, relative to the root
+    fn matchers_for(&self, dir: &Path) -> DirMatchers {
+        if let Some(matchers) = self
+            .dirs
+            .lock()
+            .ok()
+            .and_then(|dirs| dirs.get(dir).cloned())
+        {
+            return matchers;
+        }
+
+        let absolute = self.root.join(dir);
+        let matchers: DirMatchers = Arc::new(
+            IGNORE_FILE_NAMES
+                .iter()
+                .map(|name| read_ignore_file(&absolute, &absolute.join(name)))
+                .collect(),
+        );
+        if let Ok(mut dirs) = self.dirs.lock() {
+            dirs.insert(dir.to_path_buf(), Arc::clone(&matchers));
+        }
+        matchers
+    }
+}
+
+/// `.git/info/exclude` and the global excludes file, both rooted at `root`
+fn repository_matchers(root: &Path) -> Vec<Gitignore> {
+    let mut matchers = Vec::new();
+    if let Some(exclude) = read_ignore_file(root, &root.join(".git/info/exclude")) {
+        matchers.push(exclude);
+    }
+    let (global, _) = GitignoreBuilder::new(root).build_global();
+    if !global.is_empty() {
+        matchers.push(global);
+    }
+    matchers
+}
+
+/// Build a matcher for `file` rooted at `dir`; `None` when the file is
+/// missing, empty or unreadable
+fn read_ignore_file(dir: &Path, file: &Path) -> Option<Gitignore> {
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(file) {
+        tracing::debug!(
+            "[ignore] skipping unreadable rules in {}: {e}",
+            file.display()
+        );
+    }
+    builder.build().ok().filter(|matcher| !matcher.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_layered_ignore_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("vendor/lib")).unwrap();
+        fs::create_dir_all(root.join("app/generated")).unwrap();
+
+        fs::write(root.join(".gitignore"), "target/\n*.gen.rs\nvendor/\n").unwrap();
+        fs::write(root.join("app/.gitignore"), "!keep.gen.rs\n").unwrap();
+        fs::write(root.join("app/.ignore"), "generated/\n").unwrap();
+        fs::write(root.join(".codannaignore"), "!vendor/\nscratch.rs\n").unwrap();
+
+        let rules = IgnoreRules::new(root);
+
+        assert!(rules.is_ignored(Path::new("target/debug/build.rs"), false));
+        assert!(rules.is_ignored(Path::new("src/schema.gen.rs"), false));
+        assert!(rules.is_ignored(Path::new("app/generated/api.rs"), false));
+        assert!(rules.is_ignored(Path::new("scratch.rs"), false));
+        assert!(!rules.is_ignored(Path::new("src/main.rs"), false));
+
+        // A deeper .gitignore re-includes, and .codannaignore beats .gitignore
+        assert!(!rules.is_ignored(Path::new("app/keep.gen.rs"), false));
+        assert!(!rules.is_ignored(&root.join("vendor/lib/mod.rs"), false));
+
+        assert!(is_ignore_file(Path::new("app/.ignore")));
+        assert!(!is_ignore_file(Path::new("app/main.rs")));
+    }
+
+    #[test]
+    fn test_reload_picks_up_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let mut rules = IgnoreRules::new(root);
+        assert!(!rules.is_ignored(Path::new("dist/app.js"), false));
+
+        fs::write(root.join(".gitignore"), "dist/\n").unwrap();
+        assert!(!rules.is_ignored(Path::new("dist/app.js"), false));
+
+        rules.reload();
+        assert!(rules.is_ignored(Path::new("dist/app.js"), false));
+    }
+}
@@ -1,20 +1,40 @@
+pub mod archive;
+pub mod blame;
+pub mod budget;
+pub mod dependency_graph;
+pub mod diff;
+#[cfg(feature = "parquet-export")]
+pub mod export;
 pub mod facade;
 pub mod file_info;
+pub mod git_delta;
+pub mod ignore_rules;
+pub mod priority;
 pub mod progress;
+pub mod remote;
+pub mod shard;
+pub mod stats;
 pub mod transaction;
 pub mod walker;
+pub mod working_set;
 
 // Parallel pipeline for high-performance indexing
 pub mod pipeline;
 
 // Re-exports
+pub use budget::{QueryBudget, TruncationReason};
 pub use file_info::{FileInfo, calculate_hash, get_utc_timestamp};
+pub use ignore_rules::IgnoreRules;
 pub use progress::IndexStats;
 pub use transaction::{FileTransaction, IndexTransaction};
 pub use walker::FileWalker;
+pub use working_set::WorkingSet;
 
 // Pipeline exports
 pub use pipeline::{Pipeline, PipelineConfig};
 
 // Facade - primary API for indexing operations
-pub use facade::{FacadeResult, IndexFacade, IndexingStats, SyncStats};
+pub use facade::{
+    CallerNode, CallerTree, FacadeResult, ImpactRadius, Implementor, IndexFacade, IndexingStats,
+    RelationshipPage, SemanticFilter, SyncStats,
+};
@@ -0,0 +1,77 @@
+//! Cooperative cancellation of pipeline runs
+//!
+//! Every run takes the current [`CancellationToken`] of its pipeline and
+//! hands it to its stages, which check it between units of work: READ and
+//! PARSE between files, INDEX between batches, RESOLVE between files of a
+//! pass. A cancelled run stops without committing the batch in flight and
+//! returns [`PipelineError::Cancelled`](super::PipelineError::Cancelled).
+//!
+//! Files a cancelled run already wrote are journaled as an uncommitted
+//! transaction (see [`crate::indexing::transaction`]), so the next run rolls
+//! them back and indexes them again: the index never keeps symbols whose
+//! relationships were never resolved.
+//!
+//! [`PipelineCancel`] is the handle callers keep: Ctrl-C in `codanna index`,
+//! server shutdown, and the MCP `cancel-reindex` request cancel through it.
+//! Once a cancelled run has returned, the pipeline arms a fresh token, so
+//! a cancel only ever stops the run in progress or the next one to start.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio_util::sync::CancellationToken;
+
+/// Cancels the run in progress on a pipeline, from any thread
+#[derive(Debug, Clone, Default)]
+pub struct PipelineCancel {
+    current: Arc<Mutex<CancellationToken>>,
+}
+
+impl PipelineCancel {
+    /// Stop the run in progress, or the next one if none is running
+    pub fn cancel(&self) {
+        self.lock().cancel();
+    }
+
+    /// Whether the current run has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.lock().is_cancelled()
+    }
+
+    /// Token for the stages of the run starting now
+    pub fn token(&self) -> CancellationToken {
+        self.lock().clone()
+    }
+
+    /// Arm a fresh token once a cancelled run has returned
+    pub(crate) fn reset(&self) {
+        let mut current = self.lock();
+        if current.is_cancelled() {
+            *current = CancellationToken::new();
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CancellationToken> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_reaches_tokens_until_reset() {
+        let cancel = PipelineCancel::default();
+        let running = cancel.token();
+        let handle = cancel.clone();
+
+        handle.cancel();
+        assert!(running.is_cancelled());
+        assert!(cancel.is_cancelled());
+
+        cancel.reset();
+        assert!(!cancel.is_cancelled());
+        assert!(!cancel.token().is_cancelled());
+        // Tokens of the cancelled run stay cancelled
+        assert!(running.is_cancelled());
+    }
+}
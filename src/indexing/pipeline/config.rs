@@ -4,6 +4,7 @@
 //! Reads from Settings (.codanna/settings.toml).
 
 use crate::Settings;
+use std::path::PathBuf;
 
 /// Configuration for the parallel indexing pipeline.
 #[derive(Debug, Clone)]
@@ -35,8 +36,18 @@ pub struct PipelineConfig {
     /// Number of batches between Tantivy commits
     pub batches_per_commit: usize,
 
+    /// Memory budget for in-flight file contents and pending relationships
+    /// in MB (0 = unlimited)
+    pub memory_budget_mb: usize,
+
+    /// Assign IDs in path order and write single-threaded for reproducible indexes
+    pub deterministic: bool,
+
     /// Enable detailed stage tracing (timing, memory, throughput)
     pub pipeline_tracing: bool,
+
+    /// Write stage metrics as JSON to this path after each run
+    pub metrics_json: Option<PathBuf>,
 }
 
 impl Default for PipelineConfig {
@@ -54,7 +65,10 @@ impl Default for PipelineConfig {
             parsed_channel_size: 1000,
             batch_channel_size: 20,
             batches_per_commit: 10,
+            memory_budget_mb: 1024,
+            deterministic: false,
             pipeline_tracing: false,
+            metrics_json: None,
         }
     }
 }
@@ -70,7 +84,10 @@ impl PipelineConfig {
     /// Also reads:
     /// - `indexing.batch_size` -> batch_size
     /// - `indexing.batches_per_commit` -> batches_per_commit
+    /// - `indexing.memory_budget_mb` -> memory_budget_mb
+    /// - `indexing.deterministic` -> deterministic
     /// - `indexing.pipeline_tracing` -> pipeline_tracing
+    /// - `indexing.metrics_json` -> metrics_json
     pub fn from_settings(settings: &Settings) -> Self {
         let indexing = &settings.indexing;
         let parallelism = indexing.parallelism;
@@ -97,7 +114,10 @@ impl PipelineConfig {
             parsed_channel_size,
             batch_channel_size,
             batches_per_commit: indexing.batches_per_commit,
+            memory_budget_mb: indexing.memory_budget_mb,
+            deterministic: indexing.deterministic,
             pipeline_tracing: indexing.pipeline_tracing,
+            metrics_json: indexing.metrics_json.clone(),
         }
     }
 
@@ -113,7 +133,10 @@ impl PipelineConfig {
             parsed_channel_size: 500,
             batch_channel_size: 10,
             batches_per_commit: 5,
+            memory_budget_mb: 256,
+            deterministic: false,
             pipeline_tracing: false,
+            metrics_json: None,
         }
     }
 
@@ -130,7 +153,10 @@ impl PipelineConfig {
             parsed_channel_size: 2000,
             batch_channel_size: 50,
             batches_per_commit: 20,
+            memory_budget_mb: 4096,
+            deterministic: false,
             pipeline_tracing: false,
+            metrics_json: None,
         }
     }
 
@@ -152,6 +178,23 @@ impl PipelineConfig {
         self
     }
 
+    /// Set the memory budget in MB (0 = unlimited)
+    pub fn with_memory_budget_mb(mut self, megabytes: usize) -> Self {
+        self.memory_budget_mb = megabytes;
+        self
+    }
+
+    /// Enable deterministic ID assignment and write order
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Spill threshold for pending relationships: half the memory budget
+    pub fn relationship_spill_bytes(&self) -> Option<usize> {
+        (self.memory_budget_mb > 0).then(|| self.memory_budget_mb * 1024 * 1024 / 2)
+    }
+
     /// Calculate total channel buffer memory (approximate)
     pub fn estimated_memory_mb(&self) -> usize {
         // Rough estimates:
@@ -0,0 +1,79 @@
+//! Content de-duplication across one indexing run
+//!
+//! Monorepos often carry identical copies of a file: vendored dependencies,
+//! generated code checked in twice. [`ContentDedup`] remembers the first path
+//! read for each content hash; later files with the same hash become aliases
+//! of it and skip parsing and embedding.
+
+use crate::indexing::pipeline::types::FileContent;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// First path seen per content hash, shared by the READ threads
+#[derive(Debug, Default)]
+pub struct ContentDedup {
+    seen: Mutex<HashMap<String, PathBuf>>,
+}
+
+impl ContentDedup {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Path first read with `hash`, or `None` if `path` is the first
+    pub fn claim(&self, hash: &str, path: &Path) -> Option<PathBuf> {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        match seen.get(hash) {
+            Some(canonical) if canonical.as_path() != path => Some(canonical.clone()),
+            Some(_) => None,
+            None => {
+                seen.insert(hash.to_string(), path.to_path_buf());
+                None
+            }
+        }
+    }
+
+    /// Turn `content` into an alias when an identical file was read first
+    ///
+    /// An alias keeps its path and hash but drops its text, so nothing is
+    /// parsed for it.
+    pub fn mark(&self, content: &mut FileContent) {
+        if let Some(canonical) = self.claim(&content.hash, &content.path) {
+            content.duplicate_of = Some(canonical);
+            content.content = String::new();
+            content.permit = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_aliases_identical_content() {
+        let dedup = ContentDedup::new();
+        let file = |path: &str, text: &str| {
+            FileContent::new(path.into(), text.to_string(), format!("hash:{text}"))
+        };
+
+        let mut original = file("src/lib.rs", "fn lib() {}");
+        let mut copy = file("vendor/lib.rs", "fn lib() {}");
+        let mut other = file("src/main.rs", "fn main() {}");
+        for content in [&mut original, &mut copy, &mut other] {
+            dedup.mark(content);
+        }
+
+        assert_eq!(original.duplicate_of, None);
+        assert_eq!(copy.duplicate_of, Some(PathBuf::from("src/lib.rs")));
+        assert!(copy.content.is_empty());
+        assert_eq!(other.duplicate_of, None);
+
+        // The canonical file read again stays canonical
+        assert_eq!(
+            dedup.claim("hash:fn lib() {}", Path::new("src/lib.rs")),
+            None
+        );
+    }
+}
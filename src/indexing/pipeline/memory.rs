@@ -0,0 +1,305 @@
+//! Memory budget for the indexing pipeline
+//!
+//! Channel capacities bound how many items are in flight, but not how large
+//! they are: a hundred minified bundles can hold more memory than a hundred
+//! thousand small source files. [`MemoryBudget`] bounds the bytes instead.
+//! The READ stage takes a [`MemoryPermit`] for each file before reading it,
+//! and the permit travels with the content until PARSE drops it.
+//!
+//! Relationships wait in memory from the INDEX stage until resolution runs
+//! at the end of Phase 1. [`RelationshipSpill`] moves them to a temporary
+//! file once they outgrow their share of the budget.
+
+use crate::indexing::pipeline::types::{PipelineError, PipelineResult, UnresolvedRelationship};
+use crate::relationship::RelationshipMetadata;
+use crate::types::intern;
+use crate::{FileId, Range, RelationKind, SymbolId};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Bytes in flight between pipeline stages
+#[derive(Debug)]
+pub struct MemoryBudget {
+    /// Limit in bytes; 0 means unlimited
+    limit: usize,
+    in_flight: Mutex<usize>,
+    released: Condvar,
+}
+
+impl MemoryBudget {
+    /// Budget of `megabytes` MB; 0 disables throttling
+    pub fn new(megabytes: usize) -> Arc<Self> {
+        Arc::new(Self {
+            limit: megabytes.saturating_mul(1024 * 1024),
+            in_flight: Mutex::new(0),
+            released: Condvar::new(),
+        })
+    }
+
+    /// Limit in bytes, `None` when unlimited
+    pub fn limit(&self) -> Option<usize> {
+        (self.limit > 0).then_some(self.limit)
+    }
+
+    /// Bytes currently held by permits
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().map(|bytes| *bytes).unwrap_or(0)
+    }
+
+    /// Reserve `bytes`, blocking while that would exceed the limit
+    ///
+    /// A request larger than the whole budget is let through once nothing
+    /// else is in flight, so one huge file cannot stall the pipeline.
+    /// Returns the permit and how long the caller waited.
+    pub fn acquire(self: &Arc<Self>, bytes: usize) -> (MemoryPermit, Duration) {
+        let start = Instant::now();
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            while self.limit > 0 && *in_flight > 0 && *in_flight + bytes > self.limit {
+                in_flight = match self.released.wait(in_flight) {
+                    Ok(guard) => guard,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+            }
+            *in_flight += bytes;
+        }
+        let permit = MemoryPermit {
+            budget: Arc::clone(self),
+            bytes,
+        };
+        (permit, start.elapsed())
+    }
+
+    fn release(&self, bytes: usize) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            *in_flight = in_flight.saturating_sub(bytes);
+        }
+        self.released.notify_all();
+    }
+}
+
+/// Bytes reserved from a [`MemoryBudget`], returned on drop
+#[derive(Debug)]
+pub struct MemoryPermit {
+    budget: Arc<MemoryBudget>,
+    bytes: usize,
+}
+
+impl MemoryPermit {
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl Drop for MemoryPermit {
+    fn drop(&mut self) {
+        self.budget.release(self.bytes);
+    }
+}
+
+/// Relationships collected for Phase 2, spilled to disk past a threshold
+///
+/// Spilled relationships are appended to an unnamed temporary file, one JSON
+/// object per line, and read back by [`finish`](Self::finish).
+pub struct RelationshipSpill {
+    /// Bytes held in memory before spilling; `None` never spills
+    threshold: Option<usize>,
+    pending: Vec<UnresolvedRelationship>,
+    pending_bytes: usize,
+    file: Option<BufWriter<std::fs::File>>,
+    spilled: usize,
+}
+
+impl RelationshipSpill {
+    pub fn new(threshold: Option<usize>) -> Self {
+        Self {
+            threshold,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            file: None,
+            spilled: 0,
+        }
+    }
+
+    /// Number of relationships written to disk so far
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    pub fn extend(
+        &mut self,
+        relationships: impl IntoIterator<Item = UnresolvedRelationship>,
+    ) -> PipelineResult<()> {
+        for relationship in relationships {
+            self.pending_bytes += estimated_size(&relationship);
+            self.pending.push(relationship);
+        }
+        if self
+            .threshold
+            .is_some_and(|threshold| self.pending_bytes > threshold)
+        {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// All relationships, spilled ones first in the order they arrived
+    pub fn finish(mut self) -> PipelineResult<Vec<UnresolvedRelationship>> {
+        let Some(writer) = self.file.take() else {
+            return Ok(self.pending);
+        };
+        let mut file = writer
+            .into_inner()
+            .map_err(|e| spill_error(e.into_error()))?;
+        file.seek(SeekFrom::Start(0)).map_err(spill_error)?;
+
+        let mut relationships = Vec::with_capacity(self.spilled + self.pending.len());
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(spill_error)?;
+            let spilled: SpilledRelationship =
+                serde_json::from_str(&line).map_err(|e| spill_error(e.into()))?;
+            relationships.push(UnresolvedRelationship {
+                from_id: spilled.from_id,
+                // Names repeat across relationships; share one allocation per name
+                from_name: intern(&spilled.from_name),
+                to_name: intern(&spilled.to_name),
+                file_id: spilled.file_id,
+                kind: spilled.kind,
+                metadata: spilled.metadata,
+                to_range: spilled.to_range,
+            });
+        }
+        relationships.append(&mut self.pending);
+        Ok(relationships)
+    }
+
+    fn spill(&mut self) -> PipelineResult<()> {
+        if self.file.is_none() {
+            let file = tempfile::tempfile().map_err(spill_error)?;
+            self.file = Some(BufWriter::new(file));
+        }
+        let Some(writer) = self.file.as_mut() else {
+            return Ok(());
+        };
+
+        for relationship in self.pending.drain(..) {
+            let spilled = SpilledRelationship {
+                from_id: relationship.from_id,
+                from_name: relationship.from_name.to_string(),
+                to_name: relationship.to_name.to_string(),
+                file_id: relationship.file_id,
+                kind: relationship.kind,
+                metadata: relationship.metadata,
+                to_range: relationship.to_range,
+            };
+            serde_json::to_writer(&mut *writer, &spilled).map_err(|e| spill_error(e.into()))?;
+            writer.write_all(b"\n").map_err(spill_error)?;
+            self.spilled += 1;
+        }
+        tracing::debug!(
+            target: "pipeline",
+            "spilled {} bytes of relationships ({} total on disk)",
+            self.pending_bytes,
+            self.spilled
+        );
+        self.pending_bytes = 0;
+        Ok(())
+    }
+}
+
+/// On-disk form of [`UnresolvedRelationship`]
+#[derive(Serialize, Deserialize)]
+struct SpilledRelationship {
+    from_id: Option<SymbolId>,
+    from_name: String,
+    to_name: String,
+    file_id: FileId,
+    kind: RelationKind,
+    metadata: Option<RelationshipMetadata>,
+    to_range: Option<Range>,
+}
+
+/// Rough heap and inline size of a pending relationship
+fn estimated_size(relationship: &UnresolvedRelationship) -> usize {
+    std::mem::size_of::<UnresolvedRelationship>()
+        + relationship.from_name.len()
+        + relationship.to_name.len()
+}
+
+fn spill_error(source: std::io::Error) -> PipelineError {
+    PipelineError::Spill(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn relationship(from: &str, to: &str) -> UnresolvedRelationship {
+        UnresolvedRelationship {
+            from_id: SymbolId::new(1),
+            from_name: Arc::from(from),
+            to_name: Arc::from(to),
+            file_id: FileId::new(1).unwrap(),
+            kind: RelationKind::Calls,
+            metadata: None,
+            to_range: None,
+        }
+    }
+
+    #[test]
+    fn test_budget_blocks_until_released() {
+        let budget = MemoryBudget::new(1);
+        let megabyte = 1024 * 1024;
+
+        let (first, _) = budget.acquire(megabyte / 2);
+        let (second, _) = budget.acquire(megabyte / 2);
+        assert_eq!(budget.in_flight(), megabyte);
+
+        let waiter = {
+            let budget = Arc::clone(&budget);
+            thread::spawn(move || {
+                let (permit, _) = budget.acquire(megabyte / 4);
+                permit.bytes()
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        assert_eq!(waiter.join().unwrap(), megabyte / 4);
+        drop(second);
+        assert_eq!(budget.in_flight(), 0);
+
+        // Larger than the whole budget, but nothing else is in flight
+        let (huge, _) = budget.acquire(4 * megabyte);
+        assert_eq!(budget.in_flight(), 4 * megabyte);
+        drop(huge);
+    }
+
+    #[test]
+    fn test_relationships_spill_and_come_back_in_order() {
+        let mut spill = RelationshipSpill::new(Some(1));
+        spill
+            .extend([relationship("main", "parse"), relationship("main", "run")])
+            .unwrap();
+        assert_eq!(spill.spilled(), 2);
+
+        let mut unspilled = RelationshipSpill::new(None);
+        unspilled.extend([relationship("run", "exit")]).unwrap();
+        assert_eq!(unspilled.spilled(), 0);
+
+        let relationships = spill.finish().unwrap();
+        let names: Vec<(&str, &str)> = relationships
+            .iter()
+            .map(|r| (&*r.from_name, &*r.to_name))
+            .collect();
+        assert_eq!(names, vec![("main", "parse"), ("main", "run")]);
+        assert!(Arc::ptr_eq(
+            &relationships[0].from_name,
+            &relationships[1].from_name
+        ));
+        assert_eq!(unspilled.finish().unwrap().len(), 1);
+    }
+}
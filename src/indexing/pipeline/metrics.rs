@@ -1,15 +1,20 @@
 //! Pipeline metrics collection and reporting.
 //!
 //! Tracks timing, throughput, channel wait times, and memory usage
-//! for each pipeline stage to identify bottlenecks.
+//! for each pipeline stage to identify bottlenecks. The report is logged
+//! when `pipeline_tracing` is on and written as JSON to
+//! `PipelineConfig::metrics_json` (`codanna index --metrics-json`).
 
+use super::PipelineConfig;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use sysinfo::{Pid, ProcessRefreshKind, System};
 
 /// Metrics for a single pipeline stage.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct StageMetrics {
     /// Stage name
     pub name: &'static str,
@@ -233,6 +238,41 @@ impl PipelineReport {
             .max_by(|a, b| a.wall_time.cmp(&b.wall_time))
     }
 
+    /// Write the report as JSON to `path`, creating parent directories.
+    pub fn write_json(&self, path: &Path) -> std::io::Result<()> {
+        let export = ReportExport {
+            directory: &self.directory,
+            total_ms: millis(self.total_time),
+            memory_start_bytes: self.memory_start.rss,
+            memory_end_bytes: self.memory_end.rss,
+            bottleneck: self.bottleneck().map(|stage| stage.name),
+            stages: self
+                .stages
+                .iter()
+                .map(|stage| StageExport {
+                    name: stage.name,
+                    threads: stage.threads,
+                    items: stage.items_processed,
+                    items_per_sec: stage.throughput(),
+                    wall_ms: millis(stage.wall_time),
+                    active_ms: millis(stage.active_time()),
+                    input_wait_ms: millis(stage.input_wait),
+                    output_wait_ms: millis(stage.output_wait),
+                    share_pct: stage.percentage_of(self.total_time),
+                    secondary_label: (!stage.secondary_label.is_empty())
+                        .then_some(stage.secondary_label),
+                    secondary_count: stage.secondary_count,
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&export).map_err(std::io::Error::other)?;
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)
+    }
+
     /// Log the report using tracing.
     pub fn log(&self) {
         tracing::info!(target: "pipeline", "");
@@ -298,10 +338,48 @@ impl PipelineReport {
     }
 }
 
+/// JSON form of a [`PipelineReport`].
+#[derive(Serialize)]
+struct ReportExport<'a> {
+    directory: &'a str,
+    total_ms: f64,
+    memory_start_bytes: u64,
+    memory_end_bytes: u64,
+    bottleneck: Option<&'static str>,
+    stages: Vec<StageExport>,
+}
+
+/// JSON form of a [`StageMetrics`].
+#[derive(Serialize)]
+struct StageExport {
+    name: &'static str,
+    threads: usize,
+    items: usize,
+    /// Absent when the stage ran too briefly to measure
+    items_per_sec: Option<f64>,
+    wall_ms: f64,
+    active_ms: f64,
+    input_wait_ms: f64,
+    output_wait_ms: f64,
+    /// Wall time as a percentage of the whole run
+    share_pct: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secondary_label: Option<&'static str>,
+    secondary_count: usize,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1_000_000.0
+}
+
 /// Shared metrics collector for pipeline-wide tracking.
 #[derive(Debug)]
 pub struct PipelineMetrics {
     enabled: bool,
+    /// Log the report when it is finalized
+    trace: bool,
+    /// Where to write the JSON report
+    export_path: Option<PathBuf>,
     report: std::sync::Mutex<PipelineReport>,
 }
 
@@ -310,10 +388,26 @@ impl PipelineMetrics {
     pub fn new(directory: impl Into<String>, enabled: bool) -> Arc<Self> {
         Arc::new(Self {
             enabled,
+            trace: enabled,
+            export_path: None,
             report: std::sync::Mutex::new(PipelineReport::new(directory)),
         })
     }
 
+    /// Collector for a run over `root`, or `None` when neither
+    /// `pipeline_tracing` nor `metrics_json` asks for metrics.
+    pub fn from_config(root: &Path, config: &PipelineConfig) -> Option<Arc<Self>> {
+        if !config.pipeline_tracing && config.metrics_json.is_none() {
+            return None;
+        }
+        Some(Arc::new(Self {
+            enabled: true,
+            trace: config.pipeline_tracing,
+            export_path: config.metrics_json.clone(),
+            report: std::sync::Mutex::new(PipelineReport::new(root.display().to_string())),
+        }))
+    }
+
     /// Check if metrics collection is enabled.
     pub fn is_enabled(&self) -> bool {
         self.enabled
@@ -328,6 +422,13 @@ impl PipelineMetrics {
         }
     }
 
+    /// Add the metrics of several stages, in order.
+    pub fn add_stages(&self, stages: impl IntoIterator<Item = StageMetrics>) {
+        for metrics in stages {
+            self.add_stage(metrics);
+        }
+    }
+
     /// Finalize the report without logging.
     /// Use this when logging needs to be deferred (e.g., until StatusLine is dropped).
     pub fn finalize(&self, total_time: Duration) {
@@ -341,7 +442,7 @@ impl PipelineMetrics {
     /// Log the finalized report.
     /// Call after StatusLine is dropped to avoid stderr race conditions.
     pub fn log(&self) {
-        if self.enabled {
+        if self.trace {
             if let Ok(report) = self.report.lock() {
                 report.log();
             }
@@ -350,10 +451,27 @@ impl PipelineMetrics {
 
     /// Finalize and log the report.
     pub fn finalize_and_log(&self, total_time: Duration) {
-        if self.enabled {
-            if let Ok(mut report) = self.report.lock() {
-                report.finalize(total_time);
-                report.log();
+        self.finalize(total_time);
+        self.log();
+    }
+
+    /// Write the finalized report to the `metrics_json` path, if one is set.
+    ///
+    /// A failed write is logged rather than failing the indexing run.
+    pub fn export(&self) {
+        let Some(path) = &self.export_path else {
+            return;
+        };
+        if let Ok(report) = self.report.lock() {
+            match report.write_json(path) {
+                Ok(()) => {
+                    tracing::info!(target: "pipeline", "Wrote pipeline metrics to {}", path.display())
+                }
+                Err(e) => tracing::warn!(
+                    target: "pipeline",
+                    "Failed to write pipeline metrics to {}: {e}",
+                    path.display()
+                ),
             }
         }
     }
@@ -381,6 +499,48 @@ mod tests {
         assert!(metrics.wall_time >= Duration::from_millis(10));
     }
 
+    #[test]
+    fn test_write_json_reports_each_stage() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("out/metrics.json");
+
+        let mut report = PipelineReport::new("src");
+        report.add_stage(StageMetrics {
+            name: "PARSE",
+            threads: 4,
+            wall_time: Duration::from_millis(400),
+            input_wait: Duration::from_millis(100),
+            output_wait: Duration::from_millis(50),
+            items_processed: 200,
+            secondary_count: 3000,
+            secondary_label: "symbols",
+        });
+        report.add_stage(StageMetrics {
+            name: "RESOLVE",
+            threads: 1,
+            wall_time: Duration::from_millis(100),
+            ..Default::default()
+        });
+        report.finalize(Duration::from_millis(500));
+        report.write_json(&path).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["bottleneck"], "PARSE");
+        assert_eq!(json["total_ms"], 500.0);
+
+        let parse = &json["stages"][0];
+        assert_eq!(parse["threads"], 4);
+        assert_eq!(parse["items_per_sec"], 500.0);
+        assert_eq!(parse["active_ms"], 250.0);
+        assert_eq!(parse["share_pct"], 80.0);
+        assert_eq!(parse["secondary_label"], "symbols");
+
+        let resolve = &json["stages"][1];
+        assert_eq!(resolve["name"], "RESOLVE");
+        assert!(resolve.get("secondary_label").is_none());
+    }
+
     #[test]
     fn test_memory_snapshot() {
         let snapshot = MemorySnapshot::current();
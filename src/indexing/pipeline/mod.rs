@@ -29,17 +29,25 @@
 //! let stats = pipeline.index_directory(path, &index)?;
 //! ```
 
+pub mod cancel;
 pub mod config;
+pub mod dedup;
+pub mod memory;
 pub mod metrics;
+pub mod sniff;
 pub mod stages;
 pub mod types;
 
+pub use cancel::PipelineCancel;
 pub use config::PipelineConfig;
+pub use dedup::ContentDedup;
+pub use memory::{MemoryBudget, MemoryPermit, RelationshipSpill};
 pub use metrics::{PipelineMetrics, StageMetrics, StageTracker};
 pub use stages::cleanup::{CleanupStage, CleanupStats};
 pub use stages::context::{ContextStage, ContextStats};
 pub use stages::embed::{EmbedStage, EmbedStats};
 pub use stages::parse::{ParseStage, init_parser_cache, parse_file};
+pub use stages::relink::{IncomingEdge, RelinkStage, RelinkStats};
 pub use stages::resolve::{ResolveStage, ResolveStats};
 pub use stages::semantic_embed::{SemanticEmbedStage, SemanticEmbedStats};
 pub use stages::write::{WriteStage, WriteStats};
@@ -54,13 +62,17 @@ use crate::FileId;
 use crate::RelationKind;
 use crate::Settings;
 use crate::indexing::IndexStats;
+use crate::indexing::priority::FilePriorities;
+use crate::indexing::transaction;
 use crate::io::status_line::DualProgressBar;
 use crate::parsing::ParserFactory;
 use crate::semantic::SimpleSemanticSearch;
-use crate::storage::DocumentIndex;
-use crossbeam_channel::bounded;
+use crate::storage::{AliasStore, DocumentIndex, EventLog};
+use crossbeam_channel::{Receiver, Sender, bounded};
 use stages::{CollectStage, DiscoverStage, IndexStage, ReadStage};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -82,9 +94,6 @@ type ReadJoinHandle =
 /// Returns (files, errors, symbols, input_wait, output_wait, wall_time).
 type ParseJoinHandle = thread::JoinHandle<(usize, usize, usize, Duration, Duration, Duration)>;
 
-/// Thread join handle type for simple PARSE workers (no timing).
-type ParseSimpleJoinHandle = thread::JoinHandle<(usize, usize)>;
-
 /// The parallel indexing pipeline.
 ///
 /// [PIPELINE API] Orchestrates multiple stages to efficiently index source code
@@ -92,12 +101,63 @@ type ParseSimpleJoinHandle = thread::JoinHandle<(usize, usize)>;
 pub struct Pipeline {
     settings: Arc<Settings>,
     config: PipelineConfig,
+    /// Log of index mutations, present when `indexing.event_log` is enabled.
+    event_log: Option<Arc<EventLog>>,
+    /// Symbols of the whole index, kept across single-file updates so each
+    /// edit only swaps the symbols of the file that changed.
+    resolution_cache: Mutex<Option<Arc<SymbolLookupCache>>>,
+    /// Cancels the run in progress.
+    cancel: PipelineCancel,
+    /// Runs in progress, more than one when a run calls another.
+    runs: AtomicUsize,
+}
+
+/// Housekeeping when a pipeline run returns.
+///
+/// Held for the duration of a run so early returns after cleanup still log
+/// the files that were removed but never re-indexed, so the run after a
+/// cancelled one starts with a fresh cancellation token, and so strings
+/// interned only for the run's caches are released. The outermost guard
+/// also gives up a journal Phase 2 never settled, so the next run rolls its
+/// files back.
+struct RunGuard<'a> {
+    event_log: Option<&'a EventLog>,
+    cancel: &'a PipelineCancel,
+    runs: &'a AtomicUsize,
+    tantivy_path: PathBuf,
+}
+
+impl Drop for RunGuard<'_> {
+    fn drop(&mut self) {
+        if self.runs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            transaction::release(&self.tantivy_path);
+        }
+        if let Some(event_log) = self.event_log {
+            if let Err(e) = event_log.flush_pending() {
+                tracing::warn!(target: "pipeline", "Failed to write index events: {e}");
+            }
+        }
+        self.cancel.reset();
+        let pruned = crate::types::intern::prune();
+        tracing::debug!(target: "pipeline", "released {pruned} interned strings");
+    }
 }
 
 impl Pipeline {
     /// Create a new pipeline with the given settings and configuration.
     pub fn new(settings: Arc<Settings>, config: PipelineConfig) -> Self {
-        Self { settings, config }
+        let event_log = settings
+            .indexing
+            .event_log
+            .then(|| Arc::new(EventLog::new(&settings.index_path)));
+        Self {
+            settings,
+            config,
+            event_log,
+            resolution_cache: Mutex::new(None),
+            cancel: PipelineCancel::default(),
+            runs: AtomicUsize::new(0),
+        }
     }
 
     /// Create a pipeline with configuration derived from settings.
@@ -116,6 +176,27 @@ impl Pipeline {
         &self.settings
     }
 
+    /// Get the index event log, if enabled.
+    pub fn event_log(&self) -> Option<Arc<EventLog>> {
+        self.event_log.clone()
+    }
+
+    /// Handle that cancels the run in progress from another thread.
+    pub fn cancellation(&self) -> PipelineCancel {
+        self.cancel.clone()
+    }
+
+    /// Guard that tidies up after a run on `index`, whichever way it returns.
+    fn run_guard(&self, index: &DocumentIndex) -> RunGuard<'_> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        RunGuard {
+            event_log: self.event_log.as_deref(),
+            cancel: &self.cancel,
+            runs: &self.runs,
+            tantivy_path: index.path().to_path_buf(),
+        }
+    }
+
     // ─────────────────────────────────────────────────────────────────────────────
     // Helper methods for consistent data flow
     // ─────────────────────────────────────────────────────────────────────────────
@@ -233,28 +314,163 @@ impl Pipeline {
         )
     }
 
-    /// Join simple PARSE worker threads (no timing tracking).
-    ///
-    /// Used by `index_files` which doesn't need wait time metrics.
-    /// Returns (files_parsed, errors).
-    fn join_parse_workers_simple(&self, handles: Vec<ParseSimpleJoinHandle>) -> (usize, usize) {
-        let mut files = 0;
-        let mut errors = 0;
+    /// Spawn PARSE workers that record wait times, joined by `join_parse_workers`.
+    fn spawn_parse_workers(
+        &self,
+        content_rx: Receiver<FileContent>,
+        parsed_tx: Sender<ParsedFile>,
+    ) -> Vec<ParseJoinHandle> {
+        (0..self.config.parse_threads)
+            .map(|_| {
+                let rx = content_rx.clone();
+                let tx = parsed_tx.clone();
+                let settings = Arc::clone(&self.settings);
+                let cancel = self.cancel.token();
+                thread::spawn(move || {
+                    let start = Instant::now();
+                    init_parser_cache(settings.clone());
+                    let stage = ParseStage::new(settings);
+                    let mut parsed = 0;
+                    let mut errors = 0;
+                    let mut symbol_count = 0;
+                    let mut input_wait = Duration::ZERO;
+                    let mut output_wait = Duration::ZERO;
 
-        for handle in handles {
-            match handle.join() {
-                Ok((f, e)) => {
-                    files += f;
-                    errors += e;
-                }
-                Err(_) => {
-                    tracing::error!(target: "pipeline", "PARSE worker panicked");
-                    errors += 1;
-                }
+                    loop {
+                        let recv_start = Instant::now();
+                        let Ok(content) = rx.recv() else {
+                            break;
+                        };
+                        input_wait += recv_start.elapsed();
+                        if cancel.is_cancelled() {
+                            break;
+                        }
+
+                        match stage.parse(content) {
+                            Ok(p) => {
+                                parsed += 1;
+                                symbol_count += p.raw_symbols.len();
+
+                                let send_start = Instant::now();
+                                if tx.send(p).is_err() {
+                                    break;
+                                }
+                                output_wait += send_start.elapsed();
+                            }
+                            Err(_) => errors += 1,
+                        }
+                    }
+                    (
+                        parsed,
+                        errors,
+                        symbol_count,
+                        input_wait,
+                        output_wait,
+                        start.elapsed(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Hot files to index first, when `indexing.priority_indexing` is on.
+    fn file_priorities(&self, root: &Path) -> Option<Arc<FilePriorities>> {
+        self.settings
+            .indexing
+            .priority_indexing
+            .then(|| Arc::new(FilePriorities::detect(root, &self.settings.index_path)))
+    }
+
+    /// Symbol cache for resolving a single re-indexed file.
+    ///
+    /// The first call loads every symbol; later calls drop the symbols of
+    /// `replaced` and add those of `file_id`. A symbol count that no longer
+    /// matches the index (a file removed in between) triggers a reload.
+    fn resolution_cache(
+        &self,
+        index: &DocumentIndex,
+        replaced: Option<FileId>,
+        file_id: FileId,
+    ) -> PipelineResult<Arc<SymbolLookupCache>> {
+        let mut slot = self
+            .resolution_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        if let Some(cache) = slot.as_ref() {
+            if let Some(replaced) = replaced {
+                cache.remove_file(replaced);
             }
+            cache.remove_file(file_id);
+            for symbol in index.find_symbols_by_file(file_id)? {
+                cache.insert(symbol);
+            }
+            if index.count_symbols()? == cache.len() {
+                return Ok(Arc::clone(cache));
+            }
+            tracing::debug!(target: "pipeline", "Symbol cache out of date, reloading");
+        }
+
+        let cache = Arc::new(SymbolLookupCache::from_index(index)?);
+        *slot = Some(Arc::clone(&cache));
+        Ok(cache)
+    }
+
+    /// Drop the single-file symbol cache after a bulk run rewrote the index.
+    fn invalidate_resolution_cache(&self) {
+        *self
+            .resolution_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Content de-duplication for one run, when `indexing.dedup_identical_files`
+    /// is on. Deterministic runs read every file in full, since which copy is
+    /// read first depends on thread timing.
+    fn content_dedup(&self) -> Option<Arc<ContentDedup>> {
+        (self.settings.indexing.dedup_identical_files && !self.config.deterministic)
+            .then(ContentDedup::new)
+    }
+
+    /// Roll back batches that never committed, left by a crash or a failed run.
+    ///
+    /// Their files are removed from the index. Returns the rolled-back paths
+    /// for the caller to re-queue with [`requeue_rolled_back`].
+    fn recover_transactions(
+        &self,
+        index: &Arc<DocumentIndex>,
+        semantic: Option<&Arc<Mutex<SimpleSemanticSearch>>>,
+    ) -> PipelineResult<Vec<PathBuf>> {
+        let journals = transaction::abandoned(index.path())?;
+        if journals.is_empty() {
+            return Ok(Vec::new());
         }
 
-        (files, errors)
+        let mut files: Vec<PathBuf> = journals
+            .iter()
+            .flat_map(|(_, journal)| journal.files.iter().cloned())
+            .collect();
+        files.sort();
+        files.dedup();
+
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.settings.index_path);
+        let mut cleanup_stage =
+            CleanupStage::new(Arc::clone(index), &semantic_path).with_event_log(self.event_log());
+        if let Some(semantic) = semantic {
+            cleanup_stage = cleanup_stage.with_semantic(Arc::clone(semantic));
+        }
+        cleanup_stage.cleanup_files(&files)?;
+        for (journal_path, _) in &journals {
+            transaction::discard(journal_path)?;
+        }
+
+        tracing::warn!(
+            target: "pipeline",
+            "Rolled back {} file(s) from {} interrupted batch(es); re-indexing them",
+            files.len(),
+            journals.len()
+        );
+        Ok(files)
     }
 
     /// Index a directory using the parallel pipeline (Phase 1).
@@ -276,16 +492,15 @@ impl Pipeline {
         index: Arc<DocumentIndex>,
     ) -> PipelineResult<(IndexStats, Vec<UnresolvedRelationship>, SymbolLookupCache)> {
         let start = Instant::now();
+        let _run_guard = self.run_guard(&index);
 
-        // Create metrics collector if tracing is enabled
-        let metrics = if self.config.pipeline_tracing {
-            Some(PipelineMetrics::new(root.display().to_string(), true))
-        } else {
-            None
-        };
+        // Create metrics collector if tracing or metrics export is enabled
+        let metrics = PipelineMetrics::from_config(root, &self.config);
 
         // Query existing ID counters BEFORE spawning threads
         let (start_file_counter, start_symbol_counter) = self.get_start_counters(&index)?;
+        self.invalidate_resolution_cache();
+        let cancel = self.cancel.token();
 
         // Create bounded channels with backpressure
         let (path_tx, path_rx) = bounded(self.config.path_channel_size);
@@ -299,11 +514,15 @@ impl Pipeline {
         let read_threads = self.config.read_threads;
         let discover_threads = self.config.discover_threads;
         let batch_size = self.config.batch_size;
+        let deterministic = self.config.deterministic;
+        let blame = self.settings.indexing.blame;
         let batches_per_commit = self.config.batches_per_commit;
-        let tracing_enabled = self.config.pipeline_tracing;
+        let tracing_enabled = metrics.is_some();
 
         // Stage 1: DISCOVER - parallel file walk
         let discover_root = root.to_path_buf();
+        let priorities = self.file_priorities(root);
+        let index_archives = settings.indexing.index_archives;
         let discover_handle = thread::spawn(move || {
             let tracker = if tracing_enabled {
                 Some(StageTracker::new("DISCOVER", discover_threads))
@@ -311,7 +530,9 @@ impl Pipeline {
                 None
             };
 
-            let stage = DiscoverStage::new(discover_root, discover_threads);
+            let stage = DiscoverStage::new(discover_root, discover_threads)
+                .with_priorities(priorities)
+                .with_archives(index_archives);
             let result = stage.run(path_tx);
 
             // Record metrics
@@ -324,13 +545,23 @@ impl Pipeline {
 
         // Stage 2: READ - multi-threaded file reading
         let workspace_root = settings.workspace_root.clone();
+        let memory_budget = MemoryBudget::new(self.config.memory_budget_mb);
+        let generated_files = settings.indexing.generated_files;
+        let dedup = self.content_dedup();
         let read_handles: Vec<_> = (0..read_threads)
             .map(|_| {
                 let rx = path_rx.clone();
                 let tx = content_tx.clone();
                 let workspace_root = workspace_root.clone();
+                let memory_budget = Arc::clone(&memory_budget);
+                let dedup = dedup.clone();
+                let cancel = cancel.clone();
                 thread::spawn(move || {
-                    let stage = ReadStage::with_workspace_root(1, workspace_root);
+                    let stage = ReadStage::with_workspace_root(1, workspace_root)
+                        .with_memory_budget(memory_budget)
+                        .with_generated_files(generated_files)
+                        .with_dedup(dedup)
+                        .with_cancellation(cancel);
                     stage.run(rx, tx)
                 })
             })
@@ -344,6 +575,7 @@ impl Pipeline {
                 let rx = content_rx.clone();
                 let tx = parsed_tx.clone();
                 let settings = Arc::clone(&settings);
+                let cancel = cancel.clone();
                 thread::spawn(move || {
                     let start = Instant::now();
                     // Initialize thread-local parser cache
@@ -364,6 +596,9 @@ impl Pipeline {
                             Err(_) => break, // Channel closed
                         };
                         input_wait += recv_start.elapsed();
+                        if cancel.is_cancelled() {
+                            break;
+                        }
 
                         match stage.parse(content) {
                             Ok(parsed) => {
@@ -407,7 +642,8 @@ impl Pipeline {
             };
 
             let stage = CollectStage::new(batch_size)
-                .with_start_counters(start_file_counter, start_symbol_counter);
+                .with_start_counters(start_file_counter, start_symbol_counter)
+                .with_deterministic_order(deterministic);
             let result = stage.run(parsed_rx, batch_tx, None, None);
 
             // Record items and wait times before finalizing
@@ -425,6 +661,8 @@ impl Pipeline {
         // Stage 5: INDEX - single-threaded Tantivy writes
         // Clone index Arc for metadata update after pipeline completes
         let index_for_metadata = Arc::clone(&index);
+        let event_log = self.event_log();
+        let spill_threshold = self.config.relationship_spill_bytes();
         let index_handle = thread::spawn(move || {
             let tracker = if tracing_enabled {
                 Some(StageTracker::new("INDEX", 1).with_secondary("commits"))
@@ -432,7 +670,12 @@ impl Pipeline {
                 None
             };
 
-            let stage = IndexStage::new(index, batches_per_commit);
+            let stage = IndexStage::new(index, batches_per_commit)
+                .with_event_log(event_log)
+                .with_spill_threshold(spill_threshold)
+                .with_ordered_writes(deterministic)
+                .with_blame(blame)
+                .with_cancellation(cancel);
             let result = stage.run(batch_rx);
 
             // Record items and wait times before finalizing
@@ -548,11 +791,15 @@ impl Pipeline {
         root: &Path,
         index: Arc<DocumentIndex>,
         progress: Option<Arc<crate::io::status_line::ProgressBar>>,
-    ) -> PipelineResult<(IndexStats, Vec<UnresolvedRelationship>, SymbolLookupCache)> {
+    ) -> PipelineResult<Phase1Result> {
         let start = Instant::now();
+        let metrics = PipelineMetrics::from_config(root, &self.config);
+        let metrics_enabled = metrics.is_some();
 
         // Query existing ID counters BEFORE spawning threads
         let (start_file_counter, start_symbol_counter) = self.get_start_counters(&index)?;
+        self.invalidate_resolution_cache();
+        let cancel = self.cancel.token();
 
         // Create bounded channels
         let (path_tx, path_rx) = bounded(self.config.path_channel_size);
@@ -565,24 +812,45 @@ impl Pipeline {
         let read_threads = self.config.read_threads;
         let discover_threads = self.config.discover_threads;
         let batch_size = self.config.batch_size;
+        let deterministic = self.config.deterministic;
+        let blame = self.settings.indexing.blame;
         let batches_per_commit = self.config.batches_per_commit;
 
         // Stage 1: DISCOVER
         let discover_root = root.to_path_buf();
+        let priorities = self.file_priorities(root);
+        let index_archives = settings.indexing.index_archives;
         let discover_handle = thread::spawn(move || {
-            let stage = DiscoverStage::new(discover_root, discover_threads);
-            stage.run(path_tx)
+            let tracker = metrics_enabled.then(|| StageTracker::new("DISCOVER", discover_threads));
+            let stage = DiscoverStage::new(discover_root, discover_threads)
+                .with_priorities(priorities)
+                .with_archives(index_archives);
+            let result = stage.run(path_tx);
+            if let (Some(tracker), Ok(count)) = (&tracker, &result) {
+                tracker.record_items(*count);
+            }
+            (result, tracker.map(|t| t.finalize()))
         });
 
         // Stage 2: READ
         let workspace_root = settings.workspace_root.clone();
+        let memory_budget = MemoryBudget::new(self.config.memory_budget_mb);
+        let generated_files = settings.indexing.generated_files;
+        let dedup = self.content_dedup();
         let read_handles: Vec<_> = (0..read_threads)
             .map(|_| {
                 let rx = path_rx.clone();
                 let tx = content_tx.clone();
                 let workspace_root = workspace_root.clone();
+                let memory_budget = Arc::clone(&memory_budget);
+                let dedup = dedup.clone();
+                let cancel = cancel.clone();
                 thread::spawn(move || {
-                    let stage = ReadStage::with_workspace_root(1, workspace_root);
+                    let stage = ReadStage::with_workspace_root(1, workspace_root)
+                        .with_memory_budget(memory_budget)
+                        .with_generated_files(generated_files)
+                        .with_dedup(dedup)
+                        .with_cancellation(cancel);
                     stage.run(rx, tx)
                 })
             })
@@ -591,72 +859,106 @@ impl Pipeline {
         drop(content_tx);
 
         // Stage 3: PARSE
-        let parse_handles: Vec<_> = (0..parse_threads)
-            .map(|_| {
-                let rx = content_rx.clone();
-                let tx = parsed_tx.clone();
-                let settings = Arc::clone(&settings);
-                thread::spawn(move || {
-                    init_parser_cache(settings.clone());
-                    let stage = ParseStage::new(settings);
-                    let mut parsed = 0;
-                    let mut errors = 0;
-
-                    for content in rx {
-                        match stage.parse(content) {
-                            Ok(p) => {
-                                parsed += 1;
-                                if tx.send(p).is_err() {
-                                    break;
-                                }
-                            }
-                            Err(_) => errors += 1,
-                        }
-                    }
-                    (parsed, errors)
-                })
-            })
-            .collect();
-        drop(content_rx);
-        drop(parsed_tx);
+        let parse_handles = self.spawn_parse_workers(content_rx, parsed_tx);
 
         // Stage 4: COLLECT (with starting counters for multi-directory support)
         let collect_handle = thread::spawn(move || {
             let stage = CollectStage::new(batch_size)
-                .with_start_counters(start_file_counter, start_symbol_counter);
+                .with_start_counters(start_file_counter, start_symbol_counter)
+                .with_deterministic_order(deterministic);
             stage.run(parsed_rx, batch_tx, None, None)
         });
+        let collect_start = Instant::now();
 
         // Stage 5: INDEX with optional progress
         // Clone index Arc for metadata update after pipeline completes
         let index_for_metadata = Arc::clone(&index);
-        let mut index_stage = IndexStage::new(index, batches_per_commit);
+        let mut index_stage = IndexStage::new(index, batches_per_commit)
+            .with_event_log(self.event_log())
+            .with_spill_threshold(self.config.relationship_spill_bytes())
+            .with_ordered_writes(deterministic)
+            .with_blame(blame)
+            .with_cancellation(cancel);
         if let Some(prog) = progress {
             index_stage = index_stage.with_progress(prog);
         }
         let index_handle = thread::spawn(move || index_stage.run(batch_rx));
+        let index_start = Instant::now();
 
         // Wait for all stages
-        let discover_result = discover_handle
+        let (discover_result, discover_metrics) = discover_handle
             .join()
             .map_err(|_| PipelineError::ChannelRecv("DISCOVER panicked".to_string()))?;
         let _files_discovered = discover_result?;
 
         // Join READ workers and capture errors
-        let (_, read_errors, _, _, _) = self.join_read_workers(read_handles);
+        let (read_files, read_errors, read_input_wait, read_output_wait, read_wall_time) =
+            self.join_read_workers(read_handles);
 
         // Join PARSE workers and capture errors
-        let (_, parse_errors) = self.join_parse_workers_simple(parse_handles);
+        let (
+            parsed_files,
+            parse_errors,
+            total_symbols,
+            parse_input_wait,
+            parse_output_wait,
+            parse_wall_time,
+        ) = self.join_parse_workers(parse_handles);
 
         // Get final counter values from COLLECT stage
-        let (final_file_count, final_symbol_count, _, _, _) = collect_handle
-            .join()
-            .map_err(|_| PipelineError::ChannelRecv("COLLECT panicked".to_string()))??;
+        let (final_file_count, final_symbol_count, _, collect_input_wait, collect_output_wait) =
+            collect_handle
+                .join()
+                .map_err(|_| PipelineError::ChannelRecv("COLLECT panicked".to_string()))??;
+        let collect_wall_time = collect_start.elapsed();
 
         let index_result = index_handle
             .join()
             .map_err(|_| PipelineError::ChannelRecv("INDEX panicked".to_string()))?;
-        let (mut stats, pending_relationships, symbol_cache, _) = index_result?;
+        let (mut stats, pending_relationships, symbol_cache, index_input_wait) = index_result?;
+
+        if let Some(m) = &metrics {
+            m.add_stages(discover_metrics);
+            m.add_stages([
+                StageMetrics {
+                    name: "READ",
+                    threads: read_threads,
+                    wall_time: read_wall_time,
+                    input_wait: read_input_wait,
+                    output_wait: read_output_wait,
+                    items_processed: read_files,
+                    ..Default::default()
+                },
+                StageMetrics {
+                    name: "PARSE",
+                    threads: parse_threads,
+                    wall_time: parse_wall_time,
+                    input_wait: parse_input_wait,
+                    output_wait: parse_output_wait,
+                    items_processed: parsed_files,
+                    secondary_count: total_symbols,
+                    secondary_label: "symbols",
+                },
+                StageMetrics {
+                    name: "COLLECT",
+                    threads: 1,
+                    wall_time: collect_wall_time,
+                    input_wait: collect_input_wait,
+                    output_wait: collect_output_wait,
+                    items_processed: final_symbol_count.saturating_sub(start_symbol_counter)
+                        as usize,
+                    ..Default::default()
+                },
+                StageMetrics {
+                    name: "INDEX",
+                    threads: 1,
+                    wall_time: index_start.elapsed(),
+                    input_wait: index_input_wait,
+                    items_processed: stats.symbols_found,
+                    ..Default::default()
+                },
+            ]);
+        }
 
         // Store final counter values to metadata
         self.save_final_counters(&index_for_metadata, final_file_count, final_symbol_count)?;
@@ -665,7 +967,12 @@ impl Pipeline {
         stats.elapsed = start.elapsed();
         stats.files_failed = read_errors + parse_errors;
 
-        Ok((stats, pending_relationships, symbol_cache))
+        // Finalize metrics but don't log (caller logs after StatusLine drop)
+        if let Some(m) = &metrics {
+            m.finalize(start.elapsed());
+        }
+
+        Ok((stats, pending_relationships, symbol_cache, metrics))
     }
 
     /// Run Phase 2: Resolve relationships using two-pass strategy.
@@ -703,6 +1010,7 @@ impl Pipeline {
         let total_relationships = unresolved.len();
 
         if unresolved.is_empty() {
+            transaction::settle(index.path())?;
             return Ok(Phase2Stats {
                 total_relationships: 0,
                 defines_resolved: 0,
@@ -710,9 +1018,14 @@ impl Pipeline {
                 other_resolved: 0,
                 unresolved: 0,
                 elapsed: start.elapsed(),
+                stages: Vec::new(),
             });
         }
 
+        // Files being resolved, rolled back if the run is cancelled
+        let cancel = self.cancel.token();
+        let files: HashSet<FileId> = unresolved.iter().map(|rel| rel.file_id).collect();
+
         // Create stages
         let factory = Arc::new(ParserFactory::new(Arc::clone(&self.settings)));
         let context_stage =
@@ -729,6 +1042,13 @@ impl Pipeline {
             ..Default::default()
         };
 
+        // Per-stage wall times, summed over both passes
+        let mut context_time = Duration::ZERO;
+        let mut resolve_time = Duration::ZERO;
+        let mut write_time = Duration::ZERO;
+        let mut contexts_built = 0;
+        let mut written = 0;
+
         // Pass 1: Resolve Defines
         tracing::info!(
             target: "pipeline",
@@ -736,15 +1056,26 @@ impl Pipeline {
             defines.len()
         );
         if !defines.is_empty() {
+            let stage_start = Instant::now();
             let contexts = context_stage.build_contexts(defines);
             let behaviors = context_stage.behaviors();
+            context_time += stage_start.elapsed();
+            contexts_built += contexts.len();
             let resolve_stage = ResolveStage::new(Arc::clone(&symbol_cache), behaviors);
 
             for ctx in contexts {
+                if cancel.is_cancelled() {
+                    return Err(self.cancel_phase2(&index, &files));
+                }
                 let rel_count = ctx.unresolved_rels.len() as u64;
+                let stage_start = Instant::now();
                 let (batch, resolve_stats) = resolve_stage.resolve(&ctx);
+                resolve_time += stage_start.elapsed();
                 stats.defines_resolved += resolve_stats.defines_resolved;
-                write_stage.write(batch);
+
+                let stage_start = Instant::now();
+                written += write_stage.write(batch).written;
+                write_time += stage_start.elapsed();
 
                 // Update progress bar
                 if let Some(ref prog) = progress {
@@ -756,9 +1087,11 @@ impl Pipeline {
             }
 
             // BARRIER: Commit Defines so Pass 2 can query them
+            let stage_start = Instant::now();
             write_stage
                 .commit()
                 .map_err(|e| PipelineError::Index(crate::IndexError::General(e.to_string())))?;
+            write_time += stage_start.elapsed();
         }
 
         // Pass 2: Resolve Calls and other relationships
@@ -768,16 +1101,27 @@ impl Pipeline {
             others.len()
         );
         if !others.is_empty() {
+            let stage_start = Instant::now();
             let contexts = context_stage.build_contexts(others);
             let behaviors = context_stage.behaviors();
+            context_time += stage_start.elapsed();
+            contexts_built += contexts.len();
             let resolve_stage = ResolveStage::new(Arc::clone(&symbol_cache), behaviors);
 
             for ctx in contexts {
+                if cancel.is_cancelled() {
+                    return Err(self.cancel_phase2(&index, &files));
+                }
                 let rel_count = ctx.unresolved_rels.len() as u64;
+                let stage_start = Instant::now();
                 let (batch, resolve_stats) = resolve_stage.resolve(&ctx);
+                resolve_time += stage_start.elapsed();
                 stats.calls_resolved += resolve_stats.calls_resolved;
                 stats.other_resolved += resolve_stats.resolved - resolve_stats.calls_resolved;
-                write_stage.write(batch);
+
+                let stage_start = Instant::now();
+                written += write_stage.write(batch).written;
+                write_time += stage_start.elapsed();
 
                 // Update progress bar
                 if let Some(ref prog) = progress {
@@ -789,9 +1133,11 @@ impl Pipeline {
             }
 
             // Final commit
+            let stage_start = Instant::now();
             write_stage
                 .flush()
                 .map_err(|e| PipelineError::Index(crate::IndexError::General(e.to_string())))?;
+            write_time += stage_start.elapsed();
         }
 
         stats.unresolved = stats.total_relationships
@@ -799,6 +1145,33 @@ impl Pipeline {
             - stats.calls_resolved
             - stats.other_resolved;
         stats.elapsed = start.elapsed();
+        stats.stages = vec![
+            StageMetrics {
+                name: "CONTEXT",
+                threads: 1,
+                wall_time: context_time,
+                items_processed: total_relationships,
+                secondary_count: contexts_built,
+                secondary_label: "files",
+                ..Default::default()
+            },
+            StageMetrics {
+                name: "RESOLVE",
+                threads: 1,
+                wall_time: resolve_time,
+                items_processed: total_relationships,
+                secondary_count: total_relationships - stats.unresolved,
+                secondary_label: "resolved",
+                ..Default::default()
+            },
+            StageMetrics {
+                name: "WRITE",
+                threads: 1,
+                wall_time: write_time,
+                items_processed: written,
+                ..Default::default()
+            },
+        ];
 
         tracing::info!(
             target: "pipeline",
@@ -811,9 +1184,36 @@ impl Pipeline {
             stats.elapsed
         );
 
+        // Every relationship of the run is committed; its journal can go
+        transaction::settle(index.path())?;
+
         Ok(stats)
     }
 
+    /// Stop a cancelled Phase 2 and journal the files it was resolving.
+    ///
+    /// Their symbols are committed but not all their relationships are, so
+    /// the next run rolls them back and indexes them again.
+    fn cancel_phase2(&self, index: &DocumentIndex, files: &HashSet<FileId>) -> PipelineError {
+        if let Err(e) = index.abort_batch() {
+            return e.into();
+        }
+        let paths = files
+            .iter()
+            .filter_map(|file_id| index.get_file_path(*file_id).ok().flatten())
+            .map(PathBuf::from);
+        let mut journal = transaction::BatchTransaction::begin(index.path());
+        if let Err(e) = journal.record(paths) {
+            return e.into();
+        }
+        tracing::info!(
+            target: "pipeline",
+            "Resolution cancelled; {} file(s) will be re-indexed by the next run",
+            journal.files().len()
+        );
+        PipelineError::Cancelled
+    }
+
     /// Run full pipeline: Phase 1 (indexing) + Phase 2 (resolution).
     ///
     /// Convenience method that runs both phases in sequence.
@@ -822,6 +1222,8 @@ impl Pipeline {
         root: &Path,
         index: Arc<DocumentIndex>,
     ) -> PipelineResult<(IndexStats, Phase2Stats)> {
+        let _run_guard = self.run_guard(&index);
+
         // Phase 1: Index files
         let (index_stats, unresolved, symbol_cache) =
             self.index_directory(root, Arc::clone(&index))?;
@@ -862,7 +1264,8 @@ impl Pipeline {
         embedding_pool: Option<Arc<crate::semantic::EmbeddingPool>>,
     ) -> PipelineResult<SingleFileStats> {
         let start = Instant::now();
-        let semantic_path = self.settings.index_path.join("semantic");
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.settings.index_path);
+        let _run_guard = self.run_guard(&index);
 
         // Normalize path relative to workspace_root
         let normalized_path = if path.is_absolute() {
@@ -886,13 +1289,17 @@ impl Pipeline {
             })?;
 
         // Read file using ReadStage (with absolute path for fs access)
-        let read_stage = ReadStage::new(1);
+        let read_stage =
+            ReadStage::new(1).with_generated_files(self.settings.indexing.generated_files);
         let mut file_content = read_stage.read_single(&path.to_path_buf())?;
         // Use normalized path for storage consistency with full index
         file_content.path = normalized_path.to_path_buf();
         let content_hash = file_content.hash.clone();
 
         // Check if file already exists by querying Tantivy
+        let mut orphaned_aliases = Vec::new();
+        let mut replaced_file_id = None;
+        let mut incoming = Vec::new();
         if let Ok(Some((existing_file_id, existing_hash, _mtime))) = index.get_file_info(path_str) {
             if existing_hash == content_hash {
                 // File hasn't changed, skip re-indexing
@@ -906,14 +1313,24 @@ impl Pipeline {
                 });
             }
 
+            // Copies of the old content are re-indexed once this file is done
+            orphaned_aliases = stale_aliases(&index, [&normalized_path.to_path_buf()]);
+
+            // Edges from other files into this one, re-pointed after re-indexing
+            replaced_file_id = Some(existing_file_id);
+            incoming = RelinkStage::capture(&index, existing_file_id)?;
+
             // File has changed - cleanup old data within a batch
             // Start batch for cleanup to avoid creating temporary writers
             index.start_batch()?;
 
             let cleanup_stage = if let Some(ref sem) = semantic {
-                CleanupStage::new(Arc::clone(&index), &semantic_path).with_semantic(Arc::clone(sem))
+                CleanupStage::new(Arc::clone(&index), &semantic_path)
+                    .with_semantic(Arc::clone(sem))
+                    .with_event_log(self.event_log())
             } else {
                 CleanupStage::new(Arc::clone(&index), &semantic_path)
+                    .with_event_log(self.event_log())
             };
 
             cleanup_stage.cleanup_files(&[normalized_path.to_path_buf()])?;
@@ -928,12 +1345,16 @@ impl Pipeline {
         let parsed = parse_stage.parse(file_content)?;
 
         // Collect into a batch (now includes embedding candidates)
-        let collect_stage = CollectStage::new(self.config.batch_size);
+        let collect_stage = CollectStage::new(self.config.batch_size)
+            .with_deterministic_order(self.config.deterministic);
         let (batch, unresolved, embed_batch) =
             collect_stage.process_single(parsed, Arc::clone(&index))?;
 
         // Index the batch
-        let index_stage = IndexStage::new(Arc::clone(&index), self.config.batches_per_commit);
+        let index_stage = IndexStage::new(Arc::clone(&index), self.config.batches_per_commit)
+            .with_event_log(self.event_log())
+            .with_ordered_writes(self.config.deterministic)
+            .with_blame(self.settings.indexing.blame);
 
         let symbols_found = batch.symbols.len();
         // Capture file_id before batch is consumed
@@ -979,14 +1400,32 @@ impl Pipeline {
             }
         }
 
-        // Build symbol cache for resolution
-        let symbol_cache = Arc::new(SymbolLookupCache::from_index(&index)?);
-
-        // Run Phase 2 resolution
-        let phase2_stats = self.run_phase2(unresolved, symbol_cache, index)?;
+        // Resolve against the cached symbols of the whole index, updated
+        // for this file only
+        let symbol_cache = self.resolution_cache(&index, replaced_file_id, file_id)?;
+
+        // Run Phase 2 resolution for the relationships this file makes
+        let phase2_stats =
+            self.run_phase2(unresolved, Arc::clone(&symbol_cache), Arc::clone(&index))?;
+
+        // Re-point relationships other files make to this one
+        let relink_stats =
+            RelinkStage::new(Arc::clone(&index), symbol_cache).relink(incoming, file_id)?;
+        let relationships_resolved = phase2_stats.defines_resolved
+            + phase2_stats.calls_resolved
+            + phase2_stats.other_resolved
+            + relink_stats.relinked;
+        tracing::debug!(
+            target: "pipeline",
+            "Updated {} in {:?}: {relationships_resolved} relationships resolved, {} of {} incoming relinked",
+            normalized_path.display(),
+            start.elapsed(),
+            relink_stats.relinked,
+            relink_stats.captured
+        );
 
         // Save embeddings
-        if let Some(sem) = semantic {
+        if let Some(sem) = &semantic {
             if let Ok(guard) = sem.lock() {
                 if let Err(e) = guard.save(&semantic_path) {
                     tracing::warn!(target: "pipeline", "Failed to save embeddings: {e}");
@@ -994,14 +1433,39 @@ impl Pipeline {
             }
         }
 
+        if !orphaned_aliases.is_empty() {
+            let cleanup_stage = CleanupStage::new(Arc::clone(&index), &semantic_path)
+                .with_event_log(self.event_log());
+            index.start_batch()?;
+            cleanup_stage.cleanup_files(&orphaned_aliases)?;
+            index.commit_batch()?;
+
+            for alias in &orphaned_aliases {
+                let alias_path = match &self.settings.workspace_root {
+                    Some(root) => root.join(alias),
+                    None => alias.clone(),
+                };
+                if let Err(e) = self.index_file_single(
+                    &alias_path,
+                    Arc::clone(&index),
+                    semantic.clone(),
+                    embedding_pool.clone(),
+                ) {
+                    tracing::warn!(
+                        target: "pipeline",
+                        "Failed to re-index alias {}: {e}",
+                        alias.display()
+                    );
+                }
+            }
+        }
+
         Ok(SingleFileStats {
             file_id,
             indexed: true,
             cached: false,
             symbols_found,
-            relationships_resolved: phase2_stats.defines_resolved
-                + phase2_stats.calls_resolved
-                + phase2_stats.other_resolved,
+            relationships_resolved,
             elapsed: start.elapsed(),
         })
     }
@@ -1053,9 +1517,11 @@ impl Pipeline {
         if !show_progress {
             return self.index_incremental(root, index, semantic, embedding_pool, force);
         }
+        let rolled_back = self.recover_transactions(&index, semantic.as_ref())?;
 
         let start = Instant::now();
-        let semantic_path = self.settings.index_path.join("semantic");
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.settings.index_path);
+        let _run_guard = self.run_guard(&index);
 
         // Progress bar options shared between phases
         let bar_options = ProgressBarOptions::default()
@@ -1063,7 +1529,7 @@ impl Pipeline {
             .with_width(28);
 
         // Run Phase 1 indexing with appropriate progress bar
-        let (index_stats, unresolved, symbol_cache, cleanup_stats, discover_counts) = if force {
+        let (index_stats, unresolved, symbol_cache, cleanup_stats, counts, metrics) = if force {
             // Force mode: use DualProgressBar for semantic+embedding, else single bar
             let has_embedding = semantic.is_some() && embedding_pool.is_some();
 
@@ -1091,7 +1557,7 @@ impl Pipeline {
 
                 // Drop StatusLine BEFORE logging to avoid stderr race condition
                 drop(dual_status);
-                if let Some(m) = metrics {
+                if let Some(m) = &metrics {
                     m.log();
                 }
                 eprintln!("{dual_bar}");
@@ -1103,6 +1569,7 @@ impl Pipeline {
                     cache,
                     CleanupStats::default(),
                     (files_indexed, 0, 0),
+                    metrics,
                 )
             } else {
                 // Single progress bar (no embedding or no semantic)
@@ -1126,17 +1593,16 @@ impl Pipeline {
                         None,
                     )?
                 } else {
-                    let (s, u, c) = self.index_directory_with_progress(
+                    self.index_directory_with_progress(
                         root,
                         Arc::clone(&index),
                         Some(phase1_bar.clone()),
-                    )?;
-                    (s, u, c, None)
+                    )?
                 };
 
                 // Drop StatusLine BEFORE logging to avoid stderr race condition
                 drop(phase1_status);
-                if let Some(m) = metrics {
+                if let Some(m) = &metrics {
                     m.log();
                 }
                 eprintln!("{phase1_bar}");
@@ -1148,14 +1614,17 @@ impl Pipeline {
                     cache,
                     CleanupStats::default(),
                     (files_indexed, 0, 0),
+                    metrics,
                 )
             }
         } else {
             // Incremental mode: discover first, then create bar with actual count
             let discover_stage = DiscoverStage::new(root, self.config.discover_threads)
+                .with_archives(self.settings.indexing.index_archives)
                 .with_index(Arc::clone(&index))
                 .with_workspace_root(self.settings.workspace_root.clone());
-            let discover_result = discover_stage.run_incremental()?;
+            let mut discover_result = discover_stage.run_incremental()?;
+            requeue_rolled_back(&mut discover_result, rolled_back);
 
             if discover_result.is_empty() {
                 return Ok(IncrementalStats {
@@ -1169,11 +1638,17 @@ impl Pipeline {
                 });
             }
 
+            let metrics = PipelineMetrics::from_config(root, &self.config);
+
             // Cleanup
+            let cleanup_start = Instant::now();
             let cleanup_stage = if let Some(ref sem) = semantic {
-                CleanupStage::new(Arc::clone(&index), &semantic_path).with_semantic(Arc::clone(sem))
+                CleanupStage::new(Arc::clone(&index), &semantic_path)
+                    .with_semantic(Arc::clone(sem))
+                    .with_event_log(self.event_log())
             } else {
                 CleanupStage::new(Arc::clone(&index), &semantic_path)
+                    .with_event_log(self.event_log())
             };
 
             let mut cleanup_stats = CleanupStats::default();
@@ -1187,6 +1662,9 @@ impl Pipeline {
                 cleanup_stats.files_cleaned += stats.files_cleaned;
                 cleanup_stats.symbols_removed += stats.symbols_removed;
             }
+            if let Some(m) = &metrics {
+                m.add_stage(cleanup_metrics(&cleanup_stats, cleanup_start.elapsed()));
+            }
 
             let files_to_index: Vec<PathBuf> = discover_result
                 .new_files
@@ -1213,9 +1691,14 @@ impl Pipeline {
                 semantic.clone(),
                 embedding_pool.clone(),
                 Some(phase1_bar.clone()),
+                metrics.as_deref(),
             )?;
 
             drop(phase1_status);
+            if let Some(m) = &metrics {
+                m.finalize(start.elapsed());
+                m.log();
+            }
             eprintln!("{phase1_bar}");
 
             let counts = (
@@ -1223,7 +1706,7 @@ impl Pipeline {
                 discover_result.modified_files.len(),
                 discover_result.deleted_files.len(),
             );
-            (stats, unresolved, cache, cleanup_stats, counts)
+            (stats, unresolved, cache, cleanup_stats, counts, metrics)
         };
 
         // Run Phase 2 with separate progress bar (no rate - not meaningful for relationships)
@@ -1250,8 +1733,10 @@ impl Pipeline {
             eprintln!("{phase2_bar}");
             stats
         } else {
+            transaction::settle(index.path())?;
             Phase2Stats::default()
         };
+        export_metrics(metrics.as_deref(), &phase2_stats, start.elapsed());
 
         // Save embeddings
         if let Some(sem) = semantic {
@@ -1261,9 +1746,9 @@ impl Pipeline {
         }
 
         Ok(IncrementalStats {
-            new_files: discover_counts.0,
-            modified_files: discover_counts.1,
-            deleted_files: discover_counts.2,
+            new_files: counts.0,
+            modified_files: counts.1,
+            deleted_files: counts.2,
             index_stats,
             cleanup_stats,
             phase2_stats,
@@ -1282,7 +1767,9 @@ impl Pipeline {
         progress: Option<Arc<crate::io::status_line::ProgressBar>>,
     ) -> PipelineResult<IncrementalStats> {
         let start = Instant::now();
-        let semantic_path = self.settings.index_path.join("semantic");
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.settings.index_path);
+        let _run_guard = self.run_guard(&index);
+        let rolled_back = self.recover_transactions(&index, semantic.as_ref())?;
 
         if force {
             // Force mode: index everything (no cleanup needed for fresh index)
@@ -1298,10 +1785,75 @@ impl Pipeline {
 
         // Incremental mode: detect changes
         let discover_stage = DiscoverStage::new(root, self.config.discover_threads)
+            .with_archives(self.settings.indexing.index_archives)
+            .with_index(Arc::clone(&index))
+            .with_workspace_root(self.settings.workspace_root.clone());
+        let mut discover_result = discover_stage.run_incremental()?;
+        requeue_rolled_back(&mut discover_result, rolled_back);
+
+        self.index_discovered(
+            discover_result,
+            index,
+            semantic,
+            embedding_pool,
+            &semantic_path,
+            progress,
+            start,
+        )
+    }
+
+    /// Index only the files git reports as changed since `base`.
+    ///
+    /// Same as [`index_incremental_with_progress`](Self::index_incremental_with_progress)
+    /// without `force`, but discovery asks git for the changed files instead
+    /// of walking and hashing everything under `root`.
+    pub fn index_git_delta(
+        &self,
+        root: &Path,
+        base: &str,
+        index: Arc<DocumentIndex>,
+        semantic: Option<Arc<Mutex<SimpleSemanticSearch>>>,
+        embedding_pool: Option<Arc<crate::semantic::EmbeddingPool>>,
+        progress: Option<Arc<crate::io::status_line::ProgressBar>>,
+    ) -> PipelineResult<IncrementalStats> {
+        let start = Instant::now();
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.settings.index_path);
+        let _run_guard = self.run_guard(&index);
+        let rolled_back = self.recover_transactions(&index, semantic.as_ref())?;
+
+        let discover_stage = DiscoverStage::new(root, self.config.discover_threads)
+            .with_archives(self.settings.indexing.index_archives)
             .with_index(Arc::clone(&index))
             .with_workspace_root(self.settings.workspace_root.clone());
-        let discover_result = discover_stage.run_incremental()?;
+        let mut discover_result = discover_stage.run_git_delta(base)?;
+
+        // Git only reports changed files; re-queue the rolled-back ones too
+        requeue_rolled_back(&mut discover_result, rolled_back);
+
+        self.index_discovered(
+            discover_result,
+            index,
+            semantic,
+            embedding_pool,
+            &semantic_path,
+            progress,
+            start,
+        )
+    }
 
+    /// Clean up and index the files found by incremental discovery, then
+    /// resolve relationships.
+    #[allow(clippy::too_many_arguments)]
+    fn index_discovered(
+        &self,
+        discover_result: DiscoverResult,
+        index: Arc<DocumentIndex>,
+        semantic: Option<Arc<Mutex<SimpleSemanticSearch>>>,
+        embedding_pool: Option<Arc<crate::semantic::EmbeddingPool>>,
+        semantic_path: &Path,
+        progress: Option<Arc<crate::io::status_line::ProgressBar>>,
+        start: Instant,
+    ) -> PipelineResult<IncrementalStats> {
         tracing::info!(
             target: "pipeline",
             "Incremental discovery: {} new, {} modified, {} deleted",
@@ -1322,15 +1874,53 @@ impl Pipeline {
             });
         }
 
+        let root = self
+            .settings
+            .workspace_root
+            .as_deref()
+            .unwrap_or(Path::new("."));
+        let metrics = PipelineMetrics::from_config(root, &self.config);
+
         // Create cleanup stage
+        let cleanup_start = Instant::now();
         let cleanup_stage = if let Some(ref sem) = semantic {
-            CleanupStage::new(Arc::clone(&index), &semantic_path).with_semantic(Arc::clone(sem))
+            CleanupStage::new(Arc::clone(&index), semantic_path)
+                .with_semantic(Arc::clone(sem))
+                .with_event_log(self.event_log())
         } else {
-            CleanupStage::new(Arc::clone(&index), &semantic_path)
+            CleanupStage::new(Arc::clone(&index), semantic_path).with_event_log(self.event_log())
         };
 
-        // Cleanup deleted files
         let mut cleanup_stats = CleanupStats::default();
+
+        // Aliases carry no symbols of their own: when the file they copy
+        // changes or goes away, they are re-indexed with the rest
+        let orphaned_aliases = stale_aliases(
+            &index,
+            discover_result
+                .modified_files
+                .iter()
+                .chain(&discover_result.deleted_files),
+        );
+        if !orphaned_aliases.is_empty() {
+            tracing::debug!(
+                target: "pipeline",
+                "Re-indexing {} aliases of changed files",
+                orphaned_aliases.len()
+            );
+            let stats = cleanup_stage.cleanup_files(&orphaned_aliases)?;
+            cleanup_stats.files_cleaned += stats.files_cleaned;
+        }
+
+        // Edges from unchanged files into modified ones, re-pointed once the
+        // modified files are re-indexed
+        let incoming = capture_incoming(
+            &index,
+            &discover_result.modified_files,
+            &discover_result.deleted_files,
+        )?;
+
+        // Cleanup deleted files
         if !discover_result.deleted_files.is_empty() {
             let stats = cleanup_stage.cleanup_files(&discover_result.deleted_files)?;
             cleanup_stats.files_cleaned += stats.files_cleaned;
@@ -1345,14 +1935,21 @@ impl Pipeline {
             cleanup_stats.symbols_removed += stats.symbols_removed;
             cleanup_stats.embeddings_removed += stats.embeddings_removed;
         }
+        if let Some(m) = &metrics {
+            m.add_stage(cleanup_metrics(&cleanup_stats, cleanup_start.elapsed()));
+        }
 
         // Combine new + modified for indexing
-        let files_to_index: Vec<PathBuf> = discover_result
+        let mut files_to_index: Vec<PathBuf> = discover_result
             .new_files
             .iter()
             .chain(discover_result.modified_files.iter())
+            .chain(orphaned_aliases.iter())
             .cloned()
             .collect();
+        if let Some(priorities) = self.file_priorities(root) {
+            priorities.sort(&mut files_to_index);
+        }
 
         // Run Phase 1 on the files to index
         let (index_stats, unresolved, symbol_cache) = self.index_files(
@@ -1361,10 +1958,12 @@ impl Pipeline {
             semantic.clone(),
             embedding_pool.clone(),
             progress.clone(),
+            metrics.as_deref(),
         )?;
 
         // Run Phase 2 resolution with progress if Phase 1 had progress
         let symbol_cache = Arc::new(symbol_cache);
+        let relink_stage = RelinkStage::new(Arc::clone(&index), Arc::clone(&symbol_cache));
         let phase2_stats = if progress.is_some() && !unresolved.is_empty() {
             // Create Phase 2 progress bar
             use crate::io::status_line::{
@@ -1401,6 +2000,32 @@ impl Pipeline {
             self.run_phase2(unresolved, symbol_cache, Arc::clone(&index))?
         };
 
+        // Re-point relationships from unchanged files into modified ones
+        let relink_start = Instant::now();
+        let mut relink_stats = RelinkStats::default();
+        for (path, edges) in incoming {
+            if let Some((file_id, _, _)) = index.get_file_info(&path.to_string_lossy())? {
+                let stats = relink_stage.relink(edges, file_id)?;
+                relink_stats.captured += stats.captured;
+                relink_stats.relinked += stats.relinked;
+            }
+        }
+        if let Some(m) = &metrics {
+            m.add_stages(phase2_stats.stages.iter().cloned());
+            m.add_stage(StageMetrics {
+                name: "RELINK",
+                threads: 1,
+                wall_time: relink_start.elapsed(),
+                items_processed: relink_stats.captured,
+                secondary_count: relink_stats.relinked,
+                secondary_label: "relinked",
+                ..Default::default()
+            });
+            m.finalize(start.elapsed());
+            m.log();
+            m.export();
+        }
+
         // Save embeddings
         if let Some(sem) = semantic {
             let semantic_guard = sem.lock().map_err(|_| PipelineError::Parse {
@@ -1409,9 +2034,9 @@ impl Pipeline {
             })?;
 
             semantic_guard
-                .save(&semantic_path)
+                .save(semantic_path)
                 .map_err(|e| PipelineError::Parse {
-                    path: semantic_path.clone(),
+                    path: semantic_path.to_path_buf(),
                     reason: format!("Failed to save embeddings: {e}"),
                 })?;
         }
@@ -1435,6 +2060,7 @@ impl Pipeline {
         semantic: Option<Arc<Mutex<SimpleSemanticSearch>>>,
         embedding_pool: Option<Arc<crate::semantic::EmbeddingPool>>,
         progress: Option<Arc<crate::io::status_line::ProgressBar>>,
+        metrics: Option<&PipelineMetrics>,
     ) -> PipelineResult<(IndexStats, Vec<UnresolvedRelationship>, SymbolLookupCache)> {
         if files.is_empty() {
             return Ok((
@@ -1448,6 +2074,8 @@ impl Pipeline {
 
         // Query existing ID counters BEFORE spawning threads
         let (start_file_counter, start_symbol_counter) = self.get_start_counters(&index)?;
+        self.invalidate_resolution_cache();
+        let cancel = self.cancel.token();
 
         // Create bounded channels
         let (content_tx, content_rx) = bounded(self.config.content_channel_size);
@@ -1456,18 +2084,29 @@ impl Pipeline {
 
         let settings = Arc::clone(&self.settings);
         let parse_threads = self.config.parse_threads;
+        let read_threads = 1;
         let batch_size = self.config.batch_size;
+        let deterministic = self.config.deterministic;
+        let blame = self.settings.indexing.blame;
         let batches_per_commit = self.config.batches_per_commit;
 
         // Stage 1: READ - Send files directly (already have the paths)
         let files_to_read = files.to_vec();
         let workspace_root = settings.workspace_root.clone();
+        let generated_files = settings.indexing.generated_files;
+        let dedup = self.content_dedup();
+        let read_cancel = cancel.clone();
         let read_handle = thread::spawn(move || {
-            let stage = ReadStage::new(1);
+            let start = Instant::now();
+            let stage = ReadStage::new(read_threads).with_generated_files(generated_files);
             let mut count = 0;
             let mut errors = 0;
+            let mut output_wait = Duration::ZERO;
 
             for path in files_to_read {
+                if read_cancel.is_cancelled() {
+                    break;
+                }
                 match stage.read_single(&path) {
                     Ok(mut content) => {
                         // Normalize path to relative if workspace_root is set
@@ -1476,49 +2115,34 @@ impl Pipeline {
                                 content.path = relative.to_path_buf();
                             }
                         }
+                        if let Some(dedup) = &dedup {
+                            dedup.mark(&mut content);
+                        }
+                        let send_start = Instant::now();
                         if content_tx.send(content).is_err() {
                             break;
                         }
+                        output_wait += send_start.elapsed();
                         count += 1;
                     }
+                    Err(PipelineError::SkippedFile { path, kind }) => {
+                        tracing::debug!(
+                            target: "pipeline",
+                            "Skipping {kind} file: {}",
+                            path.display()
+                        );
+                    }
                     Err(_) => {
                         errors += 1;
                     }
                 }
             }
 
-            (count, errors)
+            (count, errors, output_wait, start.elapsed())
         });
 
         // Stage 2: PARSE
-        let parse_handles: Vec<_> = (0..parse_threads)
-            .map(|_| {
-                let rx = content_rx.clone();
-                let tx = parsed_tx.clone();
-                let settings = Arc::clone(&settings);
-                thread::spawn(move || {
-                    init_parser_cache(settings.clone());
-                    let stage = ParseStage::new(settings);
-                    let mut parsed = 0;
-                    let mut errors = 0;
-
-                    for content in rx {
-                        match stage.parse(content) {
-                            Ok(p) => {
-                                parsed += 1;
-                                if tx.send(p).is_err() {
-                                    break;
-                                }
-                            }
-                            Err(_) => errors += 1,
-                        }
-                    }
-                    (parsed, errors)
-                })
-            })
-            .collect();
-        drop(content_rx);
-        drop(parsed_tx);
+        let parse_handles = self.spawn_parse_workers(content_rx, parsed_tx);
 
         // Create embed channel for parallel EMBED stage (if semantic enabled)
         let (embed_tx, embed_rx) = bounded(self.config.batch_channel_size);
@@ -1533,9 +2157,11 @@ impl Pipeline {
         // Sends IndexBatch to INDEX, EmbeddingBatch to EMBED (parallel)
         let collect_handle = thread::spawn(move || {
             let stage = CollectStage::new(batch_size)
-                .with_start_counters(start_file_counter, start_symbol_counter);
+                .with_start_counters(start_file_counter, start_symbol_counter)
+                .with_deterministic_order(deterministic);
             stage.run(parsed_rx, batch_tx, embed_sender, None)
         });
+        let collect_start = Instant::now();
 
         // Stage 4a: EMBED (parallel with INDEX) - if semantic + pool are provided
         let embed_handle = if let (Some(sem), Some(pool)) = (semantic, embedding_pool) {
@@ -1551,28 +2177,43 @@ impl Pipeline {
         // Stage 4b: INDEX (parallel with EMBED)
         // Clone index Arc for metadata update after pipeline completes
         let index_for_metadata = Arc::clone(&index);
-        let mut index_stage = IndexStage::new(index, batches_per_commit);
+        let mut index_stage = IndexStage::new(index, batches_per_commit)
+            .with_event_log(self.event_log())
+            .with_spill_threshold(self.config.relationship_spill_bytes())
+            .with_ordered_writes(deterministic)
+            .with_blame(blame)
+            .with_cancellation(cancel);
         if let Some(prog) = progress {
             index_stage = index_stage.with_progress(prog);
         }
         let index_handle = thread::spawn(move || index_stage.run(batch_rx));
+        let index_start = Instant::now();
 
         // Wait for READ stage and capture errors
-        let (_, read_errors) = match read_handle.join() {
-            Ok((count, errors)) => (count, errors),
+        let (read_files, read_errors, read_output_wait, read_wall_time) = match read_handle.join() {
+            Ok(result) => result,
             Err(_) => {
                 tracing::error!(target: "pipeline", "READ thread panicked in index_files");
-                (0, 1)
+                (0, 1, Duration::ZERO, Duration::ZERO)
             }
         };
 
         // Wait for PARSE stages and aggregate errors
-        let (_, parse_errors) = self.join_parse_workers_simple(parse_handles);
+        let (
+            parsed_files,
+            parse_errors,
+            total_symbols,
+            parse_input_wait,
+            parse_output_wait,
+            parse_wall_time,
+        ) = self.join_parse_workers(parse_handles);
 
         // Join all remaining threads first (COLLECT, EMBED, INDEX)
         let collect_join = collect_handle.join();
+        let collect_wall_time = collect_start.elapsed();
         let embed_join = embed_handle.map(|h| h.join());
         let index_join = index_handle.join();
+        let index_wall_time = index_start.elapsed();
 
         // CRITICAL: Unwrap INDEX first - this is the critical path.
         // If INDEX succeeded, we MUST save counters regardless of EMBED status.
@@ -1580,19 +2221,66 @@ impl Pipeline {
             Ok(result) => result,
             Err(_) => return Err(PipelineError::ChannelRecv("INDEX panicked".into())),
         };
-        let (mut stats, pending, cache, _) = index_result?;
+        let (mut stats, pending, cache, index_input_wait) = index_result?;
 
         // Unwrap COLLECT (needed for counters)
         let collect_result = match collect_join {
             Ok(result) => result,
             Err(_) => return Err(PipelineError::ChannelRecv("COLLECT panicked".into())),
         };
-        let (final_file_count, final_symbol_count, embed_candidates, _, _) = collect_result?;
+        let (
+            final_file_count,
+            final_symbol_count,
+            embed_candidates,
+            collect_input_wait,
+            collect_output_wait,
+        ) = collect_result?;
 
         // CRITICAL: Save counters NOW, before checking EMBED.
         // INDEX succeeded, so we MUST persist the new ID pointers.
         self.save_final_counters(&index_for_metadata, final_file_count, final_symbol_count)?;
 
+        if let Some(m) = metrics {
+            m.add_stages([
+                StageMetrics {
+                    name: "READ",
+                    threads: read_threads,
+                    wall_time: read_wall_time,
+                    output_wait: read_output_wait,
+                    items_processed: read_files,
+                    ..Default::default()
+                },
+                StageMetrics {
+                    name: "PARSE",
+                    threads: parse_threads,
+                    wall_time: parse_wall_time,
+                    input_wait: parse_input_wait,
+                    output_wait: parse_output_wait,
+                    items_processed: parsed_files,
+                    secondary_count: total_symbols,
+                    secondary_label: "symbols",
+                },
+                StageMetrics {
+                    name: "COLLECT",
+                    threads: 1,
+                    wall_time: collect_wall_time,
+                    input_wait: collect_input_wait,
+                    output_wait: collect_output_wait,
+                    items_processed: final_symbol_count.saturating_sub(start_symbol_counter)
+                        as usize,
+                    ..Default::default()
+                },
+                StageMetrics {
+                    name: "INDEX",
+                    threads: 1,
+                    wall_time: index_wall_time,
+                    input_wait: index_input_wait,
+                    items_processed: stats.symbols_found,
+                    ..Default::default()
+                },
+            ]);
+        }
+
         // Handle EMBED results (Soft Failure - log but don't fail pipeline)
         if let Some(join_result) = embed_join {
             match join_result {
@@ -1613,6 +2301,19 @@ impl Pipeline {
                         stats.embeddings_failed = failed;
                     }
 
+                    if let Some(m) = metrics {
+                        m.add_stage(StageMetrics {
+                            name: "EMBED",
+                            threads: 1,
+                            wall_time: embed_stats.elapsed,
+                            input_wait: embed_stats.input_wait,
+                            output_wait: Duration::ZERO,
+                            items_processed: embed_stats.embedded,
+                            secondary_count: embed_stats.skipped,
+                            secondary_label: "skipped",
+                        });
+                    }
+
                     tracing::info!(
                         target: "semantic",
                         "EMBED: {}/{} embedded ({} candidates from COLLECT)",
@@ -1671,13 +2372,11 @@ impl Pipeline {
                 None, // TODO: Wire DualProgressBar
             )?
         } else {
-            let (s, u, c) =
-                self.index_directory_with_progress(root, Arc::clone(&index), progress)?;
-            (s, u, c, None)
+            self.index_directory_with_progress(root, Arc::clone(&index), progress)?
         };
 
         // Log pipeline metrics (no StatusLine in this path, safe to log immediately)
-        if let Some(m) = metrics {
+        if let Some(m) = &metrics {
             m.log();
         }
 
@@ -1718,6 +2417,7 @@ impl Pipeline {
         } else {
             self.run_phase2(unresolved, symbol_cache, Arc::clone(&index))?
         };
+        export_metrics(metrics.as_deref(), &phase2_stats, start.elapsed());
 
         // Save embeddings
         if let Some(sem) = semantic {
@@ -1761,15 +2461,13 @@ impl Pipeline {
     ) -> PipelineResult<Phase1Result> {
         let start = Instant::now();
 
-        // Create metrics collector if tracing is enabled
-        let metrics = if self.config.pipeline_tracing {
-            Some(PipelineMetrics::new(root.display().to_string(), true))
-        } else {
-            None
-        };
+        // Create metrics collector if tracing or metrics export is enabled
+        let metrics = PipelineMetrics::from_config(root, &self.config);
 
         // Query existing ID counters BEFORE spawning threads
         let (start_file_counter, start_symbol_counter) = self.get_start_counters(&index)?;
+        self.invalidate_resolution_cache();
+        let cancel = self.cancel.token();
 
         // Create bounded channels
         let (path_tx, path_rx) = bounded(self.config.path_channel_size);
@@ -1790,11 +2488,15 @@ impl Pipeline {
         let read_threads = self.config.read_threads;
         let discover_threads = self.config.discover_threads;
         let batch_size = self.config.batch_size;
+        let deterministic = self.config.deterministic;
+        let blame = self.settings.indexing.blame;
         let batches_per_commit = self.config.batches_per_commit;
-        let tracing_enabled = self.config.pipeline_tracing;
+        let tracing_enabled = metrics.is_some();
 
         // Stage 1: DISCOVER
         let discover_root = root.to_path_buf();
+        let priorities = self.file_priorities(root);
+        let index_archives = settings.indexing.index_archives;
         let discover_handle = thread::spawn(move || {
             let tracker = if tracing_enabled {
                 Some(StageTracker::new("DISCOVER", discover_threads))
@@ -1802,7 +2504,9 @@ impl Pipeline {
                 None
             };
 
-            let stage = DiscoverStage::new(discover_root, discover_threads);
+            let stage = DiscoverStage::new(discover_root, discover_threads)
+                .with_priorities(priorities)
+                .with_archives(index_archives);
             let result = stage.run(path_tx);
 
             if let (Some(tracker), Ok(count)) = (&tracker, &result) {
@@ -1814,13 +2518,23 @@ impl Pipeline {
 
         // Stage 2: READ
         let workspace_root = settings.workspace_root.clone();
+        let memory_budget = MemoryBudget::new(self.config.memory_budget_mb);
+        let generated_files = settings.indexing.generated_files;
+        let dedup = self.content_dedup();
         let read_handles: Vec<_> = (0..read_threads)
             .map(|_| {
                 let rx = path_rx.clone();
                 let tx = content_tx.clone();
                 let workspace_root = workspace_root.clone();
+                let memory_budget = Arc::clone(&memory_budget);
+                let dedup = dedup.clone();
+                let cancel = cancel.clone();
                 thread::spawn(move || {
-                    let stage = ReadStage::with_workspace_root(1, workspace_root);
+                    let stage = ReadStage::with_workspace_root(1, workspace_root)
+                        .with_memory_budget(memory_budget)
+                        .with_generated_files(generated_files)
+                        .with_dedup(dedup)
+                        .with_cancellation(cancel);
                     stage.run(rx, tx)
                 })
             })
@@ -1834,6 +2548,7 @@ impl Pipeline {
                 let rx = content_rx.clone();
                 let tx = parsed_tx.clone();
                 let settings = Arc::clone(&settings);
+                let cancel = cancel.clone();
                 thread::spawn(move || {
                     let start = Instant::now();
                     init_parser_cache(settings.clone());
@@ -1852,6 +2567,9 @@ impl Pipeline {
                             Err(_) => break, // Channel closed
                         };
                         input_wait += recv_start.elapsed();
+                        if cancel.is_cancelled() {
+                            break;
+                        }
 
                         match stage.parse(content) {
                             Ok(p) => {
@@ -1897,7 +2615,8 @@ impl Pipeline {
             };
 
             let stage = CollectStage::new(batch_size)
-                .with_start_counters(start_file_counter, start_symbol_counter);
+                .with_start_counters(start_file_counter, start_symbol_counter)
+                .with_deterministic_order(deterministic);
             let result = stage.run(parsed_rx, batch_tx, embed_sender, embed_total_callback);
 
             // Record items and wait times before finalizing
@@ -1945,7 +2664,12 @@ impl Pipeline {
         // Completion callback to freeze timer when INDEX finishes
         let index_complete = dual_progress.as_ref().map(Arc::clone);
         let index_handle = {
-            let mut index_stage = IndexStage::new(index, batches_per_commit);
+            let mut index_stage = IndexStage::new(index, batches_per_commit)
+                .with_event_log(self.event_log())
+                .with_spill_threshold(self.config.relationship_spill_bytes())
+                .with_ordered_writes(deterministic)
+                .with_blame(blame)
+                .with_cancellation(cancel);
 
             // Prefer dual_progress callback over single progress bar
             if let Some(ref dp) = dual_progress {
@@ -2175,7 +2899,8 @@ impl Pipeline {
         use std::collections::HashSet;
 
         let start = Instant::now();
-        let semantic_path = self.settings.index_path.join("semantic");
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.settings.index_path);
+        let _run_guard = self.run_guard(&index);
 
         // Canonicalize both path sets for accurate comparison
         let stored_set: HashSet<PathBuf> = stored_paths
@@ -2286,8 +3011,10 @@ impl Pipeline {
                 let cleanup_stage = if let Some(ref sem) = semantic {
                     CleanupStage::new(Arc::clone(&index), &semantic_path)
                         .with_semantic(Arc::clone(sem))
+                        .with_event_log(self.event_log())
                 } else {
                     CleanupStage::new(Arc::clone(&index), &semantic_path)
+                        .with_event_log(self.event_log())
                 };
 
                 match cleanup_stage.cleanup_files(&files_to_remove) {
@@ -2380,6 +3107,8 @@ pub struct Phase2Stats {
     pub unresolved: usize,
     /// Time taken
     pub elapsed: std::time::Duration,
+    /// CONTEXT, RESOLVE and WRITE timings for the metrics report
+    pub stages: Vec<StageMetrics>,
 }
 
 /// Statistics from pipeline execution.
@@ -2423,6 +3152,101 @@ impl StageTimings {
     }
 }
 
+/// Stage metrics of a cleanup pass that took `elapsed`.
+fn cleanup_metrics(stats: &CleanupStats, elapsed: Duration) -> StageMetrics {
+    StageMetrics {
+        name: "CLEANUP",
+        threads: 1,
+        wall_time: elapsed,
+        items_processed: stats.files_cleaned,
+        secondary_count: stats.symbols_removed,
+        secondary_label: "symbols",
+        ..Default::default()
+    }
+}
+
+/// Queue rolled-back files that discovery did not find again.
+///
+/// Recovery removes them from the index, so discovery under the run's root
+/// sees them as new; files outside it, or unchanged since `base` in a git
+/// delta, would otherwise be dropped from the index for good.
+fn requeue_rolled_back(discover_result: &mut DiscoverResult, rolled_back: Vec<PathBuf>) {
+    for path in rolled_back {
+        let queued = discover_result.new_files.contains(&path)
+            || discover_result.modified_files.contains(&path);
+        if !queued && path.is_file() {
+            discover_result.new_files.push(path);
+        }
+    }
+}
+
+/// Add the Phase 2 stages to `metrics` and write the `metrics_json` report.
+fn export_metrics(metrics: Option<&PipelineMetrics>, phase2: &Phase2Stats, elapsed: Duration) {
+    if let Some(m) = metrics {
+        m.add_stages(phase2.stages.iter().cloned());
+        m.finalize(elapsed);
+        m.export();
+    }
+}
+
+/// Incoming edges of each `modified` file, keeping only those from files
+/// that are neither modified nor deleted.
+fn capture_incoming(
+    index: &DocumentIndex,
+    modified: &[PathBuf],
+    deleted: &[PathBuf],
+) -> PipelineResult<Vec<(PathBuf, Vec<IncomingEdge>)>> {
+    use std::collections::HashSet;
+
+    let mut changing = HashSet::new();
+    let mut captured = Vec::new();
+    for (path, is_modified) in modified
+        .iter()
+        .map(|path| (path, true))
+        .chain(deleted.iter().map(|path| (path, false)))
+    {
+        let Some((file_id, _, _)) = index.get_file_info(&path.to_string_lossy())? else {
+            continue;
+        };
+        changing.extend(
+            index
+                .find_symbols_by_file(file_id)?
+                .into_iter()
+                .map(|symbol| symbol.id),
+        );
+        if is_modified {
+            captured.push((path.clone(), RelinkStage::capture(index, file_id)?));
+        }
+    }
+
+    for (_, edges) in &mut captured {
+        edges.retain(|edge| !changing.contains(&edge.from_id));
+    }
+    Ok(captured)
+}
+
+/// Indexed aliases of `changed` files that are not changed themselves.
+fn stale_aliases<'a>(
+    index: &DocumentIndex,
+    changed: impl IntoIterator<Item = &'a PathBuf>,
+) -> Vec<PathBuf> {
+    use std::collections::HashSet;
+
+    let changed: HashSet<String> = changed
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    let aliases = AliasStore::new(index.path()).load().unwrap_or_else(|e| {
+        tracing::warn!(target: "pipeline", "Failed to read file aliases: {e}");
+        Default::default()
+    });
+    aliases
+        .into_iter()
+        .filter(|(alias, canonical)| changed.contains(canonical) && !changed.contains(alias))
+        .map(|(alias, _)| PathBuf::from(alias))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2454,6 +3278,38 @@ mod tests {
         assert_eq!(pipeline.config().parse_threads, 4);
     }
 
+    #[test]
+    fn test_journal_lasts_until_relationships_are_resolved() {
+        use crate::storage::DocumentIndex;
+
+        let temp_dir = TempDir::new().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(src_dir.join("lib.rs"), "fn a() { b(); }\nfn b() {}\n").unwrap();
+
+        let settings = Arc::new(Settings::default());
+        let config = PipelineConfig::default().with_batches_per_commit(1);
+        let pipeline = Pipeline::new(Arc::clone(&settings), config);
+
+        // Phase 1 alone commits symbols but leaves their calls unresolved
+        let index_dir = temp_dir.path().join("phase1");
+        let index = Arc::new(DocumentIndex::new(&index_dir, &settings).unwrap());
+        pipeline
+            .index_directory(&src_dir, Arc::clone(&index))
+            .unwrap();
+        let journals = transaction::abandoned(index.path()).unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].1.files.len(), 1);
+
+        // Both phases settle the journal
+        let index_dir = temp_dir.path().join("resolved");
+        let index = Arc::new(DocumentIndex::new(&index_dir, &settings).unwrap());
+        pipeline
+            .index_and_resolve(&src_dir, Arc::clone(&index))
+            .unwrap();
+        assert!(transaction::abandoned(index.path()).unwrap().is_empty());
+    }
+
     /// End-to-end test proving Phase 1 collects symbols, imports, and pending relationships.
     ///
     /// Scenario: Two TypeScript files where file1 imports and calls file2.
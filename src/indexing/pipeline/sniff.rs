@@ -0,0 +1,192 @@
+//! File sniffing - binary, generated and minified file detection
+//!
+//! The READ stage classifies each file before it reaches a parser.
+//! Binary data is never indexed. Generated and minified files are handled
+//! per `indexing.generated_files`: skipped, indexed without embeddings, or
+//! indexed like any other file.
+
+use std::path::Path;
+
+/// Bytes inspected for NUL bytes and entropy
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Leading lines searched for a generated-code marker
+const HEADER_LINES: usize = 10;
+
+/// Shannon entropy in bits per byte above which text is encoded data.
+/// Source code sits around 4.5-5.3, base64 and compressed data near 6 and above.
+const DATA_ENTROPY: f64 = 5.8;
+
+/// Files shorter than this are too small for a meaningful entropy reading
+const MIN_ENTROPY_BYTES: usize = 4 * 1024;
+
+/// Average line length of minified JavaScript or CSS
+const MINIFIED_AVG_LINE: usize = 300;
+
+/// Average line length that marks any file as machine-written
+const MACHINE_AVG_LINE: usize = 1000;
+
+/// Markers tools put in the header of files they write (matched lowercase)
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "do not edit",
+    "code generated by",
+    "auto-generated",
+    "autogenerated",
+    "automatically generated",
+];
+
+/// File name endings of generated code
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".pb.cc",
+    ".pb.h",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+    "_generated.go",
+    ".generated.ts",
+    ".generated.js",
+];
+
+/// What a file's content looks like
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// Hand-written source code
+    Source,
+    /// NUL bytes or high-entropy encoded data
+    Binary,
+    /// Written by a code generator
+    Generated,
+    /// Minified or otherwise squashed onto very long lines
+    Minified,
+}
+
+impl FileKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileKind::Source => "source",
+            FileKind::Binary => "binary",
+            FileKind::Generated => "generated",
+            FileKind::Minified => "minified",
+        }
+    }
+}
+
+/// Classify `content` read from `path`
+pub fn classify(path: &Path, content: &str) -> FileKind {
+    let head = &content.as_bytes()[..content.len().min(SNIFF_BYTES)];
+    if head.contains(&0) {
+        return FileKind::Binary;
+    }
+    if content.len() >= MIN_ENTROPY_BYTES && entropy(head) > DATA_ENTROPY {
+        return FileKind::Binary;
+    }
+
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if name.contains(".min.") {
+        return FileKind::Minified;
+    }
+    if GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        return FileKind::Generated;
+    }
+
+    let generated_header = content.lines().take(HEADER_LINES).any(|line| {
+        let line = line.to_ascii_lowercase();
+        GENERATED_MARKERS.iter().any(|marker| line.contains(marker))
+    });
+    if generated_header {
+        return FileKind::Generated;
+    }
+
+    let lines = content.lines().count().max(1);
+    let average_line = content.len() / lines;
+    let minifiable = matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("js" | "mjs" | "cjs" | "css")
+    );
+    if (minifiable && average_line > MINIFIED_AVG_LINE) || average_line > MACHINE_AVG_LINE {
+        return FileKind::Minified;
+    }
+
+    FileKind::Source
+}
+
+/// Shannon entropy of `bytes` in bits per byte
+fn entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &byte in bytes {
+        counts[byte as usize] += 1;
+    }
+    let total = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_heuristics() {
+        let source = "fn main() {\n    println!(\"hello\");\n}\n".repeat(200);
+        assert_eq!(
+            classify(Path::new("src/main.rs"), &source),
+            FileKind::Source
+        );
+
+        assert_eq!(
+            classify(Path::new("assets/logo.rs"), "PNG\0\0\u{1}"),
+            FileKind::Binary
+        );
+
+        // Pseudo-random printable bytes, like a base64 blob
+        let mut state = 0x2545_f491_u32;
+        let blob: String = (0..8192)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                char::from(b'!' + (state % 94) as u8)
+            })
+            .collect();
+        assert_eq!(classify(Path::new("src/data.py"), &blob), FileKind::Binary);
+
+        let go = "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n";
+        assert_eq!(classify(Path::new("api/types.go"), go), FileKind::Generated);
+        assert_eq!(
+            classify(Path::new("api/types.pb.go"), "package api\n"),
+            FileKind::Generated
+        );
+
+        let bundle = format!("var a={};", "b(c,d)&&e.f(g);".repeat(40));
+        assert_eq!(
+            classify(Path::new("dist/app.js"), &bundle),
+            FileKind::Minified
+        );
+        assert_eq!(
+            classify(Path::new("dist/app.min.js"), "var a=1;\n"),
+            FileKind::Minified
+        );
+        // Long lines alone are fine outside minifiable languages
+        assert_eq!(
+            classify(Path::new("src/table.rs"), &bundle),
+            FileKind::Source
+        );
+    }
+}
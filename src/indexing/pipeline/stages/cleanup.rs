@@ -9,10 +9,11 @@
 //! 2. Remove embeddings for those symbols
 //! 3. Save embeddings to disk (prevents desync on crash)
 //! 4. Remove file documents from Tantivy
+//! 5. Drop the files' parse diagnostics, aliases and symbol provenance
 
 use crate::indexing::pipeline::types::{PipelineError, PipelineResult};
 use crate::semantic::SimpleSemanticSearch;
-use crate::storage::DocumentIndex;
+use crate::storage::{AliasStore, BlameStore, DiagnosticsStore, DocumentIndex, EventLog};
 use crate::types::SymbolId;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
@@ -33,6 +34,7 @@ pub struct CleanupStage {
     index: Arc<DocumentIndex>,
     semantic: Option<Arc<Mutex<SimpleSemanticSearch>>>,
     semantic_path: PathBuf,
+    event_log: Option<Arc<EventLog>>,
 }
 
 impl CleanupStage {
@@ -42,6 +44,7 @@ impl CleanupStage {
             index,
             semantic: None,
             semantic_path: semantic_path.into(),
+            event_log: None,
         }
     }
 
@@ -51,6 +54,12 @@ impl CleanupStage {
         self
     }
 
+    /// Record removed symbols in the index event log.
+    pub fn with_event_log(mut self, event_log: Option<Arc<EventLog>>) -> Self {
+        self.event_log = event_log;
+        self
+    }
+
     /// Clean up files before re-indexing or deletion.
     ///
     /// This removes:
@@ -83,6 +92,18 @@ impl CleanupStage {
                 reason: format!("Failed to commit batch: {e}"),
             })?;
 
+        // One write per per-file store for the whole batch
+        let index_path = self.index.path();
+        if let Err(e) = DiagnosticsStore::new(index_path).remove_many(files) {
+            tracing::warn!(target: "pipeline", "Failed to drop parse diagnostics: {e}");
+        }
+        if let Err(e) = AliasStore::new(index_path).remove_many(files) {
+            tracing::warn!(target: "pipeline", "Failed to drop file aliases: {e}");
+        }
+        if let Err(e) = BlameStore::new(index_path).remove_many(files) {
+            tracing::warn!(target: "pipeline", "Failed to drop symbol provenance: {e}");
+        }
+
         // Save embeddings to disk after all removals (critical for sync)
         if let Some(ref semantic) = self.semantic {
             let semantic_guard = semantic.lock().map_err(|_| PipelineError::Parse {
@@ -141,6 +162,12 @@ impl CleanupStage {
         // Step 5: Remove file documents from Tantivy
         self.index.remove_file_documents(&path_str)?;
 
+        // Step 6: Hold the removal for the event log until we know whether
+        // the file is re-indexed (update) or gone (removal)
+        if let Some(ref event_log) = self.event_log {
+            event_log.record_removal(&path_str, file_id, &symbols);
+        }
+
         Ok((symbol_count, embedding_count))
     }
 }
@@ -12,7 +12,7 @@ use crate::indexing::pipeline::types::{
     RawSymbol, UnresolvedRelationship,
 };
 use crate::symbol::Symbol;
-use crate::types::{FileId, Range, SymbolId};
+use crate::types::{FileId, Range, SymbolId, intern};
 use crate::utils::get_utc_timestamp;
 use crossbeam_channel::{Receiver, Sender};
 use std::collections::HashMap;
@@ -29,6 +29,8 @@ pub struct CollectStage {
     start_file_counter: u32,
     /// Starting symbol counter (for continuing from existing index)
     start_symbol_counter: u32,
+    /// Registration timestamp for every file, set in deterministic mode
+    fixed_timestamp: Option<u64>,
 }
 
 /// Ephemeral caches for relationship reconnection.
@@ -144,6 +146,7 @@ impl CollectStage {
             batch_size: batch_size.max(1),
             start_file_counter: 0,
             start_symbol_counter: 0,
+            fixed_timestamp: None,
         }
     }
 
@@ -157,6 +160,17 @@ impl CollectStage {
         self
     }
 
+    /// Number files in path order instead of arrival order.
+    ///
+    /// Parse threads finish in a different order on every run, so the stage
+    /// waits for all parsed files and sorts them before assigning IDs. File
+    /// registrations get the `SOURCE_DATE_EPOCH` timestamp (0 when unset) and
+    /// no mtime, which makes the next incremental run compare hashes instead.
+    pub fn with_deterministic_order(mut self, deterministic: bool) -> Self {
+        self.fixed_timestamp = deterministic.then(source_date_epoch);
+        self
+    }
+
     /// Create with default batch size (5000 symbols).
     pub fn default_batch_size() -> Self {
         Self::new(5000)
@@ -219,12 +233,26 @@ impl CollectStage {
         let mut output_wait = Duration::ZERO;
         let mut total_embed_candidates = 0u32;
 
+        // Deterministic mode: drain the channel, then assign IDs in path order
+        let mut ordered = if self.fixed_timestamp.is_some() {
+            let recv_start = Instant::now();
+            let mut files: Vec<ParsedFile> = receiver.iter().collect();
+            input_wait += recv_start.elapsed();
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            Some(files.into_iter())
+        } else {
+            None
+        };
+
         loop {
             // Track input wait (time blocked on recv)
             let recv_start = Instant::now();
-            let parsed = match receiver.recv() {
-                Ok(p) => p,
-                Err(_) => break, // Channel closed
+            let next = match ordered.as_mut() {
+                Some(files) => files.next(),
+                None => receiver.recv().ok(),
+            };
+            let Some(parsed) = next else {
+                break; // Channel closed
             };
             input_wait += recv_start.elapsed();
 
@@ -293,7 +321,7 @@ impl CollectStage {
     /// Process a single parsed file.
     fn process_file(&self, state: &mut CollectorState, parsed: ParsedFile) {
         let file_id = state.next_file_id();
-        let file_path: Box<str> = parsed.path.to_string_lossy().into();
+        let file_path = intern(&parsed.path.to_string_lossy());
 
         // Set current language for embedding metadata
         state.current_language = parsed.language_id.as_str().into();
@@ -302,7 +330,13 @@ impl CollectStage {
         state.caches.insert_file(parsed.path.clone(), file_id);
 
         // Register file
-        let mtime = crate::indexing::file_info::get_file_mtime(&parsed.path).unwrap_or(0);
+        let (timestamp, mtime) = match self.fixed_timestamp {
+            Some(timestamp) => (timestamp, 0),
+            None => (
+                get_utc_timestamp(),
+                crate::indexing::file_info::get_file_mtime(&parsed.path).unwrap_or(0),
+            ),
+        };
         state
             .current_batch
             .file_registrations
@@ -311,27 +345,37 @@ impl CollectStage {
                 file_id,
                 content_hash: parsed.content_hash,
                 language_id: parsed.language_id,
-                timestamp: get_utc_timestamp(),
+                timestamp,
                 mtime,
+                diagnostics: parsed.diagnostics,
+                duplicate_of: parsed.duplicate_of,
             });
 
         // Process symbols
-        for raw_sym in parsed.raw_symbols {
+        let generated = parsed.generated;
+        for mut raw_sym in parsed.raw_symbols {
             let symbol_id = state.next_symbol_id();
 
             // Cache for relationship resolution
-            let name: Arc<str> = raw_sym.name.as_ref().into();
+            let name = intern(&raw_sym.name);
             state
                 .caches
                 .insert(name.clone(), file_id, raw_sym.range, symbol_id);
 
-            // Extract embedding candidate if symbol has doc_comment
-            if let Some(ref doc) = raw_sym.doc_comment {
-                state.current_embed_batch.candidates.push((
-                    symbol_id,
-                    doc.clone(),
-                    state.current_language.clone(),
-                ));
+            // Extract embedding candidates from the chunks, or the
+            // doc_comment, unless a generator wrote the symbol
+            let texts = match raw_sym.chunks.take() {
+                Some(chunks) => chunks,
+                None => raw_sym.doc_comment.iter().cloned().collect(),
+            };
+            if !generated {
+                for text in texts {
+                    state.current_embed_batch.candidates.push((
+                        symbol_id,
+                        text,
+                        state.current_language.clone(),
+                    ));
+                }
             }
 
             // Create Symbol
@@ -372,12 +416,11 @@ fn create_symbol(
     id: SymbolId,
     raw: &RawSymbol,
     file_id: FileId,
-    file_path: Box<str>,
+    file_path: Arc<str>,
     module_path: Option<&str>,
     language_id: crate::parsing::LanguageId,
 ) -> Symbol {
     let mut symbol = Symbol::new(id, raw.name.clone(), raw.kind, file_id, raw.range)
-        .with_file_path(file_path)
         .with_visibility(raw.visibility)
         .with_language_id(language_id);
 
@@ -387,12 +430,16 @@ fn create_symbol(
     if let Some(doc) = &raw.doc_comment {
         symbol = symbol.with_doc(doc.clone());
     }
+    symbol.file_path = file_path;
     if let Some(path) = module_path {
         symbol = symbol.with_module_path(path);
     }
     if let Some(scope) = raw.scope_context.clone() {
         symbol = symbol.with_scope(scope);
     }
+    if let Some(tag) = &raw.tag {
+        symbol = symbol.with_tag(tag.clone());
+    }
 
     symbol
 }
@@ -420,6 +467,14 @@ fn create_unresolved_relationship(
     }
 }
 
+/// `SOURCE_DATE_EPOCH` in seconds, the reproducible-builds convention, or 0
+fn source_date_epoch() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -441,6 +496,9 @@ mod tests {
             raw_symbols: symbols,
             raw_imports: Vec::new(),
             raw_relationships: Vec::new(),
+            diagnostics: Vec::new(),
+            generated: false,
+            duplicate_of: None,
         }
     }
 
@@ -721,6 +779,9 @@ mod tests {
             raw_symbols: vec![sym_with_doc, sym_without_doc, sym_with_short_doc],
             raw_imports: Vec::new(),
             raw_relationships: Vec::new(),
+            diagnostics: Vec::new(),
+            generated: false,
+            duplicate_of: None,
         };
 
         parsed_tx.send(parsed).unwrap();
@@ -774,4 +835,39 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_deterministic_order_numbers_files_by_path() {
+        let (parsed_tx, parsed_rx) = bounded(100);
+        let (batch_tx, batch_rx) = bounded(100);
+
+        // Arrival order differs from path order
+        for name in ["src/zeta.rs", "src/alpha.rs", "src/mid.rs"] {
+            parsed_tx
+                .send(make_parsed_file(
+                    name,
+                    vec![make_raw_symbol("item", SymbolKind::Function, 1)],
+                ))
+                .unwrap();
+        }
+        drop(parsed_tx);
+
+        let stage = CollectStage::new(100).with_deterministic_order(true);
+        stage.run(parsed_rx, batch_tx, None, None).unwrap();
+
+        let batches: Vec<_> = batch_rx.iter().collect();
+        let registrations: Vec<(String, u32, u64)> = batches
+            .iter()
+            .flat_map(|b| &b.file_registrations)
+            .map(|r| (r.path.display().to_string(), r.file_id.value(), r.mtime))
+            .collect();
+        assert_eq!(
+            registrations,
+            vec![
+                ("src/alpha.rs".to_string(), 1, 0),
+                ("src/mid.rs".to_string(), 2, 0),
+                ("src/zeta.rs".to_string(), 3, 0),
+            ]
+        );
+    }
 }
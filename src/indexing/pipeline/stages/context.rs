@@ -94,7 +94,9 @@ impl ContextStage {
             by_file.entry(rel.file_id).or_default().push(rel);
         }
 
-        // Build context for each file
+        // Build context for each file, in file order so writes are reproducible
+        let mut by_file: Vec<_> = by_file.into_iter().collect();
+        by_file.sort_by_key(|(file_id, _)| file_id.value());
         let mut contexts = Vec::with_capacity(by_file.len());
 
         for (file_id, rels) in by_file {
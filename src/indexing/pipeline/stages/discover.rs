@@ -1,23 +1,35 @@
 //! Discover stage - parallel file system walk
 //!
-//! Uses the `ignore` crate's parallel walker for high-performance
-//! file discovery. Filters by supported extensions.
+//! Uses the work-stealing parallel walker from [`crate::indexing::walker`]
+//! for both modes. Filters by supported extensions.
 //!
-//! Supports two modes:
+//! Supports three modes:
 //! - Full: Discovers all files (for initial indexing or force re-index)
 //! - Incremental: Compares disk state to index, returns new/modified/deleted
+//! - Git delta: Like incremental, but only examines files git reports as
+//!   changed since a base commit
+//!
+//! With [`FilePriorities`] set, a full walk finishes before any path is sent
+//! so hot files can be sent first.
+//!
+//! With archives enabled, each archive found is replaced by the virtual paths
+//! of the supported sources inside it (see [`crate::indexing::archive`]).
 
+use crate::indexing::archive::{self, ArchiveFormat};
 use crate::indexing::file_info::calculate_hash;
+use crate::indexing::git_delta;
+use crate::indexing::ignore_rules::IgnoreRules;
 use crate::indexing::pipeline::types::{DiscoverResult, PipelineError, PipelineResult};
+use crate::indexing::priority::FilePriorities;
+use crate::indexing::walker::{is_hidden_file, walk_parallel};
+use crate::parsing::config::{self, is_config_file};
 use crate::parsing::get_registry;
 use crate::storage::DocumentIndex;
 use crossbeam_channel::Sender;
-use ignore::WalkBuilder;
 use std::collections::HashSet;
-use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 /// Discover stage for parallel file walking.
 pub struct DiscoverStage {
@@ -27,6 +39,10 @@ pub struct DiscoverStage {
     index: Option<Arc<DocumentIndex>>,
     /// Workspace root for path normalization.
     workspace_root: Option<PathBuf>,
+    /// Order for full discovery; paths stream in walk order without it.
+    priorities: Option<Arc<FilePriorities>>,
+    /// Index the sources inside archives.
+    archives: bool,
 }
 
 impl DiscoverStage {
@@ -37,6 +53,8 @@ impl DiscoverStage {
             threads: threads.max(1),
             index: None,
             workspace_root: None,
+            priorities: None,
+            archives: false,
         }
     }
 
@@ -52,6 +70,18 @@ impl DiscoverStage {
         self
     }
 
+    /// Send hot files first in full discovery.
+    pub fn with_priorities(mut self, priorities: Option<Arc<FilePriorities>>) -> Self {
+        self.priorities = priorities;
+        self
+    }
+
+    /// Index the sources inside archives.
+    pub fn with_archives(mut self, archives: bool) -> Self {
+        self.archives = archives;
+        self
+    }
+
     /// Normalize a path relative to workspace_root.
     fn normalize_path(&self, path: &Path) -> PathBuf {
         if path.is_absolute() {
@@ -71,73 +101,62 @@ impl DiscoverStage {
     ///
     /// Returns the number of files discovered.
     pub fn run(&self, sender: Sender<PathBuf>) -> PipelineResult<usize> {
-        let extensions = get_supported_extensions()?;
-        let count = Arc::new(AtomicUsize::new(0));
-
-        let mut builder = WalkBuilder::new(&self.root);
-        builder
-            .hidden(false) // Don't auto-skip hidden directories
-            .git_ignore(true) // Respect .gitignore
-            .git_global(true) // Respect global gitignore
-            .git_exclude(true) // Respect .git/info/exclude
-            .follow_links(false) // Don't follow symlinks
-            .require_git(false) // Allow gitignore to work in non-git directories
-            .threads(self.threads);
-
-        // Support .codannaignore files (matches FileWalker behavior)
-        builder.add_custom_ignore_filename(".codannaignore");
-
-        let walker = builder.build_parallel();
-
-        let count_clone = count.clone();
-        let extensions = Arc::new(extensions);
-
-        walker.run(|| {
-            let sender = sender.clone();
-            let extensions = extensions.clone();
-            let count = count_clone.clone();
-
-            Box::new(move |entry| {
-                let entry = match entry {
-                    Ok(e) => e,
-                    Err(_) => return ignore::WalkState::Continue,
-                };
-
-                // Skip directories
-                if entry.file_type().is_some_and(|ft| ft.is_dir()) {
-                    return ignore::WalkState::Continue;
-                }
-
-                let path = entry.path();
+        if let Some(priorities) = &self.priorities {
+            return self.run_prioritized(priorities, sender);
+        }
 
-                // Skip hidden files (files starting with .) - matches FileWalker behavior
-                if let Some(file_name) = path.file_name() {
-                    if let Some(name_str) = file_name.to_str() {
-                        if name_str.starts_with('.') {
-                            return ignore::WalkState::Continue;
-                        }
+        let extensions = get_supported_extensions()?;
+        let count = AtomicUsize::new(0);
+
+        walk_parallel(&self.root, self.threads, |path| {
+            if self.archives && ArchiveFormat::from_path(path).is_some() {
+                for source in archive_sources(path, &extensions) {
+                    count.fetch_add(1, Ordering::Relaxed);
+                    if sender.send(source).is_err() {
+                        return false;
                     }
                 }
+                return true;
+            }
 
-                // Filter by extension
-                if !has_supported_extension(path, &extensions) {
-                    return ignore::WalkState::Continue;
-                }
-
-                // Send path to channel
-                count.fetch_add(1, Ordering::Relaxed);
-                if sender.send(path.to_path_buf()).is_err() {
-                    // Channel closed, stop walking
-                    return ignore::WalkState::Quit;
-                }
+            // Filter by extension
+            if !has_supported_extension(path, &extensions) {
+                return true;
+            }
 
-                ignore::WalkState::Continue
-            })
+            // Send path to channel; a closed channel stops the walk
+            count.fetch_add(1, Ordering::Relaxed);
+            sender.send(path.to_path_buf()).is_ok()
         });
 
         Ok(count.load(Ordering::Relaxed))
     }
 
+    /// Walk everything, then send paths hottest first.
+    fn run_prioritized(
+        &self,
+        priorities: &FilePriorities,
+        sender: Sender<PathBuf>,
+    ) -> PipelineResult<usize> {
+        let mut files = self.collect_all_files()?;
+        priorities.sort(&mut files);
+
+        let [open, branch, recent, _] = priorities.counts(&files);
+        tracing::debug!(
+            target: "pipeline",
+            "priority order: {open} open, {branch} branch, {recent} recent of {} files",
+            files.len()
+        );
+
+        let count = files.len();
+        for path in files {
+            if sender.send(path).is_err() {
+                break;
+            }
+        }
+        Ok(count)
+    }
+
     /// Run incremental discovery, comparing disk state to index.
     ///
     /// Returns categorized files: new, modified, and deleted.
@@ -208,48 +227,146 @@ impl DiscoverStage {
         Ok(result)
     }
 
-    /// Collect all files on disk (synchronous, for incremental comparison).
-    fn collect_all_files(&self) -> PipelineResult<Vec<PathBuf>> {
-        let extensions = get_supported_extensions()?;
-        let mut files = Vec::new();
-
-        // Use sequential walker for simplicity in incremental mode
-        let mut builder = WalkBuilder::new(&self.root);
-        builder
-            .hidden(false) // Don't auto-skip hidden directories
-            .git_ignore(true) // Respect .gitignore
-            .git_global(true) // Respect global gitignore
-            .git_exclude(true) // Respect .git/info/exclude
-            .follow_links(false) // Don't follow symlinks
-            .require_git(false); // Allow gitignore to work in non-git directories
+    /// Run incremental discovery over the files changed since `base`.
+    ///
+    /// Asks git for the files that differ between `base` and the working
+    /// tree instead of walking `root`, then categorizes them like
+    /// [`run_incremental`](Self::run_incremental). Files the walker would
+    /// skip (hidden or matched by an ignore file) count as deleted, as they
+    /// do in a walk. Requires an index to be set via `with_index()`.
+    pub fn run_git_delta(&self, base: &str) -> PipelineResult<DiscoverResult> {
+        let index = self.index.as_ref().ok_or_else(|| PipelineError::Parse {
+            path: self.root.clone(),
+            reason: "Incremental mode requires an index".to_string(),
+        })?;
+        let git_error = |reason: String| PipelineError::Git {
+            path: self.root.clone(),
+            reason,
+        };
 
-        // Support .codannaignore files (matches FileWalker behavior)
-        builder.add_custom_ignore_filename(".codannaignore");
+        let changed =
+            git_delta::changed_files(&self.root, base).map_err(|e| git_error(e.to_string()))?;
+        let canonical_root = self
+            .root
+            .canonicalize()
+            .map_err(|e| git_error(e.to_string()))?;
+        let extensions = get_supported_extensions()?;
 
-        let walker = builder.build();
+        // Ignore files above `root` apply too when it sits inside the workspace
+        let rules_root = self
+            .workspace_root
+            .as_ref()
+            .and_then(|workspace| workspace.canonicalize().ok())
+            .filter(|workspace| canonical_root.starts_with(workspace))
+            .unwrap_or_else(|| canonical_root.clone());
+        let ignore_rules = IgnoreRules::new(rules_root);
 
-        for entry in walker.flatten() {
-            if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+        let mut result = DiscoverResult::default();
+        for changed_path in changed {
+            // Rebuild the path from `root` so it matches what the walker yields
+            let canonical = changed_path
+                .parent()
+                .and_then(|parent| parent.canonicalize().ok())
+                .zip(changed_path.file_name())
+                .map(|(parent, name)| parent.join(name))
+                .unwrap_or(changed_path);
+            let Ok(relative) = canonical.strip_prefix(&canonical_root) else {
+                continue;
+            };
+            let path = self.root.join(relative);
+            let skipped = is_hidden_file(&path) || ignore_rules.is_ignored(&canonical, false);
+            if self.archives && ArchiveFormat::from_path(&path).is_some() {
+                let removed = skipped || !path.is_file();
+                self.archive_delta(&path, removed, &extensions, index, &mut result)?;
+                continue;
+            }
+            if !has_supported_extension(&path, &extensions) {
                 continue;
             }
 
-            let path = entry.path();
-
-            // Skip hidden files (files starting with .) - matches FileWalker behavior
-            if let Some(file_name) = path.file_name() {
-                if let Some(name_str) = file_name.to_str() {
-                    if name_str.starts_with('.') {
-                        continue;
-                    }
+            let path = self.normalize_path(&path);
+            let indexed = index.get_file_info(&path.to_string_lossy())?.is_some();
+            if skipped || !path.is_file() {
+                if indexed {
+                    result.deleted_files.push(path);
                 }
+            } else if !indexed {
+                result.new_files.push(path);
+            } else if self.is_modified(&path, index)? {
+                result.modified_files.push(path);
             }
+        }
 
-            if has_supported_extension(path, &extensions) {
-                files.push(path.to_path_buf());
+        tracing::debug!(
+            target: "pipeline",
+            "git delta since {base}: new={}, modified={}, deleted={}",
+            result.new_files.len(),
+            result.modified_files.len(),
+            result.deleted_files.len()
+        );
+
+        Ok(result)
+    }
+
+    /// Categorize the sources of a changed archive as new, modified or deleted.
+    ///
+    /// `removed` treats the archive as gone, so all its indexed sources are deleted.
+    fn archive_delta(
+        &self,
+        archive_path: &Path,
+        removed: bool,
+        extensions: &HashSet<&str>,
+        index: &DocumentIndex,
+        result: &mut DiscoverResult,
+    ) -> PipelineResult<()> {
+        let sources: HashSet<PathBuf> = if removed {
+            HashSet::new()
+        } else {
+            archive_sources(archive_path, extensions)
+                .iter()
+                .map(|path| self.normalize_path(path))
+                .collect()
+        };
+
+        let archive_path = self.normalize_path(archive_path);
+        for path in index.get_all_indexed_paths()? {
+            if archive::archive_of(&path).as_ref() == Some(&archive_path)
+                && !sources.contains(&path)
+            {
+                result.deleted_files.push(path);
             }
         }
 
-        Ok(files)
+        for path in sources {
+            if index.get_file_info(&path.to_string_lossy())?.is_none() {
+                result.new_files.push(path);
+            } else if self.is_modified(&path, index)? {
+                result.modified_files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect all files on disk, for incremental comparison.
+    fn collect_all_files(&self) -> PipelineResult<Vec<PathBuf>> {
+        let extensions = get_supported_extensions()?;
+        let files = Mutex::new(Vec::new());
+
+        walk_parallel(&self.root, self.threads, |path| {
+            if self.archives && ArchiveFormat::from_path(path).is_some() {
+                let sources = archive_sources(path, &extensions);
+                if let Ok(mut files) = files.lock() {
+                    files.extend(sources);
+                }
+            } else if has_supported_extension(path, &extensions) {
+                if let Ok(mut files) = files.lock() {
+                    files.push(path.to_path_buf());
+                }
+            }
+            true
+        });
+
+        Ok(files.into_inner().unwrap_or_default())
     }
 
     /// Check if a file has been modified.
@@ -273,7 +390,7 @@ impl DiscoverStage {
         }
 
         // mtime changed or unknown - verify with hash (requires file read)
-        let content = fs::read_to_string(path).map_err(|e| PipelineError::FileRead {
+        let content = archive::read_source(path).map_err(|e| PipelineError::FileRead {
             path: path.to_path_buf(),
             source: e,
         })?;
@@ -312,11 +429,38 @@ fn get_supported_extensions() -> PipelineResult<HashSet<&'static str>> {
     Ok(extensions)
 }
 
+/// Virtual paths of the supported sources inside `archive_path`.
+///
+/// An archive that cannot be read is logged and contributes nothing.
+fn archive_sources(archive_path: &Path, extensions: &HashSet<&str>) -> Vec<PathBuf> {
+    match archive::list_entries(archive_path, |entry| {
+        has_supported_extension(entry, extensions)
+    }) {
+        Ok(entries) => entries
+            .iter()
+            .map(|entry| archive::virtual_path(archive_path, entry))
+            .collect(),
+        Err(e) => {
+            tracing::debug!(
+                target: "pipeline",
+                "Skipping archive {}: {e}",
+                archive_path.display()
+            );
+            Vec::new()
+        }
+    }
+}
+
 /// Check if a path has a supported extension.
+///
+/// `.toml`, `.json` and `.yaml` files are only supported when they are a
+/// manifest or CI file the config frontend reads.
 fn has_supported_extension(path: &Path, extensions: &HashSet<&str>) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
-        .map(|ext| extensions.contains(ext))
+        .map(|ext| {
+            extensions.contains(ext) && (!config::is_config_extension(ext) || is_config_file(path))
+        })
         .unwrap_or(false)
 }
 
@@ -324,6 +468,7 @@ fn has_supported_extension(path: &Path, extensions: &HashSet<&str>) -> bool {
 mod tests {
     use super::*;
     use crossbeam_channel::bounded;
+    use std::fs;
 
     #[test]
     fn test_discover_examples_directory() {
@@ -380,6 +525,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_discover_expands_archives() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(temp.path().join("main.py"), "import dep\n").unwrap();
+        let wheel = temp.path().join("dep-1.0-py3-none-any.whl");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&wheel).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for name in ["dep/__init__.py", "dep-1.0.dist-info/RECORD"] {
+            zip.start_file(name, options).unwrap();
+            std::io::Write::write_all(&mut zip, b"x = 1\n").unwrap();
+        }
+        zip.finish().unwrap();
+
+        let discover = |archives: bool| {
+            let (sender, receiver) = bounded(100);
+            DiscoverStage::new(temp.path(), 2)
+                .with_archives(archives)
+                .run(sender)
+                .unwrap();
+            let mut paths: Vec<PathBuf> = receiver.iter().collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(discover(false), vec![temp.path().join("main.py")]);
+        assert_eq!(
+            discover(true),
+            vec![
+                archive::virtual_path(&wheel, "dep/__init__.py"),
+                temp.path().join("main.py"),
+            ]
+        );
+    }
+
     #[test]
     fn test_get_supported_extensions() {
         let extensions = get_supported_extensions().unwrap();
@@ -5,21 +5,35 @@
 //! Parallel stage that:
 //! - Receives IndexBatch from COLLECT stage
 //! - Writes symbols, imports, file registrations to Tantivy (parallel via RwLock)
+//! - Stores the parse diagnostics of each registered file
+//! - Blames each registered file for symbol provenance, when enabled
 //! - Accumulates UnresolvedRelationships for Phase 2
 //! - Builds SymbolLookupCache for O(1) Phase 2 resolution (concurrent DashMap)
 //! - Commits every N batches for efficient I/O
+//! - Journals every file of the run, held until Phase 2 resolves their
+//!   relationships, so a crash or cancellation can be rolled back
+//! - Stops between batches when cancelled
 //!
 //! Note: Embedding generation moved to separate EMBED stage (parallel with INDEX).
 
 use crate::indexing::IndexStats;
+use crate::indexing::blame::Blamer;
+use crate::indexing::pipeline::memory::RelationshipSpill;
 use crate::indexing::pipeline::types::{
-    IndexBatch, PipelineResult, SymbolLookupCache, UnresolvedRelationship,
+    FileRegistration, IndexBatch, PipelineError, PipelineResult, SymbolLookupCache,
+    UnresolvedRelationship,
 };
+use crate::indexing::transaction::BatchTransaction;
 use crate::io::status_line::ProgressBar;
-use crate::storage::DocumentIndex;
+use crate::parsing::Import;
+use crate::storage::{AliasStore, BlameStore, DiagnosticsStore, DocumentIndex, EventLog};
+use crate::symbol::Symbol;
 use crossbeam_channel::Receiver;
 use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 /// Index stage for Tantivy writes.
 ///
@@ -37,6 +51,16 @@ pub struct IndexStage {
     progress: Option<Arc<ProgressBar>>,
     /// Optional progress callback (alternative to progress bar).
     progress_callback: Option<IndexProgressCallback>,
+    /// Optional log of indexed files and their symbols.
+    event_log: Option<Arc<EventLog>>,
+    /// Bytes of pending relationships held in memory before spilling to disk.
+    spill_threshold: Option<usize>,
+    /// Write documents one at a time in batch order (deterministic mode).
+    ordered: bool,
+    /// Record the commits that introduced and last modified each symbol.
+    blame: bool,
+    /// Stops the run between batches.
+    cancel: CancellationToken,
 }
 
 impl IndexStage {
@@ -50,6 +74,11 @@ impl IndexStage {
             batches_per_commit: batches_per_commit.max(1),
             progress: None,
             progress_callback: None,
+            event_log: None,
+            spill_threshold: None,
+            ordered: false,
+            blame: false,
+            cancel: CancellationToken::new(),
         }
     }
 
@@ -65,6 +94,36 @@ impl IndexStage {
         self
     }
 
+    /// Record each indexed file in the index event log.
+    pub fn with_event_log(mut self, event_log: Option<Arc<EventLog>>) -> Self {
+        self.event_log = event_log;
+        self
+    }
+
+    /// Write each batch sequentially so documents land in the same order every run.
+    pub fn with_ordered_writes(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// Blame every registered file and store the provenance of its symbols.
+    pub fn with_blame(mut self, blame: bool) -> Self {
+        self.blame = blame;
+        self
+    }
+
+    /// Stop before the next batch once `cancel` is cancelled.
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Spill pending relationships to a temporary file past `threshold` bytes.
+    pub fn with_spill_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.spill_threshold = threshold;
+        self
+    }
+
     /// Run the index stage.
     ///
     /// Returns (stats, accumulated_relationships, symbol_cache, input_wait) for Phase 2.
@@ -81,13 +140,16 @@ impl IndexStage {
         use std::time::{Duration, Instant};
 
         let mut stats = IndexStats::new();
-        let mut pending_relationships: Vec<UnresolvedRelationship> = Vec::new();
+        let mut pending_relationships = RelationshipSpill::new(self.spill_threshold);
         let mut batch_count = 0;
         let mut input_wait = Duration::ZERO;
 
         // Pre-allocate cache based on expected symbols (will grow if needed)
         let symbol_cache = SymbolLookupCache::with_capacity(10_000);
 
+        // Files written by this run, rolled back unless Phase 2 resolves them
+        let mut transaction = BatchTransaction::begin(self.index.path());
+
         // Start initial batch - StorageError converts to PipelineError via #[from]
         self.index.start_batch()?;
 
@@ -99,11 +161,20 @@ impl IndexStage {
                 Err(_) => break, // Channel closed
             };
             input_wait += recv_start.elapsed();
+            if self.cancel.is_cancelled() {
+                return Err(self.cancel_run(transaction));
+            }
 
+            transaction.record(
+                batch
+                    .file_registrations
+                    .iter()
+                    .map(|registration| registration.path.clone()),
+            )?;
             self.process_batch(&batch, &mut stats, &symbol_cache)?;
 
             // Accumulate relationships for Phase 2
-            pending_relationships.extend(batch.unresolved_relationships);
+            pending_relationships.extend(batch.unresolved_relationships)?;
 
             batch_count += 1;
 
@@ -113,8 +184,23 @@ impl IndexStage {
             }
         }
 
-        // Final commit
+        // Upstream stages stop early when cancelled, closing the channel
+        if self.cancel.is_cancelled() {
+            return Err(self.cancel_run(transaction));
+        }
+
+        // Final commit; the journal lasts until Phase 2 settles it
         self.index.commit_batch()?;
+        transaction.hold();
+
+        if pending_relationships.spilled() > 0 {
+            tracing::debug!(
+                target: "pipeline",
+                "reloading {} spilled relationships",
+                pending_relationships.spilled()
+            );
+        }
+        let pending_relationships = pending_relationships.finish()?;
 
         Ok((stats, pending_relationships, symbol_cache, input_wait))
     }
@@ -129,25 +215,30 @@ impl IndexStage {
         stats: &mut IndexStats,
         symbol_cache: &SymbolLookupCache,
     ) -> PipelineResult<()> {
-        // Write file registrations in parallel
-        batch
-            .file_registrations
-            .par_iter()
-            .for_each(|registration| {
-                if let Err(e) = self.index.store_file_registration(registration) {
-                    tracing::warn!(
-                        target: "pipeline",
-                        "Failed to store file registration for {}: {e}",
-                        registration.path.display()
-                    );
-                }
-            });
+        // Write file registrations (in parallel unless writes are ordered)
+        let store_registration = |registration: &FileRegistration| {
+            if let Err(e) = self.index.store_file_registration(registration) {
+                tracing::warn!(
+                    target: "pipeline",
+                    "Failed to store file registration for {}: {e}",
+                    registration.path.display()
+                );
+            }
+        };
+        if self.ordered {
+            batch.file_registrations.iter().for_each(store_registration);
+        } else {
+            batch
+                .file_registrations
+                .par_iter()
+                .for_each(store_registration);
+        }
         let files_in_batch = batch.file_registrations.len();
         stats.files_indexed += files_in_batch;
 
-        // Write symbols to Tantivy in parallel
+        // Write symbols to Tantivy (in parallel unless writes are ordered)
         // SymbolLookupCache uses DashMap which is concurrent-safe
-        batch.symbols.par_iter().for_each(|(symbol, path)| {
+        let index_symbol = |(symbol, path): &(Symbol, PathBuf)| {
             if let Err(e) = self.index.index_symbol(symbol, &path.to_string_lossy()) {
                 tracing::warn!(
                     target: "pipeline",
@@ -157,11 +248,16 @@ impl IndexStage {
             }
             // Insert into cache for O(1) Phase 2 resolution (DashMap is concurrent)
             symbol_cache.insert(symbol.clone());
-        });
+        };
+        if self.ordered {
+            batch.symbols.iter().for_each(index_symbol);
+        } else {
+            batch.symbols.par_iter().for_each(index_symbol);
+        }
         stats.symbols_found += batch.symbols.len();
 
-        // Write imports in parallel
-        batch.imports.par_iter().for_each(|import| {
+        // Write imports (in parallel unless writes are ordered)
+        let store_import = |import: &Import| {
             if let Err(e) = self.index.store_import(import) {
                 tracing::warn!(
                     target: "pipeline",
@@ -169,7 +265,47 @@ impl IndexStage {
                     import.path
                 );
             }
+        };
+        if self.ordered {
+            batch.imports.iter().for_each(store_import);
+        } else {
+            batch.imports.par_iter().for_each(store_import);
+        }
+
+        // Replace the stored diagnostics of every file in the batch; files
+        // that now parse cleanly drop their entry
+        let diagnostics = batch.file_registrations.iter().map(|registration| {
+            (
+                registration.path.to_string_lossy().into_owned(),
+                registration.diagnostics.as_slice(),
+            )
         });
+        if let Err(e) = DiagnosticsStore::new(self.index.path()).update(diagnostics) {
+            tracing::warn!(target: "pipeline", "Failed to store parse diagnostics: {e}");
+        }
+
+        // Record which files are copies of another; a file indexed on its
+        // own again stops being an alias
+        let aliases = batch.file_registrations.iter().map(|registration| {
+            (
+                registration.path.to_string_lossy().into_owned(),
+                registration
+                    .duplicate_of
+                    .as_ref()
+                    .map(|canonical| canonical.to_string_lossy().into_owned()),
+            )
+        });
+        if let Err(e) = AliasStore::new(self.index.path()).update(aliases) {
+            tracing::warn!(target: "pipeline", "Failed to store file aliases: {e}");
+        }
+
+        if self.blame {
+            self.store_blame(batch);
+        }
+
+        if let Some(ref event_log) = self.event_log {
+            self.record_events(event_log, batch);
+        }
 
         // Update progress AFTER all work is complete
         // This ensures 100% only shows when files are truly fully processed
@@ -188,6 +324,73 @@ impl IndexStage {
         Ok(())
     }
 
+    /// Replace the stored provenance of every registered file of the batch.
+    ///
+    /// Files are blamed in parallel; each worker reuses its open repository.
+    fn store_blame(&self, batch: &IndexBatch) {
+        let mut by_file: HashMap<_, Vec<_>> = HashMap::new();
+        for (symbol, _) in &batch.symbols {
+            by_file.entry(symbol.file_id).or_default().push(symbol);
+        }
+
+        let blames: Vec<_> = batch
+            .file_registrations
+            .par_iter()
+            .map_init(Blamer::default, |blamer, registration| {
+                let symbols = by_file
+                    .get(&registration.file_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                (
+                    registration.path.to_string_lossy().into_owned(),
+                    blamer.blame_symbols(&registration.path, symbols.iter().copied()),
+                )
+            })
+            .collect();
+        if let Err(e) = BlameStore::new(self.index.path()).update(blames) {
+            tracing::warn!(target: "pipeline", "Failed to store symbol provenance: {e}");
+        }
+    }
+
+    /// Log each registered file of the batch with the symbols it produced.
+    fn record_events(&self, event_log: &EventLog, batch: &IndexBatch) {
+        let mut by_file: HashMap<_, Vec<_>> = HashMap::new();
+        for (symbol, _) in &batch.symbols {
+            by_file.entry(symbol.file_id).or_default().push(symbol);
+        }
+
+        for registration in &batch.file_registrations {
+            let symbols = by_file.remove(&registration.file_id).unwrap_or_default();
+            if let Err(e) = event_log.record_indexed(
+                &registration.path.to_string_lossy(),
+                registration.file_id,
+                &symbols,
+            ) {
+                tracing::warn!(
+                    target: "pipeline",
+                    "Failed to record index event for {}: {e}",
+                    registration.path.display()
+                );
+            }
+        }
+    }
+
+    /// Drop the open batch, leaving the journal of every file the run wrote.
+    ///
+    /// Their relationships will never be resolved by this run, so the next
+    /// run rolls them back and indexes them again.
+    fn cancel_run(&self, transaction: BatchTransaction) -> PipelineError {
+        if let Err(e) = self.index.abort_batch() {
+            return e.into();
+        }
+        tracing::info!(
+            target: "pipeline",
+            "Indexing cancelled; {} file(s) will be re-indexed by the next run",
+            transaction.files().len()
+        );
+        PipelineError::Cancelled
+    }
+
     /// Commit current batch and start a new one.
     fn commit_and_restart(&self) -> PipelineResult<()> {
         self.index.commit_batch()?;
@@ -239,6 +442,8 @@ mod tests {
             language_id: LanguageId::new("rust"),
             timestamp: 1700000000,
             mtime: 1700000000,
+            diagnostics: Vec::new(),
+            duplicate_of: None,
         });
 
         for i in 0..symbol_count {
@@ -284,6 +489,55 @@ mod tests {
         assert_eq!(symbol_cache.len(), 3);
     }
 
+    #[test]
+    fn test_cancelled_run_journals_its_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = Settings::default();
+        let index = Arc::new(DocumentIndex::new(temp_dir.path(), &settings).unwrap());
+
+        let (batch_tx, batch_rx) = bounded(10);
+        batch_tx.send(make_test_batch(1, 2)).unwrap();
+        batch_tx.send(make_test_batch(2, 1)).unwrap();
+        drop(batch_tx);
+
+        // Cancel once the first batch is written; it still commits
+        let cancel = CancellationToken::new();
+        let on_batch = cancel.clone();
+        let stage = IndexStage::new(Arc::clone(&index), 1)
+            .with_cancellation(cancel)
+            .with_progress_callback(Arc::new(move |_| on_batch.cancel()));
+        let result = stage.run(batch_rx);
+        assert!(matches!(result, Err(PipelineError::Cancelled)));
+
+        let journals = crate::indexing::transaction::abandoned(index.path()).unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].1.files, vec![PathBuf::from("test_1.rs")]);
+    }
+
+    #[test]
+    fn test_index_stage_records_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = Settings::default();
+        let index = Arc::new(DocumentIndex::new(temp_dir.path(), &settings).unwrap());
+        let event_log = Arc::new(EventLog::new(temp_dir.path()));
+
+        let (batch_tx, batch_rx) = bounded(10);
+        batch_tx.send(make_test_batch(1, 2)).unwrap();
+        batch_tx.send(make_test_batch(2, 1)).unwrap();
+        drop(batch_tx);
+
+        let stage =
+            IndexStage::new(Arc::clone(&index), 10).with_event_log(Some(Arc::clone(&event_log)));
+        stage.run(batch_rx).unwrap();
+
+        let events = event_log.read_since(0).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].path, "test_1.rs");
+        assert_eq!(events[0].added.len(), 2);
+        assert_eq!(events[1].path, "test_2.rs");
+        assert_eq!(events[1].added.len(), 1);
+    }
+
     #[test]
     fn test_index_stage_commits_every_n_batches() {
         let temp_dir = TempDir::new().unwrap();
@@ -379,6 +633,8 @@ mod tests {
             language_id: LanguageId::new("rust"),
             timestamp: 1700000000,
             mtime: 1700000000,
+            diagnostics: Vec::new(),
+            duplicate_of: None,
         });
 
         // Add symbols with known names
@@ -4,6 +4,7 @@
 //!
 //! Phase 1 stages: DISCOVER → READ → PARSE → COLLECT → INDEX
 //! Phase 2 stages: CONTEXT → RESOLVE → WRITE
+//! Single-file updates: RELINK re-points edges into a re-indexed file
 //! Pre-phase: CLEANUP (for incremental mode)
 
 pub mod cleanup;
@@ -14,6 +15,7 @@ pub mod embed;
 pub mod index;
 pub mod parse;
 pub mod read;
+pub mod relink;
 pub mod resolve;
 pub mod semantic_embed;
 pub mod write;
@@ -30,6 +32,7 @@ pub use read::ReadStage;
 
 // Phase 2 stages
 pub use context::{ContextStage, ContextStats};
+pub use relink::{IncomingEdge, RelinkStage, RelinkStats};
 pub use resolve::{ResolveStage, ResolveStats};
 pub use write::{WriteStage, WriteStats};
 
@@ -4,11 +4,20 @@
 //! Uses thread-local parsers to avoid contention.
 
 use crate::Settings;
+use crate::Symbol;
 use crate::indexing::pipeline::types::{
     FileContent, ParsedFile, PipelineError, PipelineResult, RawImport, RawRelationship, RawSymbol,
 };
-use crate::parsing::{LanguageId, LanguageParser, get_registry};
-use crate::types::{FileId, SymbolCounter};
+use crate::parsing::config::{ConfigLanguage, is_config_file};
+use crate::parsing::diagnostics::{DiagnosticsParser, ParseDiagnostic};
+use crate::parsing::injection::{self, InjectedCode, InjectionParser};
+use crate::parsing::type_hints::{self, TypeHint, TypeHintParser};
+use crate::parsing::user_queries::UserQueries;
+use crate::parsing::{Import, LanguageId, LanguageParser, get_registry};
+use crate::relationship::RelationshipMetadata;
+use crate::semantic::{ChunkingStrategy, chunk_symbols};
+use crate::symbol::doc;
+use crate::types::{FileId, Range, SymbolCounter};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
@@ -20,6 +29,16 @@ use std::sync::Arc;
 /// tree-sitter parsers are not Send, so this pattern is required.
 struct ParserCache {
     parsers: HashMap<LanguageId, Box<dyn LanguageParser>>,
+    /// Parser for embedded code, created on first use when
+    /// `indexing.language_injections` is enabled
+    injection: Option<InjectionParser>,
+    /// Queries from `.codanna/queries/<language>/`, loaded on first use of
+    /// each language (`None` when the language has none)
+    user_queries: HashMap<LanguageId, Option<UserQueries>>,
+    /// Finds the regions tree-sitter could not parse
+    diagnostics: DiagnosticsParser,
+    /// Guesses receiver types when `indexing.type_inference` is enabled
+    type_hints: TypeHintParser,
     settings: Arc<Settings>,
 }
 
@@ -27,6 +46,10 @@ impl ParserCache {
     fn new(settings: Arc<Settings>) -> Self {
         Self {
             parsers: HashMap::new(),
+            injection: None,
+            user_queries: HashMap::new(),
+            diagnostics: DiagnosticsParser::new(),
+            type_hints: TypeHintParser::new(),
             settings,
         }
     }
@@ -34,12 +57,39 @@ impl ParserCache {
     fn get_or_create(
         &mut self,
         language_id: LanguageId,
-    ) -> PipelineResult<&mut dyn LanguageParser> {
+    ) -> PipelineResult<(
+        &mut dyn LanguageParser,
+        Option<&mut InjectionParser>,
+        Option<&mut UserQueries>,
+    )> {
         if !self.parsers.contains_key(&language_id) {
             let parser = create_parser(language_id, &self.settings)?;
             self.parsers.insert(language_id, parser);
         }
-        Ok(self.parsers.get_mut(&language_id).unwrap().as_mut())
+        if self.settings.indexing.language_injections && self.injection.is_none() {
+            let parser = InjectionParser::new().map_err(|reason| PipelineError::Parse {
+                path: Default::default(),
+                reason,
+            })?;
+            self.injection = Some(parser);
+        }
+        if !self.user_queries.contains_key(&language_id) {
+            let root = self
+                .settings
+                .workspace_root
+                .as_deref()
+                .unwrap_or_else(|| Path::new("."));
+            let queries = host_grammar(language_id)
+                .and_then(|grammar| UserQueries::load(root, language_id, &grammar));
+            self.user_queries.insert(language_id, queries);
+        }
+        Ok((
+            self.parsers.get_mut(&language_id).unwrap().as_mut(),
+            self.injection.as_mut(),
+            self.user_queries
+                .get_mut(&language_id)
+                .and_then(Option::as_mut),
+        ))
     }
 }
 
@@ -86,11 +136,48 @@ fn detect_language(path: &Path) -> PipelineResult<LanguageId> {
     registry
         .get_by_extension(extension)
         .map(|def| def.id())
+        .filter(|id| *id != ConfigLanguage::ID || is_config_file(path))
         .ok_or_else(|| PipelineError::UnsupportedFileType {
             path: path.to_path_buf(),
         })
 }
 
+/// Markers that only appear in Objective-C headers
+const OBJC_HEADER_MARKERS: &[&str] = &["@interface", "@protocol", "@class", "#import", "@import"];
+
+/// Route an Objective-C `.h` header to the Objective-C parser.
+///
+/// `.h` is registered by C, which would drop the classes and protocols an
+/// Objective-C header declares. The header stays C when Objective-C is
+/// disabled.
+fn header_language(
+    language_id: LanguageId,
+    path: &Path,
+    content: &str,
+    settings: &Settings,
+) -> LanguageId {
+    let objc = LanguageId::new("objc");
+    let is_header = path.extension().and_then(|ext| ext.to_str()) == Some("h");
+    if !is_header || !is_objc_source(content) {
+        return language_id;
+    }
+
+    let registry = get_registry();
+    match registry.lock() {
+        Ok(registry) if registry.is_enabled(objc, settings) => objc,
+        _ => language_id,
+    }
+}
+
+fn is_objc_source(content: &str) -> bool {
+    content.lines().any(|line| {
+        let line = line.trim_start();
+        OBJC_HEADER_MARKERS
+            .iter()
+            .any(|marker| line.starts_with(marker))
+    })
+}
+
 /// Parse stage configuration.
 #[derive(Debug, Clone)]
 pub struct ParseStage {
@@ -121,7 +208,24 @@ impl ParseStage {
 /// 3. Extracts symbols, imports, and relationships
 /// 4. Returns ParsedFile with RawSymbols (no IDs assigned)
 pub fn parse_file(content: FileContent, settings: &Settings) -> PipelineResult<ParsedFile> {
-    let language_id = detect_language(&content.path)?;
+    let language_id = header_language(
+        detect_language(&content.path)?,
+        &content.path,
+        &content.content,
+        settings,
+    );
+
+    // An identical file was read first; nothing to parse
+    if let Some(canonical) = content.duplicate_of {
+        let module_path = compute_module_path(&content.path, language_id, settings);
+        let mut parsed = ParsedFile::new(content.path, content.hash, language_id);
+        parsed.module_path = module_path;
+        parsed.duplicate_of = Some(canonical);
+        return Ok(parsed);
+    }
+
+    let oversized =
+        large_file_limit(language_id, settings).is_some_and(|limit| content.content.len() > limit);
 
     PARSER_CACHE.with(|cache| {
         let mut cache_ref = cache.borrow_mut();
@@ -129,9 +233,44 @@ pub fn parse_file(content: FileContent, settings: &Settings) -> PipelineResult<P
             .as_mut()
             .expect("Parser cache not initialized. Call init_parser_cache first.");
 
-        let parser = parser_cache.get_or_create(language_id)?;
+        if oversized {
+            tracing::info!(
+                target: "pipeline",
+                "Parsing {} ({} KB) in chunks",
+                content.path.display(),
+                content.content.len() / 1024
+            );
+            let (parser, _, _) = parser_cache.get_or_create(language_id)?;
+            return parse_chunked(content, language_id, parser, settings);
+        }
 
-        parse_with_parser(content, language_id, parser, settings)
+        let grammar = host_grammar(language_id);
+        let diagnostics = grammar
+            .as_ref()
+            .map(|grammar| parser_cache.diagnostics.check(&content.content, grammar))
+            .unwrap_or_default();
+        let hints = match grammar {
+            Some(grammar)
+                if settings.indexing.type_inference && type_hints::supports(language_id) =>
+            {
+                parser_cache.type_hints.infer(&content.content, &grammar)
+            }
+            _ => Vec::new(),
+        };
+
+        let (parser, injection, user_queries) = parser_cache.get_or_create(language_id)?;
+
+        let mut parsed = parse_with_parser(
+            content,
+            language_id,
+            parser,
+            injection,
+            user_queries,
+            &hints,
+            settings,
+        )?;
+        parsed.diagnostics = diagnostics;
+        Ok(parsed)
     })
 }
 
@@ -140,6 +279,9 @@ fn parse_with_parser(
     content: FileContent,
     language_id: LanguageId,
     parser: &mut dyn LanguageParser,
+    injection: Option<&mut InjectionParser>,
+    user_queries: Option<&mut UserQueries>,
+    hints: &[TypeHint],
     settings: &Settings,
 ) -> PipelineResult<ParsedFile> {
     // Use a dummy file_id and counter - we just need to extract symbols
@@ -151,48 +293,45 @@ fn parse_with_parser(
     let module_path = compute_module_path(&content.path, language_id, settings);
 
     // Parse symbols
-    let symbols = parser.parse(&content.content, dummy_file_id, &mut counter);
+    let mut symbols = parser.parse(&content.content, dummy_file_id, &mut counter);
+
+    // Add and tag symbols matched by the user's own queries
+    if let Some(user_queries) = user_queries {
+        user_queries.apply(&content.content, &mut symbols, dummy_file_id, &mut counter);
+    }
+
+    // Parse code embedded in string literals (e.g., SQL queries)
+    let injections = match injection {
+        Some(injection) if injection::supports_host(language_id) => host_grammar(language_id)
+            .map(|grammar| {
+                injection.parse(
+                    &content.content,
+                    &grammar,
+                    &symbols,
+                    dummy_file_id,
+                    &mut counter,
+                )
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let injected_relationships = injected_relationships(&symbols, &injections);
 
     // Convert to RawSymbols (strip the dummy ID)
-    let raw_symbols: Vec<RawSymbol> = symbols
+    let mut raw_symbols: Vec<RawSymbol> = symbols
         .into_iter()
-        .map(|sym| {
-            let mut raw = RawSymbol::new(sym.name.clone(), sym.kind, sym.range);
-            if let Some(sig) = sym.signature {
-                raw = raw.with_signature(sig);
-            }
-            if let Some(doc) = sym.doc_comment {
-                raw = raw.with_doc_comment(doc);
-            }
-            raw = raw.with_visibility(sym.visibility);
-            if let Some(ctx) = sym.scope_context {
-                raw = raw.with_scope_context(ctx);
-            }
-            raw
-        })
+        .chain(injections.into_iter().flat_map(|code| code.symbols))
+        .map(raw_symbol)
         .collect();
+    chunk_for_embedding(&mut raw_symbols, &content.content, language_id, settings);
 
     // Extract imports (without FileId)
     let imports = parser.find_imports(&content.content, dummy_file_id);
-    let raw_imports: Vec<RawImport> = imports
-        .into_iter()
-        .map(|imp| {
-            let mut raw = RawImport::new(&imp.path);
-            if let Some(alias) = imp.alias {
-                raw = raw.with_alias(alias);
-            }
-            if imp.is_glob {
-                raw = raw.as_glob();
-            }
-            if imp.is_type_only {
-                raw = raw.as_type_only();
-            }
-            raw
-        })
-        .collect();
+    let raw_imports: Vec<RawImport> = imports.into_iter().map(raw_import).collect();
 
     // Extract relationships
-    let raw_relationships = extract_relationships(parser, &content.content);
+    let mut raw_relationships = extract_relationships(parser, &content.content, hints);
+    raw_relationships.extend(injected_relationships);
 
     Ok(ParsedFile {
         path: content.path,
@@ -202,9 +341,182 @@ fn parse_with_parser(
         raw_symbols,
         raw_imports,
         raw_relationships,
+        diagnostics: Vec::new(),
+        generated: content.generated,
+        duplicate_of: None,
     })
 }
 
+/// Parse a file above its size limit chunk by chunk.
+///
+/// Chunks split at top-level definitions, so each tree-sitter pass stays
+/// small and top-level symbols, imports and calls are still found. Type
+/// hints, injections, user queries and syntax diagnostics need the whole file
+/// and are skipped.
+///
+/// A definition running past 4x the chunk size is cut wherever the limit
+/// falls (see [`split_top_level`]). Neither half parses cleanly, so symbols
+/// around the cut may be missing or cut short. Each such cut is recorded as
+/// a split diagnostic, which marks symbols overlapping it as low-confidence.
+fn parse_chunked(
+    content: FileContent,
+    language_id: LanguageId,
+    parser: &mut dyn LanguageParser,
+    settings: &Settings,
+) -> PipelineResult<ParsedFile> {
+    let dummy_file_id = FileId::new(1).unwrap();
+    let mut counter = SymbolCounter::new();
+
+    let mut parsed = ParsedFile::new(content.path.clone(), content.hash, language_id);
+    parsed.module_path = compute_module_path(&content.path, language_id, settings);
+    parsed.generated = content.generated;
+
+    for (first_line, chunk, forced) in split_top_level(&content.content, CHUNK_BYTES) {
+        if forced {
+            parsed
+                .diagnostics
+                .push(ParseDiagnostic::split(first_line, chunk));
+        }
+        let symbols = parser.parse(chunk, dummy_file_id, &mut counter);
+        parsed.raw_symbols.extend(symbols.into_iter().map(|sym| {
+            let mut raw = raw_symbol(sym);
+            raw.range = shift_range(raw.range, first_line);
+            raw
+        }));
+
+        let imports = parser.find_imports(chunk, dummy_file_id);
+        parsed
+            .raw_imports
+            .extend(imports.into_iter().map(raw_import));
+
+        for mut relationship in extract_relationships(parser, chunk, &[]) {
+            relationship.from_range = shift_range(relationship.from_range, first_line);
+            relationship.to_range = shift_range(relationship.to_range, first_line);
+            parsed.raw_relationships.push(relationship);
+        }
+    }
+    chunk_for_embedding(
+        &mut parsed.raw_symbols,
+        &content.content,
+        language_id,
+        settings,
+    );
+
+    Ok(parsed)
+}
+
+/// Set the texts semantic search embeds for `symbols`, per the chunking
+/// strategy of `language_id`
+fn chunk_for_embedding(
+    symbols: &mut [RawSymbol],
+    source: &str,
+    language_id: LanguageId,
+    settings: &Settings,
+) {
+    if !settings.semantic_search.enabled {
+        return;
+    }
+    let strategy = ChunkingStrategy::for_language(settings, language_id.as_str());
+    chunk_symbols(symbols, Some(source), strategy, settings);
+}
+
+/// Bytes per tree-sitter pass when parsing in chunks
+const CHUNK_BYTES: usize = 256 * 1024;
+
+/// Size in bytes above which files of `language_id` are parsed in chunks
+///
+/// `languages.<id>.large_file_mb` overrides `indexing.large_file_mb`; 0 means
+/// no limit.
+fn large_file_limit(language_id: LanguageId, settings: &Settings) -> Option<usize> {
+    let megabytes = settings
+        .languages
+        .get(language_id.as_str())
+        .and_then(|language| language.large_file_mb)
+        .unwrap_or(settings.indexing.large_file_mb);
+    (megabytes > 0).then(|| megabytes * 1024 * 1024)
+}
+
+/// Split `code` into pieces of about `target` bytes, with the 0-based line
+/// each piece starts on.
+///
+/// A piece ends before a line that starts in column 0 after a blank line or
+/// a closed block, which is where top-level definitions begin. Files without
+/// such lines, like one huge class, are split at any line past 4x `target`;
+/// the third value tells a piece that starts at such a forced split.
+fn split_top_level(code: &str, target: usize) -> Vec<(u32, &str, bool)> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut start_line = 0;
+    let mut offset = 0;
+    let mut after_boundary = false;
+    let mut forced = false;
+
+    for (line, text) in (0u32..).zip(code.split_inclusive('\n')) {
+        let size = offset - start;
+        let top_level = text.bytes().next().is_some_and(|byte| {
+            byte.is_ascii_alphabetic() || matches!(byte, b'_' | b'@' | b'#' | b'/')
+        });
+        let at_definition = top_level && after_boundary;
+        if size >= target && (at_definition || size >= target * 4) {
+            pieces.push((start_line, &code[start..offset], forced));
+            start = offset;
+            start_line = line;
+            forced = !at_definition;
+        }
+
+        let trimmed = text.trim_end();
+        after_boundary = trimmed.is_empty() || trimmed.ends_with(['}', ';', ')']);
+        offset += text.len();
+    }
+    if start < code.len() {
+        pieces.push((start_line, &code[start..], forced));
+    }
+    pieces
+}
+
+fn shift_range(range: Range, lines: u32) -> Range {
+    Range {
+        start_line: range.start_line + lines,
+        end_line: range.end_line + lines,
+        ..range
+    }
+}
+
+/// Convert a parsed symbol to a RawSymbol (strip the dummy ID)
+fn raw_symbol(sym: Symbol) -> RawSymbol {
+    let mut raw = RawSymbol::new(sym.name.clone(), sym.kind, sym.range);
+    if let Some(sig) = sym.signature {
+        raw = raw.with_signature(sig);
+    }
+    // Same summary/parameters/returns layout in every language
+    if let Some(doc) = sym.doc_comment.as_deref().and_then(doc::normalize) {
+        raw = raw.with_doc_comment(doc);
+    }
+    raw = raw.with_visibility(sym.visibility);
+    if let Some(ctx) = sym.scope_context {
+        raw = raw.with_scope_context(ctx);
+    }
+    if let Some(tag) = sym.tag {
+        raw = raw.with_tag(tag);
+    }
+    raw
+}
+
+/// Convert a parsed import to a RawImport (without FileId)
+fn raw_import(imp: Import) -> RawImport {
+    let mut raw = RawImport::new(&imp.path);
+    if let Some(alias) = imp.alias {
+        raw = raw.with_alias(alias);
+    }
+    if imp.is_glob {
+        raw = raw.as_glob();
+    }
+    if imp.is_type_only {
+        raw = raw.as_type_only();
+    }
+    raw
+}
+
 /// Compute module_path for a file using the language behavior.
 ///
 /// This calls behavior.module_path_from_file() which uses:
@@ -222,14 +534,70 @@ fn compute_module_path(
     let definition = registry_guard.get(language_id)?;
     let behavior = definition.create_behavior();
 
-    let project_root = settings
-        .workspace_root
-        .as_deref()
-        .unwrap_or_else(|| Path::new("."));
+    // Files of a configured repository get module paths relative to its own
+    // root, so each repository reads as a separate project
+    let repository_root = settings
+        .repository_roots()
+        .into_iter()
+        .map(|(_, root)| root)
+        .filter(|root| file_path.starts_with(root))
+        .max_by_key(|root| root.components().count());
+    let project_root = match &repository_root {
+        Some(root) => root.as_path(),
+        None => settings
+            .workspace_root
+            .as_deref()
+            .unwrap_or_else(|| Path::new(".")),
+    };
 
     behavior.module_path_from_file(file_path, project_root)
 }
 
+/// Grammar used to find string literals in files of a host language.
+fn host_grammar(language_id: LanguageId) -> Option<tree_sitter::Language> {
+    let registry = get_registry();
+    let registry_guard = registry.lock().ok()?;
+    let definition = registry_guard
+        .get(language_id)
+        .filter(|definition| definition.has_grammar())?;
+    Some(definition.create_behavior().get_language())
+}
+
+/// `Uses` edges from the symbol containing injected code to the objects it
+/// references, e.g. a function to the tables its SQL query reads.
+///
+/// The edges carry an injection context so RESOLVE can look for targets in
+/// the injected language instead of the host's.
+fn injected_relationships(symbols: &[Symbol], injections: &[InjectedCode]) -> Vec<RawRelationship> {
+    let mut relationships = Vec::new();
+
+    for code in injections {
+        let Some(host) = injection::host_symbol(symbols, &code.range) else {
+            continue;
+        };
+        let context = injection::injection_context(code.language);
+        for (name, range) in &code.references {
+            let range = *range;
+            relationships.push(
+                RawRelationship::new(
+                    &*host.name,
+                    host.range, // from_range = host definition
+                    name.as_str(),
+                    range, // to_range = reference inside the literal
+                    crate::RelationKind::Uses,
+                )
+                .with_metadata(RelationshipMetadata {
+                    line: Some(range.start_line),
+                    column: Some(range.start_column),
+                    context: Some(context.as_str().into()),
+                }),
+            );
+        }
+    }
+
+    relationships
+}
+
 /// Extract relationships from parsed content.
 ///
 /// Range semantics:
@@ -238,20 +606,42 @@ fn compute_module_path(
 ///
 /// For MethodCall: `caller_range` provides precise from_range when available.
 /// For legacy find_* methods: range typically points to the reference site.
-fn extract_relationships(parser: &mut dyn LanguageParser, content: &str) -> Vec<RawRelationship> {
+///
+/// Calls on a receiver with a type hint carry the guessed type in their
+/// metadata context for RESOLVE.
+fn extract_relationships(
+    parser: &mut dyn LanguageParser,
+    content: &str,
+    hints: &[TypeHint],
+) -> Vec<RawRelationship> {
     let mut relationships = Vec::new();
 
     // Function/method calls - MethodCall provides caller_range for precise lookup
     for call in parser.find_method_calls(content) {
         // Use caller_range when available, otherwise use call site (triggers fallback)
         let from_range = call.caller_range.unwrap_or(call.range);
-        relationships.push(RawRelationship::new(
-            call.caller,
+        let hint = match call.receiver.as_deref() {
+            Some(receiver) if !call.is_static => {
+                type_hints::receiver_type(hints, receiver, &call.caller, call.range.start_line)
+                    .map(|hint| (receiver, hint))
+            }
+            _ => None,
+        };
+        let mut relationship = RawRelationship::new(
+            call.caller.as_str(),
             from_range,
-            call.method_name,
+            call.method_name.as_str(),
             call.range, // to_range = call site
             crate::RelationKind::Calls,
-        ));
+        );
+        if let Some((receiver, hint)) = hint {
+            relationship = relationship.with_metadata(RelationshipMetadata {
+                line: Some(call.range.start_line),
+                column: Some(call.range.start_column),
+                context: Some(type_hints::call_context(receiver, hint).into()),
+            });
+        }
+        relationships.push(relationship);
     }
 
     // Plain function calls (legacy - no caller_range available)
@@ -338,6 +728,7 @@ pub fn compute_hash(content: &[u8]) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parsing::diagnostics::DiagnosticKind;
     use crate::types::Range;
 
     #[test]
@@ -366,6 +757,13 @@ mod tests {
         assert_eq!(result.unwrap().as_str(), "typescript");
     }
 
+    #[test]
+    fn test_detect_language_config_only_for_manifests() {
+        let result = detect_language(Path::new("crates/core/Cargo.toml"));
+        assert_eq!(result.unwrap().as_str(), "config");
+        assert!(detect_language(Path::new("package-lock.json")).is_err());
+    }
+
     #[test]
     fn test_detect_language_unknown() {
         let path = Path::new("file.xyz");
@@ -373,6 +771,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_objc_header_routed_to_objc() {
+        let settings = Settings::default();
+        let c = LanguageId::new("c");
+        let path = Path::new("Client.h");
+
+        let objc_header = "#import <Foundation/Foundation.h>\n@interface Client : NSObject\n@end\n";
+        assert_eq!(
+            header_language(c, path, objc_header, &settings).as_str(),
+            "objc"
+        );
+
+        let c_header = "#include <stdio.h>\nint add(int a, int b);\n";
+        assert_eq!(header_language(c, path, c_header, &settings).as_str(), "c");
+        assert_eq!(
+            header_language(c, Path::new("client.c"), objc_header, &settings).as_str(),
+            "c"
+        );
+    }
+
     #[test]
     fn test_parse_file_rust() {
         let settings = Arc::new(Settings::default());
@@ -406,6 +824,154 @@ pub struct Foo {
         assert!(names.contains(&"Foo"));
     }
 
+    #[test]
+    fn test_split_top_level_at_definitions() {
+        let code = "fn a() {\n    x();\n}\n\nfn b() {\n    y();\n}\n\nfn c() {}\n";
+        let pieces = split_top_level(code, 10);
+        let starts: Vec<u32> = pieces.iter().map(|(line, _, _)| *line).collect();
+        assert_eq!(starts, vec![0, 4, 8]);
+        assert_eq!(
+            pieces.iter().map(|(_, text, _)| *text).collect::<String>(),
+            code
+        );
+        assert!(pieces.iter().all(|(_, _, forced)| !forced));
+
+        // No top-level boundary: forced split past 4x the target
+        let class = format!("class A {{\n{}}}\n", "    int x;\n".repeat(20));
+        let pieces = split_top_level(&class, 20);
+        assert!(pieces.len() > 1);
+        assert!(!pieces[0].2 && pieces[1..].iter().all(|(_, _, forced)| *forced));
+    }
+
+    #[test]
+    fn test_definition_cut_by_a_forced_split_is_reported() {
+        let mut settings = Settings::default();
+        settings.indexing.large_file_mb = 1;
+        let settings = Arc::new(settings);
+        init_parser_cache(settings.clone());
+
+        // One impl block larger than 4x the chunk size has no top-level boundary
+        let methods: String = (0..50_000)
+            .map(|i| format!("    fn m{i}(&self) {{ g{i}(); }}\n"))
+            .collect();
+        let code = format!("struct Big;\nimpl Big {{\n{methods}}}\n");
+        assert!(code.len() > 4 * CHUNK_BYTES);
+        let content = FileContent::new("big.rs".into(), code, "abc123def456".to_string());
+
+        let parsed = parse_file(content, &settings).unwrap();
+        let splits: Vec<&ParseDiagnostic> = parsed
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.kind == DiagnosticKind::Split)
+            .collect();
+        assert!(!splits.is_empty());
+        assert!(splits[0].text.starts_with("fn m"));
+
+        // Every method is either extracted or overlaps a reported cut
+        let found: std::collections::HashSet<&str> = parsed
+            .raw_symbols
+            .iter()
+            .map(|sym| sym.name.as_ref())
+            .collect();
+        for i in 0..50_000u32 {
+            let line = i + 2;
+            let range = Range::new(line, 0, line, 0);
+            assert!(
+                found.contains(format!("m{i}").as_str())
+                    || splits.iter().any(|split| split.overlaps(&range)),
+                "m{i} lost without a diagnostic"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_file_large_file_in_chunks() {
+        let mut settings = Settings::default();
+        settings.indexing.large_file_mb = 0;
+        if let Some(rust) = settings.languages.get_mut("rust") {
+            rust.large_file_mb = Some(1);
+        }
+        let settings = Arc::new(settings);
+        init_parser_cache(settings.clone());
+        assert_eq!(
+            large_file_limit(LanguageId::new("rust"), &settings),
+            Some(1024 * 1024)
+        );
+        assert_eq!(large_file_limit(LanguageId::new("python"), &settings), None);
+
+        let code: String = (0..40_000)
+            .map(|i| format!("fn f{i}() {{\n    g{i}();\n}}\n\n"))
+            .collect();
+        assert!(code.len() > 1024 * 1024);
+        let content = FileContent::new("big.rs".into(), code, "abc123def456".to_string());
+
+        let parsed = parse_file(content, &settings).unwrap();
+        assert_eq!(parsed.raw_symbols.len(), 40_000);
+        let last = parsed
+            .raw_symbols
+            .iter()
+            .find(|sym| &*sym.name == "f39999")
+            .unwrap();
+        assert_eq!(last.range.start_line, 39_999 * 4);
+
+        let call = parsed
+            .raw_relationships
+            .iter()
+            .find(|rel| rel.to_name.as_ref() == "g30000")
+            .unwrap();
+        assert_eq!(call.from_name.as_ref(), "f30000");
+        assert_eq!(call.to_range.start_line, 30_000 * 4 + 1);
+    }
+
+    #[test]
+    fn test_parse_file_language_injections() {
+        let mut settings = Settings::default();
+        settings.indexing.language_injections = true;
+        let settings = Arc::new(settings);
+        init_parser_cache(settings.clone());
+
+        let content = FileContent::new(
+            "repo.rs".into(),
+            r#"
+fn migrate(db: &Db) {
+    db.execute("CREATE TABLE audit_log (id INTEGER PRIMARY KEY)");
+}
+
+fn load_users(db: &Db) {
+    db.query("SELECT id, name FROM users WHERE active = 1");
+}
+"#
+            .to_string(),
+            "abc123def456".to_string(),
+        );
+
+        let parsed = parse_file(content, &settings).unwrap();
+
+        let table = parsed
+            .raw_symbols
+            .iter()
+            .find(|s| s.name.as_ref() == "audit_log")
+            .expect("table created in a string literal should be indexed");
+        assert_eq!(table.range.start_line, 2);
+        assert!(matches!(
+            &table.scope_context,
+            Some(crate::ScopeContext::Local { parent_name: Some(parent), .. })
+                if &**parent == "migrate"
+        ));
+
+        let uses = parsed
+            .raw_relationships
+            .iter()
+            .find(|r| r.to_name.as_ref() == "users")
+            .expect("queried table should be a Uses target");
+        assert_eq!(uses.from_name.as_ref(), "load_users");
+        assert_eq!(uses.kind, crate::RelationKind::Uses);
+        assert_eq!(
+            uses.metadata.as_ref().and_then(|m| m.context.as_deref()),
+            Some("injected:sql")
+        );
+    }
+
     #[test]
     fn test_raw_symbol_has_no_id() {
         // RawSymbol intentionally has no id field
@@ -3,6 +3,11 @@
 //! Reads file contents and computes content hashes.
 //! Runs with multiple threads to saturate I/O.
 //!
+//! Binary files are dropped here, before any parser sees them. Generated and
+//! minified files are dropped or flagged per `indexing.generated_files`.
+//! Copies of a file already read in the run become aliases of it and carry
+//! no content. Virtual paths into archives are read from the archive.
+//!
 //! # Security (CODITECT ADR-065)
 //!
 //! This module uses secure file reading functions that:
@@ -10,20 +15,34 @@
 //! - Validate paths stay within workspace boundary
 //! - Prevent TOCTOU (time-of-check to time-of-use) attacks
 
+use crate::config::GeneratedFiles;
+use crate::indexing::archive::{self, ArchiveCache};
 use crate::indexing::file_info::calculate_hash;
+use crate::indexing::pipeline::dedup::ContentDedup;
+use crate::indexing::pipeline::memory::MemoryBudget;
+use crate::indexing::pipeline::sniff::{self, FileKind};
 use crate::indexing::pipeline::types::{FileContent, PipelineError, PipelineResult};
-use crate::security::{safe_read_to_string, SafeFileError};
+use crate::security::{SafeFileError, safe_read_to_string};
 use crossbeam_channel::{Receiver, Sender};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::thread;
+use tokio_util::sync::CancellationToken;
 
 /// Read stage for file content loading.
 pub struct ReadStage {
     threads: usize,
     /// Workspace root for path normalization (stores relative paths)
     workspace_root: Option<PathBuf>,
+    /// Bounds the bytes read but not yet parsed
+    memory_budget: Option<Arc<MemoryBudget>>,
+    /// Handling of generated and minified files
+    generated_files: GeneratedFiles,
+    /// Turns copies of an already-read file into aliases
+    dedup: Option<Arc<ContentDedup>>,
+    /// Stops reading when the run is cancelled
+    cancel: CancellationToken,
 }
 
 impl ReadStage {
@@ -32,6 +51,10 @@ impl ReadStage {
         Self {
             threads: threads.max(1),
             workspace_root: None,
+            memory_budget: None,
+            generated_files: GeneratedFiles::default(),
+            dedup: None,
+            cancel: CancellationToken::new(),
         }
     }
 
@@ -40,12 +63,48 @@ impl ReadStage {
         Self {
             threads: threads.max(1),
             workspace_root,
+            memory_budget: None,
+            generated_files: GeneratedFiles::default(),
+            dedup: None,
+            cancel: CancellationToken::new(),
         }
     }
 
+    /// Wait for room in `budget` before reading each file.
+    ///
+    /// The file's share is held until its content is dropped after parsing.
+    pub fn with_memory_budget(mut self, budget: Arc<MemoryBudget>) -> Self {
+        self.memory_budget = Some(budget);
+        self
+    }
+
+    /// Set how generated and minified files are handled.
+    pub fn with_generated_files(mut self, generated_files: GeneratedFiles) -> Self {
+        self.generated_files = generated_files;
+        self
+    }
+
+    /// Alias files whose content matches a file read earlier in the run.
+    pub fn with_dedup(mut self, dedup: Option<Arc<ContentDedup>>) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Stop reading further files once `cancel` is cancelled.
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
     /// Read a single file directly (for incremental mode).
+    ///
+    /// Fails with [`PipelineError::SkippedFile`] for files the run stage drops.
     pub fn read_single(&self, path: &PathBuf) -> PipelineResult<FileContent> {
-        read_file(path)
+        let content = read_file(path, &ArchiveCache::default())?;
+        screen(content, self.generated_files).map_err(|kind| PipelineError::SkippedFile {
+            path: path.clone(),
+            kind: kind.as_str(),
+        })
     }
 
     /// Run the read stage, reading from path channel and sending to content channel.
@@ -72,6 +131,12 @@ impl ReadStage {
 
         let workspace_root = self.workspace_root.clone();
         let workspace_root = Arc::new(workspace_root);
+        let memory_budget = self.memory_budget.clone();
+        let generated_files = self.generated_files;
+        let dedup = self.dedup.clone();
+        let cancel = self.cancel.clone();
+        // Archives stay open for the run, not for each entry read from them
+        let archives = Arc::new(ArchiveCache::default());
 
         let handles: Vec<_> = (0..self.threads)
             .map(|_| {
@@ -82,6 +147,10 @@ impl ReadStage {
                 let input_wait_ns = input_wait_ns.clone();
                 let output_wait_ns = output_wait_ns.clone();
                 let workspace_root = workspace_root.clone();
+                let memory_budget = memory_budget.clone();
+                let dedup = dedup.clone();
+                let cancel = cancel.clone();
+                let archives = archives.clone();
 
                 thread::spawn(move || {
                     loop {
@@ -91,11 +160,38 @@ impl ReadStage {
                             Ok(p) => p,
                             Err(_) => break, // Channel closed
                         };
+                        if cancel.is_cancelled() {
+                            break;
+                        }
                         input_wait_ns
                             .fetch_add(recv_start.elapsed().as_nanos() as u64, Ordering::Relaxed);
 
-                        match read_file(&path) {
-                            Ok(mut content) => {
+                        // Waiting for memory is backpressure, like a full channel
+                        let permit = memory_budget.as_ref().map(|budget| {
+                            let size = std::fs::metadata(&path)
+                                .map(|metadata| metadata.len() as usize)
+                                .unwrap_or(0);
+                            let (permit, waited) = budget.acquire(size);
+                            output_wait_ns.fetch_add(waited.as_nanos() as u64, Ordering::Relaxed);
+                            permit
+                        });
+
+                        match read_file(&path, &archives) {
+                            Ok(content) => {
+                                let mut content = match screen(content, generated_files) {
+                                    Ok(content) => content,
+                                    Err(kind) => {
+                                        tracing::debug!(
+                                            target: "pipeline",
+                                            "Skipping {} file: {}",
+                                            kind.as_str(),
+                                            path.display()
+                                        );
+                                        continue;
+                                    }
+                                };
+                                content.permit = permit;
+
                                 // Normalize path to relative if workspace_root is set
                                 if let Some(ref root) = *workspace_root {
                                     if let Ok(relative) = content.path.strip_prefix(root) {
@@ -103,6 +199,10 @@ impl ReadStage {
                                     }
                                 }
 
+                                if let Some(dedup) = &dedup {
+                                    dedup.mark(&mut content);
+                                }
+
                                 read_count.fetch_add(1, Ordering::Relaxed);
 
                                 // Track output wait (time blocked on send)
@@ -139,6 +239,27 @@ impl ReadStage {
     }
 }
 
+/// Drop or flag `content` according to what it looks like.
+///
+/// Returns the kind of file that was dropped as the error.
+fn screen(
+    mut content: FileContent,
+    generated_files: GeneratedFiles,
+) -> Result<FileContent, FileKind> {
+    match sniff::classify(&content.path, &content.content) {
+        FileKind::Source => Ok(content),
+        FileKind::Binary => Err(FileKind::Binary),
+        kind => match generated_files {
+            GeneratedFiles::Skip => Err(kind),
+            GeneratedFiles::NoEmbeddings => {
+                content.generated = true;
+                Ok(content)
+            }
+            GeneratedFiles::Index => Ok(content),
+        },
+    }
+}
+
 /// Read a single file and compute its SHA256 hash.
 ///
 /// # Security (CODITECT ADR-065)
@@ -147,8 +268,8 @@ impl ReadStage {
 /// - Blocks symlink following (O_NOFOLLOW)
 /// - Prevents TOCTOU attacks
 /// - Logs security events for monitoring
-fn read_file(path: &PathBuf) -> PipelineResult<FileContent> {
-    read_file_with_boundary(path, None)
+fn read_file(path: &PathBuf, archives: &ArchiveCache) -> PipelineResult<FileContent> {
+    read_file_with_boundary(path, None, archives)
 }
 
 /// Read a single file with optional workspace boundary enforcement.
@@ -157,6 +278,7 @@ fn read_file(path: &PathBuf) -> PipelineResult<FileContent> {
 ///
 /// * `path` - The file path to read
 /// * `workspace_root` - Optional workspace root for boundary validation
+/// * `archives` - Open archives that virtual paths are read from
 ///
 /// # Security
 ///
@@ -165,7 +287,20 @@ fn read_file(path: &PathBuf) -> PipelineResult<FileContent> {
 fn read_file_with_boundary(
     path: &PathBuf,
     workspace_root: Option<&std::path::Path>,
+    archives: &ArchiveCache,
 ) -> PipelineResult<FileContent> {
+    if let Some((archive_path, entry)) = archive::split(path) {
+        let content =
+            archives
+                .read_entry(&archive_path, &entry)
+                .map_err(|e| PipelineError::FileRead {
+                    path: path.clone(),
+                    source: e,
+                })?;
+        let hash = calculate_hash(&content);
+        return Ok(FileContent::new(path.clone(), content, hash));
+    }
+
     let content = safe_read_to_string(path, workspace_root).map_err(|e| {
         match &e {
             SafeFileError::SymlinkDetected { path } => {
@@ -205,8 +340,8 @@ fn read_file_with_boundary(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
     use crossbeam_channel::bounded;
+    use std::fs;
     use tempfile::TempDir;
 
     #[test]
@@ -217,7 +352,7 @@ mod tests {
         let content = "fn main() { println!(\"Hello\"); }";
         fs::write(&file_path, content).unwrap();
 
-        let result = read_file(&file_path);
+        let result = read_file(&file_path, &ArchiveCache::default());
         assert!(result.is_ok(), "Read should succeed");
 
         let file_content = result.unwrap();
@@ -315,6 +450,53 @@ mod tests {
         assert!(contents.is_empty(), "No content should be produced");
     }
 
+    #[test]
+    fn test_read_stage_screens_binary_and_generated_files() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("lib.rs");
+        fs::write(&source, "fn run() {}").unwrap();
+        let binary = temp.path().join("blob.rs");
+        fs::write(&binary, "\0\0\0\0").unwrap();
+        let generated = temp.path().join("api.pb.go");
+        fs::write(&generated, "package api").unwrap();
+
+        let run = |generated_files| {
+            let (path_tx, path_rx) = bounded(10);
+            let (content_tx, content_rx) = bounded(10);
+            for path in [&source, &binary, &generated] {
+                path_tx.send(path.clone()).unwrap();
+            }
+            drop(path_tx);
+
+            let stage = ReadStage::new(1).with_generated_files(generated_files);
+            let (read, failed, _, _, _) = stage.run(path_rx, content_tx).unwrap();
+            assert_eq!(failed, 0, "Skipped files are not failures");
+            let mut contents: Vec<_> = content_rx.iter().collect();
+            contents.sort_by(|a, b| a.path.cmp(&b.path));
+            assert_eq!(read, contents.len());
+            contents
+                .into_iter()
+                .map(|c| (c.path, c.generated))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            run(GeneratedFiles::NoEmbeddings),
+            vec![(generated.clone(), true), (source.clone(), false)]
+        );
+        assert_eq!(run(GeneratedFiles::Skip), vec![(source.clone(), false)]);
+        assert_eq!(
+            run(GeneratedFiles::Index),
+            vec![(generated.clone(), false), (source.clone(), false)]
+        );
+
+        let skipped = ReadStage::new(1).read_single(&binary);
+        assert!(matches!(
+            skipped,
+            Err(PipelineError::SkippedFile { kind: "binary", .. })
+        ));
+    }
+
     #[test]
     fn test_hash_consistency() {
         let content1 = "fn hello() {}";
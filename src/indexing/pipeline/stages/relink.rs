@@ -0,0 +1,241 @@
+//! Relink stage - symbol-level incremental resolution
+//!
+//! Re-indexing a file gives its symbols new IDs, and cleanup drops every
+//! relationship that pointed at the old ones, including calls made from files
+//! that did not change. Rather than re-parsing and re-resolving those files,
+//! this stage records the incoming edges before cleanup ([`RelinkStage::capture`])
+//! and points them at the new symbol of the same name once the file is
+//! re-indexed ([`RelinkStage::relink`]). Only edges into the changed file are
+//! touched; edges from it are resolved by Phase 2 as usual.
+//!
+//! An edge whose target was renamed or deleted has nothing to point at and is
+//! dropped, as cleanup would have done.
+
+use crate::indexing::pipeline::stages::write::WriteStage;
+use crate::indexing::pipeline::types::{
+    PipelineResult, ResolvedBatch, ResolvedRelationship, SymbolLookupCache,
+};
+use crate::relationship::{RelationKind, Relationship};
+use crate::storage::DocumentIndex;
+use crate::types::{FileId, SymbolId, SymbolKind};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Relationship kinds written by Phase 2, the ones cleanup can orphan
+const RESOLVED_KINDS: [RelationKind; 6] = [
+    RelationKind::Calls,
+    RelationKind::Defines,
+    RelationKind::Extends,
+    RelationKind::Implements,
+    RelationKind::Uses,
+    RelationKind::References,
+];
+
+/// Incoming edges read per query
+const PAGE_SIZE: usize = 1000;
+
+/// An edge from an unchanged file into a file about to be re-indexed.
+#[derive(Debug, Clone)]
+pub struct IncomingEdge {
+    pub from_id: SymbolId,
+    /// Name of the target symbol, matched against the re-indexed file
+    pub to_name: Box<str>,
+    pub to_kind: SymbolKind,
+    /// First line of the target, used to tell apart symbols with one name
+    pub to_line: u32,
+    pub relationship: Relationship,
+}
+
+/// Statistics from relinking.
+#[derive(Debug, Default, Clone)]
+pub struct RelinkStats {
+    /// Edges captured before cleanup
+    pub captured: usize,
+    /// Edges pointed at a symbol of the re-indexed file
+    pub relinked: usize,
+    /// Edges whose target no longer exists
+    pub dropped: usize,
+}
+
+/// Relink stage for edges into re-indexed files.
+pub struct RelinkStage {
+    index: Arc<DocumentIndex>,
+    symbol_cache: Arc<SymbolLookupCache>,
+}
+
+impl RelinkStage {
+    /// Create a relink stage; `symbol_cache` must hold the re-indexed symbols.
+    pub fn new(index: Arc<DocumentIndex>, symbol_cache: Arc<SymbolLookupCache>) -> Self {
+        Self {
+            index,
+            symbol_cache,
+        }
+    }
+
+    /// Edges into `file_id` from symbols defined in other files.
+    ///
+    /// Call before cleanup removes the file.
+    pub fn capture(index: &DocumentIndex, file_id: FileId) -> PipelineResult<Vec<IncomingEdge>> {
+        let symbols = index.find_symbols_by_file(file_id)?;
+        let local: HashSet<SymbolId> = symbols.iter().map(|symbol| symbol.id).collect();
+        let mut edges = Vec::new();
+
+        for symbol in &symbols {
+            for kind in RESOLVED_KINDS {
+                let mut offset = 0;
+                loop {
+                    let page =
+                        index.get_relationships_to_page(symbol.id, kind, offset, PAGE_SIZE)?;
+                    let full = page.len() == PAGE_SIZE;
+                    edges.extend(
+                        page.into_iter()
+                            .filter(|(from_id, _, _)| !local.contains(from_id))
+                            .map(|(from_id, _, relationship)| IncomingEdge {
+                                from_id,
+                                to_name: symbol.name.as_ref().into(),
+                                to_kind: symbol.kind,
+                                to_line: symbol.range.start_line,
+                                relationship,
+                            }),
+                    );
+                    if !full {
+                        break;
+                    }
+                    offset += PAGE_SIZE;
+                }
+            }
+        }
+
+        Ok(edges)
+    }
+
+    /// Point `edges` at the symbols now defined in `file_id`.
+    ///
+    /// A symbol of the same name and kind is preferred, then one of the same
+    /// name; among several, the one starting nearest the old target wins.
+    pub fn relink(&self, edges: Vec<IncomingEdge>, file_id: FileId) -> PipelineResult<RelinkStats> {
+        let mut stats = RelinkStats {
+            captured: edges.len(),
+            ..Default::default()
+        };
+        if edges.is_empty() {
+            return Ok(stats);
+        }
+
+        let symbols: Vec<_> = self
+            .symbol_cache
+            .symbols_in_file(file_id)
+            .into_iter()
+            .filter_map(|id| self.symbol_cache.get(id))
+            .collect();
+
+        let mut batch = ResolvedBatch::with_capacity(edges.len());
+        for edge in edges {
+            let named: Vec<_> = symbols
+                .iter()
+                .filter(|symbol| symbol.name.as_ref() == edge.to_name.as_ref())
+                .collect();
+            let same_kind: Vec<_> = named
+                .iter()
+                .copied()
+                .filter(|symbol| symbol.kind == edge.to_kind)
+                .collect();
+            let candidates = if same_kind.is_empty() {
+                named
+            } else {
+                same_kind
+            };
+
+            let Some(target) = candidates
+                .into_iter()
+                .min_by_key(|symbol| symbol.range.start_line.abs_diff(edge.to_line))
+            else {
+                stats.dropped += 1;
+                continue;
+            };
+
+            batch.relationships.push(ResolvedRelationship {
+                from_id: edge.from_id,
+                to_id: target.id,
+                kind: edge.relationship.kind,
+                metadata: edge.relationship.metadata,
+            });
+        }
+
+        let mut write_stage = WriteStage::new(Arc::clone(&self.index));
+        stats.relinked = write_stage.write(batch).written;
+        write_stage.flush()?;
+
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbol;
+    use crate::config::Settings;
+    use crate::types::Range;
+    use tempfile::TempDir;
+
+    fn symbol(id: u32, name: &str, file_id: u32, line: u32) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(file_id).unwrap(),
+            Range::new(line, 0, line + 5, 1),
+        )
+    }
+
+    #[test]
+    fn test_relink_points_callers_at_reindexed_symbols() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = Arc::new(DocumentIndex::new(temp_dir.path(), &Settings::default()).unwrap());
+
+        // caller.rs calls target and removed in lib.rs
+        index.start_batch().unwrap();
+        index
+            .index_symbol(&symbol(1, "caller", 1, 1), "caller.rs")
+            .unwrap();
+        index
+            .index_symbol(&symbol(2, "target", 2, 10), "lib.rs")
+            .unwrap();
+        index
+            .index_symbol(&symbol(3, "removed", 2, 20), "lib.rs")
+            .unwrap();
+        for to in [2, 3] {
+            index
+                .store_relationship(
+                    SymbolId::new(1).unwrap(),
+                    SymbolId::new(to).unwrap(),
+                    &Relationship::new(RelationKind::Calls),
+                )
+                .unwrap();
+        }
+        index.commit_batch().unwrap();
+
+        let edges = RelinkStage::capture(&index, FileId::new(2).unwrap()).unwrap();
+        assert_eq!(edges.len(), 2);
+
+        // lib.rs re-indexed as file 3: target moved, removed is gone
+        let reindexed = symbol(4, "target", 3, 12);
+        index.start_batch().unwrap();
+        index.index_symbol(&reindexed, "lib.rs").unwrap();
+        index.commit_batch().unwrap();
+        let cache = Arc::new(SymbolLookupCache::new());
+        cache.insert(reindexed);
+
+        let stage = RelinkStage::new(Arc::clone(&index), cache);
+        let stats = stage.relink(edges, FileId::new(3).unwrap()).unwrap();
+        assert_eq!(stats.captured, 2);
+        assert_eq!(stats.relinked, 1);
+        assert_eq!(stats.dropped, 1);
+
+        let callers = index
+            .get_relationships_to(SymbolId::new(4).unwrap(), RelationKind::Calls)
+            .unwrap();
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].0, SymbolId::new(1).unwrap());
+    }
+}
@@ -11,6 +11,10 @@
 //! 4. Use behavior.import_matches_symbol() for proper import matching
 //! 5. Produce ResolvedRelationship with (from_id, to_id, kind, metadata)
 //!
+//! Calls whose receiver type was guessed in PARSE (see `parsing::type_hints`)
+//! first look for the method on that class; if none exists, the guess is
+//! dropped and the call resolves like any other.
+//!
 //! Two-pass execution:
 //! - Pass 1: Resolve Defines relationships
 //! - Pass 2: Resolve Calls (can reference Defines from Pass 1)
@@ -19,9 +23,12 @@ use crate::indexing::pipeline::types::{
     CallerContext, ResolutionContext, ResolvedBatch, ResolvedRelationship, SymbolLookupCache,
     UnresolvedRelationship,
 };
+use crate::parsing::injection;
+use crate::parsing::protobuf::{ProtobufLanguage, grpc};
+use crate::parsing::type_hints;
 use crate::parsing::{Import, LanguageBehavior, LanguageId};
 use crate::types::{FileId, SymbolId};
-use crate::{RelationKind, Symbol};
+use crate::{RelationKind, ScopeContext, Symbol, SymbolKind};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -49,6 +56,8 @@ pub struct ResolveStats {
     pub defines_resolved: usize,
     /// Calls resolved
     pub calls_resolved: usize,
+    /// Calls resolved through an inferred receiver type
+    pub inferred_resolved: usize,
 }
 
 impl ResolveStage {
@@ -86,7 +95,25 @@ impl ResolveStage {
                     RelationKind::Calls => stats.calls_resolved += 1,
                     _ => {}
                 }
+                let inferred = resolved
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.context.as_deref());
+                if inferred.and_then(type_hints::inferred_type).is_some() {
+                    stats.inferred_resolved += 1;
+                }
                 stats.resolved += 1;
+                let links_service = resolved.kind == RelationKind::Implements
+                    && self
+                        .symbol_cache
+                        .get(resolved.to_id)
+                        .is_some_and(|s| grpc::is_proto_service(&s));
+                if links_service {
+                    // Method links are derived, not counted against the input
+                    for link in self.link_grpc_methods(resolved.from_id, resolved.to_id) {
+                        batch.push(link);
+                    }
+                }
                 batch.push(resolved);
             } else {
                 // Track why resolution failed
@@ -118,6 +145,31 @@ impl ResolveStage {
         // Must have from_id (assigned by COLLECT stage)
         let from_id = unresolved.from_id?;
 
+        // A guessed receiver type picks the method of that class
+        let inferred = unresolved
+            .metadata
+            .as_ref()
+            .and_then(|m| m.context.as_deref())
+            .and_then(type_hints::inferred_type);
+        let mut metadata = unresolved.metadata.clone();
+        if let Some((type_name, _)) = inferred {
+            if let Some(to_id) = self.resolve_on_type(&unresolved.to_name, type_name) {
+                return Some(ResolvedRelationship {
+                    from_id,
+                    to_id,
+                    kind: unresolved.kind,
+                    metadata,
+                });
+            }
+            // The guess did not help; keep the receiver but not the type
+            if let Some(m) = metadata.as_mut() {
+                m.context = m
+                    .context
+                    .as_deref()
+                    .map(|context| type_hints::without_inferred_type(context).into());
+            }
+        }
+
         // Build CallerContext from the calling symbol
         // This gives us file_id, module_path, and language_id for visibility checks
         let caller = self
@@ -145,7 +197,7 @@ impl ResolveStage {
                 from_id,
                 to_id,
                 kind: unresolved.kind,
-                metadata: unresolved.metadata.clone(),
+                metadata,
             }),
             ResolveResult::Ambiguous(candidates) => {
                 // Multiple candidates - use behavior for disambiguation
@@ -154,11 +206,197 @@ impl ResolveStage {
                     from_id,
                     to_id,
                     kind: unresolved.kind,
-                    metadata: unresolved.metadata.clone(),
+                    metadata,
                 })
             }
-            ResolveResult::NotFound => None,
+            ResolveResult::NotFound => self
+                .resolve_injected_reference(from_id, unresolved)
+                .or_else(|| self.resolve_grpc_service(from_id, unresolved)),
+        }
+    }
+
+    /// Find `method` defined by the class `type_name`.
+    ///
+    /// Python methods are indexed as `Class.method`; other languages index
+    /// the bare name, so candidates are matched by their class scope or by
+    /// lying inside a class of that name.
+    fn resolve_on_type(&self, method: &str, type_name: &str) -> Option<SymbolId> {
+        let qualified = self
+            .symbol_cache
+            .lookup_candidates(&format!("{type_name}.{method}"));
+        if let Some(&id) = qualified.first() {
+            return Some(id);
+        }
+
+        self.symbol_cache
+            .lookup_candidates(method)
+            .into_iter()
+            .find(|id| {
+                self.symbol_cache
+                    .get(*id)
+                    .is_some_and(|symbol| self.defined_in_class(&symbol, type_name))
+            })
+    }
+
+    /// Whether `symbol` is a member of a class named `type_name`
+    fn defined_in_class(&self, symbol: &Symbol, type_name: &str) -> bool {
+        if !matches!(symbol.kind, SymbolKind::Method | SymbolKind::Function) {
+            return false;
+        }
+        if member_of(symbol, type_name) {
+            return true;
+        }
+        self.symbol_cache
+            .symbols_in_file(symbol.file_id)
+            .into_iter()
+            .filter_map(|id| self.symbol_cache.get(id))
+            .any(|class| {
+                matches!(
+                    class.kind,
+                    SymbolKind::Class | SymbolKind::Struct | SymbolKind::Interface
+                ) && class.name.as_ref() == type_name
+                    && class.range.start_line <= symbol.range.start_line
+                    && symbol.range.end_line <= class.range.end_line
+            })
+    }
+
+    /// Resolve a reference made by code embedded in a string literal.
+    ///
+    /// A SQL query in a Rust function names tables defined in `.sql` files,
+    /// which same-language resolution never considers. Look for the target
+    /// among symbols of the injected language instead.
+    fn resolve_injected_reference(
+        &self,
+        from_id: SymbolId,
+        unresolved: &UnresolvedRelationship,
+    ) -> Option<ResolvedRelationship> {
+        let context = unresolved.metadata.as_ref()?.context.as_deref()?;
+        let language = injection::injected_language(context)?;
+
+        self.symbol_cache
+            .lookup_candidates(&unresolved.to_name)
+            .into_iter()
+            .find(|id| {
+                self.symbol_cache
+                    .get(*id)
+                    .is_some_and(|s| s.language_id.is_some_and(|l| l.as_str() == language))
+            })
+            .map(|to_id| ResolvedRelationship {
+                from_id,
+                to_id,
+                kind: unresolved.kind,
+                metadata: unresolved.metadata.clone(),
+            })
+    }
+
+    /// Link a handler to the `.proto` service behind a generated gRPC type.
+    ///
+    /// Generated stubs are usually not indexed, so `impl greeter_server::Greeter`
+    /// or `extends GreeterGrpc.GreeterImplBase` finds nothing by name. Strip the
+    /// generator's naming and look for a proto service instead. A use only
+    /// counts from a type, like a Go struct embedding
+    /// `pb.UnimplementedGreeterServer`; a function taking a `pb.GreeterServer`
+    /// parameter does not implement the service.
+    fn resolve_grpc_service(
+        &self,
+        from_id: SymbolId,
+        unresolved: &UnresolvedRelationship,
+    ) -> Option<ResolvedRelationship> {
+        if !matches!(
+            unresolved.kind,
+            RelationKind::Implements | RelationKind::Extends | RelationKind::Uses
+        ) {
+            return None;
+        }
+
+        let from = self.symbol_cache.get(from_id)?;
+        if from.language_id == Some(ProtobufLanguage::ID) {
+            return None;
+        }
+        if unresolved.kind == RelationKind::Uses
+            && !matches!(from.kind, SymbolKind::Struct | SymbolKind::Class)
+        {
+            return None;
+        }
+
+        grpc::service_candidates(&unresolved.to_name)
+            .into_iter()
+            .find_map(|name| {
+                self.symbol_cache
+                    .lookup_candidates(name)
+                    .into_iter()
+                    .find(|id| {
+                        self.symbol_cache
+                            .get(*id)
+                            .is_some_and(|s| grpc::is_proto_service(&s))
+                    })
+            })
+            .map(|to_id| ResolvedRelationship {
+                from_id,
+                to_id,
+                kind: RelationKind::Implements,
+                metadata: unresolved.metadata.clone(),
+            })
+    }
+
+    /// Link a handler's methods to the RPCs of the service it implements.
+    ///
+    /// Methods are matched by name modulo case and underscores, so
+    /// `say_hello` and `sayHello` both implement `rpc SayHello`. Only members
+    /// of the handler count: methods scoped to it, or Go methods whose
+    /// receiver is the handler.
+    fn link_grpc_methods(
+        &self,
+        handler_id: SymbolId,
+        service_id: SymbolId,
+    ) -> Vec<ResolvedRelationship> {
+        let (Some(handler), Some(service)) = (
+            self.symbol_cache.get(handler_id),
+            self.symbol_cache.get(service_id),
+        ) else {
+            return Vec::new();
+        };
+        if handler.language_id == Some(ProtobufLanguage::ID) {
+            return Vec::new();
+        }
+
+        let rpcs: Vec<Symbol> = self
+            .symbol_cache
+            .symbols_in_file(service.file_id)
+            .into_iter()
+            .filter_map(|id| self.symbol_cache.get(id))
+            .filter(|s| grpc::is_proto_rpc(s) && member_of(s, &service.name))
+            .collect();
+        if rpcs.is_empty() {
+            return Vec::new();
         }
+
+        self.symbol_cache
+            .symbols_in_file(handler.file_id)
+            .into_iter()
+            .filter_map(|id| self.symbol_cache.get(id))
+            .filter(|s| matches!(s.kind, SymbolKind::Method | SymbolKind::Function))
+            .filter(|s| match &s.scope_context {
+                Some(ScopeContext::ClassMember {
+                    class_name: Some(class),
+                }) => **class == *handler.name,
+                _ => {
+                    s.kind == SymbolKind::Method
+                        && s.signature.as_deref().and_then(grpc::go_receiver_type)
+                            == Some(&*handler.name)
+                }
+            })
+            .filter_map(|method| {
+                rpcs.iter()
+                    .find(|rpc| grpc::rpc_matches_method(&rpc.name, &method.name))
+                    .map(|rpc| ResolvedRelationship {
+                        from_id: method.id,
+                        to_id: rpc.id,
+                        kind: RelationKind::Implements,
+                        metadata: None,
+                    })
+            })
+            .collect()
     }
 
     /// Disambiguate among multiple candidates.
@@ -348,6 +586,14 @@ impl ResolveStage {
     }
 }
 
+/// Whether a symbol is declared directly inside `parent`
+fn member_of(symbol: &Symbol, parent: &str) -> bool {
+    matches!(
+        &symbol.scope_context,
+        Some(ScopeContext::ClassMember { class_name: Some(class) }) if **class == *parent
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -620,6 +866,112 @@ mod tests {
         assert_eq!(batch.relationships[1].to_id, SymbolId::new(3).unwrap());
     }
 
+    #[test]
+    fn test_resolve_links_grpc_handler_to_proto_service() {
+        let proto = ProtobufLanguage::ID;
+        let rust = LanguageId::new("rust");
+        let member = |class: &str| {
+            Some(ScopeContext::ClassMember {
+                class_name: Some(class.into()),
+            })
+        };
+
+        let cache = Arc::new(SymbolLookupCache::new());
+        let mut service = make_symbol(1, "Greeter", 1, proto);
+        service.kind = SymbolKind::Interface;
+        let mut rpc = make_symbol(2, "SayHello", 1, proto);
+        rpc.kind = SymbolKind::Method;
+        rpc.scope_context = member("Greeter");
+        let mut handler = make_symbol(3, "MyGreeter", 2, rust);
+        handler.kind = SymbolKind::Struct;
+        let mut method = make_symbol(4, "say_hello", 2, rust);
+        method.kind = SymbolKind::Method;
+        method.scope_context = member("MyGreeter");
+        for sym in [service, rpc, handler, method] {
+            cache.insert(sym);
+        }
+
+        let stage = make_stage(cache);
+        let context = make_context(
+            2,
+            rust,
+            vec![SymbolId::new(3).unwrap(), SymbolId::new(4).unwrap()],
+            vec![make_unresolved(
+                3,
+                "greeter_server::Greeter",
+                2,
+                RelationKind::Implements,
+            )],
+        );
+
+        let (batch, stats) = stage.resolve(&context);
+
+        assert_eq!(stats.resolved, 1);
+        let links: Vec<_> = batch
+            .relationships
+            .iter()
+            .map(|r| (r.from_id.value(), r.to_id.value(), r.kind))
+            .collect();
+        assert!(links.contains(&(3, 1, RelationKind::Implements)));
+        assert!(links.contains(&(4, 2, RelationKind::Implements)));
+    }
+
+    #[test]
+    fn test_resolve_grpc_uses_only_from_types() {
+        let proto = ProtobufLanguage::ID;
+        let go = LanguageId::new("go");
+
+        let cache = Arc::new(SymbolLookupCache::new());
+        let mut service = make_symbol(1, "Greeter", 1, proto);
+        service.kind = SymbolKind::Interface;
+        let mut rpc = make_symbol(2, "SayHello", 1, proto);
+        rpc.kind = SymbolKind::Method;
+        rpc.scope_context = Some(ScopeContext::ClassMember {
+            class_name: Some("Greeter".into()),
+        });
+        let mut handler = make_symbol(3, "server", 2, go);
+        handler.kind = SymbolKind::Struct;
+        let mut method = make_symbol(4, "SayHello", 2, go);
+        method.kind = SymbolKind::Method;
+        method.signature = Some("func (s *server) SayHello(ctx context.Context) error".into());
+        // Same name as the RPC, but not declared on the handler
+        let mut free = make_symbol(5, "sayHello", 2, go);
+        free.signature = Some("func sayHello(ctx context.Context) error".into());
+        // Takes the service as a parameter, which is no implementation
+        let mut register = make_symbol(6, "register", 2, go);
+        register.signature = Some("func register(s pb.GreeterServer)".into());
+        for sym in [service, rpc, handler, method, free, register] {
+            cache.insert(sym);
+        }
+
+        let stage = make_stage(cache);
+        let context = make_context(
+            2,
+            go,
+            (3..=6).map(|id| SymbolId::new(id).unwrap()).collect(),
+            vec![
+                make_unresolved(6, "pb.GreeterServer", 2, RelationKind::Uses),
+                make_unresolved(3, "pb.UnimplementedGreeterServer", 2, RelationKind::Uses),
+            ],
+        );
+
+        let (batch, stats) = stage.resolve(&context);
+
+        assert_eq!(stats.resolved, 1);
+        let links: Vec<_> = batch
+            .relationships
+            .iter()
+            .map(|r| (r.from_id.value(), r.to_id.value(), r.kind))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                (4, 2, RelationKind::Implements),
+                (3, 1, RelationKind::Implements)
+            ]
+        );
+    }
+
     #[test]
     fn test_resolve_stats_tracks_kinds() {
         let cache = Arc::new(SymbolLookupCache::new());
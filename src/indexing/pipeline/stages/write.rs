@@ -9,6 +9,7 @@
 //! - Outputs: WriteStats with counts
 
 use crate::indexing::pipeline::types::{ResolvedBatch, ResolvedRelationship};
+use crate::parsing::type_hints;
 use crate::relationship::Relationship;
 use crate::storage::DocumentIndex;
 use std::sync::Arc;
@@ -83,9 +84,16 @@ impl WriteStage {
 
         for resolved in batch.relationships {
             // Convert to Relationship struct (clone metadata to avoid partial move)
+            // Calls resolved through an inferred receiver type weigh their confidence
+            let weight = resolved
+                .metadata
+                .as_ref()
+                .and_then(|m| m.context.as_deref())
+                .and_then(type_hints::inferred_type)
+                .map_or(1.0, |(_, confidence)| confidence);
             let relationship = Relationship {
                 kind: resolved.kind,
-                weight: 1.0, // Default weight
+                weight,
                 metadata: resolved.metadata.clone(),
             };
 
@@ -4,10 +4,11 @@
 //! Key design principle: Parse stage produces "raw" types without IDs,
 //! Collect stage assigns IDs and produces final types.
 
-use crate::parsing::{Import, LanguageId, PipelineSymbolCache, ResolveResult};
+use crate::indexing::pipeline::memory::MemoryPermit;
+use crate::parsing::{Import, LanguageId, ParseDiagnostic, PipelineSymbolCache, ResolveResult};
 use crate::relationship::RelationshipMetadata;
 use crate::symbol::ScopeContext;
-use crate::types::{CompactString, FileId, Range, SymbolId};
+use crate::types::{CompactString, FileId, Range, SymbolId, intern};
 use crate::{RelationKind, Symbol, SymbolKind, Visibility};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -28,6 +29,11 @@ pub struct RawSymbol {
     pub doc_comment: Option<Box<str>>,
     pub visibility: Visibility,
     pub scope_context: Option<ScopeContext>,
+    /// Tag of the user-defined query that extracted this symbol
+    pub tag: Option<Box<str>>,
+    /// Texts to embed, set by the chunking strategy of the language;
+    /// None embeds the doc comment
+    pub chunks: Option<Vec<Box<str>>>,
 }
 
 impl RawSymbol {
@@ -40,6 +46,8 @@ impl RawSymbol {
             doc_comment: None,
             visibility: Visibility::Public,
             scope_context: None,
+            tag: None,
+            chunks: None,
         }
     }
 
@@ -62,6 +70,11 @@ impl RawSymbol {
         self.scope_context = Some(ctx);
         self
     }
+
+    pub fn with_tag(mut self, tag: impl Into<Box<str>>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
 }
 
 /// Import extracted from parsing, before FileId assignment.
@@ -128,17 +141,18 @@ pub struct RawRelationship {
 }
 
 impl RawRelationship {
+    /// Names are interned: callers and callees repeat across files
     pub fn new(
-        from_name: impl Into<Arc<str>>,
+        from_name: impl AsRef<str>,
         from_range: Range,
-        to_name: impl Into<Arc<str>>,
+        to_name: impl AsRef<str>,
         to_range: Range,
         kind: RelationKind,
     ) -> Self {
         Self {
-            from_name: from_name.into(),
+            from_name: intern(from_name.as_ref()),
             from_range,
-            to_name: to_name.into(),
+            to_name: intern(to_name.as_ref()),
             to_range,
             kind,
             metadata: None,
@@ -165,6 +179,12 @@ pub struct ParsedFile {
     pub raw_symbols: Vec<RawSymbol>,
     pub raw_imports: Vec<RawImport>,
     pub raw_relationships: Vec<RawRelationship>,
+    /// Regions tree-sitter could not parse
+    pub diagnostics: Vec<ParseDiagnostic>,
+    /// Generated or minified; its symbols are not embedded
+    pub generated: bool,
+    /// Earlier file with identical content; this one has no symbols
+    pub duplicate_of: Option<PathBuf>,
 }
 
 impl ParsedFile {
@@ -177,6 +197,9 @@ impl ParsedFile {
             raw_symbols: Vec::new(),
             raw_imports: Vec::new(),
             raw_relationships: Vec::new(),
+            diagnostics: Vec::new(),
+            generated: false,
+            duplicate_of: None,
         }
     }
 
@@ -220,6 +243,10 @@ pub struct FileRegistration {
     pub timestamp: u64,
     /// File modification time (seconds since UNIX_EPOCH)
     pub mtime: u64,
+    /// Regions tree-sitter could not parse, stored by the INDEX stage
+    pub diagnostics: Vec<ParseDiagnostic>,
+    /// Canonical file this one is an alias of, stored by the INDEX stage
+    pub duplicate_of: Option<PathBuf>,
 }
 
 /// Unresolved relationship with from_id populated.
@@ -344,6 +371,12 @@ pub struct FileContent {
     pub content: String,
     /// SHA256 hash of file content for change detection (compatible with Tantivy)
     pub hash: String,
+    /// Share of the memory budget held until the content is dropped
+    pub permit: Option<MemoryPermit>,
+    /// Generated or minified; its symbols are not embedded
+    pub generated: bool,
+    /// Earlier file with identical content; this one is not parsed
+    pub duplicate_of: Option<PathBuf>,
 }
 
 impl FileContent {
@@ -352,6 +385,9 @@ impl FileContent {
             path,
             content,
             hash,
+            permit: None,
+            generated: false,
+            duplicate_of: None,
         }
     }
 }
@@ -422,6 +458,26 @@ impl SymbolLookupCache {
         self.by_file_id.entry(file_id).or_default().push(id);
     }
 
+    /// Remove every symbol defined in a file.
+    ///
+    /// Used to keep a long-lived cache in step with a re-indexed file.
+    pub fn remove_file(&self, file_id: crate::types::FileId) {
+        let Some((_, ids)) = self.by_file_id.remove(&file_id) else {
+            return;
+        };
+        for id in ids {
+            let Some((_, symbol)) = self.by_id.remove(&id) else {
+                continue;
+            };
+            let name = symbol.name.as_ref();
+            if let Some(mut candidates) = self.by_name.get_mut(name) {
+                candidates.retain(|candidate| *candidate != id);
+            }
+            self.by_name
+                .remove_if(name, |_, candidates| candidates.is_empty());
+        }
+    }
+
     /// Get symbol by ID (O(1)).
     pub fn get(&self, id: crate::types::SymbolId) -> Option<crate::Symbol> {
         self.by_id.get(&id).map(|r| r.value().clone())
@@ -810,12 +866,24 @@ pub enum PipelineError {
     #[error("Unsupported file type: {path}")]
     UnsupportedFileType { path: PathBuf },
 
+    #[error("Skipped {kind} file: {path}")]
+    SkippedFile { path: PathBuf, kind: &'static str },
+
     #[error("Channel send error: {0}")]
     ChannelSend(String),
 
     #[error("Channel receive error: {0}")]
     ChannelRecv(String),
 
+    #[error("Git error in {path}: {reason}")]
+    Git { path: PathBuf, reason: String },
+
+    #[error("Failed to spill relationships to disk: {0}")]
+    Spill(std::io::Error),
+
+    #[error("Indexing cancelled")]
+    Cancelled,
+
     #[error("Index error: {0}")]
     Index(#[from] crate::IndexError),
 
@@ -0,0 +1,178 @@
+//! Indexing order for hot files
+//!
+//! A full re-index of a large repository takes minutes, but the files a
+//! developer works on are few. [`FilePriorities`] ranks every discovered file
+//! so those are parsed and committed first:
+//!
+//! 1. Files open in an editor, listed one path per line in
+//!    `<index_path>/open_files` by editor or LSP integrations
+//! 2. Files touched on the active git branch, including uncommitted changes
+//! 3. Files modified within the last day
+//! 4. Everything else
+//!
+//! Within a tier, more recently modified files go first.
+
+use crate::indexing::git_delta;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// File in the index directory listing the files open in editors
+pub const OPEN_FILES: &str = "open_files";
+
+/// How far back a modification makes a file recent
+const RECENT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Tier of a file, highest priority first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FilePriority {
+    Open,
+    Branch,
+    Recent,
+    Cold,
+}
+
+/// Hot file sets used to order indexing
+#[derive(Debug, Default)]
+pub struct FilePriorities {
+    open: HashSet<PathBuf>,
+    branch: HashSet<PathBuf>,
+    recent_since: Option<SystemTime>,
+}
+
+impl FilePriorities {
+    /// Rank with explicit open and branch files; files modified after
+    /// `recent_since` count as recent
+    pub fn new(
+        open: impl IntoIterator<Item = PathBuf>,
+        branch: impl IntoIterator<Item = PathBuf>,
+        recent_since: Option<SystemTime>,
+    ) -> Self {
+        Self {
+            open: open.into_iter().map(|path| canonical(&path)).collect(),
+            branch: branch.into_iter().map(|path| canonical(&path)).collect(),
+            recent_since,
+        }
+    }
+
+    /// Gather open files from `index_path` and branch files from the git
+    /// repository containing `root`
+    pub fn detect(root: &Path, index_path: &Path) -> Self {
+        let branch = git_delta::branch_base(root)
+            .and_then(|base| git_delta::changed_files(root, &base).ok())
+            .unwrap_or_default();
+        let recent_since = SystemTime::now().checked_sub(RECENT_WINDOW);
+        Self::new(read_open_files(index_path), branch, recent_since)
+    }
+
+    /// Tier of `path`
+    pub fn priority(&self, path: &Path) -> FilePriority {
+        self.rank(path).0
+    }
+
+    /// Sort `paths` so the hottest files come first
+    pub fn sort(&self, paths: &mut [PathBuf]) {
+        paths.sort_by_cached_key(|path| self.rank(path));
+    }
+
+    /// Files per tier, in tier order
+    pub fn counts(&self, paths: &[PathBuf]) -> [usize; 4] {
+        let mut counts = [0; 4];
+        for path in paths {
+            counts[self.priority(path) as usize] += 1;
+        }
+        counts
+    }
+
+    fn rank(&self, path: &Path) -> (FilePriority, Reverse<Option<SystemTime>>) {
+        let modified = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let tier = if self.open.is_empty() && self.branch.is_empty() {
+            None
+        } else {
+            let path = canonical(path);
+            if self.open.contains(&path) {
+                Some(FilePriority::Open)
+            } else if self.branch.contains(&path) {
+                Some(FilePriority::Branch)
+            } else {
+                None
+            }
+        };
+        let tier = tier.unwrap_or(match (modified, self.recent_since) {
+            (Some(modified), Some(since)) if modified >= since => FilePriority::Recent,
+            _ => FilePriority::Cold,
+        });
+        (tier, Reverse(modified))
+    }
+}
+
+/// Files listed in `<index_path>/open_files`, one per line
+///
+/// Blank lines and lines starting with `#` are ignored. A missing file means
+/// nothing is open.
+pub fn read_open_files(index_path: &Path) -> Vec<PathBuf> {
+    std::fs::read_to_string(index_path.join(OPEN_FILES))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_sort_puts_hot_files_first() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = |name: &str| {
+            let path = temp.path().join(name);
+            fs::write(&path, "fn f() {}").unwrap();
+            path
+        };
+        let cold = file("cold.rs");
+        let branch = file("branch.rs");
+        let open = file("open.rs");
+        let recent = file("recent.rs");
+
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        for path in [&cold, &branch, &open] {
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+
+        fs::write(
+            temp.path().join(OPEN_FILES),
+            format!("# editor buffers\n{}\n\n", open.display()),
+        )
+        .unwrap();
+        let priorities = FilePriorities::new(
+            read_open_files(temp.path()),
+            [branch.clone()],
+            Some(old + Duration::from_secs(60)),
+        );
+
+        let mut paths = vec![cold.clone(), recent.clone(), branch.clone(), open.clone()];
+        priorities.sort(&mut paths);
+        assert_eq!(paths, vec![open, branch, recent, cold.clone()]);
+        assert_eq!(priorities.counts(&paths), [1, 1, 1, 1]);
+        assert_eq!(priorities.priority(&cold), FilePriority::Cold);
+    }
+}
@@ -0,0 +1,113 @@
+//! Remote repositories indexed by URL
+//!
+//! `codanna index --remote <url>` indexes a dependency without vendoring it.
+//! The repository is shallow-cloned into `~/.codanna/remotes/<namespace>`,
+//! where the namespace is derived from the URL, and indexed from there. Each
+//! remote's files thus live under a directory of their own, apart from the
+//! workspace and from other remotes, and the namespace keys the provenance
+//! record kept in the index's `remotes.json`.
+//!
+//! Checkouts are not tracked as indexed directories, so syncing with
+//! `settings.toml` leaves them alone. Running the command again re-clones
+//! and re-indexes the remote.
+
+use crate::profiles::error::ProfileError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors fetching a remote repository
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    #[error("Cannot derive a namespace from remote URL '{0}'")]
+    InvalidUrl(String),
+
+    #[error(transparent)]
+    Clone(#[from] ProfileError),
+}
+
+/// A remote repository checked out into the cache
+#[derive(Debug, Clone)]
+pub struct RemoteCheckout {
+    /// Namespace derived from the URL
+    pub namespace: String,
+    /// Directory of the checkout
+    pub path: PathBuf,
+    /// Commit checked out
+    pub commit: String,
+}
+
+/// Cache directory holding every remote checkout
+pub fn remotes_dir() -> PathBuf {
+    crate::init::global_dir().join("remotes")
+}
+
+/// Namespace of the repository at `url`
+///
+/// Host and path joined by `-`, without scheme, user or `.git` suffix, so
+/// `https://github.com/serde-rs/serde.git` and
+/// `git@github.com:serde-rs/serde` both become `github.com-serde-rs-serde`.
+pub fn namespace(url: &str) -> Option<String> {
+    let url = url.trim();
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.trim_end_matches('/');
+    let url = url.split_once('@').map_or(url, |(_, rest)| rest);
+    let url = url.strip_suffix(".git").unwrap_or(url);
+
+    let name: String = url
+        .split(['/', ':'])
+        .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    (!name.is_empty()).then_some(name)
+}
+
+/// Shallow-clone `url` at `rev`, or its default branch, into the cache
+///
+/// An earlier checkout of the same namespace is replaced.
+pub fn checkout(url: &str, rev: Option<&str>) -> Result<RemoteCheckout, RemoteError> {
+    let namespace = namespace(url).ok_or_else(|| RemoteError::InvalidUrl(url.to_string()))?;
+    let path = remotes_dir().join(&namespace);
+    let commit = crate::profiles::git::clone_repository(url, &path, rev)?;
+
+    Ok(RemoteCheckout {
+        namespace,
+        path,
+        commit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespace_from_url() {
+        for url in [
+            "https://github.com/serde-rs/serde.git",
+            "https://github.com/serde-rs/serde/",
+            "git@github.com:serde-rs/serde.git",
+            "ssh://git@github.com/serde-rs/serde",
+        ] {
+            assert_eq!(
+                namespace(url).as_deref(),
+                Some("github.com-serde-rs-serde"),
+                "{url}"
+            );
+        }
+        assert_eq!(
+            namespace("https://host:8443/a b/../c").as_deref(),
+            Some("host-8443-a-b-c")
+        );
+        assert_eq!(namespace("https://"), None);
+    }
+}
@@ -0,0 +1,82 @@
+//! Index shards by top-level directory
+//!
+//! Every file, and every symbol defined in it, belongs to the shard named
+//! after the first directory of its path relative to the workspace root:
+//! `src/backend/api.rs` lives in shard `src`, `tests/cli.rs` in `tests`.
+//! Files directly in the root, and files stored with absolute paths because
+//! they lie outside the workspace, share the root shard `.`.
+//!
+//! The shard is stored as a term on symbol and file documents. A search
+//! restricted to a shard filters on it inside Tantivy rather than discarding
+//! hits afterwards, and `codanna index <dir> --force` for a directory inside
+//! a shard removes and re-indexes only that directory's files instead of
+//! recreating the whole index.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Shard of files in the workspace root or outside the workspace
+pub const ROOT_SHARD: &str = ".";
+
+/// Shard of a file, from its path as stored in the index
+pub fn shard_of(file_path: &str) -> &str {
+    let mut components = Path::new(file_path).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(first)), Some(_)) => first.to_str().unwrap_or(ROOT_SHARD),
+        _ => ROOT_SHARD,
+    }
+}
+
+/// `dir` relative to the workspace root, if it is a directory inside a shard
+///
+/// The root itself spans every shard and directories outside it belong to
+/// none, so both give `None`.
+pub fn shard_dir(dir: &Path, workspace_root: &Path) -> Option<PathBuf> {
+    if !dir.is_dir() {
+        return None;
+    }
+    let dir = dir.canonicalize().ok()?;
+    let root = workspace_root.canonicalize().ok()?;
+    let relative = dir.strip_prefix(&root).ok()?;
+    (relative.components().next().is_some()).then(|| relative.to_path_buf())
+}
+
+/// Whether a forced rebuild of `paths` can leave the rest of the index alone
+pub fn partial_rebuild(paths: &[PathBuf], workspace_root: Option<&Path>) -> bool {
+    let Some(root) = workspace_root else {
+        return false;
+    };
+    !paths.is_empty() && paths.iter().all(|path| shard_dir(path, root).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_shard_of_stored_paths() {
+        assert_eq!(shard_of("src/backend/api.rs"), "src");
+        assert_eq!(shard_of("tests/cli.rs"), "tests");
+        assert_eq!(shard_of("build.rs"), ROOT_SHARD);
+        assert_eq!(shard_of("/opt/vendor/lib.rs"), ROOT_SHARD);
+        assert_eq!(shard_of(""), ROOT_SHARD);
+    }
+
+    #[test]
+    fn test_partial_rebuild_needs_directories_inside_a_shard() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/backend")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+
+        assert_eq!(
+            shard_dir(&root.join("src/backend"), root),
+            Some(PathBuf::from("src/backend"))
+        );
+        assert!(partial_rebuild(&[root.join("src/backend")], Some(root)));
+        assert!(!partial_rebuild(&[root.to_path_buf()], Some(root)));
+        assert!(!partial_rebuild(&[root.join("src/lib.rs")], Some(root)));
+        assert!(!partial_rebuild(&[root.join("src")], None));
+        assert!(!partial_rebuild(&[], Some(root)));
+    }
+}
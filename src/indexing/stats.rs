@@ -0,0 +1,241 @@
+//! Index statistics and health report
+//!
+//! [`IndexReport`] breaks the index down by language, symbol kind and
+//! relationship kind, and checks its health: how many documented symbols
+//! have embeddings, how many indexed files changed or disappeared on disk
+//! since they were indexed, and how much space each part of the index
+//! directory takes.
+
+use crate::indexing::facade::{FacadeResult, IndexFacade};
+use crate::indexing::file_info::calculate_hash;
+use crate::parsing::get_registry;
+use crate::storage::AliasStore;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+/// Files and symbols of one language
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub symbols: usize,
+}
+
+/// Embeddings against the symbols that get one
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct VectorCoverage {
+    pub embeddings: usize,
+    /// Symbols with a doc comment, the ones the indexer embeds
+    pub documented_symbols: usize,
+    /// `embeddings / documented_symbols`, 1.0 when nothing needs embedding
+    pub coverage: f64,
+}
+
+/// Indexed files that no longer match the disk
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct StaleFiles {
+    /// Content changed since the file was indexed
+    pub modified: usize,
+    /// Deleted or moved since the file was indexed
+    pub missing: usize,
+}
+
+/// Bytes on disk per top-level entry of the index directory
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageSizes {
+    pub total_bytes: u64,
+    pub entries: BTreeMap<String, u64>,
+}
+
+/// Statistics and health of one index
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct IndexReport {
+    pub files: usize,
+    pub symbols: usize,
+    pub relationships: usize,
+    /// Files indexed as aliases of an identical file
+    pub aliases: usize,
+    pub languages: BTreeMap<String, LanguageStats>,
+    pub symbol_kinds: BTreeMap<String, usize>,
+    pub relationship_kinds: BTreeMap<String, usize>,
+    pub vectors: VectorCoverage,
+    pub stale: StaleFiles,
+    pub storage: StorageSizes,
+}
+
+impl IndexReport {
+    /// Build the report for `facade`, stored at `index_path`
+    ///
+    /// Relative file paths are resolved against `workspace_root`. Only files
+    /// modified after they were indexed are hashed for the stale check.
+    pub fn collect(
+        facade: &IndexFacade,
+        index_path: &Path,
+        workspace_root: &Path,
+    ) -> FacadeResult<Self> {
+        let index = facade.document_index();
+        let mut report = IndexReport::default();
+
+        let files = index.query_file_info()?;
+        report.files = files.len();
+        report.aliases = AliasStore::new(index.path())
+            .load()
+            .map(|aliases| aliases.len())
+            .unwrap_or(0);
+        for (_, path, hash, indexed_at) in &files {
+            report.languages.entry(language_of(path)).or_default().files += 1;
+
+            let path = Path::new(path);
+            let path = if path.is_relative() {
+                workspace_root.join(path)
+            } else {
+                path.to_path_buf()
+            };
+            match file_state(&path, hash, *indexed_at) {
+                FileState::Current => {}
+                FileState::Modified => report.stale.modified += 1,
+                FileState::Missing => report.stale.missing += 1,
+            }
+        }
+
+        let symbols = index.get_all_symbols(facade.symbol_count().max(1))?;
+        report.symbols = symbols.len();
+        let mut documented = 0;
+        for symbol in &symbols {
+            let language = symbol
+                .language_id
+                .map(|id| id.as_str().to_string())
+                .unwrap_or_else(|| language_of(&symbol.file_path));
+            report.languages.entry(language).or_default().symbols += 1;
+            *report
+                .symbol_kinds
+                .entry(format!("{:?}", symbol.kind))
+                .or_default() += 1;
+            if symbol.doc_comment.is_some() {
+                documented += 1;
+            }
+        }
+
+        let relationships = index.query_relationships()?;
+        report.relationships = relationships.len();
+        for (_, _, relationship) in &relationships {
+            *report
+                .relationship_kinds
+                .entry(format!("{:?}", relationship.kind))
+                .or_default() += 1;
+        }
+
+        let embeddings = facade.semantic_search_embedding_count();
+        report.vectors = VectorCoverage {
+            embeddings,
+            documented_symbols: documented,
+            coverage: if documented == 0 {
+                1.0
+            } else {
+                (embeddings as f64 / documented as f64).min(1.0)
+            },
+        };
+
+        report.storage = storage_sizes(index_path);
+        Ok(report)
+    }
+}
+
+enum FileState {
+    Current,
+    Modified,
+    Missing,
+}
+
+fn file_state(path: &Path, indexed_hash: &str, indexed_at: u64) -> FileState {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return FileState::Missing;
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|age| age.as_secs());
+    if modified.is_some_and(|modified| modified <= indexed_at) {
+        return FileState::Current;
+    }
+    match std::fs::read_to_string(path) {
+        Ok(content) if calculate_hash(&content) == indexed_hash => FileState::Current,
+        Ok(_) => FileState::Modified,
+        Err(_) => FileState::Missing,
+    }
+}
+
+/// Language registered for the file's extension, or "unknown"
+fn language_of(path: &str) -> String {
+    let extension = Path::new(path).extension().and_then(|ext| ext.to_str());
+    let language = extension.and_then(|extension| {
+        let registry = get_registry().lock().ok()?;
+        registry
+            .get_by_extension(extension)
+            .map(|language| language.id().as_str().to_string())
+    });
+    language.unwrap_or_else(|| "unknown".to_string())
+}
+
+fn storage_sizes(index_path: &Path) -> StorageSizes {
+    let mut sizes = StorageSizes::default();
+    let Ok(entries) = std::fs::read_dir(index_path) else {
+        return sizes;
+    };
+    for entry in entries.flatten() {
+        let bytes: u64 = WalkDir::new(entry.path())
+            .into_iter()
+            .flatten()
+            .filter_map(|file| file.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum();
+        sizes.total_bytes += bytes;
+        sizes
+            .entries
+            .insert(entry.file_name().to_string_lossy().into_owned(), bytes);
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_state_detects_modified_and_missing() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("lib.rs");
+        std::fs::write(&path, "fn main() {}").unwrap();
+        let hash = calculate_hash("fn main() {}");
+
+        // Touched after indexing but same content
+        assert!(matches!(file_state(&path, &hash, 0), FileState::Current));
+        assert!(matches!(
+            file_state(&path, &hash, u64::MAX),
+            FileState::Current
+        ));
+
+        std::fs::write(&path, "fn main() { run() }").unwrap();
+        assert!(matches!(file_state(&path, &hash, 0), FileState::Modified));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(file_state(&path, &hash, 0), FileState::Missing));
+    }
+
+    #[test]
+    fn test_storage_sizes_per_entry() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("tantivy")).unwrap();
+        std::fs::write(temp.path().join("tantivy/meta.json"), [0u8; 100]).unwrap();
+        std::fs::write(temp.path().join("index.meta"), [0u8; 20]).unwrap();
+
+        let sizes = storage_sizes(temp.path());
+        assert_eq!(sizes.total_bytes, 120);
+        assert_eq!(sizes.entries["tantivy"], 100);
+        assert_eq!(sizes.entries["index.meta"], 20);
+    }
+}
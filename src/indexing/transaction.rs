@@ -3,8 +3,258 @@
 //! This module provides transactional guarantees for index updates,
 //! ensuring that either all changes are committed or none are.
 //!
-//! With Tantivy-only architecture, transactions are handled by Tantivy's
-//! writer commit system, so this is now a lightweight compatibility layer.
+//! A Tantivy commit is atomic, but an indexing run commits many times and
+//! writes side stores (diagnostics, aliases, embeddings) between commits.
+//! [`BatchTransaction`] keeps a journal of the files an indexing run writes
+//! in `<tantivy>/transactions/`, one JSON line appended per batch so a long
+//! run never rewrites what it journaled before. Intermediate Tantivy commits leave the
+//! journal in place: the files' relationships are only resolved once every
+//! batch is written, so the run [`hold`](BatchTransaction::hold)s its journal
+//! past the last batch and [`settle`]s it after resolution commits. One left
+//! behind by a process that died mid-run, or by a run that failed, is found
+//! by [`abandoned`]; the pipeline rolls its files back out of the index
+//! before the next run, which then indexes them again.
+//!
+//! [`IndexTransaction`] and [`FileTransaction`] remain as lightweight
+//! compatibility types.
+
+use crate::storage::{StorageError, StorageResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Directory inside the Tantivy directory holding open batch journals
+pub const TRANSACTIONS_DIR: &str = "transactions";
+
+/// Distinguishes the journals of one process
+static NEXT_TRANSACTION: AtomicU64 = AtomicU64::new(0);
+
+/// Journals of this process whose transaction is still open
+static ACTIVE: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Journals of this process whose files are written but not yet resolved
+static HELD: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Files written by an open batch transaction
+///
+/// Each line of a journal file holds the files of one batch; reading the
+/// journal merges them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchJournal {
+    /// Process writing the batch
+    pub pid: u32,
+    /// Files written since the last commit
+    pub files: Vec<PathBuf>,
+}
+
+/// Journal of the files an index writer touched since its last commit
+#[derive(Debug)]
+pub struct BatchTransaction {
+    path: PathBuf,
+    journal: BatchJournal,
+}
+
+impl BatchTransaction {
+    /// Open a transaction for an index writer on `tantivy_path`
+    ///
+    /// Nothing is written until the first file is recorded.
+    pub fn begin(tantivy_path: &Path) -> Self {
+        let pid = std::process::id();
+        let id = NEXT_TRANSACTION.fetch_add(1, Ordering::Relaxed);
+        Self {
+            path: tantivy_path
+                .join(TRANSACTIONS_DIR)
+                .join(format!("{pid}-{id}.jsonl")),
+            journal: BatchJournal {
+                pid,
+                files: Vec::new(),
+            },
+        }
+    }
+
+    /// Journal `files` before their documents are written
+    pub fn record(&mut self, files: impl IntoIterator<Item = PathBuf>) -> StorageResult<()> {
+        let batch = BatchJournal {
+            pid: self.journal.pid,
+            files: files.into_iter().collect(),
+        };
+        if batch.files.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        active().insert(self.path.clone());
+        let mut line = serde_json::to_string(&batch)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+        self.journal.files.extend(batch.files);
+        Ok(())
+    }
+
+    /// Files journaled since the last commit
+    pub fn files(&self) -> &[PathBuf] {
+        &self.journal.files
+    }
+
+    /// Forget the journaled files once Tantivy has committed them
+    pub fn commit(&mut self) -> StorageResult<()> {
+        if self.journal.files.is_empty() {
+            return Ok(());
+        }
+        self.journal.files.clear();
+        discard(&self.path)?;
+        active().remove(&self.path);
+        Ok(())
+    }
+
+    /// Keep the journal until [`settle`] once every batch has committed
+    ///
+    /// The files are in the index but their relationships are not resolved
+    /// yet. [`release`] gives the journal up to be rolled back instead.
+    pub fn hold(self) {
+        if !self.journal.files.is_empty() {
+            held().insert(self.path.clone());
+        }
+    }
+}
+
+impl Drop for BatchTransaction {
+    /// A transaction dropped before its commit, e.g. after a failed write,
+    /// leaves its journal to be rolled back
+    fn drop(&mut self) {
+        active().remove(&self.path);
+    }
+}
+
+/// Delete the journals held for the index at `tantivy_path` once the
+/// relationships of their files are committed
+pub fn settle(tantivy_path: &Path) -> StorageResult<()> {
+    for path in take_held(tantivy_path) {
+        discard(&path)?;
+    }
+    Ok(())
+}
+
+/// Give up the journals held for the index at `tantivy_path`, e.g. after a
+/// failed run, leaving them to be rolled back
+pub fn release(tantivy_path: &Path) {
+    take_held(tantivy_path);
+}
+
+fn take_held(tantivy_path: &Path) -> Vec<PathBuf> {
+    let dir = tantivy_path.join(TRANSACTIONS_DIR);
+    let mut held = held();
+    let paths: Vec<PathBuf> = held
+        .iter()
+        .filter(|path| path.starts_with(&dir))
+        .cloned()
+        .collect();
+    for path in &paths {
+        held.remove(path);
+    }
+    paths
+}
+
+/// Journals left behind by batches that never committed, with their paths
+///
+/// Journals of open transactions, in this process or another one still
+/// running, are skipped: their batches are in flight, not abandoned.
+pub fn abandoned(tantivy_path: &Path) -> StorageResult<Vec<(PathBuf, BatchJournal)>> {
+    let entries = match std::fs::read_dir(tantivy_path.join(TRANSACTIONS_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut journals = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        // `.json` journals of earlier versions are a single line
+        if path
+            .extension()
+            .is_none_or(|ext| ext != "jsonl" && ext != "json")
+        {
+            continue;
+        }
+        let journal = read_journal(&path)?;
+        let in_flight = if journal.pid == std::process::id() {
+            active().contains(&path) || held().contains(&path)
+        } else {
+            process_running(journal.pid)
+        };
+        if !in_flight {
+            journals.push((path, journal));
+        }
+    }
+    journals.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(journals)
+}
+
+/// Merge the batches of the journal at `path`
+///
+/// A process that died while appending leaves a torn last line; that batch
+/// wrote no documents yet, so the line is skipped.
+fn read_journal(path: &Path) -> StorageResult<BatchJournal> {
+    let text = std::fs::read_to_string(path)?;
+    let mut journal = BatchJournal::default();
+    let mut lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    while let Some(line) = lines.next() {
+        let batch: BatchJournal = match serde_json::from_str(line) {
+            Ok(batch) => batch,
+            Err(_) if lines.peek().is_none() && !text.ends_with('\n') => break,
+            Err(e) => {
+                return Err(StorageError::Serialization(format!(
+                    "{}: {e}",
+                    path.display()
+                )));
+            }
+        };
+        journal.pid = batch.pid;
+        journal.files.extend(batch.files);
+    }
+    Ok(journal)
+}
+
+/// Delete a journal once its files are rolled back
+pub fn discard(journal_path: &Path) -> StorageResult<()> {
+    match std::fs::remove_file(journal_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn active() -> std::sync::MutexGuard<'static, BTreeSet<PathBuf>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn held() -> std::sync::MutexGuard<'static, BTreeSet<PathBuf>> {
+    HELD.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn process_running(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    sys.process(pid).is_some()
+}
 
 /// A transaction that can be committed or rolled back
 ///
@@ -85,3 +335,96 @@ impl FileTransaction {
         self.completed = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_uncommitted_batches_are_abandoned() {
+        let temp = TempDir::new().unwrap();
+
+        let mut committed = BatchTransaction::begin(temp.path());
+        committed.record([PathBuf::from("src/a.rs")]).unwrap();
+        committed.commit().unwrap();
+
+        let mut failed = BatchTransaction::begin(temp.path());
+        failed.record([PathBuf::from("src/b.rs")]).unwrap();
+        failed.record([PathBuf::from("src/c.rs")]).unwrap();
+
+        // Open transactions are in flight
+        assert!(abandoned(temp.path()).unwrap().is_empty());
+
+        drop(failed);
+        let journals = abandoned(temp.path()).unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(
+            journals[0].1.files,
+            vec![PathBuf::from("src/b.rs"), PathBuf::from("src/c.rs")]
+        );
+
+        discard(&journals[0].0).unwrap();
+        assert!(abandoned(temp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_appends_one_line_per_batch() {
+        let temp = TempDir::new().unwrap();
+
+        let mut transaction = BatchTransaction::begin(temp.path());
+        transaction.record([PathBuf::from("src/a.rs")]).unwrap();
+        transaction.record(Vec::new()).unwrap();
+        transaction
+            .record([PathBuf::from("src/b.rs"), PathBuf::from("src/c.rs")])
+            .unwrap();
+        let path = transaction.path.clone();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        assert_eq!(transaction.files().len(), 3);
+
+        // A process dying mid-append leaves a torn last line
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(br#"{"pid":1,"files":["src/d"#).unwrap();
+        drop(transaction);
+
+        let journals = abandoned(temp.path()).unwrap();
+        assert_eq!(
+            journals[0].1.files,
+            vec![
+                PathBuf::from("src/a.rs"),
+                PathBuf::from("src/b.rs"),
+                PathBuf::from("src/c.rs")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_held_journals_last_until_settled_or_released() {
+        let temp = TempDir::new().unwrap();
+
+        let mut resolved = BatchTransaction::begin(temp.path());
+        resolved.record([PathBuf::from("src/a.rs")]).unwrap();
+        resolved.hold();
+        assert!(abandoned(temp.path()).unwrap().is_empty());
+        settle(temp.path()).unwrap();
+        assert!(abandoned(temp.path()).unwrap().is_empty());
+        assert_eq!(
+            std::fs::read_dir(temp.path().join(TRANSACTIONS_DIR))
+                .unwrap()
+                .count(),
+            0
+        );
+
+        let mut failed = BatchTransaction::begin(temp.path());
+        failed.record([PathBuf::from("src/b.rs")]).unwrap();
+        failed.hold();
+        release(temp.path());
+        settle(temp.path()).unwrap();
+        let journals = abandoned(temp.path()).unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].1.files, vec![PathBuf::from("src/b.rs")]);
+    }
+}
@@ -5,12 +5,112 @@
 //! - Custom ignore patterns from configuration
 //! - Language filtering
 //! - Hidden file handling
+//! - Workspace boundary checks for symlinked files
+//!
+//! Directories are read on several threads. Each thread works through its
+//! own queue of directories and steals from the others once it runs dry
+//! (the `ignore` crate's parallel walker), so one deep subtree does not
+//! leave the remaining threads idle. Discovery on network filesystems is
+//! dominated by per-directory latency, which this hides.
 
 use crate::Settings;
+use crate::indexing::ignore_rules::CODANNA_IGNORE_FILE;
+use crate::parsing::config::is_config_file;
 use crate::parsing::get_registry;
-use ignore::WalkBuilder;
+use crate::security::WorkspaceBoundary;
+use ignore::{WalkBuilder, WalkState};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+/// Build a walker over `root` with codanna's ignore rules
+///
+/// Respects `.gitignore` (also outside git repositories, and nested ones
+/// in subdirectories), `.ignore`, the global gitignore, `.git/info/exclude`
+/// and `.codannaignore`. Symlinks are not followed.
+///
+/// [`IgnoreRules`](crate::indexing::IgnoreRules) applies the same files to
+/// single paths.
+pub fn walk_builder(root: &Path) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(false) // Don't auto-skip hidden directories
+        .git_ignore(true) // Respect .gitignore files
+        .ignore(true) // Respect .ignore files
+        .git_global(true) // Respect global gitignore
+        .git_exclude(true) // Respect .git/info/exclude
+        .follow_links(false) // Don't follow symlinks
+        .max_depth(None) // No depth limit
+        .require_git(false); // Allow gitignore to work in non-git directories
+
+    // Always support .codannaignore files for custom ignore patterns (follows .gitignore pattern)
+    builder.add_custom_ignore_filename(CODANNA_IGNORE_FILE);
+    builder
+}
+
+/// Walk `root` on `threads` threads and call `visit` for every file
+///
+/// Hidden files other than CI configs are skipped, and so are symlinked
+/// files that resolve outside `root`. `visit` is called concurrently from
+/// the walker threads; returning false stops the walk.
+pub fn walk_parallel<F>(root: &Path, threads: usize, visit: F)
+where
+    F: Fn(&Path) -> bool + Sync,
+{
+    let boundary = WorkspaceBoundary::new(root)
+        .ok()
+        .map(|boundary| boundary.with_internal_symlinks(true));
+    let boundary = boundary.as_ref();
+    let visit = &visit;
+
+    walk_builder(root)
+        .threads(threads.max(1))
+        .build_parallel()
+        .run(|| {
+            Box::new(move |entry| {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(_) => return WalkState::Continue, // Skip files we can't access
+                };
+                if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+                    return WalkState::Continue;
+                }
+
+                let path = entry.path();
+
+                if is_hidden_file(path) {
+                    return WalkState::Continue;
+                }
+
+                // A symlinked file may point anywhere; only index it if it stays in the workspace
+                if entry.path_is_symlink()
+                    && boundary.is_none_or(|boundary| boundary.validate(path).is_err())
+                {
+                    tracing::debug!(
+                        "[walker] skipping symlink outside {}: {}",
+                        root.display(),
+                        path.display()
+                    );
+                    return WalkState::Continue;
+                }
+
+                if visit(path) {
+                    WalkState::Continue
+                } else {
+                    WalkState::Quit
+                }
+            })
+        });
+}
+
+/// Whether `path` is a hidden file (name starting with `.`) the walker skips
+///
+/// CI files such as `.gitlab-ci.yml` are not treated as hidden.
+pub(crate) fn is_hidden_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+        && !is_config_file(path)
+}
 
 /// Walks directories to find source files to index
 #[derive(Debug)]
@@ -24,62 +124,31 @@ impl FileWalker {
         Self { settings }
     }
 
-    /// Walk a directory and return an iterator of files to index
+    /// Walk a directory and return the files to index, sorted by path
+    ///
+    /// The walk runs on `indexing.parallelism` threads.
     pub fn walk(&self, root: &Path) -> impl Iterator<Item = PathBuf> {
-        let mut builder = WalkBuilder::new(root);
-
-        // Configure the walker
-        builder
-            .hidden(false) // Don't traverse hidden directories by default
-            .git_ignore(true) // Respect .gitignore files
-            .git_global(true) // Respect global gitignore
-            .git_exclude(true) // Respect .git/info/exclude
-            .follow_links(false) // Don't follow symlinks by default
-            .max_depth(None) // No depth limit
-            .require_git(false); // Allow gitignore to work in non-git directories
-
-        // Always support .codannaignore files for custom ignore patterns (follows .gitignore pattern)
-        builder.add_custom_ignore_filename(".codannaignore");
-
-        // The ignore crate's override feature is for INCLUDING files, not excluding them.
-        // To add custom ignore patterns, we need to use a different approach.
-        // For now, we'll rely on .gitignore and .codannaignore files.
-
-        // TODO: Add support for custom ignore patterns from settings
-        // One approach would be to create a temporary .codanna-ignore file
-        // or use the glob filtering in the iterator below
-
         // Get enabled extensions from the registry
         let enabled_extensions = self.get_enabled_extensions();
-
-        // Build and filter the walker
-        builder
-            .build()
-            .filter_map(Result::ok) // Skip files we can't access
-            .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
-            .filter_map(move |entry| {
-                let path = entry.path();
-
-                // Skip hidden files (files starting with .)
-                if let Some(file_name) = path.file_name() {
-                    if let Some(name_str) = file_name.to_str() {
-                        if name_str.starts_with('.') {
-                            return None;
-                        }
-                    }
-                }
-
-                // Check if this file extension is enabled
-                if let Some(extension) = path.extension() {
-                    if let Some(ext_str) = extension.to_str() {
-                        if enabled_extensions.iter().any(|ext| ext == ext_str) {
-                            return Some(path.to_path_buf());
-                        }
-                    }
+        let files = Mutex::new(Vec::new());
+
+        walk_parallel(root, self.settings.indexing.parallelism, |path| {
+            // Check if this file extension is enabled
+            let enabled = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| enabled_extensions.iter().any(|enabled| enabled == ext));
+            if enabled {
+                if let Ok(mut files) = files.lock() {
+                    files.push(path.to_path_buf());
                 }
+            }
+            true
+        });
 
-                None
-            })
+        let mut files = files.into_inner().unwrap_or_default();
+        files.sort();
+        files.into_iter()
     }
 
     /// Get list of enabled file extensions from the registry
@@ -179,4 +248,48 @@ mod tests {
         assert_eq!(files.len(), 1);
         assert!(files[0].ends_with("included.rs"));
     }
+
+    #[test]
+    fn test_layered_ignore_files_respected() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("app/generated")).unwrap();
+        fs::create_dir_all(root.join("vendor")).unwrap();
+
+        fs::write(root.join(".gitignore"), "vendor/\n").unwrap();
+        fs::write(root.join("app/.ignore"), "generated/\n").unwrap();
+        fs::write(root.join(".codannaignore"), "scratch.rs\n").unwrap();
+
+        fs::write(root.join("app/main.rs"), "fn main() {}").unwrap();
+        fs::write(root.join("app/generated/api.rs"), "fn api() {}").unwrap();
+        fs::write(root.join("vendor/dep.rs"), "fn dep() {}").unwrap();
+        fs::write(root.join("scratch.rs"), "fn scratch() {}").unwrap();
+
+        let walker = FileWalker::new(create_test_settings());
+        let files: Vec<_> = walker.walk(root).collect();
+
+        assert_eq!(files.len(), 1, "{files:?}");
+        assert!(files[0].ends_with("app/main.rs"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_outside_root_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+
+        fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+        fs::write(outside.join("secret.rs"), "fn secret() {}").unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.rs"), root.join("secret.rs")).unwrap();
+        std::os::unix::fs::symlink(root.join("main.rs"), root.join("alias.rs")).unwrap();
+
+        let walker = FileWalker::new(create_test_settings());
+        let files: Vec<_> = walker.walk(&root).collect();
+
+        // Sorted, with the internal symlink kept and the escaping one dropped
+        assert_eq!(files, vec![root.join("alias.rs"), root.join("main.rs")]);
+    }
 }
@@ -0,0 +1,151 @@
+//! Files the user is working on, read from git
+//!
+//! Files committed to in the last `ranking.recent_days` and files the
+//! current branch changed tell what the user is busy with, so the MCP server
+//! ranks search results in them higher. Reading the history takes a moment,
+//! so each workspace keeps its set for a minute before reading it again.
+
+use crate::config::RankingConfig;
+use crate::indexing::git_delta;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long a workspace's set is used before git is read again
+const REFRESH: Duration = Duration::from_secs(60);
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Each workspace's set with the time it was read
+type Cache = HashMap<PathBuf, (Instant, Arc<WorkingSet>)>;
+
+static WORKING_SETS: OnceLock<Mutex<Cache>> = OnceLock::new();
+
+/// Recently committed files and files changed on the current branch
+#[derive(Debug, Default)]
+pub struct WorkingSet {
+    /// Canonical workspace root, which relative stored paths start from
+    root: PathBuf,
+    /// Recently committed files, from 1.0 for today to 0.0 after
+    /// `recent_days`
+    recent: HashMap<PathBuf, f32>,
+    /// Files changed since the branch forked, committed or not
+    branch: HashSet<PathBuf>,
+}
+
+impl WorkingSet {
+    /// Read the working set of the repository containing `root`
+    ///
+    /// Outside a git repository the set is empty.
+    pub fn collect(root: &Path, config: &RankingConfig) -> Self {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let now = crate::indexing::get_utc_timestamp() as i64;
+
+        let mut recent = HashMap::new();
+        if config.recent_boost > 0.0 && config.recent_days > 0 {
+            let window = i64::from(config.recent_days) * SECONDS_PER_DAY;
+            match git_delta::recently_changed(&root, now - window) {
+                Ok(files) => {
+                    for (path, time) in files {
+                        let age = (now - time).max(0) as f32 / window as f32;
+                        recent.insert(path, (1.0 - age).max(0.0));
+                    }
+                }
+                Err(e) => tracing::debug!("No recent commits for ranking: {e}"),
+            }
+        }
+
+        let mut branch = HashSet::new();
+        if config.branch_boost > 0.0 {
+            if let Some(base) = git_delta::branch_base(&root) {
+                match git_delta::changed_files(&root, &base) {
+                    Ok(files) => branch.extend(files),
+                    Err(e) => tracing::debug!("No branch changes for ranking: {e}"),
+                }
+            }
+        }
+
+        Self {
+            root,
+            recent,
+            branch,
+        }
+    }
+
+    /// The working set of `root`, read again once it is older than a minute
+    pub fn shared(root: &Path, config: &RankingConfig) -> Arc<Self> {
+        let sets = WORKING_SETS.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut sets = sets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((read_at, set)) = sets.get(root) {
+            if read_at.elapsed() < REFRESH {
+                return set.clone();
+            }
+        }
+        let set = Arc::new(Self::collect(root, config));
+        sets.insert(root.to_path_buf(), (Instant::now(), set.clone()));
+        set
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recent.is_empty() && self.branch.is_empty()
+    }
+
+    /// Share added to the score of a result in `file_path`, as the index
+    /// stores it
+    pub fn boost(&self, file_path: &str, config: &RankingConfig) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let path = self.root.join(file_path);
+        let mut boost = 0.0;
+        if let Some(freshness) = self.recent.get(&path) {
+            boost += config.recent_boost * freshness;
+        }
+        if self.branch.contains(&path) {
+            boost += config.branch_boost;
+        }
+        boost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_and_branch_files_are_boosted() {
+        let root = PathBuf::from("/work/project");
+        let set = WorkingSet {
+            recent: HashMap::from([
+                (root.join("src/today.rs"), 1.0),
+                (root.join("src/last_week.rs"), 0.5),
+            ]),
+            branch: HashSet::from([root.join("src/today.rs"), root.join("src/wip.rs")]),
+            root,
+        };
+        let config = RankingConfig::default();
+
+        assert_eq!(
+            set.boost("src/today.rs", &config),
+            config.recent_boost + config.branch_boost
+        );
+        assert_eq!(
+            set.boost("src/last_week.rs", &config),
+            config.recent_boost * 0.5
+        );
+        // Absolute stored paths are looked up as they are
+        assert_eq!(
+            set.boost("/work/project/src/wip.rs", &config),
+            config.branch_boost
+        );
+        assert_eq!(set.boost("src/other.rs", &config), 0.0);
+
+        let off = RankingConfig {
+            recent_boost: 0.0,
+            branch_boost: 0.0,
+            ..RankingConfig::default()
+        };
+        assert_eq!(set.boost("src/today.rs", &off), 0.0);
+    }
+}
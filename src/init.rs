@@ -48,6 +48,12 @@ pub fn models_dir() -> PathBuf {
     global_dir().join("models")
 }
 
+/// Get the embedding cache directory shared by all projects
+/// Returns ~/.codanna-dev/cache/embeddings/
+pub fn embedding_cache_dir() -> PathBuf {
+    global_dir().join("cache").join("embeddings")
+}
+
 /// Get the projects registry file
 /// Returns ~/.codanna-dev/projects.json
 pub fn projects_file() -> PathBuf {
@@ -394,6 +400,11 @@ impl ProjectRegistry {
         })
     }
 
+    /// All registered projects with their IDs
+    pub fn projects(&self) -> impl Iterator<Item = (&str, &ProjectInfo)> {
+        self.projects.iter().map(|(id, info)| (id.as_str(), info))
+    }
+
     /// Find a project by its UUID
     pub fn find_project_by_id(&self, project_id: &str) -> Option<&ProjectInfo> {
         self.projects.get(project_id)
@@ -212,6 +212,13 @@ pub fn walk_and_stream(
     Ok(())
 }
 
+/// Grammar of the runtime-loaded language handling `extension`
+fn external_grammar(extension: &str) -> Option<tree_sitter::Language> {
+    let registry = crate::parsing::get_registry().lock().ok()?;
+    let definition = registry.get_by_extension(extension)?;
+    Some(definition.create_behavior().get_language())
+}
+
 /// Execute the parse command with proper error handling
 pub fn execute_parse(
     file_path: &Path,
@@ -263,6 +270,26 @@ pub fn execute_parse(
         Language::Java => tree_sitter_java::LANGUAGE.into(),
         Language::Kotlin => tree_sitter_kotlin::language(),
         Language::Swift => tree_sitter_swift::LANGUAGE.into(),
+        Language::Lua => tree_sitter_lua::LANGUAGE.into(),
+        Language::Sql => tree_sitter_sequel::LANGUAGE.into(),
+        Language::Protobuf => tree_sitter_proto::LANGUAGE.into(),
+        Language::Hcl => tree_sitter_hcl::LANGUAGE.into(),
+        Language::ObjectiveC => tree_sitter_objc::LANGUAGE.into(),
+        // Manifests are read without tree-sitter, so there is no tree to print
+        Language::Config => {
+            return Err(ParseError::UnsupportedLanguage {
+                extension: extension.to_string(),
+            });
+        }
+        // Single-file components have no grammar of their own; this is the script grammar
+        Language::Vue => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Language::External => {
+            // A grammar that still cannot be used fails in set_language below
+            let _ = crate::parsing::external::prepare_parser(&mut parser);
+            external_grammar(extension).ok_or_else(|| ParseError::UnsupportedLanguage {
+                extension: extension.to_string(),
+            })?
+        }
     };
 
     parser
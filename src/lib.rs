@@ -4,8 +4,10 @@
 // extern crate tree_sitter_kotlin;
 extern crate tree_sitter_kotlin_codanna as tree_sitter_kotlin;
 
+pub mod api;
 pub mod cli;
 pub mod config;
+pub mod conversations;
 pub mod display;
 pub mod documents;
 pub mod error;
@@ -14,14 +16,18 @@ pub mod init;
 pub mod io;
 pub mod logging;
 pub mod mcp;
+pub mod nl_query;
 pub mod parsing;
 pub mod plugins;
 pub mod profiles;
 pub mod project_resolver;
+pub mod recording;
 pub mod relationship;
 pub mod retrieve;
+pub mod self_update;
 pub mod semantic;
 pub mod storage;
+pub mod summary;
 pub mod symbol;
 pub mod types;
 pub mod utils;
@@ -173,6 +173,40 @@ fn seed_indexer_with_config_paths(
     report
 }
 
+/// Make sure the on-disk index was written with a schema this binary reads.
+///
+/// Applies in-place migrations when available; otherwise explains how to get a
+/// matching binary or index and exits instead of failing inside Tantivy.
+fn check_index_compatibility(index_path: &std::path::Path) {
+    use codanna::storage::{Compatibility, INDEX_SCHEMA_VERSION, compat};
+
+    match Compatibility::check(index_path) {
+        Ok(Compatibility::Current) => {}
+        Ok(Compatibility::Migratable { found }) => match compat::migrate(index_path) {
+            Ok(_) => eprintln!("Migrated index schema v{found} to v{INDEX_SCHEMA_VERSION}"),
+            Err(e) => {
+                eprintln!(
+                    "Error: Failed to migrate index at {}: {e}",
+                    index_path.display()
+                );
+                eprintln!("Rebuild it with: codanna index --force");
+                std::process::exit(1);
+            }
+        },
+        Ok(other) => {
+            eprintln!("Error: Incompatible index at {}", index_path.display());
+            if let Some(guidance) = other.guidance() {
+                eprintln!("{guidance}");
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            // Unreadable metadata is not fatal; loading reports real problems
+            tracing::warn!(target: "cli", "could not read index metadata: {e}");
+        }
+    }
+}
+
 /// Entry point with tokio async runtime.
 ///
 /// Handles config initialization, index loading/creation, and command dispatch.
@@ -182,7 +216,7 @@ async fn main() {
     let cli = Cli::parse();
 
     // For index command, auto-initialize if needed (but not when using --config)
-    if matches!(cli.command, Commands::Index { .. }) && cli.config.is_none() {
+    if matches!(cli.command, Commands::Index { action: None, .. }) && cli.config.is_none() {
         if Settings::check_init().is_err() {
             // Auto-initialize for index command
             eprintln!("Initializing project configuration...");
@@ -196,7 +230,11 @@ async fn main() {
                 }
             }
         }
-    } else if !matches!(cli.command, Commands::Init { .. }) && cli.config.is_none() {
+    } else if !matches!(
+        cli.command,
+        Commands::Init { .. } | Commands::SelfUpdate { .. }
+    ) && cli.config.is_none()
+    {
         // For other commands without --config flag, just warn
         if let Err(warning) = Settings::check_init() {
             eprintln!("Warning: {warning}");
@@ -221,6 +259,21 @@ async fn main() {
         })
     };
 
+    // Report deprecated and unknown config keys (migrate reports its own)
+    if !config.config_warnings.is_empty()
+        && !matches!(cli.command, Commands::Config { migrate: true })
+    {
+        for warning in &config.config_warnings {
+            eprintln!("Warning: {warning}");
+        }
+        eprintln!("Run 'codanna config --migrate' to update the configuration file.");
+    }
+
+    // Make grammars declared under [grammars] available before any file is parsed
+    for error in codanna::parsing::external::register_grammars(&config) {
+        eprintln!("Warning: {error}");
+    }
+
     // Initialize logging with config (supports RUST_LOG env var override)
     // Use stderr for: MCP stdio mode (JSON-RPC protocol) and mcp --json (clean JSON output)
     let use_stderr_logging = matches!(
@@ -239,18 +292,42 @@ async fn main() {
 
     // Determine resource requirements based on command type
     // Commands are categorized by what infrastructure they need:
-    // - Thin: No index, no providers (Parse, McpTest, Benchmark)
-    // - Config-only: Settings but no index (Init, Config, AddDir, RemoveDir, ListDirs, Plugin, Profile, Documents)
-    // - Full: Index + providers (Retrieve, Mcp, Serve, Index)
+    // - Thin: No index, no providers (Parse, McpTest, Benchmark, SelfUpdate)
+    // - Config-only: Settings but no index (Init, Config, AddDir, RemoveDir, ListDirs, Plugin, Profile, Documents, Events, Vectors, Conversations)
+    // - Index-only: Index but no providers (Summary, Api, Diagnostics, Analyze, Export, Semantic)
+    // - Full: Index + providers (Retrieve, Mcp, Serve, Index, Daemon)
     let needs_providers = !matches!(
         &cli.command,
-        Commands::Parse { .. } | Commands::McpTest { .. } | Commands::Benchmark { .. }
+        Commands::Parse { .. }
+            | Commands::McpTest { .. }
+            | Commands::Benchmark { .. }
+            | Commands::SelfUpdate { .. }
+            | Commands::Events { .. }
+            | Commands::Summary { .. }
+            | Commands::Vectors { .. }
+            | Commands::Api { .. }
+            | Commands::Conversations { .. }
+            | Commands::Diagnostics { .. }
+            | Commands::Maintenance { .. }
+            | Commands::Analyze { .. }
+            | Commands::Export { .. }
+            | Commands::Semantic { .. }
+            | Commands::Record { .. }
+            | Commands::Replay { .. }
+            | Commands::Index {
+                action: Some(_),
+                ..
+            }
+            | Commands::Daemon {
+                action: Some(_),
+                ..
+            }
     );
 
     let needs_indexer = !matches!(
         &cli.command,
         Commands::Init { .. }
-            | Commands::Config
+            | Commands::Config { .. }
             | Commands::Parse { .. }
             | Commands::McpTest { .. }
             | Commands::Benchmark { .. }
@@ -261,6 +338,16 @@ async fn main() {
             | Commands::Documents { .. }
             | Commands::Profile { .. }
             | Commands::IndexParallel { .. }
+            | Commands::SelfUpdate { .. }
+            | Commands::Events { .. }
+            | Commands::Vectors { .. }
+            | Commands::Conversations { .. }
+            | Commands::Record { .. }
+            | Commands::Replay { .. }
+            | Commands::Daemon {
+                action: Some(_),
+                ..
+            }
     );
 
     // Initialize project resolution providers (only if needed)
@@ -293,6 +380,20 @@ async fn main() {
     {
         config.indexing.parallelism = *t;
     }
+    if let Commands::Index {
+        deterministic: true,
+        ..
+    } = &cli.command
+    {
+        config.indexing.deterministic = true;
+    }
+    if let Commands::Index {
+        metrics_json: Some(path),
+        ..
+    } = &cli.command
+    {
+        config.indexing.metrics_json = Some(path.clone());
+    }
 
     // Set up persistence based on config
     // Use global path resolution that handles --config properly
@@ -301,6 +402,26 @@ async fn main() {
     // Update the config with the resolved index_path so SimpleIndexer uses the correct path
     config.index_path = index_path.clone();
 
+    // Snapshot export/import works on the index directory, not a loaded index
+    if let Commands::Index {
+        action: Some(action),
+        ..
+    } = cli.command
+    {
+        let exit_code = codanna::cli::commands::snapshot::run(action, &config);
+        std::process::exit(exit_code as i32);
+    }
+
+    // Daemon control requests only talk to the running daemon's socket
+    if let Commands::Daemon {
+        action: Some(action),
+        ..
+    } = &cli.command
+    {
+        let exit_code = codanna::cli::commands::daemon::control(*action, &config);
+        std::process::exit(exit_code as i32);
+    }
+
     let persistence = IndexPersistence::new(index_path.clone());
 
     // Determine if we need full trait resolver initialization
@@ -312,6 +433,7 @@ async fn main() {
             ..
         } | Commands::Index { .. }
             | Commands::Serve { .. }
+            | Commands::Daemon { .. }
     );
 
     // Load existing index or create new one (only if command needs it)
@@ -320,8 +442,17 @@ async fn main() {
         None
     } else {
         Some({
-            // Force flag always means fresh index, regardless of path source (CLI or settings.toml)
-            let force_recreate_index = matches!(cli.command, Commands::Index { force: true, .. });
+            // Force flag means a fresh index, regardless of path source (CLI or settings.toml),
+            // unless it names directories inside shards: those are rebuilt in place
+            let force_recreate_index = match &cli.command {
+                Commands::Index {
+                    force: true, paths, ..
+                } => !codanna::indexing::shard::partial_rebuild(
+                    paths,
+                    config.workspace_root.as_deref(),
+                ),
+                _ => false,
+            };
             if persistence.exists() && !force_recreate_index {
                 tracing::debug!(target: "cli", "found existing index at {}", config.index_path.display());
                 // Use lazy loading for simple commands to improve startup time
@@ -330,7 +461,27 @@ async fn main() {
                     tracing::debug!(target: "cli", "using lazy initialization (skipping trait resolver)");
                 }
 
-                match persistence.load_facade(settings.clone()) {
+                check_index_compatibility(&config.index_path);
+
+                // One-shot queries defer semantic search until they need it.
+                // Anything that re-indexes needs embeddings and an embedding pool.
+                let one_shot = match &cli.command {
+                    Commands::Retrieve { .. } | Commands::Export { .. } => true,
+                    Commands::Mcp {
+                        tool, watch: false, ..
+                    } => {
+                        !codanna::mcp::permissions::ToolPolicy::from_config(&config.mcp.permissions)
+                            .is_mutating(tool)
+                    }
+                    _ => false,
+                };
+                let loaded = if one_shot {
+                    persistence.load_facade_lazy(settings.clone())
+                } else {
+                    persistence.load_facade(settings.clone())
+                };
+
+                match loaded {
                     Ok(loaded) => {
                         tracing::debug!(target: "cli", "successfully loaded index from disk");
                         if cli.info {
@@ -375,7 +526,7 @@ async fn main() {
     let seed_report = if let Some(ref mut idx) = indexer {
         Some(seed_indexer_with_config_paths(
             idx,
-            &config.indexing.indexed_paths,
+            &config.get_indexed_paths(),
         ))
     } else {
         None
@@ -469,7 +620,7 @@ async fn main() {
                     // Sync with current config (settings.toml is source of truth)
                     match idx.sync_with_config(
                         stored_paths,
-                        &config.indexing.indexed_paths,
+                        &config.get_indexed_paths(),
                         show_progress,
                     ) {
                         Ok(stats) => {
@@ -550,7 +701,18 @@ async fn main() {
             codanna::cli::commands::init::run_init(force);
         }
 
-        Commands::Config => {
+        Commands::Config { migrate } => {
+            if migrate {
+                let config_path = cli
+                    .config
+                    .clone()
+                    .or_else(Settings::find_workspace_config)
+                    .unwrap_or_else(|| {
+                        PathBuf::from(codanna::init::local_dir_name()).join("settings.toml")
+                    });
+                let exit_code = codanna::cli::commands::init::run_config_migrate(&config_path);
+                std::process::exit(exit_code as i32);
+            }
             codanna::cli::commands::init::run_config(&config);
         }
 
@@ -595,6 +757,7 @@ async fn main() {
             http,
             https,
             bind,
+            tcp,
         } => {
             use codanna::cli::commands::serve::{ServeArgs, run as run_serve};
             run_serve(
@@ -604,6 +767,7 @@ async fn main() {
                     http,
                     https,
                     bind,
+                    tcp,
                 },
                 config,
                 settings,
@@ -613,12 +777,26 @@ async fn main() {
             .await;
         }
 
+        Commands::Daemon { bind, .. } => {
+            codanna::cli::commands::daemon::run(
+                config,
+                indexer.expect("daemon requires indexer"),
+                bind,
+            )
+            .await;
+        }
+
         Commands::Index {
             paths,
             force,
             no_progress,
             dry_run,
             max_files,
+            git_delta,
+            since,
+            package,
+            remote,
+            rev,
             ..
         } => {
             use codanna::cli::commands::index::{IndexArgs, run as run_index};
@@ -632,6 +810,11 @@ async fn main() {
                     dry_run,
                     max_files,
                     cli_config: cli.config.clone(),
+                    git_delta,
+                    since,
+                    package,
+                    remote,
+                    rev,
                 },
                 &mut config,
                 indexer.as_mut().expect("index requires indexer"),
@@ -718,6 +901,137 @@ async fn main() {
             codanna::cli::commands::profile::run(action);
         }
 
+        Commands::Events {
+            since,
+            follow,
+            replay,
+            json,
+        } => {
+            let exit_code = codanna::cli::commands::events::run(
+                &config.index_path,
+                since,
+                follow,
+                replay,
+                json,
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Conversations {
+            query,
+            session,
+            limit,
+            json,
+        } => {
+            let exit_code = codanna::cli::commands::conversations::run(
+                &query,
+                session.as_deref(),
+                limit,
+                json,
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Api { bind, openapi } => {
+            codanna::cli::commands::api::run(indexer.expect("api requires indexer"), bind, openapi)
+                .await;
+        }
+
+        Commands::Diagnostics { path, json } => {
+            let exit_code = codanna::cli::commands::diagnostics::run(
+                indexer.as_ref().expect("diagnostics requires indexer"),
+                path.as_deref(),
+                json,
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Summary { force, map, json } => {
+            let exit_code = codanna::cli::commands::summary::run(
+                indexer.as_ref().expect("summary requires indexer"),
+                force,
+                map,
+                json,
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Vectors { action } => {
+            let exit_code = codanna::cli::commands::vectors::run(action, &config.index_path);
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Maintenance { action } => {
+            let exit_code = codanna::cli::commands::maintenance::run(
+                action,
+                indexer.as_ref().expect("maintenance requires indexer"),
+                &config,
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Analyze { action } => {
+            let exit_code = codanna::cli::commands::analyze::run(
+                action,
+                indexer.as_ref().expect("analyze requires indexer"),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Export { action } => {
+            let exit_code = codanna::cli::commands::export::run(
+                action,
+                indexer.as_ref().expect("export requires indexer"),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Semantic { action } => {
+            let exit_code = codanna::cli::commands::semantic::run(
+                action,
+                indexer.as_mut().expect("semantic requires indexer"),
+                cli.config.as_deref(),
+            );
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Record {
+            bundle,
+            include_sources,
+            command,
+        } => {
+            let exit_code = codanna::cli::commands::record::run(
+                command,
+                &bundle,
+                include_sources,
+                cli.config.as_deref(),
+                &config,
+            );
+            std::process::exit(exit_code);
+        }
+
+        Commands::Replay {
+            bundle,
+            keep,
+            verbose,
+        } => {
+            let exit_code = codanna::cli::commands::replay::run(&bundle, keep, verbose);
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::SelfUpdate {
+            version,
+            check,
+            force,
+        } => {
+            codanna::cli::commands::self_update::run(
+                version.as_deref(),
+                check,
+                force,
+                &config.index_path,
+            );
+        }
+
         Commands::IndexParallel {
             paths,
             force,
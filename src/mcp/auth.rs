@@ -0,0 +1,229 @@
+//! Bearer-token authentication for the network MCP transports
+//!
+//! Tokens come from `[[server.tokens]]` in settings.toml and from the
+//! `CODANNA_MCP_TOKEN` (reindex scope) and `CODANNA_MCP_READ_TOKEN` (read
+//! scope) environment variables. With none configured the HTTP server keeps
+//! accepting the token handed out by its built-in OAuth flow, so existing
+//! clients still connect.
+//!
+//! The HTTP middleware only checks that a request carries a known token and
+//! attaches the matching [`Grant`] to it. The scope is enforced where the
+//! request is dispatched: rmcp passes the HTTP request parts through to the
+//! handler, which compares the grant with the scope the tool or custom
+//! request needs (see [`required_scope`] and `[mcp.permissions]`). stdio
+//! sessions carry no grant and may do everything `[mcp.permissions]` allows.
+
+use crate::config::{ServerConfig, TokenScope};
+
+/// Environment variable holding a token with reindex scope
+pub const TOKEN_ENV: &str = "CODANNA_MCP_TOKEN";
+
+/// Environment variable holding a read-only token
+pub const READ_TOKEN_ENV: &str = "CODANNA_MCP_READ_TOKEN";
+
+/// Token issued by the built-in OAuth flow, accepted when no tokens are configured
+pub const OAUTH_TOKEN: &str = "mcp-access-token-dummy";
+
+/// Grant name shared by every client of the built-in OAuth flow
+pub const OAUTH_GRANT: &str = "oauth";
+
+/// Identity and scope of an authenticated request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    pub name: String,
+    pub scope: TokenScope,
+}
+
+/// Tokens accepted by a server
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: Vec<(String, Grant)>,
+}
+
+impl TokenRegistry {
+    /// Tokens from `[[server.tokens]]` and the environment
+    pub fn from_config(config: &ServerConfig) -> Self {
+        let mut tokens = Vec::new();
+        for entry in &config.tokens {
+            match entry.value() {
+                Some(token) => tokens.push((
+                    token,
+                    Grant {
+                        name: entry.name.clone(),
+                        scope: entry.scope,
+                    },
+                )),
+                None => tracing::warn!("[auth] token '{}' has no value, skipped", entry.name),
+            }
+        }
+        for (var, scope) in [
+            (TOKEN_ENV, TokenScope::Reindex),
+            (READ_TOKEN_ENV, TokenScope::Read),
+        ] {
+            if let Some(token) = std::env::var(var).ok().filter(|token| !token.is_empty()) {
+                let name = var.to_lowercase();
+                tokens.push((token, Grant { name, scope }));
+            }
+        }
+        Self { tokens }
+    }
+
+    /// Whether tokens were configured rather than the OAuth fallback used
+    pub fn is_configured(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// Grant for an `Authorization` header value, if it carries a known token
+    pub fn authorize(&self, header: &str) -> Option<Grant> {
+        let token = header.strip_prefix("Bearer ")?.trim();
+        if !self.is_configured() {
+            return constant_time_eq(token, OAUTH_TOKEN).then(|| Grant {
+                name: OAUTH_GRANT.to_string(),
+                scope: TokenScope::Reindex,
+            });
+        }
+        // Compare against every token so the time taken does not reveal which matched
+        let mut grant = None;
+        for (known, candidate) in &self.tokens {
+            if constant_time_eq(token, known) && grant.is_none() {
+                grant = Some(candidate.clone());
+            }
+        }
+        grant
+    }
+}
+
+/// Scope a built-in tool call or custom request needs
+///
+/// `[mcp.permissions]` can mark more tools as mutating; see
+/// [`super::permissions::ToolPolicy::required_scope`].
+pub fn required_scope(method: &str) -> TokenScope {
+    match method {
+        "requests/codanna/force-reindex" | "requests/codanna/cancel-reindex" | "reindex_paths" => {
+            TokenScope::Reindex
+        }
+        _ => TokenScope::Read,
+    }
+}
+
+/// Grant attached to a request by the HTTP middleware, if any
+pub fn grant_of(extensions: &rmcp::model::Extensions) -> Option<&Grant> {
+    #[cfg(feature = "http-server")]
+    {
+        extensions
+            .get::<axum::http::request::Parts>()?
+            .extensions
+            .get::<Grant>()
+    }
+    #[cfg(not(feature = "http-server"))]
+    {
+        let _ = extensions;
+        None
+    }
+}
+
+/// Refuse `method` when the request's grant lacks the `required` scope
+pub fn check_scope(
+    extensions: &rmcp::model::Extensions,
+    method: &str,
+    required: TokenScope,
+) -> Result<(), rmcp::model::ErrorData> {
+    let Some(grant) = grant_of(extensions) else {
+        return Ok(());
+    };
+    if grant.scope >= required {
+        return Ok(());
+    }
+    tracing::warn!(
+        "[auth] token '{}' denied '{method}': needs {required:?} scope",
+        grant.name
+    );
+    Err(rmcp::model::ErrorData::invalid_request(
+        format!("'{method}' needs a token with {required:?} scope"),
+        None,
+    ))
+}
+
+/// Axum middleware rejecting requests without a known bearer token
+#[cfg(feature = "http-server")]
+pub async fn require_token(
+    axum::extract::State(registry): axum::extract::State<std::sync::Arc<TokenRegistry>>,
+    mut req: axum::http::Request<axum::body::Body>,
+    next: axum::middleware::Next,
+) -> Result<axum::response::Response, axum::http::StatusCode> {
+    // CORS preflight carries no credentials
+    if req.method() == axum::http::Method::OPTIONS {
+        return Ok(next.run(req).await);
+    }
+
+    let grant = req
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|header| registry.authorize(header));
+    match grant {
+        Some(grant) => {
+            crate::debug_event!("auth", "authorized", "{}", grant.name);
+            req.extensions_mut().insert(grant);
+            Ok(next.run(req).await)
+        }
+        None => {
+            tracing::warn!("[auth] rejected request with missing or unknown bearer token");
+            Err(axum::http::StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TokenConfig;
+
+    #[test]
+    fn test_tokens_grant_their_scope() {
+        let config = ServerConfig {
+            tokens: vec![
+                TokenConfig {
+                    name: "ci".to_string(),
+                    token: Some("ci-secret".to_string()),
+                    token_env: None,
+                    scope: TokenScope::Reindex,
+                },
+                TokenConfig {
+                    name: "agents".to_string(),
+                    token: Some("agent-secret".to_string()),
+                    token_env: None,
+                    scope: TokenScope::Read,
+                },
+            ],
+            ..Default::default()
+        };
+        let registry = TokenRegistry::from_config(&config);
+
+        let grant = registry.authorize("Bearer agent-secret").unwrap();
+        assert_eq!(grant.name, "agents");
+        assert!(grant.scope < required_scope("requests/codanna/force-reindex"));
+        assert!(grant.scope < required_scope("reindex_paths"));
+        assert!(grant.scope >= required_scope("find_symbol"));
+        assert_eq!(
+            registry.authorize("Bearer ci-secret").unwrap().scope,
+            TokenScope::Reindex
+        );
+        assert!(registry.authorize("Bearer wrong").is_none());
+        assert!(registry.authorize("ci-secret").is_none());
+        // Configured tokens replace the OAuth fallback
+        assert!(
+            registry
+                .authorize(&format!("Bearer {OAUTH_TOKEN}"))
+                .is_none()
+        );
+    }
+}
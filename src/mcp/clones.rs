@@ -0,0 +1,89 @@
+//! Near-duplicate detection
+//!
+//! Backs the `find_similar_symbols` MCP tool, which an agent calls before
+//! writing a helper to learn whether one already exists. Candidates come
+//! from the semantic index, so similarity is that of documentation: code
+//! without doc comments is only found when it is the code searched from.
+//!
+//! Besides the symbol itself, hits nested in it or enclosing it are dropped:
+//! a method resembles its impl block, but is not a second copy of it.
+
+use crate::Symbol;
+
+/// Score from which two symbols are likely the same helper written twice
+pub const NEAR_DUPLICATE: f32 = 0.95;
+
+/// Score from which two symbols very likely do the same job
+pub const VERY_SIMILAR: f32 = 0.85;
+
+/// How alike a candidate is, for display
+pub fn describe(score: f32) -> &'static str {
+    if score >= NEAR_DUPLICATE {
+        "near-duplicate"
+    } else if score >= VERY_SIMILAR {
+        "very similar"
+    } else {
+        "similar"
+    }
+}
+
+/// Hits at or above `threshold` that are not `target` or overlap it,
+/// at most `limit` of them in the order given
+pub fn candidates(
+    target: Option<&Symbol>,
+    hits: Vec<(Symbol, f32)>,
+    threshold: f32,
+    limit: usize,
+) -> Vec<(Symbol, f32)> {
+    hits.into_iter()
+        .filter(|(_, score)| *score >= threshold)
+        .filter(|(symbol, _)| target.is_none_or(|target| !overlaps(target, symbol)))
+        .take(limit)
+        .collect()
+}
+
+/// Whether two symbols share lines of the same file
+fn overlaps(a: &Symbol, b: &Symbol) -> bool {
+    a.id == b.id
+        || (a.file_id == b.file_id
+            && a.range.start_line <= b.range.end_line
+            && b.range.start_line <= a.range.end_line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range, SymbolId, SymbolKind};
+
+    fn symbol(id: u32, file: u32, lines: (u32, u32)) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            format!("helper{id}"),
+            SymbolKind::Function,
+            FileId::new(file).unwrap(),
+            Range::new(lines.0, 0, lines.1, 1),
+        )
+    }
+
+    #[test]
+    fn test_candidates_skip_target_and_enclosing_symbols() {
+        let target = symbol(1, 1, (10, 20));
+        let hits = vec![
+            (symbol(2, 1, (0, 40)), 0.97),
+            (symbol(3, 2, (10, 20)), 0.96),
+            (symbol(4, 1, (30, 35)), 0.88),
+            (symbol(5, 3, (1, 5)), 0.81),
+            (symbol(6, 3, (8, 9)), 0.62),
+        ];
+
+        let found = candidates(Some(&target), hits.clone(), 0.8, 10);
+        let ids: Vec<u32> = found.iter().map(|(symbol, _)| symbol.id.value()).collect();
+        assert_eq!(ids, [3, 4, 5]);
+        assert_eq!(candidates(Some(&target), hits.clone(), 0.8, 1).len(), 1);
+        assert_eq!(candidates(None, hits, 0.9, 10).len(), 2);
+
+        assert_eq!(describe(0.96), "near-duplicate");
+        assert_eq!(describe(0.88), "very similar");
+        assert_eq!(describe(0.81), "similar");
+    }
+}
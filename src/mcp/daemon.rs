@@ -0,0 +1,298 @@
+//! Control socket for `codanna daemon`
+//!
+//! The daemon runs the file watcher, incremental indexing and the HTTP MCP
+//! server in one process. It listens on a Unix socket next to the index
+//! (`daemon.sock`) for one JSON request per line, such as
+//! `{"command":"pause"}`, and answers every request with a [`DaemonStatus`]
+//! line. `codanna daemon status|pause|resume|stop` is the client side.
+//!
+//! Pausing leaves the watcher collecting events without applying them, for
+//! example across a large checkout; resuming applies what changed meanwhile.
+
+use crate::indexing::facade::IndexFacade;
+use crate::watcher::WatchControl;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// File name of the control socket inside the index directory
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+/// Control socket path for the index at `index_path`
+pub fn socket_path(index_path: &Path) -> PathBuf {
+    index_path.join(SOCKET_FILE)
+}
+
+/// Request sent over the control socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    Status,
+    Pause,
+    Resume,
+    Stop,
+}
+
+/// State of a running daemon, returned for every request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    /// MCP endpoint address
+    pub bind: String,
+    /// Unix seconds when the daemon started
+    pub started_at: u64,
+    pub paused: bool,
+    /// True once `stop` was received and shutdown has begun
+    pub stopping: bool,
+    /// File changes waiting to be applied
+    pub pending_changes: usize,
+    pub files_reindexed: u64,
+    pub files_removed: u64,
+    /// Unix seconds of the last applied change
+    pub last_change: Option<u64>,
+    pub symbols: usize,
+    pub files: u32,
+}
+
+/// Everything the control socket needs to answer requests
+pub struct DaemonControl {
+    pub control: Arc<WatchControl>,
+    pub facade: Arc<RwLock<IndexFacade>>,
+    /// Cancelled by a `stop` request
+    pub stop: CancellationToken,
+    pub bind: String,
+    started_at: u64,
+}
+
+impl DaemonControl {
+    pub fn new(
+        control: Arc<WatchControl>,
+        facade: Arc<RwLock<IndexFacade>>,
+        stop: CancellationToken,
+        bind: String,
+    ) -> Self {
+        Self {
+            control,
+            facade,
+            stop,
+            bind,
+            started_at: unix_now(),
+        }
+    }
+
+    /// Apply `request` and report the resulting state
+    pub async fn handle(&self, request: DaemonRequest) -> DaemonStatus {
+        match request {
+            DaemonRequest::Status => {}
+            DaemonRequest::Pause => {
+                self.control.pause();
+                crate::log_event!("daemon", "paused");
+            }
+            DaemonRequest::Resume => {
+                self.control.resume();
+                crate::log_event!("daemon", "resumed");
+            }
+            DaemonRequest::Stop => {
+                crate::log_event!("daemon", "stop requested");
+                self.stop.cancel();
+            }
+        }
+        self.status().await
+    }
+
+    async fn status(&self) -> DaemonStatus {
+        let facade = self.facade.read().await;
+        DaemonStatus {
+            pid: std::process::id(),
+            bind: self.bind.clone(),
+            started_at: self.started_at,
+            paused: self.control.is_paused(),
+            stopping: self.stop.is_cancelled(),
+            pending_changes: self.control.pending(),
+            files_reindexed: self.control.reindexed(),
+            files_removed: self.control.removed(),
+            last_change: self.control.last_change(),
+            symbols: facade.symbol_count(),
+            files: facade.file_count(),
+        }
+    }
+}
+
+/// Listen on `socket` until `shutdown` is cancelled, then remove it
+///
+/// Fails if another daemon already answers on the socket; a stale socket
+/// left by a crashed daemon is replaced.
+#[cfg(unix)]
+pub async fn listen(
+    socket: PathBuf,
+    daemon: Arc<DaemonControl>,
+    shutdown: CancellationToken,
+) -> io::Result<tokio::task::JoinHandle<()>> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+
+    if socket.exists() {
+        // send() blocks for up to its read timeout, so keep it off the runtime
+        let probe = socket.clone();
+        let answered = tokio::task::spawn_blocking(move || send(&probe, DaemonRequest::Status))
+            .await
+            .map_err(io::Error::other)?
+            .is_ok();
+        if answered {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a daemon is already running on {}", socket.display()),
+            ));
+        }
+        std::fs::remove_file(&socket)?;
+    }
+    let listener = UnixListener::bind(&socket)?;
+    crate::log_event!("daemon", "control socket", "{}", socket.display());
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let stream = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::warn!("[daemon] control socket accept failed: {e}");
+                        continue;
+                    }
+                },
+                _ = shutdown.cancelled() => break,
+            };
+
+            let daemon = Arc::clone(&daemon);
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let reply = match serde_json::from_str::<DaemonRequest>(&line) {
+                        Ok(request) => serde_json::to_string(&daemon.handle(request).await),
+                        Err(e) => serde_json::to_string(&serde_json::json!({
+                            "error": format!("invalid request: {e}")
+                        })),
+                    };
+                    let Ok(mut reply) = reply else { break };
+                    reply.push('\n');
+                    if writer.write_all(reply.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        let _ = std::fs::remove_file(&socket);
+    }))
+}
+
+#[cfg(not(unix))]
+pub async fn listen(
+    socket: PathBuf,
+    _daemon: Arc<DaemonControl>,
+    _shutdown: CancellationToken,
+) -> io::Result<tokio::task::JoinHandle<()>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "control socket {} needs Unix domain sockets",
+            socket.display()
+        ),
+    ))
+}
+
+/// Send one request to the daemon listening on `socket`
+#[cfg(unix)]
+pub fn send(socket: &Path, request: DaemonRequest) -> io::Result<DaemonStatus> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+
+    let mut line = serde_json::to_string(&request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    serde_json::from_str(&reply).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(not(unix))]
+pub fn send(socket: &Path, _request: DaemonRequest) -> io::Result<DaemonStatus> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "control socket {} needs Unix domain sockets",
+            socket.display()
+        ),
+    ))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_control_socket_pause_resume_stop() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let index_path = temp.path().join("index");
+        std::fs::create_dir_all(&index_path).unwrap();
+        let settings = Settings {
+            index_path,
+            ..Settings::default()
+        };
+        let facade = IndexFacade::new(Arc::new(settings)).unwrap();
+
+        let control = Arc::new(WatchControl::new());
+        let stop = CancellationToken::new();
+        let daemon = Arc::new(DaemonControl::new(
+            Arc::clone(&control),
+            Arc::new(RwLock::new(facade)),
+            stop.clone(),
+            "127.0.0.1:8080".to_string(),
+        ));
+
+        let socket = temp.path().join(SOCKET_FILE);
+        let handle = listen(socket.clone(), Arc::clone(&daemon), stop.clone())
+            .await
+            .unwrap();
+
+        // A second daemon must not take over a live socket
+        let second = listen(socket.clone(), daemon, stop.clone()).await;
+        assert_eq!(second.unwrap_err().kind(), io::ErrorKind::AddrInUse);
+
+        let client = socket.clone();
+        let (paused, resumed, stopped) = tokio::task::spawn_blocking(move || {
+            (
+                send(&client, DaemonRequest::Pause).unwrap(),
+                send(&client, DaemonRequest::Resume).unwrap(),
+                send(&client, DaemonRequest::Stop).unwrap(),
+            )
+        })
+        .await
+        .unwrap();
+
+        assert!(paused.paused);
+        assert_eq!(paused.pid, std::process::id());
+        assert!(!resumed.paused);
+        assert!(stopped.stopping);
+        assert!(stop.is_cancelled());
+
+        handle.await.unwrap();
+        assert!(!socket.exists());
+    }
+}
@@ -0,0 +1,512 @@
+//! Tool execution layer for the MCP server
+//!
+//! Tool handlers do synchronous index work (Tantivy queries, graph traversal,
+//! embedding lookups) inside async functions. Running them on the runtime that
+//! drives the transport lets one enormous graph query starve every other
+//! request. The executor isolates handlers on a dedicated runtime and bounds
+//! how many calls of each tool may run at once.
+//!
+//! On top of the per-tool limits, `mcp.max_concurrent_queries` caps calls
+//! across all tools and clients. Calls beyond the cap wait in a queue of at
+//! most `mcp.max_queued_calls`; past that they are refused as busy, and so
+//! are calls from a client over its rate limit (see [`super::rate_limit`]).
+//!
+//! Cancellation follows the request's `CancellationToken`: a `notifications/cancelled`
+//! message or the session ending (client disconnect) aborts the call. Queued calls
+//! are dropped before they start; running calls stop at their next await point.
+//!
+//! On server shutdown the executor stops accepting calls and waits (up to a
+//! deadline) for the ones already running, so their responses still go out.
+
+use rmcp::model::{CallToolResult, ErrorCode, ErrorData as McpError};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+use super::metrics::{CallOutcome, tool_metrics};
+use super::rate_limit::RateLimiter;
+use crate::config::McpConfig;
+
+/// JSON-RPC code for requests cancelled by the client (matches LSP's RequestCancelled)
+const REQUEST_CANCELLED: ErrorCode = ErrorCode(-32800);
+
+/// JSON-RPC server error for calls refused by a rate limit or a full queue
+pub const SERVER_BUSY: ErrorCode = ErrorCode(-32001);
+
+static SHARED: OnceLock<Arc<ToolExecutor>> = OnceLock::new();
+
+/// Runs MCP tool calls with per-tool concurrency limits and cancellation
+pub struct ToolExecutor {
+    /// Dedicated runtime for tool handlers; `None` runs them on the caller's runtime
+    runtime: Option<Runtime>,
+    /// Concurrent calls allowed for tools without an override
+    default_limit: usize,
+    /// Per-tool overrides from `mcp.tool_concurrency`
+    overrides: HashMap<String, usize>,
+    /// Semaphores created on first use of each tool
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+    /// Slots shared by all tools; `None` when `max_concurrent_queries` is 0
+    global: Option<Arc<Semaphore>>,
+    /// Calls waiting for a slot
+    queued: AtomicUsize,
+    /// Most calls allowed to wait (0 = no limit)
+    max_queued: usize,
+    /// Per-client rate limits; `None` when `rate_limit_per_minute` is 0
+    limiter: Option<RateLimiter>,
+    /// Set once shutdown starts; new calls are rejected
+    closing: AtomicBool,
+}
+
+impl ToolExecutor {
+    /// Create an executor from MCP settings
+    pub fn new(config: &McpConfig) -> std::io::Result<Self> {
+        let runtime = if config.tool_threads > 0 {
+            Some(
+                tokio::runtime::Builder::new_multi_thread()
+                    .worker_threads(config.tool_threads)
+                    .thread_name("codanna-mcp-tool")
+                    .enable_all()
+                    .build()?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self::with_runtime(runtime, config))
+    }
+
+    fn with_runtime(runtime: Option<Runtime>, config: &McpConfig) -> Self {
+        Self {
+            runtime,
+            default_limit: config.max_concurrent_calls.max(1),
+            overrides: config.tool_concurrency.clone(),
+            semaphores: Mutex::new(HashMap::new()),
+            global: (config.max_concurrent_queries > 0)
+                .then(|| Arc::new(Semaphore::new(config.max_concurrent_queries))),
+            queued: AtomicUsize::new(0),
+            max_queued: config.max_queued_calls,
+            limiter: RateLimiter::new(config.rate_limit_per_minute, config.rate_limit_burst),
+            closing: AtomicBool::new(false),
+        }
+    }
+
+    /// Get the process-wide executor, creating it from `config` on first use
+    ///
+    /// HTTP transports build a server per session, so the runtime and limits
+    /// are shared rather than recreated for every connection.
+    pub fn shared(config: &McpConfig) -> Arc<Self> {
+        SHARED
+            .get_or_init(|| {
+                let executor = Self::new(config).unwrap_or_else(|e| {
+                    tracing::warn!(
+                        target: "mcp",
+                        "failed to start tool runtime, running tools inline: {e}"
+                    );
+                    Self::with_runtime(None, config)
+                });
+                Arc::new(executor)
+            })
+            .clone()
+    }
+
+    /// Concurrent calls allowed for `tool`
+    pub fn limit_for(&self, tool: &str) -> usize {
+        self.overrides
+            .get(tool)
+            .copied()
+            .unwrap_or(self.default_limit)
+            .max(1)
+    }
+
+    fn semaphore_for(&self, tool: &str) -> Arc<Semaphore> {
+        let mut semaphores = self
+            .semaphores
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        semaphores
+            .entry(tool.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.limit_for(tool))))
+            .clone()
+    }
+
+    /// Refuse a call from `client` if it is over its rate limit
+    pub fn admit(&self, client: &str) -> Result<(), McpError> {
+        let Some(limiter) = &self.limiter else {
+            return Ok(());
+        };
+        limiter.check(client).map_err(|retry_after| {
+            tracing::debug!(target: "mcp", "rate limited: {client}");
+            McpError::new(
+                SERVER_BUSY,
+                format!(
+                    "Rate limit exceeded, retry in {:.1}s",
+                    retry_after.as_secs_f64()
+                ),
+                Some(serde_json::json!({ "retry_after_ms": retry_after.as_millis() as u64 })),
+            )
+        })
+    }
+
+    /// Take a slot of `semaphore`, queueing if none is free
+    async fn acquire(
+        &self,
+        semaphore: Arc<Semaphore>,
+        tool: &str,
+        ct: &CancellationToken,
+    ) -> Result<OwnedSemaphorePermit, McpError> {
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Ok(permit);
+        }
+
+        let _queued = Queued::enter(&self.queued);
+        if self.max_queued > 0 && self.queued.load(Ordering::Acquire) > self.max_queued {
+            return Err(McpError::new(
+                SERVER_BUSY,
+                format!("Tool '{tool}' rejected: too many calls waiting, retry later"),
+                None,
+            ));
+        }
+        tokio::select! {
+            permit = semaphore.acquire_owned() => permit.map_err(|e| {
+                McpError::internal_error(format!("Tool '{tool}' is unavailable: {e}"), None)
+            }),
+            _ = ct.cancelled() => Err(cancelled(tool)),
+        }
+    }
+
+    /// Run a tool call, waiting for a free slot and honoring cancellation
+    ///
+    /// The call's outcome and latency are counted in [`tool_metrics`].
+    pub async fn execute<F>(
+        &self,
+        tool: &str,
+        ct: CancellationToken,
+        task: F,
+    ) -> Result<CallToolResult, McpError>
+    where
+        F: Future<Output = Result<CallToolResult, McpError>> + Send + 'static,
+    {
+        let start = Instant::now();
+        let result = self.run(tool, &ct, task).await;
+        let outcome = match &result {
+            Ok(output) if output.is_error != Some(true) => CallOutcome::Success,
+            Err(e) if e.code == REQUEST_CANCELLED => CallOutcome::Cancelled,
+            _ => CallOutcome::Error,
+        };
+        tool_metrics().record(tool, start.elapsed(), outcome);
+        result
+    }
+
+    /// Run a request other than a tool call, like a resource read
+    ///
+    /// It takes a slot of `method` and a global one, as a tool call does, but
+    /// is not counted in the tool metrics.
+    pub async fn execute_request<T, F>(
+        &self,
+        method: &str,
+        ct: CancellationToken,
+        task: F,
+    ) -> Result<T, McpError>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, McpError>> + Send + 'static,
+    {
+        self.run(method, &ct, task).await
+    }
+
+    async fn run<T, F>(&self, tool: &str, ct: &CancellationToken, task: F) -> Result<T, McpError>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, McpError>> + Send + 'static,
+    {
+        if self.closing.load(Ordering::Acquire) {
+            return Err(McpError::internal_error(
+                format!("Tool '{tool}' rejected: server is shutting down"),
+                None,
+            ));
+        }
+
+        // Scheduled maintenance waits while the server is busy
+        crate::mcp::maintenance::activity().record();
+
+        // The tool's own slot first, so calls queued for a busy tool do not
+        // hold global slots other tools could use
+        let permit = self.acquire(self.semaphore_for(tool), tool, ct).await?;
+        let global = match &self.global {
+            Some(global) => Some(self.acquire(global.clone(), tool, ct).await?),
+            None => None,
+        };
+
+        // The permits move into the task so the slots stay taken until the
+        // handler actually stops, even if the client has already gone away.
+        let task = async move {
+            let _permits = (permit, global);
+            task.await
+        };
+        let handle = match &self.runtime {
+            Some(runtime) => runtime.spawn(task),
+            None => tokio::spawn(task),
+        };
+        let abort = handle.abort_handle();
+
+        tokio::select! {
+            joined = handle => joined.map_err(|e| {
+                McpError::internal_error(format!("Tool '{tool}' failed: {e}"), None)
+            })?,
+            _ = ct.cancelled() => {
+                abort.abort();
+                tracing::debug!(target: "mcp", "tool call cancelled: {tool}");
+                Err(cancelled(tool))
+            }
+        }
+    }
+
+    /// Stop accepting calls and wait for running ones to finish
+    ///
+    /// Returns `false` if calls were still running when `grace` expired.
+    pub async fn shutdown(&self, grace: Duration) -> bool {
+        self.closing.store(true, Ordering::Release);
+
+        let semaphores: Vec<(Arc<Semaphore>, usize)> = self
+            .semaphores
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(tool, semaphore)| (semaphore.clone(), self.limit_for(tool)))
+            .collect();
+
+        // Holding every permit of a tool means none of its calls are running.
+        // Calls already queued for a slot are ahead of us and still complete.
+        let drained = tokio::time::timeout(grace, async {
+            let mut held = Vec::with_capacity(semaphores.len());
+            for (semaphore, limit) in &semaphores {
+                let permits = u32::try_from(*limit).unwrap_or(u32::MAX);
+                held.push(semaphore.acquire_many(permits).await);
+            }
+            held
+        })
+        .await
+        .is_ok();
+
+        for (semaphore, _) in &semaphores {
+            semaphore.close();
+        }
+        if let Some(global) = &self.global {
+            global.close();
+        }
+
+        drained
+    }
+}
+
+impl Drop for ToolExecutor {
+    fn drop(&mut self) {
+        // Dropping a runtime from async context panics; never block here
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Counts a call as queued until dropped
+struct Queued<'a>(&'a AtomicUsize);
+
+impl<'a> Queued<'a> {
+    fn enter(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::AcqRel);
+        Self(queued)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn cancelled(tool: &str) -> McpError {
+    McpError::new(
+        REQUEST_CANCELLED,
+        format!("Tool '{tool}' was cancelled"),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+    use std::time::Duration;
+
+    fn config(threads: usize, default_limit: usize) -> McpConfig {
+        McpConfig {
+            tool_threads: threads,
+            max_concurrent_calls: default_limit,
+            tool_concurrency: HashMap::from([("analyze_impact".to_string(), 1)]),
+            ..McpConfig::default()
+        }
+    }
+
+    fn test_runtime() -> Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_limit_overrides() {
+        let executor = ToolExecutor::new(&config(0, 4)).unwrap();
+        assert_eq!(executor.limit_for("analyze_impact"), 1);
+        assert_eq!(executor.limit_for("find_symbol"), 4);
+    }
+
+    #[test]
+    fn test_execute_on_dedicated_runtime() {
+        let executor = ToolExecutor::new(&config(1, 4)).unwrap();
+        let result = test_runtime().block_on(executor.execute(
+            "find_symbol",
+            CancellationToken::new(),
+            async { Ok(CallToolResult::success(vec![Content::text("ok")])) },
+        ));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_execute_request_returns_its_value() {
+        let executor = ToolExecutor::new(&config(1, 4)).unwrap();
+        let result = test_runtime().block_on(executor.execute_request(
+            "resources/read",
+            CancellationToken::new(),
+            async { Ok(42) },
+        ));
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn test_per_tool_limit_serializes_calls() {
+        let executor = ToolExecutor::new(&config(2, 4)).unwrap();
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let call = || {
+            let running = running.clone();
+            let peak = peak.clone();
+            executor.execute("analyze_impact", CancellationToken::new(), async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(CallToolResult::success(vec![]))
+            })
+        };
+
+        let (a, b, c) = test_runtime().block_on(async { tokio::join!(call(), call(), call()) });
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_global_cap_and_queue_limit() {
+        let executor = ToolExecutor::new(&McpConfig {
+            tool_threads: 2,
+            max_concurrent_queries: 1,
+            max_queued_calls: 1,
+            ..McpConfig::default()
+        })
+        .unwrap();
+
+        let call = |tool: &'static str| {
+            executor.execute(tool, CancellationToken::new(), async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(CallToolResult::success(vec![]))
+            })
+        };
+
+        // One runs, one waits, the third finds the queue full
+        let (a, b, c) = test_runtime().block_on(async {
+            tokio::join!(call("find_symbol"), call("get_calls"), async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                call("search_symbols").await
+            })
+        });
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(c.unwrap_err().code, SERVER_BUSY);
+    }
+
+    #[test]
+    fn test_rate_limit_refuses_client_over_burst() {
+        let executor = ToolExecutor::new(&McpConfig {
+            tool_threads: 0,
+            rate_limit_per_minute: 60,
+            rate_limit_burst: 1,
+            ..McpConfig::default()
+        })
+        .unwrap();
+
+        assert!(executor.admit("session:a").is_ok());
+        assert_eq!(executor.admit("session:a").unwrap_err().code, SERVER_BUSY);
+        assert!(executor.admit("session:b").is_ok());
+    }
+
+    #[test]
+    fn test_cancellation_aborts_call() {
+        let executor = ToolExecutor::new(&config(1, 4)).unwrap();
+        let ct = CancellationToken::new();
+        let cancel = ct.clone();
+
+        let result = test_runtime().block_on(async {
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                cancel.cancel();
+            });
+            executor
+                .execute("analyze_impact", ct, async {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    Ok(CallToolResult::success(vec![]))
+                })
+                .await
+        });
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, REQUEST_CANCELLED);
+    }
+
+    #[test]
+    fn test_shutdown_waits_for_running_calls() {
+        let executor = ToolExecutor::new(&config(1, 4)).unwrap();
+        let finished = Arc::new(AtomicUsize::new(0));
+        let done = finished.clone();
+
+        let (call, drained) = test_runtime().block_on(async {
+            tokio::join!(
+                executor.execute("find_symbol", CancellationToken::new(), async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    done.fetch_add(1, Ordering::SeqCst);
+                    Ok(CallToolResult::success(vec![]))
+                }),
+                async {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    executor.shutdown(Duration::from_secs(5)).await
+                }
+            )
+        });
+
+        assert!(call.is_ok());
+        assert!(drained);
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+
+        let rejected = test_runtime().block_on(executor.execute(
+            "find_symbol",
+            CancellationToken::new(),
+            async { Ok(CallToolResult::success(vec![])) },
+        ));
+        assert!(rejected.is_err());
+    }
+}
@@ -7,26 +7,13 @@
 pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> anyhow::Result<()> {
     use crate::IndexPersistence;
     use crate::indexing::facade::IndexFacade;
-    use crate::mcp::{CodeIntelligenceServer, notifications::NotificationBroadcaster};
-    use crate::watcher::HotReloadWatcher;
-    use axum::Router;
-    use rmcp::transport::streamable_http_server::{
-        StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
-    };
-    use std::path::PathBuf;
     use std::sync::Arc;
-    use std::time::Duration;
-    use tokio::sync::RwLock;
-    use tokio_util::sync::CancellationToken;
 
     // Initialize logging with config
     crate::logging::init_with_config(&config.logging);
 
     crate::log_event!("http", "starting", "MCP server on {bind}");
 
-    // Create notification broadcaster for file change events
-    let broadcaster = Arc::new(NotificationBroadcaster::new(100));
-
     // Create shared facade
     let settings = Arc::new(config.clone());
     let persistence = IndexPersistence::new(config.index_path.clone());
@@ -48,10 +35,72 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         crate::log_event!("http", "starting", "no existing index");
         IndexFacade::new(settings.clone()).expect("Failed to create IndexFacade")
     };
+
+    serve(config, watch, bind, facade, None).await
+}
+
+/// Serve an already loaded index for `codanna daemon`
+///
+/// Always watches files, and answers status/pause/resume/stop requests on
+/// the control `socket` until stopped or signalled.
+#[cfg(feature = "http-server")]
+pub async fn serve_daemon(
+    config: crate::Settings,
+    bind: String,
+    facade: crate::indexing::facade::IndexFacade,
+    socket: std::path::PathBuf,
+) -> anyhow::Result<()> {
+    crate::log_event!("daemon", "starting", "MCP server on {bind}");
+    serve(config, true, bind, facade, Some(socket)).await
+}
+
+#[cfg(feature = "http-server")]
+async fn serve(
+    config: crate::Settings,
+    watch: bool,
+    bind: String,
+    facade: crate::indexing::facade::IndexFacade,
+    daemon_socket: Option<std::path::PathBuf>,
+) -> anyhow::Result<()> {
+    use crate::mcp::auth::{TokenRegistry, require_token};
+    use crate::mcp::daemon::DaemonControl;
+    use crate::mcp::executor::ToolExecutor;
+    use crate::mcp::metrics::{HealthReport, tool_metrics};
+    use crate::mcp::shutdown::Shutdown;
+    use crate::mcp::{CodeIntelligenceServer, notifications::NotificationBroadcaster};
+    use crate::watcher::{HotReloadWatcher, WatchControl};
+    use axum::Router;
+    use rmcp::transport::streamable_http_server::{
+        StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+    };
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::RwLock;
+    use tokio_util::sync::CancellationToken;
+
+    // Create notification broadcaster for file change events
+    let broadcaster = Arc::new(NotificationBroadcaster::new(100));
+
     let indexer = Arc::new(RwLock::new(facade));
 
-    // Create cancellation token for coordinated shutdown
-    let ct = CancellationToken::new();
+    // Coordinated shutdown; the token stops watchers and sessions once tool calls drain
+    let mut shutdown = Shutdown::new(Duration::from_secs(config.server.shutdown_timeout));
+    let ct = shutdown.token();
+
+    // Daemon control socket; `stop` is cancelled by a stop request
+    let control = Arc::new(WatchControl::new());
+    let stop = CancellationToken::new();
+    if let Some(socket) = daemon_socket {
+        let daemon = Arc::new(DaemonControl::new(
+            control.clone(),
+            indexer.clone(),
+            stop.clone(),
+            bind.clone(),
+        ));
+        let handle = crate::mcp::daemon::listen(socket, daemon, ct.clone()).await?;
+        shutdown.track("control", handle);
+    }
 
     // Start index watcher if watch mode is enabled
     if watch {
@@ -87,7 +136,7 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
     // Start unified file watcher if enabled
     if watch || config.file_watch.enabled {
         use crate::documents::DocumentStore;
-        use crate::vector::{EmbeddingGenerator, FastEmbedGenerator};
+        use crate::vector::EmbeddingGenerator;
         use crate::watcher::UnifiedWatcher;
         use crate::watcher::handlers::{CodeFileHandler, ConfigFileHandler, DocumentFileHandler};
 
@@ -105,7 +154,9 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
             .indexer(indexer.clone())
             .index_path(config.index_path.clone())
             .workspace_root(workspace_root.clone())
-            .debounce_ms(debounce_ms);
+            .debounce_ms(debounce_ms)
+            .shutdown_token(ct.clone())
+            .control(control.clone());
 
         // Add code file handler
         builder = builder.handler(CodeFileHandler::new(
@@ -128,10 +179,14 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
             let doc_path = config.index_path.join("documents");
             if doc_path.exists() {
                 if let Ok(generator) =
-                    FastEmbedGenerator::from_settings(&config.semantic_search.model, false)
+                    crate::vector::create_provider(&config.semantic_search, false)
                 {
                     let dimension = generator.dimension();
-                    if let Ok(store) = DocumentStore::new(&doc_path, dimension) {
+                    if let Ok(store) = DocumentStore::new_compressed(
+                        &doc_path,
+                        dimension,
+                        config.storage.compression.documents_level,
+                    ) {
                         if let Ok(store_with_emb) = store.with_embeddings(Box::new(generator)) {
                             let store_arc = Arc::new(RwLock::new(store_with_emb));
                             builder = builder
@@ -150,19 +205,14 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         // Build and start the unified watcher
         match builder.build() {
             Ok(unified_watcher) => {
-                let watcher_ct = ct.clone();
-                tokio::spawn(async move {
-                    tokio::select! {
-                        result = unified_watcher.watch() => {
-                            if let Err(e) = result {
-                                tracing::error!("[watcher] error: {e}");
-                            }
-                        }
-                        _ = watcher_ct.cancelled() => {
-                            crate::log_event!("watcher", "stopped");
-                        }
+                // The watcher exits on its own once the token is cancelled,
+                // after flushing pending changes
+                let handle = tokio::spawn(async move {
+                    if let Err(e) = unified_watcher.watch().await {
+                        tracing::error!("[watcher] error: {e}");
                     }
                 });
+                shutdown.track("watcher", handle);
                 crate::log_event!(
                     "watcher",
                     "started",
@@ -177,6 +227,14 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         }
     }
 
+    // Compaction and retention while the server is idle
+    crate::mcp::maintenance::spawn(
+        &config,
+        indexer.clone(),
+        config.index_path.clone(),
+        ct.clone(),
+    );
+
     // Create streamable HTTP service for MCP connections
     let indexer_for_service = indexer.clone();
     let config_for_service = Arc::new(config.clone());
@@ -292,7 +350,7 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         if grant_type == "authorization_code" && code == "dummy-auth-code" {
             // Return access token WITHOUT refresh token
             axum::Json(serde_json::json!({
-                "access_token": "mcp-access-token-dummy",
+                "access_token": crate::mcp::auth::OAUTH_TOKEN,
                 "token_type": "Bearer",
                 "expires_in": 3600,
                 "scope": "mcp"
@@ -389,43 +447,26 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         axum::response::Html(html)
     }
 
-    // Helper function for shutdown signal with cancellation token
-    async fn shutdown_signal() {
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to listen for ctrl+c");
-        eprintln!("Received shutdown signal");
-    }
-
-    // Bearer token validation middleware - only for MCP endpoints
-    async fn validate_bearer_token(
-        req: axum::http::Request<axum::body::Body>,
-        next: axum::middleware::Next,
-    ) -> Result<axum::response::Response, axum::http::StatusCode> {
-        // Check for Bearer token in Authorization header
-        if let Some(auth_header) = req.headers().get("Authorization") {
-            if let Ok(auth_str) = auth_header.to_str() {
-                // Accept our dummy token
-                if auth_str == "Bearer mcp-access-token-dummy" {
-                    eprintln!("MCP request authorized with Bearer token");
-                    return Ok(next.run(req).await);
-                }
-            }
-        }
-
-        // For OPTIONS requests (CORS preflight), allow without auth
-        if req.method() == axum::http::Method::OPTIONS {
-            return Ok(next.run(req).await);
-        }
-
-        eprintln!("MCP request rejected - invalid or missing Bearer token");
-        Err(axum::http::StatusCode::UNAUTHORIZED)
+    // Bearer tokens from settings or the environment, else the OAuth token
+    let tokens = Arc::new(TokenRegistry::from_config(&config.server));
+    if tokens.is_configured() {
+        crate::log_event!("auth", "enabled", "configured bearer tokens");
     }
 
     // Create protected MCP router with Bearer token validation
     let protected_mcp_router = Router::new()
         .nest_service("/mcp", mcp_service)
-        .layer(axum::middleware::from_fn(validate_bearer_token));
+        .layer(axum::middleware::from_fn_with_state(tokens, require_token));
+
+    // Tool metrics, index freshness and memory use as JSON
+    let health_indexer = indexer.clone();
+    let healthz = move || {
+        let indexer = health_indexer.clone();
+        async move {
+            let indexer = indexer.read().await;
+            axum::Json(HealthReport::collect(&indexer, tool_metrics()))
+        }
+    };
 
     // Create main router - OAuth endpoints FIRST (no auth), then MCP endpoints (with auth)
     let router = Router::new()
@@ -439,6 +480,7 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
         .route("/oauth/authorize", axum::routing::get(oauth_authorize))
         // Health check - NO authentication required
         .route("/health", axum::routing::get(health_check))
+        .route("/healthz", axum::routing::get(healthz))
         // MCP endpoint - Bearer token authentication required
         .merge(protected_mcp_router);
 
@@ -447,19 +489,33 @@ pub async fn serve_http(config: crate::Settings, watch: bool, bind: String) -> a
     eprintln!("HTTP MCP server listening on http://{bind}");
     eprintln!("MCP endpoint: http://{bind}/mcp");
     eprintln!("Health check: http://{bind}/health");
+    eprintln!("Metrics: http://{bind}/healthz");
     eprintln!("Press Ctrl+C to stop the server");
 
-    // Create server future
-    let server = axum::serve(listener, router);
+    // Serve until the shutdown token is cancelled
+    let executor = ToolExecutor::shared(&config.mcp);
+    let server_ct = ct.clone();
+    let mut server = tokio::spawn(async move {
+        axum::serve(listener, router)
+            .with_graceful_shutdown(server_ct.cancelled_owned())
+            .await
+    });
 
-    // Handle graceful shutdown with tokio::select!
     tokio::select! {
-        result = server => {
-            result?;
+        result = &mut server => {
+            // Server stopped on its own; still let the watchers flush
+            shutdown.run(&executor).await;
+            result??;
         }
-        _ = shutdown_signal() => {
-            eprintln!("Shutting down HTTP server...");
-            ct.cancel();
+        signal = crate::mcp::shutdown::signal() => {
+            eprintln!("Received {signal}, shutting down HTTP server...");
+            shutdown.track("http", server);
+            shutdown.run(&executor).await;
+        }
+        _ = stop.cancelled() => {
+            eprintln!("Stop requested, shutting down HTTP server...");
+            shutdown.track("http", server);
+            shutdown.run(&executor).await;
         }
     }
 
@@ -477,3 +533,15 @@ pub async fn serve_http(
     eprintln!("Please rebuild with: cargo build --features http-server");
     std::process::exit(1);
 }
+
+#[cfg(not(feature = "http-server"))]
+pub async fn serve_daemon(
+    _config: crate::Settings,
+    _bind: String,
+    _facade: crate::indexing::facade::IndexFacade,
+    _socket: std::path::PathBuf,
+) -> anyhow::Result<()> {
+    eprintln!("HTTP server support is not compiled in.");
+    eprintln!("Please rebuild with: cargo build --features http-server");
+    std::process::exit(1);
+}
@@ -7,6 +7,10 @@
 pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) -> anyhow::Result<()> {
     use crate::IndexPersistence;
     use crate::indexing::facade::IndexFacade;
+    use crate::mcp::auth::{TokenRegistry, require_token};
+    use crate::mcp::executor::ToolExecutor;
+    use crate::mcp::metrics::{HealthReport, tool_metrics};
+    use crate::mcp::shutdown::Shutdown;
     use crate::mcp::{CodeIntelligenceServer, notifications::NotificationBroadcaster};
     use crate::watcher::HotReloadWatcher;
     use anyhow::Context;
@@ -20,7 +24,6 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::sync::RwLock;
-    use tokio_util::sync::CancellationToken;
 
     // Initialize logging with config
     crate::logging::init_with_config(&config.logging);
@@ -53,13 +56,14 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
     };
     let indexer = Arc::new(RwLock::new(facade));
 
-    // Create cancellation token for graceful shutdown
-    let ct = CancellationToken::new();
+    // Coordinated shutdown; the token stops watchers and sessions once tool calls drain
+    let mut shutdown = Shutdown::new(Duration::from_secs(config.server.shutdown_timeout));
+    let ct = shutdown.token();
 
     // Start unified file watcher if enabled
     if watch || config.file_watch.enabled {
         use crate::documents::DocumentStore;
-        use crate::vector::{EmbeddingGenerator, FastEmbedGenerator};
+        use crate::vector::EmbeddingGenerator;
         use crate::watcher::UnifiedWatcher;
         use crate::watcher::handlers::{CodeFileHandler, ConfigFileHandler, DocumentFileHandler};
 
@@ -77,7 +81,8 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
             .indexer(indexer.clone())
             .index_path(config.index_path.clone())
             .workspace_root(workspace_root.clone())
-            .debounce_ms(debounce_ms);
+            .debounce_ms(debounce_ms)
+            .shutdown_token(ct.clone());
 
         // Add code file handler
         builder = builder.handler(CodeFileHandler::new(
@@ -100,10 +105,14 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
             let doc_path = config.index_path.join("documents");
             if doc_path.exists() {
                 if let Ok(generator) =
-                    FastEmbedGenerator::from_settings(&config.semantic_search.model, false)
+                    crate::vector::create_provider(&config.semantic_search, false)
                 {
                     let dimension = generator.dimension();
-                    if let Ok(store) = DocumentStore::new(&doc_path, dimension) {
+                    if let Ok(store) = DocumentStore::new_compressed(
+                        &doc_path,
+                        dimension,
+                        config.storage.compression.documents_level,
+                    ) {
                         if let Ok(store_with_emb) = store.with_embeddings(Box::new(generator)) {
                             let store_arc = Arc::new(RwLock::new(store_with_emb));
                             builder = builder
@@ -122,19 +131,14 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
         // Build and start the unified watcher
         match builder.build() {
             Ok(unified_watcher) => {
-                let watcher_ct = ct.clone();
-                tokio::spawn(async move {
-                    tokio::select! {
-                        result = unified_watcher.watch() => {
-                            if let Err(e) = result {
-                                tracing::error!("[watcher] error: {e}");
-                            }
-                        }
-                        _ = watcher_ct.cancelled() => {
-                            crate::log_event!("watcher", "stopped");
-                        }
+                // The watcher exits on its own once the token is cancelled,
+                // after flushing pending changes
+                let handle = tokio::spawn(async move {
+                    if let Err(e) = unified_watcher.watch().await {
+                        tracing::error!("[watcher] error: {e}");
                     }
                 });
+                shutdown.track("watcher", handle);
                 crate::log_event!(
                     "watcher",
                     "started",
@@ -180,6 +184,14 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
         crate::log_event!("hot-reload", "started", "polling every {watch_interval}s");
     }
 
+    // Compaction and retention while the server is idle
+    crate::mcp::maintenance::spawn(
+        &config,
+        indexer.clone(),
+        config.index_path.clone(),
+        ct.clone(),
+    );
+
     // Create streamable HTTP service for MCP connections
     // Important: We share the SAME indexer instance across all connections
     // to ensure hot reload works properly. The indexer is already Arc<RwLock<_>>
@@ -194,10 +206,12 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
     // Start notification listener to forward file change events to MCP clients
     let notification_receiver = broadcaster.subscribe();
     let notification_server = shared_service.clone();
+    let listener_ct = ct.clone();
     tokio::spawn(async move {
-        notification_server
-            .start_notification_listener(notification_receiver)
-            .await;
+        tokio::select! {
+            _ = notification_server.start_notification_listener(notification_receiver) => {}
+            _ = listener_ct.cancelled() => {}
+        }
     });
 
     let mcp_service = StreamableHttpService::new(
@@ -253,10 +267,28 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
     }
 
     // Create MCP router with logging middleware
-    let mcp_router_with_logging = Router::new()
+    let mut mcp_router_with_logging = Router::new()
         .nest_service("/mcp", mcp_service)
         .layer(axum::middleware::from_fn(log_requests));
 
+    // TLS alone secures the transport; configured tokens also restrict access
+    let tokens = Arc::new(TokenRegistry::from_config(&config.server));
+    if tokens.is_configured() {
+        crate::log_event!("auth", "enabled", "configured bearer tokens");
+        mcp_router_with_logging = mcp_router_with_logging
+            .layer(axum::middleware::from_fn_with_state(tokens, require_token));
+    }
+
+    // Tool metrics, index freshness and memory use as JSON
+    let health_indexer = indexer.clone();
+    let healthz = move || {
+        let indexer = health_indexer.clone();
+        async move {
+            let indexer = indexer.read().await;
+            axum::Json(HealthReport::collect(&indexer, tool_metrics()))
+        }
+    };
+
     // Create main router - OAuth endpoints available but optional for HTTPS
     let router = Router::new()
         // OAuth endpoints - NO authentication required
@@ -269,7 +301,8 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
         .route("/oauth/authorize", axum::routing::get(oauth_authorize))
         // Health check - NO authentication required
         .route("/health", axum::routing::get(health_check))
-        // MCP endpoint - No authentication required (TLS provides transport security)
+        .route("/healthz", axum::routing::get(healthz))
+        // MCP endpoint - Bearer tokens required only when configured
         .merge(mcp_router_with_logging);
 
     // Get or create TLS certificates
@@ -288,23 +321,38 @@ pub async fn serve_https(config: crate::Settings, watch: bool, bind: String) ->
     eprintln!("HTTPS MCP server listening on https://{bind}");
     eprintln!("MCP endpoint: https://{bind}/mcp");
     eprintln!("Health check: https://{bind}/health");
+    eprintln!("Metrics: https://{bind}/healthz");
     eprintln!();
     eprintln!("Using self-signed certificate. Clients will show security warnings.");
     eprintln!("To trust the certificate, visit https://{bind} in your browser first");
     eprintln!();
     eprintln!("Press Ctrl+C to stop the server");
 
-    // Serve with TLS
-    let server = axum_server::bind_rustls(addr, tls_config).serve(router.into_make_service());
+    // Serve with TLS until the shutdown token is cancelled
+    let executor = ToolExecutor::shared(&config.mcp);
+    let server_handle = axum_server::Handle::new();
+    let mut server = tokio::spawn(
+        axum_server::bind_rustls(addr, tls_config)
+            .handle(server_handle.clone())
+            .serve(router.into_make_service()),
+    );
+    let server_ct = ct.clone();
+    let grace = Duration::from_secs(config.server.shutdown_timeout);
+    tokio::spawn(async move {
+        server_ct.cancelled().await;
+        server_handle.graceful_shutdown(Some(grace));
+    });
 
-    // Handle graceful shutdown
     tokio::select! {
-        result = server => {
-            result?;
+        result = &mut server => {
+            // Server stopped on its own; still let the watchers flush
+            shutdown.run(&executor).await;
+            result??;
         }
-        _ = shutdown_signal() => {
-            eprintln!("Shutting down HTTPS server...");
-            ct.cancel();
+        signal = crate::mcp::shutdown::signal() => {
+            eprintln!("Received {signal}, shutting down HTTPS server...");
+            shutdown.track("https", server);
+            shutdown.run(&executor).await;
         }
     }
 
@@ -367,7 +415,7 @@ async fn oauth_token(body: String) -> axum::Json<serde_json::Value> {
     if grant_type == "authorization_code" && code == "dummy-auth-code" {
         // Return access token WITHOUT refresh token
         axum::Json(serde_json::json!({
-            "access_token": "mcp-access-token-dummy",
+            "access_token": crate::mcp::auth::OAUTH_TOKEN,
             "token_type": "Bearer",
             "expires_in": 3600,
             "scope": "mcp"
@@ -485,15 +533,6 @@ async fn oauth_authorize(
     axum::response::Html(html)
 }
 
-/// Helper function for shutdown signal
-#[cfg(feature = "https-server")]
-async fn shutdown_signal() {
-    tokio::signal::ctrl_c()
-        .await
-        .expect("failed to listen for ctrl+c");
-    eprintln!("Received shutdown signal");
-}
-
 /// Get or create self-signed certificate for HTTPS
 #[cfg(feature = "https-server")]
 async fn get_or_create_certificate(bind: &str) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
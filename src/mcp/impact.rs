@@ -0,0 +1,310 @@
+//! Ranked impact analysis
+//!
+//! Backs the `analyze_impact` MCP tool. Several signals say that code is
+//! affected when a symbol changes: it calls or uses the symbol, implements
+//! or overrides it, imports or re-exports it by name, or depends on it a few
+//! hops away. Each signal carries a fixed confidence, and an entry found by
+//! several signals combines them as independent evidence (`1 - Π(1 - c)`),
+//! so code reached more than one way ranks higher.
+//!
+//! Tests are ranked with the rest and marked: they are what to re-run
+//! rather than what to change. Imports are matched by name, so a file
+//! importing an unrelated symbol of the same name is listed too, at lower
+//! confidence.
+
+use super::test_context::{is_test_path, is_test_symbol};
+use crate::indexing::facade::IndexFacade;
+use crate::{RelationKind, Symbol, SymbolId, SymbolKind};
+use std::collections::{HashMap, HashSet};
+
+/// Tests sharing the changed symbol's name looked up per symbol
+const MAX_NAMED_TESTS: usize = 10;
+
+/// Why a piece of code is considered affected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactReason {
+    /// Calls the changed symbol directly
+    Calls,
+    /// Uses it as a type: fields, parameters, returns, composition
+    Uses,
+    /// Implements or extends it, directly or through a supertype
+    Implements,
+    /// Overrides or implements the changed method
+    Overrides,
+    /// Imports or re-exports a symbol of its name
+    Imports,
+    /// Depends on it through other symbols, this many hops away
+    Transitive(usize),
+    /// A test named after it
+    TestName,
+}
+
+impl ImpactReason {
+    /// Likelihood that code found this way breaks with the change
+    pub fn confidence(self) -> f32 {
+        match self {
+            Self::Calls => 0.95,
+            Self::Implements | Self::Overrides => 0.9,
+            Self::Uses => 0.85,
+            Self::Imports => 0.6,
+            Self::Transitive(depth) => 0.7 * 0.6f32.powi(depth.saturating_sub(2) as i32),
+            Self::TestName => 0.4,
+        }
+    }
+
+    pub fn describe(self) -> String {
+        match self {
+            Self::Calls => "calls it".to_string(),
+            Self::Uses => "uses it".to_string(),
+            Self::Implements => "implements or extends it".to_string(),
+            Self::Overrides => "overrides it".to_string(),
+            Self::Imports => "imports it".to_string(),
+            Self::Transitive(depth) => format!("depends on it {depth} hops away"),
+            Self::TestName => "test named after it".to_string(),
+        }
+    }
+
+    fn for_edge(kind: RelationKind, depth: usize) -> Self {
+        match (depth, kind) {
+            (0 | 1, RelationKind::Calls) => Self::Calls,
+            (0 | 1, RelationKind::Uses) => Self::Uses,
+            (0 | 1, _) => Self::Implements,
+            _ => Self::Transitive(depth),
+        }
+    }
+}
+
+/// Code affected by a change
+#[derive(Debug, Clone)]
+pub struct ImpactEntry {
+    /// The affected symbol, or `None` for a file importing the changed one
+    pub symbol: Option<Symbol>,
+    pub file_path: String,
+    pub reasons: Vec<ImpactReason>,
+    pub is_test: bool,
+}
+
+impl ImpactEntry {
+    /// Combined confidence of all reasons, in `0.0..=1.0`
+    pub fn confidence(&self) -> f32 {
+        let missed: f32 = self
+            .reasons
+            .iter()
+            .map(|reason| 1.0 - reason.confidence())
+            .product();
+        1.0 - missed
+    }
+
+    fn line(&self) -> u32 {
+        self.symbol
+            .as_ref()
+            .map_or(0, |symbol| symbol.range.start_line + 1)
+    }
+}
+
+/// Affected code collected from several signals, one entry per symbol or file
+#[derive(Debug, Default)]
+pub struct ImpactSet {
+    entries: Vec<ImpactEntry>,
+    symbols: HashMap<SymbolId, usize>,
+    files: HashMap<String, usize>,
+}
+
+impl ImpactSet {
+    pub fn add_symbol(&mut self, symbol: Symbol, reason: ImpactReason) {
+        let position = match self.symbols.get(&symbol.id) {
+            Some(&position) => position,
+            None => {
+                self.symbols.insert(symbol.id, self.entries.len());
+                self.entries.push(ImpactEntry {
+                    file_path: symbol.file_path.to_string(),
+                    is_test: is_test_symbol(&symbol),
+                    symbol: Some(symbol),
+                    reasons: Vec::new(),
+                });
+                self.entries.len() - 1
+            }
+        };
+        self.add_reason(position, reason);
+    }
+
+    pub fn add_file(&mut self, path: &str, reason: ImpactReason) {
+        let position = match self.files.get(path) {
+            Some(&position) => position,
+            None => {
+                self.files.insert(path.to_string(), self.entries.len());
+                self.entries.push(ImpactEntry {
+                    symbol: None,
+                    file_path: path.to_string(),
+                    reasons: Vec::new(),
+                    is_test: is_test_path(path),
+                });
+                self.entries.len() - 1
+            }
+        };
+        self.add_reason(position, reason);
+    }
+
+    /// The same reason found again, e.g. from another symbol of a changed
+    /// file, is not counted twice
+    fn add_reason(&mut self, position: usize, reason: ImpactReason) {
+        let reasons = &mut self.entries[position].reasons;
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    }
+
+    /// Entries by confidence, highest first, then by location
+    pub fn ranked(self) -> Vec<ImpactEntry> {
+        let mut entries = self.entries;
+        entries.sort_by(|a, b| {
+            b.confidence()
+                .total_cmp(&a.confidence())
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.line().cmp(&b.line()))
+        });
+        entries
+    }
+}
+
+/// Result of [`analyze`]
+#[derive(Debug, Default)]
+pub struct ImpactReport {
+    pub entries: Vec<ImpactEntry>,
+    /// Symbols whose dependents were counted but not expanded
+    pub hubs: Vec<(SymbolId, usize)>,
+    /// True when the dependency walk stopped at the result limit or timeout
+    pub truncated: bool,
+    pub timed_out: bool,
+}
+
+/// Rank the code affected by changing `changed`, following dependents up to
+/// `max_depth` hops
+///
+/// Code inside `changed` itself is not listed, so analyzing every symbol of
+/// a file reports only what lies outside it.
+pub fn analyze(indexer: &IndexFacade, changed: &[Symbol], max_depth: usize) -> ImpactReport {
+    let settings = indexer.settings();
+    let max_fanout = settings.mcp.max_edges_per_symbol;
+    let max_results = settings.mcp.max_impact_results;
+    let changed_ids: HashSet<SymbolId> = changed.iter().map(|symbol| symbol.id).collect();
+    let changed_files: HashSet<&str> = changed.iter().map(|s| &*s.file_path).collect();
+
+    let mut set = ImpactSet::default();
+    let mut report = ImpactReport::default();
+    let add = |set: &mut ImpactSet, symbol: Symbol, reason: ImpactReason| {
+        if !changed_ids.contains(&symbol.id) {
+            set.add_symbol(symbol, reason);
+        }
+    };
+
+    for target in changed {
+        let radius =
+            indexer.get_impact_radius_bounded(target.id, Some(max_depth), max_fanout, max_results);
+        let reached = radius.symbols.iter().zip(&radius.depths).zip(&radius.kinds);
+        for ((id, depth), kind) in reached {
+            if let Some(symbol) = indexer.get_symbol(*id) {
+                add(&mut set, symbol, ImpactReason::for_edge(*kind, *depth));
+            }
+        }
+        report.hubs.extend(radius.hubs);
+        report.truncated |= radius.truncated;
+        report.timed_out |= radius.timed_out;
+
+        match target.kind {
+            SymbolKind::Method => {
+                for method in indexer.get_overrides(target.id, max_fanout) {
+                    add(&mut set, method, ImpactReason::Overrides);
+                }
+            }
+            SymbolKind::Trait | SymbolKind::Interface | SymbolKind::Class => {
+                for implementor in indexer.get_implementors(target.id, max_fanout) {
+                    add(&mut set, implementor.symbol, ImpactReason::Implements);
+                }
+            }
+            _ => {}
+        }
+
+        for import in indexer.find_imports_of_name(&target.name) {
+            if let Some(path) = indexer.get_file_path(import.file_id) {
+                if !changed_files.contains(path.as_str()) {
+                    set.add_file(&path, ImpactReason::Imports);
+                }
+            }
+        }
+
+        let language = target.language_id.as_ref().map(|id| id.as_str());
+        let name = target.name.to_lowercase();
+        let named = indexer
+            .search(&target.name, MAX_NAMED_TESTS * 4, None, None, language)
+            .unwrap_or_default();
+        for result in named {
+            if !result.name.to_lowercase().contains(&name) {
+                continue;
+            }
+            if let Some(test) = indexer.get_symbol(result.symbol_id) {
+                if is_test_symbol(&test) {
+                    add(&mut set, test, ImpactReason::TestName);
+                }
+            }
+        }
+    }
+
+    report.hubs.sort_by_key(|(id, _)| id.value());
+    report.hubs.dedup_by_key(|(id, _)| *id);
+    report.entries = set.ranked();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range};
+
+    fn symbol(id: u32, name: &str, path: &str) -> Symbol {
+        let mut symbol = Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(id, 0, id + 1, 0),
+        );
+        symbol.file_path = path.into();
+        symbol
+    }
+
+    #[test]
+    fn test_impact_set_combines_and_ranks_reasons() {
+        let mut set = ImpactSet::default();
+        set.add_symbol(symbol(1, "load", "src/a.rs"), ImpactReason::Transitive(3));
+        set.add_symbol(symbol(2, "parse", "src/b.rs"), ImpactReason::Calls);
+        set.add_file("src/lib.rs", ImpactReason::Imports);
+        set.add_symbol(
+            symbol(3, "test_parse", "tests/parse.rs"),
+            ImpactReason::TestName,
+        );
+        // Found again through a transitive path and through a second target
+        set.add_symbol(symbol(1, "load", "src/a.rs"), ImpactReason::Uses);
+        set.add_symbol(symbol(1, "load", "src/a.rs"), ImpactReason::Uses);
+
+        let ranked = set.ranked();
+        let order: Vec<&str> = ranked.iter().map(|e| e.file_path.as_str()).collect();
+        assert_eq!(
+            order,
+            ["src/b.rs", "src/a.rs", "src/lib.rs", "tests/parse.rs"]
+        );
+
+        let load = &ranked[1];
+        assert_eq!(
+            load.reasons,
+            [ImpactReason::Transitive(3), ImpactReason::Uses]
+        );
+        let expected = 1.0 - (1.0 - 0.42) * (1.0 - 0.85);
+        assert!((load.confidence() - expected).abs() < 1e-6);
+        assert!(ranked[1].confidence() < ranked[0].confidence());
+        assert!(ranked[3].is_test && !ranked[2].is_test);
+        assert!(
+            ImpactReason::Transitive(2).confidence() > ImpactReason::Transitive(4).confidence()
+        );
+    }
+}
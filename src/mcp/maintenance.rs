@@ -0,0 +1,447 @@
+//! Load-aware maintenance for `codanna serve`
+//!
+//! A long-running server accumulates work that is cheap to defer but slow to
+//! do while an agent waits on a tool call: every re-index leaves another
+//! Tantivy segment and a set of deleted documents, embeddings of symbols
+//! removed outside the watcher linger in the semantic store, and the event log
+//! only ever grows.
+//!
+//! With `maintenance.enabled`, [`MaintenanceScheduler`] wakes every
+//! `check_interval_secs` and runs each task that has not run for
+//! `interval_hours`, but only while the server is quiet: fewer than `max_load`
+//! tool calls and re-indexed files in the last `load_window_secs`, and inside
+//! `window` if one is set. Last runs are kept per index in `maintenance.json`,
+//! so restarts do not repeat them. `codanna maintenance run` runs tasks right
+//! away regardless of load or window.
+
+use crate::IndexResult;
+use crate::config::{MaintenanceConfig, Settings};
+use crate::indexing::facade::IndexFacade;
+use crate::storage::EventLog;
+use crate::vector::VectorNamespace;
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// File name of the last-run record inside the index directory
+pub const MAINTENANCE_FILE: &str = "maintenance.json";
+
+/// Activity older than this is forgotten, whatever `load_window_secs` says
+const MAX_LOAD_WINDOW: Duration = Duration::from_secs(3600);
+
+static ACTIVITY: OnceLock<LoadMonitor> = OnceLock::new();
+
+/// Work the scheduler can run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTask {
+    /// Merge Tantivy segments and purge deleted documents
+    Compact,
+    /// Drop embeddings of removed symbols and compact the semantic store
+    Vectors,
+    /// Trim index events older than `event_retention_days`
+    Retention,
+}
+
+impl MaintenanceTask {
+    pub const ALL: [MaintenanceTask; 3] = [Self::Compact, Self::Vectors, Self::Retention];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Compact => "compact",
+            Self::Vectors => "vectors",
+            Self::Retention => "retention",
+        }
+    }
+}
+
+impl fmt::Display for MaintenanceTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MaintenanceTask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|task| task.as_str() == s)
+            .ok_or_else(|| {
+                format!("unknown maintenance task '{s}' (expected compact, vectors or retention)")
+            })
+    }
+}
+
+/// Recent tool calls and re-indexed files
+#[derive(Default)]
+pub struct LoadMonitor {
+    events: Mutex<VecDeque<Instant>>,
+}
+
+impl LoadMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one unit of work happening now
+    pub fn record(&self) {
+        let now = Instant::now();
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        while events
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > MAX_LOAD_WINDOW)
+        {
+            events.pop_front();
+        }
+        events.push_back(now);
+    }
+
+    /// Work counted within the last `window`
+    pub fn recent(&self, window: Duration) -> usize {
+        let now = Instant::now();
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        events
+            .iter()
+            .rev()
+            .take_while(|at| now.duration_since(**at) <= window)
+            .count()
+    }
+}
+
+/// Process-wide monitor fed by the tool executor and the file watcher
+pub fn activity() -> &'static LoadMonitor {
+    ACTIVITY.get_or_init(LoadMonitor::new)
+}
+
+/// A daily window in local time; `22:00-06:00` wraps past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    /// Minutes after midnight
+    start: u32,
+    end: u32,
+}
+
+impl TimeWindow {
+    /// Parse `HH:MM-HH:MM`; an empty spec means no window
+    pub fn parse(spec: &str) -> Result<Option<Self>, String> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Ok(None);
+        }
+        let invalid = || format!("invalid maintenance window '{spec}' (expected HH:MM-HH:MM)");
+        let (start, end) = spec.split_once('-').ok_or_else(invalid)?;
+        let start = parse_time(start).ok_or_else(invalid)?;
+        let end = parse_time(end).ok_or_else(invalid)?;
+        Ok(Some(Self { start, end }))
+    }
+
+    /// Whether `minute` (after midnight) falls in the window
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            // Equal bounds cover the whole day
+            self.start == self.end || (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    /// Whether the local time now falls in the window
+    pub fn contains_now(&self) -> bool {
+        let now = chrono::Local::now();
+        self.contains(now.hour() * 60 + now.minute())
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 60,
+            self.start % 60,
+            self.end / 60,
+            self.end % 60
+        )
+    }
+}
+
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// When each task last ran
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceState {
+    /// Unix timestamps of the last successful run
+    #[serde(default)]
+    pub last_run: BTreeMap<MaintenanceTask, u64>,
+}
+
+impl MaintenanceState {
+    /// Load the state of the index at `index_path`, starting empty if missing or unreadable
+    pub fn load(index_path: &Path) -> Self {
+        let path = index_path.join(MAINTENANCE_FILE);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    tracing::debug!(target: "mcp", "cannot read {}: {e}", path.display());
+                }
+                return Self::default();
+            }
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            tracing::debug!(target: "mcp", "ignoring corrupt {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Write the state atomically
+    pub fn save(&self, index_path: &Path) -> io::Result<()> {
+        let path = index_path.join(MAINTENANCE_FILE);
+        let json = serde_json::to_string(self).map_err(io::Error::other)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Whether `task` has not run within `interval_secs` of `now`
+    pub fn is_due(&self, task: MaintenanceTask, now: u64, interval_secs: u64) -> bool {
+        self.last_run
+            .get(&task)
+            .is_none_or(|last| now.saturating_sub(*last) >= interval_secs)
+    }
+}
+
+/// Run one task now and describe what it did
+///
+/// Callers must keep the index from being written meanwhile; the scheduler
+/// holds the facade's write lock.
+pub fn run_task(
+    task: MaintenanceTask,
+    facade: &IndexFacade,
+    config: &MaintenanceConfig,
+) -> IndexResult<String> {
+    let index_path = facade.index_base();
+    match task {
+        MaintenanceTask::Compact => {
+            let stats = facade.compact_index()?;
+            Ok(format!(
+                "{} segment(s) merged into {}, {} deleted document(s) purged",
+                stats.segments_before, stats.segments_after, stats.deleted_docs
+            ))
+        }
+        MaintenanceTask::Vectors => {
+            if !facade.has_semantic_search() {
+                return Ok("skipped, semantic search is not enabled".to_string());
+            }
+            let dropped = facade.prune_semantic_search(&VectorNamespace::Code.dir(index_path))?;
+            Ok(format!(
+                "{dropped} stale embedding(s) dropped, {} kept",
+                facade.semantic_search_embedding_count()
+            ))
+        }
+        MaintenanceTask::Retention => {
+            if config.event_retention_days == 0 {
+                return Ok("skipped, event_retention_days is 0".to_string());
+            }
+            let cutoff = crate::utils::get_utc_timestamp()
+                .saturating_sub(config.event_retention_days * 24 * 60 * 60);
+            // Through the pipeline's log, so pruning waits for its appends
+            let dropped = match facade.event_log() {
+                Some(event_log) => event_log.prune_before(cutoff)?,
+                None => EventLog::new(index_path).prune_before(cutoff)?,
+            };
+            Ok(format!(
+                "{dropped} event(s) older than {} day(s) dropped",
+                config.event_retention_days
+            ))
+        }
+    }
+}
+
+/// Runs due maintenance tasks while the server is idle
+pub struct MaintenanceScheduler {
+    config: MaintenanceConfig,
+    window: Option<TimeWindow>,
+    facade: Arc<RwLock<IndexFacade>>,
+    index_path: PathBuf,
+}
+
+impl MaintenanceScheduler {
+    /// Fails if `config.window` cannot be parsed
+    pub fn new(
+        config: MaintenanceConfig,
+        facade: Arc<RwLock<IndexFacade>>,
+        index_path: PathBuf,
+    ) -> Result<Self, String> {
+        let window = TimeWindow::parse(&config.window)?;
+        Ok(Self {
+            config,
+            window,
+            facade,
+            index_path,
+        })
+    }
+
+    /// Why tasks cannot run right now, if they cannot
+    pub fn blocked(&self) -> Option<String> {
+        if let Some(window) = self.window {
+            if !window.contains_now() {
+                return Some(format!("outside window {window}"));
+            }
+        }
+        let window = Duration::from_secs(self.config.load_window_secs);
+        let load = activity().recent(window);
+        if load >= self.config.max_load {
+            return Some(format!(
+                "load {load} >= {} in the last {}s",
+                self.config.max_load, self.config.load_window_secs
+            ));
+        }
+        None
+    }
+
+    /// Check for due tasks every `check_interval_secs` until cancelled
+    pub async fn run(self, ct: CancellationToken) {
+        let interval = Duration::from_secs(self.config.check_interval_secs.max(1));
+        crate::log_event!(
+            "maintenance",
+            "scheduled",
+            "every {}h, checked every {}s",
+            self.config.interval_hours,
+            interval.as_secs()
+        );
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => self.run_due().await,
+                _ = ct.cancelled() => return,
+            }
+        }
+    }
+
+    async fn run_due(&self) {
+        let mut state = MaintenanceState::load(&self.index_path);
+        let interval_secs = self.config.interval_hours * 60 * 60;
+        let now = crate::utils::get_utc_timestamp();
+        let due: Vec<MaintenanceTask> = MaintenanceTask::ALL
+            .into_iter()
+            .filter(|task| state.is_due(*task, now, interval_secs))
+            .collect();
+
+        for task in due {
+            // Checked before every task; a burst of calls may have started
+            if let Some(reason) = self.blocked() {
+                crate::debug_event!("maintenance", "deferred", "{task}: {reason}");
+                return;
+            }
+
+            // Compaction and vector pruning block for seconds; keep them off
+            // the runtime while the owned guard holds off writers
+            let facade = self.facade.clone().write_owned().await;
+            let config = self.config.clone();
+            let started = Instant::now();
+            let result = tokio::task::spawn_blocking(move || run_task(task, &facade, &config))
+                .await
+                .unwrap_or_else(|e| Err(crate::IndexError::General(e.to_string())));
+            match result {
+                Ok(summary) => {
+                    crate::log_event!(
+                        "maintenance",
+                        task.as_str(),
+                        "{summary} in {:.1}s",
+                        started.elapsed().as_secs_f64()
+                    );
+                    state
+                        .last_run
+                        .insert(task, crate::utils::get_utc_timestamp());
+                    if let Err(e) = state.save(&self.index_path) {
+                        tracing::warn!("[maintenance] failed to save {MAINTENANCE_FILE}: {e}");
+                    }
+                }
+                Err(e) => tracing::warn!("[maintenance] {task} failed: {e}"),
+            }
+        }
+    }
+}
+
+/// Start the scheduler if `maintenance.enabled`; it stops with `ct`
+pub fn spawn(
+    settings: &Settings,
+    facade: Arc<RwLock<IndexFacade>>,
+    index_path: PathBuf,
+    ct: CancellationToken,
+) {
+    if !settings.maintenance.enabled {
+        return;
+    }
+    match MaintenanceScheduler::new(settings.maintenance.clone(), facade, index_path) {
+        Ok(scheduler) => {
+            tokio::spawn(scheduler.run(ct));
+        }
+        Err(e) => tracing::warn!("[maintenance] not scheduled: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_window_parsing_and_wrap() {
+        assert_eq!(TimeWindow::parse("").unwrap(), None);
+        assert!(TimeWindow::parse("25:00-01:00").is_err());
+        assert!(TimeWindow::parse("nightly").is_err());
+
+        let day = TimeWindow::parse("09:30-17:00").unwrap().unwrap();
+        assert!(day.contains(9 * 60 + 30));
+        assert!(!day.contains(17 * 60));
+
+        let night = TimeWindow::parse("22:00-06:00").unwrap().unwrap();
+        assert!(night.contains(23 * 60));
+        assert!(night.contains(60));
+        assert!(!night.contains(12 * 60));
+        assert_eq!(night.to_string(), "22:00-06:00");
+    }
+
+    #[test]
+    fn test_load_monitor_counts_recent_activity() {
+        let monitor = LoadMonitor::new();
+        monitor.record();
+        monitor.record();
+        assert_eq!(monitor.recent(Duration::from_secs(60)), 2);
+    }
+
+    #[test]
+    fn test_state_round_trip_and_due() {
+        let temp = TempDir::new().unwrap();
+        let mut state = MaintenanceState::load(temp.path());
+        assert!(state.is_due(MaintenanceTask::Compact, 1000, 3600));
+
+        state.last_run.insert(MaintenanceTask::Compact, 1000);
+        state.save(temp.path()).unwrap();
+
+        let state = MaintenanceState::load(temp.path());
+        assert!(!state.is_due(MaintenanceTask::Compact, 2000, 3600));
+        assert!(state.is_due(MaintenanceTask::Compact, 4600, 3600));
+        assert!(state.is_due(MaintenanceTask::Retention, 2000, 3600));
+        assert_eq!(
+            "vectors".parse::<MaintenanceTask>(),
+            Ok(MaintenanceTask::Vectors)
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Tool call metrics and the server health report
+//!
+//! The tool executor times every call and counts how it ended, per tool, in
+//! a process-wide [`ToolMetrics`]. [`HealthReport`] puts those counts next
+//! to the state of the index (its size and when it was last written) and the
+//! memory of the process, for operating a server shared by a team. It is
+//! served as the `codanna://health` resource on every transport and as
+//! `GET /healthz` on the HTTP and HTTPS transports.
+//!
+//! Counts start when the process starts; percentiles cover the most recent
+//! calls of each tool only.
+
+use crate::indexing::facade::IndexFacade;
+use crate::indexing::pipeline::metrics::MemorySnapshot;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Latencies kept per tool for percentiles
+const LATENCY_SAMPLES: usize = 256;
+
+static METRICS: OnceLock<ToolMetrics> = OnceLock::new();
+
+/// How a tool call ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallOutcome {
+    Success,
+    /// The tool reported an error, or the call was refused or failed
+    Error,
+    /// The client cancelled the call or went away
+    Cancelled,
+}
+
+#[derive(Debug, Default)]
+struct ToolStats {
+    calls: u64,
+    errors: u64,
+    cancelled: u64,
+    total: Duration,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+/// Call counts and latencies per tool
+pub struct ToolMetrics {
+    started: Instant,
+    tools: Mutex<HashMap<String, ToolStats>>,
+}
+
+impl Default for ToolMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolMetrics {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            tools: Mutex::default(),
+        }
+    }
+
+    /// Count one call of `tool` that took `elapsed`, queueing included
+    pub fn record(&self, tool: &str, elapsed: Duration, outcome: CallOutcome) {
+        let mut tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = tools.entry(tool.to_string()).or_default();
+        stats.calls += 1;
+        match outcome {
+            CallOutcome::Success => {}
+            CallOutcome::Error => stats.errors += 1,
+            CallOutcome::Cancelled => stats.cancelled += 1,
+        }
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+        if stats.recent.len() == LATENCY_SAMPLES {
+            stats.recent.pop_front();
+        }
+        stats.recent.push_back(elapsed);
+    }
+
+    /// Time since the metrics started counting
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Summary of every tool called so far, by name
+    pub fn snapshot(&self) -> BTreeMap<String, ToolSummary> {
+        let tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        tools
+            .iter()
+            .map(|(tool, stats)| (tool.clone(), ToolSummary::of(stats)))
+            .collect()
+    }
+}
+
+/// Process-wide metrics fed by the tool executor
+pub fn tool_metrics() -> &'static ToolMetrics {
+    METRICS.get_or_init(ToolMetrics::new)
+}
+
+/// Calls and latencies of one tool, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolSummary {
+    pub calls: u64,
+    pub errors: u64,
+    pub cancelled: u64,
+    pub mean_ms: f64,
+    /// Median of the recent calls
+    pub p50_ms: f64,
+    /// 95th percentile of the recent calls
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl ToolSummary {
+    fn of(stats: &ToolStats) -> Self {
+        let mut recent: Vec<Duration> = stats.recent.iter().copied().collect();
+        recent.sort_unstable();
+        let percentile = |p: usize| {
+            recent
+                .get((recent.len() * p / 100).min(recent.len().saturating_sub(1)))
+                .map_or(0.0, |d| millis(*d))
+        };
+        Self {
+            calls: stats.calls,
+            errors: stats.errors,
+            cancelled: stats.cancelled,
+            mean_ms: millis(stats.total) / stats.calls.max(1) as f64,
+            p50_ms: percentile(50),
+            p95_ms: percentile(95),
+            max_ms: millis(stats.max),
+        }
+    }
+}
+
+/// Health of the server, as served by `codanna://health` and `/healthz`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// `ok`, or `empty` while the index has no files
+    pub status: &'static str,
+    pub version: &'static str,
+    pub uptime_secs: u64,
+    pub index: IndexHealth,
+    pub memory: MemoryHealth,
+    pub tools: BTreeMap<String, ToolSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexHealth {
+    pub symbols: usize,
+    pub files: usize,
+    /// UTC seconds since the epoch of the last index commit
+    pub updated_at: Option<u64>,
+    /// Seconds since the last index commit
+    pub age_secs: Option<u64>,
+    pub embeddings: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryHealth {
+    pub rss_bytes: u64,
+    pub virtual_bytes: u64,
+}
+
+impl HealthReport {
+    pub fn collect(indexer: &IndexFacade, metrics: &ToolMetrics) -> Self {
+        let files = indexer.file_count() as usize;
+        // Tantivy rewrites meta.json on every commit
+        let updated = std::fs::metadata(indexer.index_base().join("tantivy").join("meta.json"))
+            .and_then(|meta| meta.modified())
+            .ok();
+        let memory = MemorySnapshot::current();
+        Self {
+            status: if files == 0 { "empty" } else { "ok" },
+            version: env!("CARGO_PKG_VERSION"),
+            uptime_secs: metrics.uptime().as_secs(),
+            index: IndexHealth {
+                symbols: indexer.symbol_count(),
+                files,
+                updated_at: updated
+                    .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+                    .map(|since| since.as_secs()),
+                age_secs: updated
+                    .and_then(|at| SystemTime::now().duration_since(at).ok())
+                    .map(|age| age.as_secs()),
+                embeddings: indexer
+                    .get_semantic_metadata()
+                    .map(|metadata| metadata.embedding_count),
+            },
+            memory: MemoryHealth {
+                rss_bytes: memory.rss,
+                virtual_bytes: memory.virtual_mem,
+            },
+            tools: metrics.snapshot(),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_metrics_summarize_calls() {
+        let metrics = ToolMetrics::new();
+        for ms in 1..=100 {
+            metrics.record(
+                "find_symbol",
+                Duration::from_millis(ms),
+                CallOutcome::Success,
+            );
+        }
+        metrics.record(
+            "analyze_impact",
+            Duration::from_millis(40),
+            CallOutcome::Error,
+        );
+        metrics.record(
+            "analyze_impact",
+            Duration::from_millis(20),
+            CallOutcome::Cancelled,
+        );
+
+        let snapshot = metrics.snapshot();
+        let tools: Vec<&str> = snapshot.keys().map(String::as_str).collect();
+        assert_eq!(tools, ["analyze_impact", "find_symbol"]);
+
+        let find = &snapshot["find_symbol"];
+        assert_eq!(find.calls, 100);
+        assert_eq!(find.errors, 0);
+        assert!((find.mean_ms - 50.5).abs() < 1e-6);
+        assert!((find.p50_ms - 51.0).abs() < 1e-6);
+        assert!((find.p95_ms - 96.0).abs() < 1e-6);
+        assert!((find.max_ms - 100.0).abs() < 1e-6);
+
+        let impact = &snapshot["analyze_impact"];
+        assert_eq!((impact.calls, impact.errors, impact.cancelled), (2, 1, 1));
+        assert!((impact.mean_ms - 30.0).abs() < 1e-6);
+    }
+}
@@ -17,10 +17,30 @@
 //!    - Direct access to already-loaded index
 //!    - Most memory efficient for CLI operations
 
+pub mod auth;
 pub mod client;
+pub mod clones;
+pub mod daemon;
+pub mod executor;
 pub mod http_server;
 pub mod https_server;
+pub mod impact;
+pub mod maintenance;
+pub mod metrics;
 pub mod notifications;
+pub mod pagination;
+pub mod permissions;
+pub mod projects;
+pub mod prompts;
+pub mod rate_limit;
+pub mod reindex;
+pub mod resources;
+pub mod session;
+pub mod shutdown;
+pub mod tcp_server;
+pub mod test_context;
+pub mod unused;
+pub mod warm_set;
 
 use rmcp::{
     ServerHandler,
@@ -28,7 +48,7 @@ use rmcp::{
     model::{CustomNotification, CustomRequest, CustomResult, ErrorCode, ErrorData as McpError, *},
     schemars,
     service::{Peer, RequestContext, RoleServer, ServiceError},
-    tool, tool_handler, tool_router,
+    tool, tool_router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -36,8 +56,40 @@ use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
 use crate::documents::{DocumentStore, SearchQuery as DocSearchQuery};
-use crate::indexing::facade::IndexFacade;
+use crate::indexing::facade::{IndexFacade, SemanticFilter};
+use crate::indexing::pipeline::PipelineCancel;
 use crate::{Settings, Symbol};
+use executor::ToolExecutor;
+use pagination::PageStore;
+use permissions::ToolPolicy;
+use projects::ProjectRouter;
+use prompts::{Step, WorkflowPrompt};
+use resources::IndexResource;
+use session::SessionContext;
+use warm_set::{ToolUsage, WARM_START_CAPABILITY, WarmSetTracker};
+
+/// Most sub-queries one `batch_query` call accepts
+const MAX_BATCH_QUERIES: usize = 20;
+
+/// Tools `batch_query` can run; its tool description lists them too
+const BATCH_TOOLS: &[&str] = &[
+    "find_symbol",
+    "get_calls",
+    "find_callers",
+    "find_implementations",
+    "analyze_impact",
+    "search_symbols",
+    "semantic_search_docs",
+    "semantic_search_with_context",
+    "get_file_outline",
+    "get_dependency_graph",
+    "get_symbol_source",
+    "find_similar_symbols",
+    "find_unused_symbols",
+];
+
+/// Source lines shown per semantic search hit
+const SNIPPET_LINES: usize = 4;
 
 /// Generate guidance for MCP tool responses
 fn generate_mcp_guidance(settings: &Settings, tool: &str, result_count: usize) -> Option<String> {
@@ -45,6 +97,57 @@ fn generate_mcp_guidance(settings: &Settings, tool: &str, result_count: usize) -
     generate_guidance_from_config(&settings.guidance, tool, None, result_count)
 }
 
+/// Append callers as an indented tree, one level per `<-`
+fn format_caller_nodes(
+    out: &mut String,
+    nodes: &[crate::indexing::CallerNode],
+    omitted: usize,
+    level: usize,
+) {
+    let indent = "  ".repeat(level);
+    for node in nodes {
+        let caller = &node.symbol;
+        let line = node.call_line.unwrap_or(caller.range.start_line) + 1;
+        let note = if node.repeated { " (see above)" } else { "" };
+        out.push_str(&format!(
+            "{indent}<- {:?} {} at {}:{line} [symbol_id:{}]{note}\n",
+            caller.kind,
+            caller.name,
+            caller.file_path,
+            caller.id.value()
+        ));
+        format_caller_nodes(out, &node.callers, node.omitted, level + 1);
+    }
+    if omitted > 0 {
+        out.push_str(&format!("{indent}... {omitted} more caller(s)\n"));
+    }
+}
+
+/// Append outline nodes, one symbol per line, indented by nesting level
+fn format_outline_nodes(
+    out: &mut String,
+    nodes: &[crate::symbol::outline::OutlineNode],
+    level: usize,
+) {
+    let indent = "  ".repeat(level);
+    for node in nodes {
+        let symbol = &node.symbol;
+        out.push_str(&format!(
+            "{indent}{:?} {} L{}-{} [symbol_id:{}]",
+            symbol.kind,
+            symbol.name,
+            symbol.range.start_line + 1,
+            symbol.range.end_line + 1,
+            symbol.id.value()
+        ));
+        if let Some(summary) = node.summary() {
+            out.push_str(&format!(" - {summary}"));
+        }
+        out.push('\n');
+        format_outline_nodes(out, &node.children, level + 1);
+    }
+}
+
 /// Format a Unix timestamp as relative time (e.g., "2 hours ago")
 pub fn format_relative_time(timestamp: u64) -> String {
     use chrono::{DateTime, Utc};
@@ -98,6 +201,30 @@ pub struct FindCallersRequest {
     /// Symbol ID for direct lookup (recommended to avoid ambiguity)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol_id: Option<u32>,
+    /// Number of callers to skip, for paging through heavily-called functions;
+    /// only valid with depth 1 (default: 0)
+    #[serde(default)]
+    pub offset: u32,
+    /// Maximum callers to return (default: mcp.max_edges_per_symbol)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Levels of callers to follow; above 1 returns a call tree of callers of
+    /// callers, with `limit` callers per function (default: 1)
+    #[serde(default = "default_caller_depth")]
+    pub depth: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FindImplementationsRequest {
+    /// Name of the trait, interface, class or method (use symbol_id for unambiguous lookup)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// Filter name lookup by programming language (e.g., "rust", "java")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -108,11 +235,49 @@ pub struct AnalyzeImpactRequest {
     /// Symbol ID for direct lookup (recommended to avoid ambiguity)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol_id: Option<u32>,
+    /// File to analyze instead of one symbol: every symbol in it counts as changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
     /// Maximum depth to search (default: 3)
     #[serde(default = "default_depth")]
     pub max_depth: u32,
 }
 
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct TestContextForRequest {
+    /// Name of the symbol to write a test for (use symbol_id for unambiguous lookup)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// Maximum existing tests to list (default: 5)
+    #[serde(default = "default_context_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetSymbolSourceRequest {
+    /// Name of the symbol (use symbol_id for unambiguous lookup)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// Lines shown before and after the symbol (default: 3)
+    #[serde(default = "default_source_context")]
+    pub context: u32,
+    /// Most lines of the symbol itself (default: 300)
+    #[serde(default = "default_source_lines")]
+    pub max_lines: u32,
+    /// Include the file's leading docs and imports (default: true)
+    #[serde(default = "default_true")]
+    pub header: bool,
+    /// List signatures of the other symbols in the same scope (default: true)
+    #[serde(default = "default_true")]
+    pub siblings: bool,
+}
+
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct SearchSymbolsRequest {
     /// Search query (supports fuzzy matching)
@@ -129,6 +294,15 @@ pub struct SearchSymbolsRequest {
     /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lang: Option<String>,
+    /// Filter by repository name from `[repositories]` in settings.toml
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Filter by workspace package (Cargo crate, npm package or Go module)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Filter by top-level directory shard (e.g., "src", "tests"; "." for root files)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -144,6 +318,49 @@ pub struct SemanticSearchRequest {
     /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lang: Option<String>,
+    /// "hybrid" (default) also matches exact identifiers by full-text search;
+    /// "semantic" ranks by documentation similarity only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(flatten)]
+    pub filters: SemanticSearchFilters,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct TranslateQueryRequest {
+    /// Natural-language request, e.g. "public async functions in the auth
+    /// module that touch the database"
+    pub query: String,
+}
+
+/// Filters shared by the semantic search tools, applied to every hit
+#[derive(Debug, Default, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SemanticSearchFilters {
+    /// Filter by symbol kind (e.g., "Function", "Method", "Struct", "Trait")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Filter by file path glob (e.g., "src/parsing/**", "**/*_test.go")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Filter by visibility: "public", "crate", "module" or "private"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// Filter by repository name from `[repositories]` in settings.toml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+}
+
+impl SemanticSearchFilters {
+    /// Facade filter for these filters and a language, or what is invalid
+    fn resolve(self, lang: Option<String>) -> Result<SemanticFilter, String> {
+        SemanticFilter::parse(
+            lang,
+            self.kind.as_deref(),
+            self.path.as_deref(),
+            self.visibility.as_deref(),
+            self.repo,
+        )
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -159,11 +376,16 @@ pub struct SemanticSearchWithContextRequest {
     /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lang: Option<String>,
+    #[serde(flatten)]
+    pub filters: SemanticSearchFilters,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct GetIndexInfoRequest {}
 
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ListProjectsRequest {}
+
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct SearchDocumentsRequest {
     /// Natural language search query
@@ -176,10 +398,168 @@ pub struct SearchDocumentsRequest {
     pub limit: u32,
 }
 
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SearchKnowledgeRequest {
+    /// Natural language search query
+    pub query: String,
+    /// Search only this document collection (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    /// Maximum number of results (default: 5)
+    #[serde(default = "default_context_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FindDiscussionsRequest {
+    /// Symbol name (e.g. "ContextWatcher::run" or "run") or file path
+    pub query: String,
+    /// Maximum number of messages (default: 10)
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetDiagnosticsRequest {
+    /// Only report files whose path contains this (optional)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Maximum number of files (default: 10)
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetFileOutlineRequest {
+    /// Path of the file, as indexed, absolute, or a unique suffix like "parsing/parser.rs"
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetDependencyGraphRequest {
+    /// File or directory to start from, as indexed or absolute (default: the whole index)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Workspace package to start from instead of a path (Cargo crate, npm package or Go module)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Hops to follow from the starting files (default: 1, 0 for edges among them only)
+    #[serde(default = "default_graph_depth")]
+    pub depth: u32,
+    /// "dependencies" (default), "dependents" or "both"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+    /// Maximum number of files in the graph (default: 200)
+    #[serde(default = "default_graph_nodes")]
+    pub max_nodes: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FindSimilarSymbolsRequest {
+    /// Name of the symbol to compare with (use symbol_id for unambiguous lookup)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol ID to compare with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<u32>,
+    /// Code or a description of it, instead of a symbol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Minimum similarity score (0-1, default: calibrated for the model, else 0.8)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    /// Maximum number of results (default: 10)
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FindUnusedSymbolsRequest {
+    /// File or directory to search, as indexed or absolute (default: the whole index)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Workspace package to search instead of a path (Cargo crate, npm package or Go module)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Only symbols of this kind (e.g., "Function", "Struct"; default: functions, methods, types and constants)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Also list public symbols, which code outside the index may use (default: false)
+    #[serde(default)]
+    pub include_public: bool,
+    /// Also list tests and symbols in test files (default: false)
+    #[serde(default)]
+    pub include_tests: bool,
+    /// Also list entry points: main, lifecycle hooks, dunder methods and trait method implementations (default: false)
+    #[serde(default)]
+    pub include_entry_points: bool,
+    /// Maximum number of results (default: 50)
+    #[serde(default = "default_unused_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct RecentContextRequest {
+    /// Only symbols of this kind (e.g., "Function", "Method", "Struct")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Maximum number of symbols (default: 10)
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ReindexPathsRequest {
+    /// Files or directories to re-index, relative to the workspace root or absolute
+    pub paths: Vec<String>,
+    /// Parse files again even when their content is unchanged (default: false)
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct NextPageRequest {
+    /// Cursor from the end of the previous page
+    pub cursor: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct BatchQueryRequest {
+    /// Sub-queries to run concurrently, at most 20
+    pub queries: Vec<BatchSubQuery>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct BatchSubQuery {
+    /// Key naming this sub-query's result (defaults to its position, from 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Tool to run, e.g. "find_symbol", "find_callers", "semantic_search_docs"
+    pub tool: String,
+    /// Arguments of the tool, as for a direct call
+    #[serde(default)]
+    pub arguments: serde_json::Map<String, serde_json::Value>,
+}
+
 fn default_depth() -> u32 {
     3
 }
 
+fn default_graph_depth() -> u32 {
+    1
+}
+
+fn default_graph_nodes() -> u32 {
+    200
+}
+
+fn default_caller_depth() -> u32 {
+    1
+}
+
 fn default_limit() -> u32 {
     10
 }
@@ -188,42 +568,109 @@ fn default_context_limit() -> u32 {
     5
 }
 
+fn default_source_context() -> u32 {
+    3
+}
+
+fn default_source_lines() -> u32 {
+    300
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_similarity_threshold() -> f32 {
+    0.8
+}
+
+fn default_unused_limit() -> u32 {
+    50
+}
+
 #[derive(Clone)]
 pub struct CodeIntelligenceServer {
     pub facade: Arc<RwLock<IndexFacade>>,
     pub document_store: Option<Arc<RwLock<DocumentStore>>>,
     tool_router: ToolRouter<Self>,
     peer: Arc<Mutex<Option<Peer<RoleServer>>>>,
+    executor: Arc<ToolExecutor>,
+    /// Cancels the force-reindex in progress, if any
+    reindexing: Arc<std::sync::Mutex<Option<PipelineCancel>>>,
+    /// Resource URIs this session subscribed to
+    subscriptions: Arc<Mutex<std::collections::HashSet<String>>>,
+    /// Remaining pages of large tool results
+    pages: Arc<PageStore>,
+    /// Indexes of other registered projects, opened on demand
+    projects: Arc<ProjectRouter>,
+    /// Tools clients may see and call, from `[mcp.permissions]`
+    permissions: Arc<ToolPolicy>,
+    /// Symbols and files this session looked up recently
+    session: Arc<std::sync::Mutex<SessionContext>>,
+    /// Rate limit key of the connection, for transports with one per client
+    connection: Option<Arc<str>>,
 }
 
 #[tool_router]
 impl CodeIntelligenceServer {
     pub fn new(facade: IndexFacade) -> Self {
+        let executor = ToolExecutor::shared(&facade.settings().mcp);
+        let projects = ProjectRouter::shared(&facade.settings().mcp);
+        let permissions = ToolPolicy::from_config(&facade.settings().mcp.permissions);
         Self {
             facade: Arc::new(RwLock::new(facade)),
             document_store: None,
             tool_router: Self::tool_router(),
             peer: Arc::new(Mutex::new(None)),
+            executor,
+            reindexing: Arc::default(),
+            subscriptions: Arc::default(),
+            pages: Arc::default(),
+            projects,
+            permissions: Arc::new(permissions),
+            session: Arc::default(),
+            connection: None,
         }
     }
 
     /// Create server from an already-loaded facade (most efficient)
     pub fn from_facade(facade: Arc<RwLock<IndexFacade>>) -> Self {
+        // The facade is normally uncontended at startup; fall back to defaults if not
+        let mcp_config = facade
+            .try_read()
+            .map(|f| f.settings().mcp.clone())
+            .unwrap_or_default();
         Self {
             facade,
             document_store: None,
             tool_router: Self::tool_router(),
             peer: Arc::new(Mutex::new(None)),
+            executor: ToolExecutor::shared(&mcp_config),
+            reindexing: Arc::default(),
+            subscriptions: Arc::default(),
+            pages: Arc::default(),
+            projects: ProjectRouter::shared(&mcp_config),
+            permissions: Arc::new(ToolPolicy::from_config(&mcp_config.permissions)),
+            session: Arc::default(),
+            connection: None,
         }
     }
 
     /// Create server with existing facade and settings (for HTTP server)
-    pub fn new_with_facade(facade: Arc<RwLock<IndexFacade>>, _settings: Arc<Settings>) -> Self {
+    pub fn new_with_facade(facade: Arc<RwLock<IndexFacade>>, settings: Arc<Settings>) -> Self {
         Self {
             facade,
             document_store: None,
             tool_router: Self::tool_router(),
             peer: Arc::new(Mutex::new(None)),
+            executor: ToolExecutor::shared(&settings.mcp),
+            reindexing: Arc::default(),
+            subscriptions: Arc::default(),
+            pages: Arc::default(),
+            projects: ProjectRouter::shared(&settings.mcp),
+            permissions: Arc::new(ToolPolicy::from_config(&settings.mcp.permissions)),
+            session: Arc::default(),
+            connection: None,
         }
     }
 
@@ -233,11 +680,23 @@ impl CodeIntelligenceServer {
         self
     }
 
+    /// Count this session's tool calls under `key` rather than as the stdio client
+    pub fn with_connection(mut self, key: String) -> Self {
+        self.connection = Some(key.into());
+        self
+    }
+
     /// Get a reference to the facade Arc for external management (e.g., hot-reload)
     pub fn get_facade_arc(&self) -> Arc<RwLock<IndexFacade>> {
         self.facade.clone()
     }
 
+    /// Cancel the force-reindex in progress; returns whether one was running
+    pub fn cancel_reindex(&self) -> bool {
+        let slot = self.reindexing.lock().unwrap_or_else(|e| e.into_inner());
+        slot.as_ref().inspect(|cancel| cancel.cancel()).is_some()
+    }
+
     /// Send a notification when a file is re-indexed
     pub async fn notify_file_reindexed(&self, file_path: &str) {
         let peer_guard = self.peer.lock().await;
@@ -300,11 +759,20 @@ impl CodeIntelligenceServer {
         }
 
         let mut result = format!("Found {} symbol(s) named '{}':\n\n", symbols.len(), name);
+        let diagnostics = indexer.get_parse_diagnostics().unwrap_or_default();
+        let blames = indexer.get_symbol_blames().unwrap_or_default();
+        const LOW_CONFIDENCE: &str =
+            "Confidence: low (overlaps a parse error, see get_diagnostics)\n";
 
         for (idx, symbol) in symbols.iter().enumerate() {
             if idx > 0 {
                 result.push_str("\n---\n\n");
             }
+            let low_confidence = crate::storage::DiagnosticsStore::is_low_confidence(
+                &diagnostics,
+                &symbol.file_path,
+                &symbol.range,
+            );
 
             // Try to get full context with all relationship types
             if let Some(ctx) = indexer.get_symbol_context(
@@ -318,6 +786,9 @@ impl CodeIntelligenceServer {
                 // Use formatted output from context
                 result.push_str(&ctx.format_location_with_type());
                 result.push('\n');
+                if low_confidence {
+                    result.push_str(LOW_CONFIDENCE);
+                }
 
                 // Add module path if available
                 if let Some(module) = symbol.as_module_path() {
@@ -340,6 +811,14 @@ impl CodeIntelligenceServer {
                     result.push_str(&format!("Documentation: {preview}\n"));
                 }
 
+                // Add git provenance, recorded when indexing.blame is on
+                if let Some(blame) = crate::storage::BlameStore::find(&blames, symbol) {
+                    let introduced = blame.introduced.describe();
+                    let last_modified = blame.last_modified.describe();
+                    result.push_str(&format!("Introduced: {introduced}\n"));
+                    result.push_str(&format!("Last modified: {last_modified}\n"));
+                }
+
                 // Add relationship summary
                 let mut has_relationships = false;
 
@@ -472,6 +951,9 @@ impl CodeIntelligenceServer {
                     symbol.file_path,
                     symbol.range.start_line + 1
                 ));
+                if low_confidence {
+                    result.push_str(LOW_CONFIDENCE);
+                }
 
                 if let Some(ref doc) = symbol.doc_comment {
                     let doc_preview: Vec<&str> = doc.lines().take(3).collect();
@@ -630,6 +1112,15 @@ impl CodeIntelligenceServer {
                 "  -> {:?} {} at {}:{}\n",
                 callee.kind, call_display, callee.file_path, call_line
             ));
+            if let Some((type_name, confidence)) = metadata
+                .as_ref()
+                .and_then(|m| m.context.as_deref())
+                .and_then(crate::parsing::type_hints::inferred_type)
+            {
+                result.push_str(&format!(
+                    "     Receiver type: {type_name} (inferred, confidence {confidence:.2})\n"
+                ));
+            }
             if let Some(ref sig) = callee.signature {
                 result.push_str(&format!("     Signature: {sig}\n"));
             }
@@ -647,15 +1138,24 @@ impl CodeIntelligenceServer {
     }
 
     #[tool(
-        description = "Find functions that CALL a given function (invoke it with parentheses).\n\nShows: what calls → function_name()\nDoes NOT show: Type references, component rendering, or what this function calls.\n\nUse analyze_impact for: Complete dependency graph including type usage and composition."
+        description = "Find functions that CALL a given function (invoke it with parentheses).\n\nShows: what calls → function_name(); with depth > 1, a tree of callers of callers with call-site locations\nDoes NOT show: Type references, component rendering, or what this function calls.\n\nUse analyze_impact for: Complete dependency graph including type usage and composition."
     )]
     pub async fn find_callers(
         &self,
         Parameters(FindCallersRequest {
             function_name,
             symbol_id,
+            offset,
+            limit,
+            depth,
         }): Parameters<FindCallersRequest>,
     ) -> Result<CallToolResult, McpError> {
+        if depth > 1 && offset > 0 {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "offset pages through direct callers and cannot be combined with depth > 1"
+                    .to_string(),
+            )]));
+        }
         let indexer = self.facade.read().await;
 
         // Get the symbol either by ID or by name
@@ -710,11 +1210,58 @@ impl CodeIntelligenceServer {
             )]));
         };
 
-        // Get callers for THIS SPECIFIC symbol only (no aggregation)
-        let all_callers_with_metadata = indexer.get_calling_functions_with_metadata(symbol.id);
+        // Get callers for THIS SPECIFIC symbol only (no aggregation), one page at a time
+        let limit = limit
+            .map(|l| l as usize)
+            .unwrap_or(indexer.settings().mcp.max_edges_per_symbol);
+
+        if depth > 1 {
+            let tree = indexer.get_caller_tree(
+                symbol.id,
+                depth as usize,
+                limit,
+                indexer.settings().mcp.max_impact_results,
+            );
+            let mut result = if tree.callers.is_empty() {
+                format!("No functions call {identifier}\n")
+            } else {
+                format!(
+                    "Call tree of {identifier} ({} caller(s), depth {depth}):\n",
+                    tree.listed
+                )
+            };
+            format_caller_nodes(&mut result, &tree.callers, tree.omitted, 1);
+            if tree.timed_out {
+                result.push_str("\nStopped at query timeout; the tree is incomplete\n");
+            } else if tree.truncated {
+                result.push_str("\nStopped at mcp.max_impact_results; the tree is incomplete\n");
+            }
+            if let Some(guidance) =
+                generate_mcp_guidance(indexer.settings(), "find_callers", tree.listed)
+            {
+                result.push_str("\n---\n💡 ");
+                result.push_str(&guidance);
+                result.push('\n');
+            }
+            return Ok(CallToolResult::success(vec![Content::text(result)]));
+        }
+        let page = indexer.get_calling_functions_page(symbol.id, offset as usize, limit);
+
+        if page.items.is_empty() && page.skipped == 0 && page.total > 0 {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "{} function(s) call {identifier}; offset {offset} is past the end",
+                page.total
+            ))]));
+        }
 
-        if all_callers_with_metadata.is_empty() {
+        if page.items.is_empty() && !page.is_truncated() {
             let mut output = format!("No functions call {identifier}");
+            if page.skipped > 0 {
+                output.push_str(&format!(
+                    "\n{} caller(s) are no longer in the index; re-index to drop them",
+                    page.skipped
+                ));
+            }
             // Add guidance for no results
             if let Some(guidance) = generate_mcp_guidance(indexer.settings(), "find_callers", 0) {
                 output.push_str("\n\n---\n💡 ");
@@ -724,11 +1271,22 @@ impl CodeIntelligenceServer {
             return Ok(CallToolResult::success(vec![Content::text(output)]));
         }
 
-        // Build structured text response with rich metadata
-        let result_count = all_callers_with_metadata.len();
-        let mut result = format!("{result_count} function(s) call {identifier}:\n");
+        // Build structured text response with rich metadata. Callers that no
+        // longer load are only known within this page
+        let result_count = page.total - page.skipped;
+        let mut result = if page.is_truncated() || page.offset > 0 {
+            format!(
+                "{result_count} function(s) call {identifier} (showing {}-{}):\n",
+                page.offset + 1,
+                page.end()
+            )
+        } else {
+            format!("{result_count} function(s) call {identifier}:\n")
+        };
+        let next_offset = page.next_offset();
+        let (skipped, page_total) = (page.skipped, page.total);
 
-        for (caller, metadata) in all_callers_with_metadata {
+        for (caller, metadata) in page.items {
             // Parse metadata to extract receiver info and call site location
             let (call_info, call_line) = if let Some(ref meta) = metadata {
                 let info = if let Some(context) = &meta.context {
@@ -777,12 +1335,34 @@ impl CodeIntelligenceServer {
                 "  <- {:?} {} at {}:{}{}\n",
                 caller.kind, caller.name, caller.file_path, call_line, call_info
             ));
+            if let Some((type_name, confidence)) = metadata
+                .as_ref()
+                .and_then(|m| m.context.as_deref())
+                .and_then(crate::parsing::type_hints::inferred_type)
+            {
+                result.push_str(&format!(
+                    "     Receiver type: {type_name} (inferred, confidence {confidence:.2})\n"
+                ));
+            }
 
             if let Some(ref sig) = caller.signature {
                 result.push_str(&format!("     Signature: {sig}\n"));
             }
         }
 
+        if skipped > 0 {
+            result.push_str(&format!(
+                "  ({skipped} caller(s) in this range are no longer in the index)\n"
+            ));
+        }
+        if let Some(next) = next_offset {
+            result.push_str(&format!(
+                "\n{} more caller(s). Use: find_callers symbol_id:{} offset:{next}\n",
+                page_total - next,
+                symbol.id.value()
+            ));
+        }
+
         // Add system guidance
         if let Some(guidance) =
             generate_mcp_guidance(indexer.settings(), "find_callers", result_count)
@@ -796,23 +1376,19 @@ impl CodeIntelligenceServer {
     }
 
     #[tool(
-        description = "Analyze complete impact of changing a symbol. Shows ALL relationships: function calls, type usage, composition.\n\nShows:\n- What CALLS this function\n- What USES this as a type (fields, parameters, returns)\n- What RENDERS/COMPOSES this (JSX: <Component>, Rust: struct fields, etc.)\n- Full dependency graph across files\n\nUse this when: You need to see everything that depends on a symbol."
+        description = "Find what implements or overrides a symbol.\n\nFor a trait, interface or class: every type implementing or extending it, including subclasses of implementors, across files and packages.\nFor a method: the methods of the same name in those types, which implement or override it.\n\nUse this when: You change a trait or abstract method and need every implementation."
     )]
-    pub async fn analyze_impact(
+    pub async fn find_implementations(
         &self,
-        Parameters(AnalyzeImpactRequest {
+        Parameters(FindImplementationsRequest {
             symbol_name,
             symbol_id,
-            max_depth,
-        }): Parameters<AnalyzeImpactRequest>,
+            lang,
+        }): Parameters<FindImplementationsRequest>,
     ) -> Result<CallToolResult, McpError> {
-        use crate::symbol::context::ContextIncludes;
-
         let indexer = self.facade.read().await;
 
-        // Get the symbol either by ID or by name
         let (symbol, identifier) = if let Some(id) = symbol_id {
-            // Direct lookup by symbol ID - UNAMBIGUOUS
             match indexer.get_symbol(crate::SymbolId(id)) {
                 Some(sym) => (sym, format!("symbol_id:{id}")),
                 None => {
@@ -822,7 +1398,7 @@ impl CodeIntelligenceServer {
                 }
             }
         } else if let Some(name) = symbol_name {
-            let symbols = indexer.find_symbols_by_name(&name, None);
+            let symbols = indexer.find_symbols_by_name(&name, lang.as_deref());
 
             if symbols.is_empty() {
                 return Ok(CallToolResult::success(vec![Content::text(format!(
@@ -831,7 +1407,6 @@ impl CodeIntelligenceServer {
             }
 
             if symbols.len() > 1 {
-                // MULTIPLE MATCHES - Return error with list of symbol IDs
                 let mut msg = format!(
                     "Ambiguous: found {} symbol(s) named '{}':\n",
                     symbols.len(),
@@ -850,11 +1425,10 @@ impl CodeIntelligenceServer {
                 if symbols.len() > 10 {
                     msg.push_str(&format!("  ... and {} more\n", symbols.len() - 10));
                 }
-                msg.push_str("\nUse: analyze_impact symbol_id:<id> for specific symbol");
+                msg.push_str("\nUse: find_implementations symbol_id:<id> for specific symbol");
                 return Ok(CallToolResult::success(vec![Content::text(msg)]));
             }
 
-            // SINGLE MATCH - use it
             (symbols.into_iter().next().unwrap(), name)
         } else {
             return Ok(CallToolResult::success(vec![Content::text(
@@ -862,119 +1436,71 @@ impl CodeIntelligenceServer {
             )]));
         };
 
-        // Analyze impact for THIS SPECIFIC symbol only (no aggregation)
-        let impacted = indexer.get_impact_radius(symbol.id, Some(max_depth as usize));
-
-        if impacted.is_empty() {
-            let mut output = format!("No symbols would be impacted by changing {identifier}");
-            // Add guidance for no results
-            if let Some(guidance) = generate_mcp_guidance(indexer.settings(), "analyze_impact", 0) {
-                output.push_str("\n\n---\n💡 ");
-                output.push_str(&guidance);
-                output.push('\n');
-            }
-            return Ok(CallToolResult::success(vec![Content::text(output)]));
-        }
-
-        let mut result = format!("Analyzing impact of changing: {identifier}\n");
+        let max_results = indexer.settings().mcp.max_impact_results;
+        let location = |sym: &Symbol| {
+            format!(
+                "{:?} {} at {}:{} [symbol_id:{}]",
+                sym.kind,
+                sym.name,
+                sym.file_path,
+                sym.range.start_line + 1,
+                sym.id.value()
+            )
+        };
 
-        // Show the specific symbol being analyzed
-        if let Some(ctx) = indexer.get_symbol_context(
-            symbol.id,
-            ContextIncludes::CALLERS | ContextIncludes::EXTENDS | ContextIncludes::USES,
+        let (count, mut result) = if matches!(
+            symbol.kind,
+            crate::SymbolKind::Method | crate::SymbolKind::Function
         ) {
-            let location = ctx.format_location();
-            let direct_callers = ctx
-                .relationships
-                .called_by
-                .as_ref()
-                .map(|c| c.len())
-                .unwrap_or(0);
-
-            // For classes, also show inheritance info
-            let inheritance_info = if matches!(
-                symbol.kind,
-                crate::SymbolKind::Class | crate::SymbolKind::Struct
-            ) {
-                let extends_count = ctx
-                    .relationships
-                    .extends
-                    .as_ref()
-                    .map(|e| e.len())
-                    .unwrap_or(0);
-                let extended_by_count = ctx
-                    .relationships
-                    .extended_by
-                    .as_ref()
-                    .map(|e| e.len())
-                    .unwrap_or(0);
-
-                if extends_count > 0 || extended_by_count > 0 {
-                    format!(", extends: {extends_count}, extended by: {extended_by_count}")
-                } else {
-                    String::new()
-                }
+            let overrides = indexer.get_overrides(symbol.id, max_results);
+            let mut result = if overrides.is_empty() {
+                format!("No implementations or overrides of {identifier}\n")
             } else {
-                String::new()
+                format!(
+                    "{} method(s) implement or override {identifier}:\n",
+                    overrides.len()
+                )
             };
-
-            // Show uses info for all symbols
-            let uses_count = ctx
-                .relationships
-                .uses
-                .as_ref()
-                .map(|u| u.len())
-                .unwrap_or(0);
-            let used_by_count = ctx
-                .relationships
-                .used_by
-                .as_ref()
-                .map(|u| u.len())
-                .unwrap_or(0);
-
-            let uses_info = if uses_count > 0 || used_by_count > 0 {
-                format!(", uses: {uses_count}, used by: {used_by_count}")
+            for method in &overrides {
+                result.push_str(&format!("  - {}\n", location(method)));
+            }
+            (overrides.len(), result)
+        } else {
+            let implementors = indexer.get_implementors(symbol.id, max_results);
+            let mut result = if implementors.is_empty() {
+                format!("No types implement or extend {identifier}\n")
             } else {
-                String::new()
+                format!(
+                    "{} type(s) implement or extend {identifier}:\n",
+                    implementors.len()
+                )
             };
-
-            result.push_str(&format!(
-                "Symbol: {:?} at {} (direct callers: {}{}{})\n\n",
-                symbol.kind, location, direct_callers, inheritance_info, uses_info
-            ));
-        }
-
-        let impact_count = impacted.len();
-        result.push_str(&format!(
-            "Total impact: {impact_count} symbol(s) would be affected (max depth: {max_depth})\n"
-        ));
-
-        // Group by symbol kind
-        let mut by_kind: std::collections::HashMap<crate::SymbolKind, Vec<Symbol>> =
-            std::collections::HashMap::new();
-
-        for id in impacted {
-            if let Some(sym) = indexer.get_symbol(id) {
-                by_kind.entry(sym.kind).or_default().push(sym);
-            }
-        }
-
-        // Display grouped by kind with locations
-        for (kind, symbols) in by_kind {
-            result.push_str(&format!("\n{kind:?} ({}): \n", symbols.len()));
-            for sym in symbols {
+            for implementor in &implementors {
+                let relation = match implementor.kind {
+                    crate::RelationKind::Extends => "extends",
+                    _ => "implements",
+                };
+                let supertype = if implementor.supertype == symbol.id {
+                    symbol.name.to_string()
+                } else {
+                    indexer
+                        .get_symbol(implementor.supertype)
+                        .map(|sym| sym.name.to_string())
+                        .unwrap_or_default()
+                };
                 result.push_str(&format!(
-                    "  - {} at {}:{}\n",
-                    sym.name,
-                    sym.file_path,
-                    sym.range.start_line + 1
+                    "  - {} ({relation} {supertype})\n",
+                    location(&implementor.symbol)
                 ));
             }
+            (implementors.len(), result)
+        };
+        if count >= max_results {
+            result.push_str("\nStopped at mcp.max_impact_results; the list is incomplete\n");
         }
 
-        // Add system guidance
         if let Some(guidance) =
-            generate_mcp_guidance(indexer.settings(), "analyze_impact", impact_count)
+            generate_mcp_guidance(indexer.settings(), "find_implementations", count)
         {
             result.push_str("\n---\n💡 ");
             result.push_str(&guidance);
@@ -984,8 +1510,314 @@ impl CodeIntelligenceServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
-    #[tool(description = "Get information about the indexed codebase")]
-    pub async fn get_index_info(
+    #[tool(
+        description = "Analyze complete impact of changing a symbol or a whole file, as a list ranked by confidence.\n\nCombines:\n- What CALLS this function\n- What USES this as a type (fields, parameters, returns, JSX composition, struct fields)\n- What IMPLEMENTS, EXTENDS or OVERRIDES it\n- Files that IMPORT or RE-EXPORT it by name\n- Transitive dependents across files, less confident with each hop\n- Tests that reach it or are named after it, marked [test]\n\nUse this when: You need to see everything that depends on a symbol or file before changing it."
+    )]
+    pub async fn analyze_impact(
+        &self,
+        Parameters(AnalyzeImpactRequest {
+            symbol_name,
+            symbol_id,
+            file_path,
+            max_depth,
+        }): Parameters<AnalyzeImpactRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::symbol::context::ContextIncludes;
+
+        let indexer = self.facade.read().await;
+
+        // Get the changed symbols: a whole file, or one symbol by ID or by name
+        let (changed, identifier) = if let Some(path) = &file_path {
+            let Some((file_id, stored)) = indexer.resolve_indexed_file(path) else {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "File not indexed, or more than one indexed file matches: {path}"
+                ))]));
+            };
+            (indexer.get_symbols_by_file(file_id), stored)
+        } else if let Some(id) = symbol_id {
+            // Direct lookup by symbol ID - UNAMBIGUOUS
+            match indexer.get_symbol(crate::SymbolId(id)) {
+                Some(sym) => (vec![sym], format!("symbol_id:{id}")),
+                None => {
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Symbol not found: symbol_id:{id}"
+                    ))]));
+                }
+            }
+        } else if let Some(name) = symbol_name {
+            let symbols = indexer.find_symbols_by_name(&name, None);
+
+            if symbols.is_empty() {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Symbol not found: {name}"
+                ))]));
+            }
+
+            if symbols.len() > 1 {
+                // MULTIPLE MATCHES - Return error with list of symbol IDs
+                let mut msg = format!(
+                    "Ambiguous: found {} symbol(s) named '{}':\n",
+                    symbols.len(),
+                    name
+                );
+                for (i, sym) in symbols.iter().take(10).enumerate() {
+                    msg.push_str(&format!(
+                        "  {}. symbol_id:{} - {:?} at {}:{}\n",
+                        i + 1,
+                        sym.id.value(),
+                        sym.kind,
+                        sym.file_path,
+                        sym.range.start_line + 1
+                    ));
+                }
+                if symbols.len() > 10 {
+                    msg.push_str(&format!("  ... and {} more\n", symbols.len() - 10));
+                }
+                msg.push_str("\nUse: analyze_impact symbol_id:<id> for specific symbol");
+                return Ok(CallToolResult::success(vec![Content::text(msg)]));
+            }
+
+            // SINGLE MATCH - use it
+            (symbols, name)
+        } else {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: One of symbol_name, symbol_id or file_path must be provided".to_string(),
+            )]));
+        };
+
+        let report = impact::analyze(&indexer, &changed, max_depth as usize);
+        if report.entries.is_empty() {
+            let mut output = format!("No symbols would be impacted by changing {identifier}");
+            // Add guidance for no results
+            if let Some(guidance) = generate_mcp_guidance(indexer.settings(), "analyze_impact", 0) {
+                output.push_str("\n\n---\n💡 ");
+                output.push_str(&guidance);
+                output.push('\n');
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut result = format!("Analyzing impact of changing: {identifier}\n");
+
+        // Show the specific symbol being analyzed
+        if let ([symbol], None) = (changed.as_slice(), &file_path) {
+            if let Some(ctx) = indexer.get_symbol_context(
+                symbol.id,
+                ContextIncludes::CALLERS | ContextIncludes::EXTENDS | ContextIncludes::USES,
+            ) {
+                let location = ctx.format_location();
+                let direct_callers = ctx
+                    .relationships
+                    .called_by
+                    .as_ref()
+                    .map(|c| c.len())
+                    .unwrap_or(0);
+
+                // For classes, also show inheritance info
+                let inheritance_info = if matches!(
+                    symbol.kind,
+                    crate::SymbolKind::Class | crate::SymbolKind::Struct
+                ) {
+                    let extends_count = ctx
+                        .relationships
+                        .extends
+                        .as_ref()
+                        .map(|e| e.len())
+                        .unwrap_or(0);
+                    let extended_by_count = ctx
+                        .relationships
+                        .extended_by
+                        .as_ref()
+                        .map(|e| e.len())
+                        .unwrap_or(0);
+
+                    if extends_count > 0 || extended_by_count > 0 {
+                        format!(", extends: {extends_count}, extended by: {extended_by_count}")
+                    } else {
+                        String::new()
+                    }
+                } else {
+                    String::new()
+                };
+
+                // Show uses info for all symbols
+                let uses_count = ctx
+                    .relationships
+                    .uses
+                    .as_ref()
+                    .map(|u| u.len())
+                    .unwrap_or(0);
+                let used_by_count = ctx
+                    .relationships
+                    .used_by
+                    .as_ref()
+                    .map(|u| u.len())
+                    .unwrap_or(0);
+
+                let uses_info = if uses_count > 0 || used_by_count > 0 {
+                    format!(", uses: {uses_count}, used by: {used_by_count}")
+                } else {
+                    String::new()
+                };
+
+                result.push_str(&format!(
+                    "Symbol: {:?} at {} (direct callers: {}{}{})\n\n",
+                    symbol.kind, location, direct_callers, inheritance_info, uses_info
+                ));
+            }
+        } else {
+            result.push_str(&format!(
+                "File: {identifier} ({} symbol(s))\n\n",
+                changed.len()
+            ));
+        }
+
+        let impact_count = report.entries.len();
+        if report.truncated {
+            let stop = if report.timed_out {
+                "stopped at query timeout"
+            } else {
+                "stopped at result limit"
+            };
+            result.push_str(&format!(
+                "Total impact: {impact_count}+ symbol(s) or file(s) would be affected (max depth: {max_depth}, {stop})\n"
+            ));
+        } else {
+            result.push_str(&format!(
+                "Total impact: {impact_count} symbol(s) or file(s) would be affected (max depth: {max_depth})\n"
+            ));
+        }
+
+        // Hubs are summarized rather than expanded to keep the response bounded
+        if !report.hubs.is_empty() {
+            result.push_str("\nHigh fan-in symbols (dependents summarized, not expanded):\n");
+            for (hub_id, dependents) in &report.hubs {
+                if let Some(hub) = indexer.get_symbol(*hub_id) {
+                    result.push_str(&format!(
+                        "  * {} at {}:{} ({dependents} dependents)\n",
+                        hub.name,
+                        hub.file_path,
+                        hub.range.start_line + 1
+                    ));
+                }
+            }
+        }
+
+        let tests = report.entries.iter().filter(|entry| entry.is_test).count();
+        result.push_str(&format!(
+            "\nRanked by confidence ({tests} test(s) to re-run, marked [test]):\n"
+        ));
+        for entry in &report.entries {
+            let reasons: Vec<String> = entry.reasons.iter().map(|r| r.describe()).collect();
+            let test = if entry.is_test { " [test]" } else { "" };
+            let confidence = entry.confidence();
+            match &entry.symbol {
+                Some(sym) => result.push_str(&format!(
+                    "  {confidence:.2} {:?} {} at {}:{} [symbol_id:{}]{test} - {}\n",
+                    sym.kind,
+                    sym.name,
+                    sym.file_path,
+                    sym.range.start_line + 1,
+                    sym.id.value(),
+                    reasons.join(", ")
+                )),
+                None => result.push_str(&format!(
+                    "  {confidence:.2} {}{test} - {}\n",
+                    entry.file_path,
+                    reasons.join(", ")
+                )),
+            }
+        }
+
+        // Add system guidance
+        if let Some(guidance) =
+            generate_mcp_guidance(indexer.settings(), "analyze_impact", impact_count)
+        {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Gather what is needed to write a test for a symbol in one call.\n\nShows:\n- Signature, module, and documentation\n- Types it depends on, their constructors, and where existing code constructs them (test files first)\n- Existing tests that call it or share its name\n- The test framework detected for its language (from Cargo.toml, package.json, pyproject.toml, pom.xml, ...)\n\nUse this when: You are about to write a test and want it to compile on the first try."
+    )]
+    pub async fn test_context_for(
+        &self,
+        Parameters(TestContextForRequest {
+            symbol_name,
+            symbol_id,
+            limit,
+        }): Parameters<TestContextForRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+
+        let symbol = if let Some(id) = symbol_id {
+            match indexer.get_symbol(crate::SymbolId(id)) {
+                Some(sym) => sym,
+                None => {
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Symbol not found: symbol_id:{id}"
+                    ))]));
+                }
+            }
+        } else if let Some(name) = symbol_name {
+            let symbols = indexer.find_symbols_by_name(&name, None);
+
+            if symbols.is_empty() {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Symbol not found: {name}"
+                ))]));
+            }
+
+            if symbols.len() > 1 {
+                let mut msg = format!(
+                    "Ambiguous: found {} symbol(s) named '{}':\n",
+                    symbols.len(),
+                    name
+                );
+                for (i, sym) in symbols.iter().take(10).enumerate() {
+                    msg.push_str(&format!(
+                        "  {}. symbol_id:{} - {:?} at {}:{}\n",
+                        i + 1,
+                        sym.id.value(),
+                        sym.kind,
+                        sym.file_path,
+                        sym.range.start_line + 1
+                    ));
+                }
+                if symbols.len() > 10 {
+                    msg.push_str(&format!("  ... and {} more\n", symbols.len() - 10));
+                }
+                msg.push_str("\nUse: test_context_for symbol_id:<id> for specific symbol");
+                return Ok(CallToolResult::success(vec![Content::text(msg)]));
+            }
+
+            symbols.into_iter().next().unwrap()
+        } else {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: Either symbol_name or symbol_id must be provided".to_string(),
+            )]));
+        };
+
+        let context = test_context::TestContext::collect(&indexer, symbol, limit as usize);
+        let mut result = context.format();
+
+        if let Some(guidance) =
+            generate_mcp_guidance(indexer.settings(), "test_context_for", context.tests.len())
+        {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(description = "Get information about the indexed codebase")]
+    pub async fn get_index_info(
         &self,
         Parameters(_params): Parameters<GetIndexInfoRequest>,
     ) -> Result<CallToolResult, McpError> {
@@ -1032,7 +1864,107 @@ impl CodeIntelligenceServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
-    #[tool(description = "Search documentation using natural language semantic search")]
+    #[tool(
+        description = "List the projects registered with codanna on this machine. Pass a project's name as the `project` argument of any other tool to query that project's index instead of the current one.\n\nUse this when: The code you need is in another repository."
+    )]
+    pub async fn list_projects(
+        &self,
+        Parameters(_params): Parameters<ListProjectsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let registered = match projects::registered() {
+            Ok(registered) => registered,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to read the project registry: {e}"
+                ))]));
+            }
+        };
+        if registered.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No registered projects. Run 'codanna init' in a project to register it."
+                    .to_string(),
+            )]));
+        }
+
+        let current = self.current_root().await;
+        let mut output = format!("{} registered project(s):\n", registered.len());
+        for project in &registered {
+            let status = if current.as_deref() == Some(project.path.as_path()) {
+                " (current)"
+            } else if self.projects.is_open(&project.path).await {
+                " (open)"
+            } else {
+                ""
+            };
+            output.push_str(&format!(
+                "  {}{status} - {} [id:{}]\n",
+                project.name,
+                project.path.display(),
+                project.id
+            ));
+        }
+        output.push_str("\nPass project:\"<name>\" to any tool to query another project.");
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "List the symbols and files this session looked up recently, newest first, with symbol IDs. Searches in this session already rank results in or near them higher.\n\nUse this when: Resolving a reference to earlier work, such as \"the function I just looked at\"."
+    )]
+    pub async fn recent_context(
+        &self,
+        Parameters(RecentContextRequest { kind, limit }): Parameters<RecentContextRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let kind = match kind.as_deref() {
+            None => None,
+            Some(value) => match crate::SymbolKind::parse_ignore_case(value) {
+                Some(kind) => Some(kind),
+                None => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Unknown symbol kind '{value}'"
+                    ))]));
+                }
+            },
+        };
+
+        let output = {
+            let session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+            if session.is_empty() {
+                "Nothing looked up yet in this session".to_string()
+            } else {
+                let mut output =
+                    String::from("Recently looked up in this session, newest first:\n");
+                let symbols: Vec<_> = session
+                    .symbols()
+                    .filter(|recent| kind.is_none_or(|kind| recent.kind == kind))
+                    .take(limit as usize)
+                    .collect();
+                if !symbols.is_empty() {
+                    output.push_str("\nSymbols:\n");
+                }
+                for (i, recent) in symbols.iter().enumerate() {
+                    output.push_str(&format!(
+                        "  {}. {} ({:?}) at {}:{} [symbol_id:{}]\n",
+                        i + 1,
+                        recent.name,
+                        recent.kind,
+                        recent.file_path,
+                        recent.line,
+                        recent.symbol_id.value()
+                    ));
+                }
+                output.push_str("\nFiles:\n");
+                for path in session.files() {
+                    output.push_str(&format!("  - {path}\n"));
+                }
+                output
+            }
+        };
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Search documentation using natural language semantic search. By default full-text matches are fused in, so exact identifiers in the query are found too."
+    )]
     pub async fn semantic_search_docs(
         &self,
         Parameters(SemanticSearchRequest {
@@ -1040,6 +1972,8 @@ impl CodeIntelligenceServer {
             limit,
             threshold,
             lang,
+            mode,
+            filters,
         }): Parameters<SemanticSearchRequest>,
     ) -> Result<CallToolResult, McpError> {
         let indexer = self.facade.read().await;
@@ -1053,9 +1987,10 @@ impl CodeIntelligenceServer {
 
         if !indexer.has_semantic_search() {
             // Check if semantic files exist
-            let semantic_path = indexer.settings().index_path.join("semantic");
+            let semantic_path =
+                crate::vector::VectorNamespace::Code.dir(&indexer.settings().index_path);
             let metadata_exists = semantic_path.join("metadata.json").exists();
-            let vectors_exist = semantic_path.join("segment_0.vec").exists();
+            let vectors_exist = crate::semantic::SemanticVectorStorage::exists_at(&semantic_path);
             let symbol_count = indexer.symbol_count();
 
             // Get current working directory for debugging
@@ -1073,20 +2008,29 @@ impl CodeIntelligenceServer {
             ))]));
         }
 
-        let results = match threshold {
-            Some(t) => indexer.semantic_search_docs_with_threshold_and_language(
-                &query,
-                limit as usize,
-                t,
-                lang.as_deref(),
-            ),
-            None => {
-                indexer.semantic_search_docs_with_language(&query, limit as usize, lang.as_deref())
-            }
+        let filter = match filters.resolve(lang) {
+            Ok(filter) => filter,
+            Err(message) => return Ok(CallToolResult::error(vec![Content::text(message)])),
         };
+        let hybrid = match mode.as_deref() {
+            None | Some("hybrid") => true,
+            Some("semantic") => false,
+            Some(other) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Unknown mode '{other}'. Use \"hybrid\" or \"semantic\""
+                ))]));
+            }
+        };
+        let results = if hybrid {
+            indexer.hybrid_search_filtered(&query, limit as usize, threshold, &filter)
+        } else {
+            indexer.semantic_search_filtered(&query, limit as usize, threshold, &filter)
+        };
+        let score_label = if hybrid { "Score" } else { "Similarity" };
 
         match results {
-            Ok(results) => {
+            Ok(mut results) => {
+                self.rerank_for_session(&indexer, &mut results);
                 if results.is_empty() {
                     let mut output =
                         format!("No semantically similar documentation found for: {query}");
@@ -1109,11 +2053,12 @@ impl CodeIntelligenceServer {
 
                 for (i, (symbol, score)) in results.iter().enumerate() {
                     result.push_str(&format!(
-                        "{}. {} ({:?}) - Similarity: {:.3}\n",
+                        "{}. {} ({:?}) - {score_label}: {:.3} [symbol_id:{}]\n",
                         i + 1,
                         symbol.name,
                         symbol.kind,
-                        score
+                        score,
+                        symbol.id.value()
                     ));
                     result.push_str(&format!(
                         "   File: {}:{}\n",
@@ -1136,6 +2081,13 @@ impl CodeIntelligenceServer {
                         result.push_str(&format!("   Signature: {sig}\n"));
                     }
 
+                    if let Some(snippet) = indexer.symbol_snippet(symbol, SNIPPET_LINES) {
+                        result.push_str("   Snippet:\n");
+                        for line in snippet.lines() {
+                            result.push_str(&format!("     {line}\n"));
+                        }
+                    }
+
                     result.push('\n');
                 }
 
@@ -1166,6 +2118,7 @@ impl CodeIntelligenceServer {
             limit,
             threshold,
             lang,
+            filters,
         }): Parameters<SemanticSearchWithContextRequest>,
     ) -> Result<CallToolResult, McpError> {
         let indexer = self.facade.read().await;
@@ -1178,9 +2131,10 @@ impl CodeIntelligenceServer {
                 indexer.has_semantic_search()
             );
             // Check if semantic files exist
-            let semantic_path = indexer.settings().index_path.join("semantic");
+            let semantic_path =
+                crate::vector::VectorNamespace::Code.dir(&indexer.settings().index_path);
             let metadata_exists = semantic_path.join("metadata.json").exists();
-            let vectors_exist = semantic_path.join("segment_0.vec").exists();
+            let vectors_exist = crate::semantic::SemanticVectorStorage::exists_at(&semantic_path);
 
             return Ok(CallToolResult::error(vec![Content::text(format!(
                 "Semantic search is not enabled. The index needs to be rebuilt with semantic search enabled.\n\nDEBUG INFO:\n- Index path: {}\n- Has semantic search: {}\n- Semantic path: {}\n- Metadata exists: {}\n- Vectors exist: {}",
@@ -1193,20 +2147,16 @@ impl CodeIntelligenceServer {
         }
 
         // First, perform semantic search
-        let search_results = match threshold {
-            Some(t) => indexer.semantic_search_docs_with_threshold_and_language(
-                &query,
-                limit as usize,
-                t,
-                lang.as_deref(),
-            ),
-            None => {
-                indexer.semantic_search_docs_with_language(&query, limit as usize, lang.as_deref())
-            }
+        let filter = match filters.resolve(lang) {
+            Ok(filter) => filter,
+            Err(message) => return Ok(CallToolResult::error(vec![Content::text(message)])),
         };
+        let search_results =
+            indexer.semantic_search_filtered(&query, limit as usize, threshold, &filter);
 
         match search_results {
-            Ok(results) => {
+            Ok(mut results) => {
+                self.rerank_for_session(&indexer, &mut results);
                 if results.is_empty() {
                     let mut output = format!("No documentation found matching query: {query}");
                     // Add guidance for no results
@@ -1228,7 +2178,15 @@ impl CodeIntelligenceServer {
                 ));
 
                 // For each result, gather comprehensive context
+                let budget = indexer.query_budget();
                 for (idx, (symbol, score)) in results.iter().enumerate() {
+                    if budget.expired() {
+                        output.push_str(&format!(
+                            "({} more result(s) omitted)\n\n",
+                            results.len() - idx
+                        ));
+                        break;
+                    }
                     // Basic symbol information - matching find_symbol format
                     output.push_str(&format!(
                         "{}. {} - {:?} at {} [symbol_id:{}]\n",
@@ -1668,6 +2626,10 @@ impl CodeIntelligenceServer {
                     output.push('\n');
                 }
 
+                if let Some(note) = budget.truncation_note() {
+                    output.push_str(&format!("{note}\n"));
+                }
+
                 // Add system guidance
                 if let Some(guidance) = generate_mcp_guidance(
                     indexer.settings(),
@@ -1696,30 +2658,48 @@ impl CodeIntelligenceServer {
             kind,
             module,
             lang,
+            repo,
+            package,
+            shard,
         }): Parameters<SearchSymbolsRequest>,
     ) -> Result<CallToolResult, McpError> {
         let indexer = self.facade.read().await;
 
         // Parse the kind filter if provided
-        let kind_filter = kind.as_ref().and_then(|k| match k.to_lowercase().as_str() {
-            "function" => Some(crate::SymbolKind::Function),
-            "struct" => Some(crate::SymbolKind::Struct),
-            "trait" => Some(crate::SymbolKind::Trait),
-            "method" => Some(crate::SymbolKind::Method),
-            "field" => Some(crate::SymbolKind::Field),
-            "module" => Some(crate::SymbolKind::Module),
-            "constant" => Some(crate::SymbolKind::Constant),
-            _ => None,
-        });
+        let kind_filter = match kind.as_deref() {
+            None => None,
+            Some(value) => match crate::SymbolKind::parse_ignore_case(value) {
+                Some(kind) => Some(kind),
+                None => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Unknown symbol kind '{value}'"
+                    ))]));
+                }
+            },
+        };
+
+        let budget = indexer.query_budget();
+        let capped_limit = budget.clamp_limit(limit as usize);
 
-        match indexer.search(
+        let scope = crate::indexing::facade::SearchScope {
+            repository: repo,
+            package,
+            shard,
+        };
+        match indexer.search_scoped(
             &query,
-            limit as usize,
+            capped_limit,
             kind_filter,
             module.as_deref(),
             lang.as_deref(),
+            &scope,
         ) {
-            Ok(results) => {
+            Ok(mut results) => {
+                self.rerank_for_session(&indexer, &mut results);
+                if results.len() == capped_limit && capped_limit < limit as usize {
+                    budget.mark(crate::indexing::TruncationReason::CandidateLimit);
+                }
+
                 if results.is_empty() {
                     let mut output = format!("No results found for query: {query}");
                     // Add guidance for no results
@@ -1739,6 +2719,14 @@ impl CodeIntelligenceServer {
                     query
                 );
 
+                // Cached module summaries, if `codanna summary` has been run
+                let summaries = indexer
+                    .settings()
+                    .summary
+                    .enabled
+                    .then(|| crate::summary::SummaryCache::load(indexer.index_base()).ok())
+                    .flatten();
+
                 for (i, search_result) in results.iter().enumerate() {
                     result.push_str(&format!(
                         "{}. {} ({:?})\n",
@@ -1755,6 +2743,13 @@ impl CodeIntelligenceServer {
                         result.push_str(&format!("   Module: {}\n", search_result.module_path));
                     }
 
+                    if let Some(summary) = summaries
+                        .as_ref()
+                        .and_then(|cache| cache.get(&search_result.file_path))
+                    {
+                        result.push_str(&format!("   File summary: {summary}\n"));
+                    }
+
                     if let Some(ref doc) = search_result.doc_comment {
                         // Show first line of doc comment
                         let first_line = doc.lines().next().unwrap_or("");
@@ -1769,6 +2764,10 @@ impl CodeIntelligenceServer {
                     result.push('\n');
                 }
 
+                if let Some(note) = budget.truncation_note() {
+                    result.push_str(&format!("{note}\n"));
+                }
+
                 // Add system guidance
                 if let Some(guidance) =
                     generate_mcp_guidance(indexer.settings(), "search_symbols", results.len())
@@ -1787,96 +2786,864 @@ impl CodeIntelligenceServer {
     }
 
     #[tool(
-        description = "Search indexed documents (markdown, text files) using natural language queries. Returns relevant chunks with context and highlighted keywords."
+        description = "Search indexed documents (markdown, text files) using natural language queries. Returns relevant chunks with context and highlighted keywords."
+    )]
+    pub async fn search_documents(
+        &self,
+        Parameters(SearchDocumentsRequest {
+            query,
+            collection,
+            limit,
+        }): Parameters<SearchDocumentsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let store = match &self.document_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Document search not available. No document collections are indexed.\n\n\
+                    To enable:\n\
+                    1. Add a collection: codanna documents add-collection docs docs/\n\
+                    2. Index it: codanna documents index\n\
+                    3. Restart the MCP server",
+                )]));
+            }
+        };
+
+        let mut store = store.write().await;
+        let indexer = self.facade.read().await;
+
+        // Auto-sync: check for file changes in all collections before searching
+        let settings = indexer.settings();
+        for (name, config) in &settings.documents.collections {
+            if let Err(e) = store.index_collection(name, config, &settings.documents.defaults) {
+                tracing::warn!(target: "rag", "auto-sync failed for collection '{}': {}", name, e);
+            }
+        }
+
+        let search_query = DocSearchQuery {
+            text: query.clone(),
+            collection,
+            document: None,
+            limit: limit as usize,
+            preview_config: Some(indexer.settings().documents.search.clone()),
+        };
+
+        match store.search(search_query) {
+            Ok(results) => {
+                if results.is_empty() {
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "No documents found for: {query}"
+                    ))]));
+                }
+
+                let mut output = format!(
+                    "Found {} document(s) matching '{}':\n\n",
+                    results.len(),
+                    query
+                );
+
+                for (i, result) in results.iter().enumerate() {
+                    output.push_str(&format!(
+                        "{}. {} (score: {:.3})\n",
+                        i + 1,
+                        result.source_path.display(),
+                        result.similarity
+                    ));
+
+                    if !result.heading_context.is_empty() {
+                        output.push_str(&format!(
+                            "   Context: {}\n",
+                            result.heading_context.join(" > ")
+                        ));
+                    }
+
+                    // Preview is already KWIC-processed with highlighting
+                    output.push_str(&format!("   Preview: {}\n\n", result.content_preview));
+                }
+
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Document search failed: {e}"
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "Search code and documentation with one query: doc comments of symbols and indexed documents (markdown, READMEs, ADRs) ranked together. Each result says whether it is code, a readme, an adr or a guide; a document chunk lists the symbol_ids of results it names.\n\nUse this when: You need both the implementation and the design doc or decision record explaining it."
+    )]
+    pub async fn search_knowledge(
+        &self,
+        Parameters(SearchKnowledgeRequest {
+            query,
+            collection,
+            limit,
+        }): Parameters<SearchKnowledgeRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        // Same lock order as search_documents: the store, then the facade
+        let store = match &self.document_store {
+            Some(store) => Some(store.write().await),
+            None => None,
+        };
+        let indexer = self.facade.read().await;
+        let mut documents = Vec::new();
+        if let Some(mut store) = store {
+            let settings = indexer.settings();
+            for (name, config) in &settings.documents.collections {
+                if let Err(e) = store.index_collection(name, config, &settings.documents.defaults) {
+                    tracing::warn!(target: "rag", "auto-sync failed for collection '{name}': {e}");
+                }
+            }
+            let search_query = DocSearchQuery {
+                text: query.clone(),
+                collection,
+                document: None,
+                limit: limit as usize,
+                preview_config: Some(settings.documents.search.clone()),
+            };
+            match store.search(search_query) {
+                Ok(results) => documents = results,
+                Err(e) => {
+                    tracing::warn!(target: "rag", "document search failed: {e}");
+                }
+            }
+        }
+
+        let hits = match indexer.knowledge_search(
+            &query,
+            limit as usize,
+            &SemanticFilter::default(),
+            documents,
+        ) {
+            Ok(hits) => hits,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Knowledge search failed: {e}"
+                ))]));
+            }
+        };
+        if hits.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Nothing found for: {query}"
+            ))]));
+        }
+
+        let mut output = format!("Found {} result(s) for '{}':\n\n", hits.len(), query);
+        for (i, hit) in hits.iter().enumerate() {
+            output.push_str(&format!("{}. {hit}\n\n", i + 1));
+        }
+        if self.document_store.is_none() {
+            output.push_str("Only code was searched: no document collections are indexed.\n");
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Find past AI conversations that mentioned a symbol or file. Returns the messages with session, time and an excerpt, newest first."
+    )]
+    pub async fn find_discussions(
+        &self,
+        Parameters(FindDiscussionsRequest { query, limit }): Parameters<FindDiscussionsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::conversations::LinkStore;
+
+        let links = match LinkStore::default_location().find(&query, limit as usize) {
+            Ok(links) => links,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to read conversation links: {e}"
+                ))]));
+            }
+        };
+        if links.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No conversations mention '{query}'"
+            ))]));
+        }
+
+        let mut output = format!(
+            "Found {} message(s) mentioning '{}':\n\n",
+            links.len(),
+            query
+        );
+        for (i, link) in links.iter().enumerate() {
+            output.push_str(&format!(
+                "{}. {} in session {} ({}, {})\n   {}\n\n",
+                i + 1,
+                link.mention,
+                link.session_id,
+                link.role.as_deref().unwrap_or("message"),
+                link.timestamp.as_deref().unwrap_or("unknown time"),
+                link.excerpt
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Outline of a file: its symbols nested by scope (methods under their class or impl, nested functions under their parent), with kinds, line ranges and the first line of each doc comment.\n\nUse this when: You need a file's structure without reading the whole file."
+    )]
+    pub async fn get_file_outline(
+        &self,
+        Parameters(GetFileOutlineRequest { path }): Parameters<GetFileOutlineRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+
+        let Some((stored, outline)) = indexer.get_file_outline(&path) else {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "File not indexed, or more than one indexed file matches: {path}"
+            ))]));
+        };
+        if outline.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No symbols in {stored}"
+            ))]));
+        }
+
+        let count: usize = outline.iter().map(|node| node.count()).sum();
+        let mut result = format!("Outline of {stored} ({count} symbol(s)):\n");
+        format_outline_nodes(&mut result, &outline, 0);
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Source of a symbol: its exact line range plus a few surrounding lines, the file's leading docs and imports, the enclosing scope and the signatures of its siblings. Lines are numbered, and blank lines outside the symbol are dropped to save tokens.\n\nUse this when: You need to read or edit one symbol without reading its whole file."
+    )]
+    pub async fn get_symbol_source(
+        &self,
+        Parameters(GetSymbolSourceRequest {
+            symbol_name,
+            symbol_id,
+            context,
+            max_lines,
+            header,
+            siblings,
+        }): Parameters<GetSymbolSourceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::symbol::source::{ExcerptOptions, excerpt};
+
+        let indexer = self.facade.read().await;
+
+        let symbol = if let Some(id) = symbol_id {
+            match indexer.get_symbol(crate::SymbolId(id)) {
+                Some(sym) => sym,
+                None => {
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Symbol not found: symbol_id:{id}"
+                    ))]));
+                }
+            }
+        } else if let Some(name) = symbol_name {
+            let mut symbols = indexer.find_symbols_by_name(&name, None);
+            if symbols.len() != 1 {
+                let mut msg = if symbols.is_empty() {
+                    format!("Symbol not found: {name}")
+                } else {
+                    format!(
+                        "Ambiguous: found {} symbol(s) named '{name}':\n",
+                        symbols.len()
+                    )
+                };
+                for (i, sym) in symbols.iter().take(10).enumerate() {
+                    msg.push_str(&format!(
+                        "  {}. symbol_id:{} - {:?} at {}:{}\n",
+                        i + 1,
+                        sym.id.value(),
+                        sym.kind,
+                        sym.file_path,
+                        sym.range.start_line + 1
+                    ));
+                }
+                if symbols.len() > 10 {
+                    msg.push_str(&format!("  ... and {} more\n", symbols.len() - 10));
+                }
+                if !symbols.is_empty() {
+                    msg.push_str("\nUse: get_symbol_source symbol_id:<id> for specific symbol");
+                }
+                return Ok(CallToolResult::success(vec![Content::text(msg)]));
+            }
+            symbols.remove(0)
+        } else {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: Either symbol_name or symbol_id must be provided".to_string(),
+            )]));
+        };
+
+        let Some((source, changed)) = indexer.read_indexed_source(&symbol.file_path) else {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Cannot read {}; it may have been moved or deleted since indexing",
+                symbol.file_path
+            ))]));
+        };
+        let options = ExcerptOptions {
+            context_lines: context as usize,
+            max_lines: max_lines as usize,
+            header,
+            siblings,
+        };
+        let mut result = String::new();
+        if changed {
+            result.push_str(
+                "Note: the file changed since it was indexed; line ranges may be off until it is re-indexed\n",
+            );
+        }
+        result.push_str(&excerpt(
+            &source,
+            &symbol,
+            indexer.get_symbols_by_file(symbol.file_id),
+            options,
+        ));
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Find existing symbols that do the same job as a symbol or a snippet: semantically similar symbols above a similarity threshold, marked near-duplicate (0.95+), very similar (0.85+) or similar. Similarity is measured on documentation embeddings, so undocumented code is rarely found.\n\nUse this when: You are about to write a helper and want to reuse an existing one, or to spot duplicated logic."
+    )]
+    pub async fn find_similar_symbols(
+        &self,
+        Parameters(FindSimilarSymbolsRequest {
+            symbol_name,
+            symbol_id,
+            snippet,
+            threshold,
+            limit,
+            lang,
+        }): Parameters<FindSimilarSymbolsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+        if !indexer.has_semantic_search() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Semantic search is not enabled. Re-index with semantic search enabled to find similar symbols.".to_string(),
+            )]));
+        }
+
+        let target = if let Some(id) = symbol_id {
+            match indexer.get_symbol(crate::SymbolId(id)) {
+                Some(sym) => Some(sym),
+                None => {
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Symbol not found: symbol_id:{id}"
+                    ))]));
+                }
+            }
+        } else if let Some(name) = symbol_name {
+            let mut symbols = indexer.find_symbols_by_name(&name, lang.as_deref());
+            if symbols.len() != 1 {
+                let mut msg = if symbols.is_empty() {
+                    format!("Symbol not found: {name}")
+                } else {
+                    format!(
+                        "Ambiguous: found {} symbol(s) named '{name}':\n",
+                        symbols.len()
+                    )
+                };
+                for (i, sym) in symbols.iter().take(10).enumerate() {
+                    msg.push_str(&format!(
+                        "  {}. symbol_id:{} - {:?} at {}:{}\n",
+                        i + 1,
+                        sym.id.value(),
+                        sym.kind,
+                        sym.file_path,
+                        sym.range.start_line + 1
+                    ));
+                }
+                if symbols.len() > 10 {
+                    msg.push_str(&format!("  ... and {} more\n", symbols.len() - 10));
+                }
+                if !symbols.is_empty() {
+                    msg.push_str("\nUse: find_similar_symbols symbol_id:<id> for specific symbol");
+                }
+                return Ok(CallToolResult::success(vec![Content::text(msg)]));
+            }
+            Some(symbols.remove(0))
+        } else if snippet.is_some() {
+            None
+        } else {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: One of symbol_name, symbol_id or snippet must be provided".to_string(),
+            )]));
+        };
+
+        let threshold = threshold
+            .or_else(|| indexer.calibrated_thresholds().map(|t| t.similar))
+            .unwrap_or_else(default_similarity_threshold);
+        // Extra candidates make up for the ones overlapping the target
+        let candidates = (limit as usize * 2).max(20);
+        let hits = match (&target, &snippet) {
+            (Some(symbol), _) => {
+                indexer.semantic_search_similar_to(symbol, candidates, lang.as_deref())
+            }
+            (None, Some(snippet)) => {
+                indexer.semantic_search_docs_with_language(snippet, candidates, lang.as_deref())
+            }
+            (None, None) => Ok(Vec::new()),
+        };
+        let hits = match hits {
+            Ok(hits) => hits,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Similarity search failed: {e}"
+                ))]));
+            }
+        };
+        let similar = clones::candidates(target.as_ref(), hits, threshold, limit as usize);
+
+        let subject = match &target {
+            Some(symbol) => format!(
+                "{} ({:?}) at {}:{} [symbol_id:{}]",
+                symbol.name,
+                symbol.kind,
+                symbol.file_path,
+                symbol.range.start_line + 1,
+                symbol.id.value()
+            ),
+            None => "the snippet".to_string(),
+        };
+        if similar.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No symbols similar to {subject} at threshold {threshold:.2}"
+            ))]));
+        }
+
+        let mut result = format!(
+            "Found {} symbol(s) similar to {subject} (threshold {threshold:.2}):\n\n",
+            similar.len()
+        );
+        for (i, (symbol, score)) in similar.iter().enumerate() {
+            result.push_str(&format!(
+                "{}. {} ({:?}) - {:.3} {} [symbol_id:{}]\n",
+                i + 1,
+                symbol.name,
+                symbol.kind,
+                score,
+                clones::describe(*score),
+                symbol.id.value()
+            ));
+            result.push_str(&format!(
+                "   File: {}:{}\n",
+                symbol.file_path,
+                symbol.range.start_line + 1
+            ));
+            if let Some(sig) = &symbol.signature {
+                result.push_str(&format!("   Signature: {sig}\n"));
+            }
+            if let Some(doc) = symbol.doc_comment.as_deref().and_then(|d| d.lines().next()) {
+                result.push_str(&format!("   Doc: {doc}\n"));
+            }
+            result.push('\n');
+        }
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "List symbols nothing in the index calls, uses, implements, extends or references, and whose name no file imports: candidates for dead code. Public API, tests and entry points (main, lifecycle hooks, trait method implementations) are left out unless included. Dynamic calls (reflection, callbacks registered by name, macros) are not indexed, so review results before deleting.\n\nUse this when: Cleaning up a module or package, or checking what a refactoring left behind."
+    )]
+    pub async fn find_unused_symbols(
+        &self,
+        Parameters(FindUnusedSymbolsRequest {
+            path,
+            package,
+            kind,
+            include_public,
+            include_tests,
+            include_entry_points,
+            limit,
+        }): Parameters<FindUnusedSymbolsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+
+        let kind = match kind.as_deref() {
+            None => None,
+            Some(value) => match crate::SymbolKind::parse_ignore_case(value) {
+                Some(kind) => Some(kind),
+                None => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Unknown symbol kind '{value}'"
+                    ))]));
+                }
+            },
+        };
+        let root = match (&package, &path) {
+            (Some(name), _) => match indexer.workspace_package(name) {
+                Ok(package) => package.root.to_string_lossy().into_owned(),
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(e.to_string())]));
+                }
+            },
+            (None, Some(path)) => path.clone(),
+            (None, None) => String::new(),
+        };
+        let scope = package.or(path).unwrap_or_else(|| "the index".to_string());
+
+        let files = indexer.indexed_files_under(&root);
+        if files.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No indexed files under {scope}"
+            ))]));
+        }
+
+        let filters = unused::UnusedFilters {
+            include_public,
+            include_tests,
+            include_entry_points,
+        };
+        let report = unused::find_unused(&indexer, &files, kind, filters, limit.max(1) as usize);
+
+        let mut result = if report.symbols.is_empty() {
+            format!(
+                "No unused symbols in {scope} ({} symbol(s) checked)\n",
+                report.checked
+            )
+        } else {
+            format!(
+                "Found {} unused symbol(s) in {scope} ({} symbol(s) checked):\n\n",
+                report.symbols.len(),
+                report.checked
+            )
+        };
+        for (i, symbol) in report.symbols.iter().enumerate() {
+            result.push_str(&format!(
+                "{}. {} ({:?}, {:?}) [symbol_id:{}]\n",
+                i + 1,
+                symbol.name,
+                symbol.kind,
+                symbol.visibility,
+                symbol.id.value()
+            ));
+            result.push_str(&format!(
+                "   File: {}:{}\n",
+                symbol.file_path,
+                symbol.range.start_line + 1
+            ));
+            if let Some(sig) = &symbol.signature {
+                result.push_str(&format!("   Signature: {sig}\n"));
+            }
+        }
+
+        let excluded = [
+            (report.excluded_public, "public (include_public)"),
+            (report.excluded_tests, "test (include_tests)"),
+            (
+                report.excluded_entry_points,
+                "entry point (include_entry_points)",
+            ),
+            (report.imported, "imported by name elsewhere"),
+        ];
+        let excluded: Vec<String> = excluded
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, reason)| format!("{count} {reason}"))
+            .collect();
+        if !excluded.is_empty() {
+            result.push_str(&format!(
+                "\nAlso unreferenced but not listed: {}\n",
+                excluded.join(", ")
+            ));
+        }
+        if report.timed_out {
+            result.push_str("\nStopped at query timeout; more symbols may be unused\n");
+        } else if report.truncated {
+            result.push_str(&format!(
+                "\nStopped at {limit} result(s); narrow path or kind to see more\n"
+            ));
+        }
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Re-index files or directories now instead of waiting for the file watcher. For each file returns its current symbols with their IDs and which symbols were added, modified or removed; files that no longer exist are removed from the index. Needs the reindex scope on network transports.\n\nUse this when: You just wrote or deleted files and want to query their symbols right away."
+    )]
+    pub async fn reindex_paths(
+        &self,
+        Parameters(ReindexPathsRequest { paths, force }): Parameters<ReindexPathsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if paths.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error: paths must list at least one file or directory".to_string(),
+            )]));
+        }
+        if paths.len() > reindex::MAX_PATHS {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error: at most {} paths per call, got {}",
+                reindex::MAX_PATHS,
+                paths.len()
+            ))]));
+        }
+
+        let mut indexer = self.facade.write().await;
+        let cancel = indexer.indexing_cancel();
+        *self.reindexing.lock().unwrap_or_else(|e| e.into_inner()) = Some(cancel.clone());
+        let outcomes =
+            reindex::reindex_paths(&mut indexer, &paths, force, || cancel.is_cancelled());
+        *self.reindexing.lock().unwrap_or_else(|e| e.into_inner()) = None;
+
+        let result = serde_json::json!({
+            "paths": outcomes,
+            "cancelled": outcomes.len() < paths.len(),
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "File-level dependency graph of a path or workspace package, as JSON: nodes are indexed files with their symbol counts and raw imports, edges are the calls, uses, implementations and extensions from one file into another, with how many symbol relationships back each edge.\n\nUse this when: You need the module structure around some code, what a package depends on, or what depends on it."
+    )]
+    pub async fn get_dependency_graph(
+        &self,
+        Parameters(GetDependencyGraphRequest {
+            path,
+            package,
+            depth,
+            direction,
+            max_nodes,
+        }): Parameters<GetDependencyGraphRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::indexing::dependency_graph::GraphDirection;
+
+        let indexer = self.facade.read().await;
+
+        let direction = match direction.as_deref() {
+            None => GraphDirection::default(),
+            Some(value) => match GraphDirection::parse(value) {
+                Some(direction) => direction,
+                None => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Unknown direction '{value}'. Use dependencies, dependents or both"
+                    ))]));
+                }
+            },
+        };
+        let start = match (&package, &path) {
+            (Some(name), _) => match indexer.workspace_package(name) {
+                Ok(package) => package.root.to_string_lossy().into_owned(),
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(e.to_string())]));
+                }
+            },
+            (None, Some(path)) => path.clone(),
+            (None, None) => String::new(),
+        };
+
+        let graph =
+            indexer.get_dependency_graph(&start, direction, depth, max_nodes.max(1) as usize);
+        if graph.nodes.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No indexed files under {}",
+                package.or(path).unwrap_or_else(|| ".".to_string())
+            ))]));
+        }
+
+        let result = serde_json::json!({
+            "root": package.or(path),
+            "direction": direction,
+            "depth": depth,
+            "nodes": graph.nodes,
+            "edges": graph.edges,
+            "truncated": graph.truncated,
+        });
+        let text = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        description = "List indexed files with syntax errors tree-sitter could not parse, and the symbols overlapping them. Those symbols are low-confidence: their extent or signature may be wrong, and definitions inside an error may be missing."
+    )]
+    pub async fn get_diagnostics(
+        &self,
+        Parameters(GetDiagnosticsRequest { path, limit }): Parameters<GetDiagnosticsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.facade.read().await;
+
+        let all = match indexer.get_parse_diagnostics() {
+            Ok(all) => all,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to read parse diagnostics: {e}"
+                ))]));
+            }
+        };
+        let files: Vec<_> = all
+            .iter()
+            .filter(|(file, _)| path.as_deref().is_none_or(|filter| file.contains(filter)))
+            .collect();
+        if files.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(match path {
+                Some(filter) => format!("No parse errors in files matching '{filter}'"),
+                None => "No parse errors in indexed files".to_string(),
+            })]));
+        }
+
+        let mut output = format!("Parse errors in {} file(s):\n\n", files.len());
+        for (file, diagnostics) in files.iter().take(limit as usize) {
+            output.push_str(&format!("{file} ({} error(s))\n", diagnostics.len()));
+            for diagnostic in diagnostics.iter() {
+                output.push_str(&format!(
+                    "  {}:{} {}\n",
+                    diagnostic.range.start_line + 1,
+                    diagnostic.range.start_column + 1,
+                    diagnostic.message()
+                ));
+            }
+            let affected = indexer.get_symbols_affected_by(file, diagnostics);
+            if !affected.is_empty() {
+                output.push_str("  Low-confidence symbols:\n");
+                for symbol in &affected {
+                    output.push_str(&format!(
+                        "    {:?} {} at line {} [symbol_id:{}]\n",
+                        symbol.kind,
+                        symbol.name,
+                        symbol.range.start_line + 1,
+                        symbol.id.value()
+                    ));
+                }
+            }
+            output.push('\n');
+        }
+        if files.len() > limit as usize {
+            output.push_str(&format!(
+                "... and {} more file(s)\n",
+                files.len() - limit as usize
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Run several queries in one call. Each sub-query names a tool (find_symbol, get_calls, find_callers, find_implementations, analyze_impact, search_symbols, semantic_search_docs, semantic_search_with_context, get_file_outline, get_dependency_graph, get_symbol_source, find_similar_symbols, find_unused_symbols) and its arguments; they run concurrently and each result is returned under its key.\n\nUse this when: You already know the next several lookups, e.g. the callers of five functions."
+    )]
+    pub async fn batch_query(
+        &self,
+        Parameters(BatchQueryRequest { queries }): Parameters<BatchQueryRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if queries.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "batch_query needs at least one sub-query",
+            )]));
+        }
+        if queries.len() > MAX_BATCH_QUERIES {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "batch_query accepts at most {MAX_BATCH_QUERIES} sub-queries, got {}",
+                queries.len()
+            ))]));
+        }
+
+        let total = queries.len();
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut slots = std::collections::HashMap::new();
+        for (index, query) in queries.into_iter().enumerate() {
+            let BatchSubQuery {
+                key,
+                tool,
+                arguments,
+            } = query;
+            let key = key.unwrap_or_else(|| (index + 1).to_string());
+            let server = self.clone();
+            let name = tool.clone();
+            let handle = tasks.spawn(async move { server.run_sub_query(&name, arguments).await });
+            slots.insert(handle.id(), (index, key, tool));
+        }
+
+        let mut results = Vec::with_capacity(total);
+        while let Some(joined) = tasks.join_next_with_id().await {
+            let (id, outcome) = match joined {
+                Ok((id, outcome)) => (id, outcome),
+                Err(e) => (e.id(), Err(format!("sub-query failed: {e}"))),
+            };
+            if let Some((index, key, tool)) = slots.remove(&id) {
+                results.push((index, key, tool, outcome));
+            }
+        }
+        results.sort_by_key(|(index, ..)| *index);
+
+        let failed = results
+            .iter()
+            .filter(|(.., outcome)| outcome.is_err())
+            .count();
+        let mut output = format!(
+            "Batch of {total} quer{}",
+            if total == 1 { "y" } else { "ies" }
+        );
+        if failed > 0 {
+            output.push_str(&format!(" ({failed} failed)"));
+        }
+        output.push('\n');
+        for (_, key, tool, outcome) in results {
+            output.push_str(&format!("\n=== {key} ({tool}) ===\n"));
+            match outcome {
+                Ok(text) => output.push_str(text.trim_end()),
+                Err(message) => output.push_str(&format!("Error: {message}")),
+            }
+            output.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Turn a natural-language request into a structured query: the words to search for and the filters it names (lang, kind, path, visibility, repo), using the translator configured by query.translator.\n\nUse this when: A request mixes what to find with where to look, e.g. \"public functions in the auth module that touch the database\"; pass the returned fields to semantic_search_docs."
     )]
-    pub async fn search_documents(
+    pub async fn translate_query(
         &self,
-        Parameters(SearchDocumentsRequest {
-            query,
-            collection,
-            limit,
-        }): Parameters<SearchDocumentsRequest>,
+        Parameters(TranslateQueryRequest { query }): Parameters<TranslateQueryRequest>,
     ) -> Result<CallToolResult, McpError> {
-        let store = match &self.document_store {
-            Some(s) => s,
-            None => {
-                return Ok(CallToolResult::error(vec![Content::text(
-                    "Document search not available. No document collections are indexed.\n\n\
-                    To enable:\n\
-                    1. Add a collection: codanna documents add-collection docs docs/\n\
-                    2. Index it: codanna documents index\n\
-                    3. Restart the MCP server",
-                )]));
-            }
-        };
-
-        let mut store = store.write().await;
-        let indexer = self.facade.read().await;
-
-        // Auto-sync: check for file changes in all collections before searching
-        let settings = indexer.settings();
-        for (name, config) in &settings.documents.collections {
-            if let Err(e) = store.index_collection(name, config, &settings.documents.defaults) {
-                tracing::warn!(target: "rag", "auto-sync failed for collection '{}': {}", name, e);
-            }
+        let settings = self.facade.read().await.settings().clone();
+        // The LLM translator blocks on its HTTP call
+        let structured = tokio::task::spawn_blocking(move || {
+            crate::nl_query::translator(&settings).translate(&query)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Query translation failed: {e}"), None))?;
+
+        if let Err(e) = structured.filter() {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "The request translated to an invalid filter: {e}"
+            ))]));
         }
+        let json = serde_json::to_string_pretty(&structured).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{json}\n\nPass these fields to semantic_search_docs."
+        ))]))
+    }
 
-        let search_query = DocSearchQuery {
-            text: query.clone(),
-            collection,
-            document: None,
-            limit: limit as usize,
-            preview_config: Some(indexer.settings().documents.search.clone()),
+    #[tool(
+        description = "Next page of a tool result that was too large for one response. Pass the cursor shown at the end of the previous page.\n\nUse this when: A result ends with 'Next page: next_page cursor=...'."
+    )]
+    pub async fn next_page(
+        &self,
+        Parameters(NextPageRequest { cursor }): Parameters<NextPageRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(page) = self.pages.take(&cursor) else {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown or expired cursor: {cursor}. Run the original query again."
+            ))]));
         };
-
-        match store.search(search_query) {
-            Ok(results) => {
-                if results.is_empty() {
-                    return Ok(CallToolResult::success(vec![Content::text(format!(
-                        "No documents found for: {query}"
-                    ))]));
-                }
-
-                let mut output = format!(
-                    "Found {} document(s) matching '{}':\n\n",
-                    results.len(),
-                    query
-                );
-
-                for (i, result) in results.iter().enumerate() {
-                    output.push_str(&format!(
-                        "{}. {} (score: {:.3})\n",
-                        i + 1,
-                        result.source_path.display(),
-                        result.similarity
-                    ));
-
-                    if !result.heading_context.is_empty() {
-                        output.push_str(&format!(
-                            "   Context: {}\n",
-                            result.heading_context.join(" > ")
-                        ));
-                    }
-
-                    // Preview is already KWIC-processed with highlighting
-                    output.push_str(&format!("   Preview: {}\n\n", result.content_preview));
-                }
-
-                Ok(CallToolResult::success(vec![Content::text(output)]))
-            }
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "Document search failed: {e}"
-            ))])),
+        let mut text = page.text;
+        if let Some(next) = &page.next_cursor {
+            text.push_str(&pagination::footer(page.index, page.total, next));
         }
+        let mut result = CallToolResult::success(vec![Content::text(text)]);
+        result.meta = page_meta(page.next_cursor.as_deref());
+        Ok(result)
     }
 }
 
-#[tool_handler]
 impl ServerHandler for CodeIntelligenceServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
             capabilities: ServerCapabilities::builder()
+                .enable_experimental_with(ExperimentalCapabilities::from([(
+                    WARM_START_CAPABILITY.to_string(),
+                    JsonObject::new(),
+                )]))
+                .enable_prompts()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_resources_list_changed()
                 .enable_tools()
                 .build(),
             server_info: Implementation {
@@ -1891,7 +3658,8 @@ impl ServerHandler for CodeIntelligenceServer {
                 WORKFLOW: Start with 'semantic_search_with_context' or 'semantic_search_docs' to anchor on the right files and APIs - they provide the highest-quality context. \
                 Then use 'find_symbol' and 'search_symbols' to lock onto exact files and kinds. \
                 Treat 'get_calls', 'find_callers', and 'analyze_impact' as hints; confirm with code reading or tighter queries (unique names, kind filters). \
-                Use 'search_documents' to find relevant project documentation (markdown files). \
+                Use 'search_documents' to find relevant project documentation (markdown files), \
+                or 'search_knowledge' to find code and the docs or ADRs describing it in one query. \
                 Use 'get_index_info' to understand what's indexed."
                 .to_string()
             ),
@@ -1903,6 +3671,12 @@ impl ServerHandler for CodeIntelligenceServer {
         request: InitializeRequestParam,
         context: RequestContext<RoleServer>,
     ) -> Result<InitializeResult, McpError> {
+        let warm_start_requested = request
+            .capabilities
+            .experimental
+            .as_ref()
+            .is_some_and(|experimental| experimental.contains_key(WARM_START_CAPABILITY));
+
         // Register client capabilities (required for MCP handshake)
         if context.peer.peer_info().is_none() {
             context.peer.set_peer_info(request);
@@ -1911,18 +3685,240 @@ impl ServerHandler for CodeIntelligenceServer {
         // Store the peer reference for sending notifications
         let mut peer_guard = self.peer.lock().await;
         *peer_guard = Some(context.peer.clone());
+        drop(peer_guard);
+
+        let mut info = self.get_info();
+        let warm_start = warm_start_requested || self.facade.read().await.settings().mcp.warm_start;
+        if warm_start {
+            if let Some((primer, details)) = self.warm_up().await {
+                info.instructions = Some(match info.instructions {
+                    Some(instructions) => format!("{instructions}\n\n{primer}"),
+                    None => primer,
+                });
+                if let Some(experimental) = info.capabilities.experimental.as_mut() {
+                    experimental.insert(WARM_START_CAPABILITY.to_string(), details);
+                }
+            }
+        }
 
         // Return the server info
-        Ok(self.get_info())
+        Ok(info)
+    }
+
+    async fn call_tool(
+        &self,
+        mut request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        use rmcp::handler::server::tool::ToolCallContext;
+
+        // Handlers run on the executor's runtime, so they need an owned server
+        let tool = request.name.to_string();
+        // The executor keeps a semaphore and metrics per tool name; refuse
+        // names the router does not know before they get one
+        if !self.tool_router.has_route(&tool) {
+            return Err(McpError::invalid_params(
+                format!("Unknown tool '{tool}'"),
+                None,
+            ));
+        }
+        self.permissions.check(&tool)?;
+        let mut required = self.permissions.required_scope(&tool);
+        if tool == "batch_query" {
+            // A batch needs the scope of its most privileged sub-query
+            let queries = request.arguments.as_ref().and_then(|a| a.get("queries"));
+            let sub_tools = queries
+                .and_then(|queries| queries.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|query| query.get("tool")?.as_str());
+            for sub_tool in sub_tools {
+                required = required.max(self.permissions.required_scope(sub_tool));
+            }
+        }
+        auth::check_scope(&context.extensions, &tool, required)?;
+        self.executor.admit(&rate_limit::client_of(
+            &context.extensions,
+            self.connection.as_deref(),
+        ))?;
+        let routed = self.for_project(&mut request.arguments).await?;
+        // Usage in other projects does not describe this one's warm set
+        let usage = match routed {
+            Some(_) => ToolUsage::default(),
+            None => ToolUsage::from_call(&tool, request.arguments.as_ref()),
+        };
+        let ct = context.ct.clone();
+        let progress = context.meta.get_progress_token();
+        let peer = context.peer.clone();
+        let server = routed.unwrap_or_else(|| self.clone());
+        let task = async move {
+            let tcc = ToolCallContext::new(&server, request, context);
+            server.tool_router.call(tcc).await
+        };
+
+        let result = self.executor.execute(&tool, ct, task).await;
+        let succeeded = matches!(&result, Ok(output) if output.is_error != Some(true));
+        if succeeded && !usage.is_empty() {
+            self.record_usage(usage).await;
+        }
+        match result {
+            Ok(output) if succeeded && tool != "next_page" => {
+                Ok(self.paginate(&tool, output, progress, &peer).await)
+            }
+            result => result,
+        }
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult::with_all_items(
+            WorkflowPrompt::ALL.map(WorkflowPrompt::definition).to_vec(),
+        ))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        let prompt = WorkflowPrompt::from_name(&request.name).ok_or_else(|| {
+            McpError::invalid_params(format!("Unknown prompt: {}", request.name), None)
+        })?;
+        let arguments = request.arguments.as_ref();
+        let steps = prompt.steps(arguments)?;
+        self.executor.admit(&rate_limit::client_of(
+            &context.extensions,
+            self.connection.as_deref(),
+        ))?;
+
+        let mut text = prompt.task(arguments);
+        for step in steps {
+            let output = match &step {
+                Step::Tool(tool, arguments) => self.run_sub_query(tool, arguments.clone()).await,
+                Step::UncalledSymbols(path) => {
+                    prompts::uncalled_symbols(&*self.facade.read().await, path)
+                }
+            };
+            let output = output.unwrap_or_else(|e| format!("Error: {e}"));
+            text.push_str(&format!(
+                "\n\n=== {} ===\n{}",
+                step.title(),
+                output.trim_end()
+            ));
+        }
+        Ok(GetPromptResult {
+            description: Some(prompt.definition().description.unwrap_or_default()),
+            messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+        })
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult::with_all_items(resources::list()))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            resources::templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let resource = IndexResource::parse(&request.uri).ok_or_else(|| {
+            McpError::resource_not_found(format!("Unknown resource: {}", request.uri), None)
+        })?;
+        self.executor.admit(&rate_limit::client_of(
+            &context.extensions,
+            self.connection.as_deref(),
+        ))?;
+
+        // Outlines and symbol reads are index work like any tool call
+        let facade = self.facade.clone();
+        let task = async move {
+            let indexer = facade.read().await;
+            resource.read(&indexer, &request.uri)
+        };
+        self.executor
+            .execute_request("resources/read", context.ct, task)
+            .await
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        if IndexResource::parse(&request.uri).is_none() {
+            return Err(McpError::resource_not_found(
+                format!("Unknown resource: {}", request.uri),
+                None,
+            ));
+        }
+        self.executor.admit(&rate_limit::client_of(
+            &context.extensions,
+            self.connection.as_deref(),
+        ))?;
+        self.subscriptions.lock().await.insert(request.uri);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.subscriptions.lock().await.remove(&request.uri);
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self
+                .tool_router
+                .list_all()
+                .into_iter()
+                .filter(|tool| self.permissions.is_enabled(&tool.name))
+                .map(with_project_argument)
+                .collect(),
+            meta: None,
+            next_cursor: None,
+        })
     }
 
     async fn on_custom_request(
         &self,
         request: CustomRequest,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CustomResult, McpError> {
+        self.permissions.check(&request.method)?;
+        auth::check_scope(
+            &context.extensions,
+            &request.method,
+            self.permissions.required_scope(&request.method),
+        )?;
         match request.method.as_str() {
             "requests/codanna/force-reindex" => self.handle_force_reindex(request).await,
+            "requests/codanna/cancel-reindex" => Ok(CustomResult(serde_json::json!({
+                "cancelled": self.cancel_reindex()
+            }))),
             "requests/codanna/index-stats" => self.handle_index_stats().await,
             _ => Err(McpError::new(
                 ErrorCode::METHOD_NOT_FOUND,
@@ -1933,6 +3929,378 @@ impl ServerHandler for CodeIntelligenceServer {
     }
 }
 
+// Warm set tracking and warm start
+impl CodeIntelligenceServer {
+    /// Add the symbols, files and query a tool call used to the warm set
+    /// Rank results near what this session looked up recently, and in the
+    /// files the user is changing, higher
+    fn rerank_for_session<T: session::Ranked>(&self, indexer: &IndexFacade, results: &mut [T]) {
+        let settings = indexer.settings();
+        let working_set = match settings.workspace_root.as_deref() {
+            Some(root) => crate::indexing::WorkingSet::shared(root, &settings.ranking),
+            None => Arc::default(),
+        };
+        let session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+        session.rerank(results, &settings.ranking, &working_set);
+    }
+
+    async fn record_usage(&self, usage: ToolUsage) {
+        let indexer = self.facade.read().await;
+        let mut symbols = Vec::new();
+        if let Some(id) = usage.symbol_id {
+            symbols.extend(indexer.get_symbol(crate::SymbolId(id)));
+        }
+        if let Some(name) = &usage.symbol_name {
+            symbols.extend(indexer.find_symbols_by_name(name, None));
+        }
+        let file = usage
+            .file
+            .as_deref()
+            .and_then(|path| indexer.resolve_indexed_file(path))
+            .map(|(_, stored)| stored);
+        if symbols.is_empty() && file.is_none() && usage.query.is_none() {
+            return;
+        }
+
+        {
+            let mut session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(file) = &file {
+                session.record_file(file);
+            }
+            // An ambiguous name says nothing about which symbol was looked at
+            if let [symbol] = symbols.as_slice() {
+                session.record_symbol(symbol);
+            }
+        }
+
+        let mut names: Vec<&str> = symbols.iter().map(|symbol| symbol.as_name()).collect();
+        let mut files: Vec<&str> = symbols.iter().map(|symbol| &*symbol.file_path).collect();
+        files.extend(file.as_deref());
+        names.sort_unstable();
+        names.dedup();
+        files.sort_unstable();
+        files.dedup();
+
+        WarmSetTracker::shared(indexer.index_base()).record(names, files, usage.query.as_deref());
+    }
+
+    /// Preload the warm set and build the primer sent with the handshake
+    ///
+    /// Returns the primer text and the same data as JSON, or `None` when no
+    /// earlier session recorded anything.
+    async fn warm_up(&self) -> Option<(String, serde_json::Map<String, serde_json::Value>)> {
+        use std::time::Instant;
+
+        let start = Instant::now();
+        let indexer = self.facade.read().await;
+        let set = WarmSetTracker::shared(indexer.index_base()).snapshot();
+        if set.is_empty() {
+            return None;
+        }
+        let limit = indexer.settings().mcp.warm_set_size;
+
+        // Looking symbols up pulls their index pages into memory
+        let symbols: Vec<Symbol> = set
+            .top_symbols(limit)
+            .into_iter()
+            .filter_map(|name| indexer.find_symbols_by_name(name, None).into_iter().next())
+            .collect();
+        let files: Vec<(&str, usize)> = set
+            .top_files(limit)
+            .into_iter()
+            .filter_map(|path| {
+                let file_id = indexer.get_file_id_for_path(path)?;
+                Some((path, indexer.get_symbols_by_file(file_id).len()))
+            })
+            .collect();
+
+        // Embedding the usual queries loads the model before the first search
+        let mut queries_embedded = 0;
+        if indexer.has_semantic_search() {
+            for query in set.top_queries(limit.min(5)) {
+                if indexer.semantic_search_docs(query, 1).is_ok() {
+                    queries_embedded += 1;
+                }
+            }
+        }
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+
+        let mut primer = String::from("WARM SET (most used in earlier sessions):");
+        if !files.is_empty() {
+            let listed: Vec<String> = files
+                .iter()
+                .map(|(path, count)| format!("{path} ({count} symbols)"))
+                .collect();
+            primer.push_str(&format!("\nFiles: {}", listed.join(", ")));
+        }
+        for symbol in &symbols {
+            primer.push_str(&format!(
+                "\n- {} ({:?}) at {}:{}",
+                symbol.name,
+                symbol.kind,
+                symbol.file_path,
+                symbol.range.start_line + 1
+            ));
+            if let Some(doc) = symbol.doc().filter(|doc| !doc.summary.is_empty()) {
+                primer.push_str(&format!(" - {}", doc.summary));
+            }
+        }
+
+        let details = serde_json::json!({
+            "files": files
+                .iter()
+                .map(|(path, count)| serde_json::json!({ "path": path, "symbols": count }))
+                .collect::<Vec<_>>(),
+            "symbols": symbols
+                .iter()
+                .map(|symbol| serde_json::json!({
+                    "name": symbol.name,
+                    "kind": format!("{:?}", symbol.kind),
+                    "file": symbol.file_path,
+                    "line": symbol.range.start_line + 1,
+                    "summary": symbol.doc().map(|doc| doc.summary),
+                }))
+                .collect::<Vec<_>>(),
+            "queries_embedded": queries_embedded,
+            "elapsed_ms": elapsed_ms,
+        });
+        let details = match details {
+            serde_json::Value::Object(details) => details,
+            _ => serde_json::Map::new(),
+        };
+
+        tracing::debug!(
+            target: "mcp",
+            "warm start: {} symbols, {} files, {queries_embedded} queries in {elapsed_ms}ms",
+            symbols.len(),
+            files.len()
+        );
+        Some((primer, details))
+    }
+}
+
+// Batch queries
+impl CodeIntelligenceServer {
+    /// Run one `batch_query` sub-query, returning its text or why it failed
+    async fn run_sub_query(
+        &self,
+        tool: &str,
+        arguments: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<String, String> {
+        fn parse<T: serde::de::DeserializeOwned>(
+            arguments: &serde_json::Map<String, serde_json::Value>,
+        ) -> Result<Parameters<T>, String> {
+            serde_json::from_value(serde_json::Value::Object(arguments.clone()))
+                .map(Parameters)
+                .map_err(|e| format!("invalid arguments: {e}"))
+        }
+
+        self.permissions
+            .check(tool)
+            .map_err(|e| e.message.to_string())?;
+        let result = match tool {
+            "find_symbol" => self.find_symbol(parse(&arguments)?).await,
+            "get_calls" => self.get_calls(parse(&arguments)?).await,
+            "find_callers" => self.find_callers(parse(&arguments)?).await,
+            "find_implementations" => self.find_implementations(parse(&arguments)?).await,
+            "analyze_impact" => self.analyze_impact(parse(&arguments)?).await,
+            "search_symbols" => self.search_symbols(parse(&arguments)?).await,
+            "semantic_search_docs" => self.semantic_search_docs(parse(&arguments)?).await,
+            "semantic_search_with_context" => {
+                self.semantic_search_with_context(parse(&arguments)?).await
+            }
+            "get_file_outline" => self.get_file_outline(parse(&arguments)?).await,
+            "get_dependency_graph" => self.get_dependency_graph(parse(&arguments)?).await,
+            "get_symbol_source" => self.get_symbol_source(parse(&arguments)?).await,
+            "find_similar_symbols" => self.find_similar_symbols(parse(&arguments)?).await,
+            "find_unused_symbols" => self.find_unused_symbols(parse(&arguments)?).await,
+            _ => {
+                return Err(format!(
+                    "unsupported tool '{tool}', use one of: {}",
+                    BATCH_TOOLS.join(", ")
+                ));
+            }
+        }
+        .map_err(|e| e.message.to_string())?;
+
+        let text: Vec<&str> = result
+            .content
+            .iter()
+            .filter_map(|content| match &content.raw {
+                RawContent::Text(block) => Some(block.text.as_str()),
+                _ => None,
+            })
+            .collect();
+        let text = text.join("\n");
+        if result.is_error == Some(true) {
+            return Err(text);
+        }
+
+        let usage = ToolUsage::from_call(tool, Some(&arguments));
+        if !usage.is_empty() {
+            self.record_usage(usage).await;
+        }
+        Ok(text)
+    }
+}
+
+// Project routing
+impl CodeIntelligenceServer {
+    /// Server answering for the project named by the call's `project` argument
+    ///
+    /// Takes the argument out of `arguments`. Returns `None` when no project
+    /// is named or it is the one this server was started in.
+    async fn for_project(
+        &self,
+        arguments: &mut Option<JsonObject>,
+    ) -> Result<Option<Self>, McpError> {
+        let value = arguments
+            .as_mut()
+            .and_then(|arguments| arguments.remove(projects::PROJECT_ARGUMENT));
+        let name = match value {
+            None | Some(serde_json::Value::Null) => return Ok(None),
+            Some(serde_json::Value::String(name)) => name,
+            Some(other) => {
+                return Err(McpError::invalid_params(
+                    format!("project must be a string, got {other}"),
+                    None,
+                ));
+            }
+        };
+
+        let registered = projects::registered().map_err(|e| McpError::internal_error(e, None))?;
+        let project = projects::find(&registered, name.trim())
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        if self.current_root().await.as_deref() == Some(project.path.as_path()) {
+            return Ok(None);
+        }
+        let facade = self
+            .projects
+            .open(project)
+            .await
+            .map_err(|e| McpError::internal_error(e, None))?;
+        // Documents are searched in the project the server was started in only
+        // Symbol IDs of this session mean nothing in another index
+        Ok(Some(Self {
+            facade,
+            document_store: None,
+            session: Arc::default(),
+            ..self.clone()
+        }))
+    }
+
+    /// Canonical workspace root of the project this server was started in
+    async fn current_root(&self) -> Option<std::path::PathBuf> {
+        let root = self.facade.read().await.settings().workspace_root.clone()?;
+        Some(root.canonicalize().unwrap_or(root))
+    }
+}
+
+/// Tool definition with the `project` argument every tool accepts
+fn with_project_argument(mut tool: Tool) -> Tool {
+    if matches!(
+        tool.name.as_ref(),
+        "list_projects" | "next_page" | "recent_context"
+    ) {
+        return tool;
+    }
+    let mut schema = (*tool.input_schema).clone();
+    let properties = schema
+        .entry("properties")
+        .or_insert_with(|| serde_json::Value::Object(JsonObject::new()));
+    if let Some(properties) = properties.as_object_mut() {
+        properties.insert(
+            projects::PROJECT_ARGUMENT.to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": "Registered project to query instead of the current one \
+                    (name, ID or path, see list_projects)"
+            }),
+        );
+    }
+    tool.input_schema = Arc::new(schema);
+    tool
+}
+
+// Result paging
+impl CodeIntelligenceServer {
+    /// Split a result longer than `mcp.max_response_bytes` into pages
+    ///
+    /// Returns the first page with a cursor to the rest, or streams every
+    /// page as progress notifications when `mcp.stream_pages` is set and the
+    /// client sent a progress token.
+    async fn paginate(
+        &self,
+        tool: &str,
+        mut result: CallToolResult,
+        progress: Option<ProgressToken>,
+        peer: &Peer<RoleServer>,
+    ) -> CallToolResult {
+        let (max_bytes, stream) = {
+            let indexer = self.facade.read().await;
+            let mcp = &indexer.settings().mcp;
+            (mcp.max_response_bytes, mcp.stream_pages)
+        };
+        let [content] = result.content.as_slice() else {
+            return result;
+        };
+        let RawContent::Text(block) = &content.raw else {
+            return result;
+        };
+        if max_bytes == 0 || block.text.len() <= max_bytes {
+            return result;
+        }
+        let pages = pagination::split_pages(&block.text, max_bytes);
+        let total = pages.len();
+
+        if let (true, Some(token)) = (stream, progress) {
+            match stream_pages(peer, token, &pages).await {
+                Ok(()) => {
+                    result.content = vec![Content::text(format!(
+                        "{tool} result streamed as {total} progress notification(s)"
+                    ))];
+                    return result;
+                }
+                Err(e) => tracing::warn!("[mcp] streaming {tool} result failed, paging: {e}"),
+            }
+        }
+
+        let mut text = pages[0].clone();
+        let cursor = self.pages.insert(tool, pages);
+        text.push_str(&pagination::footer(0, total, &cursor));
+        result.content = vec![Content::text(text)];
+        result.meta = page_meta(Some(&cursor));
+        result
+    }
+}
+
+async fn stream_pages(
+    peer: &Peer<RoleServer>,
+    token: ProgressToken,
+    pages: &[String],
+) -> Result<(), ServiceError> {
+    for (index, page) in pages.iter().enumerate() {
+        peer.notify_progress(ProgressNotificationParam {
+            progress_token: token.clone(),
+            progress: (index + 1) as f64,
+            total: Some(pages.len() as f64),
+            message: Some(page.clone()),
+        })
+        .await?;
+    }
+    Ok(())
+}
+
+/// `_meta` of a paged result, carrying the cursor of the next page
+fn page_meta(next_cursor: Option<&str>) -> Option<Meta> {
+    next_cursor.map(|cursor| {
+        Meta(JsonObject::from_iter([(
+            "nextCursor".to_string(),
+            serde_json::Value::from(cursor),
+        )]))
+    })
+}
+
 // Custom request handlers
 impl CodeIntelligenceServer {
     /// Handle force-reindex request
@@ -1949,11 +4317,18 @@ impl CodeIntelligenceServer {
             .and_then(|v| serde_json::from_value(v.clone()).ok());
 
         let mut indexer = self.facade.write().await;
+        let cancel = indexer.indexing_cancel();
+        *self.reindexing.lock().unwrap_or_else(|e| e.into_inner()) = Some(cancel.clone());
+        let mut cancelled = false;
 
         let (reindexed, symbols) = if let Some(paths) = paths {
             // Reindex specific paths
             let mut total_reindexed = 0;
             for path in &paths {
+                if cancel.is_cancelled() {
+                    cancelled = true;
+                    break;
+                }
                 let path = std::path::Path::new(path);
                 if path.is_file() {
                     match indexer.index_file(path) {
@@ -1966,6 +4341,10 @@ impl CodeIntelligenceServer {
                 } else if path.is_dir() {
                     match indexer.index_directory(path, false) {
                         Ok(stats) => total_reindexed += stats.files_indexed,
+                        Err(e) if e.is_cancelled() => {
+                            cancelled = true;
+                            break;
+                        }
                         Err(e) => {
                             tracing::warn!("Failed to reindex {}: {e}", path.display());
                         }
@@ -1982,6 +4361,10 @@ impl CodeIntelligenceServer {
                 if path.is_dir() {
                     match indexer.index_directory(path, false) {
                         Ok(stats) => total_reindexed += stats.files_indexed,
+                        Err(e) if e.is_cancelled() => {
+                            cancelled = true;
+                            break;
+                        }
                         Err(e) => {
                             tracing::warn!("Failed to reindex {}: {e}", path.display());
                         }
@@ -1990,12 +4373,14 @@ impl CodeIntelligenceServer {
             }
             (total_reindexed, indexer.symbol_count())
         };
+        *self.reindexing.lock().unwrap_or_else(|e| e.into_inner()) = None;
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
         Ok(CustomResult(serde_json::json!({
             "reindexed": reindexed,
             "symbols": symbols,
+            "cancelled": cancelled,
             "duration_ms": duration_ms
         })))
     }
@@ -2041,3 +4426,43 @@ impl CodeIntelligenceServer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SymbolKind, Visibility};
+
+    #[test]
+    fn test_batch_query_description_lists_every_batch_tool() {
+        let tool = CodeIntelligenceServer::tool_router()
+            .list_all()
+            .into_iter()
+            .find(|tool| tool.name == "batch_query")
+            .unwrap();
+        let description = tool.description.unwrap_or_default();
+        for name in BATCH_TOOLS {
+            assert!(description.contains(name), "{name} missing");
+        }
+    }
+    #[test]
+    fn test_semantic_search_filters_resolve() {
+        let filters = |kind: Option<&str>, visibility: Option<&str>| SemanticSearchFilters {
+            kind: kind.map(str::to_string),
+            path: Some("src/**".to_string()),
+            visibility: visibility.map(str::to_string),
+            repo: Some("core".to_string()),
+        };
+
+        let filter = filters(Some("method"), Some("crate"))
+            .resolve(Some("rust".to_string()))
+            .unwrap();
+        assert_eq!(filter.language.as_deref(), Some("rust"));
+        assert_eq!(filter.kind, Some(SymbolKind::Method));
+        assert_eq!(filter.visibility, Some(Visibility::Crate));
+        assert_eq!(filter.repository.as_deref(), Some("core"));
+        assert!(filter.path.unwrap().matches("src/lib.rs"));
+
+        assert!(filters(Some("Widget"), None).resolve(None).is_err());
+        assert!(filters(None, Some("internal")).resolve(None).is_err());
+    }
+}
@@ -2,18 +2,94 @@
 //!
 //! This module provides a broadcast channel for file change events
 //! that can be shared between file watchers and multiple MCP server instances.
+//!
+//! Code file events carry the [`SymbolChanges`] of the file, which clients
+//! receive in `notifications/codanna/file-reindexed` and `file-deleted`. An
+//! agent that caches tool results drops the entries naming those symbols or
+//! the stale ids instead of re-querying everything.
 
+use crate::storage::event_log::{SymbolRef, symbol_delta};
+use crate::{Symbol, SymbolId, SymbolKind};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 use tokio::sync::broadcast;
 
 #[derive(Debug, Clone)]
 pub enum FileChangeEvent {
-    FileReindexed { path: PathBuf },
-    FileCreated { path: PathBuf },
-    FileDeleted { path: PathBuf },
+    /// `symbols` is set for code files, not for documents
+    FileReindexed {
+        path: PathBuf,
+        symbols: Option<SymbolChanges>,
+    },
+    FileCreated {
+        path: PathBuf,
+    },
+    FileDeleted {
+        path: PathBuf,
+        symbols: Option<SymbolChanges>,
+    },
     IndexReloaded, // Entire index was reloaded from disk
 }
 
+/// How the symbols of one file changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SymbolChanges {
+    /// Symbols with no counterpart of the same name and kind before
+    pub added: Vec<SymbolRef>,
+    /// Symbols with no counterpart after, with their old ids
+    pub removed: Vec<SymbolRef>,
+    /// Symbols whose signature, documentation or length changed, with their new ids
+    pub modified: Vec<SymbolRef>,
+    /// Ids the file's symbols had before, no longer valid
+    pub stale_ids: Vec<SymbolId>,
+}
+
+impl SymbolChanges {
+    /// Changes from the symbols a file had to the symbols it has now
+    pub fn between(before: &[Symbol], after: &[Symbol]) -> Self {
+        let refs = |symbols: &[Symbol]| symbols.iter().map(SymbolRef::from).collect::<Vec<_>>();
+        let (added, removed) = symbol_delta(&refs(before), &refs(after));
+
+        let mut previous: HashMap<(&str, SymbolKind), VecDeque<&Symbol>> = HashMap::new();
+        for symbol in before {
+            previous
+                .entry((&*symbol.name, symbol.kind))
+                .or_default()
+                .push_back(symbol);
+        }
+        let modified = after
+            .iter()
+            .filter(|symbol| {
+                previous
+                    .get_mut(&(&*symbol.name, symbol.kind))
+                    .and_then(VecDeque::pop_front)
+                    .is_some_and(|old| changed(old, symbol))
+            })
+            .map(SymbolRef::from)
+            .collect();
+
+        let current: HashSet<SymbolId> = after.iter().map(|symbol| symbol.id).collect();
+        let stale_ids = before
+            .iter()
+            .map(|symbol| symbol.id)
+            .filter(|id| !current.contains(id))
+            .collect();
+
+        Self {
+            added,
+            removed,
+            modified,
+            stale_ids,
+        }
+    }
+}
+
+fn changed(old: &Symbol, new: &Symbol) -> bool {
+    let lines = |symbol: &Symbol| symbol.range.end_line - symbol.range.start_line;
+    old.signature != new.signature || old.doc_comment != new.doc_comment || lines(old) != lines(new)
+}
+
 /// Manages notification broadcasting to multiple MCP server instances
 #[derive(Clone)]
 pub struct NotificationBroadcaster {
@@ -68,7 +144,7 @@ impl super::CodeIntelligenceServer {
                     let peer_guard = self.peer.lock().await;
                     if let Some(peer) = peer_guard.as_ref() {
                         match event {
-                            FileChangeEvent::FileReindexed { path } => {
+                            FileChangeEvent::FileReindexed { path, symbols } => {
                                 let path_str = path.display().to_string();
 
                                 // Send standard MCP resource updated notification (backwards compatible)
@@ -85,19 +161,36 @@ impl super::CodeIntelligenceServer {
                                         logger: Some("codanna".to_string()),
                                         data: serde_json::json!({
                                             "action": "re-indexed",
-                                            "file": path_str
+                                            "file": path_str,
+                                            "symbols_changed": symbols.as_ref().map(|changes| {
+                                                changes.added.len()
+                                                    + changes.removed.len()
+                                                    + changes.modified.len()
+                                            }),
                                         }),
                                     })
                                     .await;
 
+                                // Subscribed codanna:// resources covering this file
+                                let updated = {
+                                    let indexer = self.facade.read().await;
+                                    let subscriptions = self.subscriptions.lock().await;
+                                    super::resources::updated_by(&indexer, &subscriptions, &path)
+                                };
+                                for uri in updated {
+                                    let _ = peer
+                                        .notify_resource_updated(ResourceUpdatedNotificationParam {
+                                            uri,
+                                        })
+                                        .await;
+                                }
+
                                 // Send custom notification (new)
                                 let _ = peer
                                     .send_notification(ServerNotification::CustomNotification(
                                         CustomNotification::new(
                                             "notifications/codanna/file-reindexed",
-                                            Some(serde_json::json!({
-                                                "path": path_str
-                                            })),
+                                            Some(file_event(&path_str, symbols.as_ref())),
                                         ),
                                     ))
                                     .await;
@@ -131,18 +224,30 @@ impl super::CodeIntelligenceServer {
                                     path.display()
                                 );
                             }
-                            FileChangeEvent::FileDeleted { path } => {
+                            FileChangeEvent::FileDeleted { path, symbols } => {
                                 let path_str = path.display().to_string();
                                 let _ = peer.notify_resource_list_changed().await;
 
+                                // Outlines and symbols of the file are gone
+                                let updated = {
+                                    let indexer = self.facade.read().await;
+                                    let subscriptions = self.subscriptions.lock().await;
+                                    super::resources::updated_by(&indexer, &subscriptions, &path)
+                                };
+                                for uri in updated {
+                                    let _ = peer
+                                        .notify_resource_updated(ResourceUpdatedNotificationParam {
+                                            uri,
+                                        })
+                                        .await;
+                                }
+
                                 // Send custom notification
                                 let _ = peer
                                     .send_notification(ServerNotification::CustomNotification(
                                         CustomNotification::new(
                                             "notifications/codanna/file-deleted",
-                                            Some(serde_json::json!({
-                                                "path": path_str
-                                            })),
+                                            Some(file_event(&path_str, symbols.as_ref())),
                                         ),
                                     ))
                                     .await;
@@ -157,6 +262,17 @@ impl super::CodeIntelligenceServer {
                             FileChangeEvent::IndexReloaded => {
                                 let _ = peer.notify_resource_list_changed().await;
 
+                                // Any subscribed resource may have changed
+                                let subscribed: Vec<String> =
+                                    self.subscriptions.lock().await.iter().cloned().collect();
+                                for uri in subscribed {
+                                    let _ = peer
+                                        .notify_resource_updated(ResourceUpdatedNotificationParam {
+                                            uri,
+                                        })
+                                        .await;
+                                }
+
                                 // Send custom notification
                                 let _ = peer
                                     .send_notification(ServerNotification::CustomNotification(
@@ -185,3 +301,57 @@ impl super::CodeIntelligenceServer {
         }
     }
 }
+
+/// Parameters of a file notification, with its symbol changes if known
+fn file_event(path: &str, symbols: Option<&SymbolChanges>) -> serde_json::Value {
+    let mut params = serde_json::json!({ "path": path });
+    if let Some(changes) = symbols.and_then(|changes| serde_json::to_value(changes).ok()) {
+        params["symbols"] = changes;
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range};
+
+    fn symbol(id: u32, name: &str, range: Range) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            range,
+        )
+    }
+
+    #[test]
+    fn test_symbol_changes_between_versions() {
+        let before = vec![
+            symbol(1, "parse", Range::new(0, 0, 10, 1)),
+            symbol(2, "load", Range::new(12, 0, 14, 1)),
+            symbol(3, "old_helper", Range::new(16, 0, 18, 1)),
+        ];
+        // `parse` moved down unchanged, `load` grew, `old_helper` became `helper`
+        let after = vec![
+            symbol(4, "parse", Range::new(2, 0, 12, 1)),
+            symbol(5, "load", Range::new(14, 0, 20, 1)),
+            symbol(6, "helper", Range::new(22, 0, 24, 1)),
+        ];
+
+        let changes = SymbolChanges::between(&before, &after);
+        let names = |symbols: &[SymbolRef]| -> Vec<String> {
+            symbols.iter().map(|symbol| symbol.name.clone()).collect()
+        };
+        assert_eq!(names(&changes.added), ["helper"]);
+        assert_eq!(names(&changes.removed), ["old_helper"]);
+        assert_eq!(names(&changes.modified), ["load"]);
+        assert_eq!(changes.modified[0].id, SymbolId(5));
+        assert_eq!(changes.stale_ids, [SymbolId(1), SymbolId(2), SymbolId(3)]);
+
+        let json = file_event("src/lib.rs", Some(&changes));
+        assert_eq!(json["symbols"]["removed"][0]["name"], "old_helper");
+        assert!(file_event("docs/guide.md", None).get("symbols").is_none());
+    }
+}
@@ -0,0 +1,211 @@
+//! Cursor-based paging of large tool results
+//!
+//! A tool result longer than `mcp.max_response_bytes` is split into pages at
+//! line boundaries. The first page is returned with a cursor, and the rest
+//! are kept in a [`PageStore`] until the client fetches them with the
+//! `next_page` tool or they expire. The cursor is also returned as
+//! `_meta.nextCursor` for clients that page programmatically.
+//!
+//! With `mcp.stream_pages` set, a call that carries a progress token gets
+//! every page as a `notifications/progress` message instead, on transports
+//! that deliver notifications while the request is open.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long unread pages are kept
+const PAGE_TTL: Duration = Duration::from_secs(600);
+
+/// Paged results kept per session before the oldest is dropped
+const MAX_PENDING_RESULTS: usize = 64;
+
+/// Split `text` into pages of at most `max_bytes`, breaking between lines
+///
+/// A page ends before a line that starts a new top-level entry when one is
+/// close enough to the limit, so entries with indented details stay whole.
+/// Lines longer than a page are split at a character boundary.
+pub fn split_pages(text: &str, max_bytes: usize) -> Vec<String> {
+    if max_bytes == 0 || text.len() <= max_bytes {
+        return vec![text.to_string()];
+    }
+
+    let mut pages = Vec::new();
+    let mut page = String::new();
+    // Length of `page` before its last top-level line
+    let mut entry_start = 0;
+    for line in text.split_inclusive('\n') {
+        if page.len() + line.len() > max_bytes && !page.is_empty() {
+            let rest = if entry_start > max_bytes / 2 {
+                page.split_off(entry_start)
+            } else {
+                String::new()
+            };
+            pages.push(std::mem::replace(&mut page, rest));
+            entry_start = 0;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            entry_start = page.len();
+        }
+        page.push_str(line);
+        while page.len() > max_bytes {
+            let mut at = max_bytes;
+            while !page.is_char_boundary(at) {
+                at -= 1;
+            }
+            let rest = page.split_off(at);
+            pages.push(std::mem::replace(&mut page, rest));
+            entry_start = 0;
+        }
+    }
+    if !page.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+/// One page of a stored result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub tool: String,
+    pub text: String,
+    /// Zero-based position of this page
+    pub index: usize,
+    pub total: usize,
+    /// Cursor of the following page, if any
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug)]
+struct PendingResult {
+    tool: String,
+    pages: Vec<String>,
+    created: Instant,
+}
+
+/// Pages of large results waiting to be fetched
+#[derive(Debug, Default)]
+pub struct PageStore {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, PendingResult>>,
+}
+
+impl PageStore {
+    /// Keep the pages of a result whose first page was sent, returning the
+    /// cursor of the second
+    pub fn insert(&self, tool: &str, pages: Vec<String>) -> String {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut pending = self.lock();
+        let now = Instant::now();
+        pending.retain(|_, result| now.duration_since(result.created) < PAGE_TTL);
+        if pending.len() >= MAX_PENDING_RESULTS {
+            let oldest = pending
+                .iter()
+                .min_by_key(|(_, result)| result.created)
+                .map(|(id, _)| *id);
+            if let Some(oldest) = oldest {
+                pending.remove(&oldest);
+            }
+        }
+        pending.insert(
+            id,
+            PendingResult {
+                tool: tool.to_string(),
+                pages,
+                created: now,
+            },
+        );
+        cursor(id, 1)
+    }
+
+    /// Page at `cursor`; results are dropped once their last page is read
+    pub fn take(&self, cursor: &str) -> Option<Page> {
+        let (id, index) = parse_cursor(cursor)?;
+        let mut pending = self.lock();
+        let result = pending.get(&id)?;
+        if result.created.elapsed() >= PAGE_TTL {
+            pending.remove(&id);
+            return None;
+        }
+        let total = result.pages.len();
+        let page = Page {
+            tool: result.tool.clone(),
+            text: result.pages.get(index)?.clone(),
+            index,
+            total,
+            next_cursor: (index + 1 < total).then(|| self::cursor(id, index + 1)),
+        };
+        if page.next_cursor.is_none() {
+            pending.remove(&id);
+        }
+        Some(page)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, PendingResult>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Footer appended to every page but the last
+pub fn footer(index: usize, total: usize, next_cursor: &str) -> String {
+    format!(
+        "\n--- Page {} of {total}. Next page: next_page cursor=\"{next_cursor}\" ---\n",
+        index + 1
+    )
+}
+
+fn cursor(id: u64, index: usize) -> String {
+    format!("{id:x}.{index}")
+}
+
+fn parse_cursor(cursor: &str) -> Option<(u64, usize)> {
+    let (id, index) = cursor.trim().split_once('.')?;
+    Some((u64::from_str_radix(id, 16).ok()?, index.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pages_keeps_entries_whole() {
+        let text = "Callers of parse (3):\n\
+                    - load at src/a.rs:10\n    called with config\n\
+                    - reload at src/b.rs:20\n    called with path\n\
+                    - main at src/main.rs:5\n";
+        let pages = split_pages(text, 70);
+        assert_eq!(pages.concat(), text);
+        assert!(pages.iter().all(|page| page.len() <= 70));
+        assert!(pages[1].starts_with("- reload"));
+
+        assert_eq!(split_pages(text, 0), vec![text.to_string()]);
+        let long = "é".repeat(40);
+        let pages = split_pages(&long, 25);
+        assert_eq!(pages.concat(), long);
+        assert!(pages.iter().all(|page| page.len() <= 25));
+    }
+
+    #[test]
+    fn test_page_store_walks_cursors() {
+        let store = PageStore::default();
+        let pages = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+        let cursor = store.insert("find_callers", pages);
+
+        let second = store.take(&cursor).unwrap();
+        assert_eq!(
+            (second.text.as_str(), second.index, second.total),
+            ("two", 1, 3)
+        );
+        // A page can be fetched again until the last one is read
+        assert_eq!(store.take(&cursor).unwrap(), second);
+
+        let last = store.take(second.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!(last.text, "three");
+        assert_eq!(last.next_cursor, None);
+        assert!(store.take(&cursor).is_none());
+        assert!(store.take("not-a-cursor").is_none());
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-tool permissions for MCP clients
+//!
+//! `[mcp.permissions]` narrows what clients can do, independently of bearer
+//! tokens: tools outside `allowed` or listed in `disabled` are left out of
+//! `tools/list` and refused when called, also inside `batch_query` and
+//! prompts. Mutating tools, the built-in reindex ones plus any listed in
+//! `mutating`, need a token with reindex scope on the network transports,
+//! and with `read_only` they are refused on every transport.
+//!
+//! Names are tool names or custom request methods such as
+//! `requests/codanna/force-reindex`.
+
+use super::auth;
+use crate::config::{TokenScope, ToolPermissions};
+use rmcp::model::ErrorData as McpError;
+use std::collections::HashSet;
+
+/// Tool permissions resolved from settings
+#[derive(Debug, Clone, Default)]
+pub struct ToolPolicy {
+    allowed: HashSet<String>,
+    disabled: HashSet<String>,
+    mutating: HashSet<String>,
+    read_only: bool,
+}
+
+impl ToolPolicy {
+    pub fn from_config(config: &ToolPermissions) -> Self {
+        Self {
+            allowed: config.allowed.iter().cloned().collect(),
+            disabled: config.disabled.iter().cloned().collect(),
+            mutating: config.mutating.iter().cloned().collect(),
+            read_only: config.read_only,
+        }
+    }
+
+    /// Whether clients may see and call `method` at all
+    ///
+    /// `next_page` only continues results of other tools, so `allowed`
+    /// does not need to list it.
+    pub fn is_enabled(&self, method: &str) -> bool {
+        let allowed =
+            self.allowed.is_empty() || self.allowed.contains(method) || method == "next_page";
+        allowed && !self.disabled.contains(method) && !(self.read_only && self.is_mutating(method))
+    }
+
+    /// Whether `method` changes the index
+    pub fn is_mutating(&self, method: &str) -> bool {
+        auth::required_scope(method) == TokenScope::Reindex || self.mutating.contains(method)
+    }
+
+    /// Scope a token needs to call `method`
+    pub fn required_scope(&self, method: &str) -> TokenScope {
+        if self.is_mutating(method) {
+            TokenScope::Reindex
+        } else {
+            TokenScope::Read
+        }
+    }
+
+    /// Refuse `method` if it is disabled
+    pub fn check(&self, method: &str) -> Result<(), McpError> {
+        if self.is_enabled(method) {
+            return Ok(());
+        }
+        tracing::debug!(target: "mcp", "tool refused by [mcp.permissions]: {method}");
+        let reason = if self.read_only && self.is_mutating(method) {
+            "the server is read-only"
+        } else {
+            "it is disabled in [mcp.permissions]"
+        };
+        Err(McpError::invalid_request(
+            format!("'{method}' is not available: {reason}"),
+            None,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_policy_filters_and_scopes_tools() {
+        let policy = ToolPolicy::default();
+        assert!(policy.is_enabled("get_symbol_source"));
+        assert!(policy.is_enabled("reindex_paths"));
+        assert_eq!(policy.required_scope("reindex_paths"), TokenScope::Reindex);
+        assert_eq!(policy.required_scope("find_symbol"), TokenScope::Read);
+
+        let policy = ToolPolicy::from_config(&ToolPermissions {
+            allowed: Vec::new(),
+            disabled: names(&["get_symbol_source"]),
+            mutating: names(&["find_discussions"]),
+            read_only: true,
+        });
+        assert!(policy.is_enabled("find_symbol"));
+        assert!(!policy.is_enabled("get_symbol_source"));
+        assert!(!policy.is_enabled("reindex_paths"));
+        assert!(!policy.is_enabled("requests/codanna/force-reindex"));
+        assert!(!policy.is_enabled("find_discussions"));
+        let error = policy.check("reindex_paths").unwrap_err();
+        assert!(error.message.contains("read-only"));
+
+        let policy = ToolPolicy::from_config(&ToolPermissions {
+            allowed: names(&["search_symbols", "semantic_search_docs"]),
+            ..ToolPermissions::default()
+        });
+        assert!(policy.is_enabled("search_symbols"));
+        assert!(!policy.is_enabled("get_calls"));
+        assert!(policy.check("get_calls").is_err());
+        assert!(policy.is_enabled("next_page"));
+    }
+}
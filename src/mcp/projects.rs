@@ -0,0 +1,199 @@
+//! Routing MCP tool calls to other registered projects
+//!
+//! Every project set up with `codanna init` is recorded in the global
+//! registry (`~/.codanna/projects.json`). A tool call with a `project`
+//! argument runs against that project's index instead of the one the server
+//! was started in, so a single MCP server configuration covers all of a
+//! user's repositories. `list_projects` shows the names that can be used.
+//!
+//! Other projects' indexes are opened on first use and kept open up to
+//! `mcp.max_open_projects`; the least recently used one is closed first.
+
+use crate::config::McpConfig;
+use crate::indexing::facade::IndexFacade;
+use crate::init::ProjectRegistry;
+use crate::{IndexPersistence, Settings};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{Mutex, RwLock};
+
+/// Tool argument naming the project to query
+pub const PROJECT_ARGUMENT: &str = "project";
+
+static SHARED: OnceLock<Arc<ProjectRouter>> = OnceLock::new();
+
+/// A project from the global registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Registered projects, sorted by name
+pub fn registered() -> Result<Vec<Project>, String> {
+    let registry = ProjectRegistry::load().map_err(|e| e.to_string())?;
+    let mut projects: Vec<Project> = registry
+        .projects()
+        .map(|(id, info)| Project {
+            id: id.to_string(),
+            name: info.name.clone(),
+            path: info.path.clone(),
+        })
+        .collect();
+    projects.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+    Ok(projects)
+}
+
+/// Project named by `query`: its directory name, registry ID or path
+pub fn find<'a>(projects: &'a [Project], query: &str) -> Result<&'a Project, String> {
+    if let Some(project) = projects.iter().find(|project| project.id == query) {
+        return Ok(project);
+    }
+    let path = Path::new(query);
+    if path.is_absolute() {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if let Some(project) = projects.iter().find(|project| project.path == canonical) {
+            return Ok(project);
+        }
+    }
+
+    let named: Vec<&Project> = projects.iter().filter(|p| p.name == query).collect();
+    match named.as_slice() {
+        [project] => Ok(project),
+        [] => {
+            let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+            Err(format!(
+                "Unknown project '{query}'. Registered projects: {}",
+                if names.is_empty() {
+                    "none (run 'codanna init' in a project)".to_string()
+                } else {
+                    names.join(", ")
+                }
+            ))
+        }
+        several => {
+            let paths: Vec<String> = several
+                .iter()
+                .map(|p| p.path.display().to_string())
+                .collect();
+            Err(format!(
+                "{} projects are named '{query}'; pass the path instead: {}",
+                several.len(),
+                paths.join(", ")
+            ))
+        }
+    }
+}
+
+/// Indexes of other projects opened by this process
+pub struct ProjectRouter {
+    max_open: usize,
+    /// Most recently used first
+    open: Mutex<Vec<(PathBuf, Arc<RwLock<IndexFacade>>)>>,
+}
+
+impl ProjectRouter {
+    pub fn new(max_open: usize) -> Self {
+        Self {
+            max_open: max_open.max(1),
+            open: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get the process-wide router, creating it from `config` on first use
+    pub fn shared(config: &McpConfig) -> Arc<Self> {
+        SHARED
+            .get_or_init(|| Arc::new(Self::new(config.max_open_projects)))
+            .clone()
+    }
+
+    /// Index of `project`, opened now if it is not open yet
+    pub async fn open(&self, project: &Project) -> Result<Arc<RwLock<IndexFacade>>, String> {
+        let mut open = self.open.lock().await;
+        if let Some(position) = open.iter().position(|(path, _)| *path == project.path) {
+            let entry = open.remove(position);
+            let facade = entry.1.clone();
+            open.insert(0, entry);
+            return Ok(facade);
+        }
+
+        let path = project.path.clone();
+        let facade = tokio::task::spawn_blocking(move || load(&path))
+            .await
+            .map_err(|e| format!("Failed to open project '{}': {e}", project.name))??;
+        tracing::info!(
+            "[projects] opened index of '{}' at {}",
+            project.name,
+            project.path.display()
+        );
+        let facade = Arc::new(RwLock::new(facade));
+        open.insert(0, (project.path.clone(), facade.clone()));
+        let kept = open.len().min(self.max_open);
+        let closed = open.split_off(kept);
+        if !closed.is_empty() {
+            for (path, _) in &closed {
+                crate::debug_event!("projects", "closed", "{}", path.display());
+            }
+            drop(closed);
+            let pruned = crate::types::intern::prune();
+            crate::debug_event!("projects", "released", "{pruned} interned strings");
+        }
+        Ok(facade)
+    }
+
+    /// Whether the index of the project at `path` is open
+    pub async fn is_open(&self, path: &Path) -> bool {
+        self.open.lock().await.iter().any(|(open, _)| open == path)
+    }
+}
+
+/// Open the index of the project rooted at `root` with its own settings
+fn load(root: &Path) -> Result<IndexFacade, String> {
+    let config = root
+        .join(crate::init::local_dir_name())
+        .join("settings.toml");
+    if !config.exists() {
+        return Err(format!("{} has no codanna configuration", root.display()));
+    }
+    let mut settings =
+        Settings::load_from(&config).map_err(|e| format!("{}: {e}", config.display()))?;
+    settings.index_path = crate::init::resolve_index_path(&settings, Some(&config));
+    if settings.workspace_root.is_none() {
+        settings.workspace_root = Some(root.to_path_buf());
+    }
+    IndexPersistence::new(settings.index_path.clone())
+        .load_facade_lazy(Arc::new(settings))
+        .map_err(|e| format!("Failed to load index of {}: {e}", root.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: &str, name: &str, path: &str) -> Project {
+        Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn test_find_project_by_name_id_or_path() {
+        let projects = vec![
+            project("a1", "api", "/src/work/api"),
+            project("b2", "web", "/src/work/web"),
+            project("c3", "web", "/src/personal/web"),
+        ];
+
+        assert_eq!(find(&projects, "api").unwrap().id, "a1");
+        assert_eq!(find(&projects, "c3").unwrap().name, "web");
+        assert_eq!(find(&projects, "/src/work/web").unwrap().id, "b2");
+
+        let ambiguous = find(&projects, "web").unwrap_err();
+        assert!(ambiguous.contains("/src/personal/web"));
+        let unknown = find(&projects, "cli").unwrap_err();
+        assert!(unknown.contains("api, web, web"));
+    }
+}
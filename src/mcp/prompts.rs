@@ -0,0 +1,277 @@
+//! MCP prompts for common code-intelligence workflows
+//!
+//! Each prompt is a canned workflow a client UI can offer as one click. On
+//! `prompts/get` the server runs the codanna tools the workflow needs and
+//! returns a single user message: the task, followed by the tool results it
+//! should be answered from. The model starts with the context an agent would
+//! otherwise gather over several tool calls.
+
+use super::format_outline_nodes;
+use crate::SymbolKind;
+use crate::indexing::facade::IndexFacade;
+use crate::symbol::outline::OutlineNode;
+use rmcp::model::{ErrorData as McpError, JsonObject, Prompt, PromptArgument};
+use serde_json::{Map, Value, json};
+
+/// Most uncalled symbols listed by `find_dead_code`
+const MAX_DEAD_CODE_CANDIDATES: usize = 50;
+
+/// A workflow offered as an MCP prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkflowPrompt {
+    ExplainSymbol,
+    AssessImpact,
+    FindDeadCode,
+}
+
+/// One step of a workflow
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// Call a tool the way `batch_query` does
+    Tool(&'static str, Map<String, Value>),
+    /// List the functions of a file nothing calls
+    UncalledSymbols(String),
+}
+
+impl WorkflowPrompt {
+    pub const ALL: [Self; 3] = [Self::ExplainSymbol, Self::AssessImpact, Self::FindDeadCode];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|prompt| prompt.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ExplainSymbol => "explain_symbol",
+            Self::AssessImpact => "assess_impact",
+            Self::FindDeadCode => "find_dead_code",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::ExplainSymbol => {
+                "Explain what a symbol does, with its definition, callees and callers"
+            }
+            Self::AssessImpact => {
+                "Assess what breaks if a symbol changes, from its impact radius and callers"
+            }
+            Self::FindDeadCode => "Find functions and methods in a file that nothing calls",
+        }
+    }
+
+    /// Definition listed by `prompts/list`
+    pub fn definition(self) -> Prompt {
+        let argument = |name: &str, description: &str, required: bool| PromptArgument {
+            name: name.to_string(),
+            title: None,
+            description: Some(description.to_string()),
+            required: Some(required),
+        };
+        let arguments = match self {
+            Self::ExplainSymbol => vec![
+                argument("symbol", "Name of the function, type or method", true),
+                argument("lang", "Language filter, e.g. 'rust'", false),
+            ],
+            Self::AssessImpact => vec![
+                argument("symbol", "Name of the symbol about to change", true),
+                argument(
+                    "max_depth",
+                    "How far to follow dependents (default: 3)",
+                    false,
+                ),
+            ],
+            Self::FindDeadCode => vec![argument(
+                "path",
+                "Indexed file to check, or a unique suffix of its path",
+                true,
+            )],
+        };
+        Prompt::new(self.name(), Some(self.description()), Some(arguments))
+    }
+
+    /// Tool calls gathering the context of the prompt
+    pub fn steps(self, arguments: Option<&JsonObject>) -> Result<Vec<Step>, McpError> {
+        let tool = |name: &'static str, arguments: Value| match arguments {
+            Value::Object(map) => Step::Tool(name, map),
+            _ => unreachable!("tool arguments are built as objects"),
+        };
+        Ok(match self {
+            Self::ExplainSymbol => {
+                let symbol = required(arguments, "symbol")?;
+                let mut find = json!({ "name": symbol });
+                if let Some(lang) = optional(arguments, "lang") {
+                    find["lang"] = json!(lang);
+                }
+                vec![
+                    tool("find_symbol", find),
+                    tool("get_calls", json!({ "function_name": symbol })),
+                    tool("find_callers", json!({ "function_name": symbol })),
+                ]
+            }
+            Self::AssessImpact => {
+                let symbol = required(arguments, "symbol")?;
+                let max_depth = match optional(arguments, "max_depth") {
+                    Some(depth) => depth.parse::<u32>().map_err(|_| {
+                        McpError::invalid_params(
+                            format!("max_depth must be a number: {depth}"),
+                            None,
+                        )
+                    })?,
+                    None => super::default_depth(),
+                };
+                vec![
+                    tool("find_symbol", json!({ "name": symbol })),
+                    tool(
+                        "analyze_impact",
+                        json!({ "symbol_name": symbol, "max_depth": max_depth }),
+                    ),
+                    tool(
+                        "find_callers",
+                        json!({ "function_name": symbol, "depth": 2 }),
+                    ),
+                ]
+            }
+            Self::FindDeadCode => {
+                let path = required(arguments, "path")?;
+                vec![
+                    tool("get_file_outline", json!({ "path": path })),
+                    Step::UncalledSymbols(path),
+                ]
+            }
+        })
+    }
+
+    /// The task put to the model, ahead of the gathered context
+    pub fn task(self, arguments: Option<&JsonObject>) -> String {
+        let argument = |name: &str| optional(arguments, name).unwrap_or_default();
+        match self {
+            Self::ExplainSymbol => format!(
+                "Explain what `{}` does in this codebase: its purpose, its inputs and outputs, \
+                 and how it fits between the code it calls and the code that calls it. \
+                 The codanna results below come from the index; read the source at the listed \
+                 locations before relying on details.",
+                argument("symbol")
+            ),
+            Self::AssessImpact => format!(
+                "I am about to change `{}`. Assess the impact: which callers and dependents \
+                 are affected, which of them are likely to break, and what should be re-tested. \
+                 Call graph results can miss dynamic dispatch and reflection, so point out \
+                 where the list may be incomplete.",
+                argument("symbol")
+            ),
+            Self::FindDeadCode => format!(
+                "Find dead code in `{}`. The functions listed as uncalled have no callers in \
+                 the index. For each, decide whether it is really unused or reached another \
+                 way (public API, entry point, trait implementation, tests, callbacks) and \
+                 recommend what can be removed.",
+                argument("path")
+            ),
+        }
+    }
+}
+
+impl Step {
+    /// Heading of the step's section in the prompt
+    pub fn title(&self) -> &str {
+        match self {
+            Self::Tool(tool, _) => tool,
+            Self::UncalledSymbols(_) => "uncalled symbols",
+        }
+    }
+}
+
+/// Functions and methods in the file at `path` that no indexed code calls
+pub fn uncalled_symbols(indexer: &IndexFacade, path: &str) -> Result<String, String> {
+    let (stored, outline) = indexer.get_file_outline(path).ok_or_else(|| {
+        format!("File not indexed, or more than one indexed file matches: {path}")
+    })?;
+
+    let mut candidates = Vec::new();
+    let mut checked = 0;
+    collect_callable(&outline, &mut |node: &OutlineNode| {
+        checked += 1;
+        if indexer.get_calling_functions(node.symbol.id).is_empty() {
+            candidates.push(node.clone());
+        }
+    });
+    if candidates.is_empty() {
+        return Ok(format!(
+            "All {checked} function(s) in {stored} have callers in the index"
+        ));
+    }
+
+    let omitted = candidates.len().saturating_sub(MAX_DEAD_CODE_CANDIDATES);
+    candidates.truncate(MAX_DEAD_CODE_CANDIDATES);
+    let mut out = format!(
+        "{} of {checked} function(s) in {stored} have no callers:\n",
+        candidates.len() + omitted
+    );
+    for node in &mut candidates {
+        node.children.clear();
+    }
+    format_outline_nodes(&mut out, &candidates, 0);
+    if omitted > 0 {
+        out.push_str(&format!("... and {omitted} more\n"));
+    }
+    Ok(out)
+}
+
+fn collect_callable(nodes: &[OutlineNode], visit: &mut impl FnMut(&OutlineNode)) {
+    for node in nodes {
+        if matches!(node.symbol.kind, SymbolKind::Function | SymbolKind::Method) {
+            visit(node);
+        }
+        collect_callable(&node.children, visit);
+    }
+}
+
+fn optional(arguments: Option<&JsonObject>, name: &str) -> Option<String> {
+    match arguments?.get(name)? {
+        Value::String(value) => Some(value.trim().to_string()).filter(|value| !value.is_empty()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn required(arguments: Option<&JsonObject>, name: &str) -> Result<String, McpError> {
+    optional(arguments, name)
+        .ok_or_else(|| McpError::invalid_params(format!("Missing argument: {name}"), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompts_chain_tools_with_their_arguments() {
+        for prompt in WorkflowPrompt::ALL {
+            assert_eq!(WorkflowPrompt::from_name(prompt.name()), Some(prompt));
+        }
+        assert_eq!(WorkflowPrompt::from_name("refactor"), None);
+
+        let arguments = json!({ "symbol": "parse_config", "max_depth": 2 });
+        let arguments = arguments.as_object();
+        let steps = WorkflowPrompt::AssessImpact.steps(arguments).unwrap();
+        let titles: Vec<_> = steps.iter().map(Step::title).collect();
+        assert_eq!(titles, ["find_symbol", "analyze_impact", "find_callers"]);
+        assert_eq!(
+            steps[1],
+            Step::Tool(
+                "analyze_impact",
+                json!({ "symbol_name": "parse_config", "max_depth": 2 })
+                    .as_object()
+                    .unwrap()
+                    .clone()
+            )
+        );
+        assert!(
+            WorkflowPrompt::AssessImpact
+                .task(arguments)
+                .contains("`parse_config`")
+        );
+
+        let missing = WorkflowPrompt::FindDeadCode.steps(arguments).unwrap_err();
+        assert!(missing.message.contains("path"));
+    }
+}
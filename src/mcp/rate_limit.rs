@@ -0,0 +1,182 @@
+//! Per-client rate limits for MCP tool calls
+//!
+//! Each client gets a token bucket holding up to `mcp.rate_limit_burst`
+//! calls and refilled at `mcp.rate_limit_per_minute`. A call that finds the
+//! bucket empty is refused with the time until the next call is allowed,
+//! rather than queued: an agent looping on semantic searches should back
+//! off, not pile up work behind the slots other clients need.
+//!
+//! Clients are identified by [`client_of`]: the name of a configured bearer
+//! token, else the MCP session (every OAuth client shares one grant), else
+//! the TCP connection, else the single stdio client.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Buckets kept before the least recently used one is dropped
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+/// Token buckets of the clients seen so far
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_second: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Limiter allowing `per_minute` calls per client, or `None` for no limit
+    pub fn new(per_minute: u32, burst: u32) -> Option<Self> {
+        (per_minute > 0).then(|| Self {
+            per_second: f64::from(per_minute) / 60.0,
+            burst: f64::from(burst.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Take one call from `client`'s bucket, or return how long until one is free
+    pub fn check(&self, client: &str) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(client) {
+            let oldest = buckets
+                .iter()
+                .min_by_key(|(_, bucket)| bucket.updated)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                buckets.remove(&oldest);
+            }
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        bucket.tokens = refilled(*bucket, now, self.per_second, self.burst);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+fn refilled(bucket: Bucket, now: Instant, per_second: f64, burst: f64) -> f64 {
+    let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+    (bucket.tokens + elapsed * per_second).min(burst)
+}
+
+/// Client a request counts against
+///
+/// `connection` is the key of the transport connection the request came
+/// in on, for transports like TCP that carry one client each.
+pub fn client_of(extensions: &rmcp::model::Extensions, connection: Option<&str>) -> String {
+    let grant = super::auth::grant_of(extensions).map(|grant| grant.name.as_str());
+    #[cfg(feature = "http-server")]
+    let session = extensions
+        .get::<axum::http::request::Parts>()
+        .and_then(|parts| parts.headers.get("mcp-session-id"))
+        .and_then(|value| value.to_str().ok());
+    #[cfg(not(feature = "http-server"))]
+    let session: Option<&str> = None;
+    client_key(grant, session, connection)
+}
+
+/// Bucket key of a request with token `grant` in MCP `session`
+///
+/// A configured token is one client however many sessions it opens, so
+/// reconnecting does not reset its bucket. Every OAuth client shares the
+/// same grant, so those are told apart by session instead.
+fn client_key(grant: Option<&str>, session: Option<&str>, connection: Option<&str>) -> String {
+    match (grant, session) {
+        (Some(grant), _) if grant != super::auth::OAUTH_GRANT => format!("token:{grant}"),
+        (_, Some(session)) => format!("session:{session}"),
+        (Some(grant), None) => format!("token:{grant}"),
+        (None, None) => connection.unwrap_or("stdio").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_refills_at_the_configured_rate() {
+        assert!(RateLimiter::new(0, 10).is_none());
+
+        let limiter = RateLimiter::new(60, 2).unwrap();
+        let start = Instant::now();
+        assert!(limiter.check_at("agent", start).is_ok());
+        assert!(limiter.check_at("agent", start).is_ok());
+        let wait = limiter.check_at("agent", start).unwrap_err();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+
+        // Other clients have their own bucket
+        assert!(limiter.check_at("other", start).is_ok());
+
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.check_at("agent", later).is_ok());
+        assert!(limiter.check_at("agent", later).is_err());
+    }
+
+    #[test]
+    fn test_tokens_share_a_bucket_across_sessions() {
+        assert_eq!(client_key(Some("ci"), Some("a1"), None), "token:ci");
+        assert_eq!(client_key(Some("ci"), Some("b2"), None), "token:ci");
+        assert_eq!(client_key(Some("ci"), None, None), "token:ci");
+
+        // OAuth clients all hold the same grant, so sessions tell them apart
+        let first = client_key(Some(crate::mcp::auth::OAUTH_GRANT), Some("a1"), None);
+        let second = client_key(Some(crate::mcp::auth::OAUTH_GRANT), Some("b2"), None);
+        assert_ne!(first, second);
+        assert_eq!(client_key(Some("oauth"), None, None), "token:oauth");
+        assert_eq!(client_key(None, Some("a1"), None), "session:a1");
+        assert_eq!(client_key(None, None, None), "stdio");
+
+        // Each TCP connection is a client of its own
+        let tcp = client_key(None, None, Some("tcp:127.0.0.1:50312"));
+        assert_eq!(tcp, "tcp:127.0.0.1:50312");
+        assert_ne!(tcp, client_key(None, None, Some("tcp:127.0.0.1:50313")));
+
+        let limiter = RateLimiter::new(60, 1).unwrap();
+        let now = Instant::now();
+        assert!(limiter.check_at(&first, now).is_ok());
+        assert!(limiter.check_at(&first, now).is_err());
+        assert!(limiter.check_at(&second, now).is_ok());
+    }
+
+    #[test]
+    fn test_least_recently_used_bucket_is_evicted() {
+        let limiter = RateLimiter::new(60, 1).unwrap();
+        let start = Instant::now();
+        for i in 0..MAX_TRACKED_CLIENTS {
+            let at = start + Duration::from_millis(i as u64);
+            assert!(limiter.check_at(&format!("client-{i}"), at).is_ok());
+        }
+
+        // A new client evicts the oldest, not whichever buckets are full
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.check_at("newcomer", later).is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), MAX_TRACKED_CLIENTS);
+        assert!(!buckets.contains_key("client-0"));
+        assert!(buckets.contains_key("client-1"));
+    }
+}
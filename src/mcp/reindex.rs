@@ -0,0 +1,205 @@
+//! Targeted re-indexing requested by a client
+//!
+//! Backs the `reindex_paths` MCP tool. An agent that has just written files
+//! asks for them to be indexed now instead of waiting for the file watcher's
+//! debounce, and gets back how each file's symbols changed, with the new
+//! symbol ids to use in follow-up queries. Files that vanished are removed
+//! from the index. The tool needs the reindex scope on network transports.
+
+use super::notifications::SymbolChanges;
+use crate::indexing::facade::IndexFacade;
+use crate::storage::event_log::SymbolRef;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Most paths one call accepts
+pub const MAX_PATHS: usize = 100;
+
+/// What happened to one requested path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PathOutcome {
+    /// Parsed again; `symbols` are all of the file's symbols now
+    Reindexed {
+        path: String,
+        symbols: Vec<SymbolRef>,
+        changes: SymbolChanges,
+    },
+    /// Content matched the index, nothing was done
+    Unchanged {
+        path: String,
+    },
+    /// The file no longer exists and its symbols were dropped
+    Removed {
+        path: String,
+        removed: Vec<SymbolRef>,
+    },
+    /// A directory, indexed incrementally
+    Directory {
+        path: String,
+        files_indexed: usize,
+    },
+    /// Outside the indexed directories, or missing and not indexed
+    Rejected {
+        path: String,
+        reason: String,
+    },
+    Failed {
+        path: String,
+        error: String,
+    },
+}
+
+/// Re-index `paths`, relative to the workspace root or absolute
+///
+/// With `force`, files are parsed again even when their content hash
+/// matches the index. Stops early when `cancelled` returns true.
+pub fn reindex_paths(
+    indexer: &mut IndexFacade,
+    paths: &[String],
+    force: bool,
+    cancelled: impl Fn() -> bool,
+) -> Vec<PathOutcome> {
+    let settings = indexer.settings().clone();
+    let mut roots = settings.get_indexed_paths();
+    if let Some(root) = &settings.workspace_root {
+        roots.push(root.clone());
+    }
+    let roots: Vec<PathBuf> = roots
+        .iter()
+        .map(|root| root.canonicalize().unwrap_or_else(|_| root.clone()))
+        .collect();
+
+    let mut outcomes = Vec::new();
+    let mut changed = false;
+    for requested in paths.iter().take(MAX_PATHS) {
+        if cancelled() {
+            break;
+        }
+        let absolute = settings.resolve_stored_path(Path::new(requested));
+        let outcome = if absolute.exists() {
+            match absolute.canonicalize() {
+                Ok(path) if within(&roots, &path) => {
+                    reindex_existing(indexer, requested, &path, force)
+                }
+                Ok(_) => PathOutcome::Rejected {
+                    path: requested.clone(),
+                    reason: "outside the indexed directories".to_string(),
+                },
+                Err(e) => PathOutcome::Failed {
+                    path: requested.clone(),
+                    error: e.to_string(),
+                },
+            }
+        } else {
+            remove_missing(indexer, requested)
+        };
+        changed |= matches!(
+            outcome,
+            PathOutcome::Reindexed { .. }
+                | PathOutcome::Removed { .. }
+                | PathOutcome::Directory { .. }
+        );
+        outcomes.push(outcome);
+    }
+
+    if changed && indexer.has_semantic_search() {
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(indexer.index_base());
+        if let Err(e) = indexer.save_semantic_search(&semantic_path) {
+            tracing::warn!("[reindex] failed to save semantic search: {e}");
+        }
+    }
+    outcomes
+}
+
+fn reindex_existing(
+    indexer: &mut IndexFacade,
+    requested: &str,
+    path: &Path,
+    force: bool,
+) -> PathOutcome {
+    let requested = requested.to_string();
+    if path.is_dir() {
+        return match indexer.index_directory(path, force) {
+            Ok(stats) => PathOutcome::Directory {
+                path: requested,
+                files_indexed: stats.files_indexed,
+            },
+            Err(e) => PathOutcome::Failed {
+                path: requested,
+                error: e.to_string(),
+            },
+        };
+    }
+
+    let before = file_symbols(indexer, path);
+    match indexer.index_file_with_force(path, force) {
+        Ok(crate::IndexingResult::Indexed(file_id)) => {
+            let after = indexer.get_symbols_by_file(file_id);
+            PathOutcome::Reindexed {
+                path: requested,
+                changes: SymbolChanges::between(&before, &after),
+                symbols: after.iter().map(SymbolRef::from).collect(),
+            }
+        }
+        Ok(crate::IndexingResult::Cached(_)) => PathOutcome::Unchanged { path: requested },
+        Err(e) => PathOutcome::Failed {
+            path: requested,
+            error: e.to_string(),
+        },
+    }
+}
+
+fn remove_missing(indexer: &mut IndexFacade, requested: &str) -> PathOutcome {
+    let Some((file_id, stored)) = indexer.resolve_indexed_file(requested) else {
+        return PathOutcome::Rejected {
+            path: requested.to_string(),
+            reason: "no such file, and not indexed".to_string(),
+        };
+    };
+    let removed = indexer.get_symbols_by_file(file_id);
+    match indexer.remove_file(&stored) {
+        Ok(()) => PathOutcome::Removed {
+            path: requested.to_string(),
+            removed: removed.iter().map(SymbolRef::from).collect(),
+        },
+        Err(e) => PathOutcome::Failed {
+            path: requested.to_string(),
+            error: e.to_string(),
+        },
+    }
+}
+
+fn file_symbols(indexer: &IndexFacade, path: &Path) -> Vec<crate::Symbol> {
+    indexer
+        .resolve_indexed_file(&path.to_string_lossy())
+        .map(|(file_id, _)| indexer.get_symbols_by_file(file_id))
+        .unwrap_or_default()
+}
+
+/// Whether `path` lies inside one of `roots`
+fn within(roots: &[PathBuf], path: &Path) -> bool {
+    roots.iter().any(|root| path.starts_with(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcomes_serialize_with_status() {
+        let roots = vec![PathBuf::from("/work/app"), PathBuf::from("/work/lib/src")];
+        assert!(within(&roots, Path::new("/work/app/src/main.rs")));
+        assert!(within(&roots, Path::new("/work/lib/src")));
+        assert!(!within(&roots, Path::new("/work/lib/Cargo.toml")));
+        assert!(!within(&roots, Path::new("/work/application/main.rs")));
+
+        let outcome = PathOutcome::Rejected {
+            path: "../secrets.rs".to_string(),
+            reason: "outside the indexed directories".to_string(),
+        };
+        let value = serde_json::to_value(&outcome).unwrap();
+        assert_eq!(value["status"], "rejected");
+        assert_eq!(value["path"], "../secrets.rs");
+    }
+}
@@ -0,0 +1,247 @@
+//! MCP resources over the index
+//!
+//! Besides tools the server exposes read-only resources, which clients can
+//! read and subscribe to without a tool call:
+//!
+//! - `codanna://stats`: symbol, file and relationship counts as JSON
+//! - `codanna://health`: tool call metrics, index freshness and memory use,
+//!   as JSON (see [`super::metrics`])
+//! - `codanna://file/{path}/outline`: a file's symbols nested by scope, as
+//!   returned by the `get_file_outline` tool
+//! - `codanna://symbol/{id}`: one symbol as JSON
+//!
+//! Subscriptions are kept per session. When the watcher re-indexes a file,
+//! the session is told which of its subscribed resources changed.
+
+use super::format_outline_nodes;
+use super::metrics::{HealthReport, tool_metrics};
+use crate::SymbolId;
+use crate::indexing::facade::IndexFacade;
+use rmcp::model::{
+    AnnotateAble, ErrorData as McpError, RawResource, RawResourceTemplate, ReadResourceResult,
+    Resource, ResourceContents, ResourceTemplate,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// URI of the index statistics resource
+pub const STATS_URI: &str = "codanna://stats";
+
+/// URI of the server health resource
+pub const HEALTH_URI: &str = "codanna://health";
+
+const FILE_PREFIX: &str = "codanna://file/";
+const OUTLINE_SUFFIX: &str = "/outline";
+const SYMBOL_PREFIX: &str = "codanna://symbol/";
+
+/// A resource the server can read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexResource {
+    Stats,
+    Health,
+    /// Outline of the file at this path, as indexed or a unique suffix
+    FileOutline(String),
+    Symbol(SymbolId),
+}
+
+impl IndexResource {
+    /// Resource named by `uri`, if it is one of ours
+    pub fn parse(uri: &str) -> Option<Self> {
+        if uri == STATS_URI {
+            return Some(Self::Stats);
+        }
+        if uri == HEALTH_URI {
+            return Some(Self::Health);
+        }
+        if let Some(rest) = uri.strip_prefix(FILE_PREFIX) {
+            let path = percent_decode(rest.strip_suffix(OUTLINE_SUFFIX)?);
+            return (!path.is_empty()).then_some(Self::FileOutline(path));
+        }
+        let id = uri.strip_prefix(SYMBOL_PREFIX)?.parse().ok()?;
+        SymbolId::new(id).map(Self::Symbol)
+    }
+
+    /// Read the resource at `uri` from the index
+    pub fn read(&self, indexer: &IndexFacade, uri: &str) -> Result<ReadResourceResult, McpError> {
+        let (text, mime_type) = match self {
+            Self::Stats => (to_json(&stats(indexer))?, "application/json"),
+            Self::Health => (
+                to_json(&HealthReport::collect(indexer, tool_metrics()))?,
+                "application/json",
+            ),
+            Self::FileOutline(path) => {
+                let (stored, outline) = indexer.get_file_outline(path).ok_or_else(|| {
+                    McpError::resource_not_found(
+                        format!("File not indexed, or more than one indexed file matches: {path}"),
+                        None,
+                    )
+                })?;
+                let count: usize = outline.iter().map(|node| node.count()).sum();
+                let mut text = format!("Outline of {stored} ({count} symbol(s)):\n");
+                format_outline_nodes(&mut text, &outline, 0);
+                (text, "text/plain")
+            }
+            Self::Symbol(id) => {
+                let symbol = indexer.get_symbol(*id).ok_or_else(|| {
+                    McpError::resource_not_found(format!("No symbol with id {}", id.value()), None)
+                })?;
+                (to_json(&symbol)?, "application/json")
+            }
+        };
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                text,
+                meta: None,
+            }],
+        })
+    }
+
+    /// Whether re-indexing the file at `path` changes this resource
+    fn affected_by(&self, indexer: &IndexFacade, path: &Path) -> bool {
+        match self {
+            Self::Stats | Self::Health => true,
+            Self::FileOutline(subscribed) => path.ends_with(subscribed),
+            Self::Symbol(id) => indexer
+                .get_symbol(*id)
+                .is_none_or(|symbol| path.ends_with(&*symbol.file_path)),
+        }
+    }
+}
+
+/// Fixed resources, listed by `resources/list`
+pub fn list() -> Vec<Resource> {
+    let mut stats = RawResource::new(STATS_URI, "index-stats");
+    stats.description = Some("Symbol, file and relationship counts of the index".to_string());
+    stats.mime_type = Some("application/json".to_string());
+    let mut health = RawResource::new(HEALTH_URI, "server-health");
+    health.description = Some(
+        "Tool call counts and latencies, index freshness and memory use of the server".to_string(),
+    );
+    health.mime_type = Some("application/json".to_string());
+    vec![stats.no_annotation(), health.no_annotation()]
+}
+
+/// Parameterized resources, listed by `resources/templates/list`
+pub fn templates() -> Vec<ResourceTemplate> {
+    let template = |uri_template: &str, name: &str, description: &str, mime_type: &str| {
+        RawResourceTemplate {
+            uri_template: uri_template.to_string(),
+            name: name.to_string(),
+            title: None,
+            description: Some(description.to_string()),
+            mime_type: Some(mime_type.to_string()),
+        }
+        .no_annotation()
+    };
+    vec![
+        template(
+            "codanna://file/{path}/outline",
+            "file-outline",
+            "Symbols of an indexed file nested by scope, with line ranges and symbol IDs",
+            "text/plain",
+        ),
+        template(
+            "codanna://symbol/{id}",
+            "symbol",
+            "A symbol by ID: kind, location, signature, documentation",
+            "application/json",
+        ),
+    ]
+}
+
+/// Subscribed URIs whose content changes when the file at `path` is re-indexed
+pub fn updated_by(
+    indexer: &IndexFacade,
+    subscriptions: &HashSet<String>,
+    path: &Path,
+) -> Vec<String> {
+    subscriptions
+        .iter()
+        .filter(|uri| {
+            IndexResource::parse(uri).is_some_and(|resource| resource.affected_by(indexer, path))
+        })
+        .cloned()
+        .collect()
+}
+
+fn stats(indexer: &IndexFacade) -> serde_json::Value {
+    let mut kinds = BTreeMap::new();
+    for symbol in indexer.get_all_symbols() {
+        *kinds.entry(format!("{:?}", symbol.kind)).or_insert(0usize) += 1;
+    }
+    let semantic = indexer.get_semantic_metadata().map(|metadata| {
+        serde_json::json!({
+            "model": metadata.model_name,
+            "dimension": metadata.dimension,
+            "embeddings": metadata.embedding_count,
+            "updated_at": metadata.updated_at,
+        })
+    });
+    serde_json::json!({
+        "symbols": indexer.symbol_count(),
+        "files": indexer.file_count(),
+        "relationships": indexer.relationship_count(),
+        "kinds": kinds,
+        "semantic": semantic,
+    })
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, McpError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| McpError::internal_error(format!("Failed to serialize resource: {e}"), None))
+}
+
+/// Decode `%XX` escapes, as clients may encode the `/` of a path
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resource_uris() {
+        assert_eq!(IndexResource::parse(STATS_URI), Some(IndexResource::Stats));
+        assert_eq!(
+            IndexResource::parse(HEALTH_URI),
+            Some(IndexResource::Health)
+        );
+        assert_eq!(
+            IndexResource::parse("codanna://file/src/lib.rs/outline"),
+            Some(IndexResource::FileOutline("src/lib.rs".to_string()))
+        );
+        assert_eq!(
+            IndexResource::parse("codanna://file/src%2Fmcp%2Fmod.rs/outline"),
+            Some(IndexResource::FileOutline("src/mcp/mod.rs".to_string()))
+        );
+        assert_eq!(
+            IndexResource::parse("codanna://symbol/42"),
+            Some(IndexResource::Symbol(SymbolId(42)))
+        );
+        assert_eq!(IndexResource::parse("codanna://symbol/0"), None);
+        assert_eq!(IndexResource::parse("codanna://file/src/lib.rs"), None);
+        assert_eq!(IndexResource::parse("file:///src/lib.rs"), None);
+    }
+}
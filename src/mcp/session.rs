@@ -0,0 +1,215 @@
+//! What a client looked at recently, per MCP session
+//!
+//! Each session remembers the symbols and files its last tool calls looked
+//! up, newest first. Searches rank results in or near them a little higher,
+//! since an agent usually keeps working where it just was, and the
+//! `recent_context` tool lists them so that a follow-up such as "the function
+//! I just looked at" resolves to a symbol id. Unlike the warm set, nothing is
+//! persisted: the context ends with the session.
+//!
+//! Results in files of the git [`WorkingSet`] are boosted along with them;
+//! `[ranking]` sets the size of each boost.
+
+use crate::config::RankingConfig;
+use crate::indexing::WorkingSet;
+use crate::storage::SearchResult;
+use crate::{Symbol, SymbolId, SymbolKind};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Symbols remembered per session
+const MAX_SYMBOLS: usize = 20;
+
+/// Files remembered per session
+const MAX_FILES: usize = 10;
+
+/// A symbol a tool call of this session looked up
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecentSymbol {
+    pub symbol_id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    pub line: u32,
+}
+
+/// Recently looked up symbols and files, newest first
+#[derive(Debug, Default)]
+pub struct SessionContext {
+    symbols: VecDeque<RecentSymbol>,
+    files: VecDeque<String>,
+}
+
+impl SessionContext {
+    /// Remember `symbol`, and the file it is defined in
+    pub fn record_symbol(&mut self, symbol: &Symbol) {
+        self.symbols.retain(|recent| recent.symbol_id != symbol.id);
+        self.symbols.push_front(RecentSymbol {
+            symbol_id: symbol.id,
+            name: symbol.name.to_string(),
+            kind: symbol.kind,
+            file_path: symbol.file_path.to_string(),
+            line: symbol.range.start_line + 1,
+        });
+        self.symbols.truncate(MAX_SYMBOLS);
+        self.record_file(&symbol.file_path);
+    }
+
+    pub fn record_file(&mut self, path: &str) {
+        self.files.retain(|recent| recent != path);
+        self.files.push_front(path.to_string());
+        self.files.truncate(MAX_FILES);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty() && self.files.is_empty()
+    }
+
+    /// Recent symbols, newest first
+    pub fn symbols(&self) -> impl Iterator<Item = &RecentSymbol> {
+        self.symbols.iter()
+    }
+
+    /// Recent files, newest first
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(String::as_str)
+    }
+
+    /// Factor for the score of a result, 1.0 unless it or its file is recent
+    ///
+    /// The most recent symbol and file get the full boost of `config`,
+    /// fading to nothing with age.
+    pub fn boost(&self, symbol_id: SymbolId, file_path: &str, config: &RankingConfig) -> f32 {
+        let fade = |position: usize, len: usize| 1.0 - position as f32 / len as f32;
+        let mut boost = 1.0;
+        if let Some(position) = self.symbols.iter().position(|s| s.symbol_id == symbol_id) {
+            boost += config.session_symbol_boost * fade(position, MAX_SYMBOLS);
+        }
+        if let Some(position) = self.files.iter().position(|f| f == file_path) {
+            boost += config.session_file_boost * fade(position, MAX_FILES);
+        }
+        boost
+    }
+
+    /// Boost the scores of `results` and sort them again, best first
+    ///
+    /// Results neither recent nor in a file of the session or the working
+    /// set keep their order.
+    pub fn rerank<T: Ranked>(
+        &self,
+        results: &mut [T],
+        config: &RankingConfig,
+        working_set: &WorkingSet,
+    ) {
+        if self.is_empty() && working_set.is_empty() {
+            return;
+        }
+        for result in results.iter_mut() {
+            let boost = self.boost(result.symbol_id(), result.file_path(), config)
+                + working_set.boost(result.file_path(), config);
+            *result.score_mut() *= boost;
+        }
+        results.sort_by(|a, b| b.score().total_cmp(&a.score()));
+    }
+}
+
+/// A scored search result that [`SessionContext::rerank`] can boost
+pub trait Ranked {
+    fn symbol_id(&self) -> SymbolId;
+    fn file_path(&self) -> &str;
+    fn score(&self) -> f32;
+    fn score_mut(&mut self) -> &mut f32;
+}
+
+impl Ranked for SearchResult {
+    fn symbol_id(&self) -> SymbolId {
+        self.symbol_id
+    }
+
+    fn file_path(&self) -> &str {
+        &self.file_path
+    }
+
+    fn score(&self) -> f32 {
+        self.score
+    }
+
+    fn score_mut(&mut self) -> &mut f32 {
+        &mut self.score
+    }
+}
+
+impl Ranked for (Symbol, f32) {
+    fn symbol_id(&self) -> SymbolId {
+        self.0.id
+    }
+
+    fn file_path(&self) -> &str {
+        &self.0.file_path
+    }
+
+    fn score(&self) -> f32 {
+        self.1
+    }
+
+    fn score_mut(&mut self) -> &mut f32 {
+        &mut self.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range};
+
+    fn symbol(id: u32, path: &str) -> Symbol {
+        let mut symbol = Symbol::new(
+            SymbolId::new(id).unwrap(),
+            format!("item{id}"),
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(id, 0, id + 1, 0),
+        );
+        symbol.file_path = path.into();
+        symbol
+    }
+
+    #[test]
+    fn test_recent_symbols_and_files_boost_results() {
+        let mut context = SessionContext::default();
+        let mut results: Vec<(Symbol, f32)> = vec![
+            (symbol(1, "src/a.rs"), 0.80),
+            (symbol(2, "src/b.rs"), 0.75),
+            (symbol(3, "src/c.rs"), 0.74),
+            (symbol(4, "src/d.rs"), 0.70),
+        ];
+        let config = RankingConfig::default();
+        let no_git = WorkingSet::default();
+        context.rerank(&mut results, &config, &no_git);
+        assert_eq!(results[0].1, 0.80);
+
+        context.record_file("src/c.rs");
+        context.record_symbol(&symbol(4, "src/d.rs"));
+        context.record_symbol(&symbol(4, "src/d.rs"));
+        assert_eq!(context.symbols().count(), 1);
+        assert_eq!(
+            context.files().collect::<Vec<_>>(),
+            ["src/d.rs", "src/c.rs"]
+        );
+
+        context.rerank(&mut results, &config, &no_git);
+        let order: Vec<u32> = results.iter().map(|(s, _)| s.id.value()).collect();
+        assert_eq!(order, [4, 3, 1, 2]);
+        assert_eq!(
+            context.boost(SymbolId::new(9).unwrap(), "src/z.rs", &config),
+            1.0
+        );
+
+        for id in 10..40 {
+            context.record_symbol(&symbol(id, &format!("src/m{id}.rs")));
+        }
+        assert_eq!(context.symbols().count(), MAX_SYMBOLS);
+        assert_eq!(context.files().count(), MAX_FILES);
+        assert_eq!(context.symbols().next().unwrap().name, "item39");
+    }
+}
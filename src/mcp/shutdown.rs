@@ -0,0 +1,150 @@
+//! Coordinated shutdown for the MCP server modes
+//!
+//! Ctrl+C and SIGTERM start a staged shutdown instead of dropping every task
+//! where it stands:
+//!
+//! 1. The tool executor stops accepting calls and lets running ones answer.
+//! 2. The shared token is cancelled, which closes transports and stops watchers.
+//!    The file watcher applies its pending changes and saves index metadata.
+//! 3. Tracked tasks are awaited until the deadline, then abandoned.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::task::{JoinError, JoinHandle};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use super::executor::ToolExecutor;
+
+type TrackedTask = Pin<Box<dyn Future<Output = Result<(), JoinError>> + Send>>;
+
+/// Sequences shutdown of the MCP server and its background tasks
+pub struct Shutdown {
+    token: CancellationToken,
+    grace: Duration,
+    tasks: Vec<(&'static str, TrackedTask)>,
+}
+
+impl Shutdown {
+    /// Create a coordinator that waits at most `grace` for in-flight work
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            grace,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Token cancelled once in-flight tool calls have drained
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Wait for `handle` to finish during shutdown
+    pub fn track<T: Send + 'static>(&mut self, name: &'static str, handle: JoinHandle<T>) {
+        let task: TrackedTask = Box::pin(async move { handle.await.map(|_| ()) });
+        self.tasks.push((name, task));
+    }
+
+    /// Drain tool calls, cancel the token, and wait for tracked tasks
+    pub async fn run(self, executor: &ToolExecutor) {
+        let deadline = Instant::now() + self.grace;
+
+        if !executor.shutdown(self.grace).await {
+            tracing::warn!(
+                "[shutdown] tool calls still running after {}s, abandoning them",
+                self.grace.as_secs()
+            );
+        }
+
+        self.token.cancel();
+
+        for (name, task) in self.tasks {
+            match tokio::time::timeout_at(deadline, task).await {
+                Ok(Ok(())) => crate::debug_event!("shutdown", "stopped", "{name}"),
+                Ok(Err(e)) => tracing::error!("[shutdown] {name} task failed: {e}"),
+                Err(_) => tracing::warn!("[shutdown] {name} did not stop before the deadline"),
+            }
+        }
+
+        crate::log_event!("shutdown", "complete");
+    }
+}
+
+/// Wait for Ctrl+C or SIGTERM and return the signal's name
+pub async fn signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = ctrl_c() => "SIGINT",
+                    _ = terminate.recv() => "SIGTERM",
+                }
+            }
+            Err(e) => {
+                tracing::warn!("[shutdown] cannot listen for SIGTERM: {e}");
+                ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        ctrl_c().await;
+        "Ctrl+C"
+    }
+}
+
+async fn ctrl_c() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        // Without a handler the only way out is a kill; never fire spuriously
+        tracing::warn!("[shutdown] cannot listen for Ctrl+C: {e}");
+        std::future::pending::<()>().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::McpConfig;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_run_cancels_token_and_waits_for_tasks() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let executor = ToolExecutor::new(&McpConfig {
+            tool_threads: 0,
+            ..McpConfig::default()
+        })
+        .unwrap();
+        let flushed = Arc::new(AtomicBool::new(false));
+
+        runtime.block_on(async {
+            let mut shutdown = Shutdown::new(Duration::from_secs(5));
+            let token = shutdown.token();
+            let done = flushed.clone();
+            shutdown.track(
+                "watcher",
+                tokio::spawn(async move {
+                    token.cancelled().await;
+                    // Stand-in for the watcher flushing pending changes
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    done.store(true, Ordering::SeqCst);
+                }),
+            );
+
+            shutdown.run(&executor).await;
+        });
+
+        assert!(flushed.load(Ordering::SeqCst));
+    }
+}
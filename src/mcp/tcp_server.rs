@@ -0,0 +1,112 @@
+//! MCP over a local TCP port, next to stdio
+//!
+//! `codanna serve --tcp 127.0.0.1:7777` keeps serving the client that
+//! started it on stdio, usually an editor, while accepting more clients such
+//! as a terminal agent on a TCP port. Each connection is its own MCP session
+//! speaking newline-delimited JSON-RPC as on stdio, over the index already
+//! loaded in memory, so a second client costs neither a second copy of the
+//! index nor its load time.
+//!
+//! Connections are not authenticated, so only loopback addresses are
+//! accepted; use `--http` with bearer tokens for other machines. Each
+//! connection has its own rate limit bucket, keyed by the peer address.
+
+use super::CodeIntelligenceServer;
+use super::notifications::NotificationBroadcaster;
+use crate::Settings;
+use crate::indexing::facade::IndexFacade;
+use rmcp::ServiceExt;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// Pause after a failed accept, such as when out of file descriptors
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Listen on `address`, which must be a loopback address
+pub async fn bind(address: &str) -> io::Result<TcpListener> {
+    let listener = TcpListener::bind(address).await?;
+    let local = listener.local_addr()?;
+    if !is_local(&local) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{local} is not a loopback address: TCP sessions are not authenticated, \
+                 use --http for remote clients"
+            ),
+        ));
+    }
+    Ok(listener)
+}
+
+/// Accept connections until `ct` is cancelled, one MCP session each
+///
+/// Sessions share `facade`, and get file change notifications from
+/// `broadcaster` like the stdio session.
+pub async fn serve(
+    listener: TcpListener,
+    facade: Arc<RwLock<IndexFacade>>,
+    settings: Arc<Settings>,
+    broadcaster: Arc<NotificationBroadcaster>,
+    ct: CancellationToken,
+) {
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!(target: "mcp", "tcp accept failed: {e}");
+                    tokio::select! {
+                        _ = tokio::time::sleep(ACCEPT_BACKOFF) => continue,
+                        _ = ct.cancelled() => break,
+                    }
+                }
+            },
+            _ = ct.cancelled() => break,
+        };
+        crate::debug_event!("tcp", "connected", "{peer}");
+
+        let server = CodeIntelligenceServer::new_with_facade(facade.clone(), settings.clone())
+            .with_connection(format!("tcp:{peer}"));
+        let notifier = server.clone();
+        let receiver = broadcaster.subscribe();
+        let session_ct = ct.child_token();
+        tokio::spawn(async move {
+            let service = match server.serve_with_ct(stream, session_ct).await {
+                Ok(service) => service,
+                Err(e) => {
+                    tracing::warn!(target: "mcp", "tcp session from {peer} failed: {e}");
+                    return;
+                }
+            };
+            tokio::select! {
+                _ = service.waiting() => {}
+                _ = notifier.start_notification_listener(receiver) => {}
+            }
+            crate::debug_event!("tcp", "disconnected", "{peer}");
+        });
+    }
+}
+
+fn is_local(address: &SocketAddr) -> bool {
+    address.ip().is_loopback()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_accepts_only_loopback() {
+        let listener = bind("127.0.0.1:0").await.unwrap();
+        assert!(listener.local_addr().unwrap().ip().is_loopback());
+
+        assert!(is_local(&"[::1]:7777".parse().unwrap()));
+        assert!(!is_local(&"0.0.0.0:7777".parse().unwrap()));
+        assert!(!is_local(&"192.168.1.20:7777".parse().unwrap()));
+    }
+}
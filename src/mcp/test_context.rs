@@ -0,0 +1,804 @@
+//! Context for writing a test against a symbol
+//!
+//! Backs the `test_context_for` MCP tool. Collects in one response what an
+//! agent otherwise gathers over several round-trips before it can write a
+//! test that compiles:
+//!
+//! - The symbol's signature, module, and docs
+//! - The types it depends on, with their constructors and existing call sites
+//!   (test call sites first, since those are the easiest to copy)
+//! - Existing tests that exercise the symbol or share its name
+//! - The test framework used for the symbol's language, detected from the
+//!   project manifests at the workspace root
+//!
+//! Everything here is heuristic: names and paths decide what counts as a test
+//! or a constructor.
+
+use crate::indexing::facade::IndexFacade;
+use crate::symbol::ScopeContext;
+use crate::{Symbol, SymbolId, SymbolKind};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Construction sites listed per dependency
+const MAX_SITES_PER_DEPENDENCY: usize = 3;
+/// Dependencies listed per symbol
+const MAX_DEPENDENCIES: usize = 8;
+
+/// Method names that create an instance of their type
+const CONSTRUCTOR_NAMES: &[&str] = &[
+    "new",
+    "default",
+    "builder",
+    "create",
+    "build",
+    "__init__",
+    "constructor",
+    "init",
+    "of",
+];
+
+/// Test framework detected for a language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFramework {
+    pub name: &'static str,
+    /// How a test is declared and run
+    pub usage: &'static str,
+    /// Manifest the framework was detected from, if any
+    pub source: Option<String>,
+}
+
+impl TestFramework {
+    fn new(name: &'static str, usage: &'static str, source: Option<&str>) -> Self {
+        Self {
+            name,
+            usage,
+            source: source.map(str::to_string),
+        }
+    }
+}
+
+/// Read a file at the workspace root, if present
+fn read_manifest(root: &Path, name: &str) -> Option<String> {
+    std::fs::read_to_string(root.join(name)).ok()
+}
+
+/// First manifest among `names` that mentions any of `needles`
+fn manifest_mentioning(root: &Path, names: &[&str], needles: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        let content = read_manifest(root, name)?;
+        needles
+            .iter()
+            .any(|needle| content.contains(needle))
+            .then(|| name.to_string())
+    })
+}
+
+/// Detect the test framework for `language` from manifests under `root`
+///
+/// Falls back to the language's built-in test support where one exists.
+pub fn detect_test_framework(root: &Path, language: &str) -> Option<TestFramework> {
+    let found = |name, usage, source: Option<String>| {
+        Some(TestFramework::new(name, usage, source.as_deref()))
+    };
+
+    match language {
+        "rust" => {
+            let source = root.join("Cargo.toml").exists().then_some("Cargo.toml");
+            if let Some(manifest) = manifest_mentioning(root, &["Cargo.toml"], &["rstest"]) {
+                return found(
+                    "rstest",
+                    "#[rstest] fn name(#[case] input: T) in #[cfg(test)] mod tests; run: cargo test",
+                    Some(manifest),
+                );
+            }
+            if let Some(manifest) = manifest_mentioning(root, &["Cargo.toml"], &["tokio"]) {
+                return found(
+                    "cargo test (tokio)",
+                    "#[test] fn name() or #[tokio::test] async fn name() in #[cfg(test)] mod tests or tests/*.rs; run: cargo test",
+                    Some(manifest),
+                );
+            }
+            Some(TestFramework::new(
+                "cargo test",
+                "#[test] fn name() in #[cfg(test)] mod tests or tests/*.rs; run: cargo test",
+                source,
+            ))
+        }
+        "python" => {
+            let manifests = [
+                "pyproject.toml",
+                "setup.cfg",
+                "tox.ini",
+                "requirements-dev.txt",
+                "requirements.txt",
+            ];
+            if let Some(manifest) = manifest_mentioning(root, &manifests, &["pytest"]) {
+                return found(
+                    "pytest",
+                    "def test_name(): with plain assert in test_*.py; run: pytest",
+                    Some(manifest),
+                );
+            }
+            for marker in ["pytest.ini", "conftest.py"] {
+                if root.join(marker).exists() {
+                    return found(
+                        "pytest",
+                        "def test_name(): with plain assert in test_*.py; run: pytest",
+                        Some(marker.to_string()),
+                    );
+                }
+            }
+            found(
+                "unittest",
+                "class NameTest(unittest.TestCase) with def test_name(self); run: python -m unittest",
+                None,
+            )
+        }
+        "javascript" | "typescript" | "vue" => {
+            let package = ["package.json"];
+            if let Some(manifest) = manifest_mentioning(root, &package, &["\"vitest\""]) {
+                return found(
+                    "Vitest",
+                    "import { describe, it, expect } from 'vitest'; it('...', () => {}) in *.test.ts; run: npx vitest",
+                    Some(manifest),
+                );
+            }
+            if let Some(manifest) = manifest_mentioning(root, &package, &["\"jest\"", "ts-jest"])
+            {
+                return found(
+                    "Jest",
+                    "describe('...', () => { it('...', () => { expect(x).toBe(y) }) }) in *.test.ts; run: npx jest",
+                    Some(manifest),
+                );
+            }
+            if let Some(manifest) = manifest_mentioning(root, &package, &["\"mocha\""]) {
+                return found(
+                    "Mocha",
+                    "describe('...', () => { it('...', () => {}) }) with an assertion library; run: npx mocha",
+                    Some(manifest),
+                );
+            }
+            found(
+                "node:test",
+                "import test from 'node:test'; test('...', () => {}); run: node --test",
+                None,
+            )
+        }
+        "go" => {
+            if let Some(manifest) = manifest_mentioning(root, &["go.mod"], &["stretchr/testify"]) {
+                return found(
+                    "testing + testify",
+                    "func TestName(t *testing.T) with assert/require in *_test.go; run: go test ./...",
+                    Some(manifest),
+                );
+            }
+            found(
+                "testing",
+                "func TestName(t *testing.T) in *_test.go of the same package; run: go test ./...",
+                None,
+            )
+        }
+        "java" | "kotlin" => {
+            let manifests = ["pom.xml", "build.gradle", "build.gradle.kts"];
+            if language == "kotlin" {
+                if let Some(manifest) = manifest_mentioning(root, &manifests, &["kotest"]) {
+                    return found(
+                        "Kotest",
+                        "class NameTest : StringSpec({ \"...\" { x shouldBe y } }) under src/test; run: gradle test",
+                        Some(manifest),
+                    );
+                }
+            }
+            if let Some(manifest) =
+                manifest_mentioning(root, &manifests, &["junit-jupiter", "org.junit.jupiter"])
+            {
+                return found(
+                    "JUnit 5",
+                    "@Test void name() in a *Test class under src/test; run: mvn test or gradle test",
+                    Some(manifest),
+                );
+            }
+            if let Some(manifest) = manifest_mentioning(root, &manifests, &["testng"]) {
+                return found(
+                    "TestNG",
+                    "@Test public void name() in a *Test class under src/test; run: mvn test or gradle test",
+                    Some(manifest),
+                );
+            }
+            if let Some(manifest) = manifest_mentioning(root, &manifests, &["junit"]) {
+                return found(
+                    "JUnit 4",
+                    "@Test public void name() in a *Test class under src/test; run: mvn test or gradle test",
+                    Some(manifest),
+                );
+            }
+            None
+        }
+        "php" => {
+            let composer = ["composer.json"];
+            if let Some(manifest) = manifest_mentioning(root, &composer, &["pestphp/pest"]) {
+                return found(
+                    "Pest",
+                    "it('...', function () { expect($x)->toBe($y); }) under tests/; run: vendor/bin/pest",
+                    Some(manifest),
+                );
+            }
+            if let Some(manifest) = manifest_mentioning(root, &composer, &["phpunit"]) {
+                return found(
+                    "PHPUnit",
+                    "class NameTest extends TestCase with public function testName(): void under tests/; run: vendor/bin/phpunit",
+                    Some(manifest),
+                );
+            }
+            None
+        }
+        "csharp" => {
+            let projects = csproj_files(root);
+            let projects: Vec<&str> = projects.iter().map(String::as_str).collect();
+            for (needle, name, usage) in [
+                (
+                    "xunit",
+                    "xUnit",
+                    "[Fact] public void Name() in a *Tests project; run: dotnet test",
+                ),
+                (
+                    "NUnit",
+                    "NUnit",
+                    "[Test] public void Name() in a [TestFixture] class; run: dotnet test",
+                ),
+                (
+                    "MSTest",
+                    "MSTest",
+                    "[TestMethod] public void Name() in a [TestClass] class; run: dotnet test",
+                ),
+            ] {
+                if let Some(manifest) = manifest_mentioning(root, &projects, &[needle]) {
+                    return found(name, usage, Some(manifest));
+                }
+            }
+            None
+        }
+        "swift" => found(
+            "XCTest",
+            "final class NameTests: XCTestCase with func testName() under Tests/; run: swift test",
+            root.join("Package.swift")
+                .exists()
+                .then(|| "Package.swift".to_string()),
+        ),
+        "c" | "cpp" => {
+            let cmake = ["CMakeLists.txt", "conanfile.txt", "vcpkg.json"];
+            for (needles, name, usage) in [
+                (
+                    &["GTest", "gtest", "googletest"][..],
+                    "GoogleTest",
+                    "TEST(Suite, Name) { EXPECT_EQ(a, b); }; run: ctest",
+                ),
+                (
+                    &["Catch2"][..],
+                    "Catch2",
+                    "TEST_CASE(\"...\") { REQUIRE(a == b); }; run: ctest",
+                ),
+                (
+                    &["doctest"][..],
+                    "doctest",
+                    "TEST_CASE(\"...\") { CHECK(a == b); }; run: ctest",
+                ),
+            ] {
+                if let Some(manifest) = manifest_mentioning(root, &cmake, needles) {
+                    return found(name, usage, Some(manifest));
+                }
+            }
+            None
+        }
+        "lua" => root.join(".busted").exists().then(|| {
+            TestFramework::new(
+                "busted",
+                "describe('...', function() it('...', function() assert.are.same(a, b) end) end) in *_spec.lua; run: busted",
+                Some(".busted"),
+            )
+        }),
+        "gdscript" => {
+            if root.join("addons/gut").exists() {
+                return found(
+                    "GUT",
+                    "extends GutTest with func test_name(): assert_eq(a, b) in test_*.gd",
+                    Some("addons/gut".to_string()),
+                );
+            }
+            if root.join("addons/gdUnit4").exists() {
+                return found(
+                    "gdUnit4",
+                    "extends GdUnitTestSuite with func test_name(): assert_that(a).is_equal(b)",
+                    Some("addons/gdUnit4".to_string()),
+                );
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// `.csproj` files at the root and one directory below
+fn csproj_files(root: &Path) -> Vec<String> {
+    let mut projects = Vec::new();
+    for entry in walkdir::WalkDir::new(root)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        if entry.path().extension().is_some_and(|ext| ext == "csproj") {
+            if let Ok(relative) = entry.path().strip_prefix(root) {
+                projects.push(relative.to_string_lossy().into_owned());
+            }
+        }
+    }
+    projects.sort();
+    projects
+}
+
+/// Whether a file path looks like a test file
+pub fn is_test_path(path: &str) -> bool {
+    let normalized = path.replace('\\', "/");
+    if ["tests/", "test/", "__tests__/", "spec/", "Tests/"]
+        .iter()
+        .any(|dir| normalized.starts_with(dir) || normalized.contains(&format!("/{dir}")))
+    {
+        return true;
+    }
+
+    let file = normalized.rsplit('/').next().unwrap_or(&normalized);
+    let stem = file.split('.').next().unwrap_or(file);
+    stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
+        || file.contains(".test.")
+        || file.contains(".spec.")
+}
+
+/// Whether a symbol looks like a test case
+pub fn is_test_symbol(symbol: &Symbol) -> bool {
+    if !matches!(
+        symbol.kind,
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Class
+    ) {
+        return false;
+    }
+    let name = &*symbol.name;
+    name.starts_with("test")
+        || name.starts_with("Test")
+        || name.ends_with("_test")
+        || name.ends_with("Test")
+        || name.ends_with("Tests")
+        || (is_test_path(&symbol.file_path) && symbol.kind != SymbolKind::Class)
+}
+
+/// Whether `method` creates an instance of a type named `type_name`
+pub fn is_constructor(method: &Symbol, type_name: &str) -> bool {
+    if !matches!(method.kind, SymbolKind::Method | SymbolKind::Function) {
+        return false;
+    }
+    let name = &*method.name;
+    // Java, C#, C++, and Kotlin secondary constructors share the type's name
+    name == type_name
+        || CONSTRUCTOR_NAMES.contains(&name)
+        || name.starts_with("new_")
+        || name.starts_with("with_")
+        || name.starts_with("from_")
+        || (name.starts_with("from") && name[4..].starts_with(char::is_uppercase))
+}
+
+/// Containing type named by a member's scope, without its qualifier
+fn owner_name(symbol: &Symbol) -> Option<&str> {
+    match &symbol.scope_context {
+        Some(ScopeContext::ClassMember {
+            class_name: Some(class_name),
+        }) => class_name.rsplit(['.', ':']).next(),
+        _ => None,
+    }
+}
+
+fn is_type(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Struct
+            | SymbolKind::Class
+            | SymbolKind::Enum
+            | SymbolKind::Interface
+            | SymbolKind::Trait
+            | SymbolKind::TypeAlias
+    )
+}
+
+/// Where a dependency is created in existing code
+#[derive(Debug, Clone)]
+pub struct ConstructionSite {
+    /// Function containing the construction
+    pub caller: Symbol,
+    pub line: u32,
+    /// Constructor that was called, or `None` for the type itself (`new Foo()`)
+    pub via: Option<String>,
+}
+
+/// A type the symbol needs, and how to build one
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    pub symbol: Symbol,
+    /// Owner of the method under test rather than a parameter/used type
+    pub is_receiver: bool,
+    pub constructors: Vec<Symbol>,
+    pub sites: Vec<ConstructionSite>,
+}
+
+/// An existing test related to the symbol
+#[derive(Debug, Clone)]
+pub struct RelatedTest {
+    pub symbol: Symbol,
+    /// Why it was picked
+    pub reason: &'static str,
+}
+
+/// Everything `test_context_for` reports about a symbol
+#[derive(Debug, Clone)]
+pub struct TestContext {
+    pub symbol: Symbol,
+    pub framework: Option<TestFramework>,
+    pub dependencies: Vec<Dependency>,
+    pub tests: Vec<RelatedTest>,
+}
+
+impl TestContext {
+    /// Collect test context for `symbol`, listing at most `limit` related tests
+    pub fn collect(indexer: &IndexFacade, symbol: Symbol, limit: usize) -> Self {
+        let language = symbol
+            .language_id
+            .as_ref()
+            .map(|id| id.as_str().to_string())
+            .unwrap_or_default();
+        let root = indexer
+            .settings()
+            .workspace_root
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+
+        Self {
+            framework: detect_test_framework(&root, &language),
+            dependencies: collect_dependencies(indexer, &symbol),
+            tests: collect_related_tests(indexer, &symbol, limit),
+            symbol,
+        }
+    }
+
+    /// Render as text for the MCP response
+    pub fn format(&self) -> String {
+        let symbol = &self.symbol;
+        let mut out = format!(
+            "Test context for: {} ({:?}) at {}:{}\n",
+            symbol.name,
+            symbol.kind,
+            symbol.file_path,
+            symbol.range.start_line + 1
+        );
+        if let Some(sig) = symbol.as_signature() {
+            out.push_str(&format!("Signature: {sig}\n"));
+        }
+        if let Some(module) = symbol.as_module_path() {
+            out.push_str(&format!("Module: {module}\n"));
+        }
+        if let Some(doc) = symbol.as_doc_comment() {
+            let preview: Vec<&str> = doc.lines().take(3).collect();
+            out.push_str(&format!("Documentation: {}\n", preview.join(" ")));
+        }
+
+        out.push_str("\nTest framework: ");
+        match &self.framework {
+            Some(framework) => {
+                out.push_str(framework.name);
+                if let Some(source) = &framework.source {
+                    out.push_str(&format!(" (from {source})"));
+                }
+                out.push_str(&format!("\n  {}\n", framework.usage));
+            }
+            None => out.push_str("not detected\n"),
+        }
+
+        out.push_str("\nDependencies:\n");
+        if self.dependencies.is_empty() {
+            out.push_str("  (none found)\n");
+        }
+        for dep in &self.dependencies {
+            let role = if dep.is_receiver { ", receiver" } else { "" };
+            out.push_str(&format!(
+                "  {} ({:?}{role}) at {}:{}\n",
+                dep.symbol.name,
+                dep.symbol.kind,
+                dep.symbol.file_path,
+                dep.symbol.range.start_line + 1
+            ));
+            for ctor in &dep.constructors {
+                match ctor.as_signature() {
+                    Some(sig) => out.push_str(&format!("    Constructor: {sig}\n")),
+                    None => out.push_str(&format!("    Constructor: {}\n", ctor.name)),
+                }
+            }
+            if dep.constructors.is_empty() {
+                if let Some(sig) = dep.symbol.as_signature() {
+                    out.push_str(&format!("    Definition: {sig}\n"));
+                }
+            }
+            for site in &dep.sites {
+                let via = site
+                    .via
+                    .as_deref()
+                    .map(|name| format!(" via {name}"))
+                    .unwrap_or_default();
+                let marker = if is_test_path(&site.caller.file_path) {
+                    " [test]"
+                } else {
+                    ""
+                };
+                out.push_str(&format!(
+                    "    Constructed in {} at {}:{}{via}{marker}\n",
+                    site.caller.name, site.caller.file_path, site.line
+                ));
+            }
+        }
+
+        out.push_str("\nExisting tests:\n");
+        if self.tests.is_empty() {
+            out.push_str("  (none found)\n");
+        }
+        for test in &self.tests {
+            out.push_str(&format!(
+                "  {} at {}:{} ({})\n",
+                test.symbol.name,
+                test.symbol.file_path,
+                test.symbol.range.start_line + 1,
+                test.reason
+            ));
+        }
+
+        out
+    }
+}
+
+/// Receiver type (for methods) followed by the types the symbol uses
+fn collect_dependencies(indexer: &IndexFacade, symbol: &Symbol) -> Vec<Dependency> {
+    let mut seen = HashSet::from([symbol.id]);
+    let mut types: Vec<(Symbol, bool)> = Vec::new();
+
+    if let Some(owner) = owner_name(symbol) {
+        if let Some(receiver) = indexer
+            .get_symbols_by_file(symbol.file_id)
+            .into_iter()
+            .find(|s| is_type(s.kind) && *s.name == *owner)
+        {
+            seen.insert(receiver.id);
+            types.push((receiver, true));
+        }
+    }
+
+    for used in indexer.get_uses(symbol.id) {
+        if is_type(used.kind) && seen.insert(used.id) {
+            types.push((used, false));
+        }
+    }
+
+    types
+        .into_iter()
+        .take(MAX_DEPENDENCIES)
+        .map(|(dep, is_receiver)| {
+            let constructors = find_constructors(indexer, &dep);
+            let sites = construction_sites(indexer, &dep, &constructors);
+            Dependency {
+                symbol: dep,
+                is_receiver,
+                constructors,
+                sites,
+            }
+        })
+        .collect()
+}
+
+/// Constructor-like members declared next to the type
+fn find_constructors(indexer: &IndexFacade, ty: &Symbol) -> Vec<Symbol> {
+    let name = &*ty.name;
+    let mut constructors: Vec<Symbol> = indexer
+        .get_symbols_by_file(ty.file_id)
+        .into_iter()
+        .filter(|s| s.id != ty.id && is_constructor(s, name))
+        .filter(|s| owner_name(s).is_none_or(|owner| owner == name))
+        .collect();
+    constructors.sort_by_key(|s| s.range.start_line);
+    constructors.truncate(MAX_SITES_PER_DEPENDENCY);
+    constructors
+}
+
+/// Callers of the constructors (or of the type itself), test files first
+fn construction_sites(
+    indexer: &IndexFacade,
+    ty: &Symbol,
+    constructors: &[Symbol],
+) -> Vec<ConstructionSite> {
+    let mut sites = Vec::new();
+    let mut seen: HashSet<SymbolId> = HashSet::new();
+
+    let targets = constructors
+        .iter()
+        .map(|c| (c.id, Some(c.name.to_string())))
+        .chain(std::iter::once((ty.id, None)));
+    for (target, via) in targets {
+        for (caller, metadata) in indexer.get_calling_functions_with_metadata(target) {
+            if !seen.insert(caller.id) {
+                continue;
+            }
+            let line = metadata
+                .and_then(|m| m.line)
+                .map(|l| l + 1)
+                .unwrap_or(caller.range.start_line + 1);
+            sites.push(ConstructionSite {
+                caller,
+                line,
+                via: via.clone(),
+            });
+        }
+    }
+
+    sites.sort_by_key(|site| !is_test_path(&site.caller.file_path));
+    sites.truncate(MAX_SITES_PER_DEPENDENCY);
+    sites
+}
+
+/// Tests that call the symbol, then tests that share its name
+fn collect_related_tests(indexer: &IndexFacade, symbol: &Symbol, limit: usize) -> Vec<RelatedTest> {
+    let mut tests = Vec::new();
+    let mut seen = HashSet::from([symbol.id]);
+
+    for caller in indexer.get_calling_functions(symbol.id) {
+        if tests.len() >= limit {
+            return tests;
+        }
+        if is_test_symbol(&caller) && seen.insert(caller.id) {
+            tests.push(RelatedTest {
+                symbol: caller,
+                reason: "calls it",
+            });
+        }
+    }
+
+    let language = symbol.language_id.as_ref().map(|id| id.as_str());
+    let name = &*symbol.name;
+    let matches = indexer
+        .search(name, limit * 4, None, None, language)
+        .unwrap_or_default();
+    for result in matches {
+        if tests.len() >= limit {
+            break;
+        }
+        if !result.name.to_lowercase().contains(&name.to_lowercase()) {
+            continue;
+        }
+        let Some(candidate) = indexer.get_symbol(result.symbol_id) else {
+            continue;
+        };
+        if is_test_symbol(&candidate) && seen.insert(candidate.id) {
+            tests.push(RelatedTest {
+                symbol: candidate,
+                reason: "name match",
+            });
+        }
+    }
+
+    // Fall back to any test in the same file (e.g. a Rust `mod tests`) as a style example
+    if tests.is_empty() {
+        if let Some(example) = indexer
+            .get_symbols_by_file(symbol.file_id)
+            .into_iter()
+            .find(|s| is_test_symbol(s) && s.id != symbol.id)
+        {
+            tests.push(RelatedTest {
+                symbol: example,
+                reason: "same file, style example",
+            });
+        }
+    }
+
+    tests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range};
+    use tempfile::TempDir;
+
+    fn symbol(name: &str, kind: SymbolKind, path: &str) -> Symbol {
+        let mut symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 0, 0),
+        );
+        symbol.file_path = path.into();
+        symbol
+    }
+
+    #[test]
+    fn test_detect_framework_from_manifests() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        std::fs::write(
+            root.join("pyproject.toml"),
+            "[tool.pytest.ini_options]\naddopts = \"-q\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"devDependencies": {"vitest": "^1.0.0"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[dev-dependencies]\ntokio = { version = \"1\" }\n",
+        )
+        .unwrap();
+
+        let python = detect_test_framework(root, "python").unwrap();
+        assert_eq!(python.name, "pytest");
+        assert_eq!(python.source.as_deref(), Some("pyproject.toml"));
+        assert_eq!(
+            detect_test_framework(root, "typescript").unwrap().name,
+            "Vitest"
+        );
+        assert_eq!(
+            detect_test_framework(root, "rust").unwrap().name,
+            "cargo test (tokio)"
+        );
+        assert_eq!(detect_test_framework(root, "go").unwrap().name, "testing");
+        assert!(detect_test_framework(root, "java").is_none());
+    }
+
+    #[test]
+    fn test_test_paths_and_symbols() {
+        assert!(is_test_path("tests/parsers_tests.rs"));
+        assert!(is_test_path("pkg/server/handler_test.go"));
+        assert!(is_test_path("src/components/Button.test.tsx"));
+        assert!(is_test_path(
+            "app/src/test/java/com/acme/UserServiceTest.java"
+        ));
+        assert!(!is_test_path("src/indexing/pipeline/mod.rs"));
+        assert!(!is_test_path("src/contest.rs"));
+
+        assert!(is_test_symbol(&symbol(
+            "test_parse",
+            SymbolKind::Function,
+            "src/lib.rs"
+        )));
+        assert!(is_test_symbol(&symbol(
+            "parses_empty_input",
+            SymbolKind::Function,
+            "tests/parse.rs"
+        )));
+        assert!(!is_test_symbol(&symbol(
+            "parse",
+            SymbolKind::Function,
+            "src/lib.rs"
+        )));
+    }
+
+    #[test]
+    fn test_constructor_names() {
+        let ctor = |name| symbol(name, SymbolKind::Method, "src/lib.rs");
+        assert!(is_constructor(&ctor("new"), "Config"));
+        assert!(is_constructor(&ctor("with_capacity"), "Config"));
+        assert!(is_constructor(&ctor("fromJson"), "Config"));
+        assert!(is_constructor(&ctor("Config"), "Config"));
+        assert!(!is_constructor(&ctor("format"), "Config"));
+        assert!(!is_constructor(&ctor("fromage"), "Config"));
+    }
+}
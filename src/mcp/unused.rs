@@ -0,0 +1,229 @@
+//! Unreferenced symbol detection
+//!
+//! Backs the `find_unused_symbols` MCP tool. A symbol is unused when nothing
+//! in the index calls, uses, implements, extends or references it, and no
+//! file imports a symbol of its name. Some symbols are reached from outside
+//! the index and are left out unless asked for: public API, tests, which
+//! their runner calls, and entry points such as `main`, lifecycle hooks, or
+//! methods implementing a trait method and called through it.
+//!
+//! Calls made through reflection, registration by name or macros are not in
+//! the index, so results are candidates to review rather than code that is
+//! safe to delete.
+
+use super::test_context::{is_test_path, is_test_symbol};
+use crate::indexing::facade::IndexFacade;
+use crate::{Symbol, SymbolKind, Visibility};
+
+/// Kinds checked when the caller does not pick one
+pub const CANDIDATE_KINDS: [SymbolKind; 9] = [
+    SymbolKind::Function,
+    SymbolKind::Method,
+    SymbolKind::Struct,
+    SymbolKind::Enum,
+    SymbolKind::Trait,
+    SymbolKind::Interface,
+    SymbolKind::Class,
+    SymbolKind::TypeAlias,
+    SymbolKind::Constant,
+];
+
+/// Names that a runtime, framework or test runner calls
+const ENTRY_POINT_NAMES: [&str; 10] = [
+    "main",
+    "Main",
+    "_start",
+    "init",
+    "constructor",
+    "__construct",
+    "setUp",
+    "tearDown",
+    "setup",
+    "teardown",
+];
+
+/// Which symbols without references are reported anyway
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnusedFilters {
+    pub include_public: bool,
+    pub include_tests: bool,
+    pub include_entry_points: bool,
+}
+
+/// Why an unreferenced symbol is not reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exclusion {
+    Public,
+    Test,
+    EntryPoint,
+}
+
+/// Result of [`find_unused`]
+#[derive(Debug, Default)]
+pub struct UnusedReport {
+    /// Unreferenced symbols, by file and line
+    pub symbols: Vec<Symbol>,
+    /// Symbols whose references were looked up
+    pub checked: usize,
+    pub excluded_public: usize,
+    pub excluded_tests: usize,
+    pub excluded_entry_points: usize,
+    /// Unreferenced symbols skipped because a file imports their name
+    pub imported: usize,
+    /// True when the scan stopped at the limit or timeout
+    pub truncated: bool,
+    pub timed_out: bool,
+}
+
+impl UnusedReport {
+    fn exclude(&mut self, exclusion: Exclusion) {
+        match exclusion {
+            Exclusion::Public => self.excluded_public += 1,
+            Exclusion::Test => self.excluded_tests += 1,
+            Exclusion::EntryPoint => self.excluded_entry_points += 1,
+        }
+    }
+}
+
+/// Whether a runtime or framework calls a symbol of this name itself
+pub fn is_entry_point_name(name: &str) -> bool {
+    // Python dunder methods are called by the interpreter
+    ENTRY_POINT_NAMES.contains(&name)
+        || (name.len() > 4 && name.starts_with("__") && name.ends_with("__"))
+}
+
+/// Why `symbol` is left out under `filters`, from what the symbol says of
+/// itself
+pub fn exclusion(symbol: &Symbol, filters: &UnusedFilters) -> Option<Exclusion> {
+    if !filters.include_tests && (is_test_symbol(symbol) || is_test_path(&symbol.file_path)) {
+        return Some(Exclusion::Test);
+    }
+    if !filters.include_entry_points && is_entry_point_name(&symbol.name) {
+        return Some(Exclusion::EntryPoint);
+    }
+    if !filters.include_public && symbol.visibility == Visibility::Public {
+        return Some(Exclusion::Public);
+    }
+    None
+}
+
+/// Unreferenced symbols of `kind`, or of [`CANDIDATE_KINDS`], defined in
+/// `files`, at most `limit` of them
+///
+/// Stops early once `query.timeout_ms` has passed.
+pub fn find_unused(
+    indexer: &IndexFacade,
+    files: &[String],
+    kind: Option<SymbolKind>,
+    filters: UnusedFilters,
+    limit: usize,
+) -> UnusedReport {
+    let budget = indexer.query_budget();
+    let mut report = UnusedReport::default();
+
+    'files: for path in files {
+        let Some((file_id, _)) = indexer.resolve_indexed_file(path) else {
+            continue;
+        };
+        let mut symbols = indexer.get_symbols_by_file(file_id);
+        symbols.sort_by_key(|symbol| (symbol.range.start_line, symbol.range.start_column));
+
+        for symbol in symbols {
+            let wanted = match kind {
+                Some(kind) => symbol.kind == kind,
+                None => CANDIDATE_KINDS.contains(&symbol.kind),
+            };
+            if !wanted {
+                continue;
+            }
+            if budget.expired() {
+                report.truncated = true;
+                report.timed_out = true;
+                break 'files;
+            }
+
+            report.checked += 1;
+            if indexer.has_incoming_references(symbol.id) {
+                continue;
+            }
+            if let Some(exclusion) = exclusion(&symbol, &filters) {
+                report.exclude(exclusion);
+                continue;
+            }
+            if !filters.include_entry_points
+                && symbol.kind == SymbolKind::Method
+                && indexer.overrides_inherited_method(&symbol)
+            {
+                report.exclude(Exclusion::EntryPoint);
+                continue;
+            }
+            if !indexer.find_imports_of_name(&symbol.name).is_empty() {
+                report.imported += 1;
+                continue;
+            }
+            if report.symbols.len() == limit {
+                report.truncated = true;
+                break 'files;
+            }
+            report.symbols.push(symbol);
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range, SymbolId};
+
+    fn symbol(name: &str, kind: SymbolKind, path: &str, visibility: Visibility) -> Symbol {
+        let mut symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 0, 0),
+        )
+        .with_visibility(visibility);
+        symbol.file_path = path.into();
+        symbol
+    }
+
+    #[test]
+    fn test_exclusions_follow_filters() {
+        let defaults = UnusedFilters::default();
+        let private = Visibility::Private;
+
+        let helper = symbol("format_row", SymbolKind::Function, "src/table.rs", private);
+        assert_eq!(exclusion(&helper, &defaults), None);
+
+        let api = symbol(
+            "Table",
+            SymbolKind::Struct,
+            "src/table.rs",
+            Visibility::Public,
+        );
+        assert_eq!(exclusion(&api, &defaults), Some(Exclusion::Public));
+
+        let test = symbol("test_render", SymbolKind::Function, "src/table.rs", private);
+        assert_eq!(exclusion(&test, &defaults), Some(Exclusion::Test));
+        let fixture = symbol("Fixture", SymbolKind::Struct, "tests/common.rs", private);
+        assert_eq!(exclusion(&fixture, &defaults), Some(Exclusion::Test));
+
+        let main = symbol("main", SymbolKind::Function, "src/main.rs", private);
+        assert_eq!(exclusion(&main, &defaults), Some(Exclusion::EntryPoint));
+        let dunder = symbol("__repr__", SymbolKind::Method, "app/models.py", private);
+        assert_eq!(exclusion(&dunder, &defaults), Some(Exclusion::EntryPoint));
+        assert!(!is_entry_point_name("__"));
+        assert!(!is_entry_point_name("__private"));
+
+        let everything = UnusedFilters {
+            include_public: true,
+            include_tests: true,
+            include_entry_points: true,
+        };
+        for symbol in [&api, &test, &fixture, &main, &dunder] {
+            assert_eq!(exclusion(symbol, &everything), None);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Profile-guided warm set for fast agent startup
+//!
+//! Every successful tool call records the symbols it looked up, the files they
+//! live in and the natural-language queries it embedded. The counts are kept
+//! per workspace in `<index_path>/warm_set.json`.
+//!
+//! A client that sends the `codanna/warmStart` experimental capability in its
+//! `initialize` request (or any client, with `mcp.warm_start = true`) gets the
+//! warm set preloaded before the handshake completes, plus a compact primer of
+//! the hot files and symbols so its first minute is not spent exploring.
+
+use rmcp::model::JsonObject;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// File name of the warm set inside the index directory
+pub const WARM_SET_FILE: &str = "warm_set.json";
+
+/// Experimental client capability that requests a warm start
+pub const WARM_START_CAPABILITY: &str = "codanna/warmStart";
+
+/// Entries kept per category; the least used are dropped first
+const MAX_ENTRIES: usize = 200;
+
+static TRACKERS: OnceLock<Mutex<HashMap<PathBuf, Arc<WarmSetTracker>>>> = OnceLock::new();
+
+/// How often and how recently an entry was used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub hits: u32,
+    /// Unix timestamp of the last use
+    pub last_used: u64,
+}
+
+/// Usage counts of symbols, files and semantic queries
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarmSet {
+    #[serde(default)]
+    pub symbols: HashMap<String, Usage>,
+    #[serde(default)]
+    pub files: HashMap<String, Usage>,
+    #[serde(default)]
+    pub queries: HashMap<String, Usage>,
+}
+
+impl WarmSet {
+    /// Load a warm set, starting empty if the file is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    tracing::debug!(target: "mcp", "cannot read {}: {e}", path.display());
+                }
+                return Self::default();
+            }
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            tracing::debug!(target: "mcp", "ignoring corrupt {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Write the warm set atomically
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string(self).map_err(io::Error::other)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty() && self.files.is_empty() && self.queries.is_empty()
+    }
+
+    pub fn record_symbol(&mut self, name: &str, now: u64) {
+        bump(&mut self.symbols, name, now);
+    }
+
+    pub fn record_file(&mut self, path: &str, now: u64) {
+        bump(&mut self.files, path, now);
+    }
+
+    pub fn record_query(&mut self, query: &str, now: u64) {
+        bump(&mut self.queries, query, now);
+    }
+
+    /// Most used symbol names, most used first
+    pub fn top_symbols(&self, limit: usize) -> Vec<&str> {
+        top(&self.symbols, limit)
+    }
+
+    /// Most used file paths, most used first
+    pub fn top_files(&self, limit: usize) -> Vec<&str> {
+        top(&self.files, limit)
+    }
+
+    /// Most used semantic queries, most used first
+    pub fn top_queries(&self, limit: usize) -> Vec<&str> {
+        top(&self.queries, limit)
+    }
+}
+
+fn bump(entries: &mut HashMap<String, Usage>, key: &str, now: u64) {
+    let usage = entries.entry(key.to_string()).or_default();
+    usage.hits = usage.hits.saturating_add(1);
+    usage.last_used = now;
+
+    if entries.len() > MAX_ENTRIES {
+        let coldest = entries
+            .iter()
+            .filter(|(name, _)| name.as_str() != key)
+            .min_by_key(|(_, usage)| (usage.hits, usage.last_used))
+            .map(|(name, _)| name.clone());
+        if let Some(coldest) = coldest {
+            entries.remove(&coldest);
+        }
+    }
+}
+
+fn top(entries: &HashMap<String, Usage>, limit: usize) -> Vec<&str> {
+    let mut ranked: Vec<(&String, &Usage)> = entries.iter().collect();
+    ranked.sort_by(|(a_name, a), (b_name, b)| {
+        b.hits
+            .cmp(&a.hits)
+            .then(b.last_used.cmp(&a.last_used))
+            .then(a_name.cmp(b_name))
+    });
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
+
+/// What a tool call looked up, read from its arguments
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ToolUsage {
+    pub symbol_name: Option<String>,
+    pub symbol_id: Option<u32>,
+    /// File looked at as a whole, as given
+    pub file: Option<String>,
+    /// Natural-language query that was embedded
+    pub query: Option<String>,
+}
+
+impl ToolUsage {
+    pub fn from_call(tool: &str, arguments: Option<&JsonObject>) -> Self {
+        let Some(arguments) = arguments else {
+            return Self::default();
+        };
+        let text = |key: &str| {
+            arguments
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        match tool {
+            "find_symbol"
+            | "get_calls"
+            | "find_callers"
+            | "find_implementations"
+            | "analyze_impact"
+            | "test_context_for"
+            | "get_symbol_source"
+            | "find_similar_symbols" => Self {
+                symbol_name: text("name")
+                    .or_else(|| text("function_name"))
+                    .or_else(|| text("symbol_name")),
+                symbol_id: arguments
+                    .get("symbol_id")
+                    .and_then(|value| value.as_u64())
+                    .and_then(|id| u32::try_from(id).ok()),
+                file: text("file_path"),
+                query: None,
+            },
+            "get_file_outline" => Self {
+                file: text("path"),
+                ..Self::default()
+            },
+            "semantic_search_docs" | "semantic_search_with_context" => Self {
+                query: text("query"),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbol_name.is_none()
+            && self.symbol_id.is_none()
+            && self.file.is_none()
+            && self.query.is_none()
+    }
+}
+
+/// The warm set of one workspace, shared by every session of the process
+pub struct WarmSetTracker {
+    path: PathBuf,
+    set: Mutex<WarmSet>,
+}
+
+impl WarmSetTracker {
+    /// Get the tracker for the index at `index_base`, loading it on first use
+    ///
+    /// HTTP transports build a server per session, so the counts live here
+    /// rather than on the server.
+    pub fn shared(index_base: &Path) -> Arc<Self> {
+        let trackers = TRACKERS.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut trackers = trackers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        trackers
+            .entry(index_base.to_path_buf())
+            .or_insert_with(|| {
+                let path = index_base.join(WARM_SET_FILE);
+                Arc::new(Self {
+                    set: Mutex::new(WarmSet::load(&path)),
+                    path,
+                })
+            })
+            .clone()
+    }
+
+    /// Record one lookup and persist the updated counts
+    pub fn record<'a>(
+        &self,
+        symbols: impl IntoIterator<Item = &'a str>,
+        files: impl IntoIterator<Item = &'a str>,
+        query: Option<&str>,
+    ) {
+        let now = crate::indexing::get_utc_timestamp();
+        let mut set = self
+            .set
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for symbol in symbols {
+            set.record_symbol(symbol, now);
+        }
+        for file in files {
+            set.record_file(file, now);
+        }
+        if let Some(query) = query {
+            set.record_query(query, now);
+        }
+
+        if let Err(e) = set.save(&self.path) {
+            tracing::debug!(target: "mcp", "cannot save {}: {e}", self.path.display());
+        }
+    }
+
+    /// Copy of the current counts
+    pub fn snapshot(&self) -> WarmSet {
+        self.set
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ranking_and_eviction() {
+        let mut set = WarmSet::default();
+        set.record_symbol("parse", 1);
+        set.record_symbol("index", 2);
+        set.record_symbol("parse", 3);
+        assert_eq!(set.top_symbols(5), vec!["parse", "index"]);
+        assert_eq!(set.top_symbols(1), vec!["parse"]);
+
+        for i in 0..MAX_ENTRIES + 10 {
+            set.record_file(&format!("src/{i}.rs"), 10 + i as u64);
+        }
+        assert_eq!(set.files.len(), MAX_ENTRIES);
+        assert!(
+            set.files
+                .contains_key(&format!("src/{}.rs", MAX_ENTRIES + 9))
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(WARM_SET_FILE);
+        assert!(WarmSet::load(&path).is_empty());
+
+        let mut set = WarmSet::default();
+        set.record_query("how are files indexed", 5);
+        set.save(&path).unwrap();
+        assert_eq!(
+            WarmSet::load(&path).top_queries(3),
+            vec!["how are files indexed"]
+        );
+    }
+
+    #[test]
+    fn test_tool_usage_from_arguments() {
+        let args = |value: serde_json::Value| value.as_object().cloned().unwrap();
+
+        let usage = ToolUsage::from_call(
+            "find_callers",
+            Some(&args(json!({ "function_name": "run", "limit": 5 }))),
+        );
+        assert_eq!(usage.symbol_name.as_deref(), Some("run"));
+
+        let usage = ToolUsage::from_call("get_calls", Some(&args(json!({ "symbol_id": 42 }))));
+        assert_eq!(usage.symbol_id, Some(42));
+
+        let usage = ToolUsage::from_call(
+            "semantic_search_docs",
+            Some(&args(json!({ "query": "parse config" }))),
+        );
+        assert_eq!(usage.query.as_deref(), Some("parse config"));
+
+        let usage = ToolUsage::from_call(
+            "get_file_outline",
+            Some(&args(json!({ "path": "src/lib.rs" }))),
+        );
+        assert_eq!(usage.file.as_deref(), Some("src/lib.rs"));
+
+        assert!(ToolUsage::from_call("get_index_info", None).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Requests translated by an LLM
+
+use super::{QueryTranslator, RuleTranslator, StructuredQuery, TranslateError};
+use crate::config::Settings;
+use crate::summary::LlmSummarizer;
+
+const SYSTEM_PROMPT: &str = "You turn requests for code search into filters. \
+Reply with one JSON object and nothing else. Keys: \
+\"query\": the words to search for once the filters are taken out; \
+\"lang\": a language id such as rust, python, typescript or go; \
+\"kind\": one of Function, Method, Struct, Enum, Trait, Interface, Class, Module, Variable, \
+Constant, Field, TypeAlias, Macro; \
+\"path\": a glob over file paths, such as **/auth* for the auth module; \
+\"visibility\": public, crate, module or private. \
+Leave out a key the request does not name.";
+
+/// Most tokens of a reply; a structured query is short
+const MAX_REPLY_TOKENS: u32 = 200;
+
+/// Translates requests with the `[summary]` chat endpoint
+pub struct LlmTranslator {
+    client: LlmSummarizer,
+    repositories: Vec<String>,
+    rules: RuleTranslator,
+}
+
+impl LlmTranslator {
+    pub fn new(settings: &Settings) -> Self {
+        let mut repositories: Vec<String> = settings.repositories.keys().cloned().collect();
+        repositories.sort();
+        Self {
+            client: LlmSummarizer::from_config(&settings.summary),
+            repositories,
+            rules: RuleTranslator::new(settings),
+        }
+    }
+
+    /// Translate `request` with the LLM alone
+    pub fn try_translate(&self, request: &str) -> Result<StructuredQuery, TranslateError> {
+        let mut system = SYSTEM_PROMPT.to_string();
+        if !self.repositories.is_empty() {
+            system.push_str(&format!(
+                " \"repo\": one of {}, when the request names a repository.",
+                self.repositories.join(", ")
+            ));
+        }
+        let reply = self.client.complete(&system, request, MAX_REPLY_TOKENS)?;
+        let mut structured = parse_reply(&reply)?;
+        if structured.query.trim().is_empty() {
+            structured.query = request.trim().to_string();
+        }
+        structured
+            .filter()
+            .map_err(|reason| TranslateError::InvalidReply { reason })?;
+        Ok(structured)
+    }
+}
+
+impl QueryTranslator for LlmTranslator {
+    fn translate(&self, request: &str) -> StructuredQuery {
+        self.try_translate(request).unwrap_or_else(|e| {
+            tracing::warn!(target: "query", "{e}; translating with rules instead");
+            self.rules.translate(request)
+        })
+    }
+}
+
+/// The JSON object of a reply, which may be wrapped in a code fence or prose
+fn parse_reply(reply: &str) -> Result<StructuredQuery, TranslateError> {
+    let json = reply
+        .find('{')
+        .zip(reply.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| &reply[start..=end])
+        .ok_or_else(|| TranslateError::InvalidReply {
+            reason: format!("no JSON object in '{reply}'"),
+        })?;
+    serde_json::from_str(json).map_err(|e| TranslateError::InvalidReply {
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_is_parsed_from_fenced_json() {
+        let reply = "```json {\"query\": \"touch database\", \"kind\": \"Function\", \
+                     \"visibility\": \"public\", \"lang\": null} ```";
+        let structured = parse_reply(reply).unwrap();
+        assert_eq!(structured.query, "touch database");
+        assert_eq!(structured.kind.as_deref(), Some("Function"));
+        assert_eq!(structured.lang, None);
+
+        assert!(parse_reply("I cannot help with that").is_err());
+        assert!(parse_reply("{\"kind\": \"Function\"}").is_err());
+    }
+}
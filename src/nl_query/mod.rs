@@ -0,0 +1,103 @@
+//! Natural-language requests turned into structured queries
+//!
+//! A request such as "public async functions in the auth module that touch
+//! the database" names filters the search tools already take: a visibility,
+//! a symbol kind and a path. A translator takes those out of the request and
+//! keeps the words left to search for, here "async touch database".
+//! [`RuleTranslator`] recognizes filters by keyword. [`LlmTranslator`] asks
+//! the `[summary]` chat endpoint instead and falls back to the rules when the
+//! endpoint fails or replies with something unusable.
+
+mod llm;
+mod rules;
+
+pub use llm::LlmTranslator;
+pub use rules::RuleTranslator;
+
+use crate::config::Settings;
+use crate::indexing::facade::SemanticFilter;
+use crate::summary::SummaryError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A request split into search text and filters, named as the semantic
+/// search tools name their parameters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuredQuery {
+    /// Words to search for once the filters are taken out
+    pub query: String,
+    /// Language id, e.g. "rust"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Symbol kind, e.g. "Function"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Glob over file paths, e.g. `**/auth*`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// "public", "crate", "module" or "private"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// Repository name from `[repositories]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+}
+
+impl StructuredQuery {
+    /// Facade filter of the query, or what is invalid
+    pub fn filter(&self) -> Result<SemanticFilter, String> {
+        SemanticFilter::parse(
+            self.lang.clone(),
+            self.kind.as_deref(),
+            self.path.as_deref(),
+            self.visibility.as_deref(),
+            self.repo.clone(),
+        )
+    }
+
+    /// The filters as `key:value` pairs, for display
+    pub fn describe_filters(&self) -> Vec<String> {
+        [
+            ("lang", &self.lang),
+            ("kind", &self.kind),
+            ("path", &self.path),
+            ("visibility", &self.visibility),
+            ("repo", &self.repo),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some(format!("{key}:{}", value.as_deref()?)))
+        .collect()
+    }
+}
+
+/// Errors of translating a request with an LLM
+#[derive(Error, Debug)]
+pub enum TranslateError {
+    #[error(transparent)]
+    Endpoint(#[from] SummaryError),
+
+    #[error(
+        "Unusable query translation from the LLM: {reason}\nSuggestion: Set query.translator = \"rules\" or use a model that follows JSON instructions"
+    )]
+    InvalidReply { reason: String },
+}
+
+/// Turns a natural-language request into a structured query
+pub trait QueryTranslator {
+    fn translate(&self, request: &str) -> StructuredQuery;
+}
+
+/// Translator selected by `query.translator`
+pub fn translator(settings: &Settings) -> Box<dyn QueryTranslator + Send + Sync> {
+    match settings.query.translator.as_str() {
+        "llm" => Box::new(LlmTranslator::new(settings)),
+        "rules" => Box::new(RuleTranslator::new(settings)),
+        other => {
+            tracing::warn!(
+                target: "query",
+                "Unknown query.translator '{other}', using \"rules\"; use \"rules\" or \"llm\""
+            );
+            Box::new(RuleTranslator::new(settings))
+        }
+    }
+}
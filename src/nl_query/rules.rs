@@ -0,0 +1,303 @@
+//! Keyword rules for natural-language requests
+
+use super::{QueryTranslator, StructuredQuery};
+use crate::config::Settings;
+
+/// Words naming a symbol kind, with the kind
+const KIND_WORDS: &[(&str, &str)] = &[
+    ("class", "Class"),
+    ("classes", "Class"),
+    ("const", "Constant"),
+    ("constant", "Constant"),
+    ("constants", "Constant"),
+    ("consts", "Constant"),
+    ("enum", "Enum"),
+    ("enums", "Enum"),
+    ("field", "Field"),
+    ("fields", "Field"),
+    ("fn", "Function"),
+    ("fns", "Function"),
+    ("func", "Function"),
+    ("funcs", "Function"),
+    ("function", "Function"),
+    ("functions", "Function"),
+    ("interface", "Interface"),
+    ("interfaces", "Interface"),
+    ("macro", "Macro"),
+    ("macros", "Macro"),
+    ("method", "Method"),
+    ("methods", "Method"),
+    ("struct", "Struct"),
+    ("structs", "Struct"),
+    ("trait", "Trait"),
+    ("traits", "Trait"),
+    ("variable", "Variable"),
+    ("variables", "Variable"),
+];
+
+/// Words naming a visibility, with the visibility
+const VISIBILITY_WORDS: &[(&str, &str)] = &[
+    ("exported", "public"),
+    ("private", "private"),
+    ("pub", "public"),
+    ("public", "public"),
+];
+
+/// Other names of language ids
+const LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("c#", "csharp"),
+    ("c++", "cpp"),
+    ("golang", "go"),
+    ("js", "javascript"),
+    ("py", "python"),
+    ("ts", "typescript"),
+];
+
+/// Language ids that are also common words, only taken as a language
+/// after "in" or before "code" or "files"
+const AMBIGUOUS_LANGUAGES: &[&str] = &["c", "d", "go", "r"];
+
+/// Nouns after the name of a place in the code, as in "the auth module"
+const PLACE_NOUNS: &[&str] = &[
+    "crate",
+    "dir",
+    "directory",
+    "file",
+    "folder",
+    "module",
+    "namespace",
+    "package",
+];
+
+/// Nouns after the name of a repository
+const REPOSITORY_NOUNS: &[&str] = &["repo", "repository"];
+
+/// Words before the name of a place
+const PLACE_PREPOSITIONS: &[&str] = &["in", "inside", "under", "within"];
+
+/// Words that carry nothing to search for
+const STOPWORDS: &[&str] = &[
+    "a", "all", "an", "and", "any", "are", "can", "code", "defined", "do", "does", "every",
+    "files", "find", "for", "from", "get", "give", "has", "have", "in", "inside", "is", "it",
+    "list", "me", "of", "on", "search", "show", "some", "symbol", "symbols", "that", "the",
+    "their", "these", "this", "those", "to", "under", "what", "where", "which", "who", "whose",
+    "with", "within",
+];
+
+/// Translates requests by keyword
+#[derive(Debug, Clone, Default)]
+pub struct RuleTranslator {
+    /// Language ids of `[languages]`
+    languages: Vec<String>,
+    /// Names of `[repositories]`
+    repositories: Vec<String>,
+}
+
+/// A word of the request, with punctuation around it removed
+struct Word<'a> {
+    text: &'a str,
+    lower: String,
+    used: bool,
+}
+
+impl RuleTranslator {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            languages: settings.languages.keys().cloned().collect(),
+            repositories: settings.repositories.keys().cloned().collect(),
+        }
+    }
+
+    fn language(&self, word: &str) -> Option<String> {
+        let alias = LANGUAGE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == word)
+            .map(|(_, id)| *id);
+        let id = alias.unwrap_or(word);
+        self.languages
+            .iter()
+            .find(|language| language.as_str() == id)
+            .cloned()
+    }
+
+    /// Take "in the X module" or "in src/auth" out of `words`
+    fn take_place(&self, words: &mut [Word], structured: &mut StructuredQuery) {
+        for i in 0..words.len() {
+            let noun = words[i].lower.as_str();
+            let is_place = PLACE_NOUNS.contains(&noun);
+            let is_repository = REPOSITORY_NOUNS.contains(&noun);
+            if (is_place || is_repository) && i > 0 && !words[i - 1].used {
+                let name = words[i - 1].text.to_string();
+                if is_repository {
+                    let Some(repository) = self
+                        .repositories
+                        .iter()
+                        .find(|repository| repository.eq_ignore_ascii_case(&name))
+                    else {
+                        continue;
+                    };
+                    if structured.repo.is_some() {
+                        continue;
+                    }
+                    structured.repo = Some(repository.clone());
+                } else if structured.path.is_none() {
+                    structured.path = Some(path_glob(&name));
+                } else {
+                    continue;
+                }
+                words[i].used = true;
+                words[i - 1].used = true;
+                mark_preposition(words, i - 1);
+                continue;
+            }
+
+            // A path after a preposition needs no noun
+            let path_like = words[i].text.contains(['/', '*']);
+            if path_like
+                && structured.path.is_none()
+                && i > 0
+                && PLACE_PREPOSITIONS.contains(&words[i - 1].lower.as_str())
+            {
+                structured.path = Some(path_glob(words[i].text));
+                words[i].used = true;
+                words[i - 1].used = true;
+            }
+        }
+    }
+}
+
+impl QueryTranslator for RuleTranslator {
+    fn translate(&self, request: &str) -> StructuredQuery {
+        let mut words: Vec<Word> = request
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| "\"'`,;:?!()".contains(c)))
+            .map(|word| word.strip_suffix('.').unwrap_or(word))
+            .filter(|word| !word.is_empty())
+            .map(|text| Word {
+                text,
+                lower: text.to_lowercase(),
+                used: false,
+            })
+            .collect();
+        let mut structured = StructuredQuery::default();
+        self.take_place(&mut words, &mut structured);
+
+        for i in 0..words.len() {
+            if words[i].used {
+                continue;
+            }
+            let lower = words[i].lower.as_str();
+            if let Some((_, kind)) = KIND_WORDS.iter().find(|(word, _)| *word == lower) {
+                if structured.kind.is_none() {
+                    structured.kind = Some(kind.to_string());
+                    words[i].used = true;
+                }
+            } else if lower == "type" && words.get(i + 1).is_some_and(|next| next.lower == "alias")
+            {
+                if structured.kind.is_none() {
+                    structured.kind = Some("TypeAlias".to_string());
+                    words[i].used = true;
+                    words[i + 1].used = true;
+                }
+            } else if let Some((_, visibility)) =
+                VISIBILITY_WORDS.iter().find(|(word, _)| *word == lower)
+            {
+                if structured.visibility.is_none() {
+                    structured.visibility = Some(visibility.to_string());
+                    words[i].used = true;
+                }
+            } else if let Some(language) = self.language(lower) {
+                let next = words.get(i + 1).map(|next| next.lower.as_str());
+                let in_context = (i > 0 && words[i - 1].lower == "in")
+                    || matches!(next, Some("code" | "files" | "file"));
+                if structured.lang.is_none()
+                    && (!AMBIGUOUS_LANGUAGES.contains(&lower) || in_context)
+                {
+                    structured.lang = Some(language);
+                    words[i].used = true;
+                }
+            }
+        }
+
+        let text: Vec<&str> = words
+            .iter()
+            .filter(|word| !word.used && !STOPWORDS.contains(&word.lower.as_str()))
+            .map(|word| word.text)
+            .collect();
+        // A request of filters only is still searched for as written
+        structured.query = if text.is_empty() {
+            request.trim().to_string()
+        } else {
+            text.join(" ")
+        };
+        structured
+    }
+}
+
+/// Mark "in" or "in the" before the name at `name`
+fn mark_preposition(words: &mut [Word], name: usize) {
+    let mut i = name;
+    if i > 0 && words[i - 1].lower == "the" {
+        i -= 1;
+        words[i].used = true;
+    }
+    if i > 0 && PLACE_PREPOSITIONS.contains(&words[i - 1].lower.as_str()) {
+        words[i - 1].used = true;
+    }
+}
+
+/// Glob matching files and directories named `name` anywhere
+fn path_glob(name: &str) -> String {
+    if name.contains('*') {
+        return name.to_string();
+    }
+    format!("**/{}*", name.trim_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translator() -> RuleTranslator {
+        RuleTranslator {
+            languages: ["rust", "go", "typescript"].map(String::from).to_vec(),
+            repositories: vec!["backend".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_filters_are_taken_out_of_the_request() {
+        let structured = translator()
+            .translate("public async functions in the auth module that touch the database");
+        assert_eq!(
+            structured,
+            StructuredQuery {
+                query: "async touch database".to_string(),
+                kind: Some("Function".to_string()),
+                path: Some("**/auth*".to_string()),
+                visibility: Some("public".to_string()),
+                ..Default::default()
+            }
+        );
+        assert!(structured.filter().is_ok());
+
+        let structured = translator().translate("Rust structs in src/parsing implementing Display");
+        assert_eq!(structured.lang.as_deref(), Some("rust"));
+        assert_eq!(structured.kind.as_deref(), Some("Struct"));
+        assert_eq!(structured.path.as_deref(), Some("**/src/parsing*"));
+        assert_eq!(structured.query, "implementing Display");
+
+        let structured = translator().translate("ts interfaces in the backend repo");
+        assert_eq!(structured.lang.as_deref(), Some("typescript"));
+        assert_eq!(structured.repo.as_deref(), Some("backend"));
+        assert_eq!(structured.query, "ts interfaces in the backend repo");
+
+        // "go" is only a language where it reads as one
+        let structured = translator().translate("functions that go to the network");
+        assert_eq!(structured.lang, None);
+        assert_eq!(structured.query, "go network");
+        let structured = translator().translate("retry loops in go code");
+        assert_eq!(structured.lang.as_deref(), Some("go"));
+        assert_eq!(structured.query, "retry loops");
+    }
+}
@@ -0,0 +1,142 @@
+//! Configuration file behavior implementation
+
+use super::parser::PACKAGE_SIGNATURE_PREFIX;
+use crate::parsing::Import;
+use crate::parsing::LanguageBehavior;
+use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::{FileId, Symbol, Visibility};
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Language behavior for project manifests and CI configuration
+#[derive(Clone)]
+pub struct ConfigBehavior {
+    state: BehaviorState,
+}
+
+impl ConfigBehavior {
+    /// Create a new behavior instance
+    pub fn new() -> Self {
+        Self {
+            state: BehaviorState::new(),
+        }
+    }
+}
+
+impl StatefulBehavior for ConfigBehavior {
+    fn state(&self) -> &BehaviorState {
+        &self.state
+    }
+}
+
+impl Default for ConfigBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageBehavior for ConfigBehavior {
+    fn language_id(&self) -> crate::parsing::registry::LanguageId {
+        crate::parsing::registry::LanguageId::new("config")
+    }
+
+    fn format_module_path(&self, base_path: &str, _symbol_name: &str) -> String {
+        base_path.to_string()
+    }
+
+    /// Only a package's own name is visible to other manifests, so
+    /// dependency edges resolve to packages and never to section keys
+    fn parse_visibility(&self, signature: &str) -> Visibility {
+        if signature.starts_with(PACKAGE_SIGNATURE_PREFIX) {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    fn module_separator(&self) -> &'static str {
+        "/"
+    }
+
+    fn supports_traits(&self) -> bool {
+        false
+    }
+
+    fn supports_inherent_methods(&self) -> bool {
+        false
+    }
+
+    /// A manifest describes its directory, so the module path is the
+    /// directory (`crates/core/Cargo.toml` -> `crates/core`)
+    fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        let relative = file_path.strip_prefix(project_root).unwrap_or(file_path);
+        let module = relative
+            .parent()?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .filter(|segment| !segment.is_empty() && segment != ".")
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if module.is_empty() {
+            Some(".".to_string())
+        } else {
+            Some(module)
+        }
+    }
+
+    fn get_language(&self) -> Language {
+        // Manifests are read without tree-sitter (see `ConfigLanguage::has_grammar`);
+        // this grammar only satisfies the trait
+        tree_sitter_javascript::LANGUAGE.into()
+    }
+
+    // Override import tracking methods to use state
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.register_file_with_state(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.add_import_with_state(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.get_imports_from_state(file_id)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.state.get_module_path(file_id)
+    }
+
+    fn get_file_path(&self, file_id: FileId) -> Option<PathBuf> {
+        self.state.get_file_path(file_id)
+    }
+
+    fn is_resolvable_symbol(&self, symbol: &Symbol) -> bool {
+        symbol.visibility == Visibility::Public
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_packages_are_public() {
+        let behavior = ConfigBehavior::new();
+
+        assert_eq!(behavior.parse_visibility("package app"), Visibility::Public);
+        assert_eq!(behavior.parse_visibility("[package]"), Visibility::Private);
+        assert_eq!(
+            behavior.parse_visibility("\"dependencies\": {"),
+            Visibility::Private
+        );
+        assert_eq!(
+            behavior.module_path_from_file(
+                Path::new("/repo/crates/core/Cargo.toml"),
+                Path::new("/repo")
+            ),
+            Some("crates/core".to_string())
+        );
+    }
+}
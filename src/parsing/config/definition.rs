@@ -0,0 +1,138 @@
+//! Configuration file language definition for the registry
+//!
+//! Provides the language metadata and glue code used by the language registry
+//! to instantiate parsers and behaviors for project manifests and CI files.
+//! The registry matches files by extension, so [`is_config_file`] narrows
+//! `.toml`, `.json` and `.yaml` files down to the ones this frontend reads.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{ConfigBehavior, ConfigParser};
+use crate::parsing::{LanguageBehavior, LanguageDefinition, LanguageId, LanguageParser};
+use crate::{IndexError, IndexResult, Settings};
+
+/// Manifests and CI files recognized by name
+const CONFIG_FILE_NAMES: &[&str] = &[
+    "Cargo.toml",
+    "pyproject.toml",
+    "package.json",
+    ".gitlab-ci.yml",
+    ".travis.yml",
+    "azure-pipelines.yml",
+    "bitbucket-pipelines.yml",
+];
+
+/// Language definition for project manifests and CI configuration
+pub struct ConfigLanguage;
+
+impl ConfigLanguage {
+    /// Stable identifier used throughout the registry
+    pub const ID: LanguageId = LanguageId::new("config");
+}
+
+impl LanguageDefinition for ConfigLanguage {
+    fn id(&self) -> LanguageId {
+        Self::ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Config"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["toml", "json", "yaml", "yml"]
+    }
+
+    fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
+        let parser = ConfigParser::new().map_err(IndexError::General)?;
+        Ok(Box::new(parser))
+    }
+
+    fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
+        Box::new(ConfigBehavior::new())
+    }
+
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings
+            .languages
+            .get(self.id().as_str())
+            .map(|config| config.enabled)
+            .unwrap_or(self.default_enabled())
+    }
+
+    fn has_grammar(&self) -> bool {
+        false
+    }
+}
+
+/// Whether `path` is a manifest or CI file the config frontend indexes
+///
+/// Other `.toml`, `.json` and `.yaml` files (lockfiles, fixtures, settings)
+/// are skipped even though the extension is registered.
+pub fn is_config_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    if CONFIG_FILE_NAMES.contains(&name) {
+        return true;
+    }
+
+    let is_yaml = name.ends_with(".yml") || name.ends_with(".yaml");
+    let parent = path.parent();
+    is_yaml
+        && match dir_name(parent) {
+            Some("workflows") => dir_name(parent.and_then(Path::parent)) == Some(".github"),
+            Some(".circleci") => name == "config.yml",
+            _ => false,
+        }
+}
+
+/// Final component of `path` as UTF-8
+fn dir_name(path: Option<&Path>) -> Option<&str> {
+    path.and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+}
+
+/// Whether `extension` is one the config frontend registers
+pub(crate) fn is_config_extension(extension: &str) -> bool {
+    ConfigLanguage.extensions().contains(&extension)
+}
+
+/// Register the config frontend with the global registry
+pub(crate) fn register(registry: &mut crate::parsing::LanguageRegistry) {
+    registry.register(Arc::new(ConfigLanguage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_metadata() {
+        let lang = ConfigLanguage;
+
+        assert_eq!(lang.id(), LanguageId::new("config"));
+        assert_eq!(lang.name(), "Config");
+        assert!(!lang.has_grammar());
+    }
+
+    #[test]
+    fn test_only_manifests_and_ci_files_are_indexed() {
+        assert!(is_config_file(Path::new("Cargo.toml")));
+        assert!(is_config_file(Path::new("crates/core/Cargo.toml")));
+        assert!(is_config_file(Path::new("web/package.json")));
+        assert!(is_config_file(Path::new(".github/workflows/ci.yml")));
+        assert!(is_config_file(Path::new(".gitlab-ci.yml")));
+        assert!(is_config_file(Path::new(".circleci/config.yml")));
+
+        assert!(!is_config_file(Path::new("package-lock.json")));
+        assert!(!is_config_file(Path::new("rustfmt.toml")));
+        assert!(!is_config_file(Path::new("config/workflows/ci.yml")));
+        assert!(!is_config_file(Path::new("docker-compose.yml")));
+    }
+}
@@ -0,0 +1,12 @@
+//! Project manifest and CI configuration parser implementation
+
+pub mod behavior;
+pub mod definition;
+pub mod parser;
+
+pub use behavior::ConfigBehavior;
+pub use definition::{ConfigLanguage, is_config_file};
+pub use parser::ConfigParser;
+
+// Re-export for registry registration
+pub(crate) use definition::{is_config_extension, register};
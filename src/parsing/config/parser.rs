@@ -0,0 +1,848 @@
+//! Configuration file parser
+//!
+//! Indexes the top-level keys of project manifests and CI files as
+//! lightweight symbols:
+//!
+//! | Entry | Symbol kind |
+//! |-------|-------------|
+//! | `[dependencies]`, `"scripts": { ... }`, `jobs:` | Module |
+//! | `edition = "2024"`, `"version": "1.0.0"`, `stages: [...]` | Variable |
+//! | The package's own `name` | Module (public) |
+//!
+//! Dependency declarations become `Uses` edges from the section declaring
+//! them to the dependency's name. When the dependency is another package of
+//! the workspace, the edge resolves to that package's manifest:
+//!
+//! - `Cargo.toml`: `[dependencies]`, `[dev-dependencies]`,
+//!   `[build-dependencies]`, `[workspace.dependencies]` and `[target.*]`
+//!   variants, following `package = "..."` renames
+//! - `pyproject.toml`: `project.dependencies`, `project.optional-dependencies`,
+//!   `[dependency-groups]`, `build-system.requires` and Poetry dependencies
+//! - `package.json`: `dependencies`, `devDependencies`, `peerDependencies`
+//!   and `optionalDependencies`
+//!
+//! Manifests are read without tree-sitter: the format is recognized from the
+//! content, keys are located by scanning lines, and dependency tables are
+//! read with the `toml` and `serde_json` parsers.
+
+use crate::parsing::{Language, LanguageParser};
+use crate::symbol::ScopeContext;
+use crate::types::SymbolCounter;
+use crate::{FileId, Range, Symbol, SymbolKind};
+use std::any::Any;
+use std::ops::Range as ByteRange;
+use tree_sitter::Node;
+
+/// Cargo tables whose keys are dependencies
+const CARGO_DEPENDENCY_TABLES: &[&str] =
+    &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// package.json objects whose keys are dependencies
+const NPM_DEPENDENCY_OBJECTS: &[&str] = &[
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Prefix of the signature of a package's own symbol
+pub(crate) const PACKAGE_SIGNATURE_PREFIX: &str = "package ";
+
+/// Parser for project manifests and CI configuration
+#[derive(Debug, Default)]
+pub struct ConfigParser;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+/// A top-level key and the bytes of its entry
+#[derive(Debug, PartialEq, Eq)]
+struct Entry<'a> {
+    key: &'a str,
+    kind: SymbolKind,
+    bytes: ByteRange<usize>,
+}
+
+/// A dependency declared under a top-level key
+struct Dependency {
+    /// Top-level key of the declaring section
+    section: String,
+    name: String,
+}
+
+impl ConfigParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        Ok(Self)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn make_symbol(
+        &self,
+        index: &LineIndex,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+        name: &str,
+        kind: SymbolKind,
+        bytes: ByteRange<usize>,
+        signature: String,
+    ) -> Symbol {
+        let mut symbol = Symbol::new(
+            counter.next_id(),
+            name,
+            kind,
+            file_id,
+            index.range(bytes.clone()),
+        );
+        symbol.signature = Some(signature.into());
+        if let Some(doc) = comment_above(code, bytes.start) {
+            symbol.doc_comment = Some(doc.into());
+        }
+        symbol.scope_context = Some(ScopeContext::Module);
+        symbol
+    }
+}
+
+impl LanguageParser for ConfigParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let Some(format) = detect_format(code) else {
+            return Vec::new();
+        };
+        let index = LineIndex::new(code);
+        let entries = top_level_entries(code, format);
+        let mut symbols = Vec::new();
+
+        for entry in &entries {
+            symbols.push(self.make_symbol(
+                &index,
+                code,
+                file_id,
+                symbol_counter,
+                entry.key,
+                entry.kind,
+                entry.bytes.clone(),
+                entry_signature(&code[entry.bytes.clone()]),
+            ));
+        }
+
+        if let Some(bytes) = package_name(code, format, &entries) {
+            let name = &code[bytes.clone()];
+            symbols.push(self.make_symbol(
+                &index,
+                code,
+                file_id,
+                symbol_counter,
+                name,
+                SymbolKind::Module,
+                bytes,
+                format!("{PACKAGE_SIGNATURE_PREFIX}{name}"),
+            ));
+        }
+
+        symbols
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, _node: &Node, _code: &str) -> Option<String> {
+        // Manifests are not parsed with tree-sitter
+        None
+    }
+
+    fn find_calls<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_implementations<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let Some(format) = detect_format(code) else {
+            return Vec::new();
+        };
+        let index = LineIndex::new(code);
+        let entries = top_level_entries(code, format);
+
+        dependencies(code, format)
+            .into_iter()
+            .filter_map(|dependency| {
+                let section = entries.iter().find(|e| e.key == dependency.section)?;
+                let bytes = locate(code, &dependency.name, &section.bytes)?;
+                Some((section.key, &code[bytes.clone()], index.range(bytes)))
+            })
+            .collect()
+    }
+
+    fn find_defines<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_imports(&mut self, _code: &str, _file_id: FileId) -> Vec<crate::parsing::Import> {
+        Vec::new()
+    }
+
+    fn language(&self) -> Language {
+        Language::Config
+    }
+}
+
+/// Recognize the format of a manifest from its content
+fn detect_format(code: &str) -> Option<ConfigFormat> {
+    let trimmed = code.trim_start();
+    if trimmed.is_empty() {
+        None
+    } else if trimmed.starts_with('{') {
+        Some(ConfigFormat::Json)
+    } else if toml::from_str::<toml::Table>(code).is_ok() {
+        Some(ConfigFormat::Toml)
+    } else {
+        Some(ConfigFormat::Yaml)
+    }
+}
+
+fn top_level_entries(code: &str, format: ConfigFormat) -> Vec<Entry<'_>> {
+    match format {
+        ConfigFormat::Toml => toml_entries(code),
+        ConfigFormat::Json => json_entries(code),
+        ConfigFormat::Yaml => yaml_entries(code),
+    }
+}
+
+/// Lines of `code` with the byte offset each starts at
+fn lines_with_offsets(code: &str) -> impl Iterator<Item = (usize, &str)> {
+    code.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line.trim_end_matches(['\n', '\r'])))
+    })
+}
+
+/// Add a top-level entry unless its key was already seen, returning the
+/// entry that following lines extend
+fn open_entry<'a>(
+    entries: &mut Vec<Entry<'a>>,
+    key: &'a str,
+    kind: SymbolKind,
+    bytes: ByteRange<usize>,
+) -> Option<usize> {
+    match entries.iter().position(|entry| entry.key == key) {
+        // Contiguous repeats (`[dependencies.serde]` after `[dependencies]`)
+        // extend the entry; a key reopened later keeps its first span
+        Some(i) if i + 1 == entries.len() => Some(i),
+        Some(_) => None,
+        None => {
+            entries.push(Entry { key, kind, bytes });
+            Some(entries.len() - 1)
+        }
+    }
+}
+
+/// `[table]` headers and root `key = value` pairs of a TOML document
+fn toml_entries(code: &str) -> Vec<Entry<'_>> {
+    let mut entries = Vec::new();
+    let mut current = None;
+    let mut in_root = true;
+
+    for (offset, line) in lines_with_offsets(code) {
+        let trimmed = line.trim_start();
+        let start = offset + (line.len() - trimmed.len());
+        let end = offset + line.trim_end().len();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed.starts_with('[') {
+            in_root = false;
+            let header = trimmed.trim_start_matches('[');
+            let header = header.split(']').next().unwrap_or(header);
+            let header_start = start + (trimmed.len() - trimmed.trim_start_matches('[').len());
+            current = first_key_segment(header).and_then(|(key, key_offset)| {
+                let key_start = header_start + key_offset;
+                open_entry(
+                    &mut entries,
+                    &code[key_start..key_start + key.len()],
+                    SymbolKind::Module,
+                    start..end,
+                )
+            });
+        } else if let Some((key, value)) = toml_pair(trimmed).filter(|_| in_root) {
+            let key_start = start + (key.as_ptr() as usize - trimmed.as_ptr() as usize);
+            let kind = if value.starts_with('{') {
+                SymbolKind::Module
+            } else {
+                SymbolKind::Variable
+            };
+            current = open_entry(
+                &mut entries,
+                &code[key_start..key_start + key.len()],
+                kind,
+                start..end,
+            );
+        }
+
+        if let Some(i) = current {
+            entries[i].bytes.end = end;
+        }
+    }
+    entries
+}
+
+/// First segment of a dotted TOML key and its offset in `key`
+///
+/// `target.'cfg(unix)'.dependencies` -> `target`, `"quoted.key".x` -> `quoted.key`.
+fn first_key_segment(key: &str) -> Option<(&str, usize)> {
+    let leading = key.len() - key.trim_start().len();
+    let key = key.trim_start();
+    let segment = match key.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let inner = &key[1..];
+            let end = inner.find(quote)?;
+            return Some((&inner[..end], leading + 1));
+        }
+        _ => key
+            .split(|c: char| c == '.' || c.is_whitespace())
+            .next()
+            .unwrap_or(key),
+    };
+    let is_bare = !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    is_bare.then_some((segment, leading))
+}
+
+/// First key segment and value of a `key = value` line
+fn toml_pair(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once('=')?;
+    let (segment, offset) = first_key_segment(key)?;
+    Some((&key[offset..offset + segment.len()], value.trim_start()))
+}
+
+/// Keys of the root object of a JSON document
+fn json_entries(code: &str) -> Vec<Entry<'_>> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut string_start = 0;
+    let mut expecting_key = false;
+    let mut last_key: Option<ByteRange<usize>> = None;
+    let mut open: Option<usize> = None;
+    let mut last_significant = 0;
+
+    fn close(open: &mut Option<usize>, entries: &mut [Entry], end: usize) {
+        if let Some(i) = open.take() {
+            entries[i].bytes.end = end;
+        }
+    }
+
+    for (i, c) in code.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                if depth == 1 && expecting_key {
+                    last_key = Some(string_start + 1..i);
+                }
+            }
+            last_significant = i + 1;
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                string_start = i;
+            }
+            ':' if depth == 1 => {
+                if let Some(key) = last_key.take() {
+                    let value = code[i + 1..].trim_start();
+                    let kind = if value.starts_with('{') {
+                        SymbolKind::Module
+                    } else {
+                        SymbolKind::Variable
+                    };
+                    entries.push(Entry {
+                        key: &code[key.clone()],
+                        kind,
+                        bytes: key.start - 1..i + 1,
+                    });
+                    open = Some(entries.len() - 1);
+                    expecting_key = false;
+                }
+            }
+            ',' if depth == 1 => {
+                close(&mut open, &mut entries, last_significant);
+                expecting_key = true;
+            }
+            '{' | '[' => {
+                depth += 1;
+                if depth == 1 {
+                    expecting_key = c == '{';
+                }
+            }
+            '}' | ']' => {
+                if depth == 1 {
+                    close(&mut open, &mut entries, last_significant);
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        if !c.is_whitespace() {
+            last_significant = i + c.len_utf8();
+        }
+    }
+    close(&mut open, &mut entries, last_significant);
+    entries
+}
+
+/// Keys of the root mapping of a YAML document
+fn yaml_entries(code: &str) -> Vec<Entry<'_>> {
+    let mut entries = Vec::new();
+    let mut current = None;
+
+    for (offset, line) in lines_with_offsets(code) {
+        let trimmed = line.trim_start();
+        let end = offset + line.trim_end().len();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if line.starts_with("---") || line.starts_with("...") {
+            current = None;
+            continue;
+        }
+
+        // Indented lines and root sequence items belong to the open entry
+        let nested = line.starts_with([' ', '\t', '-']);
+        let pair = if nested { None } else { yaml_pair(line) };
+        if let Some((key, value)) = pair {
+            let kind = if value.is_empty() || value.starts_with(['{', '&']) {
+                SymbolKind::Module
+            } else {
+                SymbolKind::Variable
+            };
+            let key_start = offset + (key.as_ptr() as usize - line.as_ptr() as usize);
+            current = if key == "<<" {
+                None
+            } else {
+                open_entry(
+                    &mut entries,
+                    &code[key_start..key_start + key.len()],
+                    kind,
+                    offset..end,
+                )
+            };
+        }
+
+        if let Some(i) = current {
+            entries[i].bytes.end = end;
+        }
+    }
+    entries
+}
+
+/// Key and value of a `key: value` line, with comments removed
+fn yaml_pair(line: &str) -> Option<(&str, &str)> {
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let inner = &line[1..];
+            let end = inner.find(quote)?;
+            (&inner[..end], inner[end + 1..].strip_prefix(':')?)
+        }
+        _ => {
+            let colon = line
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| line[i + 1..].is_empty() || line[i + 1..].starts_with([' ', '\t']))?;
+            (line[..colon].trim_end(), &line[colon + 1..])
+        }
+    };
+    let value = rest.split(" #").next().unwrap_or(rest).trim();
+    (!key.is_empty()).then_some((key, value))
+}
+
+/// Declared dependencies of a Cargo, Python or npm manifest
+fn dependencies(code: &str, format: ConfigFormat) -> Vec<Dependency> {
+    let mut found = Vec::new();
+    match format {
+        ConfigFormat::Json => {
+            let Ok(serde_json::Value::Object(root)) = serde_json::from_str(code) else {
+                return Vec::new();
+            };
+            for section in NPM_DEPENDENCY_OBJECTS {
+                if let Some(serde_json::Value::Object(deps)) = root.get(*section) {
+                    for name in deps.keys() {
+                        push_dependency(&mut found, section, name);
+                    }
+                }
+            }
+        }
+        ConfigFormat::Toml => {
+            let Ok(root) = toml::from_str::<toml::Table>(code) else {
+                return Vec::new();
+            };
+            cargo_dependencies(&root, &mut found);
+            python_dependencies(&root, &mut found);
+        }
+        ConfigFormat::Yaml => {}
+    }
+    found
+}
+
+fn push_dependency(found: &mut Vec<Dependency>, section: &str, name: &str) {
+    if !name.is_empty() {
+        found.push(Dependency {
+            section: section.to_string(),
+            name: name.to_string(),
+        });
+    }
+}
+
+fn cargo_dependencies(root: &toml::Table, found: &mut Vec<Dependency>) {
+    // Dependency tables of `table`, reported under `section` or their own key
+    fn collect(section: &str, table: &toml::Table, found: &mut Vec<Dependency>) {
+        for key in CARGO_DEPENDENCY_TABLES {
+            let Some(deps) = table.get(*key).and_then(toml::Value::as_table) else {
+                continue;
+            };
+            let section = if section.is_empty() { key } else { section };
+            for (name, spec) in deps {
+                // `alias = { package = "real-name" }` depends on `real-name`
+                let package = spec.get("package").and_then(toml::Value::as_str);
+                push_dependency(found, section, package.unwrap_or(name));
+            }
+        }
+    }
+
+    collect("", root, found);
+    if let Some(workspace) = root.get("workspace").and_then(toml::Value::as_table) {
+        collect("workspace", workspace, found);
+    }
+    if let Some(targets) = root.get("target").and_then(toml::Value::as_table) {
+        for target in targets.values().filter_map(toml::Value::as_table) {
+            collect("target", target, found);
+        }
+    }
+}
+
+fn python_dependencies(root: &toml::Table, found: &mut Vec<Dependency>) {
+    let table =
+        |table: &toml::Table, key: &str| table.get(key).and_then(toml::Value::as_table).cloned();
+    fn requirements(section: &str, value: Option<&toml::Value>, found: &mut Vec<Dependency>) {
+        let items = value.and_then(toml::Value::as_array).into_iter().flatten();
+        for requirement in items.filter_map(toml::Value::as_str) {
+            push_dependency(found, section, requirement_name(requirement));
+        }
+    }
+
+    if let Some(project) = table(root, "project") {
+        requirements("project", project.get("dependencies"), found);
+        for group in table(&project, "optional-dependencies")
+            .unwrap_or_default()
+            .values()
+        {
+            requirements("project", Some(group), found);
+        }
+    }
+    for group in table(root, "dependency-groups")
+        .unwrap_or_default()
+        .values()
+    {
+        requirements("dependency-groups", Some(group), found);
+    }
+    if let Some(build) = table(root, "build-system") {
+        requirements("build-system", build.get("requires"), found);
+    }
+
+    let Some(poetry) = table(root, "tool").and_then(|tool| table(&tool, "poetry")) else {
+        return;
+    };
+    let mut groups = vec![
+        table(&poetry, "dependencies"),
+        table(&poetry, "dev-dependencies"),
+    ];
+    for group in table(&poetry, "group").unwrap_or_default().values() {
+        groups.push(
+            group
+                .as_table()
+                .and_then(|group| table(group, "dependencies")),
+        );
+    }
+    for name in groups.iter().flatten().flat_map(toml::Table::keys) {
+        if name != "python" {
+            push_dependency(found, "tool", name);
+        }
+    }
+}
+
+/// Distribution name of a PEP 508 requirement (`requests[socks]>=2.0` -> `requests`)
+fn requirement_name(requirement: &str) -> &str {
+    let requirement = requirement.trim_start();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    &requirement[..end]
+}
+
+/// Bytes of the package's own name, if the manifest declares one
+fn package_name(code: &str, format: ConfigFormat, entries: &[Entry]) -> Option<ByteRange<usize>> {
+    let (section, name) = match format {
+        ConfigFormat::Json => {
+            let root: serde_json::Value = serde_json::from_str(code).ok()?;
+            ("name", root.get("name")?.as_str()?.to_string())
+        }
+        ConfigFormat::Toml => {
+            let root = toml::from_str::<toml::Table>(code).ok()?;
+            let name_in = |path: &[&str]| {
+                let mut value = root.get(path[0])?;
+                for key in &path[1..] {
+                    value = value.get(*key)?;
+                }
+                value.get("name")?.as_str().map(str::to_string)
+            };
+            if let Some(name) = name_in(&["package"]) {
+                ("package", name)
+            } else if let Some(name) = name_in(&["project"]) {
+                ("project", name)
+            } else {
+                ("tool", name_in(&["tool", "poetry"])?)
+            }
+        }
+        ConfigFormat::Yaml => return None,
+    };
+
+    let entry = entries.iter().find(|entry| entry.key == section)?;
+    let within = &code[entry.bytes.clone()];
+    ['"', '\''].into_iter().find_map(|quote| {
+        let start = within.find(&format!("{quote}{name}{quote}"))? + entry.bytes.start + 1;
+        Some(start..start + name.len())
+    })
+}
+
+/// Bytes of `name` as a whole word, preferring its first occurrence in `within`
+fn locate(code: &str, name: &str, within: &ByteRange<usize>) -> Option<ByteRange<usize>> {
+    let is_name_char =
+        |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@' | '/');
+    let occurrences = code.match_indices(name).map(|(i, _)| i).filter(|&i| {
+        let before = code[..i].chars().next_back();
+        let after = code[i + name.len()..].chars().next();
+        !before.is_some_and(is_name_char) && !after.is_some_and(|c| is_name_char(c) && c != '.')
+    });
+    let mut first = None;
+    for start in occurrences {
+        if within.contains(&start) {
+            return Some(start..start + name.len());
+        }
+        first.get_or_insert(start);
+    }
+    first.map(|start| start..start + name.len())
+}
+
+/// First line of an entry: `[dependencies]`, `"scripts": {`, `jobs:`
+fn entry_signature(text: &str) -> String {
+    let first = text.lines().next().unwrap_or(text).trim();
+    crate::parsing::truncate_for_display(first, 200)
+}
+
+/// `#` comment lines directly above `start`
+fn comment_above(code: &str, start: usize) -> Option<String> {
+    let line_start = code[..start].rfind('\n').map_or(0, |i| i + 1);
+    let lines: Vec<_> = code[..line_start]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .take_while(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.into_iter().rev().collect::<Vec<_>>().join("\n"))
+    }
+}
+
+/// Maps byte offsets to line and column positions
+struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(code: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    fn position(&self, byte: usize) -> (u32, u16) {
+        let line = self.line_starts.partition_point(|&start| start <= byte) - 1;
+        (line as u32, (byte - self.line_starts[line]) as u16)
+    }
+
+    fn range(&self, bytes: ByteRange<usize>) -> Range {
+        let (start_line, start_column) = self.position(bytes.start);
+        let (end_line, end_column) = self.position(bytes.end);
+        Range::new(start_line, start_column, end_line, end_column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TOML: &str = r#"[package]
+name = "app"
+edition = "2024"
+
+# Runtime dependencies
+[dependencies]
+core-lib = { path = "../core" }
+json = { package = "serde_json", version = "1" }
+
+[dependencies.log]
+version = "0.4"
+
+[target.'cfg(unix)'.dev-dependencies]
+nix = "0.29"
+"#;
+
+    fn keys<'a>(entries: &'a [Entry]) -> Vec<(&'a str, SymbolKind)> {
+        entries
+            .iter()
+            .map(|entry| (entry.key, entry.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_toml_entries_merge_contiguous_tables() {
+        let entries = toml_entries(CARGO_TOML);
+        assert_eq!(
+            keys(&entries),
+            vec![
+                ("package", SymbolKind::Module),
+                ("dependencies", SymbolKind::Module),
+                ("target", SymbolKind::Module),
+            ]
+        );
+        assert!(CARGO_TOML[entries[1].bytes.clone()].ends_with("version = \"0.4\""));
+        assert_eq!(
+            comment_above(CARGO_TOML, entries[1].bytes.start).as_deref(),
+            Some("Runtime dependencies")
+        );
+
+        let root = toml_entries("edition = \"2024\"\nlints.rust = { }\n[workspace]\n");
+        assert_eq!(
+            keys(&root),
+            vec![
+                ("edition", SymbolKind::Variable),
+                ("lints", SymbolKind::Module),
+                ("workspace", SymbolKind::Module),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cargo_dependencies_follow_renames() {
+        let found: Vec<_> = dependencies(CARGO_TOML, ConfigFormat::Toml)
+            .into_iter()
+            .map(|dep| (dep.section, dep.name))
+            .collect();
+        let pair = |section: &str, name: &str| (section.to_string(), name.to_string());
+
+        assert!(found.contains(&pair("dependencies", "core-lib")));
+        assert!(found.contains(&pair("dependencies", "serde_json")));
+        assert!(found.contains(&pair("dependencies", "log")));
+        assert!(found.contains(&pair("target", "nix")));
+
+        let entries = toml_entries(CARGO_TOML);
+        let package = package_name(CARGO_TOML, ConfigFormat::Toml, &entries).unwrap();
+        assert_eq!(&CARGO_TOML[package], "app");
+    }
+
+    #[test]
+    fn test_pyproject_requirements() {
+        let code = r#"[project]
+name = "service"
+dependencies = ["requests[socks]>=2.0", "shared-models; python_version >= '3.9'"]
+
+[tool.poetry.dependencies]
+python = "^3.11"
+httpx = "*"
+"#;
+        let names: Vec<_> = dependencies(code, ConfigFormat::Toml)
+            .into_iter()
+            .map(|dep| dep.name)
+            .collect();
+        assert_eq!(names, vec!["requests", "shared-models", "httpx"]);
+
+        let entries = toml_entries(code);
+        let bytes = locate(code, "shared-models", &entries[0].bytes).unwrap();
+        assert_eq!(&code[bytes], "shared-models");
+    }
+
+    #[test]
+    fn test_json_entries() {
+        let code = r#"{
+  "name": "@acme/web",
+  "scripts": { "build": "vite build" },
+  "dependencies": {
+    "@acme/ui": "workspace:*"
+  }
+}"#;
+        let entries = json_entries(code);
+        assert_eq!(
+            keys(&entries),
+            vec![
+                ("name", SymbolKind::Variable),
+                ("scripts", SymbolKind::Module),
+                ("dependencies", SymbolKind::Module),
+            ]
+        );
+        assert_eq!(
+            &code[entries[1].bytes.clone()],
+            r#""scripts": { "build": "vite build" }"#
+        );
+
+        let package = package_name(code, ConfigFormat::Json, &entries).unwrap();
+        assert_eq!(&code[package], "@acme/web");
+        let deps = dependencies(code, ConfigFormat::Json);
+        assert_eq!(deps[0].name, "@acme/ui");
+    }
+
+    #[test]
+    fn test_yaml_entries() {
+        let code = "name: CI\non:\n  push:\n    branches: [main]\n\njobs:\n  build:\n    runs-on: ubuntu-latest # host\nstages:\n- test\n";
+        assert_eq!(detect_format(code), Some(ConfigFormat::Yaml));
+
+        let entries = yaml_entries(code);
+        assert_eq!(
+            keys(&entries),
+            vec![
+                ("name", SymbolKind::Variable),
+                ("on", SymbolKind::Module),
+                ("jobs", SymbolKind::Module),
+                ("stages", SymbolKind::Module),
+            ]
+        );
+        assert!(code[entries[2].bytes.clone()].ends_with("# host"));
+        assert!(code[entries[3].bytes.clone()].ends_with("- test"));
+    }
+}
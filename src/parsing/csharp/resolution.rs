@@ -293,6 +293,10 @@ impl ResolutionScope for CSharpResolutionContext {
                 // Reverse relationships - typically used for finding references
                 self.resolve(to_name)
             }
+            RelationKind::Mirrors => {
+                // Linked across languages after indexing, never parsed
+                None
+            }
         }
     }
 
@@ -0,0 +1,204 @@
+//! Parse error detection
+//!
+//! tree-sitter recovers from syntax errors by wrapping text it cannot parse in
+//! an `ERROR` node, or by inserting a zero-width `MISSING` node where a token
+//! was expected. Parsers skip both, so definitions inside those regions are
+//! lost or cut short without any warning.
+//!
+//! [`DiagnosticsParser`] finds these regions during the parse stage. They are
+//! stored per file in [`crate::storage::DiagnosticsStore`], shown by
+//! `codanna diagnostics` and the `get_diagnostics` MCP tool, and symbols that
+//! overlap one are reported as low-confidence.
+
+use crate::types::Range;
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Language, Node, Parser};
+
+/// Diagnostics kept per file; a badly broken file reports its first ones
+pub const MAX_DIAGNOSTICS_PER_FILE: usize = 50;
+
+/// Characters of unparsable text kept in a diagnostic
+const SNIPPET_CHARS: usize = 40;
+
+/// How tree-sitter recovered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticKind {
+    /// Text that could not be parsed
+    Error,
+    /// A token that was expected but absent
+    Missing,
+    /// A large file was parsed in pieces and cut inside a definition here
+    Split,
+}
+
+/// A region tree-sitter could not parse
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseDiagnostic {
+    pub kind: DiagnosticKind,
+    pub range: Range,
+    /// Start of the unparsable text, or the kind of the missing node
+    pub text: String,
+}
+
+impl ParseDiagnostic {
+    /// One-line description, e.g. "syntax error near `fn (`"
+    pub fn message(&self) -> String {
+        match self.kind {
+            DiagnosticKind::Error => format!("syntax error near `{}`", self.text),
+            DiagnosticKind::Missing => format!("missing `{}`", self.text),
+            DiagnosticKind::Split => format!("file parsed in pieces, cut before `{}`", self.text),
+        }
+    }
+
+    /// A cut made between `line - 1` and `line` while parsing a large file in
+    /// pieces, where `text` starts
+    ///
+    /// It covers both lines, so a definition cut in two overlaps it.
+    pub fn split(line: u32, text: &str) -> Self {
+        Self {
+            kind: DiagnosticKind::Split,
+            range: Range::new(line.saturating_sub(1), 0, line, 0),
+            text: snippet(text),
+        }
+    }
+
+    /// Whether the diagnostic falls within the lines of `range`
+    pub fn overlaps(&self, range: &Range) -> bool {
+        self.range.start_line <= range.end_line && range.start_line <= self.range.end_line
+    }
+}
+
+/// Collect the `ERROR` and `MISSING` nodes under `root`
+///
+/// Only subtrees that contain an error are visited, so a clean tree costs a
+/// single check.
+pub fn collect(root: Node, code: &str) -> Vec<ParseDiagnostic> {
+    let mut diagnostics = Vec::new();
+    if root.has_error() {
+        visit(root, code, &mut diagnostics);
+    }
+    diagnostics
+}
+
+fn visit(node: Node, code: &str, diagnostics: &mut Vec<ParseDiagnostic>) {
+    if diagnostics.len() >= MAX_DIAGNOSTICS_PER_FILE {
+        return;
+    }
+
+    if node.is_error() {
+        let text = code.get(node.byte_range()).unwrap_or_default();
+        diagnostics.push(ParseDiagnostic {
+            kind: DiagnosticKind::Error,
+            range: range_of(node),
+            text: snippet(text),
+        });
+        return;
+    }
+    if node.is_missing() {
+        diagnostics.push(ParseDiagnostic {
+            kind: DiagnosticKind::Missing,
+            range: range_of(node),
+            text: node.kind().to_string(),
+        });
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() {
+            visit(child, code, diagnostics);
+        }
+    }
+}
+
+fn range_of(node: Node) -> Range {
+    let start = node.start_position();
+    let end = node.end_position();
+    Range::new(
+        start.row as u32,
+        start.column as u16,
+        end.row as u32,
+        end.column as u16,
+    )
+}
+
+/// First line of `text`, shortened to [`SNIPPET_CHARS`]
+fn snippet(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty());
+    let line = line.unwrap_or_default();
+    if line.chars().count() > SNIPPET_CHARS {
+        let cut: String = line.chars().take(SNIPPET_CHARS).collect();
+        format!("{cut}...")
+    } else {
+        line.to_string()
+    }
+}
+
+/// Whether any diagnostic overlaps `range`
+pub fn affects(diagnostics: &[ParseDiagnostic], range: &Range) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.overlaps(range))
+}
+
+/// Parser used only to look for syntax errors
+pub struct DiagnosticsParser {
+    parser: Parser,
+}
+
+impl Default for DiagnosticsParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiagnosticsParser {
+    pub fn new() -> Self {
+        Self {
+            parser: Parser::new(),
+        }
+    }
+
+    /// Parse `code` with `grammar` and report the regions that failed
+    pub fn check(&mut self, code: &str, grammar: &Language) -> Vec<ParseDiagnostic> {
+        if self.parser.set_language(grammar).is_err() {
+            return Vec::new();
+        }
+        match self.parser.parse(code, None) {
+            Some(tree) => collect(tree.root_node(), code),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust() -> Language {
+        tree_sitter_rust::LANGUAGE.into()
+    }
+
+    #[test]
+    fn test_clean_code_has_no_diagnostics() {
+        let mut parser = DiagnosticsParser::new();
+        assert!(parser.check("fn main() {}\n", &rust()).is_empty());
+    }
+
+    #[test]
+    fn test_error_regions_are_reported() {
+        let code = "fn ok() {}\n\nfn broken( {\n    let x = ;\n}\n\nfn later() {}\n";
+        let diagnostics = DiagnosticsParser::new().check(code, &rust());
+        assert!(!diagnostics.is_empty());
+
+        let first = &diagnostics[0];
+        assert_eq!(first.range.start_line, 2);
+        assert!(!first.message().is_empty());
+
+        let ok = Range::new(0, 0, 0, 10);
+        assert!(!affects(&diagnostics, &ok));
+        let broken = Range::new(2, 0, 4, 1);
+        assert!(affects(&diagnostics, &broken));
+    }
+}
@@ -0,0 +1,92 @@
+//! Language behavior shared by runtime grammars
+
+use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::{Import, LanguageBehavior, LanguageId};
+use crate::{FileId, Visibility};
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Behavior for a grammar loaded at runtime
+///
+/// Nothing is known about the language's visibility or module rules, so
+/// every symbol is public and a file's module path is its relative path.
+#[derive(Clone)]
+pub struct ExternalBehavior {
+    id: LanguageId,
+    language: Language,
+    state: BehaviorState,
+}
+
+impl ExternalBehavior {
+    /// Create a behavior for the grammar registered as `id`
+    pub fn new(id: LanguageId, language: Language) -> Self {
+        Self {
+            id,
+            language,
+            state: BehaviorState::new(),
+        }
+    }
+}
+
+impl StatefulBehavior for ExternalBehavior {
+    fn state(&self) -> &BehaviorState {
+        &self.state
+    }
+}
+
+impl LanguageBehavior for ExternalBehavior {
+    fn language_id(&self) -> LanguageId {
+        self.id
+    }
+
+    fn format_module_path(&self, base_path: &str, _symbol_name: &str) -> String {
+        base_path.to_string()
+    }
+
+    fn parse_visibility(&self, _signature: &str) -> Visibility {
+        Visibility::Public
+    }
+
+    fn module_separator(&self) -> &'static str {
+        "/"
+    }
+
+    /// `src/net/http.zig` becomes `src/net/http`
+    fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        let relative = file_path.strip_prefix(project_root).unwrap_or(file_path);
+        let module = relative
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .filter(|segment| !segment.is_empty() && segment != ".")
+            .collect::<Vec<_>>()
+            .join("/");
+
+        (!module.is_empty()).then_some(module)
+    }
+
+    fn get_language(&self) -> Language {
+        self.language.clone()
+    }
+
+    // Override import tracking methods to use state
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.register_file_with_state(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.add_import_with_state(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.get_imports_from_state(file_id)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.state.get_module_path(file_id)
+    }
+
+    fn get_file_path(&self, file_id: FileId) -> Option<PathBuf> {
+        self.state.get_file_path(file_id)
+    }
+}
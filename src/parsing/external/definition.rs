@@ -0,0 +1,221 @@
+//! Registry definitions for runtime grammars
+
+use super::loader::{GrammarError, load_grammar, resolve_path};
+use super::{ExternalBehavior, ExternalParser, SymbolQuery};
+use crate::config::GrammarConfig;
+use crate::parsing::{
+    Language, LanguageBehavior, LanguageDefinition, LanguageId, LanguageParser, get_registry,
+};
+use crate::{IndexError, IndexResult, Settings};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Language definition backed by a grammar loaded at runtime
+pub struct ExternalLanguage {
+    id: LanguageId,
+    extensions: &'static [&'static str],
+    language: tree_sitter::Language,
+    query: Arc<SymbolQuery>,
+}
+
+impl ExternalLanguage {
+    /// Load the grammar and query declared as `[grammars.<name>]`
+    pub fn load(name: &str, config: &GrammarConfig, root: &Path) -> Result<Self, GrammarError> {
+        let invalid = |reason: &str| GrammarError::InvalidConfig {
+            name: name.to_string(),
+            reason: reason.to_string(),
+        };
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        {
+            return Err(invalid(
+                "the name may only use letters, digits, '-' and '_'",
+            ));
+        }
+        if config.extensions.is_empty() {
+            return Err(invalid("'extensions' lists no file extensions"));
+        }
+
+        let language = load_grammar(name, config, root)?;
+        let query_path = resolve_path(root, &config.query);
+        let source = std::fs::read_to_string(&query_path).map_err(|e| GrammarError::QueryRead {
+            path: query_path.clone(),
+            reason: e.to_string(),
+        })?;
+        let query =
+            SymbolQuery::new(&language, &source).map_err(|reason| GrammarError::InvalidQuery {
+                path: query_path,
+                reason,
+            })?;
+
+        // The registry works with static ids and extensions; each grammar is
+        // registered once for the life of the process
+        let id: &'static str = Box::leak(name.to_string().into_boxed_str());
+        let extensions: Vec<&'static str> = config
+            .extensions
+            .iter()
+            .map(|ext| &*Box::leak(ext.trim_start_matches('.').to_string().into_boxed_str()))
+            .collect();
+
+        Ok(Self {
+            id: LanguageId::new(id),
+            extensions: Box::leak(extensions.into_boxed_slice()),
+            language,
+            query: Arc::new(query),
+        })
+    }
+}
+
+impl LanguageDefinition for ExternalLanguage {
+    fn id(&self) -> LanguageId {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        self.id.as_str()
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        self.extensions
+    }
+
+    fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
+        let parser = ExternalParser::new(self.id, &self.language, self.query.clone())
+            .map_err(IndexError::General)?;
+        Ok(Box::new(parser))
+    }
+
+    fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
+        Box::new(ExternalBehavior::new(self.id, self.language.clone()))
+    }
+
+    /// Declaring a grammar enables it
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings
+            .languages
+            .get(self.id().as_str())
+            .map(|config| config.enabled)
+            .unwrap_or(self.default_enabled())
+    }
+}
+
+/// Load the grammars declared in settings and add them to the global registry
+///
+/// Grammars that fail to load are skipped and returned as errors, so one
+/// broken grammar does not stop indexing of the others. Calling this again
+/// with the same settings is a no-op.
+pub fn register_grammars(settings: &Settings) -> Vec<GrammarError> {
+    let mut errors = Vec::new();
+    if settings.grammars.is_empty() {
+        return errors;
+    }
+
+    let root = settings
+        .workspace_root
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let Ok(mut registry) = get_registry().lock() else {
+        return errors;
+    };
+
+    let mut names: Vec<&String> = settings.grammars.keys().collect();
+    names.sort();
+    for name in names {
+        let config = &settings.grammars[name];
+        if let Some(id) = registry.find_language_id(name) {
+            if Language::from_language_id(id).is_some() {
+                errors.push(GrammarError::InvalidConfig {
+                    name: name.clone(),
+                    reason: "a built-in language already uses this name".to_string(),
+                });
+            }
+            continue;
+        }
+
+        let taken = config.extensions.iter().find_map(|ext| {
+            registry.get_by_extension(ext).map(|owner| {
+                format!(
+                    "'.{}' already belongs to {}",
+                    ext.trim_start_matches('.'),
+                    owner.id()
+                )
+            })
+        });
+        if let Some(reason) = taken {
+            errors.push(GrammarError::InvalidConfig {
+                name: name.clone(),
+                reason,
+            });
+            continue;
+        }
+
+        match ExternalLanguage::load(name, config, &root) {
+            Ok(language) => {
+                tracing::debug!(
+                    "[parsing] registered grammar '{name}' for {:?}",
+                    language.extensions
+                );
+                registry.register(Arc::new(language));
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn settings_with(name: &str, extensions: &[&str]) -> Settings {
+        let mut grammars = HashMap::new();
+        grammars.insert(
+            name.to_string(),
+            GrammarConfig {
+                library: PathBuf::from("missing/libtree-sitter.so"),
+                symbol: None,
+                extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+                query: PathBuf::from("missing/tags.scm"),
+            },
+        );
+        Settings {
+            grammars,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn test_rejects_builtin_names_and_extensions() {
+        let errors = register_grammars(&settings_with("rust", &["rsx"]));
+        assert!(matches!(
+            errors.as_slice(),
+            [GrammarError::InvalidConfig { reason, .. }] if reason.contains("built-in")
+        ));
+
+        let errors = register_grammars(&settings_with("rusty", &["rs"]));
+        assert!(matches!(
+            errors.as_slice(),
+            [GrammarError::InvalidConfig { reason, .. }] if reason.contains("'.rs'")
+        ));
+    }
+
+    #[test]
+    fn test_load_failure_is_reported_not_registered() {
+        let errors = register_grammars(&settings_with("nosuchlang", &["nosuch"]));
+        assert!(matches!(
+            errors.as_slice(),
+            [GrammarError::LibraryLoad { .. }]
+        ));
+
+        let registry = get_registry().lock().unwrap();
+        assert!(registry.find_language_id("nosuchlang").is_none());
+    }
+}
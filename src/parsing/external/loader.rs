@@ -0,0 +1,198 @@
+//! Loading compiled grammars from disk
+
+use crate::config::GrammarConfig;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tree_sitter::{Language, Parser};
+
+#[cfg(feature = "wasm-grammars")]
+static WASM_ENGINE: std::sync::LazyLock<tree_sitter::wasmtime::Engine> =
+    std::sync::LazyLock::new(Default::default);
+
+/// Errors raised while loading a runtime grammar
+#[derive(Error, Debug)]
+pub enum GrammarError {
+    #[error(
+        "Grammar '{name}' is invalid: {reason}\nSuggestion: Check [grammars.{name}] in .codanna/settings.toml"
+    )]
+    InvalidConfig { name: String, reason: String },
+
+    #[error(
+        "Failed to load grammar library {path}: {reason}\nSuggestion: Check that the file exists and was built for this platform"
+    )]
+    LibraryLoad { path: PathBuf, reason: String },
+
+    #[error(
+        "Grammar library {path} does not export '{symbol}'\nSuggestion: Set 'symbol' in the grammar's settings to its language function"
+    )]
+    SymbolNotFound { path: PathBuf, symbol: String },
+
+    #[error(
+        "Grammar '{name}' uses ABI version {version}, this build supports {min} to {max}\nSuggestion: Regenerate the grammar with a compatible tree-sitter CLI"
+    )]
+    IncompatibleAbi {
+        name: String,
+        version: usize,
+        min: usize,
+        max: usize,
+    },
+
+    #[error(
+        "WebAssembly grammar {path} needs the 'wasm-grammars' feature\nSuggestion: Build codanna with --features wasm-grammars or use a shared library"
+    )]
+    WasmUnsupported { path: PathBuf },
+
+    #[error("Failed to read query {path}: {reason}")]
+    QueryRead { path: PathBuf, reason: String },
+
+    #[error(
+        "Invalid query {path}: {reason}\nSuggestion: Check the query against a sample file with 'codanna parse'"
+    )]
+    InvalidQuery { path: PathBuf, reason: String },
+}
+
+/// Resolve a path from a grammar's settings against the workspace root
+pub fn resolve_path(root: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    }
+}
+
+/// Load the grammar `name` declared by `config`
+///
+/// Shared libraries stay loaded for the rest of the process, since the
+/// returned language points into their data.
+pub fn load_grammar(
+    name: &str,
+    config: &GrammarConfig,
+    root: &Path,
+) -> Result<Language, GrammarError> {
+    let path = resolve_path(root, &config.library);
+    let symbol = config
+        .symbol
+        .clone()
+        .unwrap_or_else(|| format!("tree_sitter_{}", name.replace('-', "_")));
+
+    let language = if path.extension().is_some_and(|ext| ext == "wasm") {
+        let wasm_name = symbol.strip_prefix("tree_sitter_").unwrap_or(&symbol);
+        load_wasm(wasm_name, &path)?
+    } else {
+        load_library(&path, &symbol)?
+    };
+
+    let version = language.abi_version();
+    let supported = tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION..=tree_sitter::LANGUAGE_VERSION;
+    if !supported.contains(&version) {
+        return Err(GrammarError::IncompatibleAbi {
+            name: name.to_string(),
+            version,
+            min: *supported.start(),
+            max: *supported.end(),
+        });
+    }
+
+    Ok(language)
+}
+
+/// Give `parser` what runtime grammars need: a WebAssembly store when the
+/// `wasm-grammars` feature is enabled, nothing otherwise
+pub fn prepare_parser(parser: &mut Parser) -> Result<(), String> {
+    #[cfg(feature = "wasm-grammars")]
+    {
+        let store = tree_sitter::WasmStore::new(&WASM_ENGINE).map_err(|e| e.to_string())?;
+        parser.set_wasm_store(store).map_err(|e| e.to_string())?;
+    }
+    #[cfg(not(feature = "wasm-grammars"))]
+    let _ = parser;
+
+    Ok(())
+}
+
+fn load_library(path: &Path, symbol: &str) -> Result<Language, GrammarError> {
+    // SAFETY: loading runs the library's initializers. Generated grammars
+    // have none, and the library was named explicitly in settings.
+    let library =
+        unsafe { libloading::Library::new(path) }.map_err(|e| GrammarError::LibraryLoad {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+
+    let raw = {
+        // SAFETY: grammars export `const TSLanguage *tree_sitter_<name>(void)`
+        let function = unsafe {
+            library.get::<unsafe extern "C" fn() -> *const tree_sitter::ffi::TSLanguage>(
+                symbol.as_bytes(),
+            )
+        }
+        .map_err(|_| GrammarError::SymbolNotFound {
+            path: path.to_path_buf(),
+            symbol: symbol.to_string(),
+        })?;
+        // SAFETY: the function takes no arguments and returns static data
+        unsafe { function() }
+    };
+    if raw.is_null() {
+        return Err(GrammarError::LibraryLoad {
+            path: path.to_path_buf(),
+            reason: format!("'{symbol}' returned no language"),
+        });
+    }
+
+    // The language lives in the library's static data, so never unload it
+    std::mem::forget(library);
+    // SAFETY: `raw` is a valid language that outlives the process
+    Ok(unsafe { Language::from_raw(raw) })
+}
+
+#[cfg(feature = "wasm-grammars")]
+fn load_wasm(name: &str, path: &Path) -> Result<Language, GrammarError> {
+    let load_error = |reason: String| GrammarError::LibraryLoad {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let bytes = std::fs::read(path).map_err(|e| load_error(e.to_string()))?;
+    let mut store =
+        tree_sitter::WasmStore::new(&WASM_ENGINE).map_err(|e| load_error(e.to_string()))?;
+    store
+        .load_language(name, &bytes)
+        .map_err(|e| load_error(e.to_string()))
+}
+
+#[cfg(not(feature = "wasm-grammars"))]
+fn load_wasm(_name: &str, path: &Path) -> Result<Language, GrammarError> {
+    Err(GrammarError::WasmUnsupported {
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grammar(library: &str) -> GrammarConfig {
+        GrammarConfig {
+            library: PathBuf::from(library),
+            symbol: None,
+            extensions: vec!["zig".to_string()],
+            query: PathBuf::from("zig.scm"),
+        }
+    }
+
+    #[test]
+    fn test_missing_library_is_reported() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let error = load_grammar("zig", &grammar("libtree-sitter-zig.so"), dir.path());
+        assert!(
+            matches!(error, Err(GrammarError::LibraryLoad { path, .. }) if path.starts_with(dir.path()))
+        );
+    }
+
+    #[cfg(not(feature = "wasm-grammars"))]
+    #[test]
+    fn test_wasm_needs_feature() {
+        let error = load_grammar("zig", &grammar("tree-sitter-zig.wasm"), Path::new("."));
+        assert!(matches!(error, Err(GrammarError::WasmUnsupported { .. })));
+    }
+}
@@ -0,0 +1,28 @@
+//! Runtime-loaded tree-sitter grammars
+//!
+//! Languages codanna does not ship can be indexed by declaring a compiled
+//! grammar in settings.toml:
+//!
+//! ```toml
+//! [grammars.zig]
+//! library = "grammars/libtree-sitter-zig.so"
+//! extensions = ["zig"]
+//! query = "grammars/zig-tags.scm"
+//! ```
+//!
+//! `library` is a shared library exporting `tree_sitter_<name>` or, with the
+//! `wasm-grammars` feature, a `.wasm` build of the grammar. The query uses
+//! the tree-sitter tags conventions described in [`query`], so the
+//! `tags.scm` most grammar repositories ship can be used as is.
+
+pub mod behavior;
+pub mod definition;
+pub mod loader;
+pub mod parser;
+pub mod query;
+
+pub use behavior::ExternalBehavior;
+pub use definition::{ExternalLanguage, register_grammars};
+pub use loader::{GrammarError, load_grammar, prepare_parser};
+pub use parser::ExternalParser;
+pub use query::{CaptureRole, SymbolQuery};
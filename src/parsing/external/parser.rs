@@ -0,0 +1,361 @@
+//! Query-driven parser for runtime grammars
+//!
+//! Symbols come from the `@definition.<kind>` captures of the grammar's
+//! query and calls from its `@reference.call` captures. Everything else a
+//! built-in parser extracts (imports, implementations, type uses) needs
+//! knowledge of the language and is not reported.
+
+use super::loader::prepare_parser;
+use super::query::{Definition, SymbolQuery};
+use crate::parsing::{Language, LanguageId, LanguageParser};
+use crate::symbol::ScopeContext;
+use crate::types::SymbolCounter;
+use crate::{FileId, Range, Symbol, SymbolKind};
+use std::any::Any;
+use std::sync::Arc;
+use tree_sitter::{Node, Parser};
+
+/// Parser for a grammar loaded at runtime
+pub struct ExternalParser {
+    id: LanguageId,
+    parser: Parser,
+    query: Arc<SymbolQuery>,
+}
+
+impl std::fmt::Debug for ExternalParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalParser")
+            .field("language", &self.id.as_str())
+            .finish()
+    }
+}
+
+impl ExternalParser {
+    /// Create a parser for `language` extracting symbols with `query`
+    pub fn new(
+        id: LanguageId,
+        language: &tree_sitter::Language,
+        query: Arc<SymbolQuery>,
+    ) -> Result<Self, String> {
+        let mut parser = Parser::new();
+        prepare_parser(&mut parser)?;
+        parser
+            .set_language(language)
+            .map_err(|e| format!("Failed to initialize {id} parser: {e}"))?;
+
+        Ok(Self { id, parser, query })
+    }
+
+    /// Registry id of the grammar this parser runs
+    pub fn language_id(&self) -> LanguageId {
+        self.id
+    }
+
+    fn make_symbol(
+        &self,
+        definition: &Definition,
+        parent: Option<&Definition>,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+    ) -> Option<Symbol> {
+        let name = code[definition.name.byte_range()].trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let parent_kind = parent.map(|parent| parent.kind);
+        // Tags queries capture methods as functions; the enclosing type tells them apart
+        let kind = match (definition.kind, parent_kind) {
+            (SymbolKind::Function, Some(kind)) if is_type_kind(kind) => SymbolKind::Method,
+            (kind, _) => kind,
+        };
+
+        let mut symbol = Symbol::new(
+            counter.next_id(),
+            name,
+            kind,
+            file_id,
+            node_to_range(definition.node),
+        );
+        symbol.signature = Some(signature_for(definition.node, code).into());
+
+        let doc = if definition.doc.is_empty() {
+            doc_comment_for(&definition.node, code)
+        } else {
+            let parts: Vec<_> = definition
+                .doc
+                .iter()
+                .map(|node| clean_comment(&code[node.byte_range()]))
+                .filter(|part| !part.is_empty())
+                .collect();
+            (!parts.is_empty()).then(|| parts.join("\n"))
+        };
+        symbol.doc_comment = doc.map(Into::into);
+
+        symbol.scope_context = Some(match parent {
+            Some(parent) if is_type_kind(parent.kind) => ScopeContext::ClassMember {
+                class_name: Some(code[parent.name.byte_range()].trim().into()),
+            },
+            Some(parent) if matches!(parent.kind, SymbolKind::Function | SymbolKind::Method) => {
+                ScopeContext::Local {
+                    hoisted: false,
+                    parent_name: Some(code[parent.name.byte_range()].trim().into()),
+                    parent_kind: Some(parent.kind),
+                }
+            }
+            _ => ScopeContext::Module,
+        });
+        Some(symbol)
+    }
+}
+
+/// Kinds whose nested definitions are members
+fn is_type_kind(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Class
+            | SymbolKind::Struct
+            | SymbolKind::Enum
+            | SymbolKind::Trait
+            | SymbolKind::Interface
+    )
+}
+
+/// Innermost definition strictly containing `node`
+fn enclosing<'d, 't>(definitions: &'d [Definition<'t>], node: Node) -> Option<&'d Definition<'t>> {
+    definitions
+        .iter()
+        .filter(|definition| {
+            definition.node.id() != node.id()
+                && definition.node.start_byte() <= node.start_byte()
+                && node.end_byte() <= definition.node.end_byte()
+        })
+        .min_by_key(|definition| definition.node.byte_range().len())
+}
+
+pub(crate) fn node_to_range(node: Node) -> Range {
+    let start = node.start_position();
+    let end = node.end_position();
+    Range::new(
+        start.row as u32,
+        start.column as u16,
+        end.row as u32,
+        end.column as u16,
+    )
+}
+
+/// Signature: the definition's first line
+pub(crate) fn signature_for(node: Node, code: &str) -> String {
+    let text = &code[node.byte_range()];
+    let first_line = text.lines().next().unwrap_or(text);
+    let collapsed = first_line.split_whitespace().collect::<Vec<_>>().join(" ");
+    crate::parsing::truncate_for_display(&collapsed, 200)
+}
+
+/// Comments directly above a definition
+fn doc_comment_for(node: &Node, code: &str) -> Option<String> {
+    let mut comments = Vec::new();
+    let mut current = node.prev_sibling();
+    let mut expected_row = node.start_position().row;
+
+    while let Some(sibling) = current {
+        if !sibling.kind().contains("comment") {
+            break;
+        }
+        if sibling.end_position().row + 1 < expected_row {
+            break;
+        }
+        comments.push(clean_comment(&code[sibling.byte_range()]));
+        expected_row = sibling.start_position().row;
+        current = sibling.prev_sibling();
+    }
+
+    comments.retain(|comment| !comment.is_empty());
+    if comments.is_empty() {
+        None
+    } else {
+        comments.reverse();
+        Some(comments.join("\n"))
+    }
+}
+
+/// Strip the markers of line (`//`, `#`, `--`, `;`, `%`) and block (`/* */`)
+/// comments, whichever syntax the language uses
+pub(crate) fn clean_comment(raw: &str) -> String {
+    let raw = raw.trim();
+    let lines: Vec<&str> = if let Some(block) = raw.strip_prefix("/*") {
+        block
+            .trim_end_matches("*/")
+            .lines()
+            .map(|line| line.trim().trim_start_matches('*').trim())
+            .collect()
+    } else {
+        raw.lines()
+            .map(|line| {
+                line.trim()
+                    .trim_start_matches(['/', '#', '-', ';', '%', '!'])
+                    .trim()
+            })
+            .collect()
+    };
+    lines
+        .into_iter()
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl LanguageParser for ExternalParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let (definitions, _) = self.query.matches(tree.root_node(), code);
+        definitions
+            .iter()
+            .filter_map(|definition| {
+                let parent = enclosing(&definitions, definition.node);
+                self.make_symbol(definition, parent, code, file_id, symbol_counter)
+            })
+            .collect()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
+        doc_comment_for(node, code)
+    }
+
+    fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let (definitions, calls) = self.query.matches(tree.root_node(), code);
+        let callables: Vec<_> = definitions
+            .into_iter()
+            .filter(|definition| {
+                matches!(
+                    definition.kind,
+                    SymbolKind::Function | SymbolKind::Method | SymbolKind::Macro
+                )
+            })
+            .collect();
+
+        calls
+            .iter()
+            .filter_map(|call| {
+                let caller = enclosing(&callables, call.node)?;
+                Some((
+                    code[caller.name.byte_range()].trim(),
+                    code[call.name.byte_range()].trim(),
+                    node_to_range(call.node),
+                ))
+            })
+            .collect()
+    }
+
+    fn find_implementations<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_uses<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_defines<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_imports(&mut self, _code: &str, _file_id: FileId) -> Vec<crate::parsing::Import> {
+        Vec::new()
+    }
+
+    fn language(&self) -> Language {
+        Language::External
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY: &str = r#"
+        (class_definition name: (identifier) @name) @definition.class
+        (function_definition name: (identifier) @name) @definition.function
+        (call function: (identifier) @name) @reference.call
+    "#;
+
+    fn parser() -> ExternalParser {
+        // A grammar compiled in stands in for one loaded at runtime
+        let language: tree_sitter::Language = tree_sitter_python::LANGUAGE.into();
+        let query = SymbolQuery::new(&language, QUERY).unwrap();
+        ExternalParser::new(LanguageId::new("snake"), &language, Arc::new(query)).unwrap()
+    }
+
+    #[test]
+    fn test_symbols_and_scopes() {
+        let code = "\
+# A shopping cart
+class Cart:
+    def total(self):
+        return sum(self.items)
+
+def checkout(cart):
+    return cart.total()
+";
+        let mut counter = SymbolCounter::new();
+        let symbols = parser().parse(code, FileId::new(1).unwrap(), &mut counter);
+
+        let summary: Vec<_> = symbols
+            .iter()
+            .map(|symbol| (&*symbol.name, symbol.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Cart", SymbolKind::Class),
+                ("total", SymbolKind::Method),
+                ("checkout", SymbolKind::Function),
+            ]
+        );
+        assert_eq!(symbols[0].doc_comment.as_deref(), Some("A shopping cart"));
+        assert_eq!(
+            symbols[1].scope_context,
+            Some(ScopeContext::ClassMember {
+                class_name: Some("Cart".into())
+            })
+        );
+        assert_eq!(symbols[2].scope_context, Some(ScopeContext::Module));
+    }
+
+    #[test]
+    fn test_calls_have_enclosing_caller() {
+        let code = "def load():\n    return parse(read())\n\nprint(load())\n";
+        let calls = parser().find_calls(code);
+        let pairs: Vec<_> = calls.iter().map(|(from, to, _)| (*from, *to)).collect();
+        // The module-level call has no caller to attach to
+        assert_eq!(pairs, vec![("load", "parse"), ("load", "read")]);
+    }
+
+    #[test]
+    fn test_clean_comment() {
+        assert_eq!(clean_comment("/// Adds two numbers"), "Adds two numbers");
+        assert_eq!(clean_comment("-- Lua style"), "Lua style");
+        assert_eq!(
+            clean_comment("/**\n * Block\n * comment\n */"),
+            "Block\ncomment"
+        );
+    }
+}
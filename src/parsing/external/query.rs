@@ -0,0 +1,233 @@
+//! Symbol queries for runtime grammars
+//!
+//! Captures follow the tree-sitter tags conventions:
+//!
+//! | Capture | Meaning |
+//! |---------|---------|
+//! | `@definition.<kind>` | Node defining a symbol of `<kind>` |
+//! | `@name` | Name of the definition or call in the same match |
+//! | `@doc` | Documentation of the definition in the same match |
+//! | `@reference.call` | A call; its `@name` is the callee |
+//!
+//! `<kind>` is one of the names in [`KINDS`]. Other captures (`@reference.class`,
+//! `@local.scope`, ...) are ignored, so stock `tags.scm` files load unchanged.
+
+use crate::SymbolKind;
+use std::collections::HashSet;
+use tree_sitter::{Language, Node, Query, QueryCursor, StreamingIterator};
+
+/// `@definition.<kind>` names and the symbol kinds they map to
+pub const KINDS: &[(&str, SymbolKind)] = &[
+    ("function", SymbolKind::Function),
+    ("method", SymbolKind::Method),
+    ("class", SymbolKind::Class),
+    ("struct", SymbolKind::Struct),
+    ("enum", SymbolKind::Enum),
+    ("trait", SymbolKind::Trait),
+    ("interface", SymbolKind::Interface),
+    ("module", SymbolKind::Module),
+    ("namespace", SymbolKind::Module),
+    ("constant", SymbolKind::Constant),
+    ("variable", SymbolKind::Variable),
+    ("field", SymbolKind::Field),
+    ("property", SymbolKind::Field),
+    ("parameter", SymbolKind::Parameter),
+    ("type", SymbolKind::TypeAlias),
+    ("macro", SymbolKind::Macro),
+];
+
+/// What a capture contributes to a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureRole {
+    Definition(SymbolKind),
+    Name,
+    Doc,
+    Call,
+    Ignored,
+}
+
+impl CaptureRole {
+    /// Role of a capture name, or an error for an unknown definition kind
+    pub fn from_capture(capture: &str) -> Result<Self, String> {
+        match capture {
+            "name" => Ok(Self::Name),
+            "doc" => Ok(Self::Doc),
+            "reference.call" => Ok(Self::Call),
+            _ => match capture.strip_prefix("definition.") {
+                Some(kind) => KINDS
+                    .iter()
+                    .find(|(name, _)| *name == kind)
+                    .map(|(_, kind)| Self::Definition(*kind))
+                    .ok_or_else(|| {
+                        let known: Vec<_> = KINDS.iter().map(|(name, _)| *name).collect();
+                        format!(
+                            "unknown capture @{capture}, expected @definition.<{}>",
+                            known.join("|")
+                        )
+                    }),
+                None => Ok(Self::Ignored),
+            },
+        }
+    }
+}
+
+/// A symbol definition matched by the query
+#[derive(Debug, Clone)]
+pub struct Definition<'t> {
+    pub node: Node<'t>,
+    pub name: Node<'t>,
+    pub kind: SymbolKind,
+    pub doc: Vec<Node<'t>>,
+    /// Index of the query pattern that matched
+    pub pattern: usize,
+}
+
+/// A call matched by the query
+#[derive(Debug, Clone)]
+pub struct Call<'t> {
+    pub node: Node<'t>,
+    pub name: Node<'t>,
+}
+
+/// A compiled query with the role of each of its captures
+#[derive(Debug)]
+pub struct SymbolQuery {
+    query: Query,
+    roles: Vec<CaptureRole>,
+}
+
+impl SymbolQuery {
+    /// Compile `source` for `language`
+    pub fn new(language: &Language, source: &str) -> Result<Self, String> {
+        let query = Query::new(language, source).map_err(|e| e.to_string())?;
+        let roles = query
+            .capture_names()
+            .iter()
+            .map(|name| CaptureRole::from_capture(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !roles
+            .iter()
+            .any(|role| matches!(role, CaptureRole::Definition(_)))
+        {
+            return Err("query has no @definition.<kind> captures".to_string());
+        }
+        Ok(Self { query, roles })
+    }
+
+    /// Value of `(#set! <key> "<value>")` on pattern `pattern`, if any
+    pub fn property(&self, pattern: usize, key: &str) -> Option<&str> {
+        self.query
+            .property_settings(pattern)
+            .iter()
+            .find(|property| &*property.key == key && property.capture_id.is_none())
+            .and_then(|property| property.value.as_deref())
+    }
+
+    /// Definitions and calls under `root`, in document order
+    ///
+    /// Matches without a `@name` are skipped. A node matched by several
+    /// definition patterns is reported once, by the first pattern.
+    pub fn matches<'t>(&self, root: Node<'t>, code: &str) -> (Vec<Definition<'t>>, Vec<Call<'t>>) {
+        let mut definitions = Vec::new();
+        let mut calls = Vec::new();
+        let mut seen = HashSet::new();
+
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, root, code.as_bytes());
+        while let Some(found) = matches.next() {
+            let mut name = None;
+            let mut definition = None;
+            let mut call = None;
+            let mut doc = Vec::new();
+
+            for capture in found.captures {
+                match self.roles[capture.index as usize] {
+                    CaptureRole::Definition(kind) => definition = Some((capture.node, kind)),
+                    CaptureRole::Name => name = Some(capture.node),
+                    CaptureRole::Doc => doc.push(capture.node),
+                    CaptureRole::Call => call = Some(capture.node),
+                    CaptureRole::Ignored => {}
+                }
+            }
+
+            let Some(name) = name else {
+                continue;
+            };
+            if let Some((node, kind)) = definition {
+                if seen.insert(node.id()) {
+                    definitions.push(Definition {
+                        node,
+                        name,
+                        kind,
+                        doc,
+                        pattern: found.pattern_index,
+                    });
+                }
+            } else if let Some(node) = call {
+                calls.push(Call { node, name });
+            }
+        }
+
+        definitions.sort_by_key(|definition| definition.node.start_byte());
+        calls.sort_by_key(|call| call.node.start_byte());
+        (definitions, calls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_roles() {
+        assert_eq!(
+            CaptureRole::from_capture("definition.function"),
+            Ok(CaptureRole::Definition(SymbolKind::Function))
+        );
+        assert_eq!(
+            CaptureRole::from_capture("definition.type"),
+            Ok(CaptureRole::Definition(SymbolKind::TypeAlias))
+        );
+        assert_eq!(CaptureRole::from_capture("name"), Ok(CaptureRole::Name));
+        assert_eq!(
+            CaptureRole::from_capture("reference.class"),
+            Ok(CaptureRole::Ignored)
+        );
+        assert!(CaptureRole::from_capture("definition.widget").is_err());
+    }
+
+    #[test]
+    fn test_matches_tags_query() {
+        // A grammar compiled in stands in for one loaded at runtime
+        let language: Language = tree_sitter_python::LANGUAGE.into();
+        let query = SymbolQuery::new(
+            &language,
+            r#"
+            (class_definition name: (identifier) @name) @definition.class
+            (function_definition name: (identifier) @name) @definition.function
+            (call function: (identifier) @name) @reference.call
+            "#,
+        )
+        .unwrap();
+
+        let code = "class Cart:\n    def total(self):\n        return sum(self.items)\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let (definitions, calls) = query.matches(tree.root_node(), code);
+
+        let names: Vec<_> = definitions
+            .iter()
+            .map(|d| (&code[d.name.byte_range()], d.kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("Cart", SymbolKind::Class), ("total", SymbolKind::Function)]
+        );
+        assert_eq!(calls.len(), 1);
+        assert_eq!(&code[calls[0].name.byte_range()], "sum");
+
+        assert!(SymbolQuery::new(&language, "(identifier) @name").is_err());
+    }
+}
@@ -4,11 +4,13 @@
 //! Validates language enablement and provides discovery of supported languages.
 
 use super::{
-    CBehavior, CParser, CSharpBehavior, CSharpParser, CppBehavior, CppParser, GdscriptBehavior,
-    GdscriptParser, GoBehavior, GoParser, JavaBehavior, JavaParser, JavaScriptBehavior,
-    JavaScriptParser, KotlinBehavior, KotlinParser, Language, LanguageBehavior, LanguageId,
-    LanguageParser, PhpBehavior, PhpParser, PythonBehavior, PythonParser, RustBehavior, RustParser,
-    SwiftBehavior, SwiftParser, TypeScriptBehavior, TypeScriptParser, get_registry,
+    CBehavior, CParser, CSharpBehavior, CSharpParser, ConfigBehavior, ConfigParser, CppBehavior,
+    CppParser, GdscriptBehavior, GdscriptParser, GoBehavior, GoParser, HclBehavior, HclParser,
+    JavaBehavior, JavaParser, JavaScriptBehavior, JavaScriptParser, KotlinBehavior, KotlinParser,
+    Language, LanguageBehavior, LanguageId, LanguageParser, LuaBehavior, LuaParser, ObjCBehavior,
+    ObjCParser, PhpBehavior, PhpParser, ProtobufBehavior, ProtobufParser, PythonBehavior,
+    PythonParser, RustBehavior, RustParser, SqlBehavior, SqlParser, SwiftBehavior, SwiftParser,
+    TypeScriptBehavior, TypeScriptParser, VueBehavior, VueParser, get_registry,
 };
 use crate::{IndexError, IndexResult, Settings};
 use std::sync::Arc;
@@ -180,6 +182,37 @@ impl ParserFactory {
                 let parser = SwiftParser::new().map_err(|e| IndexError::General(e.to_string()))?;
                 Ok(Box::new(parser))
             }
+            Language::Lua => {
+                let parser = LuaParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
+            Language::Sql => {
+                let parser = SqlParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
+            Language::Protobuf => {
+                let parser = ProtobufParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
+            Language::Hcl => {
+                let parser = HclParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
+            Language::ObjectiveC => {
+                let parser = ObjCParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
+            Language::Config => {
+                let parser = ConfigParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
+            Language::Vue => {
+                let parser = VueParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
+            Language::External => Err(IndexError::General(
+                "Runtime grammars are created through the language registry".to_string(),
+            )),
         }
     }
 
@@ -311,6 +344,60 @@ impl ParserFactory {
                     behavior: Box::new(SwiftBehavior::new()),
                 }
             }
+            Language::Lua => {
+                let parser = LuaParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(LuaBehavior::new()),
+                }
+            }
+            Language::Sql => {
+                let parser = SqlParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(SqlBehavior::new()),
+                }
+            }
+            Language::Protobuf => {
+                let parser = ProtobufParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(ProtobufBehavior::new()),
+                }
+            }
+            Language::Hcl => {
+                let parser = HclParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(HclBehavior::new()),
+                }
+            }
+            Language::ObjectiveC => {
+                let parser = ObjCParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(ObjCBehavior::new()),
+                }
+            }
+            Language::Config => {
+                let parser = ConfigParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(ConfigBehavior::new()),
+                }
+            }
+            Language::Vue => {
+                let parser = VueParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(VueBehavior::new()),
+                }
+            }
+            Language::External => {
+                return Err(IndexError::General(
+                    "Runtime grammars are created through the language registry".to_string(),
+                ));
+            }
         };
 
         Ok(result)
@@ -347,6 +434,13 @@ impl ParserFactory {
             Language::C,
             Language::Cpp,
             Language::Gdscript,
+            Language::Lua,
+            Language::Sql,
+            Language::Protobuf,
+            Language::Hcl,
+            Language::ObjectiveC,
+            Language::Config,
+            Language::Vue,
         ]
         .into_iter()
         .filter(|&lang| self.is_language_enabled(lang))
@@ -472,6 +566,8 @@ mod tests {
                 extensions: vec!["rs".to_string()],
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
+                large_file_mb: None,
+                chunking: None,
             },
         );
 
@@ -483,6 +579,8 @@ mod tests {
                 extensions: vec!["py".to_string()],
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
+                large_file_mb: None,
+                chunking: None,
             },
         );
 
@@ -494,6 +592,8 @@ mod tests {
                 extensions: vec!["php".to_string()],
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
+                large_file_mb: None,
+                chunking: None,
             },
         );
 
@@ -505,6 +605,8 @@ mod tests {
                 extensions: vec!["gd".to_string()],
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
+                large_file_mb: None,
+                chunking: None,
             },
         );
 
@@ -620,6 +722,8 @@ mod tests {
                 extensions: vec!["py".to_string()],
                 parser_options: HashMap::new(),
                 config_files: Vec::new(),
+                large_file_mb: None,
+                chunking: None,
             },
         );
         settings.languages = languages;
@@ -800,6 +800,7 @@ mod tests {
             visibility: Visibility::Private, // Will be updated by configure_symbol
             scope_context: None,
             language_id: Some(LanguageId::new("go")),
+            tag: None,
         };
 
         behavior.configure_symbol(&mut symbol, Some("pkg/utils"));
@@ -829,6 +830,7 @@ mod tests {
             visibility: Visibility::Public, // Will be updated by configure_symbol
             scope_context: None,
             language_id: Some(LanguageId::new("go")),
+            tag: None,
         };
 
         behavior.configure_symbol(&mut symbol, None);
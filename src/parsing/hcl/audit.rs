@@ -0,0 +1,224 @@
+//! HCL parser audit module
+//!
+//! Tracks which AST nodes the parser actually touches compared to the full
+//! grammar exposed by tree-sitter-hcl. This helps highlight extraction gaps.
+
+use super::HclParser;
+use crate::io::format::format_utc_timestamp;
+use crate::parsing::parser::LanguageParser;
+use crate::types::{FileId, SymbolCounter};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use tree_sitter::{Node, Parser};
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("Failed to read file: {0}")]
+    FileRead(#[from] std::io::Error),
+
+    #[error("Failed to set language: {0}")]
+    LanguageSetup(String),
+
+    #[error("Failed to parse code")]
+    ParseFailure,
+
+    #[error("Failed to create parser: {0}")]
+    ParserCreation(String),
+}
+
+/// Summary of grammar coverage for the HCL parser
+pub struct HclParserAudit {
+    /// All node kinds discovered in the sampled code
+    pub grammar_nodes: HashMap<String, u16>,
+    /// Node kinds that the parser marked as handled during extraction
+    pub implemented_nodes: HashSet<String>,
+    /// Symbol kinds that ended up in the index
+    pub extracted_symbol_kinds: HashSet<String>,
+}
+
+impl HclParserAudit {
+    /// Run audit on a source file
+    pub fn audit_file(path: &str) -> Result<Self, AuditError> {
+        let code = std::fs::read_to_string(path)?;
+        Self::audit_code(&code)
+    }
+
+    /// Run audit directly on a source snippet
+    pub fn audit_code(code: &str) -> Result<Self, AuditError> {
+        let mut parser = Parser::new();
+        let language = tree_sitter_hcl::LANGUAGE.into();
+        parser
+            .set_language(&language)
+            .map_err(|e| AuditError::LanguageSetup(e.to_string()))?;
+        let tree = parser.parse(code, None).ok_or(AuditError::ParseFailure)?;
+
+        let mut grammar_nodes = HashMap::new();
+        discover_nodes(tree.root_node(), &mut grammar_nodes);
+
+        let mut hcl_parser = HclParser::new().map_err(AuditError::ParserCreation)?;
+        let mut counter = SymbolCounter::new();
+        let file_id = FileId::new(1).unwrap();
+        let symbols = hcl_parser.parse(code, file_id, &mut counter);
+
+        let extracted_symbol_kinds = symbols
+            .iter()
+            .map(|symbol| format!("{:?}", symbol.kind))
+            .collect();
+
+        let implemented_nodes = hcl_parser
+            .get_handled_nodes()
+            .iter()
+            .map(|handled| handled.name.clone())
+            .collect();
+
+        Ok(Self {
+            grammar_nodes,
+            implemented_nodes,
+            extracted_symbol_kinds,
+        })
+    }
+
+    /// Produce a Markdown coverage report for docs or CI artifacts
+    pub fn generate_report(&self) -> String {
+        let mut report = String::new();
+
+        report.push_str("# HCL Parser Symbol Extraction Coverage Report\n\n");
+        report.push_str(&format!("*Generated: {}*\n\n", format_utc_timestamp()));
+
+        let key_nodes = [
+            "block",
+            "body",
+            "attribute",
+            "identifier",
+            "string_lit",
+            "variable_expr",
+            "get_attr",
+            "comment",
+        ];
+
+        let key_implemented = key_nodes
+            .iter()
+            .filter(|n| self.implemented_nodes.contains(**n))
+            .count();
+
+        report.push_str("## Summary\n");
+        report.push_str(&format!(
+            "- Key nodes: {}/{} ({}%)\n",
+            key_implemented,
+            key_nodes.len(),
+            (key_implemented * 100) / key_nodes.len()
+        ));
+        report.push_str(&format!(
+            "- Symbol kinds extracted: {}\n",
+            self.extracted_symbol_kinds.len()
+        ));
+        report.push_str(
+            "\n> **Note:** Key nodes are symbol-producing constructs (resources, data sources, modules, variables, outputs, locals).\n\n",
+        );
+
+        report.push_str("## Coverage Table\n\n");
+        report.push_str("| Node Type | ID | Status |\n");
+        report.push_str("|-----------|-----|--------|\n");
+
+        let mut gaps = Vec::new();
+        let mut missing = Vec::new();
+
+        for node_name in &key_nodes {
+            let status = if let Some(id) = self.grammar_nodes.get(*node_name) {
+                if self.implemented_nodes.contains(*node_name) {
+                    format!("{id} | ✅ implemented")
+                } else {
+                    gaps.push(node_name);
+                    format!("{id} | ⚠️ gap")
+                }
+            } else {
+                missing.push(node_name);
+                "- | ⭕ not found".to_string()
+            };
+            report.push_str(&format!("| {node_name} | {status} |\n"));
+        }
+
+        report.push_str("\n## Legend\n\n");
+        report.push_str("- ✅ **implemented**: node type is handled by the parser\n");
+        report.push_str(
+            "- ⚠️ **gap**: node exists in grammar but parser does not currently extract it\n",
+        );
+        report.push_str("- ⭕ **not found**: node isn't present in the audited sample; add fixtures to verify\n");
+
+        report.push_str("\n## Recommended Actions\n\n");
+        if !gaps.is_empty() {
+            report.push_str("### Implementation Gaps\n");
+            for gap in &gaps {
+                report.push_str(&format!(
+                    "- `{gap}`: add handling in `hcl/parser.rs` if symbol extraction is required.\n"
+                ));
+            }
+            report.push('\n');
+        }
+
+        if !missing.is_empty() {
+            report.push_str("### Missing Samples\n");
+            for node in &missing {
+                report.push_str(&format!(
+                    "- `{node}`: include representative code in audit fixtures to track coverage.\n"
+                ));
+            }
+            report.push('\n');
+        }
+
+        if gaps.is_empty() && missing.is_empty() {
+            report.push_str("All tracked nodes are currently implemented ✅\n");
+        }
+
+        report
+    }
+}
+
+fn discover_nodes(node: Node, registry: &mut HashMap<String, u16>) {
+    registry.insert(node.kind().to_string(), node.kind_id());
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        discover_nodes(child, registry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_simple_terraform() {
+        let code = r#"
+# Web server
+resource "aws_instance" "web" {
+  ami = var.ami_id
+}
+
+variable "ami_id" {
+  type = string
+}
+"#;
+
+        let audit = HclParserAudit::audit_code(code).expect("audit should succeed");
+
+        assert!(
+            audit.grammar_nodes.contains_key("block"),
+            "Blocks should be discovered"
+        );
+        assert!(
+            audit.extracted_symbol_kinds.contains("Struct"),
+            "Resources should be extracted"
+        );
+        assert!(
+            audit.extracted_symbol_kinds.contains("Variable"),
+            "Variables should be extracted"
+        );
+
+        let report = audit.generate_report();
+        assert!(
+            report.contains("HCL Parser"),
+            "Report should contain header, got:\n{report}"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! HCL-specific language behavior implementation
+
+use crate::parsing::Import;
+use crate::parsing::LanguageBehavior;
+use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::{FileId, Symbol, SymbolKind, Visibility};
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Language behavior for HCL (Terraform)
+#[derive(Clone)]
+pub struct HclBehavior {
+    language: Language,
+    state: BehaviorState,
+}
+
+impl HclBehavior {
+    /// Create a new behavior instance
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_hcl::LANGUAGE.into(),
+            state: BehaviorState::new(),
+        }
+    }
+}
+
+impl StatefulBehavior for HclBehavior {
+    fn state(&self) -> &BehaviorState {
+        &self.state
+    }
+}
+
+impl Default for HclBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageBehavior for HclBehavior {
+    fn language_id(&self) -> crate::parsing::registry::LanguageId {
+        crate::parsing::registry::LanguageId::new("hcl")
+    }
+
+    fn configure_symbol(&self, symbol: &mut Symbol, module_path: Option<&str>) {
+        if let Some(path) = module_path {
+            let full_path = self.format_module_path(path, &symbol.name);
+            symbol.module_path = Some(full_path.into());
+        }
+
+        // A Terraform module's interface is its variables and outputs. Resources,
+        // data sources, locals and child modules are only addressable inside
+        // the module's own directory.
+        symbol.visibility = match symbol.kind {
+            SymbolKind::Variable if is_input_variable(symbol) => Visibility::Public,
+            SymbolKind::Constant => Visibility::Public,
+            _ => Visibility::Private,
+        };
+    }
+
+    fn format_module_path(&self, base_path: &str, _symbol_name: &str) -> String {
+        base_path.to_string()
+    }
+
+    fn parse_visibility(&self, signature: &str) -> Visibility {
+        if signature.starts_with("variable ") || signature.starts_with("output ") {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    fn module_separator(&self) -> &'static str {
+        "/"
+    }
+
+    /// Terraform modules are directories, so every file in a directory shares
+    /// one module path
+    fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        let relative = file_path.strip_prefix(project_root).unwrap_or(file_path);
+        let module = relative
+            .parent()?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .filter(|segment| !segment.is_empty() && segment != ".")
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if module.is_empty() {
+            Some(".".to_string())
+        } else {
+            Some(module)
+        }
+    }
+
+    fn get_language(&self) -> Language {
+        self.language.clone()
+    }
+
+    // Override import tracking methods to use state
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.register_file_with_state(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.add_import_with_state(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.get_imports_from_state(file_id)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.state.get_module_path(file_id)
+    }
+
+    fn get_file_path(&self, file_id: FileId) -> Option<PathBuf> {
+        self.state.get_file_path(file_id)
+    }
+
+    fn is_resolvable_symbol(&self, symbol: &Symbol) -> bool {
+        matches!(
+            symbol.kind,
+            SymbolKind::Struct | SymbolKind::Module | SymbolKind::Variable | SymbolKind::Constant
+        )
+    }
+}
+
+/// `variable` blocks are module inputs; `locals` entries are also Variables
+fn is_input_variable(symbol: &Symbol) -> bool {
+    symbol
+        .signature
+        .as_deref()
+        .is_some_and(|sig| sig.starts_with("variable "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, kind: SymbolKind, signature: &str) -> Symbol {
+        let mut symbol = Symbol::new(
+            crate::SymbolId::new(1).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            crate::Range::new(0, 0, 3, 1),
+        );
+        symbol.signature = Some(signature.into());
+        symbol
+    }
+
+    #[test]
+    fn test_module_path_is_directory() {
+        let behavior = HclBehavior::new();
+        let root = Path::new("/infra");
+
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/infra/modules/vpc/main.tf"), root),
+            Some("modules/vpc".to_string())
+        );
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/infra/main.tf"), root),
+            Some(".".to_string())
+        );
+    }
+
+    #[test]
+    fn test_only_variables_and_outputs_are_public() {
+        let behavior = HclBehavior::new();
+
+        let mut input = symbol("region", SymbolKind::Variable, "variable \"region\"");
+        let mut local = symbol("tags", SymbolKind::Variable, "tags = { Team = \"infra\" }");
+        let mut output = symbol("vpc_id", SymbolKind::Constant, "output \"vpc_id\"");
+        let mut resource = symbol("main", SymbolKind::Struct, "resource \"aws_vpc\" \"main\"");
+        for sym in [&mut input, &mut local, &mut output, &mut resource] {
+            behavior.configure_symbol(sym, Some("modules/vpc"));
+        }
+
+        assert_eq!(input.visibility, Visibility::Public);
+        assert_eq!(output.visibility, Visibility::Public);
+        assert_eq!(local.visibility, Visibility::Private);
+        assert_eq!(resource.visibility, Visibility::Private);
+        assert_eq!(resource.module_path.as_deref(), Some("modules/vpc"));
+    }
+}
@@ -0,0 +1,79 @@
+//! HCL language definition for the registry
+//!
+//! Provides the language metadata and glue code used by the language registry
+//! to instantiate parsers and behaviors for Terraform `.tf` and `.hcl` files.
+
+use std::sync::Arc;
+
+use super::{HclBehavior, HclParser};
+use crate::parsing::{LanguageBehavior, LanguageDefinition, LanguageId, LanguageParser};
+use crate::{IndexError, IndexResult, Settings};
+
+/// Language definition for HCL (Terraform)
+pub struct HclLanguage;
+
+impl HclLanguage {
+    /// Stable identifier used throughout the registry
+    pub const ID: LanguageId = LanguageId::new("hcl");
+}
+
+impl LanguageDefinition for HclLanguage {
+    fn id(&self) -> LanguageId {
+        Self::ID
+    }
+
+    fn name(&self) -> &'static str {
+        "HCL"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["tf", "hcl"]
+    }
+
+    fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
+        let parser = HclParser::new().map_err(IndexError::General)?;
+        Ok(Box::new(parser))
+    }
+
+    fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
+        Box::new(HclBehavior::new())
+    }
+
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings
+            .languages
+            .get(self.id().as_str())
+            .map(|config| config.enabled)
+            .unwrap_or(self.default_enabled())
+    }
+}
+
+/// Register HCL language with the global registry
+pub(crate) fn register(registry: &mut crate::parsing::LanguageRegistry) {
+    registry.register(Arc::new(HclLanguage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_metadata() {
+        let lang = HclLanguage;
+
+        assert_eq!(lang.id(), LanguageId::new("hcl"));
+        assert_eq!(lang.name(), "HCL");
+        assert_eq!(lang.extensions(), &["tf", "hcl"]);
+    }
+
+    #[test]
+    fn test_parser_creation() {
+        let lang = HclLanguage;
+        let settings = Settings::default();
+        assert!(lang.create_parser(&settings).is_ok());
+    }
+}
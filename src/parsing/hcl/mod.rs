@@ -0,0 +1,14 @@
+//! HCL (Terraform) parser implementation
+
+pub mod audit;
+pub mod behavior;
+pub mod definition;
+pub mod parser;
+
+pub use audit::HclParserAudit;
+pub use behavior::HclBehavior;
+pub use definition::HclLanguage;
+pub use parser::HclParser;
+
+// Re-export for registry registration
+pub(crate) use definition::register;
@@ -0,0 +1,529 @@
+//! HCL (Terraform) parser implementation
+//!
+//! Extracts the addressable blocks of a Terraform configuration using
+//! tree-sitter-hcl:
+//!
+//! | Block | Symbol kind | Name |
+//! |-------|-------------|------|
+//! | `resource "aws_instance" "web"` | Struct | `web` |
+//! | `data "aws_ami" "ubuntu"` | Struct | `ubuntu` |
+//! | `module "vpc"` | Module | `vpc` |
+//! | `variable "region"` | Variable | `region` |
+//! | `output "vpc_id"` | Constant | `vpc_id` |
+//! | `locals { tags = ... }` | Variable | `tags` |
+//!
+//! Symbols are named by their last label, which is what a reference ends with
+//! once the address prefix (`var.`, `local.`, `module.`, `data.<type>.`,
+//! `<type>.`) is stripped. The full address stays visible in the signature.
+//!
+//! References inside a block become `Uses` edges from the block, and a
+//! module's `source` becomes an import so module dependency graphs follow the
+//! same path as application code.
+
+use crate::parsing::Import;
+use crate::parsing::parser::{check_recursion_depth, collapse_whitespace};
+use crate::parsing::{HandledNode, Language, LanguageParser, NodeTracker, NodeTrackingState};
+use crate::symbol::ScopeContext;
+use crate::types::SymbolCounter;
+use crate::{FileId, Range, Symbol, SymbolKind};
+use std::any::Any;
+use tree_sitter::{Node, Parser};
+
+/// Reference roots that never name a declared block
+const BUILTIN_ROOTS: &[&str] = &["each", "count", "self", "path", "terraform"];
+
+/// Parser for Terraform and other HCL configuration files
+pub struct HclParser {
+    parser: Parser,
+    node_tracker: NodeTrackingState,
+}
+
+impl std::fmt::Debug for HclParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HclParser")
+            .field("language", &"HCL")
+            .finish()
+    }
+}
+
+/// A top-level block split into its type and labels
+struct BlockHeader<'t, 'a> {
+    block_type: &'a str,
+    labels: Vec<&'a str>,
+    body: Option<Node<'t>>,
+}
+
+impl HclParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_hcl::LANGUAGE.into())
+            .map_err(|e| format!("Failed to initialize HCL parser: {e}"))?;
+
+        Ok(Self {
+            parser,
+            node_tracker: NodeTrackingState::new(),
+        })
+    }
+
+    /// Convert a tree-sitter node into a Range
+    fn node_to_range(&self, node: Node) -> Range {
+        let start = node.start_position();
+        let end = node.end_position();
+        Range {
+            start_line: start.row as u32,
+            start_column: start.column as u16,
+            end_line: end.row as u32,
+            end_column: end.column as u16,
+        }
+    }
+
+    /// Helper to register handled node kinds for audit tracking
+    fn register_node(&mut self, node: &Node) {
+        self.node_tracker
+            .register_handled_node(node.kind(), node.kind_id());
+    }
+
+    /// Extract raw source text for a node
+    fn text_for_node<'a>(&self, code: &'a str, node: Node) -> &'a str {
+        &code[node.byte_range()]
+    }
+
+    /// Split a `block` node into its type identifier, labels and body
+    fn block_header<'t, 'a>(&self, block: Node<'t>, code: &'a str) -> Option<BlockHeader<'t, 'a>> {
+        let mut cursor = block.walk();
+        let mut block_type = None;
+        let mut labels = Vec::new();
+        let mut body = None;
+
+        for child in block.named_children(&mut cursor) {
+            match child.kind() {
+                "identifier" if block_type.is_none() => {
+                    block_type = Some(self.text_for_node(code, child));
+                }
+                "identifier" | "string_lit" => {
+                    let label = self.text_for_node(code, child).trim_matches('"');
+                    labels.push(label);
+                }
+                "body" => body = Some(child),
+                _ => {}
+            }
+        }
+
+        Some(BlockHeader {
+            block_type: block_type?,
+            labels,
+            body,
+        })
+    }
+
+    /// The name a block's symbol takes and its kind
+    fn block_symbol<'a>(&self, header: &BlockHeader<'_, 'a>) -> Option<(&'a str, SymbolKind)> {
+        let (label, kind) = match header.block_type {
+            "resource" | "data" => (header.labels.get(1)?, SymbolKind::Struct),
+            "module" => (header.labels.first()?, SymbolKind::Module),
+            "variable" => (header.labels.first()?, SymbolKind::Variable),
+            "output" => (header.labels.first()?, SymbolKind::Constant),
+            _ => return None,
+        };
+        (!label.is_empty()).then_some((*label, kind))
+    }
+
+    /// Name of an `attribute` node (`name = value`)
+    fn attribute_name<'a>(&self, attribute: Node, code: &'a str) -> Option<&'a str> {
+        let mut cursor = attribute.walk();
+        let name = attribute
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == "identifier")?;
+        Some(self.text_for_node(code, name))
+    }
+
+    /// Extract `#`, `//` and `/* */` comments directly above a block
+    fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
+        let mut comments = Vec::new();
+        let mut current = node.prev_sibling();
+        let mut expected_row = node.start_position().row;
+
+        while let Some(sibling) = current {
+            if sibling.kind() != "comment" {
+                break;
+            }
+            if sibling.end_position().row + 1 < expected_row {
+                break;
+            }
+
+            let raw = self.text_for_node(code, sibling).trim();
+            if let Some(rest) = raw.strip_prefix('#').or_else(|| raw.strip_prefix("//")) {
+                comments.push(rest.trim_start_matches(['#', '/']).trim().to_string());
+            } else if raw.starts_with("/*") {
+                let cleaned = raw
+                    .trim_start_matches("/*")
+                    .trim_end_matches("*/")
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches('*').trim())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                comments.push(cleaned);
+            } else {
+                break;
+            }
+
+            expected_row = sibling.start_position().row;
+            current = sibling.prev_sibling();
+        }
+
+        if comments.is_empty() {
+            None
+        } else {
+            comments.reverse();
+            Some(comments.join("\n"))
+        }
+    }
+
+    /// Signature: the block header before `{`, or the whole attribute line
+    fn signature_for(&self, node: Node, code: &str) -> String {
+        let text = self.text_for_node(code, node);
+        let header = if node.kind() == "attribute" {
+            text.lines().next().unwrap_or(text)
+        } else {
+            text.split('{').next().unwrap_or(text)
+        };
+        crate::parsing::truncate_for_display(&collapse_whitespace(header), 200)
+    }
+
+    fn make_symbol(
+        &self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+        name: &str,
+        kind: SymbolKind,
+    ) -> Symbol {
+        let mut symbol = Symbol::new(
+            counter.next_id(),
+            name,
+            kind,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.signature = Some(self.signature_for(node, code).into());
+        if let Some(doc) = self.doc_comment_for(&node, code) {
+            symbol.doc_comment = Some(doc.into());
+        }
+        symbol.scope_context = Some(ScopeContext::Module);
+        symbol
+    }
+
+    /// Collect symbols from the top-level blocks of a configuration body
+    fn extract_symbols_from_body(
+        &mut self,
+        body: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+    ) {
+        let mut cursor = body.walk();
+        for node in body.named_children(&mut cursor) {
+            match node.kind() {
+                "block" => {
+                    self.register_node(&node);
+                    let Some(header) = self.block_header(node, code) else {
+                        continue;
+                    };
+
+                    if header.block_type == "locals" {
+                        if let Some(locals) = header.body {
+                            self.extract_locals(locals, code, file_id, symbols, counter);
+                        }
+                    } else if let Some((name, kind)) = self.block_symbol(&header) {
+                        symbols.push(self.make_symbol(node, code, file_id, counter, name, kind));
+                    }
+                }
+                "comment" | "attribute" => self.register_node(&node),
+                _ => {}
+            }
+        }
+    }
+
+    /// Each attribute of a `locals` block is a separate local value
+    fn extract_locals(
+        &mut self,
+        body: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+    ) {
+        let mut cursor = body.walk();
+        for attribute in body.named_children(&mut cursor) {
+            if attribute.kind() != "attribute" {
+                continue;
+            }
+            self.register_node(&attribute);
+            if let Some(name) = self.attribute_name(attribute, code) {
+                symbols.push(self.make_symbol(
+                    attribute,
+                    code,
+                    file_id,
+                    counter,
+                    name,
+                    SymbolKind::Variable,
+                ));
+            }
+        }
+    }
+
+    /// Name of the symbol a reference points at
+    ///
+    /// `var.region` -> `region`, `module.vpc.id` -> `vpc`,
+    /// `data.aws_ami.ubuntu.id` -> `ubuntu`, `aws_instance.web.id` -> `web`.
+    fn reference_target<'a>(&self, reference: &'a str) -> Option<&'a str> {
+        let mut segments = reference.split('.');
+        let root = segments.next()?;
+        let target = match root {
+            "data" => segments.nth(1),
+            _ if BUILTIN_ROOTS.contains(&root) => None,
+            _ => segments.next(),
+        }?;
+        (!target.is_empty()).then_some(target)
+    }
+
+    /// Collect references in `node` as uses by `owner`
+    fn collect_references<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        owner: &'a str,
+        uses: &mut Vec<(&'a str, &'a str, Range)>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if node.kind() == "variable_expr" {
+            let reference = traversal_text(code, node.start_byte());
+            if let Some(target) = self.reference_target(reference) {
+                if target != owner {
+                    uses.push((owner, target, self.node_to_range(node)));
+                }
+            }
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_references(child, code, owner, uses, depth + 1);
+        }
+    }
+
+    /// Module blocks and their `source` argument
+    fn module_sources<'a>(&self, root: Node, code: &'a str) -> Vec<(&'a str, &'a str)> {
+        let Some(body) = config_body(root) else {
+            return Vec::new();
+        };
+
+        let mut sources = Vec::new();
+        let mut cursor = body.walk();
+        for node in body.named_children(&mut cursor) {
+            if node.kind() != "block" {
+                continue;
+            }
+            let Some(header) = self.block_header(node, code) else {
+                continue;
+            };
+            let (Some(name), Some(module_body)) = (header.labels.first(), header.body) else {
+                continue;
+            };
+            if header.block_type != "module" {
+                continue;
+            }
+
+            let mut inner = module_body.walk();
+            for attribute in module_body.named_children(&mut inner) {
+                if attribute.kind() != "attribute"
+                    || self.attribute_name(attribute, code) != Some("source")
+                {
+                    continue;
+                }
+                let value = self
+                    .text_for_node(code, attribute)
+                    .split_once('=')
+                    .map(|(_, value)| value.trim().trim_matches('"'))
+                    .unwrap_or_default();
+                if !value.is_empty() {
+                    sources.push((*name, value));
+                }
+            }
+        }
+        sources
+    }
+
+    /// Access handled nodes for audit tooling
+    pub fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+}
+
+/// The `body` of a `config_file` root
+fn config_body(root: Node) -> Option<Node> {
+    if root.kind() == "body" {
+        return Some(root);
+    }
+    let mut cursor = root.walk();
+    root.named_children(&mut cursor)
+        .find(|child| child.kind() == "body")
+}
+
+/// The dotted traversal starting at `start` (`aws_instance.web[0].id` -> `aws_instance.web`)
+fn traversal_text(code: &str, start: usize) -> &str {
+    let rest = &code[start..];
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+        .unwrap_or(rest.len());
+    rest[..end].trim_end_matches('.')
+}
+
+impl LanguageParser for HclParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut symbols = Vec::new();
+        if let Some(body) = config_body(tree.root_node()) {
+            self.extract_symbols_from_body(body, code, file_id, &mut symbols, symbol_counter);
+        }
+        symbols
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
+        self.doc_comment_for(node, code)
+    }
+
+    fn find_calls<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        // Function calls in HCL are provider built-ins, never declared blocks
+        Vec::new()
+    }
+
+    fn find_implementations<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+        let Some(body) = config_body(tree.root_node()) else {
+            return Vec::new();
+        };
+
+        let mut uses = Vec::new();
+        let mut cursor = body.walk();
+        for node in body.named_children(&mut cursor) {
+            if node.kind() != "block" {
+                continue;
+            }
+            let Some(header) = self.block_header(node, code) else {
+                continue;
+            };
+            let Some(block_body) = header.body else {
+                continue;
+            };
+
+            if header.block_type == "locals" {
+                let mut inner = block_body.walk();
+                for attribute in block_body.named_children(&mut inner) {
+                    if let Some(name) = self.attribute_name(attribute, code) {
+                        self.collect_references(attribute, code, name, &mut uses, 0);
+                    }
+                }
+            } else if let Some((name, _)) = self.block_symbol(&header) {
+                // Includes depends_on, which lists references the same way
+                self.collect_references(block_body, code, name, &mut uses, 0);
+            }
+        }
+        uses
+    }
+
+    fn find_defines<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<Import> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        self.module_sources(tree.root_node(), code)
+            .into_iter()
+            .map(|(name, source)| Import {
+                file_id,
+                path: source.to_string(),
+                alias: Some(name.to_string()),
+                // A module call exposes the child module's outputs as a whole
+                is_glob: true,
+                is_type_only: false,
+            })
+            .collect()
+    }
+
+    fn language(&self) -> Language {
+        Language::Hcl
+    }
+}
+
+impl NodeTracker for HclParser {
+    fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+
+    fn register_handled_node(&mut self, node_kind: &str, node_id: u16) {
+        self.node_tracker.register_handled_node(node_kind, node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_target() {
+        let parser = HclParser::new().unwrap();
+
+        assert_eq!(parser.reference_target("var.region"), Some("region"));
+        assert_eq!(parser.reference_target("local.tags"), Some("tags"));
+        assert_eq!(parser.reference_target("module.vpc.vpc_id"), Some("vpc"));
+        assert_eq!(
+            parser.reference_target("data.aws_ami.ubuntu.id"),
+            Some("ubuntu")
+        );
+        assert_eq!(parser.reference_target("aws_instance.web.id"), Some("web"));
+        assert_eq!(parser.reference_target("each.value"), None);
+        assert_eq!(parser.reference_target("count.index"), None);
+        assert_eq!(parser.reference_target("Name"), None);
+    }
+
+    #[test]
+    fn test_traversal_text_stops_at_index() {
+        let code = "aws_instance.web[0].id";
+        assert_eq!(traversal_text(code, 0), "aws_instance.web");
+        assert_eq!(traversal_text("var.region)", 0), "var.region");
+    }
+}
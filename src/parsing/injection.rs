@@ -0,0 +1,363 @@
+//! Language injections
+//!
+//! Finds code written in another language inside a host file's string
+//! literals and parses it with that language's parser. SQL is the only
+//! injected language so far: queries in Rust, Python, Go or TypeScript
+//! strings are parsed with the SQL parser, tables they create become symbols
+//! nested under the enclosing host symbol, and tables they query become
+//! `Uses` references of that symbol.
+//!
+//! String literals are found with the host grammar, so any language with a
+//! tree-sitter grammar can host injections. Whether a literal holds SQL is
+//! decided from its leading keywords; interpolations (`${id}`, `{id}`) are
+//! left in place and recovered by the SQL grammar's error handling.
+
+use crate::parsing::parser::check_recursion_depth;
+use crate::parsing::{LanguageId, LanguageParser, SqlParser};
+use crate::symbol::ScopeContext;
+use crate::types::SymbolCounter;
+use crate::{FileId, Range, Symbol};
+use std::ops::Range as ByteRange;
+use tree_sitter::{Language, Node, Parser};
+
+/// Relationship context marking references that come from injected code
+pub const INJECTION_CONTEXT_PREFIX: &str = "injected:";
+
+/// Hosts whose files the host grammar does not fully cover (SFC blocks) or
+/// that already are the injected language
+const SKIPPED_HOSTS: &[&str] = &["sql", "vue"];
+
+/// Object keywords that may follow `CREATE`, `ALTER` or `DROP`
+const DDL_OBJECTS: &[&str] = &[
+    "TABLE",
+    "VIEW",
+    "INDEX",
+    "FUNCTION",
+    "PROCEDURE",
+    "TYPE",
+    "SCHEMA",
+    "TRIGGER",
+    "SEQUENCE",
+    "MATERIALIZED",
+    "UNIQUE",
+    "TEMPORARY",
+    "TEMP",
+    "OR",
+];
+
+/// Code in another language found inside a host file
+#[derive(Debug, Clone)]
+pub struct InjectedCode {
+    /// Language of the embedded code
+    pub language: LanguageId,
+    /// Position of the string literal body in the host file
+    pub range: Range,
+    /// Symbols defined by the embedded code, in host file coordinates
+    pub symbols: Vec<Symbol>,
+    /// Objects the embedded code refers to, with their host file ranges
+    pub references: Vec<(String, Range)>,
+}
+
+/// Relationship context recorded on references from injected `language` code
+pub fn injection_context(language: LanguageId) -> String {
+    format!("{INJECTION_CONTEXT_PREFIX}{}", language.as_str())
+}
+
+/// Injected language named by a relationship context, if any
+pub fn injected_language(context: &str) -> Option<&str> {
+    context.strip_prefix(INJECTION_CONTEXT_PREFIX)
+}
+
+/// Whether files of `host` are scanned for injections
+pub fn supports_host(host: LanguageId) -> bool {
+    !SKIPPED_HOSTS.contains(&host.as_str())
+}
+
+/// Finds and parses injected code in host files
+pub struct InjectionParser {
+    host: Parser,
+    sql: SqlParser,
+}
+
+impl std::fmt::Debug for InjectionParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InjectionParser")
+            .field("languages", &["sql"])
+            .finish()
+    }
+}
+
+impl InjectionParser {
+    /// Create a new injection parser
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            host: Parser::new(),
+            sql: SqlParser::new()?,
+        })
+    }
+
+    /// Parse injected code in `code`, a file written in the `grammar` language.
+    ///
+    /// Top-level injected symbols get `Local` scope naming the innermost
+    /// symbol of `host_symbols` that contains them.
+    pub fn parse(
+        &mut self,
+        code: &str,
+        grammar: &Language,
+        host_symbols: &[Symbol],
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<InjectedCode> {
+        if self.host.set_language(grammar).is_err() {
+            return Vec::new();
+        }
+        let Some(tree) = self.host.parse(code, None) else {
+            return Vec::new();
+        };
+
+        let mut literals = Vec::new();
+        collect_string_literals(tree.root_node(), code, &mut literals, 0);
+
+        let mut injections = Vec::new();
+        for (bytes, start) in literals {
+            let body = &code[bytes];
+            if !looks_like_sql(body) {
+                continue;
+            }
+
+            let range = shift_range(line_span(body), (start.row as u32, start.column as u16));
+            let mut symbols = self.sql.parse(body, file_id, symbol_counter);
+            for symbol in &mut symbols {
+                symbol.range = shift_range(symbol.range, (range.start_line, range.start_column));
+                // Columns keep their table as scope; top-level objects move under the host
+                if !matches!(symbol.scope_context, None | Some(ScopeContext::Module)) {
+                    continue;
+                }
+                if let Some(host) = host_symbol(host_symbols, &symbol.range) {
+                    symbol.scope_context = Some(ScopeContext::Local {
+                        hoisted: false,
+                        parent_name: Some(host.name.clone()),
+                        parent_kind: Some(host.kind),
+                    });
+                }
+            }
+            let references = self
+                .sql
+                .find_table_references(body)
+                .into_iter()
+                .map(|(name, reference)| {
+                    (
+                        name.to_string(),
+                        shift_range(reference, (range.start_line, range.start_column)),
+                    )
+                })
+                .collect();
+
+            injections.push(InjectedCode {
+                language: LanguageId::new("sql"),
+                range,
+                symbols,
+                references,
+            });
+        }
+        injections
+    }
+}
+
+/// Innermost symbol whose range contains `range`
+pub fn host_symbol<'a>(symbols: &'a [Symbol], range: &Range) -> Option<&'a Symbol> {
+    symbols
+        .iter()
+        .filter(|symbol| contains(&symbol.range, range))
+        .min_by_key(|symbol| {
+            (
+                symbol.range.end_line - symbol.range.start_line,
+                symbol.range.end_column.abs_diff(symbol.range.start_column),
+            )
+        })
+}
+
+fn contains(outer: &Range, inner: &Range) -> bool {
+    (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column)
+        && (inner.end_line, inner.end_column) <= (outer.end_line, outer.end_column)
+}
+
+/// Collect the bodies of string literals, without quotes or prefixes
+fn collect_string_literals(
+    node: Node,
+    code: &str,
+    literals: &mut Vec<(ByteRange<usize>, tree_sitter::Point)>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    if is_string_literal(node.kind()) {
+        if let Some(body) = literal_body(&code[node.byte_range()]) {
+            let start = node.start_position();
+            let point = tree_sitter::Point {
+                row: start.row,
+                column: start.column + body.start,
+            };
+            let offset = node.start_byte();
+            literals.push((body.start + offset..body.end + offset, point));
+        }
+        // Nested string nodes are part of this literal's body
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_string_literals(child, code, literals, depth + 1);
+    }
+}
+
+/// String node kinds across grammars (`string`, `string_literal`,
+/// `template_string`, `raw_string_literal`, `encapsed_string`, ...).
+/// Concatenations are descended into so each part is checked on its own.
+fn is_string_literal(kind: &str) -> bool {
+    (kind.ends_with("string")
+        || kind.ends_with("string_literal")
+        || kind == "interpolated_string_expression")
+        && kind != "concatenated_string"
+}
+
+/// Byte range of a literal's body within the literal: after the prefix and
+/// opening quotes (`r#"`, `f"""`, `` ` ``), before the closing ones
+fn literal_body(literal: &str) -> Option<ByteRange<usize>> {
+    let open = literal.find(['"', '\'', '`'])?;
+    let quote = literal.as_bytes()[open];
+    let quotes = if literal.as_bytes()[open..].starts_with(&[quote; 3]) {
+        3
+    } else {
+        1
+    };
+    let start = open + quotes;
+
+    let closing = literal.trim_end_matches('#');
+    let end = closing.len().checked_sub(quotes)?;
+    if !closing.as_bytes()[end..].iter().all(|&b| b == quote) {
+        return None;
+    }
+    (start <= end).then_some(start..end)
+}
+
+/// Whether a string literal holds a SQL statement
+///
+/// The first word must be a statement keyword and a keyword that statement
+/// needs must follow, so UI text like "Select a file" is not mistaken for SQL.
+pub fn looks_like_sql(text: &str) -> bool {
+    if !text
+        .trim_start()
+        .starts_with(|c: char| c.is_ascii_alphabetic())
+    {
+        return false;
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .take(64)
+        .map(|word| word.to_ascii_uppercase())
+        .collect();
+    let has = |keyword: &str| words.iter().skip(1).any(|word| word == keyword);
+
+    match words.first().map(String::as_str) {
+        Some("SELECT") => has("FROM"),
+        Some("DELETE") => has("FROM") && words.len() > 2,
+        Some("INSERT") => has("INTO"),
+        Some("UPDATE") => has("SET"),
+        Some("WITH") => has("AS") && has("SELECT"),
+        Some("CREATE" | "ALTER" | "DROP") => words
+            .get(1)
+            .is_some_and(|word| DDL_OBJECTS.contains(&word.as_str())),
+        _ => false,
+    }
+}
+
+/// Range spanning `text`, relative to its first byte
+fn line_span(text: &str) -> Range {
+    let lines = text.matches('\n').count() as u32;
+    let last_line = text.rsplit('\n').next().unwrap_or("");
+    Range::new(0, 0, lines, last_line.len() as u16)
+}
+
+/// Move a range reported relative to a string body into file coordinates
+fn shift_range(range: Range, (line, column): (u32, u16)) -> Range {
+    let shift_column = |row: u32, col: u16| if row == 0 { col + column } else { col };
+    Range::new(
+        range.start_line + line,
+        shift_column(range.start_line, range.start_column),
+        range.end_line + line,
+        shift_column(range.end_line, range.end_column),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_sql() {
+        assert!(looks_like_sql("SELECT id FROM users"));
+        assert!(looks_like_sql("\n    insert into orders (id) values ($1)"));
+        assert!(looks_like_sql("UPDATE users SET name = ?"));
+        assert!(looks_like_sql("CREATE TABLE IF NOT EXISTS t (id int)"));
+        assert!(looks_like_sql(
+            "WITH recent AS (SELECT * FROM orders) SELECT * FROM recent"
+        ));
+
+        assert!(!looks_like_sql("Select a file to open"));
+        assert!(!looks_like_sql("Update available"));
+        assert!(!looks_like_sql("create a new project"));
+        assert!(!looks_like_sql("/* SELECT * FROM users */"));
+    }
+
+    #[test]
+    fn test_literal_body() {
+        fn body(literal: &str) -> Option<&str> {
+            literal_body(literal).map(|range| &literal[range])
+        }
+
+        assert_eq!(body(r#""SELECT 1""#), Some("SELECT 1"));
+        assert_eq!(body(r##"r#"SELECT "x""#"##), Some(r#"SELECT "x""#));
+        assert_eq!(body(r#"f"""SELECT {a}""""#), Some("SELECT {a}"));
+        assert_eq!(body("`SELECT ${id}`"), Some("SELECT ${id}"));
+        assert_eq!(body(r#""""#), Some(""));
+    }
+
+    #[test]
+    fn test_parse_sql_in_python_strings() {
+        let code = r#"
+def active_orders(conn):
+    return conn.execute("""
+        SELECT o.id FROM orders o
+        JOIN customers c ON c.id = o.customer_id
+    """)
+"#;
+        let grammar: Language = tree_sitter_python::LANGUAGE.into();
+        let mut counter = SymbolCounter::new();
+        let mut host_counter = SymbolCounter::new();
+        let host = crate::parsing::PythonParser::new().unwrap().parse(
+            code,
+            FileId::new(1).unwrap(),
+            &mut host_counter,
+        );
+
+        let mut parser = InjectionParser::new().unwrap();
+        let injections = parser.parse(code, &grammar, &host, FileId::new(1).unwrap(), &mut counter);
+
+        assert_eq!(injections.len(), 1);
+        let tables: Vec<_> = injections[0]
+            .references
+            .iter()
+            .map(|(name, range)| (name.as_str(), range.start_line))
+            .collect();
+        assert_eq!(tables, vec![("orders", 3), ("customers", 4)]);
+        assert_eq!(
+            host_symbol(&host, &injections[0].range).map(|s| &*s.name),
+            Some("active_orders")
+        );
+    }
+}
@@ -432,6 +432,9 @@ impl ResolutionScope for JavaScriptResolutionContext {
             }
             References => true,
             ReferencedBy => true,
+            Mirrors => {
+                matches!(from_kind, Function | Method) && matches!(to_kind, Function | Method)
+            }
             // JavaScript doesn't support Implements/ImplementedBy (no interfaces)
             Implements | ImplementedBy => false,
         }
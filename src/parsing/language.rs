@@ -21,6 +21,16 @@ pub enum Language {
     Java,
     Kotlin,
     Swift,
+    Lua,
+    Sql,
+    Protobuf,
+    Hcl,
+    ObjectiveC,
+    Config,
+    Vue,
+    /// Grammar loaded at runtime from `[grammars]` in settings; the registry
+    /// id names the actual language
+    External,
 }
 
 impl Language {
@@ -44,6 +54,14 @@ impl Language {
             Language::Java => super::LanguageId::new("java"),
             Language::Kotlin => super::LanguageId::new("kotlin"),
             Language::Swift => super::LanguageId::new("swift"),
+            Language::Lua => super::LanguageId::new("lua"),
+            Language::Sql => super::LanguageId::new("sql"),
+            Language::Protobuf => super::LanguageId::new("protobuf"),
+            Language::Hcl => super::LanguageId::new("hcl"),
+            Language::ObjectiveC => super::LanguageId::new("objc"),
+            Language::Config => super::LanguageId::new("config"),
+            Language::Vue => super::LanguageId::new("vue"),
+            Language::External => super::LanguageId::new("external"),
         }
     }
 
@@ -66,6 +84,13 @@ impl Language {
             "java" => Some(Language::Java),
             "kotlin" => Some(Language::Kotlin),
             "swift" => Some(Language::Swift),
+            "lua" => Some(Language::Lua),
+            "sql" => Some(Language::Sql),
+            "protobuf" => Some(Language::Protobuf),
+            "hcl" => Some(Language::Hcl),
+            "objc" => Some(Language::ObjectiveC),
+            "config" => Some(Language::Config),
+            "vue" => Some(Language::Vue),
             _ => None,
         }
     }
@@ -81,7 +106,8 @@ impl Language {
         let registry = super::get_registry();
         if let Ok(registry) = registry.lock() {
             if let Some(def) = registry.get_by_extension(&ext_lower) {
-                return Self::from_language_id(def.id());
+                // Only runtime grammars are registered without a variant
+                return Some(Self::from_language_id(def.id()).unwrap_or(Language::External));
             }
         }
 
@@ -103,6 +129,13 @@ impl Language {
             "java" => Some(Language::Java),
             "kt" | "kts" => Some(Language::Kotlin),
             "swift" => Some(Language::Swift),
+            "lua" => Some(Language::Lua),
+            "sql" => Some(Language::Sql),
+            "proto" => Some(Language::Protobuf),
+            "tf" | "hcl" => Some(Language::Hcl),
+            "m" => Some(Language::ObjectiveC),
+            "toml" | "json" | "yaml" | "yml" => Some(Language::Config),
+            "vue" => Some(Language::Vue),
             _ => None,
         }
     }
@@ -132,6 +165,15 @@ impl Language {
             Language::Java => &["java"],
             Language::Kotlin => &["kt", "kts"],
             Language::Swift => &["swift"],
+            Language::Lua => &["lua"],
+            Language::Sql => &["sql"],
+            Language::Protobuf => &["proto"],
+            Language::Hcl => &["tf", "hcl"],
+            Language::ObjectiveC => &["m"],
+            Language::Config => &["toml", "json", "yaml", "yml"],
+            Language::Vue => &["vue"],
+            // Declared per grammar in settings
+            Language::External => &[],
         }
     }
 
@@ -151,6 +193,14 @@ impl Language {
             Language::Java => "java",
             Language::Kotlin => "kotlin",
             Language::Swift => "swift",
+            Language::Lua => "lua",
+            Language::Sql => "sql",
+            Language::Protobuf => "protobuf",
+            Language::Hcl => "hcl",
+            Language::ObjectiveC => "objc",
+            Language::Config => "config",
+            Language::Vue => "vue",
+            Language::External => "external",
         }
     }
 
@@ -170,6 +220,14 @@ impl Language {
             Language::Java => "Java",
             Language::Kotlin => "Kotlin",
             Language::Swift => "Swift",
+            Language::Lua => "Lua",
+            Language::Sql => "SQL",
+            Language::Protobuf => "Protobuf",
+            Language::Hcl => "HCL",
+            Language::ObjectiveC => "Objective-C",
+            Language::Config => "Config",
+            Language::Vue => "Vue",
+            Language::External => "External",
         }
     }
 }
@@ -204,6 +262,13 @@ mod tests {
         assert_eq!(Language::from_extension("go.sum"), Some(Language::Go));
         assert_eq!(Language::from_extension("txt"), None);
         assert_eq!(Language::from_extension("gd"), Some(Language::Gdscript));
+        assert_eq!(Language::from_extension("lua"), Some(Language::Lua));
+        assert_eq!(Language::from_extension("sql"), Some(Language::Sql));
+        assert_eq!(Language::from_extension("proto"), Some(Language::Protobuf));
+        assert_eq!(Language::from_extension("tf"), Some(Language::Hcl));
+        assert_eq!(Language::from_extension("m"), Some(Language::ObjectiveC));
+        assert_eq!(Language::from_extension("toml"), Some(Language::Config));
+        assert_eq!(Language::from_extension("vue"), Some(Language::Vue));
     }
 
     #[test]
@@ -1256,6 +1256,7 @@ pub fn default_relationship_compatibility(
             // Very permissive - almost anything can reference anything
             true
         }
+        Mirrors => matches!(from_kind, Function | Method) && matches!(to_kind, Function | Method),
     }
 }
 
@@ -0,0 +1,225 @@
+//! Lua-specific language behavior implementation
+
+use crate::parsing::Import;
+use crate::parsing::LanguageBehavior;
+use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::types::compact_string;
+use crate::{FileId, Symbol, SymbolKind, Visibility};
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Language behavior for Lua
+#[derive(Clone)]
+pub struct LuaBehavior {
+    language: Language,
+    state: BehaviorState,
+}
+
+impl LuaBehavior {
+    /// Create a new behavior instance
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_lua::LANGUAGE.into(),
+            state: BehaviorState::new(),
+        }
+    }
+
+    /// Normalize a `require` argument to dotted module form
+    ///
+    /// Lua's `package.path` maps `a.b` to `a/b.lua` or `a/b/init.lua`, and some
+    /// codebases write `require "a/b"` directly, so slashes are accepted too.
+    fn normalize_module(path: &str) -> String {
+        let path = path.trim().trim_end_matches(".lua");
+        let path = path.replace(['/', '\\'], ".");
+        path.strip_suffix(".init").unwrap_or(&path).to_string()
+    }
+}
+
+impl StatefulBehavior for LuaBehavior {
+    fn state(&self) -> &BehaviorState {
+        &self.state
+    }
+}
+
+impl Default for LuaBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageBehavior for LuaBehavior {
+    fn language_id(&self) -> crate::parsing::registry::LanguageId {
+        crate::parsing::registry::LanguageId::new("lua")
+    }
+
+    fn configure_symbol(&self, symbol: &mut Symbol, module_path: Option<&str>) {
+        if let Some(path) = module_path {
+            let full_path = self.format_module_path(path, &symbol.name);
+            symbol.module_path = Some(full_path.into());
+        }
+
+        if let Some(signature) = &symbol.signature {
+            symbol.visibility = self.parse_visibility(signature);
+        }
+
+        // Name the chunk symbol after its module so `require` edges can find it
+        if symbol.kind == SymbolKind::Module && symbol.name.as_ref() == "<chunk>" {
+            if let Some(name) = module_path.and_then(|path| path.rsplit('.').next()) {
+                if !name.is_empty() {
+                    symbol.name = compact_string(name);
+                }
+            }
+            symbol.visibility = Visibility::Public;
+        }
+    }
+
+    fn format_module_path(&self, base_path: &str, _symbol_name: &str) -> String {
+        base_path.to_string()
+    }
+
+    fn parse_visibility(&self, signature: &str) -> Visibility {
+        // `local` bindings are private to the chunk; everything else is global
+        // or reachable through a table returned from the module.
+        if signature.trim_start().starts_with("local ") {
+            Visibility::Private
+        } else {
+            Visibility::Public
+        }
+    }
+
+    fn module_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        let relative = file_path.strip_prefix(project_root).unwrap_or(file_path);
+        let path = relative.to_string_lossy();
+        let path = path.trim_start_matches("./");
+
+        // Common source roots used by LuaRocks and game engines
+        let path = ["src/", "lua/", "lib/", "scripts/"]
+            .iter()
+            .find_map(|root| path.strip_prefix(root))
+            .unwrap_or(path);
+
+        let module = Self::normalize_module(path);
+        if module.is_empty() {
+            None
+        } else {
+            Some(module)
+        }
+    }
+
+    fn get_language(&self) -> Language {
+        self.language.clone()
+    }
+
+    // Override import tracking methods to use state
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.register_file_with_state(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.add_import_with_state(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.get_imports_from_state(file_id)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.state.get_module_path(file_id)
+    }
+
+    fn get_file_path(&self, file_id: FileId) -> Option<PathBuf> {
+        self.state.get_file_path(file_id)
+    }
+
+    fn import_matches_symbol(
+        &self,
+        import_path: &str,
+        symbol_module_path: &str,
+        _importing_module: Option<&str>,
+    ) -> bool {
+        if import_path == symbol_module_path {
+            return true;
+        }
+
+        let import = Self::normalize_module(import_path);
+        let symbol = Self::normalize_module(symbol_module_path);
+
+        // `require "foo"` may match a module rooted deeper (e.g. `vendor.foo`)
+        import == symbol || symbol.ends_with(&format!(".{import}"))
+    }
+
+    fn is_resolvable_symbol(&self, symbol: &Symbol) -> bool {
+        use crate::symbol::ScopeContext;
+
+        let resolvable_kind = matches!(
+            symbol.kind,
+            SymbolKind::Function
+                | SymbolKind::Method
+                | SymbolKind::Module
+                | SymbolKind::Variable
+                | SymbolKind::Constant
+                | SymbolKind::Field
+        );
+
+        if !resolvable_kind {
+            return false;
+        }
+
+        !matches!(
+            symbol.scope_context,
+            Some(ScopeContext::Local { .. }) | Some(ScopeContext::Parameter)
+        )
+    }
+
+    fn is_symbol_visible_from_file(&self, symbol: &Symbol, from_file: FileId) -> bool {
+        symbol.file_id == from_file || matches!(symbol.visibility, Visibility::Public)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_path_from_file() {
+        let behavior = LuaBehavior::new();
+        let root = Path::new("/project");
+
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/project/src/net/http.lua"), root),
+            Some("net.http".to_string())
+        );
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/project/game/ui/init.lua"), root),
+            Some("game.ui".to_string())
+        );
+    }
+
+    #[test]
+    fn test_import_matching() {
+        let behavior = LuaBehavior::new();
+
+        assert!(behavior.import_matches_symbol("net.http", "net.http", None));
+        assert!(behavior.import_matches_symbol("net/http", "net.http", None));
+        assert!(behavior.import_matches_symbol("dkjson", "vendor.dkjson", None));
+        assert!(!behavior.import_matches_symbol("net.http", "net.https", None));
+    }
+
+    #[test]
+    fn test_visibility() {
+        let behavior = LuaBehavior::new();
+
+        assert_eq!(
+            behavior.parse_visibility("local function helper()"),
+            Visibility::Private
+        );
+        assert_eq!(
+            behavior.parse_visibility("function M.public_api(x)"),
+            Visibility::Public
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Lua language definition for the registry
+//!
+//! Provides the language metadata and glue code used by the language registry
+//! to instantiate parsers and behaviors for Lua.
+
+use std::sync::Arc;
+
+use super::{LuaBehavior, LuaParser};
+use crate::parsing::{LanguageBehavior, LanguageDefinition, LanguageId, LanguageParser};
+use crate::{IndexError, IndexResult, Settings};
+
+/// Language definition for Lua
+pub struct LuaLanguage;
+
+impl LuaLanguage {
+    /// Stable identifier used throughout the registry
+    pub const ID: LanguageId = LanguageId::new("lua");
+}
+
+impl LanguageDefinition for LuaLanguage {
+    fn id(&self) -> LanguageId {
+        Self::ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Lua"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["lua"]
+    }
+
+    fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
+        let parser = LuaParser::new().map_err(IndexError::General)?;
+        Ok(Box::new(parser))
+    }
+
+    fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
+        Box::new(LuaBehavior::new())
+    }
+
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings
+            .languages
+            .get(self.id().as_str())
+            .map(|config| config.enabled)
+            .unwrap_or(self.default_enabled())
+    }
+}
+
+/// Register Lua language with the global registry
+pub(crate) fn register(registry: &mut crate::parsing::LanguageRegistry) {
+    registry.register(Arc::new(LuaLanguage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_metadata() {
+        let lang = LuaLanguage;
+
+        assert_eq!(lang.id(), LanguageId::new("lua"));
+        assert_eq!(lang.name(), "Lua");
+        assert_eq!(lang.extensions(), &["lua"]);
+    }
+
+    #[test]
+    fn test_parser_creation() {
+        let lang = LuaLanguage;
+        let settings = Settings::default();
+        assert!(lang.create_parser(&settings).is_ok());
+    }
+}
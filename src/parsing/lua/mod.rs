@@ -0,0 +1,14 @@
+//! Lua language parser implementation
+
+pub mod audit;
+pub mod behavior;
+pub mod definition;
+pub mod parser;
+
+pub use audit::LuaParserAudit;
+pub use behavior::LuaBehavior;
+pub use definition::LuaLanguage;
+pub use parser::LuaParser;
+
+// Re-export for registry registration
+pub(crate) use definition::register;
@@ -0,0 +1,927 @@
+//! Lua language parser implementation
+//!
+//! Extracts functions, module tables, and `require` edges from Lua sources
+//! using tree-sitter. Lua has no classes; instead tables act as modules and
+//! objects, so `function M.foo()` / `function M:bar()` attach members to the
+//! table `M`, which is promoted to a module symbol.
+
+use crate::parsing::Import;
+use crate::parsing::parser::check_recursion_depth;
+use crate::parsing::{
+    HandledNode, Language, LanguageParser, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
+};
+use crate::symbol::ScopeContext;
+use crate::types::{SymbolCounter, compact_string};
+use crate::{FileId, Range, Symbol, SymbolKind};
+use std::any::Any;
+use std::collections::HashMap;
+use tree_sitter::{Node, Parser};
+
+const CHUNK_SCOPE: &str = "<chunk>";
+
+/// Parser for Lua source files
+pub struct LuaParser {
+    parser: Parser,
+    node_tracker: NodeTrackingState,
+}
+
+impl std::fmt::Debug for LuaParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LuaParser")
+            .field("language", &"Lua")
+            .finish()
+    }
+}
+
+/// How a function name attaches to its owner
+enum FunctionName<'a> {
+    /// `function foo()` or `local function foo()`
+    Plain(&'a str),
+    /// `function M.foo()` - a field on table `M`
+    Field { table: &'a str, name: &'a str },
+    /// `function M:foo()` - a method with implicit `self`
+    Method { table: &'a str, name: &'a str },
+}
+
+impl LuaParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_lua::LANGUAGE.into())
+            .map_err(|e| format!("Failed to initialize Lua parser: {e}"))?;
+
+        Ok(Self {
+            parser,
+            node_tracker: NodeTrackingState::new(),
+        })
+    }
+
+    /// Convert a tree-sitter node into a Range
+    fn node_to_range(&self, node: Node) -> Range {
+        let start = node.start_position();
+        let end = node.end_position();
+        Range {
+            start_line: start.row as u32,
+            start_column: start.column as u16,
+            end_line: end.row as u32,
+            end_column: end.column as u16,
+        }
+    }
+
+    /// Helper to register handled node kinds for audit tracking
+    fn register_node(&mut self, node: &Node) {
+        self.node_tracker
+            .register_handled_node(node.kind(), node.kind_id());
+    }
+
+    /// Extract raw source text for a node
+    fn text_for_node<'a>(&self, code: &'a str, node: Node) -> &'a str {
+        &code[node.byte_range()]
+    }
+
+    /// Whether a declaration starts with the `local` keyword
+    fn is_local(&self, node: Node) -> bool {
+        node.child(0).is_some_and(|child| child.kind() == "local")
+    }
+
+    /// Extract LuaDoc / EmmyLua documentation (`---` comments) above a node
+    fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
+        let mut comments = Vec::new();
+        let mut current = node.prev_sibling();
+        let mut expected_row = node.start_position().row;
+
+        while let Some(sibling) = current {
+            if sibling.kind() != "comment" {
+                break;
+            }
+
+            // Only accept comments directly adjacent to the declaration
+            if sibling.end_position().row + 1 < expected_row {
+                break;
+            }
+
+            let raw = self.text_for_node(code, sibling).trim();
+            if let Some(rest) = raw.strip_prefix("---") {
+                comments.push(rest.trim().to_string());
+                expected_row = sibling.start_position().row;
+                current = sibling.prev_sibling();
+            } else if raw.starts_with("--[[") {
+                let cleaned = raw
+                    .trim_start_matches("--[[")
+                    .trim_end_matches("]]")
+                    .trim()
+                    .to_string();
+                comments.push(cleaned);
+                break;
+            } else {
+                break;
+            }
+        }
+
+        if comments.is_empty() {
+            None
+        } else {
+            comments.reverse();
+            Some(comments.join("\n"))
+        }
+    }
+
+    /// Remove Lua string delimiters (`"..."`, `'...'`, `[[...]]`, `[==[...]==]`)
+    fn strip_string_quotes<'a>(&self, value: &'a str) -> &'a str {
+        let value = value.trim();
+        let bytes = value.as_bytes();
+        if bytes.len() >= 2 {
+            let first = bytes[0];
+            let last = bytes[bytes.len() - 1];
+            if (first == b'"' && last == b'"') || (first == b'\'' && last == b'\'') {
+                return &value[1..value.len() - 1];
+            }
+        }
+
+        if value.starts_with('[') && value.ends_with(']') {
+            let open = value[1..].bytes().take_while(|&b| b == b'=').count() + 2;
+            let close = open;
+            if value.len() >= open + close {
+                return &value[open..value.len() - close];
+            }
+        }
+
+        value
+    }
+
+    /// Classify the `name` field of a function declaration
+    fn function_name<'a>(&self, name_node: Node, code: &'a str) -> Option<FunctionName<'a>> {
+        match name_node.kind() {
+            "identifier" => {
+                let name = self.text_for_node(code, name_node).trim();
+                (!name.is_empty()).then_some(FunctionName::Plain(name))
+            }
+            "dot_index_expression" => {
+                let table = name_node.child_by_field_name("table")?;
+                let field = name_node.child_by_field_name("field")?;
+                Some(FunctionName::Field {
+                    table: self.text_for_node(code, table).trim(),
+                    name: self.text_for_node(code, field).trim(),
+                })
+            }
+            "method_index_expression" => {
+                let table = name_node.child_by_field_name("table")?;
+                let method = name_node.child_by_field_name("method")?;
+                Some(FunctionName::Method {
+                    table: self.text_for_node(code, table).trim(),
+                    name: self.text_for_node(code, method).trim(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Name of the function a call site belongs to (used for caller attribution)
+    fn callable_name<'a>(&self, node: Node, code: &'a str) -> Option<&'a str> {
+        let name_node = node.child_by_field_name("name")?;
+        match self.function_name(name_node, code)? {
+            FunctionName::Plain(name) => Some(name),
+            FunctionName::Field { name, .. } | FunctionName::Method { name, .. } => Some(name),
+        }
+    }
+
+    /// Extract the last identifier of a call target (`M.foo` -> `foo`, `obj:bar` -> `bar`)
+    fn call_target<'a>(&self, callee: Node, code: &'a str) -> Option<&'a str> {
+        let target = match callee.kind() {
+            "identifier" => self.text_for_node(code, callee),
+            "dot_index_expression" => {
+                self.text_for_node(code, callee.child_by_field_name("field")?)
+            }
+            "method_index_expression" => {
+                self.text_for_node(code, callee.child_by_field_name("method")?)
+            }
+            _ => return None,
+        };
+        let target = target.trim();
+        (!target.is_empty()).then_some(target)
+    }
+
+    /// Extract the module path from `require("a.b")`, `require "a.b"` or `require 'a.b'`
+    fn require_path<'a>(&self, call: Node, code: &'a str) -> Option<&'a str> {
+        let callee = call.child_by_field_name("name")?;
+        if self.text_for_node(code, callee).trim() != "require" {
+            return None;
+        }
+
+        let arguments = call.child_by_field_name("arguments")?;
+        let argument = if arguments.kind() == "string" {
+            arguments
+        } else {
+            arguments.named_child(0)?
+        };
+        if argument.kind() != "string" {
+            return None;
+        }
+
+        let path = self.strip_string_quotes(self.text_for_node(code, argument));
+        (!path.is_empty()).then_some(path)
+    }
+
+    /// Build a function signature: `function M:foo(a, b)`
+    fn function_signature(&self, node: Node, code: &str, display_name: &str) -> String {
+        let params = node
+            .child_by_field_name("parameters")
+            .map(|n| self.text_for_node(code, n).trim())
+            .unwrap_or("()");
+        let local = if self.is_local(node) { "local " } else { "" };
+        format!("{local}function {display_name}{params}")
+    }
+
+    /// Process AST recursively and collect symbols
+    #[allow(clippy::too_many_arguments)]
+    fn extract_symbols_from_node(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        tables: &mut HashMap<String, usize>,
+        counter: &mut SymbolCounter,
+        context: &mut ParserContext,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        match node.kind() {
+            "function_declaration" | "local_function_declaration" => {
+                self.handle_function_declaration(
+                    node, code, file_id, symbols, tables, counter, context, depth,
+                );
+                return;
+            }
+            "variable_declaration" | "assignment_statement"
+                if self.handle_assignment(
+                    node, code, file_id, symbols, tables, counter, context, depth,
+                ) =>
+            {
+                return;
+            }
+            "return_statement" => {
+                self.register_node(&node);
+                if context.is_module_level() {
+                    self.mark_returned_table(node, code, symbols, tables);
+                }
+            }
+            "function_call" | "function_definition" | "table_constructor" | "field" | "comment" => {
+                self.register_node(&node);
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_symbols_from_node(
+                child,
+                code,
+                file_id,
+                symbols,
+                tables,
+                counter,
+                context,
+                depth + 1,
+            );
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn handle_function_declaration(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        tables: &mut HashMap<String, usize>,
+        counter: &mut SymbolCounter,
+        context: &mut ParserContext,
+        depth: usize,
+    ) {
+        self.register_node(&node);
+
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let Some(function_name) = self.function_name(name_node, code) else {
+            return;
+        };
+
+        let (name, kind, scope, display_name) = match function_name {
+            FunctionName::Plain(name) => {
+                let scope = if self.is_local(node) && !context.is_module_level() {
+                    context.current_scope_context()
+                } else if self.is_local(node) {
+                    ScopeContext::Module
+                } else {
+                    ScopeContext::Global
+                };
+                (name, SymbolKind::Function, scope, name.to_string())
+            }
+            FunctionName::Field { table, name } => {
+                self.promote_table(table, symbols, tables);
+                (
+                    name,
+                    SymbolKind::Function,
+                    ScopeContext::ClassMember {
+                        class_name: Some(compact_string(table)),
+                    },
+                    format!("{table}.{name}"),
+                )
+            }
+            FunctionName::Method { table, name } => {
+                self.promote_table(table, symbols, tables);
+                (
+                    name,
+                    SymbolKind::Method,
+                    ScopeContext::ClassMember {
+                        class_name: Some(compact_string(table)),
+                    },
+                    format!("{table}:{name}"),
+                )
+            }
+        };
+
+        if name.is_empty() {
+            return;
+        }
+
+        let mut symbol = Symbol::new(
+            counter.next_id(),
+            name,
+            kind,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.signature = Some(self.function_signature(node, code, &display_name).into());
+        if let Some(doc) = self.doc_comment_for(&node, code) {
+            symbol.doc_comment = Some(doc.into());
+        }
+        symbol.scope_context = Some(scope);
+        symbols.push(symbol);
+
+        let previous_function = context.current_function().map(|s| s.to_string());
+        context.enter_scope(ScopeType::function());
+        context.set_current_function(Some(name.to_string()));
+
+        if let Some(body) = node.child_by_field_name("body") {
+            self.extract_symbols_from_node(
+                body,
+                code,
+                file_id,
+                symbols,
+                tables,
+                counter,
+                context,
+                depth + 1,
+            );
+        }
+
+        context.exit_scope();
+        context.set_current_function(previous_function);
+    }
+
+    /// Handle `local x = ...`, `M = {}`, `local f = function() end` and `M.f = function() end`
+    ///
+    /// Returns true when the node (including its children) has been fully processed.
+    #[allow(clippy::too_many_arguments)]
+    fn handle_assignment(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        tables: &mut HashMap<String, usize>,
+        counter: &mut SymbolCounter,
+        context: &mut ParserContext,
+        depth: usize,
+    ) -> bool {
+        self.register_node(&node);
+
+        // `local x = 1` wraps an assignment_statement; unwrap it here
+        let (assignment, is_local) = if node.kind() == "variable_declaration" {
+            let mut cursor = node.walk();
+            let inner = node
+                .children(&mut cursor)
+                .find(|child| child.kind() == "assignment_statement");
+            match inner {
+                Some(inner) => (inner, true),
+                // `local x` without a value
+                None => {
+                    if !context.is_in_function() {
+                        self.declare_uninitialized(node, code, file_id, symbols, counter, context);
+                    }
+                    return true;
+                }
+            }
+        } else {
+            (node, false)
+        };
+
+        // Locals inside functions are not indexed, but their values may define functions
+        if context.is_in_function() && is_local {
+            return false;
+        }
+
+        let mut cursor = assignment.walk();
+        let variables: Vec<Node> = assignment
+            .children(&mut cursor)
+            .find(|child| child.kind() == "variable_list")
+            .map(|list| {
+                let mut list_cursor = list.walk();
+                list.named_children(&mut list_cursor).collect()
+            })
+            .unwrap_or_default();
+
+        let mut cursor = assignment.walk();
+        let values: Vec<Node> = assignment
+            .children(&mut cursor)
+            .find(|child| child.kind() == "expression_list")
+            .map(|list| {
+                let mut list_cursor = list.walk();
+                list.named_children(&mut list_cursor).collect()
+            })
+            .unwrap_or_default();
+
+        for (index, variable) in variables.iter().enumerate() {
+            let value = values.get(index).copied();
+            let target = match variable.kind() {
+                "identifier" => {
+                    // Global assignments inside functions are side effects, not declarations
+                    if context.is_in_function() {
+                        continue;
+                    }
+                    FunctionName::Plain(self.text_for_node(code, *variable).trim())
+                }
+                "dot_index_expression" => {
+                    let (Some(table), Some(field)) = (
+                        variable.child_by_field_name("table"),
+                        variable.child_by_field_name("field"),
+                    ) else {
+                        continue;
+                    };
+                    let table = self.text_for_node(code, table).trim();
+                    // Only table members of known module tables are declarations
+                    if !tables.contains_key(table) {
+                        continue;
+                    }
+                    FunctionName::Field {
+                        table,
+                        name: self.text_for_node(code, field).trim(),
+                    }
+                }
+                _ => continue,
+            };
+
+            let is_function = value.is_some_and(|v| v.kind() == "function_definition");
+            let is_table = value.is_some_and(|v| v.kind() == "table_constructor");
+
+            let (name, scope, display_name) = match target {
+                FunctionName::Plain(name) => {
+                    let scope = if is_local {
+                        ScopeContext::Module
+                    } else {
+                        ScopeContext::Global
+                    };
+                    (name, scope, name.to_string())
+                }
+                FunctionName::Field { table, name } => {
+                    self.promote_table(table, symbols, tables);
+                    (
+                        name,
+                        ScopeContext::ClassMember {
+                            class_name: Some(compact_string(table)),
+                        },
+                        format!("{table}.{name}"),
+                    )
+                }
+                FunctionName::Method { .. } => continue,
+            };
+
+            if name.is_empty() {
+                continue;
+            }
+
+            let kind = if is_function {
+                SymbolKind::Function
+            } else if matches!(scope, ScopeContext::ClassMember { .. }) {
+                SymbolKind::Field
+            } else if name.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+                SymbolKind::Constant
+            } else {
+                SymbolKind::Variable
+            };
+
+            let signature = match value {
+                Some(v) if is_function => self.function_signature(v, code, &display_name),
+                _ => {
+                    let local = if is_local { "local " } else { "" };
+                    crate::parsing::truncate_for_display(
+                        &format!("{local}{}", self.text_for_node(code, node).trim()),
+                        160,
+                    )
+                }
+            };
+
+            let mut symbol = Symbol::new(
+                counter.next_id(),
+                name,
+                kind,
+                file_id,
+                self.node_to_range(node),
+            );
+            symbol.signature = Some(signature.into());
+            if let Some(doc) = self.doc_comment_for(&node, code) {
+                symbol.doc_comment = Some(doc.into());
+            }
+            symbol.scope_context = Some(scope);
+            symbols.push(symbol);
+
+            if is_table && !context.is_in_function() {
+                tables.insert(name.to_string(), symbols.len() - 1);
+            }
+
+            if let Some(value) = value {
+                if is_function {
+                    self.register_node(&value);
+                    let previous_function = context.current_function().map(|s| s.to_string());
+                    context.enter_scope(ScopeType::function());
+                    context.set_current_function(Some(name.to_string()));
+                    if let Some(body) = value.child_by_field_name("body") {
+                        self.extract_symbols_from_node(
+                            body,
+                            code,
+                            file_id,
+                            symbols,
+                            tables,
+                            counter,
+                            context,
+                            depth + 1,
+                        );
+                    }
+                    context.exit_scope();
+                    context.set_current_function(previous_function);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Declare `local a, b` at chunk level
+    fn declare_uninitialized(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+        context: &ParserContext,
+    ) {
+        let mut cursor = node.walk();
+        let mut names = Vec::new();
+        collect_identifiers(node, &mut cursor, &mut names);
+
+        for name_node in names {
+            let name = self.text_for_node(code, name_node).trim();
+            if name.is_empty() {
+                continue;
+            }
+            let mut symbol = Symbol::new(
+                counter.next_id(),
+                name,
+                SymbolKind::Variable,
+                file_id,
+                self.node_to_range(node),
+            );
+            symbol.signature = Some(self.text_for_node(code, node).trim().into());
+            symbol.scope_context = Some(context.current_scope_context());
+            symbols.push(symbol);
+        }
+    }
+
+    /// Promote a table variable to a module symbol once members are attached to it
+    fn promote_table(&self, table: &str, symbols: &mut [Symbol], tables: &HashMap<String, usize>) {
+        if let Some(&index) = tables.get(table) {
+            if let Some(symbol) = symbols.get_mut(index) {
+                symbol.kind = SymbolKind::Module;
+            }
+        }
+    }
+
+    /// `return M` at chunk level exports the table as the module value
+    fn mark_returned_table(
+        &self,
+        node: Node,
+        code: &str,
+        symbols: &mut [Symbol],
+        tables: &HashMap<String, usize>,
+    ) {
+        let mut cursor = node.walk();
+        let mut names = Vec::new();
+        collect_identifiers(node, &mut cursor, &mut names);
+
+        for name_node in names {
+            let name = self.text_for_node(code, name_node).trim();
+            if let Some(&index) = tables.get(name) {
+                if let Some(symbol) = symbols.get_mut(index) {
+                    symbol.kind = SymbolKind::Module;
+                    symbol.scope_context = Some(ScopeContext::Package);
+                }
+            }
+        }
+    }
+
+    /// Recursively find `require` calls
+    fn find_imports_in_node(
+        &self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        imports: &mut Vec<Import>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if node.kind() == "function_call" {
+            if let Some(path) = self.require_path(node, code) {
+                imports.push(Import {
+                    file_id,
+                    path: path.to_string(),
+                    alias: self.require_alias(node, code),
+                    is_glob: false,
+                    is_type_only: false,
+                });
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.find_imports_in_node(child, code, file_id, imports, depth + 1);
+        }
+    }
+
+    /// Binding name for `local json = require("dkjson")`
+    fn require_alias(&self, call: Node, code: &str) -> Option<String> {
+        let expression_list = call.parent()?;
+        if expression_list.kind() != "expression_list" {
+            return None;
+        }
+        let assignment = expression_list.parent()?;
+        if assignment.kind() != "assignment_statement" {
+            return None;
+        }
+
+        let mut cursor = assignment.walk();
+        let variables = assignment
+            .children(&mut cursor)
+            .find(|child| child.kind() == "variable_list")?;
+        let first = variables.named_child(0)?;
+        (first.kind() == "identifier").then(|| self.text_for_node(code, first).trim().to_string())
+    }
+
+    fn collect_calls<'a>(
+        &mut self,
+        node: Node,
+        code: &'a str,
+        calls: &mut Vec<(&'a str, &'a str, Range)>,
+        current_function: Option<&'a str>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let mut next_function = current_function;
+        match node.kind() {
+            "function_declaration" | "local_function_declaration" => {
+                self.register_node(&node);
+                next_function = self.callable_name(node, code).or(current_function);
+            }
+            "function_call" => {
+                self.register_node(&node);
+                if self.require_path(node, code).is_none() {
+                    if let Some(target) = node
+                        .child_by_field_name("name")
+                        .and_then(|callee| self.call_target(callee, code))
+                    {
+                        let caller = current_function.unwrap_or(CHUNK_SCOPE);
+                        calls.push((caller, target, self.node_to_range(node)));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_calls(child, code, calls, next_function, depth + 1);
+        }
+    }
+
+    fn collect_defines<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        defines: &mut Vec<(&'a str, &'a str, Range)>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if matches!(node.kind(), "function_declaration") {
+            if let Some(name_node) = node.child_by_field_name("name") {
+                match self.function_name(name_node, code) {
+                    Some(FunctionName::Field { table, name })
+                    | Some(FunctionName::Method { table, name }) => {
+                        defines.push((table, name, self.node_to_range(node)));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_defines(child, code, defines, depth + 1);
+        }
+    }
+
+    fn collect_uses<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        uses: &mut Vec<(&'a str, &'a str, Range)>,
+        current_function: Option<&'a str>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let mut next_function = current_function;
+        match node.kind() {
+            "function_declaration" | "local_function_declaration" => {
+                next_function = self.callable_name(node, code).or(current_function);
+            }
+            "function_call" => {
+                if let Some(path) = self.require_path(node, code) {
+                    let source = current_function.unwrap_or(CHUNK_SCOPE);
+                    uses.push((source, path, self.node_to_range(node)));
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_uses(child, code, uses, next_function, depth + 1);
+        }
+    }
+
+    /// Access handled nodes for audit tooling
+    pub fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+}
+
+/// Collect identifier nodes below `node` (used for `local a, b` and `return M`)
+fn collect_identifiers<'tree>(
+    node: Node<'tree>,
+    cursor: &mut tree_sitter::TreeCursor<'tree>,
+    out: &mut Vec<Node<'tree>>,
+) {
+    for child in node.named_children(cursor) {
+        if child.kind() == "identifier" {
+            out.push(child);
+        } else if matches!(
+            child.kind(),
+            "attribute_name_list" | "variable_list" | "expression_list"
+        ) {
+            let mut inner = child.walk();
+            collect_identifiers(child, &mut inner, out);
+        }
+    }
+}
+
+impl LanguageParser for LuaParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut symbols = Vec::new();
+        let mut tables = HashMap::new();
+        let mut context = ParserContext::new();
+
+        // Every Lua file is a chunk; represent it as a module symbol
+        let module_id = symbol_counter.next_id();
+        let mut module_symbol = Symbol::new(
+            module_id,
+            CHUNK_SCOPE,
+            SymbolKind::Module,
+            file_id,
+            self.node_to_range(root),
+        );
+        module_symbol.scope_context = Some(ScopeContext::Module);
+        symbols.push(module_symbol);
+
+        self.extract_symbols_from_node(
+            root,
+            code,
+            file_id,
+            &mut symbols,
+            &mut tables,
+            symbol_counter,
+            &mut context,
+            0,
+        );
+
+        symbols
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
+        self.doc_comment_for(node, code)
+    }
+
+    fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut calls = Vec::new();
+        self.collect_calls(tree.root_node(), code, &mut calls, None, 0);
+        calls
+    }
+
+    fn find_implementations<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut uses = Vec::new();
+        self.collect_uses(tree.root_node(), code, &mut uses, None, 0);
+        uses
+    }
+
+    fn find_defines<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut defines = Vec::new();
+        self.collect_defines(tree.root_node(), code, &mut defines, 0);
+        defines
+    }
+
+    fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<Import> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut imports = Vec::new();
+        self.find_imports_in_node(tree.root_node(), code, file_id, &mut imports, 0);
+        imports
+    }
+
+    fn language(&self) -> Language {
+        Language::Lua
+    }
+}
+
+impl NodeTracker for LuaParser {
+    fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+
+    fn register_handled_node(&mut self, node_kind: &str, node_id: u16) {
+        self.node_tracker.register_handled_node(node_kind, node_id);
+    }
+}
@@ -1,35 +1,52 @@
 pub mod behavior_state;
 pub mod c;
+pub mod config;
 pub mod context;
 pub mod cpp;
 pub mod csharp;
+pub mod diagnostics;
+pub mod external;
 pub mod factory;
 pub mod gdscript;
 pub mod go;
+pub mod hcl;
 pub mod import;
+pub mod injection;
 pub mod java;
 pub mod javascript;
 pub mod kotlin;
 pub mod language;
 pub mod language_behavior;
+pub mod lua;
 pub mod method_call;
+pub mod objc;
 pub mod parser;
 pub mod php;
+pub mod protobuf;
 pub mod python;
 pub mod registry;
 pub mod resolution;
 pub mod rust;
+pub mod sql;
 pub mod swift;
+pub mod type_hints;
 pub mod typescript;
+pub mod user_queries;
+pub mod vue;
 
 pub use c::{CBehavior, CParser};
+pub use config::{ConfigBehavior, ConfigParser};
 pub use context::{ParserContext, ScopeType};
 pub use cpp::{CppBehavior, CppParser};
 pub use csharp::{CSharpBehavior, CSharpParser};
+pub use diagnostics::{DiagnosticKind, DiagnosticsParser, ParseDiagnostic};
+pub use external::{ExternalBehavior, ExternalParser};
 pub use factory::{ParserFactory, ParserWithBehavior};
 pub use gdscript::{GdscriptBehavior, GdscriptParser};
 pub use go::{GoBehavior, GoParser};
+pub use hcl::{HclBehavior, HclParser};
 pub use import::Import;
+pub use injection::{InjectedCode, InjectionParser};
 pub use java::{JavaBehavior, JavaParser};
 pub use javascript::{JavaScriptBehavior, JavaScriptParser};
 pub use kotlin::{KotlinBehavior, KotlinParser};
@@ -37,12 +54,15 @@ pub use language::Language;
 pub use language_behavior::{
     LanguageBehavior, LanguageMetadata, RelationRole, default_relationship_compatibility,
 };
+pub use lua::{LuaBehavior, LuaParser};
 pub use method_call::{MethodCall, MethodCallResolver};
+pub use objc::{ObjCBehavior, ObjCParser};
 pub use parser::{
     HandledNode, LanguageParser, NodeTracker, NodeTrackingState, safe_substring_window,
     safe_truncate_str, truncate_for_display,
 };
 pub use php::{PhpBehavior, PhpParser};
+pub use protobuf::{ProtobufBehavior, ProtobufParser};
 pub use python::{PythonBehavior, PythonParser};
 pub use registry::{LanguageDefinition, LanguageId, LanguageRegistry, RegistryError, get_registry};
 pub use resolution::{
@@ -50,5 +70,8 @@ pub use resolution::{
     PipelineSymbolCache, ResolutionScope, ResolveResult, ScopeLevel,
 };
 pub use rust::{RustBehavior, RustParser};
+pub use sql::{SqlBehavior, SqlParser};
 pub use swift::{SwiftBehavior, SwiftParser};
 pub use typescript::{TypeScriptBehavior, TypeScriptParser};
+pub use user_queries::UserQueries;
+pub use vue::{VueBehavior, VueParser};
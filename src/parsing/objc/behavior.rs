@@ -0,0 +1,143 @@
+//! Objective-C-specific language behavior implementation
+
+use crate::parsing::Import;
+use crate::parsing::LanguageBehavior;
+use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::{FileId, Symbol, SymbolKind, Visibility};
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Language behavior for Objective-C
+#[derive(Clone)]
+pub struct ObjCBehavior {
+    language: Language,
+    state: BehaviorState,
+}
+
+impl ObjCBehavior {
+    /// Create a new behavior instance
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_objc::LANGUAGE.into(),
+            state: BehaviorState::new(),
+        }
+    }
+}
+
+impl StatefulBehavior for ObjCBehavior {
+    fn state(&self) -> &BehaviorState {
+        &self.state
+    }
+}
+
+impl Default for ObjCBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageBehavior for ObjCBehavior {
+    fn language_id(&self) -> crate::parsing::registry::LanguageId {
+        crate::parsing::registry::LanguageId::new("objc")
+    }
+
+    fn format_module_path(&self, base_path: &str, symbol_name: &str) -> String {
+        format!("{base_path}/{symbol_name}")
+    }
+
+    fn parse_visibility(&self, _signature: &str) -> Visibility {
+        // Objective-C has no access control for methods or classes; anything
+        // declared in a header or sent as a message is reachable at runtime
+        Visibility::Public
+    }
+
+    fn module_separator(&self) -> &'static str {
+        "/"
+    }
+
+    fn supports_traits(&self) -> bool {
+        // Protocols
+        true
+    }
+
+    fn supports_inherent_methods(&self) -> bool {
+        false
+    }
+
+    /// `Sources/Net/Client.m` -> `Sources/Net/Client`
+    ///
+    /// `Client.h` and `Client.m` share a module path, so a class declared in
+    /// the header and implemented in the source file lands in one module.
+    fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        let relative = file_path.strip_prefix(project_root).unwrap_or(file_path);
+        let module = relative
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .filter(|segment| !segment.is_empty() && segment != ".")
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if module.is_empty() {
+            Some("root".to_string())
+        } else {
+            Some(module)
+        }
+    }
+
+    fn get_language(&self) -> Language {
+        self.language.clone()
+    }
+
+    // Override import tracking methods to use state
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.register_file_with_state(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.add_import_with_state(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.get_imports_from_state(file_id)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.state.get_module_path(file_id)
+    }
+
+    fn get_file_path(&self, file_id: FileId) -> Option<PathBuf> {
+        self.state.get_file_path(file_id)
+    }
+
+    fn is_resolvable_symbol(&self, symbol: &Symbol) -> bool {
+        matches!(
+            symbol.kind,
+            SymbolKind::Class
+                | SymbolKind::Interface
+                | SymbolKind::Method
+                | SymbolKind::Function
+                | SymbolKind::Field
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_path_from_file() {
+        let behavior = ObjCBehavior::new();
+        let root = Path::new("/app");
+
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/app/Sources/Net/Client.m"), root),
+            Some("Sources/Net/Client".to_string())
+        );
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/app/Sources/Net/Client.h"), root),
+            Some("Sources/Net/Client".to_string())
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Objective-C language definition for the registry
+//!
+//! Provides the language metadata and glue code used by the language registry
+//! to instantiate parsers and behaviors for `.m` files. Headers keep the `.h`
+//! extension registered by C; the indexing pipeline routes a header here when
+//! its content is Objective-C.
+
+use std::sync::Arc;
+
+use super::{ObjCBehavior, ObjCParser};
+use crate::parsing::{LanguageBehavior, LanguageDefinition, LanguageId, LanguageParser};
+use crate::{IndexError, IndexResult, Settings};
+
+/// Language definition for Objective-C
+pub struct ObjCLanguage;
+
+impl ObjCLanguage {
+    /// Stable identifier used throughout the registry
+    pub const ID: LanguageId = LanguageId::new("objc");
+}
+
+impl LanguageDefinition for ObjCLanguage {
+    fn id(&self) -> LanguageId {
+        Self::ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Objective-C"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["m"]
+    }
+
+    fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
+        let parser = ObjCParser::new().map_err(IndexError::General)?;
+        Ok(Box::new(parser))
+    }
+
+    fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
+        Box::new(ObjCBehavior::new())
+    }
+
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings
+            .languages
+            .get(self.id().as_str())
+            .map(|config| config.enabled)
+            .unwrap_or(self.default_enabled())
+    }
+}
+
+/// Register Objective-C language with the global registry
+pub(crate) fn register(registry: &mut crate::parsing::LanguageRegistry) {
+    registry.register(Arc::new(ObjCLanguage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_metadata() {
+        let lang = ObjCLanguage;
+
+        assert_eq!(lang.id(), LanguageId::new("objc"));
+        assert_eq!(lang.name(), "Objective-C");
+        assert_eq!(lang.extensions(), &["m"]);
+    }
+
+    #[test]
+    fn test_parser_creation() {
+        let lang = ObjCLanguage;
+        let settings = Settings::default();
+        assert!(lang.create_parser(&settings).is_ok());
+    }
+}
@@ -0,0 +1,14 @@
+//! Objective-C parser implementation
+
+pub mod audit;
+pub mod behavior;
+pub mod definition;
+pub mod parser;
+
+pub use audit::ObjCParserAudit;
+pub use behavior::ObjCBehavior;
+pub use definition::ObjCLanguage;
+pub use parser::ObjCParser;
+
+// Re-export for registry registration
+pub(crate) use definition::register;
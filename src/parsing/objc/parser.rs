@@ -0,0 +1,1105 @@
+//! Objective-C parser implementation
+//!
+//! Extracts classes, protocols, categories, methods and properties using
+//! tree-sitter-objc:
+//!
+//! | Construct | Symbol kind | Name |
+//! |-----------|-------------|------|
+//! | `@interface Foo : NSObject` | Class | `Foo` |
+//! | `@protocol Loading` | Interface | `Loading` |
+//! | `- (void)loadURL:(NSURL *)url` | Method | `loadURL` |
+//! | `@property NSString *title` | Field | `title` |
+//! | `void helper(void) { }` | Function | `helper` |
+//!
+//! Categories (`@interface Foo (Networking)`) and class extensions
+//! (`@interface Foo ()`) create no symbol of their own. Their methods are
+//! members of the base class and are defined by it, so a lookup on `Foo`
+//! finds category methods the same way it finds Swift extension methods.
+//!
+//! Methods are named by their first selector keyword (`loadURL:withCache:`
+//! -> `loadURL`), which is also the base name Swift sees when it imports the
+//! selector. The full selector stays visible in the signature.
+//!
+//! A class gets its symbol from its `@interface`; an `@implementation` only
+//! creates one when the file has no interface for the class, as in a `.m`
+//! file whose header was not indexed.
+
+use crate::parsing::Import;
+use crate::parsing::parser::{check_recursion_depth, collapse_whitespace};
+use crate::parsing::{
+    HandledNode, Language, LanguageParser, MethodCall, NodeTracker, NodeTrackingState,
+};
+use crate::symbol::ScopeContext;
+use crate::types::SymbolCounter;
+use crate::{FileId, Range, Symbol, SymbolKind};
+use std::any::Any;
+use std::collections::HashSet;
+use tree_sitter::{Node, Parser};
+
+/// Node kinds that open an `@interface`, `@implementation` or `@protocol` block
+const CONTAINER_KINDS: &[&str] = &[
+    "class_interface",
+    "class_implementation",
+    "category_interface",
+    "category_implementation",
+    "protocol_declaration",
+];
+
+/// Capitalized type names that are C typedefs rather than declared types
+const BUILTIN_TYPES: &[&str] = &["BOOL", "SEL", "IMP", "Class"];
+
+/// Parser for Objective-C source and header files
+pub struct ObjCParser {
+    parser: Parser,
+    node_tracker: NodeTrackingState,
+}
+
+impl std::fmt::Debug for ObjCParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjCParser")
+            .field("language", &"Objective-C")
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContainerKind {
+    Interface,
+    Implementation,
+    Protocol,
+}
+
+/// The header of an `@interface`, `@implementation` or `@protocol` block
+#[derive(Debug, PartialEq, Eq)]
+struct ContainerHeader<'a> {
+    kind: ContainerKind,
+    name: &'a str,
+    /// `Some("")` for a class extension
+    category: Option<&'a str>,
+    superclass: Option<&'a str>,
+    /// Adopted protocols, or parent protocols of a `@protocol`
+    protocols: Vec<&'a str>,
+}
+
+/// A container node with its parsed header
+struct Container<'t, 'a> {
+    node: Node<'t>,
+    header: ContainerHeader<'a>,
+}
+
+/// A method or property inside a container
+struct Member<'t, 'a> {
+    node: Node<'t>,
+    name: &'a str,
+    kind: SymbolKind,
+    is_class_method: bool,
+}
+
+impl ObjCParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_objc::LANGUAGE.into())
+            .map_err(|e| format!("Failed to initialize Objective-C parser: {e}"))?;
+
+        Ok(Self {
+            parser,
+            node_tracker: NodeTrackingState::new(),
+        })
+    }
+
+    /// Convert a tree-sitter node into a Range
+    fn node_to_range(&self, node: Node) -> Range {
+        let start = node.start_position();
+        let end = node.end_position();
+        Range {
+            start_line: start.row as u32,
+            start_column: start.column as u16,
+            end_line: end.row as u32,
+            end_column: end.column as u16,
+        }
+    }
+
+    /// Helper to register handled node kinds for audit tracking
+    fn register_node(&mut self, node: &Node) {
+        self.node_tracker
+            .register_handled_node(node.kind(), node.kind_id());
+    }
+
+    /// Extract raw source text for a node
+    fn text_for_node<'a>(&self, code: &'a str, node: Node) -> &'a str {
+        &code[node.byte_range()]
+    }
+
+    /// Extract `//`, `///` and `/** */` comments directly above a declaration
+    fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
+        let mut comments = Vec::new();
+        let mut current = node.prev_sibling();
+        let mut expected_row = node.start_position().row;
+
+        while let Some(sibling) = current {
+            if sibling.kind() != "comment" {
+                break;
+            }
+            if sibling.end_position().row + 1 < expected_row {
+                break;
+            }
+
+            let raw = self.text_for_node(code, sibling).trim();
+            if let Some(rest) = raw.strip_prefix("//") {
+                comments.push(rest.trim_start_matches('/').trim().to_string());
+            } else if raw.starts_with("/*") {
+                let cleaned = raw
+                    .trim_start_matches("/*")
+                    .trim_end_matches("*/")
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches('*').trim())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                comments.push(cleaned);
+            } else {
+                break;
+            }
+
+            expected_row = sibling.start_position().row;
+            current = sibling.prev_sibling();
+        }
+
+        if comments.is_empty() {
+            None
+        } else {
+            comments.reverse();
+            Some(comments.join("\n"))
+        }
+    }
+
+    /// Collect every container in the file, outermost first
+    fn collect_containers<'t, 'a>(
+        &self,
+        node: Node<'t>,
+        code: &'a str,
+        containers: &mut Vec<Container<'t, 'a>>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if CONTAINER_KINDS.contains(&node.kind()) {
+            if let Some(header) = parse_container_header(self.text_for_node(code, node)) {
+                containers.push(Container { node, header });
+            }
+            return;
+        }
+        if node.kind() == "compound_statement" {
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect_containers(child, code, containers, depth + 1);
+        }
+    }
+
+    /// Methods and properties declared or defined inside a container
+    fn collect_members<'t, 'a>(
+        &self,
+        node: Node<'t>,
+        code: &'a str,
+        members: &mut Vec<Member<'t, 'a>>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        match node.kind() {
+            "method_declaration" | "method_definition" => {
+                if let Some((name, is_class_method)) =
+                    method_selector(self.text_for_node(code, node))
+                {
+                    members.push(Member {
+                        node,
+                        name,
+                        kind: SymbolKind::Method,
+                        is_class_method,
+                    });
+                }
+                return;
+            }
+            "property_declaration" => {
+                if let Some((name, _)) = property_parts(self.text_for_node(code, node)) {
+                    members.push(Member {
+                        node,
+                        name,
+                        kind: SymbolKind::Field,
+                        is_class_method: false,
+                    });
+                }
+                return;
+            }
+            "compound_statement" => return,
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect_members(child, code, members, depth + 1);
+        }
+    }
+
+    /// Containers paired with their members, with interface declarations
+    /// dropped when the same file also implements the method
+    fn containers_with_members<'t, 'a>(
+        &self,
+        root: Node<'t>,
+        code: &'a str,
+    ) -> Vec<(Container<'t, 'a>, Vec<Member<'t, 'a>>)> {
+        let mut containers = Vec::new();
+        self.collect_containers(root, code, &mut containers, 0);
+
+        let with_members: Vec<_> = containers
+            .into_iter()
+            .map(|container| {
+                let mut members = Vec::new();
+                self.collect_members(container.node, code, &mut members, 0);
+                (container, members)
+            })
+            .collect();
+
+        let implemented: HashSet<(&str, &str, bool)> = with_members
+            .iter()
+            .filter(|(container, _)| container.header.kind == ContainerKind::Implementation)
+            .flat_map(|(container, members)| {
+                members
+                    .iter()
+                    .filter(|member| member.kind == SymbolKind::Method)
+                    .map(|member| (container.header.name, member.name, member.is_class_method))
+            })
+            .collect();
+
+        with_members
+            .into_iter()
+            .map(|(container, members)| {
+                if container.header.kind != ContainerKind::Interface {
+                    return (container, members);
+                }
+                let members = members
+                    .into_iter()
+                    .filter(|member| {
+                        member.kind != SymbolKind::Method
+                            || !implemented.contains(&(
+                                container.header.name,
+                                member.name,
+                                member.is_class_method,
+                            ))
+                    })
+                    .collect();
+                (container, members)
+            })
+            .collect()
+    }
+
+    /// Names of classes that have a non-category `@interface` in this file
+    fn declared_classes<'a>(
+        containers: &[(Container<'_, 'a>, Vec<Member<'_, 'a>>)],
+    ) -> HashSet<&'a str> {
+        containers
+            .iter()
+            .filter(|(container, _)| {
+                container.header.kind == ContainerKind::Interface
+                    && container.header.category.is_none()
+            })
+            .map(|(container, _)| container.header.name)
+            .collect()
+    }
+
+    /// Kind of the symbol a container creates, if any
+    fn container_symbol_kind(
+        header: &ContainerHeader,
+        declared_classes: &HashSet<&str>,
+    ) -> Option<SymbolKind> {
+        match header.kind {
+            ContainerKind::Protocol => Some(SymbolKind::Interface),
+            _ if header.category.is_some() => None,
+            ContainerKind::Interface => Some(SymbolKind::Class),
+            ContainerKind::Implementation => {
+                (!declared_classes.contains(header.name)).then_some(SymbolKind::Class)
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn make_symbol(
+        &self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+        name: &str,
+        kind: SymbolKind,
+        signature: String,
+        scope: ScopeContext,
+    ) -> Symbol {
+        let mut symbol = Symbol::new(
+            counter.next_id(),
+            name,
+            kind,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.signature = Some(signature.into());
+        if let Some(doc) = self.doc_comment_for(&node, code) {
+            symbol.doc_comment = Some(doc.into());
+        }
+        symbol.scope_context = Some(scope);
+        symbol
+    }
+
+    /// Walk function and method bodies, reporting each call with its caller
+    fn collect_calls<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        caller: Option<&'a str>,
+        calls: &mut Vec<Call<'a>>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let caller = match node.kind() {
+            "method_definition" => method_selector(self.text_for_node(code, node))
+                .map(|(name, _)| name)
+                .or(caller),
+            "function_definition" => function_name(node, code).or(caller),
+            _ => caller,
+        };
+
+        if let Some(caller) = caller {
+            match node.kind() {
+                "message_expression" => {
+                    if let Some((receiver, selector)) = message_parts(node, code) {
+                        calls.push(Call {
+                            caller,
+                            callee: selector,
+                            receiver: Some(receiver),
+                            range: self.node_to_range(node),
+                        });
+                    }
+                }
+                "call_expression" => {
+                    let callee = node
+                        .child_by_field_name("function")
+                        .filter(|function| function.kind() == "identifier")
+                        .map(|function| self.text_for_node(code, function));
+                    if let Some(callee) = callee {
+                        calls.push(Call {
+                            caller,
+                            callee,
+                            receiver: None,
+                            range: self.node_to_range(node),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect_calls(child, code, caller, calls, depth + 1);
+        }
+    }
+
+    /// `#import`, `#include` and `@import` directives
+    fn collect_imports(
+        &self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        imports: &mut Vec<Import>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if node.parent().is_some() {
+            let kind = node.kind();
+            let text = self.text_for_node(code, node);
+            let path = if kind.starts_with("preproc") {
+                include_path(text)
+            } else if kind.contains("import") {
+                module_import_path(text)
+            } else {
+                None
+            };
+            if let Some(path) = path {
+                imports.push(Import {
+                    path: path.to_string(),
+                    alias: None,
+                    file_id,
+                    is_glob: false,
+                    is_type_only: false,
+                });
+                return;
+            }
+        }
+        if node.kind() == "compound_statement" {
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect_imports(child, code, file_id, imports, depth + 1);
+        }
+    }
+
+    /// Access handled nodes for audit tooling
+    pub fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+}
+
+/// A function call or message send found in a body
+struct Call<'a> {
+    caller: &'a str,
+    callee: &'a str,
+    /// Receiver text for message sends, `None` for C calls
+    receiver: Option<&'a str>,
+    range: Range,
+}
+
+/// C functions outside of any container
+fn collect_functions<'t>(node: Node<'t>, functions: &mut Vec<Node<'t>>, depth: usize) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    match node.kind() {
+        "function_definition" => {
+            functions.push(node);
+            return;
+        }
+        "compound_statement" => return,
+        kind if CONTAINER_KINDS.contains(&kind) => return,
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_functions(child, functions, depth + 1);
+    }
+}
+
+/// Parse the header of a container from its source text
+///
+/// Handles `@interface Name<T> (Category) : Super <P1, P2>`,
+/// `@implementation Name`, and `@protocol Name <Parent>`. Forward
+/// declarations (`@protocol Name;`) return `None`.
+fn parse_container_header(text: &str) -> Option<ContainerHeader<'_>> {
+    let text = text.trim_start();
+    let (kind, rest) = if let Some(rest) = text.strip_prefix("@interface") {
+        (ContainerKind::Interface, rest)
+    } else if let Some(rest) = text.strip_prefix("@implementation") {
+        (ContainerKind::Implementation, rest)
+    } else if let Some(rest) = text.strip_prefix("@protocol") {
+        (ContainerKind::Protocol, rest)
+    } else {
+        return None;
+    };
+
+    let (name, mut rest) = split_identifier(rest.trim_start())?;
+    let mut header = ContainerHeader {
+        kind,
+        name,
+        category: None,
+        superclass: None,
+        protocols: Vec::new(),
+    };
+
+    // Lightweight generics: `@interface Box<ObjectType> : NSObject`
+    if kind != ContainerKind::Protocol && rest.trim_start().starts_with('<') {
+        let (_, after) = split_delimited(rest.trim_start(), '<', '>')?;
+        if after.trim_start().starts_with([':', '(']) {
+            rest = after;
+        }
+    }
+
+    rest = rest.trim_start();
+    if rest.starts_with([';', ',']) {
+        return None;
+    }
+
+    if rest.starts_with('(') {
+        let (category, after) = split_delimited(rest, '(', ')')?;
+        header.category = Some(category.trim());
+        rest = after.trim_start();
+    }
+
+    if let Some(after) = rest.strip_prefix(':') {
+        let (superclass, after) = split_identifier(after.trim_start())?;
+        header.superclass = Some(superclass);
+        rest = after.trim_start();
+        // Generic superclass arguments are not adopted protocols
+        if rest.starts_with('<') && is_generic_argument(rest) {
+            rest = split_delimited(rest, '<', '>')?.1.trim_start();
+        }
+    }
+
+    if rest.starts_with('<') {
+        let (protocols, _) = split_delimited(rest, '<', '>')?;
+        header.protocols = protocols
+            .split(',')
+            .map(str::trim)
+            .filter(|protocol| is_identifier(protocol))
+            .collect();
+    }
+
+    Some(header)
+}
+
+/// `<ObjectType *>` after a superclass is a type argument, not a protocol list
+fn is_generic_argument(text: &str) -> bool {
+    split_delimited(text, '<', '>').is_some_and(|(inner, _)| inner.contains('*'))
+}
+
+/// First selector keyword of a method and whether it is a class method
+///
+/// `- (void)loadURL:(NSURL *)url withCache:(BOOL)cache` -> `("loadURL", false)`.
+fn method_selector(text: &str) -> Option<(&str, bool)> {
+    let text = text.trim_start();
+    let is_class_method = match text.chars().next()? {
+        '+' => true,
+        '-' => false,
+        _ => return None,
+    };
+    let mut rest = text[1..].trim_start();
+    if rest.starts_with('(') {
+        rest = split_delimited(rest, '(', ')')?.1.trim_start();
+    }
+    let (name, _) = split_identifier(rest)?;
+    Some((name, is_class_method))
+}
+
+/// Name and type of a `@property`
+///
+/// `@property (nonatomic, copy) NSString *title;` -> `("title", Some("NSString"))`,
+/// `@property (copy) void (^handler)(NSError *);` -> `("handler", Some("void"))`.
+fn property_parts(text: &str) -> Option<(&str, Option<&str>)> {
+    let mut rest = text.trim_start().strip_prefix("@property")?.trim_start();
+    if rest.starts_with('(') {
+        rest = split_delimited(rest, '(', ')')?.1.trim_start();
+    }
+    let declaration = rest.split(';').next().unwrap_or(rest).trim_end();
+
+    let type_name = identifiers(declaration)
+        .find(|word| !is_type_qualifier(word))
+        .filter(|word| Some(*word) != last_identifier(declaration));
+
+    let name = match declaration.find("(^") {
+        Some(caret) => split_identifier(declaration[caret + 2..].trim_start())?.0,
+        None => last_identifier(declaration)?,
+    };
+    Some((name, type_name))
+}
+
+/// Capitalized type names in the parenthesized types of a method signature
+fn signature_types(signature: &str) -> Vec<&str> {
+    let mut types = Vec::new();
+    let mut rest = signature;
+    while let Some(open) = rest.find('(') {
+        let Some((inner, after)) = split_delimited(&rest[open..], '(', ')') else {
+            break;
+        };
+        types.extend(
+            identifiers(inner)
+                .filter(|word| word.starts_with(|c: char| c.is_ascii_uppercase()))
+                .filter(|word| !BUILTIN_TYPES.contains(word)),
+        );
+        rest = after;
+    }
+    types
+}
+
+/// Path of an `#import` or `#include` directive
+fn include_path(text: &str) -> Option<&str> {
+    let text = text.trim_start();
+    let rest = text
+        .strip_prefix("#import")
+        .or_else(|| text.strip_prefix("#include"))?;
+    let path = rest
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches(|c| c == '"' || c == '<' || c == '>');
+    (!path.is_empty()).then_some(path)
+}
+
+/// Module of an `@import Foundation;` statement
+fn module_import_path(text: &str) -> Option<&str> {
+    let rest = text.trim_start().strip_prefix("@import")?;
+    let path = rest.split(';').next().unwrap_or_default().trim();
+    (!path.is_empty()).then_some(path)
+}
+
+/// Receiver text and first selector keyword of a `[receiver selector...]`
+fn message_parts<'a>(node: Node, code: &'a str) -> Option<(&'a str, &'a str)> {
+    let receiver = node.child_by_field_name("receiver").or_else(|| {
+        let mut cursor = node.walk();
+        node.named_children(&mut cursor).next()
+    })?;
+
+    let mut cursor = node.walk();
+    let selector = node
+        .named_children(&mut cursor)
+        .filter(|child| child.start_byte() >= receiver.end_byte())
+        .find_map(|child| first_identifier(child, 0))?;
+
+    Some((&code[receiver.byte_range()], &code[selector.byte_range()]))
+}
+
+/// First identifier at or under `node`, used for selector keywords
+fn first_identifier(node: Node, depth: usize) -> Option<Node> {
+    if !check_recursion_depth(depth, node) {
+        return None;
+    }
+    if node.kind() == "identifier" || node.kind() == "field_identifier" {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    let first = node.named_children(&mut cursor).next()?;
+    first_identifier(first, depth + 1)
+}
+
+/// Name of a C `function_definition`, following its declarator chain
+fn function_name<'a>(node: Node, code: &'a str) -> Option<&'a str> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    for _ in 0..8 {
+        if declarator.kind() == "identifier" {
+            return Some(&code[declarator.byte_range()]);
+        }
+        declarator = declarator.child_by_field_name("declarator")?;
+    }
+    None
+}
+
+/// Split a leading identifier off `text`
+fn split_identifier(text: &str) -> Option<(&str, &str)> {
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    let name = &text[..end];
+    is_identifier(name).then_some((name, &text[end..]))
+}
+
+/// Split `open ... close` (nesting aware) off the start of `text`
+fn split_delimited(text: &str, open: char, close: char) -> Option<(&str, &str)> {
+    let inner_start = text.strip_prefix(open)?;
+    let mut depth = 1;
+    for (i, c) in inner_start.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some((&inner_start[..i], &inner_start[i + 1..]));
+            }
+        }
+    }
+    None
+}
+
+fn identifiers(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| is_identifier(word))
+}
+
+fn last_identifier(text: &str) -> Option<&str> {
+    identifiers(text).last()
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+}
+
+fn is_type_qualifier(word: &str) -> bool {
+    matches!(
+        word,
+        "const"
+            | "volatile"
+            | "__kindof"
+            | "nullable"
+            | "nonnull"
+            | "_Nullable"
+            | "_Nonnull"
+            | "__nullable"
+            | "__nonnull"
+            | "__weak"
+            | "__strong"
+            | "__unsafe_unretained"
+            | "unsigned"
+            | "signed"
+    )
+}
+
+/// Declaration text up to the body or terminating `;`, on one line
+fn signature_for(text: &str) -> String {
+    let header = text.split(['{', ';']).next().unwrap_or(text);
+    crate::parsing::truncate_for_display(&collapse_whitespace(header), 200)
+}
+
+/// The opening line of a container: `@interface Foo : NSObject <Bar>`
+fn container_signature(text: &str) -> String {
+    let header = text
+        .lines()
+        .take_while(|line| {
+            let line = line.trim_start();
+            !(line.starts_with(['-', '+', '{']) || line.starts_with("@property"))
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let header = header.split(['{', ';']).next().unwrap_or(&header);
+    let header = header.split("@end").next().unwrap_or(header);
+    crate::parsing::truncate_for_display(&collapse_whitespace(header), 200)
+}
+
+impl LanguageParser for ObjCParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+        let root = tree.root_node();
+
+        let containers = self.containers_with_members(root, code);
+        let declared_classes = Self::declared_classes(&containers);
+        let mut symbols = Vec::new();
+
+        for (container, members) in &containers {
+            self.register_node(&container.node);
+            let header = &container.header;
+
+            if let Some(kind) = Self::container_symbol_kind(header, &declared_classes) {
+                symbols.push(self.make_symbol(
+                    container.node,
+                    code,
+                    file_id,
+                    symbol_counter,
+                    header.name,
+                    kind,
+                    container_signature(self.text_for_node(code, container.node)),
+                    ScopeContext::Module,
+                ));
+            }
+
+            for member in members {
+                self.register_node(&member.node);
+                let scope = ScopeContext::ClassMember {
+                    class_name: Some(header.name.into()),
+                };
+                symbols.push(self.make_symbol(
+                    member.node,
+                    code,
+                    file_id,
+                    symbol_counter,
+                    member.name,
+                    member.kind,
+                    signature_for(self.text_for_node(code, member.node)),
+                    scope,
+                ));
+            }
+        }
+
+        let mut functions = Vec::new();
+        collect_functions(root, &mut functions, 0);
+        for function in functions {
+            let Some(name) = function_name(function, code) else {
+                continue;
+            };
+            self.register_node(&function);
+            symbols.push(self.make_symbol(
+                function,
+                code,
+                file_id,
+                symbol_counter,
+                name,
+                SymbolKind::Function,
+                signature_for(self.text_for_node(code, function)),
+                ScopeContext::Module,
+            ));
+        }
+
+        symbols
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
+        self.doc_comment_for(node, code)
+    }
+
+    fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut calls = Vec::new();
+        self.collect_calls(tree.root_node(), code, None, &mut calls, 0);
+        calls
+            .into_iter()
+            .map(|call| (call.caller, call.callee, call.range))
+            .collect()
+    }
+
+    fn find_method_calls(&mut self, code: &str) -> Vec<MethodCall> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut calls = Vec::new();
+        self.collect_calls(tree.root_node(), code, None, &mut calls, 0);
+        calls
+            .into_iter()
+            .filter_map(|call| {
+                let receiver = call.receiver?;
+                let method_call = MethodCall::new(call.caller, call.callee, call.range)
+                    .with_receiver(&collapse_whitespace(receiver));
+                // `[NSString stringWithFormat:...]` messages the class itself
+                let is_class = is_identifier(receiver)
+                    && receiver.starts_with(|c: char| c.is_ascii_uppercase());
+                Some(if is_class {
+                    method_call.static_method()
+                } else {
+                    method_call
+                })
+            })
+            .collect()
+    }
+
+    fn find_implementations<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut containers = Vec::new();
+        self.collect_containers(tree.root_node(), code, &mut containers, 0);
+
+        // Protocols adopted by a category are adopted by the base class
+        containers
+            .iter()
+            .filter(|container| container.header.kind != ContainerKind::Protocol)
+            .flat_map(|container| {
+                let range = self.node_to_range(container.node);
+                container
+                    .header
+                    .protocols
+                    .iter()
+                    .map(move |protocol| (container.header.name, *protocol, range))
+            })
+            .collect()
+    }
+
+    fn find_extends<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut containers = Vec::new();
+        self.collect_containers(tree.root_node(), code, &mut containers, 0);
+
+        let mut extends = Vec::new();
+        let mut seen = HashSet::new();
+        for container in &containers {
+            let header = &container.header;
+            let range = self.node_to_range(container.node);
+            match header.kind {
+                ContainerKind::Protocol => {
+                    for parent in &header.protocols {
+                        extends.push((header.name, *parent, range));
+                    }
+                }
+                _ => {
+                    if let Some(superclass) = header.superclass {
+                        if seen.insert((header.name, superclass)) {
+                            extends.push((header.name, superclass, range));
+                        }
+                    }
+                }
+            }
+        }
+        extends
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut uses = Vec::new();
+        for (container, members) in self.containers_with_members(tree.root_node(), code) {
+            for member in members {
+                let text = self.text_for_node(code, member.node);
+                let range = self.node_to_range(member.node);
+                if member.kind == SymbolKind::Field {
+                    if let Some((_, Some(type_name))) = property_parts(text) {
+                        if type_name.starts_with(|c: char| c.is_ascii_uppercase())
+                            && !BUILTIN_TYPES.contains(&type_name)
+                        {
+                            uses.push((container.header.name, type_name, range));
+                        }
+                    }
+                    continue;
+                }
+
+                let signature = text.split(['{', ';']).next().unwrap_or(text);
+                for type_name in signature_types(signature) {
+                    uses.push((member.name, type_name, range));
+                }
+            }
+        }
+        uses
+    }
+
+    fn find_defines<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        // Category members are defined by the base class
+        let mut defines = Vec::new();
+        for (container, members) in self.containers_with_members(tree.root_node(), code) {
+            for member in members {
+                defines.push((
+                    container.header.name,
+                    member.name,
+                    self.node_to_range(member.node),
+                ));
+            }
+        }
+        defines
+    }
+
+    fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<Import> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut imports = Vec::new();
+        self.collect_imports(tree.root_node(), code, file_id, &mut imports, 0);
+        imports
+    }
+
+    fn language(&self) -> Language {
+        Language::ObjectiveC
+    }
+}
+
+impl NodeTracker for ObjCParser {
+    fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+
+    fn register_handled_node(&mut self, node_kind: &str, node_id: u16) {
+        self.node_tracker.register_handled_node(node_kind, node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_container_header() {
+        let header =
+            parse_container_header("@interface Client : NSObject <NSCopying, Loading>\n@end")
+                .unwrap();
+        assert_eq!(header.kind, ContainerKind::Interface);
+        assert_eq!(header.name, "Client");
+        assert_eq!(header.category, None);
+        assert_eq!(header.superclass, Some("NSObject"));
+        assert_eq!(header.protocols, vec!["NSCopying", "Loading"]);
+
+        let category = parse_container_header("@interface Client (Retry) <Retrying>").unwrap();
+        assert_eq!(category.name, "Client");
+        assert_eq!(category.category, Some("Retry"));
+        assert_eq!(category.protocols, vec!["Retrying"]);
+
+        let extension = parse_container_header("@interface Client ()\n@end").unwrap();
+        assert_eq!(extension.category, Some(""));
+
+        let generic = parse_container_header("@interface Box<ObjectType> : NSObject").unwrap();
+        assert_eq!(generic.name, "Box");
+        assert_eq!(generic.superclass, Some("NSObject"));
+        assert!(generic.protocols.is_empty());
+
+        let protocol = parse_container_header("@protocol Loading <NSObject>").unwrap();
+        assert_eq!(protocol.kind, ContainerKind::Protocol);
+        assert_eq!(protocol.protocols, vec!["NSObject"]);
+
+        assert_eq!(parse_container_header("@protocol Loading;"), None);
+    }
+
+    #[test]
+    fn test_method_selector() {
+        assert_eq!(
+            method_selector("- (void)loadURL:(NSURL *)url withCache:(BOOL)cache;"),
+            Some(("loadURL", false))
+        );
+        assert_eq!(
+            method_selector("+ (instancetype)sharedClient {"),
+            Some(("sharedClient", true))
+        );
+        assert_eq!(method_selector("-reset"), Some(("reset", false)));
+        assert_eq!(method_selector("@property int x;"), None);
+    }
+
+    #[test]
+    fn test_property_parts() {
+        assert_eq!(
+            property_parts("@property (nonatomic, copy) NSString *title;"),
+            Some(("title", Some("NSString")))
+        );
+        assert_eq!(
+            property_parts("@property (nullable, weak) id<Loading> delegate;"),
+            Some(("delegate", Some("id")))
+        );
+        assert_eq!(
+            property_parts("@property (copy) void (^handler)(NSError *error);"),
+            Some(("handler", Some("void")))
+        );
+    }
+
+    #[test]
+    fn test_imports_and_signature_types() {
+        assert_eq!(
+            include_path("#import <Foundation/Foundation.h>"),
+            Some("Foundation/Foundation.h")
+        );
+        assert_eq!(include_path("#import \"Client.h\"\n"), Some("Client.h"));
+        assert_eq!(module_import_path("@import UIKit;"), Some("UIKit"));
+        assert_eq!(
+            signature_types("- (NSData *)fetch:(NSURL *)url retry:(BOOL)retry"),
+            vec!["NSData", "NSURL"]
+        );
+    }
+}
@@ -319,6 +319,13 @@ pub fn truncate_for_display(s: &str, max_bytes: usize) -> String {
     }
 }
 
+/// Joins the words of `text` with single spaces.
+/// Used to put multi-line headers and signatures on one line.
+#[inline]
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
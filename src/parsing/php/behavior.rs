@@ -88,6 +88,18 @@ impl LanguageBehavior for PhpBehavior {
     }
 
     fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        // A declared namespace wins over the file location: Laravel's app/ maps to
+        // App\, and legacy code often ignores PSR-4 altogether
+        if let Some(namespace) = declared_namespace(file_path) {
+            let file_name = file_path.file_name()?.to_str()?;
+            let class_name = file_name
+                .strip_suffix(".class.php")
+                .or_else(|| file_name.strip_suffix(".php"))
+                .or_else(|| file_name.strip_suffix(".inc"))
+                .unwrap_or(file_name);
+            return Some(format!("\\{namespace}\\{class_name}"));
+        }
+
         // Get relative path from project root
         let relative_path = file_path.strip_prefix(project_root).ok()?;
 
@@ -282,6 +294,44 @@ impl LanguageBehavior for PhpBehavior {
     }
 }
 
+/// Bytes read when looking for a `namespace` declaration
+const NAMESPACE_SCAN_BYTES: u64 = 8 * 1024;
+
+/// The namespace declared at the top of a PHP file, if any
+///
+/// Only the head of the file is read; the declaration has to precede all
+/// other code except `declare` and comments.
+fn declared_namespace(file_path: &Path) -> Option<String> {
+    use std::io::Read;
+
+    let file = std::fs::File::open(file_path).ok()?;
+    let mut head = String::new();
+    file.take(NAMESPACE_SCAN_BYTES)
+        .read_to_string(&mut head)
+        .ok()?;
+    parse_namespace_declaration(&head)
+}
+
+/// Find `namespace Foo\Bar;` or `namespace Foo\Bar {` in PHP source
+fn parse_namespace_declaration(code: &str) -> Option<String> {
+    code.lines().find_map(|line| {
+        let rest = line.trim_start().strip_prefix("namespace")?;
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let name = rest
+            .split([';', '{'])
+            .next()?
+            .trim()
+            .trim_start_matches('\\');
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '\\');
+        valid.then(|| name.to_string())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,4 +448,33 @@ mod tests {
             Some("\\Services\\PaymentService".to_string())
         );
     }
+
+    #[test]
+    fn test_module_path_from_declared_namespace() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("app/Models");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("User.php");
+        std::fs::write(
+            &file,
+            "<?php\n\ndeclare(strict_types=1);\n\nnamespace App\\Models;\n\nclass User {}\n",
+        )
+        .unwrap();
+
+        let behavior = PhpBehavior::new();
+        assert_eq!(
+            behavior.module_path_from_file(&file, temp.path()),
+            Some("\\App\\Models\\User".to_string())
+        );
+
+        assert_eq!(
+            parse_namespace_declaration("<?php\nnamespace Vendor\\Package {\n}"),
+            Some("Vendor\\Package".to_string())
+        );
+        assert_eq!(parse_namespace_declaration("<?php\n$namespace = 1;"), None);
+        assert_eq!(
+            parse_namespace_declaration("<?php\nnamespace\\foo();"),
+            None
+        );
+    }
 }
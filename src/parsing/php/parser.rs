@@ -335,6 +335,48 @@ impl PhpParser {
                 self.context.set_current_function(saved_function);
                 self.context.set_current_class(saved_class);
             }
+            "namespace_definition" => {
+                self.register_handled_node(node.kind(), node.kind_id());
+                if let Some(symbol) = self.process_namespace(node, code, file_id, counter) {
+                    symbols.push(symbol);
+                }
+                // Braced namespaces hold their declarations in the body
+                self.process_children(node, code, file_id, symbols, counter, depth);
+            }
+            "enum_declaration" => {
+                self.register_handled_node(node.kind(), node.kind_id());
+                let enum_name = node
+                    .child_by_field_name("name")
+                    .map(|n| &code[n.byte_range()]);
+
+                if let Some(symbol) = self.process_enum(node, code, file_id, counter) {
+                    symbols.push(symbol);
+                }
+
+                // Enter enum scope (like class) for cases and methods
+                self.context.enter_scope(ScopeType::Class);
+
+                let saved_function = self.context.current_function().map(|s| s.to_string());
+                let saved_class = self.context.current_class().map(|s| s.to_string());
+
+                if let Some(name) = enum_name {
+                    self.context.set_current_class(Some(name.to_string()));
+                }
+
+                self.process_children(node, code, file_id, symbols, counter, depth);
+
+                // CRITICAL: Exit scope first (this clears the current context)
+                self.context.exit_scope();
+
+                self.context.set_current_function(saved_function);
+                self.context.set_current_class(saved_class);
+            }
+            "enum_case" => {
+                self.register_handled_node(node.kind(), node.kind_id());
+                if let Some(symbol) = self.process_enum_case(node, code, file_id, counter) {
+                    symbols.push(symbol);
+                }
+            }
             "property_declaration" => {
                 self.register_handled_node(node.kind(), node.kind_id());
                 if let Some(symbol) = self.process_property(node, code, file_id, counter) {
@@ -652,6 +694,93 @@ impl PhpParser {
         Some(symbol)
     }
 
+    /// Process a namespace definition node
+    ///
+    /// The symbol is named after the full namespace (`App\Http\Controllers`).
+    /// The global namespace (`namespace { ... }`) has no name and no symbol.
+    fn process_namespace(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+    ) -> Option<Symbol> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = &code[name_node.byte_range()];
+
+        let id = counter.next_id();
+
+        let mut symbol = Symbol::new(
+            id,
+            name,
+            SymbolKind::Module,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.scope_context = Some(crate::symbol::ScopeContext::Module);
+        symbol.doc_comment = self.extract_doc_comment(&node, code).map(Into::into);
+        symbol.signature = Some(format!("namespace {name}").into());
+
+        Some(symbol)
+    }
+
+    /// Process an enum declaration node (PHP 8.1+)
+    fn process_enum(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+    ) -> Option<Symbol> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = &code[name_node.byte_range()];
+
+        let id = counter.next_id();
+
+        let mut symbol = Symbol::new(
+            id,
+            name,
+            SymbolKind::Enum,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.scope_context = Some(self.context.current_scope_context());
+        symbol.doc_comment = self.extract_doc_comment(&node, code).map(Into::into);
+
+        // Same layout as classes: header up to the body, with backing type and implements
+        let signature = self.extract_class_signature(node, code);
+        symbol.signature = Some(signature.into());
+
+        Some(symbol)
+    }
+
+    /// Process an enum case node
+    fn process_enum_case(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+    ) -> Option<Symbol> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = &code[name_node.byte_range()];
+
+        let id = counter.next_id();
+
+        let mut symbol = Symbol::new(
+            id,
+            name,
+            SymbolKind::Constant,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.scope_context = Some(self.context.current_scope_context());
+        symbol.doc_comment = self.extract_doc_comment(&node, code).map(Into::into);
+        symbol.signature = Some(code[node.byte_range()].trim_end_matches(';').into());
+
+        Some(symbol)
+    }
+
     /// Process a property declaration node
     fn process_property(
         &mut self,
@@ -745,7 +874,8 @@ impl PhpParser {
                 | "function_definition"
                 | "method_declaration"
                 | "interface_declaration"
-                | "trait_declaration" => return false,
+                | "trait_declaration"
+                | "enum_declaration" => return false,
                 "program" => return true,
                 _ => parent = p.parent(),
             }
@@ -951,6 +1081,17 @@ impl LanguageParser for PhpParser {
         implementations
     }
 
+    fn find_extends<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut extends = Vec::with_capacity(4); // PHP classes have a single parent
+        self.extract_extends_from_node(tree.root_node(), code, &mut extends);
+        extends
+    }
+
     fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
         let tree = match self.parser.parse(code, None) {
             Some(tree) => tree,
@@ -1059,21 +1200,31 @@ impl PhpParser {
         code: &'a str,
         implementations: &mut Vec<(&'a str, &'a str, Range)>,
     ) {
-        if node.kind() == "class_declaration" {
+        if matches!(
+            node.kind(),
+            "class_declaration" | "enum_declaration" | "trait_declaration"
+        ) {
             if let Some(name_node) = node.child_by_field_name("name") {
-                let class_name = &code[name_node.byte_range()];
+                let type_name = &code[name_node.byte_range()];
 
-                // Check for implements clause
+                // `implements` clause (classes and enums)
                 let mut cursor = node.walk();
                 for child in node.children(&mut cursor) {
-                    if child.kind() == "base_clause" {
-                        let mut base_cursor = child.walk();
-                        for base_child in child.children(&mut base_cursor) {
-                            if base_child.kind() == "name" {
-                                let interface_name = &code[base_child.byte_range()];
-                                let range = self.node_to_range(base_child);
-                                implementations.push((class_name, interface_name, range));
-                            }
+                    if child.kind() == "class_interface_clause" {
+                        self.collect_type_names(child, code, |interface_name, range| {
+                            implementations.push((type_name, interface_name, range));
+                        });
+                    }
+                }
+
+                // `use SomeTrait;` inside the body pulls the trait's methods in
+                if let Some(body) = node.child_by_field_name("body") {
+                    let mut body_cursor = body.walk();
+                    for member in body.children(&mut body_cursor) {
+                        if member.kind() == "use_declaration" {
+                            self.collect_type_names(member, code, |trait_name, range| {
+                                implementations.push((type_name, trait_name, range));
+                            });
                         }
                     }
                 }
@@ -1086,6 +1237,65 @@ impl PhpParser {
         }
     }
 
+    fn extract_extends_from_node<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        extends: &mut Vec<(&'a str, &'a str, Range)>,
+    ) {
+        if matches!(node.kind(), "class_declaration" | "interface_declaration") {
+            if let Some(name_node) = node.child_by_field_name("name") {
+                let type_name = &code[name_node.byte_range()];
+
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    if child.kind() == "base_clause" {
+                        self.collect_type_names(child, code, |base_name, range| {
+                            extends.push((type_name, base_name, range));
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_extends_from_node(child, code, extends);
+        }
+    }
+
+    /// Report the type names listed in an extends/implements/use clause
+    ///
+    /// Qualified names (`\App\Contracts\Cache`) are reported by their last
+    /// segment, which is the name the symbol is indexed under.
+    fn collect_type_names<'a>(
+        &self,
+        clause: Node,
+        code: &'a str,
+        mut report: impl FnMut(&'a str, Range),
+    ) {
+        let mut cursor = clause.walk();
+        for child in clause.children(&mut cursor) {
+            let name_node = match child.kind() {
+                "name" => child,
+                "qualified_name" | "relative_name" => {
+                    // The prefix is a namespace_name; the direct `name` child is the last segment
+                    let mut name_cursor = child.walk();
+                    let last = child
+                        .children(&mut name_cursor)
+                        .filter(|n| n.kind() == "name")
+                        .last();
+                    match last {
+                        Some(last) => last,
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            report(&code[name_node.byte_range()], self.node_to_range(child));
+        }
+    }
+
     fn extract_uses_from_node<'a>(
         &self,
         node: Node,
@@ -1139,14 +1349,17 @@ impl PhpParser {
         defines: &mut Vec<(&'a str, &'a str, Range)>,
     ) {
         match node.kind() {
-            "class_declaration" | "interface_declaration" | "trait_declaration" => {
+            "class_declaration"
+            | "interface_declaration"
+            | "trait_declaration"
+            | "enum_declaration" => {
                 if let Some(name_node) = node.child_by_field_name("name") {
                     let type_name = &code[name_node.byte_range()];
 
                     // Find methods within the type - they're inside declaration_list
                     let mut cursor = node.walk();
                     for child in node.children(&mut cursor) {
-                        if child.kind() == "declaration_list" {
+                        if matches!(child.kind(), "declaration_list" | "enum_declaration_list") {
                             // Methods are inside declaration_list, not direct children
                             let mut decl_cursor = child.walk();
                             for decl_child in child.children(&mut decl_cursor) {
@@ -1180,36 +1393,24 @@ impl PhpParser {
         imports: &mut Vec<Import>,
     ) {
         if node.kind() == "namespace_use_declaration" {
+            // Grouped imports share a prefix: `use App\Models\{User, Post as P};`
+            let mut prefix = None;
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                if child.kind() == "namespace_use_clause" {
-                    let mut path = String::new();
-                    let mut alias = None;
-
-                    let mut clause_cursor = child.walk();
-                    for clause_child in child.children(&mut clause_cursor) {
-                        match clause_child.kind() {
-                            "qualified_name" => {
-                                path = code[clause_child.byte_range()].to_string();
-                            }
-                            "namespace_aliasing_clause" => {
-                                if let Some(alias_node) = clause_child.child(1) {
-                                    alias = Some(code[alias_node.byte_range()].to_string());
-                                }
+                match child.kind() {
+                    "namespace_name" => prefix = Some(&code[child.byte_range()]),
+                    "namespace_use_clause" => {
+                        Self::push_use_clause(child, code, None, file_id, imports);
+                    }
+                    "namespace_use_group" => {
+                        let mut group_cursor = child.walk();
+                        for clause in child.children(&mut group_cursor) {
+                            if clause.kind() == "namespace_use_clause" {
+                                Self::push_use_clause(clause, code, prefix, file_id, imports);
                             }
-                            _ => {}
                         }
                     }
-
-                    if !path.is_empty() {
-                        imports.push(Import {
-                            path,
-                            alias,
-                            is_glob: false,
-                            file_id,
-                            is_type_only: false,
-                        });
-                    }
+                    _ => {}
                 }
             }
         }
@@ -1245,6 +1446,42 @@ impl PhpParser {
         }
     }
 
+    /// Record one `use` clause, optionally under a group prefix
+    fn push_use_clause(
+        clause: Node,
+        code: &str,
+        prefix: Option<&str>,
+        file_id: FileId,
+        imports: &mut Vec<Import>,
+    ) {
+        let mut path = None;
+        let mut cursor = clause.walk();
+        for child in clause.children(&mut cursor) {
+            // The alias is a `name` too, so only take the first name-like child
+            if path.is_none() && matches!(child.kind(), "name" | "qualified_name") {
+                path = Some(&code[child.byte_range()]);
+            }
+        }
+        let Some(path) = path else {
+            return;
+        };
+        let alias = clause
+            .child_by_field_name("alias")
+            .map(|alias| code[alias.byte_range()].to_string());
+
+        let path = match prefix {
+            Some(prefix) => format!("{}\\{path}", prefix.trim_end_matches('\\')),
+            None => path.trim_start_matches('\\').to_string(),
+        };
+        imports.push(Import {
+            path,
+            alias,
+            is_glob: false,
+            file_id,
+            is_type_only: false,
+        });
+    }
+
     fn extract_variable_types_from_node<'a>(
         &self,
         node: Node,
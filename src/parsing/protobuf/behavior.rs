@@ -0,0 +1,163 @@
+//! Protobuf-specific language behavior implementation
+
+use crate::parsing::Import;
+use crate::parsing::LanguageBehavior;
+use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::{FileId, Symbol, SymbolKind, Visibility};
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Language behavior for Protocol Buffers
+#[derive(Clone)]
+pub struct ProtobufBehavior {
+    language: Language,
+    state: BehaviorState,
+}
+
+impl ProtobufBehavior {
+    /// Create a new behavior instance
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_proto::LANGUAGE.into(),
+            state: BehaviorState::new(),
+        }
+    }
+}
+
+impl StatefulBehavior for ProtobufBehavior {
+    fn state(&self) -> &BehaviorState {
+        &self.state
+    }
+}
+
+impl Default for ProtobufBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageBehavior for ProtobufBehavior {
+    fn language_id(&self) -> crate::parsing::registry::LanguageId {
+        crate::parsing::registry::LanguageId::new("protobuf")
+    }
+
+    fn configure_symbol(&self, symbol: &mut Symbol, module_path: Option<&str>) {
+        if let Some(path) = module_path {
+            let full_path = self.format_module_path(path, &symbol.name);
+            symbol.module_path = Some(full_path.into());
+        }
+
+        // Proto has no access modifiers; anything a file declares can be
+        // referenced by every file that imports it.
+        symbol.visibility = Visibility::Public;
+    }
+
+    fn format_module_path(&self, base_path: &str, _symbol_name: &str) -> String {
+        base_path.to_string()
+    }
+
+    fn parse_visibility(&self, _signature: &str) -> Visibility {
+        Visibility::Public
+    }
+
+    fn module_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        let relative = file_path.strip_prefix(project_root).unwrap_or(file_path);
+        let path = relative.with_extension("");
+        let module = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .filter(|segment| !segment.is_empty() && segment != ".")
+            .collect::<Vec<_>>()
+            .join(".");
+
+        if module.is_empty() {
+            None
+        } else {
+            Some(module)
+        }
+    }
+
+    fn get_language(&self) -> Language {
+        self.language.clone()
+    }
+
+    // Override import tracking methods to use state
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.register_file_with_state(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.add_import_with_state(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.get_imports_from_state(file_id)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.state.get_module_path(file_id)
+    }
+
+    fn get_file_path(&self, file_id: FileId) -> Option<PathBuf> {
+        self.state.get_file_path(file_id)
+    }
+
+    fn is_resolvable_symbol(&self, symbol: &Symbol) -> bool {
+        matches!(
+            symbol.kind,
+            SymbolKind::Struct
+                | SymbolKind::Enum
+                | SymbolKind::Interface
+                | SymbolKind::Method
+                | SymbolKind::Field
+                | SymbolKind::Constant
+        )
+    }
+
+    fn is_symbol_visible_from_file(&self, _symbol: &Symbol, _from_file: FileId) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_path_from_file() {
+        let behavior = ProtobufBehavior::new();
+        let root = Path::new("/project");
+
+        assert_eq!(
+            behavior
+                .module_path_from_file(Path::new("/project/proto/greeter/v1/greeter.proto"), root),
+            Some("proto.greeter.v1.greeter".to_string())
+        );
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/project/types.proto"), root),
+            Some("types".to_string())
+        );
+    }
+
+    #[test]
+    fn test_all_definitions_are_public() {
+        let behavior = ProtobufBehavior::new();
+        let mut symbol = Symbol::new(
+            crate::SymbolId::new(1).unwrap(),
+            "HelloRequest",
+            SymbolKind::Struct,
+            FileId::new(1).unwrap(),
+            crate::Range::new(0, 0, 3, 2),
+        );
+        symbol.visibility = Visibility::Private;
+
+        behavior.configure_symbol(&mut symbol, Some("greeter"));
+
+        assert_eq!(symbol.visibility, Visibility::Public);
+        assert_eq!(symbol.module_path.as_deref(), Some("greeter"));
+    }
+}
@@ -0,0 +1,79 @@
+//! Protobuf language definition for the registry
+//!
+//! Provides the language metadata and glue code used by the language registry
+//! to instantiate parsers and behaviors for `.proto` IDL files.
+
+use std::sync::Arc;
+
+use super::{ProtobufBehavior, ProtobufParser};
+use crate::parsing::{LanguageBehavior, LanguageDefinition, LanguageId, LanguageParser};
+use crate::{IndexError, IndexResult, Settings};
+
+/// Language definition for Protocol Buffers
+pub struct ProtobufLanguage;
+
+impl ProtobufLanguage {
+    /// Stable identifier used throughout the registry
+    pub const ID: LanguageId = LanguageId::new("protobuf");
+}
+
+impl LanguageDefinition for ProtobufLanguage {
+    fn id(&self) -> LanguageId {
+        Self::ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Protobuf"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["proto"]
+    }
+
+    fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
+        let parser = ProtobufParser::new().map_err(IndexError::General)?;
+        Ok(Box::new(parser))
+    }
+
+    fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
+        Box::new(ProtobufBehavior::new())
+    }
+
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings
+            .languages
+            .get(self.id().as_str())
+            .map(|config| config.enabled)
+            .unwrap_or(self.default_enabled())
+    }
+}
+
+/// Register Protobuf language with the global registry
+pub(crate) fn register(registry: &mut crate::parsing::LanguageRegistry) {
+    registry.register(Arc::new(ProtobufLanguage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_metadata() {
+        let lang = ProtobufLanguage;
+
+        assert_eq!(lang.id(), LanguageId::new("protobuf"));
+        assert_eq!(lang.name(), "Protobuf");
+        assert_eq!(lang.extensions(), &["proto"]);
+    }
+
+    #[test]
+    fn test_parser_creation() {
+        let lang = ProtobufLanguage;
+        let settings = Settings::default();
+        assert!(lang.create_parser(&settings).is_ok());
+    }
+}
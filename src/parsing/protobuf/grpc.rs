@@ -0,0 +1,155 @@
+//! gRPC code generation naming conventions
+//!
+//! Handler code never names a `.proto` service directly. It implements or
+//! extends a *generated* type whose name is derived from the service, and the
+//! generated stubs usually live in build output that is not indexed. These
+//! helpers map generated names back to the service and match handler methods
+//! to RPCs, so resolution can link handlers across the IDL boundary.
+//!
+//! | Generator | Type a handler references | Handler method |
+//! |-----------|---------------------------|----------------|
+//! | tonic (Rust) | `greeter_server::Greeter` | `say_hello` |
+//! | protoc-gen-go-grpc | `UnimplementedGreeterServer` | `SayHello` |
+//! | grpc-java | `GreeterGrpc.GreeterImplBase` | `sayHello` |
+//! | Grpc.Tools (C#) | `Greeter.GreeterBase` | `SayHello` |
+//! | grpcio-tools (Python) | `GreeterServicer` | `SayHello` |
+
+use super::ProtobufLanguage;
+use crate::{Symbol, SymbolKind};
+
+/// Prefixes Go generators put in front of the service name
+const GENERATED_PREFIXES: &[&str] = &["Unimplemented", "Unsafe"];
+
+/// Suffixes generators append to the service name, longest first
+const GENERATED_SUFFIXES: &[&str] = &["ImplBase", "Servicer", "Server", "Base"];
+
+/// Service names a generated type may refer to, most specific first
+///
+/// The unqualified name itself comes first (tonic keeps the service name for
+/// its trait), followed by the name with generator prefixes and suffixes removed.
+pub fn service_candidates(generated: &str) -> Vec<&str> {
+    let name = generated
+        .rsplit(['.', ':', '/'])
+        .next()
+        .unwrap_or(generated)
+        .trim();
+    if name.is_empty() {
+        return Vec::new();
+    }
+
+    let mut candidates = vec![name];
+
+    let mut stripped = name;
+    for prefix in GENERATED_PREFIXES {
+        if let Some(rest) = stripped.strip_prefix(prefix) {
+            stripped = rest;
+            break;
+        }
+    }
+    for suffix in GENERATED_SUFFIXES {
+        if let Some(rest) = stripped.strip_suffix(suffix) {
+            stripped = rest;
+            break;
+        }
+    }
+
+    if !stripped.is_empty() && stripped != name {
+        candidates.push(stripped);
+    }
+    candidates
+}
+
+/// Whether a handler method name is the generated form of an RPC name
+///
+/// Generators only change case and word separators: `SayHello` becomes
+/// `say_hello` in Rust and `sayHello` in Java.
+pub fn rpc_matches_method(rpc: &str, method: &str) -> bool {
+    let mut rpc_chars = rpc.chars().filter(|c| *c != '_');
+    let mut method_chars = method.chars().filter(|c| *c != '_');
+    loop {
+        match (rpc_chars.next(), method_chars.next()) {
+            (None, None) => return true,
+            (Some(a), Some(b)) if a.eq_ignore_ascii_case(&b) => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Type a Go method is declared on, from its signature
+///
+/// `func (s *server) SayHello(...)` gives `server`; functions without a
+/// receiver give `None`.
+pub fn go_receiver_type(signature: &str) -> Option<&str> {
+    let receiver = signature
+        .strip_prefix("func")?
+        .trim_start()
+        .strip_prefix('(')?;
+    let receiver = &receiver[..receiver.find(')')?];
+    // Type parameters may contain spaces: `(c *cache[K, V])`
+    let receiver = receiver.split('[').next()?;
+    let ty = receiver.split_whitespace().last()?.trim_start_matches('*');
+    (!ty.is_empty()).then_some(ty)
+}
+
+/// Whether `symbol` is a service declared in a `.proto` file
+pub fn is_proto_service(symbol: &Symbol) -> bool {
+    symbol.kind == SymbolKind::Interface && symbol.language_id == Some(ProtobufLanguage::ID)
+}
+
+/// Whether `symbol` is an RPC declared in a `.proto` file
+pub fn is_proto_rpc(symbol: &Symbol) -> bool {
+    symbol.kind == SymbolKind::Method && symbol.language_id == Some(ProtobufLanguage::ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_candidates() {
+        assert_eq!(
+            service_candidates("greeter_server::Greeter"),
+            vec!["Greeter"]
+        );
+        assert_eq!(
+            service_candidates("pb.UnimplementedGreeterServer"),
+            vec!["UnimplementedGreeterServer", "Greeter"]
+        );
+        assert_eq!(
+            service_candidates("GreeterGrpc.GreeterImplBase"),
+            vec!["GreeterImplBase", "Greeter"]
+        );
+        assert_eq!(
+            service_candidates("Greeter.GreeterBase"),
+            vec!["GreeterBase", "Greeter"]
+        );
+        assert_eq!(
+            service_candidates("GreeterServicer"),
+            vec!["GreeterServicer", "Greeter"]
+        );
+        assert!(service_candidates("").is_empty());
+    }
+
+    #[test]
+    fn test_rpc_matches_method() {
+        assert!(rpc_matches_method("SayHello", "say_hello"));
+        assert!(rpc_matches_method("SayHello", "sayHello"));
+        assert!(rpc_matches_method("SayHello", "SayHello"));
+        assert!(!rpc_matches_method("SayHello", "say_hello_again"));
+        assert!(!rpc_matches_method("SayHello", "hello"));
+    }
+
+    #[test]
+    fn test_go_receiver_type() {
+        assert_eq!(
+            go_receiver_type("func (s *server) SayHello(ctx context.Context) error"),
+            Some("server")
+        );
+        assert_eq!(go_receiver_type("func (server) Ping()"), Some("server"));
+        assert_eq!(
+            go_receiver_type("func (c *cache[K, V]) Get(k K) V"),
+            Some("cache")
+        );
+        assert_eq!(go_receiver_type("func SayHello(ctx context.Context)"), None);
+    }
+}
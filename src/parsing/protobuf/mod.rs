@@ -0,0 +1,15 @@
+//! Protocol Buffers (.proto) parser implementation
+
+pub mod audit;
+pub mod behavior;
+pub mod definition;
+pub mod grpc;
+pub mod parser;
+
+pub use audit::ProtobufParserAudit;
+pub use behavior::ProtobufBehavior;
+pub use definition::ProtobufLanguage;
+pub use parser::ProtobufParser;
+
+// Re-export for registry registration
+pub(crate) use definition::register;
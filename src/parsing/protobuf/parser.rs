@@ -0,0 +1,441 @@
+//! Protocol Buffers parser implementation
+//!
+//! Extracts messages, fields, enums, services and RPCs from `.proto` files
+//! using tree-sitter-proto. Field types and RPC request/response types become
+//! `Uses` edges so impact analysis follows a message through every service that
+//! carries it; services define their RPCs and messages define their fields.
+//!
+//! Qualified type references (`google.protobuf.Timestamp`, `.pkg.Msg`) are
+//! reduced to their last segment, matching how the definitions are named.
+//! Handler code in other languages is linked to services during resolution,
+//! see [`super::grpc`].
+
+use crate::parsing::Import;
+use crate::parsing::parser::{check_recursion_depth, collapse_whitespace};
+use crate::parsing::{HandledNode, Language, LanguageParser, NodeTracker, NodeTrackingState};
+use crate::symbol::ScopeContext;
+use crate::types::{SymbolCounter, compact_string};
+use crate::{FileId, Range, Symbol, SymbolKind};
+use std::any::Any;
+use tree_sitter::{Node, Parser};
+
+/// Node kinds that hold the name of their parent definition
+const NAME_NODES: &[&str] = &[
+    "message_name",
+    "enum_name",
+    "service_name",
+    "rpc_name",
+    "identifier",
+];
+
+/// Parser for Protocol Buffers IDL files
+pub struct ProtobufParser {
+    parser: Parser,
+    node_tracker: NodeTrackingState,
+}
+
+impl std::fmt::Debug for ProtobufParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProtobufParser")
+            .field("language", &"Protobuf")
+            .finish()
+    }
+}
+
+impl ProtobufParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_proto::LANGUAGE.into())
+            .map_err(|e| format!("Failed to initialize Protobuf parser: {e}"))?;
+
+        Ok(Self {
+            parser,
+            node_tracker: NodeTrackingState::new(),
+        })
+    }
+
+    /// Convert a tree-sitter node into a Range
+    fn node_to_range(&self, node: Node) -> Range {
+        let start = node.start_position();
+        let end = node.end_position();
+        Range {
+            start_line: start.row as u32,
+            start_column: start.column as u16,
+            end_line: end.row as u32,
+            end_column: end.column as u16,
+        }
+    }
+
+    /// Helper to register handled node kinds for audit tracking
+    fn register_node(&mut self, node: &Node) {
+        self.node_tracker
+            .register_handled_node(node.kind(), node.kind_id());
+    }
+
+    /// Extract raw source text for a node
+    fn text_for_node<'a>(&self, code: &'a str, node: Node) -> &'a str {
+        &code[node.byte_range()]
+    }
+
+    /// Name of a message, enum, service, RPC, field or enum value
+    fn definition_name<'a>(&self, node: Node, code: &'a str) -> Option<&'a str> {
+        let mut cursor = node.walk();
+        let name_node = node
+            .named_children(&mut cursor)
+            .find(|child| NAME_NODES.contains(&child.kind()))?;
+        let name = self.text_for_node(code, name_node).trim();
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Unqualified name of a `message_or_enum_type` (`google.protobuf.Timestamp` -> `Timestamp`)
+    fn type_name<'a>(&self, type_node: Node, code: &'a str) -> Option<&'a str> {
+        let mut cursor = type_node.walk();
+        let last = type_node
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "identifier")
+            .last();
+        let text = match last {
+            Some(identifier) => self.text_for_node(code, identifier),
+            None => self
+                .text_for_node(code, type_node)
+                .rsplit('.')
+                .next()
+                .unwrap_or_default(),
+        };
+        let name = text.trim();
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Extract `//` and `/* */` comments directly above a definition
+    fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
+        let mut comments = Vec::new();
+        let mut current = node.prev_sibling();
+        let mut expected_row = node.start_position().row;
+
+        while let Some(sibling) = current {
+            if sibling.kind() != "comment" {
+                break;
+            }
+            if sibling.end_position().row + 1 < expected_row {
+                break;
+            }
+
+            let raw = self.text_for_node(code, sibling).trim();
+            if let Some(rest) = raw.strip_prefix("//") {
+                comments.push(rest.trim_start_matches('/').trim().to_string());
+            } else if raw.starts_with("/*") {
+                let cleaned = raw
+                    .trim_start_matches("/*")
+                    .trim_end_matches("*/")
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches('*').trim())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                comments.push(cleaned);
+            } else {
+                break;
+            }
+
+            expected_row = sibling.start_position().row;
+            current = sibling.prev_sibling();
+        }
+
+        if comments.is_empty() {
+            None
+        } else {
+            comments.reverse();
+            Some(comments.join("\n"))
+        }
+    }
+
+    /// Signature: the definition's header, stopping before any body
+    fn signature_for(&self, node: Node, code: &str) -> String {
+        let text = self.text_for_node(code, node);
+        let header = text.split(['{', ';']).next().unwrap_or(text);
+        crate::parsing::truncate_for_display(&collapse_whitespace(header), 200)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn make_symbol(
+        &self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+        name: &str,
+        kind: SymbolKind,
+        parent: Option<&str>,
+    ) -> Symbol {
+        let mut symbol = Symbol::new(
+            counter.next_id(),
+            name,
+            kind,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.signature = Some(self.signature_for(node, code).into());
+        if let Some(doc) = self.doc_comment_for(&node, code) {
+            symbol.doc_comment = Some(doc.into());
+        }
+        symbol.scope_context = Some(match parent {
+            Some(parent) => ScopeContext::ClassMember {
+                class_name: Some(compact_string(parent)),
+            },
+            None => ScopeContext::Module,
+        });
+        symbol
+    }
+
+    /// Process AST recursively and collect symbols
+    #[allow(clippy::too_many_arguments)]
+    fn extract_symbols_from_node<'a>(
+        &mut self,
+        node: Node,
+        code: &'a str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+        parent: Option<&'a str>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let kind = match node.kind() {
+            "message" => Some(SymbolKind::Struct),
+            "enum" => Some(SymbolKind::Enum),
+            "service" => Some(SymbolKind::Interface),
+            "rpc" => Some(SymbolKind::Method),
+            "field" | "oneof_field" | "map_field" => Some(SymbolKind::Field),
+            "enum_field" => Some(SymbolKind::Constant),
+            "package" | "import" | "comment" | "message_body" | "enum_body" | "oneof" => {
+                self.register_node(&node);
+                None
+            }
+            _ => None,
+        };
+
+        let mut next_parent = parent;
+        if let Some(kind) = kind {
+            self.register_node(&node);
+            if let Some(name) = self.definition_name(node, code) {
+                symbols.push(self.make_symbol(node, code, file_id, counter, name, kind, parent));
+                if matches!(
+                    kind,
+                    SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Interface
+                ) {
+                    next_parent = Some(name);
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_symbols_from_node(
+                child,
+                code,
+                file_id,
+                symbols,
+                counter,
+                next_parent,
+                depth + 1,
+            );
+        }
+    }
+
+    /// Field types and RPC signatures reference messages and enums
+    fn collect_uses<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        uses: &mut Vec<(&'a str, &'a str, Range)>,
+        owner: Option<&'a str>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let mut next_owner = owner;
+        if matches!(node.kind(), "message" | "rpc") {
+            next_owner = self.definition_name(node, code).or(owner);
+        }
+
+        if node.kind() == "message_or_enum_type" {
+            if let (Some(source), Some(target)) = (owner, self.type_name(node, code)) {
+                if source != target {
+                    uses.push((source, target, self.node_to_range(node)));
+                }
+            }
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_uses(child, code, uses, next_owner, depth + 1);
+        }
+    }
+
+    /// Services define RPCs, messages define fields, enums define values
+    fn collect_defines<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        defines: &mut Vec<(&'a str, &'a str, Range)>,
+        container: Option<&'a str>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let mut next_container = container;
+        match node.kind() {
+            "message" | "enum" | "service" => {
+                next_container = self.definition_name(node, code);
+            }
+            "rpc" | "field" | "oneof_field" | "map_field" | "enum_field" => {
+                if let (Some(container), Some(member)) =
+                    (container, self.definition_name(node, code))
+                {
+                    defines.push((container, member, self.node_to_range(node)));
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_defines(child, code, defines, next_container, depth + 1);
+        }
+    }
+
+    /// Access handled nodes for audit tooling
+    pub fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+}
+
+impl LanguageParser for ProtobufParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut symbols = Vec::new();
+        self.extract_symbols_from_node(
+            tree.root_node(),
+            code,
+            file_id,
+            &mut symbols,
+            symbol_counter,
+            None,
+            0,
+        );
+        symbols
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
+        self.doc_comment_for(node, code)
+    }
+
+    fn find_calls<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        // IDL only declares RPCs; calls happen in generated client code
+        Vec::new()
+    }
+
+    fn find_implementations<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut uses = Vec::new();
+        self.collect_uses(tree.root_node(), code, &mut uses, None, 0);
+        uses
+    }
+
+    fn find_defines<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut defines = Vec::new();
+        self.collect_defines(tree.root_node(), code, &mut defines, None, 0);
+        defines
+    }
+
+    fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<Import> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut imports = Vec::new();
+        let mut cursor = root.walk();
+        for node in root.named_children(&mut cursor) {
+            if node.kind() != "import" {
+                continue;
+            }
+            let path_node = node.child_by_field_name("path").or_else(|| {
+                let mut inner = node.walk();
+                node.named_children(&mut inner)
+                    .find(|child| child.kind() == "string")
+            });
+            let Some(path_node) = path_node else {
+                continue;
+            };
+            let path = self
+                .text_for_node(code, path_node)
+                .trim_matches(|c| c == '"' || c == '\'');
+            if path.is_empty() {
+                continue;
+            }
+
+            // An import makes every definition of the imported file visible
+            imports.push(Import {
+                file_id,
+                path: path.to_string(),
+                alias: None,
+                is_glob: true,
+                is_type_only: false,
+            });
+        }
+        imports
+    }
+
+    fn language(&self) -> Language {
+        Language::Protobuf
+    }
+}
+
+impl NodeTracker for ProtobufParser {
+    fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+
+    fn register_handled_node(&mut self, node_kind: &str, node_id: u16) {
+        self.node_tracker.register_handled_node(node_kind, node_id);
+    }
+}
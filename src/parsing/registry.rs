@@ -160,6 +160,14 @@ pub trait LanguageDefinition: Send + Sync {
             .map(|config| config.enabled)
             .unwrap_or(false)
     }
+
+    /// Whether files are parsed with the behavior's tree-sitter grammar
+    ///
+    /// Frontends that read files another way return false so grammar-based
+    /// tooling (parse diagnostics, user queries) skips their files.
+    fn has_grammar(&self) -> bool {
+        true
+    }
 }
 
 /// Language registry that manages available and enabled languages
@@ -383,6 +391,13 @@ fn initialize_registry(registry: &mut LanguageRegistry) {
     super::java::register(registry);
     super::kotlin::register(registry);
     super::swift::register(registry);
+    super::lua::register(registry);
+    super::sql::register(registry);
+    super::protobuf::register(registry);
+    super::hcl::register(registry);
+    super::objc::register(registry);
+    super::config::register(registry);
+    super::vue::register(registry);
 }
 
 /// Get the global registry
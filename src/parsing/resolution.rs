@@ -8,6 +8,7 @@ use super::context::ScopeType;
 use crate::types::Range;
 use crate::{FileId, SymbolId, parsing::Import};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Scope levels that work across all languages
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -310,6 +311,10 @@ pub trait ResolutionScope: Send + Sync {
                 // Reverse of References - also permissive
                 true
             }
+            Mirrors => {
+                // Cross-language API links join executable code on both ends
+                matches!(from_kind, Function | Method) && matches!(to_kind, Function | Method)
+            }
         }
     }
 }
@@ -607,14 +612,14 @@ pub struct CallerContext {
     /// File where the call/reference originates
     pub file_id: FileId,
     /// Module path of the calling symbol (for same-module visibility check)
-    pub module_path: Option<Box<str>>,
+    pub module_path: Option<Arc<str>>,
     /// Language of the calling code (for cross-language filtering)
     pub language_id: LanguageId,
 }
 
 impl CallerContext {
     /// Create caller context with explicit values.
-    pub fn new(file_id: FileId, module_path: Option<Box<str>>, language_id: LanguageId) -> Self {
+    pub fn new(file_id: FileId, module_path: Option<Arc<str>>, language_id: LanguageId) -> Self {
         Self {
             file_id,
             module_path,
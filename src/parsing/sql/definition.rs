@@ -0,0 +1,79 @@
+//! SQL language definition for the registry
+//!
+//! Provides the language metadata and glue code used by the language registry
+//! to instantiate parsers and behaviors for SQL schema and migration files.
+
+use std::sync::Arc;
+
+use super::{SqlBehavior, SqlParser};
+use crate::parsing::{LanguageBehavior, LanguageDefinition, LanguageId, LanguageParser};
+use crate::{IndexError, IndexResult, Settings};
+
+/// Language definition for SQL
+pub struct SqlLanguage;
+
+impl SqlLanguage {
+    /// Stable identifier used throughout the registry
+    pub const ID: LanguageId = LanguageId::new("sql");
+}
+
+impl LanguageDefinition for SqlLanguage {
+    fn id(&self) -> LanguageId {
+        Self::ID
+    }
+
+    fn name(&self) -> &'static str {
+        "SQL"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sql"]
+    }
+
+    fn create_parser(&self, _settings: &Settings) -> IndexResult<Box<dyn LanguageParser>> {
+        let parser = SqlParser::new().map_err(IndexError::General)?;
+        Ok(Box::new(parser))
+    }
+
+    fn create_behavior(&self) -> Box<dyn LanguageBehavior> {
+        Box::new(SqlBehavior::new())
+    }
+
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings
+            .languages
+            .get(self.id().as_str())
+            .map(|config| config.enabled)
+            .unwrap_or(self.default_enabled())
+    }
+}
+
+/// Register SQL language with the global registry
+pub(crate) fn register(registry: &mut crate::parsing::LanguageRegistry) {
+    registry.register(Arc::new(SqlLanguage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_metadata() {
+        let lang = SqlLanguage;
+
+        assert_eq!(lang.id(), LanguageId::new("sql"));
+        assert_eq!(lang.name(), "SQL");
+        assert_eq!(lang.extensions(), &["sql"]);
+    }
+
+    #[test]
+    fn test_parser_creation() {
+        let lang = SqlLanguage;
+        let settings = Settings::default();
+        assert!(lang.create_parser(&settings).is_ok());
+    }
+}
@@ -0,0 +1,14 @@
+//! SQL DDL parser implementation
+
+pub mod audit;
+pub mod behavior;
+pub mod definition;
+pub mod parser;
+
+pub use audit::SqlParserAudit;
+pub use behavior::SqlBehavior;
+pub use definition::SqlLanguage;
+pub use parser::SqlParser;
+
+// Re-export for registry registration
+pub(crate) use definition::register;
@@ -0,0 +1,771 @@
+//! SQL DDL parser implementation
+//!
+//! Extracts tables, columns, views, functions, stored procedures and user types
+//! from schema and migration files using tree-sitter-sequel. Foreign keys and
+//! table references inside views and routine bodies become `Uses` edges;
+//! invocations inside routine bodies become `Calls` edges.
+//!
+//! Quoting is stripped from identifiers (`"users"`, `` `users` ``, `[users]`)
+//! and schema qualifiers are dropped, so `public.users` in one migration
+//! resolves to `users` created in another. Dialect statements the grammar
+//! cannot parse (notably `CREATE PROCEDURE`) are recovered from `ERROR` nodes
+//! with a keyword scan.
+
+use crate::parsing::Import;
+use crate::parsing::parser::{check_recursion_depth, collapse_whitespace};
+use crate::parsing::{HandledNode, Language, LanguageParser, NodeTracker, NodeTrackingState};
+use crate::symbol::ScopeContext;
+use crate::types::{SymbolCounter, compact_string};
+use crate::{FileId, Range, Symbol, SymbolKind};
+use std::any::Any;
+use tree_sitter::{Node, Parser};
+
+/// Parent node kinds whose `object_reference` child names a table
+const TABLE_REFERENCE_PARENTS: &[&str] =
+    &["relation", "insert", "update", "delete", "from", "truncate"];
+
+/// Parser for SQL schema and migration files
+pub struct SqlParser {
+    parser: Parser,
+    node_tracker: NodeTrackingState,
+}
+
+impl std::fmt::Debug for SqlParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlParser")
+            .field("language", &"SQL")
+            .finish()
+    }
+}
+
+/// Kind of object a `CREATE` statement defines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlObject {
+    Table,
+    View,
+    Function,
+    Procedure,
+    Type,
+}
+
+impl SqlObject {
+    /// Classify a tree-sitter node kind
+    fn from_node_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "create_table" => Some(Self::Table),
+            "create_view" | "create_materialized_view" => Some(Self::View),
+            "create_function" => Some(Self::Function),
+            "create_procedure" => Some(Self::Procedure),
+            "create_type" => Some(Self::Type),
+            _ => None,
+        }
+    }
+
+    /// Classify the object keyword following `CREATE` in raw text
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword.to_ascii_lowercase().as_str() {
+            "table" => Some(Self::Table),
+            "view" => Some(Self::View),
+            "function" => Some(Self::Function),
+            "procedure" | "proc" => Some(Self::Procedure),
+            _ => None,
+        }
+    }
+
+    fn symbol_kind(self) -> SymbolKind {
+        match self {
+            Self::Table | Self::View => SymbolKind::Struct,
+            Self::Function | Self::Procedure => SymbolKind::Function,
+            Self::Type => SymbolKind::TypeAlias,
+        }
+    }
+
+    /// Whether invocations inside the body are call edges
+    fn is_routine(self) -> bool {
+        matches!(self, Self::Function | Self::Procedure)
+    }
+}
+
+impl SqlParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_sequel::LANGUAGE.into())
+            .map_err(|e| format!("Failed to initialize SQL parser: {e}"))?;
+
+        Ok(Self {
+            parser,
+            node_tracker: NodeTrackingState::new(),
+        })
+    }
+
+    /// Convert a tree-sitter node into a Range
+    fn node_to_range(&self, node: Node) -> Range {
+        let start = node.start_position();
+        let end = node.end_position();
+        Range {
+            start_line: start.row as u32,
+            start_column: start.column as u16,
+            end_line: end.row as u32,
+            end_column: end.column as u16,
+        }
+    }
+
+    /// Helper to register handled node kinds for audit tracking
+    fn register_node(&mut self, node: &Node) {
+        self.node_tracker
+            .register_handled_node(node.kind(), node.kind_id());
+    }
+
+    /// Extract raw source text for a node
+    fn text_for_node<'a>(&self, code: &'a str, node: Node) -> &'a str {
+        &code[node.byte_range()]
+    }
+
+    /// Name of an `object_reference` (`public.users` -> `users`)
+    fn object_name<'a>(&self, reference: Node, code: &'a str) -> Option<&'a str> {
+        let name_node = reference.child_by_field_name("name").or_else(|| {
+            let mut cursor = reference.walk();
+            reference
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() == "identifier")
+                .last()
+        })?;
+        let name = unquote_identifier(self.text_for_node(code, name_node));
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// The `object_reference` naming the object a `CREATE` statement defines
+    fn defined_reference<'tree>(&self, node: Node<'tree>) -> Option<Node<'tree>> {
+        let mut cursor = node.walk();
+        node.named_children(&mut cursor)
+            .find(|child| child.kind() == "object_reference")
+    }
+
+    /// Extract `--` / `/* */` comments directly above a statement
+    fn doc_comment_for(&self, node: &Node, code: &str) -> Option<String> {
+        // Comments are siblings of the wrapping `statement`, not the CREATE node
+        let anchor = match node.parent() {
+            Some(parent) if parent.kind() == "statement" => parent,
+            _ => *node,
+        };
+
+        let mut comments = Vec::new();
+        let mut current = anchor.prev_sibling();
+        let mut expected_row = anchor.start_position().row;
+
+        while let Some(sibling) = current {
+            if !matches!(sibling.kind(), "comment" | "marginalia") {
+                break;
+            }
+            if sibling.end_position().row + 1 < expected_row {
+                break;
+            }
+
+            let raw = self.text_for_node(code, sibling).trim();
+            if let Some(rest) = raw.strip_prefix("--") {
+                comments.push(rest.trim().to_string());
+            } else if raw.starts_with("/*") {
+                let cleaned = raw
+                    .trim_start_matches("/*")
+                    .trim_end_matches("*/")
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches('*').trim())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                comments.push(cleaned);
+            } else {
+                break;
+            }
+
+            expected_row = sibling.start_position().row;
+            current = sibling.prev_sibling();
+        }
+
+        if comments.is_empty() {
+            None
+        } else {
+            comments.reverse();
+            Some(comments.join("\n"))
+        }
+    }
+
+    /// Signature for a CREATE statement: header up to the body, whitespace collapsed
+    fn create_signature(&self, node: Node, code: &str, object: SqlObject) -> String {
+        let end = match object {
+            SqlObject::Function | SqlObject::Procedure => {
+                find_descendant(node, "function_body").map(|body| body.start_byte())
+            }
+            SqlObject::View => find_descendant(node, "create_query").map(|q| q.start_byte()),
+            SqlObject::Table | SqlObject::Type => None,
+        }
+        .unwrap_or(node.end_byte());
+
+        let header = collapse_whitespace(&code[node.start_byte()..end]);
+        crate::parsing::truncate_for_display(header.trim_end_matches(';'), 200)
+    }
+
+    /// Process AST recursively and collect symbols
+    fn extract_symbols_from_node(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if let Some(object) = SqlObject::from_node_kind(node.kind()) {
+            self.handle_create(node, code, file_id, symbols, counter, object);
+            return;
+        }
+
+        match node.kind() {
+            "ERROR" => {
+                self.register_node(&node);
+                self.recover_from_error(node, code, file_id, symbols, counter);
+                return;
+            }
+            "statement" | "comment" | "marginalia" => self.register_node(&node),
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_symbols_from_node(child, code, file_id, symbols, counter, depth + 1);
+        }
+    }
+
+    fn handle_create(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+        object: SqlObject,
+    ) {
+        self.register_node(&node);
+
+        let Some(reference) = self.defined_reference(node) else {
+            return;
+        };
+        self.register_node(&reference);
+        let Some(name) = self.object_name(reference, code) else {
+            return;
+        };
+
+        let kind = if object == SqlObject::Type && find_descendant(node, "keyword_enum").is_some() {
+            SymbolKind::Enum
+        } else {
+            object.symbol_kind()
+        };
+
+        let mut symbol = Symbol::new(
+            counter.next_id(),
+            name,
+            kind,
+            file_id,
+            self.node_to_range(node),
+        );
+        symbol.signature = Some(self.create_signature(node, code, object).into());
+        if let Some(doc) = self.doc_comment_for(&node, code) {
+            symbol.doc_comment = Some(doc.into());
+        }
+        symbol.scope_context = Some(ScopeContext::Module);
+        symbols.push(symbol);
+
+        if object == SqlObject::Table {
+            self.extract_columns(node, code, file_id, symbols, counter, name);
+        }
+    }
+
+    /// Columns of a `CREATE TABLE` become fields of the table
+    fn extract_columns(
+        &mut self,
+        table_node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+        table: &str,
+    ) {
+        let Some(definitions) = find_descendant(table_node, "column_definitions") else {
+            return;
+        };
+        self.register_node(&definitions);
+
+        let mut cursor = definitions.walk();
+        for column in definitions.named_children(&mut cursor) {
+            if column.kind() != "column_definition" {
+                continue;
+            }
+            self.register_node(&column);
+
+            let name_node = column
+                .child_by_field_name("name")
+                .or_else(|| column.named_child(0));
+            let Some(name_node) = name_node else {
+                continue;
+            };
+            let name = unquote_identifier(self.text_for_node(code, name_node));
+            if name.is_empty() {
+                continue;
+            }
+
+            let mut symbol = Symbol::new(
+                counter.next_id(),
+                name,
+                SymbolKind::Field,
+                file_id,
+                self.node_to_range(column),
+            );
+            symbol.signature = Some(collapse_whitespace(self.text_for_node(code, column)).into());
+            symbol.scope_context = Some(ScopeContext::ClassMember {
+                class_name: Some(compact_string(table)),
+            });
+            symbols.push(symbol);
+        }
+    }
+
+    /// Recover `CREATE [OR REPLACE|OR ALTER] PROCEDURE name` and friends from
+    /// statements the grammar could not parse
+    fn recover_from_error(
+        &mut self,
+        node: Node,
+        code: &str,
+        file_id: FileId,
+        symbols: &mut Vec<Symbol>,
+        counter: &mut SymbolCounter,
+    ) {
+        let text = self.text_for_node(code, node);
+        for (offset, object, name) in scan_create_statements(text) {
+            let start = node.start_byte() + offset;
+            let line = code[..start].matches('\n').count() as u32;
+            let line_start = code[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let statement_end = text[offset..]
+                .find(';')
+                .map(|end| start + end)
+                .unwrap_or(node.end_byte());
+            let end_line = code[..statement_end].matches('\n').count() as u32;
+
+            let range = Range {
+                start_line: line,
+                start_column: (start - line_start) as u16,
+                end_line,
+                end_column: 0,
+            };
+
+            let header = text[offset..]
+                .lines()
+                .next()
+                .map(collapse_whitespace)
+                .unwrap_or_default();
+
+            let mut symbol = Symbol::new(
+                counter.next_id(),
+                name,
+                object.symbol_kind(),
+                file_id,
+                range,
+            );
+            symbol.signature = Some(crate::parsing::truncate_for_display(&header, 200).into());
+            symbol.scope_context = Some(ScopeContext::Module);
+            symbols.push(symbol);
+        }
+    }
+
+    /// Walk definitions and collect `Uses` edges to referenced tables
+    fn collect_uses<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        uses: &mut Vec<(&'a str, &'a str, Range)>,
+        owner: Option<(&'a str, Node)>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let mut next_owner = owner;
+        if SqlObject::from_node_kind(node.kind()).is_some() || node.kind() == "alter_table" {
+            if let Some(reference) = self.defined_reference(node) {
+                if let Some(name) = self.object_name(reference, code) {
+                    next_owner = Some((name, reference));
+                }
+            }
+        }
+
+        if let Some((source, defining)) = owner {
+            if node.kind() == "object_reference" && node != defining && is_table_reference(node) {
+                if let Some(target) = self.object_name(node, code) {
+                    if !target.eq_ignore_ascii_case(source) {
+                        uses.push((source, target, self.node_to_range(node)));
+                    }
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_uses(child, code, uses, next_owner, depth + 1);
+        }
+    }
+
+    /// Walk any statement and collect every table reference
+    fn collect_table_references<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        references: &mut Vec<(&'a str, Range)>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if node.kind() == "object_reference" && is_table_reference(node) {
+            if let Some(name) = self.object_name(node, code) {
+                references.push((name, self.node_to_range(node)));
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_table_references(child, code, references, depth + 1);
+        }
+    }
+
+    /// Tables referenced anywhere in `code` (`users` in `SELECT * FROM users`)
+    ///
+    /// Unlike `find_uses`, references need no enclosing definition, so this
+    /// works for queries embedded in other languages.
+    pub fn find_table_references<'a>(&mut self, code: &'a str) -> Vec<(&'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut references = Vec::new();
+        self.collect_table_references(tree.root_node(), code, &mut references, 0);
+        references
+    }
+
+    /// Walk routine bodies and collect invocations as calls
+    fn collect_calls<'a>(
+        &mut self,
+        node: Node,
+        code: &'a str,
+        calls: &mut Vec<(&'a str, &'a str, Range)>,
+        current_routine: Option<&'a str>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        let mut next_routine = current_routine;
+        if let Some(object) = SqlObject::from_node_kind(node.kind()) {
+            next_routine = if object.is_routine() {
+                self.defined_reference(node)
+                    .and_then(|reference| self.object_name(reference, code))
+            } else {
+                None
+            };
+        }
+
+        if node.kind() == "invocation" {
+            self.register_node(&node);
+            if let Some(caller) = current_routine {
+                let callee = self
+                    .defined_reference(node)
+                    .and_then(|reference| self.object_name(reference, code));
+                if let Some(callee) = callee {
+                    calls.push((caller, callee, self.node_to_range(node)));
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_calls(child, code, calls, next_routine, depth + 1);
+        }
+    }
+
+    /// Tables define their columns
+    fn collect_defines<'a>(
+        &self,
+        node: Node,
+        code: &'a str,
+        defines: &mut Vec<(&'a str, &'a str, Range)>,
+        depth: usize,
+    ) {
+        if !check_recursion_depth(depth, node) {
+            return;
+        }
+
+        if node.kind() == "create_table" {
+            let table = self
+                .defined_reference(node)
+                .and_then(|reference| self.object_name(reference, code));
+            if let (Some(table), Some(definitions)) =
+                (table, find_descendant(node, "column_definitions"))
+            {
+                let mut cursor = definitions.walk();
+                for column in definitions.named_children(&mut cursor) {
+                    if column.kind() != "column_definition" {
+                        continue;
+                    }
+                    let name_node = column
+                        .child_by_field_name("name")
+                        .or_else(|| column.named_child(0));
+                    if let Some(name_node) = name_node {
+                        let name = unquote_identifier(self.text_for_node(code, name_node));
+                        if !name.is_empty() {
+                            defines.push((table, name, self.node_to_range(column)));
+                        }
+                    }
+                }
+            }
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect_defines(child, code, defines, depth + 1);
+        }
+    }
+
+    /// Access handled nodes for audit tooling
+    pub fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+}
+
+/// Find the first descendant of `node` with the given kind (breadth-first)
+/// Whether an `object_reference` names a table rather than a column or routine
+fn is_table_reference(node: Node) -> bool {
+    node.parent()
+        .is_some_and(|parent| TABLE_REFERENCE_PARENTS.contains(&parent.kind()))
+        || node
+            .prev_named_sibling()
+            .is_some_and(|prev| prev.kind() == "keyword_references")
+}
+
+fn find_descendant<'tree>(node: Node<'tree>, kind: &str) -> Option<Node<'tree>> {
+    let mut queue = std::collections::VecDeque::from([node]);
+    while let Some(current) = queue.pop_front() {
+        let mut cursor = current.walk();
+        for child in current.named_children(&mut cursor) {
+            if child.kind() == kind {
+                return Some(child);
+            }
+            queue.push_back(child);
+        }
+    }
+    None
+}
+
+/// Strip identifier quoting: `"users"`, `` `users` ``, `[users]`
+fn unquote_identifier(raw: &str) -> &str {
+    let raw = raw.trim();
+    let bytes = raw.as_bytes();
+    if bytes.len() >= 2 {
+        let (first, last) = (bytes[0], bytes[bytes.len() - 1]);
+        if (first == b'"' && last == b'"')
+            || (first == b'`' && last == b'`')
+            || (first == b'[' && last == b']')
+        {
+            return &raw[1..raw.len() - 1];
+        }
+    }
+    raw
+}
+
+/// Scan raw SQL for `CREATE [OR REPLACE | OR ALTER] <object> [IF NOT EXISTS] <name>`
+///
+/// Returns the byte offset of each `CREATE`, the object kind, and the unqualified name.
+fn scan_create_statements(text: &str) -> Vec<(usize, SqlObject, &str)> {
+    let mut results = Vec::new();
+    let mut tokens: Vec<(usize, &str)> = Vec::new();
+    let mut start = None;
+    for (offset, c) in text.char_indices() {
+        let separator = c.is_whitespace() || c == '(' || c == ';';
+        match (separator, start) {
+            (true, Some(begin)) => {
+                tokens.push((begin, &text[begin..offset]));
+                start = None;
+            }
+            (false, None) => start = Some(offset),
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        tokens.push((begin, &text[begin..]));
+    }
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let (offset, token) = tokens[index];
+        index += 1;
+        if !token.eq_ignore_ascii_case("create") {
+            continue;
+        }
+
+        let mut cursor = index;
+        if tokens
+            .get(cursor)
+            .is_some_and(|(_, t)| t.eq_ignore_ascii_case("or"))
+        {
+            cursor += 2;
+        }
+        let Some(object) = tokens
+            .get(cursor)
+            .and_then(|(_, keyword)| SqlObject::from_keyword(keyword))
+        else {
+            continue;
+        };
+        cursor += 1;
+
+        // IF NOT EXISTS
+        if tokens
+            .get(cursor)
+            .is_some_and(|(_, t)| t.eq_ignore_ascii_case("if"))
+        {
+            cursor += 3;
+        }
+
+        if let Some((_, qualified)) = tokens.get(cursor) {
+            let name = unquote_identifier(qualified.rsplit('.').next().unwrap_or(qualified));
+            if !name.is_empty() {
+                results.push((offset, object, name));
+            }
+        }
+        index = cursor + 1;
+    }
+
+    results
+}
+
+impl LanguageParser for SqlParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut symbols = Vec::new();
+        self.extract_symbols_from_node(
+            tree.root_node(),
+            code,
+            file_id,
+            &mut symbols,
+            symbol_counter,
+            0,
+        );
+        symbols
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
+        self.doc_comment_for(node, code)
+    }
+
+    fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut calls = Vec::new();
+        self.collect_calls(tree.root_node(), code, &mut calls, None, 0);
+        calls
+    }
+
+    fn find_implementations<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut uses = Vec::new();
+        self.collect_uses(tree.root_node(), code, &mut uses, None, 0);
+        uses
+    }
+
+    fn find_defines<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let tree = match self.parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
+        let mut defines = Vec::new();
+        self.collect_defines(tree.root_node(), code, &mut defines, 0);
+        defines
+    }
+
+    fn find_imports(&mut self, _code: &str, _file_id: FileId) -> Vec<Import> {
+        // SQL has no module system; cross-file references resolve by object name
+        Vec::new()
+    }
+
+    fn language(&self) -> Language {
+        Language::Sql
+    }
+}
+
+impl NodeTracker for SqlParser {
+    fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+
+    fn register_handled_node(&mut self, node_kind: &str, node_id: u16) {
+        self.node_tracker.register_handled_node(node_kind, node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_create_statements() {
+        let text = "CREATE OR ALTER PROCEDURE dbo.[archive_orders] @before DATE AS\n\
+                    BEGIN DELETE FROM orders; END;\n\
+                    create table if not exists audit_log (id int);";
+        let found = scan_create_statements(text);
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].1, SqlObject::Procedure);
+        assert_eq!(found[0].2, "archive_orders");
+        assert_eq!(found[1].1, SqlObject::Table);
+        assert_eq!(found[1].2, "audit_log");
+    }
+
+    #[test]
+    fn test_unquote_identifier() {
+        assert_eq!(unquote_identifier("\"Users\""), "Users");
+        assert_eq!(unquote_identifier("`orders`"), "orders");
+        assert_eq!(unquote_identifier("[line items]"), "line items");
+        assert_eq!(unquote_identifier("plain"), "plain");
+    }
+}
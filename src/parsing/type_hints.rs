@@ -0,0 +1,477 @@
+//! Type inference-lite for dynamic languages
+//!
+//! Python and JavaScript rarely say what type a variable holds, so a call like
+//! `repo.save()` names a method without its class. When several classes define
+//! `save`, resolution falls back to guessing, and Python methods (indexed as
+//! `Class.method`) are not found by their short name at all.
+//!
+//! This pass guesses the type of local variables, parameters and `self`/`this`
+//! attributes from annotations (`repo: Repository`, `(repo: Repository) =>`)
+//! and constructor calls (`repo = Repository()`, `const repo = new
+//! Repository()`). Calls on a receiver with a guess carry it in their
+//! relationship context, so RESOLVE can prefer the method of that class. The
+//! guess is a heuristic: relationships resolved through it keep the inferred
+//! type and a confidence below 1.0 as their weight.
+
+use crate::parsing::LanguageId;
+use crate::parsing::parser::check_recursion_depth;
+use tree_sitter::{Language, Node, Parser};
+
+/// Where a type guess came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintSource {
+    /// A type annotation on the variable or parameter
+    Annotation,
+    /// The variable was assigned a constructor call
+    Constructor,
+    /// A `self`/`this` attribute was assigned a constructor call, possibly in
+    /// another method
+    Attribute,
+}
+
+impl HintSource {
+    /// How likely the guessed type is to be right
+    pub fn confidence(self) -> f32 {
+        match self {
+            HintSource::Annotation => 0.9,
+            HintSource::Constructor => 0.7,
+            HintSource::Attribute => 0.6,
+        }
+    }
+}
+
+/// A guessed type for a variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeHint {
+    /// Variable name as it appears as a receiver (`repo`, `self.repo`)
+    pub variable: String,
+    /// Class name without module path or type arguments
+    pub type_name: String,
+    /// Function the variable belongs to; `None` for module level and attributes
+    pub scope: Option<String>,
+    /// 0-based line of the binding
+    pub line: u32,
+    pub source: HintSource,
+}
+
+impl TypeHint {
+    pub fn confidence(&self) -> f32 {
+        self.source.confidence()
+    }
+}
+
+/// Whether type hints are inferred for files of `language`
+pub fn supports(language: LanguageId) -> bool {
+    matches!(language.as_str(), "python" | "javascript" | "typescript")
+}
+
+/// Guess variable types in a Python, JavaScript or TypeScript tree
+pub fn infer(root: Node, code: &str) -> Vec<TypeHint> {
+    let mut hints = Vec::new();
+    visit(root, code, None, &mut hints, 0);
+    hints
+}
+
+fn visit<'a>(
+    node: Node,
+    code: &'a str,
+    scope: Option<&'a str>,
+    hints: &mut Vec<TypeHint>,
+    depth: usize,
+) {
+    if !check_recursion_depth(depth, node) {
+        return;
+    }
+
+    let scope = function_name(node, code).or(scope);
+    match node.kind() {
+        // Python: `x = Foo()`, `x: Foo = ...`, `self.x = Foo()`
+        "assignment" => {
+            if let Some(left) = node.child_by_field_name("left") {
+                let annotation = node.child_by_field_name("type");
+                let value = node.child_by_field_name("right");
+                bind(left, annotation, value, code, scope, hints);
+            }
+        }
+        // Python parameters: `def f(x: Foo)`, `def f(x: Foo = None)`
+        "typed_parameter" | "typed_default_parameter" => {
+            let name = node
+                .child_by_field_name("name")
+                .or_else(|| node.named_child(0))
+                .filter(|name| name.kind() == "identifier");
+            if let (Some(name), Some(annotation)) = (name, node.child_by_field_name("type")) {
+                push_annotation(name, annotation, code, scope, hints);
+            }
+        }
+        // JavaScript/TypeScript: `const x = new Foo()`, `let x: Foo`
+        "variable_declarator" => {
+            if let Some(name) = node.child_by_field_name("name") {
+                let annotation = node.child_by_field_name("type");
+                let value = node.child_by_field_name("value");
+                bind(name, annotation, value, code, scope, hints);
+            }
+        }
+        // JavaScript/TypeScript: `x = new Foo()`, `this.x = new Foo()`
+        "assignment_expression" => {
+            if let Some(left) = node.child_by_field_name("left") {
+                let value = node.child_by_field_name("right");
+                bind(left, None, value, code, scope, hints);
+            }
+        }
+        // TypeScript parameters: `(x: Foo) => ...`
+        "required_parameter" | "optional_parameter" => {
+            if let (Some(name), Some(annotation)) = (
+                node.child_by_field_name("pattern"),
+                node.child_by_field_name("type"),
+            ) {
+                if name.kind() == "identifier" {
+                    push_annotation(name, annotation, code, scope, hints);
+                }
+            }
+        }
+        // TypeScript class fields: `private repo: Repository`
+        "public_field_definition" => {
+            if let (Some(name), Some(annotation)) = (
+                node.child_by_field_name("name"),
+                node.child_by_field_name("type"),
+            ) {
+                if let Some(type_name) = type_name(&code[annotation.byte_range()]) {
+                    hints.push(TypeHint {
+                        variable: format!("this.{}", &code[name.byte_range()]),
+                        type_name,
+                        scope: None,
+                        line: name.start_position().row as u32,
+                        source: HintSource::Annotation,
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        visit(child, code, scope, hints, depth + 1);
+    }
+}
+
+/// Name of the function `node` defines, if it is one
+fn function_name<'a>(node: Node, code: &'a str) -> Option<&'a str> {
+    match node.kind() {
+        "function_definition"
+        | "function_declaration"
+        | "generator_function_declaration"
+        | "method_definition" => node
+            .child_by_field_name("name")
+            .map(|name| &code[name.byte_range()]),
+        // `const handler = () => ...` takes the variable's name
+        "arrow_function" | "function_expression" | "function" => node
+            .child_by_field_name("name")
+            .or_else(|| {
+                node.parent()
+                    .filter(|parent| parent.kind() == "variable_declarator")
+                    .and_then(|parent| parent.child_by_field_name("name"))
+            })
+            .map(|name| &code[name.byte_range()]),
+        _ => None,
+    }
+}
+
+fn bind(
+    target: Node,
+    annotation: Option<Node>,
+    value: Option<Node>,
+    code: &str,
+    scope: Option<&str>,
+    hints: &mut Vec<TypeHint>,
+) {
+    let is_attribute = match target.kind() {
+        "identifier" => false,
+        "attribute" | "member_expression" => {
+            let object = target.child_by_field_name("object");
+            if !object.is_some_and(|object| matches!(&code[object.byte_range()], "self" | "this")) {
+                return;
+            }
+            true
+        }
+        _ => return,
+    };
+
+    if let Some(annotation) = annotation {
+        push_annotation(target, annotation, code, scope, hints);
+        return;
+    }
+    let Some(type_name) = value.and_then(|value| constructed_type(value, code)) else {
+        return;
+    };
+    hints.push(TypeHint {
+        variable: code[target.byte_range()].to_string(),
+        type_name,
+        scope: if is_attribute {
+            None
+        } else {
+            scope.map(str::to_string)
+        },
+        line: target.start_position().row as u32,
+        source: if is_attribute {
+            HintSource::Attribute
+        } else {
+            HintSource::Constructor
+        },
+    });
+}
+
+fn push_annotation(
+    target: Node,
+    annotation: Node,
+    code: &str,
+    scope: Option<&str>,
+    hints: &mut Vec<TypeHint>,
+) {
+    let Some(type_name) = type_name(&code[annotation.byte_range()]) else {
+        return;
+    };
+    let variable = &code[target.byte_range()];
+    let is_attribute = variable.starts_with("self.") || variable.starts_with("this.");
+    hints.push(TypeHint {
+        variable: variable.to_string(),
+        type_name,
+        scope: if is_attribute {
+            None
+        } else {
+            scope.map(str::to_string)
+        },
+        line: target.start_position().row as u32,
+        source: HintSource::Annotation,
+    });
+}
+
+/// Class instantiated by `value`: `Foo(...)`, `mod.Foo(...)` or `new Foo(...)`
+///
+/// A Python call only counts when the callee is capitalized, since classes
+/// and functions are called the same way.
+fn constructed_type(value: Node, code: &str) -> Option<String> {
+    let (callee, is_new) = match value.kind() {
+        "call" => (value.child_by_field_name("function")?, false),
+        "new_expression" => (value.child_by_field_name("constructor")?, true),
+        "await" | "await_expression" | "parenthesized_expression" => {
+            return constructed_type(value.named_child(0)?, code);
+        }
+        _ => return None,
+    };
+    let name = type_name(&code[callee.byte_range()])?;
+    if is_new || name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// Class name named by an annotation or callee
+///
+/// Drops `: ` prefixes, quotes, module paths, type arguments and `None`/`null`
+/// alternatives, and unwraps `Optional[...]`. Returns `None` for anything that
+/// is not a plain name afterwards (unions of classes, function types, ...).
+fn type_name(text: &str) -> Option<String> {
+    let mut text = text
+        .trim()
+        .trim_start_matches(':')
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'');
+
+    if let Some(inner) = text
+        .strip_prefix("Optional[")
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        text = inner.trim();
+    }
+
+    let mut alternatives = text
+        .split('|')
+        .map(str::trim)
+        .filter(|part| !matches!(*part, "None" | "null" | "undefined"));
+    let text = alternatives.next()?;
+    if alternatives.next().is_some() {
+        return None;
+    }
+
+    let text = text.split(['[', '<', '(']).next()?.trim();
+    let name = text.rsplit('.').next()?;
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    if (first.is_alphabetic() || first == '_') && chars.all(|c| c.is_alphanumeric() || c == '_') {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+/// The hint for `receiver` as seen from a call in `caller` at `line`
+///
+/// Hints of the calling function win over module-level and attribute hints;
+/// among those, the last binding at or before the call wins.
+pub fn receiver_type<'h>(
+    hints: &'h [TypeHint],
+    receiver: &str,
+    caller: &str,
+    line: u32,
+) -> Option<&'h TypeHint> {
+    let candidates = || hints.iter().filter(move |hint| hint.variable == receiver);
+    let latest = |scoped: &dyn Fn(&TypeHint) -> bool| {
+        candidates()
+            .filter(|hint| scoped(hint))
+            .filter(|hint| hint.line <= line)
+            .max_by_key(|hint| hint.line)
+            .or_else(|| candidates().filter(|hint| scoped(hint)).last())
+    };
+
+    latest(&|hint| hint.scope.as_deref() == Some(caller))
+        .or_else(|| latest(&|hint| hint.scope.is_none()))
+}
+
+/// Relationship context of a call whose receiver has a type hint
+///
+/// Extends the `receiver:{receiver},static:{is_static}` context shown by the
+/// relationship tools with the guessed type and its confidence.
+pub fn call_context(receiver: &str, hint: &TypeHint) -> String {
+    format!(
+        "receiver:{receiver},static:false,type:{},confidence:{:.2}",
+        hint.type_name,
+        hint.confidence()
+    )
+}
+
+/// Inferred receiver type and confidence recorded in a relationship context
+pub fn inferred_type(context: &str) -> Option<(&str, f32)> {
+    let mut type_name = None;
+    let mut confidence = None;
+    for part in context.split(',') {
+        if let Some(name) = part.strip_prefix("type:") {
+            type_name = Some(name);
+        } else if let Some(value) = part.strip_prefix("confidence:") {
+            confidence = value.parse().ok();
+        }
+    }
+    Some((type_name?, confidence?))
+}
+
+/// `context` without the inferred type, for calls resolved without it
+pub fn without_inferred_type(context: &str) -> String {
+    context
+        .split(',')
+        .filter(|part| !part.starts_with("type:") && !part.starts_with("confidence:"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parser used only to infer type hints
+pub struct TypeHintParser {
+    parser: Parser,
+}
+
+impl Default for TypeHintParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeHintParser {
+    pub fn new() -> Self {
+        Self {
+            parser: Parser::new(),
+        }
+    }
+
+    /// Parse `code` with `grammar` and guess its variable types
+    pub fn infer(&mut self, code: &str, grammar: &Language) -> Vec<TypeHint> {
+        if self.parser.set_language(grammar).is_err() {
+            return Vec::new();
+        }
+        match self.parser.parse(code, None) {
+            Some(tree) => infer(tree.root_node(), code),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints(code: &str, grammar: Language) -> Vec<TypeHint> {
+        TypeHintParser::new().infer(code, &grammar)
+    }
+
+    #[test]
+    fn test_python_constructors_and_annotations() {
+        let code = r#"
+class Service:
+    def __init__(self, repo: "Repository"):
+        self.cache = Cache()
+
+    def run(self, user: Optional[User] = None):
+        report = reports.Report(user)
+        count = compute()
+        report.render()
+"#;
+        let hints = hints(code, tree_sitter_python::LANGUAGE.into());
+
+        let find = |variable: &str| hints.iter().find(|hint| hint.variable == variable);
+        assert_eq!(find("repo").unwrap().type_name, "Repository");
+        assert_eq!(find("repo").unwrap().source, HintSource::Annotation);
+        assert_eq!(find("self.cache").unwrap().scope, None);
+        assert_eq!(find("self.cache").unwrap().source, HintSource::Attribute);
+        assert_eq!(find("user").unwrap().type_name, "User");
+        assert_eq!(find("report").unwrap().type_name, "Report");
+        assert_eq!(find("report").unwrap().scope.as_deref(), Some("run"));
+        assert!(find("count").is_none());
+
+        let hint = receiver_type(&hints, "report", "run", 8).unwrap();
+        assert_eq!(hint.type_name, "Report");
+        assert!(receiver_type(&hints, "report", "__init__", 8).is_none());
+        assert!(receiver_type(&hints, "self.cache", "run", 8).is_some());
+    }
+
+    #[test]
+    fn test_typescript_new_and_parameters() {
+        let code = r#"
+class Controller {
+  private store: Store<User>;
+
+  handle(req: Request | undefined) {
+    const client = new api.Client();
+    client.send(req);
+  }
+}
+const run = async () => {
+  const queue = await new Queue();
+};
+"#;
+        let hints = hints(code, tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into());
+
+        let find = |variable: &str| hints.iter().find(|hint| hint.variable == variable);
+        assert_eq!(find("this.store").unwrap().type_name, "Store");
+        assert_eq!(find("req").unwrap().type_name, "Request");
+        assert_eq!(find("client").unwrap().type_name, "Client");
+        assert_eq!(find("client").unwrap().scope.as_deref(), Some("handle"));
+        assert_eq!(find("queue").unwrap().scope.as_deref(), Some("run"));
+    }
+
+    #[test]
+    fn test_call_context_round_trip() {
+        let hint = TypeHint {
+            variable: "repo".to_string(),
+            type_name: "Repository".to_string(),
+            scope: None,
+            line: 3,
+            source: HintSource::Constructor,
+        };
+        let context = call_context("repo", &hint);
+        assert_eq!(inferred_type(&context), Some(("Repository", 0.7)));
+        assert_eq!(
+            without_inferred_type(&context),
+            "receiver:repo,static:false"
+        );
+        assert_eq!(inferred_type("receiver:repo,static:false"), None);
+    }
+}
@@ -510,6 +510,10 @@ impl ResolutionScope for TypeScriptResolutionContext {
                 // Very permissive - almost anything can reference anything
                 true
             }
+            Mirrors => {
+                // Cross-language API links join executable code on both ends
+                matches!(from_kind, Function | Method) && matches!(to_kind, Function | Method)
+            }
         }
     }
 
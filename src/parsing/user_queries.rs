@@ -0,0 +1,283 @@
+//! User-defined symbol queries
+//!
+//! Query files in `.codanna/queries/<language>/*.scm` extract symbols the
+//! built-in parsers do not model, such as route registrations or dependency
+//! injection bindings. They use the captures described in
+//! [`crate::parsing::external::query`]:
+//!
+//! ```scheme
+//! ; .codanna/queries/typescript/routes.scm
+//! (call_expression
+//!   function: (member_expression property: (property_identifier) @method)
+//!   arguments: (arguments . (string) @name)
+//!   (#match? @method "^(get|post|put|delete)$")) @definition.function
+//! ```
+//!
+//! Every symbol a file extracts is tagged with the file's stem (`routes`
+//! above), or with `(#set! tag "...")` on the pattern. A match on an existing
+//! symbol, at the same location and with the same name, tags that symbol
+//! instead of adding a duplicate.
+
+use crate::parsing::LanguageId;
+use crate::parsing::external::parser::{clean_comment, node_to_range, signature_for};
+use crate::parsing::external::{GrammarError, SymbolQuery, prepare_parser};
+use crate::symbol::ScopeContext;
+use crate::types::SymbolCounter;
+use crate::{FileId, Symbol, Visibility};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tree_sitter::Parser;
+
+/// Directory under `.codanna` holding one subdirectory of queries per language
+pub const QUERIES_DIR: &str = "queries";
+
+/// Query files already reported as broken, so each parse thread does not
+/// warn about the same file again
+static REPORTED: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(Default::default);
+
+/// A query file and the tag of the symbols it extracts
+struct UserQuery {
+    tag: Box<str>,
+    query: SymbolQuery,
+}
+
+/// The user queries of one language, with a parser to run them
+pub struct UserQueries {
+    parser: Parser,
+    queries: Vec<UserQuery>,
+}
+
+impl std::fmt::Debug for UserQueries {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserQueries")
+            .field("tags", &self.tags())
+            .finish()
+    }
+}
+
+impl UserQueries {
+    /// Directory holding the queries for `language_id`
+    pub fn dir(root: &Path, language_id: LanguageId) -> PathBuf {
+        root.join(crate::init::local_dir_name())
+            .join(QUERIES_DIR)
+            .join(language_id.as_str())
+    }
+
+    /// Load the queries for `language_id` under `root`
+    ///
+    /// Returns `None` when the language has no valid queries. Files that
+    /// cannot be read or compiled are logged once and skipped.
+    pub fn load(
+        root: &Path,
+        language_id: LanguageId,
+        language: &tree_sitter::Language,
+    ) -> Option<Self> {
+        let (queries, errors) = Self::compile_dir(&Self::dir(root, language_id), language);
+        report(errors);
+        if queries.is_empty() {
+            return None;
+        }
+
+        let mut parser = Parser::new();
+        prepare_parser(&mut parser).ok()?;
+        parser.set_language(language).ok()?;
+        Some(Self { parser, queries })
+    }
+
+    /// Compile every `.scm` file in `dir`, in file name order
+    fn compile_dir(
+        dir: &Path,
+        language: &tree_sitter::Language,
+    ) -> (Vec<UserQuery>, Vec<GrammarError>) {
+        let mut queries = Vec::new();
+        let mut errors = Vec::new();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return (queries, errors);
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "scm"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let source = match std::fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(e) => {
+                    errors.push(GrammarError::QueryRead {
+                        path,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            match SymbolQuery::new(language, &source) {
+                Ok(query) => {
+                    let tag = path
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    queries.push(UserQuery {
+                        tag: tag.into(),
+                        query,
+                    });
+                }
+                Err(reason) => errors.push(GrammarError::InvalidQuery { path, reason }),
+            }
+        }
+
+        (queries, errors)
+    }
+
+    /// Tags of the loaded query files
+    pub fn tags(&self) -> Vec<&str> {
+        self.queries.iter().map(|query| &*query.tag).collect()
+    }
+
+    /// Run the queries over `code` and merge what they find into `symbols`
+    pub fn apply(
+        &mut self,
+        code: &str,
+        symbols: &mut Vec<Symbol>,
+        file_id: FileId,
+        counter: &mut SymbolCounter,
+    ) {
+        let Some(tree) = self.parser.parse(code, None) else {
+            return;
+        };
+
+        for user_query in &self.queries {
+            let (definitions, _) = user_query.query.matches(tree.root_node(), code);
+            for definition in definitions {
+                let name = code[definition.name.byte_range()]
+                    .trim()
+                    .trim_matches(['"', '\'', '`']);
+                if name.is_empty() {
+                    continue;
+                }
+                let tag = user_query
+                    .query
+                    .property(definition.pattern, "tag")
+                    .unwrap_or(&user_query.tag);
+                let range = node_to_range(definition.node);
+
+                if let Some(existing) = symbols
+                    .iter_mut()
+                    .find(|symbol| symbol.range == range && &*symbol.name == name)
+                {
+                    if existing.tag.is_none() {
+                        existing.tag = Some(tag.into());
+                    }
+                    continue;
+                }
+
+                let mut symbol =
+                    Symbol::new(counter.next_id(), name, definition.kind, file_id, range)
+                        .with_signature(signature_for(definition.node, code))
+                        .with_visibility(Visibility::Public)
+                        .with_scope(ScopeContext::Module)
+                        .with_tag(tag);
+                let doc: Vec<_> = definition
+                    .doc
+                    .iter()
+                    .map(|node| clean_comment(&code[node.byte_range()]))
+                    .filter(|part| !part.is_empty())
+                    .collect();
+                if !doc.is_empty() {
+                    symbol = symbol.with_doc(doc.join("\n"));
+                }
+                symbols.push(symbol);
+            }
+        }
+    }
+}
+
+fn report(errors: Vec<GrammarError>) {
+    let Ok(mut reported) = REPORTED.lock() else {
+        return;
+    };
+    for error in errors {
+        let path = match &error {
+            GrammarError::QueryRead { path, .. } | GrammarError::InvalidQuery { path, .. } => {
+                path.clone()
+            }
+            _ => continue,
+        };
+        if reported.insert(path) {
+            tracing::warn!(target: "pipeline", "Skipping user query: {error}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Range, SymbolKind};
+
+    fn python() -> tree_sitter::Language {
+        tree_sitter_python::LANGUAGE.into()
+    }
+
+    fn write_query(root: &Path, file: &str, source: &str) {
+        let dir = UserQueries::dir(root, LanguageId::new("python"));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(file), source).unwrap();
+    }
+
+    #[test]
+    fn test_adds_tagged_symbols_and_tags_existing_ones() {
+        let root = tempfile::TempDir::new().unwrap();
+        write_query(
+            root.path(),
+            "routes.scm",
+            r#"
+            (decorated_definition
+              (decorator (call function: (attribute attribute: (identifier) @verb)
+                               arguments: (argument_list (string) @name)))
+              (#eq? @verb "route")) @definition.function
+            ((function_definition name: (identifier) @name) @definition.function
+             (#set! tag "handler"))
+            "#,
+        );
+
+        let code = "@app.route(\"/users\")\ndef list_users():\n    pass\n";
+        let mut queries =
+            UserQueries::load(root.path(), LanguageId::new("python"), &python()).unwrap();
+        assert_eq!(queries.tags(), vec!["routes"]);
+
+        let file_id = FileId::new(1).unwrap();
+        let mut counter = SymbolCounter::new();
+        let existing = Symbol::new(
+            counter.next_id(),
+            "list_users",
+            SymbolKind::Function,
+            file_id,
+            Range::new(1, 0, 2, 8),
+        );
+        let mut symbols = vec![existing];
+        queries.apply(code, &mut symbols, file_id, &mut counter);
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].tag.as_deref(), Some("handler"));
+        assert_eq!(&*symbols[1].name, "/users");
+        assert_eq!(symbols[1].kind, SymbolKind::Function);
+        assert_eq!(symbols[1].tag.as_deref(), Some("routes"));
+    }
+
+    #[test]
+    fn test_invalid_queries_are_skipped() {
+        let root = tempfile::TempDir::new().unwrap();
+        assert!(UserQueries::load(root.path(), LanguageId::new("python"), &python()).is_none());
+
+        write_query(
+            root.path(),
+            "broken.scm",
+            "(no_such_node) @definition.class",
+        );
+        write_query(root.path(), "notes.txt", "not a query");
+        assert!(UserQueries::load(root.path(), LanguageId::new("python"), &python()).is_none());
+    }
+}
@@ -0,0 +1,195 @@
+//! Vue-specific language behavior implementation
+//!
+//! Script blocks are TypeScript or JavaScript, so resolution, imports and
+//! visibility follow the TypeScript behavior. Vue only differs in its
+//! language id and in module paths, which drop the `.vue` extension.
+
+use crate::parsing::resolution::{InheritanceResolver, ResolutionScope};
+use crate::parsing::{
+    Import, JavaScriptBehavior, LanguageBehavior, LanguageId, PipelineSymbolCache,
+    TypeScriptBehavior,
+};
+use crate::storage::DocumentIndex;
+use crate::{FileId, IndexResult, Symbol, SymbolId, Visibility};
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Language behavior for Vue single-file components
+#[derive(Clone)]
+pub struct VueBehavior {
+    script: TypeScriptBehavior,
+}
+
+impl VueBehavior {
+    /// Create a new behavior instance
+    pub fn new() -> Self {
+        Self {
+            script: TypeScriptBehavior::new(),
+        }
+    }
+}
+
+impl Default for VueBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageBehavior for VueBehavior {
+    fn language_id(&self) -> LanguageId {
+        LanguageId::new("vue")
+    }
+
+    fn configure_symbol(&self, symbol: &mut Symbol, module_path: Option<&str>) {
+        self.script.configure_symbol(symbol, module_path);
+    }
+
+    fn format_module_path(&self, base_path: &str, symbol_name: &str) -> String {
+        self.script.format_module_path(base_path, symbol_name)
+    }
+
+    fn get_language(&self) -> Language {
+        self.script.get_language()
+    }
+
+    fn module_separator(&self) -> &'static str {
+        self.script.module_separator()
+    }
+
+    /// Resolve through tsconfig first, then jsconfig or the project root
+    fn module_path_from_file(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        let module_path = self
+            .script
+            .module_path_from_file(file_path, project_root)
+            .or_else(|| JavaScriptBehavior::new().module_path_from_file(file_path, project_root))?;
+
+        Some(
+            module_path
+                .strip_suffix(".vue")
+                .map(str::to_string)
+                .unwrap_or(module_path),
+        )
+    }
+
+    fn parse_visibility(&self, signature: &str) -> Visibility {
+        self.script.parse_visibility(signature)
+    }
+
+    fn supports_traits(&self) -> bool {
+        self.script.supports_traits()
+    }
+
+    fn supports_inherent_methods(&self) -> bool {
+        self.script.supports_inherent_methods()
+    }
+
+    fn create_resolution_context(&self, file_id: FileId) -> Box<dyn ResolutionScope> {
+        self.script.create_resolution_context(file_id)
+    }
+
+    fn create_inheritance_resolver(&self) -> Box<dyn InheritanceResolver> {
+        self.script.create_inheritance_resolver()
+    }
+
+    fn inheritance_relation_name(&self) -> &'static str {
+        self.script.inheritance_relation_name()
+    }
+
+    fn map_relationship(&self, language_specific: &str) -> crate::relationship::RelationKind {
+        self.script.map_relationship(language_specific)
+    }
+
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.script.register_file(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.script.add_import(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.script.get_imports_for_file(file_id)
+    }
+
+    fn resolve_external_call_target(
+        &self,
+        to_name: &str,
+        from_file: FileId,
+    ) -> Option<(String, String)> {
+        self.script.resolve_external_call_target(to_name, from_file)
+    }
+
+    fn create_external_symbol(
+        &self,
+        document_index: &mut DocumentIndex,
+        module_path: &str,
+        symbol_name: &str,
+        language_id: LanguageId,
+    ) -> IndexResult<SymbolId> {
+        self.script
+            .create_external_symbol(document_index, module_path, symbol_name, language_id)
+    }
+
+    fn build_resolution_context(
+        &self,
+        file_id: FileId,
+        document_index: &DocumentIndex,
+    ) -> IndexResult<Box<dyn ResolutionScope>> {
+        self.script
+            .build_resolution_context(file_id, document_index)
+    }
+
+    fn build_resolution_context_with_pipeline_cache(
+        &self,
+        file_id: FileId,
+        imports: &[Import],
+        cache: &dyn PipelineSymbolCache,
+    ) -> (Box<dyn ResolutionScope>, Vec<Import>) {
+        self.script
+            .build_resolution_context_with_pipeline_cache(file_id, imports, cache)
+    }
+
+    fn is_resolvable_symbol(&self, symbol: &Symbol) -> bool {
+        self.script.is_resolvable_symbol(symbol)
+    }
+
+    fn resolve_import(&self, import: &Import, document_index: &DocumentIndex) -> Option<SymbolId> {
+        self.script.resolve_import(import, document_index)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.script.get_module_path_for_file(file_id)
+    }
+
+    fn get_file_path(&self, file_id: FileId) -> Option<PathBuf> {
+        self.script.get_file_path(file_id)
+    }
+
+    fn import_matches_symbol(
+        &self,
+        import_path: &str,
+        symbol_module_path: &str,
+        importing_module: Option<&str>,
+    ) -> bool {
+        let import_path = import_path.strip_suffix(".vue").unwrap_or(import_path);
+        self.script
+            .import_matches_symbol(import_path, symbol_module_path, importing_module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_path_drops_vue_extension() {
+        let behavior = VueBehavior::new();
+        let root = Path::new("/app");
+
+        assert_eq!(
+            behavior.module_path_from_file(Path::new("/app/src/components/UserCard.vue"), root),
+            Some("src.components.UserCard".to_string())
+        );
+        assert_eq!(behavior.language_id(), LanguageId::new("vue"));
+    }
+}
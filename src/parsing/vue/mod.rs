@@ -0,0 +1,14 @@
+//! Vue single-file component parser implementation
+
+pub mod audit;
+pub mod behavior;
+pub mod definition;
+pub mod parser;
+
+pub use audit::VueParserAudit;
+pub use behavior::VueBehavior;
+pub use definition::VueLanguage;
+pub use parser::VueParser;
+
+// Re-export for registry registration
+pub(crate) use definition::register;
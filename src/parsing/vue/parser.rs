@@ -0,0 +1,628 @@
+//! Vue single-file component parser
+//!
+//! A `.vue` file is split into its top-level blocks without a Vue grammar:
+//!
+//! | Block | Handling |
+//! |-------|----------|
+//! | `<script>` / `<script setup>` | Parsed with the TypeScript parser when `lang="ts"` or `lang="tsx"`, otherwise the JavaScript parser |
+//! | `<template>` | Indexed as the component symbol; component tags become `Uses` edges |
+//! | `<style>` | Skipped |
+//!
+//! Script symbols and relationships keep their positions in the `.vue` file:
+//! ranges reported by the script parser are shifted by the block's offset.
+//!
+//! The parser never sees the file name, so the component symbol is named
+//! after the `name` option (`export default { name: 'UserCard' }` or
+//! `defineOptions({ name: 'UserCard' })`) and falls back to `template`.
+//! Template tags are matched to components by PascalCase name; kebab-case
+//! tags (`<user-card>`) resolve when the PascalCase name is imported or
+//! registered in a script block.
+//!
+//! Import paths drop their `.vue` suffix so they line up with the module
+//! paths [`VueBehavior`](super::VueBehavior) assigns to component files.
+
+use crate::parsing::{
+    HandledNode, Import, Language, LanguageParser, MethodCall, NodeTracker, NodeTrackingState,
+};
+use crate::parsing::{JavaScriptParser, TypeScriptParser};
+use crate::types::SymbolCounter;
+use crate::{FileId, Range, Symbol, SymbolKind, Visibility};
+use std::any::Any;
+use std::ops::Range as ByteRange;
+use tree_sitter::Node;
+
+/// Name of the component symbol when the script declares no `name` option
+const TEMPLATE_SYMBOL: &str = "template";
+
+/// Components Vue provides itself; they never resolve to a project symbol
+const BUILTIN_COMPONENTS: &[&str] = &[
+    "Component",
+    "KeepAlive",
+    "Slot",
+    "Suspense",
+    "Teleport",
+    "Transition",
+    "TransitionGroup",
+];
+
+/// Kind of top-level block in a single-file component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SfcBlockKind {
+    Script,
+    ScriptSetup,
+    Template,
+    Style,
+}
+
+/// A top-level block of a single-file component
+#[derive(Debug, Clone)]
+pub struct SfcBlock<'a> {
+    pub kind: SfcBlockKind,
+    /// Value of the `lang` attribute, if present
+    pub lang: Option<&'a str>,
+    /// Byte range from the opening `<` to the end of the closing tag
+    pub outer: ByteRange<usize>,
+    /// Byte range of the content between the tags
+    pub content: ByteRange<usize>,
+}
+
+impl SfcBlock<'_> {
+    fn is_script(&self) -> bool {
+        matches!(self.kind, SfcBlockKind::Script | SfcBlockKind::ScriptSetup)
+    }
+}
+
+/// Split a single-file component into its top-level blocks.
+///
+/// Custom blocks (`<i18n>`, `<docs>`, ...) and comments are skipped. A block
+/// without a closing tag ends the scan.
+pub fn split_blocks(code: &str) -> Vec<SfcBlock<'_>> {
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = code[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &code[start..];
+
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(code.len(), |end| start + end + 3);
+            continue;
+        }
+
+        let name_len = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(rest.len() - 1);
+        let name = &rest[1..1 + name_len];
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let open_tag = &rest[..=tag_end];
+        let content_start = start + tag_end + 1;
+
+        if name.is_empty() || open_tag.ends_with("/>") {
+            pos = content_start;
+            continue;
+        }
+
+        let kind = match name {
+            "template" => Some(SfcBlockKind::Template),
+            "script" if attribute(open_tag, "setup").is_some() => Some(SfcBlockKind::ScriptSetup),
+            "script" => Some(SfcBlockKind::Script),
+            "style" => Some(SfcBlockKind::Style),
+            _ => None,
+        };
+
+        let nested = kind == Some(SfcBlockKind::Template);
+        let Some((content_end, outer_end)) = find_close(code, content_start, name, nested) else {
+            break;
+        };
+
+        if let Some(kind) = kind {
+            blocks.push(SfcBlock {
+                kind,
+                lang: attribute(open_tag, "lang"),
+                outer: start..outer_end,
+                content: content_start..content_end,
+            });
+        }
+        pos = outer_end;
+    }
+
+    blocks
+}
+
+/// Find the closing tag for `name`, returning (content end, block end).
+///
+/// Templates may contain nested `<template>` elements, so they are matched by depth.
+fn find_close(code: &str, from: usize, name: &str, nested: bool) -> Option<(usize, usize)> {
+    let open = format!("<{name}");
+    let close = format!("</{name}");
+    let mut depth = 0usize;
+    let mut pos = from;
+
+    loop {
+        let close_at = pos + code[pos..].find(&close)?;
+        let open_at = if nested {
+            code[pos..close_at].find(&open).map(|i| pos + i)
+        } else {
+            None
+        };
+
+        match open_at {
+            Some(at) => {
+                if is_tag_boundary(code, at + open.len()) {
+                    depth += 1;
+                }
+                pos = at + open.len();
+            }
+            None if depth > 0 => {
+                depth -= 1;
+                pos = close_at + close.len();
+            }
+            None => {
+                let end = code[close_at..]
+                    .find('>')
+                    .map_or(code.len(), |i| close_at + i + 1);
+                return Some((close_at, end));
+            }
+        }
+    }
+}
+
+fn is_tag_boundary(code: &str, at: usize) -> bool {
+    code[at..]
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_whitespace() || c == '>' || c == '/')
+}
+
+/// Value of an attribute in an opening tag. Boolean attributes yield `""`.
+fn attribute<'a>(open_tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut search = 0;
+    while let Some(i) = open_tag[search..].find(name) {
+        let at = search + i;
+        search = at + name.len();
+        if !open_tag[..at].ends_with(char::is_whitespace) {
+            continue;
+        }
+
+        let after = &open_tag[search..];
+        if let Some(value) = after.trim_start().strip_prefix('=') {
+            let value = value.trim_start();
+            return match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &value[1..];
+                    inner.find(quote).map(|end| &inner[..end])
+                }
+                _ => {
+                    let end = value
+                        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                        .unwrap_or(value.len());
+                    Some(&value[..end])
+                }
+            };
+        }
+        if is_tag_boundary(open_tag, search) {
+            return Some("");
+        }
+    }
+    None
+}
+
+/// Byte offset to (line, column) lookup for the whole component
+struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(code: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    fn position(&self, byte: usize) -> (u32, u16) {
+        let line = self.line_starts.partition_point(|&start| start <= byte) - 1;
+        (line as u32, (byte - self.line_starts[line]) as u16)
+    }
+
+    fn range(&self, bytes: ByteRange<usize>) -> Range {
+        let (start_line, start_column) = self.position(bytes.start);
+        let (end_line, end_column) = self.position(bytes.end);
+        Range::new(start_line, start_column, end_line, end_column)
+    }
+}
+
+/// Move a range reported relative to a script block into file coordinates
+fn shift_range(range: Range, (line, column): (u32, u16)) -> Range {
+    let shift_column = |row: u32, col: u16| if row == 0 { col + column } else { col };
+    Range::new(
+        range.start_line + line,
+        shift_column(range.start_line, range.start_column),
+        range.end_line + line,
+        shift_column(range.end_line, range.end_column),
+    )
+}
+
+/// Parser for Vue single-file components
+pub struct VueParser {
+    typescript: TypeScriptParser,
+    javascript: JavaScriptParser,
+    node_tracker: NodeTrackingState,
+}
+
+impl std::fmt::Debug for VueParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VueParser")
+            .field("language", &"Vue")
+            .finish()
+    }
+}
+
+impl VueParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            typescript: TypeScriptParser::new()?,
+            javascript: JavaScriptParser::new()?,
+            node_tracker: NodeTrackingState::new(),
+        })
+    }
+
+    /// Parser for a script block's `lang` attribute
+    fn script_parser(&mut self, lang: Option<&str>) -> &mut dyn LanguageParser {
+        match lang {
+            Some("ts" | "tsx") => &mut self.typescript,
+            _ => &mut self.javascript,
+        }
+    }
+
+    /// Run a zero-copy extraction over every script block and shift the results.
+    fn collect_from_scripts<'a, F>(
+        &mut self,
+        code: &'a str,
+        mut find: F,
+    ) -> Vec<(&'a str, &'a str, Range)>
+    where
+        F: FnMut(&mut dyn LanguageParser, &'a str) -> Vec<(&'a str, &'a str, Range)>,
+    {
+        let index = LineIndex::new(code);
+        let mut results = Vec::new();
+        for block in split_blocks(code).into_iter().filter(SfcBlock::is_script) {
+            let offset = index.position(block.content.start);
+            let parser = self.script_parser(block.lang);
+            results.extend(
+                find(parser, &code[block.content])
+                    .into_iter()
+                    .map(|(from, to, range)| (from, to, shift_range(range, offset))),
+            );
+        }
+        results
+    }
+
+    /// The component's declared `name` option, if any
+    fn declared_name<'a>(&self, code: &'a str, blocks: &[SfcBlock]) -> Option<&'a str> {
+        blocks.iter().filter(|b| b.is_script()).find_map(|block| {
+            let script = &code[block.content.clone()];
+            let options = script
+                .find("defineOptions(")
+                .or_else(|| script.find("export default"))?;
+            let after = &script[options..];
+            let name_at = after.match_indices("name:").find_map(|(at, _)| {
+                let prefix = after[..at].chars().next_back();
+                (!prefix.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_'))
+                    .then_some(at + "name:".len())
+            })?;
+            let value = after[name_at..].trim_start();
+            let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+            let inner = &value[1..];
+            let name = &inner[..inner.find(quote)?];
+            (!name.is_empty()).then_some(name)
+        })
+    }
+
+    /// Name used for the component symbol and as the source of template edges
+    fn component_name<'a>(&self, code: &'a str, blocks: &[SfcBlock]) -> Option<&'a str> {
+        let template = blocks.iter().find(|b| b.kind == SfcBlockKind::Template)?;
+        self.declared_name(code, blocks)
+            .or_else(|| Some(&code[template.outer.start + 1..][..TEMPLATE_SYMBOL.len()]))
+    }
+
+    /// Component tags used in the template, as slices naming the PascalCase component
+    fn template_components<'a>(
+        &self,
+        code: &'a str,
+        blocks: &[SfcBlock],
+        template: &SfcBlock,
+    ) -> Vec<(&'a str, ByteRange<usize>)> {
+        let mut components = Vec::new();
+        let content = &code[template.content.clone()];
+        let mut pos = 0;
+
+        while let Some(offset) = content[pos..].find('<') {
+            let start = template.content.start + pos + offset + 1;
+            pos += offset + 1;
+
+            let tag_len = code[start..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(code.len() - start);
+            let tag = &code[start..start + tag_len];
+            let tag_range = start..start + tag_len;
+
+            let component = if tag.starts_with(|c: char| c.is_ascii_uppercase()) {
+                Some(tag)
+            } else if tag.contains('-') {
+                script_identifier(code, blocks, &pascal_case(tag))
+            } else {
+                None
+            };
+
+            if let Some(component) = component.filter(|c| !BUILTIN_COMPONENTS.contains(c)) {
+                components.push((component, tag_range));
+            }
+        }
+
+        components
+    }
+}
+
+/// `user-card` -> `UserCard`
+fn pascal_case(tag: &str) -> String {
+    tag.split('-')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Find `name` as a whole identifier in a script block, returning a slice of `code`.
+fn script_identifier<'a>(code: &'a str, blocks: &[SfcBlock], name: &str) -> Option<&'a str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    blocks.iter().filter(|b| b.is_script()).find_map(|block| {
+        let script = &code[block.content.clone()];
+        script.match_indices(name).find_map(|(at, _)| {
+            let before = script[..at].chars().next_back();
+            let after = script[at + name.len()..].chars().next();
+            if before.is_some_and(is_ident) || after.is_some_and(is_ident) {
+                return None;
+            }
+            let start = block.content.start + at;
+            Some(&code[start..start + name.len()])
+        })
+    })
+}
+
+impl LanguageParser for VueParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        let blocks = split_blocks(code);
+        let index = LineIndex::new(code);
+        let mut symbols = Vec::new();
+
+        for block in &blocks {
+            let handled = match block.kind {
+                SfcBlockKind::Script | SfcBlockKind::ScriptSetup => "script",
+                SfcBlockKind::Template => "template",
+                SfcBlockKind::Style => "style",
+            };
+            self.register_handled_node(handled, 0);
+
+            if !block.is_script() {
+                continue;
+            }
+            let offset = index.position(block.content.start);
+            let parser = self.script_parser(block.lang);
+            let mut block_symbols =
+                parser.parse(&code[block.content.clone()], file_id, symbol_counter);
+            for symbol in &mut block_symbols {
+                symbol.range = shift_range(symbol.range, offset);
+            }
+            symbols.extend(block_symbols);
+        }
+
+        if let (Some(name), Some(template)) = (
+            self.component_name(code, &blocks),
+            blocks.iter().find(|b| b.kind == SfcBlockKind::Template),
+        ) {
+            let open_tag_end = template.content.start;
+            let signature = match self.declared_name(code, &blocks) {
+                Some(declared) => format!("component {declared}"),
+                None => code[template.outer.start..open_tag_end].to_string(),
+            };
+            let mut component = Symbol::new(
+                symbol_counter.next_id(),
+                name,
+                SymbolKind::Class,
+                file_id,
+                index.range(template.outer.clone()),
+            )
+            .with_signature(signature);
+            // The component is the file's default export
+            component.visibility = Visibility::Public;
+            symbols.push(component);
+        }
+
+        symbols
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, node: &Node, code: &str) -> Option<String> {
+        self.typescript.extract_doc_comment(node, code)
+    }
+
+    fn find_calls<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        self.collect_from_scripts(code, |parser, script| parser.find_calls(script))
+    }
+
+    fn find_method_calls(&mut self, code: &str) -> Vec<MethodCall> {
+        let index = LineIndex::new(code);
+        let mut calls = Vec::new();
+        for block in split_blocks(code).into_iter().filter(SfcBlock::is_script) {
+            let offset = index.position(block.content.start);
+            let parser = self.script_parser(block.lang);
+            calls.extend(
+                parser
+                    .find_method_calls(&code[block.content])
+                    .into_iter()
+                    .map(|mut call| {
+                        call.range = shift_range(call.range, offset);
+                        call.caller_range = call.caller_range.map(|r| shift_range(r, offset));
+                        call
+                    }),
+            );
+        }
+        calls
+    }
+
+    fn find_implementations<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        self.collect_from_scripts(code, |parser, script| parser.find_implementations(script))
+    }
+
+    fn find_extends<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        self.collect_from_scripts(code, |parser, script| parser.find_extends(script))
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let mut uses = self.collect_from_scripts(code, |parser, script| parser.find_uses(script));
+
+        let blocks = split_blocks(code);
+        let (Some(component), Some(template)) = (
+            self.component_name(code, &blocks),
+            blocks.iter().find(|b| b.kind == SfcBlockKind::Template),
+        ) else {
+            return uses;
+        };
+
+        let index = LineIndex::new(code);
+        for (used, tag_range) in self.template_components(code, &blocks, template) {
+            uses.push((component, used, index.range(tag_range)));
+        }
+        uses
+    }
+
+    fn find_defines<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        self.collect_from_scripts(code, |parser, script| parser.find_defines(script))
+    }
+
+    fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<Import> {
+        let mut imports = Vec::new();
+        for block in split_blocks(code).into_iter().filter(SfcBlock::is_script) {
+            let parser = self.script_parser(block.lang);
+            imports.extend(
+                parser
+                    .find_imports(&code[block.content], file_id)
+                    .into_iter()
+                    .map(|mut import| {
+                        // `./UserCard.vue` names the same module as the file `UserCard.vue`
+                        if let Some(path) = import.path.strip_suffix(".vue") {
+                            import.path = path.to_string();
+                        }
+                        import
+                    }),
+            );
+        }
+        imports
+    }
+
+    fn language(&self) -> Language {
+        Language::Vue
+    }
+
+    fn find_variable_types<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        self.collect_from_scripts(code, |parser, script| parser.find_variable_types(script))
+    }
+
+    fn find_inherent_methods(&mut self, code: &str) -> Vec<(String, String, Range)> {
+        let index = LineIndex::new(code);
+        let mut methods = Vec::new();
+        for block in split_blocks(code).into_iter().filter(SfcBlock::is_script) {
+            let offset = index.position(block.content.start);
+            let parser = self.script_parser(block.lang);
+            methods.extend(
+                parser
+                    .find_inherent_methods(&code[block.content])
+                    .into_iter()
+                    .map(|(ty, method, range)| (ty, method, shift_range(range, offset))),
+            );
+        }
+        methods
+    }
+}
+
+impl NodeTracker for VueParser {
+    fn get_handled_nodes(&self) -> &std::collections::HashSet<HandledNode> {
+        self.node_tracker.get_handled_nodes()
+    }
+
+    fn register_handled_node(&mut self, node_kind: &str, node_id: u16) {
+        self.node_tracker.register_handled_node(node_kind, node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"<template>
+  <div>
+    <template v-if="ready"><UserCard :user="user" /></template>
+    <user-avatar />
+  </div>
+</template>
+
+<script setup lang="ts">
+import UserCard from './UserCard.vue'
+import UserAvatar from './UserAvatar.vue'
+</script>
+
+<style scoped>
+.card { color: red; }
+</style>
+"#;
+
+    #[test]
+    fn test_split_blocks() {
+        let blocks = split_blocks(COMPONENT);
+        let kinds: Vec<_> = blocks.iter().map(|b| b.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SfcBlockKind::Template,
+                SfcBlockKind::ScriptSetup,
+                SfcBlockKind::Style
+            ]
+        );
+        assert_eq!(blocks[1].lang, Some("ts"));
+        assert_eq!(blocks[2].lang, None);
+        // The nested <template v-if> does not close the outer block
+        assert!(COMPONENT[blocks[0].content.clone()].contains("<user-avatar />"));
+    }
+
+    #[test]
+    fn test_attribute() {
+        assert_eq!(attribute("<script setup lang=\"ts\">", "lang"), Some("ts"));
+        assert_eq!(attribute("<script setup lang='tsx'>", "setup"), Some(""));
+        assert_eq!(attribute("<script lang=ts>", "lang"), Some("ts"));
+        assert_eq!(attribute("<script xlang=\"ts\">", "lang"), None);
+    }
+
+    #[test]
+    fn test_shift_range_moves_first_line_column() {
+        let shifted = shift_range(Range::new(0, 2, 1, 4), (7, 10));
+        assert_eq!(shifted, Range::new(7, 12, 8, 4));
+        assert_eq!(pascal_case("user-avatar"), "UserAvatar");
+    }
+}
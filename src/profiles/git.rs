@@ -27,39 +27,25 @@ pub fn clone_repository(
         std::fs::remove_dir_all(target_dir)?;
     }
 
-    // Set up callbacks for credentials
-    let mut callbacks = RemoteCallbacks::new();
-    callbacks.credentials(credential_callback);
-
-    // Set up fetch options with shallow clone
-    let mut fetch_opts = FetchOptions::new();
-    if !is_local {
-        fetch_opts.depth(1); // Shallow clone
-    }
-    fetch_opts.download_tags(AutotagOption::All);
-    fetch_opts.remote_callbacks(callbacks);
-
-    // Respect environment proxy settings
-    let mut proxy_opts = ProxyOptions::new();
-    proxy_opts.auto();
-    fetch_opts.proxy_options(proxy_opts);
-
     // Build the clone operation
     let mut builder = RepoBuilder::new();
-    builder.fetch_options(fetch_opts);
+    builder.fetch_options(fetch_options(is_local));
 
-    // Specify branch/tag if provided
+    // Specify branch if provided
     if let Some(reference) = git_ref {
         builder.branch(reference);
     }
 
-    // Perform the clone
-    let repo =
-        builder
-            .clone(repo_url, target_dir)
-            .map_err(|e| ProfileError::GitOperationFailed {
+    // Perform the clone; a reference that is no branch is fetched by itself
+    let repo = match (builder.clone(repo_url, target_dir), git_ref) {
+        (Ok(repo), _) => repo,
+        (Err(_), Some(reference)) => fetch_revision(repo_url, target_dir, reference, is_local)?,
+        (Err(e), None) => {
+            return Err(ProfileError::GitOperationFailed {
                 operation: format!("clone {repo_url}: {e}"),
-            })?;
+            });
+        }
+    };
 
     // Ensure proper checkout for the specified reference
     if let Some(reference) = git_ref {
@@ -76,6 +62,67 @@ pub fn clone_repository(
     get_commit_sha(target_dir)
 }
 
+/// Fetch options with credentials and proxy settings, shallow for remote URLs
+fn fetch_options(is_local: bool) -> FetchOptions<'static> {
+    // Set up callbacks for credentials
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(credential_callback);
+
+    // Set up fetch options with shallow clone
+    let mut fetch_opts = FetchOptions::new();
+    if !is_local {
+        fetch_opts.depth(1); // Shallow clone
+    }
+    fetch_opts.download_tags(AutotagOption::All);
+    fetch_opts.remote_callbacks(callbacks);
+
+    // Respect environment proxy settings
+    let mut proxy_opts = ProxyOptions::new();
+    proxy_opts.auto();
+    fetch_opts.proxy_options(proxy_opts);
+    fetch_opts
+}
+
+/// Fetch a tag or commit into a new repository at `target_dir`
+///
+/// Cloning only checks out branches, so `reference` is fetched as a tag
+/// first and else as a full commit SHA.
+fn fetch_revision(
+    repo_url: &str,
+    target_dir: &Path,
+    reference: &str,
+    is_local: bool,
+) -> ProfileResult<Repository> {
+    if target_dir.exists() {
+        std::fs::remove_dir_all(target_dir)?;
+    }
+    let repo = Repository::init(target_dir).map_err(|e| ProfileError::GitOperationFailed {
+        operation: format!("init repository: {e}"),
+    })?;
+    {
+        let mut remote =
+            repo.remote("origin", repo_url)
+                .map_err(|e| ProfileError::GitOperationFailed {
+                    operation: format!("add remote {repo_url}: {e}"),
+                })?;
+        let tag = format!("+refs/tags/{reference}:refs/tags/{reference}");
+        let tag_fetched = remote
+            .fetch(&[tag.as_str()], Some(&mut fetch_options(is_local)), None)
+            .is_ok()
+            && repo
+                .find_reference(&format!("refs/tags/{reference}"))
+                .is_ok();
+        if !tag_fetched {
+            remote
+                .fetch(&[reference], Some(&mut fetch_options(is_local)), None)
+                .map_err(|e| ProfileError::GitOperationFailed {
+                    operation: format!("fetch '{reference}' from {repo_url}: {e}"),
+                })?;
+        }
+    }
+    Ok(repo)
+}
+
 /// Credential callback for git2 authentication
 fn credential_callback(
     _url: &str,
@@ -121,8 +168,8 @@ fn checkout_reference(repo: &Repository, reference: &str) -> ProfileResult<()> {
             operation: format!("checkout tree: {e}"),
         })?;
 
-    // Set HEAD appropriately
-    if obj.as_commit().is_some() {
+    // Set HEAD appropriately; an annotated tag points at its commit
+    if let Ok(commit) = obj.peel_to_commit() {
         // For branches, set HEAD to the branch
         if repo.find_branch(reference, git2::BranchType::Local).is_ok() {
             repo.set_head(&format!("refs/heads/{reference}"))
@@ -131,7 +178,7 @@ fn checkout_reference(repo: &Repository, reference: &str) -> ProfileResult<()> {
                 })?;
         } else {
             // For tags or specific SHAs, detached HEAD
-            repo.set_head_detached(obj.id())
+            repo.set_head_detached(commit.id())
                 .map_err(|e| ProfileError::GitOperationFailed {
                     operation: format!("set detached head: {e}"),
                 })?;
@@ -233,3 +280,66 @@ pub fn validate_repository(repo_url: &str) -> ProfileResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Commit `content` to `file` on the current branch, returning its SHA
+    fn commit(repo: &Repository, file: &str, content: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(file), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            content,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_clone_repository_at_commit_sha() {
+        let temp = TempDir::new().unwrap();
+        let origin = temp.path().join("origin");
+        let repo = Repository::init(&origin).unwrap();
+        let first = commit(&repo, "lib.rs", "fn first() {}");
+        commit(&repo, "lib.rs", "fn second() {}");
+        let url = format!("file://{}", origin.display());
+
+        let target = temp.path().join("checkout");
+        let sha = clone_repository(&url, &target, Some(&first.to_string())).unwrap();
+        assert_eq!(sha, first.to_string());
+        assert_eq!(
+            std::fs::read_to_string(target.join("lib.rs")).unwrap(),
+            "fn first() {}"
+        );
+    }
+
+    #[test]
+    fn test_clone_repository_at_tag() {
+        let temp = TempDir::new().unwrap();
+        let origin = temp.path().join("origin");
+        let repo = Repository::init(&origin).unwrap();
+        let first = commit(&repo, "lib.rs", "fn first() {}");
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let object = repo.find_object(first, None).unwrap();
+        repo.tag("v1", &object, &signature, "v1", false).unwrap();
+        commit(&repo, "lib.rs", "fn second() {}");
+        let url = format!("file://{}", origin.display());
+
+        let target = temp.path().join("checkout");
+        let sha = clone_repository(&url, &target, Some("v1")).unwrap();
+        assert_eq!(sha, first.to_string());
+    }
+}
@@ -13,6 +13,7 @@ pub mod provider;
 pub mod providers;
 pub mod registry;
 pub mod sha;
+pub mod workspace;
 
 // Shared core types to be extended in later steps (TDD-driven)
 use serde::{Deserialize, Serialize};
@@ -236,6 +236,8 @@ mod tests {
             ],
             extensions: vec![".ts".to_string(), ".tsx".to_string()],
             parser_options: Default::default(),
+            large_file_mb: None,
+            chunking: None,
         };
         settings
             .languages
@@ -230,6 +230,8 @@ mod tests {
             ],
             parser_options: HashMap::new(),
             config_files,
+            large_file_mb: None,
+            chunking: None,
         };
         settings
             .languages
@@ -265,6 +267,8 @@ mod tests {
             extensions: vec!["js".to_string(), "jsx".to_string()],
             parser_options: HashMap::new(),
             config_files: vec![],
+            large_file_mb: None,
+            chunking: None,
         };
         settings
             .languages
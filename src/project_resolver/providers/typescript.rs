@@ -226,6 +226,8 @@ mod tests {
             extensions: vec!["ts".to_string(), "tsx".to_string()],
             parser_options: HashMap::new(),
             config_files,
+            large_file_mb: None,
+            chunking: None,
         };
         settings
             .languages
@@ -261,6 +263,8 @@ mod tests {
             extensions: vec!["ts".to_string(), "tsx".to_string()],
             parser_options: HashMap::new(),
             config_files: vec![],
+            large_file_mb: None,
+            chunking: None,
         };
         settings
             .languages
@@ -0,0 +1,389 @@
+//! Monorepo workspace discovery
+//!
+//! Finds the member packages of Cargo workspaces, pnpm/yarn/npm workspaces
+//! and Go workspaces (`go.work`) declared at a root. The index is not split
+//! by package: packages filter the results of the one index, so a query
+//! scoped to a package keeps the results whose files fall under its root,
+//! and indexing a package walks only that directory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Workspace tool a package was declared by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceKind {
+    /// `[workspace] members` in `Cargo.toml`
+    Cargo,
+    /// `workspaces` in `package.json` or `pnpm-workspace.yaml`
+    Node,
+    /// `use` directives in `go.work`
+    Go,
+}
+
+impl WorkspaceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cargo => "cargo",
+            Self::Node => "node",
+            Self::Go => "go",
+        }
+    }
+}
+
+/// A member package of a workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspacePackage {
+    /// Name from the package manifest, or the directory name without one
+    pub name: String,
+    /// Directory holding the package manifest
+    pub root: PathBuf,
+    pub kind: WorkspaceKind,
+}
+
+/// Packages of every workspace declared at `root`, sorted by root
+///
+/// A root package (e.g. a `Cargo.toml` with both `[package]` and
+/// `[workspace]`) is listed too. Manifests that fail to parse are skipped.
+pub fn discover_packages(root: &Path) -> Vec<WorkspacePackage> {
+    let mut packages: BTreeMap<PathBuf, WorkspacePackage> = BTreeMap::new();
+    let found = cargo_packages(root)
+        .into_iter()
+        .chain(node_packages(root))
+        .chain(go_packages(root));
+    for package in found {
+        packages.entry(package.root.clone()).or_insert(package);
+    }
+    packages.into_values().collect()
+}
+
+/// Innermost package containing `path`
+pub fn package_for<'a>(
+    packages: &'a [WorkspacePackage],
+    path: &Path,
+) -> Option<&'a WorkspacePackage> {
+    packages
+        .iter()
+        .filter(|package| path.starts_with(&package.root))
+        .max_by_key(|package| package.root.components().count())
+}
+
+fn cargo_packages(root: &Path) -> Vec<WorkspacePackage> {
+    let Some(manifest) = read_toml(&root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table()) else {
+        return Vec::new();
+    };
+
+    let patterns = string_array(workspace.get("members"));
+    let excluded = string_array(workspace.get("exclude"));
+    let mut packages: Vec<WorkspacePackage> =
+        expand_members(root, &patterns, &excluded, "Cargo.toml")
+            .into_iter()
+            .map(|dir| package(dir, cargo_package_name, WorkspaceKind::Cargo))
+            .collect();
+    if manifest.contains_key("package") {
+        packages.push(package(
+            root.to_path_buf(),
+            cargo_package_name,
+            WorkspaceKind::Cargo,
+        ));
+    }
+    packages
+}
+
+fn cargo_package_name(dir: &Path) -> Option<String> {
+    let manifest = read_toml(&dir.join("Cargo.toml"))?;
+    let name = manifest.get("package")?.get("name")?.as_str()?;
+    Some(name.to_string())
+}
+
+fn node_packages(root: &Path) -> Vec<WorkspacePackage> {
+    let mut patterns = Vec::new();
+
+    if let Some(manifest) = read_json(&root.join("package.json")) {
+        // Either an array of globs or `{ "packages": [...] }` (yarn classic)
+        let workspaces = manifest.get("workspaces");
+        let globs = workspaces.and_then(|w| w.as_array()).or_else(|| {
+            workspaces
+                .and_then(|w| w.get("packages"))
+                .and_then(|p| p.as_array())
+        });
+        patterns.extend(
+            globs
+                .into_iter()
+                .flatten()
+                .filter_map(|glob| glob.as_str().map(str::to_string)),
+        );
+    }
+    if let Ok(content) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        patterns.extend(pnpm_package_globs(&content));
+    }
+
+    let (excluded, included): (Vec<String>, Vec<String>) = patterns
+        .into_iter()
+        .partition(|pattern| pattern.starts_with('!'));
+    let excluded: Vec<String> = excluded
+        .into_iter()
+        .map(|pattern| pattern.trim_start_matches('!').to_string())
+        .collect();
+
+    expand_members(root, &included, &excluded, "package.json")
+        .into_iter()
+        .map(|dir| package(dir, node_package_name, WorkspaceKind::Node))
+        .collect()
+}
+
+fn node_package_name(dir: &Path) -> Option<String> {
+    let manifest = read_json(&dir.join("package.json"))?;
+    Some(manifest.get("name")?.as_str()?.to_string())
+}
+
+/// Entries of the `packages:` list in `pnpm-workspace.yaml`
+///
+/// Only the block list form is read, which is what pnpm documents and
+/// generates.
+fn pnpm_package_globs(content: &str) -> Vec<String> {
+    let mut globs = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.split(" #").next().unwrap_or(item).trim();
+            globs.push(item.trim_matches(['\'', '"']).to_string());
+        }
+    }
+    globs
+}
+
+fn go_packages(root: &Path) -> Vec<WorkspacePackage> {
+    let Ok(content) = std::fs::read_to_string(root.join("go.work")) else {
+        return Vec::new();
+    };
+
+    // `use ./dir` or a `use ( ... )` block with one directory per line
+    let mut dirs = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                dirs.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                dirs.push(rest.to_string());
+            }
+        }
+    }
+
+    dirs.into_iter()
+        .map(|dir| root.join(dir.trim_matches('"').trim_start_matches("./")))
+        .filter(|dir| dir.join("go.mod").is_file())
+        .map(|dir| package(dir, go_module_name, WorkspaceKind::Go))
+        .collect()
+}
+
+fn go_module_name(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("go.mod")).ok()?;
+    content.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("module ")
+            .map(|module| module.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Directories matching `patterns` (relative to `root`) that hold `manifest`
+fn expand_members(
+    root: &Path,
+    patterns: &[String],
+    excluded: &[String],
+    manifest: &str,
+) -> Vec<PathBuf> {
+    let escaped_root = glob::Pattern::escape(&root.to_string_lossy());
+    let excluded: Vec<glob::Pattern> = excluded
+        .iter()
+        .filter_map(|pattern| glob::Pattern::new(normalize_pattern(pattern)).ok())
+        .collect();
+
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        let full = format!("{escaped_root}/{}", normalize_pattern(pattern));
+        let Ok(matches) = glob::glob(&full) else {
+            tracing::debug!(
+                "[workspace] invalid member pattern '{pattern}' in {}",
+                root.display()
+            );
+            continue;
+        };
+        for dir in matches.flatten() {
+            let relative = dir.strip_prefix(root).unwrap_or(&dir);
+            if dir.join(manifest).is_file() && !excluded.iter().any(|p| p.matches_path(relative)) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn normalize_pattern(pattern: &str) -> &str {
+    pattern.trim_start_matches("./").trim_end_matches('/')
+}
+
+fn package(
+    root: PathBuf,
+    name: fn(&Path) -> Option<String>,
+    kind: WorkspaceKind,
+) -> WorkspacePackage {
+    let name = name(&root).unwrap_or_else(|| {
+        root.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    WorkspacePackage { name, root, kind }
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    let content = std::fs::read_to_string(path).ok()?;
+    content
+        .parse::<toml::Table>()
+        .map_err(|e| tracing::debug!("[workspace] skipping {}: {e}", path.display()))
+        .ok()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| tracing::debug!("[workspace] skipping {}: {e}", path.display()))
+        .ok()
+}
+
+fn string_array(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn names(packages: &[WorkspacePackage]) -> Vec<(&str, WorkspaceKind)> {
+        packages
+            .iter()
+            .map(|package| (package.name.as_str(), package.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_cargo_workspace_members() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\nexclude = [\"crates/legacy\"]\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"app-core\"\n",
+        );
+        write(
+            root,
+            "crates/legacy/Cargo.toml",
+            "[package]\nname = \"legacy\"\n",
+        );
+        write(root, "crates/notes/README.md", "not a crate");
+        write(
+            root,
+            "tools/cli/Cargo.toml",
+            "[package]\nname = \"app-cli\"\n",
+        );
+
+        let packages = discover_packages(root);
+        assert_eq!(
+            names(&packages),
+            vec![
+                ("app-core", WorkspaceKind::Cargo),
+                ("app-cli", WorkspaceKind::Cargo)
+            ]
+        );
+
+        let file = root.join("crates/core/src/lib.rs");
+        assert_eq!(package_for(&packages, &file).unwrap().name, "app-core");
+        assert!(package_for(&packages, &root.join("build.rs")).is_none());
+    }
+
+    #[test]
+    fn test_node_and_go_workspaces() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{"workspaces": {"packages": ["apps/*"]}}"#,
+        );
+        write(
+            root,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'libs/**'\n  - '!libs/**/fixtures'\ncatalog:\n  - react\n",
+        );
+        write(root, "apps/web/package.json", r#"{"name": "@acme/web"}"#);
+        write(root, "libs/ui/package.json", r#"{"name": "@acme/ui"}"#);
+        write(
+            root,
+            "libs/ui/fixtures/package.json",
+            r#"{"name": "fixture"}"#,
+        );
+        write(
+            root,
+            "go.work",
+            "go 1.22\n\nuse (\n\t./services/api // main API\n)\nuse ./services/worker\n",
+        );
+        write(root, "services/api/go.mod", "module example.com/api\n");
+        write(
+            root,
+            "services/worker/go.mod",
+            "module example.com/worker\n",
+        );
+
+        let packages = discover_packages(root);
+        assert_eq!(
+            names(&packages),
+            vec![
+                ("@acme/web", WorkspaceKind::Node),
+                ("@acme/ui", WorkspaceKind::Node),
+                ("example.com/api", WorkspaceKind::Go),
+                ("example.com/worker", WorkspaceKind::Go),
+            ]
+        );
+    }
+}
@@ -0,0 +1,434 @@
+//! Scenario bundles for reproducible bug reports
+//!
+//! `codanna record -- <command>` runs a codanna command and appends what
+//! happened to a JSON bundle: the arguments, exit code, duration and error
+//! output of each step, plus a snapshot of the settings and of every indexed
+//! file (language, size, line count). Several commands can be recorded into
+//! one bundle to capture a whole scenario.
+//!
+//! Bundles are anonymized by default. Every path component is replaced by a
+//! salted hash that keeps the extension (`src/auth.rs` becomes
+//! `5f1c09e2/0b77a4d1.rs`), the same component always maps to the same hash,
+//! and the workspace and home directories are masked in error output. The
+//! salt lives in the index directory and never leaves the machine. With
+//! `--include-sources` the bundle keeps real paths and file contents instead.
+//!
+//! `codanna replay` rebuilds the workspace from a bundle in a temporary
+//! directory and runs the recorded steps against it with the recorded
+//! settings. Anonymized files are replaced by whitespace of the same size and
+//! line count, which reproduces file-walking, ignore and configuration
+//! problems; parser bugs need the sources.
+
+use crate::config::Settings;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Bundle format version; replay refuses newer bundles
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// File name of the anonymization salt inside the index directory
+pub const SALT_FILE: &str = "record.salt";
+
+/// Bytes of error output kept per step; the end of the output is kept
+const MAX_STDERR_BYTES: usize = 8 * 1024;
+
+/// Files whose names are kept when anonymizing; project providers look for them
+const KEPT_NAMES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "tsconfig.json",
+    "jsconfig.json",
+    "pyproject.toml",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+    ".gitignore",
+    ".codannaignore",
+];
+
+/// A recorded scenario
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    /// codanna version that recorded the bundle
+    pub codanna_version: String,
+    pub os: String,
+    pub arch: String,
+    /// UTC seconds since the epoch of the first step
+    pub created_at: u64,
+    /// Whether paths were anonymized and contents left out
+    pub anonymized: bool,
+    /// settings.toml as recorded, with paths anonymized
+    pub config: String,
+    /// Indexed files after the last step
+    #[serde(default)]
+    pub files: Vec<FileRecord>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+/// An indexed file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// Path relative to the workspace root
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub bytes: u64,
+    pub lines: usize,
+    /// File contents, only with `--include-sources`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// One recorded command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    /// Arguments after `codanna`
+    pub args: Vec<String>,
+    pub exit_code: i32,
+    pub duration_ms: u64,
+    /// End of the error output, sanitized
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
+    /// Lines written to stdout
+    pub stdout_lines: usize,
+}
+
+impl Bundle {
+    /// Empty bundle for this build and platform
+    pub fn new(config: String, anonymized: bool) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            codanna_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            created_at: crate::utils::get_utc_timestamp(),
+            anonymized,
+            config,
+            files: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Load a bundle, rejecting versions this build cannot replay
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let bundle: Self = serde_json::from_str(&contents)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "bundle version {} is newer than this codanna supports ({BUNDLE_VERSION})",
+                    bundle.version
+                ),
+            ));
+        }
+        Ok(bundle)
+    }
+
+    /// Write the bundle as pretty JSON, atomically
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+/// Maps workspace paths to stable, salted stand-ins
+pub struct Anonymizer {
+    salt: String,
+    workspace_root: PathBuf,
+    home: Option<PathBuf>,
+    /// Known relative paths and their stand-ins, longest first
+    known: Vec<(String, String)>,
+}
+
+impl Anonymizer {
+    pub fn new(salt: impl Into<String>, workspace_root: &Path) -> Self {
+        Self {
+            salt: salt.into(),
+            workspace_root: workspace_root.to_path_buf(),
+            home: dirs::home_dir(),
+            known: Vec::new(),
+        }
+    }
+
+    /// Anonymizer using the salt stored in `index_path`, creating one if needed
+    pub fn for_index(index_path: &Path, workspace_root: &Path) -> io::Result<Self> {
+        let salt_path = index_path.join(SALT_FILE);
+        let salt = match std::fs::read_to_string(&salt_path) {
+            Ok(salt) if !salt.trim().is_empty() => salt.trim().to_string(),
+            Ok(_) => Self::create_salt(&salt_path)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::create_salt(&salt_path)?,
+            Err(e) => return Err(e),
+        };
+        Ok(Self::new(salt, workspace_root))
+    }
+
+    fn create_salt(path: &Path) -> io::Result<String> {
+        let salt = hex::encode(rand::random::<[u8; 16]>());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, &salt)?;
+        Ok(salt)
+    }
+
+    /// Stand-in for one path component; the extension is kept
+    pub fn component(&self, name: &str) -> String {
+        if KEPT_NAMES.contains(&name) {
+            return name.to_string();
+        }
+        let (stem, extension) = match name.rfind('.') {
+            Some(dot) if dot > 0 => (&name[..dot], Some(&name[dot + 1..])),
+            _ => (name, None),
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update(stem.as_bytes());
+        let hash = hex::encode(&hasher.finalize()[..4]);
+        match extension {
+            Some(extension) => format!("{hash}.{extension}"),
+            None => hash,
+        }
+    }
+
+    /// Stand-in for a path relative to the workspace, with `/` separators
+    pub fn path(&self, relative: &Path) -> String {
+        relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(self.component(&name.to_string_lossy())),
+                Component::ParentDir => Some("..".to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Remember a relative path so [`Anonymizer::text`] replaces it
+    pub fn learn(&mut self, relative: &str) -> String {
+        let stand_in = self.path(Path::new(relative));
+        if !self.known.iter().any(|(path, _)| path == relative) {
+            self.known.push((relative.to_string(), stand_in.clone()));
+            self.known
+                .sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+        }
+        stand_in
+    }
+
+    /// Anonymize a command argument that names a path in the workspace
+    pub fn arg(&mut self, arg: &str) -> String {
+        if arg.starts_with('-') {
+            return arg.to_string();
+        }
+        let path = Path::new(arg);
+        let relative = if path.is_absolute() {
+            match path.strip_prefix(&self.workspace_root) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => return arg.to_string(),
+            }
+        } else if self.workspace_root.join(path).exists() {
+            path.to_path_buf()
+        } else {
+            return arg.to_string();
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if relative.is_empty() {
+            return ".".to_string();
+        }
+        self.learn(&relative)
+    }
+
+    /// Mask the workspace, home directory and known paths in free text
+    pub fn text(&self, text: &str) -> String {
+        let mut text = text.replace(&*self.workspace_root.to_string_lossy(), "<workspace>");
+        if let Some(home) = &self.home {
+            let home = home.to_string_lossy();
+            if !home.is_empty() {
+                text = text.replace(&*home, "~");
+            }
+        }
+        for (path, stand_in) in &self.known {
+            text = text.replace(path, stand_in);
+        }
+        text
+    }
+}
+
+/// Snapshot the indexed files under `workspace_root`
+///
+/// Paths are anonymized when `anonymizer` is given; otherwise contents are
+/// included.
+pub fn snapshot_files(
+    paths: &[PathBuf],
+    workspace_root: &Path,
+    mut anonymizer: Option<&mut Anonymizer>,
+) -> Vec<FileRecord> {
+    let registry = crate::parsing::get_registry()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let mut files = Vec::new();
+    for path in paths {
+        let absolute = if path.is_absolute() {
+            path.clone()
+        } else {
+            workspace_root.join(path)
+        };
+        let Ok(relative) = absolute.strip_prefix(workspace_root) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(&absolute) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let language = absolute
+            .extension()
+            .and_then(|extension| registry.get_by_extension(&extension.to_string_lossy()))
+            .map(|definition| definition.id().as_str().to_string());
+
+        let bytes = content.len() as u64;
+        let lines = content.lines().count();
+        let (path, content) = match anonymizer.as_deref_mut() {
+            Some(anonymizer) => (anonymizer.learn(&relative), None),
+            None => (relative, Some(content)),
+        };
+        files.push(FileRecord {
+            path,
+            language,
+            bytes,
+            lines,
+            content,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Settings as recorded: indexed paths anonymized, machine-specific values cleared
+pub fn recorded_config(settings: &Settings, anonymizer: Option<&mut Anonymizer>) -> String {
+    let mut settings = settings.clone();
+    settings.workspace_root = None;
+    settings.index_path = Settings::default().index_path;
+    if let Some(anonymizer) = anonymizer {
+        let root = anonymizer.workspace_root.clone();
+        settings.indexing.indexed_paths = settings
+            .indexing
+            .indexed_paths
+            .iter()
+            .map(|path| {
+                let relative = path.strip_prefix(&root).unwrap_or(path);
+                PathBuf::from(anonymizer.learn(&relative.to_string_lossy().replace('\\', "/")))
+            })
+            .collect();
+        settings.summary.endpoint = "<redacted>".to_string();
+    }
+    toml::to_string_pretty(&settings).unwrap_or_default()
+}
+
+/// Keep the end of `stderr`, where errors usually are
+pub fn tail(stderr: &str) -> &str {
+    if stderr.len() <= MAX_STDERR_BYTES {
+        return stderr;
+    }
+    let mut start = stderr.len() - MAX_STDERR_BYTES;
+    while !stderr.is_char_boundary(start) {
+        start += 1;
+    }
+    &stderr[start..]
+}
+
+/// Contents written in place of an anonymized file: whitespace of the same
+/// size and line count
+pub fn placeholder(file: &FileRecord) -> String {
+    let bytes = file.bytes as usize;
+    if file.lines == 0 || bytes < file.lines {
+        return " ".repeat(bytes);
+    }
+    let width = bytes / file.lines - 1;
+    let mut content = format!("{}\n", " ".repeat(width)).repeat(file.lines);
+    // Spread the remainder over the first line so the size matches exactly
+    content.insert_str(0, &" ".repeat(bytes - content.len()));
+    content
+}
+
+/// Write the files of a bundle under `root`
+pub fn materialize(bundle: &Bundle, root: &Path) -> io::Result<()> {
+    for file in &bundle.files {
+        let relative = Path::new(&file.path);
+        // Bundles come from elsewhere; never write outside the replay directory
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            continue;
+        }
+        let target = root.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match &file.content {
+            Some(content) => std::fs::write(&target, content)?,
+            None => std::fs::write(&target, placeholder(file))?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_anonymizer_is_stable_and_keeps_extensions() {
+        let mut anonymizer = Anonymizer::new("salt", Path::new("/work/project"));
+        let file = anonymizer.learn("src/auth/login.rs");
+        let dir = anonymizer.arg("/work/project/src");
+
+        assert!(file.ends_with(".rs"));
+        assert!(!file.contains("auth"));
+        assert!(file.starts_with(&dir));
+        assert_eq!(anonymizer.component("Cargo.toml"), "Cargo.toml");
+        assert_ne!(
+            Anonymizer::new("other", Path::new("/")).component("src"),
+            anonymizer.component("src")
+        );
+
+        let message = anonymizer.text("error in /work/project/src/auth/login.rs");
+        assert_eq!(message, format!("error in <workspace>/{file}"));
+    }
+
+    #[test]
+    fn test_materialize_matches_recorded_shape() {
+        let temp = TempDir::new().unwrap();
+        let mut bundle = Bundle::new(String::new(), true);
+        bundle.files.push(FileRecord {
+            path: "a1b2c3d4/e5f6a7b8.py".to_string(),
+            language: Some("python".to_string()),
+            bytes: 40,
+            lines: 4,
+            content: None,
+        });
+        bundle.files.push(FileRecord {
+            path: "../escape.py".to_string(),
+            language: None,
+            bytes: 1,
+            lines: 1,
+            content: Some("x".to_string()),
+        });
+        materialize(&bundle, temp.path()).unwrap();
+
+        let written = std::fs::read_to_string(temp.path().join("a1b2c3d4/e5f6a7b8.py")).unwrap();
+        assert_eq!(written.len(), 40);
+        assert_eq!(written.lines().count(), 4);
+        assert!(!temp.path().parent().unwrap().join("escape.py").exists());
+    }
+}
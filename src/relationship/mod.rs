@@ -15,6 +15,10 @@ pub enum RelationKind {
     DefinedIn,
     References,
     ReferencedBy,
+    /// The same API in another language, e.g. a client method and the
+    /// server handler it requests. Inferred after indexing, so its weight is
+    /// a confidence below 1.0; stored in both directions.
+    Mirrors,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -86,6 +90,7 @@ impl RelationKind {
             Self::DefinedIn => Self::Defines,
             Self::References => Self::ReferencedBy,
             Self::ReferencedBy => Self::References,
+            Self::Mirrors => Self::Mirrors,
         }
     }
 
@@ -189,6 +194,7 @@ mod tests {
             RelationKind::References.inverse(),
             RelationKind::ReferencedBy
         );
+        assert_eq!(RelationKind::Mirrors.inverse(), RelationKind::Mirrors);
     }
 
     #[test]
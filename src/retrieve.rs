@@ -1,13 +1,66 @@
 //! Retrieve command implementations using UnifiedOutput schema
 
 use crate::Symbol;
-use crate::indexing::facade::IndexFacade;
+use crate::indexing::blame::BlameCommit;
+use crate::indexing::facade::{IndexFacade, SearchScope};
+use crate::indexing::{QueryBudget, TruncationReason};
 use crate::io::{
     EntityType, ExitCode, OutputFormat, OutputManager, OutputStatus,
     schema::{OutputData, OutputMetadata, UnifiedOutput, UnifiedOutputBuilder},
 };
+use crate::storage::BlameStore;
 use crate::symbol::context::SymbolContext;
 use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Metadata for a completed query, flagging results cut short by the budget
+fn budgeted_metadata<'a>(query: Cow<'a, str>, budget: &QueryBudget) -> OutputMetadata<'a> {
+    let mut extra = HashMap::new();
+    if let Some(reason) = budget.truncation() {
+        let reason = match reason {
+            TruncationReason::Timeout => "timeout",
+            TruncationReason::CandidateLimit => "max_candidates",
+        };
+        extra.insert(Cow::Borrowed("truncated_reason"), reason.into());
+    }
+
+    OutputMetadata {
+        query: Some(query),
+        tool: None,
+        timing_ms: None,
+        truncated: budget.is_truncated().then_some(true),
+        extra,
+    }
+}
+
+/// Attach the budget's truncation note as guidance, if results were cut short
+fn with_truncation_note<'a, T>(
+    builder: UnifiedOutputBuilder<'a, T>,
+    budget: &QueryBudget,
+) -> UnifiedOutputBuilder<'a, T> {
+    match budget.truncation_note() {
+        Some(note) => builder.with_guidance(note),
+        None => builder,
+    }
+}
+
+/// Cached module summaries for the files in a result set, keyed by file path
+fn file_summaries(indexer: &IndexFacade, results: &[SymbolContext]) -> Option<serde_json::Value> {
+    if !indexer.settings().summary.enabled {
+        return None;
+    }
+    let cache = crate::summary::SummaryCache::load(indexer.index_base()).ok()?;
+    let summaries: serde_json::Map<String, serde_json::Value> = results
+        .iter()
+        .filter_map(|ctx| {
+            let path = &*ctx.symbol.file_path;
+            cache
+                .get(path)
+                .map(|summary| (path.to_string(), summary.into()))
+        })
+        .collect();
+    (!summaries.is_empty()).then_some(serde_json::Value::Object(summaries))
+}
 
 /// Execute retrieve symbol command
 pub fn retrieve_symbol(
@@ -17,6 +70,7 @@ pub fn retrieve_symbol(
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
+    let budget = indexer.query_budget();
 
     // Check if name is a symbol_id (format: "symbol_id:123")
     let symbols = if let Some(id_str) = name.strip_prefix("symbol_id:") {
@@ -63,28 +117,19 @@ pub fn retrieve_symbol(
         // Transform symbols to SymbolContext with file paths and relationships
         use crate::symbol::context::ContextIncludes;
 
-        let symbols_with_path: Vec<SymbolContext> = symbols
-            .into_iter()
-            .filter_map(|symbol| {
-                // Get full context with relationships (same as MCP find_symbol)
-                indexer.get_symbol_context(
-                    symbol.id,
-                    ContextIncludes::IMPLEMENTATIONS
-                        | ContextIncludes::DEFINITIONS
-                        | ContextIncludes::CALLERS,
-                )
-            })
-            .collect();
+        let symbols_with_path: Vec<SymbolContext> = budget.collect(symbols, |symbol| {
+            // Get full context with relationships (same as MCP find_symbol)
+            indexer.get_symbol_context(
+                symbol.id,
+                ContextIncludes::IMPLEMENTATIONS
+                    | ContextIncludes::DEFINITIONS
+                    | ContextIncludes::CALLERS,
+            )
+        });
 
-        let unified = UnifiedOutputBuilder::items(symbols_with_path, EntityType::Symbol)
-            .with_metadata(OutputMetadata {
-                query: Some(Cow::Borrowed(name)),
-                tool: None,
-                timing_ms: None,
-                truncated: None,
-                extra: Default::default(),
-            })
-            .build();
+        let builder = UnifiedOutputBuilder::items(symbols_with_path, EntityType::Symbol)
+            .with_metadata(budgeted_metadata(Cow::Borrowed(name), &budget));
+        let unified = with_truncation_note(builder, &budget).build();
 
         match output.unified(unified) {
             Ok(code) => code,
@@ -104,6 +149,7 @@ pub fn retrieve_callers(
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
+    let budget = indexer.query_budget();
 
     // Check if function is a symbol_id (format: "symbol_id:123")
     let (symbol, query_str) = if let Some(id_str) = function.strip_prefix("symbol_id:") {
@@ -235,26 +281,17 @@ pub fn retrieve_callers(
     // Transform to SymbolContext with relationships
     use crate::symbol::context::ContextIncludes;
 
-    let callers_with_path: Vec<SymbolContext> = all_callers
-        .into_iter()
-        .filter_map(|symbol| {
-            // Get context for each caller symbol (what it calls and defines)
-            indexer.get_symbol_context(
-                symbol.id,
-                ContextIncludes::CALLS | ContextIncludes::DEFINITIONS,
-            )
-        })
-        .collect();
+    let callers_with_path: Vec<SymbolContext> = budget.collect(all_callers, |symbol| {
+        // Get context for each caller symbol (what it calls and defines)
+        indexer.get_symbol_context(
+            symbol.id,
+            ContextIncludes::CALLS | ContextIncludes::DEFINITIONS,
+        )
+    });
 
-    let unified = UnifiedOutputBuilder::items(callers_with_path, EntityType::Function)
-        .with_metadata(OutputMetadata {
-            query: Some(Cow::Owned(query_str)),
-            tool: None,
-            timing_ms: None,
-            truncated: None,
-            extra: Default::default(),
-        })
-        .build();
+    let builder = UnifiedOutputBuilder::items(callers_with_path, EntityType::Function)
+        .with_metadata(budgeted_metadata(Cow::Owned(query_str), &budget));
+    let unified = with_truncation_note(builder, &budget).build();
 
     match output.unified(unified) {
         Ok(code) => code,
@@ -273,6 +310,7 @@ pub fn retrieve_calls(
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
+    let budget = indexer.query_budget();
 
     // Check if function is a symbol_id (format: "symbol_id:123" or just "123" if numeric)
     let (symbol, query_str) = if let Some(id_str) = function.strip_prefix("symbol_id:") {
@@ -404,26 +442,17 @@ pub fn retrieve_calls(
     // Transform to SymbolContext with relationships
     use crate::symbol::context::ContextIncludes;
 
-    let calls_with_path: Vec<SymbolContext> = all_calls
-        .into_iter()
-        .filter_map(|symbol| {
-            // Get context for each called function (who calls it, what it defines)
-            indexer.get_symbol_context(
-                symbol.id,
-                ContextIncludes::CALLERS | ContextIncludes::DEFINITIONS,
-            )
-        })
-        .collect();
+    let calls_with_path: Vec<SymbolContext> = budget.collect(all_calls, |symbol| {
+        // Get context for each called function (who calls it, what it defines)
+        indexer.get_symbol_context(
+            symbol.id,
+            ContextIncludes::CALLERS | ContextIncludes::DEFINITIONS,
+        )
+    });
 
-    let unified = UnifiedOutputBuilder::items(calls_with_path, EntityType::Function)
-        .with_metadata(OutputMetadata {
-            query: Some(Cow::Owned(query_str)),
-            tool: None,
-            timing_ms: None,
-            truncated: None,
-            extra: Default::default(),
-        })
-        .build();
+    let builder = UnifiedOutputBuilder::items(calls_with_path, EntityType::Function)
+        .with_metadata(budgeted_metadata(Cow::Owned(query_str), &budget));
+    let unified = with_truncation_note(builder, &budget).build();
 
     match output.unified(unified) {
         Ok(code) => code,
@@ -442,6 +471,7 @@ pub fn retrieve_implementations(
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
+    let budget = indexer.query_budget();
 
     // Find the trait symbol first
     let trait_symbols = indexer.find_symbols_by_name(trait_name, language);
@@ -454,26 +484,17 @@ pub fn retrieve_implementations(
     // Transform implementations to SymbolContext with relationships
     use crate::symbol::context::ContextIncludes;
 
-    let impls_with_path: Vec<SymbolContext> = implementations
-        .into_iter()
-        .filter_map(|symbol| {
-            // Get context for each implementation (what it defines, what calls it)
-            indexer.get_symbol_context(
-                symbol.id,
-                ContextIncludes::DEFINITIONS | ContextIncludes::CALLERS,
-            )
-        })
-        .collect();
+    let impls_with_path: Vec<SymbolContext> = budget.collect(implementations, |symbol| {
+        // Get context for each implementation (what it defines, what calls it)
+        indexer.get_symbol_context(
+            symbol.id,
+            ContextIncludes::DEFINITIONS | ContextIncludes::CALLERS,
+        )
+    });
 
-    let unified = UnifiedOutputBuilder::items(impls_with_path, EntityType::Trait)
-        .with_metadata(OutputMetadata {
-            query: Some(Cow::Borrowed(trait_name)),
-            tool: None,
-            timing_ms: None,
-            truncated: None,
-            extra: Default::default(),
-        })
-        .build();
+    let builder = UnifiedOutputBuilder::items(impls_with_path, EntityType::Trait)
+        .with_metadata(budgeted_metadata(Cow::Borrowed(trait_name), &budget));
+    let unified = with_truncation_note(builder, &budget).build();
 
     match output.unified(unified) {
         Ok(code) => code,
@@ -492,60 +513,205 @@ pub fn retrieve_search(
     kind: Option<&str>,
     module: Option<&str>,
     language: Option<&str>,
+    scope: &SearchScope,
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
+    let budget = indexer.query_budget();
+    let kind_filter = kind.and_then(parse_kind_filter);
+
+    // Never ask the index for more hits than the budget lets us expand
+    let capped_limit = budget.clamp_limit(limit);
+    let search_results =
+        match indexer.search_scoped(query, capped_limit, kind_filter, module, language, scope) {
+            Ok(results) => results,
+            Err(e) if !scope.is_empty() => {
+                eprintln!("Error: {e}");
+                return ExitCode::GeneralError;
+            }
+            Err(_) => Vec::new(),
+        };
+    if search_results.len() == capped_limit && capped_limit < limit {
+        budget.mark(TruncationReason::CandidateLimit);
+    }
 
-    // Parse the kind filter if provided
-    let kind_filter = kind.and_then(|k| match k.to_lowercase().as_str() {
-        "function" => Some(crate::SymbolKind::Function),
-        "struct" => Some(crate::SymbolKind::Struct),
-        "trait" => Some(crate::SymbolKind::Trait),
-        "interface" => Some(crate::SymbolKind::Interface),
-        "class" => Some(crate::SymbolKind::Class),
-        "method" => Some(crate::SymbolKind::Method),
-        "field" => Some(crate::SymbolKind::Field),
-        "variable" => Some(crate::SymbolKind::Variable),
-        "constant" => Some(crate::SymbolKind::Constant),
-        "module" => Some(crate::SymbolKind::Module),
-        "typealias" => Some(crate::SymbolKind::TypeAlias),
-        "enum" => Some(crate::SymbolKind::Enum),
-        _ => {
-            eprintln!("Warning: Unknown symbol kind '{k}', ignoring filter");
-            None
-        }
+    // Transform search results to SymbolContext with relationships
+    use crate::symbol::context::ContextIncludes;
+
+    let results_with_path: Vec<SymbolContext> = budget.collect(search_results, |result| {
+        // Get full context for each search result
+        indexer.get_symbol_context(
+            result.symbol_id,
+            ContextIncludes::IMPLEMENTATIONS
+                | ContextIncludes::DEFINITIONS
+                | ContextIncludes::CALLERS,
+        )
     });
 
-    let search_results = indexer
-        .search(query, limit, kind_filter, module, language)
-        .unwrap_or_default();
+    let mut metadata = budgeted_metadata(Cow::Borrowed(query), &budget);
+    if let Some(summaries) = file_summaries(indexer, &results_with_path) {
+        metadata
+            .extra
+            .insert(Cow::Borrowed("file_summaries"), summaries);
+    }
+
+    let builder = UnifiedOutputBuilder::items(results_with_path, EntityType::SearchResult)
+        .with_metadata(metadata);
+    let unified = with_truncation_note(builder, &budget).build();
 
-    // Transform search results to SymbolContext with relationships
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Parse a `--kind` filter, warning about unknown kinds
+fn parse_kind_filter(kind: &str) -> Option<crate::SymbolKind> {
+    let parsed = crate::SymbolKind::parse_ignore_case(kind);
+    if parsed.is_none() {
+        eprintln!("Warning: Unknown symbol kind '{kind}', ignoring filter");
+    }
+    parsed
+}
+
+/// Execute retrieve ask: translate a natural-language request into a
+/// structured query and run it
+///
+/// With `translate_only`, prints the structured query instead of searching.
+pub fn retrieve_ask(
+    indexer: &IndexFacade,
+    request: &str,
+    limit: usize,
+    translate_only: bool,
+    format: OutputFormat,
+) -> ExitCode {
+    use crate::nl_query;
     use crate::symbol::context::ContextIncludes;
 
-    let results_with_path: Vec<SymbolContext> = search_results
-        .into_iter()
-        .filter_map(|result| {
-            // Get full context for each search result
-            indexer.get_symbol_context(
-                result.symbol_id,
-                ContextIncludes::IMPLEMENTATIONS
-                    | ContextIncludes::DEFINITIONS
-                    | ContextIncludes::CALLERS,
-            )
-        })
-        .collect();
+    let structured = nl_query::translator(indexer.settings()).translate(request);
+    if translate_only {
+        let rendered = match format {
+            OutputFormat::Json => serde_json::to_string_pretty(&structured)
+                .unwrap_or_else(|e| format!("Error serializing query: {e}")),
+            OutputFormat::Text => {
+                let mut line = format!("query: {}", structured.query);
+                for filter in structured.describe_filters() {
+                    line.push_str(&format!("\n{}", filter.replacen(':', ": ", 1)));
+                }
+                line
+            }
+        };
+        println!("{rendered}");
+        return ExitCode::Success;
+    }
 
-    let unified = UnifiedOutputBuilder::items(results_with_path, EntityType::SearchResult)
+    let filter = match structured.filter() {
+        Ok(filter) => filter,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    let filters = structured.describe_filters();
+    if filters.is_empty() {
+        eprintln!("Searching for '{}'", structured.query);
+    } else {
+        eprintln!(
+            "Searching for '{}' with {}",
+            structured.query,
+            filters.join(" ")
+        );
+    }
+
+    let mut output = OutputManager::new(format);
+    let budget = indexer.query_budget();
+    let capped_limit = budget.clamp_limit(limit);
+    let search_results = if indexer.has_semantic_search() {
+        indexer.hybrid_search_filtered(&structured.query, capped_limit, None, &filter)
+    } else {
+        indexer.text_search_filtered(&structured.query, capped_limit, &filter)
+    };
+    let search_results = match search_results {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+    if search_results.len() == capped_limit && capped_limit < limit {
+        budget.mark(TruncationReason::CandidateLimit);
+    }
+
+    let results: Vec<SymbolContext> = budget.collect(search_results, |(symbol, _)| {
+        indexer.get_symbol_context(
+            symbol.id,
+            ContextIncludes::IMPLEMENTATIONS
+                | ContextIncludes::DEFINITIONS
+                | ContextIncludes::CALLERS,
+        )
+    });
+
+    let mut metadata = budgeted_metadata(Cow::Owned(structured.query.clone()), &budget);
+    if let Ok(value) = serde_json::to_value(&structured) {
+        metadata
+            .extra
+            .insert(Cow::Borrowed("structured_query"), value);
+    }
+
+    let builder =
+        UnifiedOutputBuilder::items(results, EntityType::SearchResult).with_metadata(metadata);
+    let unified = with_truncation_note(builder, &budget).build();
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Execute retrieve knowledge: symbols and indexed documents in one ranking
+///
+/// Document collections are searched when `codanna documents index` has
+/// built the document store; otherwise only doc comments are searched.
+pub fn retrieve_knowledge(
+    indexer: &IndexFacade,
+    query: &str,
+    collection: Option<String>,
+    limit: usize,
+    format: OutputFormat,
+) -> ExitCode {
+    use crate::indexing::facade::SemanticFilter;
+
+    let documents = match document_hits(indexer.settings(), query, collection, limit) {
+        Ok(documents) => documents,
+        Err(e) => {
+            eprintln!("Warning: {e}; searching code only");
+            Vec::new()
+        }
+    };
+    let hits = match indexer.knowledge_search(query, limit, &SemanticFilter::default(), documents) {
+        Ok(hits) => hits,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::GeneralError;
+        }
+    };
+
+    let mut output = OutputManager::new(format);
+    let unified = UnifiedOutputBuilder::items(hits, EntityType::SearchResult)
         .with_metadata(OutputMetadata {
             query: Some(Cow::Borrowed(query)),
             tool: None,
             timing_ms: None,
             truncated: None,
-            extra: Default::default(),
+            extra: HashMap::new(),
         })
         .build();
-
     match output.unified(unified) {
         Ok(code) => code,
         Err(e) => {
@@ -555,6 +721,119 @@ pub fn retrieve_search(
     }
 }
 
+/// Hits of the document store for `query`, none if it was never built
+fn document_hits(
+    settings: &crate::config::Settings,
+    query: &str,
+    collection: Option<String>,
+    limit: usize,
+) -> Result<Vec<crate::documents::SearchResult>, String> {
+    use crate::documents::{DocumentStore, SearchQuery};
+    use crate::vector::{EmbeddingGenerator, VectorDimension};
+
+    let doc_path = settings.index_path.join("documents");
+    if !doc_path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut store = if settings.semantic_search.enabled {
+        let generator = crate::vector::create_provider(&settings.semantic_search, false)
+            .map_err(|e| format!("Failed to create embedding generator: {e}"))?;
+        DocumentStore::new_compressed(
+            &doc_path,
+            generator.dimension(),
+            settings.storage.compression.documents_level,
+        )
+        .and_then(|store| store.with_embeddings(Box::new(generator)))
+        .map_err(|e| format!("Failed to open document store: {e}"))?
+    } else {
+        DocumentStore::new_compressed(
+            &doc_path,
+            VectorDimension::dimension_384(),
+            settings.storage.compression.documents_level,
+        )
+        .map_err(|e| format!("Failed to open document store: {e}"))?
+    };
+    store
+        .search(SearchQuery {
+            text: query.to_string(),
+            collection,
+            document: None,
+            limit,
+            preview_config: Some(settings.documents.search.clone()),
+        })
+        .map_err(|e| format!("Document search failed: {e}"))
+}
+
+/// Execute retrieve search with an interactive picker
+///
+/// Prints the chosen symbol as `path:line:col` (1-based) on stdout, so the
+/// result can be handed to an editor. Returns `NotFound` when nothing matched
+/// or the picker was cancelled.
+pub fn retrieve_search_pick(
+    indexer: &IndexFacade,
+    query: &str,
+    limit: usize,
+    kind: Option<&str>,
+    module: Option<&str>,
+    language: Option<&str>,
+    scope: &SearchScope,
+) -> ExitCode {
+    use crate::display::{PickItem, pick};
+
+    let budget = indexer.query_budget();
+    let kind_filter = kind.and_then(parse_kind_filter);
+    let results = match indexer.search_scoped(
+        query,
+        budget.clamp_limit(limit),
+        kind_filter,
+        module,
+        language,
+        scope,
+    ) {
+        Ok(results) => results,
+        Err(e) if !scope.is_empty() => {
+            eprintln!("Error: {e}");
+            return ExitCode::GeneralError;
+        }
+        Err(_) => Vec::new(),
+    };
+    if results.is_empty() {
+        eprintln!("No symbols found for '{query}'");
+        return ExitCode::NotFound;
+    }
+
+    let locations: Vec<String> = results
+        .iter()
+        .map(|result| {
+            format!(
+                "{}:{}:{}",
+                result.file_path,
+                result.line + 1,
+                result.column + 1
+            )
+        })
+        .collect();
+    let items: Vec<PickItem> = results
+        .iter()
+        .zip(&locations)
+        .map(|(result, location)| {
+            PickItem::new(format!("{} ({:?})", result.name, result.kind), location)
+        })
+        .collect();
+
+    match pick(&items, "search>") {
+        Ok(Some(index)) => {
+            println!("{}", locations[index]);
+            ExitCode::Success
+        }
+        Ok(None) => ExitCode::NotFound,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
 /// Execute retrieve impact command
 // DEPRECATED: This function has been disabled.
 // Use MCP semantic_search_with_context or slash commands instead.
@@ -643,6 +922,7 @@ pub fn retrieve_describe(
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
+    let budget = indexer.query_budget();
 
     // Check if symbol_name is a symbol_id (format: "symbol_id:123")
     let (symbol, query_str) = if let Some(id_str) = symbol_name.strip_prefix("symbol_id:") {
@@ -747,13 +1027,13 @@ pub fn retrieve_describe(
     };
 
     // Get calls for this specific symbol
-    let calls = indexer.get_called_functions_with_metadata(symbol.id);
+    let calls = budget.cap(indexer.get_called_functions_with_metadata(symbol.id));
     if !calls.is_empty() {
         context.relationships.calls = Some(calls);
     }
 
     // Get callers for this specific symbol
-    let callers = indexer.get_calling_functions_with_metadata(symbol.id);
+    let callers = budget.cap(indexer.get_calling_functions_with_metadata(symbol.id));
     if !callers.is_empty() {
         context.relationships.called_by = Some(callers);
     }
@@ -766,16 +1046,21 @@ pub fn retrieve_describe(
 
     // Load implementations (for traits/interfaces) and implements (for types)
     use crate::SymbolKind;
-    match symbol.kind {
-        SymbolKind::Trait | SymbolKind::Interface => {
-            let implementations = indexer.get_implementations(symbol.id);
+    let kind = if budget.expired() {
+        None
+    } else {
+        Some(symbol.kind)
+    };
+    match kind {
+        Some(SymbolKind::Trait | SymbolKind::Interface) => {
+            let implementations = budget.cap(indexer.get_implementations(symbol.id));
             if !implementations.is_empty() {
                 context.relationships.implemented_by = Some(implementations);
             }
         }
-        SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Class => {
+        Some(SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Class) => {
             // What traits does this type implement?
-            let impls = indexer.get_implemented_traits(symbol.id);
+            let impls = budget.cap(indexer.get_implemented_traits(symbol.id));
             if !impls.is_empty() {
                 context.relationships.implements = Some(impls);
             }
@@ -784,32 +1069,36 @@ pub fn retrieve_describe(
     }
 
     // Load extends relationships (for classes)
-    match symbol.kind {
-        SymbolKind::Class | SymbolKind::Struct => {
-            // What does this class extend?
-            let extends = indexer.get_extends(symbol.id);
-            if !extends.is_empty() {
-                context.relationships.extends = Some(extends);
-            }
+    if let Some(SymbolKind::Class | SymbolKind::Struct) = kind {
+        // What does this class extend?
+        let extends = budget.cap(indexer.get_extends(symbol.id));
+        if !extends.is_empty() {
+            context.relationships.extends = Some(extends);
+        }
 
-            // What classes extend this class?
-            let extended_by = indexer.get_extended_by(symbol.id);
-            if !extended_by.is_empty() {
-                context.relationships.extended_by = Some(extended_by);
-            }
+        // What classes extend this class?
+        let extended_by = budget.cap(indexer.get_extended_by(symbol.id));
+        if !extended_by.is_empty() {
+            context.relationships.extended_by = Some(extended_by);
         }
-        _ => {}
     }
 
     // Load uses relationships (for all symbols)
-    let uses = indexer.get_uses(symbol.id);
-    if !uses.is_empty() {
-        context.relationships.uses = Some(uses);
+    if !budget.expired() {
+        let uses = budget.cap(indexer.get_uses(symbol.id));
+        if !uses.is_empty() {
+            context.relationships.uses = Some(uses);
+        }
+
+        let used_by = budget.cap(indexer.get_used_by(symbol.id));
+        if !used_by.is_empty() {
+            context.relationships.used_by = Some(used_by);
+        }
     }
 
-    let used_by = indexer.get_used_by(symbol.id);
-    if !used_by.is_empty() {
-        context.relationships.used_by = Some(used_by);
+    let mirrors = indexer.get_mirrors(symbol.id);
+    if !mirrors.is_empty() {
+        context.relationships.mirrors = Some(mirrors);
     }
 
     let unified = UnifiedOutput {
@@ -819,18 +1108,111 @@ pub fn retrieve_describe(
         data: OutputData::Single {
             item: Box::new(context),
         },
-        metadata: Some(OutputMetadata {
-            query: Some(Cow::Owned(query_str)),
+        metadata: Some(budgeted_metadata(Cow::Owned(query_str), &budget)),
+        guidance: budget.truncation_note().map(Cow::Owned),
+        exit_code: ExitCode::Success,
+    };
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Git provenance of one symbol, as printed by `retrieve blame`
+#[derive(Debug, serde::Serialize)]
+struct SymbolProvenance {
+    name: String,
+    kind: String,
+    symbol_id: u32,
+    location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    introduced: Option<BlameCommit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<BlameCommit>,
+}
+
+impl std::fmt::Display for SymbolProvenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} {} at {} [symbol_id:{}]",
+            self.kind, self.name, self.location, self.symbol_id
+        )?;
+        match (&self.introduced, &self.last_modified) {
+            (Some(introduced), Some(last_modified)) => {
+                writeln!(f, "  Introduced:    {}", introduced.describe())?;
+                write!(f, "  Last modified: {}", last_modified.describe())
+            }
+            _ => write!(f, "  No provenance recorded"),
+        }
+    }
+}
+
+/// Execute retrieve blame command
+pub fn retrieve_blame(
+    indexer: &IndexFacade,
+    symbol_name: &str,
+    language: Option<&str>,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+
+    let symbols = match symbol_name.strip_prefix("symbol_id:") {
+        Some(id_str) => match id_str.parse::<u32>() {
+            Ok(id) => indexer
+                .get_symbol(crate::SymbolId(id))
+                .into_iter()
+                .collect(),
+            Err(_) => {
+                eprintln!("Invalid symbol_id format: {id_str}");
+                return ExitCode::GeneralError;
+            }
+        },
+        None => indexer.find_symbols_by_name(symbol_name, language),
+    };
+
+    let blames = match indexer.get_symbol_blames() {
+        Ok(blames) => blames,
+        Err(e) => {
+            eprintln!("Failed to read symbol provenance: {e}");
+            return ExitCode::IoError;
+        }
+    };
+    let items: Vec<SymbolProvenance> = symbols
+        .iter()
+        .map(|symbol| {
+            let blame = BlameStore::find(&blames, symbol);
+            SymbolProvenance {
+                name: symbol.name.to_string(),
+                kind: format!("{:?}", symbol.kind),
+                symbol_id: symbol.id.value(),
+                location: SymbolContext::symbol_location(symbol),
+                introduced: blame.map(|blame| blame.introduced.clone()),
+                last_modified: blame.map(|blame| blame.last_modified.clone()),
+            }
+        })
+        .collect();
+
+    let unrecorded = items.iter().any(|item| item.introduced.is_none());
+    let mut builder =
+        UnifiedOutputBuilder::items(items, EntityType::Symbol).with_metadata(OutputMetadata {
+            query: Some(Cow::Borrowed(symbol_name)),
             tool: None,
             timing_ms: None,
             truncated: None,
             extra: Default::default(),
-        }),
-        guidance: None,
-        exit_code: ExitCode::Success,
-    };
+        });
+    if unrecorded && !indexer.settings().indexing.blame {
+        builder = builder.with_guidance(
+            "Provenance is recorded while indexing: set indexing.blame = true and re-index",
+        );
+    }
 
-    match output.unified(unified) {
+    match output.unified(builder.build()) {
         Ok(code) => code,
         Err(e) => {
             eprintln!("Error writing output: {e}");
@@ -0,0 +1,76 @@
+//! Error types for self-update
+
+use crate::io::exit_code::ExitCode;
+use std::{io, path::PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur while updating the codanna binary
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    #[error(
+        "Network error: {0}\nSuggestion: Check your internet connection or set GITHUB_TOKEN if rate limited"
+    )]
+    Network(String),
+
+    #[error(
+        "Release {version} not found\nSuggestion: See https://github.com/bartolli/codanna/releases for available versions"
+    )]
+    ReleaseNotFound { version: String },
+
+    #[error(
+        "Release {version} has no build for {platform}\nSuggestion: Install from source with 'cargo install codanna'"
+    )]
+    NoArtifact { version: String, platform: String },
+
+    #[error(
+        "Self-update is not supported on {os}/{arch}\nSuggestion: Install from source with 'cargo install codanna'"
+    )]
+    UnsupportedPlatform { os: String, arch: String },
+
+    #[error(
+        "Checksum mismatch for {name}: expected {expected}, got {actual}\nSuggestion: Retry the update; the download may be corrupted"
+    )]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error(
+        "Failed to extract {archive}: {reason}\nSuggestion: Make sure the 'tar' command is available"
+    )]
+    Extract { archive: String, reason: String },
+
+    #[error("codanna at {path} is managed by {manager}\nSuggestion: Update it with '{command}'")]
+    ManagedInstall {
+        path: PathBuf,
+        manager: &'static str,
+        command: &'static str,
+    },
+
+    #[error("Invalid release manifest: {0}\nSuggestion: Retry later or install a specific version")]
+    InvalidManifest(String),
+
+    #[error("IO error: {0}\nSuggestion: Check that the codanna binary's directory is writable")]
+    Io(#[from] io::Error),
+}
+
+/// Result type for self-update operations
+pub type UpdateResult<T> = Result<T, UpdateError>;
+
+impl UpdateError {
+    /// Map update errors to CLI exit codes
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            UpdateError::ReleaseNotFound { .. } | UpdateError::NoArtifact { .. } => {
+                ExitCode::NotFound
+            }
+            UpdateError::UnsupportedPlatform { .. } | UpdateError::ManagedInstall { .. } => {
+                ExitCode::UnsupportedOperation
+            }
+            UpdateError::ChecksumMismatch { .. } => ExitCode::BlockingError,
+            UpdateError::Io(_) | UpdateError::Extract { .. } => ExitCode::IoError,
+            UpdateError::Network(_) | UpdateError::InvalidManifest(_) => ExitCode::GeneralError,
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! Self-update from GitHub releases
+//!
+//! Each release publishes per-platform archives plus a `dist-manifest.json`
+//! listing their URLs and SHA-256 checksums (see `.github/workflows/release.yml`).
+//! Updating downloads the archive for this platform, verifies the checksum,
+//! unpacks it with the system `tar`, and swaps the running executable.
+
+pub mod error;
+
+pub use error::{UpdateError, UpdateResult};
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const RELEASES_API: &str = "https://api.github.com/repos/bartolli/codanna/releases";
+const MANIFEST_ASSET: &str = "dist-manifest.json";
+/// Upper bound for a downloaded archive
+const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DistManifest {
+    version: String,
+    artifacts: Vec<Artifact>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Artifact {
+    name: String,
+    url: String,
+    sha256: String,
+    platform: String,
+}
+
+/// Outcome of an update request
+#[derive(Debug, PartialEq, Eq)]
+pub enum UpdateStatus {
+    /// Already running the requested version
+    UpToDate { version: String },
+    /// A different version is available (`--check` only)
+    Available { current: String, latest: String },
+    /// The binary was replaced
+    Updated { from: String, to: String },
+}
+
+/// Update the running binary to `target` (or the latest release)
+///
+/// With `check_only`, only reports whether a different version is available.
+/// `force` reinstalls even when the versions match. Installing an older
+/// `target` is allowed, which is how users downgrade to read an older index.
+pub fn self_update(
+    target: Option<&str>,
+    check_only: bool,
+    force: bool,
+) -> UpdateResult<UpdateStatus> {
+    let current = env!("CARGO_PKG_VERSION").to_string();
+    let release = fetch_release(target)?;
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+
+    if compare_versions(&latest, &current) == Ordering::Equal && !force {
+        return Ok(UpdateStatus::UpToDate { version: current });
+    }
+    if check_only {
+        return Ok(UpdateStatus::Available { current, latest });
+    }
+
+    let exe = std::env::current_exe()?.canonicalize()?;
+    if let Some((manager, command)) = package_manager(&exe) {
+        return Err(UpdateError::ManagedInstall {
+            path: exe,
+            manager,
+            command,
+        });
+    }
+
+    let platform = platform_name()?;
+    let manifest = fetch_manifest(&release)?;
+    let artifact = manifest
+        .artifacts
+        .iter()
+        .find(|a| a.platform == platform)
+        .ok_or_else(|| UpdateError::NoArtifact {
+            version: manifest.version.clone(),
+            platform: platform.to_string(),
+        })?;
+
+    eprintln!("Downloading {}...", artifact.name);
+    let bytes = download(&artifact.url)?;
+    verify_checksum(&artifact.name, &bytes, &artifact.sha256)?;
+
+    let staging = tempfile::tempdir()?;
+    let archive = staging.path().join(&artifact.name);
+    fs::write(&archive, &bytes)?;
+    let binary = extract_binary(&archive, staging.path())?;
+    replace_executable(&binary, &exe)?;
+
+    Ok(UpdateStatus::Updated {
+        from: current,
+        to: latest,
+    })
+}
+
+/// Release asset platform for this build, as named by the release workflow
+pub fn platform_name() -> UpdateResult<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Ok("linux-x64"),
+        ("macos", "x86_64") => Ok("macos-x64"),
+        ("macos", "aarch64") => Ok("macos-arm64"),
+        ("windows", "x86_64") => Ok("windows-x64"),
+        (os, arch) => Err(UpdateError::UnsupportedPlatform {
+            os: os.to_string(),
+            arch: arch.to_string(),
+        }),
+    }
+}
+
+/// Compare dotted release versions numerically, ignoring a `v` prefix and
+/// any pre-release suffix
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    }
+
+    let (a, b) = (parts(a), parts(b));
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            let x = a.get(i).copied().unwrap_or(0);
+            let y = b.get(i).copied().unwrap_or(0);
+            x.cmp(&y)
+        })
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Package managers that own the binary and would be confused by an in-place swap
+fn package_manager(exe: &Path) -> Option<(&'static str, &'static str)> {
+    let path = exe.to_string_lossy();
+    if path.contains("/Cellar/") || path.contains("/homebrew/") {
+        Some(("Homebrew", "brew upgrade codanna"))
+    } else {
+        None
+    }
+}
+
+fn request(url: &str) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+    let request =
+        ureq::get(url).header("User-Agent", concat!("codanna/", env!("CARGO_PKG_VERSION")));
+    match std::env::var("GITHUB_TOKEN") {
+        Ok(token) => request.header("Authorization", format!("Bearer {token}")),
+        Err(_) => request,
+    }
+}
+
+fn get(url: &str) -> UpdateResult<ureq::http::Response<ureq::Body>> {
+    request(url)
+        .call()
+        .map_err(|e| UpdateError::Network(format!("{url}: {e}")))
+}
+
+fn fetch_release(target: Option<&str>) -> UpdateResult<Release> {
+    let url = match target {
+        Some(version) => format!("{RELEASES_API}/tags/v{}", version.trim_start_matches('v')),
+        None => format!("{RELEASES_API}/latest"),
+    };
+    let mut response = request(&url).call().map_err(|e| match (e, target) {
+        (ureq::Error::StatusCode(404), Some(version)) => UpdateError::ReleaseNotFound {
+            version: version.to_string(),
+        },
+        (e, _) => UpdateError::Network(format!("{url}: {e}")),
+    })?;
+    response
+        .body_mut()
+        .read_json()
+        .map_err(|e| UpdateError::InvalidManifest(format!("release metadata: {e}")))
+}
+
+fn fetch_manifest(release: &Release) -> UpdateResult<DistManifest> {
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == MANIFEST_ASSET)
+        .ok_or_else(|| {
+            UpdateError::InvalidManifest(format!("{} has no {MANIFEST_ASSET}", release.tag_name))
+        })?;
+    get(&asset.browser_download_url)?
+        .body_mut()
+        .read_json()
+        .map_err(|e| UpdateError::InvalidManifest(e.to_string()))
+}
+
+fn download(url: &str) -> UpdateResult<Vec<u8>> {
+    get(url)?
+        .body_mut()
+        .with_config()
+        .limit(MAX_DOWNLOAD_BYTES)
+        .read_to_vec()
+        .map_err(|e| UpdateError::Network(format!("{url}: {e}")))
+}
+
+fn verify_checksum(name: &str, bytes: &[u8], expected: &str) -> UpdateResult<()> {
+    let actual = hex::encode(Sha256::digest(bytes));
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(UpdateError::ChecksumMismatch {
+            name: name.to_string(),
+            expected: expected.trim().to_string(),
+            actual,
+        })
+    }
+}
+
+/// Unpack `archive` into `dest` and return the path of the codanna binary
+///
+/// Uses the system `tar`, which reads `.tar.xz` on Unix and `.zip` on
+/// Windows 10 and later.
+fn extract_binary(archive: &Path, dest: &Path) -> UpdateResult<PathBuf> {
+    let extract_err = |reason: String| UpdateError::Extract {
+        archive: archive.display().to_string(),
+        reason,
+    };
+
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dest)
+        .output()
+        .map_err(|e| extract_err(e.to_string()))?;
+    if !output.status.success() {
+        return Err(extract_err(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    let binary_name = format!("codanna{}", std::env::consts::EXE_SUFFIX);
+    walkdir::WalkDir::new(dest)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_type().is_file() && entry.file_name() == binary_name.as_str())
+        .map(|entry| entry.into_path())
+        .ok_or_else(|| extract_err(format!("archive does not contain {binary_name}")))
+}
+
+/// Swap `new_binary` in for the executable at `exe`
+///
+/// The new file is staged next to the target so the final rename stays on one
+/// filesystem. The running binary is moved aside rather than overwritten,
+/// which Windows requires and which keeps the old version for rollback if the
+/// swap fails halfway.
+fn replace_executable(new_binary: &Path, exe: &Path) -> UpdateResult<()> {
+    let staged = exe.with_extension("new");
+    let backup = exe.with_extension("old");
+
+    fs::copy(new_binary, &staged)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    fs::rename(exe, &backup)?;
+    if let Err(e) = fs::rename(&staged, exe) {
+        let _ = fs::rename(&backup, exe);
+        let _ = fs::remove_file(&staged);
+        return Err(e.into());
+    }
+    // Windows keeps the running image locked; the leftover is replaced next time
+    let _ = fs::remove_file(&backup);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.9.10", "0.9.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v0.9.10", "0.9.10"), Ordering::Equal);
+        assert_eq!(compare_versions("0.10", "0.10.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0-rc.1", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.8.9", "0.9.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let digest = hex::encode(Sha256::digest(b"codanna"));
+        assert!(verify_checksum("a", b"codanna", &digest.to_uppercase()).is_ok());
+        assert!(matches!(
+            verify_checksum("a", b"tampered", &digest),
+            Err(UpdateError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_replace_executable_swaps_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let exe = temp.path().join("codanna");
+        let new_binary = temp.path().join("download");
+        fs::write(&exe, b"old").unwrap();
+        fs::write(&new_binary, b"new").unwrap();
+
+        replace_executable(&new_binary, &exe).unwrap();
+
+        assert_eq!(fs::read(&exe).unwrap(), b"new");
+        assert!(!exe.with_extension("old").exists());
+        assert!(!exe.with_extension("new").exists());
+    }
+
+    #[test]
+    fn test_manifest_parses_release_workflow_output() {
+        let json = r#"{
+          "version": "0.9.10",
+          "artifacts": [
+            {
+              "name": "codanna-0.9.10-linux-x64.tar.xz",
+              "url": "https://github.com/bartolli/codanna/releases/download/v0.9.10/codanna-0.9.10-linux-x64.tar.xz",
+              "sha256": "abc",
+              "platform": "linux-x64"
+            }
+          ]
+        }"#;
+        let manifest: DistManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(manifest.version, "0.9.10");
+        assert_eq!(manifest.artifacts[0].platform, "linux-x64");
+    }
+}
@@ -0,0 +1,336 @@
+//! Embeddings shared by every project of the user
+//!
+//! Documentation that was embedded once, in any project, branch, worktree
+//! or vendored copy, is not embedded again: the pool looks texts up here
+//! before calling the model. Each model has its own append-only file in
+//! `~/.codanna/cache/embeddings`, holding the vector for each text under
+//! a SHA-256 digest of it, so the cache never holds source text.
+//!
+//! Several processes can index at once: records are appended whole, and a
+//! text two of them embed at the same time is stored twice, which costs
+//! space but no correctness. A file past `embedding_cache_max_mb`, or one
+//! ending in a torn record, is never truncated in place, since other
+//! processes may be reading it: a new file is written beside it and
+//! renamed over it, and processes holding the old one keep using that.
+//! Every lookup compares the key stored with the vector, so a record that
+//! is not what the offset promised is a miss.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File header: magic and format version, then the dimension
+const MAGIC: &[u8; 8] = b"CDNAEMB1";
+const HEADER_SIZE: u64 = 16;
+
+/// Bytes of the text digest identifying a record
+const KEY_SIZE: usize = 16;
+
+type Key = [u8; KEY_SIZE];
+
+/// Per-user cache of the embeddings of one model
+#[derive(Debug)]
+pub struct EmbeddingCache {
+    path: PathBuf,
+    dimension: usize,
+    inner: Mutex<CacheFile>,
+}
+
+#[derive(Debug)]
+struct CacheFile {
+    file: File,
+    /// Offset of each record
+    offsets: HashMap<Key, u64>,
+}
+
+impl EmbeddingCache {
+    /// Cache of `model_id` in the global cache directory
+    pub fn open(model_id: &str, dimension: usize, max_bytes: u64) -> io::Result<Self> {
+        Self::open_in(
+            &crate::init::embedding_cache_dir(),
+            model_id,
+            dimension,
+            max_bytes,
+        )
+    }
+
+    /// Cache of `model_id` in `dir`
+    pub fn open_in(
+        dir: &Path,
+        model_id: &str,
+        dimension: usize,
+        max_bytes: u64,
+    ) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let name: String = model_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = dir.join(format!("{name}-{dimension}.emb"));
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let record_size = record_size(dimension);
+        let mut length = file.metadata()?.len();
+
+        if length > max_bytes || (length > 0 && !has_header(&mut file, dimension)?) {
+            tracing::debug!(
+                target: "semantic",
+                "starting embedding cache {} over ({length} bytes)",
+                path.display()
+            );
+            file = replace_file(&path, dimension, None)?;
+            length = HEADER_SIZE;
+        } else if length == 0 {
+            // Nothing another process could be reading yet
+            file.write_all(&header(dimension))?;
+            length = HEADER_SIZE;
+        }
+
+        // A record cut short by a crash would misalign every later one
+        let records = (length - HEADER_SIZE) / record_size;
+        let complete = HEADER_SIZE + records * record_size;
+        if complete != length {
+            file = replace_file(&path, dimension, Some((&mut file, complete)))?;
+        }
+
+        let mut offsets = HashMap::with_capacity(records as usize);
+        let mut key = [0u8; KEY_SIZE];
+        for record in 0..records {
+            let offset = HEADER_SIZE + record * record_size;
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut key)?;
+            offsets.insert(key, offset);
+        }
+
+        Ok(Self {
+            path,
+            dimension,
+            inner: Mutex::new(CacheFile { file, offsets }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cached embedding of `text`
+    ///
+    /// A record whose stored key is not the text's, or that cannot be read
+    /// whole, is a miss.
+    pub fn get(&self, text: &str) -> Option<Vec<f32>> {
+        let mut inner = self.lock();
+        let key = key(text);
+        let offset = *inner.offsets.get(&key)?;
+        let mut record = vec![0u8; record_size(self.dimension) as usize];
+        let read = inner
+            .file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| inner.file.read_exact(&mut record));
+        if let Err(e) = read {
+            tracing::debug!(target: "semantic", "embedding cache read failed: {e}");
+            inner.offsets.remove(&key);
+            return None;
+        }
+        if record[..KEY_SIZE] != key {
+            tracing::debug!(target: "semantic", "embedding cache record at {offset} changed");
+            inner.offsets.remove(&key);
+            return None;
+        }
+        Some(
+            record[KEY_SIZE..]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+        )
+    }
+
+    /// Store the embeddings of `texts`, skipping those already cached
+    pub fn insert_batch(&self, texts: &[&str], embeddings: &[Vec<f32>]) {
+        let record_size = record_size(self.dimension) as usize;
+        let mut inner = self.lock();
+        let mut records = Vec::new();
+        let mut keys = Vec::new();
+        for (text, embedding) in texts.iter().zip(embeddings) {
+            let key = key(text);
+            if embedding.len() != self.dimension
+                || inner.offsets.contains_key(&key)
+                || keys.contains(&key)
+            {
+                continue;
+            }
+            records.reserve(record_size);
+            records.extend_from_slice(&key);
+            for value in embedding {
+                records.extend_from_slice(&value.to_le_bytes());
+            }
+            keys.push(key);
+        }
+        if keys.is_empty() {
+            return;
+        }
+
+        // Appended in one write, so records of other processes cannot
+        // interleave with them; the position is then right after ours
+        let written = inner
+            .file
+            .write_all(&records)
+            .and_then(|_| inner.file.stream_position());
+        match written {
+            Ok(end) => {
+                let start = end - records.len() as u64;
+                for (i, key) in keys.into_iter().enumerate() {
+                    inner.offsets.insert(key, start + (i * record_size) as u64);
+                }
+            }
+            Err(e) => {
+                tracing::debug!(target: "semantic", "embedding cache write failed: {e}");
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheFile> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn record_size(dimension: usize) -> u64 {
+    (KEY_SIZE + dimension * 4) as u64
+}
+
+fn key(text: &str) -> Key {
+    let digest = Sha256::digest(text.as_bytes());
+    let mut key = [0u8; KEY_SIZE];
+    key.copy_from_slice(&digest[..KEY_SIZE]);
+    key
+}
+
+fn header(dimension: usize) -> Vec<u8> {
+    let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&(dimension as u64).to_le_bytes());
+    header
+}
+
+/// Write a new cache file beside `path` and rename it into place
+///
+/// The records of `keep`, up to the given length, are copied over. Processes
+/// that have the old file open keep reading and appending to it.
+fn replace_file(path: &Path, dimension: usize, keep: Option<(&mut File, u64)>) -> io::Result<File> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", std::process::id()));
+    let staging = path.with_file_name(name);
+
+    let mut new = File::create(&staging)?;
+    new.write_all(&header(dimension))?;
+    if let Some((old, length)) = keep {
+        old.seek(SeekFrom::Start(HEADER_SIZE))?;
+        io::copy(&mut old.take(length - HEADER_SIZE), &mut new)?;
+    }
+    new.sync_all()?;
+    drop(new);
+    if let Err(e) = std::fs::rename(&staging, path) {
+        let _ = std::fs::remove_file(&staging);
+        return Err(e);
+    }
+
+    OpenOptions::new().read(true).append(true).open(path)
+}
+
+fn has_header(file: &mut File, dimension: usize) -> io::Result<bool> {
+    let mut header = [0u8; HEADER_SIZE as usize];
+    file.seek(SeekFrom::Start(0))?;
+    if file.read_exact(&mut header).is_err() {
+        return Ok(false);
+    }
+    let stored = u64::from_le_bytes(header[8..].try_into().expect("8 bytes"));
+    Ok(&header[..8] == MAGIC && stored == dimension as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_survives_reopening_and_skips_known_texts() {
+        let dir = TempDir::new().unwrap();
+        let cache = EmbeddingCache::open_in(dir.path(), "ollama:nomic", 3, u64::MAX).unwrap();
+        assert!(cache.path().ends_with("ollama_nomic-3.emb"));
+        assert!(cache.get("parse config").is_none());
+
+        cache.insert_batch(
+            &["parse config", "open file", "parse config"],
+            &[
+                vec![1.0, 2.0, 3.0],
+                vec![4.0, 5.0, 6.0],
+                vec![9.0, 9.0, 9.0],
+            ],
+        );
+        cache.insert_batch(&["open file"], &[vec![0.0, 0.0, 0.0]]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("open file"), Some(vec![4.0, 5.0, 6.0]));
+        drop(cache);
+
+        // A torn record at the end is dropped
+        let path = dir.path().join("ollama_nomic-3.emb");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[1, 2, 3]).unwrap();
+        drop(file);
+
+        let cache = EmbeddingCache::open_in(dir.path(), "ollama:nomic", 3, u64::MAX).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("parse config"), Some(vec![1.0, 2.0, 3.0]));
+        cache.insert_batch(&["new text"], &[vec![7.0, 8.0, 9.0]]);
+        assert_eq!(cache.get("new text"), Some(vec![7.0, 8.0, 9.0]));
+        drop(cache);
+
+        let cache = EmbeddingCache::open_in(dir.path(), "ollama:nomic", 3, 64).unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_starting_over_leaves_open_caches_intact() {
+        let dir = TempDir::new().unwrap();
+        let cache = EmbeddingCache::open_in(dir.path(), "ollama:nomic", 3, u64::MAX).unwrap();
+        cache.insert_batch(&["parse config"], &[vec![1.0, 2.0, 3.0]]);
+
+        // Another process over the limit writes a new file in its place
+        let other = EmbeddingCache::open_in(dir.path(), "ollama:nomic", 3, 16).unwrap();
+        assert!(other.is_empty());
+        assert_eq!(cache.get("parse config"), Some(vec![1.0, 2.0, 3.0]));
+        assert!(!dir.path().read_dir().unwrap().any(|entry| {
+            entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".tmp")
+        }));
+
+        // A record that is not the text's is a miss
+        other.insert_batch(&["open file"], &[vec![4.0, 5.0, 6.0]]);
+        let offset = other.lock().offsets[&key("open file")];
+        other.lock().offsets.insert(key("parse config"), offset);
+        assert_eq!(other.get("parse config"), None);
+        assert_eq!(other.len(), 1);
+        assert_eq!(other.get("open file"), Some(vec![4.0, 5.0, 6.0]));
+
+        // As is one past the end of the file
+        other.lock().offsets.insert(key("parse config"), 4096);
+        assert_eq!(other.get("parse config"), None);
+    }
+}
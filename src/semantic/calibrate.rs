@@ -0,0 +1,99 @@
+//! Similarity thresholds measured on the indexed code
+//!
+//! Scores of one model are not comparable to those of another: a pair of
+//! unrelated symbols may score 0.1 with one model and 0.5 with the next.
+//! Calibration scores labeled pairs from the index with its model: each
+//! symbol against its own earlier version, which should match, and random
+//! pairs of symbols, which should not. The thresholds recommended for the
+//! model are read off the two score distributions.
+
+use crate::config::ModelThresholds;
+
+/// Fewest pairs of each label thresholds are recommended from
+pub const MIN_PAIRS: usize = 10;
+
+/// Share of random pairs scoring below the search threshold
+const SEARCH_QUANTILE: f32 = 0.95;
+
+/// Similarity scores of labeled pairs of symbols
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CalibrationScores {
+    /// Model the scores were measured with, as recorded in the index
+    pub model_id: String,
+    /// A symbol against its earlier version
+    pub same: Vec<f32>,
+    /// Two different symbols picked at random
+    pub random: Vec<f32>,
+}
+
+impl CalibrationScores {
+    /// Thresholds for the model, or `None` with fewer than [`MIN_PAIRS`]
+    /// pairs of either label
+    ///
+    /// `search` is the score most random pairs stay below. `similar` is the
+    /// score that best separates the versions of a symbol from random pairs,
+    /// and never lower than `search`.
+    pub fn recommend(&self) -> Option<ModelThresholds> {
+        if self.same.len() < MIN_PAIRS || self.random.len() < MIN_PAIRS {
+            return None;
+        }
+        let mut random = self.random.clone();
+        random.sort_by(f32::total_cmp);
+        let search = quantile(&random, SEARCH_QUANTILE);
+
+        // Youden's J: the cut with the largest gap between the share of
+        // versions and the share of random pairs at or above it
+        let mut best = (f32::MIN, search);
+        for &cut in self.same.iter().chain(&self.random) {
+            let same = share_at_least(&self.same, cut);
+            let random = share_at_least(&self.random, cut);
+            if same - random > best.0 {
+                best = (same - random, cut);
+            }
+        }
+
+        Some(ModelThresholds {
+            search: round(search),
+            similar: round(best.1.max(search)),
+        })
+    }
+}
+
+/// Score below which `q` of the sorted `scores` fall
+fn quantile(sorted: &[f32], q: f32) -> f32 {
+    let index = ((sorted.len() - 1) as f32 * q).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+fn share_at_least(scores: &[f32], cut: f32) -> f32 {
+    scores.iter().filter(|score| **score >= cut).count() as f32 / scores.len() as f32
+}
+
+fn round(score: f32) -> f32 {
+    (score * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thresholds_separate_versions_from_random_pairs() {
+        let mut scores = CalibrationScores {
+            model_id: "local:AllMiniLML6V2".to_string(),
+            same: (0..20).map(|i| 0.80 + i as f32 * 0.01).collect(),
+            random: (0..40).map(|i| 0.05 + i as f32 * 0.01).collect(),
+        };
+        let thresholds = scores.recommend().unwrap();
+        assert_eq!(thresholds.search, 0.42);
+        assert_eq!(thresholds.similar, 0.8);
+
+        // Overlapping distributions never put `similar` below `search`
+        scores.same = vec![0.3; 20];
+        let thresholds = scores.recommend().unwrap();
+        assert!(thresholds.similar >= thresholds.search);
+
+        scores.same.truncate(MIN_PAIRS - 1);
+        assert_eq!(scores.recommend(), None);
+    }
+}
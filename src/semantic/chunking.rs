@@ -0,0 +1,318 @@
+//! What text of a symbol gets embedded
+//!
+//! `semantic_search.chunking` picks a strategy, and
+//! `languages.<id>.chunking` overrides it for one language, since long
+//! Python functions and terse Rust impls are found best in different ways:
+//!
+//! - `docstring` (default): the doc comment; undocumented symbols are not
+//!   embedded
+//! - `signature`: kind, name, signature and the first paragraph of the doc
+//!   comment, so undocumented functions and types are embedded too
+//! - `symbol`: signature, doc comment and source of each function, up to
+//!   [`MAX_CHUNK_CHARS`]
+//! - `window`: the doc comment, and the source of each function in windows
+//!   of `chunk_lines` lines overlapping by `chunk_overlap`
+//!
+//! Source is only embedded for functions, methods and macros. Types are
+//! embedded by signature and summary, since their bodies are the methods,
+//! which are embedded themselves. A symbol with several chunks is stored as
+//! the mean of their embeddings.
+
+use crate::config::Settings;
+use crate::indexing::pipeline::types::RawSymbol;
+use crate::{SymbolId, SymbolKind};
+use std::collections::HashMap;
+
+/// Longest text embedded for one chunk; models truncate longer input anyway
+pub const MAX_CHUNK_CHARS: usize = 4_000;
+
+/// How a symbol is turned into texts to embed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChunkingStrategy {
+    #[default]
+    Docstring,
+    Signature,
+    Symbol,
+    Window,
+}
+
+impl ChunkingStrategy {
+    pub const NAMES: &[&str] = &["docstring", "signature", "symbol", "window"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "docstring" => Some(Self::Docstring),
+            "signature" => Some(Self::Signature),
+            "symbol" => Some(Self::Symbol),
+            "window" => Some(Self::Window),
+            _ => None,
+        }
+    }
+
+    /// Whether chunks are cut from the source file
+    pub fn needs_source(self) -> bool {
+        matches!(self, Self::Symbol | Self::Window)
+    }
+
+    /// Strategy for files of `language`
+    ///
+    /// An unknown name is reported once and read as `docstring`.
+    pub fn for_language(settings: &Settings, language: &str) -> Self {
+        let name = settings
+            .languages
+            .get(language)
+            .and_then(|config| config.chunking.as_deref())
+            .unwrap_or(&settings.semantic_search.chunking);
+        Self::parse(name).unwrap_or_else(|| {
+            static WARNED: std::sync::Once = std::sync::Once::new();
+            WARNED.call_once(|| {
+                tracing::warn!(
+                    target: "semantic",
+                    "Unknown chunking '{name}', using \"docstring\". Expected one of: {}",
+                    Self::NAMES.join(", ")
+                );
+            });
+            Self::Docstring
+        })
+    }
+}
+
+/// Set the chunks of `symbols`, parsed from `source`
+///
+/// `source` is only read by strategies that [need it](ChunkingStrategy::needs_source).
+pub fn chunk_symbols(
+    symbols: &mut [RawSymbol],
+    source: Option<&str>,
+    strategy: ChunkingStrategy,
+    settings: &Settings,
+) {
+    if strategy == ChunkingStrategy::Docstring {
+        return;
+    }
+    let lines: Vec<&str> = source.map(|s| s.lines().collect()).unwrap_or_default();
+    let window = settings.semantic_search.chunk_lines.max(1);
+    let overlap = settings.semantic_search.chunk_overlap.min(window - 1);
+
+    for symbol in symbols {
+        let body = symbol_lines(symbol, &lines);
+        let chunks = match strategy {
+            ChunkingStrategy::Docstring => continue,
+            ChunkingStrategy::Symbol if has_body(symbol.kind) => {
+                let mut text = header(symbol, false);
+                push_line(&mut text, symbol.doc_comment.as_deref());
+                push_line(&mut text, Some(&body.join("\n")));
+                vec![text]
+            }
+            ChunkingStrategy::Window if has_body(symbol.kind) => {
+                let mut chunks: Vec<String> = symbol
+                    .doc_comment
+                    .as_deref()
+                    .map(|doc| format!("{}\n{doc}", header(symbol, false)))
+                    .into_iter()
+                    .collect();
+                let mut start = 0;
+                while start < body.len() {
+                    let end = (start + window).min(body.len());
+                    let mut text = header(symbol, false);
+                    push_line(&mut text, Some(&body[start..end].join("\n")));
+                    chunks.push(text);
+                    if end == body.len() {
+                        break;
+                    }
+                    start = end - overlap;
+                }
+                chunks
+            }
+            _ => vec![header(symbol, true)],
+        };
+        symbol.chunks = Some(
+            chunks
+                .into_iter()
+                .map(|chunk| truncate(chunk).into_boxed_str())
+                .collect(),
+        );
+    }
+}
+
+/// Embeddings of the chunks of each symbol combined into one, in order
+///
+/// The mean of the normalized chunk embeddings; a symbol with one chunk
+/// keeps its embedding unchanged.
+pub fn merge_chunk_embeddings(
+    embeddings: Vec<(SymbolId, Vec<f32>, String)>,
+) -> Vec<(SymbolId, Vec<f32>, String)> {
+    let mut merged: Vec<(SymbolId, Vec<f32>, String)> = Vec::with_capacity(embeddings.len());
+    let mut counts: Vec<usize> = Vec::with_capacity(embeddings.len());
+    let mut positions: HashMap<SymbolId, usize> = HashMap::new();
+    for (id, embedding, language) in embeddings {
+        match positions.get(&id) {
+            Some(&position) => {
+                let sum = &mut merged[position].1;
+                if counts[position] == 1 {
+                    normalize(sum);
+                }
+                let mut embedding = embedding;
+                normalize(&mut embedding);
+                for (total, value) in sum.iter_mut().zip(embedding) {
+                    *total += value;
+                }
+                counts[position] += 1;
+            }
+            None => {
+                positions.insert(id, merged.len());
+                merged.push((id, embedding, language));
+                counts.push(1);
+            }
+        }
+    }
+    for ((_, embedding, _), count) in merged.iter_mut().zip(counts) {
+        if count > 1 {
+            normalize(embedding);
+        }
+    }
+    merged
+}
+
+/// Functions and the like, whose source is worth embedding
+fn has_body(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Macro
+    )
+}
+
+/// Kind and name, then the signature, and with `summary` the first
+/// paragraph of the doc comment
+fn header(symbol: &RawSymbol, summary: bool) -> String {
+    let mut text = format!("{:?} {}", symbol.kind, symbol.name);
+    push_line(&mut text, symbol.signature.as_deref());
+    if summary {
+        let first_paragraph = symbol
+            .doc_comment
+            .as_deref()
+            .and_then(|doc| doc.split("\n\n").next());
+        push_line(&mut text, first_paragraph);
+    }
+    text
+}
+
+fn push_line(text: &mut String, line: Option<&str>) {
+    if let Some(line) = line.filter(|line| !line.trim().is_empty()) {
+        text.push('\n');
+        text.push_str(line);
+    }
+}
+
+/// Lines of `lines` the symbol spans
+fn symbol_lines<'a>(symbol: &RawSymbol, lines: &[&'a str]) -> Vec<&'a str> {
+    let start = (symbol.range.start_line as usize).min(lines.len());
+    let end = (symbol.range.end_line as usize + 1).clamp(start, lines.len());
+    lines[start..end].to_vec()
+}
+
+fn truncate(mut text: String) -> String {
+    if let Some((cut, _)) = text.char_indices().nth(MAX_CHUNK_CHARS) {
+        text.truncate(cut);
+    }
+    text
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Range;
+
+    const SOURCE: &str = "/// Load settings\nfn load(path: &Path) -> Settings {\n    let text = read(path);\n    parse(text)\n}\n\nstruct Settings {\n    debug: bool,\n}\n";
+
+    fn symbols() -> Vec<RawSymbol> {
+        vec![
+            RawSymbol::new("load", SymbolKind::Function, Range::new(1, 0, 4, 1))
+                .with_signature("fn load(path: &Path) -> Settings")
+                .with_doc_comment("Load settings\n\nReads the TOML file."),
+            RawSymbol::new("Settings", SymbolKind::Struct, Range::new(6, 0, 8, 1))
+                .with_signature("struct Settings"),
+        ]
+    }
+
+    fn chunks(strategy: ChunkingStrategy, settings: &Settings) -> Vec<Vec<String>> {
+        let mut symbols = symbols();
+        chunk_symbols(&mut symbols, Some(SOURCE), strategy, settings);
+        symbols
+            .into_iter()
+            .map(|s| {
+                s.chunks
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunking_strategies() {
+        let mut settings = Settings::default();
+        assert_eq!(
+            chunks(ChunkingStrategy::Docstring, &settings),
+            [Vec::<String>::new(), Vec::new()]
+        );
+
+        let signature = chunks(ChunkingStrategy::Signature, &settings);
+        assert_eq!(
+            signature[0],
+            ["Function load\nfn load(path: &Path) -> Settings\nLoad settings"]
+        );
+        assert_eq!(signature[1], ["Struct Settings\nstruct Settings"]);
+
+        let symbol = chunks(ChunkingStrategy::Symbol, &settings);
+        assert!(symbol[0][0].contains("Reads the TOML file."));
+        assert!(symbol[0][0].ends_with("    parse(text)\n}"));
+        assert_eq!(symbol[1], signature[1]);
+
+        settings.semantic_search.chunk_lines = 2;
+        settings.semantic_search.chunk_overlap = 1;
+        let window = chunks(ChunkingStrategy::Window, &settings);
+        assert_eq!(window[0].len(), 4);
+        assert!(
+            window[0][1]
+                .ends_with("fn load(path: &Path) -> Settings {\n    let text = read(path);")
+        );
+        assert!(window[0][3].ends_with("    parse(text)\n}"));
+
+        if let Some(rust) = settings.languages.get_mut("rust") {
+            rust.chunking = Some("window".into());
+            assert_eq!(
+                ChunkingStrategy::for_language(&settings, "rust"),
+                ChunkingStrategy::Window
+            );
+        }
+        settings.semantic_search.chunking = "signature".into();
+        assert_eq!(
+            ChunkingStrategy::for_language(&settings, "python"),
+            ChunkingStrategy::Signature
+        );
+    }
+
+    #[test]
+    fn test_chunk_embeddings_are_averaged_per_symbol() {
+        let a = SymbolId::new(1).unwrap();
+        let b = SymbolId::new(2).unwrap();
+        let merged = merge_chunk_embeddings(vec![
+            (a, vec![2.0, 0.0], "rust".into()),
+            (b, vec![0.5, 0.5], "rust".into()),
+            (a, vec![0.0, 3.0], "rust".into()),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].0, a);
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((merged[0].1[0] - half).abs() < 1e-6 && (merged[0].1[1] - half).abs() < 1e-6);
+        assert_eq!(merged[1].1, vec![0.5, 0.5]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Groups of symbols with near-identical embeddings
+//!
+//! Two symbols whose embeddings are at least `threshold` similar are linked,
+//! and linked symbols form a cluster, so a cluster can chain symbols that
+//! are each close to one of the others. Clusters are a hint of duplicated
+//! logic: functions documented or written alike usually do alike.
+//!
+//! Every pair is compared, in parallel, so the cost grows with the square
+//! of the symbols clustered; scoping to a directory keeps it small.
+
+use crate::SymbolId;
+use rayon::prelude::*;
+
+/// Symbols linked by similar embeddings
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingCluster {
+    /// Members, most connected first
+    pub members: Vec<SymbolId>,
+    /// Mean similarity of the linked pairs
+    pub similarity: f32,
+}
+
+/// Members of one root with their link count, the sum of the similarities
+/// of its links, and the number of links
+type Group = (Vec<(usize, usize)>, f32, usize);
+
+/// Clusters of at least `min_size` of `embeddings`, largest first
+///
+/// Ties are ordered by similarity, most similar first.
+pub fn cluster_embeddings(
+    embeddings: Vec<(SymbolId, Vec<f32>)>,
+    threshold: f32,
+    min_size: usize,
+) -> Vec<EmbeddingCluster> {
+    let (ids, vectors): (Vec<SymbolId>, Vec<Vec<f32>>) = embeddings
+        .into_iter()
+        .map(|(id, mut vector)| {
+            normalize(&mut vector);
+            (id, vector)
+        })
+        .unzip();
+
+    let links: Vec<(usize, usize, f32)> = (0..vectors.len())
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            let vectors = &vectors;
+            (i + 1..vectors.len()).filter_map(move |j| {
+                let similarity = dot(&vectors[i], &vectors[j]);
+                (similarity >= threshold).then_some((i, j, similarity))
+            })
+        })
+        .collect();
+
+    let mut parents: Vec<usize> = (0..ids.len()).collect();
+    for (i, j, _) in &links {
+        let (a, b) = (root(&mut parents, *i), root(&mut parents, *j));
+        if a != b {
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+
+    // Per root: members with their link count, and the sum of similarities
+    let mut groups: Vec<Group> = Vec::new();
+    let mut group_of = vec![usize::MAX; ids.len()];
+    let mut degrees = vec![0usize; ids.len()];
+    for (i, j, _) in &links {
+        degrees[*i] += 1;
+        degrees[*j] += 1;
+    }
+    for (member, &degree) in degrees.iter().enumerate() {
+        if degree == 0 {
+            continue;
+        }
+        let root = root(&mut parents, member);
+        if group_of[root] == usize::MAX {
+            group_of[root] = groups.len();
+            groups.push((Vec::new(), 0.0, 0));
+        }
+        groups[group_of[root]].0.push((member, degree));
+    }
+    for (i, _, similarity) in &links {
+        let group = &mut groups[group_of[root(&mut parents, *i)]];
+        group.1 += similarity;
+        group.2 += 1;
+    }
+
+    let mut clusters: Vec<EmbeddingCluster> = groups
+        .into_iter()
+        .filter(|(members, _, _)| members.len() >= min_size.max(2))
+        .map(|(mut members, total, links)| {
+            members.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            EmbeddingCluster {
+                members: members.into_iter().map(|(member, _)| ids[member]).collect(),
+                similarity: total / links as f32,
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then(b.similarity.total_cmp(&a.similarity))
+    });
+    clusters
+}
+
+fn root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(value: u32) -> SymbolId {
+        SymbolId::new(value).unwrap()
+    }
+
+    #[test]
+    fn test_similar_embeddings_are_clustered() {
+        let embeddings = vec![
+            (id(1), vec![1.0, 0.0, 0.0]),
+            (id(2), vec![0.0, 1.0, 0.0]),
+            (id(3), vec![0.99, 0.05, 0.0]),
+            (id(4), vec![0.0, 0.0, 1.0]),
+            (id(5), vec![2.0, -0.1, 0.0]),
+            (id(6), vec![0.0, 0.05, 3.0]),
+        ];
+
+        let clusters = cluster_embeddings(embeddings.clone(), 0.95, 2);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].members.len(), 3);
+        assert!(clusters[0].members.contains(&id(5)));
+        assert_eq!(clusters[1].members, [id(4), id(6)]);
+        assert!(
+            clusters
+                .iter()
+                .all(|c| c.similarity >= 0.95 && c.similarity <= 1.0 + 1e-6)
+        );
+
+        assert_eq!(cluster_embeddings(embeddings.clone(), 0.95, 3).len(), 1);
+        assert!(cluster_embeddings(embeddings, 0.99999, 2).is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+//! Identifier-aware expansion of search queries
+//!
+//! Queries are often terse identifiers such as `parseCfg` or `get_ctx`.
+//! Before a hybrid search, identifiers are split into words at `snake_case`
+//! and `camelCase` boundaries and common abbreviations are spelled out, so
+//! `parseCfg` is embedded as "parseCfg parse cfg config" and also looked up as
+//! `parse_config` and `parseConfig` by full-text search.
+
+/// Most identifier variants searched per query
+const MAX_VARIANTS: usize = 4;
+
+/// Abbreviations common in identifiers, with the word they stand for
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("addr", "address"),
+    ("alloc", "allocate"),
+    ("arg", "argument"),
+    ("args", "arguments"),
+    ("attr", "attribute"),
+    ("auth", "authentication"),
+    ("buf", "buffer"),
+    ("calc", "calculate"),
+    ("cfg", "config"),
+    ("cmd", "command"),
+    ("col", "column"),
+    ("conf", "config"),
+    ("conn", "connection"),
+    ("ctx", "context"),
+    ("db", "database"),
+    ("del", "delete"),
+    ("dep", "dependency"),
+    ("deps", "dependencies"),
+    ("dir", "directory"),
+    ("doc", "documentation"),
+    ("dst", "destination"),
+    ("env", "environment"),
+    ("err", "error"),
+    ("exec", "execute"),
+    ("fn", "function"),
+    ("func", "function"),
+    ("gen", "generate"),
+    ("idx", "index"),
+    ("impl", "implementation"),
+    ("info", "information"),
+    ("init", "initialize"),
+    ("iter", "iterator"),
+    ("len", "length"),
+    ("mgr", "manager"),
+    ("msg", "message"),
+    ("num", "number"),
+    ("obj", "object"),
+    ("param", "parameter"),
+    ("params", "parameters"),
+    ("pos", "position"),
+    ("prev", "previous"),
+    ("ptr", "pointer"),
+    ("ref", "reference"),
+    ("repo", "repository"),
+    ("req", "request"),
+    ("res", "response"),
+    ("resp", "response"),
+    ("spec", "specification"),
+    ("src", "source"),
+    ("str", "string"),
+    ("sync", "synchronize"),
+    ("tmp", "temporary"),
+    ("util", "utility"),
+    ("val", "value"),
+    ("var", "variable"),
+];
+
+/// A query with the words and identifiers it may stand for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandedQuery {
+    pub original: String,
+    /// Words of the query, split and with abbreviations spelled out
+    pub words: Vec<String>,
+    /// Identifiers the words may name, in `snake_case` and `camelCase`
+    pub variants: Vec<String>,
+}
+
+impl ExpandedQuery {
+    /// Text to embed: the query followed by the words it does not spell out
+    pub fn semantic_text(&self) -> String {
+        let original = self.original.to_lowercase();
+        let mut text = self.original.clone();
+        for word in &self.words {
+            if !original
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|token| token == word)
+            {
+                text.push(' ');
+                text.push_str(word);
+            }
+        }
+        text
+    }
+
+    /// Queries for full-text search, the original first
+    pub fn lexical_queries(&self) -> Vec<String> {
+        let mut queries = vec![self.original.clone()];
+        let words = self.words.join(" ");
+        for query in std::iter::once(&words).chain(&self.variants) {
+            if !query.is_empty() && !queries.contains(query) {
+                queries.push(query.clone());
+            }
+        }
+        queries
+    }
+}
+
+/// Split the identifiers of `query` and spell out abbreviations
+pub fn expand_query(query: &str) -> ExpandedQuery {
+    let split: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .flat_map(split_identifier)
+        .collect();
+
+    let mut words: Vec<String> = Vec::new();
+    let mut spelled: Vec<String> = Vec::new();
+    for word in &split {
+        let expansion = expand_abbreviation(word);
+        for word in [word.as_str(), expansion.unwrap_or(word)] {
+            if !words.iter().any(|known| known == word) {
+                words.push(word.to_string());
+            }
+        }
+        spelled.push(expansion.unwrap_or(word).to_string());
+    }
+
+    let mut variants = Vec::new();
+    for sequence in [&split, &spelled] {
+        if !(2..=MAX_VARIANTS).contains(&sequence.len()) {
+            continue;
+        }
+        for variant in [snake_case(sequence), camel_case(sequence)] {
+            if variant != query && !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+    }
+    variants.truncate(MAX_VARIANTS);
+
+    ExpandedQuery {
+        original: query.to_string(),
+        words,
+        variants,
+    }
+}
+
+/// Lowercase words of an identifier, split at `_` and case changes
+///
+/// An acronym stays whole: `HTTPServer` is `http`, `server`.
+pub fn split_identifier(identifier: &str) -> Vec<String> {
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let boundary = i > 0 && !word.is_empty() && {
+            let previous = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            (c.is_uppercase() && previous.is_lowercase())
+                || (c.is_uppercase() && previous.is_uppercase() && next_lower)
+                || (c.is_ascii_digit() != previous.is_ascii_digit())
+        };
+        if boundary {
+            words.push(std::mem::take(&mut word));
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn expand_abbreviation(word: &str) -> Option<&'static str> {
+    ABBREVIATIONS
+        .binary_search_by(|(abbreviation, _)| abbreviation.cmp(&word))
+        .ok()
+        .map(|i| ABBREVIATIONS[i].1)
+}
+
+fn snake_case(words: &[String]) -> String {
+    words.join("_")
+}
+
+fn camel_case(words: &[String]) -> String {
+    let mut identifier = String::new();
+    for (i, word) in words.iter().enumerate() {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) if i > 0 => {
+                identifier.extend(first.to_uppercase());
+                identifier.push_str(chars.as_str());
+            }
+            _ => identifier.push_str(word),
+        }
+    }
+    identifier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers_are_split_and_expanded() {
+        assert!(ABBREVIATIONS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(split_identifier("HTTPServer"), ["http", "server"]);
+        assert_eq!(split_identifier("parse_cfg_v2"), ["parse", "cfg", "v", "2"]);
+        assert_eq!(split_identifier("getCtx"), ["get", "ctx"]);
+
+        let expanded = expand_query("parseCfg");
+        assert_eq!(expanded.words, ["parse", "cfg", "config"]);
+        assert_eq!(expanded.semantic_text(), "parseCfg parse cfg config");
+        assert_eq!(
+            expanded.variants,
+            ["parse_cfg", "parse_config", "parseConfig"]
+        );
+        assert_eq!(
+            expanded.lexical_queries(),
+            [
+                "parseCfg",
+                "parse cfg config",
+                "parse_cfg",
+                "parse_config",
+                "parseConfig"
+            ]
+        );
+
+        let plain = expand_query("token");
+        assert!(plain.variants.is_empty());
+        assert_eq!(plain.semantic_text(), "token");
+        assert_eq!(plain.lexical_queries(), ["token"]);
+    }
+}
@@ -0,0 +1,60 @@
+//! Reciprocal rank fusion of ranked result lists
+//!
+//! Hybrid search merges full-text (BM25) and semantic hits. Their scores are
+//! not comparable, so only ranks count: an item scores `1 / (k + rank)` in
+//! each list it appears in, summed over the lists. An exact identifier match
+//! that the embedding model ranks poorly still surfaces from the full-text
+//! list, and items both searches agree on rise to the top.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Rank offset damping the weight of the first few ranks, as in the
+/// original RRF paper
+pub const RRF_K: f32 = 60.0;
+
+/// Items of `rankings`, each best first, fused and sorted best first
+///
+/// Scores are normalized to 0..=1, where 1 is first in every list.
+pub fn reciprocal_rank_fusion<T: Copy + Eq + Hash>(rankings: &[&[T]], k: f32) -> Vec<(T, f32)> {
+    let mut scores: HashMap<T, f32> = HashMap::new();
+    let mut order = Vec::new();
+    for ranking in rankings {
+        for (rank, item) in ranking.iter().enumerate() {
+            let score = scores.entry(*item).or_insert_with(|| {
+                order.push(*item);
+                0.0
+            });
+            *score += 1.0 / (k + rank as f32 + 1.0);
+        }
+    }
+
+    let best = rankings.len() as f32 / (k + 1.0);
+    let mut fused: Vec<(T, f32)> = order
+        .into_iter()
+        .map(|item| (item, scores[&item] / best))
+        .collect();
+    // Stable: ties keep the order of the earlier lists
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1));
+    fused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fusion_favours_items_ranked_by_both_lists() {
+        let lexical = [7, 1, 2];
+        let semantic = [1, 3, 4, 7];
+        let fused = reciprocal_rank_fusion(&[&lexical[..], &semantic[..]], RRF_K);
+
+        let order: Vec<i32> = fused.iter().map(|(item, _)| *item).collect();
+        assert_eq!(order, [1, 7, 3, 2, 4]);
+        assert!(fused.iter().all(|(_, score)| *score > 0.0 && *score <= 1.0));
+
+        let alone = reciprocal_rank_fusion(&[&semantic[..]], RRF_K);
+        assert_eq!(alone[0], (1, 1.0));
+        assert!(reciprocal_rank_fusion::<i32>(&[&[], &[]], RRF_K).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! One ranking over code and the documents describing it
+//!
+//! Doc comments are embedded into the semantic index and markdown chunks
+//! into the document store, both with the configured model. Merging the two
+//! result lists answers a query with the function and the design doc or ADR
+//! about it. Similarities are compared directly while the semantic index was
+//! built with the configured model; otherwise the lists are fused by rank.
+
+use super::{RRF_K, reciprocal_rank_fusion};
+use crate::documents::SearchResult;
+use crate::{Symbol, SymbolId};
+use serde::Serialize;
+use std::fmt;
+
+/// Names shorter than this are too common to say a document is about them
+const MIN_DESCRIBED_NAME: usize = 3;
+
+/// Where a piece of knowledge comes from
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum KnowledgeSource {
+    /// A symbol, found by its doc comment
+    Code { symbol: Symbol },
+    /// A chunk of a markdown document, README or ADR
+    Document {
+        chunk: SearchResult,
+        /// Symbols among the results the chunk names
+        describes: Vec<SymbolId>,
+    },
+}
+
+/// A result of a knowledge search
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeHit {
+    /// Similarity, or the fused rank score when similarities of the two
+    /// sources are not comparable
+    pub score: f32,
+    #[serde(flatten)]
+    pub source: KnowledgeSource,
+}
+
+impl fmt::Display for KnowledgeHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            KnowledgeSource::Code { symbol } => write!(
+                f,
+                "[code] {:?} {} at {}:{} (score: {:.3})",
+                symbol.kind,
+                symbol.name,
+                symbol.file_path,
+                symbol.range.start_line + 1,
+                self.score
+            ),
+            KnowledgeSource::Document { chunk, describes } => {
+                write!(
+                    f,
+                    "[{}] {} (score: {:.3})",
+                    chunk.kind,
+                    chunk.source_path.display(),
+                    self.score
+                )?;
+                if !chunk.heading_context.is_empty() {
+                    write!(f, "\n   Context: {}", chunk.heading_context.join(" > "))?;
+                }
+                write!(f, "\n   Preview: {}", chunk.content_preview)?;
+                if !describes.is_empty() {
+                    let ids: Vec<String> = describes
+                        .iter()
+                        .map(|id| format!("symbol_id:{}", id.value()))
+                        .collect();
+                    write!(f, "\n   Describes: {}", ids.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Merge symbol and document results into the best `limit`
+///
+/// `comparable` tells whether both sides were embedded by the same model.
+pub fn fuse_knowledge(
+    code: Vec<(Symbol, f32)>,
+    documents: Vec<SearchResult>,
+    comparable: bool,
+    limit: usize,
+) -> Vec<KnowledgeHit> {
+    let described: Vec<Vec<SymbolId>> = documents
+        .iter()
+        .map(|chunk| described_symbols(chunk, &code))
+        .collect();
+
+    let mut hits: Vec<KnowledgeHit> = if comparable {
+        let mut hits: Vec<KnowledgeHit> = code
+            .into_iter()
+            .map(|(symbol, score)| KnowledgeHit {
+                score,
+                source: KnowledgeSource::Code { symbol },
+            })
+            .chain(
+                documents
+                    .into_iter()
+                    .zip(described)
+                    .map(|(chunk, describes)| KnowledgeHit {
+                        score: chunk.similarity,
+                        source: KnowledgeSource::Document { chunk, describes },
+                    }),
+            )
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits
+    } else {
+        // Both lists come ranked best first
+        let code_ranking: Vec<(bool, usize)> = (0..code.len()).map(|i| (true, i)).collect();
+        let document_ranking: Vec<(bool, usize)> =
+            (0..documents.len()).map(|i| (false, i)).collect();
+        let fused = reciprocal_rank_fusion(&[&code_ranking[..], &document_ranking[..]], RRF_K);
+        let mut code: Vec<Option<Symbol>> = code.into_iter().map(|(s, _)| Some(s)).collect();
+        let mut documents: Vec<Option<(SearchResult, Vec<SymbolId>)>> =
+            documents.into_iter().zip(described).map(Some).collect();
+        fused
+            .into_iter()
+            .filter_map(|((is_code, i), score)| {
+                let source = if is_code {
+                    KnowledgeSource::Code {
+                        symbol: code[i].take()?,
+                    }
+                } else {
+                    let (chunk, describes) = documents[i].take()?;
+                    KnowledgeSource::Document { chunk, describes }
+                };
+                Some(KnowledgeHit { score, source })
+            })
+            .collect()
+    };
+    hits.truncate(limit);
+    hits
+}
+
+/// Symbols of `code` named as a whole word in the chunk's headings or text
+fn described_symbols(chunk: &SearchResult, code: &[(Symbol, f32)]) -> Vec<SymbolId> {
+    let words: Vec<&str> = chunk
+        .heading_context
+        .iter()
+        .map(String::as_str)
+        .chain([chunk.content_preview.as_str()])
+        .flat_map(|text| text.split(|c: char| !(c.is_alphanumeric() || c == '_')))
+        .filter(|word| word.len() >= MIN_DESCRIBED_NAME)
+        .collect();
+    code.iter()
+        .filter(|(symbol, _)| words.contains(&&*symbol.name))
+        .map(|(symbol, _)| symbol.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::{ChunkId, DocumentKind};
+    use crate::{FileId, Range, SymbolKind};
+    use std::path::PathBuf;
+
+    fn symbol(id: u32, name: &str) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(1, 0, 5, 1),
+        )
+    }
+
+    fn chunk(id: u32, preview: &str, similarity: f32) -> SearchResult {
+        SearchResult {
+            chunk_id: ChunkId::from_u32(id).unwrap(),
+            collection: "docs".to_string(),
+            source_path: PathBuf::from("docs/adr/0004-chunking.md"),
+            kind: DocumentKind::Adr,
+            heading_context: vec!["ADR 4: Chunking".to_string()],
+            content_preview: preview.to_string(),
+            byte_range: (0, preview.len()),
+            similarity,
+        }
+    }
+
+    fn order(hits: &[KnowledgeHit]) -> Vec<String> {
+        hits.iter()
+            .map(|hit| match &hit.source {
+                KnowledgeSource::Code { symbol } => symbol.name.to_string(),
+                KnowledgeSource::Document { chunk, .. } => format!("#{}", chunk.chunk_id.value()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_code_and_documents_share_one_ranking() {
+        let code = vec![(symbol(1, "chunk_symbols"), 0.7), (symbol(2, "merge"), 0.4)];
+        let documents = vec![
+            chunk(
+                1,
+                "We split symbols with `chunk_symbols()` and merge them.",
+                0.8,
+            ),
+            chunk(2, "Chunks overlap by a line.", 0.5),
+        ];
+
+        let hits = fuse_knowledge(code.clone(), documents.clone(), true, 3);
+        assert_eq!(order(&hits), ["#1", "chunk_symbols", "#2"]);
+        let KnowledgeSource::Document { describes, .. } = &hits[0].source else {
+            panic!("expected a document first");
+        };
+        assert_eq!(
+            describes,
+            &[SymbolId::new(1).unwrap(), SymbolId::new(2).unwrap()]
+        );
+
+        // Scores of different models are fused by rank, each list's best first
+        let hits = fuse_knowledge(code, documents, false, 10);
+        assert_eq!(hits.len(), 4);
+        assert_eq!(hits[0].score, hits[1].score);
+        assert_eq!(order(&hits[2..]), ["merge", "#2"]);
+    }
+}
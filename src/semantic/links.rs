@@ -0,0 +1,322 @@
+//! Symbols of different languages that implement the same API
+//!
+//! A TypeScript client method and the Rust handler it requests share no call
+//! edge: the request crosses a process boundary the parsers cannot follow.
+//! Two signals suggest such a pair: a route both mention, such as
+//! `/users/${id}` and `/users/{id}`, and a name of the same words, such as
+//! `getUser` and `get_user_handler`. Only symbols sharing a signal are
+//! compared by embedding, so unrelated code of similar wording is never
+//! linked. Each symbol keeps its best link per other language.
+
+use super::split_identifier;
+use crate::SymbolId;
+use crate::vector::cosine_similarity;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Added to the similarity of a pair mentioning the same route
+const ROUTE_BONUS: f32 = 0.3;
+
+/// Added to the similarity of a pair named with the same words
+const NAME_BONUS: f32 = 0.15;
+
+/// Highest confidence of a link; relationships the parsers resolved weigh 1.0
+pub const MAX_CONFIDENCE: f32 = 0.9;
+
+/// Symbols sharing a signal beyond this many are too alike to tell apart
+const MAX_SHARED: usize = 32;
+
+/// Words naming the role of a symbol rather than what it does
+const ROLE_WORDS: &[&str] = &[
+    "api",
+    "client",
+    "controller",
+    "endpoint",
+    "handle",
+    "handler",
+    "request",
+    "route",
+    "service",
+];
+
+/// A symbol that may be linked
+#[derive(Debug, Clone)]
+pub struct LinkCandidate {
+    pub id: SymbolId,
+    pub language: String,
+    pub name: String,
+    /// Route mentioned in or just above the symbol, see [`extract_route`]
+    pub route: Option<String>,
+    pub embedding: Vec<f32>,
+}
+
+/// The signal two linked symbols share
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkEvidence {
+    /// Normalized route, e.g. `/users/{}`
+    Route(String),
+    /// Words of both names, e.g. `get user`
+    Name(String),
+}
+
+impl fmt::Display for LinkEvidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Route(route) => write!(f, "route {route}"),
+            Self::Name(words) => write!(f, "name '{words}'"),
+        }
+    }
+}
+
+/// Two symbols of different languages likely implementing the same API
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossLanguageLink {
+    pub from: SymbolId,
+    pub to: SymbolId,
+    /// Similarity of the two embeddings
+    pub similarity: f32,
+    /// Similarity raised by the shared signals, at most [`MAX_CONFIDENCE`]
+    pub confidence: f32,
+    pub evidence: LinkEvidence,
+}
+
+/// Links between `candidates` of different languages with a confidence of
+/// at least `threshold`, most confident first
+pub fn link_across_languages(
+    candidates: &[LinkCandidate],
+    threshold: f32,
+) -> Vec<CrossLanguageLink> {
+    let mut by_route: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        if let Some(route) = candidate.route.as_deref() {
+            by_route.entry(route).or_default().push(i);
+        }
+        if let Some(words) = name_words(&candidate.name) {
+            by_name.entry(words).or_default().push(i);
+        }
+    }
+
+    // Per pair: the shared route and name words
+    let mut pairs: HashMap<(usize, usize), (Option<String>, Option<String>)> = HashMap::new();
+    for (route, members) in &by_route {
+        for pair in cross_language_pairs(candidates, members) {
+            pairs.entry(pair).or_default().0 = Some(route.to_string());
+        }
+    }
+    for (words, members) in &by_name {
+        for pair in cross_language_pairs(candidates, members) {
+            pairs.entry(pair).or_default().1 = Some(words.clone());
+        }
+    }
+
+    let mut scored: Vec<(usize, usize, CrossLanguageLink)> = pairs
+        .into_iter()
+        .filter_map(|((i, j), (route, words))| {
+            let similarity = cosine_similarity(&candidates[i].embedding, &candidates[j].embedding);
+            let mut confidence = similarity;
+            if route.is_some() {
+                confidence += ROUTE_BONUS;
+            }
+            if words.is_some() {
+                confidence += NAME_BONUS;
+            }
+            let confidence = confidence.min(MAX_CONFIDENCE);
+            if confidence < threshold {
+                return None;
+            }
+            let evidence = match (route, words) {
+                (Some(route), _) => LinkEvidence::Route(route),
+                (None, Some(words)) => LinkEvidence::Name(words),
+                (None, None) => return None,
+            };
+            let link = CrossLanguageLink {
+                from: candidates[i].id,
+                to: candidates[j].id,
+                similarity,
+                confidence,
+                evidence,
+            };
+            Some((i, j, link))
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.2.confidence
+            .total_cmp(&a.2.confidence)
+            .then_with(|| (a.0, a.1).cmp(&(b.0, b.1)))
+    });
+
+    // Best link of each symbol per other language
+    let mut linked: HashSet<(usize, &str)> = HashSet::new();
+    let mut links = Vec::new();
+    for (i, j, link) in scored {
+        let (language_i, language_j) = (
+            candidates[i].language.as_str(),
+            candidates[j].language.as_str(),
+        );
+        if linked.contains(&(i, language_j)) || linked.contains(&(j, language_i)) {
+            continue;
+        }
+        linked.insert((i, language_j));
+        linked.insert((j, language_i));
+        links.push(link);
+    }
+    links
+}
+
+/// Pairs of `members` in different languages, none if there are too many
+fn cross_language_pairs(candidates: &[LinkCandidate], members: &[usize]) -> Vec<(usize, usize)> {
+    if members.len() > MAX_SHARED {
+        return Vec::new();
+    }
+    let mut pairs = Vec::new();
+    for (n, &i) in members.iter().enumerate() {
+        for &j in &members[n + 1..] {
+            if candidates[i].language != candidates[j].language {
+                pairs.push((i.min(j), i.max(j)));
+            }
+        }
+    }
+    pairs
+}
+
+/// Words of `name` without role words, if at least two are left
+///
+/// One word, such as `get` or `new`, names too many symbols to link by.
+fn name_words(name: &str) -> Option<String> {
+    let words: Vec<String> = split_identifier(name)
+        .into_iter()
+        .filter(|word| !ROLE_WORDS.contains(&word.as_str()))
+        .collect();
+    (words.len() >= 2).then(|| words.join(" "))
+}
+
+/// First HTTP route quoted in `text`, normalized so client and server
+/// spellings compare equal
+///
+/// Parameters (`{id}`, `:id`, `<int:id>`, `${id}`) become `{}`, a scheme and
+/// host or a leading `${base}` are dropped, and so are leading `api` and
+/// version segments, which routers often mount the handlers under.
+pub fn extract_route(text: &str) -> Option<String> {
+    let mut rest = text;
+    while let Some(start) = rest.find(['"', '\'', '`']) {
+        let quote = rest[start..].chars().next()?;
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(quote) else {
+            rest = after;
+            continue;
+        };
+        if let Some(route) = normalize_route(&after[..end]) {
+            return Some(route);
+        }
+        rest = &after[end + 1..];
+    }
+    None
+}
+
+fn normalize_route(literal: &str) -> Option<String> {
+    let path = if let Some(url) = literal
+        .strip_prefix("http://")
+        .or_else(|| literal.strip_prefix("https://"))
+    {
+        &url[url.find('/')?..]
+    } else if literal.starts_with("${") {
+        &literal[literal.find('}')? + 1..]
+    } else {
+        literal
+    };
+    if !path.starts_with('/') || path.contains(char::is_whitespace) {
+        return None;
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    let mut segments: Vec<String> = Vec::new();
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if segment.starts_with([':', '{', '<', '$', '*']) {
+            segments.push("{}".to_string());
+        } else if segment
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            segments.push(segment.to_lowercase());
+        } else {
+            return None;
+        }
+    }
+    let version = |segment: &str| {
+        segment.len() > 1
+            && segment.starts_with('v')
+            && segment[1..].chars().all(|c| c.is_ascii_digit())
+    };
+    let mounted = segments
+        .iter()
+        .take_while(|segment| *segment == "api" || version(segment))
+        .count();
+    let segments = &segments[mounted..];
+
+    // A file path, not a route
+    if segments.last().is_some_and(|segment| segment.contains('.')) {
+        return None;
+    }
+    if !segments
+        .iter()
+        .any(|segment| segment.chars().any(char::is_alphabetic) && segment != "{}")
+    {
+        return None;
+    }
+    Some(format!("/{}", segments.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: u32, language: &str, name: &str, route: Option<&str>) -> LinkCandidate {
+        LinkCandidate {
+            id: SymbolId::new(id).unwrap(),
+            language: language.to_string(),
+            name: name.to_string(),
+            route: route.map(str::to_string),
+            embedding: vec![1.0, id as f32 * 0.1],
+        }
+    }
+
+    #[test]
+    fn test_client_and_handler_are_linked() {
+        assert_eq!(
+            extract_route("return fetch(`${BASE}/api/v1/users/${id}?full=1`)").as_deref(),
+            Some("/users/{}")
+        );
+        assert_eq!(
+            extract_route("#[get(\"/users/{id}\")]\nasync fn show(").as_deref(),
+            Some("/users/{}")
+        );
+        assert_eq!(
+            extract_route("@app.route('/users/<int:id>')").as_deref(),
+            Some("/users/{}")
+        );
+        assert_eq!(extract_route("open(\"/etc/hosts.conf\")"), None);
+        assert_eq!(extract_route("join(\"/\", parts)"), None);
+
+        let candidates = [
+            candidate(1, "typescript", "getUser", Some("/users/{}")),
+            candidate(2, "rust", "show", Some("/users/{}")),
+            candidate(3, "rust", "get_user_handler", None),
+            candidate(4, "typescript", "listOrders", None),
+            candidate(5, "python", "list_orders", None),
+            candidate(6, "rust", "get_users", Some("/users")),
+        ];
+        let links = link_across_languages(&candidates, 0.75);
+        let pairs: Vec<(u32, u32)> = links
+            .iter()
+            .map(|link| (link.from.value(), link.to.value()))
+            .collect();
+        // The route outranks the name, and getUser keeps one rust link
+        assert_eq!(pairs, [(1, 2), (4, 5)]);
+        assert_eq!(links[0].evidence, LinkEvidence::Route("/users/{}".into()));
+        assert_eq!(links[0].confidence, MAX_CONFIDENCE);
+        assert_eq!(links[1].evidence, LinkEvidence::Name("list orders".into()));
+
+        assert!(link_across_languages(&candidates, 0.95).is_empty());
+    }
+}
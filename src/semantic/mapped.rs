@@ -0,0 +1,194 @@
+//! Embeddings searched in place on disk
+//!
+//! With `semantic_search.mmap_vectors`, a loaded index keeps only the IDs of
+//! its symbols in memory. A search reads every vector through the memory
+//! map of the segment files, so startup does not wait for the vectors to
+//! load and the OS keeps as many of them in its page cache as memory
+//! allows. Embeddings added since the index was loaded stay in memory
+//! until it is saved.
+
+use super::SemanticVectorStorage;
+use super::quantize::FullVectors;
+use crate::SymbolId;
+use std::collections::HashSet;
+use std::sync::{Mutex, MutexGuard};
+
+/// Embeddings in a memory-mapped store, with unsaved ones in memory
+#[derive(Debug, Default)]
+pub struct MappedSet {
+    ids: HashSet<SymbolId>,
+    full: Mutex<FullVectors>,
+}
+
+impl MappedSet {
+    /// Search `storage` in place
+    pub fn load(storage: SemanticVectorStorage) -> Self {
+        Self {
+            ids: storage.ids().collect(),
+            full: Mutex::new(FullVectors {
+                pending: Default::default(),
+                disk: Some(storage),
+            }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn contains(&self, id: SymbolId) -> bool {
+        self.ids.contains(&id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = SymbolId> + '_ {
+        self.ids.iter().copied()
+    }
+
+    pub fn insert(&mut self, id: SymbolId, vector: Vec<f32>) {
+        self.ids.insert(id);
+        self.full_mut().pending.insert(id, vector);
+    }
+
+    pub fn remove(&mut self, id: SymbolId) {
+        self.ids.remove(&id);
+        self.full_mut().pending.remove(&id);
+    }
+
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        *self.full_mut() = FullVectors::default();
+    }
+
+    /// Embedding of `id`, from memory if unsaved
+    pub fn get(&self, id: SymbolId) -> Option<Vec<f32>> {
+        if !self.contains(id) {
+            return None;
+        }
+        self.full().get(id)
+    }
+
+    /// The `limit` symbols most similar to `query` among those `keep`
+    /// accepts, best first
+    pub fn search(
+        &self,
+        query: &[f32],
+        limit: usize,
+        keep: impl Fn(SymbolId) -> bool,
+    ) -> Vec<(SymbolId, f32)> {
+        let mut full = self.full();
+        let FullVectors { pending, disk } = &mut *full;
+        let mut results: Vec<(SymbolId, f32)> = pending
+            .iter()
+            .filter(|(id, _)| keep(**id))
+            .map(|(id, vector)| (*id, cosine(query, vector)))
+            .collect();
+        if let Some(disk) = disk {
+            disk.for_each(|id, vector| {
+                // Stored copies of removed or changed symbols are stale
+                if self.ids.contains(&id) && !pending.contains_key(&id) && keep(id) {
+                    results.push((id, cosine(query, &vector)));
+                }
+            });
+        }
+        drop(full);
+
+        results.sort_by(|a, b| b.1.total_cmp(&a.1));
+        results.truncate(limit);
+        results
+    }
+
+    /// Every embedding, to be written to another store
+    pub fn full_vectors(&self) -> Vec<(SymbolId, Vec<f32>)> {
+        let mut full = self.full();
+        self.ids
+            .iter()
+            .filter_map(|id| Some((*id, full.get(*id)?)))
+            .collect()
+    }
+
+    /// Embeddings added since the index was loaded or saved
+    pub fn pending(&self) -> Vec<(SymbolId, Vec<f32>)> {
+        self.full()
+            .pending
+            .iter()
+            .map(|(id, vector)| (*id, vector.clone()))
+            .collect()
+    }
+
+    /// Run `f` on the store searched in place, if there is one
+    pub fn with_store<R>(&self, f: impl FnOnce(&mut SemanticVectorStorage) -> R) -> Option<R> {
+        self.full().disk.as_mut().map(f)
+    }
+
+    /// Pending embeddings were written to the store, or to `storage`, which
+    /// is searched from now on
+    pub fn saved(&self, storage: Option<SemanticVectorStorage>) {
+        let mut full = self.full();
+        if let Some(storage) = storage {
+            full.disk = Some(storage);
+        }
+        full.pending.clear();
+    }
+
+    fn full(&self) -> MutexGuard<'_, FullVectors> {
+        self.full.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn full_mut(&mut self) -> &mut FullVectors {
+        self.full.get_mut().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::VectorDimension;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mapped_search_reads_store_and_pending() {
+        let temp_dir = TempDir::new().unwrap();
+        let id = |value| SymbolId::new(value).unwrap();
+        let mut storage =
+            SemanticVectorStorage::new(temp_dir.path(), VectorDimension::new(2).unwrap()).unwrap();
+        storage
+            .save_batch(&[(id(1), vec![1.0, 0.0]), (id(2), vec![0.0, 1.0])])
+            .unwrap();
+
+        let mut set = MappedSet::load(storage);
+        assert_eq!(set.len(), 2);
+        set.insert(id(3), vec![0.9, 0.1]);
+        set.insert(id(2), vec![1.0, 0.1]);
+        set.remove(id(1));
+
+        let results = set.search(&[1.0, 0.0], 5, |_| true);
+        let ids: Vec<SymbolId> = results.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, [id(2), id(3)]);
+        assert_eq!(set.get(id(2)), Some(vec![1.0, 0.1]));
+        assert!(set.get(id(1)).is_none());
+        assert_eq!(set.search(&[1.0, 0.0], 5, |id| id.value() == 3).len(), 1);
+
+        // Once saved, vectors are read back from the store
+        let pending = set.pending();
+        set.with_store(|store| store.save_batch(&pending))
+            .unwrap()
+            .unwrap();
+        set.saved(None);
+        assert!(set.pending().is_empty());
+        assert_eq!(set.get(id(3)), Some(vec![0.9, 0.1]));
+    }
+}
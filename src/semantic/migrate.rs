@@ -0,0 +1,292 @@
+//! Re-embedding a semantic index with another model
+//!
+//! Changing `semantic_search.model` or `provider` leaves the saved vectors
+//! built by the old model, which keeps answering queries: vectors of two
+//! models cannot be compared. A migration embeds the symbols again with the
+//! configured model into a staging directory beside the index, then swaps
+//! the directories. The old vectors serve until the new ones are complete,
+//! and a migration that fails or is interrupted leaves them untouched.
+
+use super::{EmbeddingPool, SemanticSearchError, SimpleSemanticSearch};
+use crate::SymbolId;
+use crate::config::SemanticSearchConfig;
+use std::path::{Path, PathBuf};
+
+/// Symbols embedded per batch, keeping the chunks of a symbol together
+const BATCH_SYMBOLS: usize = 256;
+
+/// Texts semantic search embeds for one symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolTexts {
+    pub id: SymbolId,
+    /// The doc comment, or the chunks of the language's chunking strategy
+    pub texts: Vec<String>,
+    pub language: String,
+}
+
+/// Embeddings being rebuilt with the model `config` selects
+pub struct SemanticMigration {
+    config: SemanticSearchConfig,
+    pool: EmbeddingPool,
+    search: SimpleSemanticSearch,
+}
+
+impl SemanticMigration {
+    /// Start a migration to the configured model
+    pub fn new(config: &SemanticSearchConfig) -> Result<Self, SemanticSearchError> {
+        Ok(Self {
+            config: config.clone(),
+            pool: EmbeddingPool::from_config(config)?,
+            search: SimpleSemanticSearch::from_config(config)?,
+        })
+    }
+
+    /// Migration embedding with `provider`, for tests without a model
+    #[cfg(test)]
+    fn with_provider(provider: std::sync::Arc<dyn crate::vector::EmbeddingProvider>) -> Self {
+        Self {
+            config: SemanticSearchConfig::default(),
+            pool: EmbeddingPool::with_instances(vec![provider.clone()]),
+            search: SimpleSemanticSearch::with_provider(provider),
+        }
+    }
+
+    /// Model the embeddings are rebuilt with, as recorded in the index
+    pub fn model_id(&self) -> &str {
+        self.pool.model_name()
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.pool.dimensions()
+    }
+
+    /// Symbols embedded so far
+    pub fn embedding_count(&self) -> usize {
+        self.search.embedding_count()
+    }
+
+    pub fn symbol_ids(&self) -> Vec<SymbolId> {
+        self.search.symbol_ids()
+    }
+
+    /// Embed the texts of `symbols`, calling `progress` with the number of
+    /// symbols done after each batch. Returns the symbols embedded.
+    ///
+    /// Fails on the first batch with a text left unembedded, such as when a
+    /// remote provider is unreachable, so a partial index is never installed.
+    pub fn embed(
+        &mut self,
+        symbols: &[SymbolTexts],
+        mut progress: impl FnMut(usize),
+    ) -> Result<usize, SemanticSearchError> {
+        let mut stored = 0;
+        let mut done = 0;
+        for batch in symbols.chunks(BATCH_SYMBOLS) {
+            let items: Vec<(SymbolId, &str, &str)> = batch
+                .iter()
+                .flat_map(|symbol| {
+                    symbol
+                        .texts
+                        .iter()
+                        .map(|text| (symbol.id, text.as_str(), symbol.language.as_str()))
+                })
+                .collect();
+            // The pool skips blank texts and logs and drops failed ones
+            let expected = items
+                .iter()
+                .filter(|(_, text, _)| !text.trim().is_empty())
+                .count();
+            let embeddings = self.pool.embed_parallel(&items);
+            if embeddings.len() < expected {
+                return Err(SemanticSearchError::EmbeddingError(format!(
+                    "{} of {expected} texts were not embedded by '{}'",
+                    expected - embeddings.len(),
+                    self.model_id()
+                )));
+            }
+            stored += self.search.store_embeddings(embeddings);
+            done += batch.len();
+            progress(done);
+        }
+        Ok(stored)
+    }
+
+    /// Drop embeddings of symbols removed since their texts were collected
+    pub fn remove(&mut self, ids: &[SymbolId]) {
+        self.search.remove_embeddings(ids);
+    }
+
+    /// Save the new embeddings and swap them in for those at `path`
+    ///
+    /// Returns the installed index, loaded from `path`, and the pool to
+    /// embed updates with.
+    pub fn install(
+        self,
+        path: &Path,
+    ) -> Result<(SimpleSemanticSearch, EmbeddingPool), SemanticSearchError> {
+        let staging = sibling(path, "migrating");
+        remove_dir(&staging)?;
+        self.search.save(&staging)?;
+        let Self {
+            config,
+            pool,
+            search,
+        } = self;
+        // Release the staged files before they move
+        drop(search);
+
+        let previous = sibling(path, "previous");
+        remove_dir(&previous)?;
+        if path.exists() {
+            rename(path, &previous)?;
+        }
+        rename(&staging, path)?;
+        if let Err(e) = std::fs::remove_dir_all(&previous) {
+            tracing::warn!(
+                target: "semantic",
+                "Failed to remove {}: {e}",
+                previous.display()
+            );
+        }
+        tracing::info!(target: "semantic", "Installed re-embedded index at {}", path.display());
+
+        Ok((SimpleSemanticSearch::load_with_config(path, &config)?, pool))
+    }
+}
+
+/// Put back the index at `path` if a migration stopped between swapping
+/// the directories
+///
+/// The staging directory is left alone, since another process may be
+/// migrating; the next migration starts it over.
+pub fn recover_migration(path: &Path) {
+    let previous = sibling(path, "previous");
+    if previous.exists() && !path.exists() {
+        match std::fs::rename(&previous, path) {
+            Ok(()) => tracing::warn!(
+                target: "semantic",
+                "Restored {} after an interrupted model migration",
+                path.display()
+            ),
+            Err(e) => tracing::warn!(
+                target: "semantic",
+                "Failed to restore {}: {e}",
+                previous.display()
+            ),
+        }
+    }
+    if previous.exists() {
+        let _ = std::fs::remove_dir_all(previous);
+    }
+}
+
+/// `path` with `suffix` appended to its last component
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn remove_dir(path: &Path) -> Result<(), SemanticSearchError> {
+    if !path.exists() {
+        return Ok(());
+    }
+    std::fs::remove_dir_all(path).map_err(|e| SemanticSearchError::StorageError {
+        message: format!("Failed to remove {}: {e}", path.display()),
+        suggestion: "Check directory permissions".to_string(),
+    })
+}
+
+fn rename(from: &Path, to: &Path) -> Result<(), SemanticSearchError> {
+    std::fs::rename(from, to).map_err(|e| SemanticSearchError::StorageError {
+        message: format!("Failed to move {} to {}: {e}", from.display(), to.display()),
+        suggestion: "Check directory permissions; the index is restored on next load".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::{EmbeddingProvider, MockEmbeddingGenerator, VectorError};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Remote provider whose endpoint cannot be reached
+    struct Unreachable;
+
+    impl EmbeddingProvider for Unreachable {
+        fn model_id(&self) -> &str {
+            "ollama:unreachable"
+        }
+
+        fn dimensions(&self) -> usize {
+            4
+        }
+
+        fn max_batch_size(&self) -> usize {
+            8
+        }
+
+        fn embed_batch(&self, _texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+            Err(VectorError::EmbeddingFailed(
+                "connection refused".to_string(),
+            ))
+        }
+    }
+
+    fn symbols() -> Vec<SymbolTexts> {
+        (1..=3)
+            .map(|id| SymbolTexts {
+                id: SymbolId::new(id).unwrap(),
+                texts: vec![format!("Parse record {id}"), "  ".to_string()],
+                language: "rust".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_embed_counts_symbols() {
+        let mut migration =
+            SemanticMigration::with_provider(Arc::new(MockEmbeddingGenerator::new()));
+        let mut progress = Vec::new();
+        let stored = migration.embed(&symbols(), |done| progress.push(done));
+        assert_eq!(stored.unwrap(), 3);
+        assert_eq!(progress, [3]);
+    }
+
+    #[test]
+    fn test_embed_fails_when_a_batch_fails() {
+        let mut migration = SemanticMigration::with_provider(Arc::new(Unreachable));
+        let mut progress = Vec::new();
+        let err = migration
+            .embed(&symbols(), |done| progress.push(done))
+            .unwrap_err();
+        assert!(err.to_string().contains("3 of 3 texts"), "{err}");
+        assert!(progress.is_empty());
+        assert_eq!(migration.embedding_count(), 0);
+    }
+
+    #[test]
+    fn test_interrupted_swap_is_recovered() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("semantic");
+        assert_eq!(
+            sibling(&path, "migrating"),
+            temp_dir.path().join("semantic.migrating")
+        );
+
+        // Stopped after moving the old index aside
+        let previous = sibling(&path, "previous");
+        std::fs::create_dir(&previous).unwrap();
+        std::fs::write(previous.join("metadata.json"), "{}").unwrap();
+
+        recover_migration(&path);
+        assert!(path.join("metadata.json").exists());
+        assert!(!previous.exists());
+
+        // Nothing to do once the swap completed
+        recover_migration(&path);
+        assert!(path.join("metadata.json").exists());
+    }
+}
@@ -3,13 +3,39 @@
 //! This module provides a simple API for semantic search on documentation,
 //! designed to integrate with the existing indexing system.
 
+mod cache;
+mod calibrate;
+mod chunking;
+mod cluster;
+mod expand;
+mod fusion;
+mod knowledge;
+mod links;
+mod mapped;
 mod metadata;
+mod migrate;
 mod pool;
+mod quantize;
+mod rerank;
 mod simple;
 mod storage;
 
+pub use cache::EmbeddingCache;
+pub use calibrate::{CalibrationScores, MIN_PAIRS};
+pub use chunking::{ChunkingStrategy, MAX_CHUNK_CHARS, chunk_symbols, merge_chunk_embeddings};
+pub use cluster::{EmbeddingCluster, cluster_embeddings};
+pub use expand::{ExpandedQuery, expand_query, split_identifier};
+pub use fusion::{RRF_K, reciprocal_rank_fusion};
+pub use knowledge::{KnowledgeHit, KnowledgeSource, fuse_knowledge};
+pub use links::{
+    CrossLanguageLink, LinkCandidate, LinkEvidence, MAX_CONFIDENCE, extract_route,
+    link_across_languages,
+};
 pub use metadata::SemanticMetadata;
+pub use migrate::{SemanticMigration, SymbolTexts, recover_migration};
 pub use pool::EmbeddingPool;
+pub use quantize::Quantization;
+pub use rerank::{RERANKER_MODELS, Reranker, parse_reranker_model};
 pub use simple::{SemanticSearchError, SimpleSemanticSearch};
 pub use storage::SemanticVectorStorage;
 
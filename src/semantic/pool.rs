@@ -1,24 +1,30 @@
 //! Embedding model pool for parallel embedding generation
 //!
-//! Provides multiple TextEmbedding instances that can be used concurrently
-//! by different threads, enabling parallel embedding generation.
+//! Provides multiple embedding provider instances that can be used
+//! concurrently by different threads, enabling parallel embedding generation.
+//! Local models get one instance each; a remote provider is shared, so each
+//! slot is one request in flight.
 
 use crate::SymbolId;
+use crate::config::SemanticSearchConfig;
+use crate::vector::{EmbeddingDevice, EmbeddingProvider, FastEmbedGenerator};
 use crossbeam_channel::{Receiver, Sender, bounded};
-use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use fastembed::EmbeddingModel;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use super::SemanticSearchError;
+use super::cache::EmbeddingCache;
 
 /// Model instance with an ID for tracking
 struct ModelInstance {
-    model: TextEmbedding,
+    model: Arc<dyn EmbeddingProvider>,
     id: usize,
 }
 
-/// Pool of TextEmbedding models for parallel embedding generation.
+/// Pool of embedding providers for parallel embedding generation.
 ///
-/// Each model instance is expensive (~86MB), but having multiple allows
+/// Each local model instance is expensive (~86MB), but having multiple allows
 /// true parallel embedding generation with rayon.
 pub struct EmbeddingPool {
     /// Channel to acquire models from the pool
@@ -30,24 +36,37 @@ pub struct EmbeddingPool {
     dimensions: usize,
     /// Model name for metadata
     model_name: String,
+    /// Documents per embedding call
+    batch_size: usize,
     /// Usage counters per model instance (for tracing)
     usage_counters: Vec<AtomicUsize>,
+    /// Embeddings shared with other projects, see [`EmbeddingCache`]
+    cache: Option<EmbeddingCache>,
 }
 
 impl EmbeddingPool {
     /// Create a new embedding pool with the specified number of model instances.
     ///
     /// # Arguments
-    /// * `pool_size` - Number of TextEmbedding instances to create
+    /// * `pool_size` - Number of local model instances to create
     /// * `model` - The embedding model to use
     ///
     /// # Note
     /// Each model instance uses ~86MB of memory for AllMiniLML6V2.
     pub fn new(pool_size: usize, model: EmbeddingModel) -> Result<Self, SemanticSearchError> {
-        let pool_size = pool_size.max(1);
-        let (sender, receiver) = bounded(pool_size);
+        Self::local(pool_size, model, EmbeddingDevice::Auto)
+    }
 
-        let cache_dir = crate::init::models_dir();
+    /// Pool of local model instances on `device`.
+    ///
+    /// A model running on a GPU gets a single instance: the GPU already works
+    /// through a batch in parallel, and every instance takes device memory.
+    fn local(
+        pool_size: usize,
+        model: EmbeddingModel,
+        device: EmbeddingDevice,
+    ) -> Result<Self, SemanticSearchError> {
+        let pool_size = pool_size.max(1);
         let model_name = crate::vector::model_to_string(&model);
 
         tracing::info!(
@@ -55,41 +74,97 @@ impl EmbeddingPool {
             "Initializing embedding pool: {pool_size} instances ({model_name})"
         );
 
-        let mut dimensions = 0;
-
-        // Create usage counters for each model
-        let usage_counters: Vec<AtomicUsize> =
-            (0..pool_size).map(|_| AtomicUsize::new(0)).collect();
-
-        // Create pool_size model instances
-        for i in 0..pool_size {
-            let mut text_model = TextEmbedding::try_new(
-                InitOptions::new(model.clone())
-                    .with_cache_dir(cache_dir.clone())
-                    .with_show_download_progress(i == 0), // Only show progress for first model
-            )
-            .map_err(|e| {
+        // Only show progress for first model
+        let create = |i: usize| {
+            FastEmbedGenerator::with_device(model.clone(), i == 0, device).map_err(|e| {
                 SemanticSearchError::ModelInitError(format!(
                     "Failed to initialize model instance {}: {}",
                     i + 1,
                     e
                 ))
-            })?;
-
-            // Get dimensions from first model
-            if i == 0 {
-                let test_embedding = text_model
-                    .embed(vec!["test"], None)
-                    .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-                dimensions = test_embedding.into_iter().next().unwrap().len();
+            })
+        };
+
+        let first = create(0)?;
+        let on_gpu = first.device() != "cpu";
+        let mut instances: Vec<Arc<dyn EmbeddingProvider>> = vec![Arc::new(first)];
+        if !on_gpu {
+            for i in 1..pool_size {
+                instances.push(Arc::new(create(i)?));
             }
+        }
 
-            let instance = ModelInstance {
-                model: text_model,
-                id: i,
-            };
+        Ok(Self::with_instances(instances))
+    }
+
+    /// Create a pool for the model that built an index, see
+    /// [`provider_for_model_id`](crate::vector::provider_for_model_id).
+    ///
+    /// `config.embedding_threads` local instances are created, or that many
+    /// concurrent requests to a shared remote provider.
+    pub fn for_model_id(
+        model_id: &str,
+        config: &SemanticSearchConfig,
+    ) -> Result<Self, SemanticSearchError> {
+        let pool_size = config.embedding_threads.max(1);
+        if crate::vector::is_local_model_id(model_id) {
+            let model = crate::vector::parse_embedding_model(model_id)
+                .map_err(|e| SemanticSearchError::ModelInitError(e.to_string()))?;
+            let device = EmbeddingDevice::parse(&config.device)
+                .map_err(|e| SemanticSearchError::ModelInitError(e.to_string()))?;
+            return Ok(Self::local(pool_size, model, device)?.with_cache(config));
+        }
+
+        let provider = crate::vector::provider_for_model_id(model_id, config, false)
+            .map_err(|e| SemanticSearchError::ModelInitError(e.to_string()))?;
+        Ok(Self::with_instances(vec![provider; pool_size]).with_cache(config))
+    }
+
+    /// Reuse embeddings from the per-user cache when `config` enables it
+    fn with_cache(mut self, config: &SemanticSearchConfig) -> Self {
+        if !config.embedding_cache {
+            return self;
+        }
+        let max_bytes = config.embedding_cache_max_mb.saturating_mul(1024 * 1024);
+        match EmbeddingCache::open(&self.model_name, self.dimensions, max_bytes) {
+            Ok(cache) => {
+                tracing::debug!(
+                    target: "semantic",
+                    "Embedding cache {}: {} entries",
+                    cache.path().display(),
+                    cache.len()
+                );
+                self.cache = Some(cache);
+            }
+            Err(e) => {
+                tracing::warn!(target: "semantic", "Embedding cache unavailable: {e}");
+            }
+        }
+        self
+    }
+
+    /// Create a pool for the provider selected in settings.
+    pub fn from_config(config: &SemanticSearchConfig) -> Result<Self, SemanticSearchError> {
+        let model_id = crate::vector::configured_model_id(config)
+            .map_err(|e| SemanticSearchError::ModelInitError(e.to_string()))?;
+        Self::for_model_id(&model_id, config)
+    }
+
+    /// Pool over `instances`, which must share one model
+    pub(super) fn with_instances(instances: Vec<Arc<dyn EmbeddingProvider>>) -> Self {
+        let pool_size = instances.len();
+        let (sender, receiver) = bounded(pool_size);
+        let dimensions = instances[0].dimensions();
+        let model_name = instances[0].model_id().to_string();
+        let batch_size = instances[0].max_batch_size().max(1);
+
+        // Create usage counters for each model
+        let usage_counters: Vec<AtomicUsize> =
+            (0..pool_size).map(|_| AtomicUsize::new(0)).collect();
+
+        for (id, model) in instances.into_iter().enumerate() {
             sender
-                .send(instance)
+                .send(ModelInstance { model, id })
                 .expect("Pool channel should not be closed");
         }
 
@@ -98,14 +173,16 @@ impl EmbeddingPool {
             "Embedding pool ready: {pool_size} instances, {dimensions} dimensions"
         );
 
-        Ok(Self {
+        Self {
             model_sender: sender,
             model_receiver: receiver,
             pool_size,
             dimensions,
             model_name,
+            batch_size,
             usage_counters,
-        })
+            cache: None,
+        }
     }
 
     /// Create a pool with default model (AllMiniLML6V2)
@@ -156,14 +233,21 @@ impl EmbeddingPool {
             ));
         }
 
-        let mut instance = self.acquire();
+        if let Some(embedding) = self.cache.as_ref().and_then(|cache| cache.get(text)) {
+            return Ok(embedding);
+        }
+
+        let instance = self.acquire();
         let result = instance
             .model
-            .embed(vec![text], None)
+            .embed_one(text)
             .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()));
         self.release(instance);
 
-        result.map(|mut v| v.remove(0))
+        if let (Ok(embedding), Some(cache)) = (&result, &self.cache) {
+            cache.insert_batch(&[text], std::slice::from_ref(embedding));
+        }
+        result
     }
 
     /// Log usage statistics for all model instances.
@@ -192,7 +276,8 @@ impl EmbeddingPool {
 
     /// Generate embeddings for multiple documents in parallel using rayon.
     ///
-    /// Uses batched embedding (64 docs per model call) for optimal throughput.
+    /// Uses batched embedding (`semantic_search.batch_size` docs per model
+    /// call, 64 by default) for optimal throughput.
     /// Returns a Vec of (SymbolId, embedding, language) for successful embeddings.
     /// Failed embeddings are logged and skipped.
     pub fn embed_parallel(
@@ -201,9 +286,6 @@ impl EmbeddingPool {
     ) -> Vec<(SymbolId, Vec<f32>, String)> {
         use rayon::prelude::*;
 
-        // Optimal batch size for embedding models (matches benchmark)
-        const BATCH_SIZE: usize = 64;
-
         // Filter out empty docs first
         let valid_items: Vec<_> = items
             .iter()
@@ -214,22 +296,49 @@ impl EmbeddingPool {
             return Vec::new();
         }
 
-        // Process in batches of 64, parallelized across available model instances
-        let results: Vec<_> = valid_items
-            .chunks(BATCH_SIZE)
+        // Documentation embedded before, here or in another project
+        let mut cached = Vec::new();
+        let valid_items: Vec<_> = match &self.cache {
+            Some(cache) => valid_items
+                .into_iter()
+                .filter(|(symbol_id, doc, language)| match cache.get(doc) {
+                    Some(embedding) => {
+                        cached.push((*symbol_id, embedding, (*language).to_string()));
+                        false
+                    }
+                    None => true,
+                })
+                .collect(),
+            None => valid_items,
+        };
+        if !cached.is_empty() {
+            tracing::info!(
+                target: "semantic",
+                "Embedding cache: reused {} of {} embeddings",
+                cached.len(),
+                cached.len() + valid_items.len()
+            );
+        }
+
+        // Process in provider-sized batches, parallelized across available model instances
+        let mut results: Vec<_> = valid_items
+            .chunks(self.batch_size)
             .par_bridge()
             .flat_map(|batch| {
                 // Collect texts for batch embedding
                 let texts: Vec<&str> = batch.iter().map(|(_, doc, _)| *doc).collect();
 
                 // Acquire model, embed entire batch, release model
-                let mut instance = self.acquire();
-                let embeddings_result = instance.model.embed(texts.clone(), None);
+                let instance = self.acquire();
+                let embeddings_result = instance.model.embed(&texts);
                 self.release(instance);
 
                 // Process results
                 match embeddings_result {
                     Ok(embeddings) => {
+                        if let Some(cache) = &self.cache {
+                            cache.insert_batch(&texts, &embeddings);
+                        }
                         let mut results = Vec::with_capacity(batch.len());
                         for (item, embedding) in batch.iter().zip(embeddings.into_iter()) {
                             let (symbol_id, _, language) = *item;
@@ -262,6 +371,7 @@ impl EmbeddingPool {
         // Log usage stats after parallel embedding
         self.log_usage_stats();
 
+        results.extend(cached);
         results
     }
 }
@@ -0,0 +1,364 @@
+//! Quantized embeddings for large semantic indexes
+//!
+//! With `semantic_search.quantization` set, loaded embeddings stay in memory
+//! only as compact codes, and full-precision vectors are read from the
+//! segment files when needed:
+//!
+//! - `int8`: one signed byte per dimension with a per-vector scale, 4x smaller
+//! - `binary`: one sign bit per dimension, 32x smaller
+//!
+//! A search scores every code against the full-precision query, keeps the
+//! best `limit * rescore_factor` candidates and scores those again with
+//! their full vectors, so results match an unquantized search unless a
+//! relevant symbol falls out of the candidates. The file on disk does not
+//! change: an index can be loaded with or without quantization.
+
+use super::{SemanticSearchError, SemanticVectorStorage};
+use crate::SymbolId;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How embeddings are held in memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quantization {
+    #[default]
+    None,
+    Int8,
+    Binary,
+}
+
+impl Quantization {
+    /// Parse `semantic_search.quantization`
+    pub fn parse(name: &str) -> Result<Self, SemanticSearchError> {
+        match name {
+            "none" => Ok(Self::None),
+            "int8" => Ok(Self::Int8),
+            "binary" => Ok(Self::Binary),
+            other => Err(SemanticSearchError::ModelInitError(format!(
+                "Unknown quantization '{other}'. Expected one of: none, int8, binary"
+            ))),
+        }
+    }
+}
+
+/// Compact code standing in for an embedding
+#[derive(Debug)]
+enum Code {
+    /// `vector[i] ≈ codes[i] * scale`, with the norm of the original vector
+    Int8 {
+        codes: Box<[i8]>,
+        scale: f32,
+        norm: f32,
+    },
+    /// Sign bits, least significant first
+    Binary(Box<[u64]>),
+}
+
+impl Code {
+    fn encode(quantization: Quantization, vector: &[f32]) -> Self {
+        match quantization {
+            Quantization::Binary => Self::Binary(sign_bits(vector)),
+            Quantization::Int8 | Quantization::None => {
+                let max = vector.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+                let scale = if max == 0.0 { 1.0 } else { max / 127.0 };
+                let codes = vector
+                    .iter()
+                    .map(|x| (x / scale).round().clamp(-127.0, 127.0) as i8)
+                    .collect();
+                Self::Int8 {
+                    codes,
+                    scale,
+                    norm: norm(vector),
+                }
+            }
+        }
+    }
+
+    /// Approximate cosine similarity to `query`
+    fn similarity(&self, query: &Query) -> f32 {
+        match self {
+            Self::Int8 { codes, scale, norm } => {
+                if *norm == 0.0 || query.norm == 0.0 {
+                    return 0.0;
+                }
+                let dot: f32 = query
+                    .vector
+                    .iter()
+                    .zip(codes.iter())
+                    .map(|(q, c)| q * f32::from(*c))
+                    .sum();
+                dot * scale / (query.norm * norm)
+            }
+            Self::Binary(bits) => {
+                let differing: u32 = bits
+                    .iter()
+                    .zip(query.bits.iter())
+                    .map(|(a, b)| (a ^ b).count_ones())
+                    .sum();
+                let dimension = query.vector.len().max(1) as f32;
+                (std::f32::consts::PI * differing as f32 / dimension).cos()
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn size_bytes(&self) -> usize {
+        match self {
+            Self::Int8 { codes, .. } => codes.len(),
+            Self::Binary(bits) => bits.len() * 8,
+        }
+    }
+}
+
+/// A full-precision query with what codes compare against
+struct Query<'a> {
+    vector: &'a [f32],
+    norm: f32,
+    bits: Box<[u64]>,
+}
+
+impl<'a> Query<'a> {
+    fn new(vector: &'a [f32]) -> Self {
+        Self {
+            vector,
+            norm: norm(vector),
+            bits: sign_bits(vector),
+        }
+    }
+}
+
+/// Full-precision vectors, on disk unless added since the index was saved
+#[derive(Debug, Default)]
+pub(super) struct FullVectors {
+    /// Vectors added since the index was loaded or saved
+    pub(super) pending: HashMap<SymbolId, Vec<f32>>,
+    /// The saved vectors
+    pub(super) disk: Option<SemanticVectorStorage>,
+}
+
+impl FullVectors {
+    pub(super) fn get(&mut self, id: SymbolId) -> Option<Vec<f32>> {
+        if let Some(vector) = self.pending.get(&id) {
+            return Some(vector.clone());
+        }
+        self.disk.as_mut()?.load_embedding(id)
+    }
+}
+
+/// Embeddings held as quantized codes, full vectors on disk
+#[derive(Debug)]
+pub struct QuantizedSet {
+    quantization: Quantization,
+    rescore_factor: usize,
+    codes: HashMap<SymbolId, Code>,
+    full: Mutex<FullVectors>,
+}
+
+impl QuantizedSet {
+    pub fn new(quantization: Quantization, rescore_factor: usize) -> Self {
+        Self {
+            quantization,
+            rescore_factor: rescore_factor.max(1),
+            codes: HashMap::new(),
+            full: Mutex::new(FullVectors::default()),
+        }
+    }
+
+    /// Quantize every embedding of `storage`, keeping it open for rescoring
+    pub fn load(
+        mut storage: SemanticVectorStorage,
+        quantization: Quantization,
+        rescore_factor: usize,
+    ) -> Self {
+        let mut set = Self::new(quantization, rescore_factor);
+        storage.for_each(|id, vector| {
+            set.codes.insert(id, Code::encode(quantization, &vector));
+        });
+        set.full = Mutex::new(FullVectors {
+            pending: HashMap::new(),
+            disk: Some(storage),
+        });
+        set
+    }
+
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn contains(&self, id: SymbolId) -> bool {
+        self.codes.contains_key(&id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = SymbolId> + '_ {
+        self.codes.keys().copied()
+    }
+
+    pub fn insert(&mut self, id: SymbolId, vector: Vec<f32>) {
+        self.codes
+            .insert(id, Code::encode(self.quantization, &vector));
+        let full = self.full.get_mut().unwrap_or_else(|e| e.into_inner());
+        full.pending.insert(id, vector);
+    }
+
+    pub fn remove(&mut self, id: SymbolId) {
+        self.codes.remove(&id);
+        let full = self.full.get_mut().unwrap_or_else(|e| e.into_inner());
+        full.pending.remove(&id);
+    }
+
+    pub fn clear(&mut self) {
+        self.codes.clear();
+        *self.full.get_mut().unwrap_or_else(|e| e.into_inner()) = FullVectors::default();
+    }
+
+    /// Full-precision embedding of `id`
+    pub fn get(&self, id: SymbolId) -> Option<Vec<f32>> {
+        if !self.contains(id) {
+            return None;
+        }
+        self.full.lock().unwrap_or_else(|e| e.into_inner()).get(id)
+    }
+
+    /// The `limit` symbols most similar to `query` among those `keep`
+    /// accepts, best first, scored with their full vectors
+    pub fn search(
+        &self,
+        query: &[f32],
+        limit: usize,
+        keep: impl Fn(SymbolId) -> bool,
+    ) -> Vec<(SymbolId, f32)> {
+        let encoded = Query::new(query);
+        let mut candidates: Vec<(SymbolId, f32)> = self
+            .codes
+            .iter()
+            .filter(|(id, _)| keep(**id))
+            .map(|(id, code)| (*id, code.similarity(&encoded)))
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(limit.saturating_mul(self.rescore_factor));
+
+        let mut full = self.full.lock().unwrap_or_else(|e| e.into_inner());
+        for (id, score) in &mut candidates {
+            if let Some(vector) = full.get(*id) {
+                *score = cosine(query, &vector, encoded.norm);
+            }
+        }
+        drop(full);
+
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(limit);
+        candidates
+    }
+
+    /// Every full-precision embedding, to be written to another store
+    pub fn full_vectors(&self) -> Vec<(SymbolId, Vec<f32>)> {
+        let mut full = self.full.lock().unwrap_or_else(|e| e.into_inner());
+        self.codes
+            .keys()
+            .filter_map(|id| Some((*id, full.get(*id)?)))
+            .collect()
+    }
+
+    /// Full-precision embeddings added since the index was loaded or saved
+    pub fn pending(&self) -> Vec<(SymbolId, Vec<f32>)> {
+        let full = self.full.lock().unwrap_or_else(|e| e.into_inner());
+        full.pending
+            .iter()
+            .map(|(id, vector)| (*id, vector.clone()))
+            .collect()
+    }
+
+    /// Run `f` on the store full vectors are read from, if there is one
+    pub fn with_store<R>(&self, f: impl FnOnce(&mut SemanticVectorStorage) -> R) -> Option<R> {
+        let mut full = self.full.lock().unwrap_or_else(|e| e.into_inner());
+        full.disk.as_mut().map(f)
+    }
+
+    /// Pending embeddings were written to the store, or to `storage`, which
+    /// full vectors are read from now on
+    pub fn saved(&self, storage: Option<SemanticVectorStorage>) {
+        let mut full = self.full.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(storage) = storage {
+            full.disk = Some(storage);
+        }
+        full.pending.clear();
+    }
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    vector.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+fn cosine(query: &[f32], vector: &[f32], query_norm: f32) -> f32 {
+    let vector_norm = norm(vector);
+    if query_norm == 0.0 || vector_norm == 0.0 {
+        return 0.0;
+    }
+    let dot: f32 = query.iter().zip(vector).map(|(a, b)| a * b).sum();
+    dot / (query_norm * vector_norm)
+}
+
+fn sign_bits(vector: &[f32]) -> Box<[u64]> {
+    let mut bits = vec![0u64; vector.len().div_ceil(64)];
+    for (i, x) in vector.iter().enumerate() {
+        if *x > 0.0 {
+            bits[i / 64] |= 1 << (i % 64);
+        }
+    }
+    bits.into_boxed_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(seed: u32, dimension: usize) -> Vec<f32> {
+        (0..dimension)
+            .map(|i| (seed as f32 * 1.7 + i as f32 * 0.31).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_quantized_search_matches_full_precision() {
+        assert_eq!(Quantization::parse("int8").unwrap(), Quantization::Int8);
+        assert!(Quantization::parse("int4").is_err());
+
+        let dimension = 384;
+        let query = vector(7, dimension);
+        let full = vector(3, dimension);
+        let int8 = Code::encode(Quantization::Int8, &full);
+        let binary = Code::encode(Quantization::Binary, &full);
+        assert_eq!(dimension * 4 / int8.size_bytes(), 4);
+        assert_eq!(dimension * 4 / binary.size_bytes(), 32);
+
+        let exact = cosine(&query, &full, norm(&query));
+        let encoded = Query::new(&query);
+        assert!((int8.similarity(&encoded) - exact).abs() < 0.01);
+        assert!((binary.similarity(&encoded) - exact).abs() < 0.3);
+
+        for quantization in [Quantization::Int8, Quantization::Binary] {
+            let mut set = QuantizedSet::new(quantization, 4);
+            for seed in 1..=50 {
+                set.insert(SymbolId::new(seed).unwrap(), vector(seed, dimension));
+            }
+            let results = set.search(&vector(12, dimension), 3, |_| true);
+            assert_eq!(results[0].0, SymbolId::new(12).unwrap());
+            assert!((results[0].1 - 1.0).abs() < 1e-5);
+
+            let odd = set.search(&vector(12, dimension), 3, |id| id.value() % 2 == 1);
+            assert!(odd.iter().all(|(id, _)| id.value() % 2 == 1));
+
+            set.remove(SymbolId::new(12).unwrap());
+            assert_eq!(set.len(), 49);
+            assert!(set.get(SymbolId::new(12).unwrap()).is_none());
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Cross-encoder re-ranking of search results
+//!
+//! Embedding search compares a query and a document that were embedded
+//! apart. A cross-encoder reads both together and judges how well the
+//! document answers the query, which is slower but more precise. With
+//! `semantic_search.rerank_model` set, the best `rerank_candidates` hybrid
+//! results are scored again by such a model, run locally through ONNX, and
+//! returned in its order.
+
+use super::SemanticSearchError;
+use crate::Symbol;
+use fastembed::{RerankInitOptions, RerankerModel, TextRerank};
+use std::sync::Mutex;
+
+/// Cross-encoder models, by the name settings use
+pub const RERANKER_MODELS: &[&str] = &[
+    "JINARerankerV1TurboEn",
+    "BGERerankerBase",
+    "BGERerankerV2M3",
+    "JINARerankerV2BaseMultilingual",
+];
+
+/// Parse `semantic_search.rerank_model`
+pub fn parse_reranker_model(name: &str) -> Result<RerankerModel, SemanticSearchError> {
+    match name {
+        "JINARerankerV1TurboEn" => Ok(RerankerModel::JINARerankerV1TurboEn),
+        "BGERerankerBase" => Ok(RerankerModel::BGERerankerBase),
+        "BGERerankerV2M3" => Ok(RerankerModel::BGERerankerV2M3),
+        "JINARerankerV2BaseMultilingual" => Ok(RerankerModel::JINARerankerV2BaseMultiligual),
+        other => Err(SemanticSearchError::ModelInitError(format!(
+            "Unknown rerank model '{other}'. Expected one of: {}",
+            RERANKER_MODELS.join(", ")
+        ))),
+    }
+}
+
+/// A local cross-encoder scoring query and document pairs
+pub struct Reranker {
+    model: Mutex<TextRerank>,
+    name: String,
+}
+
+impl std::fmt::Debug for Reranker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reranker")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Reranker {
+    /// Load `model_name`, downloading it into the models directory on first use
+    pub fn new(model_name: &str, show_progress: bool) -> Result<Self, SemanticSearchError> {
+        let model = parse_reranker_model(model_name)?;
+        let options = RerankInitOptions::new(model)
+            .with_cache_dir(crate::init::models_dir())
+            .with_show_download_progress(show_progress);
+        let model = TextRerank::try_new(options).map_err(|e| {
+            SemanticSearchError::ModelInitError(format!(
+                "Failed to initialize rerank model '{model_name}': {e}"
+            ))
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+            name: model_name.to_string(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Relevance of each of `documents` to `query`, from 0 to 1, in order
+    pub fn score(
+        &self,
+        query: &str,
+        documents: &[String],
+    ) -> Result<Vec<f32>, SemanticSearchError> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+        let documents: Vec<&str> = documents.iter().map(String::as_str).collect();
+        let mut model = self.model.lock().unwrap_or_else(|e| e.into_inner());
+        let ranked = model
+            .rerank(query, documents.as_slice(), false, None)
+            .map_err(|e| SemanticSearchError::EmbeddingError(format!("Reranking failed: {e}")))?;
+
+        let mut scores = vec![0.0; documents.len()];
+        for result in ranked {
+            scores[result.index] = sigmoid(result.score);
+        }
+        Ok(scores)
+    }
+
+    /// Order the first `candidates` of `results` by relevance to `query`
+    ///
+    /// Results past the candidates keep their place and score after them.
+    pub fn rerank(
+        &self,
+        query: &str,
+        results: &mut [(Symbol, f32)],
+        candidates: usize,
+    ) -> Result<(), SemanticSearchError> {
+        let head = candidates.min(results.len());
+        let documents: Vec<String> = results[..head]
+            .iter()
+            .map(|(symbol, _)| document(symbol))
+            .collect();
+        let scores = self.score(query, &documents)?;
+
+        for ((_, score), relevance) in results.iter_mut().zip(scores) {
+            *score = relevance;
+        }
+        results[..head].sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(())
+    }
+}
+
+/// What the cross-encoder reads for a symbol
+fn document(symbol: &Symbol) -> String {
+    let mut text = format!("{:?} {}", symbol.kind, symbol.name);
+    if let Some(signature) = symbol.signature.as_deref() {
+        text.push('\n');
+        text.push_str(signature);
+    }
+    if let Some(doc) = symbol.doc_comment.as_deref() {
+        text.push('\n');
+        text.push_str(doc);
+    }
+    text
+}
+
+/// Cross-encoders return logits; map them to 0..1
+fn sigmoid(logit: f32) -> f32 {
+    1.0 / (1.0 + (-logit).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, Range, SymbolId, SymbolKind};
+
+    #[test]
+    fn test_reranker_models_and_documents() {
+        for name in RERANKER_MODELS {
+            assert!(parse_reranker_model(name).is_ok(), "{name}");
+        }
+        assert!(parse_reranker_model("AllMiniLML6V2").is_err());
+
+        let mut symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "parse_config",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(1, 0, 5, 0),
+        );
+        symbol.signature = Some("fn parse_config(path: &Path) -> Settings".into());
+        symbol.doc_comment = Some("Read settings from a TOML file".into());
+        assert_eq!(
+            document(&symbol),
+            "Function parse_config\nfn parse_config(path: &Path) -> Settings\n\
+             Read settings from a TOML file"
+        );
+
+        assert_eq!(sigmoid(0.0), 0.5);
+        assert!(sigmoid(8.0) > 0.99 && sigmoid(-8.0) < 0.01);
+    }
+}
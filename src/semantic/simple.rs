@@ -1,10 +1,15 @@
 //! Simple semantic search implementation for documentation comments
 
+use super::SemanticVectorStorage;
+use super::mapped::MappedSet;
+use super::quantize::{Quantization, QuantizedSet};
 use crate::SymbolId;
-use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
-use std::collections::HashMap;
-use std::path::Path;
-use std::sync::Mutex;
+use crate::config::SemanticSearchConfig;
+use crate::vector::{EmbeddingDevice, EmbeddingProvider, FastEmbedGenerator};
+use fastembed::EmbeddingModel;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 /// Error type for semantic search operations
 #[derive(Debug, thiserror::Error)]
@@ -40,19 +45,30 @@ pub enum SemanticSearchError {
 /// Updated: Final test - embedding cleanup working correctly!
 pub struct SimpleSemanticSearch {
     /// Embeddings indexed by symbol ID
-    embeddings: HashMap<SymbolId, Vec<f32>>,
+    embeddings: Embeddings,
 
     /// Language mapping for each symbol (for language-filtered search)
     symbol_languages: HashMap<SymbolId, String>,
 
-    /// The embedding model (wrapped in Mutex for interior mutability)
-    model: Mutex<TextEmbedding>,
+    /// Backend that embeds documentation and queries
+    provider: Arc<dyn EmbeddingProvider>,
 
     /// Model dimensions for validation
     dimensions: usize,
 
     /// Metadata for tracking model info and timestamps
     metadata: Option<crate::semantic::SemanticMetadata>,
+
+    /// Embeddings changed since the index was loaded or saved
+    changes: Mutex<Changes>,
+}
+
+/// Where the index was last loaded from or saved to, and what changed since
+#[derive(Debug, Default)]
+struct Changes {
+    saved_to: Option<PathBuf>,
+    upserted: HashSet<SymbolId>,
+    removed: HashSet<SymbolId>,
 }
 
 impl std::fmt::Debug for SimpleSemanticSearch {
@@ -60,7 +76,7 @@ impl std::fmt::Debug for SimpleSemanticSearch {
         f.debug_struct("SimpleSemanticSearch")
             .field("embeddings_count", &self.embeddings.len())
             .field("dimensions", &self.dimensions)
-            .field("model", &"<TextEmbedding>")
+            .field("model", &self.provider.model_id())
             .field("metadata", &self.metadata)
             .finish()
     }
@@ -98,6 +114,14 @@ impl SimpleSemanticSearch {
 
     /// Create with a specific model enum.
     pub fn with_model(model: EmbeddingModel) -> Result<Self, SemanticSearchError> {
+        Self::with_model_on(model, EmbeddingDevice::Auto)
+    }
+
+    /// Create with a specific model enum, run on `device`.
+    pub fn with_model_on(
+        model: EmbeddingModel,
+        device: EmbeddingDevice,
+    ) -> Result<Self, SemanticSearchError> {
         let cache_dir = crate::init::models_dir();
         let model_name = crate::vector::model_to_string(&model);
 
@@ -114,37 +138,92 @@ impl SimpleSemanticSearch {
             eprintln!("Downloading embedding model '{model_name}' (first time only)...");
         }
 
-        let mut text_model = TextEmbedding::try_new(
-            InitOptions::new(model)
-                .with_cache_dir(cache_dir)
-                .with_show_download_progress(true), // Always show progress, but with context from message above
-        )
-        .map_err(|e| {
+        let generator = FastEmbedGenerator::with_device(model, true, device).map_err(|e| {
             SemanticSearchError::ModelInitError(format!(
                 "Failed to initialize model '{model_name}': {e}"
             ))
         })?;
+        Ok(Self::with_provider(Arc::new(generator)))
+    }
 
-        // Get dimensions by generating a test embedding
-        let test_embedding = text_model
-            .embed(vec!["test"], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-        let dimensions = test_embedding.into_iter().next().unwrap().len();
+    /// Create a semantic search instance with the provider selected in settings.
+    pub fn from_config(config: &SemanticSearchConfig) -> Result<Self, SemanticSearchError> {
+        if config.provider == "local" {
+            let model = crate::vector::parse_embedding_model(&config.model).map_err(|e| {
+                SemanticSearchError::ModelInitError(format!("Invalid model name: {e}"))
+            })?;
+            let device = EmbeddingDevice::parse(&config.device)
+                .map_err(|e| SemanticSearchError::ModelInitError(e.to_string()))?;
+            let mut search = Self::with_model_on(model, device)?;
+            search.quantize(config)?;
+            return Ok(search);
+        }
+        let provider = crate::vector::create_provider(config, true)
+            .map_err(|e| SemanticSearchError::ModelInitError(e.to_string()))?;
+        let mut search = Self::with_provider(provider);
+        search.quantize(config)?;
+        Ok(search)
+    }
+
+    /// Hold embeddings as `semantic_search.quantization` says
+    fn quantize(&mut self, config: &SemanticSearchConfig) -> Result<(), SemanticSearchError> {
+        let quantization = Quantization::parse(&config.quantization)?;
+        if quantization == self.quantization() {
+            return Ok(());
+        }
+        let vectors = match std::mem::replace(&mut self.embeddings, Embeddings::empty()) {
+            Embeddings::Full(vectors) => vectors.into_iter().collect(),
+            embeddings => embeddings.full_vectors(),
+        };
+        self.embeddings = if quantization == Quantization::None {
+            Embeddings::Full(vectors.into_iter().collect())
+        } else {
+            let mut set = QuantizedSet::new(quantization, config.rescore_factor);
+            for (id, vector) in vectors {
+                set.insert(id, vector);
+            }
+            Embeddings::Quantized(set)
+        };
+        Ok(())
+    }
+
+    /// How embeddings are held in memory
+    pub fn quantization(&self) -> Quantization {
+        match &self.embeddings {
+            Embeddings::Full(_) | Embeddings::Mapped(_) => Quantization::None,
+            Embeddings::Quantized(set) => set.quantization(),
+        }
+    }
 
-        // Create initial metadata
+    /// Create an empty instance embedding with `provider`.
+    pub fn with_provider(provider: Arc<dyn EmbeddingProvider>) -> Self {
+        let dimensions = provider.dimensions();
         let metadata = crate::semantic::SemanticMetadata::new(
-            model_name.clone(),
+            provider.model_id().to_string(),
             dimensions,
             0, // No embeddings yet
         );
 
-        Ok(Self {
-            embeddings: HashMap::new(),
+        Self {
+            embeddings: Embeddings::empty(),
             symbol_languages: HashMap::new(),
-            model: Mutex::new(text_model),
+            provider,
             dimensions,
             metadata: Some(metadata),
-        })
+            changes: Mutex::default(),
+        }
+    }
+
+    /// The backend embedding documentation and queries
+    pub fn provider(&self) -> &Arc<dyn EmbeddingProvider> {
+        &self.provider
+    }
+
+    /// Embed one text with the provider
+    fn embed(&self, text: &str) -> Result<Vec<f32>, SemanticSearchError> {
+        self.provider
+            .embed_one(text)
+            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))
     }
 
     /// Index a documentation comment for a symbol
@@ -159,14 +238,7 @@ impl SimpleSemanticSearch {
         }
 
         // Generate embedding
-        let embeddings = self
-            .model
-            .lock()
-            .unwrap()
-            .embed(vec![doc], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-
-        let embedding = embeddings.into_iter().next().unwrap();
+        let embedding = self.embed(doc)?;
 
         // Validate dimensions
         if embedding.len() != self.dimensions {
@@ -177,7 +249,7 @@ impl SimpleSemanticSearch {
             )));
         }
 
-        self.embeddings.insert(symbol_id, embedding);
+        self.insert_embedding(symbol_id, embedding);
         Ok(())
     }
 
@@ -192,7 +264,7 @@ impl SimpleSemanticSearch {
         self.index_doc_comment(symbol_id, doc)?;
 
         // Then store the language mapping
-        if self.embeddings.contains_key(&symbol_id) {
+        if self.embeddings.contains(symbol_id) {
             self.symbol_languages
                 .insert(symbol_id, language.to_string());
         }
@@ -203,11 +275,13 @@ impl SimpleSemanticSearch {
     /// Store pre-generated embeddings (from parallel pool generation).
     ///
     /// Used when embeddings are generated in parallel by EmbeddingPool.
+    /// Chunks of one symbol are combined, see
+    /// [`merge_chunk_embeddings`](super::chunking::merge_chunk_embeddings).
     pub fn store_embeddings(&mut self, items: Vec<(SymbolId, Vec<f32>, String)>) -> usize {
         let mut count = 0;
-        for (symbol_id, embedding, language) in items {
+        for (symbol_id, embedding, language) in super::chunking::merge_chunk_embeddings(items) {
             if embedding.len() == self.dimensions {
-                self.embeddings.insert(symbol_id, embedding);
+                self.insert_embedding(symbol_id, embedding);
                 self.symbol_languages.insert(symbol_id, language);
                 count += 1;
             }
@@ -215,6 +289,13 @@ impl SimpleSemanticSearch {
         count
     }
 
+    fn insert_embedding(&mut self, symbol_id: SymbolId, embedding: Vec<f32>) {
+        self.embeddings.insert(symbol_id, embedding);
+        let changes = self.changes.get_mut().unwrap_or_else(|e| e.into_inner());
+        changes.removed.remove(&symbol_id);
+        changes.upserted.insert(symbol_id);
+    }
+
     /// Search for similar documentation using a natural language query
     ///
     /// Returns symbol IDs with their similarity scores, sorted by score descending
@@ -232,30 +313,9 @@ impl SimpleSemanticSearch {
         }
 
         // Generate query embedding
-        let query_embeddings = self
-            .model
-            .lock()
-            .unwrap()
-            .embed(vec![query], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-        let query_embedding = query_embeddings.into_iter().next().unwrap();
-
-        // Calculate similarities
-        let mut similarities: Vec<(SymbolId, f32)> = self
-            .embeddings
-            .iter()
-            .map(|(id, embedding)| {
-                let similarity = cosine_similarity(&query_embedding, embedding);
-                (*id, similarity)
-            })
-            .collect();
+        let query_embedding = self.embed(query)?;
 
-        // Sort by similarity descending
-        similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-        // Return top results
-        similarities.truncate(limit);
-        Ok(similarities)
+        Ok(self.search_by_embedding(&query_embedding, limit, None))
     }
 
     /// Search for similar documentation with language filtering
@@ -273,33 +333,89 @@ impl SimpleSemanticSearch {
         }
 
         // Generate query embedding
-        let query_embeddings = self
-            .model
-            .lock()
-            .unwrap()
-            .embed(vec![query], None)
-            .map_err(|e| SemanticSearchError::EmbeddingError(e.to_string()))?;
-        let query_embedding = query_embeddings.into_iter().next().unwrap();
-
-        // Filter embeddings by language BEFORE computing similarity
-        let filtered_embeddings: Vec<(&SymbolId, &Vec<f32>)> = if let Some(lang) = language {
-            self.embeddings
+        let query_embedding = self.embed(query)?;
+
+        Ok(self.search_by_embedding(&query_embedding, limit, language))
+    }
+
+    /// Search among the symbols of `scope` in `language`, either filter
+    /// skipped when `None`
+    ///
+    /// Symbols outside `scope` are never scored, so a narrow scope such as
+    /// one directory of a monorepo is fast and still returns `limit` hits
+    /// when it has that many.
+    pub fn search_in_scope(
+        &self,
+        query: &str,
+        limit: usize,
+        language: Option<&str>,
+        scope: Option<&HashSet<SymbolId>>,
+    ) -> Result<Vec<(SymbolId, f32)>, SemanticSearchError> {
+        if self.embeddings.is_empty() {
+            return Err(SemanticSearchError::NoEmbeddings);
+        }
+
+        let query_embedding = self.embed(query)?;
+
+        Ok(self.search_by_embedding_in_scope(&query_embedding, limit, language, scope))
+    }
+
+    /// Symbols whose embeddings are closest to `query_embedding`, best first
+    ///
+    /// Like [`search_with_language`](Self::search_with_language), for a
+    /// vector already at hand, such as another symbol's embedding.
+    pub fn search_by_embedding(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        language: Option<&str>,
+    ) -> Vec<(SymbolId, f32)> {
+        self.search_by_embedding_in_scope(query_embedding, limit, language, None)
+    }
+
+    /// [`search_in_scope`](Self::search_in_scope) for a vector already at hand
+    pub fn search_by_embedding_in_scope(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        language: Option<&str>,
+        scope: Option<&HashSet<SymbolId>>,
+    ) -> Vec<(SymbolId, f32)> {
+        let in_language = |id: &SymbolId| {
+            language.is_none_or(|lang| {
+                self.symbol_languages
+                    .get(id)
+                    .is_some_and(|symbol_lang| symbol_lang == lang)
+            })
+        };
+        let in_scope =
+            |id: &SymbolId| scope.is_none_or(|scope| scope.contains(id)) && in_language(id);
+        let embeddings = match &self.embeddings {
+            Embeddings::Full(embeddings) => embeddings,
+            Embeddings::Quantized(set) => {
+                return set.search(query_embedding, limit, |id| in_scope(&id));
+            }
+            Embeddings::Mapped(set) => {
+                return set.search(query_embedding, limit, |id| in_scope(&id));
+            }
+        };
+
+        // Filter embeddings BEFORE computing similarity, walking the scope
+        // instead of every embedding when it is the smaller
+        let filtered_embeddings: Vec<(&SymbolId, &Vec<f32>)> = match scope {
+            Some(scope) if scope.len() < embeddings.len() => scope
                 .iter()
-                .filter(|(id, _)| {
-                    self.symbol_languages
-                        .get(id)
-                        .is_some_and(|symbol_lang| symbol_lang == lang)
-                })
-                .collect()
-        } else {
-            self.embeddings.iter().collect()
+                .filter(|id| in_language(id))
+                .filter_map(|id| embeddings.get_key_value(id))
+                .collect(),
+            _ => embeddings.iter().filter(|(id, _)| in_scope(id)).collect(),
         };
 
         // Calculate similarities only for filtered embeddings
         let mut similarities: Vec<(SymbolId, f32)> = filtered_embeddings
             .into_iter()
             .map(|(id, embedding)| {
-                let similarity = cosine_similarity(&query_embedding, embedding);
+                let similarity = cosine_similarity(query_embedding, embedding);
                 (*id, similarity)
             })
             .collect();
@@ -309,7 +425,16 @@ impl SimpleSemanticSearch {
 
         // Return top results
         similarities.truncate(limit);
-        Ok(similarities)
+        similarities
+    }
+
+    /// Stored embedding of a symbol, if it has one, at full precision
+    pub fn embedding_of(&self, symbol_id: SymbolId) -> Option<Vec<f32>> {
+        match &self.embeddings {
+            Embeddings::Full(embeddings) => embeddings.get(&symbol_id).cloned(),
+            Embeddings::Quantized(set) => set.get(symbol_id),
+            Embeddings::Mapped(set) => set.get(symbol_id),
+        }
     }
 
     /// Search with a similarity threshold
@@ -331,10 +456,20 @@ impl SimpleSemanticSearch {
         self.embeddings.len()
     }
 
+    /// Symbols that have an embedding
+    pub fn symbol_ids(&self) -> Vec<SymbolId> {
+        match &self.embeddings {
+            Embeddings::Full(embeddings) => embeddings.keys().copied().collect(),
+            Embeddings::Quantized(set) => set.ids().collect(),
+            Embeddings::Mapped(set) => set.ids().collect(),
+        }
+    }
+
     /// Clear all embeddings
     pub fn clear(&mut self) {
         self.embeddings.clear();
         self.symbol_languages.clear();
+        *self.changes.get_mut().unwrap_or_else(|e| e.into_inner()) = Changes::default();
     }
 
     /// Remove embeddings for specific symbols
@@ -342,9 +477,12 @@ impl SimpleSemanticSearch {
     /// This is used when re-indexing files to remove embeddings for symbols
     /// that no longer exist.
     pub fn remove_embeddings(&mut self, symbol_ids: &[SymbolId]) {
+        let changes = self.changes.get_mut().unwrap_or_else(|e| e.into_inner());
         for id in symbol_ids {
-            self.embeddings.remove(id);
+            self.embeddings.remove(*id);
             self.symbol_languages.remove(id);
+            changes.upserted.remove(id);
+            changes.removed.insert(*id);
         }
     }
 
@@ -355,10 +493,15 @@ impl SimpleSemanticSearch {
 
     /// Save embeddings to disk using the efficient vector storage
     ///
+    /// Saving to where the index was loaded from or last saved to appends
+    /// the changed embeddings and marks removed ones deleted, compacting the
+    /// store once enough of it is deleted. Anywhere else, the store is
+    /// written anew.
+    ///
     /// # Arguments
     /// * `path` - Path where semantic data should be stored
     pub fn save(&self, path: &Path) -> Result<(), SemanticSearchError> {
-        use crate::semantic::{SemanticMetadata, SemanticVectorStorage};
+        use crate::semantic::SemanticMetadata;
         use crate::vector::VectorDimension;
 
         // Ensure the directory exists
@@ -375,6 +518,25 @@ impl SimpleSemanticSearch {
             "AllMiniLML6V2".to_string()
         };
 
+        // Another process may have re-embedded the index with a new model
+        // since it was loaded; saving would mix the models' vectors
+        let mut changes = self.changes.lock().unwrap_or_else(|e| e.into_inner());
+        if changes.saved_to.as_deref() == Some(path) {
+            if let Ok(saved) = SemanticMetadata::load(path) {
+                if saved.model_name != model_name {
+                    return Err(SemanticSearchError::StorageError {
+                        message: format!(
+                            "{} now holds embeddings of '{}', not '{model_name}'",
+                            path.display(),
+                            saved.model_name
+                        ),
+                        suggestion: "Reload the index to search the migrated embeddings"
+                            .to_string(),
+                    });
+                }
+            }
+        }
+
         let metadata = SemanticMetadata::new(model_name, self.dimensions, self.embeddings.len());
         metadata.save(path)?;
 
@@ -386,17 +548,17 @@ impl SimpleSemanticSearch {
             }
         })?;
 
-        let mut storage = SemanticVectorStorage::new(path, dimension)?;
-
-        // Convert HashMap to Vec for batch save
-        let embeddings: Vec<(SymbolId, Vec<f32>)> = self
-            .embeddings
-            .iter()
-            .map(|(id, embedding)| (*id, embedding.clone()))
-            .collect();
-
-        // Save all embeddings
-        storage.save_batch(&embeddings)?;
+        let updated = changes.saved_to.as_deref() == Some(path)
+            && SemanticVectorStorage::exists_at(path)
+            && self.save_changes(path, &changes)?;
+        if !updated {
+            self.rewrite(path, dimension)?;
+        }
+        *changes = Changes {
+            saved_to: Some(path.to_path_buf()),
+            ..Changes::default()
+        };
+        drop(changes);
 
         // Save language mappings as a JSON file (convert SymbolId to u32 for serialization)
         let languages_path = path.join("languages.json");
@@ -421,27 +583,97 @@ impl SimpleSemanticSearch {
         Ok(())
     }
 
+    /// Write the embeddings changed since the last save into the store at
+    /// `path`. False if the store then disagrees with the index, which is
+    /// then written anew.
+    fn save_changes(&self, path: &Path, changes: &Changes) -> Result<bool, SemanticSearchError> {
+        let upserted: Vec<(SymbolId, Vec<f32>)> = match &self.embeddings {
+            Embeddings::Full(embeddings) => changes
+                .upserted
+                .iter()
+                .filter_map(|id| Some((*id, embeddings.get(id)?.clone())))
+                .collect(),
+            Embeddings::Quantized(set) => set.pending(),
+            Embeddings::Mapped(set) => set.pending(),
+        };
+        let removed: Vec<SymbolId> = changes.removed.iter().copied().collect();
+        let expected = self.embeddings.len();
+        let update = |storage: &mut SemanticVectorStorage| -> Result<bool, SemanticSearchError> {
+            storage.remove(&removed)?;
+            storage.save_batch(&upserted)?;
+            if storage.needs_compaction() {
+                storage.compact()?;
+            }
+            Ok(storage.embedding_count() == expected)
+        };
+
+        let updated = match &self.embeddings {
+            Embeddings::Full(_) => update(&mut SemanticVectorStorage::open(path)?)?,
+            Embeddings::Quantized(set) => set.with_store(update).transpose()?.unwrap_or(false),
+            Embeddings::Mapped(set) => set.with_store(update).transpose()?.unwrap_or(false),
+        };
+        if updated {
+            match &self.embeddings {
+                Embeddings::Full(_) => {}
+                Embeddings::Quantized(set) => set.saved(None),
+                Embeddings::Mapped(set) => set.saved(None),
+            }
+        }
+        Ok(updated)
+    }
+
+    /// Write every embedding to a new store at `path`
+    fn rewrite(
+        &self,
+        path: &Path,
+        dimension: crate::vector::VectorDimension,
+    ) -> Result<(), SemanticSearchError> {
+        // Read quantized or mapped embeddings back before their files are
+        // replaced
+        let embeddings = self.embeddings.full_vectors();
+        let mut storage = SemanticVectorStorage::new(path, dimension)?;
+        storage.save_batch(&embeddings)?;
+        match &self.embeddings {
+            Embeddings::Full(_) => {}
+            Embeddings::Quantized(set) => set.saved(Some(storage)),
+            Embeddings::Mapped(set) => set.saved(Some(storage)),
+        }
+        Ok(())
+    }
+
     /// Load embeddings from disk.
     ///
-    /// Automatically uses the model specified in the metadata.
+    /// Automatically uses the model specified in the metadata, with default
+    /// settings for remote providers.
     ///
     /// # Arguments
     /// * `path` - Path where semantic data is stored
     pub fn load(path: &Path) -> Result<Self, SemanticSearchError> {
-        use crate::semantic::{SemanticMetadata, SemanticVectorStorage};
+        Self::load_with_config(path, &SemanticSearchConfig::default())
+    }
+
+    /// Load embeddings from disk, reaching the model that built them with the
+    /// endpoint, credentials and batch size of `config`.
+    pub fn load_with_config(
+        path: &Path,
+        config: &SemanticSearchConfig,
+    ) -> Result<Self, SemanticSearchError> {
+        use crate::semantic::SemanticMetadata;
 
         // Load metadata first
         let metadata = SemanticMetadata::load(path)?;
 
-        // Parse model name from metadata
-        let model = crate::vector::parse_embedding_model(&metadata.model_name)
-            .map_err(|e| SemanticSearchError::StorageError {
-                message: format!("Invalid model in metadata: {e}"),
-                suggestion: format!(
-                    "The index was created with model '{}' which is not supported. Consider re-indexing with a supported model.",
-                    metadata.model_name
-                ),
-            })?;
+        // Validate a local model name from metadata before loading anything
+        if crate::vector::is_local_model_id(&metadata.model_name) {
+            crate::vector::parse_embedding_model(&metadata.model_name)
+                .map_err(|e| SemanticSearchError::StorageError {
+                    message: format!("Invalid model in metadata: {e}"),
+                    suggestion: format!(
+                        "The index was created with model '{}' which is not supported. Consider re-indexing with a supported model.",
+                        metadata.model_name
+                    ),
+                })?;
+        }
 
         // Open existing storage
         let mut storage = SemanticVectorStorage::open(path)?;
@@ -452,42 +684,54 @@ impl SimpleSemanticSearch {
                 expected: metadata.dimension,
                 actual: storage.dimension().get(),
                 suggestion: format!(
-                    "Index was created with a {}-dimension model. Re-index with: codanna index <path> --force",
+                    "Index was created with a {}-dimension model. Re-embed with: codanna semantic migrate",
                     storage.dimension().get()
                 ),
             });
         }
 
-        // Load all embeddings
-        let embeddings_vec = storage.load_all()?;
+        // Load all embeddings, quantized or left on disk if configured
+        let quantization = Quantization::parse(&config.quantization)?;
+        let embeddings = if quantization != Quantization::None {
+            Embeddings::Quantized(QuantizedSet::load(
+                storage,
+                quantization,
+                config.rescore_factor,
+            ))
+        } else if config.mmap_vectors {
+            Embeddings::Mapped(MappedSet::load(storage))
+        } else {
+            Embeddings::Full(storage.load_all()?.into_iter().collect())
+        };
 
         // Verify count matches metadata
-        if embeddings_vec.len() != metadata.embedding_count {
+        if embeddings.len() != metadata.embedding_count {
             eprintln!(
                 "WARNING: Expected {} embeddings but found {}",
                 metadata.embedding_count,
-                embeddings_vec.len()
+                embeddings.len()
             );
         }
 
-        // Convert to HashMap
-        let mut embeddings = HashMap::with_capacity(embeddings_vec.len());
-        for (id, embedding) in embeddings_vec {
-            embeddings.insert(id, embedding);
-        }
-
         // Create new instance with model from metadata
-        let text_model = TextEmbedding::try_new(
-            InitOptions::new(model)
-                .with_cache_dir(crate::init::models_dir())
-                .with_show_download_progress(false),
-        )
-        .map_err(|e| {
-            SemanticSearchError::ModelInitError(format!(
-                "Failed to load model '{}': {}",
-                metadata.model_name, e
-            ))
-        })?;
+        let provider = crate::vector::provider_for_model_id(&metadata.model_name, config, false)
+            .map_err(|e| {
+                SemanticSearchError::ModelInitError(format!(
+                    "Failed to load model '{}': {}",
+                    metadata.model_name, e
+                ))
+            })?;
+        if provider.dimensions() != metadata.dimension {
+            return Err(SemanticSearchError::DimensionMismatch {
+                expected: metadata.dimension,
+                actual: provider.dimensions(),
+                suggestion: format!(
+                    "'{}' now returns {}-dimension vectors. Re-embed with: codanna semantic migrate",
+                    metadata.model_name,
+                    provider.dimensions()
+                ),
+            });
+        }
 
         // Load language mappings if they exist
         let languages_path = path.join("languages.json");
@@ -515,13 +759,95 @@ impl SimpleSemanticSearch {
         Ok(Self {
             embeddings,
             symbol_languages,
-            model: Mutex::new(text_model),
+            provider,
             dimensions: metadata.dimension,
             metadata: Some(metadata),
+            changes: Mutex::new(Changes {
+                saved_to: Some(path.to_path_buf()),
+                ..Changes::default()
+            }),
         })
     }
 }
 
+/// Embeddings at full precision, as quantized codes, or searched on disk
+#[derive(Debug)]
+enum Embeddings {
+    Full(HashMap<SymbolId, Vec<f32>>),
+    Quantized(QuantizedSet),
+    Mapped(MappedSet),
+}
+
+impl Embeddings {
+    fn empty() -> Self {
+        Self::Full(HashMap::new())
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Full(embeddings) => embeddings.len(),
+            Self::Quantized(set) => set.len(),
+            Self::Mapped(set) => set.len(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Full(embeddings) => embeddings.is_empty(),
+            Self::Quantized(set) => set.is_empty(),
+            Self::Mapped(set) => set.is_empty(),
+        }
+    }
+
+    fn contains(&self, id: SymbolId) -> bool {
+        match self {
+            Self::Full(embeddings) => embeddings.contains_key(&id),
+            Self::Quantized(set) => set.contains(id),
+            Self::Mapped(set) => set.contains(id),
+        }
+    }
+
+    fn insert(&mut self, id: SymbolId, embedding: Vec<f32>) {
+        match self {
+            Self::Full(embeddings) => {
+                embeddings.insert(id, embedding);
+            }
+            Self::Quantized(set) => set.insert(id, embedding),
+            Self::Mapped(set) => set.insert(id, embedding),
+        }
+    }
+
+    fn remove(&mut self, id: SymbolId) {
+        match self {
+            Self::Full(embeddings) => {
+                embeddings.remove(&id);
+            }
+            Self::Quantized(set) => set.remove(id),
+            Self::Mapped(set) => set.remove(id),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Self::Full(embeddings) => embeddings.clear(),
+            Self::Quantized(set) => set.clear(),
+            Self::Mapped(set) => set.clear(),
+        }
+    }
+
+    /// Every embedding at full precision
+    fn full_vectors(&self) -> Vec<(SymbolId, Vec<f32>)> {
+        match self {
+            Self::Full(embeddings) => embeddings
+                .iter()
+                .map(|(id, embedding)| (*id, embedding.clone()))
+                .collect(),
+            Self::Quantized(set) => set.full_vectors(),
+            Self::Mapped(set) => set.full_vectors(),
+        }
+    }
+}
+
 /// Calculate cosine similarity between two vectors
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
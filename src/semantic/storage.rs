@@ -1,49 +1,122 @@
 //! Storage backend for semantic search embeddings using memory-mapped files.
 //!
-//! This module provides efficient persistence for semantic embeddings by leveraging
-//! the existing MmapVectorStorage infrastructure, achieving <1μs access times.
+//! Embeddings live in append-only segments, `segment_<n>.vec` files in the
+//! MmapVectorStorage format, listed in order by `segments.json`. Saving
+//! appends new and changed embeddings instead of rewriting the store: the
+//! vector a changed or removed symbol had stays in its segment, marked in
+//! the deletion bitmap `segment_<n>.del`. Once deleted vectors make up
+//! [`COMPACT_DELETED_RATIO`] of the store, or there are more than
+//! [`MAX_SEGMENTS`] segments, [`compact`](SemanticVectorStorage::compact)
+//! rewrites the live vectors into one segment.
+//!
+//! Vectors are read through the memory map, so a store can be searched
+//! without loading it into RAM. A store written before segments existed,
+//! a lone `segment_0.vec`, opens as a single segment.
 
 use crate::vector::{MmapVectorStorage, SegmentOrdinal, VectorDimension, VectorId};
 use crate::{SymbolId, semantic::SemanticSearchError};
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Lists the segments of a store, in write order
+const MANIFEST_FILE: &str = "segments.json";
+
+/// Vectors appended to a segment before a new one is started
+pub const SEGMENT_CAPACITY: usize = 100_000;
+
+/// Segments a store may have before it is compacted
+pub const MAX_SEGMENTS: usize = 8;
+
+/// Share of deleted vectors at which a store is compacted
+pub const COMPACT_DELETED_RATIO: f32 = 0.25;
+
+/// Vectors copied per write while compacting
+const COMPACT_BATCH: usize = 4096;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    dimension: usize,
+    segments: Vec<u32>,
+}
+
+/// One segment file and its deletion bitmap
+#[derive(Debug)]
+struct Segment {
+    ordinal: u32,
+    vectors: MmapVectorStorage,
+    /// One bit per position, set for vectors removed or replaced since
+    deleted: Vec<u64>,
+    /// Bitmap changed since it was written
+    dirty: bool,
+}
+
+impl Segment {
+    fn is_deleted(&self, position: usize) -> bool {
+        self.deleted
+            .get(position / 64)
+            .is_some_and(|word| word & (1 << (position % 64)) != 0)
+    }
+
+    fn delete(&mut self, position: usize) {
+        let word = position / 64;
+        if self.deleted.len() <= word {
+            self.deleted.resize(word + 1, 0);
+        }
+        self.deleted[word] |= 1 << (position % 64);
+        self.dirty = true;
+    }
 
-/// Wrapper around MmapVectorStorage specifically for semantic embeddings.
+    fn deleted_count(&self) -> usize {
+        self.deleted
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+/// Segmented, memory-mapped store of semantic embeddings.
 ///
 /// Provides a semantic-search-specific API while reusing the efficient
 /// vector storage infrastructure.
 #[derive(Debug)]
 pub struct SemanticVectorStorage {
-    storage: MmapVectorStorage,
+    path: PathBuf,
     dimension: VectorDimension,
+    segments: Vec<Segment>,
+    /// Segment index and position of each live vector
+    locations: HashMap<SymbolId, (usize, usize)>,
 }
 
 impl SemanticVectorStorage {
-    /// Creates a new semantic vector storage.
+    /// Creates a new, empty semantic vector storage, replacing any at `path`.
     ///
     /// # Arguments
     /// * `path` - Base path for storage files
     /// * `dimension` - Dimension of embeddings (must match model output)
     pub fn new(path: &Path, dimension: VectorDimension) -> Result<Self, SemanticSearchError> {
-        // First, remove any existing storage file to ensure clean state
-        let storage_path = path.join("segment_0.vec");
-        if storage_path.exists() {
-            std::fs::remove_file(&storage_path).map_err(|e| SemanticSearchError::StorageError {
-                message: format!("Failed to remove old storage: {e}"),
-                suggestion: "Check file permissions".to_string(),
-            })?;
-        }
-
-        // Use segment 0 for semantic embeddings
-        let storage =
-            MmapVectorStorage::new(path, SegmentOrdinal::new(0), dimension).map_err(|e| {
-                SemanticSearchError::StorageError {
-                    message: format!("Failed to create storage: {e}"),
-                    suggestion: "Ensure the directory exists and you have write permissions"
-                        .to_string(),
-                }
-            })?;
+        std::fs::create_dir_all(path).map_err(|e| {
+            storage_error(
+                format!("Failed to create storage directory: {e}"),
+                "Ensure you have write permissions",
+            )
+        })?;
+        // Remove any existing segments to ensure clean state
+        remove_segment_files(path, |_| true).map_err(|e| {
+            storage_error(
+                format!("Failed to remove old storage: {e}"),
+                "Check file permissions",
+            )
+        })?;
 
-        Ok(Self { storage, dimension })
+        let storage = Self {
+            path: path.to_path_buf(),
+            dimension,
+            segments: Vec::new(),
+            locations: HashMap::new(),
+        };
+        storage.write_manifest()?;
+        Ok(storage)
     }
 
     /// Opens existing semantic vector storage.
@@ -51,16 +124,94 @@ impl SemanticVectorStorage {
     /// # Arguments
     /// * `path` - Base path where storage files exist
     pub fn open(path: &Path) -> Result<Self, SemanticSearchError> {
-        let storage = MmapVectorStorage::open(path, SegmentOrdinal::new(0)).map_err(|e| {
-            SemanticSearchError::StorageError {
-                message: format!("Failed to open storage: {e}"),
-                suggestion: "Check if semantic search data exists at the specified path"
-                    .to_string(),
+        let manifest_path = path.join(MANIFEST_FILE);
+        let manifest = if manifest_path.exists() {
+            let json = std::fs::read_to_string(&manifest_path).map_err(|e| {
+                storage_error(
+                    format!("Failed to read {MANIFEST_FILE}: {e}"),
+                    "Check file permissions",
+                )
+            })?;
+            serde_json::from_str::<Manifest>(&json).map_err(|e| {
+                storage_error(
+                    format!("Failed to parse {MANIFEST_FILE}: {e}"),
+                    "Try rebuilding the semantic index",
+                )
+            })?
+        } else {
+            // Written before segments existed
+            let legacy = open_segment(path, 0)?;
+            Manifest {
+                dimension: legacy.dimension().get(),
+                segments: vec![0],
             }
+        };
+        let dimension = VectorDimension::new(manifest.dimension).map_err(|e| {
+            storage_error(
+                format!("Invalid dimension in {MANIFEST_FILE}: {e}"),
+                "Try rebuilding the semantic index",
+            )
         })?;
 
-        let dimension = storage.dimension();
-        Ok(Self { storage, dimension })
+        let mut storage = Self {
+            path: path.to_path_buf(),
+            dimension,
+            segments: Vec::with_capacity(manifest.segments.len()),
+            locations: HashMap::new(),
+        };
+        for ordinal in manifest.segments {
+            let mut vectors = open_segment(path, ordinal)?;
+            if vectors.dimension() != dimension {
+                return Err(SemanticSearchError::DimensionMismatch {
+                    expected: dimension.get(),
+                    actual: vectors.dimension().get(),
+                    suggestion: "Re-index with: codanna index <path> --force".to_string(),
+                });
+            }
+            let ids = vectors.read_ids().map_err(|e| {
+                storage_error(
+                    format!("Failed to read segment {ordinal}: {e}"),
+                    "The storage file may be corrupted. Try rebuilding the semantic index.",
+                )
+            })?;
+            let deleted = std::fs::read(deletion_path(path, ordinal))
+                .map(|bytes| {
+                    bytes
+                        .chunks_exact(8)
+                        .map(|word| u64::from_le_bytes(word.try_into().expect("8 bytes")))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let index = storage.segments.len();
+            storage.segments.push(Segment {
+                ordinal,
+                vectors,
+                deleted,
+                dirty: false,
+            });
+            for (position, id) in ids.into_iter().enumerate() {
+                if storage.segments[index].is_deleted(position) {
+                    continue;
+                }
+                let Some(id) = SymbolId::new(id.get()) else {
+                    continue;
+                };
+                // A later copy replaces an earlier one
+                if let Some((segment, old)) = storage.locations.insert(id, (index, position)) {
+                    storage.segments[segment].delete(old);
+                }
+            }
+        }
+
+        if manifest_path.exists() {
+            // Left over from an interrupted compaction
+            let live: Vec<u32> = storage.segments.iter().map(|s| s.ordinal).collect();
+            if let Err(e) = remove_segment_files(path, |ordinal| !live.contains(&ordinal)) {
+                tracing::debug!(target: "semantic", "failed to remove stale segments: {e}");
+            }
+        }
+        Ok(storage)
     }
 
     /// Opens existing storage or creates new if doesn't exist.
@@ -68,13 +219,16 @@ impl SemanticVectorStorage {
         path: &Path,
         dimension: VectorDimension,
     ) -> Result<Self, SemanticSearchError> {
-        let storage = MmapVectorStorage::open_or_create(path, SegmentOrdinal::new(0), dimension)
-            .map_err(|e| SemanticSearchError::StorageError {
-                message: format!("Failed to open or create storage: {e}"),
-                suggestion: "Check path permissions and disk space".to_string(),
-            })?;
+        if Self::exists_at(path) {
+            Self::open(path)
+        } else {
+            Self::new(path, dimension)
+        }
+    }
 
-        Ok(Self { storage, dimension })
+    /// Whether a store was saved at `path`
+    pub fn exists_at(path: &Path) -> bool {
+        path.join(MANIFEST_FILE).exists() || path.join("segment_0.vec").exists()
     }
 
     /// Saves a single embedding.
@@ -87,66 +241,58 @@ impl SemanticVectorStorage {
         id: SymbolId,
         embedding: &[f32],
     ) -> Result<(), SemanticSearchError> {
-        // Validate dimension
-        if embedding.len() != self.dimension.get() {
-            return Err(SemanticSearchError::DimensionMismatch {
-                expected: self.dimension.get(),
-                actual: embedding.len(),
-                suggestion: "Ensure all embeddings are generated with the same model".to_string(),
-            });
-        }
-
-        // Convert SymbolId to VectorId (both are u32 internally)
-        let vector_id =
-            VectorId::new(id.to_u32()).ok_or_else(|| SemanticSearchError::InvalidId {
-                id: id.to_u32(),
-                suggestion: "Symbol ID must be non-zero".to_string(),
-            })?;
-
-        // Save using batch API for efficiency
-        self.storage
-            .write_batch(&[(vector_id, embedding)])
-            .map_err(|e| SemanticSearchError::StorageError {
-                message: format!("Failed to save embedding: {e}"),
-                suggestion: "Check disk space and file permissions".to_string(),
-            })
+        self.save_batch(&[(id, embedding.to_vec())])
     }
 
     /// Loads a single embedding by ID.
     ///
     /// Returns None if the embedding doesn't exist.
     pub fn load_embedding(&mut self, id: SymbolId) -> Option<Vec<f32>> {
-        let vector_id = VectorId::new(id.to_u32())?;
-        self.storage.read_vector(vector_id)
+        let (segment, position) = *self.locations.get(&id)?;
+        let (_, embedding) = self.segments[segment].vectors.read_vector_at(position)?;
+        Some(embedding)
+    }
+
+    /// Whether `id` has an embedding
+    pub fn contains(&self, id: SymbolId) -> bool {
+        self.locations.contains_key(&id)
+    }
+
+    /// Symbols with an embedding, in no particular order
+    pub fn ids(&self) -> impl Iterator<Item = SymbolId> + '_ {
+        self.locations.keys().copied()
+    }
+
+    /// Calls `f` with every live embedding, reading them through the memory map
+    pub fn for_each(&mut self, mut f: impl FnMut(SymbolId, Vec<f32>)) {
+        for segment in &mut self.segments {
+            for position in 0..segment.vectors.vector_count() {
+                if segment.is_deleted(position) {
+                    continue;
+                }
+                let Some((id, embedding)) = segment.vectors.read_vector_at(position) else {
+                    break;
+                };
+                if let Some(id) = SymbolId::new(id.get()) {
+                    f(id, embedding);
+                }
+            }
+        }
     }
 
     /// Loads all embeddings from storage.
     ///
     /// Returns a vector of (SymbolId, embedding) pairs.
     pub fn load_all(&mut self) -> Result<Vec<(SymbolId, Vec<f32>)>, SemanticSearchError> {
-        let vectors =
-            self.storage
-                .read_all_vectors()
-                .map_err(|e| SemanticSearchError::StorageError {
-                    message: format!("Failed to load embeddings: {e}"),
-                    suggestion:
-                        "The storage file may be corrupted. Try rebuilding the semantic index."
-                            .to_string(),
-                })?;
-
-        // Convert VectorId back to SymbolId
-        let mut result = Vec::with_capacity(vectors.len());
-        for (vector_id, embedding) in vectors {
-            let symbol_id = SymbolId::new(vector_id.get()).unwrap();
-            result.push((symbol_id, embedding));
-        }
-
+        let mut result = Vec::with_capacity(self.locations.len());
+        self.for_each(|id, embedding| result.push((id, embedding)));
         Ok(result)
     }
 
     /// Saves multiple embeddings in batch.
     ///
-    /// More efficient than calling save_embedding repeatedly.
+    /// More efficient than calling save_embedding repeatedly. Embeddings
+    /// of symbols already stored replace them.
     pub fn save_batch(
         &mut self,
         embeddings: &[(SymbolId, Vec<f32>)],
@@ -161,6 +307,9 @@ impl SemanticVectorStorage {
                 });
             }
         }
+        if embeddings.is_empty() {
+            return Ok(());
+        }
 
         // Convert to vector storage format
         let mut vector_batch = Vec::with_capacity(embeddings.len());
@@ -174,17 +323,122 @@ impl SemanticVectorStorage {
             vector_batch.push((vector_id, embedding.as_slice()));
         }
 
-        self.storage
+        let index = self.active_segment(embeddings.len())?;
+        let start = self.segments[index].vectors.vector_count();
+        self.segments[index]
+            .vectors
             .write_batch(&vector_batch)
-            .map_err(|e| SemanticSearchError::StorageError {
-                message: format!("Failed to save batch: {e}"),
-                suggestion: "Check disk space and file permissions".to_string(),
-            })
+            .map_err(|e| {
+                storage_error(
+                    format!("Failed to save batch: {e}"),
+                    "Check disk space and file permissions",
+                )
+            })?;
+        for (offset, (id, _)) in embeddings.iter().enumerate() {
+            if let Some((segment, old)) = self.locations.insert(*id, (index, start + offset)) {
+                self.segments[segment].delete(old);
+            }
+        }
+        self.flush()
+    }
+
+    /// Deletes the embeddings of `ids`, returning how many there were.
+    pub fn remove(&mut self, ids: &[SymbolId]) -> Result<usize, SemanticSearchError> {
+        let mut removed = 0;
+        for id in ids {
+            if let Some((segment, position)) = self.locations.remove(id) {
+                self.segments[segment].delete(position);
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            self.flush()?;
+        }
+        Ok(removed)
+    }
+
+    /// Whether deleted vectors or segments have piled up enough to compact
+    pub fn needs_compaction(&self) -> bool {
+        let deleted = self.deleted_count();
+        self.segments.len() > MAX_SEGMENTS
+            || (deleted > 0
+                && deleted as f32
+                    >= COMPACT_DELETED_RATIO * (deleted + self.locations.len()) as f32)
+    }
+
+    /// Rewrites the live vectors into one segment, dropping deleted ones.
+    ///
+    /// The new segment replaces the old ones in the manifest before they are
+    /// removed, so an interrupted compaction leaves the store as it was.
+    /// Returns the number of vectors dropped.
+    pub fn compact(&mut self) -> Result<usize, SemanticSearchError> {
+        let dropped = self.deleted_count();
+        let ordinal = self.segments.last().map_or(0, |s| s.ordinal + 1);
+        let write_error = |e: crate::vector::VectorStorageError| {
+            storage_error(
+                format!("Failed to compact storage: {e}"),
+                "Check disk space and file permissions",
+            )
+        };
+        let mut target = self.create_segment(ordinal).map_err(write_error)?;
+
+        let mut locations = HashMap::with_capacity(self.locations.len());
+        let mut written = 0;
+        let mut batch: Vec<(VectorId, Vec<f32>)> = Vec::with_capacity(COMPACT_BATCH);
+        let mut write = |batch: &mut Vec<(VectorId, Vec<f32>)>| {
+            let vectors: Vec<(VectorId, &[f32])> =
+                batch.iter().map(|(id, v)| (*id, v.as_slice())).collect();
+            let result = target.write_batch(&vectors);
+            batch.clear();
+            result
+        };
+        for segment in &mut self.segments {
+            for position in 0..segment.vectors.vector_count() {
+                if segment.is_deleted(position) {
+                    continue;
+                }
+                let Some((id, embedding)) = segment.vectors.read_vector_at(position) else {
+                    break;
+                };
+                if let Some(symbol) = SymbolId::new(id.get()) {
+                    locations.insert(symbol, (0, written));
+                    batch.push((id, embedding));
+                    written += 1;
+                }
+                if batch.len() == COMPACT_BATCH {
+                    write(&mut batch).map_err(write_error)?;
+                }
+            }
+        }
+        write(&mut batch).map_err(write_error)?;
+
+        let old: Vec<u32> = self.segments.iter().map(|s| s.ordinal).collect();
+        self.segments = vec![Segment {
+            ordinal,
+            vectors: target,
+            deleted: Vec::new(),
+            dirty: true,
+        }];
+        self.locations = locations;
+        self.flush()?;
+        if let Err(e) = remove_segment_files(&self.path, |ordinal| old.contains(&ordinal)) {
+            tracing::debug!(target: "semantic", "failed to remove compacted segments: {e}");
+        }
+        Ok(dropped)
     }
 
     /// Returns the number of embeddings stored.
     pub fn embedding_count(&self) -> usize {
-        self.storage.vector_count()
+        self.locations.len()
+    }
+
+    /// Number of vectors removed or replaced but still on disk
+    pub fn deleted_count(&self) -> usize {
+        self.segments.iter().map(Segment::deleted_count).sum()
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
     }
 
     /// Returns the embedding dimension.
@@ -192,19 +446,146 @@ impl SemanticVectorStorage {
         self.dimension
     }
 
-    /// Checks if the storage file exists.
+    /// Checks if the storage exists on disk.
     pub fn exists(&self) -> bool {
-        self.storage.exists()
+        Self::exists_at(&self.path)
     }
 
-    /// Returns the size of the storage file in bytes.
+    /// Returns the size of the segment files in bytes.
     pub fn file_size(&self) -> Result<u64, SemanticSearchError> {
-        self.storage
-            .file_size()
-            .map_err(|e| SemanticSearchError::StorageError {
-                message: format!("Failed to get file size: {e}"),
-                suggestion: "Check if the storage file exists".to_string(),
+        let mut size = 0;
+        for segment in &self.segments {
+            size += segment.vectors.file_size().map_err(|e| {
+                storage_error(
+                    format!("Failed to get file size: {e}"),
+                    "Check if the storage file exists",
+                )
+            })?;
+        }
+        Ok(size)
+    }
+
+    /// Index of the segment the next `incoming` vectors are appended to,
+    /// starting a new one when the last is full
+    fn active_segment(&mut self, incoming: usize) -> Result<usize, SemanticSearchError> {
+        if let Some(last) = self.segments.last() {
+            let count = last.vectors.vector_count();
+            if count == 0 || count + incoming <= SEGMENT_CAPACITY {
+                return Ok(self.segments.len() - 1);
+            }
+        }
+        let ordinal = self.segments.last().map_or(0, |s| s.ordinal + 1);
+        let vectors = self.create_segment(ordinal).map_err(|e| {
+            storage_error(
+                format!("Failed to create storage: {e}"),
+                "Ensure the directory exists and you have write permissions",
+            )
+        })?;
+        self.segments.push(Segment {
+            ordinal,
+            vectors,
+            deleted: Vec::new(),
+            dirty: true,
+        });
+        Ok(self.segments.len() - 1)
+    }
+
+    /// Empty segment file `ordinal`, replacing a stray one left on disk
+    fn create_segment(
+        &self,
+        ordinal: u32,
+    ) -> Result<MmapVectorStorage, crate::vector::VectorStorageError> {
+        remove_segment_files(&self.path, |stray| stray == ordinal)?;
+        MmapVectorStorage::open_or_create(&self.path, SegmentOrdinal::new(ordinal), self.dimension)
+    }
+
+    /// Writes changed deletion bitmaps, then the manifest
+    fn flush(&mut self) -> Result<(), SemanticSearchError> {
+        for segment in self.segments.iter_mut().filter(|s| s.dirty) {
+            let bytes: Vec<u8> = segment
+                .deleted
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect();
+            std::fs::write(deletion_path(&self.path, segment.ordinal), bytes).map_err(|e| {
+                storage_error(
+                    format!("Failed to write deletion bitmap: {e}"),
+                    "Check disk space and file permissions",
+                )
+            })?;
+            segment.dirty = false;
+        }
+        self.write_manifest()
+    }
+
+    /// Replace the manifest in one rename, so readers see the old or new list
+    fn write_manifest(&self) -> Result<(), SemanticSearchError> {
+        let manifest = Manifest {
+            dimension: self.dimension.get(),
+            segments: self.segments.iter().map(|s| s.ordinal).collect(),
+        };
+        let json = serde_json::to_string(&manifest).map_err(|e| {
+            storage_error(
+                format!("Failed to serialize {MANIFEST_FILE}: {e}"),
+                "This is likely a bug in the code",
+            )
+        })?;
+        let temporary = self.path.join(format!("{MANIFEST_FILE}.tmp"));
+        std::fs::write(&temporary, json)
+            .and_then(|_| std::fs::rename(&temporary, self.path.join(MANIFEST_FILE)))
+            .map_err(|e| {
+                storage_error(
+                    format!("Failed to write {MANIFEST_FILE}: {e}"),
+                    "Check disk space and file permissions",
+                )
+            })
+    }
+}
+
+fn open_segment(path: &Path, ordinal: u32) -> Result<MmapVectorStorage, SemanticSearchError> {
+    MmapVectorStorage::open(path, SegmentOrdinal::new(ordinal)).map_err(|e| {
+        storage_error(
+            format!("Failed to open storage: {e}"),
+            "Check if semantic search data exists at the specified path",
+        )
+    })
+}
+
+fn deletion_path(path: &Path, ordinal: u32) -> PathBuf {
+    path.join(format!("segment_{ordinal}.del"))
+}
+
+/// Remove the vector and bitmap files of segments `remove` picks
+fn remove_segment_files(path: &Path, remove: impl Fn(u32) -> bool) -> std::io::Result<()> {
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(ordinal) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("segment_"))
+            .and_then(|rest| {
+                rest.strip_suffix(".vec")
+                    .or_else(|| rest.strip_suffix(".del"))
             })
+            .and_then(|ordinal| ordinal.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if remove(ordinal) {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+fn storage_error(message: String, suggestion: &str) -> SemanticSearchError {
+    SemanticSearchError::StorageError {
+        message,
+        suggestion: suggestion.to_string(),
     }
 }
 
@@ -350,4 +731,39 @@ mod tests {
             assert_eq!(loaded, vec![1.5, 2.5]);
         }
     }
+
+    #[test]
+    fn test_segments_deletions_and_compaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let dimension = VectorDimension::new(2).unwrap();
+        let id = |value| SymbolId::new(value).unwrap();
+
+        let mut storage = SemanticVectorStorage::new(temp_dir.path(), dimension).unwrap();
+        storage
+            .save_batch(&[(id(1), vec![1.0, 0.0]), (id(2), vec![0.0, 1.0])])
+            .unwrap();
+        // Replaced and removed vectors stay on disk, marked deleted
+        storage.save_embedding(id(1), &[0.5, 0.5]).unwrap();
+        assert_eq!(storage.remove(&[id(2), id(9)]).unwrap(), 1);
+        assert_eq!(storage.embedding_count(), 1);
+        assert_eq!(storage.deleted_count(), 2);
+        drop(storage);
+
+        let mut storage = SemanticVectorStorage::open(temp_dir.path()).unwrap();
+        assert_eq!(storage.load_embedding(id(1)), Some(vec![0.5, 0.5]));
+        assert!(storage.load_embedding(id(2)).is_none());
+        assert_eq!(storage.load_all().unwrap().len(), 1);
+        assert!(storage.needs_compaction());
+
+        assert_eq!(storage.compact().unwrap(), 2);
+        storage.save_embedding(id(3), &[3.0, 3.0]).unwrap();
+        drop(storage);
+
+        let mut storage = SemanticVectorStorage::open(temp_dir.path()).unwrap();
+        assert_eq!(storage.deleted_count(), 0);
+        assert_eq!(storage.segment_count(), 1);
+        assert!(!temp_dir.path().join("segment_0.vec").exists());
+        assert_eq!(storage.load_embedding(id(1)), Some(vec![0.5, 0.5]));
+        assert_eq!(storage.load_embedding(id(3)), Some(vec![3.0, 3.0]));
+    }
 }
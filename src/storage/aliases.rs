@@ -0,0 +1,112 @@
+//! Path aliases of de-duplicated files
+//!
+//! `aliases.json` in the Tantivy directory maps every indexed file whose
+//! content is identical to another file's (a vendored copy, a generated
+//! duplicate) to that canonical file. Only the canonical file is parsed and
+//! embedded; aliases are registered with no symbols of their own. The index
+//! stage replaces a file's entry each time the file is written, and cleanup
+//! drops it with the file.
+
+use super::StorageResult;
+use super::json_store::JsonFileStore;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File name of the store inside the Tantivy directory
+pub const ALIASES_FILE: &str = "aliases.json";
+
+/// Canonical path of every alias, keyed by alias path
+pub type FileAliases = BTreeMap<String, String>;
+
+/// Reader and writer for `aliases.json`
+pub struct AliasStore {
+    store: JsonFileStore<String>,
+}
+
+impl AliasStore {
+    /// Store in the Tantivy directory of an index
+    pub fn new(tantivy_path: &Path) -> Self {
+        Self {
+            store: JsonFileStore::new(tantivy_path, ALIASES_FILE),
+        }
+    }
+
+    /// All aliases; a missing store reads as empty
+    pub fn load(&self) -> StorageResult<FileAliases> {
+        self.store.load()
+    }
+
+    /// Canonical file `path` is a copy of, if it is an alias
+    pub fn canonical_of(&self, path: &str) -> StorageResult<Option<String>> {
+        Ok(self.load()?.remove(path))
+    }
+
+    /// Files that are copies of `canonical`
+    pub fn aliases_of(&self, canonical: &str) -> StorageResult<Vec<String>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .filter(|(_, target)| target == canonical)
+            .map(|(alias, _)| alias)
+            .collect())
+    }
+
+    /// Set or clear the canonical file of each path
+    pub fn update(
+        &self,
+        files: impl IntoIterator<Item = (String, Option<String>)>,
+    ) -> StorageResult<()> {
+        self.store.update(files)
+    }
+
+    /// Forget files removed from the index
+    pub fn remove_many(&self, paths: &[PathBuf]) -> StorageResult<()> {
+        self.store.remove_many(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_update_sets_and_clears_aliases() {
+        let temp = TempDir::new().unwrap();
+        let store = AliasStore::new(temp.path());
+        assert!(store.load().unwrap().is_empty());
+
+        store
+            .update([
+                (
+                    "vendor/a/lib.rs".to_string(),
+                    Some("src/lib.rs".to_string()),
+                ),
+                (
+                    "vendor/b/lib.rs".to_string(),
+                    Some("src/lib.rs".to_string()),
+                ),
+                ("src/main.rs".to_string(), None),
+            ])
+            .unwrap();
+        assert_eq!(
+            store.aliases_of("src/lib.rs").unwrap(),
+            vec!["vendor/a/lib.rs", "vendor/b/lib.rs"]
+        );
+        assert_eq!(
+            store.canonical_of("vendor/a/lib.rs").unwrap().as_deref(),
+            Some("src/lib.rs")
+        );
+        assert_eq!(store.canonical_of("src/lib.rs").unwrap(), None);
+
+        // Re-indexed on its own after the copies diverged
+        store
+            .update([("vendor/a/lib.rs".to_string(), None)])
+            .unwrap();
+        store
+            .remove_many(&[PathBuf::from("vendor/b/lib.rs")])
+            .unwrap();
+        assert!(store.aliases_of("src/lib.rs").unwrap().is_empty());
+        assert!(!temp.path().join(ALIASES_FILE).exists());
+    }
+}
@@ -0,0 +1,110 @@
+//! Git provenance of indexed symbols
+//!
+//! `blame.json` in the Tantivy directory maps every file indexed with
+//! `indexing.blame` on to the provenance of its symbols: the commit that
+//! introduced each one and the commit that last modified it. The index stage
+//! replaces a file's entry each time the file is written, and cleanup drops
+//! it with the file. Entries are matched to symbols by name and first line,
+//! since symbol IDs change when a file is re-indexed.
+
+use super::StorageResult;
+use super::json_store::JsonFileStore;
+use crate::Symbol;
+use crate::indexing::blame::SymbolBlame;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File name of the store inside the Tantivy directory
+pub const BLAME_FILE: &str = "blame.json";
+
+/// Provenance of the symbols of every blamed file, keyed by path
+pub type FileBlames = BTreeMap<String, Vec<SymbolBlame>>;
+
+/// Reader and writer for `blame.json`
+pub struct BlameStore {
+    store: JsonFileStore<Vec<SymbolBlame>>,
+}
+
+impl BlameStore {
+    /// Store in the Tantivy directory of an index
+    pub fn new(tantivy_path: &Path) -> Self {
+        Self {
+            store: JsonFileStore::new(tantivy_path, BLAME_FILE),
+        }
+    }
+
+    /// Provenance of all files; a missing store reads as empty
+    pub fn load(&self) -> StorageResult<FileBlames> {
+        self.store.load()
+    }
+
+    /// Provenance of `symbol` in a loaded store
+    pub fn find<'a>(blames: &'a FileBlames, symbol: &Symbol) -> Option<&'a SymbolBlame> {
+        blames
+            .get(&*symbol.file_path)?
+            .iter()
+            .find(|blame| blame.line == symbol.range.start_line && *blame.name == *symbol.name)
+    }
+
+    /// Replace the provenance of the given files; an empty list clears the file
+    pub fn update(
+        &self,
+        files: impl IntoIterator<Item = (String, Vec<SymbolBlame>)>,
+    ) -> StorageResult<()> {
+        self.store.update(
+            files
+                .into_iter()
+                .map(|(path, blames)| (path, (!blames.is_empty()).then_some(blames))),
+        )
+    }
+
+    /// Forget the provenance of files removed from the index
+    pub fn remove_many(&self, paths: &[PathBuf]) -> StorageResult<()> {
+        self.store.remove_many(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexing::blame::BlameCommit;
+    use crate::types::{FileId, Range, SymbolId, SymbolKind};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_update_and_find_symbol_provenance() {
+        let temp = TempDir::new().unwrap();
+        let store = BlameStore::new(temp.path());
+        assert!(store.load().unwrap().is_empty());
+
+        let commit = BlameCommit {
+            commit: "a".repeat(40),
+            author: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            time: 1_600_000_000,
+        };
+        let blame = SymbolBlame {
+            name: "parse".to_string(),
+            line: 9,
+            introduced: commit.clone(),
+            last_modified: commit,
+        };
+        store
+            .update([("src/lib.rs".to_string(), vec![blame.clone()])])
+            .unwrap();
+
+        let symbol = Symbol::new(
+            SymbolId::new(7).unwrap(),
+            "parse",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(9, 0, 20, 1),
+        )
+        .with_file_path("src/lib.rs");
+        let all = store.load().unwrap();
+        assert_eq!(BlameStore::find(&all, &symbol), Some(&blame));
+
+        store.remove_many(&[PathBuf::from("src/lib.rs")]).unwrap();
+        assert!(!temp.path().join(BLAME_FILE).exists());
+    }
+}
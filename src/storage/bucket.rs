@@ -0,0 +1,508 @@
+//! Index synchronization with an object-store bucket
+//!
+//! `codanna index push` uploads the index directory to S3 or Google Cloud
+//! Storage and `codanna index pull` downloads it. Files are cut into 4 MiB
+//! chunks stored under the SHA-256 of their content, so a push uploads only
+//! chunks the bucket lacks and a pull downloads only chunks the local index
+//! lacks. Tantivy never rewrites a segment file, so after CI pushes a rebuilt
+//! index developers fetch the new segments rather than the whole index.
+//!
+//! Under the prefix of the remote URL the bucket holds:
+//!
+//! ```text
+//! chunks/<sha256>      zstd-compressed chunk content
+//! indexes/<tag>.json   RemoteManifest listing the chunks of each file
+//! ```
+//!
+//! A manifest is written after its chunks, so a tag never names missing
+//! data. As in snapshots, indexed directories are stored relative to the
+//! workspace root and local-only files stay on the machine that wrote them.
+
+use super::snapshot::{self, METADATA_FILE, SnapshotManifest};
+use super::{IndexPersistence, StorageError, StorageResult};
+use object_store::ObjectStore;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Size of every chunk but the last of a file
+pub const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Tag pushed and pulled when none is given
+pub const DEFAULT_TAG: &str = "latest";
+
+/// Files of an index pushed under a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteManifest {
+    #[serde(flatten)]
+    pub snapshot: SnapshotManifest,
+    pub files: Vec<RemoteFile>,
+}
+
+/// A file of the index and the chunks it is made of
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteFile {
+    /// Path inside the index directory, separated by `/`
+    pub path: String,
+    pub size: u64,
+    /// Chunk hashes in file order
+    pub chunks: Vec<String>,
+}
+
+/// What a push or pull moved
+#[derive(Debug, Clone, Default)]
+pub struct SyncStats {
+    pub files: usize,
+    pub chunks: usize,
+    /// Chunks uploaded or downloaded
+    pub transferred: usize,
+    /// Compressed bytes uploaded or downloaded
+    pub bytes: u64,
+}
+
+/// A bucket prefix holding pushed indexes
+pub struct Remote {
+    store: Box<dyn ObjectStore>,
+    prefix: ObjectPath,
+}
+
+impl Remote {
+    /// Open `s3://bucket/prefix`, `gs://bucket/prefix` or a local directory
+    ///
+    /// Credentials and region come from the environment, as for the AWS and
+    /// Google Cloud command line tools.
+    pub fn open(url: &str) -> StorageResult<Self> {
+        let (store, location): (Box<dyn ObjectStore>, &str) =
+            if let Some(location) = url.strip_prefix("s3://") {
+                let store = AmazonS3Builder::from_env().with_url(url).build()?;
+                (Box::new(store), location)
+            } else if let Some(location) = url.strip_prefix("gs://") {
+                let store = GoogleCloudStorageBuilder::from_env()
+                    .with_url(url)
+                    .build()?;
+                (Box::new(store), location)
+            } else {
+                let dir = url.strip_prefix("file://").unwrap_or(url);
+                fs::create_dir_all(dir)?;
+                return Ok(Self {
+                    store: Box::new(LocalFileSystem::new_with_prefix(dir)?),
+                    prefix: ObjectPath::default(),
+                });
+            };
+
+        // The first segment names the bucket
+        let prefix = location.split_once('/').map_or("", |(_, prefix)| prefix);
+        Ok(Self {
+            store,
+            prefix: ObjectPath::from(prefix),
+        })
+    }
+
+    fn chunk_path(&self, hash: &str) -> ObjectPath {
+        self.prefix.child("chunks").child(hash)
+    }
+
+    fn manifest_path(&self, tag: &str) -> ObjectPath {
+        self.prefix.child("indexes").child(format!("{tag}.json"))
+    }
+
+    /// Hashes of the chunks already in the bucket
+    async fn stored_chunks(&self) -> StorageResult<HashSet<String>> {
+        let chunks = self.prefix.child("chunks");
+        let listing = match self.store.list_with_delimiter(Some(&chunks)).await {
+            Ok(listing) => listing,
+            Err(object_store::Error::NotFound { .. }) => return Ok(HashSet::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(listing
+            .objects
+            .iter()
+            .filter_map(|object| object.location.filename())
+            .map(str::to_string)
+            .collect())
+    }
+
+    async fn fetch_chunk(&self, hash: &str, stats: &mut SyncStats) -> StorageResult<Vec<u8>> {
+        let compressed = self
+            .store
+            .get(&self.chunk_path(hash))
+            .await?
+            .bytes()
+            .await?;
+        stats.transferred += 1;
+        stats.bytes += compressed.len() as u64;
+        let chunk = zstd::decode_all(&compressed[..])?;
+        if chunk_hash(&chunk) != hash {
+            return Err(StorageError::Snapshot(format!("chunk {hash} is corrupt")));
+        }
+        Ok(chunk)
+    }
+}
+
+/// Upload the index at `index_path` and point `tag` at it
+pub async fn push(
+    remote: &Remote,
+    tag: &str,
+    index_path: &Path,
+    workspace_root: &Path,
+) -> StorageResult<(RemoteManifest, SyncStats)> {
+    if !IndexPersistence::new(index_path.to_path_buf()).exists() {
+        return Err(StorageError::Snapshot(format!(
+            "no index found at {}",
+            index_path.display()
+        )));
+    }
+
+    let (metadata, snapshot) = snapshot::portable_metadata(index_path, workspace_root)?;
+    let metadata = serde_json::to_vec_pretty(&metadata)
+        .map_err(|e| StorageError::Serialization(e.to_string()))?;
+
+    let mut stored = remote.stored_chunks().await?;
+    let mut stats = SyncStats::default();
+    let mut files = vec![
+        upload(
+            remote,
+            METADATA_FILE,
+            &mut &metadata[..],
+            &mut stored,
+            &mut stats,
+        )
+        .await?,
+    ];
+    for relative in index_files(index_path)? {
+        let mut file = File::open(index_path.join(&relative))?;
+        files.push(
+            upload(
+                remote,
+                &portable(&relative),
+                &mut file,
+                &mut stored,
+                &mut stats,
+            )
+            .await?,
+        );
+    }
+    stats.files = files.len();
+
+    let manifest = RemoteManifest { snapshot, files };
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| StorageError::Serialization(e.to_string()))?;
+    remote
+        .store
+        .put(&remote.manifest_path(tag), json.into())
+        .await?;
+    Ok((manifest, stats))
+}
+
+/// Replace the index at `index_path` with the one pushed under `tag`
+///
+/// Chunks found in the current index are copied from it; only the rest is
+/// downloaded. The index is built in a sibling directory and swapped in once
+/// complete, carrying over local-only files of the old index.
+pub async fn pull(
+    remote: &Remote,
+    tag: &str,
+    index_path: &Path,
+    workspace_root: &Path,
+) -> StorageResult<(RemoteManifest, SyncStats)> {
+    let json = match remote.store.get(&remote.manifest_path(tag)).await {
+        Ok(result) => result.bytes().await?,
+        Err(object_store::Error::NotFound { .. }) => {
+            return Err(StorageError::Snapshot(format!(
+                "no index was pushed with tag '{tag}'"
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let manifest: RemoteManifest =
+        serde_json::from_slice(&json).map_err(|e| StorageError::Serialization(e.to_string()))?;
+    let compatibility = snapshot::check_readable(&manifest.snapshot)?;
+
+    let wanted: HashSet<&str> = manifest
+        .files
+        .iter()
+        .flat_map(|file| file.chunks.iter().map(String::as_str))
+        .collect();
+    let mut local = local_chunks(index_path, &wanted)?;
+
+    let staging = snapshot::staging_dir(index_path, ".codanna-pull-")?;
+    let mut stats = SyncStats {
+        files: manifest.files.len(),
+        ..SyncStats::default()
+    };
+    for entry in &manifest.files {
+        let target = staged_path(staging.path(), &entry.path)?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&target)?;
+        let mut offset = 0;
+        for hash in &entry.chunks {
+            let copied = local
+                .get(hash)
+                .and_then(|chunk| chunk.read().ok())
+                .filter(|chunk| chunk_hash(chunk) == *hash);
+            let chunk = match copied {
+                Some(chunk) => chunk,
+                None => remote.fetch_chunk(hash, &mut stats).await?,
+            };
+            file.write_all(&chunk)?;
+            // Later files repeating the chunk copy it from here
+            local.entry(hash.clone()).or_insert(LocalChunk {
+                path: target.clone(),
+                offset,
+                len: chunk.len(),
+            });
+            offset += chunk.len() as u64;
+            stats.chunks += 1;
+        }
+    }
+
+    snapshot::finish_unpacked(staging.path(), workspace_root, &compatibility)?;
+    snapshot::install(staging, index_path)?;
+    Ok((manifest, stats))
+}
+
+/// Upload the chunks of `reader` missing from `stored`
+async fn upload(
+    remote: &Remote,
+    path: &str,
+    reader: &mut impl Read,
+    stored: &mut HashSet<String>,
+    stats: &mut SyncStats,
+) -> StorageResult<RemoteFile> {
+    let mut file = RemoteFile {
+        path: path.to_string(),
+        size: 0,
+        chunks: Vec::new(),
+    };
+    loop {
+        let chunk = next_chunk(reader)?;
+        if chunk.is_empty() {
+            break;
+        }
+        let hash = chunk_hash(&chunk);
+        if stored.insert(hash.clone()) {
+            let compressed = zstd::encode_all(&chunk[..], 0)?;
+            stats.transferred += 1;
+            stats.bytes += compressed.len() as u64;
+            remote
+                .store
+                .put(&remote.chunk_path(&hash), compressed.into())
+                .await?;
+        }
+        stats.chunks += 1;
+        file.size += chunk.len() as u64;
+        file.chunks.push(hash);
+        if chunk.len() < CHUNK_SIZE {
+            break;
+        }
+    }
+    Ok(file)
+}
+
+/// A chunk already on disk
+struct LocalChunk {
+    path: PathBuf,
+    offset: u64,
+    len: usize,
+}
+
+impl LocalChunk {
+    fn read(&self) -> std::io::Result<Vec<u8>> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut chunk = vec![0; self.len];
+        file.read_exact(&mut chunk)?;
+        Ok(chunk)
+    }
+}
+
+/// Where the chunks of `wanted` sit in the index at `index_path`
+fn local_chunks(
+    index_path: &Path,
+    wanted: &HashSet<&str>,
+) -> StorageResult<HashMap<String, LocalChunk>> {
+    let mut found = HashMap::new();
+    if !index_path.is_dir() {
+        return Ok(found);
+    }
+    for relative in index_files(index_path)? {
+        let path = index_path.join(&relative);
+        let mut file = File::open(&path)?;
+        let mut offset = 0;
+        loop {
+            let chunk = next_chunk(&mut file)?;
+            if chunk.is_empty() {
+                break;
+            }
+            let hash = chunk_hash(&chunk);
+            if wanted.contains(hash.as_str()) {
+                found.entry(hash).or_insert_with(|| LocalChunk {
+                    path: path.clone(),
+                    offset,
+                    len: chunk.len(),
+                });
+            }
+            offset += chunk.len() as u64;
+            if chunk.len() < CHUNK_SIZE {
+                break;
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Files of the index to sync, relative to `index_path`, in name order
+fn index_files(index_path: &Path) -> StorageResult<Vec<PathBuf>> {
+    let entries = WalkDir::new(index_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry
+                .path()
+                .strip_prefix(index_path)
+                .map(|relative| !snapshot::is_local_only(relative))
+                .unwrap_or(true)
+        });
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| StorageError::Io(e.into()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(index_path) else {
+            continue;
+        };
+        if relative != Path::new(METADATA_FILE) {
+            files.push(relative.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+/// Up to [`CHUNK_SIZE`] bytes of `reader`, empty at the end
+fn next_chunk(reader: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut chunk = Vec::new();
+    reader
+        .by_ref()
+        .take(CHUNK_SIZE as u64)
+        .read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
+
+fn chunk_hash(chunk: &[u8]) -> String {
+    hex::encode(Sha256::digest(chunk))
+}
+
+fn portable(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Where a manifest file goes in `dir`, refusing paths leaving it
+fn staged_path(dir: &Path, path: &str) -> StorageResult<PathBuf> {
+    let relative = Path::new(path);
+    let inside = relative.components().next().is_some()
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !inside {
+        return Err(StorageError::Snapshot(format!(
+            "file {path} points outside the index directory"
+        )));
+    }
+    Ok(dir.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::IndexMetadata;
+    use crate::storage::event_log::EVENT_LOG_FILE;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, contents: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_push_pull_transfers_only_new_chunks() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let bucket = TempDir::new().unwrap();
+        let remote = Remote::open(bucket.path().to_str().unwrap()).unwrap();
+
+        let ci = TempDir::new().unwrap();
+        let workspace = ci.path().join("repo");
+        let index = workspace.join(".codanna/index");
+        let segment: Vec<u8> = (0..CHUNK_SIZE + 10).map(|i| (i % 251) as u8).collect();
+        write(&index.join("tantivy/meta.json"), b"{}");
+        write(&index.join("tantivy/old.store"), &segment);
+        write(&index.join(EVENT_LOG_FILE), b"ci history");
+        let mut metadata = IndexMetadata::new();
+        metadata.symbol_count = 42;
+        metadata.indexed_paths = Some(vec![workspace.join("src")]);
+        metadata.save(&index).unwrap();
+
+        let (_, stats) = runtime
+            .block_on(push(&remote, DEFAULT_TAG, &index, &workspace))
+            .unwrap();
+        // index.meta, meta.json and two chunks of the segment
+        assert_eq!((stats.files, stats.chunks, stats.transferred), (3, 4, 4));
+
+        let dev = TempDir::new().unwrap();
+        let checkout = dev.path().join("checkout");
+        let local = checkout.join(".codanna/index");
+        write(&local.join(EVENT_LOG_FILE), b"local history");
+        let (_, stats) = runtime
+            .block_on(pull(&remote, DEFAULT_TAG, &local, &checkout))
+            .unwrap();
+        assert_eq!(stats.transferred, 4);
+        assert_eq!(fs::read(local.join("tantivy/old.store")).unwrap(), segment);
+
+        // CI adds a segment; unchanged chunks stay where they are
+        write(&index.join("tantivy/meta.json"), b"{\"segments\": 2}");
+        write(&index.join("tantivy/new.store"), b"new segment");
+        let (_, stats) = runtime
+            .block_on(push(&remote, DEFAULT_TAG, &index, &workspace))
+            .unwrap();
+        assert_eq!(stats.transferred, 2);
+        // index.meta was re-anchored locally, so it comes down again too
+        let (manifest, stats) = runtime
+            .block_on(pull(&remote, DEFAULT_TAG, &local, &checkout))
+            .unwrap();
+        assert_eq!(stats.transferred, 3);
+        assert_eq!(manifest.snapshot.symbol_count, 42);
+
+        assert_eq!(
+            fs::read_to_string(local.join("tantivy/new.store")).unwrap(),
+            "new segment"
+        );
+        assert_eq!(
+            fs::read_to_string(local.join(EVENT_LOG_FILE)).unwrap(),
+            "local history"
+        );
+        let pulled = IndexMetadata::load(&local).unwrap();
+        assert_eq!(pulled.indexed_paths, Some(vec![checkout.join("src")]));
+        assert!(
+            runtime
+                .block_on(pull(&remote, "missing", &local, &checkout))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Index schema compatibility checks
+//!
+//! An index written by another codanna release may use a different Tantivy
+//! schema. Opening it anyway fails deep inside Tantivy with an error that
+//! says nothing about versions, so the CLI checks `index.meta` first and tells
+//! the user whether to migrate, re-index, or change binaries.
+
+use super::IndexMetadata;
+use crate::IndexResult;
+use std::path::Path;
+
+/// Schema version written by this binary
+///
+/// Bump whenever the Tantivy schema or the on-disk layout changes in a way
+/// older binaries cannot read.
+pub const INDEX_SCHEMA_VERSION: u32 = 2;
+
+/// Upgrade step from one schema version to the next
+type Migration = fn(&Path, &mut IndexMetadata) -> IndexResult<()>;
+
+/// In-place upgrades, keyed by the version they upgrade from
+///
+/// Register a step here when a schema bump can be applied without re-parsing
+/// source files (e.g. metadata-only changes). Versions without a step require
+/// a re-index.
+const MIGRATIONS: &[(u32, Migration)] = &[];
+
+/// How the on-disk index relates to the running binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Same schema version
+    Current,
+    /// Older schema that can be upgraded in place
+    Migratable { found: u32 },
+    /// Older schema that must be rebuilt
+    Outdated { found: u32 },
+    /// Written by a newer binary
+    Newer {
+        found: u32,
+        written_by: Option<String>,
+    },
+}
+
+impl Compatibility {
+    /// Compare the index at `base_path` with this binary
+    pub fn check(base_path: &Path) -> IndexResult<Self> {
+        let metadata = IndexMetadata::load(base_path)?;
+        Ok(Self::classify(&metadata, INDEX_SCHEMA_VERSION, MIGRATIONS))
+    }
+
+    /// Compare an index described by `metadata` with this binary
+    pub fn of(metadata: &IndexMetadata) -> Self {
+        Self::classify(metadata, INDEX_SCHEMA_VERSION, MIGRATIONS)
+    }
+
+    fn classify(metadata: &IndexMetadata, current: u32, migrations: &[(u32, Migration)]) -> Self {
+        let found = metadata.version;
+        if found == current {
+            Self::Current
+        } else if found > current {
+            Self::Newer {
+                found,
+                written_by: metadata.written_by.clone(),
+            }
+        } else if (found..current).all(|v| migrations.iter().any(|(from, _)| *from == v)) {
+            Self::Migratable { found }
+        } else {
+            Self::Outdated { found }
+        }
+    }
+
+    /// What the user should do, or `None` if the index is usable
+    pub fn guidance(&self) -> Option<String> {
+        let current = INDEX_SCHEMA_VERSION;
+        let binary = env!("CARGO_PKG_VERSION");
+        match self {
+            Self::Current | Self::Migratable { .. } => None,
+            Self::Outdated { found } => Some(format!(
+                "Index schema v{found} is older than v{current} used by codanna {binary}.\n\
+                 Rebuild it with: codanna index --force"
+            )),
+            Self::Newer { found, written_by } => {
+                let writer = written_by
+                    .as_deref()
+                    .map(|v| format!("codanna {v}"))
+                    .unwrap_or_else(|| "a newer codanna".to_string());
+                Some(format!(
+                    "Index schema v{found} was written by {writer}; codanna {binary} reads up to v{current}.\n\
+                     Either upgrade this binary:    codanna self-update\n\
+                     or rebuild with this version:  codanna index --force"
+                ))
+            }
+        }
+    }
+}
+
+/// Upgrade the index at `base_path` to the current schema in place
+///
+/// Returns the version migrated from, or `None` if nothing was needed.
+pub fn migrate(base_path: &Path) -> IndexResult<Option<u32>> {
+    apply_migrations(base_path, INDEX_SCHEMA_VERSION, MIGRATIONS)
+}
+
+fn apply_migrations(
+    base_path: &Path,
+    current: u32,
+    migrations: &[(u32, Migration)],
+) -> IndexResult<Option<u32>> {
+    let mut metadata = IndexMetadata::load(base_path)?;
+    let found = metadata.version;
+    if found >= current {
+        return Ok(None);
+    }
+
+    for version in found..current {
+        let (_, step) = migrations
+            .iter()
+            .find(|(from, _)| *from == version)
+            .ok_or_else(|| {
+                crate::IndexError::General(format!(
+                    "No migration from index schema v{version}; re-index with --force"
+                ))
+            })?;
+        step(base_path, &mut metadata)?;
+        metadata.version = version + 1;
+        // Persist each step so an interrupted upgrade resumes where it stopped
+        metadata.save(base_path)?;
+    }
+
+    Ok(Some(found))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn metadata(version: u32) -> IndexMetadata {
+        IndexMetadata {
+            version,
+            ..IndexMetadata::new()
+        }
+    }
+
+    fn noop(_: &Path, _: &mut IndexMetadata) -> IndexResult<()> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_classify_versions() {
+        let migrations: &[(u32, Migration)] = &[(2, noop)];
+
+        assert_eq!(
+            Compatibility::classify(&metadata(3), 3, migrations),
+            Compatibility::Current
+        );
+        assert_eq!(
+            Compatibility::classify(&metadata(2), 3, migrations),
+            Compatibility::Migratable { found: 2 }
+        );
+        assert_eq!(
+            Compatibility::classify(&metadata(1), 3, migrations),
+            Compatibility::Outdated { found: 1 }
+        );
+
+        let mut newer = metadata(4);
+        newer.written_by = Some("1.2.0".to_string());
+        let result = Compatibility::classify(&newer, 3, migrations);
+        assert!(matches!(result, Compatibility::Newer { found: 4, .. }));
+        assert!(result.guidance().unwrap().contains("codanna 1.2.0"));
+    }
+
+    #[test]
+    fn test_apply_migrations_steps_to_current() {
+        let temp = TempDir::new().unwrap();
+        metadata(1).save(temp.path()).unwrap();
+        let migrations: &[(u32, Migration)] = &[(1, noop), (2, noop)];
+
+        let from = apply_migrations(temp.path(), 3, migrations).unwrap();
+
+        assert_eq!(from, Some(1));
+        assert_eq!(IndexMetadata::load(temp.path()).unwrap().version, 3);
+        assert_eq!(apply_migrations(temp.path(), 3, migrations).unwrap(), None);
+    }
+
+    #[test]
+    fn test_apply_migrations_without_path_fails() {
+        let temp = TempDir::new().unwrap();
+        metadata(1).save(temp.path()).unwrap();
+
+        assert!(apply_migrations(temp.path(), 2, &[]).is_err());
+        assert_eq!(IndexMetadata::load(temp.path()).unwrap().version, 1);
+    }
+}
@@ -0,0 +1,120 @@
+//! Parse diagnostics of indexed files
+//!
+//! `diagnostics.json` in the Tantivy directory maps every indexed file that
+//! has syntax errors to the regions tree-sitter could not parse. The index
+//! stage replaces a file's entry each time the file is written, cleanup drops
+//! it with the file, and clearing the index removes the directory with it.
+
+use super::StorageResult;
+use super::json_store::JsonFileStore;
+use crate::parsing::diagnostics::{ParseDiagnostic, affects};
+use crate::types::Range;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File name of the store inside the Tantivy directory
+pub const DIAGNOSTICS_FILE: &str = "diagnostics.json";
+
+/// Diagnostics of every file with parse errors, keyed by path
+pub type FileDiagnostics = BTreeMap<String, Vec<ParseDiagnostic>>;
+
+/// Reader and writer for `diagnostics.json`
+pub struct DiagnosticsStore {
+    store: JsonFileStore<Vec<ParseDiagnostic>>,
+}
+
+impl DiagnosticsStore {
+    /// Store in the Tantivy directory of an index
+    pub fn new(tantivy_path: &Path) -> Self {
+        Self {
+            store: JsonFileStore::new(tantivy_path, DIAGNOSTICS_FILE),
+        }
+    }
+
+    /// Path of the store file
+    pub fn path(&self) -> &Path {
+        self.store.path()
+    }
+
+    /// Diagnostics of all files; a missing store reads as empty
+    pub fn load(&self) -> StorageResult<FileDiagnostics> {
+        self.store.load()
+    }
+
+    /// Diagnostics of one file
+    pub fn for_file(&self, path: &str) -> StorageResult<Vec<ParseDiagnostic>> {
+        Ok(self.load()?.remove(path).unwrap_or_default())
+    }
+
+    /// Whether a symbol at `range` in `path` overlaps a parse error
+    pub fn is_low_confidence(diagnostics: &FileDiagnostics, path: &str, range: &Range) -> bool {
+        diagnostics
+            .get(path)
+            .is_some_and(|file| affects(file, range))
+    }
+
+    /// Replace the diagnostics of the given files; an empty list clears the file
+    pub fn update<'a>(
+        &self,
+        files: impl IntoIterator<Item = (String, &'a [ParseDiagnostic])>,
+    ) -> StorageResult<()> {
+        self.store
+            .update(files.into_iter().map(|(path, diagnostics)| {
+                (
+                    path,
+                    (!diagnostics.is_empty()).then(|| diagnostics.to_vec()),
+                )
+            }))
+    }
+
+    /// Forget the diagnostics of files removed from the index
+    pub fn remove_many(&self, paths: &[PathBuf]) -> StorageResult<()> {
+        self.store.remove_many(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::diagnostics::DiagnosticKind;
+    use tempfile::TempDir;
+
+    fn diagnostic(line: u32) -> ParseDiagnostic {
+        ParseDiagnostic {
+            kind: DiagnosticKind::Error,
+            range: Range::new(line, 0, line, 5),
+            text: "fn (".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_update_replaces_and_clears_files() {
+        let temp = TempDir::new().unwrap();
+        let store = DiagnosticsStore::new(temp.path());
+        assert!(store.load().unwrap().is_empty());
+
+        let broken = [diagnostic(3)];
+        store
+            .update([
+                ("src/a.rs".to_string(), &broken[..]),
+                ("src/b.rs".to_string(), &[][..]),
+            ])
+            .unwrap();
+        let all = store.load().unwrap();
+        assert_eq!(all.len(), 1);
+        assert!(DiagnosticsStore::is_low_confidence(
+            &all,
+            "src/a.rs",
+            &Range::new(2, 0, 6, 1)
+        ));
+        assert!(!DiagnosticsStore::is_low_confidence(
+            &all,
+            "src/a.rs",
+            &Range::new(10, 0, 12, 1)
+        ));
+
+        store.remove_many(&[PathBuf::from("src/a.rs")]).unwrap();
+        assert!(store.for_file("src/a.rs").unwrap().is_empty());
+        assert!(!store.path().exists());
+    }
+}
@@ -37,6 +37,16 @@ pub enum StorageError {
     #[error("Lock poisoned")]
     LockPoisoned,
 
+    #[error("Invalid snapshot: {0}")]
+    Snapshot(String),
+
+    #[error("Shared index unavailable: {0}")]
+    SharedIndex(String),
+
+    #[cfg(feature = "remote-sync")]
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
     #[error("Directory error: {0}")]
     Directory(#[from] OpenDirectoryError),
 
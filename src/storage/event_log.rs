@@ -0,0 +1,593 @@
+//! Append-only log of index mutations
+//!
+//! When `indexing.event_log` is enabled, every file the pipeline adds,
+//! re-indexes, or removes is recorded as one JSON line in
+//! `<index_path>/events.jsonl`, together with the symbols it gained or lost.
+//!
+//! The log is the source for three consumers:
+//! - Replay: fold events into per-file symbol sets ([`EventReplay`]) to rebuild
+//!   derived structures without touching Tantivy
+//! - Tailing: external tools poll [`EventLog::read_after`] with the last
+//!   `seq` they saw and the offset they stopped at (`codanna events --follow`)
+//! - Delta sync: ship the events after a known `seq` instead of a full index
+//!
+//! Re-indexing a file first removes its old symbols (CleanupStage) and then
+//! writes the new ones (IndexStage). Removals are held back until the matching
+//! write arrives so the pair is logged as a single `file_updated` event with
+//! the symbol delta. Removals nobody claims are logged as `file_removed` by
+//! [`EventLog::flush_pending`].
+
+use super::StorageResult;
+use crate::{FileId, Symbol, SymbolId, SymbolKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File name of the log inside the index directory
+pub const EVENT_LOG_FILE: &str = "events.jsonl";
+
+/// What happened to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    FileAdded,
+    FileUpdated,
+    FileRemoved,
+    /// The index was cleared (`index --force`); replay starts over
+    Reset,
+}
+
+/// Symbol as recorded in the log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolRef {
+    pub id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+}
+
+impl From<&Symbol> for SymbolRef {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            id: symbol.id,
+            name: symbol.name.to_string(),
+            kind: symbol.kind,
+        }
+    }
+}
+
+/// One line of the log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEvent {
+    /// Monotonic sequence number, starting at 1
+    pub seq: u64,
+    /// UTC seconds since the epoch
+    pub timestamp: u64,
+    pub kind: EventKind,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<FileId>,
+    /// Symbols that appeared. For updates, only those not present before.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<SymbolRef>,
+    /// Symbols that disappeared. For updates, only those not present after.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<SymbolRef>,
+}
+
+/// Removal waiting to be paired with a re-index of the same path
+struct PendingRemoval {
+    file_id: FileId,
+    symbols: Vec<SymbolRef>,
+}
+
+#[derive(Default)]
+struct LogState {
+    /// Next sequence number, read from the file on first append
+    next_seq: Option<u64>,
+    pending: HashMap<String, PendingRemoval>,
+}
+
+/// Writer and reader for `events.jsonl`
+///
+/// Appends are serialized within a process. Separate processes writing the
+/// same index (e.g. a watcher and a manual `codanna index`) each continue
+/// from the last sequence they saw, so readers should not assume `seq` is
+/// gap-free across them.
+pub struct EventLog {
+    path: PathBuf,
+    state: Mutex<LogState>,
+}
+
+impl EventLog {
+    /// Log stored in the index directory at `index_path`
+    ///
+    /// Nothing is created until the first event is appended.
+    pub fn new(index_path: &Path) -> Self {
+        Self {
+            path: index_path.join(EVENT_LOG_FILE),
+            state: Mutex::new(LogState::default()),
+        }
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Remember the symbols of a file that is being removed from the index
+    pub fn record_removal(&self, path: &str, file_id: FileId, symbols: &[Symbol]) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.pending.insert(
+            path.to_string(),
+            PendingRemoval {
+                file_id,
+                symbols: symbols.iter().map(SymbolRef::from).collect(),
+            },
+        );
+    }
+
+    /// Log a file written to the index with its symbols
+    ///
+    /// Logged as `file_updated` if the same path was removed earlier in this
+    /// run, otherwise as `file_added`.
+    pub fn record_indexed(
+        &self,
+        path: &str,
+        file_id: FileId,
+        symbols: &[&Symbol],
+    ) -> StorageResult<()> {
+        let symbols: Vec<SymbolRef> = symbols.iter().map(|s| SymbolRef::from(*s)).collect();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let event = match state.pending.remove(path) {
+            Some(previous) => {
+                let (added, removed) = symbol_delta(&previous.symbols, &symbols);
+                self.event(EventKind::FileUpdated, path, Some(file_id), added, removed)
+            }
+            None => self.event(
+                EventKind::FileAdded,
+                path,
+                Some(file_id),
+                symbols,
+                Vec::new(),
+            ),
+        };
+        self.append(&mut state, event)
+    }
+
+    /// Log every removal that was not followed by a re-index
+    pub fn flush_pending(&self) -> StorageResult<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut pending: Vec<_> = state.pending.drain().collect();
+        pending.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (path, removal) in pending {
+            let event = self.event(
+                EventKind::FileRemoved,
+                &path,
+                Some(removal.file_id),
+                Vec::new(),
+                removal.symbols,
+            );
+            self.append(&mut state, event)?;
+        }
+        Ok(())
+    }
+
+    /// Log that the index was cleared
+    pub fn record_reset(&self) -> StorageResult<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.pending.clear();
+        let event = self.event(EventKind::Reset, "", None, Vec::new(), Vec::new());
+        self.append(&mut state, event)
+    }
+
+    /// All events with `seq` greater than `since`
+    pub fn read_since(&self, since: u64) -> StorageResult<Vec<IndexEvent>> {
+        let (events, _) = self.read_from_offset(0)?;
+        Ok(events.into_iter().filter(|e| e.seq > since).collect())
+    }
+
+    /// Complete events starting at byte `offset`, and the offset to resume from
+    ///
+    /// A trailing line without a newline is still being written and is left
+    /// for the next call. A missing log reads as empty.
+    pub fn read_from_offset(&self, offset: u64) -> StorageResult<(Vec<IndexEvent>, u64)> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+            Err(e) => return Err(e.into()),
+        };
+        // The log was truncated or replaced; start over
+        let offset = if file.metadata()?.len() < offset {
+            0
+        } else {
+            offset
+        };
+
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(offset))?;
+
+        let mut events = Vec::new();
+        let mut position = offset;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            position += read as u64;
+
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let event = serde_json::from_str(trimmed).map_err(|e| {
+                super::StorageError::Serialization(format!(
+                    "{} at byte {}: {e}",
+                    self.path.display(),
+                    position - read as u64
+                ))
+            })?;
+            events.push(event);
+        }
+
+        Ok((events, position))
+    }
+
+    /// Events after `seq`, reading on from the `offset` an earlier read
+    /// stopped at, and the offset to resume from
+    ///
+    /// Pruning replaces the log with a shorter copy, so `offset` may point
+    /// into a different file. Unless reading there yields the event right
+    /// after `seq`, or nothing new, the log is read again from the start.
+    pub fn read_after(&self, seq: u64, offset: u64) -> StorageResult<(Vec<IndexEvent>, u64)> {
+        if let Ok((events, next)) = self.read_from_offset(offset) {
+            if events.first().is_none_or(|event| event.seq == seq + 1) {
+                return Ok((events, next));
+            }
+        }
+        let (mut events, next) = self.read_from_offset(0)?;
+        events.retain(|event| event.seq > seq);
+        Ok((events, next))
+    }
+
+    /// Drop events logged before `cutoff` (UTC seconds since the epoch)
+    ///
+    /// The newest event is always kept so sequence numbers continue after it.
+    /// Replaying a pruned log only reflects the events that remain. Returns
+    /// the number of events dropped.
+    pub fn prune_before(&self, cutoff: u64) -> StorageResult<usize> {
+        let _state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (events, _) = self.read_from_offset(0)?;
+        let Some(newest) = events.len().checked_sub(1) else {
+            return Ok(0);
+        };
+        let keep_from = events
+            .iter()
+            .position(|event| event.timestamp >= cutoff)
+            .unwrap_or(newest)
+            .min(newest);
+        if keep_from == 0 {
+            return Ok(0);
+        }
+
+        let mut contents = String::new();
+        for event in &events[keep_from..] {
+            let line = serde_json::to_string(event)
+                .map_err(|e| super::StorageError::Serialization(e.to_string()))?;
+            contents.push_str(&line);
+            contents.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(keep_from)
+    }
+
+    fn event(
+        &self,
+        kind: EventKind,
+        path: &str,
+        file_id: Option<FileId>,
+        added: Vec<SymbolRef>,
+        removed: Vec<SymbolRef>,
+    ) -> IndexEvent {
+        IndexEvent {
+            seq: 0,
+            timestamp: crate::utils::get_utc_timestamp(),
+            kind,
+            path: path.to_string(),
+            file_id,
+            added,
+            removed,
+        }
+    }
+
+    fn append(&self, state: &mut LogState, mut event: IndexEvent) -> StorageResult<()> {
+        let seq = match state.next_seq {
+            Some(seq) => seq,
+            None => self.last_seq()? + 1,
+        };
+        event.seq = seq;
+
+        let mut line = serde_json::to_string(&event)
+            .map_err(|e| super::StorageError::Serialization(e.to_string()))?;
+        line.push('\n');
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // One write per line so concurrent readers never see half an event
+        file.write_all(line.as_bytes())?;
+
+        state.next_seq = Some(seq + 1);
+        Ok(())
+    }
+
+    fn last_seq(&self) -> StorageResult<u64> {
+        let (events, _) = self.read_from_offset(0)?;
+        Ok(events.last().map(|e| e.seq).unwrap_or(0))
+    }
+}
+
+/// Symbols only in `after` and symbols only in `before`, matched by name and kind
+///
+/// Symbol ids change on every re-index, so they cannot identify a symbol
+/// across versions of a file.
+pub fn symbol_delta(
+    before: &[SymbolRef],
+    after: &[SymbolRef],
+) -> (Vec<SymbolRef>, Vec<SymbolRef>) {
+    let mut remaining: HashMap<(&str, SymbolKind), usize> = HashMap::new();
+    for symbol in before {
+        *remaining
+            .entry((symbol.name.as_str(), symbol.kind))
+            .or_default() += 1;
+    }
+
+    let mut added = Vec::new();
+    for symbol in after {
+        match remaining.get_mut(&(symbol.name.as_str(), symbol.kind)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => added.push(symbol.clone()),
+        }
+    }
+
+    let mut removed = Vec::new();
+    for symbol in before.iter().rev() {
+        if let Some(count) = remaining.get_mut(&(symbol.name.as_str(), symbol.kind)) {
+            if *count > 0 {
+                *count -= 1;
+                removed.push(symbol.clone());
+            }
+        }
+    }
+    removed.reverse();
+
+    (added, removed)
+}
+
+/// Index contents rebuilt from the log
+///
+/// Retained symbols keep the id they were logged with; updates only carry the
+/// symbols that changed.
+#[derive(Debug, Default, Clone)]
+pub struct EventReplay {
+    /// Symbols per file path
+    pub files: BTreeMap<String, Vec<SymbolRef>>,
+    /// Sequence number of the last applied event
+    pub last_seq: u64,
+}
+
+impl EventReplay {
+    /// Fold `events` in order
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a IndexEvent>) -> Self {
+        let mut replay = Self::default();
+        for event in events {
+            replay.apply(event);
+        }
+        replay
+    }
+
+    /// Apply a single event
+    pub fn apply(&mut self, event: &IndexEvent) {
+        match event.kind {
+            EventKind::FileAdded => {
+                self.files.insert(event.path.clone(), event.added.clone());
+            }
+            EventKind::FileUpdated => {
+                let symbols = self.files.entry(event.path.clone()).or_default();
+                for gone in &event.removed {
+                    if let Some(pos) = symbols
+                        .iter()
+                        .position(|s| s.name == gone.name && s.kind == gone.kind)
+                    {
+                        symbols.remove(pos);
+                    }
+                }
+                symbols.extend(event.added.iter().cloned());
+            }
+            EventKind::FileRemoved => {
+                self.files.remove(&event.path);
+            }
+            EventKind::Reset => self.files.clear(),
+        }
+        self.last_seq = event.seq;
+    }
+
+    /// Total symbols across all files
+    pub fn symbol_count(&self) -> usize {
+        self.files.values().map(Vec::len).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Range;
+    use tempfile::TempDir;
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 0, 0),
+        )
+    }
+
+    #[test]
+    fn test_reindex_is_logged_as_update_with_delta() {
+        let temp = TempDir::new().unwrap();
+        let log = EventLog::new(temp.path());
+        let file_id = FileId::new(1).unwrap();
+
+        let parse = symbol(1, "parse", SymbolKind::Function);
+        let old = symbol(2, "old_helper", SymbolKind::Function);
+        log.record_indexed("src/lib.rs", file_id, &[&parse, &old])
+            .unwrap();
+
+        log.record_removal("src/lib.rs", file_id, &[parse.clone(), old]);
+        let parse_again = symbol(3, "parse", SymbolKind::Function);
+        let new = symbol(4, "new_helper", SymbolKind::Function);
+        log.record_indexed("src/lib.rs", file_id, &[&parse_again, &new])
+            .unwrap();
+        log.flush_pending().unwrap();
+
+        let events = log.read_since(0).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, EventKind::FileAdded);
+        assert_eq!(events[1].kind, EventKind::FileUpdated);
+        assert_eq!(events[1].seq, 2);
+        assert_eq!(events[1].added.len(), 1);
+        assert_eq!(events[1].added[0].name, "new_helper");
+        assert_eq!(events[1].removed.len(), 1);
+        assert_eq!(events[1].removed[0].name, "old_helper");
+
+        let replay = EventReplay::from_events(&events);
+        let names: Vec<_> = replay.files["src/lib.rs"]
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["parse", "new_helper"]);
+    }
+
+    #[test]
+    fn test_unclaimed_removal_is_flushed_and_replayed() {
+        let temp = TempDir::new().unwrap();
+        let log = EventLog::new(temp.path());
+        let file_id = FileId::new(7).unwrap();
+        let gone = symbol(1, "Gone", SymbolKind::Struct);
+
+        log.record_indexed("a.rs", file_id, &[&gone]).unwrap();
+        log.record_removal("a.rs", file_id, &[gone]);
+        log.flush_pending().unwrap();
+        log.record_reset().unwrap();
+
+        let events = log.read_since(1).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, EventKind::FileRemoved);
+        assert_eq!(events[0].removed[0].name, "Gone");
+
+        let all = log.read_since(0).unwrap();
+        let before_reset = EventReplay::from_events(&all[..2]);
+        assert!(before_reset.files.is_empty());
+        assert_eq!(EventReplay::from_events(&all).last_seq, 3);
+    }
+
+    #[test]
+    fn test_sequence_continues_across_instances() {
+        let temp = TempDir::new().unwrap();
+        let file_id = FileId::new(1).unwrap();
+
+        EventLog::new(temp.path())
+            .record_indexed("a.rs", file_id, &[])
+            .unwrap();
+        EventLog::new(temp.path())
+            .record_indexed("b.rs", file_id, &[])
+            .unwrap();
+
+        let seqs: Vec<_> = EventLog::new(temp.path())
+            .read_since(0)
+            .unwrap()
+            .iter()
+            .map(|e| e.seq)
+            .collect();
+        assert_eq!(seqs, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_prune_keeps_recent_events_and_sequence() {
+        let temp = TempDir::new().unwrap();
+        let log = EventLog::new(temp.path());
+        let file_id = FileId::new(1).unwrap();
+        log.record_indexed("a.rs", file_id, &[]).unwrap();
+        log.record_indexed("b.rs", file_id, &[]).unwrap();
+
+        assert_eq!(log.prune_before(0).unwrap(), 0);
+        // Everything is older than a cutoff in the future, except the newest
+        assert_eq!(log.prune_before(u64::MAX).unwrap(), 1);
+        let events = log.read_since(0).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, "b.rs");
+
+        log.record_indexed("c.rs", file_id, &[]).unwrap();
+        assert_eq!(log.read_since(2).unwrap()[0].seq, 3);
+    }
+
+    #[test]
+    fn test_read_after_survives_prune() {
+        let temp = TempDir::new().unwrap();
+        let log = EventLog::new(temp.path());
+        let file_id = FileId::new(1).unwrap();
+        for path in ["a.rs", "b.rs", "c.rs"] {
+            log.record_indexed(path, file_id, &[]).unwrap();
+        }
+        let (events, offset) = log.read_after(0, 0).unwrap();
+        assert_eq!(events.len(), 3);
+
+        // The pruned copy replaces the file under the tailer's offset
+        log.prune_before(u64::MAX).unwrap();
+        for path in ["d.rs", "e.rs", "f.rs"] {
+            log.record_indexed(path, file_id, &[]).unwrap();
+        }
+        let (events, offset) = log.read_after(3, offset).unwrap();
+        let seqs: Vec<u64> = events.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![4, 5, 6]);
+
+        log.record_indexed("g.rs", file_id, &[]).unwrap();
+        let (events, _) = log.read_after(6, offset).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, "g.rs");
+    }
+
+    #[test]
+    fn test_read_from_offset_skips_partial_line() {
+        let temp = TempDir::new().unwrap();
+        let log = EventLog::new(temp.path());
+        log.record_indexed("a.rs", FileId::new(1).unwrap(), &[])
+            .unwrap();
+
+        let (events, offset) = log.read_from_offset(0).unwrap();
+        assert_eq!(events.len(), 1);
+
+        // Simulate a writer caught mid-line
+        let mut file = OpenOptions::new().append(true).open(log.path()).unwrap();
+        file.write_all(b"{\"seq\":2").unwrap();
+
+        let (events, next) = log.read_from_offset(offset).unwrap();
+        assert!(events.is_empty());
+        assert_eq!(next, offset);
+    }
+}
@@ -0,0 +1,138 @@
+//! Per-file records kept as one JSON map in the Tantivy directory
+//!
+//! Parse diagnostics, file aliases and symbol provenance each map indexed
+//! paths to a value in a small JSON file next to the Tantivy index. A write
+//! loads the whole map, applies its changes and writes the result to a
+//! temporary file that is renamed over the store, so readers never see a
+//! half-written file. Writes are serialized within a process, and a store
+//! whose map becomes empty is removed.
+
+use super::{StorageError, StorageResult};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles within a process
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Reader and writer for a JSON map from indexed path to `V`
+pub struct JsonFileStore<V> {
+    path: PathBuf,
+    value: PhantomData<V>,
+}
+
+impl<V: Serialize + DeserializeOwned + PartialEq> JsonFileStore<V> {
+    /// Store named `file_name` in the Tantivy directory of an index
+    pub fn new(tantivy_path: &Path, file_name: &str) -> Self {
+        Self {
+            path: tantivy_path.join(file_name),
+            value: PhantomData,
+        }
+    }
+
+    /// Path of the store file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Values of all files; a missing store reads as empty
+    pub fn load(&self) -> StorageResult<BTreeMap<String, V>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&contents)
+            .map_err(|e| StorageError::Serialization(format!("{}: {e}", self.path.display())))
+    }
+
+    /// Set or clear the value of each path; the store is only rewritten if
+    /// something changed
+    pub fn update(
+        &self,
+        files: impl IntoIterator<Item = (String, Option<V>)>,
+    ) -> StorageResult<()> {
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut all = self.load()?;
+        let mut changed = false;
+
+        for (path, value) in files {
+            match value {
+                Some(value) if all.get(&path) != Some(&value) => {
+                    all.insert(path, value);
+                    changed = true;
+                }
+                Some(_) => {}
+                None => changed |= all.remove(&path).is_some(),
+            }
+        }
+
+        if changed { self.save(&all) } else { Ok(()) }
+    }
+
+    /// Forget files removed from the index, in a single write
+    pub fn remove_many(&self, paths: &[PathBuf]) -> StorageResult<()> {
+        self.update(
+            paths
+                .iter()
+                .map(|path| (path.to_string_lossy().into_owned(), None)),
+        )
+    }
+
+    fn save(&self, all: &BTreeMap<String, V>) -> StorageResult<()> {
+        if all.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+
+        let json =
+            serde_json::to_string(all).map_err(|e| StorageError::Serialization(e.to_string()))?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_update_and_remove_many() {
+        let temp = TempDir::new().unwrap();
+        let store = JsonFileStore::<u32>::new(temp.path(), "counts.json");
+        assert!(store.load().unwrap().is_empty());
+
+        store
+            .update([
+                ("src/a.rs".to_string(), Some(1)),
+                ("src/b.rs".to_string(), Some(2)),
+                ("src/c.rs".to_string(), Some(3)),
+                ("src/d.rs".to_string(), None),
+            ])
+            .unwrap();
+        assert_eq!(store.load().unwrap().len(), 3);
+
+        store
+            .remove_many(&[PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")])
+            .unwrap();
+        let all = store.load().unwrap();
+        assert_eq!(
+            all.into_iter().collect::<Vec<_>>(),
+            vec![("src/c.rs".to_string(), 3)]
+        );
+
+        store.remove_many(&[PathBuf::from("src/c.rs")]).unwrap();
+        assert!(!store.path().exists());
+
+        std::fs::write(store.path(), "{not json").unwrap();
+        assert!(matches!(store.load(), Err(StorageError::Serialization(_))));
+    }
+}
@@ -27,6 +27,19 @@ pub struct IndexMetadata {
     /// Used to detect config changes and auto-sync on load
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub indexed_paths: Option<Vec<PathBuf>>,
+
+    /// Version of the codanna binary that last wrote the index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub written_by: Option<String>,
+
+    /// HEAD commit of the workspace repository at the last complete index
+    /// run; the base for `codanna index --git-delta`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed_commit: Option<String>,
+
+    /// Digest of the index contents, recorded by deterministic builds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_digest: Option<String>,
 }
 
 /// Describes where the index data came from
@@ -46,12 +59,15 @@ pub enum DataSource {
 impl Default for IndexMetadata {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: super::compat::INDEX_SCHEMA_VERSION,
             data_source: DataSource::Fresh,
             symbol_count: 0,
             file_count: 0,
             last_modified: crate::indexing::get_utc_timestamp(),
             indexed_paths: None,
+            written_by: None,
+            indexed_commit: None,
+            content_digest: None,
         }
     }
 }
@@ -75,6 +91,12 @@ impl IndexMetadata {
         self.last_modified = crate::indexing::get_utc_timestamp();
     }
 
+    /// Stamp the metadata with the schema version of the running binary
+    pub fn mark_current_schema(&mut self) {
+        self.version = super::compat::INDEX_SCHEMA_VERSION;
+        self.written_by = Some(env!("CARGO_PKG_VERSION").to_string());
+    }
+
     /// Save metadata to file
     pub fn save(&self, base_path: &Path) -> IndexResult<()> {
         let metadata_path = base_path.join("index.meta");
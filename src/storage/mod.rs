@@ -1,11 +1,30 @@
+pub mod aliases;
+pub mod blame;
+#[cfg(feature = "remote-sync")]
+pub mod bucket;
+pub mod compat;
+pub mod diagnostics;
 pub mod error;
+pub mod event_log;
+pub mod json_store;
 pub mod memory;
 pub mod metadata;
 pub mod metadata_keys;
 pub mod persistence;
+pub mod remotes;
+pub mod shared;
+pub mod snapshot;
 pub mod tantivy;
+pub use aliases::{AliasStore, FileAliases};
+pub use blame::{BlameStore, FileBlames};
+pub use compat::{Compatibility, INDEX_SCHEMA_VERSION};
+pub use diagnostics::{DiagnosticsStore, FileDiagnostics};
 pub use error::{StorageError, StorageResult};
+pub use event_log::{EventKind, EventLog, EventReplay, IndexEvent};
+pub use json_store::JsonFileStore;
 pub use metadata::{DataSource, IndexMetadata};
 pub use metadata_keys::MetadataKey;
 pub use persistence::IndexPersistence;
-pub use tantivy::{DocumentIndex, SearchResult};
+pub use remotes::{RemoteSource, RemoteSources, RemoteStore};
+pub use snapshot::SnapshotManifest;
+pub use tantivy::{CompactionStats, DocumentIndex, SearchResult};
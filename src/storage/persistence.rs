@@ -4,7 +4,7 @@
 //! All actual data is stored in Tantivy.
 
 use crate::indexing::facade::IndexFacade;
-use crate::storage::{DataSource, IndexMetadata};
+use crate::storage::{DataSource, EventLog, IndexMetadata};
 use crate::{IndexError, IndexResult, Settings};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -23,7 +23,7 @@ impl IndexPersistence {
 
     /// Get path for semantic search data
     fn semantic_path(&self) -> PathBuf {
-        self.base_path.join("semantic")
+        crate::vector::VectorNamespace::Code.dir(&self.base_path)
     }
 
     // =========================================================================
@@ -33,6 +33,20 @@ impl IndexPersistence {
     /// Load an IndexFacade from disk
     #[must_use = "Load errors should be handled appropriately"]
     pub fn load_facade(&self, settings: Arc<Settings>) -> IndexResult<IndexFacade> {
+        self.open_facade(settings, false)
+    }
+
+    /// Load an IndexFacade for one-shot read-only queries
+    ///
+    /// Semantic search is deferred until a query needs it, and the symbol and
+    /// file counts are not computed up front. The facade has no embedding
+    /// pool, so commands that re-index must use [`Self::load_facade`].
+    #[must_use = "Load errors should be handled appropriately"]
+    pub fn load_facade_lazy(&self, settings: Arc<Settings>) -> IndexResult<IndexFacade> {
+        self.open_facade(settings, true)
+    }
+
+    fn open_facade(&self, settings: Arc<Settings>, lazy: bool) -> IndexResult<IndexFacade> {
         // Load metadata to understand data sources
         let metadata = IndexMetadata::load(&self.base_path).ok();
 
@@ -52,7 +66,7 @@ impl IndexPersistence {
         let mut facade = IndexFacade::new(settings)?;
 
         // Display source info with fresh counts
-        if let Some(ref meta) = metadata {
+        if let Some(meta) = metadata.as_ref().filter(|_| !lazy) {
             let fresh_symbol_count = facade.symbol_count();
             let fresh_file_count = facade.file_count();
 
@@ -81,15 +95,21 @@ impl IndexPersistence {
             "[persistence] semantic path computed as: {}",
             semantic_path.display()
         );
-        match facade.load_semantic_search(&semantic_path) {
-            Ok(true) => {
-                tracing::debug!("[persistence] loaded semantic search for facade");
+        if lazy {
+            if facade.defer_semantic_search(&semantic_path) {
+                tracing::debug!("[persistence] deferred semantic search until first use");
             }
-            Ok(false) => {
-                tracing::debug!("[persistence] no semantic data found (this is optional)");
-            }
-            Err(e) => {
-                tracing::warn!("[persistence] failed to load semantic search: {e}");
+        } else {
+            match facade.load_semantic_search(&semantic_path) {
+                Ok(true) => {
+                    tracing::debug!("[persistence] loaded semantic search for facade");
+                }
+                Ok(false) => {
+                    tracing::debug!("[persistence] no semantic data found (this is optional)");
+                }
+                Err(e) => {
+                    tracing::warn!("[persistence] failed to load semantic search: {e}");
+                }
             }
         }
 
@@ -115,6 +135,7 @@ impl IndexPersistence {
             IndexMetadata::load(&self.base_path).unwrap_or_else(|_| IndexMetadata::new());
 
         metadata.update_counts(facade.symbol_count() as u32, facade.file_count());
+        metadata.mark_current_schema();
 
         // Update indexed paths for sync detection on next load
         let indexed_paths: Vec<PathBuf> = facade.get_indexed_paths().iter().cloned().collect();
@@ -131,6 +152,13 @@ impl IndexPersistence {
             timestamp: crate::indexing::get_utc_timestamp(),
         };
 
+        // Only deterministic builds have a digest worth comparing
+        metadata.content_digest = if facade.settings().indexing.deterministic {
+            facade.content_digest().ok()
+        } else {
+            None
+        };
+
         metadata.save(&self.base_path)?;
 
         // Update project registry with latest metadata
@@ -214,6 +242,12 @@ impl IndexPersistence {
                 std::thread::sleep(std::time::Duration::from_millis(100));
             }
         }
+
+        // Tell event log readers that everything before this point is gone
+        let event_log = EventLog::new(&self.base_path);
+        if event_log.path().exists() {
+            event_log.record_reset().map_err(std::io::Error::other)?;
+        }
         Ok(())
     }
 
@@ -307,4 +341,26 @@ mod tests {
         // Now has semantic data
         assert!(has_semantic_data(&persistence));
     }
+
+    #[test]
+    fn test_lazy_load_defers_semantic_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = Arc::new(Settings {
+            index_path: temp_dir.path().to_path_buf(),
+            ..Settings::default()
+        });
+        drop(IndexFacade::new(settings.clone()).unwrap());
+
+        // Unreadable semantic data: only a load would notice
+        let persistence = IndexPersistence::new(temp_dir.path().to_path_buf());
+        std::fs::create_dir_all(persistence.semantic_path()).unwrap();
+        std::fs::write(persistence.semantic_path().join("metadata.json"), "{}").unwrap();
+
+        let facade = persistence.load_facade_lazy(settings).unwrap();
+        assert!(facade.has_semantic_search());
+
+        // First use loads it, and the failure disables semantic search
+        assert_eq!(facade.semantic_search_embedding_count(), 0);
+        assert!(!facade.has_semantic_search());
+    }
 }
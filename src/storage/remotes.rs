@@ -0,0 +1,119 @@
+//! Provenance of remote repositories in the index
+//!
+//! `remotes.json` in the index directory records, for every repository
+//! indexed with `codanna index --remote`, where it came from: the URL, the
+//! requested revision, the commit actually indexed and the checkout holding
+//! its files. Records are keyed by the namespace derived from the URL and
+//! replaced each time the remote is indexed again.
+
+use super::{StorageError, StorageResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File name of the store inside the index directory
+pub const REMOTES_FILE: &str = "remotes.json";
+
+/// Where an indexed remote repository came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteSource {
+    /// URL the repository was cloned from
+    pub url: String,
+    /// Revision requested with `--rev`; the default branch when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    /// Commit that was indexed
+    pub commit: String,
+    /// Checkout the indexed files live under
+    pub path: PathBuf,
+    /// When the remote was last indexed, in seconds since the epoch
+    pub indexed_at: u64,
+}
+
+/// Provenance of every indexed remote, keyed by namespace
+pub type RemoteSources = BTreeMap<String, RemoteSource>;
+
+/// Serializes read-modify-write cycles within a process
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Reader and writer for `remotes.json`
+pub struct RemoteStore {
+    path: PathBuf,
+}
+
+impl RemoteStore {
+    /// Store in the index directory
+    pub fn new(index_path: &Path) -> Self {
+        Self {
+            path: index_path.join(REMOTES_FILE),
+        }
+    }
+
+    /// All records; a missing store reads as empty
+    pub fn load(&self) -> StorageResult<RemoteSources> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&contents)
+            .map_err(|e| StorageError::Serialization(format!("{}: {e}", self.path.display())))
+    }
+
+    /// Record of the remote indexed under `namespace`
+    pub fn get(&self, namespace: &str) -> StorageResult<Option<RemoteSource>> {
+        Ok(self.load()?.remove(namespace))
+    }
+
+    /// Set the record of `namespace`
+    pub fn record(&self, namespace: &str, source: RemoteSource) -> StorageResult<()> {
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut all = self.load()?;
+        all.insert(namespace.to_string(), source);
+
+        let json = serde_json::to_string_pretty(&all)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_replaces_provenance() {
+        let temp = TempDir::new().unwrap();
+        let store = RemoteStore::new(temp.path());
+        assert!(store.load().unwrap().is_empty());
+
+        let source = |rev: Option<&str>, commit: &str| RemoteSource {
+            url: "https://github.com/serde-rs/serde".to_string(),
+            rev: rev.map(str::to_string),
+            commit: commit.to_string(),
+            path: PathBuf::from("/cache/remotes/github.com-serde-rs-serde"),
+            indexed_at: 1_700_000_000,
+        };
+        store
+            .record("github.com-serde-rs-serde", source(None, "aaaa"))
+            .unwrap();
+        store
+            .record(
+                "github.com-serde-rs-serde",
+                source(Some("v1.0.200"), "bbbb"),
+            )
+            .unwrap();
+
+        assert_eq!(store.load().unwrap().len(), 1);
+        assert_eq!(
+            store.get("github.com-serde-rs-serde").unwrap(),
+            Some(source(Some("v1.0.200"), "bbbb"))
+        );
+        assert_eq!(store.get("github.com-tokio-rs-tokio").unwrap(), None);
+    }
+}
@@ -0,0 +1,289 @@
+//! Local changes layered over a read-only shared index
+//!
+//! With `indexing.shared_index` set, a team points every checkout at one
+//! prebuilt index directory, typically on a network share, instead of each
+//! developer indexing the same monorepo. The shared directory is never
+//! written. Tantivy reads segment files through an [`OverlayDirectory`] that
+//! looks in the local index first and falls back to the shared one, while
+//! new segments, commits and deletions land in the local index only. Files
+//! modified locally are re-indexed as usual and shadow their shared copies.
+//!
+//! The local index is seeded from the shared one on first open and again
+//! whenever the shared index is rebuilt: the Tantivy metadata and the side
+//! stores are copied, segments are not. `shared.json` records which shared
+//! index and which of its commits the local layer was seeded from.
+
+use super::event_log::EVENT_LOG_FILE;
+use super::{StorageError, StorageResult};
+use crate::indexing::calculate_hash;
+use crate::indexing::transaction::TRANSACTIONS_DIR;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tantivy::Index;
+use tantivy::directory::error::{DeleteError, LockError, OpenReadError, OpenWriteError};
+use tantivy::directory::{
+    Directory, DirectoryLock, FileHandle, Lock, MmapDirectory, WatchCallback, WatchHandle, WritePtr,
+};
+use walkdir::WalkDir;
+
+/// Marker in the local index root naming the shared index it layers over
+pub const SHARED_MARKER_FILE: &str = "shared.json";
+
+/// Top-level entries of the shared index that are never copied
+const LOCAL_ONLY: &[&str] = &[EVENT_LOG_FILE, "conversations", SHARED_MARKER_FILE];
+
+/// Shared index and commit the local layer was seeded from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SharedMarker {
+    path: PathBuf,
+    /// Hash of the shared `meta.json` at seeding time
+    generation: String,
+}
+
+/// Tantivy directory reading through to a shared base
+///
+/// Reads try the local delta first; writes and deletions only ever touch
+/// the delta, so deleting a segment that lives in the base is a no-op.
+#[derive(Debug, Clone)]
+pub struct OverlayDirectory {
+    base: MmapDirectory,
+    delta: MmapDirectory,
+}
+
+impl OverlayDirectory {
+    pub fn open(base: &Path, delta: &Path) -> StorageResult<Self> {
+        Ok(Self {
+            base: MmapDirectory::open(base)?,
+            delta: MmapDirectory::open(delta)?,
+        })
+    }
+}
+
+impl Directory for OverlayDirectory {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        match self.delta.get_file_handle(path) {
+            Err(OpenReadError::FileDoesNotExist(_)) => self.base.get_file_handle(path),
+            result => result,
+        }
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        match self.delta.delete(path) {
+            Err(DeleteError::FileDoesNotExist(_)) if self.base.exists(path).unwrap_or(false) => {
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        Ok(self.delta.exists(path)? || self.base.exists(path)?)
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        self.delta.open_write(path)
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        match self.delta.atomic_read(path) {
+            Err(OpenReadError::FileDoesNotExist(_)) => self.base.atomic_read(path),
+            result => result,
+        }
+    }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.delta.atomic_write(path, data)
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        self.delta.sync_directory()
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        self.delta.acquire_lock(lock)
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> tantivy::Result<WatchHandle> {
+        self.delta.watch(watch_callback)
+    }
+}
+
+/// Open the Tantivy index at `tantivy_path` layered over `shared_root`
+///
+/// `shared_root` is an index directory laid out like `index_path`. The local
+/// index is reseeded when it was seeded from another shared index or an
+/// older commit of this one, discarding local changes.
+pub fn open_layered(shared_root: &Path, tantivy_path: &Path) -> StorageResult<Index> {
+    let shared_tantivy = shared_root.join("tantivy");
+    let meta = fs::read_to_string(shared_tantivy.join("meta.json"))
+        .map_err(|e| StorageError::SharedIndex(format!("{}: {e}", shared_tantivy.display())))?;
+    let marker = SharedMarker {
+        path: shared_root.to_path_buf(),
+        generation: calculate_hash(&meta),
+    };
+
+    let local_root = tantivy_path.parent().unwrap_or(tantivy_path);
+    let marker_path = local_root.join(SHARED_MARKER_FILE);
+    let seeded = fs::read_to_string(&marker_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<SharedMarker>(&contents).ok());
+    if seeded.as_ref() != Some(&marker) || !tantivy_path.join("meta.json").exists() {
+        seed(shared_root, local_root, tantivy_path)?;
+        let json = serde_json::to_string_pretty(&marker)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        fs::write(&marker_path, json)?;
+        tracing::info!(
+            "[shared] seeded local index from {} (generation {})",
+            shared_root.display(),
+            &marker.generation[..marker.generation.len().min(12)]
+        );
+    }
+
+    let directory = OverlayDirectory::open(&shared_tantivy, tantivy_path)?;
+    Ok(Index::open(directory)?)
+}
+
+/// Replace the local index with the metadata and side stores of the shared one
+fn seed(shared_root: &Path, local_root: &Path, tantivy_path: &Path) -> StorageResult<()> {
+    if tantivy_path.exists() {
+        fs::remove_dir_all(tantivy_path)?;
+    }
+    fs::create_dir_all(tantivy_path)?;
+
+    let entries = WalkDir::new(shared_root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry
+                .path()
+                .strip_prefix(shared_root)
+                .map(|relative| !skipped(relative))
+                .unwrap_or(true)
+        });
+    for entry in entries {
+        let entry = entry.map_err(|e| StorageError::Io(e.into()))?;
+        let Ok(relative) = entry.path().strip_prefix(shared_root) else {
+            continue;
+        };
+        let target = local_root.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Entries of the shared index that stay where they are
+///
+/// Segment files are read in place through the overlay, and locks, journals,
+/// the event log and conversations belong to the machine that wrote them.
+fn skipped(relative: &Path) -> bool {
+    let mut components = relative.components().map(|c| c.as_os_str().to_str());
+    let Some(Some(top_level)) = components.next() else {
+        return false;
+    };
+    if top_level != "tantivy" {
+        return LOCAL_ONLY.contains(&top_level);
+    }
+    let Some(Some(name)) = components.next() else {
+        return false;
+    };
+    name == TRANSACTIONS_DIR || is_lock(name) || is_segment_file(name)
+}
+
+fn is_lock(name: &str) -> bool {
+    name.starts_with(".tantivy-") && name.ends_with(".lock")
+}
+
+/// Segment files are named after the segment's UUID, e.g. `<uuid>.idx`
+fn is_segment_file(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default();
+    stem.len() == 32 && stem.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{STORED, STRING, Schema};
+    use tantivy::{IndexSettings, TantivyDocument, doc};
+    use tempfile::TempDir;
+
+    fn count(index: &Index) -> u64 {
+        index.reader().unwrap().searcher().num_docs()
+    }
+
+    #[test]
+    fn test_local_changes_layer_over_shared_index() {
+        let shared = TempDir::new().unwrap();
+        let local = TempDir::new().unwrap();
+        let shared_tantivy = shared.path().join("tantivy");
+        let local_tantivy = local.path().join("tantivy");
+        fs::create_dir_all(&shared_tantivy).unwrap();
+        fs::write(shared.path().join("index.meta"), "{}").unwrap();
+        fs::write(shared.path().join(EVENT_LOG_FILE), "").unwrap();
+
+        let mut builder = Schema::builder();
+        let path = builder.add_text_field("path", STRING | STORED);
+        let schema = builder.build();
+        let base = Index::create(
+            MmapDirectory::open(&shared_tantivy).unwrap(),
+            schema,
+            IndexSettings::default(),
+        )
+        .unwrap();
+        let mut writer = base.writer::<TantivyDocument>(15_000_000).unwrap();
+        writer.add_document(doc!(path => "src/a.rs")).unwrap();
+        writer.add_document(doc!(path => "src/b.rs")).unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+        let shared_files = || {
+            let mut names: Vec<_> = fs::read_dir(&shared_tantivy)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect();
+            names.sort();
+            names
+        };
+        let before = shared_files();
+
+        // Re-index one file locally
+        let layered = open_layered(shared.path(), &local_tantivy).unwrap();
+        assert_eq!(count(&layered), 2);
+        assert!(local.path().join("index.meta").exists());
+        assert!(!local.path().join(EVENT_LOG_FILE).exists());
+        let mut writer = layered.writer::<TantivyDocument>(15_000_000).unwrap();
+        writer.delete_term(tantivy::Term::from_field_text(path, "src/a.rs"));
+        writer.add_document(doc!(path => "src/a.rs")).unwrap();
+        writer.add_document(doc!(path => "src/c.rs")).unwrap();
+        writer.commit().unwrap();
+        writer.wait_merging_threads().unwrap();
+        assert_eq!(count(&layered), 3);
+        assert_eq!(shared_files(), before);
+
+        // Local changes survive a reopen
+        let reopened = open_layered(shared.path(), &local_tantivy).unwrap();
+        assert_eq!(count(&reopened), 3);
+
+        // A rebuilt shared index replaces them
+        let mut writer = base.writer::<TantivyDocument>(15_000_000).unwrap();
+        writer.add_document(doc!(path => "src/d.rs")).unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+        let reseeded = open_layered(shared.path(), &local_tantivy).unwrap();
+        assert_eq!(count(&reseeded), 3);
+        let searcher = reseeded.reader().unwrap().searcher();
+        let query = tantivy::query::TermQuery::new(
+            tantivy::Term::from_field_text(path, "src/c.rs"),
+            tantivy::schema::IndexRecordOption::Basic,
+        );
+        assert_eq!(
+            searcher.search(&query, &tantivy::collector::Count).unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! Portable index snapshots
+//!
+//! A snapshot is a zstd-compressed tar archive of the index directory: the
+//! Tantivy index with its symbol and file tables, vector namespaces, parse
+//! diagnostics and `index.meta`. CI builds the index once and exports it;
+//! developers import the archive instead of indexing from scratch.
+//!
+//! The first entry is a [`SnapshotManifest`], so an import rejects a snapshot
+//! with an unreadable schema before unpacking anything. Indexed directories
+//! in `index.meta` are stored relative to the workspace root and re-anchored
+//! at the importing workspace. The event log, conversation history and lock
+//! files stay on the machine that wrote them.
+
+use super::event_log::EVENT_LOG_FILE;
+use super::{Compatibility, IndexMetadata, StorageError, StorageResult, compat};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Manifest entry at the start of every snapshot
+pub const MANIFEST_FILE: &str = "snapshot.json";
+
+/// Layout version of the archive itself, independent of the index schema
+const FORMAT_VERSION: u32 = 1;
+
+pub(super) const METADATA_FILE: &str = "index.meta";
+
+/// Top-level entries that belong to the local machine, not the index
+const LOCAL_ONLY: &[&str] = &[EVENT_LOG_FILE, "conversations"];
+
+/// Describes the index inside a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub format: u32,
+    /// Index schema version (see [`super::INDEX_SCHEMA_VERSION`])
+    pub schema_version: u32,
+    /// codanna version that exported the snapshot
+    pub written_by: String,
+    pub created_at: u64,
+    pub symbol_count: u32,
+    pub file_count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_digest: Option<String>,
+    /// Workspace root on the exporting machine; symbol paths start with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_root: Option<PathBuf>,
+}
+
+/// Write the index at `index_path` to `archive`
+///
+/// The archive is written next to its destination and renamed into place,
+/// so an interrupted export never leaves a truncated file behind.
+pub fn export(
+    index_path: &Path,
+    workspace_root: &Path,
+    archive: &Path,
+) -> StorageResult<SnapshotManifest> {
+    if !index_path.join("tantivy").join("meta.json").exists() {
+        return Err(StorageError::Snapshot(format!(
+            "no index found at {}",
+            index_path.display()
+        )));
+    }
+
+    let (metadata, manifest) = portable_metadata(index_path, workspace_root)?;
+
+    let parent = archive
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+    let staged = tempfile::NamedTempFile::new_in(parent)?;
+
+    let encoder = zstd::Encoder::new(BufWriter::new(staged.reopen()?), 0)?;
+    let mut builder = tar::Builder::new(encoder);
+    builder.mode(tar::HeaderMode::Deterministic);
+
+    append_json(&mut builder, MANIFEST_FILE, &manifest)?;
+    append_json(&mut builder, METADATA_FILE, &metadata)?;
+
+    let entries = WalkDir::new(index_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry
+                .path()
+                .strip_prefix(index_path)
+                .map(|relative| !is_local_only(relative))
+                .unwrap_or(true)
+        });
+    for entry in entries {
+        let entry = entry.map_err(|e| StorageError::Io(e.into()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(index_path) else {
+            continue;
+        };
+        if relative == Path::new(METADATA_FILE) {
+            continue;
+        }
+        builder.append_path_with_name(entry.path(), relative)?;
+    }
+
+    let encoder = builder.into_inner()?;
+    encoder.finish()?.into_inner().map_err(|e| e.into_error())?;
+    staged
+        .persist(archive)
+        .map_err(|e| StorageError::Io(e.error))?;
+
+    Ok(manifest)
+}
+
+/// Read the manifest of `archive` without unpacking it
+pub fn read_manifest(archive: &Path) -> StorageResult<SnapshotManifest> {
+    let mut reader = open_archive(archive)?;
+    next_manifest(&mut reader.entries()?)
+}
+
+/// Replace the index at `index_path` with the one in `archive`
+///
+/// Refuses to overwrite an existing index unless `replace` is set. The
+/// snapshot is unpacked into a sibling directory first and swapped in once
+/// complete; local-only files of the old index are carried over.
+pub fn import(
+    archive: &Path,
+    index_path: &Path,
+    workspace_root: &Path,
+    replace: bool,
+) -> StorageResult<SnapshotManifest> {
+    let exists = index_path.join("tantivy").join("meta.json").exists();
+    if exists && !replace {
+        return Err(StorageError::Snapshot(format!(
+            "an index already exists at {}; pass --force to replace it",
+            index_path.display()
+        )));
+    }
+
+    let staging = staging_dir(index_path, ".codanna-import-")?;
+    let manifest = unpack(archive, staging.path(), workspace_root)?;
+    install(staging, index_path)?;
+    Ok(manifest)
+}
+
+/// Unpack `archive` into the empty directory `dir` as a loadable index
+///
+/// Checks the manifest first, re-anchors indexed directories at
+/// `workspace_root` and migrates an older schema in place.
+pub fn unpack(
+    archive: &Path,
+    dir: &Path,
+    workspace_root: &Path,
+) -> StorageResult<SnapshotManifest> {
+    let mut reader = open_archive(archive)?;
+    let mut entries = reader.entries()?;
+    let manifest = next_manifest(&mut entries)?;
+    let compatibility = check_readable(&manifest)?;
+
+    for entry in entries {
+        let mut entry = entry?;
+        if !entry.unpack_in(dir)? {
+            return Err(StorageError::Snapshot(format!(
+                "entry {} points outside the index directory",
+                entry.path()?.display()
+            )));
+        }
+    }
+
+    finish_unpacked(dir, workspace_root, &compatibility)?;
+    Ok(manifest)
+}
+
+/// Index metadata with indexed directories relative to `workspace_root`,
+/// and the manifest describing it
+pub(super) fn portable_metadata(
+    index_path: &Path,
+    workspace_root: &Path,
+) -> StorageResult<(IndexMetadata, SnapshotManifest)> {
+    let mut metadata = load_metadata(index_path)?;
+    if let Some(paths) = metadata.indexed_paths.as_mut() {
+        for path in paths.iter_mut() {
+            if let Ok(relative) = path.strip_prefix(workspace_root) {
+                *path = relative.to_path_buf();
+            }
+        }
+    }
+
+    let manifest = SnapshotManifest {
+        format: FORMAT_VERSION,
+        schema_version: metadata.version,
+        written_by: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: crate::indexing::get_utc_timestamp(),
+        symbol_count: metadata.symbol_count,
+        file_count: metadata.file_count,
+        indexed_commit: metadata.indexed_commit.clone(),
+        content_digest: metadata.content_digest.clone(),
+        source_root: Some(workspace_root.to_path_buf()),
+    };
+    Ok((metadata, manifest))
+}
+
+/// Reject a snapshot this binary cannot load, telling what to do instead
+pub(super) fn check_readable(manifest: &SnapshotManifest) -> StorageResult<Compatibility> {
+    if manifest.format > FORMAT_VERSION {
+        return Err(StorageError::Snapshot(format!(
+            "snapshot format v{} was written by codanna {}; this binary reads up to v{FORMAT_VERSION}",
+            manifest.format, manifest.written_by
+        )));
+    }
+    let compatibility = Compatibility::of(&IndexMetadata {
+        version: manifest.schema_version,
+        written_by: Some(manifest.written_by.clone()),
+        ..IndexMetadata::new()
+    });
+    if let Some(guidance) = compatibility.guidance() {
+        return Err(StorageError::Snapshot(guidance));
+    }
+    Ok(compatibility)
+}
+
+/// Re-anchor indexed directories of the index unpacked in `dir` at
+/// `workspace_root` and migrate an older schema in place
+pub(super) fn finish_unpacked(
+    dir: &Path,
+    workspace_root: &Path,
+    compatibility: &Compatibility,
+) -> StorageResult<()> {
+    let mut metadata = load_metadata(dir)?;
+    if let Some(paths) = metadata.indexed_paths.as_mut() {
+        for path in paths.iter_mut() {
+            if path.is_relative() {
+                let absolute = workspace_root.join(&*path);
+                *path = absolute.canonicalize().unwrap_or(absolute);
+            }
+        }
+    }
+    metadata
+        .save(dir)
+        .map_err(|e| StorageError::Metadata(e.to_string()))?;
+    if matches!(compatibility, Compatibility::Migratable { .. }) {
+        compat::migrate(dir).map_err(|e| StorageError::Metadata(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Empty directory next to `index_path` to build a replacement index in
+pub(super) fn staging_dir(index_path: &Path, prefix: &str) -> StorageResult<tempfile::TempDir> {
+    let parent = index_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+    Ok(tempfile::Builder::new().prefix(prefix).tempdir_in(parent)?)
+}
+
+/// Move the index built in `staging` to `index_path`, carrying over the
+/// local-only files of the index it replaces
+pub(super) fn install(staging: tempfile::TempDir, index_path: &Path) -> StorageResult<()> {
+    if index_path.exists() {
+        for name in LOCAL_ONLY {
+            let local = index_path.join(name);
+            if local.exists() {
+                fs::rename(&local, staging.path().join(name))?;
+            }
+        }
+        swap_in(staging.keep(), index_path)
+    } else {
+        fs::rename(staging.keep(), index_path)?;
+        Ok(())
+    }
+}
+
+/// Replace `index_path` with `staged`, restoring the old index on failure
+fn swap_in(staged: PathBuf, index_path: &Path) -> StorageResult<()> {
+    let mut backup = index_path.as_os_str().to_owned();
+    backup.push(".replaced");
+    let backup = PathBuf::from(backup);
+    if backup.exists() {
+        fs::remove_dir_all(&backup)?;
+    }
+
+    fs::rename(index_path, &backup)?;
+    if let Err(e) = fs::rename(&staged, index_path) {
+        let _ = fs::rename(&backup, index_path);
+        let _ = fs::remove_dir_all(&staged);
+        return Err(e.into());
+    }
+    if let Err(e) = fs::remove_dir_all(&backup) {
+        tracing::warn!(
+            "[snapshot] could not remove previous index at {}: {e}",
+            backup.display()
+        );
+    }
+    Ok(())
+}
+
+pub(super) fn is_local_only(relative: &Path) -> bool {
+    let top_level = relative
+        .components()
+        .next()
+        .and_then(|component| component.as_os_str().to_str());
+    let is_lock = relative
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(".tantivy-") && name.ends_with(".lock"));
+    is_lock || top_level.is_some_and(|name| LOCAL_ONLY.contains(&name))
+}
+
+fn load_metadata(base_path: &Path) -> StorageResult<IndexMetadata> {
+    IndexMetadata::load(base_path).map_err(|e| StorageError::Metadata(e.to_string()))
+}
+
+fn open_archive(archive: &Path) -> StorageResult<tar::Archive<impl Read>> {
+    let file = File::open(archive)?;
+    let decoder = zstd::Decoder::with_buffer(BufReader::new(file))?;
+    Ok(tar::Archive::new(decoder))
+}
+
+fn next_manifest<R: Read>(entries: &mut tar::Entries<'_, R>) -> StorageResult<SnapshotManifest> {
+    let not_a_snapshot = || StorageError::Snapshot(format!("missing {MANIFEST_FILE}"));
+    let mut entry = entries.next().ok_or_else(not_a_snapshot)??;
+    if entry.path()? != Path::new(MANIFEST_FILE) {
+        return Err(not_a_snapshot());
+    }
+    let mut json = String::new();
+    entry.read_to_string(&mut json)?;
+    serde_json::from_str(&json).map_err(|e| StorageError::Serialization(e.to_string()))
+}
+
+fn append_json<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    value: &impl Serialize,
+) -> StorageResult<()> {
+    let bytes =
+        serde_json::to_vec_pretty(value).map_err(|e| StorageError::Serialization(e.to_string()))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, bytes.as_slice())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let ci = TempDir::new().unwrap();
+        let workspace = ci.path().join("repo");
+        let index = workspace.join(".codanna/index");
+        write(&index.join("tantivy/meta.json"), "{}");
+        write(&index.join("tantivy/abc.store"), "segment");
+        write(&index.join("tantivy/.tantivy-writer.lock"), "");
+        write(&index.join("semantic/segment_0.vec"), "vectors");
+        write(&index.join(EVENT_LOG_FILE), "ci history");
+        let mut metadata = IndexMetadata::new();
+        metadata.symbol_count = 42;
+        metadata.indexed_paths = Some(vec![workspace.join("src")]);
+        metadata.save(&index).unwrap();
+
+        let archive = ci.path().join("out/index.tar.zst");
+        let exported = export(&index, &workspace, &archive).unwrap();
+        assert_eq!(exported.symbol_count, 42);
+        assert_eq!(read_manifest(&archive).unwrap().symbol_count, 42);
+
+        // A developer machine with an older index and its own event log
+        let dev = TempDir::new().unwrap();
+        let workspace = dev.path().join("checkout");
+        let index = workspace.join(".codanna/index");
+        write(&index.join("tantivy/meta.json"), "{\"old\": true}");
+        write(&index.join(EVENT_LOG_FILE), "local history");
+
+        assert!(import(&archive, &index, &workspace, false).is_err());
+        import(&archive, &index, &workspace, true).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(index.join("tantivy/meta.json")).unwrap(),
+            "{}"
+        );
+        assert!(index.join("semantic/segment_0.vec").exists());
+        assert!(!index.join("tantivy/.tantivy-writer.lock").exists());
+        assert_eq!(
+            fs::read_to_string(index.join(EVENT_LOG_FILE)).unwrap(),
+            "local history"
+        );
+
+        let imported = IndexMetadata::load(&index).unwrap();
+        assert_eq!(imported.symbol_count, 42);
+        assert_eq!(imported.indexed_paths, Some(vec![workspace.join("src")]));
+    }
+}
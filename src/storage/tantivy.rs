@@ -4,11 +4,13 @@
 //! enabling semantic search across documentation, code, and symbols.
 
 use super::{MetadataKey, StorageError, StorageResult};
+use crate::config::CompressionLevel;
+use crate::indexing::shard::shard_of;
 use crate::relationship::RelationshipMetadata;
 use crate::vector::{ClusterId, EmbeddingGenerator, SegmentOrdinal, VectorId, VectorSearchEngine};
 use crate::{FileId, RelationKind, Relationship, SymbolId, SymbolKind};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::sync::{Arc, RwLock};
@@ -16,16 +18,35 @@ use tantivy::DocId;
 use tantivy::{
     Index, IndexReader, IndexSettings, IndexWriter, ReloadPolicy, TantivyDocument as Document,
     Term,
-    collector::TopDocs,
+    collector::{DocSetCollector, TopDocs},
     directory::MmapDirectory,
-    query::{BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser, TermQuery},
+    indexer::NoMergePolicy,
+    query::{
+        AllQuery, BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser, RegexQuery, TermQuery,
+        TermSetQuery,
+    },
     schema::{
         FAST, Field, IndexRecordOption, NumericOptions, STORED, STRING, Schema, SchemaBuilder,
         TextFieldIndexing, TextOptions, Value,
     },
+    store::{Compressor, ZstdCompressor},
     tokenizer::{NgramTokenizer, TextAnalyzer},
 };
 
+/// Settings for a new index whose stored fields are compressed at `level`
+pub fn docstore_settings(level: CompressionLevel) -> IndexSettings {
+    let docstore_compression = match level.zstd() {
+        Some(zstd_level) => Compressor::Zstd(ZstdCompressor {
+            compression_level: Some(zstd_level),
+        }),
+        None => Compressor::default(),
+    };
+    IndexSettings {
+        docstore_compression,
+        ..IndexSettings::default()
+    }
+}
+
 /// Schema fields for the document index
 #[derive(Debug)]
 pub struct IndexSchema {
@@ -49,6 +70,7 @@ pub struct IndexSchema {
     pub visibility: Field,
     pub scope_context: Field,
     pub language: Field, // Language identifier for the symbol
+    pub shard: Field,    // Top-level directory, see crate::indexing::shard
 
     // Relationship fields
     pub from_symbol_id: Field,
@@ -138,6 +160,7 @@ impl IndexSchema {
         let visibility = builder.add_u64_field("visibility", STORED);
         let scope_context = builder.add_text_field("scope_context", STRING | STORED);
         let language = builder.add_text_field("language", STRING | STORED | FAST);
+        let shard = builder.add_text_field("shard", STRING | STORED);
 
         // Relationship fields
         let from_symbol_id = builder.add_u64_field("from_symbol_id", indexed_u64_options.clone());
@@ -189,6 +212,7 @@ impl IndexSchema {
             visibility,
             scope_context,
             language,
+            shard,
             from_symbol_id,
             to_symbol_id,
             relation_kind,
@@ -392,6 +416,15 @@ pub struct TextHighlight {
     pub end: usize,
 }
 
+/// Segment counts before and after [`DocumentIndex::compact`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CompactionStats {
+    pub segments_before: usize,
+    pub segments_after: usize,
+    /// Deleted documents purged by the merge
+    pub deleted_docs: u64,
+}
+
 /// Document index for full-text search
 pub struct DocumentIndex {
     index: Index,
@@ -403,6 +436,8 @@ pub struct DocumentIndex {
     heap_size: usize,
     /// Maximum retry attempts for transient errors
     max_retry_attempts: u32,
+    /// Write with one indexing thread and no background merges
+    deterministic: bool,
     /// Optional path for vector storage files
     vector_storage_path: Option<PathBuf>,
     /// Optional vector search engine for semantic search
@@ -456,15 +491,19 @@ impl DocumentIndex {
         let heap_size = heap_size.clamp(10_000_000, 1_000_000_000); // 10MB-1GB
 
         let max_retry_attempts = settings.indexing.max_retry_attempts;
+        let deterministic = settings.indexing.deterministic;
 
         let (schema, index_schema) = IndexSchema::build();
 
         // Create or open the index
-        let index = if index_path.join("meta.json").exists() {
+        let index = if let Some(shared_root) = settings.shared_index_root() {
+            super::shared::open_layered(&shared_root, &index_path)?
+        } else if index_path.join("meta.json").exists() {
             Index::open_in_dir(&index_path)?
         } else {
             let dir = MmapDirectory::open(&index_path)?;
-            Index::create(dir, schema, IndexSettings::default())?
+            let index_settings = docstore_settings(settings.storage.compression.code_level);
+            Index::create(dir, schema, index_settings)?
         };
 
         // Register custom tokenizer for partial matching (ngram with min_gram=3, max_gram=10)
@@ -491,6 +530,7 @@ impl DocumentIndex {
             writer: RwLock::new(None),
             heap_size,
             max_retry_attempts,
+            deterministic,
             vector_storage_path: None,
             vector_engine: None,
             cluster_cache: Arc::new(RwLock::new(None)),
@@ -502,9 +542,20 @@ impl DocumentIndex {
     }
 
     /// Create index writer with retry logic for transient errors
+    ///
+    /// In deterministic mode a single indexing thread receives every document
+    /// and merges are left to `compact`, so segments hold the same documents
+    /// in the same order on every run.
     fn create_writer_with_retry(&self) -> Result<IndexWriter<Document>, tantivy::TantivyError> {
         for attempt in 0..self.max_retry_attempts {
-            match self.index.writer::<Document>(self.heap_size) {
+            let writer = if self.deterministic {
+                self.index
+                    .writer_with_num_threads::<Document>(1, self.heap_size)
+                    .inspect(|writer| writer.set_merge_policy(Box::new(NoMergePolicy)))
+            } else {
+                self.index.writer::<Document>(self.heap_size)
+            };
+            match writer {
                 Ok(writer) => return Ok(writer),
                 Err(e) => {
                     // Check for transient I/O errors using ErrorKind
@@ -921,6 +972,7 @@ impl DocumentIndex {
         doc.add_text(self.schema.name, name);
         doc.add_text(self.schema.name_text, name); // Also add to full-text searchable field
         doc.add_text(self.schema.file_path, file_path);
+        doc.add_text(self.schema.shard, shard_of(file_path));
         doc.add_u64(self.schema.line_number, line as u64);
         doc.add_u64(self.schema.column, column as u64);
         doc.add_u64(self.schema.end_line, end_line as u64);
@@ -1045,6 +1097,77 @@ impl DocumentIndex {
         Ok(())
     }
 
+    /// Drop the current batch without committing it
+    ///
+    /// Documents added or deleted since the last commit are discarded, along
+    /// with the counters and embeddings the batch had reserved.
+    pub fn abort_batch(&self) -> StorageResult<()> {
+        let mut writer_lock = self.writer.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(mut writer) = writer_lock.take() {
+            writer.rollback()?;
+            for counter in [&self.pending_symbol_counter, &self.pending_file_counter] {
+                if let Ok(mut pending_guard) = counter.lock() {
+                    *pending_guard = None;
+                }
+            }
+            if let Ok(mut pending) = self.pending_embeddings.lock() {
+                pending.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge all segments into one and purge deleted documents
+    ///
+    /// Every re-index adds a segment and marks the file's old documents as
+    /// deleted; Tantivy's merge policy only catches up with that gradually.
+    /// Fails while a batch is open rather than waiting for it.
+    pub fn compact(&self) -> StorageResult<CompactionStats> {
+        // Hold the batch slot so no batch starts while segments are merged
+        let writer_lock = self
+            .writer
+            .write()
+            .map_err(|_| StorageError::LockPoisoned)?;
+        if writer_lock.is_some() {
+            return Err(StorageError::General(
+                "cannot compact while an indexing batch is open".to_string(),
+            ));
+        }
+
+        let searcher = self.reader.searcher();
+        let segments_before = searcher.segment_readers().len();
+        let deleted_docs: u64 = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment| u64::from(segment.num_deleted_docs()))
+            .sum();
+        if segments_before <= 1 && deleted_docs == 0 {
+            return Ok(CompactionStats {
+                segments_before,
+                segments_after: segments_before,
+                deleted_docs,
+            });
+        }
+
+        let mut writer = self.create_writer_with_retry()?;
+        let segment_ids = self.index.searchable_segment_ids()?;
+        if !segment_ids.is_empty() {
+            writer.merge(&segment_ids).wait()?;
+        }
+        writer.garbage_collect_files().wait()?;
+        writer.wait_merging_threads()?;
+        drop(writer_lock);
+
+        self.reader.reload()?;
+        self.build_cluster_cache()?;
+
+        Ok(CompactionStats {
+            segments_before,
+            segments_after: self.reader.searcher().segment_readers().len(),
+            deleted_docs,
+        })
+    }
+
     /// Remove documents for a specific file
     pub fn remove_file_documents(&self, file_path: &str) -> StorageResult<()> {
         // Use existing batch writer if available, otherwise create temporary one
@@ -1075,6 +1198,26 @@ impl DocumentIndex {
         kind_filter: Option<SymbolKind>,
         module_filter: Option<&str>,
         language_filter: Option<&str>,
+    ) -> StorageResult<Vec<SearchResult>> {
+        self.search_in_shard(
+            query_str,
+            limit,
+            kind_filter,
+            module_filter,
+            language_filter,
+            None,
+        )
+    }
+
+    /// Search for documents, optionally within one top-level directory shard
+    pub fn search_in_shard(
+        &self,
+        query_str: &str,
+        limit: usize,
+        kind_filter: Option<SymbolKind>,
+        module_filter: Option<&str>,
+        language_filter: Option<&str>,
+        shard_filter: Option<&str>,
     ) -> StorageResult<Vec<SearchResult>> {
         let searcher = self.reader.searcher();
 
@@ -1185,6 +1328,14 @@ impl DocumentIndex {
             ));
         }
 
+        if let Some(shard) = shard_filter {
+            let term = Term::from_field_text(self.schema.shard, shard);
+            all_clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            ));
+        }
+
         let final_query = BooleanQuery::new(all_clauses);
 
         let top_docs = searcher.search(&final_query, &TopDocs::with_limit(limit))?;
@@ -1288,6 +1439,33 @@ impl DocumentIndex {
         Ok(searcher.num_docs())
     }
 
+    /// SHA-256 over the stored fields of every live document
+    ///
+    /// Independent of segment layout and document order: two indexes built
+    /// from the same files in deterministic mode have the same digest even
+    /// though Tantivy gives their segment files random names.
+    pub fn content_digest(&self) -> StorageResult<String> {
+        use sha2::{Digest, Sha256};
+        use tantivy::Document as _;
+
+        let searcher = self.reader.searcher();
+        let schema = self.index.schema();
+        let addresses = searcher.search(&AllQuery, &DocSetCollector)?;
+
+        let mut doc_hashes = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let doc = searcher.doc::<Document>(address)?;
+            doc_hashes.push(Sha256::digest(doc.to_json(&schema).as_bytes()));
+        }
+        doc_hashes.sort_unstable();
+
+        let mut hasher = Sha256::new();
+        for doc_hash in &doc_hashes {
+            hasher.update(doc_hash);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
     /// Clear all documents from the index
     pub fn clear(&self) -> StorageResult<()> {
         // Check if index has been initialized (has meta.json)
@@ -1498,6 +1676,56 @@ impl DocumentIndex {
         Ok(symbols)
     }
 
+    /// IDs of the symbols of `kind` in the files `file_paths`, either filter
+    /// skipped when `None`
+    ///
+    /// Only the fast `symbol_id` column is read, so this stays cheap when
+    /// most of the index matches. Used to narrow semantic search before
+    /// scoring.
+    pub fn find_symbol_ids(
+        &self,
+        kind: Option<SymbolKind>,
+        file_paths: Option<&[String]>,
+    ) -> StorageResult<HashSet<SymbolId>> {
+        let searcher = self.reader.searcher();
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(
+            Occur::Must,
+            Box::new(TermQuery::new(
+                Term::from_field_text(self.schema.doc_type, "symbol"),
+                IndexRecordOption::Basic,
+            )),
+        )];
+        if let Some(kind) = kind {
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.kind, &format!("{kind:?}")),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+        if let Some(file_paths) = file_paths {
+            let terms = file_paths
+                .iter()
+                .map(|path| Term::from_field_text(self.schema.file_path, path));
+            clauses.push((Occur::Must, Box::new(TermSetQuery::new(terms))));
+        }
+        let addresses = searcher.search(&BooleanQuery::from(clauses), &DocSetCollector)?;
+
+        let columns = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment| segment.fast_fields().u64("symbol_id"))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(addresses
+            .into_iter()
+            .filter_map(|address| {
+                let id = columns[address.segment_ord as usize].first(address.doc_id)?;
+                SymbolId::new(id as u32)
+            })
+            .collect())
+    }
+
     /// Find all symbols in a specific module/package
     ///
     /// Used for same-package symbol resolution (Java, Kotlin, etc.)
@@ -1630,7 +1858,7 @@ impl DocumentIndex {
         let module_path = doc
             .get_first(self.schema.module_path)
             .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+            .map(crate::types::intern);
 
         // Get visibility from stored field
         let visibility = doc
@@ -1719,14 +1947,14 @@ impl DocumentIndex {
                 end_line,
                 end_column: end_col,
             },
-            file_path: doc
-                .get_first(self.schema.file_path)
-                .and_then(|v| v.as_str())
-                .unwrap_or("<unknown>")
-                .into(),
+            file_path: crate::types::intern(
+                doc.get_first(self.schema.file_path)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("<unknown>"),
+            ),
             signature: signature.map(|s| s.into()),
             doc_comment: doc_comment.map(|s| s.into()),
-            module_path: module_path.map(|s| s.into()),
+            module_path,
             visibility,
             scope_context,
             language_id: {
@@ -1745,6 +1973,10 @@ impl DocumentIndex {
                             .and_then(|registry| registry.find_language_id(lang_str))
                     })
             },
+            tag: doc
+                .get_first(self.schema.context)
+                .and_then(|v| v.as_str())
+                .map(|s| s.into()),
         })
     }
 
@@ -1887,6 +2119,33 @@ impl DocumentIndex {
         Ok(())
     }
 
+    /// Replace every relationship of `kind` with `edges`, in one commit
+    ///
+    /// For kinds computed over the whole index after indexing, which are
+    /// rebuilt rather than updated.
+    pub fn replace_relationships_of_kind(
+        &self,
+        kind: RelationKind,
+        edges: &[(SymbolId, SymbolId, Relationship)],
+    ) -> StorageResult<()> {
+        self.start_batch()?;
+        {
+            let writer_lock = self.writer.read().map_err(|_| StorageError::LockPoisoned)?;
+            let writer = writer_lock.as_ref().ok_or(StorageError::NoActiveBatch)?;
+            writer.delete_term(Term::from_field_text(
+                self.schema.relation_kind,
+                &format!("{kind:?}"),
+            ));
+        }
+        for (from, to, relationship) in edges {
+            if let Err(e) = self.store_relationship(*from, *to, relationship) {
+                let _ = self.abort_batch();
+                return Err(e);
+            }
+        }
+        self.commit_batch()
+    }
+
     /// Count symbols
     pub fn count_symbols(&self) -> StorageResult<usize> {
         let searcher = self.reader.searcher();
@@ -1952,6 +2211,42 @@ impl DocumentIndex {
         Ok(paths)
     }
 
+    /// Paths of the indexed files in a top-level directory shard
+    pub fn get_shard_paths(&self, shard: &str) -> StorageResult<Vec<PathBuf>> {
+        let searcher = self.reader.searcher();
+        let query = BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.doc_type, "file_info"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.shard, shard),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+        ]);
+
+        // Every file of the shard, however many; a shard is cleared by this list
+        let addresses = searcher.search(&query, &DocSetCollector)?;
+        let mut paths = Vec::with_capacity(addresses.len());
+        for doc_address in addresses {
+            let doc: Document = searcher.doc(doc_address)?;
+            if let Some(path) = doc
+                .get_first(self.schema.file_path)
+                .and_then(|v| v.as_str())
+            {
+                paths.push(PathBuf::from(path));
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
     /// Get relationships from a symbol
     pub fn get_relationships_from(
         &self,
@@ -1998,8 +2293,13 @@ impl DocumentIndex {
                     reason: "not a valid u32".to_string(),
                 })?;
 
+            let weight = doc
+                .get_first(self.schema.relation_weight)
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0) as f32;
+
             // Extract metadata if present
-            let mut relationship = Relationship::new(kind);
+            let mut relationship = Relationship::new(kind).with_weight(weight);
 
             // Extract metadata fields
             if let Some(line) = doc
@@ -2036,32 +2336,43 @@ impl DocumentIndex {
         to_id: SymbolId,
         kind: RelationKind,
     ) -> StorageResult<Vec<(SymbolId, SymbolId, Relationship)>> {
+        self.get_relationships_to_page(to_id, kind, 0, 1000)
+    }
+
+    /// Count relationships pointing to a symbol without loading them
+    ///
+    /// Hub symbols (loggers, utility functions) can have tens of thousands of
+    /// incoming edges; callers use this to decide whether to page or summarize.
+    pub fn count_relationships_to(
+        &self,
+        to_id: SymbolId,
+        kind: RelationKind,
+    ) -> StorageResult<usize> {
         let searcher = self.reader.searcher();
-        let query = BooleanQuery::from(vec![
-            (
-                Occur::Must,
-                Box::new(TermQuery::new(
-                    Term::from_field_text(self.schema.doc_type, "relationship"),
-                    IndexRecordOption::Basic,
-                )) as Box<dyn Query>,
-            ),
-            (
-                Occur::Must,
-                Box::new(TermQuery::new(
-                    Term::from_field_u64(self.schema.to_symbol_id, to_id.0 as u64),
-                    IndexRecordOption::Basic,
-                )) as Box<dyn Query>,
-            ),
-            (
-                Occur::Must,
-                Box::new(TermQuery::new(
-                    Term::from_field_text(self.schema.relation_kind, &format!("{kind:?}")),
-                    IndexRecordOption::Basic,
-                )) as Box<dyn Query>,
-            ),
-        ]);
+        let query = self.relationships_to_query(to_id, kind);
+        let count = searcher.search(&query, &tantivy::collector::Count)?;
+        Ok(count)
+    }
 
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(1000))?;
+    /// Get one page of relationships to a symbol
+    ///
+    /// Pages are stable for a given index generation, so `offset` can be used
+    /// as a continuation cursor between queries.
+    pub fn get_relationships_to_page(
+        &self,
+        to_id: SymbolId,
+        kind: RelationKind,
+        offset: usize,
+        limit: usize,
+    ) -> StorageResult<Vec<(SymbolId, SymbolId, Relationship)>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let searcher = self.reader.searcher();
+        let query = self.relationships_to_query(to_id, kind);
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit).and_offset(offset))?;
         let mut relationships = Vec::new();
 
         for (_score, doc_address) in top_docs {
@@ -2108,6 +2419,33 @@ impl DocumentIndex {
         Ok(relationships)
     }
 
+    /// Build the query matching relationships of `kind` that point to `to_id`
+    fn relationships_to_query(&self, to_id: SymbolId, kind: RelationKind) -> BooleanQuery {
+        BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.doc_type, "relationship"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_u64(self.schema.to_symbol_id, to_id.0 as u64),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.relation_kind, &format!("{kind:?}")),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+        ])
+    }
+
     /// Get all relationships of a specific kind
     pub fn get_all_relationships_by_kind(
         &self,
@@ -2269,7 +2607,7 @@ impl DocumentIndex {
                 .as_ref()
                 .map(|s| s.as_ref())
                 .unwrap_or(""),
-            None, // context (old field, different from scope_context)
+            symbol.tag.as_deref(), // context holds the user query tag
             symbol.visibility,
             // NOTE: We clone scope_context here because ScopeContext now contains CompactString
             // (for parent_name) which doesn't implement Copy. This clone happens during indexing
@@ -2311,6 +2649,7 @@ impl DocumentIndex {
         doc.add_text(self.schema.doc_type, "file_info");
         doc.add_u64(self.schema.file_id, file_id.value() as u64);
         doc.add_text(self.schema.file_path, path);
+        doc.add_text(self.schema.shard, shard_of(path));
         doc.add_text(self.schema.file_hash, hash);
         doc.add_u64(self.schema.file_timestamp, timestamp);
 
@@ -2341,10 +2680,9 @@ impl DocumentIndex {
         let mut doc = Document::new();
         doc.add_text(self.schema.doc_type, "file_info");
         doc.add_u64(self.schema.file_id, registration.file_id.value() as u64);
-        doc.add_text(
-            self.schema.file_path,
-            registration.path.to_string_lossy().as_ref(),
-        );
+        let path = registration.path.to_string_lossy();
+        doc.add_text(self.schema.file_path, path.as_ref());
+        doc.add_text(self.schema.shard, shard_of(&path));
         // Hash is already a SHA256 hex string
         doc.add_text(self.schema.file_hash, &registration.content_hash);
         doc.add_u64(self.schema.file_timestamp, registration.timestamp);
@@ -2430,43 +2768,102 @@ impl DocumentIndex {
             let doc: Document = searcher.doc(doc_address).map_err(|e| {
                 StorageError::General(format!("Failed to retrieve import document: {e}"))
             })?;
+            imports.push(self.import_from_doc(&doc, file_id)?);
+        }
 
-            // Extract fields from document
-            let import_path = doc
-                .get_first(self.schema.import_path)
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| StorageError::General("Missing import_path".to_string()))?
-                .to_string();
+        Ok(imports)
+    }
 
-            let alias = doc
-                .get_first(self.schema.import_alias)
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
+    /// Imports whose path names `name` as its last segment, or that bind it
+    /// as an alias, across all files
+    ///
+    /// Catches `use a::b::Name`, `pub use a::b::Name` re-exports, `from a
+    /// import Name` and `import { Name } from './a'` style paths.
+    pub fn find_imports_of_name(&self, name: &str) -> StorageResult<Vec<crate::parsing::Import>> {
+        let pattern = format!("(.*[:./\\\\])?{}", regex::escape(name));
+        let by_path = RegexQuery::from_pattern(&pattern, self.schema.import_path)
+            .map_err(|e| StorageError::General(format!("Invalid import pattern: {e}")))?;
+        let query = BooleanQuery::new(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.doc_type, "import"),
+                    IndexRecordOption::Basic,
+                )),
+            ),
+            (
+                Occur::Must,
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Should, Box::new(by_path) as Box<dyn Query>),
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(
+                            Term::from_field_text(self.schema.import_alias, name),
+                            IndexRecordOption::Basic,
+                        )),
+                    ),
+                ])),
+            ),
+        ]);
 
-            let is_glob = doc
-                .get_first(self.schema.import_is_glob)
-                .and_then(|v| v.as_u64())
-                .map(|v| v == 1)
-                .unwrap_or(false);
+        let searcher = self.reader.searcher();
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(1000))
+            .map_err(|e| StorageError::General(format!("Import search failed: {e}")))?;
 
-            let is_type_only = doc
-                .get_first(self.schema.import_is_type_only)
+        let mut imports = Vec::new();
+        for (_score, doc_address) in top_docs {
+            let doc: Document = searcher.doc(doc_address).map_err(|e| {
+                StorageError::General(format!("Failed to retrieve import document: {e}"))
+            })?;
+            let file_id = doc
+                .get_first(self.schema.import_file_id)
                 .and_then(|v| v.as_u64())
-                .map(|v| v == 1)
-                .unwrap_or(false);
-
-            imports.push(crate::parsing::Import {
-                path: import_path,
-                alias,
-                file_id,
-                is_glob,
-                is_type_only,
-            });
+                .and_then(|id| FileId::new(id as u32))
+                .ok_or_else(|| StorageError::General("Missing import_file_id".to_string()))?;
+            imports.push(self.import_from_doc(&doc, file_id)?);
         }
-
         Ok(imports)
     }
 
+    fn import_from_doc(
+        &self,
+        doc: &Document,
+        file_id: FileId,
+    ) -> StorageResult<crate::parsing::Import> {
+        // Extract fields from document
+        let import_path = doc
+            .get_first(self.schema.import_path)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| StorageError::General("Missing import_path".to_string()))?
+            .to_string();
+
+        let alias = doc
+            .get_first(self.schema.import_alias)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let is_glob = doc
+            .get_first(self.schema.import_is_glob)
+            .and_then(|v| v.as_u64())
+            .map(|v| v == 1)
+            .unwrap_or(false);
+
+        let is_type_only = doc
+            .get_first(self.schema.import_is_type_only)
+            .and_then(|v| v.as_u64())
+            .map(|v| v == 1)
+            .unwrap_or(false);
+
+        Ok(crate::parsing::Import {
+            path: import_path,
+            alias,
+            file_id,
+            is_glob,
+            is_type_only,
+        })
+    }
+
     /// Delete all import documents for a file
     ///
     /// Used during file updates and deletions.
@@ -2529,7 +2926,6 @@ impl DocumentIndex {
     }
 
     /// Query all relationships from the index
-    #[allow(dead_code)]
     pub(crate) fn query_relationships(
         &self,
     ) -> StorageResult<Vec<(SymbolId, SymbolId, crate::Relationship)>> {
@@ -2592,6 +2988,7 @@ impl DocumentIndex {
                 "DefinedIn" => RelationKind::DefinedIn,
                 "References" => RelationKind::References,
                 "ReferencedBy" => RelationKind::ReferencedBy,
+                "Mirrors" => RelationKind::Mirrors,
                 _ => continue, // Skip unknown relation kinds
             };
 
@@ -2634,7 +3031,6 @@ impl DocumentIndex {
     }
 
     /// Query all file information from the index
-    #[allow(dead_code)]
     pub(crate) fn query_file_info(&self) -> StorageResult<Vec<(FileId, String, String, u64)>> {
         let searcher = self.reader.searcher();
         let query = TermQuery::new(
@@ -2752,6 +3148,31 @@ mod tests {
         assert!(index.vector_engine().is_none());
     }
 
+    #[test]
+    fn test_new_index_compresses_stored_fields_with_zstd() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut settings = crate::config::Settings::default();
+        settings.storage.compression.code_level = CompressionLevel::try_from(19).unwrap();
+        DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        let zstd = Compressor::Zstd(ZstdCompressor {
+            compression_level: Some(19),
+        });
+        let index = Index::open_in_dir(temp_dir.path()).unwrap();
+        assert_eq!(index.settings().docstore_compression, zstd);
+
+        // An existing index keeps the compression it was created with
+        settings.storage.compression.code_level = CompressionLevel::LZ4;
+        DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+        let index = Index::open_in_dir(temp_dir.path()).unwrap();
+        assert_eq!(index.settings().docstore_compression, zstd);
+
+        assert_eq!(
+            docstore_settings(CompressionLevel::LZ4).docstore_compression,
+            Compressor::default()
+        );
+    }
+
     #[test]
     fn test_schema_has_language_field() {
         let (schema, _) = IndexSchema::build();
@@ -2930,6 +3351,137 @@ mod tests {
         assert_eq!(r.weight, 0.8);
     }
 
+    #[test]
+    fn test_replace_relationships_of_kind() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+        let id = |value| SymbolId::new(value).unwrap();
+
+        index.start_batch().unwrap();
+        let calls = crate::Relationship::new(crate::RelationKind::Calls);
+        index.store_relationship(id(1), id(2), &calls).unwrap();
+        index.commit_batch().unwrap();
+
+        let mirrors = crate::Relationship::new(crate::RelationKind::Mirrors).with_weight(0.8);
+        for to in [3, 4] {
+            index
+                .replace_relationships_of_kind(
+                    crate::RelationKind::Mirrors,
+                    &[(id(1), id(to), mirrors.clone())],
+                )
+                .unwrap();
+        }
+
+        // Only the last set of the kind is kept, other kinds are untouched
+        let linked = index
+            .get_relationships_from(id(1), crate::RelationKind::Mirrors)
+            .unwrap();
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].1, id(4));
+        assert_eq!(linked[0].2.weight, 0.8);
+        assert_eq!(index.query_relationships().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_relationships_to_paging() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+
+        // A hub symbol called from 25 places
+        let hub = SymbolId::new(1).unwrap();
+        for caller in 2..27 {
+            let rel = crate::Relationship::new(crate::RelationKind::Calls);
+            index
+                .store_relationship(SymbolId::new(caller).unwrap(), hub, &rel)
+                .unwrap();
+        }
+
+        index.commit_batch().unwrap();
+
+        assert_eq!(
+            index
+                .count_relationships_to(hub, crate::RelationKind::Calls)
+                .unwrap(),
+            25
+        );
+        assert_eq!(
+            index
+                .count_relationships_to(hub, crate::RelationKind::Uses)
+                .unwrap(),
+            0
+        );
+
+        let first = index
+            .get_relationships_to_page(hub, crate::RelationKind::Calls, 0, 10)
+            .unwrap();
+        let second = index
+            .get_relationships_to_page(hub, crate::RelationKind::Calls, 10, 10)
+            .unwrap();
+        let last = index
+            .get_relationships_to_page(hub, crate::RelationKind::Calls, 20, 10)
+            .unwrap();
+        assert_eq!(first.len(), 10);
+        assert_eq!(second.len(), 10);
+        assert_eq!(last.len(), 5);
+
+        // Pages must not overlap
+        let mut callers: Vec<SymbolId> = first
+            .iter()
+            .chain(&second)
+            .chain(&last)
+            .map(|(from, _, _)| *from)
+            .collect();
+        callers.sort_by_key(|id| id.value());
+        callers.dedup();
+        assert_eq!(callers.len(), 25);
+    }
+
+    #[test]
+    fn test_find_symbol_ids_by_kind_and_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        let symbols = [
+            (1, SymbolKind::Function, "src/api/handler.rs"),
+            (2, SymbolKind::Struct, "src/api/handler.rs"),
+            (3, SymbolKind::Function, "src/db/pool.rs"),
+        ];
+        for (id, kind, path) in symbols {
+            let symbol = crate::Symbol::new(
+                SymbolId::new(id).unwrap(),
+                format!("symbol_{id}"),
+                kind,
+                FileId::new(id).unwrap(),
+                crate::Range::new(1, 0, 2, 0),
+            );
+            index.index_symbol(&symbol, path).unwrap();
+        }
+        index.commit_batch().unwrap();
+
+        let ids = |kind, paths: Option<&[String]>| {
+            let mut ids: Vec<u32> = index
+                .find_symbol_ids(kind, paths)
+                .unwrap()
+                .into_iter()
+                .map(|id| id.value())
+                .collect();
+            ids.sort();
+            ids
+        };
+        let api = ["src/api/handler.rs".to_string()];
+        assert_eq!(ids(None, None), [1, 2, 3]);
+        assert_eq!(ids(Some(SymbolKind::Function), None), [1, 3]);
+        assert_eq!(ids(None, Some(&api)), [1, 2]);
+        assert_eq!(ids(Some(SymbolKind::Function), Some(&api)), [1]);
+        assert!(ids(None, Some(&[])).is_empty());
+    }
+
     #[test]
     fn test_file_info_storage() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,49 @@
+//! Error types for module summaries
+
+use crate::io::exit_code::ExitCode;
+use std::{io, path::PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur while generating or caching module summaries
+#[derive(Error, Debug)]
+pub enum SummaryError {
+    #[error(
+        "Module summaries are disabled\nSuggestion: Set 'enabled = true' under [summary] in settings.toml"
+    )]
+    Disabled,
+
+    #[error(
+        "Summary endpoint {endpoint} failed: {reason}\nSuggestion: Check summary.endpoint and that the model server is running"
+    )]
+    Endpoint { endpoint: String, reason: String },
+
+    #[error(
+        "Unexpected response from {endpoint}: {reason}\nSuggestion: summary.endpoint must accept OpenAI-style chat completion requests"
+    )]
+    InvalidResponse { endpoint: String, reason: String },
+
+    #[error(
+        "Summary cache {path} is unreadable: {reason}\nSuggestion: Delete the file and run 'codanna summary' again"
+    )]
+    Cache { path: PathBuf, reason: String },
+
+    #[error("IO error: {0}\nSuggestion: Check that the index directory is writable")]
+    Io(#[from] io::Error),
+}
+
+/// Result type for summary operations
+pub type SummaryResult<T> = Result<T, SummaryError>;
+
+impl SummaryError {
+    /// Map summary errors to CLI exit codes
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            SummaryError::Disabled => ExitCode::ConfigError,
+            SummaryError::Endpoint { .. } | SummaryError::InvalidResponse { .. } => {
+                ExitCode::GeneralError
+            }
+            SummaryError::Cache { .. } => ExitCode::IndexCorrupted,
+            SummaryError::Io(_) => ExitCode::IoError,
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Natural-language module summaries
+//!
+//! `codanna summary` asks a configurable LLM endpoint for a short description
+//! of each indexed file and caches the result in `<index_path>/summaries.json`,
+//! keyed by the file's content hash. Later runs only regenerate summaries for
+//! files whose hash changed. Search results and the workspace map read the
+//! cache; nothing calls the endpoint at query time.
+
+pub mod error;
+
+pub use error::{SummaryError, SummaryResult};
+
+use crate::config::SummaryConfig;
+use crate::indexing::archive;
+use crate::indexing::facade::IndexFacade;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File name of the summary cache inside the index directory
+pub const SUMMARY_CACHE_FILE: &str = "summaries.json";
+
+const SYSTEM_PROMPT: &str = "You summarize source files for a code navigation tool. \
+Reply with one or two plain sentences describing what the file is responsible for. \
+Do not list every function and do not use markdown.";
+
+/// Cached summary for one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleSummary {
+    /// Content hash of the file when the summary was generated
+    pub hash: String,
+    pub summary: String,
+}
+
+/// Summaries keyed by indexed file path
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SummaryCache {
+    #[serde(skip)]
+    path: PathBuf,
+    pub entries: BTreeMap<String, ModuleSummary>,
+}
+
+impl SummaryCache {
+    /// Load the cache from an index directory. A missing file yields an empty cache.
+    pub fn load(index_path: &Path) -> SummaryResult<Self> {
+        let path = index_path.join(SUMMARY_CACHE_FILE);
+        let mut cache = match fs::read_to_string(&path) {
+            Ok(content) => {
+                serde_json::from_str::<SummaryCache>(&content).map_err(|e| SummaryError::Cache {
+                    path: path.clone(),
+                    reason: e.to_string(),
+                })?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => SummaryCache::default(),
+            Err(e) => return Err(e.into()),
+        };
+        cache.path = path;
+        Ok(cache)
+    }
+
+    /// Write the cache back to disk atomically.
+    pub fn save(&self) -> SummaryResult<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| SummaryError::Cache {
+            path: self.path.clone(),
+            reason: e.to_string(),
+        })?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Summary text for a file, if one has been generated
+    pub fn get(&self, file_path: &str) -> Option<&str> {
+        self.entries.get(file_path).map(|e| e.summary.as_str())
+    }
+
+    /// Whether the cached summary was generated from content with this hash
+    pub fn is_fresh(&self, file_path: &str, hash: &str) -> bool {
+        self.entries
+            .get(file_path)
+            .is_some_and(|entry| entry.hash == hash)
+    }
+
+    pub fn insert(&mut self, file_path: String, hash: String, summary: String) {
+        self.entries
+            .insert(file_path, ModuleSummary { hash, summary });
+    }
+
+    /// Drop summaries for files no longer in the index, returning how many were removed.
+    pub fn retain_paths(&mut self, indexed: &HashSet<String>) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| indexed.contains(path));
+        before - self.entries.len()
+    }
+
+    /// Group summaries by directory for the workspace map.
+    pub fn workspace_map(&self) -> BTreeMap<String, Vec<(String, &str)>> {
+        let mut map: BTreeMap<String, Vec<(String, &str)>> = BTreeMap::new();
+        for (path, entry) in &self.entries {
+            let path = Path::new(path);
+            let dir = path
+                .parent()
+                .map(|p| p.to_string_lossy().into_owned())
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| ".".to_string());
+            let file = path
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            map.entry(dir)
+                .or_default()
+                .push((file, entry.summary.as_str()));
+        }
+        map
+    }
+}
+
+/// Produces a summary for one file
+pub trait Summarizer {
+    fn summarize(&self, file_path: &str, symbols: &[String], source: &str)
+    -> SummaryResult<String>;
+}
+
+/// Summarizer backed by an OpenAI-compatible chat completions endpoint
+pub struct LlmSummarizer {
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    max_input_chars: usize,
+    timeout: Duration,
+}
+
+impl LlmSummarizer {
+    pub fn from_config(config: &SummaryConfig) -> Self {
+        Self {
+            endpoint: config.endpoint.clone(),
+            model: config.model.clone(),
+            api_key: std::env::var(&config.api_key_env)
+                .ok()
+                .filter(|k| !k.is_empty()),
+            max_input_chars: config.max_input_chars,
+            timeout: Duration::from_secs(config.timeout_secs),
+        }
+    }
+
+    fn prompt(&self, file_path: &str, symbols: &[String], source: &str) -> String {
+        let source = crate::parsing::safe_truncate_str(source, self.max_input_chars);
+        let mut prompt = format!("File: {file_path}\n");
+        if !symbols.is_empty() {
+            prompt.push_str(&format!("Symbols: {}\n", symbols.join(", ")));
+        }
+        prompt.push_str("\n```\n");
+        prompt.push_str(source);
+        prompt.push_str("\n```\n");
+        prompt
+    }
+}
+
+impl Summarizer for LlmSummarizer {
+    fn summarize(
+        &self,
+        file_path: &str,
+        symbols: &[String],
+        source: &str,
+    ) -> SummaryResult<String> {
+        self.complete(SYSTEM_PROMPT, &self.prompt(file_path, symbols, source), 200)
+    }
+}
+
+impl LlmSummarizer {
+    /// Reply of the endpoint to one user message, with whitespace collapsed
+    pub fn complete(&self, system: &str, user: &str, max_tokens: u32) -> SummaryResult<String> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "temperature": 0.2,
+            "max_tokens": max_tokens,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": user },
+            ],
+        });
+
+        let request = ureq::post(&self.endpoint)
+            .header("User-Agent", concat!("codanna/", env!("CARGO_PKG_VERSION")))
+            .config()
+            .timeout_global(Some(self.timeout))
+            .build();
+        let request = match &self.api_key {
+            Some(key) => request.header("Authorization", format!("Bearer {key}")),
+            None => request,
+        };
+
+        let mut response = request
+            .send_json(&body)
+            .map_err(|e| SummaryError::Endpoint {
+                endpoint: self.endpoint.clone(),
+                reason: e.to_string(),
+            })?;
+        let json: serde_json::Value =
+            response
+                .body_mut()
+                .read_json()
+                .map_err(|e| SummaryError::InvalidResponse {
+                    endpoint: self.endpoint.clone(),
+                    reason: e.to_string(),
+                })?;
+
+        parse_completion(&json).ok_or_else(|| SummaryError::InvalidResponse {
+            endpoint: self.endpoint.clone(),
+            reason: "missing choices[0].message.content".to_string(),
+        })
+    }
+}
+
+/// Extract the reply text from a chat completion response.
+fn parse_completion(json: &serde_json::Value) -> Option<String> {
+    let content = json
+        .pointer("/choices/0/message/content")?
+        .as_str()?
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!content.is_empty()).then_some(content)
+}
+
+/// Outcome of a summary refresh
+#[derive(Debug, Default)]
+pub struct RefreshStats {
+    pub generated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    /// Files whose summary could not be generated, with the reason
+    pub failed: Vec<(String, String)>,
+}
+
+/// Regenerate summaries for indexed files whose content hash changed.
+///
+/// With `force`, every file is summarized again. Summaries for files that
+/// left the index are dropped. The cache is not saved; call
+/// [`SummaryCache::save`] afterwards.
+pub fn refresh(
+    indexer: &IndexFacade,
+    cache: &mut SummaryCache,
+    summarizer: &dyn Summarizer,
+    force: bool,
+    mut on_progress: impl FnMut(&str),
+) -> RefreshStats {
+    let mut stats = RefreshStats::default();
+    let workspace_root = indexer.settings().workspace_root.clone();
+    let mut indexed = HashSet::new();
+
+    for path in indexer.get_all_indexed_paths() {
+        let path_str = path.to_string_lossy().into_owned();
+        indexed.insert(path_str.clone());
+
+        let Ok(Some((file_id, hash, _))) = indexer.document_index().get_file_info(&path_str) else {
+            continue;
+        };
+        if !force && cache.is_fresh(&path_str, &hash) {
+            stats.unchanged += 1;
+            continue;
+        }
+
+        let full_path = match &workspace_root {
+            Some(root) if path.is_relative() => root.join(&path),
+            _ => path.clone(),
+        };
+        let source = match archive::read_source(&full_path) {
+            Ok(source) => source,
+            Err(e) => {
+                stats.failed.push((path_str, e.to_string()));
+                continue;
+            }
+        };
+        let symbols: Vec<String> = indexer
+            .get_symbols_by_file(file_id)
+            .iter()
+            .map(|s| s.name.to_string())
+            .collect();
+
+        on_progress(&path_str);
+        match summarizer.summarize(&path_str, &symbols, &source) {
+            Ok(summary) => {
+                cache.insert(path_str, hash, summary);
+                stats.generated += 1;
+            }
+            Err(e) => stats.failed.push((path_str, e.to_string())),
+        }
+    }
+
+    stats.removed = cache.retain_paths(&indexed);
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_round_trip_and_freshness() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = SummaryCache::load(dir.path()).unwrap();
+        assert!(cache.entries.is_empty());
+
+        cache.insert(
+            "src/lib.rs".to_string(),
+            "abc".to_string(),
+            "Crate root.".to_string(),
+        );
+        cache.save().unwrap();
+
+        let cache = SummaryCache::load(dir.path()).unwrap();
+        assert_eq!(cache.get("src/lib.rs"), Some("Crate root."));
+        assert!(cache.is_fresh("src/lib.rs", "abc"));
+        assert!(!cache.is_fresh("src/lib.rs", "def"));
+        assert!(!cache.is_fresh("src/main.rs", "abc"));
+    }
+
+    #[test]
+    fn test_retain_and_workspace_map() {
+        let mut cache = SummaryCache::default();
+        for path in ["src/a.rs", "src/b.rs", "build.rs", "tests/gone.rs"] {
+            cache.insert(path.to_string(), "h".to_string(), format!("About {path}"));
+        }
+
+        let indexed: HashSet<String> = ["src/a.rs", "src/b.rs", "build.rs"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(cache.retain_paths(&indexed), 1);
+
+        let map = cache.workspace_map();
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec![".", "src"]);
+        assert_eq!(map["src"].len(), 2);
+        assert_eq!(map["."][0].0, "build.rs");
+    }
+
+    #[test]
+    fn test_parse_completion() {
+        let json = serde_json::json!({
+            "choices": [{ "message": { "content": "  Parses\n config files.  " } }]
+        });
+        assert_eq!(
+            parse_completion(&json).as_deref(),
+            Some("Parses config files.")
+        );
+        assert_eq!(
+            parse_completion(&serde_json::json!({ "choices": [] })),
+            None
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Symbol context aggregation for comprehensive metadata display
 
-use crate::relationship::RelationshipMetadata;
+use crate::relationship::{Relationship, RelationshipMetadata};
 use crate::{Symbol, Visibility};
 use bitflags::bitflags;
 use serde::Serialize;
@@ -38,6 +38,8 @@ pub struct SymbolRelationships {
     pub calls: Option<Vec<(Symbol, Option<RelationshipMetadata>)>>,
     /// What calls this symbol (with relationship metadata including call site location)
     pub called_by: Option<Vec<(Symbol, Option<RelationshipMetadata>)>>,
+    /// The same API in other languages (weight is the link's confidence)
+    pub mirrors: Option<Vec<(Symbol, Relationship)>>,
 }
 
 bitflags! {
@@ -125,16 +127,27 @@ impl SymbolContext {
             ));
         }
 
-        // Documentation preview
-        if let Some(doc) = self.symbol.as_doc_comment() {
-            let preview: Vec<&str> = doc.lines().take(2).collect();
-            if !preview.is_empty() {
-                output.push_str(&format!("{}Doc: {}", indent, preview.join(" ")));
-                if doc.lines().count() > 2 {
+        // Documentation summary, parameters and return value
+        if let Some(doc) = self.symbol.doc() {
+            if !doc.summary.is_empty() {
+                output.push_str(&format!("{}Doc: {}", indent, doc.summary));
+                if !doc.description.is_empty() {
                     output.push_str("...");
                 }
                 output.push('\n');
             }
+            if !doc.params.is_empty() {
+                output.push_str(&format!("{indent}Params:\n"));
+                for param in &doc.params {
+                    output.push_str(&format!(
+                        "{}  - {}: {}\n",
+                        indent, param.name, param.description
+                    ));
+                }
+            }
+            if let Some(returns) = &doc.returns {
+                output.push_str(&format!("{indent}Returns: {returns}\n"));
+            }
         }
     }
 
@@ -343,6 +356,41 @@ impl SymbolContext {
                 }
             }
         }
+
+        // Same API in other languages
+        if let Some(mirrors) = &self.relationships.mirrors {
+            if !mirrors.is_empty() {
+                output.push_str(&format!(
+                    "{}Mirrored in {} other language(s):\n",
+                    indent,
+                    mirrors.len()
+                ));
+                for (mirror, relationship) in mirrors {
+                    let language = mirror
+                        .language_id
+                        .map(|id| id.as_str())
+                        .unwrap_or("unknown");
+                    output.push_str(&format!(
+                        "{}  - {} ({:?}, {}) at {} [symbol_id:{}] confidence {:.2}",
+                        indent,
+                        mirror.name,
+                        mirror.kind,
+                        language,
+                        Self::symbol_location(mirror),
+                        mirror.id.value(),
+                        relationship.weight
+                    ));
+                    if let Some(context) = relationship
+                        .metadata
+                        .as_ref()
+                        .and_then(|meta| meta.context.as_deref())
+                    {
+                        output.push_str(&format!(" [{context}]"));
+                    }
+                    output.push('\n');
+                }
+            }
+        }
     }
 
     pub(crate) fn symbol_location(symbol: &Symbol) -> String {
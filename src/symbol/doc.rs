@@ -0,0 +1,693 @@
+//! Language-neutral documentation comments
+//!
+//! Parsers hand over doc comments in their language's own style: `///` with
+//! `# Arguments` sections in Rust, `@param` tags in Javadoc, JSDoc and PHPDoc,
+//! Sphinx, Google or NumPy docstrings in Python, XML tags in C#.
+//! [`DocComment::parse`] reads all of them into one structure, and
+//! [`normalize`] renders that structure as the text stored on
+//! [`Symbol`](super::Symbol), so search, embeddings and context output see the
+//! same shape in every language:
+//!
+//! ```text
+//! Summary paragraph.
+//!
+//! Further description.
+//!
+//! Parameters:
+//! - name: what it is
+//! Returns: what comes back
+//! ```
+//!
+//! The rendered form parses back to the same structure.
+
+use serde::Serialize;
+use std::fmt;
+
+/// A documented parameter
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DocParam {
+    pub name: String,
+    pub description: String,
+}
+
+/// A doc comment split into summary, description, parameters and return value
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DocComment {
+    /// First paragraph, on one line
+    pub summary: String,
+    /// Remaining prose, including sections such as examples
+    pub description: String,
+    pub params: Vec<DocParam>,
+    pub returns: Option<String>,
+}
+
+/// Normalize a doc comment to the common layout, `None` if nothing is left
+pub fn normalize(raw: &str) -> Option<String> {
+    let doc = DocComment::parse(raw);
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc.to_string())
+    }
+}
+
+impl DocComment {
+    /// Parse a doc comment in any supported style
+    ///
+    /// Comment markers (`///`, `/** */`, `"""`, `--`) are stripped first, so
+    /// both raw comments and the text parsers already cleaned are accepted.
+    pub fn parse(raw: &str) -> Self {
+        let text = strip_markers(raw);
+        if text.contains("<summary>") || text.contains("<param name=") {
+            parse_xml(&text)
+        } else {
+            parse_lines(&text)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_empty()
+            && self.description.is_empty()
+            && self.params.is_empty()
+            && self.returns.is_none()
+    }
+
+    /// Description of the parameter called `name`
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|param| param.name == name)
+            .map(|param| param.description.as_str())
+    }
+}
+
+impl fmt::Display for DocComment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut blocks = Vec::new();
+        if !self.summary.is_empty() {
+            blocks.push(self.summary.clone());
+        }
+        if !self.description.is_empty() {
+            blocks.push(self.description.clone());
+        }
+
+        let mut sections = Vec::new();
+        if !self.params.is_empty() {
+            sections.push("Parameters:".to_string());
+            for param in &self.params {
+                if param.description.is_empty() {
+                    sections.push(format!("- {}", param.name));
+                } else {
+                    sections.push(format!("- {}: {}", param.name, param.description));
+                }
+            }
+        }
+        if let Some(returns) = &self.returns {
+            sections.push(format!("Returns: {returns}"));
+        }
+        if !sections.is_empty() {
+            blocks.push(sections.join("\n"));
+        }
+
+        write!(f, "{}", blocks.join("\n\n"))
+    }
+}
+
+/// Remove comment delimiters and common indentation
+fn strip_markers(raw: &str) -> String {
+    let text = raw.trim();
+
+    let block = ["/**", "/*!", "/*"]
+        .iter()
+        .find_map(|open| text.strip_prefix(open))
+        .map(|inner| inner.strip_suffix("*/").unwrap_or(inner));
+    let docstring = ["\"\"\"", "'''"].iter().find_map(|quote| {
+        let inner = text.strip_prefix(quote)?;
+        Some(inner.strip_suffix(quote).unwrap_or(inner))
+    });
+
+    let lines: Vec<String> = if let Some(inner) = block {
+        inner
+            .lines()
+            .map(|line| {
+                let line = line.trim_start();
+                match line.strip_prefix('*') {
+                    Some(rest) => rest.strip_prefix(' ').unwrap_or(rest).to_string(),
+                    None => line.to_string(),
+                }
+            })
+            .collect()
+    } else if let Some(inner) = docstring {
+        inner.lines().map(str::to_string).collect()
+    } else {
+        let prefix = ["///", "//!", "//", "---", "--"]
+            .into_iter()
+            .find(|prefix| {
+                text.lines()
+                    .map(str::trim_start)
+                    .filter(|line| !line.is_empty())
+                    .all(|line| line.starts_with(prefix))
+            });
+        text.lines()
+            .map(|line| match prefix {
+                Some(prefix) => {
+                    let rest = line.trim_start().strip_prefix(prefix).unwrap_or("");
+                    rest.strip_prefix(' ').unwrap_or(rest).to_string()
+                }
+                None => line.to_string(),
+            })
+            .collect()
+    };
+
+    dedent(&lines)
+}
+
+/// Trim the first line and remove the indentation shared by the others
+fn dedent(lines: &[String]) -> String {
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line.trim()
+            } else if line.trim().is_empty() {
+                ""
+            } else {
+                line.get(indent..).unwrap_or(line.trim_start()).trim_end()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Body,
+    /// Parameters introduced by `@param`-style tags
+    TaggedParams,
+    /// Parameters listed under a heading such as `Args:` or `# Arguments`
+    ListedParams,
+    Returns,
+}
+
+enum Tag<'a> {
+    Param(String, &'a str),
+    Returns(&'a str),
+    Brief(&'a str),
+    /// Type annotations already present in the signature
+    Type,
+}
+
+fn parse_lines(text: &str) -> DocComment {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut body: Vec<&str> = Vec::new();
+    let mut params: Vec<DocParam> = Vec::new();
+    let mut returns: Vec<&str> = Vec::new();
+    let mut section = Section::Body;
+    let mut section_has_content = false;
+    let mut item_indent = 0;
+    let mut in_fence = false;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        i += 1;
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            section = Section::Body;
+            body.push(line);
+            continue;
+        }
+        if in_fence {
+            body.push(line);
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            // A heading may be followed by a blank line before its content
+            if section == Section::Body || section_has_content {
+                section = Section::Body;
+                body.push("");
+            }
+            continue;
+        }
+
+        if let Some(tag) = parse_tag(trimmed) {
+            match tag {
+                Tag::Param(name, description) => {
+                    params.push(DocParam {
+                        name,
+                        description: description.to_string(),
+                    });
+                    section = Section::TaggedParams;
+                }
+                Tag::Returns(description) => {
+                    returns.push(description);
+                    section = Section::Returns;
+                }
+                Tag::Brief(summary) => {
+                    body.push(summary);
+                    section = Section::Body;
+                }
+                Tag::Type => section = Section::Body,
+            }
+            section_has_content = true;
+            continue;
+        }
+
+        let underlined = lines
+            .get(i)
+            .is_some_and(|next| is_underline(next.trim()) && !is_underline(trimmed));
+        if let Some((heading, inline)) = parse_heading(trimmed, underlined) {
+            if underlined {
+                i += 1;
+            }
+            section = heading;
+            section_has_content = false;
+            if let Some(inline) = inline {
+                returns.push(inline);
+                section_has_content = true;
+            }
+            continue;
+        }
+
+        match section {
+            Section::Body => body.push(line),
+            Section::Returns => returns.push(trimmed),
+            Section::TaggedParams => {
+                if let Some(param) = params.last_mut() {
+                    append(param, trimmed);
+                }
+            }
+            Section::ListedParams => {
+                let indent = line.len() - line.trim_start().len();
+                let continues = section_has_content && indent > item_indent;
+                match param_item(trimmed).filter(|_| !continues) {
+                    Some((name, description)) => {
+                        item_indent = indent;
+                        params.push(DocParam {
+                            name,
+                            description: description.to_string(),
+                        });
+                    }
+                    None => match params.last_mut() {
+                        Some(param) => append(param, trimmed),
+                        None => body.push(line),
+                    },
+                }
+            }
+        }
+        section_has_content = true;
+    }
+
+    let (summary, description) = split_body(&body);
+    let returns = (!returns.is_empty()).then(|| join_words(&returns));
+
+    DocComment {
+        summary,
+        description,
+        params,
+        returns,
+    }
+}
+
+fn append(param: &mut DocParam, text: &str) {
+    if !param.description.is_empty() {
+        param.description.push(' ');
+    }
+    param.description.push_str(text);
+}
+
+fn join_words(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// First paragraph as the summary, the rest as the description
+fn split_body(body: &[&str]) -> (String, String) {
+    let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+    let mut in_fence = false;
+    for line in body {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if trimmed.is_empty() && !in_fence {
+            if !paragraphs.last().is_some_and(Vec::is_empty) {
+                paragraphs.push(Vec::new());
+            }
+        } else {
+            paragraphs.last_mut().unwrap().push(line.trim_end());
+        }
+    }
+    paragraphs.retain(|paragraph| !paragraph.is_empty());
+
+    let mut paragraphs = paragraphs.into_iter();
+    let summary = paragraphs
+        .next()
+        .map(|first| join_words(&first))
+        .unwrap_or_default();
+    let description = paragraphs
+        .map(|paragraph| paragraph.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (summary, description)
+}
+
+fn parse_tag(line: &str) -> Option<Tag<'_>> {
+    // Sphinx: `:param name: text`, `:returns: text`, `:type name:`, `:rtype:`
+    if let Some(rest) = line.strip_prefix(':') {
+        let (field, text) = rest.split_once(':')?;
+        let mut words = field.split_whitespace();
+        let text = text.trim();
+        return match words.next()? {
+            "param" | "parameter" | "arg" | "argument" | "key" | "keyword" => {
+                let name = words.last()?;
+                Some(Tag::Param(name.to_string(), text))
+            }
+            "returns" | "return" => Some(Tag::Returns(text)),
+            "type" | "rtype" => Some(Tag::Type),
+            _ => None,
+        };
+    }
+
+    // Javadoc, JSDoc, PHPDoc, LuaLS and Doxygen: `@param name text`, `\param name text`
+    let rest = line.strip_prefix('@').or_else(|| line.strip_prefix('\\'))?;
+    let (tag, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let text = text.trim();
+    match tag {
+        "param" | "arg" | "argument" | "param[in]" | "param[out]" | "param[in,out]" => {
+            let text = skip_braced_type(text);
+            let (mut name, mut text) = split_word(text);
+            // PHPDoc puts the type first: `@param int $count`
+            let (next, after) = split_word(text);
+            if !name.starts_with('$') && next.starts_with('$') {
+                name = next;
+                text = after;
+            }
+            let name = name
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split('=')
+                .next()
+                .unwrap_or_default();
+            if name.is_empty() {
+                return None;
+            }
+            let text = text.strip_prefix("- ").unwrap_or(text);
+            Some(Tag::Param(name.to_string(), text.trim()))
+        }
+        "return" | "returns" | "result" => Some(Tag::Returns(skip_braced_type(text))),
+        "brief" | "summary" => Some(Tag::Brief(text)),
+        _ => None,
+    }
+}
+
+/// Skip a JSDoc `{Type}` in front of the text
+fn skip_braced_type(text: &str) -> &str {
+    if !text.starts_with('{') {
+        return text;
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return text[i + 1..].trim_start();
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+fn split_word(text: &str) -> (&str, &str) {
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (text, ""),
+    }
+}
+
+fn is_underline(line: &str) -> bool {
+    line.len() >= 3 && line.chars().all(|c| c == '-' || c == '=')
+}
+
+/// Recognize `Args:`, `# Arguments`, `Returns: text` and NumPy headings
+fn parse_heading(line: &str, underlined: bool) -> Option<(Section, Option<&str>)> {
+    let hashed = line.starts_with('#');
+    let line = line.trim_start_matches('#').trim();
+    let (word, inline) = match line.split_once(':') {
+        Some((word, inline)) => (word.trim(), Some(inline.trim())),
+        None => (line, None),
+    };
+    if !hashed && !underlined && inline.is_none() {
+        return None;
+    }
+
+    match word.to_ascii_lowercase().as_str() {
+        "args" | "arguments" | "parameters" | "params" | "parameter" => {
+            // `Args: x` is prose, not a heading
+            if inline.is_some_and(|text| !text.is_empty()) {
+                None
+            } else {
+                Some((Section::ListedParams, None))
+            }
+        }
+        "returns" | "return" | "return value" => {
+            Some((Section::Returns, inline.filter(|text| !text.is_empty())))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a parameter list item such as `* `name` - text`, `name (int): text`
+/// or `- name: text`
+fn param_item(line: &str) -> Option<(String, &str)> {
+    let bulleted = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet));
+    let rest = bulleted.unwrap_or(line).trim_start();
+
+    let (name, after) = match rest.strip_prefix('`') {
+        Some(quoted) => {
+            let end = quoted.find('`')?;
+            (&quoted[..end], &quoted[end + 1..])
+        }
+        None => {
+            let end = rest
+                .find(|c: char| c == ':' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        }
+    };
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '*' | '.'));
+    if !valid {
+        return None;
+    }
+
+    let mut text = after.trim_start();
+    let mut separated = false;
+    if let Some(typed) = text.strip_prefix('(') {
+        // Google style: `name (int): text`
+        text = typed
+            .split_once(')')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+        separated = true;
+    }
+    for separator in [":", "--", "-", "\u{2014}"] {
+        if let Some(rest) = text.strip_prefix(separator) {
+            text = rest.trim_start();
+            separated = true;
+            break;
+        }
+    }
+
+    // An unmarked line needs a separator, otherwise it is prose
+    if bulleted.is_none() && !separated {
+        return None;
+    }
+    Some((name.to_string(), text))
+}
+
+/// Parse C# XML documentation
+fn parse_xml(text: &str) -> DocComment {
+    let summary = element(text, "summary").map(|inner| collapse(&strip_tags(inner)));
+    let returns = element(text, "returns").map(|inner| collapse(&strip_tags(inner)));
+    let description = element(text, "remarks")
+        .map(|inner| strip_tags(inner).trim().to_string())
+        .unwrap_or_default();
+
+    let mut params = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("<param name=\"") {
+        rest = &rest[start + "<param name=\"".len()..];
+        let Some((name, after)) = rest.split_once('"') else {
+            break;
+        };
+        let Some(open_end) = after.find('>') else {
+            break;
+        };
+        let after = &after[open_end + 1..];
+        let inner = after.find("</param>").map_or("", |close| &after[..close]);
+        params.push(DocParam {
+            name: name.to_string(),
+            description: collapse(&strip_tags(inner)),
+        });
+        rest = after;
+    }
+
+    match summary {
+        Some(summary) => DocComment {
+            summary,
+            description,
+            params,
+            returns: returns.filter(|text| !text.is_empty()),
+        },
+        None => {
+            let mut doc = parse_lines(&strip_tags(text));
+            doc.params = params;
+            doc.returns = returns.filter(|text| !text.is_empty());
+            doc
+        }
+    }
+}
+
+fn element<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let start = text.find(&format!("<{name}>"))? + name.len() + 2;
+    let end = text[start..].find(&format!("</{name}>"))? + start;
+    Some(&text[start..end])
+}
+
+/// Drop XML tags, keeping the target of `<see cref="X"/>` and `<paramref name="x"/>`
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('<') {
+        out.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            rest = &rest[open..];
+            break;
+        };
+        let tag = &rest[open + 1..open + close];
+        if tag.ends_with('/') {
+            let target = ["cref=\"", "name=\"", "langword=\""]
+                .iter()
+                .find_map(|attr| tag.split_once(attr))
+                .and_then(|(_, value)| value.split_once('"'))
+                .map(|(value, _)| value);
+            if let Some(target) = target {
+                out.push_str(target.rsplit(':').next().unwrap_or(target));
+            }
+        }
+        rest = &rest[open + close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_sections() {
+        let doc = DocComment::parse(
+            "/// Parse a file.\n///\n/// Uses the cache.\n///\n/// # Arguments\n///\n/// * `path` - File to read\n///   from disk\n///\n/// # Returns\n///\n/// The parsed symbols\n///\n/// # Examples\n///\n/// ```\n/// # use x;\n/// parse(\"a\");\n/// ```",
+        );
+        assert_eq!(doc.summary, "Parse a file.");
+        assert_eq!(doc.param("path"), Some("File to read from disk"));
+        assert_eq!(doc.returns.as_deref(), Some("The parsed symbols"));
+        assert!(doc.description.starts_with("Uses the cache.\n\n# Examples"));
+        assert!(doc.description.contains("# use x;"));
+    }
+
+    #[test]
+    fn test_tagged_styles() {
+        let javadoc = DocComment::parse(
+            "/**\n * Adds two numbers.\n * @param {number} a first operand\n * @param b second\n *        operand\n * @returns {number} the sum\n */",
+        );
+        assert_eq!(javadoc.summary, "Adds two numbers.");
+        assert_eq!(javadoc.param("a"), Some("first operand"));
+        assert_eq!(javadoc.param("b"), Some("second operand"));
+        assert_eq!(javadoc.returns.as_deref(), Some("the sum"));
+
+        let php = DocComment::parse("Count items.\n@param int $limit Upper bound\n@return int");
+        assert_eq!(php.param("$limit"), Some("Upper bound"));
+
+        let sphinx = DocComment::parse(
+            "\"\"\"Load settings.\n\n    :param path: Where to look\n    :type path: str\n    :returns: The settings\n    \"\"\"",
+        );
+        assert_eq!(sphinx.summary, "Load settings.");
+        assert_eq!(sphinx.param("path"), Some("Where to look"));
+        assert_eq!(sphinx.returns.as_deref(), Some("The settings"));
+    }
+
+    #[test]
+    fn test_python_sections() {
+        let google = DocComment::parse(
+            "Fetch rows.\n\nArgs:\n    query (str): SQL to run.\n    limit: Maximum rows,\n        or None.\n\nReturns:\n    list: The rows.",
+        );
+        assert_eq!(google.summary, "Fetch rows.");
+        assert_eq!(google.param("query"), Some("SQL to run."));
+        assert_eq!(google.param("limit"), Some("Maximum rows, or None."));
+        assert_eq!(google.returns.as_deref(), Some("list: The rows."));
+        assert!(google.description.is_empty());
+
+        let numpy = DocComment::parse(
+            "Scale values.\n\nParameters\n----------\nfactor : float\n    Multiplier.\n\nReturns\n-------\nndarray",
+        );
+        assert_eq!(numpy.param("factor"), Some("float Multiplier."));
+        assert_eq!(numpy.returns.as_deref(), Some("ndarray"));
+    }
+
+    #[test]
+    fn test_csharp_xml() {
+        let doc = DocComment::parse(
+            "<summary>\nGets the <see cref=\"T:System.String\"/> name.\n</summary>\n<param name=\"id\">The <paramref name=\"id\"/> to find.</param>\n<returns>The name.</returns>",
+        );
+        assert_eq!(doc.summary, "Gets the System.String name.");
+        assert_eq!(doc.param("id"), Some("The id to find."));
+        assert_eq!(doc.returns.as_deref(), Some("The name."));
+    }
+
+    #[test]
+    fn test_normalized_form_round_trips() {
+        let doc = DocComment::parse(
+            "/// Open a store.\n/// @param path Directory\n/// @return The store",
+        );
+        let normalized = doc.to_string();
+        assert_eq!(
+            normalized,
+            "Open a store.\n\nParameters:\n- path: Directory\nReturns: The store"
+        );
+        assert_eq!(DocComment::parse(&normalized), doc);
+
+        assert_eq!(normalize("Plain text."), Some("Plain text.".to_string()));
+        assert_eq!(normalize("/** */"), None);
+    }
+}
@@ -1,9 +1,15 @@
 pub mod context;
+pub mod doc;
+pub mod outline;
+pub mod source;
+
+pub use doc::{DocComment, DocParam};
 
 use crate::parsing::registry::LanguageId;
-use crate::types::{CompactString, FileId, Range, SymbolId, SymbolKind, compact_string};
+use crate::types::{CompactString, FileId, Range, SymbolId, SymbolKind, compact_string, intern};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::Arc;
 
 /// Visibility of a symbol
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -57,13 +63,13 @@ pub struct Symbol {
     pub kind: SymbolKind,
     pub file_id: FileId,
     pub range: Range,
-    /// Clean file path without line numbers (e.g., "src/lib.rs")
-    pub file_path: Box<str>,
+    /// Clean file path without line numbers (e.g., "src/lib.rs"), interned
+    pub file_path: Arc<str>,
     pub signature: Option<Box<str>>,
-    /// Documentation comment extracted from source (e.g., /// or /** */ in Rust)
+    /// Documentation comment extracted from source, normalized by [`doc::normalize`]
     pub doc_comment: Option<Box<str>>,
     /// Full module path (e.g., "crate::storage::memory" or "std::collections")
-    pub module_path: Option<Box<str>>,
+    pub module_path: Option<Arc<str>>,
     /// Visibility of the symbol
     pub visibility: Visibility,
     /// Scope context where this symbol is defined
@@ -76,6 +82,9 @@ pub struct Symbol {
     /// This field enables language-specific filtering in searches.
     /// It's Optional for backward compatibility - existing indexes will have None.
     pub language_id: Option<LanguageId>,
+    /// Tag of the user-defined query that extracted this symbol (e.g., "routes")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<Box<str>>,
 }
 
 #[repr(C, align(32))]
@@ -107,13 +116,14 @@ impl Symbol {
             kind,
             file_id,
             range,
-            file_path: "<unknown>".into(),
+            file_path: intern("<unknown>"),
             signature: None,
             doc_comment: None,
             module_path: None,
             visibility: Visibility::Private,
             scope_context: None, // Default to None for backward compatibility
             language_id: None,   // Default to None for backward compatibility
+            tag: None,
         }
     }
 
@@ -131,8 +141,8 @@ impl Symbol {
         symbol
     }
 
-    pub fn with_file_path(mut self, file_path: impl Into<Box<str>>) -> Self {
-        self.file_path = file_path.into();
+    pub fn with_file_path(mut self, file_path: impl AsRef<str>) -> Self {
+        self.file_path = intern(file_path.as_ref());
         self
     }
 
@@ -146,8 +156,8 @@ impl Symbol {
         self
     }
 
-    pub fn with_module_path(mut self, path: impl Into<Box<str>>) -> Self {
-        self.module_path = Some(path.into());
+    pub fn with_module_path(mut self, path: impl AsRef<str>) -> Self {
+        self.module_path = Some(intern(path.as_ref()));
         self
     }
 
@@ -166,6 +176,11 @@ impl Symbol {
         self
     }
 
+    pub fn with_tag(mut self, tag: impl Into<Box<str>>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
     /// Get the symbol name as a string slice
     pub fn as_name(&self) -> &str {
         &self.name
@@ -186,6 +201,11 @@ impl Symbol {
         self.doc_comment.as_deref()
     }
 
+    /// Parse the doc comment into summary, parameters and return value
+    pub fn doc(&self) -> Option<DocComment> {
+        self.doc_comment.as_deref().map(DocComment::parse)
+    }
+
     /// Get a reference to the module path if present
     pub fn as_module_path(&self) -> Option<&str> {
         self.module_path.as_deref()
@@ -246,9 +266,13 @@ impl fmt::Display for Symbol {
     }
 }
 
+/// Offsets of names packed for [`CompactSymbol`]
+///
+/// Keys come from the crate-wide [interner](crate::types::intern::intern), so a
+/// table shares its names with the symbols and relationships using them.
 pub struct StringTable {
     data: Vec<u8>,
-    offsets: std::collections::HashMap<String, u32>,
+    offsets: std::collections::HashMap<Arc<str>, u32>,
 }
 
 impl Default for StringTable {
@@ -273,7 +297,7 @@ impl StringTable {
         let offset = self.data.len() as u32;
         self.data.extend_from_slice(s.as_bytes());
         self.data.push(0); // Null terminator
-        self.offsets.insert(s.to_string(), offset);
+        self.offsets.insert(intern(s), offset);
         offset
     }
 
@@ -336,13 +360,14 @@ impl CompactSymbol {
             kind,
             file_id: FileId::new(self.file_id as u32)?,
             range: Range::new(self.start_line, self.start_col, self.end_line, self.end_col),
-            file_path: "<unknown>".into(),
+            file_path: intern("<unknown>"),
             signature: None,
             doc_comment: None,
             module_path: None,
             visibility: Visibility::Private,
             scope_context: None, // CompactSymbol doesn't store scope info yet
             language_id: None,   // CompactSymbol doesn't store language info yet
+            tag: None,
         })
     }
 }
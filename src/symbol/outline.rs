@@ -0,0 +1,117 @@
+//! Hierarchical outlines of indexed files
+//!
+//! An outline nests the symbols of one file by source range: a method sits
+//! under the class or impl block whose range contains it, a nested function
+//! under its enclosing function. Agents read the outline instead of the file
+//! to see its structure, then fetch only the symbols they need.
+
+use super::Symbol;
+use super::doc::DocComment;
+use crate::parsing::parser::truncate_for_display;
+use crate::types::Range;
+use std::cmp::Reverse;
+
+/// Longest doc summary shown per symbol, in bytes
+const SUMMARY_MAX_BYTES: usize = 120;
+
+/// A symbol and the symbols nested inside it
+#[derive(Debug, Clone)]
+pub struct OutlineNode {
+    pub symbol: Symbol,
+    pub children: Vec<OutlineNode>,
+}
+
+impl OutlineNode {
+    /// First paragraph of the symbol's doc comment, shortened for display
+    pub fn summary(&self) -> Option<String> {
+        let doc = DocComment::parse(self.symbol.doc_comment.as_deref()?);
+        (!doc.summary.is_empty()).then(|| truncate_for_display(&doc.summary, SUMMARY_MAX_BYTES))
+    }
+
+    /// Number of symbols in this subtree, including this one
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(OutlineNode::count).sum::<usize>()
+    }
+}
+
+/// Nest the symbols of one file by range, in source order
+pub fn build(mut symbols: Vec<Symbol>) -> Vec<OutlineNode> {
+    // Outer symbols sort before the symbols they contain
+    symbols.sort_by_key(|symbol| {
+        let range = &symbol.range;
+        (
+            range.start_line,
+            range.start_column,
+            Reverse((range.end_line, range.end_column)),
+        )
+    });
+
+    let mut roots = Vec::new();
+    let mut open: Vec<OutlineNode> = Vec::new();
+    for symbol in symbols {
+        while let Some(parent) = open.last() {
+            if contains(&parent.symbol.range, &symbol.range) {
+                break;
+            }
+            let closed = open.pop().expect("checked above");
+            attach(&mut open, &mut roots, closed);
+        }
+        open.push(OutlineNode {
+            symbol,
+            children: Vec::new(),
+        });
+    }
+    while let Some(closed) = open.pop() {
+        attach(&mut open, &mut roots, closed);
+    }
+    roots
+}
+
+fn attach(open: &mut [OutlineNode], roots: &mut Vec<OutlineNode>, node: OutlineNode) {
+    match open.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => roots.push(node),
+    }
+}
+
+fn contains(outer: &Range, inner: &Range) -> bool {
+    (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column)
+        && (outer.end_line, outer.end_column) >= (inner.end_line, inner.end_column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, SymbolId, SymbolKind};
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind, range: Range) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            range,
+        )
+    }
+
+    #[test]
+    fn test_build_nests_symbols_by_range() {
+        let symbols = vec![
+            symbol(3, "area", SymbolKind::Method, Range::new(3, 4, 5, 5)),
+            symbol(4, "helper", SymbolKind::Function, Range::new(8, 0, 9, 1)),
+            symbol(1, "UNIT", SymbolKind::Constant, Range::new(0, 0, 0, 20)),
+            symbol(2, "Square", SymbolKind::Struct, Range::new(2, 0, 6, 1))
+                .with_doc("Area of a square.\n\nMultiplies the side by itself."),
+        ];
+
+        let outline = build(symbols);
+        let roots: Vec<_> = outline
+            .iter()
+            .map(|node| (node.symbol.id.value(), node.children.len()))
+            .collect();
+        assert_eq!(roots, vec![(1, 0), (2, 1), (4, 0)]);
+        assert_eq!(outline[1].children[0].symbol.name.as_ref(), "area");
+        assert_eq!(outline[1].summary().as_deref(), Some("Area of a square."));
+        assert_eq!(outline.iter().map(OutlineNode::count).sum::<usize>(), 4);
+    }
+}
@@ -0,0 +1,267 @@
+//! Token-lean source excerpts of symbols
+//!
+//! An excerpt is a symbol's exact source range with a few lines around it,
+//! preceded by what is needed to read it in place: the file's module header
+//! (leading docs and imports), the signature of the enclosing scope, and the
+//! signatures of the other symbols in that scope. Every line keeps its
+//! number so follow-up edits can cite it; blank lines outside the symbol
+//! itself are dropped.
+
+use super::Symbol;
+use super::outline::{self, OutlineNode};
+use crate::parsing::parser::truncate_for_display;
+use crate::types::SymbolId;
+
+/// Most header lines shown before the rest are summarized
+const MAX_HEADER_LINES: usize = 30;
+
+/// Most sibling signatures listed
+const MAX_SIBLINGS: usize = 40;
+
+/// Longest signature shown for the enclosing scope and siblings, in bytes
+const SIGNATURE_MAX_BYTES: usize = 160;
+
+/// What an excerpt includes besides the symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExcerptOptions {
+    /// Lines shown before and after the symbol
+    pub context_lines: usize,
+    /// Most lines of the symbol itself
+    pub max_lines: usize,
+    /// Show the file's leading docs and imports
+    pub header: bool,
+    /// List the other symbols of the enclosing scope
+    pub siblings: bool,
+}
+
+/// Excerpt of `symbol` from `source`, the text of its file
+///
+/// `file_symbols` are the symbols of the same file; they place the symbol
+/// in its enclosing scope and bound the module header.
+pub fn excerpt(
+    source: &str,
+    symbol: &Symbol,
+    file_symbols: Vec<Symbol>,
+    options: ExcerptOptions,
+) -> String {
+    let lines: Vec<&str> = source.lines().map(str::trim_end).collect();
+    let start = symbol.range.start_line as usize;
+    let end = (symbol.range.end_line as usize).max(start);
+    let first = start.saturating_sub(options.context_lines);
+
+    let mut out = format!(
+        "--- {}:{}-{} {:?} {} [symbol_id:{}] ---\n",
+        symbol.file_path,
+        start + 1,
+        end + 1,
+        symbol.kind,
+        symbol.name,
+        symbol.id.value()
+    );
+
+    let outline = outline::build(file_symbols);
+    if options.header {
+        // Everything above the first symbol of the file, unless the excerpt shows it anyway
+        let header_end = outline
+            .iter()
+            .map(|node| node.symbol.range.start_line as usize)
+            .min()
+            .unwrap_or(0)
+            .min(first)
+            .min(lines.len());
+        let header: Vec<(usize, &str)> = numbered(&lines, 0, header_end)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect();
+        if !header.is_empty() {
+            out.push_str("--- header ---\n");
+            for (number, line) in header.iter().take(MAX_HEADER_LINES) {
+                out.push_str(&format!("{number}: {line}\n"));
+            }
+            if header.len() > MAX_HEADER_LINES {
+                out.push_str(&format!(
+                    "... {} more header line(s)\n",
+                    header.len() - MAX_HEADER_LINES
+                ));
+            }
+        }
+    }
+
+    let (parent, scope) = match enclosing(&outline, symbol.id) {
+        Some(parent) => (Some(&parent.symbol), parent.children.as_slice()),
+        None => (None, outline.as_slice()),
+    };
+    if let Some(parent) = parent {
+        out.push_str(&format!(
+            "--- in {} (line {}) ---\n",
+            signature(parent),
+            parent.range.start_line + 1
+        ));
+    }
+    if options.siblings {
+        let siblings: Vec<&Symbol> = scope
+            .iter()
+            .map(|node| &node.symbol)
+            .filter(|sibling| sibling.id != symbol.id)
+            .collect();
+        if !siblings.is_empty() {
+            out.push_str("--- siblings ---\n");
+            for sibling in siblings.iter().take(MAX_SIBLINGS) {
+                out.push_str(&format!(
+                    "{}: {}\n",
+                    sibling.range.start_line + 1,
+                    signature(sibling)
+                ));
+            }
+            if siblings.len() > MAX_SIBLINGS {
+                out.push_str(&format!(
+                    "... {} more sibling(s)\n",
+                    siblings.len() - MAX_SIBLINGS
+                ));
+            }
+        }
+    }
+
+    out.push_str("--- source ---\n");
+    for (number, line) in numbered(&lines, first, start) {
+        if !line.trim().is_empty() {
+            out.push_str(&format!("{number}: {line}\n"));
+        }
+    }
+    let shown_end = end.min(start + options.max_lines.max(1) - 1);
+    for (number, line) in numbered(&lines, start, shown_end + 1) {
+        out.push_str(&format!("{number}: {line}\n"));
+    }
+    if shown_end < end {
+        out.push_str(&format!("... {} more line(s)\n", end - shown_end));
+    } else {
+        let after = end + 1 + options.context_lines;
+        for (number, line) in numbered(&lines, end + 1, after) {
+            if !line.trim().is_empty() {
+                out.push_str(&format!("{number}: {line}\n"));
+            }
+        }
+    }
+    out
+}
+
+/// Lines `from..to` (0-based, clipped to the file) with 1-based numbers
+fn numbered<'a>(
+    lines: &'a [&'a str],
+    from: usize,
+    to: usize,
+) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+    let to = to.min(lines.len());
+    let from = from.min(to);
+    lines[from..to]
+        .iter()
+        .enumerate()
+        .map(move |(offset, line)| (from + offset + 1, *line))
+}
+
+/// The node whose children include the symbol `id`, if it is nested
+fn enclosing(nodes: &[OutlineNode], id: SymbolId) -> Option<&OutlineNode> {
+    for node in nodes {
+        if node.children.iter().any(|child| child.symbol.id == id) {
+            return Some(node);
+        }
+        if let Some(found) = enclosing(&node.children, id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// First line of the symbol's signature, or its kind and name without one
+fn signature(symbol: &Symbol) -> String {
+    match symbol.signature.as_deref().and_then(|s| s.lines().next()) {
+        Some(line) if !line.trim().is_empty() => {
+            truncate_for_display(line.trim(), SIGNATURE_MAX_BYTES)
+        }
+        _ => format!("{:?} {}", symbol.kind, symbol.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileId, Range, SymbolKind};
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind, range: Range, signature: &str) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            range,
+        )
+        .with_signature(signature)
+    }
+
+    #[test]
+    fn test_excerpt_shows_header_scope_and_context() {
+        let source = concat!(
+            "//! Shapes\n\nuse std::fmt;\n\nimpl Square {\n",
+            "    fn new(side: f64) -> Self {\n        Self { side }\n    }\n\n",
+            "    fn area(&self) -> f64 {\n        self.side * self.side\n    }\n}\n"
+        );
+        let symbols = vec![
+            symbol(
+                1,
+                "Square",
+                SymbolKind::Struct,
+                Range::new(4, 0, 12, 1),
+                "impl Square",
+            ),
+            symbol(
+                2,
+                "new",
+                SymbolKind::Method,
+                Range::new(5, 4, 7, 5),
+                "fn new(side: f64) -> Self",
+            ),
+            symbol(
+                3,
+                "area",
+                SymbolKind::Method,
+                Range::new(9, 4, 11, 5),
+                "fn area(&self) -> f64",
+            ),
+        ];
+        let options = ExcerptOptions {
+            context_lines: 1,
+            max_lines: 100,
+            header: true,
+            siblings: true,
+        };
+
+        let text = excerpt(source, &symbols[2], symbols.clone(), options);
+        assert_eq!(
+            text,
+            "--- <unknown>:10-12 Method area [symbol_id:3] ---\n\
+             --- header ---\n\
+             1: //! Shapes\n\
+             3: use std::fmt;\n\
+             --- in impl Square (line 5) ---\n\
+             --- siblings ---\n\
+             6: fn new(side: f64) -> Self\n\
+             --- source ---\n\
+             10:     fn area(&self) -> f64 {\n\
+             11:         self.side * self.side\n\
+             12:     }\n\
+             13: }\n"
+        );
+
+        let short = ExcerptOptions {
+            context_lines: 0,
+            max_lines: 1,
+            header: false,
+            siblings: false,
+        };
+        let text = excerpt(source, &symbols[1].clone(), symbols, short);
+        assert!(
+            text.ends_with(
+                "--- source ---\n6:     fn new(side: f64) -> Self {\n... 2 more line(s)\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Crate-wide string interning
+//!
+//! File paths, module paths and symbol names repeat across every subsystem:
+//! each symbol of a file carries the file's path, every relationship names
+//! its endpoints, and the watcher tracks the same paths again. Interning
+//! them here stores each distinct string once and hands out shared
+//! [`Arc`]s, so a path held by a thousand symbols costs one allocation.
+//!
+//! Interned strings live as long as something holds them. [`prune`] drops
+//! the ones only the interner still references. The pipeline calls it once
+//! a run has finished with its caches, and long-running servers whenever
+//! they let go of an index: on hot reload and when closing a project.
+
+use dashmap::DashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+static INTERNER: LazyLock<Interner> = LazyLock::new(Interner::default);
+
+/// Concurrent set of shared strings and paths
+#[derive(Debug, Default)]
+struct Interner {
+    strings: DashMap<Arc<str>, ()>,
+    paths: DashMap<Arc<Path>, ()>,
+}
+
+/// Shared copy of `s`, allocated on first use
+pub fn intern(s: &str) -> Arc<str> {
+    if let Some(entry) = INTERNER.strings.get(s) {
+        return Arc::clone(entry.key());
+    }
+    // Another thread may have inserted it meanwhile; the entry keeps its key
+    Arc::clone(INTERNER.strings.entry(Arc::from(s)).or_default().key())
+}
+
+/// Shared copy of `path`, allocated on first use
+pub fn intern_path(path: &Path) -> Arc<Path> {
+    if let Some(entry) = INTERNER.paths.get(path) {
+        return Arc::clone(entry.key());
+    }
+    Arc::clone(INTERNER.paths.entry(Arc::from(path)).or_default().key())
+}
+
+/// Drop interned strings and paths nothing else holds, returning how many
+pub fn prune() -> usize {
+    let before = interned();
+    INTERNER
+        .strings
+        .retain(|string, _| Arc::strong_count(string) > 1);
+    INTERNER.paths.retain(|path, _| Arc::strong_count(path) > 1);
+    before.saturating_sub(interned())
+}
+
+/// Number of distinct strings and paths currently interned
+pub fn interned() -> usize {
+    INTERNER.strings.len() + INTERNER.paths.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_one_allocation() {
+        let first = intern("src/intern_test_only.rs");
+        let second = intern(&String::from("src/intern_test_only.rs"));
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let path = Path::new("/tmp/intern_test_only/lib.rs");
+        assert!(Arc::ptr_eq(&intern_path(path), &intern_path(path)));
+
+        // Held strings survive a prune, released ones are dropped
+        drop(second);
+        prune();
+        assert!(Arc::ptr_eq(&first, &intern("src/intern_test_only.rs")));
+        assert!(INTERNER.paths.get(path).is_none());
+    }
+}
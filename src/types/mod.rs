@@ -1,5 +1,7 @@
+pub mod intern;
 mod symbol_counter;
 
+pub use intern::{intern, intern_path};
 pub use symbol_counter::SymbolCounter;
 
 use serde::{Deserialize, Serialize};
@@ -145,6 +147,30 @@ impl SymbolKind {
     pub fn from_str_with_default(s: &str) -> Self {
         s.parse().unwrap_or(SymbolKind::Function)
     }
+
+    /// Parse a kind name in any letter case, e.g. "function" or "typealias"
+    pub fn parse_ignore_case(name: &str) -> Option<Self> {
+        const KINDS: [&str; 14] = [
+            "Function",
+            "Method",
+            "Struct",
+            "Enum",
+            "Trait",
+            "Interface",
+            "Class",
+            "Module",
+            "Variable",
+            "Constant",
+            "Field",
+            "Parameter",
+            "TypeAlias",
+            "Macro",
+        ];
+        KINDS
+            .iter()
+            .find(|kind| kind.eq_ignore_ascii_case(name))
+            .and_then(|kind| kind.parse().ok())
+    }
 }
 
 pub type CompactString = Box<str>;
@@ -157,6 +183,19 @@ pub fn compact_string(s: &str) -> CompactString {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_kind_ignores_case() {
+        assert_eq!(
+            SymbolKind::parse_ignore_case("function"),
+            Some(SymbolKind::Function)
+        );
+        assert_eq!(
+            SymbolKind::parse_ignore_case("TYPEALIAS"),
+            Some(SymbolKind::TypeAlias)
+        );
+        assert_eq!(SymbolKind::parse_ignore_case("widget"), None);
+    }
+
     #[test]
     fn test_symbol_id_creation() {
         assert!(SymbolId::new(0).is_none());
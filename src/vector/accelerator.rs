@@ -0,0 +1,113 @@
+//! GPU execution of local embedding models
+//!
+//! Builds with `--features gpu-cuda` (NVIDIA) or `--features gpu-coreml`
+//! (Apple silicon, through CoreML on the GPU and Neural Engine) register the
+//! matching ONNX Runtime execution provider when a local model loads.
+//! `semantic_search.device` picks where models run:
+//!
+//! - `auto` (default): on the GPU when the build supports one and it
+//!   initializes, on the CPU otherwise
+//! - `gpu`: like `auto`, but running on the CPU is reported as a warning
+//! - `cpu`: never on the GPU
+//!
+//! A model that fails to load on the GPU, for a missing driver or library,
+//! is loaded again on the CPU, so a GPU build runs anywhere.
+
+use super::types::VectorError;
+use fastembed::InitOptions;
+
+/// Where local embedding models run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingDevice {
+    #[default]
+    Auto,
+    Cpu,
+    Gpu,
+}
+
+impl EmbeddingDevice {
+    /// Parse `semantic_search.device`
+    pub fn parse(name: &str) -> Result<Self, VectorError> {
+        match name {
+            "auto" => Ok(Self::Auto),
+            "cpu" => Ok(Self::Cpu),
+            "gpu" => Ok(Self::Gpu),
+            other => Err(VectorError::EmbeddingFailed(format!(
+                "Unknown embedding device '{other}'. Expected one of: auto, cpu, gpu"
+            ))),
+        }
+    }
+}
+
+/// GPU execution providers compiled into this build, most preferred first
+pub fn accelerators() -> Vec<&'static str> {
+    #[allow(unused_mut)]
+    let mut names = Vec::new();
+    #[cfg(feature = "gpu-cuda")]
+    names.push("cuda");
+    #[cfg(feature = "gpu-coreml")]
+    names.push("coreml");
+    names
+}
+
+/// Accelerators to try for `device`, in order, before the CPU
+pub(crate) fn candidates(device: EmbeddingDevice) -> Vec<&'static str> {
+    match device {
+        EmbeddingDevice::Cpu => Vec::new(),
+        EmbeddingDevice::Auto => accelerators(),
+        EmbeddingDevice::Gpu => {
+            let names = accelerators();
+            if names.is_empty() {
+                tracing::warn!(
+                    target: "semantic",
+                    "semantic_search.device is \"gpu\" but this build has no GPU support \
+                     (rebuild with --features gpu-cuda or gpu-coreml); using the CPU"
+                );
+            }
+            names
+        }
+    }
+}
+
+/// `options` loading the model on `accelerator`, failing rather than
+/// running on the CPU without telling
+pub(crate) fn with_accelerator(options: InitOptions, accelerator: &str) -> InitOptions {
+    #[cfg(any(feature = "gpu-cuda", feature = "gpu-coreml"))]
+    {
+        let dispatch = match accelerator {
+            #[cfg(feature = "gpu-cuda")]
+            "cuda" => ort::ep::CUDA::default().build(),
+            #[cfg(feature = "gpu-coreml")]
+            "coreml" => ort::ep::CoreML::default().build(),
+            _ => return options,
+        };
+        options.with_execution_providers(vec![dispatch.error_on_failure()])
+    }
+    #[cfg(not(any(feature = "gpu-cuda", feature = "gpu-coreml")))]
+    {
+        let _ = accelerator;
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_selects_candidates() {
+        assert_eq!(
+            EmbeddingDevice::parse("auto").unwrap(),
+            EmbeddingDevice::Auto
+        );
+        assert_eq!(EmbeddingDevice::parse("gpu").unwrap(), EmbeddingDevice::Gpu);
+        assert!(EmbeddingDevice::parse("tpu").is_err());
+
+        assert!(candidates(EmbeddingDevice::Cpu).is_empty());
+        assert_eq!(candidates(EmbeddingDevice::Auto), accelerators());
+        assert_eq!(candidates(EmbeddingDevice::Gpu), accelerators());
+        if cfg!(not(any(feature = "gpu-cuda", feature = "gpu-coreml"))) {
+            assert!(accelerators().is_empty());
+        }
+    }
+}
@@ -144,7 +144,8 @@
 //! 4. **Simple Mapping**: Direct SymbolId → VectorId mapping (no lookup table)
 //! 5. **Clean Separation**: Vector logic isolated in vector module
 
-use crate::vector::{VectorDimension, VectorError};
+use crate::vector::accelerator::{self, EmbeddingDevice};
+use crate::vector::{EmbeddingProvider, VectorDimension, VectorError};
 use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
 use std::sync::Mutex;
 
@@ -289,6 +290,20 @@ pub trait EmbeddingGenerator: Send + Sync {
     fn dimension(&self) -> VectorDimension;
 }
 
+/// Every provider generates embeddings, in batches of its size
+impl<P: EmbeddingProvider + ?Sized> EmbeddingGenerator for P {
+    fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+        self.embed(texts)
+    }
+
+    fn dimension(&self) -> VectorDimension {
+        VectorDimension::new(self.dimensions()).expect("providers probe a non-zero dimension")
+    }
+}
+
+/// Texts embedded per call of a local model, unless configured
+pub const DEFAULT_LOCAL_BATCH_SIZE: usize = 64;
+
 /// FastEmbed implementation with configurable embedding models.
 ///
 /// This implementation supports multiple embedding models for different use cases:
@@ -303,6 +318,8 @@ pub struct FastEmbedGenerator {
     model: Mutex<TextEmbedding>,
     dimension: VectorDimension,
     model_name: String,
+    batch_size: usize,
+    device: &'static str,
 }
 
 impl FastEmbedGenerator {
@@ -324,6 +341,8 @@ impl FastEmbedGenerator {
 
     /// Create a new generator with a specific model.
     ///
+    /// Runs on the GPU when the build supports one, see [`EmbeddingDevice`].
+    ///
     /// # Arguments
     /// * `model` - The embedding model to use
     /// * `show_progress` - Whether to show download progress
@@ -331,14 +350,54 @@ impl FastEmbedGenerator {
     /// # Errors
     /// Returns an error if the model fails to initialize or download.
     pub fn with_model(model: EmbeddingModel, show_progress: bool) -> Result<Self, VectorError> {
+        Self::with_device(model, show_progress, EmbeddingDevice::Auto)
+    }
+
+    /// Create a new generator with a specific model on `device`.
+    ///
+    /// A model that fails to load on a GPU is loaded on the CPU instead.
+    ///
+    /// # Errors
+    /// Returns an error if the model fails to initialize or download.
+    pub fn with_device(
+        model: EmbeddingModel,
+        show_progress: bool,
+        device: EmbeddingDevice,
+    ) -> Result<Self, VectorError> {
+        for accelerator in accelerator::candidates(device) {
+            match Self::load(model.clone(), show_progress, Some(accelerator)) {
+                Ok(generator) => {
+                    tracing::info!(target: "semantic", "Embedding model runs on {accelerator}");
+                    return Ok(generator);
+                }
+                Err(e) if device == EmbeddingDevice::Gpu => tracing::warn!(
+                    target: "semantic",
+                    "Embedding model could not run on {accelerator}, using the CPU: {e}"
+                ),
+                Err(e) => tracing::debug!(
+                    target: "semantic",
+                    "Embedding model could not run on {accelerator}, using the CPU: {e}"
+                ),
+            }
+        }
+        Self::load(model, show_progress, None)
+    }
+
+    fn load(
+        model: EmbeddingModel,
+        show_progress: bool,
+        accelerator: Option<&'static str>,
+    ) -> Result<Self, VectorError> {
         let model_name = model_to_string(&model);
 
-        let mut text_model = TextEmbedding::try_new(
-            InitOptions::new(model)
-                .with_cache_dir(crate::init::models_dir())
-                .with_show_download_progress(show_progress),
-        )
-        .map_err(|e| VectorError::EmbeddingFailed(
+        let mut options = InitOptions::new(model)
+            .with_cache_dir(crate::init::models_dir())
+            .with_show_download_progress(show_progress);
+        if let Some(accelerator) = accelerator {
+            options = accelerator::with_accelerator(options, accelerator);
+        }
+
+        let mut text_model = TextEmbedding::try_new(options).map_err(|e| VectorError::EmbeddingFailed(
             format!("Failed to initialize embedding model '{model_name}': {e}. Ensure you have internet connection for first-time model download")
         ))?;
 
@@ -356,9 +415,23 @@ impl FastEmbedGenerator {
             model: Mutex::new(text_model),
             dimension,
             model_name,
+            batch_size: DEFAULT_LOCAL_BATCH_SIZE,
+            device: accelerator.unwrap_or("cpu"),
         })
     }
 
+    /// Where the model runs: `cpu`, or the GPU execution provider
+    pub fn device(&self) -> &'static str {
+        self.device
+    }
+
+    /// Embed at most `batch_size` texts per model call.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
     /// Create a generator from settings.
     ///
     /// Reads the model name from settings and initializes the appropriate model.
@@ -380,8 +453,20 @@ impl FastEmbedGenerator {
     }
 }
 
-impl EmbeddingGenerator for FastEmbedGenerator {
-    fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+impl EmbeddingProvider for FastEmbedGenerator {
+    fn model_id(&self) -> &str {
+        &self.model_name
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimension.get()
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
         if texts.is_empty() {
             return Ok(Vec::new());
         }
@@ -391,9 +476,7 @@ impl EmbeddingGenerator for FastEmbedGenerator {
         // to avoid these allocations
         let text_strings: Vec<String> = texts.iter().map(|&s| s.to_string()).collect();
 
-        // Generate embeddings
-        let embeddings = self
-            .model
+        self.model
             .lock()
             .map_err(|_| {
                 VectorError::EmbeddingFailed(
@@ -403,24 +486,7 @@ impl EmbeddingGenerator for FastEmbedGenerator {
             .embed(text_strings, None)
             .map_err(|e| {
                 VectorError::EmbeddingFailed(format!("Failed to generate embeddings: {e}"))
-            })?;
-
-        // Validate dimensions
-        let expected_dim = self.dimension.get();
-        for embedding in embeddings.iter() {
-            if embedding.len() != expected_dim {
-                return Err(VectorError::DimensionMismatch {
-                    expected: expected_dim,
-                    actual: embedding.len(),
-                });
-            }
-        }
-
-        Ok(embeddings)
-    }
-
-    fn dimension(&self) -> VectorDimension {
-        self.dimension
+            })
     }
 }
 
@@ -458,8 +524,20 @@ impl MockEmbeddingGenerator {
 }
 
 #[cfg(test)]
-impl EmbeddingGenerator for MockEmbeddingGenerator {
-    fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+impl EmbeddingProvider for MockEmbeddingGenerator {
+    fn model_id(&self) -> &str {
+        "mock"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimension.get()
+    }
+
+    fn max_batch_size(&self) -> usize {
+        DEFAULT_LOCAL_BATCH_SIZE
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
         let dim = self.dimension.get();
         let mut embeddings = Vec::new();
 
@@ -498,10 +576,6 @@ impl EmbeddingGenerator for MockEmbeddingGenerator {
 
         Ok(embeddings)
     }
-
-    fn dimension(&self) -> VectorDimension {
-        self.dimension
-    }
 }
 
 /// Helper to create symbol text for embedding.
@@ -14,23 +14,32 @@
 //! with K-means clustering to achieve sub-linear search performance. Vectors are
 //! stored in memory-mapped files for instant loading and minimal memory overhead.
 
+mod accelerator;
 mod clustering;
 mod embedding;
 mod engine;
+pub mod namespace;
+mod provider;
 mod storage;
 mod types;
 
 // Re-export core types for public API
+pub use accelerator::{EmbeddingDevice, accelerators};
 pub use clustering::{
     ClusteringError, KMeansResult, assign_to_nearest_centroid, cosine_similarity, kmeans_clustering,
 };
 #[cfg(test)]
 pub use embedding::MockEmbeddingGenerator;
 pub use embedding::{
-    EmbeddingGenerator, FastEmbedGenerator, create_symbol_text, model_to_string,
-    parse_embedding_model,
+    DEFAULT_LOCAL_BATCH_SIZE, EmbeddingGenerator, FastEmbedGenerator, create_symbol_text,
+    model_to_string, parse_embedding_model,
 };
 pub use engine::VectorSearchEngine;
+pub use namespace::{NamespaceInfo, VectorNamespace};
+pub use provider::{
+    EmbeddingProvider, OllamaProvider, OpenAiProvider, PROVIDERS, configured_model_id,
+    create_provider, is_local_model_id, provider_for_model_id,
+};
 pub use storage::{ConcurrentVectorStorage, MmapVectorStorage, VectorStorageError};
 pub use types::{
     ClusterId, Score, SegmentOrdinal, VECTOR_DIMENSION_384, VectorDimension, VectorError, VectorId,
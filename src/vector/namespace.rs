@@ -0,0 +1,171 @@
+//! Vector store namespaces.
+//!
+//! Embeddings are partitioned by content type (code, docs, conversations).
+//! Each namespace lives in its own directory, so one can be dropped or
+//! rebuilt without touching the others. They keep their historical
+//! locations:
+//!
+//! | Namespace | Directory |
+//! |-----------|-----------|
+//! | `code` | `<index>/semantic` |
+//! | `docs` | `<index>/documents/vectors` |
+//! | `conversations` | `<index>/conversations/vectors` |
+
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A partition of the vector store, by the content type it holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorNamespace {
+    Code,
+    Docs,
+    Conversations,
+}
+
+impl VectorNamespace {
+    pub const ALL: [VectorNamespace; 3] = [
+        VectorNamespace::Code,
+        VectorNamespace::Docs,
+        VectorNamespace::Conversations,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VectorNamespace::Code => "code",
+            VectorNamespace::Docs => "docs",
+            VectorNamespace::Conversations => "conversations",
+        }
+    }
+
+    /// Directory holding this namespace's vectors
+    pub fn dir(&self, index_path: &Path) -> PathBuf {
+        match self {
+            VectorNamespace::Code => index_path.join("semantic"),
+            VectorNamespace::Docs => index_path.join("documents").join("vectors"),
+            VectorNamespace::Conversations => index_path.join("conversations").join("vectors"),
+        }
+    }
+}
+
+impl fmt::Display for VectorNamespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for VectorNamespace {
+    type Err = String;
+
+    /// Parse `code`, `docs` or `conversations`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VectorNamespace::ALL
+            .into_iter()
+            .find(|k| k.as_str() == s)
+            .ok_or_else(|| {
+                format!("Unknown namespace '{s}'. Expected code, docs, or conversations")
+            })
+    }
+}
+
+/// A namespace found on disk
+#[derive(Debug, Clone, Serialize)]
+pub struct NamespaceInfo {
+    pub namespace: VectorNamespace,
+    pub path: PathBuf,
+    /// Total size of the namespace's files
+    pub bytes: u64,
+    /// Embedding count, when the namespace records one
+    pub embeddings: Option<usize>,
+}
+
+/// Find every namespace that has data under an index directory.
+pub fn discover(index_path: &Path) -> Vec<NamespaceInfo> {
+    VectorNamespace::ALL
+        .into_iter()
+        .filter_map(|namespace| {
+            let path = namespace.dir(index_path);
+            let bytes = dir_size(&path)?;
+            let embeddings = crate::semantic::SemanticMetadata::load(&path)
+                .ok()
+                .map(|m| m.embedding_count);
+            Some(NamespaceInfo {
+                namespace,
+                path,
+                bytes,
+                embeddings,
+            })
+        })
+        .collect()
+}
+
+/// Delete one namespace's vectors. Returns false if it had no data.
+pub fn remove(index_path: &Path, namespace: VectorNamespace) -> std::io::Result<bool> {
+    let dir = namespace.dir(index_path);
+    if !dir.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_dir_all(&dir)?;
+    Ok(true)
+}
+
+fn dir_size(path: &Path) -> Option<u64> {
+    let entries = std::fs::read_dir(path).ok()?;
+    let mut total = 0;
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        total += if metadata.is_dir() {
+            dir_size(&entry.path()).unwrap_or(0)
+        } else {
+            metadata.len()
+        };
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_and_dirs() {
+        let index = Path::new("/idx");
+
+        let code: VectorNamespace = "code".parse().unwrap();
+        assert_eq!(code, VectorNamespace::Code);
+        assert_eq!(code.to_string(), "code");
+        assert_eq!(code.dir(index), index.join("semantic"));
+        assert_eq!(
+            VectorNamespace::Docs.dir(index),
+            index.join("documents").join("vectors")
+        );
+
+        assert!("symbols".parse::<VectorNamespace>().is_err());
+        assert!("code@frontend".parse::<VectorNamespace>().is_err());
+    }
+
+    #[test]
+    fn test_remove_leaves_other_namespaces() {
+        let dir = TempDir::new().unwrap();
+        let index = dir.path();
+        for namespace in [VectorNamespace::Code, VectorNamespace::Docs] {
+            let path = namespace.dir(index);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(path.join("segment_0.vec"), [0u8; 16]).unwrap();
+        }
+
+        let found: Vec<_> = discover(index).into_iter().map(|i| i.namespace).collect();
+        assert_eq!(found, vec![VectorNamespace::Code, VectorNamespace::Docs]);
+
+        assert!(remove(index, VectorNamespace::Docs).unwrap());
+        assert!(!remove(index, VectorNamespace::Docs).unwrap());
+        assert!(VectorNamespace::Code.dir(index).exists());
+        let found: Vec<_> = discover(index).into_iter().map(|i| i.namespace).collect();
+        assert_eq!(found, vec![VectorNamespace::Code]);
+    }
+}
@@ -0,0 +1,449 @@
+//! Pluggable embedding backends
+//!
+//! An [`EmbeddingProvider`] turns text into vectors. Codanna ships three:
+//! local ONNX models run in process by fastembed ([`FastEmbedGenerator`]),
+//! an Ollama server, and any OpenAI-compatible `/embeddings` endpoint. The
+//! `[semantic_search]` settings pick one with `provider`.
+//!
+//! Each provider has a model id, stored in the index metadata so that an index
+//! is reopened with the model that built it. Local models keep their plain
+//! name (`AllMiniLML6V2`) as before; remote ones are prefixed with the
+//! provider (`ollama:nomic-embed-text`, `openai:text-embedding-3-small`).
+//!
+//! Dimensions are not configured per model: every provider embeds a probe
+//! text when created and reports the length it got back. OpenAI-compatible
+//! endpoints that can shorten vectors are asked for `dimensions` when set,
+//! and creation fails if the endpoint ignores it.
+
+use super::accelerator::EmbeddingDevice;
+use super::embedding::{FastEmbedGenerator, parse_embedding_model};
+use super::types::{VectorDimension, VectorError};
+use crate::config::SemanticSearchConfig;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Provider names accepted by `semantic_search.provider`
+pub const PROVIDERS: [&str; 3] = ["local", "ollama", "openai"];
+
+const OLLAMA_PREFIX: &str = "ollama:";
+const OPENAI_PREFIX: &str = "openai:";
+const DEFAULT_OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+const DEFAULT_OPENAI_ENDPOINT: &str = "https://api.openai.com/v1";
+
+/// Text embedded to learn the dimensions of a model
+const PROBE_TEXT: &str = "test";
+
+/// A backend that embeds text
+///
+/// Implementations only embed what fits one request; callers use
+/// [`embed`](Self::embed), which splits input into batches and checks what
+/// comes back. Every provider is also an
+/// [`EmbeddingGenerator`](super::EmbeddingGenerator).
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifier stored in index metadata, e.g. `ollama:nomic-embed-text`
+    fn model_id(&self) -> &str;
+
+    /// Length of every vector this provider returns
+    fn dimensions(&self) -> usize;
+
+    /// Most texts sent to [`embed_batch`](Self::embed_batch) at once
+    fn max_batch_size(&self) -> usize;
+
+    /// Embed at most [`max_batch_size`](Self::max_batch_size) texts
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError>;
+
+    /// Embed any number of texts, one vector each, in input order
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.max_batch_size().max(1)) {
+            let batch_embeddings = self.embed_batch(batch)?;
+            if batch_embeddings.len() != batch.len() {
+                return Err(VectorError::EmbeddingFailed(format!(
+                    "'{}' returned {} embeddings for {} texts",
+                    self.model_id(),
+                    batch_embeddings.len(),
+                    batch.len()
+                )));
+            }
+            for embedding in &batch_embeddings {
+                if embedding.len() != self.dimensions() {
+                    return Err(VectorError::DimensionMismatch {
+                        expected: self.dimensions(),
+                        actual: embedding.len(),
+                    });
+                }
+            }
+            embeddings.extend(batch_embeddings);
+        }
+        Ok(embeddings)
+    }
+
+    /// Embed a single text
+    fn embed_one(&self, text: &str) -> Result<Vec<f32>, VectorError> {
+        Ok(self.embed(&[text])?.remove(0))
+    }
+}
+
+impl<P: EmbeddingProvider + ?Sized> EmbeddingProvider for Arc<P> {
+    fn model_id(&self) -> &str {
+        (**self).model_id()
+    }
+
+    fn dimensions(&self) -> usize {
+        (**self).dimensions()
+    }
+
+    fn max_batch_size(&self) -> usize {
+        (**self).max_batch_size()
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+        (**self).embed_batch(texts)
+    }
+}
+
+/// Model id the configured provider records in index metadata
+pub fn configured_model_id(config: &SemanticSearchConfig) -> Result<String, VectorError> {
+    match config.provider.as_str() {
+        "local" => Ok(config.model.clone()),
+        "ollama" => Ok(format!("{OLLAMA_PREFIX}{}", config.model)),
+        "openai" => Ok(format!("{OPENAI_PREFIX}{}", config.model)),
+        other => Err(VectorError::EmbeddingFailed(format!(
+            "Unknown embedding provider '{other}'. Expected one of: {}",
+            PROVIDERS.join(", ")
+        ))),
+    }
+}
+
+/// Whether `model_id` names a model run in process
+pub fn is_local_model_id(model_id: &str) -> bool {
+    !model_id.starts_with(OLLAMA_PREFIX) && !model_id.starts_with(OPENAI_PREFIX)
+}
+
+/// Create the provider selected in `config`
+pub fn create_provider(
+    config: &SemanticSearchConfig,
+    show_progress: bool,
+) -> Result<Arc<dyn EmbeddingProvider>, VectorError> {
+    provider_for_model_id(&configured_model_id(config)?, config, show_progress)
+}
+
+/// Create a provider for the model that built an index
+///
+/// `model_id` comes from the index metadata; `config` supplies endpoints,
+/// credentials and batch size for remote providers.
+pub fn provider_for_model_id(
+    model_id: &str,
+    config: &SemanticSearchConfig,
+    show_progress: bool,
+) -> Result<Arc<dyn EmbeddingProvider>, VectorError> {
+    let client = |default_endpoint: &str| HttpClient::from_config(config, default_endpoint);
+    if let Some(model) = model_id.strip_prefix(OLLAMA_PREFIX) {
+        let client = client(DEFAULT_OLLAMA_ENDPOINT);
+        return Ok(Arc::new(OllamaProvider::new(
+            client,
+            model,
+            config.batch_size,
+        )?));
+    }
+    if let Some(model) = model_id.strip_prefix(OPENAI_PREFIX) {
+        let client = client(DEFAULT_OPENAI_ENDPOINT);
+        let dimensions = (config.dimensions > 0).then_some(config.dimensions);
+        return Ok(Arc::new(OpenAiProvider::new(
+            client,
+            model,
+            dimensions,
+            config.batch_size,
+        )?));
+    }
+    let model = parse_embedding_model(model_id)?;
+    let device = EmbeddingDevice::parse(&config.device)?;
+    Ok(Arc::new(
+        FastEmbedGenerator::with_device(model, show_progress, device)?
+            .with_batch_size(config.batch_size),
+    ))
+}
+
+/// JSON over HTTP to an embedding server
+struct HttpClient {
+    endpoint: String,
+    api_key: Option<String>,
+    timeout: Duration,
+}
+
+impl HttpClient {
+    fn from_config(config: &SemanticSearchConfig, default_endpoint: &str) -> Self {
+        let endpoint = if config.endpoint.is_empty() {
+            default_endpoint
+        } else {
+            config.endpoint.as_str()
+        };
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: std::env::var(&config.api_key_env)
+                .ok()
+                .filter(|k| !k.is_empty()),
+            timeout: Duration::from_secs(config.timeout_secs),
+        }
+    }
+
+    fn post(&self, path: &str, body: &serde_json::Value) -> Result<serde_json::Value, VectorError> {
+        let url = format!("{}{path}", self.endpoint);
+        let request = ureq::post(&url)
+            .header("User-Agent", concat!("codanna/", env!("CARGO_PKG_VERSION")))
+            .config()
+            .timeout_global(Some(self.timeout))
+            .build();
+        let request = match &self.api_key {
+            Some(key) => request.header("Authorization", format!("Bearer {key}")),
+            None => request,
+        };
+
+        let mut response = request
+            .send_json(body)
+            .map_err(|e| VectorError::EmbeddingFailed(format!("{url}: {e}")))?;
+        response
+            .body_mut()
+            .read_json()
+            .map_err(|e| VectorError::EmbeddingFailed(format!("Invalid response from {url}: {e}")))
+    }
+}
+
+/// Embed the probe text and return its length
+fn probe_dimensions(provider: &dyn EmbeddingProvider) -> Result<usize, VectorError> {
+    let embeddings = provider.embed_batch(&[PROBE_TEXT])?;
+    let size = embeddings.first().map_or(0, Vec::len);
+    VectorDimension::new(size)
+        .map(|dimension| dimension.get())
+        .map_err(|e| {
+            VectorError::EmbeddingFailed(format!(
+                "'{}' returned no usable embedding: {e}",
+                provider.model_id()
+            ))
+        })
+}
+
+fn parse_vector(value: &serde_json::Value) -> Option<Vec<f32>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|v| v.as_f64().map(|f| f as f32))
+        .collect()
+}
+
+/// Embeddings of an Ollama `/api/embed` response
+fn parse_ollama_response(json: &serde_json::Value) -> Option<Vec<Vec<f32>>> {
+    json.get("embeddings")?
+        .as_array()?
+        .iter()
+        .map(parse_vector)
+        .collect()
+}
+
+/// Embeddings of an OpenAI `/embeddings` response, in input order
+fn parse_openai_response(json: &serde_json::Value) -> Option<Vec<Vec<f32>>> {
+    let mut indexed = json
+        .get("data")?
+        .as_array()?
+        .iter()
+        .enumerate()
+        .map(|(position, item)| {
+            let index = item
+                .get("index")
+                .and_then(|i| i.as_u64())
+                .map_or(position, |i| i as usize);
+            Some((index, parse_vector(item.get("embedding")?)?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    indexed.sort_by_key(|(index, _)| *index);
+    Some(indexed.into_iter().map(|(_, vector)| vector).collect())
+}
+
+/// Models served by Ollama through `/api/embed`
+pub struct OllamaProvider {
+    client: HttpClient,
+    model: String,
+    model_id: String,
+    dimensions: usize,
+    batch_size: usize,
+}
+
+impl OllamaProvider {
+    fn new(client: HttpClient, model: &str, batch_size: usize) -> Result<Self, VectorError> {
+        let mut provider = Self {
+            client,
+            model: model.to_string(),
+            model_id: format!("{OLLAMA_PREFIX}{model}"),
+            dimensions: 0,
+            batch_size,
+        };
+        provider.dimensions = probe_dimensions(&provider)?;
+        Ok(provider)
+    }
+}
+
+impl EmbeddingProvider for OllamaProvider {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = serde_json::json!({ "model": self.model, "input": texts });
+        let json = self.client.post("/api/embed", &body)?;
+        parse_ollama_response(&json).ok_or_else(|| {
+            VectorError::EmbeddingFailed(format!(
+                "Ollama response for '{}' has no embeddings",
+                self.model
+            ))
+        })
+    }
+}
+
+/// Models behind an OpenAI-compatible `/embeddings` endpoint
+pub struct OpenAiProvider {
+    client: HttpClient,
+    model: String,
+    model_id: String,
+    requested_dimensions: Option<usize>,
+    dimensions: usize,
+    batch_size: usize,
+}
+
+impl OpenAiProvider {
+    fn new(
+        client: HttpClient,
+        model: &str,
+        requested_dimensions: Option<usize>,
+        batch_size: usize,
+    ) -> Result<Self, VectorError> {
+        let mut provider = Self {
+            client,
+            model: model.to_string(),
+            model_id: format!("{OPENAI_PREFIX}{model}"),
+            requested_dimensions,
+            dimensions: 0,
+            batch_size,
+        };
+        provider.dimensions = probe_dimensions(&provider)?;
+        if let Some(requested) = requested_dimensions {
+            if requested != provider.dimensions {
+                return Err(VectorError::EmbeddingFailed(format!(
+                    "Requested {requested} dimensions from '{model}' but got {}. \
+                     Remove semantic_search.dimensions if the model has a fixed size",
+                    provider.dimensions
+                )));
+            }
+        }
+        Ok(provider)
+    }
+}
+
+impl EmbeddingProvider for OpenAiProvider {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut body = serde_json::json!({ "model": self.model, "input": texts });
+        if let Some(dimensions) = self.requested_dimensions {
+            body["dimensions"] = dimensions.into();
+        }
+        let json = self.client.post("/embeddings", &body)?;
+        parse_openai_response(&json).ok_or_else(|| {
+            VectorError::EmbeddingFailed(format!(
+                "Response for '{}' has no data[].embedding",
+                self.model
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Returns `[batch number, position]` vectors and records batch sizes
+    struct CountingProvider {
+        batches: Mutex<Vec<usize>>,
+    }
+
+    impl EmbeddingProvider for CountingProvider {
+        fn model_id(&self) -> &str {
+            "counting"
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        fn max_batch_size(&self) -> usize {
+            2
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, VectorError> {
+            let mut batches = self.batches.lock().unwrap();
+            batches.push(texts.len());
+            let batch = batches.len() as f32;
+            Ok((0..texts.len()).map(|i| vec![batch, i as f32]).collect())
+        }
+    }
+
+    #[test]
+    fn test_embed_splits_batches_and_parses_responses() {
+        let provider = CountingProvider {
+            batches: Mutex::new(Vec::new()),
+        };
+        let embeddings = provider.embed(&["a", "b", "c", "d", "e"]).unwrap();
+        assert_eq!(*provider.batches.lock().unwrap(), [2, 2, 1]);
+        assert_eq!(embeddings.len(), 5);
+        assert_eq!(embeddings[4], [3.0, 0.0]);
+        assert!(provider.embed(&[]).unwrap().is_empty());
+
+        let ollama = serde_json::json!({ "embeddings": [[0.5, 1.0], [0.25, 2.0]] });
+        assert_eq!(
+            parse_ollama_response(&ollama).unwrap(),
+            [vec![0.5, 1.0], vec![0.25, 2.0]]
+        );
+        let openai = serde_json::json!({ "data": [
+            { "index": 1, "embedding": [2.0] },
+            { "index": 0, "embedding": [1.0] },
+        ] });
+        assert_eq!(parse_openai_response(&openai).unwrap(), [[1.0], [2.0]]);
+        assert!(parse_openai_response(&serde_json::json!({ "error": "quota" })).is_none());
+
+        let mut config = SemanticSearchConfig::default();
+        assert_eq!(configured_model_id(&config).unwrap(), "AllMiniLML6V2");
+        config.provider = "ollama".to_string();
+        config.model = "nomic-embed-text".to_string();
+        assert_eq!(
+            configured_model_id(&config).unwrap(),
+            "ollama:nomic-embed-text"
+        );
+        assert!(!is_local_model_id("ollama:nomic-embed-text"));
+        config.provider = "cohere".to_string();
+        assert!(configured_model_id(&config).is_err());
+    }
+}
@@ -226,6 +226,56 @@ impl MmapVectorStorage {
         Ok(())
     }
 
+    /// Reads the vector stored at `index`, in write order, with its ID.
+    ///
+    /// Unlike [`read_vector`](Self::read_vector), no scan is needed.
+    #[must_use]
+    pub fn read_vector_at(&mut self, index: usize) -> Option<(VectorId, Vec<f32>)> {
+        self.ensure_mapped().ok()?;
+        let mmap = self.mmap.as_ref()?;
+
+        let dimension = self.dimension.get();
+        let vector_size = BYTES_PER_ID + dimension * BYTES_PER_F32;
+        let offset = HEADER_SIZE + index * vector_size;
+        if offset + vector_size > mmap.len() {
+            return None;
+        }
+
+        let id = VectorId::from_bytes([
+            mmap[offset],
+            mmap[offset + 1],
+            mmap[offset + 2],
+            mmap[offset + 3],
+        ])?;
+        let vector = mmap[offset + BYTES_PER_ID..offset + vector_size]
+            .chunks_exact(BYTES_PER_F32)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        Some((id, vector))
+    }
+
+    /// Reads the IDs of all vectors, in write order, without their data.
+    pub fn read_ids(&mut self) -> Result<Vec<VectorId>, VectorStorageError> {
+        self.ensure_mapped()?;
+        let mmap = self.mmap.as_ref().unwrap();
+
+        let vector_size = BYTES_PER_ID + self.dimension.get() * BYTES_PER_F32;
+        let mut ids = Vec::with_capacity(self.vector_count);
+        let mut offset = HEADER_SIZE;
+        while offset + vector_size <= mmap.len() {
+            let id = VectorId::from_bytes([
+                mmap[offset],
+                mmap[offset + 1],
+                mmap[offset + 2],
+                mmap[offset + 3],
+            ])
+            .ok_or_else(|| VectorStorageError::InvalidFormat("Invalid vector ID".to_string()))?;
+            ids.push(id);
+            offset += vector_size;
+        }
+        Ok(ids)
+    }
+
     /// Invalidates the memory map cache to force reload on next read.
     fn invalidate_cache(&mut self) {
         self.mmap = None;
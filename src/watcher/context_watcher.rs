@@ -19,6 +19,7 @@
 //! CxProcessor (integrated)
 //!   - Scans ~/.coditect/context-storage/exports-pending/
 //!   - Calls unified-message-extractor.py for each file
+//!   - Links messages to the files and symbols they mention
 //!   - Moves processed files to exports-archive/
 //!   - Generates processing reports in cx-processing-reports/
 //!   - Updates session log with processing results
@@ -41,6 +42,8 @@
 //! - `context_limit_tokens`: Total context window (default: 200,000)
 //! - `cooldown_minutes`: Time between exports (default: 10)
 //! - `cx_processing_interval_secs`: Auto /cx interval (default: 60)
+//! - `delta_export`: Export only content appended since the last export (default: true)
+//! - `export_overlap_bytes`: Already-exported tail repeated in each delta (default: 16 KiB)
 //!
 //! # CODI2 Heritage
 //!
@@ -94,6 +97,13 @@ pub struct ContextConfig {
     pub session_logs_dir: PathBuf,
     /// Path to machine-id.json
     pub machine_id_path: PathBuf,
+    /// Export only the content appended since the previous export of a session
+    pub delta_export: bool,
+    /// Bytes before the previous export's end to repeat in a delta, so the
+    /// extractor can line up the delta with what it already has
+    pub export_overlap_bytes: u64,
+    /// Store of message links to mentioned files and symbols
+    pub conversation_links_file: PathBuf,
 }
 
 impl Default for ContextConfig {
@@ -118,6 +128,11 @@ impl Default for ContextConfig {
             cx_reports_dir: coditect_dir.join("context-storage/cx-processing-reports"),
             session_logs_dir: coditect_dir.join("session-logs"),
             machine_id_path: coditect_dir.join("machine-id.json"),
+            delta_export: true,
+            export_overlap_bytes: 16 * 1024,
+            conversation_links_file: coditect_dir
+                .join("context-storage")
+                .join(crate::conversations::LINKS_FILE),
         }
     }
 }
@@ -455,6 +470,9 @@ pub struct WatcherState {
     /// Count of active Claude processes (for quick access)
     #[serde(default)]
     pub active_process_count: u32,
+    /// Per-session byte offset where the last export ended (session_id -> offset)
+    #[serde(default)]
+    pub export_offsets: HashMap<String, u64>,
 }
 
 impl Default for WatcherState {
@@ -470,8 +488,55 @@ impl Default for WatcherState {
             cx_runs_total: 0,
             active_processes: Vec::new(),
             active_process_count: 0,
+            export_offsets: HashMap::new(),
+        }
+    }
+}
+
+/// Copy the part of a session file appended since `last_offset` to `export_path`.
+///
+/// The copy starts `overlap` bytes before `last_offset`, moved forward to the
+/// next line boundary, and ends at the last complete line so a half-written
+/// entry is left for the next export. A file shorter than `last_offset` was
+/// rewritten and is exported from the start. Returns the offset the next
+/// export should resume from, or `None` if no complete line was appended.
+fn export_session_delta(
+    session_path: &Path,
+    export_path: &Path,
+    last_offset: Option<u64>,
+    overlap: u64,
+) -> std::io::Result<Option<u64>> {
+    let mut file = File::open(session_path)?;
+    let file_size = file.metadata()?.len();
+    let last_offset = last_offset.filter(|&offset| offset <= file_size).unwrap_or(0);
+
+    // Read from one byte early so we can tell whether `start` is already a line start
+    let start = last_offset.saturating_sub(overlap);
+    let base = start.saturating_sub(1);
+    file.seek(SeekFrom::Start(base))?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+
+    let begin = if start == 0 {
+        0
+    } else {
+        match buffer.iter().position(|&b| b == b'\n') {
+            Some(i) => i + 1,
+            None => return Ok(None),
         }
+    };
+    let end = match buffer.iter().rposition(|&b| b == b'\n') {
+        Some(i) => i + 1,
+        None => return Ok(None),
+    };
+
+    let next_offset = base + end as u64;
+    if begin >= end || next_offset <= last_offset {
+        return Ok(None);
     }
+
+    fs::write(export_path, &buffer[begin..end])?;
+    Ok(Some(next_offset))
 }
 
 /// Context watcher for Claude Code sessions
@@ -759,21 +824,40 @@ impl ContextWatcher {
     }
 
     /// Trigger export for a session
-    pub fn trigger_export(&mut self, session_path: &Path, context_pct: f64) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    ///
+    /// With `delta_export`, only content appended since this session's last
+    /// export is written. Returns `None` when there is nothing new to export.
+    pub fn trigger_export(&mut self, session_path: &Path, context_pct: f64) -> Result<Option<PathBuf>, Box<dyn std::error::Error + Send + Sync>> {
         let session_id = Self::session_id_from_path(session_path);
         let timestamp = Utc::now().format("%Y-%m-%d-%H%M%S").to_string();
         // Include session ID prefix (first 8 chars) in filename for clarity
         let session_prefix = &session_id[..session_id.len().min(8)];
+        let last_offset = if self.config.delta_export {
+            self.state.export_offsets.get(&session_id).copied()
+        } else {
+            None
+        };
         // Use .jsonl extension for consistency with unified message format
-        let filename = format!("{}-{}-CONTEXT-{:.0}pct-EXPORT.jsonl", timestamp, session_prefix, context_pct);
+        let kind = if last_offset.is_some() { "DELTA-EXPORT" } else { "EXPORT" };
+        let filename = format!("{timestamp}-{session_prefix}-CONTEXT-{context_pct:.0}pct-{kind}.jsonl");
         let export_path = self.config.export_destination.join(&filename);
 
-        // Copy session file to export destination
-        fs::copy(session_path, &export_path)?;
+        // Copy new session content to export destination
+        let next_offset = match export_session_delta(session_path, &export_path, last_offset, self.config.export_overlap_bytes)? {
+            Some(offset) => offset,
+            None => {
+                tracing::debug!(
+                    "[context-watcher] session {} has no new content since last export",
+                    session_prefix
+                );
+                return Ok(None);
+            }
+        };
 
-        // Update state with per-session cooldown
+        // Update state with per-session cooldown and export offset
         let now = Utc::now();
         self.state.session_cooldowns.insert(session_id.clone(), now);
+        self.state.export_offsets.insert(session_id.clone(), next_offset);
         self.state.last_export = Some(now);
         self.state.exports_triggered += 1;
         self.save_state()?;
@@ -793,7 +877,7 @@ impl ContextWatcher {
             context_pct
         );
 
-        Ok(export_path)
+        Ok(Some(export_path))
     }
 
     /// Check a single session and export if needed
@@ -821,8 +905,7 @@ impl ContextWatcher {
                 &session_id[..session_id.len().min(8)],
                 context_pct
             );
-            let export_path = self.trigger_export(session_file, context_pct)?;
-            return Ok(Some(export_path));
+            return self.trigger_export(session_file, context_pct);
         }
 
         Ok(None)
@@ -982,6 +1065,21 @@ impl ContextWatcher {
         })
     }
 
+    /// Record links from the export's messages to the files and symbols they mention
+    fn link_export(&self, file: &Path) {
+        let store = crate::conversations::LinkStore::new(&self.config.conversation_links_file);
+        let result = crate::conversations::links_from_export(file)
+            .and_then(|links| store.append(&links));
+        match result {
+            Ok(count) => {
+                tracing::debug!("[context-watcher] linked {} mention(s) from {}", count, file.display());
+            }
+            Err(e) => {
+                tracing::warn!("[context-watcher] failed to link {}: {}", file.display(), e);
+            }
+        }
+    }
+
     /// Move processed file to archive directory
     fn move_to_archive(&self, file: &Path) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
         let filename = file.file_name().ok_or("No filename")?;
@@ -1066,9 +1164,10 @@ impl ContextWatcher {
     /// This method:
     /// 1. Scans exports-pending/ for .jsonl and .txt files
     /// 2. Calls Python unified-message-extractor.py for each file
-    /// 3. Moves processed files to exports-archive/
-    /// 4. Generates a processing report in cx-processing-reports/
-    /// 5. Updates the session log with results
+    /// 3. Links messages to the files and symbols they mention
+    /// 4. Moves processed files to exports-archive/
+    /// 5. Generates a processing report in cx-processing-reports/
+    /// 6. Updates the session log with results
     pub fn process_pending_exports(&mut self) -> Result<Option<CxProcessingReport>, Box<dyn std::error::Error + Send + Sync>> {
         let pending_files = self.find_pending_exports();
 
@@ -1099,6 +1198,8 @@ impl ContextWatcher {
                         total_new += result.messages_new;
                         total_duplicate += result.messages_duplicate;
 
+                        self.link_export(file);
+
                         // Move to archive
                         match self.move_to_archive(file) {
                             Ok(archive_path) => {
@@ -1361,6 +1462,7 @@ mod tests {
             cx_runs_total: 0,
             active_processes: Vec::new(),
             active_process_count: 0,
+            export_offsets: HashMap::from([("abc".to_string(), 4096)]),
         };
 
         let json = serde_json::to_string(&state).unwrap();
@@ -1369,6 +1471,37 @@ mod tests {
         assert_eq!(restored.last_tokens, 150_000);
         assert_eq!(restored.exports_triggered, 5);
         assert_eq!(restored.active_process_count, 0);
+        assert_eq!(restored.export_offsets.get("abc"), Some(&4096));
+    }
+
+    #[test]
+    fn test_export_session_delta() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let session = dir.path().join("session.jsonl");
+        let export = dir.path().join("export.jsonl");
+
+        fs::write(&session, "{\"a\":1}\n{\"b\":2}\n{\"c\"").unwrap();
+        let offset = export_session_delta(&session, &export, None, 0).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&export).unwrap(), "{\"a\":1}\n{\"b\":2}\n");
+        assert_eq!(offset, 16);
+
+        // Nothing new until the partial line is completed
+        assert_eq!(export_session_delta(&session, &export, Some(offset), 0).unwrap(), None);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&session).unwrap();
+        file.write_all(b":3}\n{\"d\":4}\n").unwrap();
+        let next = export_session_delta(&session, &export, Some(offset), 0).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&export).unwrap(), "{\"c\":3}\n{\"d\":4}\n");
+
+        // Overlap repeats whole lines from the previous export
+        export_session_delta(&session, &export, Some(offset), 8).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&export).unwrap(), "{\"b\":2}\n{\"c\":3}\n{\"d\":4}\n");
+
+        // A rewritten (shorter) file is exported from the start
+        fs::write(&session, "{\"x\":1}\n").unwrap();
+        let restarted = export_session_delta(&session, &export, Some(next), 0).unwrap().unwrap();
+        assert_eq!(restarted, 8);
+        assert_eq!(fs::read_to_string(&export).unwrap(), "{\"x\":1}\n");
     }
 
     #[test]
@@ -0,0 +1,84 @@
+//! Pause/resume switch and counters shared with a running watcher.
+//!
+//! The daemon's control socket holds one side, the [`UnifiedWatcher`] the
+//! other. While paused, the watcher keeps recording file events but applies
+//! none of them; resuming applies everything that changed in the meantime.
+//!
+//! [`UnifiedWatcher`]: super::UnifiedWatcher
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Shared state between a watcher and whoever controls it.
+#[derive(Debug, Default)]
+pub struct WatchControl {
+    paused: AtomicBool,
+    pending: AtomicUsize,
+    reindexed: AtomicU64,
+    removed: AtomicU64,
+    /// Unix seconds of the last applied change, 0 if none.
+    last_change: AtomicU64,
+}
+
+impl WatchControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop applying changes; events are still collected.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Apply collected changes again.
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Changes waiting in the debouncer.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Files re-indexed since the watcher started.
+    pub fn reindexed(&self) -> u64 {
+        self.reindexed.load(Ordering::Relaxed)
+    }
+
+    /// Files removed from the index since the watcher started.
+    pub fn removed(&self) -> u64 {
+        self.removed.load(Ordering::Relaxed)
+    }
+
+    /// Unix seconds of the last applied change.
+    pub fn last_change(&self) -> Option<u64> {
+        let secs = self.last_change.load(Ordering::Relaxed);
+        (secs > 0).then_some(secs)
+    }
+
+    pub(crate) fn set_pending(&self, count: usize) {
+        self.pending.store(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_reindex(&self) {
+        self.reindexed.fetch_add(1, Ordering::Relaxed);
+        self.touch();
+    }
+
+    pub(crate) fn record_removal(&self) {
+        self.removed.fetch_add(1, Ordering::Relaxed);
+        self.touch();
+    }
+
+    fn touch(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.last_change.store(now, Ordering::Relaxed);
+    }
+}
@@ -59,13 +59,19 @@ impl Debouncer {
         ready
     }
 
+    /// Take every pending path regardless of how recently it changed.
+    ///
+    /// Used on shutdown so edits inside the debounce window are not lost.
+    pub fn take_all(&mut self) -> Vec<PathBuf> {
+        self.pending.drain().map(|(path, _)| path).collect()
+    }
+
     /// Check if there are any pending changes.
     pub fn has_pending(&self) -> bool {
         !self.pending.is_empty()
     }
 
     /// Get the number of pending changes.
-    #[allow(dead_code)]
     pub fn pending_count(&self) -> usize {
         self.pending.len()
     }
@@ -164,4 +170,16 @@ mod tests {
         debouncer.remove(&path);
         assert!(!debouncer.has_pending());
     }
+
+    #[test]
+    fn test_debouncer_take_all() {
+        let mut debouncer = Debouncer::new(10_000);
+
+        debouncer.record(PathBuf::from("/test/file.rs"));
+
+        // Nothing is stable yet, but shutdown takes everything
+        assert!(debouncer.take_ready().is_empty());
+        assert_eq!(debouncer.take_all().len(), 1);
+        assert!(!debouncer.has_pending());
+    }
 }
@@ -48,6 +48,14 @@ pub trait WatchHandler: Send + Sync {
     /// Used at startup to compute which directories to watch.
     async fn tracked_paths(&self) -> Vec<PathBuf>;
 
+    /// Whether events for paths excluded by ignore files are dropped.
+    ///
+    /// Code handlers opt in so build output and vendored trees stay out of
+    /// the index; handlers for configuration files keep the default.
+    fn respects_ignore_files(&self) -> bool {
+        false
+    }
+
     /// Handle a file modification event (called after debouncing).
     async fn on_modify(&self, path: &Path) -> Result<WatchAction, WatchError>;
 
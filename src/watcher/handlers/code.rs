@@ -82,6 +82,10 @@ impl WatchHandler for CodeFileHandler {
         }
     }
 
+    fn respects_ignore_files(&self) -> bool {
+        true
+    }
+
     async fn tracked_paths(&self) -> Vec<PathBuf> {
         let facade = self.facade.read().await;
         facade
@@ -30,7 +30,7 @@ impl ConfigFileHandler {
             reason: format!("Failed to load config: {e}"),
         })?;
 
-        let initial_paths: HashSet<PathBuf> = config.indexing.indexed_paths.into_iter().collect();
+        let initial_paths = indexed_paths(config, &settings_path);
 
         Ok(Self {
             settings_path,
@@ -46,7 +46,7 @@ impl ConfigFileHandler {
                 reason: format!("Failed to reload config: {e}"),
             })?;
 
-        let new_paths: HashSet<PathBuf> = new_config.indexing.indexed_paths.into_iter().collect();
+        let new_paths = indexed_paths(new_config, &self.settings_path);
 
         let last_paths = self.last_indexed_paths.read().await;
 
@@ -65,6 +65,20 @@ impl ConfigFileHandler {
     }
 }
 
+/// Indexed paths plus repository roots from a loaded settings file
+///
+/// Repository paths are relative to the workspace, the directory holding
+/// `.codanna/settings.toml`.
+fn indexed_paths(mut config: Settings, settings_path: &Path) -> HashSet<PathBuf> {
+    if config.workspace_root.is_none() {
+        config.workspace_root = settings_path
+            .parent()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf);
+    }
+    config.get_indexed_paths().into_iter().collect()
+}
+
 #[async_trait]
 impl WatchHandler for ConfigFileHandler {
     fn name(&self) -> &str {
@@ -26,6 +26,8 @@ pub struct HotReloadWatcher {
     persistence: IndexPersistence,
     last_modified: Option<SystemTime>,
     last_doc_modified: Option<SystemTime>,
+    /// Migrated semantic model last reloaded, or tried
+    last_semantic_model: Option<String>,
     check_interval: Duration,
     broadcaster: Option<Arc<NotificationBroadcaster>>,
 }
@@ -59,6 +61,7 @@ impl HotReloadWatcher {
             persistence,
             last_modified,
             last_doc_modified,
+            last_semantic_model: None,
             check_interval,
             broadcaster: None,
         }
@@ -88,6 +91,7 @@ impl HotReloadWatcher {
     async fn check_and_reload(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // Check for document store changes (state.json modified externally)
         self.check_document_changes();
+        self.check_semantic_model().await;
 
         // Check if index file exists
         if !self.persistence.exists() {
@@ -120,13 +124,17 @@ impl HotReloadWatcher {
                 let mut facade_guard = self.facade.write().await;
                 *facade_guard = new_facade;
 
+                // Release paths and names only the old index still used
+                let pruned = crate::types::intern::prune();
+                crate::debug_event!("hot-reload", "released", "{pruned} interned strings");
+
                 // Update last modified time
                 self.last_modified = Some(current_modified);
 
                 // Ensure semantic search stays attached after hot reloads
                 let mut restored_semantic = false;
                 if !facade_guard.has_semantic_search() {
-                    let semantic_path = self.index_path.join("semantic");
+                    let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.index_path);
                     let metadata_exists = semantic_path.join("metadata.json").exists();
                     if metadata_exists {
                         match facade_guard.load_semantic_search(&semantic_path) {
@@ -180,6 +188,37 @@ impl HotReloadWatcher {
     }
 
     /// Check if document store state.json has changed (documents indexed externally).
+    /// Reload semantic search once its saved embeddings come from another
+    /// model, as after `codanna semantic migrate` in another terminal
+    async fn check_semantic_model(&mut self) {
+        let semantic_path = crate::vector::VectorNamespace::Code.dir(&self.index_path);
+        let Ok(saved) = crate::semantic::SemanticMetadata::load(&semantic_path) else {
+            return;
+        };
+        let loaded = self
+            .facade
+            .read()
+            .await
+            .get_semantic_metadata()
+            .map(|metadata| metadata.model_name);
+        if loaded.is_none_or(|model| model == saved.model_name)
+            || self.last_semantic_model.as_ref() == Some(&saved.model_name)
+        {
+            return;
+        }
+        self.last_semantic_model = Some(saved.model_name.clone());
+
+        let mut facade_guard = self.facade.write().await;
+        match facade_guard.load_semantic_search(&semantic_path) {
+            Ok(true) => info!(
+                "Reloaded semantic search re-embedded with '{}'",
+                saved.model_name
+            ),
+            Ok(false) => {}
+            Err(e) => warn!("Failed to reload migrated semantic search: {e}"),
+        }
+    }
+
     fn check_document_changes(&mut self) {
         let doc_state_path = self.index_path.join("documents").join("state.json");
 
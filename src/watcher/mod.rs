@@ -27,6 +27,7 @@
 //!
 //! See `codi_fork/` for reference implementations from CODI2.
 
+mod control;
 mod debouncer;
 mod error;
 mod handler;
@@ -41,6 +42,7 @@ pub mod context_watcher;
 // CODI2 reference implementations (forked)
 pub mod codi_fork;
 
+pub use control::WatchControl;
 pub use debouncer::Debouncer;
 pub use error::WatchError;
 pub use handler::{WatchAction, WatchHandler};
@@ -1,8 +1,10 @@
 //! Path registry with interning and watch directory computation.
 //!
 //! Provides efficient path storage and lookup for the unified watcher.
-//! Paths are interned (stored once) to avoid duplicate allocations.
+//! Paths are interned with [`intern_path`], so the registry shares them
+//! with every other holder of the same path instead of copying them.
 
+use crate::types::intern_path;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -14,7 +16,7 @@ use std::sync::Arc;
 #[derive(Debug, Default)]
 pub struct PathRegistry {
     /// Interned paths - each unique path stored once.
-    paths: HashSet<Arc<PathBuf>>,
+    paths: HashSet<Arc<Path>>,
     /// Computed watch directories (parent dirs of tracked files).
     watch_dirs: HashSet<PathBuf>,
 }
@@ -33,7 +35,7 @@ impl PathRegistry {
 
         for path in paths {
             // Intern the path
-            let arc_path = Arc::new(path);
+            let arc_path = intern_path(&path);
             if self.paths.insert(arc_path.clone()) {
                 // New path - check if we need to watch its parent
                 if let Some(parent) = arc_path.parent() {
@@ -58,17 +60,17 @@ impl PathRegistry {
     /// Note: Does not remove watch directories even if empty, as other
     /// handlers might still have files there.
     pub fn remove_path(&mut self, path: &Path) {
-        self.paths.retain(|p| p.as_ref() != path);
+        self.paths.remove(path);
     }
 
     /// Check if a path is in the registry.
     pub fn contains(&self, path: &Path) -> bool {
-        self.paths.iter().any(|p| p.as_ref() == path)
+        self.paths.contains(path)
     }
 
     /// Get all tracked paths.
     pub fn paths(&self) -> impl Iterator<Item = &Path> {
-        self.paths.iter().map(|p| p.as_ref().as_path())
+        self.paths.iter().map(|p| &**p)
     }
 
     /// Get all watch directories.
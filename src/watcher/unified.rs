@@ -7,15 +7,19 @@ use std::sync::Arc;
 use notify::{Event, EventKind, RecursiveMode, Watcher};
 use tokio::sync::{RwLock, mpsc};
 use tokio::time::{Duration, sleep};
+use tokio_util::sync::CancellationToken;
 
+use crate::IndexPersistence;
 use crate::documents::DocumentStore;
 use crate::documents::config::ChunkingConfig;
 use crate::indexing::facade::IndexFacade;
-use crate::mcp::notifications::{FileChangeEvent, NotificationBroadcaster};
+use crate::indexing::ignore_rules::{IgnoreRules, is_ignore_file};
+use crate::mcp::notifications::{FileChangeEvent, NotificationBroadcaster, SymbolChanges};
 
 use super::debouncer::Debouncer;
 use super::error::WatchError;
 use super::handler::{WatchAction, WatchHandler};
+use super::control::WatchControl;
 use super::path_registry::PathRegistry;
 
 /// Unified file watcher with pluggable handlers.
@@ -45,6 +49,12 @@ pub struct UnifiedWatcher {
     index_path: PathBuf,
     /// Workspace root for path resolution.
     workspace_root: PathBuf,
+    /// `.gitignore`, `.ignore` and `.codannaignore` rules under the workspace root.
+    ignore_rules: IgnoreRules,
+    /// Cancelled when the server shuts down.
+    shutdown: CancellationToken,
+    /// Pause switch and counters, shared with the daemon's control socket.
+    control: Arc<WatchControl>,
 }
 
 impl UnifiedWatcher {
@@ -61,6 +71,9 @@ impl UnifiedWatcher {
     /// 3. Routes events to matching handlers
     /// 4. Executes returned actions
     /// 5. Broadcasts notifications
+    ///
+    /// Returns once the shutdown token is cancelled, after flushing pending
+    /// changes and saving index metadata.
     pub async fn watch(mut self) -> Result<(), WatchError> {
         // Initialize all handlers
         for handler in &self.handlers {
@@ -102,6 +115,8 @@ impl UnifiedWatcher {
 
         crate::log_event!("watcher", "started");
 
+        let shutdown = self.shutdown.clone();
+
         loop {
             // Periodic check for debounced events
             let timeout = sleep(Duration::from_millis(100));
@@ -120,12 +135,15 @@ impl UnifiedWatcher {
                     }
                 }
 
-                // Process debounced changes
+                // Process debounced changes; while paused they stay pending
                 _ = &mut timeout => {
-                    let ready = self.debouncer.take_ready();
-                    for path in ready {
-                        self.process_modification(&path).await;
+                    if !self.control.is_paused() {
+                        let ready = self.debouncer.take_ready();
+                        for path in ready {
+                            self.process_modification(&path).await;
+                        }
                     }
+                    self.control.set_pending(self.debouncer.pending_count());
                 }
 
                 // Handle broadcast notifications
@@ -134,10 +152,32 @@ impl UnifiedWatcher {
                         self.handle_index_reloaded().await;
                     }
                 }
+
+                _ = shutdown.cancelled() => {
+                    self.flush().await;
+                    crate::log_event!("watcher", "stopped");
+                    return Ok(());
+                }
             }
         }
     }
 
+    /// Apply changes still inside the debounce window and persist index metadata.
+    async fn flush(&mut self) {
+        let pending = self.debouncer.take_all();
+        if !pending.is_empty() {
+            crate::log_event!("watcher", "flushing", "{} pending changes", pending.len());
+        }
+        for path in pending {
+            self.process_modification(&path).await;
+        }
+
+        let facade = self.facade.read().await;
+        if let Err(e) = IndexPersistence::new(self.index_path.clone()).save_facade(&facade) {
+            tracing::error!("[watcher] failed to save index metadata: {e}");
+        }
+    }
+
     /// Watch a directory for changes.
     fn watch_directory(&mut self, dir: &PathBuf) -> Result<(), WatchError> {
         let watch_path = if dir.is_absolute() {
@@ -165,8 +205,22 @@ impl UnifiedWatcher {
     /// Handle an incoming file event.
     async fn handle_event(&mut self, event: Event) {
         for path in event.paths {
+            // An edited ignore file may now exclude files that are indexed
+            if is_ignore_file(&path)
+                && matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                )
+            {
+                self.reload_ignore_rules().await;
+                continue;
+            }
+
             // Check if any handler cares about this path
-            let matched = self.handlers.iter().any(|h| h.matches(&path));
+            let matched = self
+                .handlers
+                .iter()
+                .any(|h| h.matches(&path) && !self.ignores(h.as_ref(), &path));
             if !matched {
                 crate::trace_event!(
                     "watcher",
@@ -183,6 +237,10 @@ impl UnifiedWatcher {
                     // Debounce modifications
                     self.debouncer.record(path);
                 }
+                EventKind::Remove(_) if self.control.is_paused() => {
+                    // Applied on resume; a missing file is processed as a deletion
+                    self.debouncer.record(path);
+                }
                 EventKind::Remove(_) => {
                     // Handle deletions immediately
                     self.debouncer.remove(&path);
@@ -202,11 +260,12 @@ impl UnifiedWatcher {
         }
 
         for handler in &self.handlers {
-            if !handler.matches(path) {
+            if !handler.matches(path) || self.ignores(handler.as_ref(), path) {
                 continue;
             }
 
             crate::log_event!(handler.name(), "modified", "{}", path.display());
+            crate::mcp::maintenance::activity().record();
 
             match handler.on_modify(path).await {
                 Ok(action) => {
@@ -221,6 +280,46 @@ impl UnifiedWatcher {
         }
     }
 
+    /// Whether `handler` skips `path` because an ignore file excludes it.
+    fn ignores(&self, handler: &dyn WatchHandler, path: &Path) -> bool {
+        handler.respects_ignore_files() && self.ignore_rules.is_ignored(path, false)
+    }
+
+    /// Re-read ignore files and remove tracked files they now exclude.
+    async fn reload_ignore_rules(&mut self) {
+        self.ignore_rules.reload();
+        crate::log_event!("watcher", "ignore rules reloaded");
+
+        for handler in &self.handlers {
+            if !handler.respects_ignore_files() {
+                continue;
+            }
+
+            let ignored: Vec<PathBuf> = handler
+                .tracked_paths()
+                .await
+                .into_iter()
+                .filter(|path| self.ignore_rules.is_ignored(path, false))
+                .collect();
+
+            for path in ignored {
+                self.debouncer.remove(&path);
+                crate::log_event!(handler.name(), "ignored", "{}", path.display());
+
+                match handler.on_delete(&path).await {
+                    Ok(action) => {
+                        if let Err(e) = self.execute_action(action, handler.name()).await {
+                            tracing::error!("[{}] action error: {e}", handler.name());
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("[{}] handler error: {e}", handler.name());
+                    }
+                }
+            }
+        }
+    }
+
     /// Process a file deletion.
     async fn process_deletion(&self, path: &Path) {
         for handler in &self.handlers {
@@ -229,6 +328,7 @@ impl UnifiedWatcher {
             }
 
             crate::log_event!(handler.name(), "deleted", "{}", path.display());
+            crate::mcp::maintenance::activity().record();
 
             match handler.on_delete(path).await {
                 Ok(action) => {
@@ -252,16 +352,19 @@ impl UnifiedWatcher {
         match action {
             WatchAction::ReindexCode { path } => {
                 let mut indexer = self.facade.write().await;
+                let before = file_symbols(&indexer, &path);
                 match indexer.index_file(&path) {
                     Ok(result) => {
                         use crate::IndexingResult;
                         match result {
-                            IndexingResult::Indexed(_) => {
+                            IndexingResult::Indexed(file_id) => {
                                 crate::log_event!(handler_name, "reindexed");
+                                self.control.record_reindex();
 
                                 // Save semantic search
                                 if indexer.has_semantic_search() {
-                                    let semantic_path = self.index_path.join("semantic");
+                                    let semantic_path =
+                                        crate::vector::VectorNamespace::Code.dir(&self.index_path);
                                     if let Err(e) = indexer.save_semantic_search(&semantic_path) {
                                         tracing::warn!(
                                             "[{handler_name}] failed to save semantic search: {e}"
@@ -270,8 +373,11 @@ impl UnifiedWatcher {
                                 }
 
                                 // Notify
-                                self.broadcaster
-                                    .send(FileChangeEvent::FileReindexed { path: path.clone() });
+                                let after = indexer.get_symbols_by_file(file_id);
+                                self.broadcaster.send(FileChangeEvent::FileReindexed {
+                                    path: path.clone(),
+                                    symbols: Some(SymbolChanges::between(&before, &after)),
+                                });
                             }
                             IndexingResult::Cached(_) => {
                                 crate::debug_event!(handler_name, "unchanged (hash match)");
@@ -286,12 +392,16 @@ impl UnifiedWatcher {
 
             WatchAction::RemoveCode { path } => {
                 let mut indexer = self.facade.write().await;
+                let before = file_symbols(&indexer, &path);
                 if let Err(e) = indexer.remove_file(&path) {
                     tracing::error!("[{handler_name}] failed to remove: {e}");
                 } else {
                     crate::log_event!(handler_name, "removed");
-                    self.broadcaster
-                        .send(FileChangeEvent::FileDeleted { path: path.clone() });
+                    self.control.record_removal();
+                    self.broadcaster.send(FileChangeEvent::FileDeleted {
+                        path: path.clone(),
+                        symbols: Some(SymbolChanges::between(&before, &[])),
+                    });
                 }
             }
 
@@ -301,8 +411,10 @@ impl UnifiedWatcher {
                     match store.reindex_file(&path, &self.chunking_config) {
                         Ok(Some(chunks)) => {
                             crate::log_event!(handler_name, "reindexed", "{chunks} chunks");
-                            self.broadcaster
-                                .send(FileChangeEvent::FileReindexed { path: path.clone() });
+                            self.broadcaster.send(FileChangeEvent::FileReindexed {
+                                path: path.clone(),
+                                symbols: None,
+                            });
                         }
                         Ok(None) => {
                             crate::debug_event!(handler_name, "not in index, skipped");
@@ -320,8 +432,10 @@ impl UnifiedWatcher {
                     match store.remove_file(&path) {
                         Ok(true) => {
                             crate::log_event!(handler_name, "removed");
-                            self.broadcaster
-                                .send(FileChangeEvent::FileDeleted { path: path.clone() });
+                            self.broadcaster.send(FileChangeEvent::FileDeleted {
+                                path: path.clone(),
+                                symbols: None,
+                            });
                         }
                         Ok(false) => {
                             crate::debug_event!(handler_name, "was not in index");
@@ -426,6 +540,14 @@ impl UnifiedWatcher {
     }
 }
 
+/// Symbols the index holds for the file at `path`, empty if it is not indexed
+fn file_symbols(indexer: &IndexFacade, path: &Path) -> Vec<crate::Symbol> {
+    indexer
+        .resolve_indexed_file(&path.to_string_lossy())
+        .map(|(file_id, _)| indexer.get_symbols_by_file(file_id))
+        .unwrap_or_default()
+}
+
 /// Builder for constructing a UnifiedWatcher.
 pub struct UnifiedWatcherBuilder {
     handlers: Vec<Box<dyn WatchHandler>>,
@@ -436,6 +558,8 @@ pub struct UnifiedWatcherBuilder {
     index_path: Option<PathBuf>,
     workspace_root: Option<PathBuf>,
     debounce_ms: u64,
+    shutdown: Option<CancellationToken>,
+    control: Option<Arc<WatchControl>>,
 }
 
 impl UnifiedWatcherBuilder {
@@ -450,6 +574,8 @@ impl UnifiedWatcherBuilder {
             index_path: None,
             workspace_root: None,
             debounce_ms: 500,
+            shutdown: None,
+            control: None,
         }
     }
 
@@ -501,6 +627,18 @@ impl UnifiedWatcherBuilder {
         self
     }
 
+    /// Set the token that stops the watcher on server shutdown.
+    pub fn shutdown_token(mut self, token: CancellationToken) -> Self {
+        self.shutdown = Some(token);
+        self
+    }
+
+    /// Share a pause switch and counters with the watcher.
+    pub fn control(mut self, control: Arc<WatchControl>) -> Self {
+        self.control = Some(control);
+        self
+    }
+
     /// Build the UnifiedWatcher.
     pub fn build(self) -> Result<UnifiedWatcher, WatchError> {
         let broadcaster = self.broadcaster.ok_or_else(|| WatchError::InitFailed {
@@ -519,6 +657,8 @@ impl UnifiedWatcherBuilder {
             .index_path
             .unwrap_or_else(|| workspace_root.join(".codanna/index"));
 
+        let ignore_rules = IgnoreRules::new(workspace_root.clone());
+
         // Create channel for events
         let (tx, rx) = mpsc::channel(100);
 
@@ -539,6 +679,9 @@ impl UnifiedWatcherBuilder {
             chunking_config: self.chunking_config,
             index_path,
             workspace_root,
+            ignore_rules,
+            shutdown: self.shutdown.unwrap_or_default(),
+            control: self.control.unwrap_or_default(),
         })
     }
 }
@@ -67,6 +67,7 @@ async fn test_gdscript_semantic_search_and_analyze_impact() {
             limit: 1,
             threshold: None,
             lang: Some("gdscript".to_string()),
+            filters: Default::default(),
         }))
         .await
         .expect("semantic_search_with_context should succeed");
@@ -97,6 +98,7 @@ async fn test_gdscript_semantic_search_and_analyze_impact() {
         .analyze_impact(Parameters(AnalyzeImpactRequest {
             symbol_name: None,
             symbol_id: Some(apply_damage_symbol_id),
+            file_path: None,
             max_depth: 2,
         }))
         .await
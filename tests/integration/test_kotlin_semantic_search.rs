@@ -168,6 +168,8 @@ async fn test_kotlin_semantic_search_and_dependency_injection() {
             limit: 5,
             threshold: None,
             lang: Some("kotlin".to_string()),
+            mode: None,
+            filters: Default::default(),
         }))
         .await
         .expect("semantic_search_docs should succeed");
@@ -194,6 +196,7 @@ async fn test_kotlin_semantic_search_and_dependency_injection() {
             limit: 3,
             threshold: None,
             lang: Some("kotlin".to_string()),
+            filters: Default::default(),
         }))
         .await
         .expect("semantic_search_with_context should succeed");
@@ -332,6 +335,8 @@ class UserService(
             limit: 10,
             threshold: None,
             lang: Some("kotlin".to_string()),
+            mode: None,
+            filters: Default::default(),
         }))
         .await
         .expect("semantic_search_docs should succeed");
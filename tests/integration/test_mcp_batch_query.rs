@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use codanna::config::Settings;
+use codanna::indexing::facade::IndexFacade;
+use codanna::mcp::{BatchQueryRequest, BatchSubQuery, CodeIntelligenceServer};
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::RawContent;
+use serde_json::json;
+use tempfile::TempDir;
+
+const CALL_CHAIN: &str = r#"
+fn leaf() {}
+
+fn middle() {
+    leaf();
+}
+"#;
+
+fn sub_query(key: Option<&str>, tool: &str, arguments: serde_json::Value) -> BatchSubQuery {
+    BatchSubQuery {
+        key: key.map(str::to_string),
+        tool: tool.to_string(),
+        arguments: arguments.as_object().cloned().unwrap_or_default(),
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_batch_query_returns_keyed_results_in_order() {
+    let temp_dir = TempDir::new().expect("create temp dir");
+    let workspace_root = temp_dir.path();
+    let file_path = workspace_root.join("chain.rs");
+    std::fs::write(&file_path, CALL_CHAIN).expect("write fixture");
+
+    let settings = Settings {
+        workspace_root: Some(workspace_root.to_path_buf()),
+        index_path: workspace_root.join(".codanna-index"),
+        ..Default::default()
+    };
+    let mut indexer = IndexFacade::new(Arc::new(settings)).expect("create IndexFacade");
+    indexer.index_file(&file_path).expect("index fixture");
+    let server = CodeIntelligenceServer::new(indexer);
+
+    let result = server
+        .batch_query(Parameters(BatchQueryRequest {
+            queries: vec![
+                sub_query(
+                    Some("callers"),
+                    "find_callers",
+                    json!({ "function_name": "leaf" }),
+                ),
+                sub_query(None, "get_calls", json!({ "function_name": "middle" })),
+                sub_query(Some("bad"), "get_index_info", json!({})),
+            ],
+        }))
+        .await
+        .expect("batch_query should succeed");
+    let text = result
+        .content
+        .iter()
+        .filter_map(|content| match &content.raw {
+            RawContent::Text(block) => Some(block.text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    assert!(
+        text.starts_with("Batch of 3 queries (1 failed)"),
+        "got:\n{text}"
+    );
+    let callers = text
+        .find("=== callers (find_callers) ===")
+        .expect("callers section");
+    let calls = text.find("=== 2 (get_calls) ===").expect("calls section");
+    let bad = text
+        .find("=== bad (get_index_info) ===")
+        .expect("bad section");
+    assert!(callers < calls && calls < bad, "got:\n{text}");
+    assert!(text[callers..calls].contains("middle"), "got:\n{text}");
+    assert!(text[calls..bad].contains("leaf"), "got:\n{text}");
+    assert!(
+        text[bad..].contains("Error: unsupported tool 'get_index_info'"),
+        "got:\n{text}"
+    );
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use codanna::config::Settings;
+use codanna::indexing::facade::IndexFacade;
+use codanna::mcp::{CodeIntelligenceServer, FindCallersRequest};
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::RawContent;
+use tempfile::TempDir;
+
+const CALL_CHAIN: &str = r#"
+fn leaf() {}
+
+fn middle() {
+    leaf();
+}
+
+fn top() {
+    middle();
+}
+
+fn recurse(n: u32) {
+    if n > 0 {
+        recurse(n - 1);
+    }
+    leaf();
+}
+"#;
+
+fn text_of(result: &rmcp::model::CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|content| match &content.raw {
+            RawContent::Text(block) => Some(block.text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_find_callers_walks_transitive_callers() {
+    let temp_dir = TempDir::new().expect("create temp dir");
+    let workspace_root = temp_dir.path();
+    let file_path = workspace_root.join("chain.rs");
+    std::fs::write(&file_path, CALL_CHAIN).expect("write fixture");
+
+    let settings = Settings {
+        workspace_root: Some(workspace_root.to_path_buf()),
+        index_path: workspace_root.join(".codanna-index"),
+        ..Default::default()
+    };
+    let mut indexer = IndexFacade::new(Arc::new(settings)).expect("create IndexFacade");
+    indexer.index_file(&file_path).expect("index fixture");
+    let server = CodeIntelligenceServer::new(indexer);
+
+    let result = server
+        .find_callers(Parameters(FindCallersRequest {
+            function_name: Some("leaf".to_string()),
+            symbol_id: None,
+            offset: 0,
+            limit: None,
+            depth: 3,
+        }))
+        .await
+        .expect("find_callers should succeed");
+    let text = text_of(&result);
+
+    assert!(text.starts_with("Call tree of leaf"), "got:\n{text}");
+    assert!(text.contains("\n  <- Function middle at "), "got:\n{text}");
+    assert!(text.contains("\n    <- Function top at "), "got:\n{text}");
+    // The recursive call is listed once, not followed down to the depth limit
+    assert!(text.contains("\n  <- Function recurse at "), "got:\n{text}");
+    assert!(
+        !text.contains("\n      <- Function recurse"),
+        "got:\n{text}"
+    );
+
+    // The tree starts at the first direct caller, so paging is refused
+    let result = server
+        .find_callers(Parameters(FindCallersRequest {
+            function_name: Some("leaf".to_string()),
+            symbol_id: None,
+            offset: 1,
+            limit: None,
+            depth: 3,
+        }))
+        .await
+        .expect("find_callers should return a tool error");
+    assert_eq!(result.is_error, Some(true));
+    assert!(text_of(&result).contains("depth > 1"));
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use codanna::config::Settings;
+use codanna::indexing::facade::IndexFacade;
+use codanna::mcp::{CodeIntelligenceServer, FindImplementationsRequest};
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::RawContent;
+use tempfile::TempDir;
+
+const SHAPES: &str = r#"
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+pub struct Circle(f64);
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14 * self.0 * self.0
+    }
+}
+"#;
+
+async fn find_implementations(server: &CodeIntelligenceServer, symbol_id: u32) -> String {
+    let result = server
+        .find_implementations(Parameters(FindImplementationsRequest {
+            symbol_name: None,
+            symbol_id: Some(symbol_id),
+            lang: None,
+        }))
+        .await
+        .expect("find_implementations should succeed");
+    result
+        .content
+        .iter()
+        .filter_map(|content| match &content.raw {
+            RawContent::Text(block) => Some(block.text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_find_implementations_of_trait_and_method() {
+    let temp_dir = TempDir::new().expect("create temp dir");
+    let workspace_root = temp_dir.path();
+    let file_path = workspace_root.join("shapes.rs");
+    std::fs::write(&file_path, SHAPES).expect("write fixture");
+
+    let settings = Settings {
+        workspace_root: Some(workspace_root.to_path_buf()),
+        index_path: workspace_root.join(".codanna-index"),
+        ..Default::default()
+    };
+    let mut indexer = IndexFacade::new(Arc::new(settings)).expect("create IndexFacade");
+    indexer.index_file(&file_path).expect("index fixture");
+
+    let shape = indexer.find_symbols_by_name("Shape", None)[0].id.value();
+    let trait_area = indexer
+        .find_symbols_by_name("area", None)
+        .into_iter()
+        .min_by_key(|symbol| symbol.range.start_line)
+        .expect("trait method is indexed")
+        .id
+        .value();
+    let server = CodeIntelligenceServer::new(indexer);
+
+    let types = find_implementations(&server, shape).await;
+    assert!(types.starts_with("2 type(s)"), "got:\n{types}");
+    assert!(types.contains("Square at "), "got:\n{types}");
+    assert!(types.contains("Circle at "), "got:\n{types}");
+    assert!(types.contains("(implements Shape)"), "got:\n{types}");
+
+    let methods = find_implementations(&server, trait_area).await;
+    assert!(methods.starts_with("2 method(s)"), "got:\n{methods}");
+    assert!(methods.contains("shapes.rs:9 "), "got:\n{methods}");
+    assert!(methods.contains("shapes.rs:17 "), "got:\n{methods}");
+}
@@ -54,3 +54,12 @@ mod test_kotlin_semantic_search;
 
 #[path = "integration/test_pipeline_parse_stage.rs"]
 mod test_pipeline_parse_stage;
+
+#[path = "integration/test_mcp_find_callers.rs"]
+mod test_mcp_find_callers;
+
+#[path = "integration/test_mcp_find_implementations.rs"]
+mod test_mcp_find_implementations;
+
+#[path = "integration/test_mcp_batch_query.rs"]
+mod test_mcp_batch_query;
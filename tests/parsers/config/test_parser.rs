@@ -0,0 +1,88 @@
+use codanna::parsing::LanguageParser;
+use codanna::parsing::config::{ConfigParser, is_config_file};
+use codanna::types::{FileId, SymbolCounter, SymbolKind};
+use std::path::Path;
+
+fn build_parser() -> (ConfigParser, FileId, SymbolCounter) {
+    let parser = ConfigParser::new().expect("Failed to create config parser");
+    let file_id = FileId::new(1).expect("Invalid file id");
+    let counter = SymbolCounter::new();
+    (parser, file_id, counter)
+}
+
+const CARGO_TOML: &str = r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+# Runtime dependencies
+[dependencies]
+core-lib = { path = "../core" }
+serde = "1"
+
+[dev-dependencies]
+test-utils = { path = "../test-utils" }
+"#;
+
+const PACKAGE_JSON: &str = r#"{
+  "name": "@acme/web",
+  "scripts": { "build": "vite build" },
+  "dependencies": { "@acme/ui": "workspace:*" }
+}"#;
+
+#[test]
+fn test_config_parser_indexes_top_level_keys_and_package() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(CARGO_TOML, file_id, &mut counter);
+
+    let names: Vec<_> = symbols.iter().map(|s| s.name.as_ref()).collect();
+    assert!(names.contains(&"package"), "got {names:?}");
+    assert!(names.contains(&"dependencies"), "got {names:?}");
+    assert!(names.contains(&"dev-dependencies"), "got {names:?}");
+
+    let package = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "app")
+        .expect("package symbol");
+    assert_eq!(package.kind, SymbolKind::Module);
+    assert_eq!(package.signature.as_deref(), Some("package app"));
+
+    let dependencies = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "dependencies")
+        .unwrap();
+    assert_eq!(
+        dependencies.doc_comment.as_deref(),
+        Some("Runtime dependencies")
+    );
+}
+
+#[test]
+fn test_config_parser_links_dependencies_to_sections() {
+    let (mut parser, _, _) = build_parser();
+
+    let uses = parser.find_uses(CARGO_TOML);
+    let pairs: Vec<_> = uses.iter().map(|(from, to, _)| (*from, *to)).collect();
+    assert!(
+        pairs.contains(&("dependencies", "core-lib")),
+        "got {pairs:?}"
+    );
+    assert!(pairs.contains(&("dependencies", "serde")), "got {pairs:?}");
+    assert!(
+        pairs.contains(&("dev-dependencies", "test-utils")),
+        "got {pairs:?}"
+    );
+
+    let uses = parser.find_uses(PACKAGE_JSON);
+    let pairs: Vec<_> = uses.iter().map(|(from, to, _)| (*from, *to)).collect();
+    assert_eq!(pairs, vec![("dependencies", "@acme/ui")]);
+}
+
+#[test]
+fn test_only_manifests_and_ci_files_are_config() {
+    assert!(is_config_file(Path::new("crates/core/Cargo.toml")));
+    assert!(is_config_file(Path::new(".github/workflows/ci.yml")));
+    assert!(is_config_file(Path::new(".gitlab-ci.yml")));
+    assert!(!is_config_file(Path::new("package-lock.json")));
+    assert!(!is_config_file(Path::new("config/settings.yaml")));
+}
@@ -0,0 +1,126 @@
+use codanna::parsing::LanguageParser;
+use codanna::parsing::hcl::HclParser;
+use codanna::types::{FileId, SymbolCounter, SymbolKind};
+
+fn build_parser() -> (HclParser, FileId, SymbolCounter) {
+    let parser = HclParser::new().expect("Failed to create HCL parser");
+    let file_id = FileId::new(1).expect("Invalid file id");
+    let counter = SymbolCounter::new();
+    (parser, file_id, counter)
+}
+
+const MAIN_TF: &str = r#"
+variable "region" {
+  type    = string
+  default = "us-east-1"
+}
+
+locals {
+  common_tags = {
+    Region = var.region
+  }
+}
+
+# Network for all services
+module "vpc" {
+  source = "./modules/vpc"
+  cidr   = "10.0.0.0/16"
+}
+
+data "aws_ami" "ubuntu" {
+  most_recent = true
+}
+
+resource "aws_instance" "web" {
+  ami           = data.aws_ami.ubuntu.id
+  subnet_id     = module.vpc.private_subnets[0]
+  tags          = local.common_tags
+  depends_on    = [aws_security_group.web_sg]
+}
+
+resource "aws_security_group" "web_sg" {
+  vpc_id = module.vpc.vpc_id
+}
+
+output "instance_ip" {
+  value = aws_instance.web.private_ip
+}
+"#;
+
+#[test]
+fn test_hcl_parser_extracts_blocks() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(MAIN_TF, file_id, &mut counter);
+
+    let kind_of = |name: &str| {
+        symbols
+            .iter()
+            .find(|s| s.name.as_ref() == name)
+            .map(|s| s.kind)
+            .unwrap_or_else(|| panic!("{name} should be extracted"))
+    };
+
+    assert_eq!(kind_of("region"), SymbolKind::Variable);
+    assert_eq!(kind_of("common_tags"), SymbolKind::Variable);
+    assert_eq!(kind_of("vpc"), SymbolKind::Module);
+    assert_eq!(kind_of("ubuntu"), SymbolKind::Struct);
+    assert_eq!(kind_of("web"), SymbolKind::Struct);
+    assert_eq!(kind_of("instance_ip"), SymbolKind::Constant);
+}
+
+#[test]
+fn test_hcl_parser_keeps_address_in_signature_and_docs() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(MAIN_TF, file_id, &mut counter);
+
+    let web = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "web")
+        .expect("web resource should be extracted");
+    assert_eq!(
+        web.signature.as_deref(),
+        Some(r#"resource "aws_instance" "web""#)
+    );
+
+    let vpc = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "vpc")
+        .expect("vpc module should be extracted");
+    assert!(
+        vpc.doc_comment
+            .as_deref()
+            .is_some_and(|doc| doc.contains("Network for all services")),
+        "Module should keep its leading comment"
+    );
+}
+
+#[test]
+fn test_hcl_parser_finds_references() {
+    let (mut parser, _, _) = build_parser();
+    let uses = parser.find_uses(MAIN_TF);
+
+    for (from, to) in [
+        ("common_tags", "region"),
+        ("web", "ubuntu"),
+        ("web", "vpc"),
+        ("web", "common_tags"),
+        ("web", "web_sg"),
+        ("web_sg", "vpc"),
+        ("instance_ip", "web"),
+    ] {
+        assert!(
+            uses.iter().any(|(f, t, _)| *f == from && *t == to),
+            "Expected {from} -> {to}, got {uses:?}"
+        );
+    }
+}
+
+#[test]
+fn test_hcl_parser_module_sources_are_imports() {
+    let (mut parser, file_id, _) = build_parser();
+    let imports = parser.find_imports(MAIN_TF, file_id);
+
+    assert_eq!(imports.len(), 1, "got {imports:?}");
+    assert_eq!(imports[0].path, "./modules/vpc");
+    assert_eq!(imports[0].alias.as_deref(), Some("vpc"));
+}
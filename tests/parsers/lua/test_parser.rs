@@ -0,0 +1,174 @@
+use codanna::Visibility;
+use codanna::parsing::LanguageParser;
+use codanna::parsing::lua::LuaParser;
+use codanna::symbol::ScopeContext;
+use codanna::types::{FileId, SymbolCounter, SymbolKind};
+
+fn build_parser() -> (LuaParser, FileId, SymbolCounter) {
+    let parser = LuaParser::new().expect("Failed to create Lua parser");
+    let file_id = FileId::new(1).expect("Invalid file id");
+    let counter = SymbolCounter::new();
+    (parser, file_id, counter)
+}
+
+const INVENTORY: &str = r#"
+local json = require("dkjson")
+local util = require "game.util"
+
+--- Inventory module for the player
+local Inventory = {}
+
+--- Maximum number of slots
+MAX_SLOTS = 32
+
+--- Create a new inventory
+function Inventory.new(owner)
+    local self = setmetatable({}, { __index = Inventory })
+    self.owner = owner
+    return self
+end
+
+--- Add an item to the inventory
+function Inventory:add(item)
+    util.validate(item)
+    table.insert(self.items, item)
+end
+
+local function serialize(inv)
+    return json.encode(inv)
+end
+
+Inventory.save = function(inv)
+    return serialize(inv)
+end
+
+return Inventory
+"#;
+
+#[test]
+fn test_lua_parser_extracts_module_table_and_members() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(INVENTORY, file_id, &mut counter);
+
+    let chunk = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "<chunk>")
+        .expect("Chunk should generate a module symbol");
+    assert_eq!(chunk.kind, SymbolKind::Module);
+
+    let inventory = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "Inventory")
+        .expect("Inventory table should be extracted");
+    assert_eq!(inventory.kind, SymbolKind::Module);
+    assert!(
+        inventory
+            .doc_comment
+            .as_deref()
+            .is_some_and(|doc| doc.contains("Inventory module")),
+        "Module table should keep its doc comment"
+    );
+
+    let new_fn = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "new")
+        .expect("Inventory.new should be extracted");
+    assert_eq!(new_fn.kind, SymbolKind::Function);
+    assert_eq!(
+        new_fn.scope_context,
+        Some(ScopeContext::ClassMember {
+            class_name: Some("Inventory".into())
+        })
+    );
+    assert_eq!(
+        new_fn.signature.as_deref(),
+        Some("function Inventory.new(owner)")
+    );
+
+    let add = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "add")
+        .expect("Inventory:add should be extracted");
+    assert_eq!(add.kind, SymbolKind::Method);
+
+    let save = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "save")
+        .expect("Inventory.save assignment should be extracted");
+    assert_eq!(save.kind, SymbolKind::Function);
+
+    let max_slots = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "MAX_SLOTS")
+        .expect("Global constant should be extracted");
+    assert_eq!(max_slots.kind, SymbolKind::Constant);
+
+    // Locals inside functions are not indexed
+    assert!(
+        !symbols.iter().any(|s| s.name.as_ref() == "self"),
+        "Function locals should not be indexed"
+    );
+}
+
+#[test]
+fn test_lua_local_function_is_private() {
+    use codanna::parsing::LanguageBehavior;
+    use codanna::parsing::lua::LuaBehavior;
+
+    let (mut parser, file_id, mut counter) = build_parser();
+    let mut symbols = parser.parse(INVENTORY, file_id, &mut counter);
+    let behavior = LuaBehavior::new();
+
+    let serialize = symbols
+        .iter_mut()
+        .find(|s| s.name.as_ref() == "serialize")
+        .expect("Local function should be extracted");
+    behavior.configure_symbol(serialize, Some("game.inventory"));
+    assert_eq!(serialize.visibility, Visibility::Private);
+}
+
+#[test]
+fn test_lua_require_imports() {
+    let (mut parser, file_id, _) = build_parser();
+    let imports = parser.find_imports(INVENTORY, file_id);
+
+    let paths: Vec<&str> = imports.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(paths, vec!["dkjson", "game.util"]);
+    assert_eq!(imports[0].alias.as_deref(), Some("json"));
+    assert_eq!(imports[1].alias.as_deref(), Some("util"));
+}
+
+#[test]
+fn test_lua_calls_and_defines() {
+    let (mut parser, _, _) = build_parser();
+
+    let calls = parser.find_calls(INVENTORY);
+    assert!(
+        calls
+            .iter()
+            .any(|(caller, callee, _)| *caller == "add" && *callee == "validate"),
+        "Expected add -> validate call, got {calls:?}"
+    );
+    assert!(
+        calls
+            .iter()
+            .any(|(caller, callee, _)| *caller == "serialize" && *callee == "encode"),
+        "Expected serialize -> encode call, got {calls:?}"
+    );
+    assert!(
+        !calls.iter().any(|(_, callee, _)| *callee == "require"),
+        "require() should be tracked as an import, not a call"
+    );
+
+    let defines = parser.find_defines(INVENTORY);
+    assert!(
+        defines
+            .iter()
+            .any(|(table, name, _)| *table == "Inventory" && *name == "new")
+    );
+    assert!(
+        defines
+            .iter()
+            .any(|(table, name, _)| *table == "Inventory" && *name == "add")
+    );
+}
@@ -0,0 +1,156 @@
+use codanna::parsing::LanguageParser;
+use codanna::parsing::objc::ObjCParser;
+use codanna::types::{FileId, SymbolCounter, SymbolKind};
+
+fn build_parser() -> (ObjCParser, FileId, SymbolCounter) {
+    let parser = ObjCParser::new().expect("Failed to create Objective-C parser");
+    let file_id = FileId::new(1).expect("Invalid file id");
+    let counter = SymbolCounter::new();
+    (parser, file_id, counter)
+}
+
+const CLIENT: &str = r#"
+#import <Foundation/Foundation.h>
+#import "Loading.h"
+@import Network;
+
+@protocol Retrying <NSObject>
+- (void)retryAfter:(NSTimeInterval)delay;
+@end
+
+/// Loads remote resources
+@interface Client : NSObject <Loading>
+@property (nonatomic, copy) NSString *baseURL;
++ (instancetype)sharedClient;
+- (NSData *)fetch:(NSURL *)url cache:(BOOL)cache;
+@end
+
+@interface Client (Retry) <Retrying>
+- (void)retryAfter:(NSTimeInterval)delay;
+@end
+
+@implementation Client
++ (instancetype)sharedClient {
+    return [[Client alloc] init];
+}
+
+- (NSData *)fetch:(NSURL *)url cache:(BOOL)cache {
+    [self log:url];
+    return nil;
+}
+@end
+
+static void logRequest(NSURL *url) {
+    NSLog(@"%@", url);
+}
+"#;
+
+#[test]
+fn test_objc_parser_extracts_classes_protocols_and_methods() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(CLIENT, file_id, &mut counter);
+
+    let kinds_of = |name: &str| {
+        symbols
+            .iter()
+            .filter(|s| s.name.as_ref() == name)
+            .map(|s| s.kind)
+            .collect::<Vec<_>>()
+    };
+
+    // The interface creates the class; the implementation does not repeat it
+    assert_eq!(kinds_of("Client"), vec![SymbolKind::Class]);
+    assert_eq!(kinds_of("Retrying"), vec![SymbolKind::Interface]);
+    assert_eq!(kinds_of("baseURL"), vec![SymbolKind::Field]);
+    assert_eq!(kinds_of("logRequest"), vec![SymbolKind::Function]);
+    // Declared in the interface and implemented in this file: one symbol
+    assert_eq!(kinds_of("fetch"), vec![SymbolKind::Method]);
+    assert_eq!(kinds_of("sharedClient"), vec![SymbolKind::Method]);
+
+    let client = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "Client")
+        .unwrap();
+    assert_eq!(
+        client.doc_comment.as_deref(),
+        Some("Loads remote resources")
+    );
+
+    let fetch = symbols.iter().find(|s| s.name.as_ref() == "fetch").unwrap();
+    assert!(
+        fetch
+            .signature
+            .as_deref()
+            .unwrap()
+            .contains("fetch:(NSURL *)url cache:(BOOL)cache"),
+        "Signature should keep the full selector"
+    );
+}
+
+#[test]
+fn test_objc_category_methods_belong_to_base_class() {
+    let (mut parser, _, _) = build_parser();
+
+    let defines = parser.find_defines(CLIENT);
+    assert!(
+        defines
+            .iter()
+            .any(|(owner, method, _)| *owner == "Client" && *method == "retryAfter"),
+        "Category methods should be defined by the base class, got {defines:?}"
+    );
+    assert!(
+        defines
+            .iter()
+            .any(|(owner, method, _)| *owner == "Retrying" && *method == "retryAfter")
+    );
+
+    let implementations = parser.find_implementations(CLIENT);
+    assert!(
+        implementations
+            .iter()
+            .any(|(class, protocol, _)| *class == "Client" && *protocol == "Loading")
+    );
+    assert!(
+        implementations
+            .iter()
+            .any(|(class, protocol, _)| *class == "Client" && *protocol == "Retrying")
+    );
+
+    let extends = parser.find_extends(CLIENT);
+    assert!(
+        extends
+            .iter()
+            .any(|(class, parent, _)| *class == "Client" && *parent == "NSObject")
+    );
+    assert!(
+        extends
+            .iter()
+            .any(|(protocol, parent, _)| *protocol == "Retrying" && *parent == "NSObject")
+    );
+}
+
+#[test]
+fn test_objc_imports_and_message_sends() {
+    let (mut parser, file_id, _) = build_parser();
+
+    let imports = parser.find_imports(CLIENT, file_id);
+    let paths: Vec<_> = imports.iter().map(|i| i.path.as_str()).collect();
+    assert!(paths.contains(&"Foundation/Foundation.h"), "got {paths:?}");
+    assert!(paths.contains(&"Loading.h"), "got {paths:?}");
+    assert!(paths.contains(&"Network"), "got {paths:?}");
+
+    let calls = parser.find_method_calls(CLIENT);
+    let log = calls
+        .iter()
+        .find(|call| call.method_name == "log")
+        .expect("[self log:] should be reported");
+    assert_eq!(log.caller, "fetch");
+    assert!(log.is_self_call());
+
+    let alloc = calls
+        .iter()
+        .find(|call| call.method_name == "alloc")
+        .expect("[Client alloc] should be reported");
+    assert_eq!(alloc.receiver.as_deref(), Some("Client"));
+    assert!(alloc.is_static);
+}
@@ -0,0 +1,101 @@
+//! PHP namespace, enum, trait use and import tests
+
+use codanna::parsing::LanguageParser;
+use codanna::parsing::php::PhpParser;
+use codanna::types::SymbolCounter;
+use codanna::{FileId, SymbolKind};
+
+const CODE: &str = r#"<?php
+namespace App\Models;
+
+use App\Contracts\{Cache, Store as KeyValueStore};
+use Illuminate\Database\Eloquent\Model;
+use function App\Support\helper;
+
+enum Status: string implements HasLabel {
+    use LabelsFromName;
+
+    case Active = 'active';
+    case Banned = 'banned';
+
+    public function label(): string { return $this->name; }
+}
+
+class User extends Model implements Cache, \App\Contracts\Auditable {
+    use HasFactory, Notifiable;
+
+    public function save(): bool { return true; }
+}
+"#;
+
+#[test]
+fn test_namespace_and_enum_symbols() {
+    let mut parser = PhpParser::new().expect("Failed to create PHP parser");
+    let mut counter = SymbolCounter::new();
+    let symbols = parser.parse(CODE, FileId(1), &mut counter);
+
+    let find = |name: &str| symbols.iter().find(|s| s.name.as_ref() == name);
+
+    let namespace = find("App\\Models").expect("Should find the namespace");
+    assert_eq!(namespace.kind, SymbolKind::Module);
+
+    assert_eq!(find("Status").map(|s| s.kind), Some(SymbolKind::Enum));
+    assert_eq!(find("Active").map(|s| s.kind), Some(SymbolKind::Constant));
+    assert_eq!(find("label").map(|s| s.kind), Some(SymbolKind::Method));
+    assert_eq!(find("User").map(|s| s.kind), Some(SymbolKind::Class));
+}
+
+#[test]
+fn test_use_imports() {
+    let mut parser = PhpParser::new().expect("Failed to create PHP parser");
+    let imports = parser.find_imports(CODE, FileId(1));
+
+    let paths: Vec<_> = imports
+        .iter()
+        .map(|i| (i.path.as_str(), i.alias.as_deref()))
+        .collect();
+    assert!(paths.contains(&("App\\Contracts\\Cache", None)));
+    assert!(paths.contains(&("App\\Contracts\\Store", Some("KeyValueStore"))));
+    assert!(paths.contains(&("Illuminate\\Database\\Eloquent\\Model", None)));
+    assert!(paths.contains(&("App\\Support\\helper", None)));
+}
+
+#[test]
+fn test_extends_implements_and_trait_use() {
+    let mut parser = PhpParser::new().expect("Failed to create PHP parser");
+
+    let extends: Vec<_> = parser
+        .find_extends(CODE)
+        .into_iter()
+        .map(|(derived, base, _)| (derived, base))
+        .collect();
+    assert_eq!(extends, vec![("User", "Model")]);
+
+    let implementations: Vec<_> = parser
+        .find_implementations(CODE)
+        .into_iter()
+        .map(|(ty, target, _)| (ty, target))
+        .collect();
+    for expected in [
+        ("Status", "HasLabel"),
+        ("Status", "LabelsFromName"),
+        ("User", "Cache"),
+        ("User", "Auditable"),
+        ("User", "HasFactory"),
+        ("User", "Notifiable"),
+    ] {
+        assert!(
+            implementations.contains(&expected),
+            "missing {expected:?} in {implementations:?}"
+        );
+    }
+    assert!(!implementations.contains(&("User", "Model")));
+
+    let defines: Vec<_> = parser
+        .find_defines(CODE)
+        .into_iter()
+        .map(|(ty, method, _)| (ty, method))
+        .collect();
+    assert!(defines.contains(&("Status", "label")));
+    assert!(defines.contains(&("User", "save")));
+}
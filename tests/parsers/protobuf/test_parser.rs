@@ -0,0 +1,152 @@
+use codanna::parsing::LanguageParser;
+use codanna::parsing::protobuf::ProtobufParser;
+use codanna::symbol::ScopeContext;
+use codanna::types::{FileId, SymbolCounter, SymbolKind};
+
+fn build_parser() -> (ProtobufParser, FileId, SymbolCounter) {
+    let parser = ProtobufParser::new().expect("Failed to create Protobuf parser");
+    let file_id = FileId::new(1).expect("Invalid file id");
+    let counter = SymbolCounter::new();
+    (parser, file_id, counter)
+}
+
+const GREETER: &str = r#"
+syntax = "proto3";
+
+package helloworld;
+
+import "google/protobuf/timestamp.proto";
+
+// The greeting service definition.
+service Greeter {
+  // Sends a greeting
+  rpc SayHello (HelloRequest) returns (HelloReply);
+}
+
+// The request message containing the user's name.
+message HelloRequest {
+  string name = 1;
+  Mood mood = 2;
+}
+
+message HelloReply {
+  string message = 1;
+  google.protobuf.Timestamp sent_at = 2;
+}
+
+enum Mood {
+  MOOD_UNSPECIFIED = 0;
+  MOOD_HAPPY = 1;
+}
+"#;
+
+#[test]
+fn test_protobuf_parser_extracts_messages_and_fields() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(GREETER, file_id, &mut counter);
+
+    let request = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "HelloRequest")
+        .expect("HelloRequest message should be extracted");
+    assert_eq!(request.kind, SymbolKind::Struct);
+    assert!(
+        request
+            .doc_comment
+            .as_deref()
+            .is_some_and(|doc| doc.contains("containing the user's name")),
+        "Message should keep its leading comment"
+    );
+
+    let name = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "name")
+        .expect("name field should be extracted");
+    assert_eq!(name.kind, SymbolKind::Field);
+    assert_eq!(
+        name.scope_context,
+        Some(ScopeContext::ClassMember {
+            class_name: Some("HelloRequest".into())
+        })
+    );
+
+    let happy = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "MOOD_HAPPY")
+        .expect("enum value should be extracted");
+    assert_eq!(happy.kind, SymbolKind::Constant);
+}
+
+#[test]
+fn test_protobuf_parser_extracts_services_and_rpcs() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(GREETER, file_id, &mut counter);
+
+    let service = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "Greeter")
+        .expect("service should be extracted");
+    assert_eq!(service.kind, SymbolKind::Interface);
+
+    let rpc = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "SayHello")
+        .expect("rpc should be extracted");
+    assert_eq!(rpc.kind, SymbolKind::Method);
+    assert_eq!(
+        rpc.scope_context,
+        Some(ScopeContext::ClassMember {
+            class_name: Some("Greeter".into())
+        })
+    );
+    assert!(
+        rpc.signature
+            .as_deref()
+            .is_some_and(|sig| sig.starts_with("rpc SayHello")),
+        "RPC signature should be its declaration, got {:?}",
+        rpc.signature
+    );
+}
+
+#[test]
+fn test_protobuf_parser_finds_type_uses() {
+    let (mut parser, _, _) = build_parser();
+    let uses = parser.find_uses(GREETER);
+
+    assert!(
+        uses.iter()
+            .any(|(from, to, _)| *from == "SayHello" && *to == "HelloRequest"),
+        "RPC should use its request type, got {uses:?}"
+    );
+    assert!(
+        uses.iter()
+            .any(|(from, to, _)| *from == "HelloRequest" && *to == "Mood"),
+        "Message should use its field types, got {uses:?}"
+    );
+}
+
+#[test]
+fn test_protobuf_parser_defines_members() {
+    let (mut parser, _, _) = build_parser();
+    let defines = parser.find_defines(GREETER);
+
+    assert!(
+        defines
+            .iter()
+            .any(|(parent, member, _)| *parent == "Greeter" && *member == "SayHello"),
+        "Services should define their RPCs, got {defines:?}"
+    );
+}
+
+#[test]
+fn test_protobuf_parser_finds_imports() {
+    let (mut parser, file_id, _) = build_parser();
+    let imports = parser.find_imports(GREETER, file_id);
+
+    assert!(
+        imports
+            .iter()
+            .any(|i| i.path == "google/protobuf/timestamp.proto"),
+        "Import path should be unquoted, got {imports:?}"
+    );
+}
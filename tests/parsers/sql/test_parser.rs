@@ -0,0 +1,141 @@
+use codanna::parsing::LanguageParser;
+use codanna::parsing::sql::SqlParser;
+use codanna::symbol::ScopeContext;
+use codanna::types::{FileId, SymbolCounter, SymbolKind};
+
+fn build_parser() -> (SqlParser, FileId, SymbolCounter) {
+    let parser = SqlParser::new().expect("Failed to create SQL parser");
+    let file_id = FileId::new(1).expect("Invalid file id");
+    let counter = SymbolCounter::new();
+    (parser, file_id, counter)
+}
+
+const SCHEMA: &str = r#"
+-- Registered accounts
+CREATE TABLE users (
+    id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE
+);
+
+-- Orders placed by users
+CREATE TABLE orders (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    total NUMERIC(10, 2) NOT NULL
+);
+
+CREATE VIEW big_spenders AS
+SELECT u.id, u.email
+FROM users u
+JOIN orders o ON o.user_id = u.id
+WHERE o.total > 1000;
+
+CREATE FUNCTION order_count(uid INTEGER) RETURNS BIGINT AS $$
+    SELECT count(*) FROM orders WHERE user_id = uid;
+$$ LANGUAGE sql;
+"#;
+
+#[test]
+fn test_sql_parser_extracts_tables_and_columns() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(SCHEMA, file_id, &mut counter);
+
+    let users = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "users")
+        .expect("users table should be extracted");
+    assert_eq!(users.kind, SymbolKind::Struct);
+    assert!(
+        users
+            .doc_comment
+            .as_deref()
+            .is_some_and(|doc| doc.contains("Registered accounts")),
+        "Table should keep its leading comment"
+    );
+
+    let email = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "email")
+        .expect("email column should be extracted");
+    assert_eq!(email.kind, SymbolKind::Field);
+    assert_eq!(
+        email.scope_context,
+        Some(ScopeContext::ClassMember {
+            class_name: Some("users".into())
+        })
+    );
+}
+
+#[test]
+fn test_sql_parser_extracts_views_and_functions() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(SCHEMA, file_id, &mut counter);
+
+    let view = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "big_spenders")
+        .expect("view should be extracted");
+    assert_eq!(view.kind, SymbolKind::Struct);
+    assert!(
+        view.signature
+            .as_deref()
+            .is_some_and(|sig| sig.starts_with("CREATE VIEW big_spenders")),
+        "View signature should be its header, got {:?}",
+        view.signature
+    );
+
+    let function = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "order_count")
+        .expect("function should be extracted");
+    assert_eq!(function.kind, SymbolKind::Function);
+}
+
+#[test]
+fn test_sql_parser_finds_foreign_keys_and_view_dependencies() {
+    let (mut parser, _, _) = build_parser();
+    let uses = parser.find_uses(SCHEMA);
+
+    assert!(
+        uses.iter()
+            .any(|(from, to, _)| *from == "orders" && *to == "users"),
+        "Foreign key should produce orders -> users, got {uses:?}"
+    );
+    assert!(
+        uses.iter()
+            .any(|(from, to, _)| *from == "big_spenders" && *to == "orders"),
+        "View should depend on joined tables, got {uses:?}"
+    );
+}
+
+#[test]
+fn test_sql_parser_defines_columns() {
+    let (mut parser, _, _) = build_parser();
+    let defines = parser.find_defines(SCHEMA);
+
+    assert!(
+        defines
+            .iter()
+            .any(|(table, column, _)| *table == "orders" && *column == "user_id"),
+        "Tables should define their columns, got {defines:?}"
+    );
+}
+
+#[test]
+fn test_sql_parser_recovers_stored_procedures() {
+    let code = r#"
+CREATE OR ALTER PROCEDURE dbo.archive_orders @before DATE
+AS
+BEGIN
+    DELETE FROM orders WHERE created_at < @before;
+END;
+"#;
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(code, file_id, &mut counter);
+
+    let procedure = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "archive_orders")
+        .expect("procedure should be recovered from unparsed dialect syntax");
+    assert_eq!(procedure.kind, SymbolKind::Function);
+}
@@ -0,0 +1,128 @@
+use codanna::parsing::LanguageParser;
+use codanna::parsing::vue::VueParser;
+use codanna::types::{FileId, SymbolCounter, SymbolKind};
+
+fn build_parser() -> (VueParser, FileId, SymbolCounter) {
+    let parser = VueParser::new().expect("Failed to create Vue parser");
+    let file_id = FileId::new(1).expect("Invalid file id");
+    let counter = SymbolCounter::new();
+    (parser, file_id, counter)
+}
+
+const USER_LIST: &str = r#"<template>
+  <ul>
+    <li v-for="user in users" :key="user.id">
+      <UserCard :user="user" @select="selectUser" />
+    </li>
+  </ul>
+  <user-avatar :src="avatar" />
+  <Transition><p v-if="selected">{{ selected.name }}</p></Transition>
+</template>
+
+<script lang="ts">
+import { defineComponent } from 'vue'
+import UserCard from './UserCard.vue'
+import UserAvatar from './UserAvatar.vue'
+
+/** Pick the first active user */
+export function firstActive(users: User[]): User | undefined {
+  return users.find(isActive)
+}
+
+export default defineComponent({
+  name: 'UserList',
+  components: { UserCard, UserAvatar },
+})
+</script>
+
+<style scoped>
+ul { list-style: none; }
+</style>
+"#;
+
+#[test]
+fn test_vue_parser_extracts_script_symbols_at_file_positions() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(USER_LIST, file_id, &mut counter);
+
+    let first_active = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "firstActive")
+        .unwrap_or_else(|| panic!("firstActive should be extracted, got {symbols:?}"));
+    assert_eq!(first_active.kind, SymbolKind::Function);
+    // Line 17 of the .vue file (0-based 16), not line 6 of the script block
+    assert_eq!(first_active.range.start_line, 16);
+    assert!(
+        first_active
+            .doc_comment
+            .as_deref()
+            .is_some_and(|doc| doc.contains("first active user"))
+    );
+}
+
+#[test]
+fn test_vue_parser_names_component_from_options() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let symbols = parser.parse(USER_LIST, file_id, &mut counter);
+
+    let component = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "UserList")
+        .expect("component symbol should be extracted");
+    assert_eq!(component.kind, SymbolKind::Class);
+    assert_eq!(component.range.start_line, 0);
+    assert_eq!(component.signature.as_deref(), Some("component UserList"));
+}
+
+#[test]
+fn test_vue_parser_records_template_component_usage() {
+    let (mut parser, _, _) = build_parser();
+    let uses = parser.find_uses(USER_LIST);
+
+    for used in ["UserCard", "UserAvatar"] {
+        assert!(
+            uses.iter()
+                .any(|(from, to, _)| *from == "UserList" && *to == used),
+            "Expected UserList -> {used}, got {uses:?}"
+        );
+    }
+    assert!(
+        !uses.iter().any(|(_, to, _)| *to == "Transition"),
+        "Built-in components should be skipped"
+    );
+
+    let (_, _, range) = uses.iter().find(|(_, to, _)| *to == "UserCard").unwrap();
+    assert_eq!(range.start_line, 3);
+}
+
+#[test]
+fn test_vue_parser_falls_back_to_template_name() {
+    let (mut parser, file_id, mut counter) = build_parser();
+    let code = "<script setup>\nimport Child from './Child.vue'\n</script>\n\n<template>\n  <Child />\n</template>\n";
+
+    let symbols = parser.parse(code, file_id, &mut counter);
+    assert!(
+        symbols
+            .iter()
+            .any(|s| s.name.as_ref() == "template" && s.kind == SymbolKind::Class),
+        "got {symbols:?}"
+    );
+
+    let uses = parser.find_uses(code);
+    assert!(
+        uses.iter()
+            .any(|(from, to, _)| *from == "template" && *to == "Child"),
+        "got {uses:?}"
+    );
+}
+
+#[test]
+fn test_vue_parser_imports_drop_vue_extension() {
+    let (mut parser, file_id, _) = build_parser();
+    let imports = parser.find_imports(USER_LIST, file_id);
+
+    let paths: Vec<_> = imports.iter().map(|i| i.path.as_str()).collect();
+    assert!(paths.contains(&"vue"), "got {paths:?}");
+    assert!(paths.contains(&"./UserCard"), "got {paths:?}");
+    assert!(paths.contains(&"./UserAvatar"), "got {paths:?}");
+}
@@ -49,6 +49,27 @@ mod test_gdscript_import_extraction;
 #[path = "parsers/gdscript/test_relationships.rs"]
 mod test_gdscript_relationships;
 
+#[path = "parsers/lua/test_parser.rs"]
+mod test_lua_parser;
+
+#[path = "parsers/sql/test_parser.rs"]
+mod test_sql_parser;
+
+#[path = "parsers/protobuf/test_parser.rs"]
+mod test_protobuf_parser;
+
+#[path = "parsers/hcl/test_parser.rs"]
+mod test_hcl_parser;
+
+#[path = "parsers/objc/test_parser.rs"]
+mod test_objc_parser;
+
+#[path = "parsers/config/test_parser.rs"]
+mod test_config_parser;
+
+#[path = "parsers/vue/test_parser.rs"]
+mod test_vue_parser;
+
 #[path = "parsers/kotlin/test_type_usage.rs"]
 mod test_kotlin_type_usage;
 
@@ -103,6 +124,9 @@ mod test_kotlin_value_class;
 #[path = "parsers/php/test_readonly_class.rs"]
 mod test_php_readonly_class;
 
+#[path = "parsers/php/test_namespaces_and_traits.rs"]
+mod test_php_namespaces_and_traits;
+
 #[path = "parsers/kotlin/test_context_receiver.rs"]
 mod test_kotlin_context_receiver;
 